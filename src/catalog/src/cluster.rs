@@ -0,0 +1,168 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_telemetry::{info, warn};
+use common_time::util::current_time_millis;
+use futures_util::StreamExt;
+use snafu::ResultExt;
+use tokio::task::JoinHandle;
+
+use crate::error::{InvalidCatalogValueSnafu, Result};
+use crate::helper::{
+    build_cluster_node_prefix, build_region_inconsistency_prefix, ClusterNodeKey, ClusterNodeValue,
+    NodeRole, RegionInconsistencyKey, RegionInconsistencyValue,
+};
+use crate::remote::{Kv, KvBackendRef};
+
+/// Default interval of reporting the node info in a [NodeInfoReporter].
+pub const DEFAULT_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of report intervals the reported node info stays valid, so that a node missing a
+/// few reports is not considered gone.
+pub const LEASE_INTERVALS: u32 = 3;
+
+/// Version of the running GreptimeDB.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Reports the info of current node to the [KvBackend](crate::remote::KvBackend) periodically,
+/// so that all nodes in the cluster can be listed by [list_cluster_nodes].
+pub struct NodeInfoReporter {
+    backend: KvBackendRef,
+    key: ClusterNodeKey,
+    value: ClusterNodeValue,
+    interval: Duration,
+}
+
+impl NodeInfoReporter {
+    pub fn new(
+        backend: KvBackendRef,
+        role: NodeRole,
+        addr: impl Into<String>,
+        node_id: Option<u64>,
+    ) -> Self {
+        let now = current_time_millis();
+        Self {
+            backend,
+            key: ClusterNodeKey {
+                role,
+                addr: addr.into(),
+            },
+            value: ClusterNodeValue {
+                node_id,
+                version: VERSION.to_string(),
+                start_time_millis: now,
+                last_activity_millis: now,
+                disk_low: false,
+                lease_millis: lease_millis(DEFAULT_REPORT_INTERVAL),
+            },
+            interval: DEFAULT_REPORT_INTERVAL,
+        }
+    }
+
+    /// Sets the report interval, the lease of the reported info follows the interval.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self.value.lease_millis = lease_millis(interval);
+        self
+    }
+
+    /// Starts reporting in background.
+    pub fn start(mut self) -> JoinHandle<()> {
+        info!("Start reporting node info, key: {}", self.key);
+        common_runtime::spawn_bg(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                let _ = interval.tick().await;
+                if let Err(e) = self.report().await {
+                    warn!(
+                        "Failed to report node info, key: {}, error: {}",
+                        self.key, e
+                    );
+                }
+            }
+        })
+    }
+
+    /// Sets whether the free disk space of the node is low, reported along with the node info.
+    pub fn set_disk_low(&mut self, disk_low: bool) {
+        self.value.disk_low = disk_low;
+    }
+
+    /// Reports the node info once, with the last activity time refreshed.
+    pub async fn report(&mut self) -> Result<()> {
+        self.value.last_activity_millis = current_time_millis();
+        let value = self.value.as_bytes().context(InvalidCatalogValueSnafu)?;
+        self.backend
+            .set(self.key.to_string().as_bytes(), &value)
+            .await
+    }
+}
+
+/// Returns the lease of the node info reported every `interval`.
+pub fn lease_millis(interval: Duration) -> i64 {
+    (interval * LEASE_INTERVALS).as_millis() as i64
+}
+
+/// Lists the info of the nodes in the cluster. Nodes whose lease has expired are skipped,
+/// their info is removed by the metasrv later.
+pub async fn list_cluster_nodes(
+    backend: &KvBackendRef,
+) -> Result<Vec<(ClusterNodeKey, ClusterNodeValue)>> {
+    let now = current_time_millis();
+    let mut nodes = Vec::new();
+    let mut iter = backend.range(build_cluster_node_prefix().as_bytes());
+    while let Some(r) = iter.next().await {
+        let Kv(k, v) = r?;
+        let Ok(key) = ClusterNodeKey::parse(String::from_utf8_lossy(&k)) else { continue };
+        let value = ClusterNodeValue::from_bytes(v).context(InvalidCatalogValueSnafu)?;
+        if value.is_expired(now) {
+            continue;
+        }
+        nodes.push((key, value));
+    }
+    Ok(nodes)
+}
+
+/// Returns the warnings of the `nodes` running out of disk space, reported by health checks.
+pub fn disk_low_warnings(nodes: &[(ClusterNodeKey, ClusterNodeValue)]) -> Vec<String> {
+    nodes
+        .iter()
+        .filter(|(_, value)| value.disk_low)
+        .map(|(key, _)| {
+            format!(
+                "Disk space of {} {} is below the low watermark",
+                key.role.as_str(),
+                key.addr
+            )
+        })
+        .collect()
+}
+
+/// Lists the inconsistencies between the regions served by datanodes and the table routes
+/// detected by the metasrv. Resolved inconsistencies are removed by the metasrv.
+pub async fn list_region_inconsistencies(
+    backend: &KvBackendRef,
+) -> Result<Vec<(RegionInconsistencyKey, RegionInconsistencyValue)>> {
+    let mut inconsistencies = Vec::new();
+    let mut iter = backend.range(build_region_inconsistency_prefix(None).as_bytes());
+    while let Some(r) = iter.next().await {
+        let Kv(k, v) = r?;
+        let Ok(key) = RegionInconsistencyKey::parse(String::from_utf8_lossy(&k)) else { continue };
+        let value = RegionInconsistencyValue::from_bytes(v).context(InvalidCatalogValueSnafu)?;
+        inconsistencies.push((key, value));
+    }
+    Ok(inconsistencies)
+}
@@ -136,6 +136,17 @@ pub enum Error {
         source: table::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to close table, table info: {}, source: {}",
+        table_info,
+        source
+    ))]
+    CloseTable {
+        table_info: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
     #[snafu(display("Failed to open table in parallel, source: {}", source))]
     ParallelOpenTable { source: JoinError },
 
@@ -221,6 +232,13 @@ pub enum Error {
         source: datatypes::error::Error,
     },
 
+    #[snafu(display("Invalid view: {}, source: {}", view_name, source))]
+    InvalidView {
+        view_name: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
     #[snafu(display("Failed to serialize or deserialize catalog entry: {}", source))]
     CatalogEntrySerde {
         #[snafu(backtrace)]
@@ -299,6 +317,7 @@ impl ErrorExt for Error {
             | Error::CreateSystemCatalog { source, .. }
             | Error::InsertCatalogRecord { source, .. }
             | Error::OpenTable { source, .. }
+            | Error::CloseTable { source, .. }
             | Error::CreateTable { source, .. }
             | Error::DeregisterTable { source, .. }
             | Error::RegionStats { source, .. }
@@ -308,6 +327,7 @@ impl ErrorExt for Error {
             Error::SystemCatalogTableScan { source } => source.status_code(),
             Error::SystemCatalogTableScanExec { source } => source.status_code(),
             Error::InvalidTableInfoInCatalog { source } => source.status_code(),
+            Error::InvalidView { source, .. } => source.status_code(),
 
             Error::CompileScriptInternal { source }
             | Error::SchemaProviderOperation { source }
@@ -28,6 +28,10 @@ pub const CATALOG_KEY_PREFIX: &str = "__c";
 pub const SCHEMA_KEY_PREFIX: &str = "__s";
 pub const TABLE_GLOBAL_KEY_PREFIX: &str = "__tg";
 pub const TABLE_REGIONAL_KEY_PREFIX: &str = "__tr";
+pub const CLUSTER_NODE_KEY_PREFIX: &str = "__cn";
+pub const REGION_INCONSISTENCY_KEY_PREFIX: &str = "__ri";
+/// Key of a counter bumped whenever a table is created, dropped or renamed.
+pub const TABLES_VERSION_KEY: &str = "__tv";
 
 const ALPHANUMERICS_NAME_PATTERN: &str = "[a-zA-Z_][a-zA-Z0-9_]*";
 
@@ -59,6 +63,20 @@ lazy_static! {
     .unwrap();
 }
 
+lazy_static! {
+    static ref CLUSTER_NODE_KEY_PATTERN: Regex = Regex::new(&format!(
+        "^{CLUSTER_NODE_KEY_PREFIX}-(frontend|datanode|metasrv)-(.+)$"
+    ))
+    .unwrap();
+}
+
+lazy_static! {
+    static ref REGION_INCONSISTENCY_KEY_PATTERN: Regex = Regex::new(&format!(
+        "^{REGION_INCONSISTENCY_KEY_PREFIX}-([0-9]+)-([0-9]+)$"
+    ))
+    .unwrap();
+}
+
 pub fn build_catalog_prefix() -> String {
     format!("{CATALOG_KEY_PREFIX}-")
 }
@@ -254,6 +272,166 @@ impl SchemaKey {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SchemaValue;
 
+pub fn build_cluster_node_prefix() -> String {
+    format!("{CLUSTER_NODE_KEY_PREFIX}-")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRole {
+    Frontend,
+    Datanode,
+    Metasrv,
+}
+
+impl NodeRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeRole::Frontend => "frontend",
+            NodeRole::Datanode => "datanode",
+            NodeRole::Metasrv => "metasrv",
+        }
+    }
+}
+
+/// Key of the info reported by a node in the cluster, the node is identified by its role
+/// and address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterNodeKey {
+    pub role: NodeRole,
+    pub addr: String,
+}
+
+impl Display for ClusterNodeKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(CLUSTER_NODE_KEY_PREFIX)?;
+        f.write_str("-")?;
+        f.write_str(self.role.as_str())?;
+        f.write_str("-")?;
+        f.write_str(&self.addr)
+    }
+}
+
+impl ClusterNodeKey {
+    pub fn parse(s: impl AsRef<str>) -> Result<Self, Error> {
+        let key = s.as_ref();
+        let captures = CLUSTER_NODE_KEY_PATTERN
+            .captures(key)
+            .context(InvalidCatalogSnafu { key })?;
+        ensure!(captures.len() == 3, InvalidCatalogSnafu { key });
+        let role = match &captures[1] {
+            "frontend" => NodeRole::Frontend,
+            "datanode" => NodeRole::Datanode,
+            _ => NodeRole::Metasrv,
+        };
+        Ok(Self {
+            role,
+            addr: captures[2].to_string(),
+        })
+    }
+}
+
+/// Info of a node in the cluster, refreshed by the node periodically.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClusterNodeValue {
+    /// Id of the node, only datanodes have ids.
+    pub node_id: Option<u64>,
+    pub version: String,
+    pub start_time_millis: i64,
+    /// The last time this node reported its info.
+    pub last_activity_millis: i64,
+    /// Whether the free disk space of the node is below the low watermark, only reported by
+    /// datanodes.
+    #[serde(default)]
+    pub disk_low: bool,
+    /// How long the info stays valid after the last activity. The node is considered gone if
+    /// it doesn't report again within the lease. Zero means the info never expires, as
+    /// reported by older versions.
+    #[serde(default)]
+    pub lease_millis: i64,
+}
+
+impl ClusterNodeValue {
+    /// Whether the lease of the info has expired at `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.lease_millis > 0 && self.last_activity_millis + self.lease_millis < now
+    }
+}
+
+/// Builds the prefix of the region inconsistencies of the datanode `node_id`, or of all
+/// datanodes if `node_id` is `None`.
+pub fn build_region_inconsistency_prefix(node_id: Option<u64>) -> String {
+    match node_id {
+        Some(node_id) => format!("{REGION_INCONSISTENCY_KEY_PREFIX}-{node_id}-"),
+        None => format!("{REGION_INCONSISTENCY_KEY_PREFIX}-"),
+    }
+}
+
+/// Key of an inconsistency between the regions served by a datanode and the table routes,
+/// detected by the metasrv from the heartbeats of the datanode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionInconsistencyKey {
+    pub node_id: u64,
+    pub region_id: u64,
+}
+
+impl Display for RegionInconsistencyKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REGION_INCONSISTENCY_KEY_PREFIX)?;
+        f.write_str("-")?;
+        f.serialize_u64(self.node_id)?;
+        f.write_str("-")?;
+        f.serialize_u64(self.region_id)
+    }
+}
+
+impl RegionInconsistencyKey {
+    pub fn parse(s: impl AsRef<str>) -> Result<Self, Error> {
+        let key = s.as_ref();
+        let captures = REGION_INCONSISTENCY_KEY_PATTERN
+            .captures(key)
+            .context(InvalidCatalogSnafu { key })?;
+        ensure!(captures.len() == 3, InvalidCatalogSnafu { key });
+        let node_id = captures[1]
+            .parse()
+            .map_err(|_| InvalidCatalogSnafu { key }.build())?;
+        let region_id = captures[2]
+            .parse()
+            .map_err(|_| InvalidCatalogSnafu { key }.build())?;
+        Ok(Self { node_id, region_id })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegionInconsistencyKind {
+    /// The datanode serves a region the table route doesn't assign to it.
+    Unowned,
+    /// The datanode doesn't serve a region the table route assigns to it.
+    Missing,
+}
+
+impl RegionInconsistencyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegionInconsistencyKind::Unowned => "unowned",
+            RegionInconsistencyKind::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegionInconsistencyValue {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub region_number: u32,
+    pub kind: RegionInconsistencyKind,
+    /// Id of the datanode the table route assigns the region to, `None` if the region
+    /// isn't assigned to any datanode or the table route is absent.
+    pub assigned_node_id: Option<u64>,
+    /// The first time the inconsistency was detected.
+    pub detected_time_millis: i64,
+}
+
 macro_rules! define_catalog_value {
     ( $($val_ty: ty), *) => {
             $(
@@ -281,7 +459,9 @@ define_catalog_value!(
     TableRegionalValue,
     TableGlobalValue,
     CatalogValue,
-    SchemaValue
+    SchemaValue,
+    ClusterNodeValue,
+    RegionInconsistencyValue
 );
 
 #[cfg(test)]
@@ -319,6 +499,34 @@ mod tests {
         assert_eq!(key, &entry.to_string());
     }
 
+    #[test]
+    fn test_parse_cluster_node_key() {
+        let key = "__cn-datanode-127.0.0.1:3001";
+        let node_key = ClusterNodeKey::parse(key).unwrap();
+        assert_eq!(NodeRole::Datanode, node_key.role);
+        assert_eq!("127.0.0.1:3001", node_key.addr);
+        assert_eq!(key, node_key.to_string());
+
+        assert!(ClusterNodeKey::parse("__cn-unknown-127.0.0.1:3001").is_err());
+        assert!(ClusterNodeKey::parse("__cn-frontend-").is_err());
+    }
+
+    #[test]
+    fn test_parse_region_inconsistency_key() {
+        let key = "__ri-3-4398046511105";
+        let inconsistency_key = RegionInconsistencyKey::parse(key).unwrap();
+        assert_eq!(3, inconsistency_key.node_id);
+        assert_eq!(4398046511105, inconsistency_key.region_id);
+        assert_eq!(key, inconsistency_key.to_string());
+        assert!(inconsistency_key
+            .to_string()
+            .starts_with(&build_region_inconsistency_prefix(Some(3))));
+
+        assert!(RegionInconsistencyKey::parse("__ri-3-").is_err());
+        assert!(RegionInconsistencyKey::parse("__ri-a-1").is_err());
+        assert!(RegionInconsistencyKey::parse("__ri-3-99999999999999999999").is_err());
+    }
+
     #[test]
     fn test_build_prefix() {
         assert_eq!("__c-", build_catalog_prefix());
@@ -341,6 +549,7 @@ mod tests {
             schema: RawSchema::from(&schema),
             engine: "mito".to_string(),
             created_on: chrono::DateTime::default(),
+            column_history: vec![],
             primary_key_indices: vec![0, 1],
             next_column_id: 3,
             engine_options: Default::default(),
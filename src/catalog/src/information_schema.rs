@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cluster_info;
+mod column_history;
 mod columns;
+mod region_inconsistencies;
+mod table_statistics;
 mod tables;
 
 use std::any::Any;
@@ -24,26 +28,47 @@ use snafu::ResultExt;
 use table::table::adapter::TableAdapter;
 use table::TableRef;
 
+use self::cluster_info::InformationSchemaClusterInfo;
+use self::column_history::InformationSchemaColumnHistory;
 use self::columns::InformationSchemaColumns;
+use self::region_inconsistencies::InformationSchemaRegionInconsistencies;
+use self::table_statistics::InformationSchemaTableStatistics;
 use crate::error::{DatafusionSnafu, Result, TableSchemaMismatchSnafu};
 use crate::information_schema::tables::InformationSchemaTables;
-use crate::{CatalogProviderRef, SchemaProvider};
+use crate::{CatalogManagerRef, CatalogProviderRef, SchemaProvider};
 
 const TABLES: &str = "tables";
 const COLUMNS: &str = "columns";
+const COLUMN_HISTORY: &str = "column_history";
+const CLUSTER_INFO: &str = "cluster_info";
+const TABLE_STATISTICS: &str = "table_statistics";
+const REGION_INCONSISTENCIES: &str = "region_inconsistencies";
 
 pub(crate) struct InformationSchemaProvider {
     catalog_name: String,
     catalog_provider: CatalogProviderRef,
+    catalog_manager: CatalogManagerRef,
     tables: Vec<String>,
 }
 
 impl InformationSchemaProvider {
-    pub(crate) fn new(catalog_name: String, catalog_provider: CatalogProviderRef) -> Self {
+    pub(crate) fn new(
+        catalog_name: String,
+        catalog_provider: CatalogProviderRef,
+        catalog_manager: CatalogManagerRef,
+    ) -> Self {
         Self {
             catalog_name,
             catalog_provider,
-            tables: vec![TABLES.to_string(), COLUMNS.to_string()],
+            catalog_manager,
+            tables: vec![
+                TABLES.to_string(),
+                COLUMNS.to_string(),
+                COLUMN_HISTORY.to_string(),
+                CLUSTER_INFO.to_string(),
+                TABLE_STATISTICS.to_string(),
+                REGION_INCONSISTENCIES.to_string(),
+            ],
         }
     }
 }
@@ -86,6 +111,56 @@ impl SchemaProvider for InformationSchemaProvider {
                     )?,
                 )
             }
+            COLUMN_HISTORY => {
+                let inner = Arc::new(InformationSchemaColumnHistory::new(
+                    self.catalog_name.clone(),
+                    self.catalog_provider.clone(),
+                ));
+                Arc::new(
+                    StreamingTable::try_new(inner.schema().clone(), vec![inner]).with_context(
+                        |_| DatafusionSnafu {
+                            msg: format!("Failed to get InformationSchema table '{name}'"),
+                        },
+                    )?,
+                )
+            }
+            CLUSTER_INFO => {
+                let inner = Arc::new(InformationSchemaClusterInfo::new(
+                    self.catalog_manager.clone(),
+                ));
+                Arc::new(
+                    StreamingTable::try_new(inner.schema().clone(), vec![inner]).with_context(
+                        |_| DatafusionSnafu {
+                            msg: format!("Failed to get InformationSchema table '{name}'"),
+                        },
+                    )?,
+                )
+            }
+            TABLE_STATISTICS => {
+                let inner = Arc::new(InformationSchemaTableStatistics::new(
+                    self.catalog_name.clone(),
+                    self.catalog_provider.clone(),
+                ));
+                Arc::new(
+                    StreamingTable::try_new(inner.schema().clone(), vec![inner]).with_context(
+                        |_| DatafusionSnafu {
+                            msg: format!("Failed to get InformationSchema table '{name}'"),
+                        },
+                    )?,
+                )
+            }
+            REGION_INCONSISTENCIES => {
+                let inner = Arc::new(InformationSchemaRegionInconsistencies::new(
+                    self.catalog_manager.clone(),
+                ));
+                Arc::new(
+                    StreamingTable::try_new(inner.schema().clone(), vec![inner]).with_context(
+                        |_| DatafusionSnafu {
+                            msg: format!("Failed to get InformationSchema table '{name}'"),
+                        },
+                    )?,
+                )
+            }
             _ => {
                 return Ok(None);
             }
@@ -0,0 +1,175 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::RecordBatch;
+use common_time::util::current_time_millis;
+use datafusion::datasource::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::vectors::{
+    BooleanVectorBuilder, Int64VectorBuilder, StringVectorBuilder,
+    TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+};
+use snafu::ResultExt;
+
+use crate::error::{CreateRecordBatchSnafu, Result};
+use crate::helper::{ClusterNodeKey, ClusterNodeValue};
+use crate::CatalogManagerRef;
+
+/// The `information_schema.cluster_info` virtual table, listing the frontends, datanodes and
+/// metasrvs in the cluster. It's always empty in standalone mode.
+pub(super) struct InformationSchemaClusterInfo {
+    schema: SchemaRef,
+    catalog_manager: CatalogManagerRef,
+}
+
+impl InformationSchemaClusterInfo {
+    pub(super) fn new(catalog_manager: CatalogManagerRef) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("peer_id", ConcreteDataType::uint64_datatype(), true),
+            ColumnSchema::new("peer_type", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("peer_addr", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("version", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                "start_time",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("uptime_ms", ConcreteDataType::int64_datatype(), false),
+            ColumnSchema::new(
+                "last_heartbeat_time",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new(
+                "heartbeat_lag_ms",
+                ConcreteDataType::int64_datatype(),
+                false,
+            ),
+            ColumnSchema::new("disk_low", ConcreteDataType::boolean_datatype(), false),
+        ]));
+        Self {
+            schema,
+            catalog_manager,
+        }
+    }
+
+    fn builder(&self) -> InformationSchemaClusterInfoBuilder {
+        InformationSchemaClusterInfoBuilder::new(self.schema.clone(), self.catalog_manager.clone())
+    }
+}
+
+struct InformationSchemaClusterInfoBuilder {
+    schema: SchemaRef,
+    catalog_manager: CatalogManagerRef,
+
+    peer_ids: UInt64VectorBuilder,
+    peer_types: StringVectorBuilder,
+    peer_addrs: StringVectorBuilder,
+    versions: StringVectorBuilder,
+    start_times: TimestampMillisecondVectorBuilder,
+    uptimes: Int64VectorBuilder,
+    last_heartbeat_times: TimestampMillisecondVectorBuilder,
+    heartbeat_lags: Int64VectorBuilder,
+    disk_lows: BooleanVectorBuilder,
+}
+
+impl InformationSchemaClusterInfoBuilder {
+    fn new(schema: SchemaRef, catalog_manager: CatalogManagerRef) -> Self {
+        Self {
+            schema,
+            catalog_manager,
+            peer_ids: UInt64VectorBuilder::with_capacity(42),
+            peer_types: StringVectorBuilder::with_capacity(42),
+            peer_addrs: StringVectorBuilder::with_capacity(42),
+            versions: StringVectorBuilder::with_capacity(42),
+            start_times: TimestampMillisecondVectorBuilder::with_capacity(42),
+            uptimes: Int64VectorBuilder::with_capacity(42),
+            last_heartbeat_times: TimestampMillisecondVectorBuilder::with_capacity(42),
+            heartbeat_lags: Int64VectorBuilder::with_capacity(42),
+            disk_lows: BooleanVectorBuilder::with_capacity(42),
+        }
+    }
+
+    /// Construct the `information_schema.cluster_info` virtual table
+    async fn make_cluster_info(&mut self) -> Result<RecordBatch> {
+        let now = current_time_millis();
+        let mut nodes = self.catalog_manager.cluster_nodes().await?;
+        nodes.sort_by_key(|(key, _)| (key.role.as_str(), key.addr.clone()));
+        for (key, value) in &nodes {
+            self.add_node(key, value, now);
+        }
+
+        self.finish()
+    }
+
+    fn add_node(&mut self, key: &ClusterNodeKey, value: &ClusterNodeValue, now: i64) {
+        self.peer_ids.push(value.node_id);
+        self.peer_types.push(Some(key.role.as_str()));
+        self.peer_addrs.push(Some(&key.addr));
+        self.versions.push(Some(&value.version));
+        self.start_times
+            .push(Some(TimestampMillisecond::new(value.start_time_millis)));
+        self.uptimes
+            .push(Some(value.last_activity_millis - value.start_time_millis));
+        self.last_heartbeat_times
+            .push(Some(TimestampMillisecond::new(value.last_activity_millis)));
+        self.heartbeat_lags
+            .push(Some((now - value.last_activity_millis).max(0)));
+        self.disk_lows.push(Some(value.disk_low));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.peer_ids.finish()),
+            Arc::new(self.peer_types.finish()),
+            Arc::new(self.peer_addrs.finish()),
+            Arc::new(self.versions.finish()),
+            Arc::new(self.start_times.finish()),
+            Arc::new(self.uptimes.finish()),
+            Arc::new(self.last_heartbeat_times.finish()),
+            Arc::new(self.heartbeat_lags.finish()),
+            Arc::new(self.disk_lows.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaClusterInfo {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_cluster_info()
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::RecordBatch;
+use datafusion::datasource::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::vectors::{
+    StringVectorBuilder, TimestampMillisecondVectorBuilder, UInt32VectorBuilder,
+    UInt64VectorBuilder,
+};
+use snafu::ResultExt;
+
+use crate::error::{CreateRecordBatchSnafu, Result};
+use crate::helper::{RegionInconsistencyKey, RegionInconsistencyValue};
+use crate::CatalogManagerRef;
+
+/// The `information_schema.region_inconsistencies` virtual table, listing the regions a
+/// datanode serves but not assigned to it by the table routes, and the other way around.
+/// It's always empty in standalone mode.
+pub(super) struct InformationSchemaRegionInconsistencies {
+    schema: SchemaRef,
+    catalog_manager: CatalogManagerRef,
+}
+
+impl InformationSchemaRegionInconsistencies {
+    pub(super) fn new(catalog_manager: CatalogManagerRef) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("peer_id", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("region_id", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("table_catalog", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("table_schema", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("table_name", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("region_number", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(
+                "inconsistency_type",
+                ConcreteDataType::string_datatype(),
+                false,
+            ),
+            ColumnSchema::new(
+                "assigned_peer_id",
+                ConcreteDataType::uint64_datatype(),
+                true,
+            ),
+            ColumnSchema::new(
+                "detected_time",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]));
+        Self {
+            schema,
+            catalog_manager,
+        }
+    }
+
+    fn builder(&self) -> InformationSchemaRegionInconsistenciesBuilder {
+        InformationSchemaRegionInconsistenciesBuilder::new(
+            self.schema.clone(),
+            self.catalog_manager.clone(),
+        )
+    }
+}
+
+struct InformationSchemaRegionInconsistenciesBuilder {
+    schema: SchemaRef,
+    catalog_manager: CatalogManagerRef,
+
+    peer_ids: UInt64VectorBuilder,
+    region_ids: UInt64VectorBuilder,
+    catalog_names: StringVectorBuilder,
+    schema_names: StringVectorBuilder,
+    table_names: StringVectorBuilder,
+    region_numbers: UInt32VectorBuilder,
+    inconsistency_types: StringVectorBuilder,
+    assigned_peer_ids: UInt64VectorBuilder,
+    detected_times: TimestampMillisecondVectorBuilder,
+}
+
+impl InformationSchemaRegionInconsistenciesBuilder {
+    fn new(schema: SchemaRef, catalog_manager: CatalogManagerRef) -> Self {
+        Self {
+            schema,
+            catalog_manager,
+            peer_ids: UInt64VectorBuilder::with_capacity(42),
+            region_ids: UInt64VectorBuilder::with_capacity(42),
+            catalog_names: StringVectorBuilder::with_capacity(42),
+            schema_names: StringVectorBuilder::with_capacity(42),
+            table_names: StringVectorBuilder::with_capacity(42),
+            region_numbers: UInt32VectorBuilder::with_capacity(42),
+            inconsistency_types: StringVectorBuilder::with_capacity(42),
+            assigned_peer_ids: UInt64VectorBuilder::with_capacity(42),
+            detected_times: TimestampMillisecondVectorBuilder::with_capacity(42),
+        }
+    }
+
+    /// Construct the `information_schema.region_inconsistencies` virtual table
+    async fn make_region_inconsistencies(&mut self) -> Result<RecordBatch> {
+        let mut inconsistencies = self.catalog_manager.region_inconsistencies().await?;
+        inconsistencies.sort_by_key(|(key, _)| (key.node_id, key.region_id));
+        for (key, value) in &inconsistencies {
+            self.add_inconsistency(key, value);
+        }
+
+        self.finish()
+    }
+
+    fn add_inconsistency(
+        &mut self,
+        key: &RegionInconsistencyKey,
+        value: &RegionInconsistencyValue,
+    ) {
+        self.peer_ids.push(Some(key.node_id));
+        self.region_ids.push(Some(key.region_id));
+        self.catalog_names.push(Some(&value.catalog_name));
+        self.schema_names.push(Some(&value.schema_name));
+        self.table_names.push(Some(&value.table_name));
+        self.region_numbers.push(Some(value.region_number));
+        self.inconsistency_types.push(Some(value.kind.as_str()));
+        self.assigned_peer_ids.push(value.assigned_node_id);
+        self.detected_times
+            .push(Some(TimestampMillisecond::new(value.detected_time_millis)));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.peer_ids.finish()),
+            Arc::new(self.region_ids.finish()),
+            Arc::new(self.catalog_names.finish()),
+            Arc::new(self.schema_names.finish()),
+            Arc::new(self.table_names.finish()),
+            Arc::new(self.region_numbers.finish()),
+            Arc::new(self.inconsistency_types.finish()),
+            Arc::new(self.assigned_peer_ids.finish()),
+            Arc::new(self.detected_times.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaRegionInconsistencies {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_region_inconsistencies()
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
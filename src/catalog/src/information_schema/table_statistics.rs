@@ -0,0 +1,210 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::RecordBatch;
+use datafusion::datasource::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::vectors::{
+    Float64VectorBuilder, StringVectorBuilder, TimestampMillisecondVectorBuilder,
+    UInt64VectorBuilder,
+};
+use snafu::ResultExt;
+use table::stats::TableStatistics;
+
+use crate::error::{CreateRecordBatchSnafu, Result};
+use crate::CatalogProviderRef;
+
+/// The `information_schema.table_statistics` virtual table, listing the statistics of each
+/// column collected last time. Tables without statistics are not listed.
+pub(super) struct InformationSchemaTableStatistics {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_provider: CatalogProviderRef,
+}
+
+impl InformationSchemaTableStatistics {
+    pub(super) fn new(catalog_name: String, catalog_provider: CatalogProviderRef) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("table_catalog", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("table_schema", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("table_name", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("column_name", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("row_count", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(
+                "total_byte_size",
+                ConcreteDataType::uint64_datatype(),
+                false,
+            ),
+            ColumnSchema::new("null_count", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("null_fraction", ConcreteDataType::float64_datatype(), false),
+            ColumnSchema::new("distinct_count", ConcreteDataType::uint64_datatype(), true),
+            ColumnSchema::new(
+                "collected_at",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]));
+        Self {
+            schema,
+            catalog_name,
+            catalog_provider,
+        }
+    }
+
+    fn builder(&self) -> InformationSchemaTableStatisticsBuilder {
+        InformationSchemaTableStatisticsBuilder::new(
+            self.schema.clone(),
+            self.catalog_name.clone(),
+            self.catalog_provider.clone(),
+        )
+    }
+}
+
+struct InformationSchemaTableStatisticsBuilder {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_provider: CatalogProviderRef,
+
+    catalog_names: StringVectorBuilder,
+    schema_names: StringVectorBuilder,
+    table_names: StringVectorBuilder,
+    column_names: StringVectorBuilder,
+    row_counts: UInt64VectorBuilder,
+    total_byte_sizes: UInt64VectorBuilder,
+    null_counts: UInt64VectorBuilder,
+    null_fractions: Float64VectorBuilder,
+    distinct_counts: UInt64VectorBuilder,
+    collected_ats: TimestampMillisecondVectorBuilder,
+}
+
+impl InformationSchemaTableStatisticsBuilder {
+    fn new(schema: SchemaRef, catalog_name: String, catalog_provider: CatalogProviderRef) -> Self {
+        Self {
+            schema,
+            catalog_name,
+            catalog_provider,
+            catalog_names: StringVectorBuilder::with_capacity(42),
+            schema_names: StringVectorBuilder::with_capacity(42),
+            table_names: StringVectorBuilder::with_capacity(42),
+            column_names: StringVectorBuilder::with_capacity(42),
+            row_counts: UInt64VectorBuilder::with_capacity(42),
+            total_byte_sizes: UInt64VectorBuilder::with_capacity(42),
+            null_counts: UInt64VectorBuilder::with_capacity(42),
+            null_fractions: Float64VectorBuilder::with_capacity(42),
+            distinct_counts: UInt64VectorBuilder::with_capacity(42),
+            collected_ats: TimestampMillisecondVectorBuilder::with_capacity(42),
+        }
+    }
+
+    /// Construct the `information_schema.table_statistics` virtual table
+    async fn make_table_statistics(&mut self) -> Result<RecordBatch> {
+        let catalog_name = self.catalog_name.clone();
+
+        for schema_name in self.catalog_provider.schema_names().await? {
+            if schema_name == INFORMATION_SCHEMA_NAME {
+                continue;
+            }
+
+            let Some(schema) = self.catalog_provider.schema(&schema_name).await? else { continue };
+            for table_name in schema.table_names().await? {
+                let Some(table) = schema.table(&table_name).await? else { continue };
+                let Some(stats) = table.statistics() else { continue };
+                // Lists the columns in the order of the table schema.
+                for column_schema in table.schema().column_schemas() {
+                    self.add_column_statistics(
+                        &catalog_name,
+                        &schema_name,
+                        &table_name,
+                        &column_schema.name,
+                        &stats,
+                    );
+                }
+            }
+        }
+
+        self.finish()
+    }
+
+    fn add_column_statistics(
+        &mut self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        column_name: &str,
+        stats: &TableStatistics,
+    ) {
+        let Some(column_stats) = stats.columns.get(column_name) else { return };
+
+        self.catalog_names.push(Some(catalog_name));
+        self.schema_names.push(Some(schema_name));
+        self.table_names.push(Some(table_name));
+        self.column_names.push(Some(column_name));
+        self.row_counts.push(Some(stats.num_rows as u64));
+        self.total_byte_sizes
+            .push(Some(stats.total_byte_size as u64));
+        self.null_counts.push(Some(column_stats.null_count as u64));
+        self.null_fractions
+            .push(Some(column_stats.null_fraction(stats.num_rows)));
+        self.distinct_counts
+            .push(column_stats.distinct_count.map(|count| count as u64));
+        self.collected_ats
+            .push(Some(TimestampMillisecond::new(stats.collected_at_millis)));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.catalog_names.finish()),
+            Arc::new(self.schema_names.finish()),
+            Arc::new(self.table_names.finish()),
+            Arc::new(self.column_names.finish()),
+            Arc::new(self.row_counts.finish()),
+            Arc::new(self.total_byte_sizes.finish()),
+            Arc::new(self.null_counts.finish()),
+            Arc::new(self.null_fractions.finish()),
+            Arc::new(self.distinct_counts.finish()),
+            Arc::new(self.collected_ats.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaTableStatistics {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_table_statistics()
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
@@ -24,16 +24,23 @@ use snafu::ResultExt;
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
 use table::requests::CreateTableRequest;
+use table::stats::TableStatistics;
 use table::TableRef;
 
 use crate::error::{CreateTableSnafu, Result};
+use crate::helper::{
+    ClusterNodeKey, ClusterNodeValue, RegionInconsistencyKey, RegionInconsistencyValue,
+};
+use crate::notifier::CatalogEventReceiver;
 pub use crate::schema::{SchemaProvider, SchemaProviderRef};
 
+pub mod cluster;
 pub mod error;
 pub mod helper;
 pub(crate) mod information_schema;
 pub mod local;
 mod metrics;
+pub mod notifier;
 pub mod remote;
 pub mod schema;
 pub mod system;
@@ -107,6 +114,58 @@ pub trait CatalogManager: Send + Sync {
         table_name: &str,
     ) -> Result<Option<TableRef>>;
 
+    /// Returns the tables by catalog, schema and table names. The result is in the same order
+    /// of `table_names`, with `None` for tables that don't exist.
+    ///
+    /// Default implementation looks up the tables one by one, catalog managers backed by a
+    /// remote storage should override it to fetch all tables at once.
+    async fn get_tables(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table_names: &[&str],
+    ) -> Result<Vec<Option<TableRef>>> {
+        let mut tables = Vec::with_capacity(table_names.len());
+        for table_name in table_names {
+            tables.push(self.table(catalog, schema, table_name).await?);
+        }
+        Ok(tables)
+    }
+
+    /// Subscribes to the changes of catalogs, schemas and tables in this catalog manager.
+    /// Returns `None` if the catalog manager is not able to notify its changes.
+    fn subscribe(&self) -> Option<CatalogEventReceiver> {
+        None
+    }
+
+    /// Returns the nodes of the cluster this catalog manager belongs to, as reported to the
+    /// metasrv. Returns an empty list if the catalog manager is not in a cluster.
+    async fn cluster_nodes(&self) -> Result<Vec<(ClusterNodeKey, ClusterNodeValue)>> {
+        Ok(vec![])
+    }
+
+    /// Returns the inconsistencies between the regions served by datanodes and the table
+    /// routes detected by the metasrv. Returns an empty list if the catalog manager is not
+    /// in a cluster.
+    async fn region_inconsistencies(
+        &self,
+    ) -> Result<Vec<(RegionInconsistencyKey, RegionInconsistencyValue)>> {
+        Ok(vec![])
+    }
+
+    /// Persists the statistics of the table in the system catalog table, so they are restored
+    /// once the table is opened again. Does nothing if the catalog manager has no system
+    /// catalog table.
+    async fn persist_table_statistics(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _table_id: TableId,
+        _statistics: &TableStatistics,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn Any;
 }
 
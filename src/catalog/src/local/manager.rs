@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
@@ -30,9 +31,11 @@ use metrics::increment_gauge;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::manager::TableEngineManagerRef;
 use table::engine::EngineContext;
-use table::metadata::TableId;
+use table::metadata::{RawTableInfo, TableId, TableInfo, TableType};
 use table::requests::OpenTableRequest;
+use table::stats::TableStatistics;
 use table::table::numbers::NumbersTable;
+use table::table::view::ViewTable;
 use table::table::TableIdProvider;
 use table::TableRef;
 
@@ -43,6 +46,7 @@ use crate::error::{
     TableNotFoundSnafu,
 };
 use crate::local::memory::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider};
+use crate::notifier::{CatalogEvent, CatalogEventReceiver};
 use crate::system::{
     decode_system_catalog, Entry, SystemCatalogTable, TableEntry, ENTRY_TYPE_INDEX, KEY_INDEX,
     VALUE_INDEX,
@@ -204,6 +208,7 @@ impl LocalCatalogManager {
     async fn handle_system_catalog_entries(&self, entries: Vec<Entry>) -> Result<TableId> {
         let entries = Self::sort_entries(entries);
         let mut max_table_id = 0;
+        let mut table_names = HashMap::new();
         for entry in entries {
             match entry {
                 Entry::Catalog(c) => {
@@ -228,9 +233,30 @@ impl LocalCatalogManager {
                     info!("Registered schema: {:?}", s);
                 }
                 Entry::Table(t) => {
-                    self.open_and_register_table(&t).await?;
-                    info!("Registered table: {:?}", t);
+                    if let Some(view_info) = t.view_info.clone() {
+                        self.register_view(&t, view_info).await?;
+                    } else {
+                        self.open_and_register_table(&t).await?;
+                    }
+                    info!(
+                        "Registered table: {}.{}.{}, id: {}, engine: {}",
+                        t.catalog_name, t.schema_name, t.table_name, t.table_id, t.engine
+                    );
                     max_table_id = max_table_id.max(t.table_id);
+                    let _ = table_names
+                        .insert((t.catalog_name, t.schema_name, t.table_id), t.table_name);
+                }
+                Entry::TableStatistics(s) => {
+                    let key = (s.catalog_name, s.schema_name, s.table_id);
+                    let Some(table_name) = table_names.get(&key) else { continue };
+                    let (catalog_name, schema_name, _) = &key;
+                    if let Some(table) = self
+                        .catalogs
+                        .table(catalog_name, schema_name, table_name)
+                        .await?
+                    {
+                        table.restore_statistics(s.statistics);
+                    }
                 }
             }
         }
@@ -238,12 +264,43 @@ impl LocalCatalogManager {
     }
 
     /// Sort catalog entries to ensure catalog entries comes first, then schema entries,
-    /// and table entries is the last.
+    /// table entries, and table statistics entries is the last.
     fn sort_entries(mut entries: Vec<Entry>) -> Vec<Entry> {
-        entries.sort();
+        entries.sort_by_key(|entry| match entry {
+            Entry::Catalog(_) => 0,
+            Entry::Schema(_) => 1,
+            Entry::Table(_) => 2,
+            Entry::TableStatistics(_) => 3,
+        });
         entries
     }
 
+    /// Views are not opened by any table engine, they are restored from the view info
+    /// persisted in system catalog.
+    async fn register_view(&self, t: &TableEntry, view_info: RawTableInfo) -> Result<()> {
+        let schema = self
+            .catalogs
+            .schema(&t.catalog_name, &t.schema_name)
+            .await?
+            .context(SchemaNotFoundSnafu {
+                catalog: &t.catalog_name,
+                schema: &t.schema_name,
+            })?;
+        let view_info = view_info
+            .try_into()
+            .context(error::InvalidTableInfoInCatalogSnafu)?;
+        let view = ViewTable::try_from_table_info(Arc::new(view_info)).with_context(|_| {
+            error::InvalidViewSnafu {
+                view_name: &t.table_name,
+            }
+        })?;
+
+        schema
+            .register_table(t.table_name.clone(), Arc::new(view))
+            .await?;
+        Ok(())
+    }
+
     async fn open_and_register_table(&self, t: &TableEntry) -> Result<()> {
         let catalog =
             self.catalogs
@@ -357,25 +414,48 @@ impl CatalogManager for LocalCatalogManager {
                 // Try to register table with same table id, just ignore.
                 Ok(false)
             } else {
-                let engine = request.table.table_info().meta.engine.to_string();
+                let table_info = request.table.table_info();
                 // table does not exist
-                self.system
-                    .register_table(
-                        catalog_name.clone(),
-                        schema_name.clone(),
-                        request.table_name.clone(),
-                        request.table_id,
-                        engine,
-                    )
-                    .await?;
+                if table_info.table_type == TableType::View {
+                    self.system
+                        .register_view(
+                            catalog_name.clone(),
+                            schema_name.clone(),
+                            request.table_id,
+                            RawTableInfo::from(TableInfo::clone(&table_info)),
+                        )
+                        .await?;
+                } else {
+                    self.system
+                        .register_table(
+                            catalog_name.clone(),
+                            schema_name.clone(),
+                            request.table_name.clone(),
+                            request.table_id,
+                            table_info.meta.engine.to_string(),
+                        )
+                        .await?;
+                }
+                // The table may be created with a given id, e.g. when it's imported from
+                // another instance, which must not be allocated again.
+                let _ = self
+                    .next_table_id
+                    .fetch_max(request.table_id + 1, Ordering::Relaxed);
                 schema
-                    .register_table(request.table_name, request.table)
+                    .register_table(request.table_name.clone(), request.table)
                     .await?;
                 increment_gauge!(
                     crate::metrics::METRIC_CATALOG_MANAGER_TABLE_COUNT,
                     1.0,
                     &[crate::metrics::db_label(catalog_name, schema_name)],
                 );
+                self.catalogs
+                    .notifier()
+                    .notify(CatalogEvent::TableRegistered {
+                        catalog: catalog_name.clone(),
+                        schema: schema_name.clone(),
+                        table_name: request.table_name,
+                    });
                 Ok(true)
             }
         }
@@ -438,6 +518,14 @@ impl CatalogManager for LocalCatalogManager {
             .rename_table(&request.table_name, request.new_table_name.clone())
             .await
             .is_ok();
+        if renamed {
+            self.catalogs.notifier().notify(CatalogEvent::TableRenamed {
+                catalog: request.catalog,
+                schema: request.schema,
+                table_name: request.table_name,
+                new_table_name: request.new_table_name,
+            });
+        }
         Ok(renamed)
     }
 
@@ -500,11 +588,20 @@ impl CatalogManager for LocalCatalogManager {
                 }
             );
             self.system
-                .register_schema(request.catalog, schema_name.clone())
+                .register_schema(request.catalog.clone(), schema_name.clone())
                 .await?;
             catalog
-                .register_schema(request.schema, Arc::new(MemorySchemaProvider::new()))
+                .register_schema(
+                    request.schema.clone(),
+                    Arc::new(MemorySchemaProvider::new()),
+                )
                 .await?;
+            self.catalogs
+                .notifier()
+                .notify(CatalogEvent::SchemaRegistered {
+                    catalog: request.catalog,
+                    schema: request.schema,
+                });
 
             Ok(true)
         }
@@ -583,6 +680,22 @@ impl CatalogManager for LocalCatalogManager {
         self.catalogs.register_catalog(name, catalog).await
     }
 
+    fn subscribe(&self) -> Option<CatalogEventReceiver> {
+        self.catalogs.subscribe()
+    }
+
+    async fn persist_table_statistics(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table_id: TableId,
+        statistics: &TableStatistics,
+    ) -> Result<()> {
+        self.system
+            .persist_table_statistics(catalog, schema, table_id, statistics)
+            .await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -606,6 +719,7 @@ mod tests {
                 table_name: "T1".to_string(),
                 table_id: 1,
                 engine: MITO_ENGINE.to_string(),
+                view_info: None,
             }),
             Entry::Catalog(CatalogEntry {
                 catalog_name: "C2".to_string(),
@@ -627,6 +741,7 @@ mod tests {
                 table_name: "T2".to_string(),
                 table_id: 2,
                 engine: MITO_ENGINE.to_string(),
+                view_info: None,
             }),
         ];
         let res = LocalCatalogManager::sort_entries(vec);
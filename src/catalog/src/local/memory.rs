@@ -30,6 +30,7 @@ use table::TableRef;
 use crate::error::{
     self, CatalogNotFoundSnafu, Result, SchemaNotFoundSnafu, TableExistsSnafu, TableNotFoundSnafu,
 };
+use crate::notifier::{CatalogEvent, CatalogEventNotifier, CatalogEventReceiver};
 use crate::schema::SchemaProvider;
 use crate::{
     CatalogManager, CatalogProvider, CatalogProviderRef, DeregisterTableRequest,
@@ -42,6 +43,7 @@ pub struct MemoryCatalogManager {
     /// Collection of catalogs containing schemas and ultimately Tables
     pub catalogs: RwLock<HashMap<String, CatalogProviderRef>>,
     pub table_id: AtomicU32,
+    notifier: CatalogEventNotifier,
 }
 
 impl Default for MemoryCatalogManager {
@@ -49,6 +51,7 @@ impl Default for MemoryCatalogManager {
         let manager = Self {
             table_id: AtomicU32::new(MIN_USER_TABLE_ID),
             catalogs: Default::default(),
+            notifier: Default::default(),
         };
         let default_catalog = Arc::new(MemoryCatalogProvider::new());
         manager
@@ -92,10 +95,17 @@ impl CatalogManager for MemoryCatalogManager {
             1.0,
             &[crate::metrics::db_label(&request.catalog, &request.schema)],
         );
-        schema
+        let event = CatalogEvent::TableRegistered {
+            catalog: request.catalog,
+            schema: request.schema,
+            table_name: request.table_name.clone(),
+        };
+        let registered = schema
             .register_table(request.table_name, request.table)
             .await
-            .map(|v| v.is_none())
+            .map(|v| v.is_none())?;
+        self.notifier.notify(event);
+        Ok(registered)
     }
 
     async fn rename_table(&self, request: RenameTableRequest) -> Result<bool> {
@@ -112,10 +122,19 @@ impl CatalogManager for MemoryCatalogManager {
                     catalog: &request.catalog,
                     schema: &request.schema,
                 })?;
-        Ok(schema
-            .rename_table(&request.table_name, request.new_table_name)
+        let renamed = schema
+            .rename_table(&request.table_name, request.new_table_name.clone())
             .await
-            .is_ok())
+            .is_ok();
+        if renamed {
+            self.notifier.notify(CatalogEvent::TableRenamed {
+                catalog: request.catalog,
+                schema: request.schema,
+                table_name: request.table_name,
+                new_table_name: request.new_table_name,
+            });
+        }
+        Ok(renamed)
     }
 
     async fn deregister_table(&self, request: DeregisterTableRequest) -> Result<bool> {
@@ -135,10 +154,18 @@ impl CatalogManager for MemoryCatalogManager {
             1.0,
             &[crate::metrics::db_label(&request.catalog, &request.schema)],
         );
-        schema
+        let deregistered = schema
             .deregister_table(&request.table_name)
             .await
-            .map(|v| v.is_some())
+            .map(|v| v.is_some())?;
+        if deregistered {
+            self.notifier.notify(CatalogEvent::TableDeregistered {
+                catalog: request.catalog,
+                schema: request.schema,
+                table_name: request.table_name,
+            });
+        }
+        Ok(deregistered)
     }
 
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
@@ -148,9 +175,16 @@ impl CatalogManager for MemoryCatalogManager {
                 catalog_name: &request.catalog,
             })?;
         catalog
-            .register_schema(request.schema, Arc::new(MemorySchemaProvider::new()))
+            .register_schema(
+                request.schema.clone(),
+                Arc::new(MemorySchemaProvider::new()),
+            )
             .await?;
         increment_gauge!(crate::metrics::METRIC_CATALOG_MANAGER_SCHEMA_COUNT, 1.0);
+        self.notifier.notify(CatalogEvent::SchemaRegistered {
+            catalog: request.catalog,
+            schema: request.schema,
+        });
         Ok(true)
     }
 
@@ -196,12 +230,20 @@ impl CatalogManager for MemoryCatalogManager {
         self.register_catalog_sync(name, catalog)
     }
 
+    fn subscribe(&self) -> Option<CatalogEventReceiver> {
+        Some(self.notifier.subscribe())
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
 impl MemoryCatalogManager {
+    pub(crate) fn notifier(&self) -> &CatalogEventNotifier {
+        &self.notifier
+    }
+
     /// Registers a catalog and return `None` if no catalog with the same name was already
     /// registered, or `Some` with the previously registered catalog.
     pub fn register_catalog_if_absent(
@@ -589,4 +631,45 @@ mod tests {
             .unwrap();
         assert!(!schema.table_exist("numbers").await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_catalog_events() {
+        let catalog = MemoryCatalogManager::default();
+        let mut receiver = catalog.subscribe().unwrap();
+
+        let register_table_req = RegisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "numbers".to_string(),
+            table_id: 2333,
+            table: Arc::new(NumbersTable::default()),
+        };
+        catalog.register_table(register_table_req).await.unwrap();
+        assert_eq!(
+            CatalogEvent::TableRegistered {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: "numbers".to_string(),
+            },
+            receiver.recv().await.unwrap()
+        );
+
+        let deregister_table_req = DeregisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "numbers".to_string(),
+        };
+        catalog
+            .deregister_table(deregister_table_req)
+            .await
+            .unwrap();
+        assert_eq!(
+            CatalogEvent::TableDeregistered {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: "numbers".to_string(),
+            },
+            receiver.recv().await.unwrap()
+        );
+    }
 }
@@ -0,0 +1,321 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use common_telemetry::{debug, warn};
+use futures_util::StreamExt;
+use snafu::ResultExt;
+use table::metadata::TableId;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::error::{InvalidCatalogValueSnafu, Result};
+use crate::helper::{
+    TableGlobalKey, TableGlobalValue, TABLES_VERSION_KEY, TABLE_GLOBAL_KEY_PREFIX,
+};
+use crate::remote::{Kv, KvBackendRef};
+
+const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Default interval of polling the tables version in a [KvBackendWatcher], used only if the
+/// backend can't push the changes.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Changes happened in a catalog manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogEvent {
+    SchemaRegistered {
+        catalog: String,
+        schema: String,
+    },
+    TableRegistered {
+        catalog: String,
+        schema: String,
+        table_name: String,
+    },
+    TableDeregistered {
+        catalog: String,
+        schema: String,
+        table_name: String,
+    },
+    TableRenamed {
+        catalog: String,
+        schema: String,
+        table_name: String,
+        new_table_name: String,
+    },
+}
+
+pub type CatalogEventReceiver = broadcast::Receiver<CatalogEvent>;
+
+/// Broadcasts [CatalogEvent]s to all subscribers.
+///
+/// Sending an event never blocks. A subscriber lagging behind more than the channel capacity
+/// will receive a [RecvError::Lagged](broadcast::error::RecvError::Lagged), and should treat
+/// all its cached states as stale.
+#[derive(Clone)]
+pub struct CatalogEventNotifier {
+    sender: broadcast::Sender<CatalogEvent>,
+}
+
+impl Default for CatalogEventNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHANNEL_CAPACITY)
+    }
+}
+
+impl CatalogEventNotifier {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> CatalogEventReceiver {
+        self.sender.subscribe()
+    }
+
+    pub fn notify(&self, event: CatalogEvent) {
+        debug!("Catalog event: {:?}", event);
+        // An error is returned only if there's no subscriber, which is fine.
+        let _ = self.sender.send(event);
+    }
+}
+
+/// Watches the table keys stored in a [KvBackend](crate::remote::KvBackend), and turns the
+/// changes made by other nodes into [CatalogEvent]s.
+///
+/// The watcher watches the tables version (see [bump_tables_version]) through
+/// [KvBackend::watch](crate::remote::KvBackend::watch), and only scans the table global keys to
+/// compare them with the last seen ones when the version changes. Backends pushing the changes
+/// notify the watcher immediately, others are polled every `interval` (the metasrv store doesn't
+/// support watching keys yet, but reading the version is a single key lookup). A table that is
+/// dropped and created again (with a different table id) between two scans is reported as
+/// deregistered and then registered.
+pub struct KvBackendWatcher {
+    backend: KvBackendRef,
+    notifier: CatalogEventNotifier,
+    interval: Duration,
+    tables: HashMap<String, TableId>,
+    /// Tables version of the last scan, `None` if the tables have never been scanned.
+    version: Option<Option<Vec<u8>>>,
+}
+
+impl KvBackendWatcher {
+    pub fn new(backend: KvBackendRef, notifier: CatalogEventNotifier) -> Self {
+        Self {
+            backend,
+            notifier,
+            interval: DEFAULT_WATCH_INTERVAL,
+            tables: HashMap::new(),
+            version: None,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Starts watching in background. The first scan only records existing tables, no event
+    /// will be emitted for them.
+    pub fn start(mut self) -> JoinHandle<()> {
+        common_runtime::spawn_bg(async move {
+            let backend = self.backend.clone();
+            let mut versions = backend.watch(TABLES_VERSION_KEY.as_bytes(), self.interval);
+            let mut emit = false;
+            while let Some(version) = versions.next().await {
+                let result = match version {
+                    Ok(version) => self.scan(version, emit).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    warn!(
+                        "Failed to watch table changes in catalog watcher, error: {}",
+                        e
+                    );
+                }
+                emit = true;
+            }
+        })
+    }
+
+    /// Reads the tables version once and scans the table keys if the version changed, see
+    /// [KvBackendWatcher::scan].
+    pub async fn poll(&mut self, emit: bool) -> Result<()> {
+        let version = self
+            .backend
+            .get(TABLES_VERSION_KEY.as_bytes())
+            .await?
+            .map(|kv| kv.1);
+        self.scan(version, emit).await
+    }
+
+    /// Scans the table keys if the tables `version` differs from the last scanned one, and
+    /// notifies the changes since last scan if `emit` is true. The version must be read before
+    /// scanning, so changes made during the scan are picked up by the next one.
+    async fn scan(&mut self, version: Option<Vec<u8>>, emit: bool) -> Result<()> {
+        if self.version.as_ref() == Some(&version) {
+            return Ok(());
+        }
+
+        let mut current = HashMap::with_capacity(self.tables.len());
+        let mut iter = self.backend.range(TABLE_GLOBAL_KEY_PREFIX.as_bytes());
+        while let Some(r) = iter.next().await {
+            let Kv(k, v) = r?;
+            let key = String::from_utf8_lossy(&k).to_string();
+            if TableGlobalKey::parse(&key).is_err() {
+                continue;
+            }
+            let value = TableGlobalValue::from_bytes(v).context(InvalidCatalogValueSnafu)?;
+            current.insert(key, value.table_id());
+        }
+
+        if emit {
+            for event in diff_tables(&self.tables, &current) {
+                self.notifier.notify(event);
+            }
+        }
+        self.tables = current;
+        self.version = Some(version);
+        Ok(())
+    }
+}
+
+/// Bumps the tables version stored in the `backend`, so that the [KvBackendWatcher]s of all
+/// nodes rescan the tables. Must be called after a table is created, dropped or renamed.
+pub async fn bump_tables_version(backend: &KvBackendRef) -> Result<()> {
+    loop {
+        let current = backend
+            .get(TABLES_VERSION_KEY.as_bytes())
+            .await?
+            .map(|kv| kv.1)
+            .unwrap_or_default();
+        let version = std::str::from_utf8(&current)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        let next = (version + 1).to_string();
+        if backend
+            .compare_and_set(TABLES_VERSION_KEY.as_bytes(), &current, next.as_bytes())
+            .await?
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+}
+
+fn diff_tables(
+    previous: &HashMap<String, TableId>,
+    current: &HashMap<String, TableId>,
+) -> Vec<CatalogEvent> {
+    let deregistered = |key: &TableGlobalKey| CatalogEvent::TableDeregistered {
+        catalog: key.catalog_name.clone(),
+        schema: key.schema_name.clone(),
+        table_name: key.table_name.clone(),
+    };
+    let registered = |key: &TableGlobalKey| CatalogEvent::TableRegistered {
+        catalog: key.catalog_name.clone(),
+        schema: key.schema_name.clone(),
+        table_name: key.table_name.clone(),
+    };
+
+    let mut events = Vec::new();
+    for (k, table_id) in previous {
+        let Ok(key) = TableGlobalKey::parse(k) else { continue };
+        match current.get(k) {
+            None => events.push(deregistered(&key)),
+            Some(id) if id != table_id => {
+                events.push(deregistered(&key));
+                events.push(registered(&key));
+            }
+            _ => {}
+        }
+    }
+    for k in current.keys() {
+        if previous.contains_key(k) {
+            continue;
+        }
+        let Ok(key) = TableGlobalKey::parse(k) else { continue };
+        events.push(registered(&key));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: &str, table_name: &str) -> CatalogEvent {
+        let (catalog, schema, table_name) = (
+            "greptime".to_string(),
+            "public".to_string(),
+            table_name.to_string(),
+        );
+        match kind {
+            "registered" => CatalogEvent::TableRegistered {
+                catalog,
+                schema,
+                table_name,
+            },
+            _ => CatalogEvent::TableDeregistered {
+                catalog,
+                schema,
+                table_name,
+            },
+        }
+    }
+
+    #[test]
+    fn test_diff_tables() {
+        let previous = HashMap::from([
+            ("__tg-greptime-public-a".to_string(), 1024),
+            ("__tg-greptime-public-b".to_string(), 1025),
+            ("__tg-greptime-public-c".to_string(), 1026),
+        ]);
+        let current = HashMap::from([
+            ("__tg-greptime-public-a".to_string(), 1024),
+            ("__tg-greptime-public-c".to_string(), 1028),
+            ("__tg-greptime-public-d".to_string(), 1027),
+        ]);
+
+        let mut events = diff_tables(&previous, &current);
+        events.sort_by_key(|e| format!("{e:?}"));
+        let mut expected = vec![
+            event("deregistered", "b"),
+            event("deregistered", "c"),
+            event("registered", "c"),
+            event("registered", "d"),
+        ];
+        expected.sort_by_key(|e| format!("{e:?}"));
+        assert_eq!(expected, events);
+
+        assert!(diff_tables(&current, &current).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_notify() {
+        let notifier = CatalogEventNotifier::default();
+        // Notifying without subscribers must not fail.
+        notifier.notify(event("registered", "a"));
+
+        let mut rx1 = notifier.subscribe();
+        let mut rx2 = notifier.subscribe();
+        notifier.notify(event("deregistered", "a"));
+        assert_eq!(event("deregistered", "a"), rx1.recv().await.unwrap());
+        assert_eq!(event("deregistered", "a"), rx2.recv().await.unwrap());
+    }
+}
@@ -15,7 +15,9 @@
 use std::fmt::Debug;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
+use async_stream::stream;
 pub use client::MetaKvBackend;
 use futures::Stream;
 use futures_util::StreamExt;
@@ -31,6 +33,9 @@ pub struct Kv(pub Vec<u8>, pub Vec<u8>);
 
 pub type ValueIter<'a, E> = Pin<Box<dyn Stream<Item = Result<Kv, E>> + Send + 'a>>;
 
+/// Stream of the values of a watched key, `None` if the key doesn't exist.
+pub type ValueWatch<'a, E> = Pin<Box<dyn Stream<Item = Result<Option<Vec<u8>>, E>> + Send + 'a>>;
+
 #[async_trait::async_trait]
 pub trait KvBackend: Send + Sync {
     fn range<'a, 'b>(&'a self, key: &[u8]) -> ValueIter<'b, Error>
@@ -70,6 +75,44 @@ pub trait KvBackend: Send + Sync {
         }
         return Ok(None);
     }
+
+    /// Gets the values of given keys, keys that don't exist are absent in the result.
+    /// Default batch get is implemented by getting keys one by one.
+    async fn batch_get(&self, keys: &[Vec<u8>]) -> Result<Vec<Kv>, Error> {
+        let mut kvs = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(kv) = self.get(key).await? {
+                kvs.push(kv);
+            }
+        }
+        Ok(kvs)
+    }
+
+    /// Watches the value of the key. The stream yields the current value first, then the new
+    /// value every time it changes. Errors are yielded without ending the stream.
+    ///
+    /// Default watch is implemented by getting the key every `interval`, backends able to
+    /// push the changes should override it.
+    fn watch<'a>(&'a self, key: &[u8], interval: Duration) -> ValueWatch<'a, Error> {
+        let key = key.to_vec();
+        Box::pin(stream!({
+            let mut ticker = tokio::time::interval(interval);
+            let mut last = None;
+            loop {
+                let _ = ticker.tick().await;
+                match self.get(&key).await {
+                    Ok(kv) => {
+                        let value = kv.map(|kv| kv.1);
+                        if last.as_ref() != Some(&value) {
+                            last = Some(value.clone());
+                            yield Ok(value);
+                        }
+                    }
+                    Err(e) => yield Err(e),
+                }
+            }
+        }))
+    }
 }
 
 pub type KvBackendRef = Arc<dyn KvBackend>;
@@ -128,4 +171,31 @@ mod tests {
         let result = backend.get(3.to_string().as_bytes()).await;
         assert!(result.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_default_watch() {
+        let backend = MockKvBackend {};
+        let mut watch = backend.watch(1.to_string().as_bytes(), Duration::from_millis(10));
+        let value = watch.next().await.unwrap().unwrap();
+        assert_eq!(Some(1.to_string().into_bytes()), value);
+        // The value never changes, so nothing more is yielded.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), watch.next())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_get() {
+        let backend = MockKvBackend {};
+        let keys = [0, 2, 3]
+            .iter()
+            .map(|i| i.to_string().into_bytes())
+            .collect::<Vec<_>>();
+        let result = backend.batch_get(&keys).await.unwrap();
+        assert_eq!(2, result.len());
+        assert_eq!(0.to_string().as_bytes(), result[0].0);
+        assert_eq!(2.to_string().as_bytes(), result[1].0);
+    }
 }
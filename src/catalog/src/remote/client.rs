@@ -18,7 +18,9 @@ use std::sync::Arc;
 use async_stream::stream;
 use common_telemetry::info;
 use meta_client::client::MetaClient;
-use meta_client::rpc::{CompareAndPutRequest, DeleteRangeRequest, PutRequest, RangeRequest};
+use meta_client::rpc::{
+    BatchGetRequest, CompareAndPutRequest, DeleteRangeRequest, PutRequest, RangeRequest,
+};
 use snafu::ResultExt;
 
 use crate::error::{Error, MetaSrvSnafu};
@@ -63,6 +65,18 @@ impl KvBackend for MetaKvBackend {
             .map(|kv| Kv(kv.take_key(), kv.take_value())))
     }
 
+    async fn batch_get(&self, keys: &[Vec<u8>]) -> Result<Vec<Kv>, Error> {
+        let req = keys
+            .iter()
+            .fold(BatchGetRequest::new(), |req, key| req.add_key(key.clone()));
+        let mut response = self.client.batch_get(req).await.context(MetaSrvSnafu)?;
+        Ok(response
+            .take_kvs()
+            .into_iter()
+            .map(|mut kv| Kv(kv.take_key(), kv.take_value()))
+            .collect())
+    }
+
     async fn set(&self, key: &[u8], val: &[u8]) -> Result<(), Error> {
         let req = PutRequest::new()
             .with_key(key.to_vec())
@@ -30,20 +30,24 @@ use snafu::{OptionExt, ResultExt};
 use table::engine::manager::TableEngineManagerRef;
 use table::engine::{EngineContext, TableReference};
 use table::metadata::TableId;
-use table::requests::{CreateTableRequest, OpenTableRequest};
+use table::requests::{CreateTableRequest, DropTableRequest, OpenTableRequest};
 use table::TableRef;
 use tokio::sync::Mutex;
 
+use crate::cluster::{list_cluster_nodes, list_region_inconsistencies};
 use crate::error::{
-    CatalogNotFoundSnafu, CreateTableSnafu, InvalidCatalogValueSnafu, OpenTableSnafu,
-    ParallelOpenTableSnafu, Result, SchemaNotFoundSnafu, TableEngineNotFoundSnafu,
+    CatalogNotFoundSnafu, CloseTableSnafu, CreateTableSnafu, InvalidCatalogValueSnafu,
+    OpenTableSnafu, ParallelOpenTableSnafu, Result, SchemaNotFoundSnafu, TableEngineNotFoundSnafu,
     TableExistsSnafu, UnimplementedSnafu,
 };
 use crate::helper::{
     build_catalog_prefix, build_schema_prefix, build_table_global_prefix,
-    build_table_regional_prefix, CatalogKey, CatalogValue, SchemaKey, SchemaValue, TableGlobalKey,
+    build_table_regional_prefix, CatalogKey, CatalogValue, ClusterNodeKey, ClusterNodeValue,
+    RegionInconsistencyKey, RegionInconsistencyValue, SchemaKey, SchemaValue, TableGlobalKey,
     TableGlobalValue, TableRegionalKey, TableRegionalValue, CATALOG_KEY_PREFIX,
+    TABLE_GLOBAL_KEY_PREFIX,
 };
+use crate::notifier::{CatalogEvent, CatalogEventNotifier, CatalogEventReceiver};
 use crate::remote::{Kv, KvBackendRef};
 use crate::{
     handle_system_table_request, CatalogManager, CatalogProvider, CatalogProviderRef,
@@ -58,6 +62,22 @@ pub struct RemoteCatalogManager {
     catalogs: Arc<RwLock<DashMap<String, CatalogProviderRef>>>,
     engine_manager: TableEngineManagerRef,
     system_table_requests: Mutex<Vec<RegisterSystemTableRequest>>,
+    notifier: CatalogEventNotifier,
+    /// Tables diverging from their routes found by the last [Self::reconcile_tables].
+    divergences: Mutex<HashSet<TableDivergence>>,
+}
+
+/// A table whose state on this datanode diverges from its route.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum TableDivergence {
+    /// The table is assigned to this datanode but not opened.
+    NotOpened(String),
+    /// The table is opened but not assigned to this datanode.
+    NotAssigned {
+        catalog: String,
+        schema: String,
+        table: String,
+    },
 }
 
 impl RemoteCatalogManager {
@@ -68,6 +88,8 @@ impl RemoteCatalogManager {
             backend,
             catalogs: Default::default(),
             system_table_requests: Default::default(),
+            notifier: Default::default(),
+            divergences: Default::default(),
         }
     }
 
@@ -330,6 +352,165 @@ impl RemoteCatalogManager {
         info!("Created catalog '{catalog_key}");
         Ok(catalog_provider)
     }
+
+    /// Opens the tables metasrv assigns to this datanode but not opened, and closes the
+    /// opened tables not assigned to it any more, e.g. after their routes are repaired by
+    /// metasrv. Closing a table keeps its data.
+    ///
+    /// A table is only opened or closed if it still diverges from its route in the next
+    /// call, to leave the tables being created or dropped alone.
+    pub async fn reconcile_tables(&self) -> Result<()> {
+        let mut assigned = HashMap::new();
+        let prefix = format!("{TABLE_GLOBAL_KEY_PREFIX}-");
+        let mut tables = self.backend.range(prefix.as_bytes());
+        while let Some(r) = tables.next().await {
+            let Kv(k, v) = r?;
+            if !k.starts_with(prefix.as_bytes()) {
+                continue;
+            }
+            let table_key = TableGlobalKey::parse(String::from_utf8_lossy(&k))
+                .context(InvalidCatalogValueSnafu)?;
+            let table_value = TableGlobalValue::from_bytes(&v).context(InvalidCatalogValueSnafu)?;
+            if table_value
+                .regions_id_map
+                .get(&self.node_id)
+                .map(|v| !v.is_empty())
+                .unwrap_or(false)
+            {
+                let _ = assigned.insert(table_key.to_string(), (table_key, table_value));
+            }
+        }
+
+        let mut divergences = HashSet::new();
+        let mut opened = HashSet::new();
+        for catalog_name in self.catalog_names().await? {
+            let Some(catalog) = self.catalog(&catalog_name).await? else { continue };
+            for schema_name in catalog.schema_names().await? {
+                let Some(schema) = catalog.schema(&schema_name).await? else { continue };
+                for table_name in schema.table_names().await? {
+                    let Some(table) = schema.table(&table_name).await? else { continue };
+                    let table_info = table.table_info();
+                    if table_info.ident.table_id <= MAX_SYS_TABLE_ID
+                        || table_info.meta.engine != MITO_ENGINE
+                    {
+                        continue;
+                    }
+
+                    let table_key = TableGlobalKey {
+                        catalog_name: catalog_name.clone(),
+                        schema_name: schema_name.clone(),
+                        table_name: table_name.clone(),
+                    }
+                    .to_string();
+                    if !assigned.contains_key(&table_key) {
+                        let _ = divergences.insert(TableDivergence::NotAssigned {
+                            catalog: catalog_name.clone(),
+                            schema: schema_name.clone(),
+                            table: table_name,
+                        });
+                    }
+                    let _ = opened.insert(table_key);
+                }
+            }
+        }
+        for table_key in assigned.keys() {
+            if !opened.contains(table_key) {
+                let _ = divergences.insert(TableDivergence::NotOpened(table_key.clone()));
+            }
+        }
+
+        let mut last_divergences = self.divergences.lock().await;
+        for divergence in divergences.iter().filter(|d| last_divergences.contains(d)) {
+            let result = match divergence {
+                TableDivergence::NotOpened(table_key) => {
+                    let (table_key, table_value) = &assigned[table_key];
+                    self.open_assigned_table(table_key, table_value).await
+                }
+                TableDivergence::NotAssigned {
+                    catalog,
+                    schema,
+                    table,
+                } => self.close_unassigned_table(catalog, schema, table).await,
+            };
+            if let Err(e) = result {
+                error!(e; "Failed to reconcile table {:?}", divergence);
+            }
+        }
+        *last_divergences = divergences;
+
+        Ok(())
+    }
+
+    async fn open_assigned_table(
+        &self,
+        table_key: &TableGlobalKey,
+        table_value: &TableGlobalValue,
+    ) -> Result<()> {
+        let TableGlobalKey {
+            catalog_name,
+            schema_name,
+            table_name,
+        } = table_key;
+        let schema =
+            self.schema(catalog_name, schema_name)
+                .await?
+                .context(SchemaNotFoundSnafu {
+                    catalog: catalog_name,
+                    schema: schema_name,
+                })?;
+
+        let table = open_or_create_table(
+            self.node_id,
+            self.engine_manager.clone(),
+            table_key,
+            table_value,
+        )
+        .await?;
+        let _ = schema.register_table(table_name.clone(), table).await?;
+        increment_gauge!(
+            crate::metrics::METRIC_CATALOG_MANAGER_TABLE_COUNT,
+            1.0,
+            &[crate::metrics::db_label(catalog_name, schema_name)],
+        );
+        info!(
+            "Opened table {} assigned to datanode {}",
+            table_key, self.node_id
+        );
+        Ok(())
+    }
+
+    async fn close_unassigned_table(&self, catalog: &str, schema: &str, table: &str) -> Result<()> {
+        let _ = self
+            .deregister_table(DeregisterTableRequest {
+                catalog: catalog.to_string(),
+                schema: schema.to_string(),
+                table_name: table.to_string(),
+            })
+            .await?;
+
+        let engine = self
+            .engine_manager
+            .engine(MITO_ENGINE)
+            .context(TableEngineNotFoundSnafu {
+                engine_name: MITO_ENGINE,
+            })?;
+        let request = DropTableRequest {
+            catalog_name: catalog.to_string(),
+            schema_name: schema.to_string(),
+            table_name: table.to_string(),
+        };
+        let _ = engine
+            .drop_table(&EngineContext {}, request)
+            .await
+            .with_context(|_| CloseTableSnafu {
+                table_info: format!("{catalog}.{schema}.{table}"),
+            })?;
+        info!(
+            "Closed table {}.{}.{} not assigned to datanode {}",
+            catalog, schema, table, self.node_id
+        );
+        Ok(())
+    }
 }
 
 async fn open_or_create_table(
@@ -472,8 +653,13 @@ impl CatalogManager for RemoteCatalogManager {
             &[crate::metrics::db_label(&catalog_name, &schema_name)],
         );
         schema_provider
-            .register_table(request.table_name, request.table)
+            .register_table(request.table_name.clone(), request.table)
             .await?;
+        self.notifier.notify(CatalogEvent::TableRegistered {
+            catalog: catalog_name,
+            schema: schema_name,
+            table_name: request.table_name,
+        });
 
         Ok(true)
     }
@@ -490,11 +676,18 @@ impl CatalogManager for RemoteCatalogManager {
             })?
             .deregister_table(&request.table_name)
             .await?;
-        decrement_gauge!(
-            crate::metrics::METRIC_CATALOG_MANAGER_TABLE_COUNT,
-            1.0,
-            &[crate::metrics::db_label(catalog_name, schema_name)],
-        );
+        if result.is_some() {
+            decrement_gauge!(
+                crate::metrics::METRIC_CATALOG_MANAGER_TABLE_COUNT,
+                1.0,
+                &[crate::metrics::db_label(catalog_name, schema_name)],
+            );
+            self.notifier.notify(CatalogEvent::TableDeregistered {
+                catalog: request.catalog,
+                schema: request.schema,
+                table_name: request.table_name,
+            });
+        }
         Ok(result.is_none())
     }
 
@@ -509,9 +702,13 @@ impl CatalogManager for RemoteCatalogManager {
                 })?;
         let schema_provider = self.new_schema_provider(&catalog_name, &schema_name);
         catalog_provider
-            .register_schema(schema_name, schema_provider)
+            .register_schema(schema_name.clone(), schema_provider)
             .await?;
         increment_gauge!(crate::metrics::METRIC_CATALOG_MANAGER_SCHEMA_COUNT, 1.0);
+        self.notifier.notify(CatalogEvent::SchemaRegistered {
+            catalog: catalog_name,
+            schema: schema_name,
+        });
         Ok(true)
     }
 
@@ -529,7 +726,7 @@ impl CatalogManager for RemoteCatalogManager {
         let new_table_key = TableRegionalKey {
             catalog_name: request.catalog.clone(),
             schema_name: request.schema.clone(),
-            table_name: request.new_table_name,
+            table_name: request.new_table_name.clone(),
             node_id: self.node_id,
         };
         self.backend
@@ -538,6 +735,12 @@ impl CatalogManager for RemoteCatalogManager {
         self.backend
             .delete(old_table_key.to_string().as_bytes())
             .await?;
+        self.notifier.notify(CatalogEvent::TableRenamed {
+            catalog: request.catalog,
+            schema: request.schema,
+            table_name: request.table_name,
+            new_table_name: request.new_table_name,
+        });
         Ok(true)
     }
 
@@ -632,6 +835,20 @@ impl CatalogManager for RemoteCatalogManager {
         Ok(None)
     }
 
+    fn subscribe(&self) -> Option<CatalogEventReceiver> {
+        Some(self.notifier.subscribe())
+    }
+
+    async fn cluster_nodes(&self) -> Result<Vec<(ClusterNodeKey, ClusterNodeValue)>> {
+        list_cluster_nodes(&self.backend).await
+    }
+
+    async fn region_inconsistencies(
+        &self,
+    ) -> Result<Vec<(RegionInconsistencyKey, RegionInconsistencyValue)>> {
+        list_region_inconsistencies(&self.backend).await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -31,10 +31,11 @@ use datatypes::vectors::{BinaryVector, TimestampMillisecondVector, UInt8Vector};
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
-use table::metadata::{TableId, TableInfoRef};
+use table::metadata::{RawTableInfo, TableId, TableInfoRef};
 use table::requests::{
     CreateTableRequest, DeleteRequest, InsertRequest, OpenTableRequest, TableOptions,
 };
+use table::stats::TableStatistics;
 use table::{Table, TableRef};
 
 use crate::error::{
@@ -201,12 +202,67 @@ pub fn build_table_insert_request(
     build_insert_request(
         EntryType::Table,
         entry_key.as_bytes(),
-        serde_json::to_string(&TableEntryValue { table_name, engine })
-            .unwrap()
-            .as_bytes(),
+        serde_json::to_string(&TableEntryValue {
+            table_name,
+            engine,
+            view_info: None,
+        })
+        .unwrap()
+        .as_bytes(),
     )
 }
 
+/// Builds the request to insert a view entry. Views are stored as table entries with the
+/// whole view info, since there is no table engine to open them.
+pub fn build_view_insert_request(
+    catalog: String,
+    schema: String,
+    table_id: TableId,
+    view_info: RawTableInfo,
+) -> InsertRequest {
+    let entry_key = format_table_entry_key(&catalog, &schema, table_id);
+    build_insert_request(
+        EntryType::Table,
+        entry_key.as_bytes(),
+        serde_json::to_string(&TableEntryValue {
+            table_name: view_info.name.clone(),
+            engine: view_info.meta.engine.clone(),
+            view_info: Some(view_info),
+        })
+        .unwrap()
+        .as_bytes(),
+    )
+}
+
+/// Builds the request to persist the statistics of a table, which replaces the ones
+/// persisted before.
+pub fn build_table_statistics_insert_request(
+    catalog: &str,
+    schema: &str,
+    table_id: TableId,
+    statistics: &TableStatistics,
+) -> InsertRequest {
+    let entry_key = format_table_entry_key(catalog, schema, table_id);
+    build_insert_request(
+        EntryType::TableStatistics,
+        entry_key.as_bytes(),
+        serde_json::to_string(statistics).unwrap().as_bytes(),
+    )
+}
+
+pub(crate) fn build_table_statistics_deletion_request(
+    request: &DeregisterTableRequest,
+    table_id: TableId,
+) -> DeleteRequest {
+    let table_key = format_table_entry_key(&request.catalog, &request.schema, table_id);
+    DeleteRequest {
+        key_column_values: build_primary_key_columns(
+            EntryType::TableStatistics,
+            table_key.as_bytes(),
+        ),
+    }
+}
+
 pub(crate) fn build_table_deletion_request(
     request: &DeregisterTableRequest,
     table_id: TableId,
@@ -333,6 +389,29 @@ pub fn decode_system_catalog(
                 table_name: table_meta.table_name,
                 table_id,
                 engine: table_meta.engine,
+                view_info: table_meta.view_info,
+            }))
+        }
+
+        EntryType::TableStatistics => {
+            // As for table statistics entry, the key is the same as the table entry, and
+            // the value is the JSON string of the statistics.
+            let table_parts = key.split('.').collect::<Vec<_>>();
+            ensure!(
+                table_parts.len() >= 3,
+                InvalidKeySnafu {
+                    key: Some(key.to_string())
+                }
+            );
+            let value = value.context(EmptyValueSnafu)?;
+            let statistics: TableStatistics =
+                serde_json::from_slice(value).context(ValueDeserializeSnafu)?;
+            let table_id = table_parts[2].parse::<TableId>().unwrap();
+            Ok(Entry::TableStatistics(TableStatisticsEntry {
+                catalog_name: table_parts[0].to_string(),
+                schema_name: table_parts[1].to_string(),
+                table_id,
+                statistics,
             }))
         }
     }
@@ -343,6 +422,7 @@ pub enum EntryType {
     Catalog = 1,
     Schema = 2,
     Table = 3,
+    TableStatistics = 4,
 }
 
 impl TryFrom<u8> for EntryType {
@@ -353,6 +433,7 @@ impl TryFrom<u8> for EntryType {
             b if b == Self::Catalog as u8 => Ok(Self::Catalog),
             b if b == Self::Schema as u8 => Ok(Self::Schema),
             b if b == Self::Table as u8 => Ok(Self::Table),
+            b if b == Self::TableStatistics as u8 => Ok(Self::TableStatistics),
             b => InvalidEntryTypeSnafu {
                 entry_type: Some(b),
             }
@@ -361,11 +442,12 @@ impl TryFrom<u8> for EntryType {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum Entry {
     Catalog(CatalogEntry),
     Schema(SchemaEntry),
     Table(TableEntry),
+    TableStatistics(TableStatisticsEntry),
 }
 
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
@@ -382,13 +464,24 @@ pub struct SchemaEntry {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SchemaEntryValue;
 
-#[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct TableEntry {
     pub catalog_name: String,
     pub schema_name: String,
     pub table_name: String,
     pub table_id: TableId,
     pub engine: String,
+    /// Info of the view if this entry is a view.
+    pub view_info: Option<RawTableInfo>,
+}
+
+/// Statistics of a table collected last time, restored to the table once it's opened.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TableStatisticsEntry {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_id: TableId,
+    pub statistics: TableStatistics,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -397,6 +490,9 @@ pub struct TableEntryValue {
 
     #[serde(default = "mito_engine")]
     pub engine: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_info: Option<RawTableInfo>,
 }
 
 fn mito_engine() -> String {
@@ -405,6 +501,7 @@ fn mito_engine() -> String {
 
 #[cfg(test)]
 mod tests {
+    use common_catalog::consts::VIEW_ENGINE;
     use common_recordbatch::RecordBatches;
     use common_test_util::temp_dir::{create_temp_dir, TempDir};
     use datatypes::value::Value;
@@ -415,8 +512,10 @@ mod tests {
     use storage::compaction::noop::NoopCompactionScheduler;
     use storage::config::EngineConfig as StorageEngineConfig;
     use storage::EngineImpl;
-    use table::metadata::TableType;
     use table::metadata::TableType::Base;
+    use table::metadata::{TableInfo, TableType};
+    use table::requests::CreateViewRequest;
+    use table::table::view::ViewTable;
 
     use super::*;
 
@@ -487,7 +586,37 @@ mod tests {
         assert_eq!(EntryType::Catalog, EntryType::try_from(1).unwrap());
         assert_eq!(EntryType::Schema, EntryType::try_from(2).unwrap());
         assert_eq!(EntryType::Table, EntryType::try_from(3).unwrap());
-        assert!(EntryType::try_from(4).is_err());
+        assert_eq!(EntryType::TableStatistics, EntryType::try_from(4).unwrap());
+        assert!(EntryType::try_from(5).is_err());
+    }
+
+    #[test]
+    pub fn test_decode_table_statistics() {
+        let statistics = TableStatistics {
+            num_rows: 3,
+            total_byte_size: 128,
+            collected_at_millis: 1,
+            ..Default::default()
+        };
+        let request =
+            build_table_statistics_insert_request("some_catalog", "some_schema", 42, &statistics);
+        let value = request.columns_values["value"].get(0);
+        let Value::Binary(value) = value else { unreachable!() };
+        let entry = decode_system_catalog(
+            Some(EntryType::TableStatistics as u8),
+            Some("some_catalog.some_schema.42".as_bytes()),
+            Some(&*value),
+        )
+        .unwrap();
+        assert_eq!(
+            Entry::TableStatistics(TableStatisticsEntry {
+                catalog_name: "some_catalog".to_string(),
+                schema_name: "some_schema".to_string(),
+                table_id: 42,
+                statistics,
+            }),
+            entry
+        );
     }
 
     pub async fn prepare_table_engine() -> (TempDir, TableEngineRef) {
@@ -529,6 +658,57 @@ mod tests {
         assert_eq!(INFORMATION_SCHEMA_NAME, info.schema_name);
     }
 
+    #[test]
+    fn test_decode_view_entry() {
+        let view = ViewTable::new(CreateViewRequest {
+            id: 1024,
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            view_name: "my_view".to_string(),
+            schema: Arc::new(datatypes::schema::Schema::new(vec![ColumnSchema::new(
+                "number",
+                ConcreteDataType::uint32_datatype(),
+                false,
+            )])),
+            definition: "SELECT number FROM numbers".to_string(),
+            create_if_not_exists: false,
+        });
+        let view_info = RawTableInfo::from(TableInfo::clone(&view.table_info()));
+
+        let request = build_view_insert_request(
+            DEFAULT_CATALOG_NAME.to_string(),
+            DEFAULT_SCHEMA_NAME.to_string(),
+            1024,
+            view_info.clone(),
+        );
+        let key = request.columns_values["key"]
+            .as_any()
+            .downcast_ref::<BinaryVector>()
+            .unwrap()
+            .get_data(0)
+            .unwrap()
+            .to_vec();
+        let value = request.columns_values["value"]
+            .as_any()
+            .downcast_ref::<BinaryVector>()
+            .unwrap()
+            .get_data(0)
+            .unwrap()
+            .to_vec();
+
+        let entry =
+            decode_system_catalog(Some(EntryType::Table as u8), Some(&key), Some(&value)).unwrap();
+        let expected = Entry::Table(TableEntry {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "my_view".to_string(),
+            table_id: 1024,
+            engine: VIEW_ENGINE.to_string(),
+            view_info: Some(view_info),
+        });
+        assert_eq!(expected, entry);
+    }
+
     #[tokio::test]
     async fn test_system_catalog_table_records() {
         let (_, table_engine) = prepare_table_engine().await;
@@ -561,6 +741,7 @@ mod tests {
             table_name: "my_table".to_string(),
             table_id: 1,
             engine: MITO_ENGINE.to_string(),
+            view_info: None,
         });
         assert_eq!(entry, expected);
 
@@ -124,6 +124,7 @@ impl DfTableSourceProvider {
             Arc::new(InformationSchemaProvider::new(
                 catalog_name.to_string(),
                 catalog_provider,
+                self.catalog_manager.clone(),
             ))
         };
         let table = schema
@@ -138,6 +139,42 @@ impl DfTableSourceProvider {
         self.resolved_tables.insert(resolved_name, table.clone());
         Ok(table)
     }
+
+    /// Resolves the tables of given names under default catalog and schema in one batch, so
+    /// that the following [resolve_table](Self::resolve_table) of these tables won't look up
+    /// catalog one by one. Tables that don't exist are ignored here.
+    pub async fn prefetch_tables(&mut self, table_names: &[&str]) -> Result<()> {
+        let table_names = table_names
+            .iter()
+            .filter(|table_name| {
+                !self
+                    .resolved_tables
+                    .contains_key(&self.resolved_bare_name(table_name))
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        if table_names.is_empty() {
+            return Ok(());
+        }
+
+        let tables = self
+            .catalog_manager
+            .get_tables(&self.default_catalog, &self.default_schema, &table_names)
+            .await?;
+        for (table_name, table) in table_names.into_iter().zip(tables) {
+            let Some(table) = table else { continue };
+            let table = provider_as_source(Arc::new(DfTableProviderAdapter::new(table)));
+            self.resolved_tables
+                .insert(self.resolved_bare_name(table_name), table);
+        }
+        Ok(())
+    }
+
+    fn resolved_bare_name(&self, table_name: &str) -> String {
+        TableReference::bare(table_name)
+            .resolve(&self.default_catalog, &self.default_schema)
+            .to_string()
+    }
 }
 
 #[cfg(test)]
@@ -145,9 +182,11 @@ mod tests {
     use std::borrow::Cow;
 
     use session::context::QueryContext;
+    use table::table::numbers::NumbersTable;
 
     use super::*;
     use crate::local::MemoryCatalogManager;
+    use crate::CatalogManager;
 
     #[test]
     fn test_validate_table_ref() {
@@ -192,4 +231,35 @@ mod tests {
         let result = table_provider.resolve_table_ref(table_ref);
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_prefetch_tables() {
+        let query_ctx = &QueryContext::with("greptime", "public");
+        let catalog_manager = Arc::new(MemoryCatalogManager::default());
+        catalog_manager
+            .register_table(crate::RegisterTableRequest {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                table_name: "numbers".to_string(),
+                table_id: 1,
+                table: Arc::new(NumbersTable::default()),
+            })
+            .await
+            .unwrap();
+
+        let mut table_provider = DfTableSourceProvider::new(catalog_manager, false, query_ctx);
+        table_provider
+            .prefetch_tables(&["numbers", "not_exist"])
+            .await
+            .unwrap();
+        assert_eq!(1, table_provider.resolved_tables.len());
+        assert!(table_provider
+            .resolved_tables
+            .contains_key("greptime.public.numbers"));
+
+        let table_ref = TableReference::Bare {
+            table: Cow::Borrowed("not_exist"),
+        };
+        assert!(table_provider.resolve_table(table_ref).await.is_err());
+    }
 }
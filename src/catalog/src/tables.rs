@@ -20,13 +20,15 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use common_catalog::consts::{INFORMATION_SCHEMA_NAME, SYSTEM_CATALOG_TABLE_NAME};
 use snafu::ResultExt;
-use table::metadata::TableId;
+use table::metadata::{RawTableInfo, TableId};
+use table::stats::TableStatistics;
 use table::{Table, TableRef};
 
 use crate::error::{self, Error, InsertCatalogRecordSnafu, Result as CatalogResult};
 use crate::system::{
     build_schema_insert_request, build_table_deletion_request, build_table_insert_request,
-    SystemCatalogTable,
+    build_table_statistics_deletion_request, build_table_statistics_insert_request,
+    build_view_insert_request, SystemCatalogTable,
 };
 use crate::{CatalogProvider, DeregisterTableRequest, SchemaProvider, SchemaProviderRef};
 
@@ -87,19 +89,62 @@ impl SystemCatalog {
             .context(InsertCatalogRecordSnafu)
     }
 
+    pub async fn register_view(
+        &self,
+        catalog: String,
+        schema: String,
+        table_id: TableId,
+        view_info: RawTableInfo,
+    ) -> crate::error::Result<usize> {
+        let request = build_view_insert_request(catalog, schema, table_id, view_info);
+        self.information_schema
+            .system
+            .insert(request)
+            .await
+            .context(InsertCatalogRecordSnafu)
+    }
+
     pub(crate) async fn deregister_table(
         &self,
         request: &DeregisterTableRequest,
         table_id: TableId,
     ) -> CatalogResult<bool> {
-        self.information_schema
+        let deregistered = self
+            .information_schema
             .system
             .delete(build_table_deletion_request(request, table_id))
             .await
             .map(|x| x == 1)
             .with_context(|_| error::DeregisterTableSnafu {
                 request: request.clone(),
-            })
+            })?;
+        let _ = self
+            .information_schema
+            .system
+            .delete(build_table_statistics_deletion_request(request, table_id))
+            .await
+            .with_context(|_| error::DeregisterTableSnafu {
+                request: request.clone(),
+            })?;
+        Ok(deregistered)
+    }
+
+    /// Persists the statistics of the table, replacing the ones persisted before.
+    pub async fn persist_table_statistics(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table_id: TableId,
+        statistics: &TableStatistics,
+    ) -> crate::error::Result<()> {
+        let request = build_table_statistics_insert_request(catalog, schema, table_id, statistics);
+        let _ = self
+            .information_schema
+            .system
+            .insert(request)
+            .await
+            .context(InsertCatalogRecordSnafu)?;
+        Ok(())
     }
 
     pub async fn register_schema(
@@ -17,11 +17,12 @@ use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_stream::stream;
 use catalog::error::Error;
 use catalog::helper::{CatalogKey, CatalogValue, SchemaKey, SchemaValue};
-use catalog::remote::{Kv, KvBackend, ValueIter};
+use catalog::remote::{Kv, KvBackend, ValueIter, ValueWatch};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_recordbatch::RecordBatch;
 use common_telemetry::logging::info;
@@ -34,10 +35,13 @@ use table::metadata::TableId;
 use table::requests::{AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest};
 use table::test_util::MemTable;
 use table::TableRef;
-use tokio::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, RwLock};
 
 pub struct MockKvBackend {
     map: RwLock<BTreeMap<Vec<u8>, Vec<u8>>>,
+    /// Notified on every change of the `map`, to push the changes to the watchers.
+    changes: broadcast::Sender<()>,
 }
 
 impl Default for MockKvBackend {
@@ -62,7 +66,8 @@ impl Default for MockKvBackend {
         map.insert(default_schema_key.into(), schema_value);
 
         let map = RwLock::new(map);
-        Self { map }
+        let (changes, _) = broadcast::channel(16);
+        Self { map, changes }
     }
 }
 
@@ -107,6 +112,7 @@ impl KvBackend for MockKvBackend {
     async fn set(&self, key: &[u8], val: &[u8]) -> Result<(), Error> {
         let mut map = self.map.write().await;
         map.insert(key.to_vec(), val.to_vec());
+        let _ = self.changes.send(());
         Ok(())
     }
 
@@ -122,6 +128,7 @@ impl KvBackend for MockKvBackend {
             Entry::Vacant(e) => {
                 if expect.is_empty() {
                     e.insert(val.to_vec());
+                    let _ = self.changes.send(());
                     Ok(Ok(()))
                 } else {
                     Ok(Err(None))
@@ -130,6 +137,7 @@ impl KvBackend for MockKvBackend {
             Entry::Occupied(mut existing) => {
                 if existing.get() == expect {
                     existing.insert(val.to_vec());
+                    let _ = self.changes.send(());
                     Ok(Ok(()))
                 } else {
                     Ok(Err(Some(existing.get().clone())))
@@ -145,8 +153,29 @@ impl KvBackend for MockKvBackend {
 
         let mut map = self.map.write().await;
         map.retain(|k, _| !range.contains(k));
+        let _ = self.changes.send(());
         Ok(())
     }
+
+    /// Pushes the changes instead of polling.
+    fn watch<'a>(&'a self, key: &[u8], _interval: Duration) -> ValueWatch<'a, Error> {
+        let key = key.to_vec();
+        // Subscribes before reading the value, so no change is missed.
+        let mut changes = self.changes.subscribe();
+        Box::pin(stream!({
+            let mut last = None;
+            loop {
+                let value = self.map.read().await.get(&key).cloned();
+                if last.as_ref() != Some(&value) {
+                    last = Some(value.clone());
+                    yield Ok(value);
+                }
+                if let Err(RecvError::Closed) = changes.recv().await {
+                    return;
+                }
+            }
+        }))
+    }
 }
 
 #[derive(Default)]
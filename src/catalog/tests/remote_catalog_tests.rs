@@ -21,8 +21,16 @@ mod tests {
     use std::assert_matches::assert_matches;
     use std::collections::HashSet;
     use std::sync::Arc;
+    use std::time::Duration;
 
-    use catalog::helper::{CatalogKey, CatalogValue, SchemaKey, SchemaValue};
+    use catalog::cluster::{disk_low_warnings, list_cluster_nodes, NodeInfoReporter, VERSION};
+    use catalog::helper::{
+        CatalogKey, CatalogValue, NodeRole, SchemaKey, SchemaValue, TableGlobalKey,
+        TABLES_VERSION_KEY,
+    };
+    use catalog::notifier::{
+        bump_tables_version, CatalogEvent, CatalogEventNotifier, KvBackendWatcher,
+    };
     use catalog::remote::{
         KvBackend, KvBackendRef, RemoteCatalogManager, RemoteCatalogProvider, RemoteSchemaProvider,
     };
@@ -320,4 +328,172 @@ mod tests {
                 .collect()
         )
     }
+
+    #[tokio::test]
+    async fn test_reconcile_tables() {
+        let node_id = 42;
+        let (backend, _, catalog_manager, _) = prepare_components(node_id).await;
+        let table_key = TableGlobalKey {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "assigned".to_string(),
+        };
+        let table_value = r#"{"node_id":42,"regions_id_map":{"42":[0]},"table_info":{"ident":{"table_id":1025,"version":1},"name":"assigned","desc":null,"catalog_name":"greptime","schema_name":"public","meta":{"schema":{"column_schemas":[],"timestamp_index":null,"version":0},"primary_key_indices":[],"value_indices":[],"engine":"mito","next_column_id":0,"region_numbers":[0],"engine_options":{},"options":{},"created_on":"1970-01-01T00:00:00Z"},"table_type":"Base"}}"#;
+        backend
+            .set(table_key.to_string().as_bytes(), table_value.as_bytes())
+            .await
+            .unwrap();
+        let schema = catalog_manager
+            .schema(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The table assigned to the datanode may be being created, it's left alone in the
+        // first round it diverges from its route.
+        catalog_manager.reconcile_tables().await.unwrap();
+        assert!(!schema
+            .table_names()
+            .await
+            .unwrap()
+            .contains(&"assigned".to_string()));
+
+        // Still not opened in the next round, so it's opened.
+        catalog_manager.reconcile_tables().await.unwrap();
+        assert!(schema
+            .table_names()
+            .await
+            .unwrap()
+            .contains(&"assigned".to_string()));
+        let table = schema.table("assigned").await.unwrap().unwrap();
+        assert_eq!("assigned", table.table_info().name);
+    }
+
+    #[tokio::test]
+    async fn test_report_cluster_nodes() {
+        let backend: KvBackendRef = Arc::new(MockKvBackend::default());
+        let mut frontend =
+            NodeInfoReporter::new(backend.clone(), NodeRole::Frontend, "127.0.0.1:4001", None);
+        let mut datanode = NodeInfoReporter::new(
+            backend.clone(),
+            NodeRole::Datanode,
+            "127.0.0.1:3001",
+            Some(1),
+        );
+        frontend.report().await.unwrap();
+        datanode.report().await.unwrap();
+        // Reporting again only refreshes the node info.
+        datanode.report().await.unwrap();
+
+        let mut nodes = list_cluster_nodes(&backend).await.unwrap();
+        nodes.sort_by(|a, b| a.0.addr.cmp(&b.0.addr));
+        assert_eq!(2, nodes.len());
+
+        let (key, value) = &nodes[0];
+        assert_eq!(NodeRole::Datanode, key.role);
+        assert_eq!("127.0.0.1:3001", key.addr);
+        assert_eq!(Some(1), value.node_id);
+        assert_eq!(VERSION, value.version);
+        assert!(value.last_activity_millis >= value.start_time_millis);
+
+        let (key, value) = &nodes[1];
+        assert_eq!(NodeRole::Frontend, key.role);
+        assert_eq!("127.0.0.1:4001", key.addr);
+        assert_eq!(None, value.node_id);
+        assert!(disk_low_warnings(&nodes).is_empty());
+
+        datanode.set_disk_low(true);
+        datanode.report().await.unwrap();
+        let nodes = list_cluster_nodes(&backend).await.unwrap();
+        assert_eq!(
+            vec!["Disk space of datanode 127.0.0.1:3001 is below the low watermark".to_string()],
+            disk_low_warnings(&nodes)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skip_expired_cluster_nodes() {
+        let backend: KvBackendRef = Arc::new(MockKvBackend::default());
+        let mut reporter =
+            NodeInfoReporter::new(backend.clone(), NodeRole::Frontend, "127.0.0.1:4001", None)
+                .with_interval(Duration::from_millis(10));
+        reporter.report().await.unwrap();
+        assert_eq!(1, list_cluster_nodes(&backend).await.unwrap().len());
+
+        // Stops reporting for longer than the lease.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(list_cluster_nodes(&backend).await.unwrap().is_empty());
+
+        // Reported again, the node is back.
+        reporter.report().await.unwrap();
+        assert_eq!(1, list_cluster_nodes(&backend).await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn test_watch_tables_by_version() {
+        let backend: KvBackendRef = Arc::new(MockKvBackend::default());
+        let notifier = CatalogEventNotifier::default();
+        let mut receiver = notifier.subscribe();
+        let mut watcher = KvBackendWatcher::new(backend.clone(), notifier);
+        watcher.poll(false).await.unwrap();
+
+        let value = r#"{"node_id":1,"regions_id_map":{"1":[0]},"table_info":{"ident":{"table_id":1024,"version":1},"name":"a","desc":null,"catalog_name":"greptime","schema_name":"public","meta":{"schema":{"column_schemas":[{"name":"ts","data_type":{"Timestamp":{"Millisecond":null}},"is_nullable":false,"is_time_index":true,"default_constraint":null,"metadata":{}}],"timestamp_index":0,"version":1},"primary_key_indices":[],"value_indices":[],"engine":"mito","next_column_id":1,"region_numbers":[],"engine_options":{},"options":{},"created_on":"1970-01-01T00:00:00Z"},"table_type":"Base"}}"#;
+        backend
+            .set(b"__tg-greptime-public-a", value.as_bytes())
+            .await
+            .unwrap();
+
+        // Tables are not scanned until the version changes.
+        watcher.poll(true).await.unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        bump_tables_version(&backend).await.unwrap();
+        watcher.poll(true).await.unwrap();
+        assert_eq!(
+            CatalogEvent::TableRegistered {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                table_name: "a".to_string(),
+            },
+            receiver.try_recv().unwrap()
+        );
+
+        bump_tables_version(&backend).await.unwrap();
+        let version = backend.get(TABLES_VERSION_KEY.as_bytes()).await.unwrap();
+        assert_eq!(b"2".to_vec(), version.unwrap().1);
+    }
+
+    #[tokio::test]
+    async fn test_watcher_notified_by_pushed_changes() {
+        let backend: KvBackendRef = Arc::new(MockKvBackend::default());
+        let notifier = CatalogEventNotifier::default();
+        let mut receiver = notifier.subscribe();
+        // The mock backend pushes the changes, so the watcher never waits for the interval.
+        let watcher = KvBackendWatcher::new(backend.clone(), notifier)
+            .with_interval(Duration::from_secs(3600))
+            .start();
+        // Waits for the initial scan.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let value = r#"{"node_id":1,"regions_id_map":{"1":[0]},"table_info":{"ident":{"table_id":1024,"version":1},"name":"a","desc":null,"catalog_name":"greptime","schema_name":"public","meta":{"schema":{"column_schemas":[{"name":"ts","data_type":{"Timestamp":{"Millisecond":null}},"is_nullable":false,"is_time_index":true,"default_constraint":null,"metadata":{}}],"timestamp_index":0,"version":1},"primary_key_indices":[],"value_indices":[],"engine":"mito","next_column_id":1,"region_numbers":[],"engine_options":{},"options":{},"created_on":"1970-01-01T00:00:00Z"},"table_type":"Base"}}"#;
+        backend
+            .set(b"__tg-greptime-public-a", value.as_bytes())
+            .await
+            .unwrap();
+        bump_tables_version(&backend).await.unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(1), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            CatalogEvent::TableRegistered {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                table_name: "a".to_string(),
+            },
+            event
+        );
+        watcher.abort();
+    }
 }
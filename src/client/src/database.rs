@@ -21,9 +21,10 @@ use api::v1::{
     DeleteRequest, DropTableExpr, FlushTableExpr, GreptimeRequest, InsertRequest, PromRangeQuery,
     QueryRequest, RequestHeader,
 };
-use arrow_flight::{FlightData, Ticket};
+use arrow_flight::{Action, FlightData, Ticket};
 use common_error::prelude::*;
 use common_grpc::flight::{flight_messages_to_recordbatches, FlightDecoder, FlightMessage};
+use common_grpc::GREPTIME_TRACE_ID_HEADER;
 use common_query::Output;
 use common_telemetry::{logging, timer};
 use futures_util::{TryFutureExt, TryStreamExt};
@@ -31,7 +32,8 @@ use prost::Message;
 use snafu::{ensure, ResultExt};
 
 use crate::error::{
-    ConvertFlightDataSnafu, IllegalDatabaseResponseSnafu, IllegalFlightMessagesSnafu,
+    ConvertFlightDataSnafu, DecodeFlightActionResultSnafu, IllegalDatabaseResponseSnafu,
+    IllegalFlightMessagesSnafu,
 };
 use crate::{error, metrics, Client, Result};
 
@@ -106,6 +108,26 @@ impl Database {
         });
     }
 
+    /// Sets the trace id sent along with the requests. Without it, the trace id of the request
+    /// the current task is serving, if any, is sent.
+    pub fn set_trace_id(&mut self, trace_id: u64) {
+        self.ctx.trace_id = Some(trace_id);
+    }
+
+    /// Wraps `message` in a gRPC request carrying the trace id in its metadata.
+    fn new_request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        let trace_id = self.ctx.trace_id.or_else(logging::trace_id);
+        if let Some(trace_id) = trace_id {
+            if let Ok(value) = logging::format_trace_id(trace_id).parse() {
+                let _ = request
+                    .metadata_mut()
+                    .insert(GREPTIME_TRACE_ID_HEADER, value);
+            }
+        }
+        request
+    }
+
     pub async fn insert(&self, request: InsertRequest) -> Result<u32> {
         let _timer = timer!(metrics::METRIC_GRPC_INSERT);
         self.handle(Request::Insert(request)).await
@@ -128,7 +150,7 @@ impl Database {
             request: Some(request),
         };
         let response = client
-            .handle(request)
+            .handle(self.new_request(request))
             .await?
             .into_inner()
             .response
@@ -227,59 +249,87 @@ impl Database {
         // TODO(LFC): Streaming get flight data.
         let flight_data: Vec<FlightData> = client
             .mut_inner()
-            .do_get(request)
+            .do_get(self.new_request(request))
             .and_then(|response| response.into_inner().try_collect())
             .await
-            .map_err(|e| {
-                let tonic_code = e.code();
-                let e: error::Error = e.into();
-                let code = e.status_code();
-                let msg = e.to_string();
-                error::ServerSnafu { code, msg }
-                    .fail::<()>()
-                    .map_err(BoxedError::new)
-                    .context(error::FlightGetSnafu {
-                        tonic_code,
-                        addr: client.addr(),
-                    })
-                    .map_err(|error| {
-                        logging::error!(
-                            "Failed to do Flight get, addr: {}, code: {}, source: {}",
-                            client.addr(),
-                            tonic_code,
-                            error
-                        );
-                        error
-                    })
-                    .unwrap_err()
-            })?;
+            .map_err(|e| flight_error(e, client.addr()))?;
 
-        let decoder = &mut FlightDecoder::default();
-        let flight_messages = flight_data
-            .into_iter()
-            .map(|x| decoder.try_decode(x).context(ConvertFlightDataSnafu))
-            .collect::<Result<Vec<_>>>()?;
-
-        let output = if let Some(FlightMessage::AffectedRows(rows)) = flight_messages.get(0) {
-            ensure!(
-                flight_messages.len() == 1,
-                IllegalFlightMessagesSnafu {
-                    reason: "Expect 'AffectedRows' Flight messages to be one and only!"
-                }
-            );
-            Output::AffectedRows(*rows)
-        } else {
-            let recordbatches = flight_messages_to_recordbatches(flight_messages)
-                .context(ConvertFlightDataSnafu)?;
-            Output::RecordBatches(recordbatches)
+        flight_data_to_output(flight_data)
+    }
+
+    /// Runs the Flight action of `action_type`, like [ADMIN_ACTION], with the encoded request
+    /// `body`. The results of the action are the Flight data of the output.
+    ///
+    /// [ADMIN_ACTION]: common_grpc::flight::ADMIN_ACTION
+    pub async fn do_action(&self, action_type: &str, body: Vec<u8>) -> Result<Output> {
+        let action = Action {
+            r#type: action_type.to_string(),
+            body: body.into(),
         };
-        Ok(output)
+
+        let mut client = self.client.make_flight_client()?;
+        let results: Vec<arrow_flight::Result> = client
+            .mut_inner()
+            .do_action(self.new_request(action))
+            .and_then(|response| response.into_inner().try_collect())
+            .await
+            .map_err(|e| flight_error(e, client.addr()))?;
+
+        let flight_data = results
+            .into_iter()
+            .map(|result| FlightData::decode(result.body.as_ref()))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .context(DecodeFlightActionResultSnafu)?;
+        flight_data_to_output(flight_data)
     }
 }
 
+fn flight_error(e: tonic::Status, addr: &str) -> error::Error {
+    let tonic_code = e.code();
+    let e: error::Error = e.into();
+    let code = e.status_code();
+    let msg = e.to_string();
+    let error = error::ServerSnafu { code, msg }
+        .fail::<()>()
+        .map_err(BoxedError::new)
+        .context(error::FlightGetSnafu { tonic_code, addr })
+        .unwrap_err();
+    logging::error!(
+        "Failed to do Flight get, addr: {}, code: {}, source: {}",
+        addr,
+        tonic_code,
+        error
+    );
+    error
+}
+
+fn flight_data_to_output(flight_data: Vec<FlightData>) -> Result<Output> {
+    let decoder = &mut FlightDecoder::default();
+    let flight_messages = flight_data
+        .into_iter()
+        .map(|x| decoder.try_decode(x).context(ConvertFlightDataSnafu))
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = if let Some(FlightMessage::AffectedRows(rows)) = flight_messages.get(0) {
+        ensure!(
+            flight_messages.len() == 1,
+            IllegalFlightMessagesSnafu {
+                reason: "Expect 'AffectedRows' Flight messages to be one and only!"
+            }
+        );
+        Output::AffectedRows(*rows)
+    } else {
+        let recordbatches =
+            flight_messages_to_recordbatches(flight_messages).context(ConvertFlightDataSnafu)?;
+        Output::RecordBatches(recordbatches)
+    };
+    Ok(output)
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct FlightContext {
     auth_header: Option<AuthHeader>,
+    trace_id: Option<u64>,
 }
 
 #[cfg(test)]
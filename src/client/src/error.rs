@@ -32,6 +32,12 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to decode Flight action result, source: {}", source))]
+    DecodeFlightActionResult {
+        source: prost::DecodeError,
+        location: Location,
+    },
+
     #[snafu(display("Failed to convert FlightData, source: {}", source))]
     ConvertFlightData {
         #[snafu(backtrace)]
@@ -77,6 +83,7 @@ impl ErrorExt for Error {
             Error::IllegalFlightMessages { .. }
             | Error::ColumnDataType { .. }
             | Error::MissingField { .. }
+            | Error::DecodeFlightActionResult { .. }
             | Error::IllegalDatabaseResponse { .. } => StatusCode::Internal,
 
             Error::Server { code, .. } => *code,
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod backup;
 mod cmd;
 mod helper;
 mod repl;
@@ -24,12 +25,24 @@ use crate::error::Result;
 use crate::options::{Options, TopLevelOptions};
 
 pub struct Instance {
-    repl: Repl,
+    tool: Tool,
+}
+
+enum Tool {
+    Repl(Box<Repl>),
+    ExportCatalog(ExportCatalogCommand),
+    ImportCatalog(ImportCatalogCommand),
 }
 
 impl Instance {
     pub async fn run(&mut self) -> Result<()> {
-        self.repl.run().await
+        match &mut self.tool {
+            Tool::Repl(repl) => repl.run().await,
+            Tool::ExportCatalog(cmd) => {
+                backup::export_catalog(&cmd.grpc_addr, &cmd.output, cmd.skip_redacted_tables).await
+            }
+            Tool::ImportCatalog(cmd) => backup::import_catalog(&cmd.grpc_addr, &cmd.input).await,
+        }
     }
 
     pub async fn stop(&self) -> Result<()> {
@@ -63,12 +76,20 @@ impl Command {
 #[derive(Parser)]
 enum SubCommand {
     Attach(AttachCommand),
+    ExportCatalog(ExportCatalogCommand),
+    ImportCatalog(ImportCatalogCommand),
 }
 
 impl SubCommand {
     async fn build(self) -> Result<Instance> {
         match self {
             SubCommand::Attach(cmd) => cmd.build().await,
+            SubCommand::ExportCatalog(cmd) => Ok(Instance {
+                tool: Tool::ExportCatalog(cmd),
+            }),
+            SubCommand::ImportCatalog(cmd) => Ok(Instance {
+                tool: Tool::ImportCatalog(cmd),
+            }),
         }
     }
 }
@@ -86,10 +107,36 @@ pub(crate) struct AttachCommand {
 impl AttachCommand {
     async fn build(self) -> Result<Instance> {
         let repl = Repl::try_new(&self).await?;
-        Ok(Instance { repl })
+        Ok(Instance {
+            tool: Tool::Repl(Box::new(repl)),
+        })
     }
 }
 
+/// Exports the catalog metadata to a JSON bundle.
+#[derive(Debug, Parser)]
+pub(crate) struct ExportCatalogCommand {
+    #[clap(long)]
+    pub(crate) grpc_addr: String,
+    /// Path of the bundle to write.
+    #[clap(long)]
+    pub(crate) output: String,
+    /// Skips the remote tables whose url has a password instead of failing. The password is
+    /// never shown, so they must be recreated after import.
+    #[clap(long, action)]
+    pub(crate) skip_redacted_tables: bool,
+}
+
+/// Imports the catalog metadata from a JSON bundle exported before.
+#[derive(Debug, Parser)]
+pub(crate) struct ImportCatalogCommand {
+    #[clap(long)]
+    pub(crate) grpc_addr: String,
+    /// Path of the bundle to read.
+    #[clap(long)]
+    pub(crate) input: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +178,49 @@ mod tests {
         assert_eq!("/tmp/greptimedb/test/logs", logging_opts.dir);
         assert_eq!("debug", logging_opts.level);
     }
+
+    #[test]
+    fn test_parse_catalog_backup_commands() {
+        let cmd = Command::parse_from([
+            "cli",
+            "export-catalog",
+            "--grpc-addr",
+            "127.0.0.1:4001",
+            "--output",
+            "/tmp/catalog.json",
+        ]);
+        let SubCommand::ExportCatalog(export) = cmd.cmd else {
+            unreachable!()
+        };
+        assert_eq!("127.0.0.1:4001", export.grpc_addr);
+        assert_eq!("/tmp/catalog.json", export.output);
+        assert!(!export.skip_redacted_tables);
+
+        let cmd = Command::parse_from([
+            "cli",
+            "export-catalog",
+            "--grpc-addr",
+            "127.0.0.1:4001",
+            "--output",
+            "/tmp/catalog.json",
+            "--skip-redacted-tables",
+        ]);
+        let SubCommand::ExportCatalog(export) = cmd.cmd else {
+            unreachable!()
+        };
+        assert!(export.skip_redacted_tables);
+
+        let cmd = Command::parse_from([
+            "cli",
+            "import-catalog",
+            "--grpc-addr",
+            "127.0.0.1:4001",
+            "--input",
+            "/tmp/catalog.json",
+        ]);
+        let SubCommand::ImportCatalog(import) = cmd.cmd else {
+            unreachable!()
+        };
+        assert_eq!("/tmp/catalog.json", import.input);
+    }
 }
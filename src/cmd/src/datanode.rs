@@ -16,12 +16,16 @@ use std::time::Duration;
 
 use clap::Parser;
 use common_telemetry::logging;
-use datanode::datanode::{Datanode, DatanodeOptions, FileConfig, ObjectStoreConfig};
+use datanode::datanode::{
+    BackgroundConfig, Datanode, DatanodeOptions, FileConfig, ObjectStoreConfig,
+};
 use meta_client::MetaClientOptions;
 use servers::Mode;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 
-use crate::error::{MissingConfigSnafu, Result, ShutdownDatanodeSnafu, StartDatanodeSnafu};
+use crate::error::{
+    IllegalConfigSnafu, MissingConfigSnafu, Result, ShutdownDatanodeSnafu, StartDatanodeSnafu,
+};
 use crate::options::{Options, TopLevelOptions};
 use crate::toml_loader;
 
@@ -167,6 +171,8 @@ impl StartCommand {
         // Disable dashboard in datanode.
         opts.http_opts.disable_dashboard = true;
 
+        validate_background_config(&opts.storage.background)?;
+
         Ok(Options::Datanode(Box::new(opts)))
     }
 
@@ -180,6 +186,24 @@ impl StartCommand {
     }
 }
 
+/// Checks the options of the background jobs, which would otherwise panic while creating
+/// the thread pools or the rate limiter.
+pub(crate) fn validate_background_config(config: &BackgroundConfig) -> Result<()> {
+    ensure!(
+        config.flush_workers > 0 && config.compaction_workers > 0,
+        IllegalConfigSnafu {
+            msg: "The number of flush and compaction workers must be positive",
+        }
+    );
+    ensure!(
+        config.io_rate_limit.map_or(true, |limit| limit.0 > 0),
+        IllegalConfigSnafu {
+            msg: "The background IO rate limit must be positive, leave it unset for unlimited",
+        }
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Write;
@@ -187,7 +211,10 @@ mod tests {
 
     use common_base::readable_size::ReadableSize;
     use common_test_util::temp_dir::create_named_temp_file;
-    use datanode::datanode::{CompactionConfig, ObjectStoreConfig, RegionManifestConfig};
+    use datanode::datanode::{
+        ChecksumConfig, CompactionConfig, ObjectStoreConfig, ObjectStoreRetryConfig,
+        RegionManifestConfig,
+    };
     use servers::Mode;
 
     use super::*;
@@ -227,11 +254,30 @@ mod tests {
             max_inflight_tasks = 3
             max_files_in_level0 = 7
             max_purge_tasks = 32
+            export_sst = true
 
             [storage.manifest]
             checkpoint_margin = 9
             gc_duration = '7s'
             checkpoint_on_startup = true
+            checkpoint_concurrency = 16
+            checkpoint_rate_limit = "32MB"
+            checkpoint_jitter = "10s"
+
+            [storage.checksum]
+            verify_on_read = true
+            scrub_interval = "1h"
+
+            [storage.retry]
+            max_retries = 5
+            min_delay = "100ms"
+
+            [storage.tiering]
+            cold_after = "30days"
+
+            [storage.tiering.cold_store]
+            type = "File"
+            data_dir = "/tmp/greptimedb/cold/"
 
             [logging]
             level = "debug"
@@ -262,12 +308,16 @@ mod tests {
             timeout_millis,
             connect_timeout_millis,
             tcp_nodelay,
+            backoff_min_millis,
+            backoff_max_millis,
         } = options.meta_client_options.unwrap();
 
         assert_eq!(vec!["127.0.0.1:3002".to_string()], metasrv_addr);
         assert_eq!(5000, connect_timeout_millis);
         assert_eq!(3000, timeout_millis);
         assert!(tcp_nodelay);
+        assert_eq!(100, backoff_min_millis);
+        assert_eq!(10_000, backoff_max_millis);
 
         match &options.storage.store {
             ObjectStoreConfig::File(FileConfig { data_dir, .. }) => {
@@ -283,6 +333,7 @@ mod tests {
                 max_files_in_level0: 7,
                 max_purge_tasks: 32,
                 sst_write_buffer_size: ReadableSize::mb(8),
+                export_sst: true,
             },
             options.storage.compaction,
         );
@@ -291,9 +342,37 @@ mod tests {
                 checkpoint_margin: Some(9),
                 gc_duration: Some(Duration::from_secs(7)),
                 checkpoint_on_startup: true,
+                checkpoint_concurrency: Some(16),
+                checkpoint_rate_limit: Some(ReadableSize::mb(32)),
+                checkpoint_jitter: Duration::from_secs(10),
             },
             options.storage.manifest,
         );
+        assert_eq!(
+            ChecksumConfig {
+                verify_on_read: true,
+                scrub_interval: Some(Duration::from_secs(3600)),
+            },
+            options.storage.checksum,
+        );
+        assert_eq!(
+            ObjectStoreRetryConfig {
+                max_retries: 5,
+                min_delay: Duration::from_millis(100),
+                ..Default::default()
+            },
+            options.storage.retry,
+        );
+        assert_eq!(
+            Some(Duration::from_secs(30 * 24 * 3600)),
+            options.storage.tiering.cold_after,
+        );
+        match &options.storage.tiering.cold_store {
+            Some(ObjectStoreConfig::File(FileConfig { data_dir })) => {
+                assert_eq!("/tmp/greptimedb/cold/", data_dir);
+            }
+            other => unreachable!("unexpected cold store {:?}", other),
+        }
 
         assert_eq!("debug".to_string(), options.logging.level);
         assert_eq!("/tmp/greptimedb/test/logs".to_string(), options.logging.dir);
@@ -335,6 +414,35 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_validate_background_config() {
+        let mut file = create_named_temp_file();
+        let toml_str = r#"
+            [storage.background]
+            flush_workers = 0
+        "#;
+        write!(file, "{}", toml_str).unwrap();
+        let cmd = StartCommand {
+            config_file: Some(file.path().to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+        assert!(cmd.load_options(TopLevelOptions::default()).is_err());
+
+        let mut file = create_named_temp_file();
+        let toml_str = r#"
+            [storage.background]
+            io_rate_limit = "0B"
+        "#;
+        write!(file, "{}", toml_str).unwrap();
+        let cmd = StartCommand {
+            config_file: Some(file.path().to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+        assert!(cmd.load_options(TopLevelOptions::default()).is_err());
+
+        assert!(validate_background_config(&BackgroundConfig::default()).is_ok());
+    }
+
     #[test]
     fn test_top_level_options() {
         let cmd = StartCommand::default();
@@ -142,6 +142,17 @@ pub enum Error {
         source: query::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to build the request to create table: {}, source: {}",
+        sql,
+        source
+    ))]
+    BuildCreateTableExpr {
+        sql: String,
+        #[snafu(backtrace)]
+        source: frontend::error::Error,
+    },
+
     #[snafu(display("Failed to plan statement, source: {}", source))]
     PlanStatement {
         #[snafu(backtrace)]
@@ -153,6 +164,42 @@ pub enum Error {
         #[snafu(backtrace)]
         source: substrait::error::Error,
     },
+
+    #[snafu(display("Unexpected output of SQL: {}, reason: {}", sql, reason))]
+    UnexpectedOutput {
+        sql: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to read catalog bundle: {}, source: {}", path, source))]
+    ReadCatalogBundle {
+        path: String,
+        source: std::io::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to write catalog bundle: {}, source: {}", path, source))]
+    WriteCatalogBundle {
+        path: String,
+        source: std::io::Error,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Cannot export remote table {} whose url has a password, which is never shown. Skip it by --skip-redacted-tables and recreate it after import",
+        table
+    ))]
+    RedactedRemoteTable { table: String, location: Location },
+
+    #[snafu(display("Illegal catalog bundle, reason: {}", reason))]
+    IllegalCatalogBundle { reason: String, location: Location },
+
+    #[snafu(display("Failed to serialize or deserialize json, source: {}", source))]
+    SerdeJson {
+        source: serde_json::Error,
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -185,6 +232,15 @@ impl ErrorExt for Error {
                 source.status_code()
             }
             Error::SubstraitEncodeLogicalPlan { source } => source.status_code(),
+            Error::BuildCreateTableExpr { source, .. } => source.status_code(),
+            Error::UnexpectedOutput { .. } => StatusCode::Unexpected,
+            Error::ReadCatalogBundle { .. } | Error::WriteCatalogBundle { .. } => {
+                StatusCode::StorageUnavailable
+            }
+            Error::RedactedRemoteTable { .. } => StatusCode::Unsupported,
+            Error::IllegalCatalogBundle { .. } | Error::SerdeJson { .. } => {
+                StatusCode::InvalidArguments
+            }
         }
     }
 
@@ -19,6 +19,7 @@ use common_base::Plugins;
 use frontend::frontend::FrontendOptions;
 use frontend::grpc::GrpcOptions;
 use frontend::influxdb::InfluxdbOptions;
+use frontend::instance::builder::FrontendBuilder;
 use frontend::instance::{FrontendInstance, Instance as FeInstance};
 use frontend::mysql::MysqlOptions;
 use frontend::opentsdb::OpentsdbOptions;
@@ -167,7 +168,10 @@ impl StartCommand {
             });
         }
         if let Some(addr) = self.prom_addr.clone() {
-            opts.prom_options = Some(PromOptions { addr });
+            opts.prom_options = Some(PromOptions {
+                addr,
+                ..Default::default()
+            });
         }
         if let Some(addr) = self.postgres_addr.clone() {
             opts.postgres_options = Some(PostgresOptions {
@@ -202,12 +206,10 @@ impl StartCommand {
     async fn build(self, opts: FrontendOptions) -> Result<Instance> {
         let plugins = Arc::new(load_frontend_plugins(&self.user_provider)?);
 
-        let mut instance = FeInstance::try_new_distributed(&opts, plugins.clone())
-            .await
-            .context(error::StartFrontendSnafu)?;
-
-        instance
-            .build_servers(&opts)
+        let instance = FrontendBuilder::new()
+            .options(opts)
+            .plugins(plugins)
+            .build()
             .await
             .context(error::StartFrontendSnafu)?;
 
@@ -14,27 +14,39 @@
 
 use std::sync::Arc;
 
+use catalog::notifier::DEFAULT_WATCH_INTERVAL;
 use clap::Parser;
-use common_base::Plugins;
 use common_telemetry::info;
 use common_telemetry::logging::LoggingOptions;
-use datanode::datanode::{Datanode, DatanodeOptions, ProcedureConfig, StorageConfig, WalConfig};
-use datanode::instance::InstanceRef;
+use datanode::datanode::{
+    CardinalityLimitConfig, Datanode, DatanodeOptions, DiskWatermarkConfig, IdleTableConfig,
+    ProcedureConfig, StatisticsConfig, StorageConfig, WalConfig,
+};
+use frontend::database_alias::DatabaseAliasOptions;
+use frontend::dead_letter::DeadLetterOptions;
+use frontend::expr_factory::TableDefaultsOptions;
 use frontend::frontend::FrontendOptions;
 use frontend::grpc::GrpcOptions;
 use frontend::influxdb::InfluxdbOptions;
+use frontend::insert_batch::InsertBatchOptions;
+use frontend::instance::builder::FrontendBuilder;
 use frontend::instance::{FrontendInstance, Instance as FeInstance};
+use frontend::kafka::KafkaOptions;
 use frontend::mysql::MysqlOptions;
 use frontend::opentsdb::OpentsdbOptions;
 use frontend::postgres::PostgresOptions;
 use frontend::prom::PromOptions;
 use frontend::prometheus::PrometheusOptions;
+use frontend::rule::RuleOptions;
+use frontend::scrape::ScrapeOptions;
+use frontend::table_name::TableNameNormalization;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
 use servers::tls::{TlsMode, TlsOption};
 use servers::Mode;
 use snafu::ResultExt;
 
+use crate::datanode::validate_background_config;
 use crate::error::{
     IllegalConfigSnafu, Result, ShutdownDatanodeSnafu, ShutdownFrontendSnafu, StartDatanodeSnafu,
     StartFrontendSnafu,
@@ -87,6 +99,8 @@ impl SubCommand {
 pub struct StandaloneOptions {
     pub mode: Mode,
     pub enable_memory_catalog: bool,
+    pub table_defaults: Vec<TableDefaultsOptions>,
+    pub table_name_normalization: TableNameNormalization,
     pub http_options: Option<HttpOptions>,
     pub grpc_options: Option<GrpcOptions>,
     pub mysql_options: Option<MysqlOptions>,
@@ -95,9 +109,18 @@ pub struct StandaloneOptions {
     pub influxdb_options: Option<InfluxdbOptions>,
     pub prometheus_options: Option<PrometheusOptions>,
     pub prom_options: Option<PromOptions>,
+    pub rule_options: Option<RuleOptions>,
+    pub scrape_options: Option<ScrapeOptions>,
+    pub kafka_options: Option<KafkaOptions>,
+    pub dead_letter_options: Option<DeadLetterOptions>,
+    pub database_alias_options: Option<DatabaseAliasOptions>,
     pub wal: WalConfig,
     pub storage: StorageConfig,
     pub procedure: ProcedureConfig,
+    pub statistics: StatisticsConfig,
+    pub idle_table: IdleTableConfig,
+    pub cardinality_limit: CardinalityLimitConfig,
+    pub disk_watermark: DiskWatermarkConfig,
     pub logging: LoggingOptions,
 }
 
@@ -106,6 +129,8 @@ impl Default for StandaloneOptions {
         Self {
             mode: Mode::Standalone,
             enable_memory_catalog: false,
+            table_defaults: vec![],
+            table_name_normalization: TableNameNormalization::default(),
             http_options: Some(HttpOptions::default()),
             grpc_options: Some(GrpcOptions::default()),
             mysql_options: Some(MysqlOptions::default()),
@@ -114,9 +139,18 @@ impl Default for StandaloneOptions {
             influxdb_options: Some(InfluxdbOptions::default()),
             prometheus_options: Some(PrometheusOptions::default()),
             prom_options: Some(PromOptions::default()),
+            rule_options: None,
+            scrape_options: None,
+            kafka_options: None,
+            dead_letter_options: None,
+            database_alias_options: None,
             wal: WalConfig::default(),
             storage: StorageConfig::default(),
             procedure: ProcedureConfig::default(),
+            statistics: StatisticsConfig::default(),
+            idle_table: IdleTableConfig::default(),
+            cardinality_limit: CardinalityLimitConfig::default(),
+            disk_watermark: DiskWatermarkConfig::default(),
             logging: LoggingOptions::default(),
         }
     }
@@ -126,6 +160,8 @@ impl StandaloneOptions {
     fn frontend_options(self) -> FrontendOptions {
         FrontendOptions {
             mode: self.mode,
+            table_defaults: self.table_defaults,
+            table_name_normalization: self.table_name_normalization,
             http_options: self.http_options,
             grpc_options: self.grpc_options,
             mysql_options: self.mysql_options,
@@ -134,6 +170,15 @@ impl StandaloneOptions {
             influxdb_options: self.influxdb_options,
             prometheus_options: self.prometheus_options,
             prom_options: self.prom_options,
+            rule_options: self.rule_options,
+            scrape_options: self.scrape_options,
+            kafka_options: self.kafka_options,
+            dead_letter_options: self.dead_letter_options,
+            database_alias_options: self.database_alias_options,
+            // Inserts are only split into batches for distributed tables.
+            insert_batch_options: InsertBatchOptions::default(),
+            // Only distributed frontends watch the catalog.
+            catalog_watch_interval: DEFAULT_WATCH_INTERVAL,
             meta_client_options: None,
             logging: self.logging,
         }
@@ -145,6 +190,10 @@ impl StandaloneOptions {
             wal: self.wal,
             storage: self.storage,
             procedure: self.procedure,
+            statistics: self.statistics,
+            idle_table: self.idle_table,
+            cardinality_limit: self.cardinality_limit,
+            disk_watermark: self.disk_watermark,
             ..Default::default()
         }
     }
@@ -229,6 +278,7 @@ impl StartCommand {
         let mut fe_opts = opts.clone().frontend_options();
         let mut logging = opts.logging.clone();
         let dn_opts = opts.datanode_options();
+        validate_background_config(&dn_opts.storage.background)?;
 
         if let Some(dir) = top_level_options.log_dir {
             logging.dir = dir;
@@ -270,7 +320,10 @@ impl StartCommand {
         }
 
         if let Some(addr) = self.prom_addr.clone() {
-            fe_opts.prom_options = Some(PromOptions { addr })
+            fe_opts.prom_options = Some(PromOptions {
+                addr,
+                ..Default::default()
+            })
         }
 
         if let Some(addr) = self.postgres_addr.clone() {
@@ -326,10 +379,11 @@ impl StartCommand {
             .await
             .context(StartDatanodeSnafu)?;
 
-        let mut frontend = build_frontend(plugins.clone(), datanode.get_instance()).await?;
-
-        frontend
-            .build_servers(&fe_opts)
+        let frontend = FrontendBuilder::new()
+            .options(fe_opts)
+            .plugins(plugins)
+            .datanode_instance(datanode.get_instance())
+            .build()
             .await
             .context(StartFrontendSnafu)?;
 
@@ -337,18 +391,6 @@ impl StartCommand {
     }
 }
 
-/// Build frontend instance in standalone mode
-async fn build_frontend(
-    plugins: Arc<Plugins>,
-    datanode_instance: InstanceRef,
-) -> Result<FeInstance> {
-    let mut frontend_instance = FeInstance::try_new_standalone(datanode_instance.clone())
-        .await
-        .context(StartFrontendSnafu)?;
-    frontend_instance.set_plugins(plugins.clone());
-    Ok(frontend_instance)
-}
-
 #[cfg(test)]
 mod tests {
     use std::io::Write;
@@ -30,3 +30,5 @@ pub const SCRIPTS_TABLE_ID: u32 = 1;
 
 pub const MITO_ENGINE: &str = "mito";
 pub const IMMUTABLE_FILE_ENGINE: &str = "file";
+pub const REMOTE_TABLE_ENGINE: &str = "remote";
+pub const VIEW_ENGINE: &str = "view";
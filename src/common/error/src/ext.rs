@@ -23,6 +23,12 @@ pub trait ErrorExt: std::error::Error {
         StatusCode::Unknown
     }
 
+    /// Returns `true` if the error is transient, so retrying the failed operation may
+    /// succeed. It's decided by the [StatusCode] of the error.
+    fn is_retryable(&self) -> bool {
+        self.status_code().is_retryable()
+    }
+
     // TODO(ruihang): remove this default implementation
     /// Get the location of this error, None if the location is unavailable.
     /// Add `_opt` suffix to avoid confusing with similar method in `std::error::Error`
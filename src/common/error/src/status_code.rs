@@ -59,6 +59,8 @@ pub enum StatusCode {
     // ====== Begin of storage related status code =====
     /// Storage is temporarily unable to handle the request
     StorageUnavailable = 5000,
+    /// Storage data is corrupted, e.g. the checksum of a file mismatches
+    StorageCorrupted = 5001,
     // ====== End of storage related status code =======
 
     // ====== Begin of server related status code =====
@@ -113,6 +115,7 @@ impl StatusCode {
             | StatusCode::UserPasswordMismatch
             | StatusCode::AuthHeaderNotFound
             | StatusCode::InvalidAuthHeader
+            | StatusCode::StorageCorrupted
             | StatusCode::AccessDenied => false,
         }
     }
@@ -128,6 +131,7 @@ impl StatusCode {
             | StatusCode::PlanQuery
             | StatusCode::EngineExecuteQuery
             | StatusCode::StorageUnavailable
+            | StatusCode::StorageCorrupted
             | StatusCode::RuntimeResourcesExhausted => true,
             StatusCode::Success
             | StatusCode::InvalidArguments
@@ -176,4 +180,18 @@ mod tests {
         assert!(!StatusCode::is_success(2));
         assert!(!StatusCode::is_success(3));
     }
+
+    #[test]
+    fn test_is_retryable() {
+        use crate::ext::{BoxedError, ErrorExt};
+        use crate::mock::MockError;
+
+        assert!(StatusCode::StorageUnavailable.is_retryable());
+        assert!(!StatusCode::InvalidArguments.is_retryable());
+
+        let err = MockError::new(StatusCode::RuntimeResourcesExhausted);
+        assert!(err.is_retryable());
+        let err = BoxedError::new(MockError::new(StatusCode::TableNotFound));
+        assert!(!err.is_retryable());
+    }
 }
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 pub mod aggregate;
+pub mod anomaly;
 pub mod expression;
+pub mod forecast;
 pub mod function;
 pub mod function_registry;
 pub mod math;
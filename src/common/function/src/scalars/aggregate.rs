@@ -12,14 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod anomaly;
 mod argmax;
 mod argmin;
 mod diff;
+mod double_exponential_forecast;
+mod forecast;
+mod mad_over_time;
 mod mean;
 mod percentile;
 mod polyval;
 mod scipy_stats_norm_cdf;
 mod scipy_stats_norm_pdf;
+mod seasonal_forecast;
+mod zscore_over_time;
 
 use std::sync::Arc;
 
@@ -27,11 +33,15 @@ pub use argmax::ArgmaxAccumulatorCreator;
 pub use argmin::ArgminAccumulatorCreator;
 use common_query::logical_plan::AggregateFunctionCreatorRef;
 pub use diff::DiffAccumulatorCreator;
+pub use double_exponential_forecast::DoubleExponentialForecastAccumulatorCreator;
+pub use mad_over_time::MadOverTimeAccumulatorCreator;
 pub use mean::MeanAccumulatorCreator;
 pub use percentile::PercentileAccumulatorCreator;
 pub use polyval::PolyvalAccumulatorCreator;
 pub use scipy_stats_norm_cdf::ScipyStatsNormCdfAccumulatorCreator;
 pub use scipy_stats_norm_pdf::ScipyStatsNormPdfAccumulatorCreator;
+pub use seasonal_forecast::SeasonalForecastAccumulatorCreator;
+pub use zscore_over_time::ZscoreOverTimeAccumulatorCreator;
 
 use crate::scalars::FunctionRegistry;
 
@@ -94,5 +104,13 @@ impl AggregateFunctions {
         register_aggr_func!("percentile", 2, PercentileAccumulatorCreator);
         register_aggr_func!("scipystatsnormcdf", 2, ScipyStatsNormCdfAccumulatorCreator);
         register_aggr_func!("scipystatsnormpdf", 2, ScipyStatsNormPdfAccumulatorCreator);
+        register_aggr_func!("zscore_over_time", 2, ZscoreOverTimeAccumulatorCreator);
+        register_aggr_func!("mad_over_time", 1, MadOverTimeAccumulatorCreator);
+        register_aggr_func!(
+            "double_exponential_forecast",
+            5,
+            DoubleExponentialForecastAccumulatorCreator
+        );
+        register_aggr_func!("seasonal_forecast", 4, SeasonalForecastAccumulatorCreator);
     }
 }
@@ -0,0 +1,308 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu, FromScalarValueSnafu,
+    InvalidInputStateSnafu, Result,
+};
+use common_query::logical_plan::Accumulator;
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use datatypes::value::ListValue;
+use datatypes::vectors::{ConstantVector, Float64Vector, Helper, Int64Vector, ListVector};
+use datatypes::with_match_primitive_type_id;
+use num_traits::AsPrimitive;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::scalars::aggregate::forecast::{downcast, value_to_f64, value_to_millis};
+use crate::scalars::anomaly::{median_absolute_deviation, zscore};
+
+/// Computes the z-score of the latest point in a time window to detect anomalies, usually
+/// grouped by a time bucket like `date_bin`. The arguments are the timestamp and the value of
+/// the points, so the input doesn't need to be ordered.
+#[derive(Debug, Default)]
+pub struct Zscore {
+    /// Timestamps in milliseconds.
+    times: Vec<i64>,
+    values: Vec<f64>,
+}
+
+impl Accumulator for Zscore {
+    fn state(&self) -> Result<Vec<Value>> {
+        let times = self.times.iter().map(|&t| t.into()).collect::<Vec<Value>>();
+        let values = self
+            .values
+            .iter()
+            .map(|&v| v.into())
+            .collect::<Vec<Value>>();
+        Ok(vec![
+            Value::List(ListValue::new(
+                Some(Box::new(times)),
+                ConcreteDataType::int64_datatype(),
+            )),
+            Value::List(ListValue::new(
+                Some(Box::new(values)),
+                ConcreteDataType::float64_datatype(),
+            )),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        ensure!(values.len() == 2, InvalidInputStateSnafu);
+
+        let (times, column) = (&values[0], &values[1]);
+        for i in 0..times.len() {
+            let time = value_to_millis(&times.get(i));
+            if let (Some(time), Some(value)) = (time, value_to_f64(&column.get(i))) {
+                self.times.push(time);
+                self.values.push(value);
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            states.len() == 2,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 2 states in `merge_batch`",
+            }
+        );
+
+        let times = downcast::<ListVector>(&states[0])?;
+        let values = downcast::<ListVector>(&states[1])?;
+        for (times, values) in times.values_iter().zip(values.values_iter()) {
+            let (Some(times), Some(values)) = (
+                times.context(FromScalarValueSnafu)?,
+                values.context(FromScalarValueSnafu)?,
+            ) else {
+                continue;
+            };
+            self.times
+                .extend(downcast::<Int64Vector>(&times)?.iter_data().flatten());
+            self.values
+                .extend(downcast::<Float64Vector>(&values)?.iter_data().flatten());
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        // The points of the same timestamp are ordered by values, so the result doesn't depend
+        // on the order of the input.
+        let latest = self
+            .times
+            .iter()
+            .zip(self.values.iter())
+            .max_by(|(t1, v1), (t2, v2)| t1.cmp(t2).then(v1.total_cmp(v2)))
+            .map(|(_, v)| *v);
+        let score = latest.and_then(|latest| zscore(&self.values, latest));
+        Ok(score.map(Value::from).unwrap_or(Value::Null))
+    }
+}
+
+/// Computes the median absolute deviation of all values in a time window to detect anomalies,
+/// usually grouped by a time bucket like `date_bin`.
+#[derive(Debug)]
+pub struct MedianAbsoluteDeviation<T> {
+    values: Vec<f64>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Default for MedianAbsoluteDeviation<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Accumulator for MedianAbsoluteDeviation<T>
+where
+    T: WrapperType,
+    T::Native: AsPrimitive<f64>,
+{
+    fn state(&self) -> Result<Vec<Value>> {
+        let nums = self
+            .values
+            .iter()
+            .map(|&n| n.into())
+            .collect::<Vec<Value>>();
+        Ok(vec![Value::List(ListValue::new(
+            Some(Box::new(nums)),
+            ConcreteDataType::float64_datatype(),
+        ))])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(values.len() == 1, InvalidInputStateSnafu);
+        let column = &values[0];
+        let mut len = 1;
+        let column: &<T as Scalar>::VectorType = if column.is_const() {
+            len = column.len();
+            let column: &ConstantVector = unsafe { Helper::static_cast(column) };
+            unsafe { Helper::static_cast(column.inner()) }
+        } else {
+            unsafe { Helper::static_cast(column) }
+        };
+        (0..len).for_each(|_| {
+            for v in column.iter_data().flatten() {
+                self.values.push(v.into_native().as_());
+            }
+        });
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let states = &states[0];
+        let states = states
+            .as_any()
+            .downcast_ref::<ListVector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect ListVector, got vector type {}",
+                    states.vector_type_name()
+                ),
+            })?;
+        for state in states.values_iter() {
+            if let Some(state) = state.context(FromScalarValueSnafu)? {
+                let state = state
+                    .as_any()
+                    .downcast_ref::<Float64Vector>()
+                    .with_context(|| DowncastVectorSnafu {
+                        err_msg: format!(
+                            "expect Float64Vector, got vector type {}",
+                            state.vector_type_name()
+                        ),
+                    })?;
+                self.values.extend(state.iter_data().flatten());
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        let mad = median_absolute_deviation(&self.values);
+        Ok(mad.map(Value::from).unwrap_or(Value::Null))
+    }
+}
+
+pub(crate) fn create_zscore_accumulator() -> AccumulatorCreatorFunction {
+    Arc::new(|_: &[ConcreteDataType]| Ok(Box::<Zscore>::default()))
+}
+
+pub(crate) fn zscore_state_types() -> Vec<ConcreteDataType> {
+    vec![
+        ConcreteDataType::list_datatype(ConcreteDataType::int64_datatype()),
+        ConcreteDataType::list_datatype(ConcreteDataType::float64_datatype()),
+    ]
+}
+
+pub(crate) fn create_mad_accumulator(name: &str) -> AccumulatorCreatorFunction {
+    let name = name.to_string();
+    Arc::new(move |types: &[ConcreteDataType]| {
+        let input_type = &types[0];
+        with_match_primitive_type_id!(
+            input_type.logical_type_id(),
+            |$S| {
+                Ok(Box::<MedianAbsoluteDeviation<<$S as LogicalPrimitiveType>::Native>>::default())
+            },
+            {
+                let err_msg = format!(
+                    "\"{}\" aggregate function not support data type {:?}",
+                    name,
+                    input_type.logical_type_id(),
+                );
+                CreateAccumulatorSnafu { err_msg }.fail()?
+            }
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::{Int32Vector, TimestampMillisecondVector};
+
+    use super::*;
+
+    #[test]
+    fn test_zscore() {
+        let mut zscore = Zscore::default();
+        assert!(zscore.update_batch(&[]).is_ok());
+        assert_eq!(Value::Null, zscore.evaluate().unwrap());
+
+        // only one value
+        let times: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![0]));
+        let values: VectorRef = Arc::new(Int32Vector::from(vec![Some(1)]));
+        assert!(zscore.update_batch(&[times, values]).is_ok());
+        assert_eq!(Value::Null, zscore.evaluate().unwrap());
+
+        // The points are out of order, the latest one is scored.
+        let times: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![
+            4000, 3000, 1000, 5000, 2000,
+        ]));
+        let values: VectorRef = Arc::new(Int32Vector::from(vec![
+            Some(10),
+            Some(4),
+            Some(2),
+            None,
+            Some(3),
+        ]));
+        assert!(zscore.update_batch(&[times, values]).is_ok());
+        assert_eq!(Value::from(6.0 / 10f64.sqrt()), zscore.evaluate().unwrap());
+
+        // all values are the same
+        let mut zscore = Zscore::default();
+        let times: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![0, 1, 2, 3]));
+        let values: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(Int32Vector::from_vec(vec![4])),
+            4,
+        ));
+        assert!(zscore.update_batch(&[times, values]).is_ok());
+        assert_eq!(Value::Null, zscore.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_mad() {
+        let mut mad = MedianAbsoluteDeviation::<i32>::default();
+        assert_eq!(Value::Null, mad.evaluate().unwrap());
+
+        let v: Vec<VectorRef> = vec![Arc::new(Int32Vector::from(vec![
+            Some(1),
+            Some(2),
+            Some(3),
+            None,
+            Some(4),
+            Some(10),
+        ]))];
+        assert!(mad.update_batch(&v).is_ok());
+        assert_eq!(Value::from(1.0), mad.evaluate().unwrap());
+    }
+}
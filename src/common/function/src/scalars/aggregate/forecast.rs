@@ -0,0 +1,331 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::error::{
+    BadAccumulatorImplSnafu, DowncastVectorSnafu, FromScalarValueSnafu, InvalidFuncArgsSnafu,
+    InvalidInputStateSnafu, Result,
+};
+use common_query::logical_plan::Accumulator;
+use common_query::prelude::*;
+use common_time::timestamp::TimeUnit;
+use datatypes::prelude::*;
+use datatypes::value::ListValue;
+use datatypes::vectors::{Float64Vector, Int64Vector, ListVector};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::scalars::forecast::{double_exponential_forecast, seasonal_forecast};
+
+/// The forecasting method and the number of its parameters following the timestamp and
+/// value arguments.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Method {
+    /// Parameters: smoothing factor, trend factor and horizon in seconds.
+    DoubleExponential,
+    /// Parameters: season in seconds and horizon in seconds.
+    Seasonal,
+}
+
+impl Method {
+    pub(crate) fn num_params(&self) -> usize {
+        match self {
+            Method::DoubleExponential => 3,
+            Method::Seasonal => 2,
+        }
+    }
+}
+
+/// Forecasts the value of a series some time after its last point, from the points in a
+/// group. The points are sorted by their timestamps before forecasting, so the input
+/// doesn't need to be ordered.
+#[derive(Debug)]
+pub struct Forecast {
+    method: Method,
+    /// Timestamps in milliseconds.
+    times: Vec<i64>,
+    values: Vec<f64>,
+    /// The constant parameters, set by the first batch.
+    params: Option<Vec<f64>>,
+}
+
+impl Forecast {
+    pub(crate) fn new(method: Method) -> Self {
+        Self {
+            method,
+            times: Vec::new(),
+            values: Vec::new(),
+            params: None,
+        }
+    }
+
+    fn set_params(&mut self, params: Vec<f64>) -> Result<()> {
+        match &self.params {
+            Some(old) => ensure!(
+                *old == params,
+                InvalidFuncArgsSnafu {
+                    err_msg: "expecting the forecasting parameters to be constants",
+                }
+            ),
+            None => self.params = Some(params),
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for Forecast {
+    fn state(&self) -> Result<Vec<Value>> {
+        let times = self.times.iter().map(|&t| t.into()).collect::<Vec<Value>>();
+        let values = self
+            .values
+            .iter()
+            .map(|&v| v.into())
+            .collect::<Vec<Value>>();
+        let params = self
+            .params
+            .iter()
+            .flatten()
+            .map(|&p| p.into())
+            .collect::<Vec<Value>>();
+        Ok(vec![
+            Value::List(ListValue::new(
+                Some(Box::new(times)),
+                ConcreteDataType::int64_datatype(),
+            )),
+            Value::List(ListValue::new(
+                Some(Box::new(values)),
+                ConcreteDataType::float64_datatype(),
+            )),
+            Value::List(ListValue::new(
+                Some(Box::new(params)),
+                ConcreteDataType::float64_datatype(),
+            )),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            values.len() == 2 + self.method.num_params(),
+            InvalidInputStateSnafu
+        );
+        if values[0].is_empty() {
+            return Ok(());
+        }
+
+        let params = values[2..]
+            .iter()
+            .map(|param| {
+                value_to_f64(&param.get(0)).context(InvalidFuncArgsSnafu {
+                    err_msg: "expecting the forecasting parameters to be numbers",
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.set_params(params)?;
+
+        let (times, column) = (&values[0], &values[1]);
+        for i in 0..times.len() {
+            let time = value_to_millis(&times.get(i));
+            if let (Some(time), Some(value)) = (time, value_to_f64(&column.get(i))) {
+                self.times.push(time);
+                self.values.push(value);
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            states.len() == 3,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 3 states in `merge_batch`",
+            }
+        );
+
+        let lists = states
+            .iter()
+            .map(|state| {
+                state
+                    .as_any()
+                    .downcast_ref::<ListVector>()
+                    .with_context(|| DowncastVectorSnafu {
+                        err_msg: format!(
+                            "expect ListVector, got vector type {}",
+                            state.vector_type_name()
+                        ),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for ((times, values), params) in lists[0]
+            .values_iter()
+            .zip(lists[1].values_iter())
+            .zip(lists[2].values_iter())
+        {
+            let (Some(times), Some(values), Some(params)) = (
+                times.context(FromScalarValueSnafu)?,
+                values.context(FromScalarValueSnafu)?,
+                params.context(FromScalarValueSnafu)?,
+            ) else {
+                continue;
+            };
+            let times = downcast::<Int64Vector>(&times)?;
+            let values = downcast::<Float64Vector>(&values)?;
+            let params = downcast::<Float64Vector>(&params)?;
+            if params.is_empty() {
+                // The partial accumulator had no input.
+                continue;
+            }
+            self.set_params(params.iter_data().flatten().collect())?;
+            self.times.extend(times.iter_data().flatten());
+            self.values.extend(values.iter_data().flatten());
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        let Some(params) = &self.params else {
+            return Ok(Value::Null);
+        };
+        let mut points = self
+            .times
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+            .collect::<Vec<_>>();
+        points.sort_by_key(|(time, _)| *time);
+        let (times, values): (Vec<_>, Vec<_>) = points.into_iter().unzip();
+
+        let forecast = match self.method {
+            Method::DoubleExponential => {
+                double_exponential_forecast(&times, &values, params[0], params[1], params[2])
+            }
+            Method::Seasonal => seasonal_forecast(&times, &values, params[0], params[1]),
+        };
+        Ok(forecast.map(Value::from).unwrap_or(Value::Null))
+    }
+}
+
+pub(crate) fn downcast<T: Vector>(vector: &VectorRef) -> Result<&T> {
+    vector
+        .as_any()
+        .downcast_ref::<T>()
+        .with_context(|| DowncastVectorSnafu {
+            err_msg: format!(
+                "expect {}, got vector type {}",
+                std::any::type_name::<T>(),
+                vector.vector_type_name()
+            ),
+        })
+}
+
+/// Converts a timestamp (or a number of milliseconds) to milliseconds.
+pub(crate) fn value_to_millis(value: &Value) -> Option<i64> {
+    match value {
+        Value::Timestamp(ts) => ts.convert_to(TimeUnit::Millisecond).map(|ts| ts.value()),
+        Value::Int64(ts) => Some(*ts),
+        _ => None,
+    }
+}
+
+pub(crate) fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::UInt8(v) => Some(*v as f64),
+        Value::UInt16(v) => Some(*v as f64),
+        Value::UInt32(v) => Some(*v as f64),
+        Value::UInt64(v) => Some(*v as f64),
+        Value::Int8(v) => Some(*v as f64),
+        Value::Int16(v) => Some(*v as f64),
+        Value::Int32(v) => Some(*v as f64),
+        Value::Int64(v) => Some(*v as f64),
+        Value::Float32(v) => Some(v.0 as f64),
+        Value::Float64(v) => Some(v.0),
+        _ => None,
+    }
+}
+
+pub(crate) fn create_accumulator(method: Method) -> AccumulatorCreatorFunction {
+    Arc::new(move |_: &[ConcreteDataType]| Ok(Box::new(Forecast::new(method))))
+}
+
+pub(crate) fn state_types() -> Vec<ConcreteDataType> {
+    vec![
+        ConcreteDataType::list_datatype(ConcreteDataType::int64_datatype()),
+        ConcreteDataType::list_datatype(ConcreteDataType::float64_datatype()),
+        ConcreteDataType::list_datatype(ConcreteDataType::float64_datatype()),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::{
+        ConstantVector, Float64Vector, Int32Vector, TimestampMillisecondVector,
+    };
+
+    use super::*;
+
+    fn constant(value: f64, len: usize) -> VectorRef {
+        Arc::new(ConstantVector::new(
+            Arc::new(Float64Vector::from_vec(vec![value])),
+            len,
+        ))
+    }
+
+    #[test]
+    fn test_double_exponential_forecast() {
+        let mut forecast = Forecast::new(Method::DoubleExponential);
+        assert!(forecast.update_batch(&[]).is_ok());
+        assert_eq!(Value::Null, forecast.evaluate().unwrap());
+
+        // The points are out of order.
+        let times: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![
+            20_000, 0, 10_000, 30_000,
+        ]));
+        let values: VectorRef = Arc::new(Int32Vector::from(vec![Some(4), Some(0), Some(2), None]));
+        assert!(forecast
+            .update_batch(&[
+                times,
+                values,
+                constant(0.5, 4),
+                constant(0.5, 4),
+                constant(20.0, 4)
+            ])
+            .is_ok());
+        assert_eq!(Value::from(8.0), forecast.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_seasonal_forecast() {
+        let mut forecast = Forecast::new(Method::Seasonal);
+        let times: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![0, 60_000]));
+        let values: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0]));
+        assert!(forecast
+            .update_batch(&[times, values, constant(120.0, 2), constant(60.0, 2)])
+            .is_ok());
+        // Two points on a line, the seasonal component is zero.
+        let Value::Float64(result) = forecast.evaluate().unwrap() else {
+            unreachable!()
+        };
+        assert!((result.0 - 3.0).abs() < 1e-6);
+
+        // Wrong number of arguments.
+        let times: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![0]));
+        let values: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0]));
+        assert!(forecast.update_batch(&[times, values]).is_err());
+    }
+}
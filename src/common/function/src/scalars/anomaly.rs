@@ -0,0 +1,51 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Anomaly detection algorithms shared by the anomaly aggregate functions and the PromQL range
+//! functions.
+
+/// Returns the z-score of `value` among `values`, or `None` if there are less than two values
+/// or all values are the same.
+pub fn zscore(values: &[f64], value: f64) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / count;
+    let stddev = variance.sqrt();
+    if stddev == 0.0 {
+        return None;
+    }
+    Some((value - mean) / stddev)
+}
+
+/// Returns the median absolute deviation of `values`, or `None` if there are no values.
+pub fn median_absolute_deviation(values: &[f64]) -> Option<f64> {
+    let median = median(values.to_vec())?;
+    median(values.iter().map(|v| (v - median).abs()).collect())
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Forecasting algorithms shared by the SQL aggregate functions and the PromQL range
+//! functions. The timestamps are in milliseconds, while the season and the horizon are in
+//! seconds.
+
+/// Rounds of fitting the trend and the seasonal component in [seasonal_forecast].
+const DECOMPOSE_ITERATIONS: usize = 10;
+
+/// Forecasts the value `horizon` seconds after the last point by Holt's linear method
+/// (a.k.a. double exponential smoothing), with the smoothing factor `sf` and the trend
+/// factor `tf`.
+///
+/// The trend is learned per point, so it's scaled by the average interval of the points to
+/// extrapolate. Returns `None` if there are less than two points or the factors are not in
+/// `(0, 1)`.
+pub fn double_exponential_forecast(
+    times: &[i64],
+    values: &[f64],
+    sf: f64,
+    tf: f64,
+    horizon: f64,
+) -> Option<f64> {
+    if values.len() < 2 || times.len() != values.len() {
+        return None;
+    }
+    if sf <= 0.0 || sf >= 1.0 || tf <= 0.0 || tf >= 1.0 {
+        return None;
+    }
+    let interval = average_interval(times)?;
+
+    let mut level = values[0];
+    let mut trend = values[1] - values[0];
+    for value in &values[1..] {
+        let last_level = level;
+        level = sf * value + (1.0 - sf) * (level + trend);
+        trend = tf * (level - last_level) + (1.0 - tf) * trend;
+    }
+    Some(level + trend * horizon / interval)
+}
+
+/// Forecasts the value `horizon` seconds after the last point by decomposing the points into
+/// a linear trend and an additive seasonal component with a period of `season` seconds.
+///
+/// The season is split into buckets of the average interval of the points, the seasonal
+/// component of a bucket is the mean of the residuals of the trend in it. Returns `None` if
+/// there are less than two points or the season is not positive.
+pub fn seasonal_forecast(times: &[i64], values: &[f64], season: f64, horizon: f64) -> Option<f64> {
+    if values.len() < 2 || times.len() != values.len() || season <= 0.0 {
+        return None;
+    }
+    let interval = average_interval(times)?;
+    // Seconds since the first point.
+    let xs = times
+        .iter()
+        .map(|t| (t - times[0]) as f64 / 1000.0)
+        .collect::<Vec<_>>();
+    let num_buckets = ((season / interval).round() as usize).max(1);
+    let bucket =
+        |x: f64| ((x.rem_euclid(season) / season * num_buckets as f64) as usize) % num_buckets;
+
+    // Fits the trend and the seasonal component alternately, as the trend fitted on the raw
+    // points is biased by the seasonal component.
+    let mut seasonal = vec![0.0; num_buckets];
+    let (mut slope, mut intercept) = (0.0, 0.0);
+    for _ in 0..DECOMPOSE_ITERATIONS {
+        let deseasonalized = xs
+            .iter()
+            .zip(values)
+            .map(|(x, value)| value - seasonal[bucket(*x)])
+            .collect::<Vec<_>>();
+        (slope, intercept) = linear_regression(&xs, &deseasonalized)?;
+
+        let mut sums = vec![0.0; num_buckets];
+        let mut counts = vec![0usize; num_buckets];
+        for (x, value) in xs.iter().zip(values) {
+            let b = bucket(*x);
+            sums[b] += value - (intercept + slope * x);
+            counts[b] += 1;
+        }
+        for (b, component) in seasonal.iter_mut().enumerate() {
+            *component = if counts[b] > 0 {
+                sums[b] / counts[b] as f64
+            } else {
+                0.0
+            };
+        }
+    }
+
+    let target = xs[xs.len() - 1] + horizon;
+    Some(intercept + slope * target + seasonal[bucket(target)])
+}
+
+/// Average interval of the points in seconds, `None` if all points have the same timestamp.
+fn average_interval(times: &[i64]) -> Option<f64> {
+    let span = (times[times.len() - 1] - times[0]) as f64 / 1000.0;
+    let interval = span / (times.len() - 1) as f64;
+    (interval > 0.0).then_some(interval)
+}
+
+/// Least-square linear regression, returns the slope and the intercept.
+fn linear_regression(xs: &[f64], ys: &[f64]) -> Option<(f64, f64)> {
+    let count = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / count;
+    let mean_y = ys.iter().sum::<f64>() / count;
+    let cov_xy = xs
+        .iter()
+        .zip(ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let var_x = xs.iter().map(|x| (x - mean_x) * (x - mean_x)).sum::<f64>();
+    if var_x == 0.0 {
+        return None;
+    }
+    let slope = cov_xy / var_x;
+    Some((slope, mean_y - slope * mean_x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: Option<f64>) {
+        let actual = actual.unwrap();
+        assert!(
+            (expected - actual).abs() < 1e-6,
+            "expected {expected}, actual {actual}"
+        );
+    }
+
+    #[test]
+    fn test_double_exponential_forecast() {
+        // A perfect line grows 2 per 10 seconds.
+        let times = (0..10).map(|i| i * 10_000).collect::<Vec<_>>();
+        let values = (0..10).map(|i| i as f64 * 2.0).collect::<Vec<_>>();
+        assert_close(
+            38.0,
+            double_exponential_forecast(&times, &values, 0.5, 0.5, 100.0),
+        );
+        assert_close(
+            18.0,
+            double_exponential_forecast(&times, &values, 0.3, 0.7, 0.0),
+        );
+
+        assert_eq!(
+            None,
+            double_exponential_forecast(&times[..1], &values[..1], 0.5, 0.5, 1.0)
+        );
+        assert_eq!(
+            None,
+            double_exponential_forecast(&times, &values, 1.0, 0.5, 1.0)
+        );
+        assert_eq!(
+            None,
+            double_exponential_forecast(&times, &values, 0.5, 0.0, 1.0)
+        );
+        assert_eq!(
+            None,
+            double_exponential_forecast(&[0, 0], &[1.0, 2.0], 0.5, 0.5, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_seasonal_forecast() {
+        // A line growing 1 per minute, plus a season of 4 minutes.
+        let pattern = [0.0, 10.0, 0.0, -10.0];
+        let times = (0..16).map(|i| i * 60_000).collect::<Vec<_>>();
+        let values = (0..16)
+            .map(|i| i as f64 + pattern[i % 4])
+            .collect::<Vec<_>>();
+        // 4 minutes after the last point lands on the same phase as the last point.
+        assert_close(
+            19.0 - 10.0,
+            seasonal_forecast(&times, &values, 240.0, 240.0),
+        );
+        assert_close(16.0, seasonal_forecast(&times, &values, 240.0, 60.0));
+        // 2 minutes after the last point lands on the peak.
+        assert_close(
+            17.0 + 10.0,
+            seasonal_forecast(&times, &values, 240.0, 120.0),
+        );
+
+        assert_eq!(
+            None,
+            seasonal_forecast(&times[..1], &values[..1], 240.0, 60.0)
+        );
+        assert_eq!(None, seasonal_forecast(&times, &values, 0.0, 60.0));
+    }
+}
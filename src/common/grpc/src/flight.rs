@@ -22,7 +22,7 @@ use common_base::bytes::Bytes;
 use common_recordbatch::{RecordBatch, RecordBatches};
 use datatypes::arrow;
 use datatypes::arrow::datatypes::Schema as ArrowSchema;
-use datatypes::arrow::ipc::{root_as_message, writer, MessageHeader};
+use datatypes::arrow::ipc::{root_as_message, writer, CompressionType, MessageHeader};
 use datatypes::schema::{Schema, SchemaRef};
 use flatbuffers::FlatBufferBuilder;
 use prost::Message;
@@ -33,6 +33,10 @@ use crate::error::{
     Result,
 };
 
+/// Type of the Flight action carrying an administrative request to the datanode, whose body
+/// is the request encoded in JSON. The results of the action are the Flight data of the output.
+pub const ADMIN_ACTION: &str = "admin";
+
 #[derive(Debug, Clone)]
 pub enum FlightMessage {
     Schema(SchemaRef),
@@ -42,6 +46,8 @@ pub enum FlightMessage {
 
 pub struct FlightEncoder {
     write_options: writer::IpcWriteOptions,
+    /// Write options to compress the record batches whose sizes reach the threshold.
+    compression: Option<(usize, writer::IpcWriteOptions)>,
     data_gen: writer::IpcDataGenerator,
     dictionary_tracker: writer::DictionaryTracker,
 }
@@ -50,6 +56,7 @@ impl Default for FlightEncoder {
     fn default() -> Self {
         Self {
             write_options: writer::IpcWriteOptions::default(),
+            compression: None,
             data_gen: writer::IpcDataGenerator::default(),
             dictionary_tracker: writer::DictionaryTracker::new(false),
         }
@@ -57,18 +64,42 @@ impl Default for FlightEncoder {
 }
 
 impl FlightEncoder {
+    /// Creates an encoder compressing the record batches of no less than `threshold` bytes
+    /// with zstd. The decoder needs no option to read them.
+    pub fn with_compression(threshold: usize) -> Self {
+        let compressed_options = writer::IpcWriteOptions::default()
+            .try_with_compression(Some(CompressionType::ZSTD))
+            .expect("IPC compression should be supported with default IPC options");
+        Self {
+            compression: Some((threshold, compressed_options)),
+            ..Default::default()
+        }
+    }
+
+    fn batch_write_options(&self, recordbatch: &RecordBatch) -> &writer::IpcWriteOptions {
+        match &self.compression {
+            Some((threshold, options))
+                if recordbatch.df_record_batch().get_array_memory_size() >= *threshold =>
+            {
+                options
+            }
+            _ => &self.write_options,
+        }
+    }
+
     pub fn encode(&mut self, flight_message: FlightMessage) -> FlightData {
         match flight_message {
             FlightMessage::Schema(schema) => {
                 SchemaAsIpc::new(schema.arrow_schema(), &self.write_options).into()
             }
             FlightMessage::Recordbatch(recordbatch) => {
+                let write_options = self.batch_write_options(&recordbatch).clone();
                 let (encoded_dictionaries, encoded_batch) = self
                     .data_gen
                     .encoded_batch(
                         recordbatch.df_record_batch(),
                         &mut self.dictionary_tracker,
-                        &self.write_options,
+                        &write_options,
                     )
                     .expect("DictionaryTracker configured above to not fail on replacement");
 
@@ -278,6 +309,49 @@ mod test {
         assert_eq!(actual_batch, batch2);
     }
 
+    #[test]
+    fn test_encode_compressed_batches() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )]));
+        let small_batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice([1, 2, 3])) as _],
+        )
+        .unwrap();
+        let large_batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice(vec![42; 10000])) as _],
+        )
+        .unwrap();
+        let threshold = large_batch.df_record_batch().get_array_memory_size();
+
+        let mut encoder = FlightEncoder::with_compression(threshold);
+        let mut plain_encoder = FlightEncoder::default();
+        let decoder = &mut FlightDecoder::default();
+        let _ = decoder
+            .try_decode(encoder.encode(FlightMessage::Schema(schema)))
+            .unwrap();
+
+        for batch in [small_batch, large_batch] {
+            let flight_data = encoder.encode(FlightMessage::Recordbatch(batch.clone()));
+            let plain_flight_data = plain_encoder.encode(FlightMessage::Recordbatch(batch.clone()));
+            // Only batches reaching the threshold are compressed.
+            if batch.num_rows() == 3 {
+                assert_eq!(plain_flight_data.data_body, flight_data.data_body);
+            } else {
+                assert!(flight_data.data_body.len() < plain_flight_data.data_body.len());
+            }
+
+            let FlightMessage::Recordbatch(decoded) = decoder.try_decode(flight_data).unwrap() else {
+                unreachable!()
+            };
+            assert_eq!(batch, decoded);
+        }
+    }
+
     #[test]
     fn test_flight_messages_to_recordbatches() {
         let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
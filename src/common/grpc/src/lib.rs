@@ -19,3 +19,7 @@ pub mod select;
 pub mod writer;
 
 pub use error::Error;
+
+/// Request metadata carrying the trace id of the request in 16 hex digits. The frontend
+/// propagates it to the datanodes so the logs of a request could be correlated across nodes.
+pub const GREPTIME_TRACE_ID_HEADER: &str = "x-greptime-trace-id";
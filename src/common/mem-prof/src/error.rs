@@ -38,6 +38,9 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    #[snafu(display("Failed to read allocator statistics"))]
+    ReadStats { source: tikv_jemalloc_ctl::Error },
+
     #[snafu(display("Failed to dump profiling data to temp file: {:?}", path))]
     DumpProfileData {
         path: PathBuf,
@@ -48,7 +51,7 @@ pub enum Error {
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
-            Error::ReadOptProf { .. } => StatusCode::Internal,
+            Error::ReadOptProf { .. } | Error::ReadStats { .. } => StatusCode::Internal,
             Error::ProfilingNotEnabled => StatusCode::InvalidArguments,
             Error::BuildTempPath { .. } => StatusCode::Internal,
             Error::OpenTempFile { .. } => StatusCode::StorageUnavailable,
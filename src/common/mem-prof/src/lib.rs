@@ -22,12 +22,45 @@ use tokio::io::AsyncReadExt;
 
 use crate::error::{
     BuildTempPathSnafu, DumpProfileDataSnafu, OpenTempFileSnafu, ProfilingNotEnabledSnafu,
-    ReadOptProfSnafu,
+    ReadOptProfSnafu, ReadStatsSnafu,
 };
 
 const PROF_DUMP: &[u8] = b"prof.dump\0";
 const OPT_PROF: &[u8] = b"opt.prof\0";
 
+/// Statistics of jemalloc in bytes, see `stats.*` in the jemalloc manual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocatorStats {
+    /// Bytes allocated by the application.
+    pub allocated: usize,
+    /// Bytes in active pages allocated by the application.
+    pub active: usize,
+    /// Bytes in physically resident data pages mapped by the allocator.
+    pub resident: usize,
+    /// Bytes in active extents mapped by the allocator.
+    pub mapped: usize,
+    /// Bytes in virtual memory mappings retained instead of being returned to the OS.
+    pub retained: usize,
+    /// Bytes dedicated to the allocator metadata.
+    pub metadata: usize,
+}
+
+/// Reads the latest allocator statistics.
+pub fn allocator_stats() -> error::Result<AllocatorStats> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    // Statistics are cached by jemalloc until the epoch is advanced.
+    epoch::advance().context(ReadStatsSnafu)?;
+    Ok(AllocatorStats {
+        allocated: stats::allocated::read().context(ReadStatsSnafu)?,
+        active: stats::active::read().context(ReadStatsSnafu)?,
+        resident: stats::resident::read().context(ReadStatsSnafu)?,
+        mapped: stats::mapped::read().context(ReadStatsSnafu)?,
+        retained: stats::retained::read().context(ReadStatsSnafu)?,
+        metadata: stats::metadata::read().context(ReadStatsSnafu)?,
+    })
+}
+
 pub async fn dump_profile() -> error::Result<Vec<u8>> {
     ensure!(is_prof_enabled()?, ProfilingNotEnabledSnafu);
     let tmp_path = tempfile::tempdir().map_err(|_| {
@@ -176,7 +176,7 @@ impl Error {
     /// Creates a new [Error::RetryLater] or [Error::External] error from source `err` according
     /// to its [StatusCode].
     pub fn from_error_ext<E: ErrorExt + Send + Sync + 'static>(err: E) -> Self {
-        if err.status_code().is_retryable() {
+        if err.is_retryable() {
             Error::retry_later(err)
         } else {
             Error::external(err)
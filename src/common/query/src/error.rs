@@ -35,6 +35,9 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Fail to execute WASM UDF, source: {}", msg))]
+    WasmUdf { msg: String, location: Location },
+
     #[snafu(display(
         "Fail to create temporary recordbatch when eval Python UDF, source: {}",
         source
@@ -109,6 +112,17 @@ pub enum Error {
     #[snafu(display("Not expected to run ExecutionPlan more than once"))]
     ExecuteRepeatedly { location: Location },
 
+    #[snafu(display(
+        "Partition {} out of range, the plan has {} partitions",
+        partition,
+        num_partitions
+    ))]
+    PartitionOutOfRange {
+        partition: usize,
+        num_partitions: usize,
+        location: Location,
+    },
+
     #[snafu(display("General DataFusion error, source: {}", source))]
     GeneralDataFusion {
         source: DataFusionError,
@@ -181,6 +195,7 @@ impl ErrorExt for Error {
         match self {
             Error::UdfTempRecordBatch { .. }
             | Error::PyUdf { .. }
+            | Error::WasmUdf { .. }
             | Error::ExecuteFunction { .. }
             | Error::GenerateFunction { .. }
             | Error::CreateAccumulator { .. }
@@ -199,6 +214,7 @@ impl ErrorExt for Error {
             | Error::FromArrowArray { source } => source.status_code(),
 
             Error::ExecuteRepeatedly { .. }
+            | Error::PartitionOutOfRange { .. }
             | Error::GeneralDataFusion { .. }
             | Error::DataFusionExecutionPlan { .. } => StatusCode::Unexpected,
 
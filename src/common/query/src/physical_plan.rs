@@ -22,16 +22,21 @@ use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
 use datafusion::error::Result as DfResult;
 pub use datafusion::execution::context::{SessionContext, TaskContext};
 use datafusion::physical_plan::expressions::PhysicalSortExpr;
-pub use datafusion::physical_plan::Partitioning;
-use datafusion::physical_plan::Statistics;
+use datafusion::physical_plan::metrics::MetricsSet;
+pub use datafusion::physical_plan::{Partitioning, Statistics};
 use datatypes::schema::SchemaRef;
 use snafu::ResultExt;
 
 use crate::error::{self, Result};
+use crate::logical_plan::DfExpr;
 use crate::DfPhysicalPlan;
 
 pub type PhysicalPlanRef = Arc<dyn PhysicalPlan>;
 
+/// Name of the metric counting the bytes read by a table scan, the same as the one of
+/// DataFusion's file scans.
+pub const BYTES_SCANNED: &str = "bytes_scanned";
+
 /// `PhysicalPlan` represent nodes in the Physical Plan.
 ///
 /// Each `PhysicalPlan` is Partition-aware and is responsible for
@@ -69,6 +74,45 @@ pub trait PhysicalPlan: Debug + Send + Sync {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream>;
+
+    /// Returns the statistics of this plan's output, used by the optimizer to estimate
+    /// the cost of the plan. Unknown by default.
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    /// Returns the metrics collected while executing this plan, if any.
+    fn metrics(&self) -> Option<MetricsSet> {
+        None
+    }
+
+    /// Returns a new plan whose every partition only outputs its first `fetch` rows in the
+    /// order of `sort_exprs`, or `None` if the plan is not able to do such top-k pushdown.
+    ///
+    /// The `sort_exprs` are [DfExpr::Sort]s on the columns of this plan's schema. The rows
+    /// are still sorted and limited above the returned plan, so it's free to output more.
+    fn with_top_k(&self, _sort_exprs: &[DfExpr], _fetch: usize) -> Option<PhysicalPlanRef> {
+        None
+    }
+
+    /// Returns a new plan that outputs the partial states of the `aggregate` on the rows of
+    /// this plan, or `None` if the plan is not able to do such aggregate pushdown.
+    fn with_partial_aggregate(&self, _aggregate: &PartialAggregate) -> Option<PhysicalPlanRef> {
+        None
+    }
+}
+
+/// A partial aggregation pushed down to the table scans, whose outputs are merged by the
+/// final aggregation later.
+#[derive(Debug, Clone)]
+pub struct PartialAggregate {
+    /// Expressions to group the rows by, on the columns of the scanned plan.
+    pub group_exprs: Vec<DfExpr>,
+    /// Aggregate expressions computing the partial states, on the columns of the scanned plan.
+    pub aggr_exprs: Vec<DfExpr>,
+    /// Schema of the partial states, which are the results of `group_exprs` followed by the
+    /// results of `aggr_exprs`, in the same order.
+    pub schema: SchemaRef,
 }
 
 #[derive(Debug)]
@@ -135,6 +179,14 @@ impl PhysicalPlan for PhysicalPlanAdapter {
 
         Ok(Box::pin(adapter))
     }
+
+    fn statistics(&self) -> Statistics {
+        self.df_plan.statistics()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.df_plan.metrics()
+    }
 }
 
 #[derive(Debug)]
@@ -193,8 +245,11 @@ impl DfPhysicalPlan for DfPhysicalPlanAdapter {
     }
 
     fn statistics(&self) -> Statistics {
-        // TODO(LFC): impl statistics
-        Statistics::default()
+        self.0.statistics()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.0.metrics()
     }
 }
 
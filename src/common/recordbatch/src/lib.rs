@@ -34,6 +34,35 @@ use snafu::{ensure, ResultExt};
 
 pub trait RecordBatchStream: Stream<Item = Result<RecordBatch>> {
     fn schema(&self) -> SchemaRef;
+
+    /// Statistics of the execution producing this stream, only available after the stream
+    /// is exhausted.
+    fn stats(&self) -> Option<ExecutionStats> {
+        None
+    }
+}
+
+/// Cost of executing a query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionStats {
+    /// Time from the start of the execution to the end of the output, in milliseconds.
+    pub elapsed_ms: u64,
+    /// Number of rows read by the table scans.
+    pub rows_scanned: u64,
+    /// In-memory size of the rows read by the table scans, in bytes.
+    pub bytes_read: u64,
+    /// Peak memory reserved by operators like sorts and joins, in bytes.
+    pub peak_memory_bytes: u64,
+}
+
+impl ExecutionStats {
+    /// Accumulates the stats of another execution, as if they were run one after another.
+    pub fn merge(&mut self, other: &ExecutionStats) {
+        self.elapsed_ms += other.elapsed_ms;
+        self.rows_scanned += other.rows_scanned;
+        self.bytes_read += other.bytes_read;
+        self.peak_memory_bytes = self.peak_memory_bytes.max(other.peak_memory_bytes);
+    }
 }
 
 pub type SendableRecordBatchStream = Pin<Box<dyn RecordBatchStream + Send>>;
@@ -271,4 +300,29 @@ mod tests {
         assert_eq!(collected[0], batch1);
         assert_eq!(collected[1], batch2);
     }
+
+    #[test]
+    fn test_merge_execution_stats() {
+        let mut stats = ExecutionStats {
+            elapsed_ms: 10,
+            rows_scanned: 100,
+            bytes_read: 1000,
+            peak_memory_bytes: 4096,
+        };
+        stats.merge(&ExecutionStats {
+            elapsed_ms: 5,
+            rows_scanned: 20,
+            bytes_read: 200,
+            peak_memory_bytes: 1024,
+        });
+        assert_eq!(
+            ExecutionStats {
+                elapsed_ms: 15,
+                rows_scanned: 120,
+                bytes_read: 1200,
+                peak_memory_bytes: 4096,
+            },
+            stats
+        );
+    }
 }
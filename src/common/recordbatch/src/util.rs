@@ -15,13 +15,22 @@
 use futures::TryStreamExt;
 
 use crate::error::Result;
-use crate::{RecordBatch, RecordBatches, SendableRecordBatchStream};
+use crate::{ExecutionStats, RecordBatch, RecordBatches, SendableRecordBatchStream};
 
 /// Collect all the items from the stream into a vector of [`RecordBatch`].
 pub async fn collect(stream: SendableRecordBatchStream) -> Result<Vec<RecordBatch>> {
     stream.try_collect::<Vec<_>>().await
 }
 
+/// Collect all the items from the stream into a vector of [`RecordBatch`], along with the
+/// [ExecutionStats] of the stream if it has.
+pub async fn collect_with_stats(
+    mut stream: SendableRecordBatchStream,
+) -> Result<(Vec<RecordBatch>, Option<ExecutionStats>)> {
+    let batches = (&mut stream).try_collect::<Vec<_>>().await?;
+    Ok((batches, stream.stats()))
+}
+
 /// Collect all the items from the stream into [RecordBatches].
 pub async fn collect_batches(stream: SendableRecordBatchStream) -> Result<RecordBatches> {
     let schema = stream.schema();
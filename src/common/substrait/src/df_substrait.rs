@@ -12,22 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cell::{Cell, RefCell};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::{Buf, Bytes, BytesMut};
 use datafusion::catalog::catalog::CatalogList;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::datasource::{provider_as_source, source_as_provider};
 use datafusion::prelude::SessionContext;
-use datafusion_expr::LogicalPlan;
+use datafusion_expr::{LogicalPlan, TableScan};
 use datafusion_substrait::logical_plan::consumer::from_substrait_plan;
 use datafusion_substrait::logical_plan::producer::to_substrait_plan;
 use prost::Message;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use substrait_proto::proto::Plan;
+use table::table::adapter::DfTableProviderAdapter;
 
-use crate::error::{DecodeDfPlanSnafu, DecodeRelSnafu, EncodeDfPlanSnafu, EncodeRelSnafu, Error};
+use crate::error::{
+    DFInternalSnafu, DecodeDfPlanSnafu, DecodeRelSnafu, EncodeDfPlanSnafu, EncodeRelSnafu, Error,
+    InvalidParametersSnafu,
+};
 use crate::SubstraitPlan;
 
+/// Type url of the plan optimization extension carrying the sample percents of the table scans.
+/// Substrait has no notion of table samples, so the percents are passed alongside the plan.
+const TABLE_SAMPLES_TYPE_URL: &str = "greptime.table_samples";
+
 pub struct DFLogicalSubstraitConvertor;
 
 #[async_trait]
@@ -43,19 +54,133 @@ impl SubstraitPlan for DFLogicalSubstraitConvertor {
     ) -> Result<Self::Plan, Self::Error> {
         let mut context = SessionContext::new();
         let plan = Plan::decode(message).context(DecodeRelSnafu)?;
+        let samples = decode_table_samples(&plan)?;
         context.register_catalog_list(catalog_list);
         let df_plan = from_substrait_plan(&mut context, &plan)
             .await
             .context(DecodeDfPlanSnafu)?;
-        Ok(df_plan)
+        apply_table_samples(df_plan, &samples)
     }
 
     fn encode(&self, plan: Self::Plan) -> Result<Bytes, Self::Error> {
         let mut buf = BytesMut::new();
 
-        let substrait_plan = to_substrait_plan(&plan).context(EncodeDfPlanSnafu)?;
+        let samples = table_samples(&plan)?;
+        let mut substrait_plan = to_substrait_plan(&plan).context(EncodeDfPlanSnafu)?;
+        encode_table_samples(&mut substrait_plan, &samples);
         substrait_plan.encode(&mut buf).context(EncodeRelSnafu)?;
 
         Ok(buf.freeze())
     }
 }
+
+/// Collects the sample percents of the table scans in `plan`, in the order they are visited.
+/// `None` is collected for the scans that are not sampled.
+fn table_samples(plan: &LogicalPlan) -> Result<Vec<Option<f64>>, Error> {
+    let samples = RefCell::new(Vec::new());
+    let _ = plan
+        .clone()
+        .transform_up(&|plan| {
+            if let LogicalPlan::TableScan(scan) = &plan {
+                let provider = source_as_provider(&scan.source)?;
+                let percent = provider
+                    .as_any()
+                    .downcast_ref::<DfTableProviderAdapter>()
+                    .and_then(|adapter| adapter.sample_percent());
+                samples.borrow_mut().push(percent);
+            }
+            Ok(Transformed::No(plan))
+        })
+        .context(DFInternalSnafu)?;
+    Ok(samples.into_inner())
+}
+
+fn encode_table_samples(plan: &mut Plan, samples: &[Option<f64>]) {
+    if samples.iter().all(Option::is_none) {
+        return;
+    }
+    // Each scan is encoded as its percent in little endian, NaN for the scans not sampled.
+    let value = samples
+        .iter()
+        .flat_map(|percent| percent.unwrap_or(f64::NAN).to_le_bytes())
+        .collect::<Vec<_>>();
+    let extensions = plan
+        .advanced_extensions
+        .get_or_insert_with(Default::default);
+    let optimization = extensions.optimization.get_or_insert_with(Default::default);
+    optimization.type_url = TABLE_SAMPLES_TYPE_URL.to_string();
+    optimization.value = value.into();
+}
+
+fn decode_table_samples(plan: &Plan) -> Result<Vec<Option<f64>>, Error> {
+    let Some(optimization) = plan
+        .advanced_extensions
+        .as_ref()
+        .and_then(|extensions| extensions.optimization.as_ref())
+        .filter(|optimization| optimization.type_url == TABLE_SAMPLES_TYPE_URL)
+    else {
+        return Ok(Vec::new());
+    };
+    let value: &[u8] = optimization.value.as_ref();
+    ensure!(
+        value.len() % 8 == 0,
+        InvalidParametersSnafu {
+            reason: format!("invalid table samples of {} bytes", value.len()),
+        }
+    );
+    Ok(value
+        .chunks_exact(8)
+        .map(|bytes| {
+            let percent = f64::from_le_bytes(bytes.try_into().unwrap());
+            (!percent.is_nan()).then_some(percent)
+        })
+        .collect())
+}
+
+/// Replaces the sources of the sampled table scans, visited in the same order as
+/// [table_samples], with adapters that only scan samples of the tables.
+fn apply_table_samples(plan: LogicalPlan, samples: &[Option<f64>]) -> Result<LogicalPlan, Error> {
+    if samples.is_empty() {
+        return Ok(plan);
+    }
+    let index = Cell::new(0);
+    plan.transform_up(&|plan| {
+        let LogicalPlan::TableScan(scan) = plan else { return Ok(Transformed::No(plan)) };
+        let percent = samples.get(index.get()).copied().flatten();
+        index.set(index.get() + 1);
+        let Some(percent) = percent else {
+            return Ok(Transformed::No(LogicalPlan::TableScan(scan)));
+        };
+        let provider = source_as_provider(&scan.source)?;
+        let Some(adapter) = provider.as_any().downcast_ref::<DfTableProviderAdapter>() else {
+            return Ok(Transformed::No(LogicalPlan::TableScan(scan)));
+        };
+        let source = provider_as_source(Arc::new(DfTableProviderAdapter::with_sample(
+            adapter.table(),
+            percent,
+        )));
+        Ok(Transformed::Yes(LogicalPlan::TableScan(TableScan {
+            source,
+            ..scan
+        })))
+    })
+    .context(DFInternalSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_samples_round_trip() {
+        let mut plan = Plan::default();
+        encode_table_samples(&mut plan, &[None, None]);
+        assert!(plan.advanced_extensions.is_none());
+        assert!(decode_table_samples(&plan).unwrap().is_empty());
+
+        let samples = vec![None, Some(12.5), Some(100.0)];
+        encode_table_samples(&mut plan, &samples);
+        let plan = Plan::decode(plan.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(samples, decode_table_samples(&plan).unwrap());
+    }
+}
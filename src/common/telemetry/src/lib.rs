@@ -14,6 +14,7 @@
 
 pub mod logging;
 mod macros;
+pub mod memory;
 pub mod metric;
 mod panic_hook;
 
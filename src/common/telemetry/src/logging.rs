@@ -14,12 +14,15 @@
 
 //! logging stuffs, inspired by databend
 use std::env;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, Once};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use opentelemetry::global;
 use opentelemetry::sdk::propagation::TraceContextPropagator;
 use serde::{Deserialize, Serialize};
+use tracing::Instrument;
 pub use tracing::{event, span, Level};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
@@ -28,15 +31,19 @@ use tracing_log::LogTracer;
 use tracing_subscriber::fmt::Layer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::{filter, EnvFilter, Registry};
+use tracing_subscriber::{filter, reload, EnvFilter, Registry};
 
-pub use crate::{debug, error, info, log, trace, warn};
+pub use crate::{debug, error, info, log, log_sampled, trace, warn};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LoggingOptions {
     pub dir: String,
+    /// Default log level, optionally followed by per-target levels,
+    /// e.g. `info,mito=debug,storage::flush=trace`.
     pub level: String,
+    /// Format of logs written to stdout. Log files are always in JSON.
+    pub log_format: LogFormat,
     pub enable_jaeger_tracing: bool,
 }
 
@@ -45,11 +52,115 @@ impl Default for LoggingOptions {
         Self {
             dir: "/tmp/greptimedb/logs".to_string(),
             level: "info".to_string(),
+            log_format: LogFormat::Text,
             enable_jaeger_tracing: false,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human readable lines.
+    Text,
+    /// One JSON object per line, with structured fields kept as JSON fields.
+    Json,
+}
+
+/// Handle to change the log filter after the global logging is initialized.
+static LOG_FILTER_HANDLE: OnceCell<reload::Handle<filter::Targets, Registry>> = OnceCell::new();
+
+/// Directives of the log filter in use.
+static LOG_DIRECTIVES: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(String::new()));
+
+/// Builds the log filter from directives like `info,mito=debug`. 3rd-party crates
+/// only log WARN and ERROR unless they are specified in the directives.
+fn build_log_filter(directives: &str) -> Result<filter::Targets, filter::ParseError> {
+    let targets = directives.parse::<filter::Targets>()?;
+    // TODO(dennis): configure them?
+    let filter = filter::Targets::new()
+        .with_target("hyper", Level::WARN)
+        .with_target("tower", Level::WARN)
+        .with_target("datafusion", Level::WARN)
+        .with_target("reqwest", Level::WARN)
+        .with_target("sqlparser", Level::WARN)
+        .with_target("h2", Level::INFO)
+        .with_targets(targets.iter())
+        .with_default(targets.default_level().unwrap_or(filter::LevelFilter::INFO));
+    Ok(filter)
+}
+
+/// Replaces the log filter of global logging with `directives` like
+/// `info,mito=debug` at runtime.
+pub fn set_log_level(directives: &str) -> Result<(), String> {
+    let handle = LOG_FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "global logging is not initialized".to_string())?;
+    let filter = build_log_filter(directives)
+        .map_err(|e| format!("invalid log level {directives:?}: {e}"))?;
+    handle.reload(filter).map_err(|e| e.to_string())?;
+    *LOG_DIRECTIVES.lock().unwrap() = directives.to_string();
+    Ok(())
+}
+
+/// Returns the directives of the log filter in use.
+pub fn log_level() -> String {
+    LOG_DIRECTIVES.lock().unwrap().clone()
+}
+
+tokio::task_local! {
+    /// Trace id of the request the current task is serving.
+    static TRACE_ID: u64;
+}
+
+/// Returns the trace id of the request the current task is serving, see [with_trace_id].
+pub fn trace_id() -> Option<u64> {
+    TRACE_ID.try_with(|trace_id| *trace_id).ok()
+}
+
+/// Generates a random trace id for a new request.
+pub fn gen_trace_id() -> u64 {
+    rand::random()
+}
+
+/// Formats `trace_id` as 16 hex digits, the form it's logged and propagated in.
+pub fn format_trace_id(trace_id: u64) -> String {
+    format!("{trace_id:016x}")
+}
+
+/// Parses the trace id formatted by [format_trace_id].
+pub fn parse_trace_id(trace_id: &str) -> Option<u64> {
+    u64::from_str_radix(trace_id, 16).ok()
+}
+
+/// Runs `future` serving the request of `trace_id`, in which [trace_id] returns it and the
+/// logs are recorded with field `trace_id`. Tasks spawned by the future don't inherit it.
+pub async fn with_trace_id<F: Future>(trace_id: u64, future: F) -> F::Output {
+    let span = tracing::info_span!("request", trace_id = %format_trace_id(trace_id));
+    TRACE_ID.scope(trace_id, future.instrument(span)).await
+}
+
+/// Samples one of every `every` events of a log call site, see [log_sampled](crate::log_sampled).
+pub struct LogSampler {
+    every: u64,
+    seen: AtomicU64,
+}
+
+impl LogSampler {
+    pub const fn new(every: u64) -> Self {
+        Self {
+            every,
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of events seen so far if this event should be logged.
+    pub fn sample(&self) -> Option<u64> {
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed) + 1;
+        (self.every <= 1 || seen % self.every == 1).then_some(seen)
+    }
+}
+
 /// Init tracing for unittest.
 /// Write logs to file `unittest`.
 pub fn init_default_ut_logging() {
@@ -91,7 +202,16 @@ pub fn init_global_logging(app_name: &str, opts: &LoggingOptions) -> Vec<WorkerG
 
     // Stdout layer.
     let (stdout_writer, stdout_guard) = tracing_appender::non_blocking(std::io::stdout());
-    let stdout_logging_layer = Layer::new().with_writer(stdout_writer);
+    let (stdout_logging_layer, stdout_json_logging_layer) = match opts.log_format {
+        LogFormat::Text => (Some(Layer::new().with_writer(stdout_writer)), None),
+        LogFormat::Json => (
+            None,
+            Some(BunyanFormattingLayer::new(
+                app_name.to_string(),
+                stdout_writer,
+            )),
+        ),
+    };
     guards.push(stdout_guard);
 
     // JSON log layer.
@@ -112,25 +232,18 @@ pub fn init_global_logging(app_name: &str, opts: &LoggingOptions) -> Vec<WorkerG
     // Use env RUST_LOG to initialize log if present.
     // Otherwise use the specified level.
     let directives = env::var(EnvFilter::DEFAULT_ENV).unwrap_or_else(|_x| level.to_string());
-    let filter = filter::Targets::new()
-        // Only enable WARN and ERROR for 3rd-party crates
-        // TODO(dennis): configure them?
-        .with_target("hyper", Level::WARN)
-        .with_target("tower", Level::WARN)
-        .with_target("datafusion", Level::WARN)
-        .with_target("reqwest", Level::WARN)
-        .with_target("sqlparser", Level::WARN)
-        .with_target("h2", Level::INFO)
-        .with_default(
-            directives
-                .parse::<filter::LevelFilter>()
-                .expect("error parsing level string"),
-        );
+    let filter = build_log_filter(&directives).expect("error parsing level string");
+    let (filter, filter_handle) = reload::Layer::new(filter);
+    // Only the first initialization takes effect, like the global subscriber.
+    if LOG_FILTER_HANDLE.set(filter_handle).is_ok() {
+        *LOG_DIRECTIVES.lock().unwrap() = directives;
+    }
 
     let subscriber = Registry::default()
         .with(filter)
         .with(JsonStorageLayer)
         .with(stdout_logging_layer)
+        .with(stdout_json_logging_layer)
         .with(file_logging_layer)
         .with(err_file_logging_layer.with_filter(filter::LevelFilter::ERROR));
 
@@ -156,3 +269,56 @@ pub fn init_global_logging(app_name: &str, opts: &LoggingOptions) -> Vec<WorkerG
 
     guards
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_log_filter() {
+        let filter = build_log_filter("info").unwrap();
+        assert_eq!(Some(filter::LevelFilter::INFO), filter.default_level());
+        assert!(filter.would_enable("datanode", &Level::INFO));
+        assert!(!filter.would_enable("datanode", &Level::DEBUG));
+        assert!(!filter.would_enable("hyper", &Level::INFO));
+
+        let filter = build_log_filter("warn,mito=debug,storage::flush=trace,hyper=debug").unwrap();
+        assert_eq!(Some(filter::LevelFilter::WARN), filter.default_level());
+        assert!(!filter.would_enable("datanode", &Level::INFO));
+        assert!(filter.would_enable("mito::table", &Level::DEBUG));
+        assert!(!filter.would_enable("mito::table", &Level::TRACE));
+        assert!(filter.would_enable("storage::flush", &Level::TRACE));
+        assert!(!filter.would_enable("storage::region", &Level::INFO));
+        assert!(filter.would_enable("hyper", &Level::DEBUG));
+
+        // targets without default level
+        let filter = build_log_filter("mito=debug").unwrap();
+        assert_eq!(Some(filter::LevelFilter::INFO), filter.default_level());
+
+        assert!(build_log_filter("mito=loud").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_trace_id() {
+        assert_eq!(None, trace_id());
+        let id = gen_trace_id();
+        let traced = with_trace_id(id, async { trace_id() }).await;
+        assert_eq!(Some(id), traced);
+        assert_eq!(None, trace_id());
+
+        assert_eq!("00000000000000ff", format_trace_id(255));
+        assert_eq!(Some(id), parse_trace_id(&format_trace_id(id)));
+        assert_eq!(None, parse_trace_id("not-a-trace-id"));
+    }
+
+    #[test]
+    fn test_set_log_level() {
+        init_default_ut_logging();
+
+        assert!(set_log_level("info,mito=loud").is_err());
+        let previous = log_level();
+        set_log_level("debug,mito=trace").unwrap();
+        assert_eq!("debug,mito=trace", log_level());
+        set_log_level(&previous).unwrap();
+    }
+}
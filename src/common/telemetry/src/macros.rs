@@ -188,6 +188,21 @@ macro_rules! trace {
     };
 }
 
+/// Logs only one of every `every` events of the call site, for noisy paths like
+/// per-row logs. The number of events seen so far is logged as field `sampled`.
+#[macro_export]
+macro_rules! log_sampled {
+    // log_sampled!(every: 100, Level::TRACE, "a {} event", "log")
+    (every: $every:expr, $lvl:expr, $($arg:tt)+) => {{
+        static SAMPLER: $crate::logging::LogSampler = $crate::logging::LogSampler::new($every);
+        if $crate::tracing::enabled!($lvl) {
+            if let Some(seen) = SAMPLER.sample() {
+                $crate::log!($lvl, sampled = seen, $($arg)+)
+            }
+        }
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use common_error::mock::MockError;
@@ -235,6 +250,22 @@ mod tests {
         all_log_macros!("foo: {}", 3);
     }
 
+    #[test]
+    fn test_log_sampled() {
+        let table = "foo";
+        for i in 0..10 {
+            log_sampled!(every: 3, Level::TRACE, table, "row {i}");
+            log_sampled!(every: 1, Level::DEBUG, "row {}", i);
+        }
+
+        let sampler = crate::logging::LogSampler::new(3);
+        let sampled = (0..7).filter_map(|_| sampler.sample()).collect::<Vec<_>>();
+        assert_eq!(vec![1, 4, 7], sampled);
+        let sampler = crate::logging::LogSampler::new(0);
+        assert_eq!(Some(1), sampler.sample());
+        assert_eq!(Some(2), sampler.sample());
+    }
+
     #[test]
     fn test_log_ref_scope_args() {
         let bar = 35;
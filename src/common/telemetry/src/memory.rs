@@ -0,0 +1,137 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory usage of subsystems.
+//!
+//! The allocator can't tell which subsystem an allocation belongs to, so subsystems
+//! report the memory they hold by themselves through [MemoryUsage].
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Subsystems that report their memory usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Subsystem {
+    Memtables,
+    Caches,
+    QueryExecution,
+}
+
+static SUBSYSTEM_USAGES: [AtomicI64; 3] = [AtomicI64::new(0), AtomicI64::new(0), AtomicI64::new(0)];
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 3] = [
+        Subsystem::Memtables,
+        Subsystem::Caches,
+        Subsystem::QueryExecution,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Subsystem::Memtables => "memtables",
+            Subsystem::Caches => "caches",
+            Subsystem::QueryExecution => "query_execution",
+        }
+    }
+
+    fn usage_counter(&self) -> &'static AtomicI64 {
+        &SUBSYSTEM_USAGES[*self as usize]
+    }
+
+    /// Returns the bytes currently held by this subsystem.
+    pub fn bytes(&self) -> i64 {
+        self.usage_counter().load(Ordering::Relaxed)
+    }
+}
+
+/// Returns the bytes held by each subsystem.
+pub fn subsystem_usages() -> Vec<(Subsystem, i64)> {
+    Subsystem::ALL
+        .iter()
+        .map(|subsystem| (*subsystem, subsystem.bytes()))
+        .collect()
+}
+
+/// Memory held by a component of a subsystem. The memory is released from the
+/// subsystem when the [MemoryUsage] is dropped.
+#[derive(Debug)]
+pub struct MemoryUsage {
+    subsystem: Subsystem,
+    bytes: AtomicI64,
+}
+
+impl MemoryUsage {
+    pub fn new(subsystem: Subsystem) -> MemoryUsage {
+        MemoryUsage {
+            subsystem,
+            bytes: AtomicI64::new(0),
+        }
+    }
+
+    pub fn add(&self, bytes: usize) {
+        let bytes = bytes as i64;
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.subsystem
+            .usage_counter()
+            .fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn sub(&self, bytes: usize) {
+        let bytes = bytes as i64;
+        self.bytes.fetch_sub(bytes, Ordering::Relaxed);
+        self.subsystem
+            .usage_counter()
+            .fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    /// Returns the bytes held by this component.
+    pub fn bytes(&self) -> i64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for MemoryUsage {
+    fn drop(&mut self) {
+        self.subsystem
+            .usage_counter()
+            .fetch_sub(self.bytes(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_usage() {
+        // Other tests may report to the same subsystem concurrently, so only check
+        // the usage of the component here.
+        let usage = MemoryUsage::new(Subsystem::QueryExecution);
+        usage.add(100);
+        usage.add(50);
+        usage.sub(30);
+        assert_eq!(120, usage.bytes());
+        drop(usage);
+
+        let usages = subsystem_usages();
+        assert_eq!(
+            vec![
+                Subsystem::Memtables,
+                Subsystem::Caches,
+                Subsystem::QueryExecution
+            ],
+            usages.iter().map(|(s, _)| *s).collect::<Vec<_>>()
+        );
+        assert_eq!("query_execution", Subsystem::QueryExecution.name());
+    }
+}
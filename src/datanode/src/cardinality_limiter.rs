@@ -0,0 +1,339 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+use common_catalog::format_full_table_name;
+use common_telemetry::warn;
+use datatypes::vectors::{BooleanVector, VectorRef};
+use snafu::ResultExt;
+use table::metadata::TableId;
+use table::requests::InsertRequest;
+use table::sketch::{hash_value, HyperLogLog};
+use table::TableRef;
+
+use crate::datanode::{CardinalityLimitConfig, OverflowAction};
+use crate::error::{Result, TableCardinalityExceededSnafu, VectorComputationSnafu};
+
+/// Guards the tables against the explosion of series (distinct primary keys), which bloats
+/// the memtables and SSTs of the storage engine.
+///
+/// The number of series written to each table since the datanode started is tracked
+/// approximately by a HyperLogLog sketch. Once a table reaches the limit, the rows of new
+/// series are either rejected or sampled, while the rows of existing series are still
+/// accepted. A row is regarded as a new series if it would change the sketch, so a few new
+/// series may slip through.
+///
+/// The sketches only live in memory, and at most `max_tracked_tables` of them are kept. The
+/// sketch of the least recently written table is evicted to track a new table, so the
+/// counting of an evicted table, like that of all tables after a restart, starts over.
+pub struct CardinalityLimiter {
+    max_series_per_table: usize,
+    max_tracked_tables: usize,
+    overflow_action: OverflowAction,
+    /// Hashes lower than this are accepted when the new series are sampled.
+    sample_threshold: u64,
+    sketches: RwLock<Sketches>,
+}
+
+#[derive(Default)]
+struct Sketches {
+    tables: HashMap<TableId, TrackedSketch>,
+    /// Incremented on each write, to order the tables by their last write.
+    tick: u64,
+}
+
+struct TrackedSketch {
+    sketch: HyperLogLog,
+    last_write: u64,
+}
+
+impl Sketches {
+    /// Returns the sketch of the table to update, evicting the least recently written table
+    /// if there are too many tables tracked.
+    fn get_or_track(&mut self, table_id: TableId, max_tables: usize) -> &mut TrackedSketch {
+        self.tick += 1;
+        if !self.tables.contains_key(&table_id) && self.tables.len() >= max_tables.max(1) {
+            let evicted = self
+                .tables
+                .iter()
+                .min_by_key(|(_, tracked)| tracked.last_write)
+                .map(|(table_id, _)| *table_id);
+            if let Some(evicted) = evicted {
+                let _ = self.tables.remove(&evicted);
+            }
+        }
+        let tracked = self
+            .tables
+            .entry(table_id)
+            .or_insert_with(|| TrackedSketch {
+                sketch: HyperLogLog::new(),
+                last_write: 0,
+            });
+        tracked.last_write = self.tick;
+        tracked
+    }
+}
+
+impl CardinalityLimiter {
+    pub fn new(config: &CardinalityLimitConfig) -> Self {
+        let sample_ratio = config.sample_ratio.clamp(0.0, 1.0);
+        Self {
+            max_series_per_table: config.max_series_per_table,
+            max_tracked_tables: config.max_tracked_tables,
+            overflow_action: config.overflow_action,
+            sample_threshold: (u64::MAX as f64 * sample_ratio) as u64,
+            sketches: RwLock::new(Sketches::default()),
+        }
+    }
+
+    /// Whether the series of the tables are tracked.
+    pub fn is_enabled(&self) -> bool {
+        self.max_series_per_table > 0
+    }
+
+    /// Returns the estimated number of series written to the table, or `None` if the table
+    /// is not tracked.
+    pub fn estimate(&self, table_id: TableId) -> Option<u64> {
+        self.sketches
+            .read()
+            .unwrap()
+            .tables
+            .get(&table_id)
+            .map(|tracked| tracked.sketch.estimate().round() as u64)
+    }
+
+    /// Checks the series of the insert request against the limit of the table. Returns the
+    /// request to write, which may have the rows of new series removed.
+    pub fn check(&self, table: &TableRef, request: InsertRequest) -> Result<InsertRequest> {
+        if !self.is_enabled() {
+            return Ok(request);
+        }
+        let table_info = table.table_info();
+        let primary_keys = table_info
+            .meta
+            .row_key_column_names()
+            .cloned()
+            .collect::<Vec<_>>();
+        if primary_keys.is_empty() {
+            // All rows belong to the same series.
+            return Ok(request);
+        }
+        self.check_series(table_info.ident.table_id, &primary_keys, request)
+    }
+
+    fn check_series(
+        &self,
+        table_id: TableId,
+        primary_keys: &[String],
+        request: InsertRequest,
+    ) -> Result<InsertRequest> {
+        let Some(num_rows) = request.columns_values.values().next().map(|v| v.len()) else {
+            return Ok(request);
+        };
+        let key_columns = primary_keys
+            .iter()
+            .map(|name| request.columns_values.get(name))
+            .collect::<Vec<_>>();
+        let hashes = (0..num_rows)
+            .map(|row| hash_row(&key_columns, row))
+            .collect::<Vec<_>>();
+
+        let mut sketches = self.sketches.write().unwrap();
+        let sketch = &mut sketches
+            .get_or_track(table_id, self.max_tracked_tables)
+            .sketch;
+        // Updates a copy of the sketch, so it's left untouched if the request is rejected.
+        let mut updated = sketch.clone();
+        let mut accepted = Vec::with_capacity(num_rows);
+        for hash in hashes {
+            let exceeded = updated.estimate() >= self.max_series_per_table as f64;
+            if exceeded && updated.is_new(hash) {
+                match self.overflow_action {
+                    OverflowAction::Reject => {
+                        return TableCardinalityExceededSnafu {
+                            table_name: format_full_table_name(
+                                &request.catalog_name,
+                                &request.schema_name,
+                                &request.table_name,
+                            ),
+                            limit: self.max_series_per_table,
+                        }
+                        .fail();
+                    }
+                    OverflowAction::Sample if hash >= self.sample_threshold => {
+                        accepted.push(false);
+                        continue;
+                    }
+                    OverflowAction::Sample => {}
+                }
+            }
+            updated.insert(hash);
+            accepted.push(true);
+        }
+        *sketch = updated;
+        drop(sketches);
+
+        let dropped = accepted.iter().filter(|accepted| !**accepted).count();
+        if dropped == 0 {
+            return Ok(request);
+        }
+        warn!(
+            "Table {} has reached the limit of {} series, dropped {} rows of new series",
+            format_full_table_name(
+                &request.catalog_name,
+                &request.schema_name,
+                &request.table_name
+            ),
+            self.max_series_per_table,
+            dropped
+        );
+        filter_rows(request, &BooleanVector::from(accepted))
+    }
+}
+
+fn hash_row(key_columns: &[Option<&VectorRef>], row: usize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for column in key_columns {
+        match column {
+            Some(vector) => hash_value(vector.get_ref(row), &mut hasher),
+            // Absent columns are filled with their default values, which are the same for
+            // all rows.
+            None => 0u8.hash(&mut hasher),
+        }
+    }
+    hasher.finish()
+}
+
+fn filter_rows(mut request: InsertRequest, filter: &BooleanVector) -> Result<InsertRequest> {
+    for vector in request.columns_values.values_mut() {
+        *vector = vector.filter(filter).context(VectorComputationSnafu)?;
+    }
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::vectors::{Int64Vector, StringVector, TimestampMillisecondVector};
+
+    use super::*;
+
+    fn new_limiter(
+        max_series_per_table: usize,
+        overflow_action: OverflowAction,
+    ) -> CardinalityLimiter {
+        CardinalityLimiter::new(&CardinalityLimitConfig {
+            max_series_per_table,
+            overflow_action,
+            sample_ratio: 0.0,
+            ..Default::default()
+        })
+    }
+
+    fn new_request(hosts: &[&str]) -> InsertRequest {
+        let hosts: VectorRef = Arc::new(StringVector::from(hosts.to_vec()));
+        let values: VectorRef = Arc::new(Int64Vector::from_vec(vec![1; hosts.len()]));
+        let ts: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1; hosts.len()]));
+        InsertRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+            columns_values: HashMap::from([
+                ("host".to_string(), hosts),
+                ("value".to_string(), values),
+                ("ts".to_string(), ts),
+            ]),
+            region_number: 0,
+        }
+    }
+
+    fn hosts(range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|i| format!("host{i}")).collect()
+    }
+
+    fn check(limiter: &CardinalityLimiter, hosts: &[String]) -> Result<InsertRequest> {
+        check_table(limiter, 1, hosts)
+    }
+
+    fn check_table(
+        limiter: &CardinalityLimiter,
+        table_id: TableId,
+        hosts: &[String],
+    ) -> Result<InsertRequest> {
+        let hosts = hosts.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        limiter.check_series(table_id, &["host".to_string()], new_request(&hosts))
+    }
+
+    #[test]
+    fn test_reject_new_series() {
+        let limiter = new_limiter(100, OverflowAction::Reject);
+        assert!(limiter.is_enabled());
+        assert!(check(&limiter, &hosts(0..90)).is_ok());
+        let estimate = limiter.estimate(1).unwrap();
+        assert!((85..=95).contains(&estimate), "estimate: {estimate}");
+
+        let err = check(&limiter, &hosts(90..200)).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::Error::TableCardinalityExceeded { .. }
+        ));
+        // The rejected request doesn't change the sketch.
+        assert_eq!(estimate, limiter.estimate(1).unwrap());
+
+        // Existing series are still accepted.
+        let request = check(&limiter, &hosts(0..90)).unwrap();
+        assert_eq!(90, request.columns_values["host"].len());
+        assert_eq!(None, limiter.estimate(2));
+    }
+
+    #[test]
+    fn test_sample_new_series() {
+        let limiter = new_limiter(100, OverflowAction::Sample);
+        let request = check(&limiter, &hosts(0..1000)).unwrap();
+        let num_rows = request.columns_values["host"].len();
+        assert!((90..=110).contains(&num_rows), "num_rows: {num_rows}");
+        for vector in request.columns_values.values() {
+            assert_eq!(num_rows, vector.len());
+        }
+
+        // The sampled series are accepted afterwards.
+        let sampled = (0..num_rows)
+            .map(|i| request.columns_values["host"].get(i).to_string())
+            .collect::<Vec<_>>();
+        let request = check(&limiter, &sampled).unwrap();
+        assert_eq!(num_rows, request.columns_values["host"].len());
+    }
+
+    #[test]
+    fn test_evict_least_recently_written_table() {
+        let limiter = CardinalityLimiter::new(&CardinalityLimitConfig {
+            max_series_per_table: 100,
+            max_tracked_tables: 2,
+            ..Default::default()
+        });
+        assert!(check_table(&limiter, 1, &hosts(0..10)).is_ok());
+        assert!(check_table(&limiter, 2, &hosts(0..20)).is_ok());
+        assert!(check_table(&limiter, 1, &hosts(0..10)).is_ok());
+
+        // Table 2 is the least recently written one.
+        assert!(check_table(&limiter, 3, &hosts(0..30)).is_ok());
+        assert_eq!(None, limiter.estimate(2));
+        assert!(limiter.estimate(1).is_some());
+        assert!(limiter.estimate(3).is_some());
+    }
+}
@@ -19,6 +19,7 @@ use common_base::readable_size::ReadableSize;
 use common_telemetry::info;
 use common_telemetry::logging::LoggingOptions;
 use meta_client::MetaClientOptions;
+use object_store::retry::RetryPolicy;
 use secrecy::SecretString;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
@@ -49,6 +50,12 @@ pub struct StorageConfig {
     pub store: ObjectStoreConfig,
     pub compaction: CompactionConfig,
     pub manifest: RegionManifestConfig,
+    pub checksum: ChecksumConfig,
+    pub retry: ObjectStoreRetryConfig,
+    pub tiering: TieringConfig,
+    pub background: BackgroundConfig,
+    pub block_cache: BlockCacheConfig,
+    pub scan: ScanConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Default, Deserialize)]
@@ -166,6 +173,16 @@ pub struct RegionManifestConfig {
     pub gc_duration: Option<Duration>,
     /// Whether to try creating a manifest checkpoint on region opening
     pub checkpoint_on_startup: bool,
+    /// Max number of checkpoints of all regions running concurrently. Setting it or the
+    /// `checkpoint_rate_limit` schedules checkpoints in background instead of doing them
+    /// on manifest updates.
+    pub checkpoint_concurrency: Option<usize>,
+    /// Max bytes of checkpoints written per second, unlimited if not set.
+    pub checkpoint_rate_limit: Option<ReadableSize>,
+    /// Max random delay of a scheduled checkpoint, which spreads out the checkpoints
+    /// triggered at the same time, e.g. on startup.
+    #[serde(with = "humantime_serde")]
+    pub checkpoint_jitter: Duration,
 }
 
 impl Default for RegionManifestConfig {
@@ -174,6 +191,9 @@ impl Default for RegionManifestConfig {
             checkpoint_margin: Some(10u16),
             gc_duration: Some(Duration::from_secs(30)),
             checkpoint_on_startup: false,
+            checkpoint_concurrency: None,
+            checkpoint_rate_limit: None,
+            checkpoint_jitter: Duration::from_secs(5),
         }
     }
 }
@@ -190,6 +210,9 @@ pub struct CompactionConfig {
     pub max_purge_tasks: usize,
     /// Buffer threshold while writing SST files
     pub sst_write_buffer_size: ReadableSize,
+    /// Whether to write a copy of compaction outputs in standard parquet format
+    /// under the `export/` directory of regions, for external readers like Spark.
+    pub export_sst: bool,
 }
 
 impl Default for CompactionConfig {
@@ -199,6 +222,138 @@ impl Default for CompactionConfig {
             max_files_in_level0: 8,
             max_purge_tasks: 32,
             sst_write_buffer_size: ReadableSize::mb(8),
+            export_sst: false,
+        }
+    }
+}
+
+/// Options for SST checksums
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Default)]
+#[serde(default)]
+pub struct ChecksumConfig {
+    /// Whether to verify checksums of SST files on read.
+    pub verify_on_read: bool,
+    /// Interval to verify checksums of all SST files in background, disabled if not set.
+    #[serde(with = "humantime_serde")]
+    pub scrub_interval: Option<Duration>,
+}
+
+/// Options for retrying object store requests
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct ObjectStoreRetryConfig {
+    /// Max retry times of an idempotent request, 0 to disable retry.
+    pub max_retries: usize,
+    /// Delay before the first retry, doubled after each retry.
+    #[serde(with = "humantime_serde")]
+    pub min_delay: Duration,
+    /// Max delay between two retries.
+    #[serde(with = "humantime_serde")]
+    pub max_delay: Duration,
+    /// Whether to randomize the delay.
+    pub jitter: bool,
+}
+
+impl Default for ObjectStoreRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            min_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            jitter: true,
+        }
+    }
+}
+
+/// Options for moving old SST files to a cold object store
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TieringConfig {
+    /// Object store for old SST files, all files stay in the main object store if not set.
+    pub cold_store: Option<ObjectStoreConfig>,
+    /// SST files whose data are all older than this duration are moved to the cold
+    /// store during compaction.
+    #[serde(with = "humantime_serde")]
+    pub cold_after: Option<Duration>,
+}
+
+/// Options of the thread pools running flush and compaction jobs, which are separated
+/// from the runtimes serving queries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackgroundConfig {
+    /// Number of threads running flush jobs.
+    pub flush_workers: usize,
+    /// Number of threads running compaction tasks.
+    pub compaction_workers: usize,
+    /// Max bytes written per second by flush and compaction jobs, unlimited if not set.
+    pub io_rate_limit: Option<ReadableSize>,
+    /// Interval to flush tables by their `flush_rows` and `flush_interval` options even
+    /// if they receive no writes.
+    #[serde(with = "humantime_serde")]
+    pub flush_check_interval: Duration,
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        Self {
+            flush_workers: 2,
+            compaction_workers: 2,
+            io_rate_limit: None,
+            flush_check_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Options of the in-memory caches of data read from SST files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockCacheConfig {
+    /// Max size of cached pages, the cache is disabled if it's zero.
+    pub size: ReadableSize,
+    /// Max number of SST files whose parsed footers (schema and min/max statistics) are
+    /// cached, the cache is disabled if it's zero.
+    pub sst_meta_capacity: u64,
+}
+
+impl Default for BlockCacheConfig {
+    fn default() -> Self {
+        Self {
+            size: ReadableSize::mb(256),
+            sst_meta_capacity: 10000,
+        }
+    }
+}
+
+/// Options of scans over SST files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// Number of batches of a SST file, and SST files of a scan, read ahead to hide the
+    /// latency of the object store, 0 to disable reading ahead.
+    pub prefetch_depth: usize,
+    /// Max number of SST files read ahead at the same time across all scans, files beyond
+    /// the limit are read without reading ahead. 0 for unlimited.
+    pub max_prefetch_readers: usize,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            prefetch_depth: 2,
+            max_prefetch_readers: 256,
+        }
+    }
+}
+
+impl From<&ObjectStoreRetryConfig> for RetryPolicy {
+    fn from(value: &ObjectStoreRetryConfig) -> Self {
+        Self {
+            max_retries: value.max_retries,
+            min_delay: value.min_delay,
+            max_delay: value.max_delay,
+            jitter: value.jitter,
+            ..Default::default()
         }
     }
 }
@@ -217,9 +372,26 @@ impl From<&DatanodeOptions> for StorageEngineConfig {
             manifest_checkpoint_on_startup: value.storage.manifest.checkpoint_on_startup,
             manifest_checkpoint_margin: value.storage.manifest.checkpoint_margin,
             manifest_gc_duration: value.storage.manifest.gc_duration,
+            manifest_checkpoint_concurrency: value.storage.manifest.checkpoint_concurrency,
+            manifest_checkpoint_rate_limit: value.storage.manifest.checkpoint_rate_limit,
+            manifest_checkpoint_jitter: value.storage.manifest.checkpoint_jitter,
             max_files_in_l0: value.storage.compaction.max_files_in_level0,
             max_purge_tasks: value.storage.compaction.max_purge_tasks,
             sst_write_buffer_size: value.storage.compaction.sst_write_buffer_size,
+            verify_sst_checksum: value.storage.checksum.verify_on_read,
+            sst_scrub_interval: value.storage.checksum.scrub_interval,
+            cold_after: value.storage.tiering.cold_after,
+            export_sst: value.storage.compaction.export_sst,
+            flush_workers: Some(value.storage.background.flush_workers),
+            background_io_rate_limit: value.storage.background.io_rate_limit,
+            block_cache_size: Some(value.storage.block_cache.size)
+                .filter(|size| size.as_bytes() > 0),
+            sst_meta_cache_capacity: Some(value.storage.block_cache.sst_meta_capacity)
+                .filter(|capacity| *capacity > 0),
+            scan_prefetch_depth: value.storage.scan.prefetch_depth,
+            scan_max_prefetch_readers: Some(value.storage.scan.max_prefetch_readers)
+                .filter(|max_readers| *max_readers > 0),
+            flush_check_interval: Some(value.storage.background.flush_check_interval),
         }
     }
 }
@@ -243,6 +415,183 @@ impl Default for ProcedureConfig {
     }
 }
 
+/// Options for collecting table statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatisticsConfig {
+    /// Whether to collect statistics of tables periodically. Off by default, as each
+    /// collection scans all data in the tables.
+    pub enable: bool,
+    /// Interval of collecting statistics.
+    #[serde(with = "humantime_serde")]
+    pub collect_interval: Duration,
+}
+
+impl Default for StatisticsConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            collect_interval: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Options for closing the tables not read or written for a while, so tables rarely
+/// accessed don't hold memory.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct IdleTableConfig {
+    /// Whether to close idle tables.
+    pub enable: bool,
+    /// A table is closed after not being read or written for this long.
+    #[serde(with = "humantime_serde")]
+    pub idle_timeout: Duration,
+    /// Interval of checking idle tables.
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+}
+
+impl Default for IdleTableConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            idle_timeout: Duration::from_secs(30 * 60),
+            check_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Options for limiting the scans running concurrently in the datanode.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ScanLimitConfig {
+    /// Max number of scans running concurrently, `0` means unlimited.
+    pub max_concurrent_scans: usize,
+    /// Max number of scans waiting to run, more scans are rejected.
+    pub max_queued_scans: usize,
+    /// Max time a scan waits to run before it's rejected.
+    #[serde(with = "humantime_serde")]
+    pub queue_timeout: Duration,
+}
+
+impl Default for ScanLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_scans: 0,
+            max_queued_scans: 128,
+            queue_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Options for limiting the number of distinct primary keys (series) written to each table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CardinalityLimitConfig {
+    /// Max number of series in each table, `0` means unlimited.
+    pub max_series_per_table: usize,
+    /// Max number of tables whose series are tracked, each taking a 4KiB sketch. The least
+    /// recently written table stops being tracked once there are more tables.
+    pub max_tracked_tables: usize,
+    /// What to do with the rows of new series once a table reaches the limit.
+    pub overflow_action: OverflowAction,
+    /// Ratio of the new series still accepted once a table reaches the limit, only used by
+    /// [OverflowAction::Sample].
+    pub sample_ratio: f64,
+}
+
+impl Default for CardinalityLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_series_per_table: 0,
+            max_tracked_tables: 10000,
+            overflow_action: OverflowAction::Reject,
+            sample_ratio: 0.01,
+        }
+    }
+}
+
+/// Options for protecting the datanode from running out of disk space.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DiskWatermarkConfig {
+    /// Whether to watch the free space of the disks storing the WAL and data.
+    pub enable: bool,
+    /// A disk is low once its free space is less than this ratio of its total space.
+    pub low_free_ratio: f64,
+    /// A disk is low once its free space is less than this size.
+    pub low_free_space: ReadableSize,
+    /// Whether to reject new writes while any disk is low, otherwise only warns.
+    pub reject_writes: bool,
+    /// Interval of checking the free space.
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+}
+
+impl Default for DiskWatermarkConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            low_free_ratio: 0.05,
+            low_free_space: ReadableSize::gb(1),
+            // Only warns by default, so small disks keep accepting writes after upgrading.
+            reject_writes: false,
+            check_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Options for reconciling the tables opened by the datanode with the table routes in
+/// distributed mode.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RegionReconcileConfig {
+    /// Whether to open the tables assigned to the datanode but not opened, and close the
+    /// opened tables not assigned to it.
+    pub enable: bool,
+    /// Interval of reconciling, a table is opened or closed after diverging from its route
+    /// for two intervals in a row.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+}
+
+impl Default for RegionReconcileConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowAction {
+    /// Rejects the whole write.
+    Reject,
+    /// Drops the rows of new series except a sampled fraction of them.
+    Sample,
+}
+
+/// Options for compressing the query results sent to the frontends over gRPC.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RpcCompressionConfig {
+    /// Whether to compress the record batches with zstd.
+    pub enable: bool,
+    /// Only the record batches no smaller than this are compressed.
+    pub threshold: ReadableSize,
+}
+
+impl Default for RpcCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            threshold: ReadableSize::mb(1),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct DatanodeOptions {
@@ -252,6 +601,7 @@ pub struct DatanodeOptions {
     pub rpc_addr: String,
     pub rpc_hostname: Option<String>,
     pub rpc_runtime_size: usize,
+    pub rpc_compression: RpcCompressionConfig,
     pub mysql_addr: String,
     pub mysql_runtime_size: usize,
     pub http_opts: HttpOptions,
@@ -259,6 +609,12 @@ pub struct DatanodeOptions {
     pub wal: WalConfig,
     pub storage: StorageConfig,
     pub procedure: ProcedureConfig,
+    pub statistics: StatisticsConfig,
+    pub idle_table: IdleTableConfig,
+    pub scan_limit: ScanLimitConfig,
+    pub cardinality_limit: CardinalityLimitConfig,
+    pub disk_watermark: DiskWatermarkConfig,
+    pub region_reconcile: RegionReconcileConfig,
     pub logging: LoggingOptions,
 }
 
@@ -271,6 +627,7 @@ impl Default for DatanodeOptions {
             rpc_addr: "127.0.0.1:3001".to_string(),
             rpc_hostname: None,
             rpc_runtime_size: 8,
+            rpc_compression: RpcCompressionConfig::default(),
             mysql_addr: "127.0.0.1:4406".to_string(),
             mysql_runtime_size: 2,
             http_opts: HttpOptions::default(),
@@ -278,6 +635,12 @@ impl Default for DatanodeOptions {
             wal: WalConfig::default(),
             storage: StorageConfig::default(),
             procedure: ProcedureConfig::default(),
+            statistics: StatisticsConfig::default(),
+            idle_table: IdleTableConfig::default(),
+            scan_limit: ScanLimitConfig::default(),
+            cardinality_limit: CardinalityLimitConfig::default(),
+            disk_watermark: DiskWatermarkConfig::default(),
+            region_reconcile: RegionReconcileConfig::default(),
             logging: LoggingOptions::default(),
         }
     }
@@ -0,0 +1,269 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use catalog::CatalogManagerRef;
+use common_catalog::consts::{INFORMATION_SCHEMA_NAME, SYSTEM_CATALOG_NAME};
+use common_catalog::format_full_table_name;
+use common_error::prelude::{ErrorExt, StatusCode};
+use common_telemetry::{info, warn};
+use servers::health_checker::HealthChecker;
+use table::metadata::TableType;
+
+use crate::datanode::{DatanodeOptions, DiskWatermarkConfig, ObjectStoreConfig};
+use crate::error::{DiskSpaceLowSnafu, Result};
+
+/// Watches the free space of the local disks storing the WAL and data of the datanode, so
+/// the datanode stops growing before the disks are exhausted.
+///
+/// Once the free space of any disk drops below the low watermark, new writes are rejected
+/// (if configured) and all tables are compacted to purge the expired and obsolete SSTs.
+/// Writes are accepted again once the free space is back above the watermark. The state is
+/// reported to the metasrv along with the heartbeats and served by the health API.
+pub struct DiskWatermark {
+    config: DiskWatermarkConfig,
+    dirs: Vec<String>,
+    /// The directory found below the watermark in the last check, `None` if all disks have
+    /// enough free space.
+    low_dir: RwLock<Option<String>>,
+    catalog_manager: CatalogManagerRef,
+    running: AtomicBool,
+}
+
+pub type DiskWatermarkRef = Arc<DiskWatermark>;
+
+impl DiskWatermark {
+    pub fn new(opts: &DatanodeOptions, catalog_manager: CatalogManagerRef) -> Self {
+        Self {
+            config: opts.disk_watermark.clone(),
+            dirs: local_dirs(opts),
+            low_dir: RwLock::new(None),
+            catalog_manager,
+            running: AtomicBool::new(false),
+        }
+    }
+
+    /// Start checking the free space in background.
+    pub fn start(self: &Arc<Self>) {
+        if !self.config.enable {
+            return;
+        }
+        if self
+            .running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Disk watermark task started multiple times");
+            return;
+        }
+
+        let watermark = self.clone();
+        info!(
+            "Start checking disk space, dirs: {:?}, interval: {:?}",
+            watermark.dirs, watermark.config.check_interval
+        );
+        common_runtime::spawn_bg(async move {
+            while watermark.running.load(Ordering::Acquire) {
+                watermark.check().await;
+                tokio::time::sleep(watermark.config.check_interval).await;
+            }
+            info!("Disk watermark task exit");
+        });
+    }
+
+    pub fn close(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Whether the free space of any disk is below the low watermark.
+    pub fn is_low(&self) -> bool {
+        self.low_dir.read().unwrap().is_some()
+    }
+
+    /// Returns an error if new writes should be rejected as the disk space is low.
+    pub fn check_writable(&self) -> Result<()> {
+        if !self.config.reject_writes {
+            return Ok(());
+        }
+        match &*self.low_dir.read().unwrap() {
+            Some(dir) => DiskSpaceLowSnafu { dir }.fail(),
+            None => Ok(()),
+        }
+    }
+
+    async fn check(&self) {
+        let low_dir = self.dirs.iter().find(|dir| self.is_dir_low(dir)).cloned();
+        let was_low = self.set_low_dir(low_dir.clone());
+        match (was_low, low_dir) {
+            (false, Some(dir)) => {
+                warn!(
+                    dir = %dir,
+                    reject_writes = self.config.reject_writes,
+                    "Disk space is below the low watermark, compacting all tables to free space"
+                );
+                compact_tables(&self.catalog_manager).await;
+            }
+            (true, None) => info!("Disk space is back above the low watermark"),
+            _ => {}
+        }
+    }
+
+    /// Sets the directory below the watermark, returns whether the disk space was low.
+    fn set_low_dir(&self, low_dir: Option<String>) -> bool {
+        std::mem::replace(&mut *self.low_dir.write().unwrap(), low_dir).is_some()
+    }
+
+    fn is_dir_low(&self, dir: &str) -> bool {
+        match fs2::available_space(dir)
+            .and_then(|available| fs2::total_space(dir).map(|total| (available, total)))
+        {
+            Ok((available, total)) => is_below_watermark(&self.config, available, total),
+            Err(e) => {
+                warn!("Failed to get the disk space of {}, error: {}", dir, e);
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HealthChecker for DiskWatermark {
+    async fn warnings(&self) -> Vec<String> {
+        self.low_dir
+            .read()
+            .unwrap()
+            .iter()
+            .map(|dir| format!("Disk space of {dir} is below the low watermark"))
+            .collect()
+    }
+}
+
+fn is_below_watermark(config: &DiskWatermarkConfig, available: u64, total: u64) -> bool {
+    available < config.low_free_space.0 || (available as f64) < total as f64 * config.low_free_ratio
+}
+
+/// Directories on the local disks written by the datanode.
+fn local_dirs(opts: &DatanodeOptions) -> Vec<String> {
+    let mut dirs = vec![opts.wal.dir.clone()];
+    let data_dir = match &opts.storage.store {
+        ObjectStoreConfig::File(file) => Some(&file.data_dir),
+        ObjectStoreConfig::S3(s3) => s3.cache_path.as_ref(),
+        ObjectStoreConfig::Oss(oss) => oss.cache_path.as_ref(),
+    };
+    dirs.extend(data_dir.cloned());
+    dirs
+}
+
+/// Requests compactions of all base tables without waiting, which also purges the SSTs
+/// expired by the TTL of the tables.
+async fn compact_tables(catalog_manager: &CatalogManagerRef) {
+    let Ok(catalog_names) = catalog_manager.catalog_names().await else { return };
+    for catalog_name in catalog_names {
+        if catalog_name == SYSTEM_CATALOG_NAME {
+            continue;
+        }
+        let Ok(Some(catalog)) = catalog_manager.catalog(&catalog_name).await else { continue };
+
+        let Ok(schema_names) = catalog.schema_names().await else { continue };
+        for schema_name in schema_names {
+            if schema_name == INFORMATION_SCHEMA_NAME {
+                continue;
+            }
+            let Ok(Some(schema)) = catalog.schema(&schema_name).await else { continue };
+
+            let Ok(table_names) = schema.table_names().await else { continue };
+            for table_name in table_names {
+                let Ok(Some(table)) = schema.table(&table_name).await else { continue };
+                if table.table_type() != TableType::Base {
+                    continue;
+                }
+
+                match table.compact(None, Some(false)).await {
+                    Ok(()) => {}
+                    Err(e) if e.status_code() == StatusCode::Unsupported => {}
+                    Err(e) => warn!(
+                        table = %format_full_table_name(&catalog_name, &schema_name, &table_name),
+                        "Failed to compact table to free disk space, error: {}",
+                        e
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_base::readable_size::ReadableSize;
+    use common_query::Output;
+    use datatypes::prelude::ConcreteDataType;
+    use query::parser::{QueryLanguageParser, QueryStatement};
+    use query::query_engine::SqlStatementExecutor;
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::instance::Instance;
+    use crate::tests::test_util::{self, MockInstance};
+
+    #[test]
+    fn test_is_below_watermark() {
+        let config = DiskWatermarkConfig {
+            low_free_ratio: 0.1,
+            low_free_space: ReadableSize::mb(1),
+            ..Default::default()
+        };
+        let total = ReadableSize::gb(1).0;
+        assert!(!is_below_watermark(&config, total / 2, total));
+        // Below the ratio.
+        assert!(is_below_watermark(&config, total / 20, total));
+        // Below the size.
+        assert!(is_below_watermark(&config, 1024, 2048));
+    }
+
+    async fn insert(instance: &Instance) -> query::error::Result<Output> {
+        let sql = "INSERT INTO demo(host, cpu, memory, ts) VALUES ('host1', 1.0, 1.0, 1000)";
+        let QueryStatement::Sql(stmt) = QueryLanguageParser::parse_sql(sql).unwrap() else {
+            unreachable!()
+        };
+        instance.execute_sql(stmt, QueryContext::arc()).await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reject_writes_on_low_disk() {
+        let instance = MockInstance::new("test_reject_writes_on_low_disk").await;
+        let instance = instance.inner();
+        test_util::create_test_table(instance, ConcreteDataType::timestamp_millisecond_datatype())
+            .await
+            .unwrap();
+
+        let watermark = &instance.disk_watermark;
+        assert!(watermark.warnings().await.is_empty());
+        assert!(!watermark.set_low_dir(Some("/data".to_string())));
+        assert!(watermark.is_low());
+        assert_eq!(
+            vec!["Disk space of /data is below the low watermark".to_string()],
+            watermark.warnings().await
+        );
+        let err = insert(instance).await.unwrap_err();
+        assert_eq!(StatusCode::RuntimeResourcesExhausted, err.status_code());
+
+        // Writes are accepted again once the disk space is freed.
+        assert!(watermark.set_low_dir(None));
+        let output = insert(instance).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+    }
+}
@@ -0,0 +1,322 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An embedded engine to query mito tables from a data directory without starting
+//! any server, catalog or WAL. Useful for offline analysis tools and for inspecting
+//! snapshots of a datanode's data directory.
+//!
+//! The data directory is opened read-only: writes and deletes to the object store
+//! are rejected and only query statements are accepted. Data not flushed to SST
+//! files (still in the WAL) and SST files moved to the cold storage are invisible.
+
+use std::sync::Arc;
+
+use catalog::local::{MemoryCatalogManager, MemoryCatalogProvider};
+use catalog::{CatalogManager, RegisterSchemaRequest, RegisterTableRequest};
+use common_query::Output;
+use common_telemetry::logging::info;
+use log_store::NoopLogStore;
+use mito::config::EngineConfig as TableEngineConfig;
+use mito::engine::MitoEngine;
+use object_store::read_only::ReadOnlyLayer;
+use object_store::services::Fs as FsBuilder;
+use object_store::{util, ObjectStore};
+use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
+use query::{QueryEngineFactory, QueryEngineRef};
+use session::context::QueryContextRef;
+use snafu::prelude::*;
+use sql::statements::statement::Statement;
+use storage::compaction::noop::NoopCompactionScheduler;
+use storage::config::EngineConfig as StorageEngineConfig;
+use storage::EngineImpl;
+use table::engine::{EngineContext, TableEngine};
+use table::metadata::TableId;
+use table::requests::OpenTableRequest;
+use table::TableRef;
+
+use crate::error::{
+    CatalogSnafu, ExecuteSqlSnafu, ExecuteStatementSnafu, InitBackendSnafu, InvalidTableDirSnafu,
+    NotSupportSqlSnafu, OpenTableSnafu, PlanStatementSnafu, Result, TableNotFoundSnafu,
+};
+
+type EmbeddedTableEngine = MitoEngine<EngineImpl<NoopLogStore>>;
+
+/// Engine to run SQL and PromQL against tables opened read-only from a data directory.
+pub struct EmbeddedEngine {
+    catalog_manager: Arc<MemoryCatalogManager>,
+    query_engine: QueryEngineRef,
+    table_engine: EmbeddedTableEngine,
+}
+
+impl EmbeddedEngine {
+    /// Creates an engine over the `data_dir` of a datanode using the file storage.
+    pub fn new(data_dir: &str) -> Result<Self> {
+        let data_dir = util::normalize_dir(data_dir);
+        let mut builder = FsBuilder::default();
+        builder.root(&data_dir);
+        let object_store = ObjectStore::new(builder)
+            .context(InitBackendSnafu)?
+            .finish()
+            .layer(ReadOnlyLayer);
+        info!("Open data directory {} read-only", data_dir);
+
+        let storage_config = StorageEngineConfig {
+            // Never touch the manifest of the opened regions.
+            manifest_checkpoint_on_startup: false,
+            manifest_gc_duration: None,
+            ..Default::default()
+        };
+        let table_engine = MitoEngine::new(
+            TableEngineConfig::default(),
+            EngineImpl::new(
+                storage_config,
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+                Arc::new(NoopCompactionScheduler::default()),
+            ),
+            object_store,
+        );
+
+        let catalog_manager = Arc::new(MemoryCatalogManager::default());
+        let query_engine = QueryEngineFactory::new(catalog_manager.clone()).query_engine();
+
+        Ok(Self {
+            catalog_manager,
+            query_engine,
+            table_engine,
+        })
+    }
+
+    /// Opens the table under `table_dir`, a path like `<catalog>/<schema>/<table_id>/`
+    /// relative to the data directory, and registers it to the catalog under its
+    /// original name.
+    pub async fn open_table(&self, table_dir: &str) -> Result<TableRef> {
+        let (catalog, schema, table_id) = parse_table_dir(table_dir)?;
+
+        let request = OpenTableRequest {
+            catalog_name: catalog.clone(),
+            schema_name: schema.clone(),
+            // Only used to identify the table in the engine, the name stored in the
+            // manifest is recovered on opening.
+            table_name: table_id.to_string(),
+            table_id,
+        };
+        let table = self
+            .table_engine
+            .open_table(&EngineContext::default(), request)
+            .await
+            .context(OpenTableSnafu { table_dir })?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: table_dir,
+            })?;
+        let table_name = table.table_info().name.clone();
+
+        if self
+            .catalog_manager
+            .schema(&catalog, &schema)
+            .await
+            .context(CatalogSnafu)?
+            .is_none()
+        {
+            let _ = self.catalog_manager.register_catalog_if_absent(
+                catalog.clone(),
+                Arc::new(MemoryCatalogProvider::new()),
+            );
+            let _ = self
+                .catalog_manager
+                .register_schema(RegisterSchemaRequest {
+                    catalog: catalog.clone(),
+                    schema: schema.clone(),
+                })
+                .await
+                .context(CatalogSnafu)?;
+        }
+        let _ = self
+            .catalog_manager
+            .register_table(RegisterTableRequest {
+                catalog: catalog.clone(),
+                schema: schema.clone(),
+                table_name: table_name.clone(),
+                table_id,
+                table: table.clone(),
+            })
+            .await
+            .context(CatalogSnafu)?;
+
+        info!(
+            "Opened table {}.{}.{} from {}",
+            catalog, schema, table_name, table_dir
+        );
+
+        Ok(table)
+    }
+
+    /// Executes a query statement in `sql`, other statements are rejected.
+    pub async fn execute_sql(&self, sql: &str, query_ctx: QueryContextRef) -> Result<Output> {
+        let stmt = QueryLanguageParser::parse_sql(sql).context(ExecuteSqlSnafu)?;
+        ensure!(
+            matches!(
+                stmt,
+                QueryStatement::Sql(Statement::Query(_) | Statement::Explain(_))
+            ),
+            NotSupportSqlSnafu {
+                msg: format!("only queries are allowed on an embedded engine: {sql}"),
+            }
+        );
+        self.execute_stmt(stmt, query_ctx).await
+    }
+
+    /// Evaluates `promql` against the opened tables.
+    pub async fn execute_promql(
+        &self,
+        promql: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let stmt = QueryLanguageParser::parse_promql(promql).context(ExecuteSqlSnafu)?;
+        self.execute_stmt(stmt, query_ctx).await
+    }
+
+    async fn execute_stmt(
+        &self,
+        stmt: QueryStatement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(stmt, query_ctx.clone())
+            .await
+            .context(PlanStatementSnafu)?;
+        self.query_engine
+            .execute(plan, query_ctx)
+            .await
+            .context(ExecuteStatementSnafu)
+    }
+}
+
+/// Parses catalog, schema and table id from a table directory generated by
+/// [table::engine::table_dir].
+fn parse_table_dir(table_dir: &str) -> Result<(String, String, TableId)> {
+    let parts = table_dir.trim_matches('/').split('/').collect::<Vec<_>>();
+    let [catalog, schema, table_id] = parts[..] else {
+        return InvalidTableDirSnafu { dir: table_dir }.fail();
+    };
+    let table_id = table_id
+        .parse::<TableId>()
+        .ok()
+        .context(InvalidTableDirSnafu { dir: table_dir })?;
+    ensure!(
+        !catalog.is_empty() && !schema.is_empty(),
+        InvalidTableDirSnafu { dir: table_dir }
+    );
+
+    Ok((catalog.to_string(), schema.to_string(), table_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use common_recordbatch::RecordBatches;
+    use mito::table::test_util::{self, TestEngineComponents};
+    use session::context::QueryContext;
+
+    use super::*;
+
+    /// Returns the files under `dir` recursively with their sizes.
+    fn list_files(dir: &Path) -> Vec<(PathBuf, u64)> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let entry = entry.unwrap();
+            let metadata = entry.metadata().unwrap();
+            if metadata.is_dir() {
+                files.extend(list_files(&entry.path()));
+            } else {
+                files.push((entry.path(), metadata.len()));
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_query_table_read_only() {
+        let TestEngineComponents {
+            table_engine,
+            table_ref,
+            dir,
+            ..
+        } = test_util::setup_test_engine_and_table().await;
+        test_util::setup_table(table_ref.clone()).await;
+        table_ref.flush(None, Some(true)).await.unwrap();
+        let table_id = table_ref.table_info().ident.table_id;
+        drop(table_ref);
+        table_engine.close().await.unwrap();
+        let files = list_files(dir.path());
+
+        let engine = EmbeddedEngine::new(&dir.path().to_string_lossy()).unwrap();
+        let table = engine
+            .open_table(&table::engine::table_dir("greptime", "public", table_id))
+            .await
+            .unwrap();
+        assert_eq!(test_util::TABLE_NAME, table.table_info().name);
+
+        let output = engine
+            .execute_sql(
+                "SELECT host, cpu FROM greptime.public.demo ORDER BY host",
+                QueryContext::arc(),
+            )
+            .await
+            .unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let records = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.0 |
+| host2 | 2.0 |
+| host3 | 3.0 |
+| host4 | 4.0 |
++-------+-----+";
+        assert_eq!(expected, records.pretty_print().unwrap());
+
+        assert!(engine
+            .execute_sql(
+                "INSERT INTO greptime.public.demo (host, cpu, memory, ts) VALUES ('host5', 5.0, 5.0, 5)",
+                QueryContext::arc(),
+            )
+            .await
+            .is_err());
+
+        // Opening and querying the table doesn't change the data directory.
+        assert_eq!(files, list_files(dir.path()));
+    }
+
+    #[test]
+    fn test_parse_table_dir() {
+        assert_eq!(
+            ("greptime".to_string(), "public".to_string(), 1024),
+            parse_table_dir("greptime/public/1024/").unwrap()
+        );
+        assert_eq!(
+            ("greptime".to_string(), "public".to_string(), 1024),
+            parse_table_dir(&table::engine::table_dir("greptime", "public", 1024)).unwrap()
+        );
+        assert!(parse_table_dir("greptime/public/").is_err());
+        assert!(parse_table_dir("greptime/public/abc/").is_err());
+        assert!(parse_table_dir("greptime/public/1024/region/").is_err());
+        assert!(parse_table_dir("greptime//1024/").is_err());
+    }
+}
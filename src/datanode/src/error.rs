@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::time::Duration;
 
 use common_error::prelude::*;
 use common_procedure::ProcedureId;
@@ -49,6 +50,12 @@ pub enum Error {
         source: query::error::Error,
     },
 
+    #[snafu(display("Failed to evaluate computed columns, source: {}", source))]
+    EvalComputedColumns {
+        #[snafu(backtrace)]
+        source: query::error::Error,
+    },
+
     #[snafu(display("Failed to decode logical plan, source: {}", source))]
     DecodeLogicalPlan {
         #[snafu(backtrace)]
@@ -122,6 +129,12 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Table already exists: {}", table_name))]
+    TableExists {
+        table_name: String,
+        location: Location,
+    },
+
     #[snafu(display("Column {} not found in table {}", column_name, table_name))]
     ColumnNotFound {
         column_name: String,
@@ -172,6 +185,101 @@ pub enum Error {
         source: TableError,
     },
 
+    #[snafu(display("Failed to fence region of table: {}, source: {}", table_name, source))]
+    FenceRegion {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display(
+        "Failed to unfence region of table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    UnfenceRegion {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to compact table: {}, source: {}", table_name, source))]
+    CompactTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display(
+        "Failed to purge expired data of table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    PurgeTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display(
+        "Failed to drop time range of table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    DropRangeTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display(
+        "Failed to attach staged SSTs to table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    AttachTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to scrub table: {}, source: {}", table_name, source))]
+    ScrubTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to clone data into table: {}, source: {}", table_name, source))]
+    CloneTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display(
+        "Failed to open table from directory {}, source: {}",
+        table_dir,
+        source
+    ))]
+    OpenTable {
+        table_dir: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display(
+        "Invalid table directory {}, expect a path like <catalog>/<schema>/<table_id>/",
+        dir
+    ))]
+    InvalidTableDir { dir: String, location: Location },
+
+    #[snafu(display("Failed to create record batches, source: {}", source))]
+    CreateRecordBatches {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
     #[snafu(display("Failed to start server, source: {}", source))]
     StartServer {
         #[snafu(backtrace)]
@@ -315,6 +423,12 @@ pub enum Error {
         source: query::error::Error,
     },
 
+    #[snafu(display("Failed to prepare remote table: {}", source))]
+    PrepareRemoteTable {
+        #[snafu(backtrace)]
+        source: query::error::Error,
+    },
+
     #[snafu(display("Failed to access catalog, source: {}", source))]
     Catalog {
         #[snafu(backtrace)]
@@ -441,6 +555,38 @@ pub enum Error {
         location: Location,
         source: JsonError,
     },
+
+    #[snafu(display(
+        "Too many scans waiting in queue, max queued scans: {}",
+        max_queued_scans
+    ))]
+    TooManyScans {
+        max_queued_scans: usize,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Table {} has reached the limit of {} series, rejecting new series",
+        table_name,
+        limit
+    ))]
+    TableCardinalityExceeded {
+        table_name: String,
+        limit: usize,
+        location: Location,
+    },
+
+    #[snafu(display("Scan has waited in queue for more than {:?}", timeout))]
+    ScanQueueTimeout {
+        timeout: Duration,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Disk space of {} is below the low watermark, rejecting new writes",
+        dir
+    ))]
+    DiskSpaceLow { dir: String, location: Location },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -453,6 +599,7 @@ impl ErrorExt for Error {
             | PlanStatement { source }
             | ExecuteStatement { source }
             | ExecuteLogicalPlan { source }
+            | EvalComputedColumns { source }
             | DescribeStatement { source } => source.status_code(),
 
             DecodeLogicalPlan { source } => source.status_code(),
@@ -463,6 +610,15 @@ impl ErrorExt for Error {
             }
             DropTable { source, .. } => source.status_code(),
             FlushTable { source, .. } => source.status_code(),
+            CompactTable { source, .. } => source.status_code(),
+            FenceRegion { source, .. } | UnfenceRegion { source, .. } => source.status_code(),
+            PurgeTable { source, .. } => source.status_code(),
+            DropRangeTable { source, .. } => source.status_code(),
+            AttachTable { source, .. } => source.status_code(),
+            ScrubTable { source, .. } => source.status_code(),
+            CloneTable { source, .. } => source.status_code(),
+            OpenTable { source, .. } => source.status_code(),
+            CreateRecordBatches { source } => source.status_code(),
 
             Insert { source, .. } => source.status_code(),
             Delete { source, .. } => source.status_code(),
@@ -470,6 +626,7 @@ impl ErrorExt for Error {
                 source.status_code()
             }
             TableNotFound { .. } => StatusCode::TableNotFound,
+            TableExists { .. } => StatusCode::TableAlreadyExists,
             ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
 
             ParseSqlValue { source, .. } | ParseSql { source, .. } => source.status_code(),
@@ -499,7 +656,9 @@ impl ErrorExt for Error {
             | MissingNodeId { .. }
             | MissingMetasrvOpts { .. }
             | ColumnNoneDefaultValue { .. }
-            | PrepareImmutableTable { .. } => StatusCode::InvalidArguments,
+            | PrepareImmutableTable { .. }
+            | PrepareRemoteTable { .. }
+            | InvalidTableDir { .. } => StatusCode::InvalidArguments,
 
             EncodeJson { .. } => StatusCode::Unexpected,
 
@@ -523,7 +682,11 @@ impl ErrorExt for Error {
 
             OpenLogStore { source } => source.status_code(),
             OpenStorageEngine { source } => source.status_code(),
-            RuntimeResource { .. } => StatusCode::RuntimeResourcesExhausted,
+            RuntimeResource { .. }
+            | TooManyScans { .. }
+            | ScanQueueTimeout { .. }
+            | TableCardinalityExceeded { .. }
+            | DiskSpaceLow { .. } => StatusCode::RuntimeResourcesExhausted,
             MetaClientInit { source, .. } => source.status_code(),
             TableIdProviderNotFound { .. } => StatusCode::Unsupported,
             BumpTableId { source, .. } => source.status_code(),
@@ -543,7 +706,7 @@ impl ErrorExt for Error {
 
 impl From<Error> for tonic::Status {
     fn from(err: Error) -> Self {
-        tonic::Status::from_error(Box::new(err))
+        servers::error::status_from_error(&err)
     }
 }
 
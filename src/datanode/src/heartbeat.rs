@@ -17,11 +17,16 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, NodeStat, Peer};
+use catalog::cluster::NodeInfoReporter;
+use catalog::helper::NodeRole;
+use catalog::remote::{MetaKvBackend, RemoteCatalogManager};
 use catalog::{datanode_stat, CatalogManagerRef};
 use common_telemetry::{error, info, trace, warn};
 use meta_client::client::{HeartbeatSender, MetaClient};
 use snafu::ResultExt;
 
+use crate::datanode::RegionReconcileConfig;
+use crate::disk_watermark::DiskWatermarkRef;
 use crate::error::{MetaClientInitSnafu, Result};
 
 pub struct HeartbeatTask {
@@ -31,6 +36,8 @@ pub struct HeartbeatTask {
     running: Arc<AtomicBool>,
     meta_client: Arc<MetaClient>,
     catalog_manager: CatalogManagerRef,
+    disk_watermark: DiskWatermarkRef,
+    region_reconcile: RegionReconcileConfig,
     interval: u64,
 }
 
@@ -48,6 +55,8 @@ impl HeartbeatTask {
         server_hostname: Option<String>,
         meta_client: Arc<MetaClient>,
         catalog_manager: CatalogManagerRef,
+        disk_watermark: DiskWatermarkRef,
+        region_reconcile: RegionReconcileConfig,
     ) -> Self {
         Self {
             node_id,
@@ -56,6 +65,8 @@ impl HeartbeatTask {
             running: Arc::new(AtomicBool::new(false)),
             meta_client,
             catalog_manager,
+            disk_watermark,
+            region_reconcile,
             interval: 5_000, // default interval is set to 5 secs
         }
     }
@@ -103,6 +114,16 @@ impl HeartbeatTask {
         let meta_client = self.meta_client.clone();
 
         let catalog_manager_clone = self.catalog_manager.clone();
+        let disk_watermark = self.disk_watermark.clone();
+        let mut node_info_reporter = NodeInfoReporter::new(
+            Arc::new(MetaKvBackend {
+                client: meta_client.clone(),
+            }),
+            NodeRole::Datanode,
+            addr.clone(),
+            Some(node_id),
+        )
+        .with_interval(Duration::from_millis(interval));
         let mut tx = Self::create_streams(&meta_client, running.clone()).await?;
         common_runtime::spawn_bg(async move {
             while running.load(Ordering::Acquire) {
@@ -133,13 +154,43 @@ impl HeartbeatTask {
                         }
                     }
                 }
+                node_info_reporter.set_disk_low(disk_watermark.is_low());
+                if let Err(e) = node_info_reporter.report().await {
+                    warn!("Failed to report node info to metasrv, error: {}", e);
+                }
                 tokio::time::sleep(Duration::from_millis(interval)).await;
             }
         });
 
+        if self.region_reconcile.enable {
+            self.start_region_reconcile();
+        }
+
         Ok(())
     }
 
+    /// Periodically reconciles the tables opened by the datanode with the table routes,
+    /// which metasrv repairs according to the regions reported in heartbeats.
+    fn start_region_reconcile(&self) {
+        let running = self.running.clone();
+        let interval = self.region_reconcile.interval;
+        let catalog_manager = self.catalog_manager.clone();
+        common_runtime::spawn_bg(async move {
+            let Some(catalog_manager) = catalog_manager
+                .as_any()
+                .downcast_ref::<RemoteCatalogManager>() else {
+                warn!("Region reconcile is only supported by the remote catalog manager");
+                return;
+            };
+            while running.load(Ordering::Acquire) {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = catalog_manager.reconcile_tables().await {
+                    error!(e; "Failed to reconcile tables with table routes");
+                }
+            }
+        });
+    }
+
     pub async fn close(&self) -> Result<()> {
         let running = self.running.clone();
         if running
@@ -0,0 +1,200 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::CatalogManagerRef;
+use common_catalog::consts::{INFORMATION_SCHEMA_NAME, SYSTEM_CATALOG_NAME};
+use common_catalog::format_full_table_name;
+use common_error::prelude::{ErrorExt, StatusCode};
+use common_telemetry::{info, warn};
+use table::metadata::TableType;
+
+use crate::datanode::IdleTableConfig;
+
+/// Closes the tables not read or written for a while periodically, which flushes their
+/// regions and releases the memtables and caches. Closed tables are reopened lazily by
+/// the next request, so sprawling tables auto-created by metrics don't hold memory forever.
+pub struct IdleTableCloseTask {
+    catalog_manager: CatalogManagerRef,
+    idle_timeout: Duration,
+    check_interval: Duration,
+    running: Arc<AtomicBool>,
+}
+
+impl Drop for IdleTableCloseTask {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+impl IdleTableCloseTask {
+    pub fn new(catalog_manager: CatalogManagerRef, config: &IdleTableConfig) -> Self {
+        Self {
+            catalog_manager,
+            idle_timeout: config.idle_timeout,
+            check_interval: config.check_interval,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Start closing idle tables in background.
+    pub fn start(&self) {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Idle table close task started multiple times");
+            return;
+        }
+
+        let catalog_manager = self.catalog_manager.clone();
+        let idle_timeout = self.idle_timeout;
+        let check_interval = self.check_interval;
+        info!(
+            "Start closing idle tables, idle timeout: {:?}, check interval: {:?}",
+            idle_timeout, check_interval
+        );
+        common_runtime::spawn_bg(async move {
+            while running.load(Ordering::Acquire) {
+                tokio::time::sleep(check_interval).await;
+                let closed = close_idle_tables(&catalog_manager, idle_timeout).await;
+                if closed > 0 {
+                    info!("Closed {} idle tables", closed);
+                }
+            }
+            info!("Idle table close task exit");
+        });
+    }
+
+    pub fn close(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+/// Closes all base tables not read or written for `idle_timeout`, returns the number of
+/// tables closed. Tables not supporting closing for idleness are skipped.
+pub(crate) async fn close_idle_tables(
+    catalog_manager: &CatalogManagerRef,
+    idle_timeout: Duration,
+) -> usize {
+    let mut closed = 0;
+    let Ok(catalog_names) = catalog_manager.catalog_names().await else { return closed };
+    for catalog_name in catalog_names {
+        if catalog_name == SYSTEM_CATALOG_NAME {
+            continue;
+        }
+        let Ok(Some(catalog)) = catalog_manager.catalog(&catalog_name).await else { continue };
+
+        let Ok(schema_names) = catalog.schema_names().await else { continue };
+        for schema_name in schema_names {
+            if schema_name == INFORMATION_SCHEMA_NAME {
+                continue;
+            }
+            let Ok(Some(schema)) = catalog.schema(&schema_name).await else { continue };
+
+            let Ok(table_names) = schema.table_names().await else { continue };
+            for table_name in table_names {
+                let Ok(Some(table)) = schema.table(&table_name).await else { continue };
+                if table.table_type() != TableType::Base {
+                    continue;
+                }
+
+                match table.close_if_idle(idle_timeout).await {
+                    Ok(true) => closed += 1,
+                    Ok(false) => {}
+                    Err(e) if e.status_code() == StatusCode::Unsupported => {}
+                    Err(e) => warn!(
+                        table = %format_full_table_name(&catalog_name, &schema_name, &table_name),
+                        "Failed to close idle table, error: {}",
+                        e
+                    ),
+                }
+            }
+        }
+    }
+    closed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+    use table::requests::InsertRequest;
+
+    use super::*;
+    use crate::tests::test_util::{self, MockInstance};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_close_idle_tables() {
+        let instance = MockInstance::new("test_close_idle_tables").await;
+        let instance = instance.inner();
+        test_util::create_test_table(instance, ConcreteDataType::timestamp_millisecond_datatype())
+            .await
+            .unwrap();
+
+        let table = instance
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+            .await
+            .unwrap()
+            .unwrap();
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::new();
+        let _ = columns_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1", "host2"])),
+        );
+        let _ = columns_values.insert(
+            "cpu".to_string(),
+            Arc::new(Float64Vector::from_vec(vec![1.0, 2.0])),
+        );
+        let _ = columns_values.insert(
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2])),
+        );
+        let request = InsertRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "demo".to_string(),
+            columns_values,
+            region_number: 0,
+        };
+        assert_eq!(2, table.insert(request).await.unwrap());
+
+        // The table was just written.
+        assert_eq!(
+            0,
+            close_idle_tables(&instance.catalog_manager, Duration::from_secs(3600)).await
+        );
+        assert_eq!(
+            1,
+            close_idle_tables(&instance.catalog_manager, Duration::ZERO).await
+        );
+        // Closed tables aren't closed again.
+        assert_eq!(
+            0,
+            close_idle_tables(&instance.catalog_manager, Duration::ZERO).await
+        );
+
+        // Statistics are not collected yet, collecting them scans and reopens the table.
+        let stats = table.collect_statistics().await.unwrap();
+        assert_eq!(2, stats.num_rows);
+    }
+}
@@ -35,11 +35,14 @@ use meta_client::MetaClientOptions;
 use mito::config::EngineConfig as TableEngineConfig;
 use mito::engine::MitoEngine;
 use object_store::cache_policy::LruCacheLayer;
-use object_store::layers::{LoggingLayer, MetricsLayer, RetryLayer, TracingLayer};
+use object_store::layers::{LoggingLayer, MetricsLayer, TracingLayer};
+use object_store::retry::{RetryPolicy, RetryPolicyLayer};
 use object_store::services::{Fs as FsBuilder, Oss as OSSBuilder, S3 as S3Builder};
 use object_store::{util, ObjectStore, ObjectStoreBuilder};
 use query::query_engine::{QueryEngineFactory, QueryEngineRef};
+use remote_table_engine::engine::RemoteTableEngine;
 use secrecy::ExposeSecret;
+use servers::health_checker::HealthCheckerRef;
 use servers::Mode;
 use session::context::QueryContext;
 use snafu::prelude::*;
@@ -55,15 +58,21 @@ use table::table::numbers::NumbersTable;
 use table::table::TableIdProviderRef;
 use table::Table;
 
+use crate::cardinality_limiter::CardinalityLimiter;
 use crate::datanode::{
-    DatanodeOptions, ObjectStoreConfig, ProcedureConfig, WalConfig, DEFAULT_OBJECT_STORE_CACHE_SIZE,
+    DatanodeOptions, ObjectStoreConfig, ObjectStoreRetryConfig, ProcedureConfig, StorageConfig,
+    WalConfig, DEFAULT_OBJECT_STORE_CACHE_SIZE,
 };
+use crate::disk_watermark::{DiskWatermark, DiskWatermarkRef};
 use crate::error::{
     self, CatalogSnafu, MetaClientInitSnafu, MissingMetasrvOptsSnafu, MissingNodeIdSnafu,
     NewCatalogSnafu, OpenLogStoreSnafu, RecoverProcedureSnafu, Result, ShutdownInstanceSnafu,
 };
 use crate::heartbeat::HeartbeatTask;
+use crate::idle_table::IdleTableCloseTask;
+use crate::scan_limiter::ScanLimiter;
 use crate::sql::{SqlHandler, SqlRequest};
+use crate::statistics::StatisticsCollectTask;
 
 mod grpc;
 pub mod sql;
@@ -77,6 +86,11 @@ pub struct Instance {
     pub(crate) catalog_manager: CatalogManagerRef,
     pub(crate) table_id_provider: Option<TableIdProviderRef>,
     pub(crate) heartbeat_task: Option<HeartbeatTask>,
+    statistics_task: Option<StatisticsCollectTask>,
+    idle_table_task: Option<IdleTableCloseTask>,
+    pub(crate) scan_limiter: ScanLimiter,
+    pub(crate) cardinality_limiter: CardinalityLimiter,
+    pub(crate) disk_watermark: DiskWatermarkRef,
     procedure_manager: ProcedureManagerRef,
 }
 
@@ -108,21 +122,23 @@ impl Instance {
         meta_client: Option<Arc<MetaClient>>,
         compaction_scheduler: CompactionSchedulerRef<RaftEngineLogStore>,
     ) -> Result<Self> {
-        let object_store = new_object_store(&opts.storage.store).await?;
+        let object_store = new_object_store(&opts.storage).await?;
+        let cold_store = new_cold_object_store(&opts.storage).await?;
         let log_store = Arc::new(create_log_store(&opts.wal).await?);
 
         let mito_engine = Arc::new(DefaultEngine::new(
             TableEngineConfig::default(),
-            EngineImpl::new(
+            EngineImpl::new_with_cold_store(
                 StorageEngineConfig::from(opts),
                 log_store.clone(),
                 object_store.clone(),
+                cold_store,
                 compaction_scheduler,
             ),
             object_store.clone(),
         ));
 
-        let mut engine_procedures = HashMap::with_capacity(2);
+        let mut engine_procedures = HashMap::with_capacity(3);
         engine_procedures.insert(
             mito_engine.name().to_string(),
             mito_engine.clone() as TableEngineProcedureRef,
@@ -137,10 +153,20 @@ impl Instance {
             immutable_file_engine.clone() as TableEngineProcedureRef,
         );
 
+        let remote_table_engine = Arc::new(RemoteTableEngine::new(
+            remote_table_engine::config::EngineConfig::default(),
+            object_store.clone(),
+        ));
+        engine_procedures.insert(
+            remote_table_engine.name().to_string(),
+            remote_table_engine.clone() as TableEngineProcedureRef,
+        );
+
         let engine_manager = Arc::new(
             MemoryTableEngineManager::with(vec![
                 mito_engine.clone(),
                 immutable_file_engine.clone(),
+                remote_table_engine.clone(),
             ])
             .with_engine_procedures(engine_procedures),
         );
@@ -196,6 +222,8 @@ impl Instance {
         let factory = QueryEngineFactory::new(catalog_manager.clone());
         let query_engine = factory.query_engine();
 
+        let disk_watermark = Arc::new(DiskWatermark::new(opts, catalog_manager.clone()));
+
         let heartbeat_task = match opts.mode {
             Mode::Standalone => None,
             Mode::Distributed => Some(HeartbeatTask::new(
@@ -204,15 +232,27 @@ impl Instance {
                 opts.rpc_hostname.clone(),
                 meta_client.as_ref().unwrap().clone(),
                 catalog_manager.clone(),
+                disk_watermark.clone(),
+                opts.region_reconcile.clone(),
             )),
         };
 
+        let statistics_task = opts.statistics.enable.then(|| {
+            StatisticsCollectTask::new(catalog_manager.clone(), opts.statistics.collect_interval)
+        });
+        let idle_table_task = opts
+            .idle_table
+            .enable
+            .then(|| IdleTableCloseTask::new(catalog_manager.clone(), &opts.idle_table));
+
         let procedure_manager = create_procedure_manager(&opts.procedure, object_store).await?;
         // Register all procedures.
         // Register procedures of the mito engine.
         mito_engine.register_procedure_loaders(&*procedure_manager);
         // Register procedures of the file table engine.
         immutable_file_engine.register_procedure_loaders(&*procedure_manager);
+        // Register procedures of the remote table engine.
+        remote_table_engine.register_procedure_loaders(&*procedure_manager);
         // Register procedures in table-procedure crate.
         table_procedure::register_procedure_loaders(
             catalog_manager.clone(),
@@ -230,6 +270,11 @@ impl Instance {
             ),
             catalog_manager,
             heartbeat_task,
+            statistics_task,
+            idle_table_task,
+            scan_limiter: ScanLimiter::new(&opts.scan_limit),
+            cardinality_limiter: CardinalityLimiter::new(&opts.cardinality_limit),
+            disk_watermark,
             table_id_provider,
             procedure_manager,
         })
@@ -243,6 +288,13 @@ impl Instance {
         if let Some(task) = &self.heartbeat_task {
             task.start().await?;
         }
+        if let Some(task) = &self.statistics_task {
+            task.start();
+        }
+        if let Some(task) = &self.idle_table_task {
+            task.start();
+        }
+        self.disk_watermark.start();
 
         // Recover procedures after the catalog manager is started, so we can
         // ensure we can access all tables from the catalog manager.
@@ -254,6 +306,13 @@ impl Instance {
     }
 
     pub async fn shutdown(&self) -> Result<()> {
+        if let Some(task) = &self.statistics_task {
+            task.close();
+        }
+        if let Some(task) = &self.idle_table_task {
+            task.close();
+        }
+        self.disk_watermark.close();
         if let Some(heartbeat_task) = &self.heartbeat_task {
             heartbeat_task
                 .close()
@@ -321,17 +380,45 @@ impl Instance {
     pub fn query_engine(&self) -> QueryEngineRef {
         self.query_engine.clone()
     }
+
+    /// Returns the checker reporting whether this datanode is running out of disk space.
+    pub fn health_checker(&self) -> HealthCheckerRef {
+        self.disk_watermark.clone()
+    }
 }
 
 fn create_compaction_scheduler<S: LogStore>(opts: &DatanodeOptions) -> CompactionSchedulerRef<S> {
     let picker = SimplePicker::default();
     let config = SchedulerConfig::from(opts);
-    let handler = CompactionHandler::new(picker);
+    let runtime = common_runtime::create_runtime(
+        "compaction-worker",
+        opts.storage.background.compaction_workers,
+    );
+    let handler = CompactionHandler::new(picker).with_runtime(runtime);
     let scheduler = LocalScheduler::new(config, handler);
     Arc::new(scheduler)
 }
 
-pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+pub(crate) async fn new_object_store(storage_config: &StorageConfig) -> Result<ObjectStore> {
+    build_object_store(&storage_config.store, &storage_config.retry).await
+}
+
+/// Creates the object store for old SST files, returns `None` if the cold store is not configured.
+pub(crate) async fn new_cold_object_store(
+    storage_config: &StorageConfig,
+) -> Result<Option<ObjectStore>> {
+    match &storage_config.tiering.cold_store {
+        Some(store_config) => build_object_store(store_config, &storage_config.retry)
+            .await
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+async fn build_object_store(
+    store_config: &ObjectStoreConfig,
+    retry_config: &ObjectStoreRetryConfig,
+) -> Result<ObjectStore> {
     let object_store = match store_config {
         ObjectStoreConfig::File { .. } => new_fs_object_store(store_config).await,
         ObjectStoreConfig::S3 { .. } => new_s3_object_store(store_config).await,
@@ -340,7 +427,8 @@ pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result
 
     // Don't enable retry layer when using local file backend.
     let object_store = if !matches!(store_config, ObjectStoreConfig::File(..)) {
-        object_store.map(|object_store| object_store.layer(RetryLayer::new().with_jitter()))
+        let retry_policy = RetryPolicy::from(retry_config);
+        object_store.map(|object_store| object_store.layer(RetryPolicyLayer::new(retry_policy)))
     } else {
         object_store
     };
@@ -508,6 +596,7 @@ async fn new_metasrv_client(node_id: u64, meta_config: &MetaClientOptions) -> Re
         .enable_router()
         .enable_store()
         .channel_manager(channel_manager)
+        .backoff_policy(meta_config.backoff_policy())
         .build();
     meta_client
         .start(&meta_config.metasrv_addrs)
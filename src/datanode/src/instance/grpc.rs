@@ -21,6 +21,7 @@ use api::v1::query_request::Query;
 use api::v1::{CreateDatabaseExpr, DdlRequest, DeleteRequest, InsertRequest};
 use async_trait::async_trait;
 use catalog::CatalogManagerRef;
+use common_error::prelude::BoxedError;
 use common_query::Output;
 use datafusion::catalog::catalog::{
     CatalogList, CatalogProvider, MemoryCatalogList, MemoryCatalogProvider,
@@ -31,12 +32,13 @@ use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::plan::LogicalPlan;
 use query::query_engine::SqlStatementExecutor;
 use servers::query_handler::grpc::GrpcQueryHandler;
+use servers::query_handler::AdminHandler;
 use session::context::{QueryContext, QueryContextRef};
 use snafu::prelude::*;
 use sql::statements::statement::Statement;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::engine::TableReference;
-use table::requests::CreateDatabaseRequest;
+use table::requests::{AdminRequest, CreateDatabaseRequest};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::error::{
@@ -140,6 +142,8 @@ impl Instance {
 
         let request = common_grpc_expr::insert::to_table_insert_request(catalog, schema, request)
             .context(error::InsertDataSnafu)?;
+        self.disk_watermark.check_writable()?;
+        let request = self.cardinality_limiter.check(&table, request)?;
 
         let affected_rows = table.insert(request).await.with_context(|_| InsertSnafu {
             table_name: table_ref.to_string(),
@@ -185,6 +189,26 @@ impl Instance {
     }
 }
 
+#[async_trait]
+impl AdminHandler for Instance {
+    async fn do_admin(&self, request: AdminRequest) -> servers::error::Result<Output> {
+        let result = match request {
+            AdminRequest::CompactTable(req) => self.sql_handler.compact_table(req).await,
+            AdminRequest::FenceRegion(req) => self.sql_handler.fence_region(req).await,
+            AdminRequest::UnfenceRegion(req) => self.sql_handler.unfence_region(req).await,
+            AdminRequest::AttachTable(req) => self.sql_handler.attach_table(req).await,
+            AdminRequest::CloneData(req) => self.sql_handler.clone_data(req).await,
+            AdminRequest::AlterTable(req) => self.sql_handler.alter_table(req).await,
+            AdminRequest::PurgeTable(req) => self.sql_handler.purge_table(req).await,
+            AdminRequest::DropRange(req) => self.sql_handler.drop_range(req).await,
+            AdminRequest::ScrubTable(req) => self.sql_handler.scrub_table(req).await,
+        };
+        result
+            .map_err(BoxedError::new)
+            .context(servers::error::ExecuteGrpcQuerySnafu)
+    }
+}
+
 #[async_trait]
 impl GrpcQueryHandler for Instance {
     type Error = error::Error;
@@ -199,7 +223,10 @@ impl GrpcQueryHandler for Instance {
                     .context(error::MissingRequiredFieldSnafu {
                         name: "QueryRequest.query",
                     })?;
-                self.handle_query(query, ctx).await
+                // Hold the permit until the results are all consumed.
+                let permit = self.scan_limiter.acquire().await?;
+                let output = self.handle_query(query, ctx).await?;
+                Ok(permit.attach(output))
             }
             GrpcRequest::Ddl(request) => self.handle_ddl(request, ctx).await,
         }
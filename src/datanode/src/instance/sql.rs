@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
@@ -19,15 +21,21 @@ use common_error::prelude::BoxedError;
 use common_query::Output;
 use common_telemetry::logging::info;
 use common_telemetry::timer;
+use common_time::Timestamp;
 use query::error::QueryExecutionSnafu;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::query_engine::SqlStatementExecutor;
-use session::context::QueryContextRef;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::prelude::*;
 use sql::ast::ObjectName;
+use sql::statements::admin::Admin;
 use sql::statements::statement::Statement;
 use table::engine::TableReference;
-use table::requests::{CreateDatabaseRequest, DropTableRequest};
+use table::requests::{
+    AttachTableRequest, CloneTableRequest, CompactTableRequest, CreateDatabaseRequest,
+    CreateViewRequest, DropRangeTableRequest, DropTableRequest, FlushTableRequest, InsertRequest,
+    PurgeTableRequest, ScrubTableRequest,
+};
 
 use crate::error::{
     self, BumpTableIdSnafu, ExecuteSqlSnafu, ExecuteStatementSnafu, NotSupportSqlSnafu,
@@ -38,17 +46,34 @@ use crate::metrics;
 use crate::sql::{SqlHandler, SqlRequest};
 
 impl Instance {
+    async fn check_cardinality(&self, request: InsertRequest) -> Result<InsertRequest> {
+        if !self.cardinality_limiter.is_enabled() {
+            return Ok(request);
+        }
+        let table = self
+            .sql_handler
+            .get_table(&TableReference::full(
+                &request.catalog_name,
+                &request.schema_name,
+                &request.table_name,
+            ))
+            .await?;
+        self.cardinality_limiter.check(&table, request)
+    }
+
     async fn do_execute_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
         match stmt {
             Statement::Insert(insert) => {
                 let request =
                     SqlHandler::insert_to_request(self.catalog_manager.clone(), *insert, query_ctx)
                         .await?;
+                self.disk_watermark.check_writable()?;
+                let request = self.check_cardinality(request).await?;
                 self.sql_handler.insert(request).await
             }
             Statement::CreateDatabase(create_database) => {
                 let request = CreateDatabaseRequest {
-                    db_name: create_database.name.to_string(),
+                    db_name: create_database.database_name(),
                     create_if_not_exists: create_database.if_not_exists,
                 };
 
@@ -75,12 +100,44 @@ impl Instance {
                 let table_ref = TableReference::full(&catalog, &schema, &table);
                 let request = SqlHandler::create_to_request(table_id, create_table, &table_ref)?;
                 let table_id = request.id;
-                info!("Creating table: {table_ref}, table id = {table_id}",);
+                info!(table = %table_ref, table_id, "Creating table");
 
                 self.sql_handler
                     .execute(SqlRequest::CreateTable(request), query_ctx)
                     .await
             }
+            Statement::CloneTable(clone_table) => {
+                let table_id = self
+                    .table_id_provider
+                    .as_ref()
+                    .context(TableIdProviderNotFoundSnafu)?
+                    .next_table_id()
+                    .await
+                    .context(BumpTableIdSnafu)?;
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&clone_table.name, query_ctx.clone())?;
+                let (source_catalog_name, source_schema_name, source_table_name) =
+                    table_idents_to_full_name(&clone_table.source, query_ctx.clone())?;
+                info!(
+                    table = %table_name,
+                    source = %source_table_name,
+                    table_id,
+                    "Cloning table"
+                );
+                let req = CloneTableRequest {
+                    id: table_id,
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    source_catalog_name,
+                    source_schema_name,
+                    source_table_name,
+                    create_if_not_exists: clone_table.if_not_exists,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::CloneTable(req), query_ctx)
+                    .await
+            }
             Statement::CreateExternalTable(create_external_table) => {
                 let table_id = self
                     .table_id_provider
@@ -97,11 +154,62 @@ impl Instance {
                     .create_external_to_request(table_id, create_external_table, &table_ref)
                     .await?;
                 let table_id = request.id;
-                info!("Creating external table: {table_ref}, table id = {table_id}",);
+                info!(table = %table_ref, table_id, "Creating external table");
                 self.sql_handler
                     .execute(SqlRequest::CreateTable(request), query_ctx)
                     .await
             }
+            Statement::CreateView(create_view) => {
+                let (catalog, schema, view) =
+                    table_idents_to_full_name(&create_view.name, query_ctx.clone())?;
+                let table_id = self
+                    .table_id_provider
+                    .as_ref()
+                    .context(TableIdProviderNotFoundSnafu)?
+                    .next_table_id()
+                    .await
+                    .context(BumpTableIdSnafu)?;
+
+                // Tables in the definition are resolved under the schema of the view, the same
+                // as they are when the view is expanded.
+                let definition = create_view.query.inner.to_string();
+                let plan = self
+                    .query_engine
+                    .planner()
+                    .plan(
+                        QueryStatement::Sql(Statement::Query(create_view.query)),
+                        Arc::new(QueryContext::with(&catalog, &schema)),
+                    )
+                    .await
+                    .context(PlanStatementSnafu)?;
+                let view_schema = plan.schema().context(PlanStatementSnafu)?;
+
+                info!("Creating view: {catalog}.{schema}.{view}, table id = {table_id}");
+                let request = CreateViewRequest {
+                    id: table_id,
+                    catalog_name: catalog,
+                    schema_name: schema,
+                    view_name: view,
+                    schema: Arc::new(view_schema),
+                    definition,
+                    create_if_not_exists: create_view.if_not_exists,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::CreateView(request), query_ctx)
+                    .await
+            }
+            Statement::DropView(drop_view) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(drop_view.view_name(), query_ctx.clone())?;
+                let req = DropTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::DropView(req), query_ctx)
+                    .await
+            }
             Statement::Alter(alter_table) => {
                 let name = alter_table.table_name().clone();
                 let (catalog, schema, table) = table_idents_to_full_name(&name, query_ctx.clone())?;
@@ -131,6 +239,98 @@ impl Instance {
 
                 query::sql::show_create_table(table, None).context(ExecuteStatementSnafu)
             }
+            Statement::Admin(Admin::Flush(flush)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&flush.table_name, query_ctx.clone())?;
+                let req = FlushTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name: Some(table_name),
+                    region_number: flush.region_number,
+                    wait: Some(true),
+                };
+                self.sql_handler
+                    .execute(SqlRequest::FlushTable(req), query_ctx)
+                    .await
+            }
+            Statement::Admin(Admin::Compact(compact)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&compact.table_name, query_ctx.clone())?;
+                let req = CompactTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    region_number: compact.region_number,
+                    wait: Some(true),
+                };
+                self.sql_handler
+                    .execute(SqlRequest::CompactTable(req), query_ctx)
+                    .await
+            }
+            Statement::Admin(Admin::Purge(purge)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&purge.table_name, query_ctx.clone())?;
+                let req = PurgeTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    region_number: purge.region_number,
+                    dry_run: purge.dry_run,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::PurgeTable(req), query_ctx)
+                    .await
+            }
+            Statement::Admin(Admin::DropRange(drop_range)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&drop_range.table_name, query_ctx.clone())?;
+                let parse_timestamp = |raw: &str| {
+                    Timestamp::from_str(raw).context(error::ParseTimestampSnafu { raw })
+                };
+                let req = DropRangeTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    region_number: drop_range.region_number,
+                    start: parse_timestamp(&drop_range.start)?,
+                    end: parse_timestamp(&drop_range.end)?,
+                    dry_run: drop_range.dry_run,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::DropRange(req), query_ctx)
+                    .await
+            }
+            Statement::Admin(Admin::Attach(attach)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&attach.table_name, query_ctx.clone())?;
+                let req = AttachTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    region_number: attach.region_number,
+                    staging_dir: attach.staging_dir,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::AttachTable(req), query_ctx)
+                    .await
+            }
+            Statement::Admin(Admin::Scrub(scrub)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&scrub.table_name, query_ctx.clone())?;
+                let req = ScrubTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    region_number: scrub.region_number,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::ScrubTable(req), query_ctx)
+                    .await
+            }
+            Statement::Admin(Admin::Migrate(_)) => NotSupportSqlSnafu {
+                msg: "region migration is only supported in distributed mode",
+            }
+            .fail(),
             _ => NotSupportSqlSnafu {
                 msg: format!("not supported to execute {stmt:?}"),
             }
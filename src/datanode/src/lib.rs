@@ -15,13 +15,19 @@
 #![feature(assert_matches)]
 #![feature(trait_upcasting)]
 
+pub mod cardinality_limiter;
 pub mod datanode;
+pub mod disk_watermark;
+pub mod embedded;
 pub mod error;
 mod heartbeat;
+pub mod idle_table;
 pub mod instance;
 pub mod metrics;
 mod mock;
+pub mod scan_limiter;
 pub mod server;
 pub mod sql;
+pub mod statistics;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,234 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use common_query::Output;
+use common_recordbatch::error::Result as RecordBatchResult;
+use common_recordbatch::{
+    ExecutionStats, RecordBatch, RecordBatchStream, SendableRecordBatchStream,
+};
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use snafu::ensure;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::datanode::ScanLimitConfig;
+use crate::error::{Result, ScanQueueTimeoutSnafu, TooManyScansSnafu};
+
+/// Limits the number of scans running concurrently in the datanode, so that the analytical
+/// queries fanned out to many regions at once won't starve the ingestion.
+///
+/// Scans exceeding the limit wait in a bounded queue, and are rejected if the queue is full
+/// or they have waited too long.
+pub struct ScanLimiter {
+    /// `None` if the scans are not limited.
+    semaphore: Option<Arc<Semaphore>>,
+    queued: Arc<AtomicUsize>,
+    max_queued_scans: usize,
+    queue_timeout: Duration,
+}
+
+impl ScanLimiter {
+    pub fn new(config: &ScanLimitConfig) -> Self {
+        Self {
+            semaphore: (config.max_concurrent_scans > 0)
+                .then(|| Arc::new(Semaphore::new(config.max_concurrent_scans))),
+            queued: Arc::new(AtomicUsize::new(0)),
+            max_queued_scans: config.max_queued_scans,
+            queue_timeout: config.queue_timeout,
+        }
+    }
+
+    /// Waits until a scan is allowed to run. The returned permit must be held until the
+    /// scan is finished.
+    pub async fn acquire(&self) -> Result<ScanPermit> {
+        let Some(semaphore) = &self.semaphore else {
+            return Ok(ScanPermit(None));
+        };
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Ok(ScanPermit(Some(permit)));
+        }
+
+        let _guard = QueuedGuard::try_new(&self.queued, self.max_queued_scans)?;
+        let permit = tokio::time::timeout(self.queue_timeout, semaphore.clone().acquire_owned())
+            .await
+            .ok()
+            // The semaphore is never closed.
+            .and_then(|permit| permit.ok());
+        match permit {
+            Some(permit) => Ok(ScanPermit(Some(permit))),
+            None => ScanQueueTimeoutSnafu {
+                timeout: self.queue_timeout,
+            }
+            .fail(),
+        }
+    }
+
+    /// Number of scans waiting in the queue.
+    pub fn num_queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+}
+
+/// Counts a scan in the queue until dropped.
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl<'a> QueuedGuard<'a> {
+    fn try_new(queued: &'a AtomicUsize, max_queued_scans: usize) -> Result<Self> {
+        let prev = queued.fetch_add(1, Ordering::Relaxed);
+        let guard = Self(queued);
+        ensure!(
+            prev < max_queued_scans,
+            TooManyScansSnafu { max_queued_scans }
+        );
+        Ok(guard)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// The permission for a scan to run, released when dropped.
+pub struct ScanPermit(Option<OwnedSemaphorePermit>);
+
+impl ScanPermit {
+    /// Holds the permit until the output is consumed.
+    pub fn attach(self, output: Output) -> Output {
+        match output {
+            Output::Stream(stream) if self.0.is_some() => {
+                Output::Stream(Box::pin(PermittedStream {
+                    stream,
+                    _permit: self,
+                }))
+            }
+            output => output,
+        }
+    }
+}
+
+struct PermittedStream {
+    stream: SendableRecordBatchStream,
+    _permit: ScanPermit,
+}
+
+impl RecordBatchStream for PermittedStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn stats(&self) -> Option<ExecutionStats> {
+        self.stream.stats()
+    }
+}
+
+impl Stream for PermittedStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+
+    use super::*;
+    use crate::error::Error;
+
+    fn new_limiter(max_concurrent_scans: usize, max_queued_scans: usize) -> Arc<ScanLimiter> {
+        Arc::new(ScanLimiter::new(&ScanLimitConfig {
+            max_concurrent_scans,
+            max_queued_scans,
+            queue_timeout: Duration::from_millis(100),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_scans() {
+        let limiter = new_limiter(0, 0);
+        let permits = futures::future::try_join_all((0..10).map(|_| limiter.acquire()))
+            .await
+            .unwrap();
+        assert_eq!(10, permits.len());
+    }
+
+    #[tokio::test]
+    async fn test_scan_queue_timeout() {
+        let limiter = new_limiter(1, 1);
+        let permit = limiter.acquire().await.unwrap();
+
+        let err = limiter.acquire().await.unwrap_err();
+        assert!(matches!(err, Error::ScanQueueTimeout { .. }));
+        assert_eq!(0, limiter.num_queued());
+
+        // The queued scan runs once the running one is finished.
+        let queued = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire().await.map(|_| ()) })
+        };
+        drop(permit);
+        queued.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_too_many_queued_scans() {
+        let limiter = new_limiter(1, 1);
+        let _permit = limiter.acquire().await.unwrap();
+
+        let queued = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire().await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(1, limiter.num_queued());
+
+        let err = limiter.acquire().await.unwrap_err();
+        assert!(matches!(err, Error::TooManyScans { .. }));
+        assert!(queued.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_release_permit_after_stream_consumed() {
+        let limiter = new_limiter(1, 0);
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let stream = RecordBatches::try_new(schema, vec![]).unwrap().as_stream();
+
+        let permit = limiter.acquire().await.unwrap();
+        let output = permit.attach(Output::Stream(stream));
+        assert!(limiter.acquire().await.is_err());
+
+        let Output::Stream(stream) = output else { unreachable!() };
+        let _ = RecordBatches::try_collect(stream).await.unwrap();
+        assert!(limiter.acquire().await.is_ok());
+    }
+}
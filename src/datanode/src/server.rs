@@ -51,13 +51,20 @@ impl Services {
 
         Ok(Self {
             grpc_server: GrpcServer::new(
-                ServerGrpcQueryHandlerAdaptor::arc(instance),
+                ServerGrpcQueryHandlerAdaptor::arc(instance.clone()),
                 None,
                 None,
                 grpc_runtime,
-            ),
+            )
+            .with_flight_compression(
+                opts.rpc_compression
+                    .enable
+                    .then_some(opts.rpc_compression.threshold.0 as usize),
+            )
+            .with_admin_handler(instance.clone()),
             http_server: HttpServerBuilder::new(opts.http_opts.clone())
                 .with_metrics_handler(MetricsHandler)
+                .with_health_checker(instance.disk_watermark.clone())
                 .build(),
         })
     }
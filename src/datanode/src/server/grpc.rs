@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use api::v1::{AlterExpr, CreateTableExpr, DropTableExpr, FlushTableExpr};
-use common_catalog::consts::IMMUTABLE_FILE_ENGINE;
+use common_catalog::consts::{IMMUTABLE_FILE_ENGINE, REMOTE_TABLE_ENGINE};
 use common_grpc_expr::{alter_expr_to_request, create_expr_to_request};
 use common_query::Output;
 use common_telemetry::info;
@@ -59,7 +59,8 @@ impl Instance {
             table_id
         };
 
-        let require_time_index = expr.engine != IMMUTABLE_FILE_ENGINE;
+        let require_time_index =
+            expr.engine != IMMUTABLE_FILE_ENGINE && expr.engine != REMOTE_TABLE_ENGINE;
         let request = create_expr_to_request(table_id, expr, require_time_index)
             .context(CreateExprToRequestSnafu)?;
 
@@ -31,19 +31,35 @@ use crate::error::{
 use crate::instance::sql::table_idents_to_full_name;
 
 mod alter;
+mod attach_table;
+mod clone_table;
+mod compact_table;
 mod create;
 mod create_external;
+mod drop_range;
 mod drop_table;
+mod fence_region;
 mod flush_table;
 pub(crate) mod insert;
+pub(crate) mod purge_table;
+mod scrub_table;
+mod view;
 
 #[derive(Debug)]
 pub enum SqlRequest {
     CreateTable(CreateTableRequest),
+    CloneTable(CloneTableRequest),
     CreateDatabase(CreateDatabaseRequest),
     Alter(AlterTableRequest),
     DropTable(DropTableRequest),
     FlushTable(FlushTableRequest),
+    CompactTable(CompactTableRequest),
+    PurgeTable(PurgeTableRequest),
+    DropRange(DropRangeTableRequest),
+    AttachTable(AttachTableRequest),
+    ScrubTable(ScrubTableRequest),
+    CreateView(CreateViewRequest),
+    DropView(DropTableRequest),
 }
 
 // Handler to execute SQL except query
@@ -74,10 +90,18 @@ impl SqlHandler {
     pub async fn execute(&self, request: SqlRequest, query_ctx: QueryContextRef) -> Result<Output> {
         let result = match request {
             SqlRequest::CreateTable(req) => self.create_table(req).await,
+            SqlRequest::CloneTable(req) => self.clone_table(req).await,
             SqlRequest::CreateDatabase(req) => self.create_database(req, query_ctx.clone()).await,
             SqlRequest::Alter(req) => self.alter_table(req).await,
             SqlRequest::DropTable(req) => self.drop_table(req).await,
             SqlRequest::FlushTable(req) => self.flush_table(req).await,
+            SqlRequest::CompactTable(req) => self.compact_table(req).await,
+            SqlRequest::PurgeTable(req) => self.purge_table(req).await,
+            SqlRequest::DropRange(req) => self.drop_range(req).await,
+            SqlRequest::AttachTable(req) => self.attach_table(req).await,
+            SqlRequest::ScrubTable(req) => self.scrub_table(req).await,
+            SqlRequest::CreateView(req) => self.create_view(req).await,
+            SqlRequest::DropView(req) => self.drop_view(req).await,
         };
         if let Err(e) = &result {
             error!(e; "{query_ctx}");
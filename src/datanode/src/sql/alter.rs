@@ -42,7 +42,7 @@ impl SqlHandler {
         let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
         let procedure_id = procedure_with_id.id;
 
-        info!("Alter table {} by procedure {}", table_name, procedure_id);
+        info!(table = %table_name, "Alter table by procedure {}", procedure_id);
 
         let mut watcher = self
             .procedure_manager
@@ -82,6 +82,9 @@ impl SqlHandler {
             AlterTableOperation::RenameTable { new_table_name } => AlterKind::RenameTable {
                 new_table_name: new_table_name.clone(),
             },
+            AlterTableOperation::SetOptions { options } => AlterKind::SetOptions {
+                options: options.clone(),
+            },
         };
         Ok(AlterTableRequest {
             catalog_name: table_ref.catalog.to_string(),
@@ -0,0 +1,103 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_telemetry::info;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{UInt32Vector, UInt64Vector};
+use snafu::ResultExt;
+use store_api::storage::{AttachReport, RegionNumber};
+use table::engine::TableReference;
+use table::requests::AttachTableRequest;
+
+use crate::error::{self, Result};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    pub(crate) async fn attach_table(&self, req: AttachTableRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let table = self.get_table(&table_ref).await?;
+        let mut reports = table
+            .attach_ssts(req.region_number, &req.staging_dir)
+            .await
+            .context(error::AttachTableSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        reports.sort_unstable_by_key(|(region_number, _)| *region_number);
+
+        info!(
+            table = %table_ref,
+            "Attached SSTs staged in {}: {:?}",
+            req.staging_dir,
+            reports
+        );
+
+        attach_reports_to_output(reports)
+    }
+}
+
+fn attach_reports_to_output(reports: Vec<(RegionNumber, AttachReport)>) -> Result<Output> {
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new("region", ConcreteDataType::uint32_datatype(), false),
+        ColumnSchema::new("attached_files", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("attached_bytes", ConcreteDataType::uint64_datatype(), false),
+    ]));
+    let columns = vec![
+        Arc::new(UInt32Vector::from_values(
+            reports.iter().map(|(number, _)| *number),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            reports.iter().map(|(_, report)| report.num_files as u64),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            reports.iter().map(|(_, report)| report.file_size),
+        )) as _,
+    ];
+    let records = RecordBatches::try_from_columns(schema, columns)
+        .context(error::CreateRecordBatchesSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach_reports_to_output() {
+        let reports = vec![
+            (0, AttachReport::default()),
+            (
+                1,
+                AttachReport {
+                    num_files: 2,
+                    file_size: 1024,
+                    time_range: None,
+                },
+            ),
+        ];
+        let Output::RecordBatches(records) = attach_reports_to_output(reports).unwrap() else { unreachable!() };
+        let expected = "\
++--------+----------------+----------------+
+| region | attached_files | attached_bytes |
++--------+----------------+----------------+
+| 0      | 0              | 0              |
+| 1      | 2              | 1024           |
++--------+----------------+----------------+";
+        assert_eq!(expected, records.pretty_print().unwrap());
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_catalog::consts::MITO_ENGINE;
+use common_query::Output;
+use common_telemetry::{error, info};
+use datatypes::schema::RawSchema;
+use snafu::{ensure, ResultExt};
+use table::engine::TableReference;
+use table::requests::{CloneDataRequest, CloneTableRequest, CreateTableRequest, DropTableRequest};
+
+use crate::error::{self, CatalogSnafu, NotSupportSqlSnafu, Result};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    /// Creates a table with the schema of the source table, then shares the data of the
+    /// source with it. Data written to either table later is not visible to the other.
+    /// The created table is dropped if the data can't be shared.
+    pub(crate) async fn clone_table(&self, req: CloneTableRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let source_ref = TableReference::full(
+            &req.source_catalog_name,
+            &req.source_schema_name,
+            &req.source_table_name,
+        );
+        let source = self.get_table(&source_ref).await?;
+
+        if req.create_if_not_exists
+            && self
+                .catalog_manager
+                .table(&req.catalog_name, &req.schema_name, &req.table_name)
+                .await
+                .context(CatalogSnafu)?
+                .is_some()
+        {
+            return Ok(Output::AffectedRows(0));
+        }
+
+        let source_info = source.table_info();
+        let meta = &source_info.meta;
+        ensure!(
+            meta.engine == MITO_ENGINE,
+            NotSupportSqlSnafu {
+                msg: format!("Cloning table {} of engine {}", source_ref, meta.engine),
+            }
+        );
+        // Column ids of altered tables differ from ids of a newly created table, so
+        // their data files can't be shared.
+        ensure!(
+            meta.column_history.is_empty(),
+            NotSupportSqlSnafu {
+                msg: format!("Cloning table {source_ref} with altered columns"),
+            }
+        );
+
+        let create_req = CreateTableRequest {
+            id: req.id,
+            catalog_name: req.catalog_name.clone(),
+            schema_name: req.schema_name.clone(),
+            table_name: req.table_name.clone(),
+            desc: source_info.desc.clone(),
+            schema: RawSchema::from(&*meta.schema),
+            region_numbers: meta.region_numbers.clone(),
+            primary_key_indices: meta.primary_key_indices.clone(),
+            create_if_not_exists: false,
+            table_options: meta.options.clone(),
+            engine: meta.engine.clone(),
+        };
+        let _ = self.create_table(create_req).await?;
+
+        let clone_req = CloneDataRequest {
+            catalog_name: req.catalog_name.clone(),
+            schema_name: req.schema_name.clone(),
+            table_name: req.table_name.clone(),
+            source_catalog_name: req.source_catalog_name.clone(),
+            source_schema_name: req.source_schema_name.clone(),
+            source_table_name: req.source_table_name.clone(),
+        };
+        if let Err(e) = self.clone_data(clone_req).await {
+            let drop_req = DropTableRequest {
+                catalog_name: req.catalog_name,
+                schema_name: req.schema_name,
+                table_name: req.table_name,
+            };
+            if let Err(drop_err) = self.drop_table(drop_req).await {
+                error!(drop_err; table = %table_ref, "Failed to drop table after failing to clone it");
+            }
+            return Err(e);
+        }
+
+        info!(table = %table_ref, source = %source_ref, "Cloned table");
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Shares the data of the source table with the table, which must already exist.
+    pub(crate) async fn clone_data(&self, req: CloneDataRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let source_ref = TableReference::full(
+            &req.source_catalog_name,
+            &req.source_schema_name,
+            &req.source_table_name,
+        );
+        let source = self.get_table(&source_ref).await?;
+        let table = self.get_table(&table_ref).await?;
+        table
+            .clone_data_from(source)
+            .await
+            .context(error::CloneTableSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+
+        info!(table = %table_ref, source = %source_ref, "Cloned data of table");
+        Ok(Output::AffectedRows(0))
+    }
+}
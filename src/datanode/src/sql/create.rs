@@ -96,7 +96,7 @@ impl SqlHandler {
         let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
         let procedure_id = procedure_with_id.id;
 
-        info!("Create table {} by procedure {}", table_name, procedure_id);
+        info!(table = %table_name, "Create table by procedure {}", procedure_id);
 
         let mut watcher = self
             .procedure_manager
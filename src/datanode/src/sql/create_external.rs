@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_catalog::consts::REMOTE_TABLE_ENGINE;
 use file_table_engine::table::immutable::ImmutableFileTableOptions;
-use query::sql::prepare_immutable_file_table_files_and_schema;
+use query::sql::{prepare_immutable_file_table_files_and_schema, prepare_remote_table_schema};
 use snafu::ResultExt;
 use sql::statements::create::CreateExternalTable;
 use table::engine::TableReference;
@@ -32,16 +33,23 @@ impl SqlHandler {
     ) -> Result<CreateTableRequest> {
         let mut options = stmt.options;
 
-        let (files, schema) =
-            prepare_immutable_file_table_files_and_schema(&options, &stmt.columns)
-                .await
-                .context(error::PrepareImmutableTableSnafu)?;
+        let schema = if stmt.engine == REMOTE_TABLE_ENGINE {
+            // Remote tables read rows from the remote database, there are no files to prepare.
+            prepare_remote_table_schema(table_ref.table, &stmt.columns)
+                .context(error::PrepareRemoteTableSnafu)?
+        } else {
+            let (files, schema) =
+                prepare_immutable_file_table_files_and_schema(&options, &stmt.columns)
+                    .await
+                    .context(error::PrepareImmutableTableSnafu)?;
 
-        let meta = ImmutableFileTableOptions { files };
-        options.insert(
-            IMMUTABLE_TABLE_META_KEY.to_string(),
-            serde_json::to_string(&meta).context(error::EncodeJsonSnafu)?,
-        );
+            let meta = ImmutableFileTableOptions { files };
+            options.insert(
+                IMMUTABLE_TABLE_META_KEY.to_string(),
+                serde_json::to_string(&meta).context(error::EncodeJsonSnafu)?,
+            );
+            schema
+        };
 
         Ok(CreateTableRequest {
             id: table_id,
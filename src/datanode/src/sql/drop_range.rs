@@ -0,0 +1,112 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_telemetry::info;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{UInt32Vector, UInt64Vector};
+use snafu::ResultExt;
+use store_api::storage::{DropRangeReport, RegionNumber};
+use table::engine::TableReference;
+use table::requests::DropRangeTableRequest;
+
+use crate::error::{self, Result};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    pub(crate) async fn drop_range(&self, req: DropRangeTableRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let table = self.get_table(&table_ref).await?;
+        let mut reports = table
+            .drop_range(req.region_number, req.start, req.end, req.dry_run)
+            .await
+            .context(error::DropRangeTableSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        reports.sort_unstable_by_key(|(region_number, _)| *region_number);
+
+        if !req.dry_run {
+            info!(
+                table = %table_ref,
+                "Dropped range [{:?}, {:?}]: {:?}",
+                req.start,
+                req.end,
+                reports
+            );
+        }
+
+        drop_range_reports_to_output(reports)
+    }
+}
+
+fn drop_range_reports_to_output(reports: Vec<(RegionNumber, DropRangeReport)>) -> Result<Output> {
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new("region", ConcreteDataType::uint32_datatype(), false),
+        ColumnSchema::new("removed_files", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("removed_bytes", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("masked_files", ConcreteDataType::uint64_datatype(), false),
+    ]));
+    let columns = vec![
+        Arc::new(UInt32Vector::from_values(
+            reports.iter().map(|(number, _)| *number),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            reports
+                .iter()
+                .map(|(_, report)| report.removed_files as u64),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            reports.iter().map(|(_, report)| report.removed_file_size),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            reports.iter().map(|(_, report)| report.masked_files as u64),
+        )) as _,
+    ];
+    let records = RecordBatches::try_from_columns(schema, columns)
+        .context(error::CreateRecordBatchesSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_range_reports_to_output() {
+        let reports = vec![
+            (0, DropRangeReport::default()),
+            (
+                1,
+                DropRangeReport {
+                    removed_files: 2,
+                    removed_file_size: 1024,
+                    masked_files: 1,
+                },
+            ),
+        ];
+        let Output::RecordBatches(records) = drop_range_reports_to_output(reports).unwrap() else { unreachable!() };
+        let expected = "\
++--------+---------------+---------------+--------------+
+| region | removed_files | removed_bytes | masked_files |
++--------+---------------+---------------+--------------+
+| 0      | 0             | 0             | 0            |
+| 1      | 2             | 1024          | 1            |
++--------+---------------+---------------+--------------+";
+        assert_eq!(expected, records.pretty_print().unwrap());
+    }
+}
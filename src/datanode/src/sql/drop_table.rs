@@ -41,7 +41,7 @@ impl SqlHandler {
         let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
         let procedure_id = procedure_with_id.id;
 
-        info!("Drop table {} by procedure {}", table_name, procedure_id);
+        info!(table = %table_name, "Drop table by procedure {}", procedure_id);
 
         let mut watcher = self
             .procedure_manager
@@ -0,0 +1,52 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_query::Output;
+use common_telemetry::info;
+use snafu::ResultExt;
+use table::engine::TableReference;
+use table::requests::FenceRegionRequest;
+
+use crate::error::{self, Result};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    pub(crate) async fn fence_region(&self, req: FenceRegionRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let table = self.get_table(&table_ref).await?;
+        table
+            .fence_region(req.region_number)
+            .await
+            .context(error::FenceRegionSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        info!(table = %table_ref, region = req.region_number, "Fenced region");
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(crate) async fn unfence_region(&self, req: FenceRegionRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let table = self.get_table(&table_ref).await?;
+        table
+            .unfence_region(req.region_number)
+            .await
+            .context(error::UnfenceRegionSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        info!(table = %table_ref, region = req.region_number, "Unfenced region");
+
+        Ok(Output::AffectedRows(0))
+    }
+}
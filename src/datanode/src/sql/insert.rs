@@ -29,8 +29,8 @@ use table::TableRef;
 
 use crate::error::{
     CatalogSnafu, ColumnDefaultValueSnafu, ColumnNoneDefaultValueSnafu, ColumnNotFoundSnafu,
-    ColumnValuesNumberMismatchSnafu, InsertSnafu, MissingInsertBodySnafu, ParseSqlSnafu,
-    ParseSqlValueSnafu, Result, TableNotFoundSnafu,
+    ColumnValuesNumberMismatchSnafu, EvalComputedColumnsSnafu, InsertSnafu, MissingInsertBodySnafu,
+    ParseSqlSnafu, ParseSqlValueSnafu, Result, TableNotFoundSnafu,
 };
 use crate::sql::{table_idents_to_full_name, SqlHandler};
 
@@ -66,8 +66,15 @@ impl SqlHandler {
             .context(MissingInsertBodySnafu)?;
         let columns = stmt.columns();
         let schema = table.schema();
+        // Computed columns are evaluated from the others, so they are left out when the
+        // columns are not specified.
+        let implicit_columns = schema
+            .column_schemas()
+            .iter()
+            .filter(|column_schema| column_schema.computed_expr().is_none())
+            .collect::<Vec<_>>();
         let columns_num = if columns.is_empty() {
-            schema.column_schemas().len()
+            implicit_columns.len()
         } else {
             columns.len()
         };
@@ -78,7 +85,7 @@ impl SqlHandler {
 
         // Initialize vectors
         if columns.is_empty() {
-            for column_schema in schema.column_schemas() {
+            for column_schema in implicit_columns {
                 let data_type = &column_schema.data_type;
                 columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
             }
@@ -140,7 +147,11 @@ impl SqlHandler {
             })?;
 
         let table_ref = TableReference::full(&catalog_name, &schema_name, &table_name);
-        Self::build_request_from_values(table_ref, &table, stmt)
+        let mut request = Self::build_request_from_values(table_ref, &table, stmt)?;
+        query::computed::fill_computed_columns(&table.schema(), &mut request.columns_values)
+            .await
+            .context(EvalComputedColumnsSnafu)?;
+        Ok(request)
     }
 }
 
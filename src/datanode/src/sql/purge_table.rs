@@ -0,0 +1,122 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{TimestampMillisecondVector, UInt32Vector, UInt64Vector};
+use snafu::ResultExt;
+use store_api::storage::{PurgeReport, RegionNumber};
+use table::engine::TableReference;
+use table::requests::PurgeTableRequest;
+
+use crate::error::{self, Result};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    /// Purges the data expired by the TTL of the table, the frontend records the executed
+    /// purges.
+    pub(crate) async fn purge_table(&self, req: PurgeTableRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let table = self.get_table(&table_ref).await?;
+        let mut reports = table
+            .purge_expired(req.region_number, req.dry_run)
+            .await
+            .context(error::PurgeTableSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        reports.sort_unstable_by_key(|(region_number, _)| *region_number);
+
+        purge_reports_to_output(reports)
+    }
+}
+
+/// Returns the time range of the purged data in milliseconds.
+fn time_range_millis(report: &PurgeReport) -> (Option<i64>, Option<i64>) {
+    let Some((start, end)) = report.time_range else { return (None, None) };
+    let to_millis = |ts: Timestamp| ts.convert_to(TimeUnit::Millisecond).map(|ts| ts.value());
+    (to_millis(start), to_millis(end))
+}
+
+fn purge_reports_to_output(reports: Vec<(RegionNumber, PurgeReport)>) -> Result<Output> {
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new("region", ConcreteDataType::uint32_datatype(), false),
+        ColumnSchema::new("files", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("bytes", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new(
+            "start_time",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            true,
+        ),
+        ColumnSchema::new(
+            "end_time",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            true,
+        ),
+    ]));
+    let (start_times, end_times) = reports
+        .iter()
+        .map(|(_, report)| time_range_millis(report))
+        .unzip::<_, _, Vec<_>, Vec<_>>();
+    let columns = vec![
+        Arc::new(UInt32Vector::from_values(
+            reports.iter().map(|(number, _)| *number),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            reports.iter().map(|(_, report)| report.num_files as u64),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            reports.iter().map(|(_, report)| report.file_size),
+        )) as _,
+        Arc::new(TimestampMillisecondVector::from(start_times)) as _,
+        Arc::new(TimestampMillisecondVector::from(end_times)) as _,
+    ];
+    let records = RecordBatches::try_from_columns(schema, columns)
+        .context(error::CreateRecordBatchesSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_reports_to_output() {
+        let reports = vec![
+            (0, PurgeReport::default()),
+            (
+                1,
+                PurgeReport {
+                    num_files: 2,
+                    file_size: 1024,
+                    time_range: Some((Timestamp::new_second(1), Timestamp::new_second(2))),
+                },
+            ),
+        ];
+        let Output::RecordBatches(records) = purge_reports_to_output(reports).unwrap() else { unreachable!() };
+        let expected = "\
++--------+-------+-------+---------------------+---------------------+
+| region | files | bytes | start_time          | end_time            |
++--------+-------+-------+---------------------+---------------------+
+| 0      | 0     | 0     |                     |                     |
+| 1      | 2     | 1024  | 1970-01-01T00:00:01 | 1970-01-01T00:00:02 |
++--------+-------+-------+---------------------+---------------------+";
+        assert_eq!(expected, records.pretty_print().unwrap());
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_telemetry::{info, warn};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{StringVector, UInt32Vector};
+use snafu::ResultExt;
+use store_api::storage::{RegionHealthReport, RegionNumber};
+use table::engine::TableReference;
+use table::requests::ScrubTableRequest;
+
+use crate::error::{self, Result};
+use crate::sql::SqlHandler;
+
+/// Status of a region without any issue in the scrub output.
+const STATUS_OK: &str = "ok";
+
+impl SqlHandler {
+    pub(crate) async fn scrub_table(&self, req: ScrubTableRequest) -> Result<Output> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let table = self.get_table(&table_ref).await?;
+        let mut reports = table
+            .scrub(req.region_number)
+            .await
+            .context(error::ScrubTableSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        reports.sort_unstable_by_key(|(region_number, _)| *region_number);
+
+        let num_issues = reports
+            .iter()
+            .map(|(_, report)| report.issues.len())
+            .sum::<usize>();
+        if num_issues == 0 {
+            info!(table = %table_ref, "Scrubbed table, all regions are healthy");
+        } else {
+            warn!(table = %table_ref, "Scrubbed table, found {} issues", num_issues);
+        }
+
+        scrub_reports_to_output(reports)
+    }
+}
+
+/// Converts the reports to rows of `(region, file, status, detail)`, one row for each
+/// issue, or a row with the `ok` status for a healthy region.
+fn scrub_reports_to_output(reports: Vec<(RegionNumber, RegionHealthReport)>) -> Result<Output> {
+    let mut regions = Vec::new();
+    let mut files = Vec::new();
+    let mut statuses = Vec::new();
+    let mut details = Vec::new();
+    for (region_number, report) in reports {
+        if report.is_healthy() {
+            regions.push(region_number);
+            files.push(None);
+            statuses.push(STATUS_OK.to_string());
+            details.push(format!(
+                "{} files of {} bytes in manifest versions [{}, {}]",
+                report.num_files, report.file_size, report.start_version, report.end_version
+            ));
+            continue;
+        }
+        for issue in report.issues {
+            regions.push(region_number);
+            files.push(issue.file);
+            statuses.push(issue.kind.as_str().to_string());
+            details.push(issue.detail);
+        }
+    }
+
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new("region", ConcreteDataType::uint32_datatype(), false),
+        ColumnSchema::new("file", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new("status", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("detail", ConcreteDataType::string_datatype(), false),
+    ]));
+    let columns = vec![
+        Arc::new(UInt32Vector::from_values(regions)) as _,
+        Arc::new(StringVector::from(files)) as _,
+        Arc::new(StringVector::from(statuses)) as _,
+        Arc::new(StringVector::from(details)) as _,
+    ];
+    let records = RecordBatches::try_from_columns(schema, columns)
+        .context(error::CreateRecordBatchesSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use store_api::storage::{HealthIssue, HealthIssueKind};
+
+    use super::*;
+
+    #[test]
+    fn test_scrub_reports_to_output() {
+        let reports = vec![
+            (
+                0,
+                RegionHealthReport {
+                    start_version: 0,
+                    end_version: 3,
+                    num_files: 2,
+                    file_size: 1024,
+                    issues: Vec::new(),
+                },
+            ),
+            (
+                1,
+                RegionHealthReport {
+                    start_version: 0,
+                    end_version: 5,
+                    num_files: 1,
+                    file_size: 512,
+                    issues: vec![
+                        HealthIssue {
+                            kind: HealthIssueKind::ManifestGap,
+                            file: None,
+                            detail: "manifest versions 1..3 are missing".to_string(),
+                        },
+                        HealthIssue {
+                            kind: HealthIssueKind::MissingFile,
+                            file: Some("a.parquet".to_string()),
+                            detail: "file doesn't exist".to_string(),
+                        },
+                    ],
+                },
+            ),
+        ];
+        let Output::RecordBatches(records) = scrub_reports_to_output(reports).unwrap() else { unreachable!() };
+        let expected = "\
++--------+-----------+--------------+---------------------------------------------------+
+| region | file      | status       | detail                                            |
++--------+-----------+--------------+---------------------------------------------------+
+| 0      |           | ok           | 2 files of 1024 bytes in manifest versions [0, 3] |
+| 1      |           | manifest_gap | manifest versions 1..3 are missing                |
+| 1      | a.parquet | missing_file | file doesn't exist                                |
++--------+-----------+--------------+---------------------------------------------------+";
+        assert_eq!(expected, records.pretty_print().unwrap());
+    }
+}
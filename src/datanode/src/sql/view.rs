@@ -0,0 +1,90 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use catalog::{DeregisterTableRequest, RegisterTableRequest};
+use common_query::Output;
+use common_telemetry::tracing::info;
+use snafu::{ensure, ResultExt};
+use table::engine::TableReference;
+use table::metadata::TableType;
+use table::requests::{CreateViewRequest, DropTableRequest};
+use table::table::view::ViewTable;
+
+use crate::error::{CatalogSnafu, InvalidSqlSnafu, Result, TableExistsSnafu};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    pub(crate) async fn create_view(&self, req: CreateViewRequest) -> Result<Output> {
+        let view_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.view_name);
+        if self
+            .catalog_manager
+            .table(&req.catalog_name, &req.schema_name, &req.view_name)
+            .await
+            .context(CatalogSnafu)?
+            .is_some()
+        {
+            return if req.create_if_not_exists {
+                Ok(Output::AffectedRows(0))
+            } else {
+                TableExistsSnafu {
+                    table_name: view_ref.to_string(),
+                }
+                .fail()
+            };
+        }
+
+        let request = RegisterTableRequest {
+            catalog: req.catalog_name.clone(),
+            schema: req.schema_name.clone(),
+            table_name: req.view_name.clone(),
+            table_id: req.id,
+            table: Arc::new(ViewTable::new(req.clone())),
+        };
+        let _ = self
+            .catalog_manager
+            .register_table(request)
+            .await
+            .context(CatalogSnafu)?;
+        info!(table = %view_ref, table_id = req.id, "Created view");
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(crate) async fn drop_view(&self, req: DropTableRequest) -> Result<Output> {
+        let view_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let view = self.get_table(&view_ref).await?;
+        ensure!(
+            view.table_type() == TableType::View,
+            InvalidSqlSnafu {
+                msg: format!("{view_ref} is not a view"),
+            }
+        );
+
+        let request = DeregisterTableRequest {
+            catalog: req.catalog_name.clone(),
+            schema: req.schema_name.clone(),
+            table_name: req.table_name.clone(),
+        };
+        let _ = self
+            .catalog_manager
+            .deregister_table(request)
+            .await
+            .context(CatalogSnafu)?;
+        info!(table = %view_ref, "Dropped view");
+
+        Ok(Output::AffectedRows(1))
+    }
+}
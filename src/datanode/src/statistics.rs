@@ -0,0 +1,210 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::CatalogManagerRef;
+use common_catalog::consts::{INFORMATION_SCHEMA_NAME, SYSTEM_CATALOG_NAME};
+use common_catalog::format_full_table_name;
+use common_error::prelude::{ErrorExt, StatusCode};
+use common_telemetry::{debug, info, warn};
+use table::metadata::TableType;
+
+/// Collects the statistics of all tables in the catalog periodically, so the query engine
+/// could make use of them while planning.
+pub struct StatisticsCollectTask {
+    catalog_manager: CatalogManagerRef,
+    interval: Duration,
+    running: Arc<AtomicBool>,
+}
+
+impl Drop for StatisticsCollectTask {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+impl StatisticsCollectTask {
+    pub fn new(catalog_manager: CatalogManagerRef, interval: Duration) -> Self {
+        Self {
+            catalog_manager,
+            interval,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Start collecting statistics in background.
+    pub fn start(&self) {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Statistics collect task started multiple times");
+            return;
+        }
+
+        let catalog_manager = self.catalog_manager.clone();
+        let interval = self.interval;
+        info!(
+            "Start collecting table statistics, interval: {:?}",
+            interval
+        );
+        common_runtime::spawn_bg(async move {
+            while running.load(Ordering::Acquire) {
+                tokio::time::sleep(interval).await;
+                collect_statistics(&catalog_manager).await;
+            }
+            info!("Statistics collect task exit");
+        });
+    }
+
+    pub fn close(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+/// Collects the statistics of all base tables in the catalog, and persists them in the
+/// system catalog table. Tables not supporting statistics are skipped.
+pub(crate) async fn collect_statistics(catalog_manager: &CatalogManagerRef) {
+    let Ok(catalog_names) = catalog_manager.catalog_names().await else { return };
+    for catalog_name in catalog_names {
+        if catalog_name == SYSTEM_CATALOG_NAME {
+            continue;
+        }
+        let Ok(Some(catalog)) = catalog_manager.catalog(&catalog_name).await else { continue };
+
+        let Ok(schema_names) = catalog.schema_names().await else { continue };
+        for schema_name in schema_names {
+            if schema_name == INFORMATION_SCHEMA_NAME {
+                continue;
+            }
+            let Ok(Some(schema)) = catalog.schema(&schema_name).await else { continue };
+
+            let Ok(table_names) = schema.table_names().await else { continue };
+            for table_name in table_names {
+                let Ok(Some(table)) = schema.table(&table_name).await else { continue };
+                if table.table_type() != TableType::Base {
+                    continue;
+                }
+
+                match table.collect_statistics().await {
+                    Ok(stats) => {
+                        debug!(
+                            table = %format_full_table_name(&catalog_name, &schema_name, &table_name),
+                            rows = stats.num_rows,
+                            "Collected statistics of table"
+                        );
+                        let table_id = table.table_info().ident.table_id;
+                        if let Err(e) = catalog_manager
+                            .persist_table_statistics(&catalog_name, &schema_name, table_id, &stats)
+                            .await
+                        {
+                            warn!(
+                                table = %format_full_table_name(&catalog_name, &schema_name, &table_name),
+                                "Failed to persist statistics of table, error: {}",
+                                e
+                            );
+                        }
+                    }
+                    Err(e) if e.status_code() == StatusCode::Unsupported => {}
+                    Err(e) => warn!(
+                        table = %format_full_table_name(&catalog_name, &schema_name, &table_name),
+                        "Failed to collect statistics of table, error: {}",
+                        e
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use catalog::local::LocalCatalogManager;
+    use catalog::CatalogManager;
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+    use table::requests::InsertRequest;
+    use table::stats::TableStatistics;
+
+    use super::*;
+    use crate::tests::test_util::{self, MockInstance};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_collect_statistics() {
+        let instance = MockInstance::new("test_collect_statistics").await;
+        let instance = instance.inner();
+        test_util::create_test_table(instance, ConcreteDataType::timestamp_millisecond_datatype())
+            .await
+            .unwrap();
+
+        let table = instance
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(table.statistics().is_none());
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::new();
+        let _ = columns_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1", "host2", "host1"])),
+        );
+        let _ = columns_values.insert(
+            "cpu".to_string(),
+            Arc::new(Float64Vector::from(vec![Some(1.0), None, Some(3.0)])),
+        );
+        let _ = columns_values.insert(
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2, 3])),
+        );
+        let request = InsertRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "demo".to_string(),
+            columns_values,
+            region_number: 0,
+        };
+        assert_eq!(3, table.insert(request).await.unwrap());
+
+        collect_statistics(&instance.catalog_manager).await;
+
+        let stats = table.statistics().unwrap();
+        assert_eq!(3, stats.num_rows);
+        assert_eq!(Some(2), stats.columns["host"].distinct_count);
+        assert_eq!(1, stats.columns["cpu"].null_count);
+        // The column "memory" is not written and filled with nulls.
+        assert_eq!(3, stats.columns["memory"].null_count);
+
+        // The statistics are persisted, and restored once the table is opened by a catalog
+        // manager again.
+        table.restore_statistics(TableStatistics::default());
+        let engine_manager = instance.sql_handler.table_engine_manager();
+        let catalog_manager = LocalCatalogManager::try_new(engine_manager).await.unwrap();
+        catalog_manager.start().await.unwrap();
+        let table = catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(Some(stats), table.statistics());
+    }
+}
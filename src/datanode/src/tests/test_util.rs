@@ -24,7 +24,8 @@ use table::engine::{EngineContext, TableEngineRef};
 use table::requests::{CreateTableRequest, TableOptions};
 
 use crate::datanode::{
-    DatanodeOptions, FileConfig, ObjectStoreConfig, ProcedureConfig, StorageConfig, WalConfig,
+    DatanodeOptions, DiskWatermarkConfig, FileConfig, ObjectStoreConfig, ProcedureConfig,
+    StorageConfig, WalConfig,
 };
 use crate::error::{CreateTableSnafu, Result};
 use crate::instance::Instance;
@@ -70,6 +71,13 @@ fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard)
         },
         mode: Mode::Standalone,
         procedure: ProcedureConfig::default(),
+        // Don't let the free space of the test machine affect the tests, which set the low
+        // watermark themselves.
+        disk_watermark: DiskWatermarkConfig {
+            enable: false,
+            reject_writes: true,
+            ..Default::default()
+        },
         ..Default::default()
     };
     (
@@ -25,7 +25,10 @@ use snafu::{ensure, ResultExt};
 
 use crate::data_type::DataType;
 use crate::error::{self, Error, Result};
-pub use crate::schema::column_schema::{ColumnSchema, Metadata, COMMENT_KEY, TIME_INDEX_KEY};
+pub use crate::schema::column_schema::{
+    ColumnSchema, Metadata, COMMENT_KEY, COMPUTED_EXPR_KEY, PRECISION_KEY, SEMANTIC_TYPE_FIELD,
+    SEMANTIC_TYPE_KEY, SEMANTIC_TYPE_TAG, SEMANTIC_TYPE_TIMESTAMP, TIME_INDEX_KEY, UNIT_KEY,
+};
 pub use crate::schema::constraint::ColumnDefaultConstraint;
 pub use crate::schema::raw::RawSchema;
 
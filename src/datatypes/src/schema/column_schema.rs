@@ -15,6 +15,7 @@
 use std::collections::HashMap;
 
 use arrow::datatypes::Field;
+use common_time::timestamp::TimeUnit;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 
@@ -29,6 +30,21 @@ pub type Metadata = HashMap<String, String>;
 /// Key used to store whether the column is time index in arrow field's metadata.
 pub const TIME_INDEX_KEY: &str = "greptime:time_index";
 pub const COMMENT_KEY: &str = "greptime:storage:comment";
+/// Key used to store the semantic type of a column in the schema of query results, the
+/// value is one of [SEMANTIC_TYPE_TIMESTAMP], [SEMANTIC_TYPE_TAG] and [SEMANTIC_TYPE_FIELD].
+pub const SEMANTIC_TYPE_KEY: &str = "greptime:semantic_type";
+pub const SEMANTIC_TYPE_TIMESTAMP: &str = "timestamp";
+pub const SEMANTIC_TYPE_TAG: &str = "tag";
+pub const SEMANTIC_TYPE_FIELD: &str = "field";
+/// Key used to store the precision of timestamp columns in the schema of query results, the
+/// value is one of `s`, `ms`, `us` and `ns`.
+pub const PRECISION_KEY: &str = "greptime:precision";
+/// Key used to store the unit of the values of a column, e.g. `bytes`, which is kept in the
+/// schema of query results.
+pub const UNIT_KEY: &str = "greptime:unit";
+/// Key used to store the expression of a computed column, whose values are evaluated from
+/// other columns on insertion.
+pub const COMPUTED_EXPR_KEY: &str = "greptime:computed_expr";
 /// Key used to store default constraint in arrow field's metadata.
 const DEFAULT_CONSTRAINT_KEY: &str = "greptime:default_constraint";
 
@@ -74,6 +90,32 @@ impl ColumnSchema {
         self.default_constraint.as_ref()
     }
 
+    /// Returns the expression of the column if it's a computed column.
+    #[inline]
+    pub fn computed_expr(&self) -> Option<&str> {
+        self.metadata.get(COMPUTED_EXPR_KEY).map(String::as_str)
+    }
+
+    /// Returns the unit of the values of the column.
+    #[inline]
+    pub fn unit(&self) -> Option<&str> {
+        self.metadata.get(UNIT_KEY).map(String::as_str)
+    }
+
+    /// Returns the precision of the column if it's a timestamp column, one of `s`, `ms`, `us`
+    /// and `ns`.
+    pub fn timestamp_precision(&self) -> Option<&'static str> {
+        let ConcreteDataType::Timestamp(t) = &self.data_type else {
+            return None;
+        };
+        Some(match t.unit() {
+            TimeUnit::Second => "s",
+            TimeUnit::Millisecond => "ms",
+            TimeUnit::Microsecond => "us",
+            TimeUnit::Nanosecond => "ns",
+        })
+    }
+
     #[inline]
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
@@ -107,6 +149,19 @@ impl ColumnSchema {
         Ok(self)
     }
 
+    /// Creates a new [`ColumnSchema`] computed from the expression on insertion.
+    pub fn with_computed_expr(mut self, expr: impl Into<String>) -> Self {
+        self.metadata
+            .insert(COMPUTED_EXPR_KEY.to_string(), expr.into());
+        self
+    }
+
+    /// Creates a new [`ColumnSchema`] whose values are in the unit.
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.metadata.insert(UNIT_KEY.to_string(), unit.into());
+        self
+    }
+
     /// Creates a new [`ColumnSchema`] with given metadata.
     pub fn with_metadata(mut self, metadata: Metadata) -> Self {
         self.metadata = metadata;
@@ -264,6 +319,41 @@ mod tests {
         assert_eq!(column_schema, new_column_schema);
     }
 
+    #[test]
+    fn test_column_schema_with_computed_expr() {
+        let column_schema = ColumnSchema::new("dc", ConcreteDataType::string_datatype(), true);
+        assert!(column_schema.computed_expr().is_none());
+
+        let column_schema = column_schema.with_computed_expr("substr(host, 1, 3)");
+        assert_eq!(Some("substr(host, 1, 3)"), column_schema.computed_expr());
+
+        let field = Field::try_from(&column_schema).unwrap();
+        let new_column_schema = ColumnSchema::try_from(&field).unwrap();
+        assert_eq!(
+            Some("substr(host, 1, 3)"),
+            new_column_schema.computed_expr()
+        );
+    }
+
+    #[test]
+    fn test_column_schema_unit_and_precision() {
+        let column_schema = ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), true);
+        assert!(column_schema.unit().is_none());
+
+        let column_schema = column_schema.with_unit("bytes");
+        let field = Field::try_from(&column_schema).unwrap();
+        let new_column_schema = ColumnSchema::try_from(&field).unwrap();
+        assert_eq!(Some("bytes"), new_column_schema.unit());
+        assert!(new_column_schema.timestamp_precision().is_none());
+
+        let column_schema = ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_microsecond_datatype(),
+            false,
+        );
+        assert_eq!(Some("us"), column_schema.timestamp_precision());
+    }
+
     #[test]
     fn test_column_schema_with_metadata() {
         let mut metadata = Metadata::new();
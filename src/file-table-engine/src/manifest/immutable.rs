@@ -45,7 +45,7 @@ pub fn manifest_path(dir: &str) -> String {
     format!("{}{}", dir, IMMUTABLE_MANIFEST_FILE)
 }
 
-pub(crate) async fn delete_table_manifest(
+pub async fn delete_table_manifest(
     table_name: &str,
     dir: &str,
     object_store: &ObjectStore,
@@ -56,7 +56,7 @@ pub(crate) async fn delete_table_manifest(
         .context(DeleteTableManifestSnafu { table_name })
 }
 
-pub(crate) async fn write_table_manifest(
+pub async fn write_table_manifest(
     table_name: &str,
     dir: &str,
     object_store: &ObjectStore,
@@ -78,7 +78,7 @@ pub(crate) async fn write_table_manifest(
         .context(WriteTableManifestSnafu { table_name })
 }
 
-pub(crate) async fn read_table_manifest(
+pub async fn read_table_manifest(
     table_name: &str,
     dir: &str,
     object_store: &ObjectStore,
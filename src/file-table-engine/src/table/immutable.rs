@@ -105,6 +105,15 @@ impl Table for ImmutableFileTable {
         Ok(())
     }
 
+    async fn compact(
+        &self,
+        _region_number: Option<RegionNumber>,
+        _wait: Option<bool>,
+    ) -> TableResult<()> {
+        // nothing to compact
+        Ok(())
+    }
+
     async fn close(&self) -> TableResult<()> {
         Ok(())
     }
@@ -13,19 +13,26 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use api::v1::CreateTableExpr;
 use async_trait::async_trait;
+use catalog::cluster::{disk_low_warnings, list_cluster_nodes, list_region_inconsistencies};
 use catalog::error::{
     self as catalog_err, InternalSnafu, InvalidCatalogValueSnafu, InvalidSystemTableDefSnafu,
     Result as CatalogResult, UnimplementedSnafu,
 };
 use catalog::helper::{
-    build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, SchemaKey,
+    build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey,
+    ClusterNodeKey, ClusterNodeValue, RegionInconsistencyKey, RegionInconsistencyValue, SchemaKey,
     TableGlobalKey, TableGlobalValue,
 };
+use catalog::notifier::{
+    bump_tables_version, CatalogEvent, CatalogEventNotifier, CatalogEventReceiver,
+    KvBackendWatcher, DEFAULT_WATCH_INTERVAL,
+};
 use catalog::remote::{Kv, KvBackendRef};
 use catalog::{
     CatalogManager, CatalogProvider, CatalogProviderRef, DeregisterTableRequest,
@@ -34,17 +41,21 @@ use catalog::{
 };
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::prelude::BoxedError;
-use common_telemetry::warn;
+use common_telemetry::{info, warn};
 use futures::StreamExt;
 use futures_util::TryStreamExt;
 use meta_client::rpc::TableName;
 use partition::manager::PartitionRuleManagerRef;
+use servers::health_checker::HealthChecker;
 use snafu::prelude::*;
 use table::table::numbers::NumbersTable;
 use table::TableRef;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
 
 use crate::datanode::DatanodeClients;
 use crate::expr_factory;
+use crate::insert_batch::InsertBatchOptions;
 use crate::instance::distributed::DistInstance;
 use crate::table::DistTable;
 
@@ -53,6 +64,11 @@ pub struct FrontendCatalogManager {
     backend: KvBackendRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_clients: Arc<DatanodeClients>,
+    notifier: CatalogEventNotifier,
+    insert_batch_options: InsertBatchOptions,
+    watch_interval: Duration,
+    /// Background tasks started by [CatalogManager::start], aborted on [Self::stop].
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
 
     // TODO(LFC): Remove this field.
     // DistInstance in FrontendCatalogManager is only used for creating distributed script table now.
@@ -71,14 +87,37 @@ impl FrontendCatalogManager {
             backend,
             partition_manager,
             datanode_clients,
+            notifier: CatalogEventNotifier::default(),
+            insert_batch_options: InsertBatchOptions::default(),
+            watch_interval: DEFAULT_WATCH_INTERVAL,
+            tasks: Arc::new(Mutex::new(Vec::new())),
             dist_instance: None,
         }
     }
 
+    pub(crate) fn set_watch_interval(&mut self, watch_interval: Duration) {
+        self.watch_interval = watch_interval
+    }
+
+    /// Stops the background tasks started by [CatalogManager::start].
+    pub(crate) fn stop(&self) {
+        for task in self.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+
     pub(crate) fn set_dist_instance(&mut self, dist_instance: Arc<DistInstance>) {
         self.dist_instance = Some(dist_instance)
     }
 
+    pub(crate) fn set_insert_batch_options(&mut self, insert_batch_options: InsertBatchOptions) {
+        self.insert_batch_options = insert_batch_options
+    }
+
+    pub(crate) fn insert_batch_options(&self) -> InsertBatchOptions {
+        self.insert_batch_options.clone()
+    }
+
     pub(crate) fn backend(&self) -> KvBackendRef {
         self.backend.clone()
     }
@@ -96,7 +135,21 @@ impl FrontendCatalogManager {
 // as soon as it's stable: https://github.com/rust-lang/rust/issues/65991
 #[async_trait::async_trait]
 impl CatalogManager for FrontendCatalogManager {
+    /// Starts watching the tables created or dropped by other frontends, and invalidates the
+    /// cached table routes when any table changes.
     async fn start(&self) -> catalog::error::Result<()> {
+        let watcher = KvBackendWatcher::new(self.backend.clone(), self.notifier.clone())
+            .with_interval(self.watch_interval)
+            .start();
+
+        let partition_manager = self.partition_manager.clone();
+        let receiver = self.notifier.subscribe();
+        let invalidator =
+            common_runtime::spawn_bg(invalidate_table_routes(partition_manager, receiver));
+
+        self.tasks.lock().unwrap().extend([watcher, invalidator]);
+
+        info!("Frontend catalog manager started");
         Ok(())
     }
 
@@ -109,16 +162,43 @@ impl CatalogManager for FrontendCatalogManager {
     }
 
     // TODO(LFC): Handle the table caching in (de)register_table.
-    async fn register_table(&self, _request: RegisterTableRequest) -> CatalogResult<bool> {
+    async fn register_table(&self, request: RegisterTableRequest) -> CatalogResult<bool> {
+        bump_tables_version(&self.backend).await?;
+        self.notifier.notify(CatalogEvent::TableRegistered {
+            catalog: request.catalog,
+            schema: request.schema,
+            table_name: request.table_name,
+        });
         Ok(true)
     }
 
+    /// Deregisters a table whose route has been deleted from metasrv. Nothing is notified if
+    /// the table still exists in metasrv.
     async fn deregister_table(&self, request: DeregisterTableRequest) -> CatalogResult<bool> {
-        let table_name = TableName::new(request.catalog, request.schema, request.table_name);
+        let table_name = TableName::new(&request.catalog, &request.schema, &request.table_name);
         self.partition_manager
             .table_routes()
             .invalidate_table_route(&table_name)
             .await;
+        let table_global_key = TableGlobalKey {
+            catalog_name: request.catalog.clone(),
+            schema_name: request.schema.clone(),
+            table_name: request.table_name.clone(),
+        };
+        if self
+            .backend
+            .get(table_global_key.to_string().as_bytes())
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+        bump_tables_version(&self.backend).await?;
+        self.notifier.notify(CatalogEvent::TableDeregistered {
+            catalog: request.catalog,
+            schema: request.schema,
+            table_name: request.table_name,
+        });
         Ok(true)
     }
 
@@ -252,6 +332,7 @@ impl CatalogManager for FrontendCatalogManager {
                 backend: self.backend.clone(),
                 partition_manager: self.partition_manager.clone(),
                 datanode_clients: self.datanode_clients.clone(),
+                insert_batch_options: self.insert_batch_options.clone(),
             }) as Arc<_>
         }))
     }
@@ -283,16 +364,156 @@ impl CatalogManager for FrontendCatalogManager {
             .await
     }
 
+    async fn get_tables(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table_names: &[&str],
+    ) -> catalog::error::Result<Vec<Option<TableRef>>> {
+        let keys = table_names
+            .iter()
+            .map(|table_name| {
+                TableGlobalKey {
+                    catalog_name: catalog.to_string(),
+                    schema_name: schema.to_string(),
+                    table_name: table_name.to_string(),
+                }
+                .to_string()
+                .into_bytes()
+            })
+            .collect::<Vec<_>>();
+        let mut values = self
+            .backend
+            .batch_get(&keys)
+            .await?
+            .into_iter()
+            .map(|Kv(k, v)| (k, v))
+            .collect::<HashMap<_, _>>();
+
+        table_names
+            .iter()
+            .zip(keys.iter())
+            .map(|(table_name, key)| {
+                if catalog == DEFAULT_CATALOG_NAME
+                    && schema == DEFAULT_SCHEMA_NAME
+                    && *table_name == "numbers"
+                {
+                    return Ok(Some(Arc::new(NumbersTable::default()) as TableRef));
+                }
+                values
+                    .remove(key)
+                    .map(|value| {
+                        new_dist_table(
+                            TableName::new(catalog, schema, *table_name),
+                            value,
+                            &self.partition_manager,
+                            &self.datanode_clients,
+                            &self.backend,
+                            &self.insert_batch_options,
+                        )
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+
+    fn subscribe(&self) -> Option<CatalogEventReceiver> {
+        Some(self.notifier.subscribe())
+    }
+
+    async fn cluster_nodes(&self) -> CatalogResult<Vec<(ClusterNodeKey, ClusterNodeValue)>> {
+        list_cluster_nodes(&self.backend).await
+    }
+
+    async fn region_inconsistencies(
+        &self,
+    ) -> CatalogResult<Vec<(RegionInconsistencyKey, RegionInconsistencyValue)>> {
+        list_region_inconsistencies(&self.backend).await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+fn new_dist_table(
+    table_name: TableName,
+    table_global_value: Vec<u8>,
+    partition_manager: &PartitionRuleManagerRef,
+    datanode_clients: &Arc<DatanodeClients>,
+    backend: &KvBackendRef,
+    insert_batch_options: &InsertBatchOptions,
+) -> catalog::error::Result<TableRef> {
+    let v = TableGlobalValue::from_bytes(table_global_value).context(InvalidCatalogValueSnafu)?;
+    let table_info = Arc::new(
+        v.table_info
+            .try_into()
+            .context(catalog_err::InvalidTableInfoInCatalogSnafu)?,
+    );
+    Ok(Arc::new(DistTable::new(
+        table_name,
+        table_info,
+        partition_manager.clone(),
+        datanode_clients.clone(),
+        backend.clone(),
+        insert_batch_options.clone(),
+    )))
+}
+
+/// Invalidates the cached route of the tables changed in catalog, so that the stale routes of
+/// tables dropped and re-created elsewhere won't be used.
+async fn invalidate_table_routes(
+    partition_manager: PartitionRuleManagerRef,
+    mut receiver: CatalogEventReceiver,
+) {
+    let table_routes = partition_manager.table_routes();
+    loop {
+        let table_name = match receiver.recv().await {
+            Ok(CatalogEvent::TableRegistered {
+                catalog,
+                schema,
+                table_name,
+            })
+            | Ok(CatalogEvent::TableDeregistered {
+                catalog,
+                schema,
+                table_name,
+            })
+            | Ok(CatalogEvent::TableRenamed {
+                catalog,
+                schema,
+                table_name,
+                ..
+            }) => TableName::new(catalog, schema, table_name),
+            Ok(CatalogEvent::SchemaRegistered { .. }) => continue,
+            Err(RecvError::Lagged(n)) => {
+                warn!("Missed {n} catalog events, invalidate all cached table routes");
+                table_routes.invalidate_all();
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        table_routes.invalidate_table_route(&table_name).await;
+    }
+}
+
+/// Reports the nodes in the cluster running out of disk space.
+#[async_trait]
+impl HealthChecker for FrontendCatalogManager {
+    async fn warnings(&self) -> Vec<String> {
+        match list_cluster_nodes(&self.backend).await {
+            Ok(nodes) => disk_low_warnings(&nodes),
+            Err(e) => vec![format!("Failed to list the nodes in the cluster: {e}")],
+        }
+    }
+}
+
 pub struct FrontendCatalogProvider {
     catalog_name: String,
     backend: KvBackendRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_clients: Arc<DatanodeClients>,
+    insert_batch_options: InsertBatchOptions,
 }
 
 #[async_trait::async_trait]
@@ -331,6 +552,7 @@ impl CatalogProvider for FrontendCatalogProvider {
                 backend: self.backend.clone(),
                 partition_manager: self.partition_manager.clone(),
                 datanode_clients: self.datanode_clients.clone(),
+                insert_batch_options: self.insert_batch_options.clone(),
             })))
         } else {
             Ok(None)
@@ -344,6 +566,7 @@ pub struct FrontendSchemaProvider {
     backend: KvBackendRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_clients: Arc<DatanodeClients>,
+    insert_batch_options: InsertBatchOptions,
 }
 
 #[async_trait]
@@ -386,19 +609,14 @@ impl SchemaProvider for FrontendSchemaProvider {
             table_name: name.to_string(),
         };
         let Some(kv) = self.backend.get(table_global_key.to_string().as_bytes()).await? else { return Ok(None) };
-        let v = TableGlobalValue::from_bytes(kv.1).context(InvalidCatalogValueSnafu)?;
-        let table_info = Arc::new(
-            v.table_info
-                .try_into()
-                .context(catalog_err::InvalidTableInfoInCatalogSnafu)?,
-        );
-        let table = Arc::new(DistTable::new(
+        let table = new_dist_table(
             TableName::new(&self.catalog_name, &self.schema_name, name),
-            table_info,
-            self.partition_manager.clone(),
-            self.datanode_clients.clone(),
-            self.backend.clone(),
-        ));
+            kv.1,
+            &self.partition_manager,
+            &self.datanode_clients,
+            &self.backend,
+            &self.insert_batch_options,
+        )?;
         Ok(Some(table))
     }
 
@@ -0,0 +1,247 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loads the database aliases honored by the InfluxDB, OpenTSDB and Prometheus handlers
+//! from a mapping table, so an alias could be added or changed with SQL without restarting
+//! the frontend.
+//!
+//! An alias is changed by inserting a row with a later `ts`, the row with the latest `ts` of
+//! each alias wins.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_runtime::{RepeatedTask, TaskFunction};
+use common_telemetry::info;
+use datatypes::prelude::*;
+use serde::{Deserialize, Serialize};
+use servers::database_alias::{DatabaseAlias, DatabaseAliasesRef};
+use servers::query_handler::sql::SqlQueryHandler;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::ResultExt;
+
+use crate::error::{CollectRecordbatchSnafu, Error, Result, RuntimeResourceSnafu};
+use crate::instance::Instance;
+
+pub const DEFAULT_DATABASE_ALIAS_TABLE: &str = "database_aliases";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseAliasOptions {
+    /// Table in the default database holding the aliases.
+    pub table: String,
+    /// Interval to reload the aliases from the table.
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
+}
+
+impl Default for DatabaseAliasOptions {
+    fn default() -> Self {
+        Self {
+            table: DEFAULT_DATABASE_ALIAS_TABLE.to_string(),
+            refresh_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Runs a [RepeatedTask] reloading the aliases from the mapping table.
+pub struct DatabaseAliasLoader {
+    loader: Arc<LoadAliasesTask>,
+    task: RepeatedTask<Error>,
+}
+
+impl DatabaseAliasLoader {
+    pub fn new(
+        opts: &DatabaseAliasOptions,
+        instance: Arc<Instance>,
+        aliases: DatabaseAliasesRef,
+    ) -> Self {
+        let loader = Arc::new(LoadAliasesTask {
+            table: opts.table.clone(),
+            instance,
+            aliases,
+            query_ctx: QueryContext::arc(),
+        });
+        let task = RepeatedTask::new(opts.refresh_interval, loader.clone());
+        Self { loader, task }
+    }
+
+    /// Creates the mapping table if not exists and loads the aliases before the first
+    /// refresh, so requests are resolved with the aliases once the frontend is started.
+    pub async fn start(&self) -> Result<()> {
+        self.loader.init().await?;
+        self.loader.load().await?;
+        self.task
+            .start(common_runtime::bg_runtime())
+            .await
+            .context(RuntimeResourceSnafu)?;
+        info!(
+            "Database alias loader started with {} aliases",
+            self.loader.aliases.len()
+        );
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        if self.task.started() {
+            self.task.stop().await.context(RuntimeResourceSnafu)?;
+        }
+        Ok(())
+    }
+}
+
+struct LoadAliasesTask {
+    table: String,
+    instance: Arc<Instance>,
+    aliases: DatabaseAliasesRef,
+    query_ctx: QueryContextRef,
+}
+
+#[async_trait]
+impl TaskFunction<Error> for LoadAliasesTask {
+    async fn call(&self) -> Result<()> {
+        self.load().await
+    }
+
+    fn name(&self) -> &str {
+        "load-database-aliases"
+    }
+}
+
+impl LoadAliasesTask {
+    async fn init(&self) -> Result<()> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} (\
+                alias STRING, \
+                catalog_name STRING, \
+                schema_name STRING, \
+                ts TIMESTAMP TIME INDEX, \
+                PRIMARY KEY (alias))",
+            self.table
+        );
+        let _ = self.execute(&sql).await?;
+        Ok(())
+    }
+
+    /// Replaces the aliases with the rows of the mapping table. Rows with a null column
+    /// are ignored. The rows are read in the order of `ts`, so the latest row of an alias
+    /// overrides the older ones.
+    async fn load(&self) -> Result<()> {
+        let sql = format!(
+            "SELECT alias, catalog_name, schema_name FROM {} ORDER BY ts",
+            self.table
+        );
+        let batches = match self.execute(&sql).await? {
+            Output::Stream(stream) => RecordBatches::try_collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            Output::RecordBatches(batches) => batches,
+            Output::AffectedRows(_) => return Ok(()),
+        };
+
+        let mut aliases = HashMap::new();
+        for batch in batches.iter() {
+            for row in 0..batch.num_rows() {
+                let (Value::String(alias), Value::String(catalog), Value::String(schema)) = (
+                    batch.column(0).get(row),
+                    batch.column(1).get(row),
+                    batch.column(2).get(row),
+                ) else {
+                    continue;
+                };
+                let _ = aliases.insert(
+                    alias.as_utf8().to_string(),
+                    DatabaseAlias {
+                        catalog: catalog.as_utf8().to_string(),
+                        schema: schema.as_utf8().to_string(),
+                    },
+                );
+            }
+        }
+        self.aliases.replace(aliases);
+        Ok(())
+    }
+
+    async fn execute(&self, sql: &str) -> Result<Output> {
+        // A single statement always has a single output.
+        self.instance
+            .do_query(sql, self.query_ctx.clone())
+            .await
+            .remove(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use servers::database_alias::DatabaseAliases;
+
+    use super::*;
+    use crate::tests;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_load_database_aliases() {
+        let standalone = tests::create_standalone_instance("test_load_database_aliases").await;
+        let instance = standalone.instance.clone();
+        let aliases = Arc::new(DatabaseAliases::default());
+        let loader = DatabaseAliasLoader::new(
+            &DatabaseAliasOptions::default(),
+            instance.clone(),
+            aliases.clone(),
+        );
+        loader.start().await.unwrap();
+        assert!(aliases.is_empty());
+
+        let sql = "INSERT INTO database_aliases (alias, catalog_name, schema_name, ts) VALUES \
+            ('telegraf', 'greptime', 'metrics', 0), ('collectd', 'greptime', 'public', 0)";
+        let _ = instance
+            .do_query(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        loader.loader.load().await.unwrap();
+        assert_eq!(2, aliases.len());
+        assert_eq!(
+            Some(DatabaseAlias {
+                catalog: "greptime".to_string(),
+                schema: "metrics".to_string(),
+            }),
+            aliases.get("telegraf")
+        );
+        assert_eq!(
+            ("greptime".to_string(), "metrics".to_string()),
+            aliases.resolve_catalog_and_schema(None, "telegraf")
+        );
+
+        // Re-points the alias by a later row, the earlier row is kept in the table.
+        let sql = "INSERT INTO database_aliases (alias, catalog_name, schema_name, ts) VALUES \
+            ('telegraf', 'greptime', 'telegraf', 2000), ('telegraf', 'greptime', 'stale', 1000)";
+        let _ = instance
+            .do_query(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        loader.loader.load().await.unwrap();
+        assert_eq!(2, aliases.len());
+        assert_eq!(
+            ("greptime".to_string(), "telegraf".to_string()),
+            aliases.resolve_catalog_and_schema(None, "telegraf")
+        );
+
+        loader.stop().await.unwrap();
+    }
+}
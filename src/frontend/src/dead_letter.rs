@@ -0,0 +1,415 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Writes the rows rejected by the insert path of the ingestion protocols, e.g. because
+//! their types mismatch the table or a not null column is missing, to a dead-letter
+//! table in the same database, instead of failing the whole batch. The other rows of
+//! the batch are still inserted.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use api::helper::{push_vals, ColumnDataTypeWrapper};
+use api::v1::{Column, ColumnDataType, InsertRequest};
+use common_base::BitVec;
+use common_error::prelude::{ErrorExt, StatusCode};
+use common_grpc::writer::{LinesWriter, Precision};
+use datatypes::schema::Schema;
+use datatypes::vectors::BooleanVector;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::error::{Error, IntoVectorsSnafu, Result, ToTableInsertRequestSnafu, WriteLinesSnafu};
+
+pub const DEFAULT_DEAD_LETTER_TABLE: &str = "greptime_dead_letter";
+
+/// Columns of the dead-letter table.
+const TS_COLUMN: &str = "ts";
+const TABLE_COLUMN: &str = "table_name";
+const PAYLOAD_COLUMN: &str = "payload";
+const ERROR_COLUMN: &str = "error";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeadLetterOptions {
+    /// Table in each database to write the rejected rows to.
+    pub table: String,
+}
+
+impl Default for DeadLetterOptions {
+    fn default() -> Self {
+        Self {
+            table: DEFAULT_DEAD_LETTER_TABLE.to_string(),
+        }
+    }
+}
+
+/// Returns true if creating or altering the table on insertion fails because the columns
+/// of the request are invalid, e.g. they are duplicated or of unknown types, or the table
+/// holds another metric, rather than because of the cluster, so retrying it won't succeed.
+pub(crate) fn is_rejected(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::BuildCreateExprOnInsertion { .. }
+            | Error::FindNewColumnsOnInsertion { .. }
+            | Error::MetricNameConflict { .. }
+    ) && error.status_code() == StatusCode::InvalidArguments
+}
+
+/// Splits the rows of `request` rejected by the table `schema` out of it.
+///
+/// Returns the request of the accepted rows, `None` if all rows are rejected, and the
+/// request writing the rejected rows to the dead-letter table, `None` if no row is
+/// rejected. The `request` is returned as is if no row is rejected.
+pub(crate) fn split_rejected(
+    options: &DeadLetterOptions,
+    schema: &Schema,
+    request: InsertRequest,
+) -> Result<(Option<InsertRequest>, Option<InsertRequest>)> {
+    let reasons = rejected_rows(schema, &request);
+    if reasons.iter().all(Option::is_none) {
+        return Ok((Some(request), None));
+    }
+
+    let dead_letter = to_dead_letter_request(options, &request, &reasons)?;
+    let accepted = retain_rows(request, &reasons)?;
+    Ok((accepted, Some(dead_letter)))
+}
+
+/// Returns the reason why each row of the `request` is rejected by the table `schema`,
+/// `None` if it's accepted. The rules follow the checks of the datanodes on insertion.
+fn rejected_rows(schema: &Schema, request: &InsertRequest) -> Vec<Option<String>> {
+    let mut reasons = vec![None; request.row_count as usize];
+    let mut reject = |rows: &dyn Fn(usize) -> bool, reason: &dyn Fn() -> String| {
+        for (i, row_reason) in reasons.iter_mut().enumerate() {
+            if row_reason.is_none() && rows(i) {
+                *row_reason = Some(reason());
+            }
+        }
+    };
+
+    for column in &request.columns {
+        let Some(column_schema) = schema.column_schema_by_name(&column.column_name) else {
+            // New columns are added to the table before insertion.
+            continue;
+        };
+        if column_schema.computed_expr().is_some() {
+            continue;
+        }
+        let Ok(expected) = ColumnDataTypeWrapper::try_from(column_schema.data_type.clone()) else {
+            continue;
+        };
+        if expected.datatype() as i32 != column.datatype {
+            reject(&|_| true, &|| {
+                format!(
+                    "Type mismatch of column '{}', expect: {:?}, actual: {:?}",
+                    column.column_name,
+                    expected.datatype(),
+                    ColumnDataType::from_i32(column.datatype),
+                )
+            });
+        }
+    }
+
+    for column_schema in schema.column_schemas() {
+        if column_schema.is_nullable()
+            || column_schema.default_constraint().is_some()
+            || column_schema.computed_expr().is_some()
+        {
+            continue;
+        }
+        let reason = || {
+            format!(
+                "Expecting insert data to be presented on a not null or no default value column '{}'.",
+                column_schema.name
+            )
+        };
+        match request
+            .columns
+            .iter()
+            .find(|column| column.column_name == column_schema.name)
+        {
+            Some(column) => {
+                let null_mask = BitVec::from_slice(&column.null_mask);
+                reject(
+                    &|i| null_mask.get(i).map(|is_null| *is_null).unwrap_or(false),
+                    &reason,
+                );
+            }
+            None => reject(&|_| true, &reason),
+        }
+    }
+
+    reasons
+}
+
+/// Keeps the rows of `request` that are not rejected, returns `None` if all rows are
+/// rejected.
+fn retain_rows(
+    request: InsertRequest,
+    reasons: &[Option<String>],
+) -> Result<Option<InsertRequest>> {
+    let row_count = reasons.iter().filter(|reason| reason.is_none()).count();
+    if row_count == 0 {
+        return Ok(None);
+    }
+
+    let filter = BooleanVector::from(
+        reasons
+            .iter()
+            .map(|reason| reason.is_none())
+            .collect::<Vec<_>>(),
+    );
+    let columns = request
+        .columns
+        .iter()
+        .map(|column| {
+            let vector = common_grpc_expr::column_to_vector(column, request.row_count)
+                .context(ToTableInsertRequestSnafu)?;
+            let vector = vector.filter(&filter).context(IntoVectorsSnafu)?;
+            let mut retained = Column {
+                column_name: column.column_name.clone(),
+                semantic_type: column.semantic_type,
+                datatype: column.datatype,
+                ..Default::default()
+            };
+            push_vals(&mut retained, 0, vector);
+            Ok(retained)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(InsertRequest {
+        columns,
+        row_count: row_count as u32,
+        ..request
+    }))
+}
+
+/// Builds the request to write the rows of `request` with errors to the dead-letter
+/// table along with their errors, `errors` are indexed by rows. Rows are written as
+/// JSON objects.
+pub(crate) fn to_dead_letter_request(
+    options: &DeadLetterOptions,
+    request: &InsertRequest,
+    errors: &[Option<String>],
+) -> Result<InsertRequest> {
+    let payloads = rows_to_json(request)
+        .unwrap_or_else(|| vec![format!("{:?}", request.columns); request.row_count as usize]);
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+
+    let mut writer = LinesWriter::with_lines(errors.len());
+    for (payload, error) in payloads.iter().zip(errors) {
+        let Some(error) = error else {
+            continue;
+        };
+        writer
+            .write_ts(TS_COLUMN, (ts, Precision::Millisecond))
+            .context(WriteLinesSnafu)?;
+        writer
+            .write_tag(TABLE_COLUMN, &request.table_name)
+            .context(WriteLinesSnafu)?;
+        writer
+            .write_string(PAYLOAD_COLUMN, payload)
+            .context(WriteLinesSnafu)?;
+        writer
+            .write_string(ERROR_COLUMN, error)
+            .context(WriteLinesSnafu)?;
+        writer.commit();
+    }
+
+    let (columns, row_count) = writer.finish();
+    Ok(InsertRequest {
+        table_name: options.table.clone(),
+        region_number: 0,
+        columns,
+        row_count,
+    })
+}
+
+/// Renders the rows of the `request` as JSON objects, returns `None` if the columns
+/// can't be decoded.
+fn rows_to_json(request: &InsertRequest) -> Option<Vec<String>> {
+    let vectors = request
+        .columns
+        .iter()
+        .map(|column| {
+            common_grpc_expr::column_to_vector(column, request.row_count)
+                .ok()
+                .map(|vector| (&column.column_name, vector))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let rows = (0..request.row_count as usize)
+        .map(|i| {
+            let row = vectors
+                .iter()
+                .map(|(name, vector)| {
+                    let value = serde_json::Value::try_from(vector.get(i))
+                        .unwrap_or(serde_json::Value::Null);
+                    (name.to_string(), value)
+                })
+                .collect::<serde_json::Map<_, _>>();
+            serde_json::Value::Object(row).to_string()
+        })
+        .collect();
+    Some(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::column::{SemanticType, Values};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+
+    use super::*;
+    use crate::error::InvalidInsertRequestSnafu;
+
+    fn new_request(usage_datatype: ColumnDataType, usage_values: Values) -> InsertRequest {
+        InsertRequest {
+            table_name: "cpu".to_string(),
+            region_number: 0,
+            columns: vec![
+                Column {
+                    column_name: "host".to_string(),
+                    semantic_type: SemanticType::Tag as i32,
+                    values: Some(Values {
+                        string_values: vec!["a".to_string(), "b".to_string()],
+                        ..Default::default()
+                    }),
+                    datatype: ColumnDataType::String as i32,
+                    ..Default::default()
+                },
+                Column {
+                    column_name: "usage".to_string(),
+                    semantic_type: SemanticType::Field as i32,
+                    values: Some(usage_values),
+                    null_mask: vec![2],
+                    datatype: usage_datatype as i32,
+                },
+            ],
+            row_count: 2,
+        }
+    }
+
+    fn new_schema() -> Schema {
+        Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("usage", ConcreteDataType::float64_datatype(), false),
+        ])
+    }
+
+    fn column_values(request: &InsertRequest, name: &str) -> Values {
+        request
+            .columns
+            .iter()
+            .find(|column| column.column_name == name)
+            .unwrap()
+            .values
+            .clone()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_split_rejected_rows() {
+        let request = new_request(
+            ColumnDataType::Float64,
+            Values {
+                f64_values: vec![0.5],
+                ..Default::default()
+            },
+        );
+        let options = DeadLetterOptions::default();
+        let (accepted, dead_letter) = split_rejected(&options, &new_schema(), request).unwrap();
+
+        // Only the row with null usage is rejected.
+        let accepted = accepted.unwrap();
+        assert_eq!(1, accepted.row_count);
+        assert_eq!(
+            vec!["a".to_string()],
+            column_values(&accepted, "host").string_values
+        );
+        assert_eq!(vec![0.5], column_values(&accepted, "usage").f64_values);
+
+        let dead_letter = dead_letter.unwrap();
+        assert_eq!(DEFAULT_DEAD_LETTER_TABLE, dead_letter.table_name);
+        assert_eq!(1, dead_letter.row_count);
+        assert_eq!(
+            vec!["cpu".to_string()],
+            column_values(&dead_letter, TABLE_COLUMN).string_values
+        );
+        assert_eq!(
+            vec![r#"{"host":"b","usage":null}"#.to_string()],
+            column_values(&dead_letter, PAYLOAD_COLUMN).string_values
+        );
+        assert!(column_values(&dead_letter, ERROR_COLUMN).string_values[0]
+            .contains("not null or no default value column 'usage'"));
+    }
+
+    #[test]
+    fn test_split_rejected_type_mismatch() {
+        let request = new_request(
+            ColumnDataType::Int64,
+            Values {
+                i64_values: vec![1],
+                ..Default::default()
+            },
+        );
+        let options = DeadLetterOptions::default();
+        let (accepted, dead_letter) = split_rejected(&options, &new_schema(), request).unwrap();
+        assert!(accepted.is_none());
+
+        let dead_letter = dead_letter.unwrap();
+        assert_eq!(2, dead_letter.row_count);
+        assert_eq!(
+            vec![
+                r#"{"host":"a","usage":1}"#.to_string(),
+                r#"{"host":"b","usage":null}"#.to_string(),
+            ],
+            column_values(&dead_letter, PAYLOAD_COLUMN).string_values
+        );
+        assert!(column_values(&dead_letter, ERROR_COLUMN).string_values[0]
+            .contains("Type mismatch of column 'usage'"));
+    }
+
+    #[test]
+    fn test_split_accepted_rows() {
+        let request = new_request(
+            ColumnDataType::Float64,
+            Values {
+                f64_values: vec![0.5],
+                ..Default::default()
+            },
+        );
+        let schema = Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("usage", ConcreteDataType::float64_datatype(), true),
+        ]);
+        let options = DeadLetterOptions::default();
+        let (accepted, dead_letter) = split_rejected(&options, &schema, request.clone()).unwrap();
+        assert_eq!(Some(request), accepted);
+        assert!(dead_letter.is_none());
+    }
+
+    #[test]
+    fn test_is_rejected() {
+        // Rows violating the schema are split out before insertion.
+        let error = InvalidInsertRequestSnafu {
+            reason: "column usage is not null",
+        }
+        .build();
+        assert!(!is_rejected(&error));
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::sync::Arc;
 
 use common_error::prelude::*;
 use datafusion::parquet;
@@ -260,6 +261,45 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Failed to migrate region {} of table {}, reason: {}",
+        region_number,
+        table_name,
+        reason
+    ))]
+    InvalidRegionMigration {
+        table_name: String,
+        region_number: u32,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Region {} of table {} is not served by the datanode of the source table {}",
+        region_number,
+        table_name,
+        source_table_name
+    ))]
+    ClonePlacementMismatch {
+        table_name: String,
+        source_table_name: String,
+        region_number: u32,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Timeout waiting for region {} of table {} to be served by datanode {}",
+        region_number,
+        table_name,
+        to_peer
+    ))]
+    MigrateRegionTimeout {
+        table_name: String,
+        region_number: u32,
+        to_peer: u64,
+        location: Location,
+    },
+
     #[snafu(display("Cannot find primary key column by name: {}", msg))]
     PrimaryKeyNotFound { msg: String, location: Location },
 
@@ -287,6 +327,18 @@ pub enum Error {
         source: query::error::Error,
     },
 
+    #[snafu(display("Failed to collect recordbatch stream, source: {}", source))]
+    CollectRecordbatch {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Failed to create recordbatch, source: {}", source))]
+    CreateRecordbatch {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
     #[snafu(display("Failed to build DataFusion logical plan, source: {}", source))]
     BuildDfLogicalPlan {
         source: datafusion_common::DataFusionError,
@@ -361,6 +413,21 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("ADMIN statements are not allowed"))]
+    AdminStatementDenied { location: Location },
+
+    #[snafu(display("User {} is not allowed to run ADMIN statements", username))]
+    AdminPrivilegeRequired {
+        username: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to create table {} on demand, source: {}", table_name, source))]
+    CreateTableOnDemand {
+        table_name: String,
+        source: Arc<Error>,
+    },
+
     #[snafu(display(
         "Failed to deserialize partition in meta to partition def, source: {}",
         source
@@ -378,6 +445,12 @@ pub enum Error {
         source: servers::error::Error,
     },
 
+    #[snafu(display("Failed to evaluate computed columns, source: {}", source))]
+    EvalComputedColumns {
+        #[snafu(backtrace)]
+        source: query::error::Error,
+    },
+
     #[snafu(display("Failed to describe schema for given statement, source: {}", source))]
     DescribeStatement {
         #[snafu(backtrace)]
@@ -429,6 +502,12 @@ pub enum Error {
         source: common_query::error::Error,
     },
 
+    #[snafu(display("Failed to merge partitions of table scan, source: {}", source))]
+    MergeTableScan {
+        source: datafusion::error::DataFusionError,
+        location: Location,
+    },
+
     #[snafu(display("Failed to parse data source url, source: {}", source))]
     ParseUrl {
         #[snafu(backtrace)]
@@ -523,6 +602,17 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Failed to parse string to timestamp, string: {}, source: {}",
+        raw,
+        source
+    ))]
+    ParseTimestamp {
+        raw: String,
+        #[snafu(backtrace)]
+        source: common_time::error::Error,
+    },
+
     #[snafu(display("Failed to encode object into json, source: {}", source))]
     EncodeJson {
         source: serde_json::error::Error,
@@ -534,6 +624,222 @@ pub enum Error {
         #[snafu(backtrace)]
         source: query::error::Error,
     },
+
+    #[snafu(display("Failed to prepare remote table: {}", source))]
+    PrepareRemoteTable {
+        #[snafu(backtrace)]
+        source: query::error::Error,
+    },
+
+    #[snafu(display("Failed to read rule file {}, source: {}", path, source))]
+    ReadRuleFile {
+        path: String,
+        source: std::io::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to parse rule file {}, source: {}", path, source))]
+    ParseRuleFile {
+        path: String,
+        source: serde_yaml::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid rule in group {}: {}", group, reason))]
+    InvalidRule {
+        group: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to write result of recording rule {}, source: {}",
+        rule,
+        source
+    ))]
+    WriteRuleResult {
+        rule: String,
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
+    #[snafu(display("Invalid Alertmanager url {}, source: {}", url, source))]
+    InvalidAlertmanagerUrl {
+        url: String,
+        source: hyper::http::uri::InvalidUri,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to send alerts to {}, source: {}", url, source))]
+    SendAlerts {
+        url: String,
+        source: hyper::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Alertmanager {} rejected alerts with status {}", url, status))]
+    AlertmanagerRejected {
+        url: String,
+        status: u16,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid scrape url {}, source: {}", url, source))]
+    InvalidScrapeUrl {
+        url: String,
+        source: hyper::http::uri::InvalidUri,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to scrape {}, source: {}", url, source))]
+    ScrapeTarget {
+        url: String,
+        source: hyper::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Timeout scraping {}", url))]
+    ScrapeTimeout { url: String, location: Location },
+
+    #[snafu(display("Scrape target {} responded with status {}", url, status))]
+    ScrapeStatus {
+        url: String,
+        status: u16,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid metrics exposition at line {}: {}", line, content))]
+    ParseExposition {
+        line: usize,
+        content: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to write samples scraped from {}, source: {}", url, source))]
+    WriteScrapedSamples {
+        url: String,
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
+    #[snafu(display("Kafka client error, source: {}", source))]
+    Kafka {
+        source: rskafka::client::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Kafka topic not found: {}", topic))]
+    KafkaTopicNotFound { topic: String, location: Location },
+
+    #[snafu(display("Failed to decode JSON message, source: {}", source))]
+    DecodeJsonMessage {
+        source: serde_json::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to decode protobuf message, source: {}", source))]
+    DecodeProtobufMessage {
+        source: prost::DecodeError,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to decode InfluxDB line protocol message, source: {}", source))]
+    DecodeInfluxLineMessage {
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
+    #[snafu(display("Invalid Kafka message: {}", reason))]
+    InvalidKafkaMessage { reason: String, location: Location },
+
+    #[snafu(display("Invalid row filter of table {}: {}", table, filter))]
+    InvalidRowFilter {
+        table: String,
+        filter: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to write lines, source: {}", source))]
+    WriteLines {
+        #[snafu(backtrace)]
+        source: common_grpc::error::Error,
+    },
+
+    #[snafu(display("Invalid connection to replay into, reason: {}", reason))]
+    InvalidReplayConnection { reason: String, location: Location },
+
+    #[snafu(display(
+        "Failed to replay into remote table {}, source: {}",
+        table_name,
+        source
+    ))]
+    ReplayRemoteTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: client::Error,
+    },
+
+    #[snafu(display(
+        "Writes to table {} are throttled, memtables of the table are full",
+        table_name
+    ))]
+    TableWriteThrottled {
+        table_name: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to match table names by pattern {}, source: {}",
+        pattern,
+        source
+    ))]
+    MatchTableNames {
+        pattern: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Parameter ${} of TQL is not bound", name))]
+    UnboundTqlParameter { name: String, location: Location },
+
+    #[snafu(display(
+        "Metric {} is normalized to table {}, which holds metric {}",
+        metric_name,
+        table_name,
+        table_metric_name
+    ))]
+    MetricNameConflict {
+        metric_name: String,
+        table_name: String,
+        table_metric_name: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to insert {} of {} batches into datanodes, {} rows are inserted, source: {}",
+        failed,
+        total,
+        affected_rows,
+        source
+    ))]
+    InsertBatches {
+        failed: usize,
+        total: usize,
+        affected_rows: usize,
+        source: Box<Error>,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to apply ingest rule to the insertion of table {}, source: {}",
+        table_name,
+        source
+    ))]
+    ApplyIngestRule {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -543,6 +849,9 @@ impl ErrorExt for Error {
         match self {
             Error::ParseAddr { .. }
             | Error::InvalidSql { .. }
+            | Error::ParseTimestamp { .. }
+            | Error::InvalidReplayConnection { .. }
+            | Error::InvalidRegionMigration { .. }
             | Error::InvalidInsertRequest { .. }
             | Error::ColumnValuesNumberMismatch { .. }
             | Error::IllegalPrimaryKeysDef { .. }
@@ -556,14 +865,34 @@ impl ErrorExt for Error {
             | Error::BuildRegex { .. }
             | Error::InvalidSchema { .. }
             | Error::PrepareImmutableTable { .. }
-            | Error::BuildCsvConfig { .. } => StatusCode::InvalidArguments,
-
-            Error::NotSupported { .. } => StatusCode::Unsupported,
+            | Error::PrepareRemoteTable { .. }
+            | Error::BuildCsvConfig { .. }
+            | Error::ReadRuleFile { .. }
+            | Error::ParseRuleFile { .. }
+            | Error::InvalidRule { .. }
+            | Error::InvalidAlertmanagerUrl { .. }
+            | Error::InvalidScrapeUrl { .. }
+            | Error::ParseExposition { .. }
+            | Error::KafkaTopicNotFound { .. }
+            | Error::DecodeJsonMessage { .. }
+            | Error::DecodeProtobufMessage { .. }
+            | Error::InvalidKafkaMessage { .. }
+            | Error::InvalidRowFilter { .. }
+            | Error::UnboundTqlParameter { .. }
+            | Error::MetricNameConflict { .. } => StatusCode::InvalidArguments,
+
+            Error::NotSupported { .. } | Error::ClonePlacementMismatch { .. } => {
+                StatusCode::Unsupported
+            }
 
             Error::RuntimeResource { source, .. } => source.status_code(),
             Error::ExecutePromql { source, .. } => source.status_code(),
 
             Error::SqlExecIntercepted { source, .. } => source.status_code(),
+            Error::AdminStatementDenied { .. } | Error::AdminPrivilegeRequired { .. } => {
+                StatusCode::AccessDenied
+            }
+            Error::CreateTableOnDemand { source, .. } => source.status_code(),
             Error::StartServer { source, .. } => source.status_code(),
             Error::ShutdownServer { source, .. } => source.status_code(),
 
@@ -581,7 +910,8 @@ impl ErrorExt for Error {
 
             Error::ConvertColumnDefaultConstraint { source, .. }
             | Error::CreateTableInfo { source }
-            | Error::IntoVectors { source } => source.status_code(),
+            | Error::IntoVectors { source }
+            | Error::MatchTableNames { source, .. } => source.status_code(),
 
             Error::RequestDatanode { source } => source.status_code(),
 
@@ -624,7 +954,12 @@ impl ErrorExt for Error {
             | Error::PlanStatement { source }
             | Error::ParseQuery { source }
             | Error::ExecLogicalPlan { source }
-            | Error::DescribeStatement { source } => source.status_code(),
+            | Error::DescribeStatement { source }
+            | Error::EvalComputedColumns { source } => source.status_code(),
+
+            Error::CollectRecordbatch { source } | Error::CreateRecordbatch { source } => {
+                source.status_code()
+            }
 
             Error::AlterExprToRequest { source, .. } => source.status_code(),
             Error::LeaderNotFound { .. } => StatusCode::StorageUnavailable,
@@ -641,6 +976,7 @@ impl ErrorExt for Error {
             Error::StartScriptManager { source } => source.status_code(),
 
             Error::TableScanExec { source, .. } => source.status_code(),
+            Error::MergeTableScan { .. } => StatusCode::EngineExecuteQuery,
 
             Error::ReadObject { .. } | Error::ReadParquet { .. } => StatusCode::StorageUnavailable,
 
@@ -649,6 +985,24 @@ impl ErrorExt for Error {
             | Error::BuildBackend { source } => source.status_code(),
 
             Error::WriteParquet { source, .. } => source.status_code(),
+
+            Error::WriteRuleResult { source, .. } => source.status_code(),
+            Error::SendAlerts { .. } | Error::AlertmanagerRejected { .. } => StatusCode::Internal,
+
+            Error::WriteScrapedSamples { source, .. } => source.status_code(),
+            Error::ScrapeTarget { .. }
+            | Error::ScrapeStatus { .. }
+            | Error::ScrapeTimeout { .. }
+            | Error::MigrateRegionTimeout { .. } => StatusCode::Internal,
+
+            Error::Kafka { .. } | Error::TableWriteThrottled { .. } => {
+                StatusCode::StorageUnavailable
+            }
+            Error::DecodeInfluxLineMessage { source } => source.status_code(),
+            Error::WriteLines { source } => source.status_code(),
+            Error::ReplayRemoteTable { source, .. } => source.status_code(),
+            Error::InsertBatches { source, .. } => source.status_code(),
+            Error::ApplyIngestRule { source, .. } => source.status_code(),
         }
     }
 
@@ -659,6 +1013,6 @@ impl ErrorExt for Error {
 
 impl From<Error> for tonic::Status {
     fn from(err: Error) -> Self {
-        tonic::Status::new(tonic::Code::Internal, err.to_string())
+        servers::error::status_from_error(&err)
     }
 }
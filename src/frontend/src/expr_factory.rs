@@ -17,11 +17,13 @@ use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
 use api::v1::{Column, ColumnDataType, CreateTableExpr};
+use common_catalog::consts::REMOTE_TABLE_ENGINE;
 use common_error::prelude::BoxedError;
 use datanode::instance::sql::table_idents_to_full_name;
 use datatypes::schema::ColumnSchema;
 use file_table_engine::table::immutable::ImmutableFileTableOptions;
-use query::sql::prepare_immutable_file_table_files_and_schema;
+use query::sql::{prepare_immutable_file_table_files_and_schema, prepare_remote_table_schema};
+use serde::{Deserialize, Serialize};
 use session::context::QueryContextRef;
 use snafu::{ensure, ResultExt};
 use sql::ast::{ColumnDef, ColumnOption, TableConstraint};
@@ -50,8 +52,74 @@ pub trait CreateExprFactory {
     ) -> crate::error::Result<CreateTableExpr>;
 }
 
-#[derive(Debug)]
-pub struct DefaultCreateExprFactory;
+/// Table options applied to the tables implicitly created on insertion, e.g. by the
+/// ingestion protocols, into the matched catalog and schema.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableDefaultsOptions {
+    /// Catalog to apply the options to, all catalogs if not set.
+    pub catalog: Option<String>,
+    /// Schema to apply the options to, all schemas if not set.
+    pub schema: Option<String>,
+    /// Table options like `ttl`, `append_mode` or `compaction_time_window`.
+    pub options: HashMap<String, String>,
+}
+
+impl TableDefaultsOptions {
+    fn matches(&self, catalog_name: &str, schema_name: &str) -> bool {
+        self.catalog.as_ref().map_or(true, |c| c == catalog_name)
+            && self.schema.as_ref().map_or(true, |s| s == schema_name)
+    }
+
+    /// The options of more specific entries override the ones of less specific entries.
+    fn specificity(&self) -> (bool, bool) {
+        (self.catalog.is_some(), self.schema.is_some())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DefaultCreateExprFactory {
+    table_defaults: Vec<TableDefaultsOptions>,
+}
+
+impl DefaultCreateExprFactory {
+    pub fn try_new(table_defaults: Vec<TableDefaultsOptions>) -> Result<Self> {
+        let table_defaults = table_defaults
+            .into_iter()
+            .map(|mut defaults| {
+                defaults.options = defaults
+                    .options
+                    .into_iter()
+                    .map(|(k, v)| (k.to_lowercase(), v))
+                    .collect();
+                TableOptions::try_from(&defaults.options)
+                    .context(error::UnrecognizedTableOptionSnafu)?;
+                Ok(defaults)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { table_defaults })
+    }
+
+    /// Returns the default table options of the tables created in the schema.
+    fn default_table_options(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+    ) -> HashMap<String, String> {
+        let mut matched = self
+            .table_defaults
+            .iter()
+            .filter(|defaults| defaults.matches(catalog_name, schema_name))
+            .collect::<Vec<_>>();
+        matched.sort_by_key(|defaults| defaults.specificity());
+
+        let mut options = HashMap::new();
+        for defaults in matched {
+            options.extend(defaults.options.clone());
+        }
+        options
+    }
+}
 
 #[async_trait::async_trait]
 impl CreateExprFactory for DefaultCreateExprFactory {
@@ -64,7 +132,7 @@ impl CreateExprFactory for DefaultCreateExprFactory {
         engine: &str,
     ) -> Result<CreateTableExpr> {
         let table_id = None;
-        let create_expr = common_grpc_expr::build_create_expr_from_insertion(
+        let mut create_expr = common_grpc_expr::build_create_expr_from_insertion(
             catalog_name,
             schema_name,
             table_id,
@@ -74,6 +142,10 @@ impl CreateExprFactory for DefaultCreateExprFactory {
         )
         .context(BuildCreateExprOnInsertionSnafu)?;
 
+        for (key, value) in self.default_table_options(catalog_name, schema_name) {
+            create_expr.table_options.entry(key).or_insert(value);
+        }
+
         Ok(create_expr)
     }
 }
@@ -89,15 +161,22 @@ pub(crate) async fn create_external_expr(
 
     let mut options = create.options;
 
-    let (files, schema) = prepare_immutable_file_table_files_and_schema(&options, &create.columns)
-        .await
-        .context(error::PrepareImmutableTableSnafu)?;
+    let schema = if create.engine == REMOTE_TABLE_ENGINE {
+        prepare_remote_table_schema(&table_name, &create.columns)
+            .context(error::PrepareRemoteTableSnafu)?
+    } else {
+        let (files, schema) =
+            prepare_immutable_file_table_files_and_schema(&options, &create.columns)
+                .await
+                .context(error::PrepareImmutableTableSnafu)?;
 
-    let meta = ImmutableFileTableOptions { files };
-    options.insert(
-        IMMUTABLE_TABLE_META_KEY.to_string(),
-        serde_json::to_string(&meta).context(error::EncodeJsonSnafu)?,
-    );
+        let meta = ImmutableFileTableOptions { files };
+        options.insert(
+            IMMUTABLE_TABLE_META_KEY.to_string(),
+            serde_json::to_string(&meta).context(error::EncodeJsonSnafu)?,
+        );
+        schema
+    };
 
     let expr = CreateTableExpr {
         catalog_name,
@@ -117,10 +196,7 @@ pub(crate) async fn create_external_expr(
 }
 
 /// Convert `CreateTable` statement to `CreateExpr` gRPC request.
-pub(crate) fn create_to_expr(
-    create: &CreateTable,
-    query_ctx: QueryContextRef,
-) -> Result<CreateTableExpr> {
+pub fn create_to_expr(create: &CreateTable, query_ctx: QueryContextRef) -> Result<CreateTableExpr> {
     let (catalog_name, schema_name, table_name) =
         table_idents_to_full_name(&create.name, query_ctx)
             .map_err(BoxedError::new)
@@ -298,4 +374,58 @@ mod tests {
             expr.table_options.get("write_buffer_size").unwrap()
         );
     }
+
+    fn table_defaults(
+        catalog: Option<&str>,
+        schema: Option<&str>,
+        options: &[(&str, &str)],
+    ) -> TableDefaultsOptions {
+        TableDefaultsOptions {
+            catalog: catalog.map(|c| c.to_string()),
+            schema: schema.map(|s| s.to_string()),
+            options: options
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_default_table_options() {
+        let factory = DefaultCreateExprFactory::try_new(vec![
+            table_defaults(Some("greptime"), Some("tenant"), &[("ttl", "1d")]),
+            table_defaults(None, None, &[("TTL", "7d"), ("append_mode", "true")]),
+            table_defaults(
+                Some("greptime"),
+                None,
+                &[("compaction_time_window", "3600")],
+            ),
+        ])
+        .unwrap();
+
+        let options = factory.default_table_options("greptime", "tenant");
+        assert_eq!(3, options.len());
+        assert_eq!("1d", options.get("ttl").unwrap());
+        assert_eq!("true", options.get("append_mode").unwrap());
+        assert_eq!("3600", options.get("compaction_time_window").unwrap());
+
+        let options = factory.default_table_options("greptime", "public");
+        assert_eq!("7d", options.get("ttl").unwrap());
+        assert_eq!("3600", options.get("compaction_time_window").unwrap());
+
+        let options = factory.default_table_options("other", "tenant");
+        assert_eq!(2, options.len());
+        assert_eq!("7d", options.get("ttl").unwrap());
+        assert!(!options.contains_key("compaction_time_window"));
+    }
+
+    #[test]
+    fn test_invalid_table_defaults() {
+        let result = DefaultCreateExprFactory::try_new(vec![table_defaults(
+            None,
+            None,
+            &[("compaction_time_window", "1h")],
+        )]);
+        assert!(result.is_err());
+    }
 }
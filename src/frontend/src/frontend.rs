@@ -11,25 +11,37 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::time::Duration;
 
+use catalog::notifier::DEFAULT_WATCH_INTERVAL;
 use common_telemetry::logging::LoggingOptions;
 use meta_client::MetaClientOptions;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
 use servers::Mode;
 
+use crate::database_alias::DatabaseAliasOptions;
+use crate::dead_letter::DeadLetterOptions;
+use crate::expr_factory::TableDefaultsOptions;
 use crate::grpc::GrpcOptions;
 use crate::influxdb::InfluxdbOptions;
+use crate::insert_batch::InsertBatchOptions;
+use crate::kafka::KafkaOptions;
 use crate::mysql::MysqlOptions;
 use crate::opentsdb::OpentsdbOptions;
 use crate::postgres::PostgresOptions;
 use crate::prom::PromOptions;
 use crate::prometheus::PrometheusOptions;
+use crate::rule::RuleOptions;
+use crate::scrape::ScrapeOptions;
+use crate::table_name::TableNameNormalization;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct FrontendOptions {
     pub mode: Mode,
+    pub table_defaults: Vec<TableDefaultsOptions>,
+    pub table_name_normalization: TableNameNormalization,
     pub http_options: Option<HttpOptions>,
     pub grpc_options: Option<GrpcOptions>,
     pub mysql_options: Option<MysqlOptions>,
@@ -38,6 +50,16 @@ pub struct FrontendOptions {
     pub influxdb_options: Option<InfluxdbOptions>,
     pub prometheus_options: Option<PrometheusOptions>,
     pub prom_options: Option<PromOptions>,
+    pub rule_options: Option<RuleOptions>,
+    pub scrape_options: Option<ScrapeOptions>,
+    pub kafka_options: Option<KafkaOptions>,
+    pub dead_letter_options: Option<DeadLetterOptions>,
+    pub database_alias_options: Option<DatabaseAliasOptions>,
+    pub insert_batch_options: InsertBatchOptions,
+    /// Interval of checking the tables created or dropped by other frontends, in distributed
+    /// mode. Only used if the changes can't be watched from the catalog backend.
+    #[serde(with = "humantime_serde")]
+    pub catalog_watch_interval: Duration,
     pub meta_client_options: Option<MetaClientOptions>,
     pub logging: LoggingOptions,
 }
@@ -46,6 +68,8 @@ impl Default for FrontendOptions {
     fn default() -> Self {
         Self {
             mode: Mode::Standalone,
+            table_defaults: vec![],
+            table_name_normalization: TableNameNormalization::default(),
             http_options: Some(HttpOptions::default()),
             grpc_options: Some(GrpcOptions::default()),
             mysql_options: Some(MysqlOptions::default()),
@@ -54,6 +78,13 @@ impl Default for FrontendOptions {
             influxdb_options: Some(InfluxdbOptions::default()),
             prometheus_options: Some(PrometheusOptions::default()),
             prom_options: Some(PromOptions::default()),
+            rule_options: None,
+            scrape_options: None,
+            kafka_options: None,
+            dead_letter_options: None,
+            database_alias_options: None,
+            insert_batch_options: InsertBatchOptions::default(),
+            catalog_watch_interval: DEFAULT_WATCH_INTERVAL,
             meta_client_options: None,
             logging: LoggingOptions::default(),
         }
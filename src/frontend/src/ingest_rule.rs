@@ -0,0 +1,555 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applies the [IngestRule] of a table to the rows written by the ingestion protocols,
+//! before they are sent to storage.
+//!
+//! Rows are grouped into time series by their tag columns. Sampling counts the rows of each
+//! time series across writes, the counts are kept in the memory of each frontend. Aggregating
+//! only collapses the rows within a write, the rows of the same window written by different
+//! writes are stored as they're aggregated, and they overwrite each other if the table
+//! deduplicates the rows of the same time series and timestamp.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::helper::push_vals;
+use api::v1::column::SemanticType;
+use api::v1::InsertRequest;
+use common_time::Timestamp;
+use datatypes::prelude::*;
+use datatypes::vectors::{BooleanVector, Float64Vector};
+use moka::future::{Cache, CacheBuilder};
+use snafu::ResultExt;
+use table::metadata::TableId;
+use table::requests::{IngestAggregator, IngestRule};
+use tokio::sync::Mutex;
+
+use crate::error::{self, Result};
+
+/// Max number of tables whose rule states are kept.
+const MAX_TABLES: u64 = 10_000;
+/// Time to idle of the rule state of a table.
+const TABLE_STATE_TTI: Duration = Duration::from_secs(60 * 60);
+/// Max number of time series counted in the state of a table, the counts are reset once it's
+/// exceeded.
+const MAX_SERIES: usize = 100_000;
+
+/// Tag names and values of a time series.
+type SeriesKey = Vec<(String, Value)>;
+
+/// States of the ingest rules of the tables, keyed by the table ids.
+#[derive(Clone)]
+pub(crate) struct IngestRuleStates {
+    tables: Cache<TableId, Arc<Mutex<IngestRuleState>>>,
+}
+
+impl Default for IngestRuleStates {
+    fn default() -> Self {
+        Self {
+            tables: CacheBuilder::new(MAX_TABLES)
+                .time_to_idle(TABLE_STATE_TTI)
+                .build(),
+        }
+    }
+}
+
+impl IngestRuleStates {
+    /// Applies the `rule` of the table to the rows of the `request` in place.
+    pub(crate) async fn apply(
+        &self,
+        table_id: TableId,
+        rule: &IngestRule,
+        request: &mut InsertRequest,
+    ) -> Result<()> {
+        if request.row_count == 0 || *rule == IngestRule::Sample(1) {
+            return Ok(());
+        }
+
+        let state = self
+            .tables
+            .get_with(table_id, async {
+                Arc::new(Mutex::new(IngestRuleState::default()))
+            })
+            .await;
+        let mut state = state.lock().await;
+        apply_ingest_rule(rule, request, &mut state)
+    }
+}
+
+/// State of the ingest rule of a table.
+#[derive(Debug, Default)]
+pub(crate) struct IngestRuleState {
+    /// The rule the state belongs to, the state is reset once the rule is altered.
+    rule: Option<IngestRule>,
+    /// Number of rows seen of each time series, by sampling.
+    sample_counts: BTreeMap<SeriesKey, usize>,
+}
+
+/// Partial aggregate of a field column within a window.
+#[derive(Debug, Clone, Default)]
+struct Partial {
+    min: Option<Value>,
+    max: Option<Value>,
+    last: Option<Value>,
+    /// Sum and count of the numeric values.
+    sum: f64,
+    count: usize,
+}
+
+impl Partial {
+    fn update(&mut self, value: Value) {
+        if value.is_null() {
+            return;
+        }
+        if let Some(v) = value_to_f64(&value) {
+            self.sum += v;
+            self.count += 1;
+        }
+        self.min = self.min.take().into_iter().chain(Some(value.clone())).min();
+        self.max = self.max.take().into_iter().chain(Some(value.clone())).max();
+        self.last = Some(value);
+    }
+
+    fn avg(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    /// Evaluates the aggregate, the average of non-numeric values is the last value.
+    fn evaluate(&self, aggregator: IngestAggregator) -> Value {
+        match aggregator {
+            IngestAggregator::Min => self.min.clone(),
+            IngestAggregator::Max => self.max.clone(),
+            IngestAggregator::Avg => self.last.clone(),
+        }
+        .unwrap_or(Value::Null)
+    }
+}
+
+/// Applies the `rule` to the rows of the `request` in place, with the `state` of the rule
+/// kept from the previous writes.
+fn apply_ingest_rule(
+    rule: &IngestRule,
+    request: &mut InsertRequest,
+    state: &mut IngestRuleState,
+) -> Result<()> {
+    if request.row_count == 0 || *rule == IngestRule::Sample(1) {
+        return Ok(());
+    }
+    if state.rule.as_ref() != Some(rule) {
+        *state = IngestRuleState {
+            rule: Some(*rule),
+            ..Default::default()
+        };
+    }
+
+    let vectors = request
+        .columns
+        .iter()
+        .map(|column| {
+            common_grpc_expr::column_to_vector(column, request.row_count)
+                .context(error::ToTableInsertRequestSnafu)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let names = request
+        .columns
+        .iter()
+        .map(|column| column.column_name.clone())
+        .collect::<Vec<_>>();
+    let indices_of = |semantic_type: SemanticType| {
+        request
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.semantic_type == semantic_type as i32)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()
+    };
+    // Tags are ordered by names, so the keys of a time series are the same in all writes.
+    let mut tag_indices = indices_of(SemanticType::Tag);
+    tag_indices.sort_by(|a, b| names[*a].cmp(&names[*b]));
+    let series = Series {
+        vectors: &vectors,
+        names: &names,
+        tag_indices: &tag_indices,
+    };
+
+    let vectors = match rule {
+        IngestRule::Sample(n) => sample(&series, *n, &mut state.sample_counts),
+        IngestRule::Aggregate { aggregator, window } => {
+            let Some(&ts_index) = indices_of(SemanticType::Timestamp).first() else {
+                return Ok(());
+            };
+            aggregate(&series, ts_index, *aggregator, *window)
+        }
+    }
+    .context(error::ApplyIngestRuleSnafu {
+        table_name: &request.table_name,
+    })?;
+
+    request.row_count = vectors.first().map(|vector| vector.len()).unwrap_or(0) as u32;
+    for (column, vector) in request.columns.iter_mut().zip(vectors) {
+        column.values = None;
+        column.null_mask.clear();
+        push_vals(column, 0, vector);
+    }
+    Ok(())
+}
+
+/// Columns of a write, whose rows are grouped into time series by the tag columns.
+struct Series<'a> {
+    vectors: &'a [VectorRef],
+    names: &'a [String],
+    tag_indices: &'a [usize],
+}
+
+impl Series<'_> {
+    fn row_count(&self) -> usize {
+        self.vectors.first().map(|vector| vector.len()).unwrap_or(0)
+    }
+
+    fn key(&self, row: usize) -> SeriesKey {
+        self.tag_indices
+            .iter()
+            .map(|&i| (self.names[i].clone(), self.vectors[i].get(row)))
+            .collect()
+    }
+}
+
+/// Resets the counts of the time series if there are too many of them to count the `key`.
+fn reserve_series(counts: &mut BTreeMap<SeriesKey, usize>, key: &SeriesKey) {
+    if counts.len() >= MAX_SERIES && !counts.contains_key(key) {
+        counts.clear();
+    }
+}
+
+/// Keeps the first of every `n` rows of each time series, counting from the rows seen in
+/// the previous writes.
+fn sample(
+    series: &Series,
+    n: usize,
+    counts: &mut BTreeMap<SeriesKey, usize>,
+) -> datatypes::error::Result<Vec<VectorRef>> {
+    let keep = (0..series.row_count())
+        .map(|row| {
+            let key = series.key(row);
+            reserve_series(counts, &key);
+            let count = counts.entry(key).or_insert(0usize);
+            let keep = *count % n == 0;
+            *count += 1;
+            keep
+        })
+        .collect::<Vec<_>>();
+    let filter = BooleanVector::from(keep);
+    series
+        .vectors
+        .iter()
+        .map(|vector| vector.filter(&filter))
+        .collect()
+}
+
+/// Aggregates the rows of each time series within the same window into one row at the
+/// start of the window, the rows are ordered by the first row of each group.
+fn aggregate(
+    series: &Series,
+    ts_index: usize,
+    aggregator: IngestAggregator,
+    window: Duration,
+) -> datatypes::error::Result<Vec<VectorRef>> {
+    let vectors = series.vectors;
+    let mut group_ids = BTreeMap::new();
+    // Time series, window start and rows of each group.
+    let mut groups: Vec<(SeriesKey, Value, Vec<usize>)> = Vec::new();
+    for row in 0..series.row_count() {
+        let window_start = match vectors[ts_index].get(row) {
+            Value::Timestamp(ts) => {
+                let window = (window.as_nanos() / ts.unit().factor() as u128).max(1) as i64;
+                let start = ts.value().div_euclid(window) * window;
+                Value::Timestamp(Timestamp::new(start, ts.unit()))
+            }
+            value => value,
+        };
+        let key = series.key(row);
+        let group_id = *group_ids
+            .entry((key.clone(), window_start.clone()))
+            .or_insert_with(|| {
+                groups.push((key, window_start, Vec::new()));
+                groups.len() - 1
+            });
+        groups[group_id].2.push(row);
+    }
+
+    let field_indices = (0..vectors.len())
+        .filter(|i| *i != ts_index && !series.tag_indices.contains(i))
+        .collect::<Vec<_>>();
+    let partials = groups
+        .iter()
+        .map(|(_, _, rows)| {
+            field_indices
+                .iter()
+                .map(|&i| {
+                    let mut partial = Partial::default();
+                    for &row in rows {
+                        partial.update(vectors[i].get(row));
+                    }
+                    (series.names[i].clone(), partial)
+                })
+                .collect::<HashMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+
+    vectors
+        .iter()
+        .enumerate()
+        .map(|(i, vector)| {
+            let data_type = vector.data_type();
+            if i == ts_index {
+                let values = groups.iter().map(|(_, start, _)| start.clone());
+                return Ok(to_vector(&data_type, values));
+            }
+            if series.tag_indices.contains(&i) {
+                let values = groups.iter().map(|(_, _, rows)| vector.get(rows[0]));
+                return Ok(to_vector(&data_type, values));
+            }
+
+            let partials = partials.iter().map(|partials| &partials[&series.names[i]]);
+            if aggregator == IngestAggregator::Avg && is_numeric(&data_type) {
+                let averages = partials
+                    .map(|partial| {
+                        // Integers are rounded instead of truncated on casting.
+                        partial.avg().map(|avg| {
+                            if data_type.is_float() {
+                                avg
+                            } else {
+                                avg.round()
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                return Float64Vector::from(averages).cast(&data_type);
+            }
+            let values = partials.map(|partial| partial.evaluate(aggregator));
+            Ok(to_vector(&data_type, values))
+        })
+        .collect()
+}
+
+fn to_vector(data_type: &ConcreteDataType, values: impl Iterator<Item = Value>) -> VectorRef {
+    let mut builder = data_type.create_mutable_vector(0);
+    for value in values {
+        builder.push_value_ref(value.as_value_ref());
+    }
+    builder.to_vector()
+}
+
+fn is_numeric(data_type: &ConcreteDataType) -> bool {
+    data_type.is_float()
+        || data_type.is_unsigned()
+        || matches!(
+            data_type,
+            ConcreteDataType::Int8(_)
+                | ConcreteDataType::Int16(_)
+                | ConcreteDataType::Int32(_)
+                | ConcreteDataType::Int64(_)
+        )
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::UInt8(v) => Some(*v as f64),
+        Value::UInt16(v) => Some(*v as f64),
+        Value::UInt32(v) => Some(*v as f64),
+        Value::UInt64(v) => Some(*v as f64),
+        Value::Int8(v) => Some(*v as f64),
+        Value::Int16(v) => Some(*v as f64),
+        Value::Int32(v) => Some(*v as f64),
+        Value::Int64(v) => Some(*v as f64),
+        Value::Float32(v) => Some(v.0 as f64),
+        Value::Float64(v) => Some(v.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_grpc::writer::{LinesWriter, Precision};
+
+    use super::*;
+
+    fn new_request(rows: &[(&str, i64, f64, i64)]) -> InsertRequest {
+        let mut writer = LinesWriter::with_lines(rows.len());
+        for (host, ts, cpu, count) in rows {
+            writer.write_tag("host", host).unwrap();
+            writer
+                .write_ts("ts", (*ts, Precision::Millisecond))
+                .unwrap();
+            writer.write_f64("cpu", *cpu).unwrap();
+            writer.write_i64("count", *count).unwrap();
+            writer.commit();
+        }
+        let (columns, row_count) = writer.finish();
+        InsertRequest {
+            table_name: "metrics".to_string(),
+            region_number: 0,
+            columns,
+            row_count,
+        }
+    }
+
+    fn column_values(request: &InsertRequest, name: &str) -> Vec<Value> {
+        let column = request
+            .columns
+            .iter()
+            .find(|column| column.column_name == name)
+            .unwrap();
+        let vector = common_grpc_expr::column_to_vector(column, request.row_count).unwrap();
+        (0..vector.len()).map(|i| vector.get(i)).collect()
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut request = new_request(&[
+            ("a", 0, 1.0, 1),
+            ("b", 0, 2.0, 2),
+            ("a", 100, 3.0, 3),
+            ("a", 200, 4.0, 4),
+            ("b", 100, 5.0, 5),
+            ("a", 300, 6.0, 6),
+        ]);
+        let mut state = IngestRuleState::default();
+        apply_ingest_rule(&IngestRule::Sample(2), &mut request, &mut state).unwrap();
+
+        assert_eq!(3, request.row_count);
+        assert_eq!(
+            vec![Value::from("a"), Value::from("b"), Value::from("a")],
+            column_values(&request, "host")
+        );
+        assert_eq!(
+            vec![Value::from(1.0), Value::from(2.0), Value::from(4.0)],
+            column_values(&request, "cpu")
+        );
+
+        // Rows are counted from the rows of the previous writes.
+        let mut request = new_request(&[("b", 200, 7.0, 7)]);
+        apply_ingest_rule(&IngestRule::Sample(2), &mut request, &mut state).unwrap();
+        assert_eq!(vec![Value::from(7.0)], column_values(&request, "cpu"));
+        let mut request = new_request(&[("b", 300, 8.0, 8)]);
+        apply_ingest_rule(&IngestRule::Sample(2), &mut request, &mut state).unwrap();
+        assert_eq!(0, request.row_count);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let rows = [
+            ("a", 100, 1.0, 1),
+            ("b", 200, 2.0, 2),
+            ("a", 900, 4.0, 2),
+            ("a", 1000, 8.0, 8),
+        ];
+        let rule = |aggregator| IngestRule::Aggregate {
+            aggregator,
+            window: Duration::from_secs(1),
+        };
+
+        let mut request = new_request(&rows);
+        apply_ingest_rule(
+            &rule(IngestAggregator::Avg),
+            &mut request,
+            &mut IngestRuleState::default(),
+        )
+        .unwrap();
+        assert_eq!(3, request.row_count);
+        assert_eq!(
+            vec![Value::from("a"), Value::from("b"), Value::from("a")],
+            column_values(&request, "host")
+        );
+        assert_eq!(
+            vec![
+                Value::Timestamp(Timestamp::new_millisecond(0)),
+                Value::Timestamp(Timestamp::new_millisecond(0)),
+                Value::Timestamp(Timestamp::new_millisecond(1000)),
+            ],
+            column_values(&request, "ts")
+        );
+        assert_eq!(
+            vec![Value::from(2.5), Value::from(2.0), Value::from(8.0)],
+            column_values(&request, "cpu")
+        );
+        // The average of integers is rounded.
+        assert_eq!(
+            vec![Value::from(2i64), Value::from(2i64), Value::from(8i64)],
+            column_values(&request, "count")
+        );
+
+        let mut request = new_request(&rows);
+        apply_ingest_rule(
+            &rule(IngestAggregator::Min),
+            &mut request,
+            &mut IngestRuleState::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![Value::from(1.0), Value::from(2.0), Value::from(8.0)],
+            column_values(&request, "cpu")
+        );
+
+        let mut request = new_request(&rows);
+        apply_ingest_rule(
+            &rule(IngestAggregator::Max),
+            &mut request,
+            &mut IngestRuleState::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![Value::from(4.0), Value::from(2.0), Value::from(8.0)],
+            column_values(&request, "cpu")
+        );
+    }
+
+    #[test]
+    fn test_aggregate_per_write() {
+        let rule = IngestRule::Aggregate {
+            aggregator: IngestAggregator::Avg,
+            window: Duration::from_secs(1),
+        };
+        let mut state = IngestRuleState::default();
+
+        let mut request = new_request(&[("a", 100, 1.0, 1), ("b", 100, 2.0, 2)]);
+        apply_ingest_rule(&rule, &mut request, &mut state).unwrap();
+        assert_eq!(
+            vec![Value::from(1.0), Value::from(2.0)],
+            column_values(&request, "cpu")
+        );
+
+        // Rows of the same window written later are aggregated on their own.
+        let mut request = new_request(&[("a", 500, 3.0, 3), ("a", 900, 8.0, 8)]);
+        apply_ingest_rule(&rule, &mut request, &mut state).unwrap();
+        assert_eq!(1, request.row_count);
+        assert_eq!(
+            vec![Value::Timestamp(Timestamp::new_millisecond(0))],
+            column_values(&request, "ts")
+        );
+        assert_eq!(vec![Value::from(5.5)], column_values(&request, "cpu"));
+        assert_eq!(vec![Value::from(6i64)], column_values(&request, "count"));
+
+        // So are the rows written late to an earlier window.
+        let mut request = new_request(&[("a", 1200, 5.0, 5), ("a", 200, 10.0, 10)]);
+        apply_ingest_rule(&rule, &mut request, &mut state).unwrap();
+        assert_eq!(
+            vec![Value::from(5.0), Value::from(10.0)],
+            column_values(&request, "cpu")
+        );
+    }
+}
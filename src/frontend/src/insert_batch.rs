@@ -0,0 +1,154 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::readable_size::ReadableSize;
+use datatypes::vectors::Vector;
+use serde::{Deserialize, Serialize};
+use table::requests::InsertRequest;
+
+/// Options of splitting the inserts of distributed tables into batches sent to datanodes, so
+/// that large inserts don't exceed the gRPC message limits or stall datanodes for long.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InsertBatchOptions {
+    /// Max rows of a batch.
+    pub max_rows: usize,
+    /// Max size of a batch, estimated by the memory size of its columns.
+    pub max_bytes: ReadableSize,
+    /// Max number of regions receiving batches concurrently. The batches of the same region
+    /// are sent one by one in their original order.
+    pub parallelism: usize,
+}
+
+impl Default for InsertBatchOptions {
+    fn default() -> Self {
+        Self {
+            max_rows: 50_000,
+            max_bytes: ReadableSize::mb(2),
+            parallelism: 16,
+        }
+    }
+}
+
+/// Splits `insert` into batches of at most `max_rows` rows and about `max_bytes` bytes.
+pub(crate) fn split_insert_batches(
+    insert: InsertRequest,
+    options: &InsertBatchOptions,
+) -> Vec<InsertRequest> {
+    let rows = insert
+        .columns_values
+        .values()
+        .next()
+        .map(|x| x.len())
+        .unwrap_or(0);
+    let bytes = insert
+        .columns_values
+        .values()
+        .map(|x| x.memory_size())
+        .sum::<usize>();
+    let bytes_per_row = (bytes / rows.max(1)).max(1);
+    let batch_rows = (options.max_bytes.as_bytes() as usize / bytes_per_row)
+        .min(options.max_rows)
+        .max(1);
+    if rows <= batch_rows {
+        return vec![insert];
+    }
+
+    (0..rows)
+        .step_by(batch_rows)
+        .map(|offset| {
+            let length = batch_rows.min(rows - offset);
+            InsertRequest {
+                catalog_name: insert.catalog_name.clone(),
+                schema_name: insert.schema_name.clone(),
+                table_name: insert.table_name.clone(),
+                columns_values: insert
+                    .columns_values
+                    .iter()
+                    .map(|(name, vector)| (name.clone(), vector.slice(offset, length)))
+                    .collect(),
+                region_number: insert.region_number,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use datatypes::value::Value;
+    use datatypes::vectors::{Int16Vector, StringVector, VectorRef};
+
+    use super::*;
+
+    fn mock_insert_request() -> InsertRequest {
+        let columns_values = HashMap::from([
+            (
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["host1", "host2", "host3"])) as VectorRef,
+            ),
+            (
+                "id".to_string(),
+                Arc::new(Int16Vector::from_slice([1, 2, 3])) as VectorRef,
+            ),
+        ]);
+        InsertRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+            columns_values,
+            region_number: 0,
+        }
+    }
+
+    #[test]
+    fn test_split_insert_batches() {
+        let options = InsertBatchOptions {
+            max_rows: 2,
+            ..Default::default()
+        };
+        let batches = split_insert_batches(mock_insert_request(), &options);
+        assert_eq!(2, batches.len());
+        let ids = batches
+            .iter()
+            .map(|x| {
+                let ids = &x.columns_values["id"];
+                assert_eq!(ids.len(), x.columns_values["host"].len());
+                (0..ids.len()).map(|i| ids.get(i)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                vec![Value::Int16(1), Value::Int16(2)],
+                vec![Value::Int16(3)]
+            ],
+            ids
+        );
+
+        // Batches are also split by size.
+        let options = InsertBatchOptions {
+            max_bytes: ReadableSize(1),
+            ..Default::default()
+        };
+        let batches = split_insert_batches(mock_insert_request(), &options);
+        assert_eq!(3, batches.len());
+        assert!(batches.iter().all(|x| x.columns_values["id"].len() == 1));
+
+        let batches = split_insert_batches(mock_insert_request(), &InsertBatchOptions::default());
+        assert_eq!(1, batches.len());
+        assert_eq!(3, batches[0].columns_values["id"].len());
+    }
+}
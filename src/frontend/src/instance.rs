@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod builder;
 pub(crate) mod distributed;
 mod grpc;
 mod influxdb;
+mod on_demand;
 mod opentsdb;
+mod plan_cache;
 mod prometheus;
 mod script;
 mod standalone;
@@ -24,19 +27,24 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use api::helper::{push_vals, ColumnDataTypeWrapper};
 use api::v1::alter_expr::Kind;
+use api::v1::column::SemanticType;
 use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use api::v1::{AddColumns, AlterExpr, Column, DdlRequest, InsertRequest};
 use async_trait::async_trait;
+use catalog::cluster::NodeInfoReporter;
+use catalog::helper::NodeRole;
 use catalog::remote::MetaKvBackend;
-use catalog::CatalogManagerRef;
+use catalog::{CatalogManager, CatalogManagerRef};
 use common_base::Plugins;
 use common_catalog::consts::MITO_ENGINE;
+use common_catalog::format_full_table_name;
 use common_error::ext::BoxedError;
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use common_query::Output;
-use common_telemetry::logging::{debug, info};
+use common_telemetry::logging::{self, debug, info, warn};
 use common_telemetry::timer;
 use datafusion::sql::sqlparser::ast::ObjectName;
 use datanode::instance::sql::table_idents_to_full_name;
@@ -44,14 +52,16 @@ use datanode::instance::InstanceRef as DnInstanceRef;
 use datatypes::schema::Schema;
 use distributed::DistInstance;
 use meta_client::client::{MetaClient, MetaClientBuilder};
-use meta_client::MetaClientOptions;
 use partition::manager::PartitionRuleManager;
 use partition::route::TableRoutes;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::query_engine::options::{validate_catalog_and_schema, QueryOptions};
 use query::{QueryEngineFactory, QueryEngineRef};
+use servers::auth::UserProviderRef;
+use servers::database_alias::DatabaseAliasesRef;
 use servers::error as server_error;
 use servers::error::{ExecuteQuerySnafu, ParsePromQLSnafu};
+use servers::health_checker::HealthCheckerRef;
 use servers::interceptor::{SqlQueryInterceptor, SqlQueryInterceptorRef};
 use servers::prom::PromHandler;
 use servers::query_handler::grpc::{GrpcQueryHandler, GrpcQueryHandlerRef};
@@ -59,26 +69,46 @@ use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::{
     InfluxdbLineProtocolHandler, OpentsdbProtocolHandler, PrometheusProtocolHandler, ScriptHandler,
 };
-use session::context::QueryContextRef;
+use session::context::{QueryContextRef, QueryHints};
 use snafu::prelude::*;
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
+use sql::statements::admin::Admin;
 use sql::statements::copy::CopyTable;
+use sql::statements::hint::Hint;
 use sql::statements::statement::Statement;
+use store_api::storage::WriteThrottle;
+use table::metadata::TableMeta;
+use table::requests::METRIC_NAME_KEY;
+use table::TableRef;
+use tokio::task::JoinHandle;
 
 use crate::catalog::FrontendCatalogManager;
+use crate::database_alias::DatabaseAliasLoader;
 use crate::datanode::DatanodeClients;
+use crate::dead_letter::{self, DeadLetterOptions};
 use crate::error::{
-    self, Error, ExecutePromqlSnafu, ExternalSnafu, InvalidInsertRequestSnafu,
-    MissingMetasrvOptsSnafu, ParseSqlSnafu, PlanStatementSnafu, Result, SqlExecInterceptedSnafu,
+    self, AdminPrivilegeRequiredSnafu, AdminStatementDeniedSnafu, Error, ExecutePromqlSnafu,
+    ExternalSnafu, InvalidInsertRequestSnafu, MissingMetasrvOptsSnafu, ParseSqlSnafu,
+    PlanStatementSnafu, Result, SqlExecInterceptedSnafu,
 };
 use crate::expr_factory::{CreateExprFactoryRef, DefaultCreateExprFactory};
 use crate::frontend::FrontendOptions;
+use crate::ingest_rule::IngestRuleStates;
+use crate::instance::on_demand::OnDemandTables;
+use crate::instance::plan_cache::PlanCache;
 use crate::instance::standalone::StandaloneGrpcQueryHandler;
+use crate::kafka::KafkaConsumer;
 use crate::metrics;
+use crate::rule::RuleManager;
+use crate::scrape::Scraper;
 use crate::script::ScriptExecutor;
 use crate::server::{start_server, ServerHandlers, Services};
-use crate::statement::StatementExecutor;
+use crate::statement::{ReadPolicyQueryEngine, StatementExecutor};
+use crate::table_name::TableNameNormalization;
+
+/// Time an insert is held for when the target table asks writers to slow down.
+const WRITE_THROTTLE_DELAY: Duration = Duration::from_millis(100);
 
 #[async_trait]
 pub trait FrontendInstance:
@@ -107,18 +137,46 @@ pub struct Instance {
     grpc_query_handler: GrpcQueryHandlerRef<Error>,
 
     create_expr_factory: CreateExprFactoryRef,
+    on_demand_tables: OnDemandTables,
+    ingest_rule_states: IngestRuleStates,
+    plan_cache: PlanCache,
 
     /// plugins: this map holds extensions to customize query or auth
     /// behaviours.
     plugins: Arc<Plugins>,
 
     servers: Arc<ServerHandlers>,
+    rule_manager: Option<Arc<RuleManager>>,
+    scraper: Option<Arc<Scraper>>,
+    kafka_consumer: Option<Arc<KafkaConsumer>>,
+    dead_letter_options: Option<DeadLetterOptions>,
+    /// Database aliases shared with the HTTP handlers, loaded by the `database_alias_loader`.
+    database_aliases: DatabaseAliasesRef,
+    database_alias_loader: Option<Arc<DatabaseAliasLoader>>,
+    table_name_normalization: TableNameNormalization,
+    /// Catalog manager of the tables in metasrv, only in distributed mode.
+    frontend_catalog_manager: Option<Arc<FrontendCatalogManager>>,
+    /// Task reporting the info of this frontend to metasrv, only in distributed mode.
+    node_info_reporter: Option<Arc<JoinHandle<()>>>,
+    /// Checker served by the `/health` HTTP API.
+    health_checker: Option<HealthCheckerRef>,
 }
 
 impl Instance {
     pub async fn try_new_distributed(
         opts: &FrontendOptions,
         plugins: Arc<Plugins>,
+    ) -> Result<Self> {
+        Self::try_new_distributed_with(opts, plugins, None).await
+    }
+
+    /// Creates a distributed instance, queries resolve tables from the `query_catalog_manager`
+    /// if it's given, otherwise from the catalog stored in the metasrv. Writes, DDL and
+    /// the other statements always use the catalog stored in the metasrv.
+    pub(crate) async fn try_new_distributed_with(
+        opts: &FrontendOptions,
+        plugins: Arc<Plugins>,
+        query_catalog_manager: Option<CatalogManagerRef>,
     ) -> Result<Self> {
         let meta_client = Self::create_meta_client(opts).await?;
 
@@ -129,56 +187,100 @@ impl Instance {
         let partition_manager = Arc::new(PartitionRuleManager::new(table_routes));
         let datanode_clients = Arc::new(DatanodeClients::default());
 
-        let mut catalog_manager =
+        let node_info_reporter = opts.grpc_options.as_ref().map(|grpc_options| {
+            Arc::new(
+                NodeInfoReporter::new(
+                    meta_backend.clone(),
+                    NodeRole::Frontend,
+                    grpc_options.addr.clone(),
+                    None,
+                )
+                .start(),
+            )
+        });
+
+        let mut frontend_catalog_manager =
             FrontendCatalogManager::new(meta_backend, partition_manager, datanode_clients.clone());
+        frontend_catalog_manager.set_insert_batch_options(opts.insert_batch_options.clone());
+        frontend_catalog_manager.set_watch_interval(opts.catalog_watch_interval);
 
         let dist_instance = DistInstance::new(
             meta_client,
-            Arc::new(catalog_manager.clone()),
+            Arc::new(frontend_catalog_manager.clone()),
             datanode_clients,
         );
         let dist_instance = Arc::new(dist_instance);
 
-        catalog_manager.set_dist_instance(dist_instance.clone());
-        let catalog_manager = Arc::new(catalog_manager);
+        frontend_catalog_manager.set_dist_instance(dist_instance.clone());
+        frontend_catalog_manager
+            .start()
+            .await
+            .context(error::CatalogSnafu)?;
+        let frontend_catalog_manager = Arc::new(frontend_catalog_manager);
+        let catalog_manager: CatalogManagerRef = frontend_catalog_manager.clone();
+        let query_catalog_manager =
+            query_catalog_manager.unwrap_or_else(|| catalog_manager.clone());
 
         let query_engine =
-            QueryEngineFactory::new_with_plugins(catalog_manager.clone(), plugins.clone())
+            QueryEngineFactory::new_with_plugins(query_catalog_manager.clone(), plugins.clone())
                 .query_engine();
 
-        let script_executor =
-            Arc::new(ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?);
+        let plan_cache = PlanCache::new(query_catalog_manager);
+        let on_demand_tables = OnDemandTables::new(&catalog_manager);
+        let statement_executor = Arc::new(
+            StatementExecutor::new(
+                catalog_manager.clone(),
+                query_engine.clone(),
+                dist_instance.clone(),
+            )
+            .with_user_provider(plugins.get::<UserProviderRef>().cloned()),
+        );
 
-        let statement_executor = Arc::new(StatementExecutor::new(
-            catalog_manager.clone(),
+        // The scripts query the tables with the engine directly, so the engine applies the
+        // read policies by itself.
+        let script_query_engine = Arc::new(ReadPolicyQueryEngine::new(
             query_engine.clone(),
-            dist_instance.clone(),
+            statement_executor.clone(),
         ));
+        let script_executor =
+            Arc::new(ScriptExecutor::new(catalog_manager.clone(), script_query_engine).await?);
 
         Ok(Instance {
             catalog_manager,
             script_executor,
-            create_expr_factory: Arc::new(DefaultCreateExprFactory),
+            create_expr_factory: Arc::new(DefaultCreateExprFactory::default()),
+            on_demand_tables,
+            ingest_rule_states: IngestRuleStates::default(),
+            plan_cache,
             statement_executor,
             query_engine,
             grpc_query_handler: dist_instance,
             plugins: plugins.clone(),
             servers: Arc::new(HashMap::new()),
+            rule_manager: None,
+            scraper: None,
+            kafka_consumer: None,
+            dead_letter_options: None,
+            database_aliases: Default::default(),
+            database_alias_loader: None,
+            table_name_normalization: TableNameNormalization::default(),
+            frontend_catalog_manager: Some(frontend_catalog_manager.clone()),
+            node_info_reporter,
+            health_checker: Some(frontend_catalog_manager),
         })
     }
 
     async fn create_meta_client(opts: &FrontendOptions) -> Result<Arc<MetaClient>> {
-        let metasrv_addr = &opts
+        let meta_config = opts
             .meta_client_options
             .as_ref()
-            .context(MissingMetasrvOptsSnafu)?
-            .metasrv_addrs;
+            .context(MissingMetasrvOptsSnafu)?;
+        let metasrv_addr = &meta_config.metasrv_addrs;
         info!(
             "Creating Frontend instance in distributed mode with Meta server addr {:?}",
             metasrv_addr
         );
 
-        let meta_config = MetaClientOptions::default();
         let channel_config = ChannelConfig::new()
             .timeout(Duration::from_millis(meta_config.timeout_millis))
             .connect_timeout(Duration::from_millis(meta_config.connect_timeout_millis))
@@ -189,6 +291,7 @@ impl Instance {
             .enable_router()
             .enable_store()
             .channel_manager(channel_manager)
+            .backoff_policy(meta_config.backoff_policy())
             .build();
         meta_client
             .start(metasrv_addr)
@@ -198,33 +301,115 @@ impl Instance {
     }
 
     pub async fn try_new_standalone(dn_instance: DnInstanceRef) -> Result<Self> {
-        let catalog_manager = dn_instance.catalog_manager();
-        let query_engine = dn_instance.query_engine();
-        let script_executor =
-            Arc::new(ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?);
+        Self::try_new_standalone_with(dn_instance, Default::default(), None).await
+    }
 
-        let statement_executor = Arc::new(StatementExecutor::new(
-            catalog_manager.clone(),
+    /// Creates a standalone instance, queries resolve tables from the `query_catalog_manager`
+    /// with a query engine of their own if it's given, otherwise they share the catalog
+    /// and query engine of the datanode. Writes, DDL and the other statements always use
+    /// the catalog of the datanode.
+    pub(crate) async fn try_new_standalone_with(
+        dn_instance: DnInstanceRef,
+        plugins: Arc<Plugins>,
+        query_catalog_manager: Option<CatalogManagerRef>,
+    ) -> Result<Self> {
+        let catalog_manager = dn_instance.catalog_manager().clone();
+        let (query_catalog_manager, query_engine) = match query_catalog_manager {
+            Some(query_catalog_manager) => {
+                let query_engine = QueryEngineFactory::new_with_plugins(
+                    query_catalog_manager.clone(),
+                    plugins.clone(),
+                )
+                .query_engine();
+                (query_catalog_manager, query_engine)
+            }
+            None => (catalog_manager.clone(), dn_instance.query_engine()),
+        };
+        let plan_cache = PlanCache::new(query_catalog_manager);
+        let on_demand_tables = OnDemandTables::new(&catalog_manager);
+        let statement_executor = Arc::new(
+            StatementExecutor::new(
+                catalog_manager.clone(),
+                query_engine.clone(),
+                dn_instance.clone(),
+            )
+            .with_user_provider(plugins.get::<UserProviderRef>().cloned()),
+        );
+
+        // The scripts query the tables with the engine directly, so the engine applies the
+        // read policies by itself.
+        let script_query_engine = Arc::new(ReadPolicyQueryEngine::new(
             query_engine.clone(),
-            dn_instance.clone(),
+            statement_executor.clone(),
         ));
+        let script_executor =
+            Arc::new(ScriptExecutor::new(catalog_manager.clone(), script_query_engine).await?);
 
         Ok(Instance {
-            catalog_manager: catalog_manager.clone(),
+            catalog_manager,
             script_executor,
-            create_expr_factory: Arc::new(DefaultCreateExprFactory),
+            create_expr_factory: Arc::new(DefaultCreateExprFactory::default()),
+            on_demand_tables,
+            ingest_rule_states: IngestRuleStates::default(),
+            plan_cache,
             statement_executor,
             query_engine,
             grpc_query_handler: StandaloneGrpcQueryHandler::arc(dn_instance.clone()),
-            plugins: Default::default(),
+            plugins,
             servers: Arc::new(HashMap::new()),
+            rule_manager: None,
+            scraper: None,
+            kafka_consumer: None,
+            dead_letter_options: None,
+            database_aliases: Default::default(),
+            database_alias_loader: None,
+            table_name_normalization: TableNameNormalization::default(),
+            frontend_catalog_manager: None,
+            node_info_reporter: None,
+            health_checker: Some(dn_instance.health_checker()),
         })
     }
 
     pub async fn build_servers(&mut self, opts: &FrontendOptions) -> Result<()> {
+        // The servers and tasks below hold clones of the instance, so the options of the
+        // instance itself go first.
+        self.dead_letter_options = opts.dead_letter_options.clone();
+        self.table_name_normalization = opts.table_name_normalization;
+
+        if !opts.table_defaults.is_empty() {
+            self.create_expr_factory = Arc::new(DefaultCreateExprFactory::try_new(
+                opts.table_defaults.clone(),
+            )?);
+        }
+
         let servers = Services::build(opts, Arc::new(self.clone()), self.plugins.clone()).await?;
         self.servers = Arc::new(servers);
 
+        if let Some(rule_options) = &opts.rule_options {
+            let instance = Arc::new(self.clone());
+            let rule_manager = RuleManager::try_new(rule_options, instance.clone(), instance)?;
+            self.rule_manager = Some(Arc::new(rule_manager));
+        }
+
+        if let Some(scrape_options) = &opts.scrape_options {
+            let scraper = Scraper::try_new(scrape_options, Arc::new(self.clone()))?;
+            self.scraper = Some(Arc::new(scraper));
+        }
+
+        if let Some(kafka_options) = &opts.kafka_options {
+            let consumer = KafkaConsumer::new(kafka_options.clone(), Arc::new(self.clone()));
+            self.kafka_consumer = Some(Arc::new(consumer));
+        }
+
+        if let Some(database_alias_options) = &opts.database_alias_options {
+            let loader = DatabaseAliasLoader::new(
+                database_alias_options,
+                Arc::new(self.clone()),
+                self.database_aliases.clone(),
+            );
+            self.database_alias_loader = Some(Arc::new(loader));
+        }
+
         Ok(())
     }
 
@@ -240,6 +425,8 @@ impl Instance {
                 .unwrap(),
         );
 
+        let plan_cache = PlanCache::new(catalog_manager.clone());
+        let on_demand_tables = OnDemandTables::new(&catalog_manager);
         let statement_executor = Arc::new(StatementExecutor::new(
             catalog_manager.clone(),
             query_engine.clone(),
@@ -251,26 +438,99 @@ impl Instance {
             script_executor,
             statement_executor,
             query_engine,
-            create_expr_factory: Arc::new(DefaultCreateExprFactory),
+            create_expr_factory: Arc::new(DefaultCreateExprFactory::default()),
+            on_demand_tables,
+            ingest_rule_states: IngestRuleStates::default(),
+            plan_cache,
             grpc_query_handler: dist_instance,
             plugins: Default::default(),
             servers: Arc::new(HashMap::new()),
+            rule_manager: None,
+            scraper: None,
+            kafka_consumer: None,
+            dead_letter_options: None,
+            database_aliases: Default::default(),
+            database_alias_loader: None,
+            table_name_normalization: TableNameNormalization::default(),
+            frontend_catalog_manager: None,
+            node_info_reporter: None,
+            health_checker: None,
         }
     }
 
+    /// Returns the checker reporting the datanode running out of disk space in standalone
+    /// mode, or the nodes running out of disk space in the cluster in distributed mode.
+    pub fn health_checker(&self) -> Option<HealthCheckerRef> {
+        self.health_checker.clone()
+    }
+
     pub fn catalog_manager(&self) -> &CatalogManagerRef {
         &self.catalog_manager
     }
 
     /// Handle batch inserts
+    ///
+    /// If the dead-letter table is enabled, the rejected requests are written to it instead
+    /// of failing the batch.
     pub async fn handle_inserts(
         &self,
         requests: Vec<InsertRequest>,
         ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let metric_names = vec![None; requests.len()];
+        self.do_handle_inserts(requests, metric_names, ctx).await
+    }
+
+    /// Handle batch inserts of the ingestion protocols, whose table names are metric names.
+    ///
+    /// The metric names are normalized into table names by the configured
+    /// [TableNameNormalization], and the tables created for the renamed metrics keep the
+    /// metric names in their options. Metrics normalized to tables holding other metrics
+    /// are rejected.
+    pub async fn handle_metric_inserts(
+        &self,
+        mut requests: Vec<InsertRequest>,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        if self.table_name_normalization == TableNameNormalization::None {
+            return self.handle_inserts(requests, ctx).await;
+        }
+
+        let metric_names = requests
+            .iter_mut()
+            .map(|request| {
+                let table_name = self
+                    .table_name_normalization
+                    .normalize(&request.table_name)
+                    .into_owned();
+                Some(std::mem::replace(&mut request.table_name, table_name))
+            })
+            .collect();
+        self.do_handle_inserts(requests, metric_names, ctx).await
+    }
+
+    /// Inserts the `requests`, `metric_names` are the names of the metrics the requests
+    /// are normalized from, `None` if they are not from the ingestion protocols.
+    async fn do_handle_inserts(
+        &self,
+        requests: Vec<InsertRequest>,
+        metric_names: Vec<Option<String>>,
+        ctx: QueryContextRef,
     ) -> Result<Output> {
         let mut success = 0;
-        for request in requests {
-            match self.handle_insert(request, ctx.clone()).await? {
+        for (request, metric_name) in requests.into_iter().zip(metric_names) {
+            let metric_name = metric_name.as_deref();
+            let output = match &self.dead_letter_options {
+                Some(options) => {
+                    self.handle_insert_or_dead_letter(request, metric_name, options, ctx.clone())
+                        .await?
+                }
+                None => {
+                    self.handle_insert(request, metric_name, ctx.clone())
+                        .await?
+                }
+            };
+            match output {
                 Output::AffectedRows(rows) => success += rows,
                 _ => unreachable!("Insert should not yield output other than AffectedRows"),
             }
@@ -278,9 +538,92 @@ impl Instance {
         Ok(Output::AffectedRows(success))
     }
 
-    async fn handle_insert(&self, request: InsertRequest, ctx: QueryContextRef) -> Result<Output> {
-        self.create_or_alter_table_on_demand(ctx.clone(), &request)
+    /// Inserts the `request`, writes the rows rejected by the table to the dead-letter
+    /// table and inserts the others.
+    async fn handle_insert_or_dead_letter(
+        &self,
+        request: InsertRequest,
+        metric_name: Option<&str>,
+        options: &DeadLetterOptions,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let table = match self
+            .create_or_alter_table_on_demand(ctx.clone(), &request, metric_name)
+            .await
+        {
+            Ok(table) => table,
+            Err(e) if dead_letter::is_rejected(&e) => {
+                warn!(
+                    "Insert to table {} is rejected, write to dead-letter table {}, error: {}",
+                    request.table_name, options.table, e
+                );
+                let errors = vec![Some(e.to_string()); request.row_count as usize];
+                let dead_letter = dead_letter::to_dead_letter_request(options, &request, &errors)?;
+                self.handle_insert(dead_letter, None, ctx).await?;
+                return Ok(Output::AffectedRows(0));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let table_name = request.table_name.clone();
+        let (request, dead_letter) = match &table {
+            Some(table) => dead_letter::split_rejected(options, &table.schema(), request)?,
+            None => (Some(request), None),
+        };
+        if let Some(dead_letter) = dead_letter {
+            warn!(
+                "{} rows inserted to table {} are rejected, write to dead-letter table {}",
+                dead_letter.row_count, table_name, options.table
+            );
+            self.handle_insert(dead_letter, None, ctx.clone()).await?;
+        }
+        match request {
+            Some(request) => self.write_insert(table, request, ctx).await,
+            None => Ok(Output::AffectedRows(0)),
+        }
+    }
+
+    /// Inserts the `request`, the table is created on demand for the `metric_name` if
+    /// it's given.
+    async fn handle_insert(
+        &self,
+        request: InsertRequest,
+        metric_name: Option<&str>,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let table = self
+            .create_or_alter_table_on_demand(ctx.clone(), &request, metric_name)
             .await?;
+        self.write_insert(table, request, ctx).await
+    }
+
+    /// Writes the `request` to the `table` created or altered for it. The ingest rule of
+    /// the table is applied to the rows before they are written, including the rows
+    /// creating the table.
+    async fn write_insert(
+        &self,
+        table: Option<TableRef>,
+        mut request: InsertRequest,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        if let Some(table) = table {
+            let full_table_name = format_full_table_name(
+                &ctx.current_catalog(),
+                &ctx.current_schema(),
+                &request.table_name,
+            );
+            throttle_write(&table, &full_table_name).await?;
+
+            validate_insert_request(table.schema().as_ref(), &request)?;
+
+            let table_info = table.table_info();
+            if let Some(rule) = &table_info.meta.options.ingest_rule {
+                self.ingest_rule_states
+                    .apply(table_info.ident.table_id, rule, &mut request)
+                    .await?;
+            }
+            fill_computed_columns(&table_info.meta, &mut request).await?;
+        }
 
         let query = Request::Insert(request);
         GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, ctx).await
@@ -289,39 +632,72 @@ impl Instance {
     // check if table already exist:
     // - if table does not exist, create table by inferred CreateExpr
     // - if table exist, check if schema matches. If any new column found, alter table by inferred `AlterExpr`
+    // Returns the table, or `None` if it's not found after creation.
     async fn create_or_alter_table_on_demand(
         &self,
         ctx: QueryContextRef,
         request: &InsertRequest,
-    ) -> Result<()> {
+        metric_name: Option<&str>,
+    ) -> Result<Option<TableRef>> {
         let catalog_name = &ctx.current_catalog();
         let schema_name = &ctx.current_schema();
         let table_name = &request.table_name;
         let columns = &request.columns;
 
-        let table = self
-            .catalog_manager
-            .table(catalog_name, schema_name, table_name)
-            .await
-            .context(error::CatalogSnafu)?;
+        let full_table_name = format_full_table_name(catalog_name, schema_name, table_name);
+
+        // Skip looking up the tables being created by others.
+        let table = if self.on_demand_tables.is_missing(&full_table_name) {
+            None
+        } else {
+            let table = self
+                .catalog_manager
+                .table(catalog_name, schema_name, table_name)
+                .await
+                .context(error::CatalogSnafu)?;
+            if table.is_none() {
+                self.on_demand_tables.mark_missing(&full_table_name).await;
+            }
+            table
+        };
         match table {
             None => {
                 info!(
                     "Table {}.{}.{} does not exist, try create table",
                     catalog_name, schema_name, table_name,
                 );
-                self.create_table_by_columns(ctx, table_name, columns, MITO_ENGINE)
+                self.on_demand_tables
+                    .create(&full_table_name, async {
+                        self.create_table_by_columns(
+                            ctx,
+                            table_name,
+                            columns,
+                            MITO_ENGINE,
+                            metric_name,
+                        )
+                        .await
+                        .map(|_| ())
+                    })
                     .await?;
                 info!(
                     "Successfully created table on insertion: {}.{}.{}",
                     catalog_name, schema_name, table_name
                 );
+                let table = self
+                    .catalog_manager
+                    .table(catalog_name, schema_name, table_name)
+                    .await
+                    .context(error::CatalogSnafu)?;
+                // The table may be created by others for another metric.
+                if let Some(table) = &table {
+                    check_table_metric_name(table, metric_name)?;
+                }
+                Ok(table)
             }
             Some(table) => {
-                let schema = table.schema();
-
-                validate_insert_request(schema.as_ref(), request)?;
+                check_table_metric_name(&table, metric_name)?;
 
+                let schema = table.schema();
                 if let Some(add_columns) = common_grpc_expr::find_new_columns(&schema, columns)
                     .context(error::FindNewColumnsOnInsertionSnafu)?
                 {
@@ -336,9 +712,9 @@ impl Instance {
                         catalog_name, schema_name, table_name
                     );
                 }
+                Ok(Some(table))
             }
-        };
-        Ok(())
+        }
     }
 
     /// Infer create table expr from inserting data
@@ -348,15 +724,21 @@ impl Instance {
         table_name: &str,
         columns: &[Column],
         engine: &str,
+        metric_name: Option<&str>,
     ) -> Result<Output> {
         let catalog_name = &ctx.current_catalog();
         let schema_name = &ctx.current_schema();
 
         // Create table automatically, build schema from data.
-        let create_expr = self
+        let mut create_expr = self
             .create_expr_factory
             .create_expr_by_columns(catalog_name, schema_name, table_name, columns, engine)
             .await?;
+        if let Some(metric_name) = metric_name.filter(|metric_name| *metric_name != table_name) {
+            let _ = create_expr
+                .table_options
+                .insert(METRIC_NAME_KEY.to_string(), metric_name.to_string());
+        }
 
         info!(
             "Try to create table: {} automatically with request: {:?}",
@@ -379,10 +761,7 @@ impl Instance {
         table_name: &str,
         add_columns: AddColumns,
     ) -> Result<Output> {
-        debug!(
-            "Adding new columns: {:?} to table: {}",
-            add_columns, table_name
-        );
+        debug!(table = table_name, "Adding new columns: {:?}", add_columns);
         let expr = AlterExpr {
             catalog_name: ctx.current_catalog(),
             schema_name: ctx.current_schema(),
@@ -400,7 +779,36 @@ impl Instance {
             .await
     }
 
+    /// Ensures the table the `metric_name` is normalized to, if it exists, holds the metric,
+    /// so the series of the table are the series of the metric.
+    pub(crate) async fn check_metric_table(
+        &self,
+        metric_name: &str,
+        ctx: &QueryContextRef,
+    ) -> Result<()> {
+        if self.table_name_normalization == TableNameNormalization::None {
+            return Ok(());
+        }
+
+        let table_name = self.table_name_normalization.normalize(metric_name);
+        let table = self
+            .catalog_manager
+            .table(&ctx.current_catalog(), &ctx.current_schema(), &table_name)
+            .await
+            .context(error::CatalogSnafu)?;
+        match table {
+            Some(table) => check_table_metric_name(&table, Some(metric_name)),
+            None => Ok(()),
+        }
+    }
+
     pub fn set_plugins(&mut self, map: Arc<Plugins>) {
+        let user_provider = map.get::<UserProviderRef>().cloned();
+        self.statement_executor = Arc::new(
+            (*self.statement_executor)
+                .clone()
+                .with_user_provider(user_provider),
+        );
         self.plugins = map;
     }
 
@@ -408,7 +816,29 @@ impl Instance {
         self.plugins.clone()
     }
 
+    pub fn database_aliases(&self) -> DatabaseAliasesRef {
+        self.database_aliases.clone()
+    }
+
     pub async fn shutdown(&self) -> Result<()> {
+        if let Some(catalog_manager) = &self.frontend_catalog_manager {
+            catalog_manager.stop();
+        }
+        if let Some(node_info_reporter) = &self.node_info_reporter {
+            node_info_reporter.abort();
+        }
+        if let Some(kafka_consumer) = &self.kafka_consumer {
+            kafka_consumer.stop();
+        }
+        if let Some(scraper) = &self.scraper {
+            scraper.stop().await?;
+        }
+        if let Some(loader) = &self.database_alias_loader {
+            loader.stop().await?;
+        }
+        if let Some(rule_manager) = &self.rule_manager {
+            rule_manager.stop().await?;
+        }
         futures::future::try_join_all(self.servers.values().map(|server| server.0.shutdown()))
             .await
             .context(error::ShutdownServerSnafu)
@@ -428,8 +858,21 @@ impl FrontendInstance for Instance {
 
         futures::future::try_join_all(self.servers.values().map(start_server))
             .await
-            .context(error::StartServerSnafu)
-            .map(|_| ())
+            .context(error::StartServerSnafu)?;
+
+        if let Some(loader) = &self.database_alias_loader {
+            loader.start().await?;
+        }
+        if let Some(rule_manager) = &self.rule_manager {
+            rule_manager.start().await?;
+        }
+        if let Some(scraper) = &self.scraper {
+            scraper.start().await?;
+        }
+        if let Some(kafka_consumer) = &self.kafka_consumer {
+            kafka_consumer.start().await?;
+        }
+        Ok(())
     }
 }
 
@@ -437,20 +880,81 @@ fn parse_stmt(sql: &str) -> Result<Vec<Statement>> {
     ParserContext::create_with_dialect(sql, &GenericDialect {}).context(ParseSqlSnafu)
 }
 
+fn parse_hints(sql: &str) -> Result<QueryHints> {
+    let hints = ParserContext::parse_hints(sql, &GenericDialect {}).context(ParseSqlSnafu)?;
+    let mut query_hints = QueryHints::default();
+    for hint in hints {
+        match hint {
+            Hint::NoIndex => query_hints.no_index = true,
+            Hint::Parallel(parallelism) => query_hints.parallelism = Some(parallelism),
+            Hint::ReadReplica => query_hints.read_replica = true,
+        }
+    }
+    Ok(query_hints)
+}
+
 impl Instance {
     async fn query_statement(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
         check_permission(self.plugins.clone(), &stmt, &query_ctx)?;
 
+        let is_ddl = is_ddl_statement(&stmt);
         let stmt = QueryStatement::Sql(stmt);
-        self.statement_executor.execute_stmt(stmt, query_ctx).await
+        let result = self.statement_executor.execute_stmt(stmt, query_ctx).await;
+        if is_ddl {
+            self.plan_cache.invalidate_all();
+        }
+        result
+    }
+
+    /// Executes the query statement `stmt`, reusing the cached plan if possible. The plan is
+    /// cached with `parsed_stmt`, the statement parsed from `sql` before the interceptor
+    /// processes it into `stmt`.
+    async fn query_with_plan_cache(
+        &self,
+        sql: &str,
+        parsed_stmt: Statement,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        check_permission(self.plugins.clone(), &stmt, &query_ctx)?;
+
+        let plan = match self
+            .plan_cache
+            .get_plan(sql, &parsed_stmt, &query_ctx)
+            .await?
+        {
+            Some(plan) => plan,
+            None => {
+                let plan = self
+                    .statement_executor
+                    .plan(QueryStatement::Sql(stmt), query_ctx.clone())
+                    .await?;
+                self.plan_cache
+                    .insert(sql, parsed_stmt, plan.clone(), &query_ctx)
+                    .await;
+                plan
+            }
+        };
+        self.statement_executor.exec_plan(plan, query_ctx).await
     }
 }
 
-#[async_trait]
-impl SqlQueryHandler for Instance {
-    type Error = Error;
+fn is_ddl_statement(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::CreateDatabase(_)
+            | Statement::CreateTable(_)
+            | Statement::CloneTable(_)
+            | Statement::CreateExternalTable(_)
+            | Statement::Alter(_)
+            | Statement::DropTable(_)
+            | Statement::CreateView(_)
+            | Statement::DropView(_)
+    )
+}
 
-    async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
+impl Instance {
+    async fn do_sql_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
         let _timer = timer!(metrics::METRIC_HANDLE_SQL_ELAPSED);
 
         let query_interceptor = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
@@ -459,10 +963,34 @@ impl SqlQueryHandler for Instance {
             Err(e) => return vec![Err(e)],
         };
 
-        match parse_stmt(query.as_ref())
-            .and_then(|stmts| query_interceptor.post_parsing(stmts, query_ctx.clone()))
-        {
+        // Hints apply to all the statements of the query. They are set even if the query has
+        // none, to clear the hints of the last query in the session.
+        match parse_hints(query.as_ref()) {
+            Ok(hints) => query_ctx.set_hints(hints),
+            Err(e) => return vec![Err(e)],
+        }
+
+        // Repeated queries skip parsing if their statements are cached. The statements are
+        // cached as parsed, the interceptor still processes them on every query.
+        let stmts = match self.plan_cache.get_statement(query.as_ref(), &query_ctx) {
+            Some(stmt) => Ok(vec![stmt]),
+            None => parse_stmt(query.as_ref()),
+        };
+        // Only cache the plans of the queries containing a single statement. Plans may call the
+        // functions of the session, which are invisible to other sessions.
+        let parsed_stmt = match &stmts {
+            Ok(stmts)
+                if stmts.len() == 1
+                    && matches!(stmts[0], Statement::Query(_))
+                    && !query_ctx.has_functions() =>
+            {
+                Some(stmts[0].clone())
+            }
+            _ => None,
+        };
+        match stmts.and_then(|stmts| query_interceptor.post_parsing(stmts, query_ctx.clone())) {
             Ok(stmts) => {
+                let mut parsed_stmt = parsed_stmt.filter(|_| stmts.len() == 1);
                 let mut results = Vec::with_capacity(stmts.len());
                 for stmt in stmts {
                     // TODO(sunng87): figure out at which stage we can call
@@ -472,7 +1000,19 @@ impl SqlQueryHandler for Instance {
                         results.push(Err(e));
                         break;
                     }
-                    match self.query_statement(stmt, query_ctx.clone()).await {
+                    let result = match parsed_stmt.take() {
+                        Some(parsed_stmt) if matches!(stmt, Statement::Query(_)) => {
+                            self.query_with_plan_cache(
+                                query.as_ref(),
+                                parsed_stmt,
+                                stmt,
+                                query_ctx.clone(),
+                            )
+                            .await
+                        }
+                        _ => self.query_statement(stmt, query_ctx.clone()).await,
+                    };
+                    match result {
                         Ok(output) => {
                             let output_result =
                                 query_interceptor.post_execute(output, query_ctx.clone());
@@ -491,6 +1031,22 @@ impl SqlQueryHandler for Instance {
             }
         }
     }
+}
+
+#[async_trait]
+impl SqlQueryHandler for Instance {
+    type Error = Error;
+
+    async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
+        // Queries received by gRPC are already traced, the others start their traces here.
+        match logging::trace_id() {
+            Some(_) => self.do_sql_query(query, query_ctx).await,
+            None => {
+                let trace_id = logging::gen_trace_id();
+                logging::with_trace_id(trace_id, self.do_sql_query(query, query_ctx)).await
+            }
+        }
+    }
 
     async fn do_promql_query(
         &self,
@@ -543,9 +1099,23 @@ impl PromHandler for Instance {
         query: &PromQuery,
         query_ctx: QueryContextRef,
     ) -> server_error::Result<Output> {
-        let stmt = QueryLanguageParser::parse_promql(query).with_context(|_| ParsePromQLSnafu {
-            query: query.clone(),
-        })?;
+        let mut stmt =
+            QueryLanguageParser::parse_promql(query).with_context(|_| ParsePromQLSnafu {
+                query: query.clone(),
+            })?;
+        if let QueryStatement::Promql(eval_stmt) = &mut stmt {
+            let metric_names = self
+                .table_name_normalization
+                .normalize_promql(&mut eval_stmt.expr);
+            for metric_name in metric_names {
+                self.check_metric_table(&metric_name, &query_ctx)
+                    .await
+                    .map_err(BoxedError::new)
+                    .with_context(|_| ExecuteQuerySnafu {
+                        query: format!("{query:?}"),
+                    })?;
+            }
+        }
         self.statement_executor
             .execute_stmt(stmt, query_ctx)
             .await
@@ -561,12 +1131,25 @@ pub fn check_permission(
     stmt: &Statement,
     query_ctx: &QueryContextRef,
 ) -> Result<()> {
-    let need_validate = plugins
-        .get::<QueryOptions>()
-        .map(|opts| opts.disallow_cross_schema_query)
-        .unwrap_or_default();
+    let query_options = plugins.get::<QueryOptions>().cloned().unwrap_or_default();
+
+    if let Statement::Admin(_) = stmt {
+        ensure!(
+            !query_options.disallow_admin_statement,
+            AdminStatementDeniedSnafu
+        );
+        if let Some(user_provider) = plugins.get::<UserProviderRef>() {
+            let user = query_ctx.current_user();
+            ensure!(
+                user_provider.allow_admin(&user),
+                AdminPrivilegeRequiredSnafu {
+                    username: user.username(),
+                }
+            );
+        }
+    }
 
-    if !need_validate {
+    if !query_options.disallow_cross_schema_query {
         return Ok(());
     }
 
@@ -574,7 +1157,12 @@ pub fn check_permission(
         // These are executed by query engine, and will be checked there.
         Statement::Query(_) | Statement::Explain(_) | Statement::Tql(_) | Statement::Delete(_) => {}
         // database ops won't be checked
-        Statement::CreateDatabase(_) | Statement::ShowDatabases(_) | Statement::Use(_) => {}
+        Statement::CreateDatabase(_)
+        | Statement::ShowCatalogs(_)
+        | Statement::ShowDatabases(_)
+        | Statement::Use(_) => {}
+        // session variables and functions are not bound to any database
+        Statement::SetVariables(_) | Statement::CreateFunction(_) | Statement::DropFunction(_) => {}
         // show create table and alter are not supported yet
         Statement::ShowCreateTable(_) | Statement::CreateExternalTable(_) | Statement::Alter(_) => {
         }
@@ -585,9 +1173,31 @@ pub fn check_permission(
         Statement::CreateTable(stmt) => {
             validate_param(&stmt.name, query_ctx)?;
         }
+        Statement::CloneTable(stmt) => {
+            validate_param(&stmt.name, query_ctx)?;
+            validate_param(&stmt.source, query_ctx)?;
+        }
         Statement::DropTable(drop_stmt) => {
             validate_param(drop_stmt.table_name(), query_ctx)?;
         }
+        Statement::CreateView(stmt) => {
+            validate_param(&stmt.name, query_ctx)?;
+        }
+        Statement::DropView(drop_stmt) => {
+            validate_param(drop_stmt.view_name(), query_ctx)?;
+        }
+        Statement::Admin(Admin::Bulk(bulk)) => {
+            if let Some(schema) = &bulk.schema {
+                validate_catalog_and_schema(&query_ctx.current_catalog(), schema, query_ctx)
+                    .map_err(BoxedError::new)
+                    .context(SqlExecInterceptedSnafu)?;
+            }
+        }
+        Statement::Admin(admin) => {
+            if let Some(table_name) = admin.table_name() {
+                validate_param(table_name, query_ctx)?;
+            }
+        }
         Statement::ShowTables(stmt) => {
             if let Some(database) = &stmt.database {
                 validate_catalog_and_schema(&query_ctx.current_catalog(), database, query_ctx)
@@ -598,6 +1208,9 @@ pub fn check_permission(
         Statement::DescribeTable(stmt) => {
             validate_param(stmt.name(), query_ctx)?;
         }
+        Statement::ShowCardinality(stmt) => {
+            validate_param(&stmt.table_name, query_ctx)?;
+        }
         Statement::Copy(stmd) => match stmd {
             CopyTable::To(copy_table_to) => validate_param(&copy_table_to.table_name, query_ctx)?,
             CopyTable::From(copy_table_from) => {
@@ -618,9 +1231,59 @@ fn validate_param(name: &ObjectName, query_ctx: &QueryContextRef) -> Result<()>
         .context(SqlExecInterceptedSnafu)
 }
 
+/// Returns the name of the metric the `table` holds, which is kept in the table options if
+/// the table name is normalized from it.
+pub(crate) fn table_metric_name(table: &TableRef) -> String {
+    let table_info = table.table_info();
+    table_info
+        .meta
+        .options
+        .extra_options
+        .get(METRIC_NAME_KEY)
+        .cloned()
+        .unwrap_or_else(|| table_info.name.clone())
+}
+
+/// Ensures the `table` holds the metric `metric_name` if it's given.
+pub(crate) fn check_table_metric_name(table: &TableRef, metric_name: Option<&str>) -> Result<()> {
+    let Some(metric_name) = metric_name else {
+        return Ok(());
+    };
+    let table_metric_name = table_metric_name(table);
+    ensure!(
+        table_metric_name == metric_name,
+        error::MetricNameConflictSnafu {
+            metric_name,
+            table_name: &table.table_info().name,
+            table_metric_name,
+        }
+    );
+    Ok(())
+}
+
+/// Applies the write throttle of the `table` to an insert, which is delayed or rejected
+/// if the table is under write pressure.
+pub(crate) async fn throttle_write(table: &TableRef, full_table_name: &str) -> Result<()> {
+    match table.write_throttle() {
+        WriteThrottle::Admit => Ok(()),
+        WriteThrottle::Delay => {
+            debug!(table = %full_table_name, "Delay insertion to throttled table");
+            tokio::time::sleep(WRITE_THROTTLE_DELAY).await;
+            Ok(())
+        }
+        WriteThrottle::Reject => error::TableWriteThrottledSnafu {
+            table_name: full_table_name,
+        }
+        .fail(),
+    }
+}
+
 fn validate_insert_request(schema: &Schema, request: &InsertRequest) -> Result<()> {
     for column_schema in schema.column_schemas() {
-        if column_schema.is_nullable() || column_schema.default_constraint().is_some() {
+        if column_schema.is_nullable()
+            || column_schema.default_constraint().is_some()
+            || column_schema.computed_expr().is_some()
+        {
             continue;
         }
         let not_null = request
@@ -641,6 +1304,73 @@ fn validate_insert_request(schema: &Schema, request: &InsertRequest) -> Result<(
     Ok(())
 }
 
+/// Evaluates the computed columns of the table over the inserted columns, replaces the
+/// values given for them in `request`.
+async fn fill_computed_columns(table_meta: &TableMeta, request: &mut InsertRequest) -> Result<()> {
+    let schema = &table_meta.schema;
+    let is_computed = |name: &str| {
+        schema
+            .column_schema_by_name(name)
+            .map(|column_schema| column_schema.computed_expr().is_some())
+            .unwrap_or(false)
+    };
+    if !schema
+        .column_schemas()
+        .iter()
+        .any(|column_schema| is_computed(&column_schema.name))
+    {
+        return Ok(());
+    }
+
+    let mut columns_values = request
+        .columns
+        .iter()
+        .map(|column| {
+            let vector = common_grpc_expr::column_to_vector(column, request.row_count)
+                .context(error::ToTableInsertRequestSnafu)?;
+            Ok((column.column_name.clone(), vector))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+    query::computed::fill_computed_columns(schema, &mut columns_values)
+        .await
+        .context(error::EvalComputedColumnsSnafu)?;
+
+    request
+        .columns
+        .retain(|column| !is_computed(&column.column_name));
+    for (index, column_schema) in schema.column_schemas().iter().enumerate() {
+        if !is_computed(&column_schema.name) {
+            continue;
+        }
+        let Some(vector) = columns_values.get(&column_schema.name) else {
+            continue;
+        };
+        let datatype: ColumnDataTypeWrapper = column_schema
+            .data_type
+            .clone()
+            .try_into()
+            .context(error::ColumnDataTypeSnafu)?;
+        // Computed columns are of the same semantic types as they are declared in the table,
+        // which the table may be created or altered by on demand.
+        let semantic_type = if table_meta.primary_key_indices.contains(&index) {
+            SemanticType::Tag
+        } else if schema.timestamp_index() == Some(index) {
+            SemanticType::Timestamp
+        } else {
+            SemanticType::Field
+        };
+        let mut column = Column {
+            column_name: column_schema.name.clone(),
+            semantic_type: semantic_type.into(),
+            datatype: datatype.datatype() as i32,
+            ..Default::default()
+        };
+        push_vals(&mut column, 0, vector.clone());
+        request.columns.push(column);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::borrow::Cow;
@@ -648,12 +1378,15 @@ mod tests {
     use std::sync::atomic::AtomicU32;
 
     use api::v1::column::Values;
+    use api::v1::ColumnDataType;
     use catalog::helper::{TableGlobalKey, TableGlobalValue};
     use common_recordbatch::RecordBatches;
     use datatypes::prelude::{ConcreteDataType, Value};
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
     use query::query_engine::options::QueryOptions;
-    use session::context::QueryContext;
+    use query::QueryEngine;
+    use servers::auth::{Identity, MaskingRule, Password, UserProvider};
+    use session::context::{QueryContext, UserInfo};
     use strfmt::Format;
 
     use super::*;
@@ -661,6 +1394,48 @@ mod tests {
     use crate::tests;
     use crate::tests::MockDistributedInstance;
 
+    #[test]
+    fn test_check_table_metric_name() {
+        let new_table = |table_name: &str, metric_name: Option<&str>| -> TableRef {
+            let mut table_options = table::requests::TableOptions::default();
+            if let Some(metric_name) = metric_name {
+                let _ = table_options
+                    .extra_options
+                    .insert(METRIC_NAME_KEY.to_string(), metric_name.to_string());
+            }
+            Arc::new(table::test_util::EmptyTable::new(
+                table::requests::CreateTableRequest {
+                    id: 1,
+                    catalog_name: "greptime".to_string(),
+                    schema_name: "public".to_string(),
+                    table_name: table_name.to_string(),
+                    desc: None,
+                    schema: datatypes::schema::RawSchema::new(vec![]),
+                    region_numbers: vec![0],
+                    primary_key_indices: vec![],
+                    create_if_not_exists: false,
+                    table_options,
+                    engine: MITO_ENGINE.to_string(),
+                },
+            ))
+        };
+
+        // The table created for the metric `a.b`.
+        let table = new_table("a_x2e_b", Some("a.b"));
+        assert_eq!("a.b", table_metric_name(&table));
+        assert!(check_table_metric_name(&table, None).is_ok());
+        assert!(check_table_metric_name(&table, Some("a.b")).is_ok());
+        let error = check_table_metric_name(&table, Some("a_x2e_b")).unwrap_err();
+        assert!(matches!(error, Error::MetricNameConflict { .. }), "{error}");
+
+        // The table created for the metric `a_x2e_b`, or by SQL.
+        let table = new_table("a_x2e_b", None);
+        assert_eq!("a_x2e_b", table_metric_name(&table));
+        assert!(check_table_metric_name(&table, Some("a_x2e_b")).is_ok());
+        let error = check_table_metric_name(&table, Some("a.b")).unwrap_err();
+        assert!(matches!(error, Error::MetricNameConflict { .. }), "{error}");
+    }
+
     #[test]
     fn test_validate_insert_request() {
         let schema = Schema::new(vec![
@@ -726,12 +1501,66 @@ mod tests {
         assert!(validate_insert_request(&schema, &request).is_err());
     }
 
+    #[tokio::test]
+    async fn test_fill_computed_columns() {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("idc", ConcreteDataType::string_datatype(), true)
+                .with_computed_expr("substr(host, 1, 3)"),
+            ColumnSchema::new("host_len", ConcreteDataType::int64_datatype(), true)
+                .with_computed_expr("length(host)"),
+        ]);
+        let table_meta = table::metadata::TableMetaBuilder::default()
+            .schema(Arc::new(schema))
+            .primary_key_indices(vec![0, 1])
+            .next_column_id(3)
+            .build()
+            .unwrap();
+        let mut request = InsertRequest {
+            columns: vec![Column {
+                column_name: "host".to_string(),
+                semantic_type: SemanticType::Tag.into(),
+                values: Some(Values {
+                    string_values: vec!["sh1-host".to_string()],
+                    ..Default::default()
+                }),
+                datatype: ColumnDataType::String as i32,
+                ..Default::default()
+            }],
+            row_count: 1,
+            ..Default::default()
+        };
+
+        fill_computed_columns(&table_meta, &mut request)
+            .await
+            .unwrap();
+
+        let semantic_types = request
+            .columns
+            .iter()
+            .map(|column| (column.column_name.as_str(), column.semantic_type))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("host", SemanticType::Tag as i32),
+                ("idc", SemanticType::Tag as i32),
+                ("host_len", SemanticType::Field as i32),
+            ],
+            semantic_types
+        );
+        assert_eq!(
+            vec!["sh1".to_string()],
+            request.columns[1].values.as_ref().unwrap().string_values
+        );
+    }
+
     #[test]
     fn test_exec_validation() {
         let query_ctx = Arc::new(QueryContext::new());
         let mut plugins = Plugins::new();
         plugins.insert(QueryOptions {
             disallow_cross_schema_query: true,
+            ..Default::default()
         });
         let plugins = Arc::new(plugins);
 
@@ -833,6 +1662,89 @@ mod tests {
         // test describe table
         let sql = "DESC TABLE {catalog}{schema}demo;";
         replace_test(sql, plugins.clone(), &query_ctx);
+
+        // test admin
+        let sql = "ADMIN FLUSH TABLE {catalog}{schema}demo;";
+        replace_test(sql, plugins.clone(), &query_ctx);
+    }
+
+    #[test]
+    fn test_parse_hints() {
+        let hints = parse_hints("SELECT /*+ no_index, parallel(4) */ * FROM demo").unwrap();
+        assert_eq!(
+            QueryHints {
+                no_index: true,
+                parallelism: Some(4),
+                read_replica: false,
+            },
+            hints
+        );
+        assert!(parse_hints("SELECT * FROM demo").unwrap().is_empty());
+        assert!(parse_hints("SELECT /*+ parallel(x) */ * FROM demo").is_err());
+    }
+
+    #[test]
+    fn test_admin_statement_permission() {
+        let query_ctx = Arc::new(QueryContext::new());
+        let stmt = &parse_stmt("ADMIN COMPACT TABLE demo").unwrap()[0];
+
+        let plugins = Arc::new(Plugins::new());
+        assert!(check_permission(plugins, stmt, &query_ctx).is_ok());
+
+        let mut plugins = Plugins::new();
+        plugins.insert(QueryOptions {
+            disallow_admin_statement: true,
+            ..Default::default()
+        });
+        let plugins = Arc::new(plugins);
+        let err = check_permission(plugins.clone(), stmt, &query_ctx).unwrap_err();
+        assert!(matches!(err, Error::AdminStatementDenied { .. }));
+
+        // Other statements are not affected.
+        let stmt = &parse_stmt("SELECT * FROM demo").unwrap()[0];
+        assert!(check_permission(plugins, stmt, &query_ctx).is_ok());
+
+        struct RootAdminUserProvider;
+
+        #[async_trait]
+        impl UserProvider for RootAdminUserProvider {
+            fn name(&self) -> &str {
+                "root_admin_user_provider"
+            }
+
+            async fn authenticate(
+                &self,
+                _id: Identity<'_>,
+                _password: Password<'_>,
+            ) -> servers::auth::Result<UserInfo> {
+                unreachable!()
+            }
+
+            async fn authorize(
+                &self,
+                _catalog: &str,
+                _schema: &str,
+                _user_info: &UserInfo,
+            ) -> servers::auth::Result<()> {
+                Ok(())
+            }
+
+            fn allow_admin(&self, user_info: &UserInfo) -> bool {
+                user_info.username() == "root"
+            }
+        }
+
+        let mut plugins = Plugins::new();
+        plugins.insert::<UserProviderRef>(Arc::new(RootAdminUserProvider));
+        let plugins = Arc::new(plugins);
+        let stmt = &parse_stmt("ADMIN COMPACT TABLE demo").unwrap()[0];
+        query_ctx.set_current_user(UserInfo::new("root"));
+        assert!(check_permission(plugins.clone(), stmt, &query_ctx).is_ok());
+        query_ctx.set_current_user(UserInfo::new("alice"));
+        let err = check_permission(plugins.clone(), stmt, &query_ctx).unwrap_err();
+        assert!(matches!(err, Error::AdminPrivilegeRequired { .. }));
+        let stmt = &parse_stmt("SELECT * FROM demo").unwrap()[0];
+        assert!(check_permission(plugins, stmt, &query_ctx).is_ok());
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -1125,6 +2037,48 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sql_interceptor_with_plan_cache() {
+        struct RewriteHook;
+
+        impl SqlQueryInterceptor for RewriteHook {
+            type Error = Error;
+
+            fn post_parsing(
+                &self,
+                statements: Vec<Statement>,
+                _query_ctx: QueryContextRef,
+            ) -> Result<Vec<Statement>> {
+                // The cached statements are the parsed ones, not the rewritten ones.
+                assert_eq!(parse_stmt("SELECT 1").unwrap(), statements);
+                parse_stmt("SELECT 2")
+            }
+        }
+
+        let standalone = tests::create_standalone_instance("test_interceptor_plan_cache").await;
+        let mut instance = standalone.instance;
+
+        let mut plugins = Plugins::new();
+        plugins.insert::<SqlQueryInterceptorRef<Error>>(Arc::new(RewriteHook));
+        Arc::make_mut(&mut instance).set_plugins(Arc::new(plugins));
+
+        let expected = "\
++----------+
+| Int64(2) |
++----------+
+| 2        |
++----------+";
+        for _ in 0..2 {
+            let output = SqlQueryHandler::do_query(&*instance, "SELECT 1", QueryContext::arc())
+                .await
+                .remove(0)
+                .unwrap();
+            let Output::Stream(stream) = output else { unreachable!() };
+            let batches = RecordBatches::try_collect(stream).await.unwrap();
+            assert_eq!(expected, batches.pretty_print().unwrap());
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_disable_db_operation_plugin() {
         #[derive(Default)]
@@ -1202,4 +2156,162 @@ mod tests {
             unreachable!();
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_read_policy_plugin() {
+        struct TenantUserProvider;
+
+        #[async_trait]
+        impl UserProvider for TenantUserProvider {
+            fn name(&self) -> &str {
+                "tenant_user_provider"
+            }
+
+            async fn authenticate(
+                &self,
+                _id: Identity<'_>,
+                _password: Password<'_>,
+            ) -> servers::auth::Result<UserInfo> {
+                unreachable!()
+            }
+
+            async fn authorize(
+                &self,
+                _catalog: &str,
+                _schema: &str,
+                _user_info: &UserInfo,
+            ) -> servers::auth::Result<()> {
+                Ok(())
+            }
+
+            fn row_filter(
+                &self,
+                user_info: &UserInfo,
+                _catalog: &str,
+                _schema: &str,
+                table: &str,
+            ) -> Option<String> {
+                let username = user_info.username();
+                (username != "root" && username != "auditor" && table == "demo")
+                    .then(|| format!("tenant = '{username}'"))
+            }
+
+            fn column_masks(
+                &self,
+                user_info: &UserInfo,
+                _catalog: &str,
+                _schema: &str,
+                table: &str,
+            ) -> HashMap<String, MaskingRule> {
+                if user_info.username() != "auditor" || table != "demo" {
+                    return HashMap::new();
+                }
+                HashMap::from([
+                    ("tenant".to_string(), MaskingRule::Hash),
+                    ("host".to_string(), MaskingRule::Redact),
+                ])
+            }
+        }
+
+        let standalone = tests::create_standalone_instance("test_read_policy_plugin").await;
+        let mut instance = standalone.instance;
+
+        let mut plugins = Plugins::new();
+        plugins.insert::<UserProviderRef>(Arc::new(TenantUserProvider));
+        Arc::make_mut(&mut instance).set_plugins(Arc::new(plugins));
+
+        let query_as = |user: &str, sql: &str| {
+            let query_ctx = QueryContext::arc();
+            query_ctx.set_current_user(UserInfo::new(user));
+            let instance = instance.clone();
+            let sql = sql.to_string();
+            async move {
+                SqlQueryHandler::do_query(&*instance, &sql, query_ctx)
+                    .await
+                    .remove(0)
+                    .unwrap()
+            }
+        };
+        let pretty_print = |output: Output| async move {
+            let Output::Stream(stream) = output else { unreachable!() };
+            let batches = RecordBatches::try_collect(stream).await.unwrap();
+            batches.pretty_print().unwrap()
+        };
+
+        let sql = "CREATE TABLE demo(tenant STRING, host STRING, val DOUBLE, \
+                   ts TIMESTAMP TIME INDEX, PRIMARY KEY(tenant, host)) engine=mito";
+        let _ = query_as("root", sql).await;
+        let sql = "INSERT INTO demo(tenant, host, val, ts) VALUES \
+                   ('alice', 'a1', 1, 1), ('alice', 'a2', 2, 2), ('bob', 'b1', 3, 3)";
+        let _ = query_as("root", sql).await;
+
+        let sql = "SELECT tenant, host FROM demo ORDER BY host";
+        let expected = "\
++--------+------+
+| tenant | host |
++--------+------+
+| alice  | a1   |
+| alice  | a2   |
++--------+------+";
+        assert_eq!(expected, pretty_print(query_as("alice", sql).await).await);
+
+        // The filter applies to the aliased table and the tables in subqueries too.
+        let sql =
+            "SELECT d.host FROM demo d WHERE d.host IN (SELECT host FROM demo) ORDER BY d.host";
+        let expected = "\
++------+
+| host |
++------+
+| b1   |
++------+";
+        assert_eq!(expected, pretty_print(query_as("bob", sql).await).await);
+
+        // The masked columns are seen by the filters too, and can still be qualified.
+        let sql = "SELECT tenant, host FROM demo WHERE demo.host = '***' ORDER BY ts";
+        let expected = "\
++----------------------------------+------+
+| tenant                           | host |
++----------------------------------+------+
+| 6384e2b2184bcbf58eccf10ca7a6563c | ***  |
+| 6384e2b2184bcbf58eccf10ca7a6563c | ***  |
+| 9f9d51bc70ef21ca5c14f307980a29d8 | ***  |
++----------------------------------+------+";
+        assert_eq!(expected, pretty_print(query_as("auditor", sql).await).await);
+
+        let sql = "SELECT count(*) FROM demo";
+        let expected = "\
++-----------------+
+| COUNT(UInt8(1)) |
++-----------------+
+| 3               |
++-----------------+";
+        assert_eq!(expected, pretty_print(query_as("root", sql).await).await);
+
+        // TQL reads the table with the policies of the user too.
+        let count_rows = |output: Output| async move {
+            let Output::Stream(stream) = output else { unreachable!() };
+            let batches = RecordBatches::try_collect(stream).await.unwrap();
+            batches.iter().map(|b| b.num_rows()).sum::<usize>()
+        };
+        let sql = "TQL EVAL (1, 1, '1s') demo";
+        assert_eq!(2, count_rows(query_as("alice", sql).await).await);
+        assert_eq!(3, count_rows(query_as("root", sql).await).await);
+
+        // The engine of the scripts applies the policies of the default user.
+        let query_engine = ReadPolicyQueryEngine::new(
+            instance.query_engine.clone(),
+            instance.statement_executor(),
+        );
+        let stmt = QueryLanguageParser::parse_sql("SELECT host FROM demo").unwrap();
+        let plan = query_engine
+            .planner()
+            .plan(stmt, QueryContext::arc())
+            .await
+            .unwrap();
+        let output = query_engine
+            .execute(plan, QueryContext::arc())
+            .await
+            .unwrap();
+        assert_eq!(0, count_rows(output).await);
+    }
 }
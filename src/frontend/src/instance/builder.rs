@@ -0,0 +1,246 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Builder to construct a frontend [Instance] programmatically, for embedders that
+//! start a frontend in their own process rather than through the command line.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use catalog::CatalogManagerRef;
+use common_base::Plugins;
+use datanode::instance::InstanceRef as DnInstanceRef;
+
+use crate::error::Result;
+use crate::frontend::FrontendOptions;
+use crate::instance::Instance;
+
+/// Protocol servers of a frontend that listen on an address of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerKind {
+    Grpc,
+    Http,
+    Mysql,
+    Postgres,
+    Opentsdb,
+    Prom,
+}
+
+pub struct FrontendBuilder {
+    options: Option<FrontendOptions>,
+    plugins: Option<Arc<Plugins>>,
+    catalog_manager: Option<CatalogManagerRef>,
+    datanode_instance: Option<DnInstanceRef>,
+    servers: Option<HashSet<ServerKind>>,
+}
+
+impl FrontendBuilder {
+    pub fn new() -> Self {
+        Self {
+            options: None,
+            plugins: None,
+            catalog_manager: None,
+            datanode_instance: None,
+            servers: None,
+        }
+    }
+
+    pub fn options(mut self, options: FrontendOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Plugins to customize the query engine, authentication and so on.
+    pub fn plugins(mut self, plugins: Arc<Plugins>) -> Self {
+        self.plugins = Some(plugins);
+        self
+    }
+
+    /// Catalog manager the queries resolve tables from, instead of the catalog of the
+    /// datanode in standalone mode or the catalog in metasrv in distributed mode. Only
+    /// the queries planned by the query engine (SQL queries, TQL and PromQL) use it,
+    /// writes, DDL and the other statements like `SHOW TABLES` still use the catalog of
+    /// the datanode or the metasrv.
+    pub fn catalog_manager(mut self, catalog_manager: CatalogManagerRef) -> Self {
+        self.catalog_manager = Some(catalog_manager);
+        self
+    }
+
+    /// Datanode instance to run against in standalone mode, the frontend runs in
+    /// distributed mode without it.
+    pub fn datanode_instance(mut self, datanode_instance: DnInstanceRef) -> Self {
+        self.datanode_instance = Some(datanode_instance);
+        self
+    }
+
+    /// Only starts the given servers among those configured in the options. All
+    /// configured servers are started by default.
+    pub fn servers(mut self, servers: impl IntoIterator<Item = ServerKind>) -> Self {
+        self.servers = Some(servers.into_iter().collect());
+        self
+    }
+
+    /// Builds the instance with its servers, the servers are started along with the
+    /// instance.
+    pub async fn build(self) -> Result<Instance> {
+        let FrontendBuilder {
+            options,
+            plugins,
+            catalog_manager,
+            datanode_instance,
+            servers,
+        } = self;
+
+        let mut options = options.unwrap_or_default();
+        if let Some(servers) = servers {
+            retain_servers(&mut options, &servers);
+        }
+        let plugins = plugins.unwrap_or_default();
+
+        let mut instance = match datanode_instance {
+            Some(datanode_instance) => {
+                Instance::try_new_standalone_with(datanode_instance, plugins, catalog_manager)
+                    .await?
+            }
+            None => Instance::try_new_distributed_with(&options, plugins, catalog_manager).await?,
+        };
+        instance.build_servers(&options).await?;
+        Ok(instance)
+    }
+}
+
+impl Default for FrontendBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Disables the servers not in `servers` in the `options`.
+fn retain_servers(options: &mut FrontendOptions, servers: &HashSet<ServerKind>) {
+    let enabled = |kind| servers.contains(&kind);
+    if !enabled(ServerKind::Grpc) {
+        options.grpc_options = None;
+    }
+    if !enabled(ServerKind::Http) {
+        options.http_options = None;
+    }
+    if !enabled(ServerKind::Mysql) {
+        options.mysql_options = None;
+    }
+    if !enabled(ServerKind::Postgres) {
+        options.postgres_options = None;
+    }
+    if !enabled(ServerKind::Opentsdb) {
+        options.opentsdb_options = None;
+    }
+    if !enabled(ServerKind::Prom) {
+        options.prom_options = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use catalog::local::MemoryCatalogManager;
+    use catalog::{CatalogManager, RegisterTableRequest};
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_query::Output;
+    use servers::query_handler::sql::SqlQueryHandler;
+    use session::context::QueryContext;
+    use table::table::numbers::NumbersTable;
+
+    use super::*;
+    use crate::tests::create_tmp_dir_and_datanode_opts;
+
+    #[test]
+    fn test_retain_servers() {
+        let mut options = FrontendOptions::default();
+        retain_servers(
+            &mut options,
+            &HashSet::from([ServerKind::Http, ServerKind::Mysql]),
+        );
+        assert!(options.http_options.is_some());
+        assert!(options.mysql_options.is_some());
+        assert!(options.grpc_options.is_none());
+        assert!(options.postgres_options.is_none());
+        assert!(options.opentsdb_options.is_none());
+        assert!(options.prom_options.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_build_standalone_with_catalog_manager() {
+        let (opts, _guard) = create_tmp_dir_and_datanode_opts("build_standalone");
+        let dn_instance = Arc::new(datanode::instance::Instance::new(&opts).await.unwrap());
+        dn_instance.start().await.unwrap();
+
+        let query_catalog_manager = Arc::new(MemoryCatalogManager::default());
+        let _ = query_catalog_manager
+            .register_table(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: "numbers".to_string(),
+                table_id: 1,
+                table: Arc::new(NumbersTable::default()),
+            })
+            .await
+            .unwrap();
+
+        let instance = FrontendBuilder::new()
+            .datanode_instance(dn_instance.clone())
+            .catalog_manager(query_catalog_manager)
+            .servers([])
+            .build()
+            .await
+            .unwrap();
+        let execute = |sql: &'static str| {
+            let instance = &instance;
+            async move {
+                SqlQueryHandler::do_query(instance, sql, QueryContext::arc())
+                    .await
+                    .remove(0)
+            }
+        };
+
+        // DDL and writes go to the catalog of the datanode.
+        let output = execute("CREATE TABLE demo(host STRING, ts TIMESTAMP TIME INDEX)")
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+        let output = execute("INSERT INTO demo VALUES ('host1', 1)")
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+        assert!(dn_instance
+            .catalog_manager()
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+            .await
+            .unwrap()
+            .is_some());
+
+        // Queries resolve tables from the given catalog.
+        assert!(execute("SELECT * FROM numbers LIMIT 1").await.is_ok());
+        assert!(execute("SELECT * FROM demo").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_build_distributed_without_metasrv() {
+        let result = FrontendBuilder::new()
+            .options(FrontendOptions::default())
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::MissingMetasrvOpts { .. })
+        ));
+    }
+}
@@ -14,8 +14,10 @@
 
 mod grpc;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use api::helper::ColumnDataTypeWrapper;
 use api::v1::{
@@ -23,23 +25,26 @@ use api::v1::{
     FlushTableExpr, InsertRequest, TableId,
 };
 use async_trait::async_trait;
-use catalog::helper::{SchemaKey, SchemaValue};
+use catalog::helper::{SchemaKey, SchemaValue, TableGlobalKey, TableGlobalValue};
 use catalog::{CatalogManager, DeregisterTableRequest, RegisterTableRequest};
 use chrono::DateTime;
 use client::Database;
-use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MITO_ENGINE};
 use common_catalog::format_full_table_name;
 use common_error::prelude::BoxedError;
+use common_grpc::flight::ADMIN_ACTION;
 use common_query::Output;
-use common_telemetry::debug;
+use common_recordbatch::RecordBatches;
+use common_telemetry::{debug, error, info, warn};
+use common_time::Timestamp;
 use datanode::instance::sql::table_idents_to_full_name;
 use datanode::sql::SqlHandler;
 use datatypes::prelude::ConcreteDataType;
-use datatypes::schema::RawSchema;
+use datatypes::schema::{Metadata, RawSchema, COMPUTED_EXPR_KEY, UNIT_KEY};
 use meta_client::client::MetaClient;
 use meta_client::rpc::router::DeleteRequest as MetaDeleteRequest;
 use meta_client::rpc::{
-    CompareAndPutRequest, CreateRequest as MetaCreateRequest, Partition as MetaPartition,
+    CompareAndPutRequest, CreateRequest as MetaCreateRequest, Partition as MetaPartition, Peer,
     RouteRequest, RouteResponse, TableName,
 };
 use partition::manager::PartitionInfo;
@@ -49,12 +54,21 @@ use query::query_engine::SqlStatementExecutor;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::ast::{Ident, Value as SqlValue};
-use sql::statements::create::{PartitionEntry, Partitions};
+use sql::statements::admin::{Admin, AdminAttach, AdminMigrate};
+use sql::statements::alter::AlterTableOperation;
+use sql::statements::create::{
+    column_unit, computed_expr, CloneTable, CreateTable, PartitionEntry, Partitions,
+};
 use sql::statements::statement::Statement;
 use sql::statements::{self, sql_value_to_value};
+use store_api::storage::RegionId;
 use table::engine::TableReference;
 use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
-use table::requests::TableOptions;
+use table::requests::{
+    AdminRequest, AlterKind, AlterTableRequest, AttachTableRequest, CloneDataRequest,
+    CompactTableRequest, DropRangeTableRequest, FenceRegionRequest, PurgeTableRequest,
+    ScrubTableRequest, TableOptions,
+};
 use table::table::AlterContext;
 use table::TableRef;
 
@@ -62,16 +76,21 @@ use crate::catalog::FrontendCatalogManager;
 use crate::datanode::DatanodeClients;
 use crate::error::{
     self, AlterExprToRequestSnafu, CatalogEntrySerdeSnafu, CatalogSnafu, ColumnDataTypeSnafu,
-    DeserializePartitionSnafu, InvokeDatanodeSnafu, ParseSqlSnafu, PrimaryKeyNotFoundSnafu,
-    RequestDatanodeSnafu, RequestMetaSnafu, Result, SchemaExistsSnafu, StartMetaClientSnafu,
-    TableAlreadyExistSnafu, TableNotFoundSnafu, TableSnafu, ToTableDeleteRequestSnafu,
-    ToTableInsertRequestSnafu, UnrecognizedTableOptionSnafu,
+    DeserializePartitionSnafu, EncodeJsonSnafu, InvalidRegionMigrationSnafu, InvokeDatanodeSnafu,
+    MigrateRegionTimeoutSnafu, ParseSqlSnafu, PrimaryKeyNotFoundSnafu, RequestDatanodeSnafu,
+    RequestMetaSnafu, Result, SchemaExistsSnafu, StartMetaClientSnafu, TableAlreadyExistSnafu,
+    TableNotFoundSnafu, TableSnafu, ToTableDeleteRequestSnafu, ToTableInsertRequestSnafu,
+    UnrecognizedTableOptionSnafu,
 };
 use crate::expr_factory;
 use crate::table::DistTable;
 
 const MAX_VALUE: &str = "MAXVALUE";
 
+/// How long to wait for metasrv to move the route of a migrated region.
+const MIGRATE_REGION_TIMEOUT: Duration = Duration::from_secs(180);
+const MIGRATE_REGION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone)]
 pub(crate) struct DistInstance {
     meta_client: Arc<MetaClient>,
@@ -96,6 +115,22 @@ impl DistInstance {
         &self,
         create_table: &mut CreateTableExpr,
         partitions: Option<Partitions>,
+    ) -> Result<TableRef> {
+        self.create_table_with_column_metadata(create_table, partitions, &HashMap::new())
+            .await
+    }
+
+    /// Creates the table like [DistInstance::create_table], with the metadata of the columns
+    /// keyed by the column names, e.g. the expressions of the computed columns and the units.
+    ///
+    /// The metadata can't be carried by the [CreateTableExpr], so it's only kept in the table
+    /// info stored in metasrv, by which the frontends evaluate the computed columns on insertion
+    /// and annotate the query results. The datanodes store them as normal columns.
+    async fn create_table_with_column_metadata(
+        &self,
+        create_table: &mut CreateTableExpr,
+        partitions: Option<Partitions>,
+        column_metadata: &HashMap<String, Metadata>,
     ) -> Result<TableRef> {
         let _timer = common_telemetry::timer!(crate::metrics::DIST_CREATE_TABLE);
         let table_name = TableName::new(
@@ -124,7 +159,7 @@ impl DistInstance {
             };
         }
 
-        let mut table_info = create_table_info(create_table)?;
+        let mut table_info = create_table_info(create_table, column_metadata)?;
 
         let response = self
             .create_table_in_meta(create_table, partitions, &table_info)
@@ -162,6 +197,7 @@ impl DistInstance {
             self.catalog_manager.partition_manager(),
             self.catalog_manager.datanode_clients(),
             self.catalog_manager.backend(),
+            self.catalog_manager.insert_batch_options(),
         ));
 
         let request = RegisterTableRequest {
@@ -203,6 +239,169 @@ impl DistInstance {
         Ok(table)
     }
 
+    async fn create_table_by_stmt(
+        &self,
+        stmt: CreateTable,
+        query_ctx: QueryContextRef,
+    ) -> Result<TableRef> {
+        let create_expr = &mut expr_factory::create_to_expr(&stmt, query_ctx)?;
+        let column_metadata: HashMap<_, _> = stmt
+            .columns
+            .iter()
+            .filter_map(|column| {
+                let mut metadata = Metadata::new();
+                for option in &column.options {
+                    if let Some(expr) = computed_expr(&option.option) {
+                        let _ = metadata.insert(COMPUTED_EXPR_KEY.to_string(), expr.to_string());
+                    }
+                    if let Some(unit) = column_unit(&option.option) {
+                        let _ = metadata.insert(UNIT_KEY.to_string(), unit.to_string());
+                    }
+                }
+                (!metadata.is_empty()).then(|| (column.name.value.clone(), metadata))
+            })
+            .collect();
+        self.create_table_with_column_metadata(create_expr, stmt.partitions, &column_metadata)
+            .await
+    }
+
+    /// Creates a table with the schema and partitions of the source table, then asks the
+    /// datanodes to share the data of the source regions with the regions of the table.
+    ///
+    /// Datanodes only share the data of regions they serve, so each region of the table
+    /// must be placed on the datanode serving the same region of the source. The created
+    /// table is dropped if it isn't, or if the data can't be shared.
+    async fn clone_table(&self, stmt: CloneTable, query_ctx: QueryContextRef) -> Result<Output> {
+        let (catalog, schema, table) = table_idents_to_full_name(&stmt.name, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(error::ExternalSnafu)?;
+        let table_name = TableName::new(catalog, schema, table);
+        let (catalog, schema, table) = table_idents_to_full_name(&stmt.source, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(error::ExternalSnafu)?;
+        let source_name = TableName::new(catalog, schema, table);
+
+        if stmt.if_not_exists
+            && self
+                .catalog_manager
+                .table(
+                    &table_name.catalog_name,
+                    &table_name.schema_name,
+                    &table_name.table_name,
+                )
+                .await
+                .context(CatalogSnafu)?
+                .is_some()
+        {
+            return Ok(Output::AffectedRows(0));
+        }
+
+        let source = self
+            .catalog_manager
+            .table(
+                &source_name.catalog_name,
+                &source_name.schema_name,
+                &source_name.table_name,
+            )
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: source_name.to_string(),
+            })?;
+        let source_info = source.table_info();
+        let meta = &source_info.meta;
+        ensure!(
+            meta.engine == MITO_ENGINE,
+            error::NotSupportedSnafu {
+                feat: format!("Cloning table {} of engine {}", source_name, meta.engine),
+            }
+        );
+        // Column ids of altered tables differ from ids of a newly created table, so
+        // their data files can't be shared.
+        ensure!(
+            meta.column_history.is_empty(),
+            error::NotSupportedSnafu {
+                feat: format!("Cloning table {source_name} with altered columns"),
+            }
+        );
+
+        let partitions = self
+            .catalog_manager
+            .partition_manager()
+            .find_table_partitions(&source_name)
+            .await
+            .context(error::FindTablePartitionRuleSnafu {
+                table_name: &source_name.table_name,
+            })?;
+        let mut create =
+            query::sql::create_table_stmt(&source_info).context(error::ExecuteStatementSnafu)?;
+        create.name = stmt.name;
+        create.if_not_exists = false;
+        create.partitions = create_partitions_stmt(partitions)?;
+        let _ = self.create_table_by_stmt(create, query_ctx).await?;
+
+        if let Err(e) = self.clone_data(&table_name, &source_name).await {
+            if let Err(drop_err) = self.drop_table(table_name.clone()).await {
+                error!(drop_err; table = %table_name, "Failed to drop table after failing to clone it");
+            }
+            return Err(e);
+        }
+
+        info!(table = %table_name, source = %source_name, "Cloned table");
+        Ok(Output::AffectedRows(0))
+    }
+
+    async fn clone_data(&self, table_name: &TableName, source_name: &TableName) -> Result<()> {
+        let route_response = self
+            .meta_client
+            .route(RouteRequest {
+                table_names: vec![table_name.clone(), source_name.clone()],
+            })
+            .await
+            .context(RequestMetaSnafu)?;
+        let region_leaders = |name: &TableName| -> HashMap<u32, Option<Peer>> {
+            route_response
+                .table_routes
+                .iter()
+                .filter(|table_route| &table_route.table.table_name == name)
+                .flat_map(|table_route| &table_route.region_routes)
+                .map(|route| (route.region.id as u32, route.leader_peer.clone()))
+                .collect()
+        };
+        let leaders = region_leaders(table_name);
+        let source_leaders = region_leaders(source_name);
+
+        let mut datanodes = HashSet::new();
+        for (region_number, leader) in leaders {
+            let leader = leader.with_context(|| error::FindDatanodeSnafu {
+                region: region_number as RegionId,
+            })?;
+            ensure!(
+                source_leaders.get(&region_number) == Some(&Some(leader.clone())),
+                error::ClonePlacementMismatchSnafu {
+                    table_name: table_name.to_string(),
+                    source_table_name: source_name.to_string(),
+                    region_number,
+                }
+            );
+            let _ = datanodes.insert(leader);
+        }
+
+        let request = AdminRequest::CloneData(CloneDataRequest {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+            source_catalog_name: source_name.catalog_name.clone(),
+            source_schema_name: source_name.schema_name.clone(),
+            source_table_name: source_name.table_name.clone(),
+        });
+        for datanode in datanodes {
+            debug!(table = %table_name, "Cloning data of {source_name} on Datanode {datanode:?}");
+            let _ = self.admin_datanode(&datanode, &request).await?;
+        }
+        Ok(())
+    }
+
     async fn drop_table(&self, table_name: TableName) -> Result<Output> {
         let _ = self
             .catalog_manager
@@ -247,7 +446,7 @@ impl DistInstance {
         };
         for table_route in route_response.table_routes.iter() {
             for datanode in table_route.find_leaders() {
-                debug!("Dropping table {table_name} on Datanode {datanode:?}");
+                debug!(table = %table_name, "Dropping table on Datanode {datanode:?}");
 
                 let client = self.datanode_clients.get_client(&datanode).await;
                 let client = Database::new(&expr.catalog_name, &expr.schema_name, client);
@@ -303,7 +502,7 @@ impl DistInstance {
                 continue;
             }
             for datanode in table_route.find_leaders() {
-                debug!("Flushing table {table_name} on Datanode {datanode:?}");
+                debug!(table = %table_name, "Flushing table on Datanode {datanode:?}");
 
                 let client = self.datanode_clients.get_client(&datanode).await;
                 let client = Database::new(&expr.catalog_name, &expr.schema_name, client);
@@ -316,6 +515,328 @@ impl DistInstance {
         Ok(Output::AffectedRows(0))
     }
 
+    /// Compacts the table, or only the region `region_id` of it, on the datanodes leading its
+    /// regions.
+    async fn compact_table(&self, table_name: TableName, region_id: Option<u32>) -> Result<Output> {
+        let request = AdminRequest::CompactTable(CompactTableRequest {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+            region_number: region_id,
+            wait: Some(true),
+        });
+        let _ = self
+            .admin_table_regions(&table_name, region_id, &request)
+            .await?;
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Attaches the SST files staged for the regions of the table, each datanode validates
+    /// and copies the files of all its regions before attaching any of them.
+    async fn attach_table(&self, table_name: TableName, attach: &AdminAttach) -> Result<Output> {
+        let request = AdminRequest::AttachTable(AttachTableRequest {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+            region_number: attach.region_number,
+            staging_dir: attach.staging_dir.clone(),
+        });
+        self.admin_table_regions(&table_name, attach.region_number, &request)
+            .await
+    }
+
+    /// Sends the admin request to the datanodes leading the regions of the table, or only the
+    /// region `region_number` of it, and concatenates the records they return.
+    async fn admin_table_regions(
+        &self,
+        table_name: &TableName,
+        region_number: Option<u32>,
+        request: &AdminRequest,
+    ) -> Result<Output> {
+        let _ = self
+            .catalog_manager
+            .table(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            )
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: table_name.to_string(),
+            })?;
+
+        let route_response = self
+            .meta_client
+            .route(RouteRequest {
+                table_names: vec![table_name.clone()],
+            })
+            .await
+            .context(RequestMetaSnafu)?;
+
+        let leaders = match region_number {
+            Some(region_number) => {
+                let leader = route_response
+                    .table_routes
+                    .iter()
+                    .flat_map(|table_route| &table_route.region_routes)
+                    .find(|route| route.region.id as u32 == region_number)
+                    .and_then(|route| route.leader_peer.clone())
+                    .context(error::FindDatanodeSnafu {
+                        region: region_number as RegionId,
+                    })?;
+                HashSet::from([leader])
+            }
+            None => route_response
+                .table_routes
+                .iter()
+                .flat_map(|table_route| table_route.find_leaders())
+                .collect(),
+        };
+
+        let mut batches = Vec::new();
+        for datanode in leaders {
+            debug!(table = %table_name, "Sending {request:?} to Datanode {datanode:?}");
+
+            match self.admin_datanode(&datanode, request).await? {
+                Output::RecordBatches(records) => batches.extend(records.take()),
+                Output::Stream(stream) => batches.extend(
+                    common_recordbatch::util::collect(stream)
+                        .await
+                        .context(error::CollectRecordbatchSnafu)?,
+                ),
+                Output::AffectedRows(_) => {}
+            }
+        }
+
+        let Some(schema) = batches.first().map(|batch| batch.schema.clone()) else {
+            return Ok(Output::AffectedRows(0));
+        };
+        let records =
+            RecordBatches::try_new(schema, batches).context(error::CreateRecordbatchSnafu)?;
+        Ok(Output::RecordBatches(records))
+    }
+
+    /// Sends the admin request to the datanode as a Flight action.
+    async fn admin_datanode(&self, datanode: &Peer, request: &AdminRequest) -> Result<Output> {
+        let body = serde_json::to_vec(request).context(EncodeJsonSnafu)?;
+        let client = self.datanode_clients.get_client(datanode).await;
+        let client = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, client);
+        client
+            .do_action(ADMIN_ACTION, body)
+            .await
+            .context(RequestDatanodeSnafu)
+    }
+
+    /// Migrates a region of the table from one datanode to another. The region is fenced on
+    /// the source datanode, which flushes it and rejects the writes after the flush, then it's
+    /// assigned to the target datanode in the table global value.
+    /// The datanodes open or close the table as their assignments change, and metasrv moves
+    /// the route of the region once the target datanode serves it, which this waits for.
+    ///
+    /// As datanodes open or close a table as a whole, the source datanode must not serve other
+    /// regions of the table, nor the target datanode any region of it. The datanodes must share
+    /// the object store. If the migration fails after the region is fenced, the assignment is
+    /// rolled back and the region is unfenced, so the source datanode keeps serving it.
+    async fn migrate_region(
+        &self,
+        table_name: TableName,
+        migrate: &AdminMigrate,
+    ) -> Result<Output> {
+        let AdminMigrate {
+            region_number,
+            from_peer,
+            to_peer,
+            ..
+        } = *migrate;
+        let invalid = |reason: String| InvalidRegionMigrationSnafu {
+            table_name: table_name.to_string(),
+            region_number,
+            reason,
+        };
+        ensure!(
+            from_peer != to_peer,
+            invalid("the source and target datanodes are the same".to_string())
+        );
+
+        let leader = self.region_leader_peer(&table_name, region_number).await?;
+        let leader = match leader {
+            Some(leader) if leader.id == from_peer => leader,
+            Some(leader) => {
+                return invalid(format!("the region is served by datanode {}", leader.id)).fail()
+            }
+            None => return invalid("the region has no leader".to_string()).fail(),
+        };
+
+        let backend = self.catalog_manager.backend();
+        let key = TableGlobalKey {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+        }
+        .to_string();
+        let raw = backend
+            .get(key.as_bytes())
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: table_name.to_string(),
+            })?
+            .1;
+        let mut value = TableGlobalValue::from_bytes(&raw).context(CatalogEntrySerdeSnafu)?;
+        ensure!(
+            value.regions_id_map.get(&from_peer) == Some(&vec![region_number]),
+            invalid(format!(
+                "datanode {from_peer} serves other regions of the table"
+            ))
+        );
+        ensure!(
+            !value.regions_id_map.contains_key(&to_peer),
+            invalid(format!(
+                "datanode {to_peer} already serves regions of the table"
+            ))
+        );
+        let _ = value.regions_id_map.remove(&from_peer);
+        let _ = value.regions_id_map.insert(to_peer, vec![region_number]);
+        let new_raw = value.as_bytes().context(CatalogEntrySerdeSnafu)?;
+
+        let fence = FenceRegionRequest {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+            region_number,
+        };
+        let _ = self
+            .admin_datanode(&leader, &AdminRequest::FenceRegion(fence.clone()))
+            .await?;
+
+        // The source datanode rejects the writes to the region from now on, so the region is
+        // unfenced on every failure before the target datanode takes it over.
+        let assigned = match backend
+            .compare_and_set(key.as_bytes(), &raw, &new_raw)
+            .await
+            .context(CatalogSnafu)
+        {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(_)) => invalid("the table is altered during the migration".to_string()).fail(),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = assigned {
+            self.unfence_region(&leader, fence).await;
+            return Err(e);
+        }
+        info!(
+            table = %table_name,
+            region = region_number,
+            "Assigned region from datanode {} to datanode {}",
+            from_peer,
+            to_peer
+        );
+
+        if let Err(e) = self
+            .wait_region_leader(&table_name, region_number, to_peer)
+            .await
+        {
+            // Assigns the region back, so the source datanode keeps serving it.
+            match backend
+                .compare_and_set(key.as_bytes(), &new_raw, &raw)
+                .await
+            {
+                Ok(Ok(())) => self.unfence_region(&leader, fence).await,
+                Ok(Err(_)) => warn!(
+                    "Failed to assign region {} of table {} back to datanode {}, the table is altered",
+                    region_number, table_name, from_peer
+                ),
+                Err(err) => warn!(
+                    "Failed to assign region {} of table {} back to datanode {}, error: {}",
+                    region_number, table_name, from_peer, err
+                ),
+            }
+            return Err(e);
+        }
+        self.catalog_manager
+            .partition_manager()
+            .table_routes()
+            .invalidate_table_route(&table_name)
+            .await;
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Waits until the datanode leads the region in the route from metasrv.
+    async fn wait_region_leader(
+        &self,
+        table_name: &TableName,
+        region_number: u32,
+        to_peer: u64,
+    ) -> Result<()> {
+        let deadline = Instant::now() + MIGRATE_REGION_TIMEOUT;
+        while self.region_leader(table_name, region_number).await? != Some(to_peer) {
+            ensure!(
+                Instant::now() < deadline,
+                MigrateRegionTimeoutSnafu {
+                    table_name: table_name.to_string(),
+                    region_number,
+                    to_peer,
+                }
+            );
+            tokio::time::sleep(MIGRATE_REGION_POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+
+    /// Unfences the region on the datanode after a failed migration. The failure is only
+    /// logged, as the error of the migration is more useful to the caller.
+    async fn unfence_region(&self, datanode: &Peer, request: FenceRegionRequest) {
+        let region_number = request.region_number;
+        let table = format_full_table_name(
+            &request.catalog_name,
+            &request.schema_name,
+            &request.table_name,
+        );
+        if let Err(e) = self
+            .admin_datanode(datanode, &AdminRequest::UnfenceRegion(request))
+            .await
+        {
+            warn!(
+                "Failed to unfence region {} of table {} on datanode {}, error: {}",
+                region_number, table, datanode.id, e
+            );
+        }
+    }
+
+    /// Returns the id of the datanode leading the region in the route from metasrv.
+    async fn region_leader(
+        &self,
+        table_name: &TableName,
+        region_number: u32,
+    ) -> Result<Option<u64>> {
+        Ok(self
+            .region_leader_peer(table_name, region_number)
+            .await?
+            .map(|peer| peer.id))
+    }
+
+    /// Returns the datanode leading the region in the route from metasrv.
+    async fn region_leader_peer(
+        &self,
+        table_name: &TableName,
+        region_number: u32,
+    ) -> Result<Option<Peer>> {
+        let route_response = self
+            .meta_client
+            .route(RouteRequest {
+                table_names: vec![table_name.clone()],
+            })
+            .await
+            .context(RequestMetaSnafu)?;
+        let region_route = route_response
+            .table_routes
+            .iter()
+            .flat_map(|table_route| &table_route.region_routes)
+            .find(|route| route.region.id as u32 == region_number);
+        Ok(region_route.and_then(|route| route.leader_peer.clone()))
+    }
+
     async fn handle_statement(
         &self,
         stmt: Statement,
@@ -324,22 +845,30 @@ impl DistInstance {
         match stmt {
             Statement::CreateDatabase(stmt) => {
                 let expr = CreateDatabaseExpr {
-                    database_name: stmt.name.to_string(),
+                    database_name: stmt.database_name(),
                     create_if_not_exists: stmt.if_not_exists,
                 };
                 self.handle_create_database(expr, query_ctx).await
             }
             Statement::CreateTable(stmt) => {
-                let create_expr = &mut expr_factory::create_to_expr(&stmt, query_ctx)?;
-                let _ = self.create_table(create_expr, stmt.partitions).await?;
+                let _ = self.create_table_by_stmt(stmt, query_ctx).await?;
                 Ok(Output::AffectedRows(0))
             }
+            Statement::CloneTable(stmt) => self.clone_table(stmt, query_ctx).await,
             Statement::CreateExternalTable(stmt) => {
                 let create_expr = &mut expr_factory::create_external_expr(stmt, query_ctx).await?;
                 self.create_table(create_expr, None).await?;
                 Ok(Output::AffectedRows(0))
             }
             Statement::Alter(alter_table) => {
+                if let AlterTableOperation::SetOptions { options } = alter_table.alter_operation() {
+                    let (catalog, schema, table) =
+                        table_idents_to_full_name(alter_table.table_name(), query_ctx)
+                            .map_err(BoxedError::new)
+                            .context(error::ExternalSnafu)?;
+                    let table_name = TableName::new(catalog, schema, table);
+                    return self.set_table_options(table_name, options.clone()).await;
+                }
                 let expr = grpc::to_alter_expr(alter_table, query_ctx)?;
                 self.handle_alter_table(expr).await
             }
@@ -389,6 +918,96 @@ impl DistInstance {
 
                 self.show_create_table(table_name, table_ref).await
             }
+            Statement::Admin(Admin::Flush(flush)) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&flush.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                self.flush_table(table_name, flush.region_number).await
+            }
+            Statement::Admin(Admin::Compact(compact)) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&compact.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                self.compact_table(table_name, compact.region_number).await
+            }
+            Statement::Admin(Admin::Attach(attach)) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&attach.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                self.attach_table(table_name, &attach).await
+            }
+            Statement::Admin(Admin::Purge(purge)) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&purge.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                let request = AdminRequest::PurgeTable(PurgeTableRequest {
+                    catalog_name: table_name.catalog_name.clone(),
+                    schema_name: table_name.schema_name.clone(),
+                    table_name: table_name.table_name.clone(),
+                    region_number: purge.region_number,
+                    dry_run: purge.dry_run,
+                });
+                self.admin_table_regions(&table_name, purge.region_number, &request)
+                    .await
+            }
+            Statement::Admin(Admin::DropRange(drop_range)) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&drop_range.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                let parse_timestamp = |raw: &str| {
+                    Timestamp::from_str(raw).context(error::ParseTimestampSnafu { raw })
+                };
+                let request = AdminRequest::DropRange(DropRangeTableRequest {
+                    catalog_name: table_name.catalog_name.clone(),
+                    schema_name: table_name.schema_name.clone(),
+                    table_name: table_name.table_name.clone(),
+                    region_number: drop_range.region_number,
+                    start: parse_timestamp(&drop_range.start)?,
+                    end: parse_timestamp(&drop_range.end)?,
+                    dry_run: drop_range.dry_run,
+                });
+                self.admin_table_regions(&table_name, drop_range.region_number, &request)
+                    .await
+            }
+            Statement::Admin(Admin::Scrub(scrub)) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&scrub.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                let request = AdminRequest::ScrubTable(ScrubTableRequest {
+                    catalog_name: table_name.catalog_name.clone(),
+                    schema_name: table_name.schema_name.clone(),
+                    table_name: table_name.table_name.clone(),
+                    region_number: scrub.region_number,
+                });
+                self.admin_table_regions(&table_name, scrub.region_number, &request)
+                    .await
+            }
+            Statement::Admin(Admin::Migrate(migrate)) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&migrate.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                self.migrate_region(table_name, &migrate).await
+            }
+            // The view definitions are kept in the system catalog of the datanode, which is
+            // only available in standalone mode.
+            Statement::CreateView(_) | Statement::DropView(_) => error::NotSupportedSnafu {
+                feat: "Views in distributed mode",
+            }
+            .fail(),
             _ => error::NotSupportedSnafu {
                 feat: format!("{stmt:?}"),
             }
@@ -494,6 +1113,39 @@ impl DistInstance {
         Ok(Output::AffectedRows(0))
     }
 
+    /// Sets the options of the table, on its datanodes and in its global value.
+    async fn set_table_options(
+        &self,
+        table_name: TableName,
+        options: HashMap<String, String>,
+    ) -> Result<Output> {
+        let table = self
+            .catalog_manager
+            .table(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            )
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: table_name.to_string(),
+            })?;
+
+        let request = AlterTableRequest {
+            catalog_name: table_name.catalog_name,
+            schema_name: table_name.schema_name,
+            table_name: table_name.table_name,
+            alter_kind: AlterKind::SetOptions { options },
+        };
+        table
+            .alter(AlterContext::new(), &request)
+            .await
+            .context(TableSnafu)?;
+
+        Ok(Output::AffectedRows(0))
+    }
+
     async fn create_table_in_meta(
         &self,
         create_table: &CreateTableExpr,
@@ -639,7 +1291,10 @@ fn create_partitions_stmt(partitions: Vec<PartitionInfo>) -> Result<Option<Parti
     }))
 }
 
-fn create_table_info(create_table: &CreateTableExpr) -> Result<RawTableInfo> {
+fn create_table_info(
+    create_table: &CreateTableExpr,
+    column_metadata: &HashMap<String, Metadata>,
+) -> Result<RawTableInfo> {
     let mut column_schemas = Vec::with_capacity(create_table.column_defs.len());
     let mut column_name_to_index_map = HashMap::new();
 
@@ -648,7 +1303,10 @@ fn create_table_info(create_table: &CreateTableExpr) -> Result<RawTableInfo> {
             column_def::try_as_column_schema(column).context(error::InvalidColumnDefSnafu {
                 column: &column.name,
             })?;
-        let schema = schema.with_time_index(column.name == create_table.time_index);
+        let mut schema = schema.with_time_index(column.name == create_table.time_index);
+        if let Some(metadata) = column_metadata.get(&column.name) {
+            schema.mut_metadata().extend(metadata.clone());
+        }
 
         column_schemas.push(schema);
         column_name_to_index_map.insert(column.name.clone(), idx);
@@ -686,6 +1344,7 @@ fn create_table_info(create_table: &CreateTableExpr) -> Result<RawTableInfo> {
         options: TableOptions::try_from(&create_table.table_options)
             .context(UnrecognizedTableOptionSnafu)?,
         created_on: DateTime::default(),
+        column_history: vec![],
     };
 
     let desc = if create_table.desc.is_empty() {
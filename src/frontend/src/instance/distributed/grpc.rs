@@ -104,6 +104,12 @@ pub(crate) fn to_alter_expr(
         AlterTableOperation::RenameTable { new_table_name } => Kind::RenameTable(RenameTable {
             new_table_name: new_table_name.to_string(),
         }),
+        AlterTableOperation::SetOptions { .. } => {
+            return error::NotSupportedSnafu {
+                feat: "SET table options in gRPC alter",
+            }
+            .fail();
+        }
     };
 
     Ok(AlterExpr {
@@ -31,7 +31,7 @@ impl GrpcQueryHandler for Instance {
 
     async fn do_query(&self, request: Request, ctx: QueryContextRef) -> Result<Output> {
         let output = match request {
-            Request::Insert(request) => self.handle_insert(request, ctx).await?,
+            Request::Insert(request) => self.handle_insert(request, None, ctx).await?,
             Request::Query(query_request) => {
                 let query = query_request
                     .query
@@ -74,7 +74,14 @@ impl GrpcQueryHandler for Instance {
                     }
                 }
             }
-            Request::Ddl(_) | Request::Delete(_) => {
+            Request::Ddl(_) => {
+                let result =
+                    GrpcQueryHandler::do_query(self.grpc_query_handler.as_ref(), request, ctx)
+                        .await;
+                self.plan_cache.invalidate_all();
+                result?
+            }
+            Request::Delete(_) => {
                 GrpcQueryHandler::do_query(self.grpc_query_handler.as_ref(), request, ctx).await?
             }
         };
@@ -29,7 +29,7 @@ impl InfluxdbLineProtocolHandler for Instance {
         ctx: QueryContextRef,
     ) -> servers::error::Result<()> {
         let requests = request.try_into()?;
-        self.handle_inserts(requests, ctx)
+        self.handle_metric_inserts(requests, ctx)
             .await
             .map_err(BoxedError::new)
             .context(servers::error::ExecuteGrpcQuerySnafu)?;
@@ -43,6 +43,7 @@ mod test {
 
     use common_query::Output;
     use common_recordbatch::RecordBatches;
+    use datatypes::prelude::ConcreteDataType;
     use servers::query_handler::sql::SqlQueryHandler;
     use session::context::QueryContext;
 
@@ -67,6 +68,105 @@ mod test {
         test_put_influxdb_lines(instance).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_influxdb_lines_with_ingest_rule() {
+        let standalone =
+            tests::create_standalone_instance("test_put_influxdb_lines_with_ingest_rule").await;
+        let instance = &standalone.instance;
+
+        let sql = "CREATE TABLE monitor2 (host STRING, cpu DOUBLE, ts TIMESTAMP TIME INDEX, \
+            PRIMARY KEY(host)) WITH(ingest_rule = 'avg:1s')";
+        let output = instance.do_query(sql, QueryContext::arc()).await.remove(0);
+        assert!(matches!(output.unwrap(), Output::AffectedRows(0)));
+
+        let lines = r"
+monitor2,host=host1 cpu=1 1663840496100000000
+monitor2,host=host1 cpu=3 1663840496900000000
+monitor2,host=host2 cpu=5 1663840496400000000
+monitor2,host=host1 cpu=10 1663840497000000000";
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: lines.to_string(),
+        };
+        instance.exec(&request, QueryContext::arc()).await.unwrap();
+
+        // Each write is aggregated on its own.
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: "monitor2,host=host1 cpu=14 1663840498500000000\n\
+                monitor2,host=host1 cpu=16 1663840498600000000"
+                .to_string(),
+        };
+        instance.exec(&request, QueryContext::arc()).await.unwrap();
+
+        let mut output = instance
+            .do_query(
+                "SELECT ts, host, cpu FROM monitor2 ORDER BY host, ts",
+                QueryContext::arc(),
+            )
+            .await;
+        let output = output.remove(0).unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        assert_eq!(
+            recordbatches.pretty_print().unwrap(),
+            "\
++---------------------+-------+------+
+| ts                  | host  | cpu  |
++---------------------+-------+------+
+| 2022-09-22T09:54:56 | host1 | 2.0  |
+| 2022-09-22T09:54:57 | host1 | 10.0 |
+| 2022-09-22T09:54:58 | host1 | 15.0 |
+| 2022-09-22T09:54:56 | host2 | 5.0  |
++---------------------+-------+------+"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_influxdb_lines_with_bool_and_uint() {
+        let standalone =
+            tests::create_standalone_instance("test_put_influxdb_lines_with_bool_and_uint").await;
+        let instance = &standalone.instance;
+
+        let lines = r"
+monitor3,host=host1 up=t,requests=10u 1663840496100023100
+monitor3,host=host2 up=false,requests=18446744073709551615u 1663840496400340001";
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: lines.to_string(),
+        };
+        instance.exec(&request, QueryContext::arc()).await.unwrap();
+
+        let mut output = instance
+            .do_query(
+                "SELECT ts, host, up, requests FROM monitor3 ORDER BY ts",
+                QueryContext::arc(),
+            )
+            .await;
+        let output = output.remove(0).unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        let column_schemas = recordbatches.schema().column_schemas().to_vec();
+        assert_eq!(
+            column_schemas[2].data_type,
+            ConcreteDataType::boolean_datatype()
+        );
+        assert_eq!(
+            column_schemas[3].data_type,
+            ConcreteDataType::uint64_datatype()
+        );
+        assert_eq!(
+            recordbatches.pretty_print().unwrap(),
+            "\
++-------------------------+-------+-------+----------------------+
+| ts                      | host  | up    | requests             |
++-------------------------+-------+-------+----------------------+
+| 2022-09-22T09:54:56.100 | host1 | true  | 10                   |
+| 2022-09-22T09:54:56.400 | host2 | false | 18446744073709551615 |
++-------------------------+-------+-------+----------------------+"
+        );
+    }
+
     async fn test_put_influxdb_lines(instance: &Arc<Instance>) {
         let lines = r"
 monitor1,host=host1 cpu=66.6,memory=1024 1663840496100023100
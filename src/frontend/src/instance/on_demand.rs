@@ -0,0 +1,261 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::time::Duration;
+
+use catalog::notifier::{CatalogEvent, CatalogEventReceiver};
+use catalog::CatalogManagerRef;
+use common_catalog::format_full_table_name;
+use common_telemetry::warn;
+use moka::future::{Cache, CacheBuilder};
+use snafu::ResultExt;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::error::{CreateTableOnDemandSnafu, Result};
+
+/// Max number of tables kept in the caches.
+const CACHE_CAPACITY: u64 = 10_000;
+/// Time to live of the tables found missing in the catalog, in case they are created by others
+/// and the catalog event is missed.
+const MISSING_TABLE_TTL: Duration = Duration::from_secs(30);
+/// Time to live of the tables created on demand, in which the creation is not repeated.
+const CREATED_TABLE_TTL: Duration = Duration::from_secs(10);
+
+/// Caches to reduce the catalog lookups and table creations when inserting into missing
+/// tables, e.g. lots of protocol writers writing to new metrics at the same time.
+///
+/// The entries are invalidated by the changes of the catalog, so a table dropped (or renamed)
+/// right after it's created on demand is created again by the next insertion.
+#[derive(Clone)]
+pub(crate) struct OnDemandTables {
+    /// Tables found missing in the catalog, which needn't be looked up again until they are
+    /// created or expired.
+    missing_tables: Cache<String, ()>,
+    /// Tables created on demand recently. Concurrent creations of the same table are
+    /// coalesced into one.
+    created_tables: Cache<String, ()>,
+}
+
+impl Default for OnDemandTables {
+    fn default() -> Self {
+        Self {
+            missing_tables: CacheBuilder::new(CACHE_CAPACITY)
+                .time_to_live(MISSING_TABLE_TTL)
+                .build(),
+            created_tables: CacheBuilder::new(CACHE_CAPACITY)
+                .time_to_live(CREATED_TABLE_TTL)
+                .build(),
+        }
+    }
+}
+
+impl OnDemandTables {
+    /// Creates the caches, which are invalidated by the events of the `catalog_manager` if it's
+    /// able to notify its changes.
+    pub(crate) fn new(catalog_manager: &CatalogManagerRef) -> Self {
+        let tables = Self::default();
+        if let Some(receiver) = catalog_manager.subscribe() {
+            let _handle = common_runtime::spawn_bg(tables.clone().invalidate_on_events(receiver));
+        }
+        tables
+    }
+
+    /// Whether the table is known to be missing, then there is no need to look it up.
+    pub(crate) fn is_missing(&self, table_name: &str) -> bool {
+        self.missing_tables.contains_key(table_name)
+    }
+
+    /// Remembers the table is not found in the catalog.
+    pub(crate) async fn mark_missing(&self, table_name: &str) {
+        self.missing_tables.insert(table_name.to_string(), ()).await;
+    }
+
+    /// Creates the missing table by `create`, which is only called once among the concurrent
+    /// callers creating the same table.
+    pub(crate) async fn create<F>(&self, table_name: &str, create: F) -> Result<()>
+    where
+        F: Future<Output = Result<()>>,
+    {
+        let result = self
+            .created_tables
+            .try_get_with_by_ref(table_name, create)
+            .await;
+
+        // The table is not missing anymore if created, otherwise the next insertion tries to
+        // create it again without looking it up.
+        if result.is_ok() {
+            self.missing_tables.invalidate(table_name).await;
+        }
+        result.context(CreateTableOnDemandSnafu { table_name })
+    }
+
+    async fn handle_event(&self, event: CatalogEvent) {
+        match event {
+            CatalogEvent::TableRegistered {
+                catalog,
+                schema,
+                table_name,
+            } => {
+                let full_table_name = format_full_table_name(&catalog, &schema, &table_name);
+                self.missing_tables.invalidate(&full_table_name).await;
+            }
+            CatalogEvent::TableDeregistered {
+                catalog,
+                schema,
+                table_name,
+            } => {
+                let full_table_name = format_full_table_name(&catalog, &schema, &table_name);
+                self.created_tables.invalidate(&full_table_name).await;
+            }
+            CatalogEvent::TableRenamed {
+                catalog,
+                schema,
+                table_name,
+                new_table_name,
+            } => {
+                let old_name = format_full_table_name(&catalog, &schema, &table_name);
+                self.created_tables.invalidate(&old_name).await;
+                let new_name = format_full_table_name(&catalog, &schema, &new_table_name);
+                self.missing_tables.invalidate(&new_name).await;
+            }
+            CatalogEvent::SchemaRegistered { .. } => {}
+        }
+    }
+
+    async fn invalidate_on_events(self, mut receiver: CatalogEventReceiver) {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => self.handle_event(event).await,
+                Err(RecvError::Lagged(n)) => {
+                    warn!("Missed {n} catalog events, invalidate all on-demand tables");
+                    self.missing_tables.invalidate_all();
+                    self.created_tables.invalidate_all();
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use catalog::notifier::CatalogEventNotifier;
+
+    use super::*;
+    use crate::error::{Error, InvalidInsertRequestSnafu};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_missing_table_once() {
+        let tables = OnDemandTables::default();
+        let created = Arc::new(AtomicUsize::new(0));
+
+        let tasks = (0..10)
+            .map(|_| {
+                let tables = tables.clone();
+                let created = created.clone();
+                tokio::spawn(async move {
+                    tables
+                        .create("greptime.public.demo", async {
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                            let _ = created.fetch_add(1, Ordering::Relaxed);
+                            Ok(())
+                        })
+                        .await
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(1, created.load(Ordering::Relaxed));
+        assert!(!tables.is_missing("greptime.public.demo"));
+    }
+
+    #[tokio::test]
+    async fn test_create_missing_table_failed() {
+        let tables = OnDemandTables::default();
+        tables.mark_missing("greptime.public.demo").await;
+
+        let err = tables
+            .create("greptime.public.demo", async {
+                InvalidInsertRequestSnafu { reason: "test" }.fail()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::CreateTableOnDemand { .. }));
+        assert!(tables.is_missing("greptime.public.demo"));
+
+        // Failed creations are not cached.
+        let created = AtomicUsize::new(0);
+        tables
+            .create("greptime.public.demo", async {
+                let _ = created.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(1, created.load(Ordering::Relaxed));
+        assert!(!tables.is_missing("greptime.public.demo"));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_on_catalog_events() {
+        let tables = OnDemandTables::default();
+        let notifier = CatalogEventNotifier::default();
+        let invalidator = tokio::spawn(tables.clone().invalidate_on_events(notifier.subscribe()));
+
+        let created = AtomicUsize::new(0);
+        let create = || async {
+            let _ = created.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        };
+        tables
+            .create("greptime.public.demo", create())
+            .await
+            .unwrap();
+        tables
+            .create("greptime.public.demo", create())
+            .await
+            .unwrap();
+        assert_eq!(1, created.load(Ordering::Relaxed));
+
+        // The table dropped is created again by the next insertion.
+        notifier.notify(CatalogEvent::TableDeregistered {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table_name: "demo".to_string(),
+        });
+        // The table created by others is looked up again.
+        tables.mark_missing("greptime.public.foo").await;
+        notifier.notify(CatalogEvent::TableRegistered {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table_name: "foo".to_string(),
+        });
+        drop(notifier);
+        invalidator.await.unwrap();
+
+        tables
+            .create("greptime.public.demo", create())
+            .await
+            .unwrap();
+        assert_eq!(2, created.load(Ordering::Relaxed));
+        assert!(!tables.is_missing("greptime.public.foo"));
+    }
+}
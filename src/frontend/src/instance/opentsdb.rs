@@ -26,7 +26,7 @@ use crate::instance::Instance;
 impl OpentsdbProtocolHandler for Instance {
     async fn exec(&self, data_point: &DataPoint, ctx: QueryContextRef) -> server_error::Result<()> {
         let request = data_point.as_grpc_insert();
-        self.handle_insert(request, ctx)
+        self.handle_metric_inserts(vec![request], ctx)
             .await
             .map_err(BoxedError::new)
             .with_context(|_| server_error::ExecuteQuerySnafu {
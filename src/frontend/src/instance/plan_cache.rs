@@ -0,0 +1,285 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::CatalogManagerRef;
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use datafusion::datasource::source_as_provider;
+use datafusion_common::{DataFusionError, Result as DfResult};
+use datafusion_expr::{LogicalPlan as DfLogicalPlan, PlanVisitor};
+use moka::future::{Cache, CacheBuilder};
+use query::plan::LogicalPlan;
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use sql::statements::statement::Statement;
+use table::metadata::{TableId, TableVersion};
+use table::table::adapter::DfTableProviderAdapter;
+use table::TableRef;
+
+use crate::error::{CatalogSnafu, Result};
+
+/// Max number of plans kept in the cache.
+const CACHE_CAPACITY: u64 = 1024;
+/// Time to idle of the cached plans, so plans of the one-off queries are evicted soon.
+const CACHE_TTI: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PlanCacheKey {
+    catalog: String,
+    schema: String,
+    sql: String,
+}
+
+impl PlanCacheKey {
+    fn new(sql: &str, query_ctx: &QueryContextRef) -> Self {
+        Self {
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            sql: normalize_sql(sql),
+        }
+    }
+}
+
+struct CachedPlan {
+    stmt: Statement,
+    plan: LogicalPlan,
+    /// The tables scanned by the plan, whose schemas must not be changed since planning.
+    tables: Vec<CachedTable>,
+}
+
+struct CachedTable {
+    catalog: String,
+    schema: String,
+    table: String,
+    table_id: TableId,
+    version: TableVersion,
+}
+
+impl CachedTable {
+    fn new(table: &TableRef) -> Self {
+        let table_info = table.table_info();
+        Self {
+            catalog: table_info.catalog_name.clone(),
+            schema: table_info.schema_name.clone(),
+            table: table_info.name.clone(),
+            table_id: table_info.ident.table_id,
+            version: table_info.ident.version,
+        }
+    }
+}
+
+/// Caches the statements and logical plans of the repeated SQL queries (like those from the
+/// dashboards and alert rules), so they can skip parsing and planning.
+///
+/// A cached plan is only valid if the schemas of the tables it scans remain unchanged. All plans
+/// are invalidated on DDL executed by this frontend.
+#[derive(Clone)]
+pub(crate) struct PlanCache {
+    catalog_manager: CatalogManagerRef,
+    cache: Cache<PlanCacheKey, Arc<CachedPlan>>,
+}
+
+impl PlanCache {
+    pub(crate) fn new(catalog_manager: CatalogManagerRef) -> Self {
+        Self {
+            catalog_manager,
+            cache: CacheBuilder::new(CACHE_CAPACITY)
+                .time_to_idle(CACHE_TTI)
+                .build(),
+        }
+    }
+
+    /// Returns the statement parsed from the same `sql` before.
+    pub(crate) fn get_statement(
+        &self,
+        sql: &str,
+        query_ctx: &QueryContextRef,
+    ) -> Option<Statement> {
+        self.cache
+            .get(&PlanCacheKey::new(sql, query_ctx))
+            .map(|cached| cached.stmt.clone())
+    }
+
+    /// Returns the plan of `stmt` parsed from `sql`, if it's cached and still valid.
+    pub(crate) async fn get_plan(
+        &self,
+        sql: &str,
+        stmt: &Statement,
+        query_ctx: &QueryContextRef,
+    ) -> Result<Option<LogicalPlan>> {
+        let key = PlanCacheKey::new(sql, query_ctx);
+        let Some(cached) = self.cache.get(&key) else {
+            return Ok(None);
+        };
+        if cached.stmt != *stmt {
+            return Ok(None);
+        }
+
+        for cached_table in &cached.tables {
+            let table = self
+                .catalog_manager
+                .table(
+                    &cached_table.catalog,
+                    &cached_table.schema,
+                    &cached_table.table,
+                )
+                .await
+                .context(CatalogSnafu)?;
+            let unchanged = table.map_or(false, |table| {
+                let ident = &table.table_info().ident;
+                ident.table_id == cached_table.table_id && ident.version == cached_table.version
+            });
+            if !unchanged {
+                self.cache.invalidate(&key).await;
+                return Ok(None);
+            }
+        }
+        Ok(Some(cached.plan.clone()))
+    }
+
+    /// Caches the `plan` of `stmt` parsed from `sql`.
+    pub(crate) async fn insert(
+        &self,
+        sql: &str,
+        stmt: Statement,
+        plan: LogicalPlan,
+        query_ctx: &QueryContextRef,
+    ) {
+        let tables = scanned_tables(&plan)
+            .iter()
+            .map(CachedTable::new)
+            .filter(|table| table.schema != INFORMATION_SCHEMA_NAME)
+            .collect();
+        let cached = CachedPlan { stmt, plan, tables };
+        self.cache
+            .insert(PlanCacheKey::new(sql, query_ctx), Arc::new(cached))
+            .await;
+    }
+
+    pub(crate) fn invalidate_all(&self) {
+        self.cache.invalidate_all();
+    }
+}
+
+/// Normalizes the SQL text by trimming the spaces and the trailing semicolon, and collapsing
+/// consecutive whitespaces outside the quotes and comments into one space. A line comment keeps
+/// the line break ending it.
+fn normalize_sql(sql: &str) -> String {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    let mut normalized = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut last_is_space = false;
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            if !last_is_space {
+                normalized.push(' ');
+            }
+            last_is_space = true;
+            continue;
+        }
+        normalized.push(c);
+        last_is_space = false;
+        match c {
+            '\'' | '"' | '`' => {
+                for next in chars.by_ref() {
+                    normalized.push(next);
+                    if next == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    normalized.push(next);
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                // The line break separates the comment from the following text.
+                last_is_space = normalized.ends_with('\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                normalized.push(chars.next().unwrap());
+                while let Some(next) = chars.next() {
+                    normalized.push(next);
+                    if next == '*' && chars.peek() == Some(&'/') {
+                        normalized.push(chars.next().unwrap());
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    normalized
+}
+
+/// Collects the tables scanned by the `plan`.
+fn scanned_tables(plan: &LogicalPlan) -> Vec<TableRef> {
+    struct TableCollector(Vec<TableRef>);
+
+    impl PlanVisitor for TableCollector {
+        type Error = DataFusionError;
+
+        fn pre_visit(&mut self, plan: &DfLogicalPlan) -> DfResult<bool> {
+            if let DfLogicalPlan::TableScan(scan) = plan {
+                let provider = source_as_provider(&scan.source)?;
+                if let Some(adapter) = provider.as_any().downcast_ref::<DfTableProviderAdapter>() {
+                    self.0.push(adapter.table());
+                }
+            }
+            Ok(true)
+        }
+    }
+
+    let LogicalPlan::DfPlan(plan) = plan;
+    let mut collector = TableCollector(vec![]);
+    // The collector never fails on the tables scanned by our planner.
+    let _ = plan.accept(&mut collector);
+    collector.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_sql() {
+        assert_eq!(
+            "SELECT * FROM t",
+            normalize_sql("  SELECT *\n  FROM\tt ;  ")
+        );
+        assert_eq!(
+            "SELECT 'a  b', \"c\td\" FROM t WHERE x = 1",
+            normalize_sql("SELECT 'a  b',  \"c\td\" FROM t\n WHERE x = 1;")
+        );
+        assert_eq!("SELECT `a  b`", normalize_sql("SELECT   `a  b`"));
+
+        // The comments are kept as is, and the line comments end at the line breaks.
+        assert_eq!(
+            "SELECT 1 -- x\n, 2",
+            normalize_sql("SELECT 1   -- x\n  , 2")
+        );
+        assert_ne!(
+            normalize_sql("SELECT 1 -- x\n, 2"),
+            normalize_sql("SELECT 1 -- x , 2")
+        );
+        assert_eq!(
+            "SELECT /* a  'b */ 1 -- it's",
+            normalize_sql("SELECT  /* a  'b */\t1 -- it's")
+        );
+    }
+}
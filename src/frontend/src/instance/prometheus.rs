@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use api::prometheus::remote::label_matcher::Type as MatcherType;
 use api::prometheus::remote::read_request::ResponseType;
 use api::prometheus::remote::{Query, QueryResult, ReadRequest, ReadResponse, WriteRequest};
 use api::v1::greptime_request::Request;
@@ -23,7 +24,7 @@ use common_recordbatch::RecordBatches;
 use common_telemetry::logging;
 use prost::Message;
 use servers::error::{self, Result as ServerResult};
-use servers::prometheus::{self, Metrics};
+use servers::prometheus::{self, Metrics, METRIC_NAME_LABEL};
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::{PrometheusProtocolHandler, PrometheusResponse};
 use session::context::QueryContextRef;
@@ -81,7 +82,27 @@ impl Instance {
         let mut results = Vec::with_capacity(queries.len());
 
         for query in queries {
-            let (table_name, sql) = prometheus::query_to_sql(query)?;
+            // Queries the table of the metric, but responds with the metric name.
+            let mut query = query.clone();
+            let mut metric_name = None;
+            for matcher in &mut query.matchers {
+                if matcher.name == METRIC_NAME_LABEL && matcher.r#type == MatcherType::Eq as i32 {
+                    let table_name = self
+                        .table_name_normalization
+                        .normalize(&matcher.value)
+                        .into_owned();
+                    metric_name = Some(std::mem::replace(&mut matcher.value, table_name));
+                }
+            }
+
+            if let Some(metric_name) = &metric_name {
+                self.check_metric_table(metric_name, &ctx)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(error::ExecuteGrpcQuerySnafu)?;
+            }
+
+            let (table_name, sql) = prometheus::query_to_sql(&query)?;
             logging::debug!(
                 "prometheus remote read, table: {}, sql: {}",
                 table_name,
@@ -97,7 +118,7 @@ impl Instance {
                 .map_err(BoxedError::new)
                 .context(error::ExecuteGrpcQuerySnafu)?;
 
-            results.push((table_name, output));
+            results.push((metric_name.unwrap_or(table_name), output));
         }
         Ok(results)
     }
@@ -107,7 +128,7 @@ impl Instance {
 impl PrometheusProtocolHandler for Instance {
     async fn write(&self, request: WriteRequest, ctx: QueryContextRef) -> ServerResult<()> {
         let requests = prometheus::to_grpc_insert_requests(request.clone())?;
-        self.handle_inserts(requests, ctx)
+        self.handle_metric_inserts(requests, ctx)
             .await
             .map_err(BoxedError::new)
             .context(error::ExecuteGrpcQuerySnafu)?;
@@ -36,6 +36,18 @@ impl ScriptHandler for Instance {
             .await
     }
 
+    async fn insert_wasm_function(
+        &self,
+        schema: &str,
+        name: &str,
+        module: &[u8],
+    ) -> servers::error::Result<()> {
+        let _timer = timer!(metrics::METRIC_HANDLE_SCRIPTS_ELAPSED);
+        self.script_executor
+            .insert_wasm_function(schema, name, module)
+            .await
+    }
+
     async fn execute_script(
         &self,
         schema: &str,
@@ -0,0 +1,328 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consumes messages from Kafka topics and writes them through the insert path of the
+//! frontend. The offset to consume next is checkpointed in a table after the messages are
+//! written, so each message is written at least once.
+
+mod decode;
+mod offset;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_runtime::JoinHandle;
+use common_telemetry::{error, info, warn};
+use rskafka::client::partition::{OffsetAt, PartitionClient, UnknownTopicHandling};
+use rskafka::client::ClientBuilder;
+use serde::{Deserialize, Serialize};
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{KafkaSnafu, KafkaTopicNotFoundSnafu, Result};
+use crate::instance::Instance;
+use crate::kafka::decode::decode_message;
+use crate::kafka::offset::OffsetStore;
+
+/// Interval to retry after failing to consume a partition.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KafkaOptions {
+    /// Bootstrap brokers, e.g. "127.0.0.1:9092".
+    pub brokers: Vec<String>,
+    /// Max time to wait for new messages in a fetch.
+    #[serde(with = "humantime_serde")]
+    pub fetch_max_wait: Duration,
+    /// Max bytes of messages in a fetch.
+    pub fetch_max_bytes: i32,
+    /// Where to start consuming partitions without checkpointed offsets.
+    pub start_offset: StartOffset,
+    /// The schema to write messages and checkpoint offsets to.
+    pub schema: String,
+    pub topics: Vec<KafkaTopicOptions>,
+}
+
+impl Default for KafkaOptions {
+    fn default() -> Self {
+        Self {
+            brokers: vec![],
+            fetch_max_wait: Duration::from_millis(500),
+            fetch_max_bytes: 1024 * 1024,
+            start_offset: StartOffset::Earliest,
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            topics: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartOffset {
+    Earliest,
+    Latest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageFormat {
+    /// A JSON object or an array of JSON objects, each object is a row.
+    Json,
+    /// InfluxDB line protocol, with timestamps in nanoseconds.
+    InfluxLine,
+    /// A protobuf encoded gRPC `InsertRequest`.
+    Protobuf,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KafkaTopicOptions {
+    pub topic: String,
+    pub format: MessageFormat,
+    /// The table to write JSON messages, and protobuf messages without a table name, to.
+    /// Defaults to the topic name.
+    #[serde(default)]
+    pub table: Option<String>,
+    /// Column of the timestamps in milliseconds in JSON messages. Timestamps of the messages
+    /// are used if absent.
+    #[serde(default = "default_timestamp_column")]
+    pub timestamp_column: String,
+    /// Columns of JSON messages written as tags.
+    #[serde(default)]
+    pub tag_columns: Vec<String>,
+}
+
+fn default_timestamp_column() -> String {
+    "ts".to_string()
+}
+
+impl KafkaTopicOptions {
+    pub fn table_name(&self) -> &str {
+        self.table.as_deref().unwrap_or(&self.topic)
+    }
+}
+
+/// Runs a consumer for each partition of the configured topics.
+pub struct KafkaConsumer {
+    opts: KafkaOptions,
+    instance: Arc<Instance>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl KafkaConsumer {
+    pub fn new(opts: KafkaOptions, instance: Arc<Instance>) -> Self {
+        Self {
+            opts,
+            instance,
+            handles: Mutex::new(vec![]),
+        }
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        let query_ctx = Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, &self.opts.schema));
+        let offsets = Arc::new(OffsetStore::new(self.instance.clone(), query_ctx.clone()));
+        offsets.init().await?;
+
+        let client = ClientBuilder::new(self.opts.brokers.clone())
+            .build()
+            .await
+            .context(KafkaSnafu)?;
+        let topics = client.list_topics().await.context(KafkaSnafu)?;
+
+        let mut consumers = Vec::new();
+        for topic in &self.opts.topics {
+            let partitions = topics
+                .iter()
+                .find(|t| t.name == topic.topic)
+                .map(|t| t.partitions.clone())
+                .context(KafkaTopicNotFoundSnafu {
+                    topic: &topic.topic,
+                })?;
+            let topic = Arc::new(topic.clone());
+            for partition in partitions {
+                let client = client
+                    .partition_client(topic.topic.clone(), partition, UnknownTopicHandling::Retry)
+                    .await
+                    .context(KafkaSnafu)?;
+                consumers.push(PartitionConsumer {
+                    topic: topic.clone(),
+                    partition,
+                    client,
+                    instance: self.instance.clone(),
+                    offsets: offsets.clone(),
+                    query_ctx: query_ctx.clone(),
+                    fetch_max_wait_ms: self.opts.fetch_max_wait.as_millis() as i32,
+                    fetch_max_bytes: self.opts.fetch_max_bytes,
+                    start_offset: self.opts.start_offset,
+                });
+            }
+        }
+
+        info!("Starting {} Kafka partition consumers", consumers.len());
+        let mut handles = self.handles.lock().unwrap();
+        for consumer in consumers {
+            handles.push(common_runtime::spawn_bg(consumer.run()));
+        }
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        for handle in self.handles.lock().unwrap().drain(..) {
+            handle.abort();
+        }
+    }
+}
+
+struct PartitionConsumer {
+    topic: Arc<KafkaTopicOptions>,
+    partition: i32,
+    client: PartitionClient,
+    instance: Arc<Instance>,
+    offsets: Arc<OffsetStore>,
+    query_ctx: QueryContextRef,
+    fetch_max_wait_ms: i32,
+    fetch_max_bytes: i32,
+    start_offset: StartOffset,
+}
+
+impl PartitionConsumer {
+    async fn run(self) {
+        let mut offset = loop {
+            match self.initial_offset().await {
+                Ok(offset) => break offset,
+                Err(e) => {
+                    error!(
+                        e; "Failed to get initial offset of topic {} partition {}",
+                        self.topic.topic, self.partition
+                    );
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                }
+            }
+        };
+        info!(
+            "Start consuming topic {} partition {} from offset {}",
+            self.topic.topic, self.partition, offset
+        );
+
+        loop {
+            match self.consume(offset).await {
+                Ok(next) => offset = next,
+                Err(e) => {
+                    error!(
+                        e; "Failed to consume topic {} partition {} at offset {}",
+                        self.topic.topic, self.partition, offset
+                    );
+                    // The offset may have been deleted by the retention of the topic.
+                    if let Ok(earliest) = self.client.get_offset(OffsetAt::Earliest).await {
+                        if offset < earliest {
+                            warn!(
+                                "Offset {} of topic {} partition {} is out of range, skip to {}",
+                                offset, self.topic.topic, self.partition, earliest
+                            );
+                            offset = earliest;
+                        }
+                    }
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn initial_offset(&self) -> Result<i64> {
+        if let Some(offset) = self.offsets.load(&self.topic.topic, self.partition).await? {
+            return Ok(offset);
+        }
+        let at = match self.start_offset {
+            StartOffset::Earliest => OffsetAt::Earliest,
+            StartOffset::Latest => OffsetAt::Latest,
+        };
+        self.client.get_offset(at).await.context(KafkaSnafu)
+    }
+
+    /// Consumes messages from `offset` and returns the offset to consume next.
+    async fn consume(&self, offset: i64) -> Result<i64> {
+        let (records, _) = self
+            .client
+            .fetch_records(offset, 1..self.fetch_max_bytes, self.fetch_max_wait_ms)
+            .await
+            .context(KafkaSnafu)?;
+        let Some(last) = records.last() else {
+            return Ok(offset);
+        };
+        let next = last.offset + 1;
+
+        let mut requests = Vec::new();
+        for record in records {
+            let Some(message) = record.record.value else {
+                continue;
+            };
+            let timestamp = record.record.timestamp.timestamp_millis();
+            match decode_message(&self.topic, &message, timestamp) {
+                Ok(decoded) => requests.extend(decoded),
+                // Skips malformed messages, otherwise they block the partition forever.
+                Err(e) => warn!(
+                    "Skip malformed message at offset {} of topic {} partition {}, error: {}",
+                    record.offset, self.topic.topic, self.partition, e
+                ),
+            }
+        }
+        if !requests.is_empty() {
+            let _ = self
+                .instance
+                .handle_metric_inserts(requests, self.query_ctx.clone())
+                .await?;
+        }
+
+        self.offsets
+            .save(&self.topic.topic, self.partition, next)
+            .await?;
+        Ok(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kafka_options() {
+        let opts: KafkaOptions = toml::from_str(
+            r#"
+brokers = ["127.0.0.1:9092"]
+start_offset = "latest"
+
+[[topics]]
+topic = "metrics"
+format = "json"
+tag_columns = ["host"]
+
+[[topics]]
+topic = "influx"
+format = "influx_line"
+table = "ignored"
+"#,
+        )
+        .unwrap();
+        assert_eq!(vec!["127.0.0.1:9092"], opts.brokers);
+        assert_eq!(StartOffset::Latest, opts.start_offset);
+        assert_eq!(Duration::from_millis(500), opts.fetch_max_wait);
+        assert_eq!(2, opts.topics.len());
+        assert_eq!(MessageFormat::Json, opts.topics[0].format);
+        assert_eq!("metrics", opts.topics[0].table_name());
+        assert_eq!("ts", opts.topics[0].timestamp_column);
+        assert_eq!(MessageFormat::InfluxLine, opts.topics[1].format);
+        assert_eq!("ignored", opts.topics[1].table_name());
+    }
+}
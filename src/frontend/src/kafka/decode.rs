@@ -0,0 +1,229 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::InsertRequest;
+use common_grpc::writer::{LinesWriter, Precision};
+use prost::Message;
+use servers::influxdb::InfluxdbRequest;
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{
+    DecodeInfluxLineMessageSnafu, DecodeJsonMessageSnafu, DecodeProtobufMessageSnafu,
+    InvalidKafkaMessageSnafu, Result, WriteLinesSnafu,
+};
+use crate::kafka::{KafkaTopicOptions, MessageFormat};
+
+/// Decodes a message of the topic to insert requests. `timestamp` is the timestamp of the
+/// message in milliseconds, used if a JSON row doesn't have one.
+pub(crate) fn decode_message(
+    topic: &KafkaTopicOptions,
+    message: &[u8],
+    timestamp: i64,
+) -> Result<Vec<InsertRequest>> {
+    match topic.format {
+        MessageFormat::Json => decode_json(topic, message, timestamp),
+        MessageFormat::InfluxLine => {
+            let request = InfluxdbRequest {
+                precision: None,
+                lines: String::from_utf8_lossy(message).to_string(),
+            };
+            (&request).try_into().context(DecodeInfluxLineMessageSnafu)
+        }
+        MessageFormat::Protobuf => {
+            let mut request = InsertRequest::decode(message).context(DecodeProtobufMessageSnafu)?;
+            if request.table_name.is_empty() {
+                request.table_name = topic.table_name().to_string();
+            }
+            Ok(vec![request])
+        }
+    }
+}
+
+/// Decodes a JSON object, or an array of JSON objects, to rows of the table of the topic.
+///
+/// Strings in the tag columns of the topic are written as tags, numbers are written as
+/// float fields, and nested objects or arrays are written as JSON strings.
+fn decode_json(
+    topic: &KafkaTopicOptions,
+    message: &[u8],
+    timestamp: i64,
+) -> Result<Vec<InsertRequest>> {
+    let json: serde_json::Value =
+        serde_json::from_slice(message).context(DecodeJsonMessageSnafu)?;
+    let rows = match json {
+        serde_json::Value::Array(rows) => rows,
+        row => vec![row],
+    };
+
+    let mut writer = LinesWriter::with_lines(rows.len());
+    for row in rows {
+        let serde_json::Value::Object(row) = row else {
+            return InvalidKafkaMessageSnafu {
+                reason: "expect JSON objects",
+            }
+            .fail();
+        };
+
+        let mut has_timestamp = false;
+        for (key, value) in row {
+            if key == topic.timestamp_column {
+                let ts = value.as_i64().with_context(|| InvalidKafkaMessageSnafu {
+                    reason: format!("expect milliseconds as timestamp {key}, found {value}"),
+                })?;
+                writer
+                    .write_ts(&key, (ts, Precision::Millisecond))
+                    .context(WriteLinesSnafu)?;
+                has_timestamp = true;
+                continue;
+            }
+
+            match value {
+                serde_json::Value::Null => Ok(()),
+                serde_json::Value::Bool(v) => writer.write_bool(&key, v),
+                serde_json::Value::Number(v) => {
+                    writer.write_f64(&key, v.as_f64().unwrap_or(f64::NAN))
+                }
+                serde_json::Value::String(v) if topic.tag_columns.contains(&key) => {
+                    writer.write_tag(&key, &v)
+                }
+                serde_json::Value::String(v) => writer.write_string(&key, &v),
+                v => writer.write_string(&key, &v.to_string()),
+            }
+            .context(WriteLinesSnafu)?;
+        }
+        if !has_timestamp {
+            writer
+                .write_ts(&topic.timestamp_column, (timestamp, Precision::Millisecond))
+                .context(WriteLinesSnafu)?;
+        }
+        writer.commit();
+    }
+
+    let (columns, row_count) = writer.finish();
+    Ok(vec![InsertRequest {
+        table_name: topic.table_name().to_string(),
+        region_number: 0,
+        columns,
+        row_count,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::column::{SemanticType, Values};
+    use api::v1::{Column, ColumnDataType};
+
+    use super::*;
+
+    fn topic(format: MessageFormat) -> KafkaTopicOptions {
+        KafkaTopicOptions {
+            topic: "metrics".to_string(),
+            format,
+            table: None,
+            timestamp_column: "ts".to_string(),
+            tag_columns: vec!["host".to_string()],
+        }
+    }
+
+    fn column<'a>(request: &'a InsertRequest, name: &str) -> &'a Column {
+        request
+            .columns
+            .iter()
+            .find(|c| c.column_name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_decode_json() {
+        let topic = topic(MessageFormat::Json);
+        let message = br#"[
+            {"host": "a", "cpu": 0.5, "ts": 1000, "msg": "ok"},
+            {"host": "b", "cpu": 1, "labels": {"k": "v"}}
+        ]"#;
+        let requests = decode_message(&topic, message, 2000).unwrap();
+        assert_eq!(1, requests.len());
+        let request = &requests[0];
+        assert_eq!("metrics", request.table_name);
+        assert_eq!(2, request.row_count);
+
+        let host = column(request, "host");
+        assert_eq!(SemanticType::Tag as i32, host.semantic_type);
+        assert_eq!(
+            vec!["a".to_string(), "b".to_string()],
+            host.values.as_ref().unwrap().string_values
+        );
+        let cpu = column(request, "cpu");
+        assert_eq!(ColumnDataType::Float64 as i32, cpu.datatype);
+        assert_eq!(vec![0.5, 1.0], cpu.values.as_ref().unwrap().f64_values);
+        let ts = column(request, "ts");
+        assert_eq!(SemanticType::Timestamp as i32, ts.semantic_type);
+        assert_eq!(
+            vec![1000, 2000],
+            ts.values.as_ref().unwrap().ts_millisecond_values
+        );
+        let msg = column(request, "msg");
+        assert_eq!(SemanticType::Field as i32, msg.semantic_type);
+        let labels = column(request, "labels");
+        assert_eq!(
+            vec![r#"{"k":"v"}"#.to_string()],
+            labels.values.as_ref().unwrap().string_values
+        );
+
+        assert!(decode_message(&topic, b"not json", 0).is_err());
+        assert!(decode_message(&topic, b"[1, 2]", 0).is_err());
+        assert!(decode_message(&topic, br#"{"ts": "now"}"#, 0).is_err());
+    }
+
+    #[test]
+    fn test_decode_influx_line() {
+        let topic = topic(MessageFormat::InfluxLine);
+        let message = b"monitor,host=a cpu=0.5 1663840496100023100\nmonitor,host=b cpu=1";
+        let requests = decode_message(&topic, message, 0).unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!("monitor", requests[0].table_name);
+        assert_eq!(2, requests[0].row_count);
+
+        assert!(decode_message(&topic, b"monitor,host=a", 0).is_err());
+    }
+
+    #[test]
+    fn test_decode_protobuf() {
+        let topic = topic(MessageFormat::Protobuf);
+        let request = InsertRequest {
+            table_name: String::new(),
+            columns: vec![Column {
+                column_name: "cpu".to_string(),
+                semantic_type: SemanticType::Field as i32,
+                values: Some(Values {
+                    f64_values: vec![0.5],
+                    ..Default::default()
+                }),
+                datatype: ColumnDataType::Float64 as i32,
+                ..Default::default()
+            }],
+            row_count: 1,
+            region_number: 0,
+        };
+        let requests = decode_message(&topic, &request.encode_to_vec(), 0).unwrap();
+        assert_eq!(
+            vec![InsertRequest {
+                table_name: "metrics".to_string(),
+                ..request
+            }],
+            requests
+        );
+
+        assert!(decode_message(&topic, b"\xff\xff", 0).is_err());
+    }
+}
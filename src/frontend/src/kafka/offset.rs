@@ -0,0 +1,127 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use datatypes::prelude::*;
+use servers::query_handler::sql::SqlQueryHandler;
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+
+use crate::error::{CollectRecordbatchSnafu, Result};
+use crate::instance::Instance;
+
+/// Table checkpointing the offsets to consume next.
+pub(crate) const OFFSET_TABLE: &str = "kafka_offsets";
+
+/// Stores the offsets of the partitions in [OFFSET_TABLE]. Each partition has only one row
+/// at timestamp 0, which is overwritten on saving.
+pub(crate) struct OffsetStore {
+    instance: Arc<Instance>,
+    query_ctx: QueryContextRef,
+}
+
+impl OffsetStore {
+    pub(crate) fn new(instance: Arc<Instance>, query_ctx: QueryContextRef) -> Self {
+        Self {
+            instance,
+            query_ctx,
+        }
+    }
+
+    /// Creates the offset table if not exists.
+    pub(crate) async fn init(&self) -> Result<()> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {OFFSET_TABLE} (\
+                topic STRING, \
+                partition_id INT, \
+                next_offset BIGINT, \
+                ts TIMESTAMP TIME INDEX, \
+                PRIMARY KEY (topic, partition_id))"
+        );
+        let _ = self.execute(&sql).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn load(&self, topic: &str, partition: i32) -> Result<Option<i64>> {
+        let sql = format!(
+            "SELECT next_offset FROM {OFFSET_TABLE} WHERE topic = '{}' AND partition_id = {partition}",
+            escape(topic)
+        );
+        let batches = match self.execute(&sql).await? {
+            Output::Stream(stream) => RecordBatches::try_collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            Output::RecordBatches(batches) => batches,
+            Output::AffectedRows(_) => return Ok(None),
+        };
+        Ok(batches
+            .iter()
+            .filter(|batch| batch.num_rows() > 0)
+            .find_map(|batch| match batch.column(0).get(0) {
+                Value::Int64(offset) => Some(offset),
+                _ => None,
+            }))
+    }
+
+    pub(crate) async fn save(&self, topic: &str, partition: i32, offset: i64) -> Result<()> {
+        let sql = format!(
+            "INSERT INTO {OFFSET_TABLE} (topic, partition_id, next_offset, ts) VALUES ('{}', {partition}, {offset}, 0)",
+            escape(topic)
+        );
+        let _ = self.execute(&sql).await?;
+        Ok(())
+    }
+
+    async fn execute(&self, sql: &str) -> Result<Output> {
+        // A single statement always has a single output.
+        self.instance
+            .do_query(sql, self.query_ctx.clone())
+            .await
+            .remove(0)
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::tests;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_offset_store() {
+        let standalone = tests::create_standalone_instance("test_kafka_offset_store").await;
+        let store = OffsetStore::new(standalone.instance.clone(), QueryContext::arc());
+        store.init().await.unwrap();
+        // Initializing again is fine.
+        store.init().await.unwrap();
+
+        assert_eq!(None, store.load("metrics", 0).await.unwrap());
+        store.save("metrics", 0, 42).await.unwrap();
+        store.save("metrics", 1, 7).await.unwrap();
+        assert_eq!(Some(42), store.load("metrics", 0).await.unwrap());
+        assert_eq!(Some(7), store.load("metrics", 1).await.unwrap());
+
+        store.save("metrics", 0, 100).await.unwrap();
+        assert_eq!(Some(100), store.load("metrics", 0).await.unwrap());
+        assert_eq!(None, store.load("logs", 0).await.unwrap());
+    }
+}
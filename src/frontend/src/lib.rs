@@ -16,23 +16,31 @@
 #![feature(trait_upcasting)]
 
 pub mod catalog;
+pub mod database_alias;
 pub mod datanode;
+pub mod dead_letter;
 pub mod error;
-mod expr_factory;
+pub mod expr_factory;
 pub mod frontend;
 pub mod grpc;
 pub mod influxdb;
+mod ingest_rule;
+pub mod insert_batch;
 pub mod instance;
+pub mod kafka;
 pub(crate) mod metrics;
 pub mod mysql;
 pub mod opentsdb;
 pub mod postgres;
 pub mod prom;
 pub mod prometheus;
+pub mod rule;
+pub mod scrape;
 mod script;
 mod server;
 pub(crate) mod statement;
 mod table;
+pub mod table_name;
 #[cfg(test)]
 mod tests;
 
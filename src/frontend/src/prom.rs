@@ -15,14 +15,20 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PromOptions {
     pub addr: String,
+    /// Sorts the series of query results by their label sets, so the responses are stable
+    /// for tools diffing or alerting on them. Disable it to save the sorting of large
+    /// results.
+    pub sort_series: bool,
 }
 
 impl Default for PromOptions {
     fn default() -> Self {
         Self {
             addr: "127.0.0.1:4004".to_string(),
+            sort_series: true,
         }
     }
 }
@@ -35,5 +41,6 @@ mod tests {
     fn test_prometheus_options() {
         let default = PromOptions::default();
         assert_eq!(default.addr, "127.0.0.1:4004".to_string());
+        assert!(default.sort_series);
     }
 }
@@ -0,0 +1,82 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Evaluates Prometheus-style recording and alerting rules periodically. Results of recording
+//! rules are written back as metrics, and alerts are sent to an Alertmanager-compatible
+//! webhook.
+
+mod alert;
+mod file;
+mod manager;
+mod notifier;
+
+use std::time::Duration;
+
+pub use alert::{Alert, AlertState, AlertingRule, Labels};
+pub use file::{load_rule_files, RecordingRule, Rule, RuleGroup};
+pub use manager::RuleManager;
+pub use notifier::Notifier;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleOptions {
+    /// Paths of the rule files, the only source of the rules.
+    pub rule_files: Vec<String>,
+    /// Evaluation interval of the rule groups without one.
+    #[serde(with = "humantime_serde")]
+    pub evaluation_interval: Duration,
+    /// Address of the Alertmanager, alerts are not sent anywhere if not set.
+    pub alertmanager_url: Option<String>,
+    /// The schema to evaluate rules in and to write results of recording rules to.
+    pub schema: String,
+}
+
+impl Default for RuleOptions {
+    fn default() -> Self {
+        Self {
+            rule_files: vec![],
+            evaluation_interval: Duration::from_secs(60),
+            alertmanager_url: None,
+            schema: common_catalog::consts::DEFAULT_SCHEMA_NAME.to_string(),
+        }
+    }
+}
+
+/// A series in the result of an instant query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub labels: Labels,
+    pub value: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_options() {
+        let opts: RuleOptions = toml::from_str(
+            r#"
+rule_files = ["/etc/greptimedb/rules.yml"]
+evaluation_interval = "30s"
+"#,
+        )
+        .unwrap();
+        assert_eq!(vec!["/etc/greptimedb/rules.yml"], opts.rule_files);
+        assert_eq!(Duration::from_secs(30), opts.evaluation_interval);
+        assert_eq!(None, opts.alertmanager_url);
+        assert_eq!("public", opts.schema);
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::rule::Sample;
+
+pub type Labels = BTreeMap<String, String>;
+
+const ALERT_NAME_LABEL: &str = "alertname";
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// The state of an alert, an alert is pending until its condition holds for the `for`
+/// duration of the rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertState {
+    Pending,
+    Firing,
+}
+
+#[derive(Debug)]
+struct ActiveAlert {
+    annotations: Labels,
+    value: f64,
+    state: AlertState,
+    /// Milliseconds since the epoch when the condition starts to hold.
+    active_at: i64,
+}
+
+/// An alert in the payload of Alertmanager's `POST /api/v2/alerts`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub labels: Labels,
+    pub annotations: Labels,
+    pub starts_at: String,
+    pub ends_at: String,
+}
+
+/// Fires an alert for each series returned by `expr`.
+#[derive(Debug)]
+pub struct AlertingRule {
+    name: String,
+    expr: String,
+    for_duration: Duration,
+    labels: Labels,
+    annotations: Labels,
+    active: HashMap<Labels, ActiveAlert>,
+}
+
+impl AlertingRule {
+    pub fn new(
+        name: String,
+        expr: String,
+        for_duration: Duration,
+        labels: Labels,
+        annotations: Labels,
+    ) -> Self {
+        Self {
+            name,
+            expr,
+            for_duration,
+            labels,
+            annotations,
+            active: HashMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+
+    pub fn for_duration(&self) -> Duration {
+        self.for_duration
+    }
+
+    /// Returns the state of the alert with `labels`, `None` if it's inactive.
+    pub fn state(&self, labels: &Labels) -> Option<AlertState> {
+        self.active.get(labels).map(|alert| alert.state)
+    }
+
+    /// Updates the active alerts with the `samples` evaluated at `now` (in milliseconds),
+    /// and returns the alerts to send: the firing ones, which are valid until `valid_for`
+    /// later unless being sent again, and the resolved ones.
+    pub fn eval(&mut self, samples: Vec<Sample>, now: i64, valid_for: Duration) -> Vec<Alert> {
+        let mut seen = HashMap::with_capacity(samples.len());
+        for sample in samples {
+            let mut labels = sample.labels;
+            labels.remove(METRIC_NAME_LABEL);
+            let annotations = self
+                .annotations
+                .iter()
+                .map(|(k, v)| (k.clone(), expand_template(v, &labels, sample.value)))
+                .collect::<Labels>();
+            labels.extend(self.labels.clone());
+            labels.insert(ALERT_NAME_LABEL.to_string(), self.name.clone());
+            let _ = seen.insert(labels, (annotations, sample.value));
+        }
+
+        let mut alerts = Vec::new();
+        // Resolves the alerts whose conditions don't hold anymore.
+        self.active.retain(|labels, alert| {
+            if seen.contains_key(labels) {
+                return true;
+            }
+            if alert.state == AlertState::Firing {
+                alerts.push(Alert {
+                    labels: labels.clone(),
+                    annotations: alert.annotations.clone(),
+                    starts_at: to_rfc3339(alert.active_at),
+                    ends_at: to_rfc3339(now),
+                });
+            }
+            false
+        });
+
+        let for_millis = self.for_duration.as_millis() as i64;
+        let ends_at = to_rfc3339(now + valid_for.as_millis() as i64);
+        for (labels, (annotations, value)) in seen {
+            let alert = self.active.entry(labels.clone()).or_insert(ActiveAlert {
+                annotations: Labels::new(),
+                value,
+                state: AlertState::Pending,
+                active_at: now,
+            });
+            alert.annotations = annotations;
+            alert.value = value;
+            if alert.state == AlertState::Pending && now - alert.active_at >= for_millis {
+                alert.state = AlertState::Firing;
+            }
+            if alert.state == AlertState::Firing {
+                alerts.push(Alert {
+                    labels,
+                    annotations: alert.annotations.clone(),
+                    starts_at: to_rfc3339(alert.active_at),
+                    ends_at: ends_at.clone(),
+                });
+            }
+        }
+        alerts
+    }
+}
+
+fn to_rfc3339(millis: i64) -> String {
+    chrono::NaiveDateTime::from_timestamp_millis(millis)
+        .map(|t| {
+            chrono::DateTime::<chrono::Utc>::from_utc(t, chrono::Utc)
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        })
+        .unwrap_or_default()
+}
+
+/// Expands `{{ $value }}` and `{{ $labels.<name> }}` in annotations, other text is kept
+/// verbatim.
+fn expand_template(template: &str, labels: &Labels, value: f64) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let placeholder = &rest[start..start + len + 2];
+        let var = placeholder[2..placeholder.len() - 2].trim();
+        if var == "$value" {
+            result.push_str(&value.to_string());
+        } else if let Some(name) = var.strip_prefix("$labels.") {
+            result.push_str(labels.get(name).map(String::as_str).unwrap_or_default());
+        } else {
+            result.push_str(placeholder);
+        }
+        rest = &rest[start + len + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(instance: &str, value: f64) -> Sample {
+        Sample {
+            labels: Labels::from([
+                (METRIC_NAME_LABEL.to_string(), "up".to_string()),
+                ("instance".to_string(), instance.to_string()),
+            ]),
+            value,
+        }
+    }
+
+    fn alert_labels(instance: &str) -> Labels {
+        Labels::from([
+            (ALERT_NAME_LABEL.to_string(), "InstanceDown".to_string()),
+            ("instance".to_string(), instance.to_string()),
+            ("severity".to_string(), "page".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_alert_lifecycle() {
+        let mut rule = AlertingRule::new(
+            "InstanceDown".to_string(),
+            "up == 0".to_string(),
+            Duration::from_secs(60),
+            Labels::from([("severity".to_string(), "page".to_string())]),
+            Labels::from([(
+                "summary".to_string(),
+                "{{ $labels.instance }} is down, up = {{$value}}".to_string(),
+            )]),
+        );
+        let valid_for = Duration::from_secs(120);
+
+        // Pending until the condition holds for 1 minute.
+        assert!(rule.eval(vec![sample("a", 0.0)], 0, valid_for).is_empty());
+        assert_eq!(Some(AlertState::Pending), rule.state(&alert_labels("a")));
+        assert!(rule
+            .eval(vec![sample("a", 0.0)], 30_000, valid_for)
+            .is_empty());
+
+        let alerts = rule.eval(vec![sample("a", 0.0), sample("b", 0.0)], 60_000, valid_for);
+        assert_eq!(
+            vec![Alert {
+                labels: alert_labels("a"),
+                annotations: Labels::from([(
+                    "summary".to_string(),
+                    "a is down, up = 0".to_string()
+                )]),
+                starts_at: "1970-01-01T00:00:00.000Z".to_string(),
+                ends_at: "1970-01-01T00:03:00.000Z".to_string(),
+            }],
+            alerts
+        );
+        assert_eq!(Some(AlertState::Firing), rule.state(&alert_labels("a")));
+        assert_eq!(Some(AlertState::Pending), rule.state(&alert_labels("b")));
+
+        // "a" is resolved, and pending "b" is dropped.
+        let alerts = rule.eval(vec![], 90_000, valid_for);
+        assert_eq!(1, alerts.len());
+        assert_eq!(alert_labels("a"), alerts[0].labels);
+        assert_eq!("1970-01-01T00:01:30.000Z", alerts[0].ends_at);
+        assert_eq!(None, rule.state(&alert_labels("a")));
+        assert_eq!(None, rule.state(&alert_labels("b")));
+    }
+
+    #[test]
+    fn test_fire_immediately() {
+        let mut rule = AlertingRule::new(
+            "InstanceDown".to_string(),
+            "up == 0".to_string(),
+            Duration::ZERO,
+            Labels::from([("severity".to_string(), "page".to_string())]),
+            Labels::new(),
+        );
+        let alerts = rule.eval(vec![sample("a", 0.0)], 0, Duration::from_secs(60));
+        assert_eq!(1, alerts.len());
+        assert_eq!(Some(AlertState::Firing), rule.state(&alert_labels("a")));
+    }
+
+    #[test]
+    fn test_expand_template() {
+        let labels = Labels::from([("job".to_string(), "api".to_string())]);
+        assert_eq!(
+            "api: 1.5 {{ .Unknown }} {{",
+            expand_template(
+                "{{ $labels.job }}: {{ $value }} {{ .Unknown }} {{",
+                &labels,
+                1.5
+            )
+        );
+        assert_eq!(": ", expand_template("{{$labels.missing}}: ", &labels, 0.0));
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prometheus-compatible rule files, see
+//! <https://prometheus.io/docs/prometheus/latest/configuration/recording_rules/>.
+
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+use snafu::{ensure, ResultExt};
+
+use crate::error::{InvalidRuleSnafu, ParseRuleFileSnafu, ReadRuleFileSnafu, Result};
+use crate::rule::alert::AlertingRule;
+
+#[derive(Debug, Deserialize)]
+struct RuleFileConfig {
+    groups: Vec<RuleGroupConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleGroupConfig {
+    name: String,
+    #[serde(default, deserialize_with = "deserialize_optional_duration")]
+    interval: Option<Duration>,
+    rules: Vec<RuleConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleConfig {
+    record: Option<String>,
+    alert: Option<String>,
+    expr: String,
+    #[serde(
+        default,
+        rename = "for",
+        deserialize_with = "deserialize_optional_duration"
+    )]
+    for_duration: Option<Duration>,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    #[serde(default)]
+    annotations: BTreeMap<String, String>,
+}
+
+fn deserialize_optional_duration<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| promql_parser::util::parse_duration(&s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// A group of rules evaluated sequentially at the same interval.
+#[derive(Debug)]
+pub struct RuleGroup {
+    pub name: String,
+    pub interval: Duration,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+pub enum Rule {
+    Recording(RecordingRule),
+    Alerting(AlertingRule),
+}
+
+impl Rule {
+    pub fn name(&self) -> &str {
+        match self {
+            Rule::Recording(rule) => &rule.name,
+            Rule::Alerting(rule) => rule.name(),
+        }
+    }
+}
+
+/// Evaluates `expr` and writes the result as the metric `name`.
+#[derive(Debug)]
+pub struct RecordingRule {
+    pub name: String,
+    pub expr: String,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Loads rule groups from the rule files, groups without an interval are evaluated at
+/// `default_interval`.
+pub fn load_rule_files(paths: &[String], default_interval: Duration) -> Result<Vec<RuleGroup>> {
+    let mut groups = Vec::new();
+    for path in paths {
+        let content = std::fs::read_to_string(path).context(ReadRuleFileSnafu { path })?;
+        groups.extend(parse_rule_file(path, &content, default_interval)?);
+    }
+    Ok(groups)
+}
+
+fn parse_rule_file(
+    path: &str,
+    content: &str,
+    default_interval: Duration,
+) -> Result<Vec<RuleGroup>> {
+    let config: RuleFileConfig =
+        serde_yaml::from_str(content).context(ParseRuleFileSnafu { path })?;
+
+    let mut names = HashSet::with_capacity(config.groups.len());
+    let mut groups = Vec::with_capacity(config.groups.len());
+    for group in config.groups {
+        ensure!(
+            names.insert(group.name.clone()),
+            InvalidRuleSnafu {
+                group: &group.name,
+                reason: "duplicated group name",
+            }
+        );
+        groups.push(RuleGroup::try_new(group, default_interval)?);
+    }
+    Ok(groups)
+}
+
+impl RuleGroup {
+    fn try_new(config: RuleGroupConfig, default_interval: Duration) -> Result<Self> {
+        let interval = config.interval.unwrap_or(default_interval);
+        ensure!(
+            !interval.is_zero(),
+            InvalidRuleSnafu {
+                group: &config.name,
+                reason: "interval must be positive",
+            }
+        );
+
+        let rules = config
+            .rules
+            .into_iter()
+            .map(|rule| to_rule(&config.name, rule))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            name: config.name,
+            interval,
+            rules,
+        })
+    }
+}
+
+fn to_rule(group: &str, config: RuleConfig) -> Result<Rule> {
+    if let Err(e) = promql::parser::parse(&config.expr) {
+        return InvalidRuleSnafu {
+            group,
+            reason: format!("invalid expr {:?}: {e}", config.expr),
+        }
+        .fail();
+    }
+
+    match (config.record, config.alert) {
+        (Some(record), None) => {
+            ensure!(
+                !record.is_empty(),
+                InvalidRuleSnafu {
+                    group,
+                    reason: "empty record name",
+                }
+            );
+            ensure!(
+                config.for_duration.is_none() && config.annotations.is_empty(),
+                InvalidRuleSnafu {
+                    group,
+                    reason: format!("recording rule {record} can't have `for` or `annotations`"),
+                }
+            );
+            Ok(Rule::Recording(RecordingRule {
+                name: record,
+                expr: config.expr,
+                labels: config.labels,
+            }))
+        }
+        (None, Some(alert)) => {
+            ensure!(
+                !alert.is_empty(),
+                InvalidRuleSnafu {
+                    group,
+                    reason: "empty alert name",
+                }
+            );
+            Ok(Rule::Alerting(AlertingRule::new(
+                alert,
+                config.expr,
+                config.for_duration.unwrap_or_default(),
+                config.labels,
+                config.annotations,
+            )))
+        }
+        _ => InvalidRuleSnafu {
+            group,
+            reason: "exactly one of `record` and `alert` must be set",
+        }
+        .fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule_file() {
+        let content = r#"
+groups:
+  - name: example
+    interval: 30s
+    rules:
+      - record: job:http_requests:rate5m
+        expr: sum by (job) (rate(http_requests[5m]))
+        labels:
+          source: recording
+      - alert: HighRequestLatency
+        expr: job:request_latency_seconds:mean5m > 0.5
+        for: 10m
+        labels:
+          severity: page
+        annotations:
+          summary: High request latency of {{ $labels.job }}
+  - name: default_interval
+    rules:
+      - alert: InstanceDown
+        expr: up == 0
+"#;
+        let groups = parse_rule_file("rules.yml", content, Duration::from_secs(60)).unwrap();
+        assert_eq!(2, groups.len());
+
+        let group = &groups[0];
+        assert_eq!("example", group.name);
+        assert_eq!(Duration::from_secs(30), group.interval);
+        assert_eq!(2, group.rules.len());
+        let Rule::Recording(rule) = &group.rules[0] else {
+            unreachable!()
+        };
+        assert_eq!("job:http_requests:rate5m", rule.name);
+        assert_eq!("sum by (job) (rate(http_requests[5m]))", rule.expr);
+        assert_eq!("recording", rule.labels["source"]);
+        let Rule::Alerting(rule) = &group.rules[1] else {
+            unreachable!()
+        };
+        assert_eq!("HighRequestLatency", rule.name());
+        assert_eq!(Duration::from_secs(600), rule.for_duration());
+
+        let group = &groups[1];
+        assert_eq!(Duration::from_secs(60), group.interval);
+        assert_eq!("InstanceDown", group.rules[0].name());
+    }
+
+    #[test]
+    fn test_parse_invalid_rule_file() {
+        let interval = Duration::from_secs(60);
+        assert!(parse_rule_file("rules.yml", "groups: [", interval).is_err());
+
+        let both = r#"
+groups:
+  - name: g
+    rules:
+      - record: r
+        alert: a
+        expr: up
+"#;
+        let err = parse_rule_file("rules.yml", both, interval).unwrap_err();
+        assert!(err.to_string().contains("exactly one of"), "{err}");
+
+        let invalid_expr = r#"
+groups:
+  - name: g
+    rules:
+      - record: r
+        expr: sum(
+"#;
+        let err = parse_rule_file("rules.yml", invalid_expr, interval).unwrap_err();
+        assert!(err.to_string().contains("invalid expr"), "{err}");
+
+        let recording_with_for = r#"
+groups:
+  - name: g
+    rules:
+      - record: r
+        expr: up
+        for: 1m
+"#;
+        assert!(parse_rule_file("rules.yml", recording_with_for, interval).is_err());
+
+        let duplicated = r#"
+groups:
+  - name: g
+    rules: []
+  - name: g
+    rules: []
+"#;
+        let err = parse_rule_file("rules.yml", duplicated, interval).unwrap_err();
+        assert!(err.to_string().contains("duplicated group name"), "{err}");
+    }
+}
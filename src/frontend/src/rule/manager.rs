@@ -0,0 +1,351 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use api::prometheus::remote::{Label, Sample as PromSample, TimeSeries, WriteRequest};
+use async_trait::async_trait;
+use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use common_error::prelude::*;
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_runtime::{RepeatedTask, TaskFunction};
+use common_telemetry::{error, info};
+use datatypes::prelude::*;
+use datatypes::vectors::{Float64Vector, StringVector};
+use query::parser::PromQuery;
+use servers::prom::PromHandlerRef;
+use servers::query_handler::PrometheusProtocolHandlerRef;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::ResultExt;
+use tokio::sync::Mutex;
+
+use crate::error::{
+    CollectRecordbatchSnafu, Error, ExecutePromqlSnafu, NotSupportedSnafu, Result,
+    RuntimeResourceSnafu, WriteRuleResultSnafu,
+};
+use crate::rule::{
+    load_rule_files, AlertingRule, Labels, Notifier, RecordingRule, Rule, RuleGroup, RuleOptions,
+    Sample,
+};
+
+const METRIC_NAME_LABEL: &str = "__name__";
+/// Firing alerts are valid for this many evaluation intervals unless being sent again, so
+/// that Alertmanager resolves them if the rule stops being evaluated.
+const ALERT_VALID_INTERVALS: u32 = 4;
+
+/// Runs each rule group in a [RepeatedTask].
+pub struct RuleManager {
+    tasks: Vec<RepeatedTask<Error>>,
+}
+
+impl RuleManager {
+    pub fn try_new(
+        opts: &RuleOptions,
+        prom_handler: PromHandlerRef,
+        prometheus_handler: PrometheusProtocolHandlerRef,
+    ) -> Result<Self> {
+        let groups = load_rule_files(&opts.rule_files, opts.evaluation_interval)?;
+        let notifier = opts
+            .alertmanager_url
+            .as_deref()
+            .map(Notifier::try_new)
+            .transpose()?;
+        let evaluator = Arc::new(RuleEvaluator {
+            prom_handler,
+            prometheus_handler,
+            notifier,
+            query_ctx: Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, &opts.schema)),
+        });
+
+        let tasks = groups
+            .into_iter()
+            .map(|group| {
+                RepeatedTask::new(
+                    group.interval,
+                    Arc::new(RuleGroupTask {
+                        name: format!("rule-group-{}", group.name),
+                        evaluator: evaluator.clone(),
+                        group: Mutex::new(group),
+                    }),
+                )
+            })
+            .collect();
+        Ok(Self { tasks })
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        for task in &self.tasks {
+            task.start(common_runtime::bg_runtime())
+                .await
+                .context(RuntimeResourceSnafu)?;
+        }
+        info!("Rule manager started with {} rule groups", self.tasks.len());
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        for task in &self.tasks {
+            if task.started() {
+                task.stop().await.context(RuntimeResourceSnafu)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct RuleGroupTask {
+    name: String,
+    evaluator: Arc<RuleEvaluator>,
+    group: Mutex<RuleGroup>,
+}
+
+#[async_trait]
+impl TaskFunction<Error> for RuleGroupTask {
+    async fn call(&self) -> Result<()> {
+        let mut group = self.group.lock().await;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let valid_for = group.interval * ALERT_VALID_INTERVALS;
+        let group = &mut *group;
+        for rule in &mut group.rules {
+            let result = match rule {
+                Rule::Recording(rule) => self.evaluator.record(rule, now).await,
+                Rule::Alerting(rule) => self.evaluator.alert(rule, now, valid_for).await,
+            };
+            // Failure of a rule doesn't stop evaluating the rest.
+            if let Err(e) = result {
+                error!(e; "Failed to evaluate rule {} in group {}", rule.name(), group.name);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+struct RuleEvaluator {
+    prom_handler: PromHandlerRef,
+    prometheus_handler: PrometheusProtocolHandlerRef,
+    notifier: Option<Notifier>,
+    query_ctx: QueryContextRef,
+}
+
+impl RuleEvaluator {
+    /// Writes the result of the recording rule at `now` as the metric named by the rule.
+    async fn record(&self, rule: &RecordingRule, now: i64) -> Result<()> {
+        let samples = self.query(&rule.expr, now).await?;
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        let timeseries = samples
+            .into_iter()
+            .map(|sample| {
+                let mut labels = sample.labels;
+                labels.extend(rule.labels.clone());
+                let _ = labels.insert(METRIC_NAME_LABEL.to_string(), rule.name.clone());
+                TimeSeries {
+                    labels: labels
+                        .into_iter()
+                        .map(|(name, value)| Label { name, value })
+                        .collect(),
+                    samples: vec![PromSample {
+                        value: sample.value,
+                        timestamp: now,
+                    }],
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let request = WriteRequest {
+            timeseries,
+            ..Default::default()
+        };
+        self.prometheus_handler
+            .write(request, self.query_ctx.clone())
+            .await
+            .context(WriteRuleResultSnafu { rule: &rule.name })
+    }
+
+    async fn alert(&self, rule: &mut AlertingRule, now: i64, valid_for: Duration) -> Result<()> {
+        let samples = self.query(rule.expr(), now).await?;
+        let alerts = rule.eval(samples, now, valid_for);
+        if let Some(notifier) = &self.notifier {
+            notifier.send(&alerts).await?;
+        }
+        Ok(())
+    }
+
+    /// Evaluates `expr` as an instant query at `now`.
+    async fn query(&self, expr: &str, now: i64) -> Result<Vec<Sample>> {
+        let time = format!("{:.3}", now as f64 / 1000.0);
+        let query = PromQuery {
+            query: expr.to_string(),
+            start: time.clone(),
+            end: time,
+            step: "1s".to_string(),
+        };
+        match self
+            .prom_handler
+            .do_query(&query, self.query_ctx.clone())
+            .await
+        {
+            Ok(output) => output_to_samples(output).await,
+            // Like Prometheus, querying a nonexistent metric or label returns nothing.
+            Err(e)
+                if e.status_code() == StatusCode::TableNotFound
+                    || e.status_code() == StatusCode::TableColumnNotFound =>
+            {
+                Ok(vec![])
+            }
+            Err(e) => Err(e).context(ExecutePromqlSnafu { query: expr }),
+        }
+    }
+}
+
+/// Converts the output of an instant query to samples, the string columns are labels and
+/// the first float column is the value.
+async fn output_to_samples(output: Output) -> Result<Vec<Sample>> {
+    let batches = match output {
+        Output::RecordBatches(batches) => batches,
+        Output::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(CollectRecordbatchSnafu)?,
+        Output::AffectedRows(_) => {
+            return NotSupportedSnafu {
+                feat: "rule expression returning affected rows",
+            }
+            .fail()
+        }
+    };
+
+    let schema = batches.schema();
+    let mut tag_columns = Vec::new();
+    let mut value_column = None;
+    for (i, column) in schema.column_schemas().iter().enumerate() {
+        match column.data_type {
+            ConcreteDataType::String(_) => tag_columns.push((i, column.name.clone())),
+            ConcreteDataType::Float64(_) if value_column.is_none() => value_column = Some(i),
+            _ => {}
+        }
+    }
+    let Some(value_column) = value_column else {
+        return NotSupportedSnafu {
+            feat: "rule expression without a float value",
+        }
+        .fail();
+    };
+
+    // Keeps the latest value of each series.
+    let mut series = BTreeMap::<Labels, f64>::new();
+    for batch in batches.iter() {
+        let values = batch
+            .column(value_column)
+            .as_any()
+            .downcast_ref::<Float64Vector>()
+            .unwrap();
+        let tags = tag_columns
+            .iter()
+            .map(|(i, name)| {
+                let column = batch
+                    .column(*i)
+                    .as_any()
+                    .downcast_ref::<StringVector>()
+                    .unwrap();
+                (name, column)
+            })
+            .collect::<Vec<_>>();
+        for row in 0..batch.num_rows() {
+            let Some(value) = values.get_data(row) else {
+                continue;
+            };
+            let labels = tags
+                .iter()
+                .filter_map(|(name, column)| {
+                    column
+                        .get_data(row)
+                        .map(|v| (name.to_string(), v.to_string()))
+                })
+                .collect::<Labels>();
+            let _ = series.insert(labels, value);
+        }
+    }
+    Ok(series
+        .into_iter()
+        .map(|(labels, value)| Sample { labels, value })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::TimestampMillisecondVector;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_output_to_samples() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("value", ConcreteDataType::float64_datatype(), true),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec![
+                Some("a"),
+                Some("b"),
+                None,
+                Some("a"),
+            ])),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![0, 0, 0, 1000])),
+            Arc::new(Float64Vector::from(vec![
+                Some(1.0),
+                None,
+                Some(3.0),
+                Some(4.0),
+            ])),
+        ];
+        let batches = RecordBatches::try_from_columns(schema, columns).unwrap();
+
+        let samples = output_to_samples(Output::RecordBatches(batches))
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![
+                Sample {
+                    labels: Labels::new(),
+                    value: 3.0,
+                },
+                Sample {
+                    labels: Labels::from([("host".to_string(), "a".to_string())]),
+                    value: 4.0,
+                },
+            ],
+            samples
+        );
+
+        assert!(output_to_samples(Output::AffectedRows(1)).await.is_err());
+    }
+}
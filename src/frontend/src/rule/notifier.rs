@@ -0,0 +1,96 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hyper::client::HttpConnector;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Client, Method, Request, Uri};
+use snafu::{ensure, ResultExt};
+
+use crate::error::{
+    AlertmanagerRejectedSnafu, EncodeJsonSnafu, InvalidAlertmanagerUrlSnafu, Result,
+    SendAlertsSnafu,
+};
+use crate::rule::alert::Alert;
+
+const ALERTS_API_PATH: &str = "/api/v2/alerts";
+
+/// Sends alerts to an Alertmanager-compatible webhook.
+#[derive(Debug)]
+pub struct Notifier {
+    client: Client<HttpConnector>,
+    url: Uri,
+}
+
+impl Notifier {
+    /// Creates a notifier posting to the alerts API of the Alertmanager at `url`.
+    pub fn try_new(url: &str) -> Result<Self> {
+        let full_url = format!("{}{ALERTS_API_PATH}", url.trim_end_matches('/'));
+        let url = full_url
+            .parse::<Uri>()
+            .context(InvalidAlertmanagerUrlSnafu { url })?;
+        Ok(Self {
+            client: Client::new(),
+            url,
+        })
+    }
+
+    pub fn url(&self) -> &Uri {
+        &self.url
+    }
+
+    pub async fn send(&self, alerts: &[Alert]) -> Result<()> {
+        if alerts.is_empty() {
+            return Ok(());
+        }
+
+        let body = serde_json::to_vec(alerts).context(EncodeJsonSnafu)?;
+        // The request can't fail to build as the url has been validated.
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let response = self
+            .client
+            .request(request)
+            .await
+            .context(SendAlertsSnafu {
+                url: self.url.to_string(),
+            })?;
+        ensure!(
+            response.status().is_success(),
+            AlertmanagerRejectedSnafu {
+                url: self.url.to_string(),
+                status: response.status().as_u16(),
+            }
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notifier_url() {
+        let notifier = Notifier::try_new("http://127.0.0.1:9093/").unwrap();
+        assert_eq!(
+            "http://127.0.0.1:9093/api/v2/alerts",
+            notifier.url().to_string()
+        );
+        assert!(Notifier::try_new("not a url").is_err());
+    }
+}
@@ -0,0 +1,469 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scrapes metrics from Prometheus exporters periodically and writes them through the
+//! Prometheus remote write path, so small deployments don't need to run a Prometheus.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use api::prometheus::remote::{Label, Sample, TimeSeries, WriteRequest};
+use async_trait::async_trait;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_runtime::{RepeatedTask, TaskFunction};
+use common_telemetry::{info, warn};
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Uri};
+use serde::{Deserialize, Serialize};
+use servers::query_handler::PrometheusProtocolHandlerRef;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{
+    Error, InvalidScrapeUrlSnafu, ParseExpositionSnafu, Result, RuntimeResourceSnafu,
+    ScrapeStatusSnafu, ScrapeTargetSnafu, ScrapeTimeoutSnafu, WriteScrapedSamplesSnafu,
+};
+
+const METRIC_NAME_LABEL: &str = "__name__";
+const JOB_LABEL: &str = "job";
+const INSTANCE_LABEL: &str = "instance";
+/// Prefix of the exposed labels conflicting with the labels of the target.
+const EXPORTED_LABEL_PREFIX: &str = "exported_";
+/// Metric recording whether the last scrape of a target succeeded.
+const UP_METRIC: &str = "up";
+const SCRAPE_DURATION_METRIC: &str = "scrape_duration_seconds";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrapeOptions {
+    /// Scrape interval of the targets without one.
+    #[serde(with = "humantime_serde")]
+    pub scrape_interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub scrape_timeout: Duration,
+    /// The schema to write scraped metrics to.
+    pub schema: String,
+    pub targets: Vec<ScrapeTarget>,
+}
+
+impl Default for ScrapeOptions {
+    fn default() -> Self {
+        Self {
+            scrape_interval: Duration::from_secs(60),
+            scrape_timeout: Duration::from_secs(10),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            targets: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScrapeTarget {
+    /// Value of the `job` label of the scraped metrics.
+    pub job: String,
+    /// Url of the metrics endpoint, e.g. "http://127.0.0.1:9100/metrics".
+    pub url: String,
+    #[serde(default, with = "humantime_serde")]
+    pub interval: Option<Duration>,
+    /// Extra labels attached to the scraped metrics.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Runs a [RepeatedTask] scraping each target.
+pub struct Scraper {
+    tasks: Vec<RepeatedTask<Error>>,
+}
+
+impl Scraper {
+    pub fn try_new(opts: &ScrapeOptions, handler: PrometheusProtocolHandlerRef) -> Result<Self> {
+        let client = Client::new();
+        let query_ctx = Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, &opts.schema));
+
+        let tasks = opts
+            .targets
+            .iter()
+            .map(|target| {
+                let url = target
+                    .url
+                    .parse::<Uri>()
+                    .context(InvalidScrapeUrlSnafu { url: &target.url })?;
+                let mut labels = target.labels.clone();
+                let _ = labels.insert(JOB_LABEL.to_string(), target.job.clone());
+                let _ = labels.insert(
+                    INSTANCE_LABEL.to_string(),
+                    url.authority().map(|a| a.to_string()).unwrap_or_default(),
+                );
+                let task = ScrapeTask {
+                    name: format!("scrape-{}-{}", target.job, target.url),
+                    client: client.clone(),
+                    url,
+                    timeout: opts.scrape_timeout,
+                    labels,
+                    handler: handler.clone(),
+                    query_ctx: query_ctx.clone(),
+                };
+                Ok(RepeatedTask::new(
+                    target.interval.unwrap_or(opts.scrape_interval),
+                    Arc::new(task),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { tasks })
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        for task in &self.tasks {
+            task.start(common_runtime::bg_runtime())
+                .await
+                .context(RuntimeResourceSnafu)?;
+        }
+        info!("Scraper started with {} targets", self.tasks.len());
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        for task in &self.tasks {
+            if task.started() {
+                task.stop().await.context(RuntimeResourceSnafu)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct ScrapeTask {
+    name: String,
+    client: Client<HttpConnector>,
+    url: Uri,
+    timeout: Duration,
+    /// Labels of the target, attached to every scraped sample.
+    labels: BTreeMap<String, String>,
+    handler: PrometheusProtocolHandlerRef,
+    query_ctx: QueryContextRef,
+}
+
+#[async_trait]
+impl TaskFunction<Error> for ScrapeTask {
+    async fn call(&self) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let start = Instant::now();
+        let scraped = self.scrape(now).await;
+        let duration = start.elapsed().as_secs_f64();
+
+        // Like Prometheus, the health of the target is recorded even if the scrape fails.
+        let up = if scraped.is_ok() { 1.0 } else { 0.0 };
+        let mut timeseries = scraped.unwrap_or_else(|e| {
+            warn!("Failed to scrape {}, error: {}", self.url, e);
+            vec![]
+        });
+        timeseries.push(self.target_series(UP_METRIC, up, now));
+        timeseries.push(self.target_series(SCRAPE_DURATION_METRIC, duration, now));
+
+        let request = WriteRequest {
+            timeseries,
+            ..Default::default()
+        };
+        self.handler
+            .write(request, self.query_ctx.clone())
+            .await
+            .context(WriteScrapedSamplesSnafu {
+                url: self.url.to_string(),
+            })
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ScrapeTask {
+    async fn scrape(&self, now: i64) -> Result<Vec<TimeSeries>> {
+        let url = self.url.to_string();
+        let response = tokio::time::timeout(self.timeout, self.client.get(self.url.clone()))
+            .await
+            .ok()
+            .context(ScrapeTimeoutSnafu { url: &url })?
+            .context(ScrapeTargetSnafu { url: &url })?;
+        ensure!(
+            response.status().is_success(),
+            ScrapeStatusSnafu {
+                url: &url,
+                status: response.status().as_u16(),
+            }
+        );
+        let body = tokio::time::timeout(self.timeout, hyper::body::to_bytes(response.into_body()))
+            .await
+            .ok()
+            .context(ScrapeTimeoutSnafu { url: &url })?
+            .context(ScrapeTargetSnafu { url: &url })?;
+        let text = String::from_utf8_lossy(&body);
+        parse_text_exposition(&text, &self.labels, now)
+    }
+
+    fn target_series(&self, name: &str, value: f64, now: i64) -> TimeSeries {
+        let mut labels = self.labels.clone();
+        let _ = labels.insert(METRIC_NAME_LABEL.to_string(), name.to_string());
+        to_timeseries(labels, value, now)
+    }
+}
+
+fn to_timeseries(labels: BTreeMap<String, String>, value: f64, timestamp: i64) -> TimeSeries {
+    TimeSeries {
+        labels: labels
+            .into_iter()
+            .map(|(name, value)| Label { name, value })
+            .collect(),
+        samples: vec![Sample { value, timestamp }],
+        ..Default::default()
+    }
+}
+
+/// Parses metrics in the Prometheus text exposition format, see
+/// <https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format>.
+///
+/// `target_labels` are attached to every sample, exposed labels conflicting with them are
+/// renamed with the prefix `exported_`. Samples without a timestamp are stamped with `now`.
+fn parse_text_exposition(
+    text: &str,
+    target_labels: &BTreeMap<String, String>,
+    now: i64,
+) -> Result<Vec<TimeSeries>> {
+    let mut timeseries = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        // Comments, including HELP and TYPE, don't affect the samples.
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (mut labels, value, timestamp) =
+            parse_sample_line(line).with_context(|| ParseExpositionSnafu {
+                line: i + 1,
+                content: line,
+            })?;
+        for (name, value) in target_labels {
+            if let Some(exposed) = labels.remove(name) {
+                let _ = labels.insert(format!("{EXPORTED_LABEL_PREFIX}{name}"), exposed);
+            }
+            let _ = labels.insert(name.clone(), value.clone());
+        }
+        timeseries.push(to_timeseries(labels, value, timestamp.unwrap_or(now)));
+    }
+    Ok(timeseries)
+}
+
+/// Parses `metric_name{label="value",...} value [timestamp]`, returns `None` if the line is
+/// malformed.
+fn parse_sample_line(line: &str) -> Option<(BTreeMap<String, String>, f64, Option<i64>)> {
+    let name_end = line
+        .find(|c: char| c == '{' || c.is_whitespace())
+        .unwrap_or(line.len());
+    let name = &line[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+    let mut labels = BTreeMap::new();
+    let _ = labels.insert(METRIC_NAME_LABEL.to_string(), name.to_string());
+
+    let mut rest = &line[name_end..];
+    if let Some(label_str) = rest.strip_prefix('{') {
+        rest = parse_labels(label_str, &mut labels)?;
+    }
+
+    let mut parts = rest.split_whitespace();
+    let value = parse_value(parts.next()?)?;
+    let timestamp = parts.next().map(|t| t.parse::<i64>().ok()).transpose()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((labels, value, timestamp))
+}
+
+/// Parses labels until the closing `}`, returns the text after it.
+fn parse_labels<'a>(mut s: &'a str, labels: &mut BTreeMap<String, String>) -> Option<&'a str> {
+    loop {
+        s = s.trim_start();
+        if let Some(rest) = s.strip_prefix('}') {
+            return Some(rest);
+        }
+        let (name, rest) = s.split_once('=')?;
+        let rest = rest.trim_start().strip_prefix('"')?;
+
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        let end = loop {
+            match chars.next()? {
+                (i, '"') => break i,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    c => value.push(c),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        let _ = labels.insert(name.trim().to_string(), value);
+
+        s = rest[end + 1..].trim_start();
+        if let Some(rest) = s.strip_prefix(',') {
+            s = rest;
+        }
+    }
+}
+
+fn parse_value(s: &str) -> Option<f64> {
+    match s {
+        "+Inf" | "Inf" => Some(f64::INFINITY),
+        "-Inf" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        s => s.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Vec<Label> {
+        pairs
+            .iter()
+            .map(|(name, value)| Label {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_text_exposition() {
+        let text = r#"
+# HELP http_requests_total The total number of HTTP requests.
+# TYPE http_requests_total counter
+http_requests_total{method="post",code="200"} 1027 1395066363000
+http_requests_total{method="post", code="400", } 3 1395066363000
+
+# Escaping in label values:
+msdos_file_access_time_seconds{path="C:\\DIR\\FILE.TXT",error="Cannot find file:\n\"FILE.TXT\""} 1.458255915e9
+metric_without_timestamp_and_labels 12.47
+something_weird{problem="division by zero"} +Inf -3982045
+rpc_duration_seconds{job="exported",quantile="0.5"} 4773
+"#;
+        let target_labels = BTreeMap::from([
+            ("job".to_string(), "node".to_string()),
+            ("instance".to_string(), "localhost:9100".to_string()),
+        ]);
+        let timeseries = parse_text_exposition(text, &target_labels, 1000).unwrap();
+        assert_eq!(6, timeseries.len());
+
+        assert_eq!(
+            labels(&[
+                ("__name__", "http_requests_total"),
+                ("code", "200"),
+                ("instance", "localhost:9100"),
+                ("job", "node"),
+                ("method", "post"),
+            ]),
+            timeseries[0].labels
+        );
+        assert_eq!(
+            vec![Sample {
+                value: 1027.0,
+                timestamp: 1395066363000
+            }],
+            timeseries[0].samples
+        );
+        assert_eq!("400", timeseries[1].labels[1].value);
+
+        assert_eq!(
+            labels(&[
+                ("__name__", "msdos_file_access_time_seconds"),
+                ("error", "Cannot find file:\n\"FILE.TXT\""),
+                ("instance", "localhost:9100"),
+                ("job", "node"),
+                ("path", "C:\\DIR\\FILE.TXT"),
+            ]),
+            timeseries[2].labels
+        );
+        assert_eq!(1.458255915e9, timeseries[2].samples[0].value);
+
+        assert_eq!(
+            vec![Sample {
+                value: 12.47,
+                timestamp: 1000
+            }],
+            timeseries[3].samples
+        );
+        assert_eq!(f64::INFINITY, timeseries[4].samples[0].value);
+        assert_eq!(-3982045, timeseries[4].samples[0].timestamp);
+
+        // The exposed `job` label conflicts with the target's.
+        assert_eq!(
+            labels(&[
+                ("__name__", "rpc_duration_seconds"),
+                ("exported_job", "exported"),
+                ("instance", "localhost:9100"),
+                ("job", "node"),
+                ("quantile", "0.5"),
+            ]),
+            timeseries[5].labels
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_exposition() {
+        let target_labels = BTreeMap::new();
+        for text in [
+            "metric_without_value",
+            "metric{label=\"unclosed} 1",
+            "metric{label=unquoted} 1",
+            "metric 1 not_a_timestamp",
+            "metric 1 2 3",
+            "{label=\"no name\"} 1",
+        ] {
+            assert!(
+                parse_text_exposition(text, &target_labels, 0).is_err(),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_scrape_options() {
+        let opts: ScrapeOptions = toml::from_str(
+            r#"
+scrape_interval = "15s"
+
+[[targets]]
+job = "node"
+url = "http://127.0.0.1:9100/metrics"
+labels = { env = "dev" }
+
+[[targets]]
+job = "greptimedb"
+url = "http://127.0.0.1:4000/metrics"
+interval = "1m"
+"#,
+        )
+        .unwrap();
+        assert_eq!(Duration::from_secs(15), opts.scrape_interval);
+        assert_eq!(Duration::from_secs(10), opts.scrape_timeout);
+        assert_eq!(2, opts.targets.len());
+        assert_eq!("dev", opts.targets[0].labels["env"]);
+        assert_eq!(None, opts.targets[0].interval);
+        assert_eq!(Some(Duration::from_secs(60)), opts.targets[1].interval);
+    }
+}
@@ -20,7 +20,7 @@ use query::QueryEngineRef;
 
 use crate::error::Result;
 
-#[cfg(not(feature = "python"))]
+#[cfg(not(any(feature = "python", feature = "wasm")))]
 mod dummy {
     use super::*;
 
@@ -43,6 +43,15 @@ mod dummy {
             servers::error::NotSupportedSnafu { feat: "script" }.fail()
         }
 
+        pub async fn insert_wasm_function(
+            &self,
+            _schema: &str,
+            _name: &str,
+            _module: &[u8],
+        ) -> servers::error::Result<()> {
+            servers::error::NotSupportedSnafu { feat: "script" }.fail()
+        }
+
         pub async fn execute_script(
             &self,
             _schema: &str,
@@ -54,8 +63,8 @@ mod dummy {
     }
 }
 
-#[cfg(feature = "python")]
-mod python {
+#[cfg(any(feature = "python", feature = "wasm"))]
+mod manager {
     use common_error::prelude::BoxedError;
     use common_telemetry::logging::error;
     use script::manager::ScriptManager;
@@ -85,8 +94,7 @@ mod python {
             name: &str,
             script: &str,
         ) -> servers::error::Result<()> {
-            let _s = self
-                .script_manager
+            self.script_manager
                 .insert_and_compile(schema, name, script)
                 .await
                 .map_err(|e| {
@@ -98,6 +106,22 @@ mod python {
             Ok(())
         }
 
+        pub async fn insert_wasm_function(
+            &self,
+            schema: &str,
+            name: &str,
+            module: &[u8],
+        ) -> servers::error::Result<()> {
+            self.script_manager
+                .insert_and_register_wasm(schema, name, module)
+                .await
+                .map_err(|e| {
+                    error!(e; "Instance failed to insert WASM function");
+                    BoxedError::new(e)
+                })
+                .context(servers::error::InsertScriptSnafu { name })
+        }
+
         pub async fn execute_script(
             &self,
             schema: &str,
@@ -116,7 +140,7 @@ mod python {
     }
 }
 
-#[cfg(not(feature = "python"))]
+#[cfg(not(any(feature = "python", feature = "wasm")))]
 pub use self::dummy::*;
-#[cfg(feature = "python")]
-pub use self::python::*;
+#[cfg(any(feature = "python", feature = "wasm"))]
+pub use self::manager::*;
@@ -27,7 +27,7 @@ use servers::metrics_handler::MetricsHandler;
 use servers::mysql::server::{MysqlServer, MysqlSpawnConfig, MysqlSpawnRef};
 use servers::opentsdb::OpentsdbServer;
 use servers::postgres::PostgresServer;
-use servers::prom::PromServer;
+use servers::prom::{PromResponseOptions, PromServer};
 use servers::query_handler::grpc::ServerGrpcQueryHandlerAdaptor;
 use servers::query_handler::sql::ServerSqlQueryHandlerAdaptor;
 use servers::server::Server;
@@ -37,7 +37,7 @@ use crate::error::Error::StartServer;
 use crate::error::{self, Result};
 use crate::frontend::FrontendOptions;
 use crate::influxdb::InfluxdbOptions;
-use crate::instance::FrontendInstance;
+use crate::instance::Instance;
 use crate::prometheus::PrometheusOptions;
 
 pub(crate) struct Services;
@@ -47,14 +47,11 @@ pub type ServerHandlers = HashMap<String, ServerHandler>;
 pub type ServerHandler = (Box<dyn Server>, SocketAddr);
 
 impl Services {
-    pub(crate) async fn build<T>(
+    pub(crate) async fn build(
         opts: &FrontendOptions,
-        instance: Arc<T>,
+        instance: Arc<Instance>,
         plugins: Arc<Plugins>,
-    ) -> Result<ServerHandlers>
-    where
-        T: FrontendInstance,
-    {
+    ) -> Result<ServerHandlers> {
         let mut result = Vec::<ServerHandler>::with_capacity(plugins.len());
         let user_provider = plugins.get::<UserProviderRef>().cloned();
 
@@ -179,6 +176,10 @@ impl Services {
             }
             http_server_builder.with_metrics_handler(MetricsHandler);
             http_server_builder.with_script_handler(instance.clone());
+            http_server_builder.with_database_aliases(instance.database_aliases());
+            if let Some(health_checker) = instance.health_checker() {
+                http_server_builder.with_health_checker(health_checker);
+            }
             let http_server = http_server_builder.build();
             result.push((Box::new(http_server), http_addr));
         }
@@ -186,10 +187,15 @@ impl Services {
         if let Some(prom_options) = &opts.prom_options {
             let prom_addr = parse_addr(&prom_options.addr)?;
 
+            let database_aliases = instance.database_aliases();
             let mut prom_server = PromServer::create_server(instance);
             if let Some(user_provider) = user_provider {
                 prom_server.set_user_provider(user_provider);
             }
+            prom_server.set_database_aliases(database_aliases);
+            prom_server.set_response_options(PromResponseOptions {
+                sort_series: prom_options.sort_series,
+            });
 
             result.push((prom_server, prom_addr));
         };
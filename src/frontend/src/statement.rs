@@ -12,38 +12,53 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bulk_admin;
 mod copy_table_from;
 mod copy_table_to;
 mod describe;
+mod purge;
+mod read_policy;
+mod replay;
 mod show;
 mod tql;
 
 use catalog::CatalogManagerRef;
+use common_catalog::format_full_table_name;
 use common_error::prelude::BoxedError;
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use datanode::instance::sql::table_idents_to_full_name;
 use query::parser::QueryStatement;
+use query::plan::LogicalPlan;
 use query::query_engine::SqlStatementExecutorRef;
 use query::QueryEngineRef;
-use session::context::QueryContextRef;
+use servers::auth::UserProviderRef;
+use session::context::{QueryContextRef, SessionFunction};
 use snafu::{ensure, OptionExt, ResultExt};
+use sql::ast::{Expr, ObjectName, UnaryOperator, Value};
+use sql::statements::admin::Admin;
 use sql::statements::copy::{CopyTable, CopyTableArgument};
+use sql::statements::create::CreateFunction;
+use sql::statements::drop::DropFunction;
+use sql::statements::set_variables::SetVariables;
 use sql::statements::statement::Statement;
 use table::engine::TableReference;
 use table::requests::{CopyDirection, CopyTableRequest};
 use table::TableRef;
 
+pub(crate) use self::read_policy::ReadPolicyQueryEngine;
 use crate::error::{
-    CatalogSnafu, ExecLogicalPlanSnafu, ExecuteStatementSnafu, ExternalSnafu, PlanStatementSnafu,
-    Result, SchemaNotFoundSnafu, TableNotFoundSnafu,
+    CatalogSnafu, ExecLogicalPlanSnafu, ExecuteStatementSnafu, ExternalSnafu, InvalidSqlSnafu,
+    NotSupportedSnafu, PlanStatementSnafu, Result, SchemaNotFoundSnafu, TableNotFoundSnafu,
 };
+use crate::instance::throttle_write;
 
 #[derive(Clone)]
 pub(crate) struct StatementExecutor {
     catalog_manager: CatalogManagerRef,
     query_engine: QueryEngineRef,
     sql_stmt_executor: SqlStatementExecutorRef,
+    user_provider: Option<UserProviderRef>,
 }
 
 impl StatementExecutor {
@@ -56,9 +71,16 @@ impl StatementExecutor {
             catalog_manager,
             query_engine,
             sql_stmt_executor,
+            user_provider: None,
         }
     }
 
+    /// Sets the user provider, whose read policies are applied to the table scans of the users.
+    pub(crate) fn with_user_provider(mut self, user_provider: Option<UserProviderRef>) -> Self {
+        self.user_provider = user_provider;
+        self
+    }
+
     pub(crate) async fn execute_stmt(
         &self,
         stmt: QueryStatement,
@@ -88,24 +110,72 @@ impl StatementExecutor {
 
             Statement::Use(db) => self.handle_use(db, query_ctx).await,
 
-            Statement::ShowDatabases(stmt) => self.show_databases(stmt).await,
+            Statement::SetVariables(stmt) => set_variables(stmt, query_ctx),
+
+            Statement::CreateFunction(stmt) => create_function(stmt, query_ctx),
+
+            Statement::DropFunction(stmt) => drop_function(stmt, query_ctx),
+
+            Statement::ShowCatalogs(_) => self.show_catalogs().await,
+
+            Statement::ShowDatabases(stmt) => self.show_databases(stmt, query_ctx).await,
 
             Statement::ShowTables(stmt) => self.show_tables(stmt, query_ctx).await,
 
+            Statement::ShowCardinality(stmt) => self.show_cardinality(stmt, query_ctx).await,
+
             Statement::Copy(stmt) => {
-                let req = to_copy_table_request(stmt, query_ctx)?;
+                let req = to_copy_table_request(stmt, query_ctx.clone())?;
+                if matches!(req.direction, CopyDirection::Export) {
+                    // The export scans the table directly, which would bypass the read policies.
+                    ensure!(
+                        !self.has_read_policy(
+                            &req.catalog_name,
+                            &req.schema_name,
+                            &req.table_name,
+                            &query_ctx
+                        ),
+                        NotSupportedSnafu {
+                            feat: "COPY TO on table with read policies"
+                        }
+                    );
+                }
                 match req.direction {
                     CopyDirection::Export => self.copy_table_to(req).await,
                     CopyDirection::Import => self.copy_table_from(req).await,
                 }
             }
 
+            // Replay reads and writes through the frontend, so the target could be in another
+            // cluster.
+            Statement::Admin(Admin::Replay(replay)) => self.replay_table(replay, query_ctx).await,
+
+            // The purges are recorded through the frontend, as the audit table may be on any
+            // datanode.
+            Statement::Admin(Admin::Purge(purge)) => self.purge_table(purge, query_ctx).await,
+
+            // Bulk operations are split into operations on each table, which are forwarded
+            // as other admin statements.
+            Statement::Admin(Admin::Bulk(bulk)) => self.bulk_admin(bulk, query_ctx).await,
+
+            Statement::Insert(insert) => {
+                self.throttle_insert(insert.table_name(), &query_ctx)
+                    .await?;
+                self.sql_stmt_executor
+                    .execute_sql(Statement::Insert(insert), query_ctx)
+                    .await
+                    .context(ExecuteStatementSnafu)
+            }
+
             Statement::CreateDatabase(_)
             | Statement::CreateTable(_)
+            | Statement::CloneTable(_)
             | Statement::CreateExternalTable(_)
-            | Statement::Insert(_)
             | Statement::Alter(_)
             | Statement::DropTable(_)
+            | Statement::CreateView(_)
+            | Statement::DropView(_)
+            | Statement::Admin(_)
             | Statement::ShowCreateTable(_) => self
                 .sql_stmt_executor
                 .execute_sql(stmt, query_ctx)
@@ -114,18 +184,35 @@ impl StatementExecutor {
         }
     }
 
-    async fn plan_exec(&self, stmt: QueryStatement, query_ctx: QueryContextRef) -> Result<Output> {
-        let planner = self.query_engine.planner();
-        let plan = planner
-            .plan(stmt, query_ctx.clone())
+    pub(crate) async fn plan(
+        &self,
+        stmt: QueryStatement,
+        query_ctx: QueryContextRef,
+    ) -> Result<LogicalPlan> {
+        self.query_engine
+            .planner()
+            .plan(stmt, query_ctx)
             .await
-            .context(PlanStatementSnafu)?;
+            .context(PlanStatementSnafu)
+    }
+
+    pub(crate) async fn exec_plan(
+        &self,
+        plan: LogicalPlan,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let plan = self.apply_read_policies(plan, &query_ctx).await?;
         self.query_engine
             .execute(plan, query_ctx)
             .await
             .context(ExecLogicalPlanSnafu)
     }
 
+    async fn plan_exec(&self, stmt: QueryStatement, query_ctx: QueryContextRef) -> Result<Output> {
+        let plan = self.plan(stmt, query_ctx.clone()).await?;
+        self.exec_plan(plan, query_ctx).await
+    }
+
     async fn handle_use(&self, db: String, query_ctx: QueryContextRef) -> Result<Output> {
         let catalog = &query_ctx.current_catalog();
         ensure!(
@@ -142,6 +229,30 @@ impl StatementExecutor {
         Ok(Output::RecordBatches(RecordBatches::empty()))
     }
 
+    /// Applies the write throttle of the inserted table, the same as gRPC inserts. Inserts
+    /// to missing tables are left to fail in the executor.
+    async fn throttle_insert(
+        &self,
+        table_name: &ObjectName,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let (catalog, schema, table) = table_idents_to_full_name(table_name, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        let Some(table_ref) = self
+            .catalog_manager
+            .table(&catalog, &schema, &table)
+            .await
+            .context(CatalogSnafu)? else {
+            return Ok(());
+        };
+        throttle_write(
+            &table_ref,
+            &format_full_table_name(&catalog, &schema, &table),
+        )
+        .await
+    }
+
     async fn get_table(&self, table_ref: &TableReference<'_>) -> Result<TableRef> {
         let TableReference {
             catalog,
@@ -158,6 +269,69 @@ impl StatementExecutor {
     }
 }
 
+/// Sets a session variable by `SET @name = value`, the value must be a literal.
+fn set_variables(stmt: SetVariables, query_ctx: QueryContextRef) -> Result<Output> {
+    let name = stmt.variable.to_string();
+    let name = name.trim_start_matches('@');
+    let value = match stmt.value.as_slice() {
+        [Expr::Value(Value::SingleQuotedString(s) | Value::DoubleQuotedString(s))] => s.clone(),
+        [Expr::Value(Value::Number(n, _))] => n.clone(),
+        [Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        }] if matches!(expr.as_ref(), Expr::Value(Value::Number(..))) => format!("-{expr}"),
+        value => {
+            return InvalidSqlSnafu {
+                err_msg: format!(
+                    "Value of variable {name} must be a string or number literal, found: {}",
+                    value
+                        .iter()
+                        .map(|expr| expr.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            }
+            .fail();
+        }
+    };
+    query_ctx.set_variable(name, value);
+
+    Ok(Output::RecordBatches(RecordBatches::empty()))
+}
+
+fn create_function(stmt: CreateFunction, query_ctx: QueryContextRef) -> Result<Output> {
+    let name = stmt.name.value.to_lowercase();
+    ensure!(
+        stmt.or_replace || query_ctx.function(&name).is_none(),
+        InvalidSqlSnafu {
+            err_msg: format!("Function {name} already exists"),
+        }
+    );
+    query_ctx.set_function(SessionFunction {
+        name,
+        args: stmt
+            .args
+            .iter()
+            .map(|arg| arg.value.to_lowercase())
+            .collect(),
+        body: stmt.body,
+    });
+
+    Ok(Output::RecordBatches(RecordBatches::empty()))
+}
+
+fn drop_function(stmt: DropFunction, query_ctx: QueryContextRef) -> Result<Output> {
+    let name = stmt.name.value.to_lowercase();
+    ensure!(
+        query_ctx.remove_function(&name) || stmt.if_exists,
+        InvalidSqlSnafu {
+            err_msg: format!("Function {name} not found"),
+        }
+    );
+
+    Ok(Output::RecordBatches(RecordBatches::empty()))
+}
+
 fn to_copy_table_request(stmt: CopyTable, query_ctx: QueryContextRef) -> Result<CopyTableRequest> {
     let direction = match stmt {
         CopyTable::To(_) => CopyDirection::Export,
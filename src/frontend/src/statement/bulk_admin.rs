@@ -0,0 +1,228 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applies an administrative operation to all tables matching a pattern in a schema, e.g.
+//! to flush the auto-created tables of metrics.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_telemetry::{info, warn};
+use datatypes::prelude::{ConcreteDataType, ScalarVector};
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{BooleanVector, Helper, StringVector};
+use futures::{stream, StreamExt};
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt};
+use sql::ast::{Ident, ObjectName};
+use sql::statements::admin::{Admin, AdminBulk, BulkOperation};
+use sql::statements::statement::Statement;
+use table::metadata::TableType;
+
+use crate::error::{
+    CatalogSnafu, CreateRecordbatchSnafu, ExecuteStatementSnafu, MatchTableNamesSnafu, Result,
+    SchemaNotFoundSnafu,
+};
+use crate::statement::StatementExecutor;
+
+/// Number of tables operated on at the same time if the parallelism is not specified.
+const DEFAULT_BULK_PARALLELISM: usize = 4;
+
+/// Result of the operation on a table.
+struct TableOutcome {
+    table_name: String,
+    error: Option<String>,
+}
+
+impl StatementExecutor {
+    /// Applies the operation of `bulk` to the matched tables. A failure on one table doesn't
+    /// stop the others, the outcome of each table is returned instead.
+    pub(super) async fn bulk_admin(
+        &self,
+        bulk: AdminBulk,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let catalog = query_ctx.current_catalog();
+        let schema = bulk.schema.unwrap_or_else(|| query_ctx.current_schema());
+        let table_names = self
+            .match_table_names(&catalog, &schema, bulk.pattern.as_deref())
+            .await?;
+
+        let total = table_names.len();
+        let finished = AtomicUsize::new(0);
+        let parallelism = bulk.parallelism.unwrap_or(DEFAULT_BULK_PARALLELISM);
+        info!(
+            "Start bulk {:?} on {} tables in {}.{}, parallelism: {}",
+            bulk.operation, total, catalog, schema, parallelism
+        );
+
+        let mut outcomes = stream::iter(table_names)
+            .map(|table_name| {
+                let (operation, catalog, schema, finished) =
+                    (&bulk.operation, &catalog, &schema, &finished);
+                let query_ctx = query_ctx.clone();
+                async move {
+                    let outcome = self
+                        .run_bulk_operation(operation, catalog, schema, table_name, query_ctx)
+                        .await;
+                    let finished = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                    info!(
+                        "Bulk {:?} progress: {}/{}, table: {}",
+                        operation, finished, total, outcome.table_name
+                    );
+                    outcome
+                }
+            })
+            .buffer_unordered(parallelism)
+            .collect::<Vec<_>>()
+            .await;
+        outcomes.sort_unstable_by(|a, b| a.table_name.cmp(&b.table_name));
+
+        bulk_outcomes_to_output(outcomes)
+    }
+
+    /// Returns the sorted names of the base tables in the schema matching the `LIKE` pattern.
+    async fn match_table_names(
+        &self,
+        catalog: &str,
+        schema: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let schema_provider = self
+            .catalog_manager
+            .schema(catalog, schema)
+            .await
+            .context(CatalogSnafu)?
+            .context(SchemaNotFoundSnafu {
+                schema_info: format!("{catalog}.{schema}"),
+            })?;
+        let mut table_names = schema_provider.table_names().await.context(CatalogSnafu)?;
+        if let Some(pattern) = pattern {
+            let matched = Helper::like_utf8(table_names, pattern)
+                .context(MatchTableNamesSnafu { pattern })?;
+            let matched = matched
+                .as_any()
+                .downcast_ref::<StringVector>()
+                .expect("like_utf8 returns a string vector");
+            table_names = matched
+                .iter_data()
+                .flatten()
+                .map(|name| name.to_string())
+                .collect();
+        }
+
+        let mut base_tables = Vec::with_capacity(table_names.len());
+        for table_name in table_names {
+            let table = schema_provider
+                .table(&table_name)
+                .await
+                .context(CatalogSnafu)?;
+            // Views and system tables have no data to operate on.
+            if table.map_or(false, |table| table.table_type() == TableType::Base) {
+                base_tables.push(table_name);
+            }
+        }
+        base_tables.sort();
+        Ok(base_tables)
+    }
+
+    async fn run_bulk_operation(
+        &self,
+        operation: &BulkOperation,
+        catalog: &str,
+        schema: &str,
+        table_name: String,
+        query_ctx: QueryContextRef,
+    ) -> TableOutcome {
+        let object_name = ObjectName(vec![
+            Ident::new(catalog),
+            Ident::new(schema),
+            Ident::new(&table_name),
+        ]);
+        let result = match operation.to_statement(object_name) {
+            // Purges are recorded by the frontend.
+            Statement::Admin(Admin::Purge(purge)) => self.purge_table(purge, query_ctx).await,
+            stmt => self
+                .sql_stmt_executor
+                .execute_sql(stmt, query_ctx)
+                .await
+                .context(ExecuteStatementSnafu),
+        };
+        let error = match result {
+            Ok(_) => None,
+            Err(e) => {
+                warn!(
+                    "Failed to run bulk {:?} on table {}: {}",
+                    operation, table_name, e
+                );
+                Some(e.to_string())
+            }
+        };
+        TableOutcome { table_name, error }
+    }
+}
+
+fn bulk_outcomes_to_output(outcomes: Vec<TableOutcome>) -> Result<Output> {
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new("table", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("success", ConcreteDataType::boolean_datatype(), false),
+        ColumnSchema::new("error", ConcreteDataType::string_datatype(), true),
+    ]));
+    let columns = vec![
+        Arc::new(StringVector::from_iterator(
+            outcomes.iter().map(|o| o.table_name.as_str()),
+        )) as _,
+        Arc::new(BooleanVector::from_iterator(
+            outcomes.iter().map(|o| o.error.is_none()),
+        )) as _,
+        Arc::new(StringVector::from(
+            outcomes.iter().map(|o| o.error.clone()).collect::<Vec<_>>(),
+        )) as _,
+    ];
+    let records =
+        RecordBatches::try_from_columns(schema, columns).context(CreateRecordbatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_outcomes_to_output() {
+        let outcomes = vec![
+            TableOutcome {
+                table_name: "cpu".to_string(),
+                error: None,
+            },
+            TableOutcome {
+                table_name: "mem".to_string(),
+                error: Some("Table not found".to_string()),
+            },
+        ];
+        let Output::RecordBatches(records) = bulk_outcomes_to_output(outcomes).unwrap() else {
+            unreachable!()
+        };
+        let expected = "\
++-------+---------+-----------------+
+| table | success | error           |
++-------+---------+-----------------+
+| cpu   | true    |                 |
+| mem   | false   | Table not found |
++-------+---------+-----------------+";
+        assert_eq!(expected, records.pretty_print().unwrap());
+    }
+}
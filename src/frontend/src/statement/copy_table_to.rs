@@ -12,24 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use common_base::readable_size::ReadableSize;
 use common_datasource::file_format::csv::stream_to_csv;
 use common_datasource::file_format::json::stream_to_json;
 use common_datasource::file_format::Format;
 use common_datasource::object_store::{build_backend, parse_url};
-use common_query::physical_plan::SessionContext;
+use common_error::prelude::{ErrorExt, StatusCode};
+use common_query::physical_plan::{DfPhysicalPlanAdapter, SessionContext};
 use common_query::Output;
-use common_recordbatch::adapter::DfRecordBatchStreamAdapter;
+use common_recordbatch::adapter::{DfRecordBatchStreamAdapter, RecordBatchStreamAdapter};
 use common_recordbatch::SendableRecordBatchStream;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::ExecutionPlan;
 use object_store::ObjectStore;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use storage::sst::SstInfo;
 use storage::{ParquetWriter, Source};
 use table::engine::TableReference;
 use table::requests::CopyTableRequest;
+use table::TableRef;
 
 use crate::error::{self, Result, WriteParquetSnafu};
 use crate::statement::StatementExecutor;
+use crate::table::DistTable;
 
 impl StatementExecutor {
     async fn stream_to_file(
@@ -82,16 +89,46 @@ impl StatementExecutor {
 
         let format = Format::try_from(&req.with).context(error::ParseFileFormatSnafu)?;
 
-        let stream = table
-            .scan(None, &[], None)
-            .await
-            .with_context(|_| error::CopyTableSnafu {
-                table_name: table_ref.to_string(),
-            })?;
+        // Pins all regions at the same point before exporting, so the exported rows
+        // of different regions are consistent. A table without snapshots is exported
+        // by a normal scan only if it has a single region, which is read at one point.
+        let plan = match table.snapshot().await {
+            Ok(snapshot) => table.scan_snapshot(None, &[], None, &snapshot).await,
+            Err(e) if e.status_code() == StatusCode::Unsupported => {
+                let region_count = region_count(&table).await?;
+                ensure!(
+                    region_count <= 1,
+                    error::NotSupportedSnafu {
+                        feat: format!(
+                            "COPY TO of table {table_ref} with {region_count} regions, \
+                             which can't be read at a consistent snapshot"
+                        ),
+                    }
+                );
+                table.scan(None, &[], None).await
+            }
+            Err(e) => Err(e),
+        }
+        .with_context(|_| error::CopyTableSnafu {
+            table_name: table_ref.to_string(),
+        })?;
 
-        let stream = stream
-            .execute(0, SessionContext::default().task_ctx())
-            .context(error::TableScanExecSnafu)?;
+        // Regions may be scanned by different partitions, exports all of them.
+        let task_ctx = SessionContext::default().task_ctx();
+        let stream: SendableRecordBatchStream = if plan.output_partitioning().partition_count() == 1
+        {
+            plan.execute(0, task_ctx)
+                .context(error::TableScanExecSnafu)?
+        } else {
+            let plan = CoalescePartitionsExec::new(Arc::new(DfPhysicalPlanAdapter(plan)));
+            let df_stream = plan
+                .execute(0, task_ctx)
+                .context(error::MergeTableScanSnafu)?;
+            Box::pin(
+                RecordBatchStreamAdapter::try_new(df_stream)
+                    .context(error::CreateRecordbatchSnafu)?,
+            )
+        };
 
         let (_schema, _host, path) = parse_url(&req.location).context(error::ParseUrlSnafu)?;
         let object_store =
@@ -104,3 +141,12 @@ impl StatementExecutor {
         Ok(Output::AffectedRows(rows_copied))
     }
 }
+
+/// Returns the number of regions of the table, distributed tables are partitioned into
+/// regions on datanodes.
+async fn region_count(table: &TableRef) -> Result<usize> {
+    match table.as_any().downcast_ref::<DistTable>() {
+        Some(table) => table.region_count().await,
+        None => Ok(table.table_info().meta.region_numbers.len()),
+    }
+}
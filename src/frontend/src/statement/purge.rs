@@ -0,0 +1,319 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Purges the data expired by the TTL of a table, and records the executed purges into the
+//! retention audit table. The audit table is created through the frontend like any other
+//! table, so its id is allocated the same way in standalone and distributed mode.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_catalog::format_full_table_name;
+use common_error::prelude::BoxedError;
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_telemetry::{error, info};
+use common_time::util;
+use datanode::instance::sql::table_idents_to_full_name;
+use datatypes::value::Value;
+use datatypes::vectors::{
+    StringVector, TimestampMillisecondVector, UInt32Vector, UInt64Vector, VectorRef,
+};
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt};
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::admin::{Admin, AdminPurge};
+use sql::statements::statement::Statement;
+use table::requests::InsertRequest;
+
+use crate::error::{
+    CatalogSnafu, CollectRecordbatchSnafu, ExecuteStatementSnafu, ExternalSnafu, InsertSnafu,
+    ParseSqlSnafu, Result, TableNotFoundSnafu,
+};
+use crate::statement::StatementExecutor;
+
+/// Name of the table recording executed purges.
+pub const RETENTION_AUDIT_TABLE_NAME: &str = "retention_audit";
+
+/// Columns of the retention audit table.
+const RETENTION_AUDIT_TABLE_COLUMNS: &str = r#"(
+    catalog_name STRING,
+    schema_name STRING,
+    table_name STRING,
+    region_number INT UNSIGNED,
+    purged_at TIMESTAMP(3) TIME INDEX,
+    num_files BIGINT UNSIGNED,
+    file_size BIGINT UNSIGNED,
+    start_time TIMESTAMP(3) NULL,
+    end_time TIMESTAMP(3) NULL,
+    PRIMARY KEY (catalog_name, schema_name, table_name, region_number)
+) ENGINE=mito"#;
+
+/// Purge of a region, as reported by the datanode.
+#[derive(Debug, PartialEq)]
+struct PurgedRegion {
+    region_number: u32,
+    num_files: u64,
+    file_size: u64,
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+}
+
+impl StatementExecutor {
+    /// Purges the expired data of the table on the datanodes, then records the regions
+    /// that have purged files.
+    pub(super) async fn purge_table(
+        &self,
+        purge: AdminPurge,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, table) =
+            table_idents_to_full_name(&purge.table_name, query_ctx.clone())
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+        let dry_run = purge.dry_run;
+        let output = self
+            .sql_stmt_executor
+            .execute_sql(Statement::Admin(Admin::Purge(purge)), query_ctx.clone())
+            .await
+            .context(ExecuteStatementSnafu)?;
+        if dry_run {
+            return Ok(output);
+        }
+
+        let records = match output {
+            Output::RecordBatches(records) => records,
+            Output::Stream(stream) => RecordBatches::try_collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            Output::AffectedRows(_) => return Ok(output),
+        };
+        let full_table_name = format_full_table_name(&catalog, &schema, &table);
+        let purged = purged_regions(&records);
+        info!(
+            "Purged expired data of table {}: {:?}",
+            full_table_name, purged
+        );
+        // The data is already purged, so we only log the failure of auditing.
+        if let Err(e) = self
+            .audit_purges(&catalog, &schema, &table, &purged, query_ctx)
+            .await
+        {
+            error!(e; "Failed to record purges of table {}", full_table_name);
+        }
+
+        Ok(Output::RecordBatches(records))
+    }
+
+    /// Records the purges into the retention audit table, which is created on the first purge.
+    async fn audit_purges(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        purged: &[PurgedRegion],
+        query_ctx: QueryContextRef,
+    ) -> Result<()> {
+        if purged.is_empty() {
+            return Ok(());
+        }
+
+        let audit_table = match self
+            .catalog_manager
+            .table(
+                DEFAULT_CATALOG_NAME,
+                DEFAULT_SCHEMA_NAME,
+                RETENTION_AUDIT_TABLE_NAME,
+            )
+            .await
+            .context(CatalogSnafu)?
+        {
+            Some(table) => table,
+            None => {
+                self.create_retention_audit_table(query_ctx).await?;
+                self.catalog_manager
+                    .table(
+                        DEFAULT_CATALOG_NAME,
+                        DEFAULT_SCHEMA_NAME,
+                        RETENTION_AUDIT_TABLE_NAME,
+                    )
+                    .await
+                    .context(CatalogSnafu)?
+                    .with_context(|| TableNotFoundSnafu {
+                        table_name: RETENTION_AUDIT_TABLE_NAME,
+                    })?
+            }
+        };
+
+        let num_rows = purged.len();
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(9);
+        columns_values.insert(
+            "catalog_name".to_string(),
+            Arc::new(StringVector::from(vec![catalog; num_rows])) as _,
+        );
+        columns_values.insert(
+            "schema_name".to_string(),
+            Arc::new(StringVector::from(vec![schema; num_rows])) as _,
+        );
+        columns_values.insert(
+            "table_name".to_string(),
+            Arc::new(StringVector::from(vec![table; num_rows])) as _,
+        );
+        columns_values.insert(
+            "region_number".to_string(),
+            Arc::new(UInt32Vector::from_values(
+                purged.iter().map(|p| p.region_number),
+            )) as _,
+        );
+        columns_values.insert(
+            "purged_at".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![
+                util::current_time_millis();
+                num_rows
+            ])) as _,
+        );
+        columns_values.insert(
+            "num_files".to_string(),
+            Arc::new(UInt64Vector::from_values(
+                purged.iter().map(|p| p.num_files),
+            )) as _,
+        );
+        columns_values.insert(
+            "file_size".to_string(),
+            Arc::new(UInt64Vector::from_values(
+                purged.iter().map(|p| p.file_size),
+            )) as _,
+        );
+        columns_values.insert(
+            "start_time".to_string(),
+            Arc::new(TimestampMillisecondVector::from(
+                purged.iter().map(|p| p.start_time).collect::<Vec<_>>(),
+            )) as _,
+        );
+        columns_values.insert(
+            "end_time".to_string(),
+            Arc::new(TimestampMillisecondVector::from(
+                purged.iter().map(|p| p.end_time).collect::<Vec<_>>(),
+            )) as _,
+        );
+
+        let _ = audit_table
+            .insert(InsertRequest {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: RETENTION_AUDIT_TABLE_NAME.to_string(),
+                columns_values,
+                region_number: 0,
+            })
+            .await
+            .context(InsertSnafu {
+                table_name: RETENTION_AUDIT_TABLE_NAME,
+            })?;
+        Ok(())
+    }
+
+    async fn create_retention_audit_table(&self, query_ctx: QueryContextRef) -> Result<()> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} {RETENTION_AUDIT_TABLE_COLUMNS}",
+            format_full_table_name(
+                DEFAULT_CATALOG_NAME,
+                DEFAULT_SCHEMA_NAME,
+                RETENTION_AUDIT_TABLE_NAME
+            )
+        );
+        let mut stmts =
+            ParserContext::create_with_dialect(&sql, &GenericDialect {}).context(ParseSqlSnafu)?;
+        let _ = self
+            .sql_stmt_executor
+            .execute_sql(stmts.remove(0), query_ctx)
+            .await
+            .context(ExecuteStatementSnafu)?;
+        Ok(())
+    }
+}
+
+/// Returns the regions having purged files from the purge reports of the datanodes.
+fn purged_regions(records: &RecordBatches) -> Vec<PurgedRegion> {
+    let timestamp = |value: Value| match value {
+        Value::Timestamp(ts) => Some(ts.value()),
+        _ => None,
+    };
+    let mut purged = Vec::new();
+    for batch in records.iter() {
+        let [region, files, bytes, start, end] = batch.columns() else { continue };
+        for row in 0..batch.num_rows() {
+            let (Value::UInt32(region_number), Value::UInt64(num_files), Value::UInt64(file_size)) =
+                (region.get(row), files.get(row), bytes.get(row)) else { continue };
+            if num_files == 0 {
+                continue;
+            }
+            purged.push(PurgedRegion {
+                region_number,
+                num_files,
+                file_size,
+                start_time: timestamp(start.get(row)),
+                end_time: timestamp(end.get(row)),
+            });
+        }
+    }
+    purged
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_purged_regions() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("region", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("files", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("bytes", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(
+                "start_time",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new(
+                "end_time",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+        ]));
+        let columns = vec![
+            Arc::new(UInt32Vector::from_slice([0, 1])) as _,
+            Arc::new(UInt64Vector::from_slice([0, 2])) as _,
+            Arc::new(UInt64Vector::from_slice([0, 1024])) as _,
+            Arc::new(TimestampMillisecondVector::from(vec![None, Some(1000)])) as _,
+            Arc::new(TimestampMillisecondVector::from(vec![None, Some(2000)])) as _,
+        ];
+        let records = RecordBatches::try_from_columns(schema, columns).unwrap();
+
+        assert_eq!(
+            vec![PurgedRegion {
+                region_number: 1,
+                num_files: 2,
+                file_size: 1024,
+                start_time: Some(1000),
+                end_time: Some(2000),
+            }],
+            purged_regions(&records)
+        );
+    }
+}
@@ -0,0 +1,419 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read policies of the users from the user provider, including the row filters and the
+//! masking rules of the columns, which are applied to every scan on the tables having them.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_catalog::format_full_table_name;
+use common_error::prelude::BoxedError;
+use common_function::scalars::aggregate::AggregateFunctionMetaRef;
+use common_function::scalars::FunctionRef;
+use common_query::prelude::ScalarUdf;
+use common_query::Output;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::datasource::{provider_as_source, source_as_provider, ViewTable};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Column, OwnedTableReference, Result as DfResult, ScalarValue};
+use datafusion_expr::expr::Cast;
+use datafusion_expr::utils::from_plan;
+use datafusion_expr::{
+    lit, md5, Expr, LogicalPlan as DfLogicalPlan, LogicalPlanBuilder, Subquery, TableScan, WriteOp,
+};
+use datatypes::schema::Schema;
+use query::error::{QueryExecutionSnafu, QueryPlanSnafu};
+use query::parser::QueryStatement;
+use query::plan::LogicalPlan;
+use query::planner::LogicalPlanner;
+use query::{QueryEngine, QueryEngineRef};
+use servers::auth::MaskingRule;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{ensure, ResultExt};
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use table::metadata::TableInfo;
+use table::table::adapter::DfTableProviderAdapter;
+
+use crate::error::{BuildDfLogicalPlanSnafu, InvalidRowFilterSnafu, ParseSqlSnafu, Result};
+use crate::statement::StatementExecutor;
+
+/// The string replacing the redacted values.
+const REDACTED: &str = "***";
+
+/// Catalog, schema and name of a table.
+type TableKey = (String, String, String);
+
+/// The function rewriting the scan on a table, returns `None` if the scan is kept as is.
+type ScanRewriter<'a> = dyn Fn(&TableScan, &TableInfo) -> DfResult<Option<DfLogicalPlan>> + 'a;
+
+/// The policies applied to a table when it's read by the current user.
+struct TablePolicy {
+    row_filter: Option<Expr>,
+    column_masks: HashMap<String, MaskingRule>,
+}
+
+impl TablePolicy {
+    /// Filters the rows of the scan, then masks the columns in a projection. The projection is
+    /// aliased to the table name, so the columns can still be referenced by it.
+    fn apply(&self, scan: &TableScan) -> DfResult<DfLogicalPlan> {
+        let mut builder = LogicalPlanBuilder::from(DfLogicalPlan::TableScan(scan.clone()));
+        if let Some(predicate) = &self.row_filter {
+            builder = builder.filter(predicate.clone())?;
+        }
+        if self.column_masks.is_empty() {
+            return builder.build();
+        }
+
+        let exprs = scan
+            .projected_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let column = Expr::Column(field.qualified_column());
+                match self.column_masks.get(field.name()) {
+                    Some(rule) => mask(column, rule, field.data_type()).alias(field.name()),
+                    None => column,
+                }
+            })
+            .collect::<Vec<_>>();
+        builder
+            .project(exprs)?
+            .alias(quoted_table_name(&scan.table_name))?
+            .build()
+    }
+}
+
+fn mask(column: Expr, rule: &MaskingRule, data_type: &DataType) -> Expr {
+    match rule {
+        MaskingRule::Unmasked => column,
+        MaskingRule::Redact if matches!(data_type, DataType::Utf8 | DataType::LargeUtf8) => {
+            lit(REDACTED)
+        }
+        MaskingRule::Redact => Expr::Cast(Cast::new(
+            Box::new(lit(ScalarValue::Null)),
+            data_type.clone(),
+        )),
+        MaskingRule::Hash => md5(Expr::Cast(Cast::new(Box::new(column), DataType::Utf8))),
+    }
+}
+
+impl StatementExecutor {
+    /// Applies the read policies of the current user to the plan. The columns are not masked
+    /// for the scans of `DELETE`, which must see the real values to locate the rows.
+    pub(super) async fn apply_read_policies(
+        &self,
+        plan: LogicalPlan,
+        query_ctx: &QueryContextRef,
+    ) -> Result<LogicalPlan> {
+        let Some(user_provider) = &self.user_provider else { return Ok(plan) };
+        let LogicalPlan::DfPlan(df_plan) = &plan;
+        let is_delete =
+            matches!(df_plan, DfLogicalPlan::Dml(dml) if matches!(dml.op, WriteOp::Delete));
+
+        let user = query_ctx.current_user();
+        let policies = RefCell::new(HashMap::new());
+        let _ = rewrite_scans(df_plan, &|_, table| {
+            let (catalog, schema, name) = (&table.catalog_name, &table.schema_name, &table.name);
+            let row_filter = user_provider.row_filter(&user, catalog, schema, name);
+            let column_masks = if is_delete {
+                HashMap::new()
+            } else {
+                user_provider.column_masks(&user, catalog, schema, name)
+            };
+            if row_filter.is_some() || !column_masks.is_empty() {
+                let _ = policies
+                    .borrow_mut()
+                    .insert(table_key(table), (row_filter, column_masks));
+            }
+            Ok(None)
+        })
+        .context(BuildDfLogicalPlanSnafu)?;
+        let policies = policies.into_inner();
+        if policies.is_empty() {
+            return Ok(plan);
+        }
+
+        let mut table_policies = HashMap::with_capacity(policies.len());
+        for (table, (row_filter, column_masks)) in policies {
+            let row_filter = match row_filter {
+                Some(filter) => Some(self.plan_row_filter(&table, &filter, query_ctx).await?),
+                None => None,
+            };
+            let policy = TablePolicy {
+                row_filter,
+                column_masks,
+            };
+            let _ = table_policies.insert(table, policy);
+        }
+        let rewritten = rewrite_scans(df_plan, &|scan, table| {
+            table_policies
+                .get(&table_key(table))
+                .map(|policy| policy.apply(scan))
+                .transpose()
+        })
+        .context(BuildDfLogicalPlanSnafu)?;
+        Ok(rewritten.map(LogicalPlan::DfPlan).unwrap_or(plan))
+    }
+
+    /// Returns whether the table has any read policy for the current user.
+    pub(super) fn has_read_policy(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        query_ctx: &QueryContextRef,
+    ) -> bool {
+        let Some(user_provider) = &self.user_provider else { return false };
+        let user = query_ctx.current_user();
+        user_provider
+            .row_filter(&user, catalog, schema, table)
+            .is_some()
+            || !user_provider
+                .column_masks(&user, catalog, schema, table)
+                .is_empty()
+    }
+
+    /// Plans the row filter of the table as the `WHERE` clause of a query on it, and returns
+    /// the predicate with the column qualifiers stripped, so it applies to any scan on the table
+    /// no matter how the table is referenced.
+    async fn plan_row_filter(
+        &self,
+        (catalog, schema, table): &TableKey,
+        filter: &str,
+        query_ctx: &QueryContextRef,
+    ) -> Result<Expr> {
+        let sql = format!(
+            "SELECT * FROM {}.{}.{} WHERE {filter}",
+            quote(catalog),
+            quote(schema),
+            quote(table)
+        );
+        let invalid = || InvalidRowFilterSnafu {
+            table: format_full_table_name(catalog, schema, table),
+            filter,
+        };
+
+        let mut stmts =
+            ParserContext::create_with_dialect(&sql, &GenericDialect {}).context(ParseSqlSnafu)?;
+        ensure!(stmts.len() == 1, invalid());
+        let LogicalPlan::DfPlan(plan) = self
+            .plan(QueryStatement::Sql(stmts.remove(0)), query_ctx.clone())
+            .await?;
+
+        // Anything other than a filter under the projection means the filter smuggles other
+        // clauses into the query, like `LIMIT` or `UNION`.
+        let mut plan = &plan;
+        let predicate = loop {
+            match plan {
+                DfLogicalPlan::Projection(projection) => plan = projection.input.as_ref(),
+                DfLogicalPlan::Filter(filter) => break filter.predicate.clone(),
+                _ => return invalid().fail(),
+            }
+        };
+        predicate
+            .transform_up(&|expr| match expr {
+                Expr::Column(column) if column.relation.is_some() => Ok(Transformed::Yes(
+                    Expr::Column(Column::from_name(column.name)),
+                )),
+                expr => Ok(Transformed::No(expr)),
+            })
+            .context(BuildDfLogicalPlanSnafu)
+    }
+}
+
+/// A query engine applying the read policies to the plans before executing them, for the
+/// queries not executed by the [StatementExecutor], like the ones of the scripts. The
+/// policies are the ones of the user of the query context, which is the default user for
+/// the scripts, so the rules for all the users always apply to them.
+pub(crate) struct ReadPolicyQueryEngine {
+    inner: QueryEngineRef,
+    statement_executor: Arc<StatementExecutor>,
+}
+
+impl ReadPolicyQueryEngine {
+    pub(crate) fn new(inner: QueryEngineRef, statement_executor: Arc<StatementExecutor>) -> Self {
+        Self {
+            inner,
+            statement_executor,
+        }
+    }
+}
+
+#[async_trait]
+impl QueryEngine for ReadPolicyQueryEngine {
+    fn planner(&self) -> Arc<dyn LogicalPlanner> {
+        self.inner.planner()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn describe(&self, plan: LogicalPlan) -> query::error::Result<Schema> {
+        let plan = self
+            .statement_executor
+            .apply_read_policies(plan, &QueryContext::arc())
+            .await
+            .map_err(BoxedError::new)
+            .context(QueryPlanSnafu)?;
+        self.inner.describe(plan).await
+    }
+
+    async fn execute(
+        &self,
+        plan: LogicalPlan,
+        query_ctx: QueryContextRef,
+    ) -> query::error::Result<Output> {
+        let plan = self
+            .statement_executor
+            .apply_read_policies(plan, &query_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(QueryExecutionSnafu)?;
+        self.inner.execute(plan, query_ctx).await
+    }
+
+    fn register_udf(&self, udf: ScalarUdf) {
+        self.inner.register_udf(udf)
+    }
+
+    fn register_aggregate_function(&self, func: AggregateFunctionMetaRef) {
+        self.inner.register_aggregate_function(func)
+    }
+
+    fn register_function(&self, func: FunctionRef) {
+        self.inner.register_function(func)
+    }
+}
+
+fn table_key(table: &TableInfo) -> TableKey {
+    (
+        table.catalog_name.clone(),
+        table.schema_name.clone(),
+        table.name.clone(),
+    )
+}
+
+fn quote(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quotes each part of the table name, so it's parsed back to the same table reference.
+fn quoted_table_name(table: &OwnedTableReference) -> String {
+    match table {
+        OwnedTableReference::Bare { table } => quote(table),
+        OwnedTableReference::Partial { schema, table } => {
+            format!("{}.{}", quote(schema), quote(table))
+        }
+        OwnedTableReference::Full {
+            catalog,
+            schema,
+            table,
+        } => format!("{}.{}.{}", quote(catalog), quote(schema), quote(table)),
+    }
+}
+
+/// Rewrites each scan of our tables in the plan by `f`, including the scans in the subqueries
+/// and views. Returns `None` if nothing is changed.
+fn rewrite_scans(plan: &DfLogicalPlan, f: &ScanRewriter) -> DfResult<Option<DfLogicalPlan>> {
+    if let DfLogicalPlan::TableScan(scan) = plan {
+        return rewrite_scan(scan, f);
+    }
+
+    let inputs = plan.inputs();
+    let exprs = plan.expressions();
+    let new_inputs = inputs
+        .iter()
+        .map(|input| rewrite_scans(input, f))
+        .collect::<DfResult<Vec<_>>>()?;
+    let new_exprs = exprs
+        .iter()
+        .map(|expr| rewrite_subqueries(expr, f))
+        .collect::<DfResult<Vec<_>>>()?;
+    if new_inputs.iter().all(Option::is_none) && new_exprs.iter().all(Option::is_none) {
+        return Ok(None);
+    }
+
+    let inputs = new_inputs
+        .into_iter()
+        .zip(inputs)
+        .map(|(new, old)| new.unwrap_or_else(|| old.clone()))
+        .collect::<Vec<_>>();
+    let exprs = new_exprs
+        .into_iter()
+        .zip(exprs)
+        .map(|(new, old)| new.unwrap_or(old))
+        .collect::<Vec<_>>();
+    from_plan(plan, &exprs, &inputs).map(Some)
+}
+
+fn rewrite_scan(scan: &TableScan, f: &ScanRewriter) -> DfResult<Option<DfLogicalPlan>> {
+    // Sources other than providers are never our tables.
+    let Ok(provider) = source_as_provider(&scan.source) else { return Ok(None) };
+
+    if let Some(view) = provider.as_any().downcast_ref::<ViewTable>() {
+        // Views are inlined by the optimizer later, so the scans in their plans must be
+        // rewritten here.
+        let Some(view_plan) = rewrite_scans(view.logical_plan(), f)? else { return Ok(None) };
+        let view = ViewTable::try_new(view_plan, view.definition().cloned())?;
+        return Ok(Some(DfLogicalPlan::TableScan(TableScan {
+            source: provider_as_source(Arc::new(view)),
+            ..scan.clone()
+        })));
+    }
+
+    let Some(adapter) = provider.as_any().downcast_ref::<DfTableProviderAdapter>() else {
+        return Ok(None);
+    };
+    let table_info = adapter.table().table_info();
+    f(scan, table_info.as_ref())
+}
+
+/// Rewrites the plans of the subqueries in the expression by [rewrite_scans].
+fn rewrite_subqueries(expr: &Expr, f: &ScanRewriter) -> DfResult<Option<Expr>> {
+    let rewritten = Cell::new(false);
+    let expr = expr.clone().transform_up(&|expr| {
+        let subquery = match &expr {
+            Expr::ScalarSubquery(subquery)
+            | Expr::Exists { subquery, .. }
+            | Expr::InSubquery { subquery, .. } => subquery,
+            _ => return Ok(Transformed::No(expr)),
+        };
+        let Some(plan) = rewrite_scans(&subquery.subquery, f)? else {
+            return Ok(Transformed::No(expr));
+        };
+        let subquery = Subquery {
+            subquery: Arc::new(plan),
+            ..subquery.clone()
+        };
+        rewritten.set(true);
+
+        let expr = match expr {
+            Expr::ScalarSubquery(_) => Expr::ScalarSubquery(subquery),
+            Expr::Exists { negated, .. } => Expr::Exists { subquery, negated },
+            Expr::InSubquery { expr, negated, .. } => Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            },
+            _ => unreachable!(),
+        };
+        Ok(Transformed::Yes(expr))
+    })?;
+    Ok(rewritten.get().then_some(expr))
+}
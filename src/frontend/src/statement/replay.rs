@@ -0,0 +1,288 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replays the rows of a table in a time range into another table, optionally in another
+//! cluster, and transforms the rows on the way, e.g. to fix the schema of a table after
+//! data is written.
+
+use std::collections::HashMap;
+
+use api::v1::auth_header::AuthScheme;
+use api::v1::{Basic, InsertRequest as GrpcInsertRequest};
+use client::{Client, Database};
+use common_error::prelude::BoxedError;
+use common_query::Output;
+use common_recordbatch::RecordBatch;
+use datanode::instance::sql::table_idents_to_full_name;
+use futures_util::StreamExt;
+use itertools::Itertools;
+use query::parser::QueryStatement;
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::admin::AdminReplay;
+use table::engine::TableReference;
+use table::requests::InsertRequest;
+use table::TableRef;
+
+use crate::error::{
+    self, CollectRecordbatchSnafu, ExternalSnafu, InvalidReplayConnectionSnafu, ParseSqlSnafu,
+    Result, TableSnafu,
+};
+use crate::statement::StatementExecutor;
+use crate::table::insert::to_grpc_columns;
+
+const ENDPOINT: &str = "endpoint";
+const USERNAME: &str = "username";
+const PASSWORD: &str = "password";
+
+/// Where the replayed rows are written to.
+enum ReplayTarget {
+    Local {
+        table: TableRef,
+        catalog_name: String,
+        schema_name: String,
+        table_name: String,
+    },
+    Remote {
+        database: Database,
+        table_name: String,
+    },
+}
+
+impl ReplayTarget {
+    async fn write(&self, batch: RecordBatch) -> Result<usize> {
+        let columns_values = batch
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column| column.name.clone())
+            .zip(batch.columns().iter().cloned())
+            .collect::<HashMap<_, _>>();
+
+        match self {
+            ReplayTarget::Local {
+                table,
+                catalog_name,
+                schema_name,
+                table_name,
+            } => table
+                .insert(InsertRequest {
+                    catalog_name: catalog_name.clone(),
+                    schema_name: schema_name.clone(),
+                    table_name: table_name.clone(),
+                    columns_values,
+                    region_number: 0,
+                })
+                .await
+                .context(TableSnafu),
+            ReplayTarget::Remote {
+                database,
+                table_name,
+            } => {
+                let (columns, row_count) = to_grpc_columns(&columns_values)?;
+                let request = GrpcInsertRequest {
+                    table_name: table_name.clone(),
+                    region_number: 0,
+                    columns,
+                    row_count,
+                };
+                database
+                    .insert(request)
+                    .await
+                    .map(|rows| rows as usize)
+                    .context(error::ReplayRemoteTableSnafu { table_name })
+            }
+        }
+    }
+}
+
+impl StatementExecutor {
+    pub(crate) async fn replay_table(
+        &self,
+        replay: AdminReplay,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog_name, schema_name, table_name) =
+            table_idents_to_full_name(&replay.table_name, query_ctx.clone())
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+        let table = self
+            .get_table(&TableReference::full(
+                &catalog_name,
+                &schema_name,
+                &table_name,
+            ))
+            .await?;
+        let target = self.replay_target(&replay, &query_ctx).await?;
+
+        // The rows are read by a query, so the read policies still apply.
+        let ts_column = table
+            .schema()
+            .timestamp_column()
+            .map(|column| column.name.clone());
+        let sql = replay_query(&replay, ts_column.as_deref());
+        let mut stmts =
+            ParserContext::create_with_dialect(&sql, &GenericDialect {}).context(ParseSqlSnafu)?;
+        let stmt = stmts.remove(0);
+        let output = self.plan_exec(QueryStatement::Sql(stmt), query_ctx).await?;
+        let mut stream = match output {
+            Output::Stream(stream) => stream,
+            Output::RecordBatches(batches) => batches.as_stream(),
+            Output::AffectedRows(_) => unreachable!("query should not yield affected rows"),
+        };
+
+        let mut rows = 0;
+        while let Some(batch) = stream.next().await {
+            let batch = batch.context(CollectRecordbatchSnafu)?;
+            if batch.num_rows() > 0 {
+                rows += target.write(batch).await?;
+            }
+        }
+        Ok(Output::AffectedRows(rows))
+    }
+
+    async fn replay_target(
+        &self,
+        replay: &AdminReplay,
+        query_ctx: &QueryContextRef,
+    ) -> Result<ReplayTarget> {
+        let (catalog_name, schema_name, table_name) =
+            table_idents_to_full_name(&replay.target_table, query_ctx.clone())
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+
+        if replay.connection.is_empty() {
+            let table = self
+                .get_table(&TableReference::full(
+                    &catalog_name,
+                    &schema_name,
+                    &table_name,
+                ))
+                .await?;
+            return Ok(ReplayTarget::Local {
+                table,
+                catalog_name,
+                schema_name,
+                table_name,
+            });
+        }
+
+        let connection = &replay.connection;
+        if let Some(key) = connection
+            .keys()
+            .find(|key| ![ENDPOINT, USERNAME, PASSWORD].contains(&key.as_str()))
+        {
+            return InvalidReplayConnectionSnafu {
+                reason: format!("unknown option {key}"),
+            }
+            .fail();
+        }
+        let endpoint = connection
+            .get(ENDPOINT)
+            .context(InvalidReplayConnectionSnafu {
+                reason: "missing endpoint",
+            })?;
+        ensure!(
+            connection.contains_key(USERNAME) || !connection.contains_key(PASSWORD),
+            InvalidReplayConnectionSnafu {
+                reason: "missing username",
+            }
+        );
+
+        let client = Client::with_urls(vec![endpoint]);
+        let mut database = Database::new(catalog_name, schema_name, client);
+        if let Some(username) = connection.get(USERNAME) {
+            database.set_auth(AuthScheme::Basic(Basic {
+                username: username.clone(),
+                password: connection.get(PASSWORD).cloned().unwrap_or_default(),
+            }));
+        }
+        Ok(ReplayTarget::Remote {
+            database,
+            table_name,
+        })
+    }
+}
+
+/// Builds the query reading the rows to replay.
+fn replay_query(replay: &AdminReplay, ts_column: Option<&str>) -> String {
+    let projection = if replay.transforms.is_empty() {
+        "*".to_string()
+    } else {
+        replay.transforms.iter().join(", ")
+    };
+    let mut sql = format!("SELECT {projection} FROM {}", replay.table_name);
+
+    let quote_ident = |ident: &str| format!("\"{}\"", ident.replace('"', "\"\""));
+    let quote_literal = |literal: &str| format!("'{}'", literal.replace('\'', "''"));
+    let conditions = ts_column
+        .map(|ts_column| {
+            let ts_column = quote_ident(ts_column);
+            replay
+                .start
+                .iter()
+                .map(|start| format!("{ts_column} >= {}", quote_literal(start)))
+                .chain(
+                    replay
+                        .end
+                        .iter()
+                        .map(|end| format!("{ts_column} < {}", quote_literal(end))),
+                )
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use sql::statements::admin::Admin;
+    use sql::statements::statement::Statement;
+
+    use super::*;
+
+    fn parse_replay(sql: &str) -> AdminReplay {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Admin(Admin::Replay(replay)) = stmts.remove(0) else { unreachable!() };
+        replay
+    }
+
+    #[test]
+    fn test_replay_query() {
+        let replay = parse_replay("ADMIN REPLAY TABLE monitor INTO TABLE monitor_v2");
+        assert_eq!("SELECT * FROM monitor", replay_query(&replay, Some("ts")));
+
+        let replay = parse_replay(
+            "ADMIN REPLAY TABLE monitor FROM '2023-01-01 00:00:00' TO '2023-01-02 00:00:00' \
+             INTO TABLE monitor_v2 TRANSFORM (host, CAST(cpu AS DOUBLE) AS cpu, ts)",
+        );
+        assert_eq!(
+            "SELECT host, CAST(cpu AS DOUBLE) AS cpu, ts FROM monitor \
+             WHERE \"ts\" >= '2023-01-01 00:00:00' AND \"ts\" < '2023-01-02 00:00:00'",
+            replay_query(&replay, Some("ts"))
+        );
+
+        let replay = parse_replay("ADMIN REPLAY TABLE monitor TO 'x''y' INTO TABLE monitor_v2");
+        assert_eq!(
+            "SELECT * FROM monitor WHERE \"ts\" < 'x''y'",
+            replay_query(&replay, Some("ts"))
+        );
+    }
+}
@@ -12,17 +12,54 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
+use common_catalog::format_full_table_name;
+use common_error::prelude::BoxedError;
 use common_query::Output;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datafusion::datasource::DefaultTableSource;
+use datafusion_common::Column;
+use datafusion_expr::{
+    count, count_distinct, lit, Expr as DfExpr, LogicalPlan as DfLogicalPlan, LogicalPlanBuilder,
+};
+use datanode::instance::sql::table_idents_to_full_name;
+use datatypes::prelude::{ConcreteDataType, Value};
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{StringVector, UInt64Vector};
+use query::plan::LogicalPlan;
 use session::context::QueryContextRef;
 use snafu::ResultExt;
-use sql::statements::show::{ShowDatabases, ShowTables};
+use sql::statements::show::{ShowCardinality, ShowDatabases, ShowTables};
+use table::engine::TableReference;
+use table::table::adapter::DfTableProviderAdapter;
 
-use crate::error::{ExecuteStatementSnafu, Result};
+use crate::error::{
+    BuildDfLogicalPlanSnafu, CollectRecordbatchSnafu, CreateRecordbatchSnafu,
+    ExecuteStatementSnafu, ExternalSnafu, Result,
+};
 use crate::statement::StatementExecutor;
 
+/// Max number of rows scanned to estimate the cardinality of a table.
+const CARDINALITY_SCAN_ROWS: usize = 1_000_000;
+/// Number of the most frequent values shown for each tag.
+const CARDINALITY_TOP_VALUES: usize = 5;
+/// Name of the row showing the number of series, i.e. distinct combinations of all tags.
+const SERIES_ROW_NAME: &str = "*";
+
 impl StatementExecutor {
-    pub(super) async fn show_databases(&self, stmt: ShowDatabases) -> Result<Output> {
-        query::sql::show_databases(stmt, self.catalog_manager.clone())
+    pub(super) async fn show_catalogs(&self) -> Result<Output> {
+        query::sql::show_catalogs(self.catalog_manager.clone())
+            .await
+            .context(ExecuteStatementSnafu)
+    }
+
+    pub(super) async fn show_databases(
+        &self,
+        stmt: ShowDatabases,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        query::sql::show_databases(stmt, self.catalog_manager.clone(), query_ctx)
             .await
             .context(ExecuteStatementSnafu)
     }
@@ -36,4 +73,151 @@ impl StatementExecutor {
             .await
             .context(ExecuteStatementSnafu)
     }
+
+    /// Estimates the number of series, the number of distinct values of each tag and the
+    /// most frequent values of each tag. Only the first [CARDINALITY_SCAN_ROWS] rows of the
+    /// table are scanned, so the cardinality of larger tables is underestimated.
+    pub(super) async fn show_cardinality(
+        &self,
+        stmt: ShowCardinality,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, table) =
+            table_idents_to_full_name(&stmt.table_name, query_ctx.clone())
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+        let table = self
+            .get_table(&TableReference::full(&catalog, &schema, &table))
+            .await?;
+
+        let table_info = table.table_info();
+        let tags = table_info
+            .meta
+            .row_key_column_names()
+            .cloned()
+            .collect::<Vec<_>>();
+        let projection = if tags.is_empty() {
+            // Scans the time index only to count the rows.
+            table_info
+                .meta
+                .schema
+                .timestamp_index()
+                .map(|index| vec![index])
+        } else {
+            Some(table_info.meta.primary_key_indices.clone())
+        };
+        let source = DefaultTableSource::new(Arc::new(DfTableProviderAdapter::new(table)));
+        let scan = LogicalPlanBuilder::scan(
+            format_full_table_name(&catalog, &schema, &table_info.name),
+            Arc::new(source),
+            projection,
+        )
+        .and_then(|builder| builder.limit(0, Some(CARDINALITY_SCAN_ROWS)))
+        .and_then(|builder| builder.build())
+        .context(BuildDfLogicalPlanSnafu)?;
+
+        let mut aggr_exprs = vec![count(lit(1))];
+        aggr_exprs.extend(tags.iter().map(|tag| count_distinct(tag_column(tag))));
+        let plan = LogicalPlanBuilder::from(scan.clone())
+            .aggregate(Vec::<DfExpr>::new(), aggr_exprs)
+            .and_then(|builder| builder.build())
+            .context(BuildDfLogicalPlanSnafu)?;
+        let counts = self
+            .collect_plan(plan, query_ctx.clone())
+            .await?
+            .iter()
+            .flat_map(|batch| batch.rows())
+            .next()
+            .map(|row| row.into_iter().map(count_value).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let num_rows = counts.first().copied().unwrap_or_default();
+
+        let num_series = if tags.is_empty() {
+            num_rows.min(1)
+        } else {
+            let plan = LogicalPlanBuilder::from(scan.clone())
+                .distinct()
+                .and_then(|builder| builder.aggregate(Vec::<DfExpr>::new(), vec![count(lit(1))]))
+                .and_then(|builder| builder.build())
+                .context(BuildDfLogicalPlanSnafu)?;
+            self.collect_plan(plan, query_ctx.clone())
+                .await?
+                .iter()
+                .flat_map(|batch| batch.rows())
+                .next()
+                .and_then(|row| row.into_iter().next())
+                .map(count_value)
+                .unwrap_or_default()
+        };
+
+        let mut names = vec![SERIES_ROW_NAME.to_string()];
+        let mut distinct_values = vec![num_series];
+        let mut top_values = vec![String::new()];
+        for (i, tag) in tags.iter().enumerate() {
+            let plan = LogicalPlanBuilder::from(scan.clone())
+                .aggregate(vec![tag_column(tag)], vec![count(lit(1)).alias("count")])
+                .and_then(|builder| {
+                    builder.sort(vec![
+                        DfExpr::Column(Column::from_name("count")).sort(false, false),
+                        tag_column(tag).sort(true, true),
+                    ])
+                })
+                .and_then(|builder| builder.limit(0, Some(CARDINALITY_TOP_VALUES)))
+                .and_then(|builder| builder.build())
+                .context(BuildDfLogicalPlanSnafu)?;
+            let values = self
+                .collect_plan(plan, query_ctx.clone())
+                .await?
+                .iter()
+                .flat_map(|batch| batch.rows())
+                .map(|row| format!("{} ({})", row[0], count_value(row[1].clone())))
+                .collect::<Vec<_>>();
+
+            names.push(tag.clone());
+            distinct_values.push(counts.get(i + 1).copied().unwrap_or_default());
+            top_values.push(values.join(", "));
+        }
+
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("Column", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("Distinct", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("Top Values", ConcreteDataType::string_datatype(), false),
+        ]));
+        let columns = vec![
+            Arc::new(StringVector::from(names)) as _,
+            Arc::new(UInt64Vector::from_vec(distinct_values)) as _,
+            Arc::new(StringVector::from(top_values)) as _,
+        ];
+        let records =
+            RecordBatches::try_from_columns(schema, columns).context(CreateRecordbatchSnafu)?;
+        Ok(Output::RecordBatches(records))
+    }
+
+    async fn collect_plan(
+        &self,
+        plan: DfLogicalPlan,
+        query_ctx: QueryContextRef,
+    ) -> Result<Vec<RecordBatch>> {
+        let output = self.exec_plan(LogicalPlan::DfPlan(plan), query_ctx).await?;
+        let batches = match output {
+            Output::Stream(stream) => RecordBatches::try_collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            Output::RecordBatches(batches) => batches,
+            Output::AffectedRows(_) => RecordBatches::empty(),
+        };
+        Ok(batches.take())
+    }
+}
+
+fn tag_column(name: &str) -> DfExpr {
+    DfExpr::Column(Column::from_name(name))
+}
+
+fn count_value(value: Value) -> u64 {
+    match value {
+        Value::Int64(v) => v as u64,
+        Value::UInt64(v) => v,
+        _ => 0,
+    }
 }
@@ -15,11 +15,11 @@
 use common_query::Output;
 use query::parser::{PromQuery, QueryLanguageParser};
 use session::context::QueryContextRef;
-use snafu::ResultExt;
-use sql::statements::tql::Tql;
+use snafu::{OptionExt, ResultExt};
+use sql::statements::tql::{Tql, TqlEval};
 
 use crate::error::{
-    ExecLogicalPlanSnafu, NotSupportedSnafu, ParseQuerySnafu, PlanStatementSnafu, Result,
+    NotSupportedSnafu, ParseQuerySnafu, PlanStatementSnafu, Result, UnboundTqlParameterSnafu,
 };
 use crate::statement::StatementExecutor;
 
@@ -27,6 +27,7 @@ impl StatementExecutor {
     pub(super) async fn execute_tql(&self, tql: Tql, query_ctx: QueryContextRef) -> Result<Output> {
         let plan = match tql {
             Tql::Eval(eval) => {
+                let eval = bind_parameters(eval, &query_ctx)?;
                 let promql = PromQuery {
                     start: eval.start,
                     end: eval.end,
@@ -47,9 +48,202 @@ impl StatementExecutor {
                 .fail()
             }
         };
-        self.query_engine
-            .execute(plan, query_ctx)
-            .await
-            .context(ExecLogicalPlanSnafu)
+        // The plan of TQL reads the tables like other queries, so the read policies of
+        // the user are applied too.
+        self.exec_plan(plan, query_ctx).await
+    }
+}
+
+/// Binds the `$name` parameters of TQL to the variables of the session.
+///
+/// Parameters in the range arguments are replaced by the values as they are. In the
+/// query, values are rendered as PromQL literals, so a parameter can never change the
+/// structure of the query: values in label matchers are always string literals, and
+/// elsewhere only numbers and durations are kept as they are.
+fn bind_parameters(eval: TqlEval, query_ctx: &QueryContextRef) -> Result<TqlEval> {
+    Ok(TqlEval {
+        start: bind_range_argument(eval.start, query_ctx)?,
+        end: bind_range_argument(eval.end, query_ctx)?,
+        step: bind_range_argument(eval.step, query_ctx)?,
+        query: bind_query(&eval.query, query_ctx)?,
+    })
+}
+
+fn bind_range_argument(argument: String, query_ctx: &QueryContextRef) -> Result<String> {
+    match argument.strip_prefix('$') {
+        Some(name) => variable(name, query_ctx),
+        None => Ok(argument),
+    }
+}
+
+fn bind_query(query: &str, query_ctx: &QueryContextRef) -> Result<String> {
+    let mut bound = String::with_capacity(query.len());
+    let mut chars = query.chars().peekable();
+    let mut quote = None;
+    let mut in_matchers = false;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            bound.push(c);
+            if c == '\\' && q != '`' {
+                if let Some(escaped) = chars.next() {
+                    bound.push(escaped);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+                bound.push(c);
+            }
+            '{' | '}' => {
+                in_matchers = c == '{';
+                bound.push(c);
+            }
+            '$' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                if name.is_empty() {
+                    // Not a parameter, leaves it to the PromQL parser.
+                    bound.push(c);
+                    continue;
+                }
+                let value = variable(&name, query_ctx)?;
+                if in_matchers || !(is_number(&value) || is_duration(&value)) {
+                    bound.push_str(&quote_string(&value));
+                } else {
+                    bound.push_str(&value);
+                }
+            }
+            _ => bound.push(c),
+        }
+    }
+    Ok(bound)
+}
+
+fn variable(name: &str, query_ctx: &QueryContextRef) -> Result<String> {
+    query_ctx
+        .variable(name)
+        .context(UnboundTqlParameterSnafu { name })
+}
+
+fn is_number(value: &str) -> bool {
+    value
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+        && value.parse::<f64>().is_ok()
+}
+
+fn is_duration(value: &str) -> bool {
+    const UNITS: [&str; 7] = ["ms", "s", "m", "h", "d", "w", "y"];
+
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return false;
+        }
+        rest = &rest[digits..];
+        let Some(unit) = UNITS.iter().find(|unit| rest.starts_with(*unit)) else {
+            return false;
+        };
+        rest = &rest[unit.len()..];
+    }
+    !value.is_empty()
+}
+
+fn quote_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use session::context::QueryContext;
+
+    use super::*;
+
+    fn query_ctx() -> QueryContextRef {
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_variable("start", "0".to_string());
+        query_ctx.set_variable("end", "100".to_string());
+        query_ctx.set_variable("host", "host1".to_string());
+        query_ctx.set_variable("threshold", "0.5".to_string());
+        query_ctx.set_variable("window", "5m".to_string());
+        query_ctx.set_variable("evil", "a\"} or vector(1) #".to_string());
+        query_ctx
+    }
+
+    #[test]
+    fn test_bind_parameters() {
+        let eval = TqlEval {
+            start: "$start".to_string(),
+            end: "$end".to_string(),
+            step: "1m".to_string(),
+            query: "rate(cpu{host=$host, dc=\"$dc\"}[$window]) > $threshold".to_string(),
+        };
+        let eval = bind_parameters(eval, &query_ctx()).unwrap();
+        assert_eq!("0", eval.start);
+        assert_eq!("100", eval.end);
+        assert_eq!("1m", eval.step);
+        // Parameters in string literals are not bound.
+        assert_eq!(
+            "rate(cpu{host=\"host1\", dc=\"$dc\"}[5m]) > 0.5",
+            eval.query
+        );
+    }
+
+    #[test]
+    fn test_bind_parameters_quoted() {
+        let query_ctx = query_ctx();
+        assert_eq!(
+            "cpu{host=\"a\\\"} or vector(1) #\"}",
+            bind_query("cpu{host=$evil}", &query_ctx).unwrap()
+        );
+        assert_eq!(
+            "label_replace(cpu, \"dst\", \"host1\", \"src\", \"(.*)\")",
+            bind_query(
+                "label_replace(cpu, \"dst\", $host, \"src\", \"(.*)\")",
+                &query_ctx
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "cpu{threshold=\"0.5\"}",
+            bind_query("cpu{threshold=$threshold}", &query_ctx).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bind_unbound_parameter() {
+        let query_ctx = query_ctx();
+        let err = bind_query("cpu{host=$unknown}", &query_ctx).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::Error::UnboundTqlParameter { .. }
+        ));
+
+        let eval = TqlEval {
+            start: "$unknown".to_string(),
+            end: "$end".to_string(),
+            step: "1m".to_string(),
+            query: "cpu".to_string(),
+        };
+        assert!(bind_parameters(eval, &query_ctx).is_err());
+    }
+
+    #[test]
+    fn test_is_duration() {
+        assert!(is_duration("5m"));
+        assert!(is_duration("1h30m"));
+        assert!(is_duration("100ms"));
+        assert!(!is_duration(""));
+        assert!(!is_duration("m"));
+        assert!(!is_duration("5"));
+        assert!(!is_duration("5x"));
     }
 }
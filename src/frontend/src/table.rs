@@ -19,16 +19,19 @@ use std::sync::Arc;
 use api::v1::AlterExpr;
 use async_trait::async_trait;
 use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use catalog::notifier::bump_tables_version;
 use catalog::remote::KvBackendRef;
 use client::Database;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::prelude::BoxedError;
+use common_grpc::flight::ADMIN_ACTION;
 use common_query::error::Result as QueryResult;
-use common_query::logical_plan::Expr;
-use common_query::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use common_query::logical_plan::{DfExpr, Expr};
+use common_query::physical_plan::{PartialAggregate, PhysicalPlan, PhysicalPlanRef};
 use common_query::Output;
 use common_recordbatch::adapter::AsyncRecordBatchStreamAdapter;
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
-use common_telemetry::debug;
+use common_telemetry::{debug, logging};
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_plan::{
     Partitioning, SendableRecordBatchStream as DfSendableRecordBatchStream,
@@ -42,13 +45,14 @@ use snafu::prelude::*;
 use store_api::storage::RegionNumber;
 use table::error::TableOperationSnafu;
 use table::metadata::{FilterPushDownType, TableInfo, TableInfoRef};
-use table::requests::{AlterKind, AlterTableRequest, DeleteRequest, InsertRequest};
+use table::requests::{AdminRequest, AlterKind, AlterTableRequest, DeleteRequest, InsertRequest};
 use table::table::AlterContext;
 use table::{meter_insert_request, Table};
 use tokio::sync::RwLock;
 
 use crate::datanode::DatanodeClients;
 use crate::error::{self, FindDatanodeSnafu, FindTableRouteSnafu, Result};
+use crate::insert_batch::{split_insert_batches, InsertBatchOptions};
 use crate::table::delete::to_grpc_delete_request;
 use crate::table::insert::to_grpc_insert_request;
 use crate::table::scan::{DatanodeInstance, TableScanPlan};
@@ -64,6 +68,7 @@ pub struct DistTable {
     partition_manager: PartitionRuleManagerRef,
     datanode_clients: Arc<DatanodeClients>,
     backend: KvBackendRef,
+    insert_batch_options: InsertBatchOptions,
 }
 
 #[async_trait]
@@ -92,7 +97,11 @@ impl Table for DistTable {
 
         let inserts = splits
             .into_iter()
-            .map(|(region_number, insert)| to_grpc_insert_request(region_number, insert))
+            .flat_map(|(region_number, insert)| {
+                split_insert_batches(insert, &self.insert_batch_options)
+                    .into_iter()
+                    .map(move |batch| to_grpc_insert_request(region_number, batch))
+            })
             .collect::<Result<Vec<_>>>()
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)?;
@@ -112,47 +121,19 @@ impl Table for DistTable {
         filters: &[Expr],
         limit: Option<usize>,
     ) -> table::Result<PhysicalPlanRef> {
-        let partition_rule = self
-            .partition_manager
-            .find_table_partition_rule(&self.table_name)
-            .await
-            .map_err(BoxedError::new)
-            .context(TableOperationSnafu)?;
+        self.scan_partitions(projection, filters, limit, None).await
+    }
 
-        let regions = self
-            .partition_manager
-            .find_regions_by_filters(partition_rule, filters)
-            .map_err(BoxedError::new)
-            .context(TableOperationSnafu)?;
-        let datanodes = self
-            .partition_manager
-            .find_region_datanodes(&self.table_name, regions)
+    /// Samples the table on the datanodes, which scan only a sample of their regions.
+    async fn scan_sample(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        percent: f64,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.scan_partitions(projection, filters, limit, Some(percent))
             .await
-            .map_err(BoxedError::new)
-            .context(TableOperationSnafu)?;
-
-        let table_name = &self.table_name;
-        let mut partition_execs = Vec::with_capacity(datanodes.len());
-        for (datanode, _regions) in datanodes.iter() {
-            let client = self.datanode_clients.get_client(datanode).await;
-            let db = Database::new(&table_name.catalog_name, &table_name.schema_name, client);
-            let datanode_instance = DatanodeInstance::new(Arc::new(self.clone()) as _, db);
-
-            partition_execs.push(Arc::new(PartitionExec {
-                table_name: table_name.clone(),
-                datanode_instance,
-                projection: projection.cloned(),
-                filters: filters.to_vec(),
-                limit,
-                batches: Arc::new(RwLock::new(None)),
-            }));
-        }
-
-        let dist_scan = DistTableScan {
-            schema: project_schema(self.schema(), projection),
-            partition_execs,
-        };
-        Ok(Arc::new(dist_scan))
     }
 
     fn supports_filters_pushdown(
@@ -217,12 +198,82 @@ impl Table for DistTable {
 }
 
 impl DistTable {
+    /// Scans the regions of the table on their datanodes, which only scan a sample of about
+    /// `sample_percent` percent of the regions if it's set.
+    async fn scan_partitions(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        sample_percent: Option<f64>,
+    ) -> table::Result<PhysicalPlanRef> {
+        let partition_rule = self
+            .partition_manager
+            .find_table_partition_rule(&self.table_name)
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+
+        let regions = self
+            .partition_manager
+            .find_regions_by_filters(partition_rule.clone(), filters)
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+        let regions = match self.schema().timestamp_column() {
+            Some(ts_column) => self.partition_manager.prune_regions_by_time_range(
+                &partition_rule,
+                ts_column,
+                filters,
+                regions,
+            ),
+            None => regions,
+        };
+        let datanodes = self
+            .partition_manager
+            .find_region_datanodes(&self.table_name, regions)
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+
+        // The partitions may be fetched by other tasks, which don't inherit the trace id.
+        let trace_id = logging::trace_id();
+        let table_name = &self.table_name;
+        let mut partition_execs = Vec::with_capacity(datanodes.len());
+        for (datanode, _regions) in datanodes.iter() {
+            let client = self.datanode_clients.get_client(datanode).await;
+            let mut db = Database::new(&table_name.catalog_name, &table_name.schema_name, client);
+            if let Some(trace_id) = trace_id {
+                db.set_trace_id(trace_id);
+            }
+            let datanode_instance = DatanodeInstance::new(Arc::new(self.clone()) as _, db);
+
+            partition_execs.push(Arc::new(PartitionExec {
+                table_name: table_name.clone(),
+                datanode_instance,
+                projection: projection.cloned(),
+                filters: filters.to_vec(),
+                order_by: vec![],
+                limit,
+                aggregate: None,
+                sample_percent,
+                batches: Arc::new(RwLock::new(None)),
+            }));
+        }
+
+        let dist_scan = DistTableScan {
+            schema: project_schema(self.schema(), projection),
+            partition_execs,
+        };
+        Ok(Arc::new(dist_scan))
+    }
+
     pub(crate) fn new(
         table_name: TableName,
         table_info: TableInfoRef,
         partition_manager: PartitionRuleManagerRef,
         datanode_clients: Arc<DatanodeClients>,
         backend: KvBackendRef,
+        insert_batch_options: InsertBatchOptions,
     ) -> Self {
         Self {
             table_name,
@@ -230,6 +281,7 @@ impl DistTable {
             partition_manager,
             datanode_clients,
             backend,
+            insert_batch_options,
         }
     }
 
@@ -269,11 +321,16 @@ impl DistTable {
     }
 
     async fn handle_alter(&self, context: AlterContext, request: &AlterTableRequest) -> Result<()> {
-        let alter_expr = context
-            .get::<AlterExpr>()
-            .context(error::ContextValueNotFoundSnafu { key: "AlterExpr" })?;
+        if let AlterKind::SetOptions { .. } = &request.alter_kind {
+            // The options can't be carried by an `AlterExpr`, so the request is sent as is.
+            self.alter_by_admin(request).await?;
+        } else {
+            let alter_expr = context
+                .get::<AlterExpr>()
+                .context(error::ContextValueNotFoundSnafu { key: "AlterExpr" })?;
 
-        self.alter_by_expr(alter_expr).await?;
+            self.alter_by_expr(alter_expr).await?;
+        }
 
         let table_info = self.table_info();
         let table_name = &table_info.name;
@@ -291,32 +348,66 @@ impl DistTable {
         new_info.meta = new_meta;
 
         let key = TableGlobalKey {
-            catalog_name: alter_expr.catalog_name.clone(),
-            schema_name: alter_expr.schema_name.clone(),
-            table_name: alter_expr.table_name.clone(),
+            catalog_name: request.catalog_name.clone(),
+            schema_name: request.schema_name.clone(),
+            table_name: request.table_name.clone(),
         };
         let mut value =
             self.table_global_value(&key)
                 .await?
                 .context(error::TableNotFoundSnafu {
-                    table_name: alter_expr.table_name.clone(),
+                    table_name: request.table_name.clone(),
                 })?;
 
         value.table_info = new_info.into();
 
         if let AlterKind::RenameTable { new_table_name } = &request.alter_kind {
             let new_key = TableGlobalKey {
-                catalog_name: alter_expr.catalog_name.clone(),
-                schema_name: alter_expr.schema_name.clone(),
+                catalog_name: request.catalog_name.clone(),
+                schema_name: request.schema_name.clone(),
                 table_name: new_table_name.clone(),
             };
             self.set_table_global_value(new_key, value).await?;
-            self.delete_table_global_value(key).await
+            self.delete_table_global_value(key).await?;
+            bump_tables_version(&self.backend)
+                .await
+                .context(error::CatalogSnafu)
         } else {
             self.set_table_global_value(key, value).await
         }
     }
 
+    /// Sends the alter request to the datanodes leading the regions of the table as an admin
+    /// action, for the alterations that [`AlterExpr`] can't describe.
+    async fn alter_by_admin(&self, request: &AlterTableRequest) -> Result<()> {
+        let table_routes = self
+            .partition_manager
+            .find_table_route(&self.table_name)
+            .await
+            .with_context(|_| error::FindTableRouteSnafu {
+                table_name: self.table_name.to_string(),
+            })?;
+        let leaders = table_routes.find_leaders();
+        ensure!(
+            !leaders.is_empty(),
+            error::LeaderNotFoundSnafu {
+                table: self.table_name.to_string(),
+            }
+        );
+        let body = serde_json::to_vec(&AdminRequest::AlterTable(request.clone()))
+            .context(error::EncodeJsonSnafu)?;
+        for datanode in leaders {
+            let client = self.datanode_clients.get_client(&datanode).await;
+            let db = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, client);
+            debug!("Sending {:?} to {:?}", request, datanode);
+            let _ = db
+                .do_action(ADMIN_ACTION, body.clone())
+                .await
+                .context(error::RequestDatanodeSnafu)?;
+        }
+        Ok(())
+    }
+
     /// Define a `alter_by_expr` instead of impl [`Table::alter`] to avoid redundant conversion between
     /// [`table::requests::AlterTableRequest`] and [`AlterExpr`].
     async fn alter_by_expr(&self, expr: &AlterExpr) -> Result<()> {
@@ -351,6 +442,18 @@ impl DistTable {
         Ok(())
     }
 
+    /// Returns the number of regions the table is partitioned into.
+    pub(crate) async fn region_count(&self) -> Result<usize> {
+        let partitions = self
+            .partition_manager
+            .find_table_partitions(&self.table_name)
+            .await
+            .with_context(|_| error::FindTablePartitionRuleSnafu {
+                table_name: self.table_name.to_string(),
+            })?;
+        Ok(partitions.len())
+    }
+
     async fn find_datanode_instances(
         &self,
         regions: &[RegionNumber],
@@ -447,6 +550,46 @@ impl PhysicalPlan for DistTableScan {
         let stream = AsyncRecordBatchStreamAdapter::new(self.schema(), stream);
         Ok(Box::pin(stream))
     }
+
+    fn with_top_k(&self, sort_exprs: &[DfExpr], fetch: usize) -> Option<PhysicalPlanRef> {
+        // Rows already limited without ordering can't be replaced by the top-k rows.
+        if self
+            .partition_execs
+            .iter()
+            .any(|exec| exec.limit.is_some() || exec.aggregate.is_some())
+        {
+            return None;
+        }
+
+        let partition_execs = self
+            .partition_execs
+            .iter()
+            .map(|exec| Arc::new(exec.with_top_k(sort_exprs, fetch)))
+            .collect();
+        Some(Arc::new(DistTableScan {
+            schema: self.schema.clone(),
+            partition_execs,
+        }))
+    }
+
+    fn with_partial_aggregate(&self, aggregate: &PartialAggregate) -> Option<PhysicalPlanRef> {
+        // Only the scans outputting all the rows can be aggregated.
+        if self.partition_execs.iter().any(|exec| {
+            exec.limit.is_some() || !exec.order_by.is_empty() || exec.aggregate.is_some()
+        }) {
+            return None;
+        }
+
+        let partition_execs = self
+            .partition_execs
+            .iter()
+            .map(|exec| Arc::new(exec.with_partial_aggregate(aggregate)))
+            .collect();
+        Some(Arc::new(DistTableScan {
+            schema: aggregate.schema.clone(),
+            partition_execs,
+        }))
+    }
 }
 
 #[derive(Debug)]
@@ -455,11 +598,46 @@ struct PartitionExec {
     datanode_instance: DatanodeInstance,
     projection: Option<Vec<usize>>,
     filters: Vec<Expr>,
+    order_by: Vec<DfExpr>,
     limit: Option<usize>,
+    aggregate: Option<PartialAggregate>,
+    sample_percent: Option<f64>,
     batches: Arc<RwLock<Option<RecordBatches>>>,
 }
 
 impl PartitionExec {
+    /// Creates a new [PartitionExec] that only fetches the top `fetch` rows ordered by
+    /// `sort_exprs` from the datanode.
+    fn with_top_k(&self, sort_exprs: &[DfExpr], fetch: usize) -> Self {
+        Self {
+            table_name: self.table_name.clone(),
+            datanode_instance: self.datanode_instance.clone(),
+            projection: self.projection.clone(),
+            filters: self.filters.clone(),
+            order_by: sort_exprs.to_vec(),
+            limit: Some(fetch),
+            aggregate: None,
+            sample_percent: self.sample_percent,
+            batches: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Creates a new [PartitionExec] that fetches the partial states of the `aggregate`
+    /// computed by the datanode.
+    fn with_partial_aggregate(&self, aggregate: &PartialAggregate) -> Self {
+        Self {
+            table_name: self.table_name.clone(),
+            datanode_instance: self.datanode_instance.clone(),
+            projection: self.projection.clone(),
+            filters: self.filters.clone(),
+            order_by: vec![],
+            limit: None,
+            aggregate: Some(aggregate.clone()),
+            sample_percent: self.sample_percent,
+            batches: Arc::new(RwLock::new(None)),
+        }
+    }
+
     async fn maybe_init(&self) -> Result<()> {
         if self.batches.read().await.is_some() {
             return Ok(());
@@ -474,7 +652,10 @@ impl PartitionExec {
             table_name: self.table_name.clone(),
             projection: self.projection.clone(),
             filters: self.filters.clone(),
+            order_by: self.order_by.clone(),
             limit: self.limit,
+            aggregate: self.aggregate.clone(),
+            sample_percent: self.sample_percent,
         };
         let result = self.datanode_instance.grpc_table_scan(plan).await?;
         let _ = batches.insert(result);
@@ -503,17 +684,21 @@ mod test {
     use common_query::physical_plan::DfPhysicalPlanAdapter;
     use common_query::DfPhysicalPlan;
     use common_recordbatch::adapter::RecordBatchStreamAdapter;
+    use common_time::Timestamp;
     use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
     use datafusion::physical_plan::expressions::{col as physical_col, PhysicalSortExpr};
     use datafusion::physical_plan::sorts::sort::SortExec;
     use datafusion::prelude::SessionContext;
     use datafusion::sql::sqlparser;
-    use datafusion_expr::expr_fn::{and, binary_expr, col, or};
-    use datafusion_expr::{lit, Operator};
+    use datafusion_common::ScalarValue;
+    use datafusion_expr::expr_fn::{and, binary_expr, cast, col, or};
+    use datafusion_expr::{count, lit, max, Operator};
     use datanode::instance::Instance;
     use datatypes::arrow::compute::SortOptions;
+    use datatypes::arrow::datatypes::{DataType, TimeUnit};
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::value::Value;
     use itertools::Itertools;
     use meta_client::client::MetaClient;
     use meta_client::rpc::router::RegionRoute;
@@ -867,6 +1052,99 @@ mod test {
         exec_table_scan(table.clone(), projection, filters, 4, expected_output).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dist_table_scan_top_k() {
+        common_telemetry::init_default_ut_logging();
+        let table = Arc::new(new_dist_table("test_dist_table_scan_top_k").await);
+        // select a, row_id from numbers order by a desc limit 2
+        let projection = Some(vec![1, 2]);
+        let table_scan = table.scan(projection.as_ref(), &[], None).await.unwrap();
+        let sort_exprs = vec![col("a").sort(false, false)];
+        let top_k = table_scan.with_top_k(&sort_exprs, 2).unwrap();
+        assert_eq!(4, top_k.output_partitioning().partition_count());
+
+        let session_ctx = SessionContext::new();
+        for partition in 0..4 {
+            let stream = top_k.execute(partition, session_ctx.task_ctx()).unwrap();
+            let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+            let rows = recordbatches.iter().map(|x| x.num_rows()).sum::<usize>();
+            assert_eq!(2, rows);
+        }
+
+        let merge = CoalescePartitionsExec::new(Arc::new(DfPhysicalPlanAdapter(top_k.clone())));
+        let sort = SortExec::new(
+            vec![PhysicalSortExpr {
+                expr: physical_col("a", top_k.schema().arrow_schema()).unwrap(),
+                options: SortOptions {
+                    descending: true,
+                    nulls_first: false,
+                },
+            }],
+            Arc::new(merge),
+        )
+        .with_fetch(Some(2));
+        let stream = sort.execute(0, session_ctx.task_ctx()).unwrap();
+        let stream = Box::pin(RecordBatchStreamAdapter::try_new(stream).unwrap());
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected_output = vec![
+            "+-----+--------+",
+            "| a   | row_id |",
+            "+-----+--------+",
+            "| 104 | 5      |",
+            "| 103 | 4      |",
+            "+-----+--------+",
+        ]
+        .into_iter()
+        .join("\n");
+        assert_eq!(recordbatches.pretty_print().unwrap(), expected_output);
+
+        // Scans already limited are not ordered.
+        let table_scan = table.scan(projection.as_ref(), &[], Some(1)).await.unwrap();
+        assert!(table_scan.with_top_k(&sort_exprs, 2).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dist_table_scan_partial_aggregate() {
+        common_telemetry::init_default_ut_logging();
+        let table = Arc::new(new_dist_table("test_dist_table_scan_partial_aggregate").await);
+        // select count(a), max(a) from numbers
+        let projection = Some(vec![1, 2]);
+        let table_scan = table.scan(projection.as_ref(), &[], None).await.unwrap();
+        let aggregate = PartialAggregate {
+            group_exprs: vec![],
+            aggr_exprs: vec![count(col("a")), max(col("a"))],
+            schema: Arc::new(Schema::new(vec![
+                ColumnSchema::new("COUNT(a)[count]", ConcreteDataType::int64_datatype(), true),
+                ColumnSchema::new("MAX(a)[max]", ConcreteDataType::int32_datatype(), true),
+            ])),
+        };
+        let plan = table_scan.with_partial_aggregate(&aggregate).unwrap();
+        assert_eq!(aggregate.schema, plan.schema());
+
+        // Each datanode outputs the partial states of its own region.
+        let session_ctx = SessionContext::new();
+        let mut states = Vec::new();
+        for partition in 0..plan.output_partitioning().partition_count() {
+            let stream = plan.execute(partition, session_ctx.task_ctx()).unwrap();
+            let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+            for batch in recordbatches.iter() {
+                for row in 0..batch.num_rows() {
+                    states.push((batch.column(0).get(row), batch.column(1).get(row)));
+                }
+            }
+        }
+        states.sort();
+        let expected = [4, 14, 34, 104]
+            .into_iter()
+            .map(|max| (Value::Int64(5), Value::Int32(max)))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, states);
+
+        // Scans already limited can't be aggregated.
+        let table_scan = table.scan(projection.as_ref(), &[], Some(1)).await.unwrap();
+        assert!(table_scan.with_partial_aggregate(&aggregate).is_none());
+    }
+
     async fn exec_table_scan(
         table: TableRef,
         projection: Option<Vec<usize>>,
@@ -1007,6 +1285,7 @@ mod test {
             partition_manager,
             datanode_clients,
             backend: catalog_manager.backend(),
+            insert_batch_options: InsertBatchOptions::default(),
         }
     }
 
@@ -1181,6 +1460,103 @@ mod test {
         ));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prune_regions_by_time_range() {
+        let partition_manager = Arc::new(PartitionRuleManager::new(Arc::new(TableRoutes::new(
+            Arc::new(MetaClient::default()),
+        ))));
+
+        // PARTITION BY RANGE (ts) (
+        //   PARTITION r1 VALUES LESS THAN ('1970-01-01 00:00:10'),
+        //   PARTITION r2 VALUES LESS THAN ('1970-01-01 00:00:20'),
+        //   PARTITION r3 VALUES LESS THAN ('1970-01-01 00:00:30'),
+        //   PARTITION r4 VALUES LESS THAN (MAXVALUE),
+        // )
+        let partition_rule: PartitionRuleRef = Arc::new(RangePartitionRule::new(
+            "ts",
+            vec![
+                Value::Timestamp(Timestamp::new_millisecond(10_000)),
+                Value::Timestamp(Timestamp::new_millisecond(20_000)),
+                Value::Timestamp(Timestamp::new_millisecond(30_000)),
+            ],
+            vec![0_u32, 1, 2, 3],
+        )) as _;
+
+        let ts_column = ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        );
+        let ts_lit = |millis: i64| lit(ScalarValue::TimestampMillisecond(Some(millis), None));
+        let test = |filters: Vec<Expr>, expect_regions: Vec<RegionNumber>| {
+            let regions = partition_manager
+                .find_regions_by_filters(partition_rule.clone(), &filters)
+                .unwrap();
+            let mut regions = partition_manager.prune_regions_by_time_range(
+                &partition_rule,
+                &ts_column,
+                &filters,
+                regions,
+            );
+            regions.sort();
+            assert_eq!(regions, expect_regions);
+        };
+
+        // Queries on recent data only fan out to the latest regions.
+        test(
+            vec![binary_expr(col("ts"), Operator::GtEq, ts_lit(25_000)).into()], // ts >= 25s
+            vec![2, 3],
+        );
+        test(
+            vec![binary_expr(
+                cast(col("ts"), DataType::Timestamp(TimeUnit::Nanosecond, None)),
+                Operator::Gt,
+                lit(ScalarValue::TimestampNanosecond(Some(35_000_000_000), None)),
+            )
+            .into()], // CAST(ts AS TIMESTAMP(9)) > 35s
+            vec![3],
+        );
+        test(
+            vec![col("ts").between(ts_lit(12_000), ts_lit(18_000)).into()], // ts BETWEEN 12s AND 18s
+            vec![1],
+        );
+        test(
+            vec![col("ts")
+                .in_list(vec![ts_lit(1_000), ts_lit(21_000)], false)
+                .into()], // ts IN (1s, 21s)
+            vec![0, 1, 2],
+        );
+
+        // Filters not on the time index do not prune.
+        test(
+            vec![binary_expr(col("a"), Operator::Gt, lit(1)).into()], // a > 1
+            vec![0, 1, 2, 3],
+        );
+
+        // A time range out of all regions still keeps one region to scan.
+        let regions = partition_manager.prune_regions_by_time_range(
+            &partition_rule,
+            &ts_column,
+            &[col("ts").in_list(vec![], false).into()], // ts IN ()
+            vec![0, 1, 2, 3],
+        );
+        assert_eq!(regions, vec![0]);
+
+        // Tables not partitioned by the time index are not pruned.
+        let ts2_column = ColumnSchema::new(
+            "ts2",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        );
+        let regions = partition_manager.prune_regions_by_time_range(
+            &partition_rule,
+            &ts2_column,
+            &[binary_expr(col("ts2"), Operator::GtEq, ts_lit(25_000)).into()],
+            vec![0, 1, 2, 3],
+        );
+        assert_eq!(regions, vec![0, 1, 2, 3]);
+    }
+
     #[derive(Default)]
     struct MockCollector {
         pub write_sum: AtomicU32,
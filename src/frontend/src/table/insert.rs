@@ -18,36 +18,91 @@ use api::helper::{push_vals, ColumnDataTypeWrapper};
 use api::v1::column::SemanticType;
 use api::v1::{Column, InsertRequest as GrpcInsertRequest};
 use common_query::Output;
+use common_telemetry::error;
 use datatypes::prelude::{ConcreteDataType, VectorRef};
-use futures::future;
+use futures::{stream, StreamExt};
 use snafu::{ensure, ResultExt};
 use store_api::storage::RegionNumber;
 use table::requests::InsertRequest;
 
 use super::DistTable;
 use crate::error;
-use crate::error::{JoinTaskSnafu, RequestDatanodeSnafu, Result};
+use crate::error::{InsertBatchesSnafu, JoinTaskSnafu, RequestDatanodeSnafu, Result};
 
 impl DistTable {
     pub async fn dist_insert(&self, inserts: Vec<GrpcInsertRequest>) -> Result<Output> {
         let regions = inserts.iter().map(|x| x.region_number).collect::<Vec<_>>();
         let instances = self.find_datanode_instances(&regions).await?;
 
-        let results = future::try_join_all(instances.into_iter().zip(inserts.into_iter()).map(
-            |(instance, request)| {
-                common_runtime::spawn_write(async move {
-                    instance
-                        .grpc_insert(request)
-                        .await
-                        .context(RequestDatanodeSnafu)
-                })
-            },
-        ))
-        .await
-        .context(JoinTaskSnafu)?;
-
-        let affected_rows = results.into_iter().sum::<Result<u32>>()?;
-        Ok(Output::AffectedRows(affected_rows as _))
+        let total = inserts.len();
+        // Batches of the same region are sent sequentially, so the rows of a later batch
+        // overwrite those of an earlier batch with the same key, as in a single insert.
+        let mut region_indexes = HashMap::new();
+        let mut region_batches: Vec<Vec<_>> = Vec::new();
+        for (instance, request) in instances.into_iter().zip(inserts.into_iter()) {
+            let index = *region_indexes
+                .entry(request.region_number)
+                .or_insert_with(|| {
+                    region_batches.push(Vec::new());
+                    region_batches.len() - 1
+                });
+            region_batches[index].push((instance, request));
+        }
+
+        let results = stream::iter(region_batches.into_iter().map(|batches| {
+            common_runtime::spawn_write(async move {
+                let mut results = Vec::with_capacity(batches.len());
+                for (instance, request) in batches {
+                    results.push(
+                        instance
+                            .grpc_insert(request)
+                            .await
+                            .context(RequestDatanodeSnafu),
+                    );
+                }
+                results
+            })
+        }))
+        .buffer_unordered(self.insert_batch_options.parallelism.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+        // Wait for all the batches rather than failing fast, so the caller knows how many
+        // rows were actually inserted when some of the batches fail.
+        let mut affected_rows = 0;
+        let mut errors = vec![];
+        for result in results {
+            let results = match result.context(JoinTaskSnafu) {
+                Ok(results) => results,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            for result in results {
+                match result {
+                    Ok(rows) => affected_rows += rows as usize,
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+
+        let failed = errors.len();
+        let mut errors = errors.into_iter();
+        let Some(first_error) = errors.next() else {
+            return Ok(Output::AffectedRows(affected_rows));
+        };
+        if total == 1 {
+            return Err(first_error);
+        }
+        for e in errors {
+            error!(e; table = %self.table_name, "Failed to insert a batch");
+        }
+        Err(Box::new(first_error)).context(InsertBatchesSnafu {
+            failed,
+            total,
+            affected_rows,
+        })
     }
 }
 
@@ -17,11 +17,13 @@ use std::sync::Arc;
 
 use api::v1::{DeleteRequest, InsertRequest};
 use client::Database;
+use common_query::logical_plan::DfExpr;
+use common_query::physical_plan::PartialAggregate;
 use common_query::prelude::Expr;
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use datafusion::datasource::DefaultTableSource;
-use datafusion_expr::{LogicalPlan, LogicalPlanBuilder};
+use datafusion_expr::{cast, LogicalPlan, LogicalPlanBuilder};
 use meta_client::rpc::TableName;
 use snafu::ResultExt;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
@@ -72,7 +74,10 @@ impl DatanodeInstance {
     }
 
     fn build_logical_plan(&self, table_scan: &TableScanPlan) -> Result<LogicalPlan> {
-        let table_provider = Arc::new(DfTableProviderAdapter::new(self.table.clone()));
+        let table_provider = Arc::new(match table_scan.sample_percent {
+            Some(percent) => DfTableProviderAdapter::with_sample(self.table.clone(), percent),
+            None => DfTableProviderAdapter::new(self.table.clone()),
+        });
 
         let mut builder = LogicalPlanBuilder::scan_with_filters(
             table_scan.table_name.to_string(),
@@ -98,6 +103,37 @@ impl DatanodeInstance {
                 .context(error::BuildDfLogicalPlanSnafu)?;
         }
 
+        if let Some(aggregate) = &table_scan.aggregate {
+            builder = builder
+                .aggregate(aggregate.group_exprs.clone(), aggregate.aggr_exprs.clone())
+                .context(error::BuildDfLogicalPlanSnafu)?;
+
+            // Cast the results to the types of the partial states, which may differ from
+            // the types returned by the aggregate functions.
+            let exprs = builder
+                .schema()
+                .fields()
+                .iter()
+                .zip(aggregate.schema.column_schemas())
+                .map(|(field, column_schema)| {
+                    cast(
+                        DfExpr::Column(field.qualified_column()),
+                        column_schema.data_type.as_arrow_type(),
+                    )
+                    .alias(&column_schema.name)
+                })
+                .collect::<Vec<_>>();
+            builder = builder
+                .project(exprs)
+                .context(error::BuildDfLogicalPlanSnafu)?;
+        }
+
+        if !table_scan.order_by.is_empty() {
+            builder = builder
+                .sort(table_scan.order_by.clone())
+                .context(error::BuildDfLogicalPlanSnafu)?;
+        }
+
         if table_scan.limit.is_some() {
             builder = builder
                 .limit(0, table_scan.limit)
@@ -113,5 +149,8 @@ pub(crate) struct TableScanPlan {
     pub table_name: TableName,
     pub projection: Option<Vec<usize>>,
     pub filters: Vec<Expr>,
+    pub order_by: Vec<DfExpr>,
     pub limit: Option<usize>,
+    pub aggregate: Option<PartialAggregate>,
+    pub sample_percent: Option<f64>,
 }
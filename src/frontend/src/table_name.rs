@@ -0,0 +1,199 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Normalization of the metric names from the ingestion protocols, like Prometheus, InfluxDB
+//! and OpenTSDB, into table names.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use promql_parser::label::{MatchOp, METRIC_NAME};
+use promql_parser::parser::{
+    AggregateExpr, BinaryExpr, Call, Expr, MatrixSelector, ParenExpr, SubqueryExpr, UnaryExpr,
+    VectorSelector,
+};
+use serde::{Deserialize, Serialize};
+
+/// How to turn metric names into table names. Only ASCII letters, digits and `_` are kept
+/// as they are, and a name starting with a digit is prefixed with `_`.
+///
+/// The tables created for the renamed metrics keep the metric names in their
+/// [`metric_name`](table::requests::METRIC_NAME_KEY) option. As a normalized name may also
+/// be the name of another metric, e.g. both `a.b` and `a_b` are replaced with `a_b`, a
+/// metric is only written to or queried from a table holding the same metric, the others
+/// are rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableNameNormalization {
+    /// Uses metric names as table names as they are.
+    #[default]
+    None,
+    /// Replaces each disallowed character with `_`, e.g. `http.requests` becomes
+    /// `http_requests`.
+    Replace,
+    /// Escapes each disallowed character with its hex code, e.g. `http.requests` becomes
+    /// `http_x2e_requests`. Unlike `Replace`, different metric names only collide with
+    /// metric names looking like escaped ones.
+    Escape,
+}
+
+fn is_allowed(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+impl TableNameNormalization {
+    /// Returns the table name of the metric `name`.
+    pub fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if *self == TableNameNormalization::None
+            || name.is_empty()
+            || (name.chars().all(is_allowed) && !name.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            return Cow::Borrowed(name);
+        }
+
+        let mut normalized = String::with_capacity(name.len() + 1);
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            normalized.push('_');
+        }
+        for c in name.chars() {
+            if is_allowed(c) {
+                normalized.push(c);
+            } else if *self == TableNameNormalization::Replace {
+                normalized.push('_');
+            } else {
+                // Writing to a `String` never fails.
+                let _ = write!(normalized, "_x{:x}_", c as u32);
+            }
+        }
+        Cow::Owned(normalized)
+    }
+
+    /// Normalizes the metric names of all selectors in the PromQL expression, so they
+    /// match the tables the metrics are written to. Returns the metric names before
+    /// normalization, empty if metric names are not normalized.
+    pub fn normalize_promql(&self, expr: &mut Expr) -> HashSet<String> {
+        let mut metric_names = HashSet::new();
+        if *self != TableNameNormalization::None {
+            self.normalize_expr(expr, &mut metric_names);
+        }
+        metric_names
+    }
+
+    fn normalize_expr(&self, expr: &mut Expr, metric_names: &mut HashSet<String>) {
+        match expr {
+            Expr::Aggregate(AggregateExpr { expr, param, .. }) => {
+                self.normalize_expr(expr, metric_names);
+                if let Some(param) = param {
+                    self.normalize_expr(param, metric_names);
+                }
+            }
+            Expr::Unary(UnaryExpr { expr })
+            | Expr::Paren(ParenExpr { expr })
+            | Expr::Subquery(SubqueryExpr { expr, .. }) => self.normalize_expr(expr, metric_names),
+            Expr::Binary(BinaryExpr { lhs, rhs, .. }) => {
+                self.normalize_expr(lhs, metric_names);
+                self.normalize_expr(rhs, metric_names);
+            }
+            Expr::VectorSelector(selector)
+            | Expr::MatrixSelector(MatrixSelector {
+                vector_selector: selector,
+                ..
+            }) => self.normalize_selector(selector, metric_names),
+            Expr::Call(Call { args, .. }) => {
+                for arg in &mut args.args {
+                    self.normalize_expr(arg, metric_names);
+                }
+            }
+            Expr::NumberLiteral(_) | Expr::StringLiteral(_) | Expr::Extension(_) => {}
+        }
+    }
+
+    fn normalize_selector(
+        &self,
+        selector: &mut VectorSelector,
+        metric_names: &mut HashSet<String>,
+    ) {
+        if let Some(name) = &mut selector.name {
+            let normalized = self.normalize(name).into_owned();
+            let _ = metric_names.insert(std::mem::replace(name, normalized));
+        }
+        selector.matchers.matchers = std::mem::take(&mut selector.matchers.matchers)
+            .into_iter()
+            .map(|mut matcher| {
+                if matcher.name == METRIC_NAME && matches!(matcher.op, MatchOp::Equal) {
+                    let normalized = self.normalize(&matcher.value).into_owned();
+                    let _ = metric_names.insert(std::mem::replace(&mut matcher.value, normalized));
+                }
+                matcher
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use promql_parser::parser;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let none = TableNameNormalization::None;
+        let replace = TableNameNormalization::Replace;
+        let escape = TableNameNormalization::Escape;
+
+        for normalization in [none, replace, escape] {
+            assert!(matches!(
+                normalization.normalize("http_requests_total"),
+                Cow::Borrowed("http_requests_total")
+            ));
+        }
+        assert_eq!("sys.cpu:user", none.normalize("sys.cpu:user"));
+        assert_eq!("sys_cpu_user", replace.normalize("sys.cpu:user"));
+        assert_eq!("sys_x2e_cpu_x3a_user", escape.normalize("sys.cpu:user"));
+        assert_eq!("_5xx_errors", replace.normalize("5xx_errors"));
+        assert_eq!("_5xx_x2d_errors", escape.normalize("5xx-errors"));
+        assert_eq!("temp__c", replace.normalize("temp_°c"));
+        assert_eq!("temp__xb0_c", escape.normalize("temp_°c"));
+    }
+
+    #[test]
+    fn test_normalize_promql() {
+        let mut expr =
+            parser::parse(r#"sum(rate({__name__="http.requests", job="api"}[5m])) / up"#).unwrap();
+        let mut metric_names = TableNameNormalization::Replace
+            .normalize_promql(&mut expr)
+            .into_iter()
+            .collect::<Vec<_>>();
+        metric_names.sort();
+        assert_eq!(vec!["http.requests", "up"], metric_names);
+
+        let Expr::Binary(BinaryExpr { lhs, rhs, .. }) = &expr else { unreachable!() };
+        let Expr::Aggregate(AggregateExpr { expr: rate, .. }) = lhs.as_ref() else { unreachable!() };
+        let Expr::Call(Call { args, .. }) = rate.as_ref() else { unreachable!() };
+        let Expr::MatrixSelector(MatrixSelector { vector_selector, .. }) = args.args[0].as_ref() else { unreachable!() };
+        let metric_names = vector_selector
+            .matchers
+            .matchers
+            .iter()
+            .filter(|matcher| matcher.name == METRIC_NAME)
+            .map(|matcher| matcher.value.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["http_requests"], metric_names);
+
+        let Expr::VectorSelector(selector) = rhs.as_ref() else { unreachable!() };
+        assert_eq!(Some("up"), selector.name.as_deref());
+    }
+}
@@ -14,12 +14,15 @@
 
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
-use common_recordbatch::util;
+use common_recordbatch::{util, RecordBatch, RecordBatchStream, RecordBatches};
 use common_telemetry::logging;
 use datatypes::vectors::{Int64Vector, StringVector, UInt64Vector, VectorRef};
+use futures::StreamExt;
 use rstest::rstest;
 use rstest_reuse::apply;
 use servers::query_handler::sql::SqlQueryHandler;
@@ -138,6 +141,44 @@ async fn test_show_create_table(instance: Arc<dyn MockInstance>) {
     check_output_stream(output, expected).await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_show_cardinality(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    execute_sql(
+        &instance,
+        r#"create table demo(
+             host STRING,
+             dc STRING,
+             cpu DOUBLE,
+             ts TIMESTAMP TIME INDEX,
+             PRIMARY KEY(host, dc)
+)"#,
+    )
+    .await;
+    execute_sql(
+        &instance,
+        r#"insert into demo(host, dc, cpu, ts) values
+            ('host1', 'dc1', 1.0, 1000),
+            ('host1', 'dc1', 2.0, 2000),
+            ('host2', 'dc1', 3.0, 1000),
+            ('host3', 'dc2', 4.0, 1000)
+        "#,
+    )
+    .await;
+
+    let output = execute_sql(&instance, "show cardinality for table demo").await;
+    let expected = "\
++--------+----------+---------------------------------+
+| Column | Distinct | Top Values                      |
++--------+----------+---------------------------------+
+| *      | 3        |                                 |
+| host   | 3        | host1 (2), host2 (1), host3 (1) |
+| dc     | 2        | dc1 (3), dc2 (1)                |
++--------+----------+---------------------------------+";
+    check_output_stream(output, expected).await;
+}
+
 #[apply(both_instances_cases)]
 async fn test_issue477_same_table_name_in_different_databases(instance: Arc<dyn MockInstance>) {
     let instance = instance.frontend();
@@ -309,6 +350,178 @@ async fn test_execute_insert_by_select(instance: Arc<dyn MockInstance>) {
     check_output_stream(output, expected).await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_execute_clone_table(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, memory double, ts timestamp time index);",
+    )
+    .await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 66.6, 1024, 1655276557000),
+                           ('host2', 88.8,  333.3, 1655276558000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    match try_execute_sql(&instance, "create table demo_clone clone demo").await {
+        Ok(_) => {
+            let output = execute_sql(&instance, "select * from demo_clone order by ts").await;
+            let expected = "\
++-------+------+--------+---------------------+
+| host  | cpu  | memory | ts                  |
++-------+------+--------+---------------------+
+| host1 | 66.6 | 1024.0 | 2022-06-15T07:02:37 |
+| host2 | 88.8 | 333.3  | 2022-06-15T07:02:38 |
++-------+------+--------+---------------------+";
+            check_output_stream(output, expected).await;
+        }
+        // The region of the clone may be placed on another datanode in distributed mode,
+        // then the clone is dropped.
+        Err(e) => {
+            assert_eq!(StatusCode::Unsupported, e.status_code(), "{e}");
+            assert!(try_execute_sql(&instance, "select * from demo_clone")
+                .await
+                .is_err());
+        }
+    }
+}
+
+#[apply(both_instances_cases)]
+async fn test_execute_admin_purge(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp time index) with(ttl='1d')",
+    )
+    .await;
+    let output = execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host1', 66.6, 1655276557000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    execute_sql(&instance, "admin flush table demo").await;
+
+    let output = execute_sql(&instance, "admin purge table demo dry run").await;
+    let Output::RecordBatches(records) = output else { unreachable!() };
+    assert_eq!(
+        1,
+        records.iter().map(|batch| batch.num_rows()).sum::<usize>()
+    );
+    assert!(
+        try_execute_sql(&instance, "select * from greptime.public.retention_audit")
+            .await
+            .is_err()
+    );
+
+    execute_sql(&instance, "admin purge table demo").await;
+    let output = execute_sql(
+        &instance,
+        "select table_name, region_number, num_files from greptime.public.retention_audit",
+    )
+    .await;
+    let expected = "\
++------------+---------------+-----------+
+| table_name | region_number | num_files |
++------------+---------------+-----------+
+| demo       | 0             | 1         |
++------------+---------------+-----------+";
+    check_output_stream(output, expected).await;
+
+    assert!(
+        try_execute_sql(&instance, "admin purge table demo region 9")
+            .await
+            .is_err()
+    );
+}
+
+#[apply(both_instances_cases)]
+async fn test_execute_admin_drop_range(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp time index)",
+    )
+    .await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, ts) values
+                           ('host1', 66.6, 1655276557000),
+                           ('host2', 88.8, 1655276558000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+    execute_sql(&instance, "admin flush table demo").await;
+
+    let output = execute_sql(
+        &instance,
+        "admin drop range table demo from '2022-06-15 07:00:00+0000' to '2022-06-15 08:00:00+0000'",
+    )
+    .await;
+    let Output::RecordBatches(records) = output else { unreachable!() };
+    assert_eq!(
+        1,
+        records.iter().map(|batch| batch.num_rows()).sum::<usize>()
+    );
+    let output = execute_sql(&instance, "select count(*) from demo").await;
+    let expected = "\
++-----------------+
+| COUNT(UInt8(1)) |
++-----------------+
+| 0               |
++-----------------+";
+    check_output_stream(output, expected).await;
+
+    assert!(try_execute_sql(
+        &instance,
+        "admin drop range table demo region 9 from '2022-06-15 07:00:00+0000' to '2022-06-15 08:00:00+0000'",
+    )
+    .await
+    .is_err());
+}
+
+#[apply(both_instances_cases)]
+async fn test_execute_admin_scrub(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp time index)",
+    )
+    .await;
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host1', 66.6, 1655276557000)",
+    )
+    .await;
+    execute_sql(&instance, "admin flush table demo").await;
+
+    let output = execute_sql(&instance, "admin scrub table demo").await;
+    let Output::RecordBatches(records) = output else { unreachable!() };
+    let batches = records.take();
+    assert_eq!(
+        1,
+        batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+    );
+    let status = batches[0].column_by_name("status").unwrap().get(0);
+    assert_eq!(datatypes::value::Value::from("ok"), status);
+
+    assert!(
+        try_execute_sql(&instance, "admin scrub table demo region 9")
+            .await
+            .is_err()
+    );
+}
+
 #[apply(both_instances_cases)]
 async fn test_execute_insert_query_with_i64_timestamp(instance: Arc<dyn MockInstance>) {
     let instance = instance.frontend();
@@ -950,6 +1163,139 @@ async fn test_alter_table(instance: Arc<dyn MockInstance>) {
     check_output_stream(output, expected).await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_repeated_query_with_plan_cache(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp time index)",
+    )
+    .await;
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host1', 1.1, 1000)",
+    )
+    .await;
+
+    let output = execute_sql(&instance, "select host, cpu from demo order by ts").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.1 |
++-------+-----+";
+    check_output_stream(output, expected).await;
+
+    // The cached plan still reads the latest data.
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host2', 2.2, 2000)",
+    )
+    .await;
+    let output = execute_sql(&instance, "  select host, cpu\n from demo order by ts;").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.1 |
+| host2 | 2.2 |
++-------+-----+";
+    check_output_stream(output, expected).await;
+
+    // The cached plan is invalidated after the table is recreated.
+    execute_sql(&instance, "drop table demo").await;
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu bigint, ts timestamp time index)",
+    )
+    .await;
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host3', 3, 3000)",
+    )
+    .await;
+    let output = execute_sql(&instance, "select host, cpu from demo order by ts").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host3 | 3   |
++-------+-----+";
+    check_output_stream(output, expected).await;
+}
+
+#[apply(both_instances_cases)]
+async fn test_session_functions(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp time index)",
+    )
+    .await;
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host1', 1.5, 1000), ('host2', 3.0, 2000)",
+    )
+    .await;
+
+    let query_ctx = QueryContext::arc();
+    execute_sql_with(
+        &instance,
+        "create function scaled(x, factor) as 'x * factor + 1'",
+        query_ctx.clone(),
+    )
+    .await;
+    let sql = "select host, scaled(cpu, 2) as s from demo order by ts";
+    let output = execute_sql_with(&instance, sql, query_ctx.clone()).await;
+    let expected = "\
++-------+-----+
+| host  | s   |
++-------+-----+
+| host1 | 4.0 |
+| host2 | 7.0 |
++-------+-----+";
+    check_output_stream(output, expected).await;
+
+    // The functions are only visible in the session creating them.
+    assert!(try_execute_sql(&instance, sql).await.is_err());
+
+    // Functions can't be created twice unless replaced.
+    let sql_create = "create function scaled(x, factor) as 'x * factor'";
+    assert!(
+        try_execute_sql_with(&instance, sql_create, query_ctx.clone())
+            .await
+            .is_err()
+    );
+    execute_sql_with(
+        &instance,
+        "create or replace function scaled(x, factor) as 'x * factor'",
+        query_ctx.clone(),
+    )
+    .await;
+    let output = execute_sql_with(&instance, sql, query_ctx.clone()).await;
+    let expected = "\
++-------+-----+
+| host  | s   |
++-------+-----+
+| host1 | 3.0 |
+| host2 | 6.0 |
++-------+-----+";
+    check_output_stream(output, expected).await;
+
+    execute_sql_with(&instance, "drop function scaled", query_ctx.clone()).await;
+    assert!(try_execute_sql_with(&instance, sql, query_ctx.clone())
+        .await
+        .is_err());
+    assert!(
+        try_execute_sql_with(&instance, "drop function scaled", query_ctx.clone())
+            .await
+            .is_err()
+    );
+    execute_sql_with(&instance, "drop function if exists scaled", query_ctx).await;
+}
+
 async fn test_insert_with_default_value_for_type(instance: Arc<Instance>, type_name: &str) {
     let table_name = format!("test_table_with_{type_name}");
     let create_sql = format!(
@@ -998,6 +1344,45 @@ async fn test_insert_with_default_value(instance: Arc<dyn MockInstance>) {
     test_insert_with_default_value_for_type(instance.frontend(), "bigint").await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_insert_with_computed_column(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let sql = r#"create table demo(
+                    host STRING,
+                    idc STRING AS (substr(host, 1, 3)),
+                    ts TIMESTAMP,
+                    TIME INDEX(ts)
+                )"#;
+    let output = execute_sql(&instance, sql).await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        "insert into demo(host, ts) values ('sh1-host', 1655276557000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    // Computed columns are left out of the implicit column list.
+    let output = execute_sql(
+        &instance,
+        "insert into demo values ('bj1-host', 1655276558000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = execute_sql(&instance, "select host, idc from demo order by ts").await;
+    let expected = "\
++----------+-----+
+| host     | idc |
++----------+-----+
+| sh1-host | sh1 |
+| bj1-host | bj1 |
++----------+-----+";
+    check_output_stream(output, expected).await;
+}
+
 #[apply(both_instances_cases)]
 async fn test_use_database(instance: Arc<dyn MockInstance>) {
     let instance = instance.frontend();
@@ -1334,6 +1719,64 @@ async fn test_information_schema_dot_columns(instance: Arc<dyn MockInstance>) {
     check_output_stream(output, expected).await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_tail_table(instance: Arc<dyn MockInstance>) {
+    let is_distributed_mode = instance.is_distributed_mode();
+    let instance = instance.frontend();
+
+    let output = execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp time index)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let sql = "select host, cpu from demo where cpu > 1 with (tail = true)";
+    if is_distributed_mode {
+        // Only the mito tables in the datanodes can be tailed.
+        let _ = try_execute_sql(&instance, sql).await.unwrap_err();
+        return;
+    }
+
+    // The protocols collecting the whole output reject the tails.
+    let query_ctx = QueryContext::arc();
+    query_ctx.set_collects_output(true);
+    let _ = try_execute_sql_with(&instance, sql, query_ctx)
+        .await
+        .unwrap_err();
+
+    let Output::Stream(mut stream) = execute_sql(&instance, sql).await else { unreachable!() };
+    let output = execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host1', 0.5, 1000), ('host2', 2.5, 2000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let mut batches = vec![];
+    while batches
+        .iter()
+        .map(|batch: &RecordBatch| batch.num_rows())
+        .sum::<usize>()
+        < 1
+    {
+        let batch = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        batches.push(batch);
+    }
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host2 | 2.5 |
++-------+-----+";
+    let batches = RecordBatches::try_new(stream.schema(), batches).unwrap();
+    assert_eq!(batches.pretty_print().unwrap(), expected);
+}
+
 async fn execute_sql(instance: &Arc<Instance>, sql: &str) -> Output {
     execute_sql_with(instance, sql, QueryContext::arc()).await
 }
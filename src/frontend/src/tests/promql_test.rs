@@ -127,6 +127,72 @@ async fn sql_insert_tql_query_ceil(instance: Arc<dyn MockInstance>) {
     .await;
 }
 
+#[apply(standalone_instance_case)]
+async fn sql_insert_tql_query_with_parameters(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let query_ctx = QueryContext::arc();
+    instance
+        .do_query(
+            r#"create table http_requests_total (
+                host string,
+                cpu double,
+                ts timestamp TIME INDEX,
+                PRIMARY KEY (host),
+            );"#,
+            query_ctx.clone(),
+        )
+        .await;
+    instance
+        .do_query(
+            r#"insert into http_requests_total(host, cpu, ts) values
+                ('host1', 66.6, 0),
+                ('host2', 43.1, 0),
+                ('host1', 99.1, 10000),
+                ('host2', 19.1, 10000);
+            "#,
+            query_ctx.clone(),
+        )
+        .await;
+
+    for sql in ["SET @start = 0", "SET @end = 10", "SET @host = 'host1'"] {
+        let _ = instance
+            .do_query(sql, query_ctx.clone())
+            .await
+            .remove(0)
+            .unwrap();
+    }
+
+    let query_output = instance
+        .do_query(
+            "TQL EVAL ($start, $end, '10s') ceil(http_requests_total{host=$host})",
+            query_ctx.clone(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+    check_unordered_output_stream(
+        query_output,
+        "+---------------------+-----------+-------+\
+        \n| ts                  | ceil(cpu) | host  |\
+        \n+---------------------+-----------+-------+\
+        \n| 1970-01-01T00:00:00 | 67.0      | host1 |\
+        \n| 1970-01-01T00:00:10 | 100.0     | host1 |\
+        \n+---------------------+-----------+-------+",
+    )
+    .await;
+
+    // Unbound parameters are rejected.
+    let result = instance
+        .do_query(
+            "TQL EVAL ($start, $end, '10s') http_requests_total{host=$unknown}",
+            query_ctx,
+        )
+        .await
+        .remove(0);
+    assert!(result.is_err());
+}
+
 // should apply to both instances. tracked in #1296
 #[apply(standalone_instance_case)]
 async fn sql_insert_promql_query_ceil(instance: Arc<dyn MockInstance>) {
@@ -174,6 +240,41 @@ async fn sql_insert_promql_query_ceil(instance: Arc<dyn MockInstance>) {
     .await;
 }
 
+// should apply to both instances. tracked in #1296
+#[apply(standalone_instance_case)]
+async fn sql_insert_promql_query_multi_quantile(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+    let at_4s = UNIX_EPOCH.checked_add(Duration::from_secs(4)).unwrap();
+
+    create_insert_query_assert(
+        instance,
+        r#"create table http_requests_total (
+            host string,
+            cpu double,
+            ts timestamp TIME INDEX,
+            PRIMARY KEY (host),
+        );"#,
+        r#"insert into http_requests_total(host, cpu, ts) values
+            ('host1', 1, 0),
+            ('host1', 2, 1000),
+            ('host1', 3, 2000),
+            ('host1', 4, 3000),
+            ('host1', 5, 4000);
+        "#,
+        "quantile_over_time(\"0.5,0.75\", http_requests_total{host=\"host1\"}[5s])",
+        at_4s,
+        at_4s,
+        Duration::from_secs(1),
+        Duration::from_secs(1),
+        "+---------------------+----------------------------------------------------+-----------------------------------------------------+-------+\
+        \n| ts                  | prom_quantile_over_time(ts_range,cpu,Float64(0.5)) | prom_quantile_over_time(ts_range,cpu,Float64(0.75)) | host  |\
+        \n+---------------------+----------------------------------------------------+-----------------------------------------------------+-------+\
+        \n| 1970-01-01T00:00:04 | 3.0                                                | 4.0                                                 | host1 |\
+        \n+---------------------+----------------------------------------------------+-----------------------------------------------------+-------+",
+    )
+    .await;
+}
+
 const AGGREGATORS_CREATE_TABLE: &str = r#"create table http_requests (
     job string,
     instance string,
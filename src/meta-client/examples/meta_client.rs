@@ -204,6 +204,7 @@ fn new_table_info() -> RawTableInfo {
             engine_options: HashMap::new(),
             options: TableOptions::default(),
             created_on: DateTime::default(),
+            column_history: vec![],
         },
         table_type: TableType::Base,
     }
@@ -27,6 +27,7 @@ use snafu::OptionExt;
 use store::Client as StoreClient;
 
 pub use self::heartbeat::{HeartbeatSender, HeartbeatStream};
+pub use self::load_balance::BackoffPolicy;
 use crate::error;
 use crate::error::Result;
 use crate::rpc::lock::{LockRequest, LockResponse, UnlockRequest};
@@ -48,6 +49,7 @@ pub struct MetaClientBuilder {
     enable_store: bool,
     enable_lock: bool,
     channel_manager: Option<ChannelManager>,
+    backoff_policy: BackoffPolicy,
 }
 
 impl MetaClientBuilder {
@@ -93,6 +95,15 @@ impl MetaClientBuilder {
         }
     }
 
+    /// Sets the policy to back off from failed metasrv peers of the router and
+    /// store clients.
+    pub fn backoff_policy(self, backoff_policy: BackoffPolicy) -> Self {
+        Self {
+            backoff_policy,
+            ..self
+        }
+    }
+
     pub fn build(self) -> MetaClient {
         let mut client = if let Some(mgr) = self.channel_manager {
             MetaClient::with_channel_manager(self.id, mgr)
@@ -107,16 +118,32 @@ impl MetaClientBuilder {
         let mgr = client.channel_manager.clone();
 
         if self.enable_heartbeat {
-            client.heartbeat = Some(HeartbeatClient::new(self.id, mgr.clone()));
+            client.heartbeat = Some(HeartbeatClient::with_backoff_policy(
+                self.id,
+                mgr.clone(),
+                self.backoff_policy,
+            ));
         }
         if self.enable_router {
-            client.router = Some(RouterClient::new(self.id, mgr.clone()));
+            client.router = Some(RouterClient::with_backoff_policy(
+                self.id,
+                mgr.clone(),
+                self.backoff_policy,
+            ));
         }
         if self.enable_store {
-            client.store = Some(StoreClient::new(self.id, mgr.clone()));
+            client.store = Some(StoreClient::with_backoff_policy(
+                self.id,
+                mgr.clone(),
+                self.backoff_policy,
+            ));
         }
         if self.enable_lock {
-            client.lock = Some(LockClient::new(self.id, mgr));
+            client.lock = Some(LockClient::with_backoff_policy(
+                self.id,
+                mgr,
+                self.backoff_policy,
+            ));
         }
 
         client
@@ -486,6 +513,7 @@ mod tests {
                 engine_options: HashMap::new(),
                 options: TableOptions::default(),
                 created_on: DateTime::default(),
+                column_history: vec![],
             },
             table_type: TableType::Base,
         }
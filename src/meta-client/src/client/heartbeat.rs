@@ -14,6 +14,7 @@
 
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 
 use api::v1::meta::heartbeat_client::HeartbeatClient;
 use api::v1::meta::{AskLeaderRequest, HeartbeatRequest, HeartbeatResponse, RequestHeader};
@@ -25,6 +26,7 @@ use tokio_stream::wrappers::ReceiverStream;
 use tonic::transport::Channel;
 use tonic::Streaming;
 
+use crate::client::load_balance::{BackoffPolicy, PeerSelector};
 use crate::client::Id;
 use crate::error;
 use crate::error::Result;
@@ -93,10 +95,19 @@ pub struct Client {
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_backoff_policy(id, channel_manager, BackoffPolicy::default())
+    }
+
+    pub fn with_backoff_policy(
+        id: Id,
+        channel_manager: ChannelManager,
+        backoff_policy: BackoffPolicy,
+    ) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
             channel_manager,
-            peers: HashSet::default(),
+            backoff_policy,
+            peers: PeerSelector::new(backoff_policy, vec![]),
             leader: None,
         }));
 
@@ -133,7 +144,8 @@ impl Client {
 struct Inner {
     id: Id,
     channel_manager: ChannelManager,
-    peers: HashSet<String>,
+    backoff_policy: BackoffPolicy,
+    peers: PeerSelector,
     leader: Option<String>,
 }
 
@@ -150,15 +162,20 @@ impl Inner {
             }
         );
 
-        self.peers = urls
+        let peers = urls
             .as_ref()
             .iter()
             .map(|url| url.as_ref().to_string())
-            .collect();
+            .collect::<HashSet<_>>()
+            .drain()
+            .collect::<Vec<_>>();
+        self.peers = PeerSelector::new(self.backoff_policy, peers);
 
         Ok(())
     }
 
+    /// Asks the healthiest peers first, peers failing to answer are skipped by
+    /// later asks until they recover.
     async fn ask_leader(&mut self) -> Result<()> {
         ensure!(
             self.is_started(),
@@ -169,22 +186,27 @@ impl Inner {
 
         let header = RequestHeader::new(self.id);
         let mut leader = None;
-        for addr in &self.peers {
+        let mut tried = Vec::new();
+        while let Some(addr) = self.peers.select(&tried) {
             let req = AskLeaderRequest {
                 header: Some(header.clone()),
             };
-            let mut client = self.make_client(addr)?;
+            let mut client = self.make_client(&addr)?;
+            let start = Instant::now();
             match client.ask_leader(req).await {
                 Ok(res) => {
+                    self.peers.report_success(&addr, start.elapsed());
                     if let Some(endpoint) = res.into_inner().leader {
                         leader = Some(endpoint.addr);
                         break;
                     }
                 }
                 Err(status) => {
+                    self.peers.report_failure(&addr);
                     debug!("Failed to ask leader from: {}, {}", addr, status);
                 }
             }
+            tried.push(addr);
         }
         self.leader = Some(leader.context(error::AskLeaderSnafu)?);
         Ok(())
@@ -12,20 +12,244 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use rand::Rng;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-pub fn random_get<T, F>(len: usize, func: F) -> Option<T>
-where
-    F: FnOnce(usize) -> Option<T>,
-{
-    if len == 0 {
-        return None;
+use common_telemetry::warn;
+use metrics::{histogram, increment_counter};
+use rand::seq::SliceRandom;
+use snafu::ResultExt;
+use tonic::Code;
+
+use crate::error;
+use crate::error::Result;
+use crate::metrics::{
+    METRIC_META_CLIENT_FAILOVER, METRIC_META_CLIENT_RPC_ELAPSED, METRIC_META_CLIENT_RPC_ERRORS,
+};
+
+/// Policy to back off from a peer after its requests failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BackoffPolicy {
+    /// Duration to skip a peer after its first failure.
+    pub min_delay: Duration,
+    /// Max duration to skip a peer, the duration doubles on each consecutive failure.
+    pub max_delay: Duration,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            min_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl BackoffPolicy {
+    fn backoff(&self, failures: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(failures.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.min_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
     }
+}
+
+/// Whether a request is sent again to other peers if its peer is unavailable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Retry {
+    /// Retries the request on other peers, only for requests that could be processed
+    /// twice without changing the result, like reads.
+    Failover,
+    /// Never sends the request twice, as the unavailable peer may have processed it.
+    /// The peer is still skipped by later requests.
+    Never,
+}
 
-    let mut rng = rand::thread_rng();
-    let i = rng.gen_range(0..len);
+/// Weight of the latest sample in the moving average of latencies.
+const LATENCY_SMOOTHING: f64 = 0.2;
 
-    func(i)
+#[derive(Debug)]
+struct PeerState {
+    addr: String,
+    /// Exponential moving average of the latencies in seconds, `None` if the peer
+    /// has never responded.
+    latency: Option<f64>,
+    /// Number of consecutive failures.
+    failures: u32,
+    /// The peer is skipped until this instant after failures.
+    retry_at: Option<Instant>,
+}
+
+/// Selects peers by their health.
+///
+/// Available peers are preferred over peers backing off from failures, and among
+/// them the one with the lowest average latency is selected. Peers that have never
+/// responded are selected first so they are probed. If all peers are backing off,
+/// the one whose backoff ends first is selected.
+#[derive(Debug)]
+pub struct PeerSelector {
+    policy: BackoffPolicy,
+    peers: Mutex<Vec<PeerState>>,
+}
+
+impl PeerSelector {
+    pub fn new(policy: BackoffPolicy, peers: Vec<String>) -> Self {
+        let peers = peers
+            .into_iter()
+            .map(|addr| PeerState {
+                addr,
+                latency: None,
+                failures: 0,
+                retry_at: None,
+            })
+            .collect();
+
+        Self {
+            policy,
+            peers: Mutex::new(peers),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.lock().unwrap().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.lock().unwrap().len()
+    }
+
+    /// Returns the best peer not in `excluded`, `None` if no such peer.
+    pub fn select(&self, excluded: &[String]) -> Option<String> {
+        let now = Instant::now();
+        let peers = self.peers.lock().unwrap();
+        let mut candidates = peers
+            .iter()
+            .filter(|peer| !excluded.contains(&peer.addr))
+            .collect::<Vec<_>>();
+        // Shuffles the peers to spread requests among peers with the same score.
+        candidates.shuffle(&mut rand::thread_rng());
+
+        let available = candidates
+            .iter()
+            .filter(|peer| peer.retry_at.map_or(true, |retry_at| retry_at <= now))
+            .min_by(|a, b| {
+                let a = a.latency.unwrap_or(0.0);
+                let b = b.latency.unwrap_or(0.0);
+                a.total_cmp(&b)
+            });
+        let peer = match available {
+            Some(peer) => Some(peer),
+            None => candidates.iter().min_by_key(|peer| peer.retry_at),
+        };
+
+        peer.map(|peer| peer.addr.clone())
+    }
+
+    /// Reports that `addr` responded in `elapsed`.
+    pub fn report_success(&self, addr: &str, elapsed: Duration) {
+        let mut peers = self.peers.lock().unwrap();
+        if let Some(peer) = peers.iter_mut().find(|peer| peer.addr == addr) {
+            let sample = elapsed.as_secs_f64();
+            peer.latency = Some(match peer.latency {
+                Some(latency) => latency * (1.0 - LATENCY_SMOOTHING) + sample * LATENCY_SMOOTHING,
+                None => sample,
+            });
+            peer.failures = 0;
+            peer.retry_at = None;
+        }
+    }
+
+    /// Reports that `addr` failed to respond, the peer is skipped for a while.
+    pub fn report_failure(&self, addr: &str) {
+        let mut peers = self.peers.lock().unwrap();
+        if let Some(peer) = peers.iter_mut().find(|peer| peer.addr == addr) {
+            peer.failures = peer.failures.saturating_add(1);
+            peer.retry_at = Some(Instant::now() + self.policy.backoff(peer.failures));
+        }
+    }
+
+    /// Calls `method` of a client created by `make_client` for the best peer.
+    ///
+    /// If the peer is unavailable and `retry` is [Retry::Failover], the request is retried
+    /// on the next best peer until all peers are tried. Other errors are returned directly
+    /// as the request may have been processed by the peer.
+    pub async fn call<C, T, M, F, Fut>(
+        &self,
+        method: &'static str,
+        retry: Retry,
+        make_client: M,
+        f: F,
+    ) -> Result<T>
+    where
+        M: Fn(&str) -> Result<C>,
+        F: Fn(C) -> Fut,
+        Fut: Future<Output = std::result::Result<tonic::Response<T>, tonic::Status>>,
+    {
+        let mut tried = Vec::new();
+        let mut last_status = None;
+        while let Some(peer) = self.select(&tried) {
+            if !tried.is_empty() {
+                increment_counter!(METRIC_META_CLIENT_FAILOVER, "method" => method);
+            }
+
+            let client = make_client(&peer)?;
+            let start = Instant::now();
+            let res = f(client).await;
+            let elapsed = start.elapsed();
+            histogram!(METRIC_META_CLIENT_RPC_ELAPSED, elapsed, "method" => method, "peer" => peer.clone());
+
+            let status = match res {
+                Ok(res) => {
+                    self.report_success(&peer, elapsed);
+                    return Ok(res.into_inner());
+                }
+                Err(status) => status,
+            };
+            increment_counter!(
+                METRIC_META_CLIENT_RPC_ERRORS,
+                "method" => method,
+                "peer" => peer.clone(),
+                "code" => format!("{:?}", status.code()),
+            );
+
+            match status.code() {
+                // The peer may be down, but it may also have processed the request
+                // before the connection is broken.
+                Code::Unavailable => {
+                    warn!(
+                        "Metasrv {} is unavailable on {}, status: {}",
+                        peer, method, status
+                    );
+                    self.report_failure(&peer);
+                    if retry == Retry::Never {
+                        return Err(status).context(error::TonicStatusSnafu);
+                    }
+                    tried.push(peer);
+                    last_status = Some(status);
+                }
+                Code::DeadlineExceeded => {
+                    self.report_failure(&peer);
+                    return Err(status).context(error::TonicStatusSnafu);
+                }
+                _ => {
+                    self.report_success(&peer, elapsed);
+                    return Err(status).context(error::TonicStatusSnafu);
+                }
+            }
+        }
+
+        match last_status {
+            Some(status) => Err(status).context(error::TonicStatusSnafu),
+            None => error::IllegalGrpcClientStateSnafu {
+                err_msg: format!("Empty peers to call {method}, client may not start yet"),
+            }
+            .fail(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -33,16 +257,86 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_random_get() {
-        for i in 1..100 {
-            let res = random_get(i, |index| Some(2 * index));
-            assert!(res.unwrap() < 2 * i);
-        }
+    fn test_backoff() {
+        let policy = BackoffPolicy {
+            min_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff(2));
+        assert_eq!(Duration::from_millis(400), policy.backoff(3));
+        assert_eq!(Duration::from_millis(500), policy.backoff(4));
+        assert_eq!(Duration::from_millis(500), policy.backoff(100));
     }
 
     #[test]
-    fn test_random_get_none() {
-        let res = random_get(0, |index| Some(2 * index));
-        assert!(res.is_none());
+    fn test_peer_selector() {
+        let policy = BackoffPolicy {
+            min_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(600),
+        };
+        let peers = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let selector = PeerSelector::new(policy, peers);
+        assert_eq!(3, selector.len());
+
+        selector.report_success("a", Duration::from_millis(10));
+        selector.report_success("b", Duration::from_millis(20));
+        selector.report_success("c", Duration::from_millis(30));
+        assert_eq!("a", selector.select(&[]).unwrap());
+        assert_eq!("b", selector.select(&["a".to_string()]).unwrap());
+
+        // Skips the failed peer.
+        selector.report_failure("a");
+        assert_eq!("b", selector.select(&[]).unwrap());
+        selector.report_failure("b");
+        assert_eq!("c", selector.select(&[]).unwrap());
+
+        // Selects the peer recovering first if all peers are failed.
+        selector.report_failure("c");
+        selector.report_failure("c");
+        let selected = selector.select(&[]).unwrap();
+        assert!(selected == "a" || selected == "b", "{selected}");
+
+        let all = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert!(selector.select(&all).is_none());
+
+        // The peer is available again after a success.
+        selector.report_success("c", Duration::from_millis(30));
+        assert_eq!("c", selector.select(&[]).unwrap());
+    }
+
+    async fn call_unavailable(selector: &PeerSelector, retry: Retry) -> Vec<String> {
+        let called = Mutex::new(Vec::new());
+        let res: Result<()> = selector
+            .call(
+                "test",
+                retry,
+                |peer| Ok(peer.to_string()),
+                |peer| {
+                    called.lock().unwrap().push(peer);
+                    async { Err(tonic::Status::unavailable("down")) }
+                },
+            )
+            .await;
+        assert!(matches!(res, Err(error::Error::TonicStatus { .. })));
+
+        called.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_call_retry() {
+        let policy = BackoffPolicy::default();
+        let peers = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        let selector = PeerSelector::new(policy, peers.clone());
+        let mut called = call_unavailable(&selector, Retry::Failover).await;
+        called.sort();
+        assert_eq!(peers, called);
+
+        let selector = PeerSelector::new(policy, peers);
+        let called = call_unavailable(&selector, Retry::Never).await;
+        assert_eq!(1, called.len());
+        // The failed peer is skipped by the next request.
+        assert_ne!(called[0], selector.select(&[]).unwrap());
     }
 }
@@ -13,16 +13,18 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::future::Future;
 use std::sync::Arc;
 
 use api::v1::meta::lock_client::LockClient;
 use api::v1::meta::{LockRequest, LockResponse, UnlockRequest, UnlockResponse};
 use common_grpc::channel_manager::ChannelManager;
-use snafu::{ensure, OptionExt, ResultExt};
+use snafu::{ensure, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
 
-use crate::client::{load_balance, Id};
+use crate::client::load_balance::{BackoffPolicy, PeerSelector, Retry};
+use crate::client::Id;
 use crate::error;
 use crate::error::Result;
 
@@ -33,10 +35,19 @@ pub struct Client {
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_backoff_policy(id, channel_manager, BackoffPolicy::default())
+    }
+
+    pub fn with_backoff_policy(
+        id: Id,
+        channel_manager: ChannelManager,
+        backoff_policy: BackoffPolicy,
+    ) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
             channel_manager,
-            peers: vec![],
+            backoff_policy,
+            peers: PeerSelector::new(backoff_policy, vec![]),
         }));
 
         Self { inner }
@@ -71,7 +82,8 @@ impl Client {
 struct Inner {
     id: Id,
     channel_manager: ChannelManager,
-    peers: Vec<String>,
+    backoff_policy: BackoffPolicy,
+    peers: PeerSelector,
 }
 
 impl Inner {
@@ -87,26 +99,29 @@ impl Inner {
             }
         );
 
-        self.peers = urls
+        let peers = urls
             .as_ref()
             .iter()
             .map(|url| url.as_ref().to_string())
             .collect::<HashSet<_>>()
             .drain()
             .collect::<Vec<_>>();
+        self.peers = PeerSelector::new(self.backoff_policy, peers);
 
         Ok(())
     }
 
-    fn random_client(&self) -> Result<LockClient<Channel>> {
-        let len = self.peers.len();
-        let peer = load_balance::random_get(len, |i| Some(&self.peers[i])).context(
-            error::IllegalGrpcClientStateSnafu {
-                err_msg: "Empty peers, lock client may not start yet",
-            },
-        )?;
-
-        self.make_client(peer)
+    /// Locks and unlocks are never retried on other peers: acquiring a lock twice
+    /// may block the caller by itself, and releasing it twice may release a lock
+    /// acquired by others in between.
+    async fn call<T, F, Fut>(&self, method: &'static str, f: F) -> Result<T>
+    where
+        F: Fn(LockClient<Channel>) -> Fut,
+        Fut: Future<Output = std::result::Result<tonic::Response<T>, tonic::Status>>,
+    {
+        self.peers
+            .call(method, Retry::Never, |peer| self.make_client(peer), f)
+            .await
     }
 
     fn make_client(&self, addr: impl AsRef<str>) -> Result<LockClient<Channel>> {
@@ -124,19 +139,21 @@ impl Inner {
     }
 
     async fn lock(&self, mut req: LockRequest) -> Result<LockResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client.lock(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("lock", |mut client| {
+            let req = req.clone();
+            async move { client.lock(req).await }
+        })
+        .await
     }
 
     async fn unlock(&self, mut req: UnlockRequest) -> Result<UnlockResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client.unlock(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("unlock", |mut client| {
+            let req = req.clone();
+            async move { client.unlock(req).await }
+        })
+        .await
     }
 }
 
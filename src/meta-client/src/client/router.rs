@@ -13,16 +13,18 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::future::Future;
 use std::sync::Arc;
 
 use api::v1::meta::router_client::RouterClient;
 use api::v1::meta::{CreateRequest, DeleteRequest, RouteRequest, RouteResponse};
 use common_grpc::channel_manager::ChannelManager;
-use snafu::{ensure, OptionExt, ResultExt};
+use snafu::{ensure, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
 
-use crate::client::{load_balance as lb, Id};
+use crate::client::load_balance::{BackoffPolicy, PeerSelector, Retry};
+use crate::client::Id;
 use crate::error;
 use crate::error::Result;
 
@@ -33,10 +35,19 @@ pub struct Client {
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_backoff_policy(id, channel_manager, BackoffPolicy::default())
+    }
+
+    pub fn with_backoff_policy(
+        id: Id,
+        channel_manager: ChannelManager,
+        backoff_policy: BackoffPolicy,
+    ) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
             channel_manager,
-            peers: vec![],
+            backoff_policy,
+            peers: PeerSelector::new(backoff_policy, vec![]),
         }));
 
         Self { inner }
@@ -76,7 +87,8 @@ impl Client {
 struct Inner {
     id: Id,
     channel_manager: ChannelManager,
-    peers: Vec<String>,
+    backoff_policy: BackoffPolicy,
+    peers: PeerSelector,
 }
 
 impl Inner {
@@ -92,50 +104,53 @@ impl Inner {
             }
         );
 
-        self.peers = urls
+        let peers = urls
             .as_ref()
             .iter()
             .map(|url| url.as_ref().to_string())
             .collect::<HashSet<_>>()
             .drain()
             .collect::<Vec<_>>();
+        self.peers = PeerSelector::new(self.backoff_policy, peers);
 
         Ok(())
     }
 
     async fn create(&self, mut req: CreateRequest) -> Result<RouteResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client.create(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("create", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.create(req).await }
+        })
+        .await
     }
 
     async fn route(&self, mut req: RouteRequest) -> Result<RouteResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client.route(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("route", Retry::Failover, |mut client| {
+            let req = req.clone();
+            async move { client.route(req).await }
+        })
+        .await
     }
 
     async fn delete(&self, mut req: DeleteRequest) -> Result<RouteResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client.delete(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("delete", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.delete(req).await }
+        })
+        .await
     }
 
-    fn random_client(&self) -> Result<RouterClient<Channel>> {
-        let len = self.peers.len();
-        let peer = lb::random_get(len, |i| Some(&self.peers[i])).context(
-            error::IllegalGrpcClientStateSnafu {
-                err_msg: "Empty peers, router client may not start yet",
-            },
-        )?;
-
-        self.make_client(peer)
+    async fn call<T, F, Fut>(&self, method: &'static str, retry: Retry, f: F) -> Result<T>
+    where
+        F: Fn(RouterClient<Channel>) -> Fut,
+        Fut: Future<Output = std::result::Result<tonic::Response<T>, tonic::Status>>,
+    {
+        self.peers
+            .call(method, retry, |peer| self.make_client(peer), f)
+            .await
     }
 
     fn make_client(&self, addr: impl AsRef<str>) -> Result<RouterClient<Channel>> {
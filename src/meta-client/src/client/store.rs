@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::future::Future;
 use std::sync::Arc;
 
 use api::v1::meta::store_client::StoreClient;
@@ -23,11 +24,12 @@ use api::v1::meta::{
     RangeRequest, RangeResponse,
 };
 use common_grpc::channel_manager::ChannelManager;
-use snafu::{ensure, OptionExt, ResultExt};
+use snafu::{ensure, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
 
-use crate::client::{load_balance as lb, Id};
+use crate::client::load_balance::{BackoffPolicy, PeerSelector, Retry};
+use crate::client::Id;
 use crate::error;
 use crate::error::Result;
 
@@ -38,10 +40,19 @@ pub struct Client {
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_backoff_policy(id, channel_manager, BackoffPolicy::default())
+    }
+
+    pub fn with_backoff_policy(
+        id: Id,
+        channel_manager: ChannelManager,
+        backoff_policy: BackoffPolicy,
+    ) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
             channel_manager,
-            peers: vec![],
+            backoff_policy,
+            peers: PeerSelector::new(backoff_policy, vec![]),
         }));
 
         Self { inner }
@@ -109,7 +120,8 @@ impl Client {
 struct Inner {
     id: Id,
     channel_manager: ChannelManager,
-    peers: Vec<String>,
+    backoff_policy: BackoffPolicy,
+    peers: PeerSelector,
 }
 
 impl Inner {
@@ -125,112 +137,101 @@ impl Inner {
             }
         );
 
-        self.peers = urls
+        let peers = urls
             .as_ref()
             .iter()
             .map(|url| url.as_ref().to_string())
             .collect::<HashSet<_>>()
             .drain()
             .collect::<Vec<_>>();
+        self.peers = PeerSelector::new(self.backoff_policy, peers);
 
         Ok(())
     }
 
     async fn range(&self, mut req: RangeRequest) -> Result<RangeResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client.range(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("range", Retry::Failover, |mut client| {
+            let req = req.clone();
+            async move { client.range(req).await }
+        })
+        .await
     }
 
     async fn put(&self, mut req: PutRequest) -> Result<PutResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client.put(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("put", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.put(req).await }
+        })
+        .await
     }
 
     async fn batch_get(&self, mut req: BatchGetRequest) -> Result<BatchGetResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-
-        let res = client
-            .batch_get(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("batch_get", Retry::Failover, |mut client| {
+            let req = req.clone();
+            async move { client.batch_get(req).await }
+        })
+        .await
     }
 
     async fn batch_put(&self, mut req: BatchPutRequest) -> Result<BatchPutResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client
-            .batch_put(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("batch_put", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.batch_put(req).await }
+        })
+        .await
     }
 
     async fn batch_delete(&self, mut req: BatchDeleteRequest) -> Result<BatchDeleteResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client
-            .batch_delete(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("batch_delete", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.batch_delete(req).await }
+        })
+        .await
     }
 
     async fn compare_and_put(
         &self,
         mut req: CompareAndPutRequest,
     ) -> Result<CompareAndPutResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client
-            .compare_and_put(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("compare_and_put", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.compare_and_put(req).await }
+        })
+        .await
     }
 
     async fn delete_range(&self, mut req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client
-            .delete_range(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("delete_range", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.delete_range(req).await }
+        })
+        .await
     }
 
     async fn move_value(&self, mut req: MoveValueRequest) -> Result<MoveValueResponse> {
-        let mut client = self.random_client()?;
         req.set_header(self.id);
-        let res = client
-            .move_value(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        self.call("move_value", Retry::Never, |mut client| {
+            let req = req.clone();
+            async move { client.move_value(req).await }
+        })
+        .await
     }
 
-    fn random_client(&self) -> Result<StoreClient<Channel>> {
-        let len = self.peers.len();
-        let peer = lb::random_get(len, |i| Some(&self.peers[i])).context(
-            error::IllegalGrpcClientStateSnafu {
-                err_msg: "Empty peers, store client may not start yet",
-            },
-        )?;
-
-        self.make_client(peer)
+    async fn call<T, F, Fut>(&self, method: &'static str, retry: Retry, f: F) -> Result<T>
+    where
+        F: Fn(StoreClient<Channel>) -> Fut,
+        Fut: Future<Output = std::result::Result<tonic::Response<T>, tonic::Status>>,
+    {
+        self.peers
+            .call(method, retry, |peer| self.make_client(peer), f)
+            .await
     }
 
     fn make_client(&self, addr: impl AsRef<str>) -> Result<StoreClient<Channel>> {
@@ -12,21 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
+use crate::client::BackoffPolicy;
+
 pub mod client;
 pub mod error;
+mod metrics;
 #[cfg(test)]
 mod mocks;
 pub mod rpc;
 
 // Options for meta client in datanode instance.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MetaClientOptions {
     pub metasrv_addrs: Vec<String>,
     pub timeout_millis: u64,
     pub connect_timeout_millis: u64,
     pub tcp_nodelay: bool,
+    /// Duration in milliseconds to skip a metasrv after its first failure.
+    pub backoff_min_millis: u64,
+    /// Max duration in milliseconds to skip a failed metasrv, the duration doubles
+    /// on each consecutive failure.
+    pub backoff_max_millis: u64,
 }
 
 impl Default for MetaClientOptions {
@@ -36,6 +47,17 @@ impl Default for MetaClientOptions {
             timeout_millis: 3_000u64,
             connect_timeout_millis: 5_000u64,
             tcp_nodelay: true,
+            backoff_min_millis: 100u64,
+            backoff_max_millis: 10_000u64,
+        }
+    }
+}
+
+impl MetaClientOptions {
+    pub fn backoff_policy(&self) -> BackoffPolicy {
+        BackoffPolicy {
+            min_delay: Duration::from_millis(self.backoff_min_millis),
+            max_delay: Duration::from_millis(self.backoff_max_millis),
         }
     }
 }
@@ -0,0 +1,22 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! meta client metrics
+
+/// Elapsed time of RPCs to metasrv, labeled by method and peer.
+pub const METRIC_META_CLIENT_RPC_ELAPSED: &str = "meta.client.rpc.elapsed";
+/// Counter of failed RPCs to metasrv, labeled by method, peer and status code.
+pub const METRIC_META_CLIENT_RPC_ERRORS: &str = "meta.client.rpc.errors";
+/// Counter of RPCs retried on another metasrv after the peer was unavailable.
+pub const METRIC_META_CLIENT_FAILOVER: &str = "meta.client.failover";
@@ -348,6 +348,7 @@ mod tests {
                 engine_options: HashMap::new(),
                 options: TableOptions::default(),
                 created_on: DateTime::default(),
+                column_history: vec![],
             },
             table_type: TableType::Base,
         }
@@ -28,6 +28,7 @@ pub use failure_handler::RegionFailureHandler;
 pub use keep_lease_handler::KeepLeaseHandler;
 pub use on_leader_start::OnLeaderStartHandler;
 pub use persist_stats_handler::PersistStatsHandler;
+pub use region_reconcile_handler::RegionReconcileHandler;
 pub use response_header_handler::ResponseHeaderHandler;
 use snafu::OptionExt;
 use tokio::sync::mpsc::Sender;
@@ -49,6 +50,7 @@ pub mod mailbox_handler;
 pub mod node_stat;
 mod on_leader_start;
 mod persist_stats_handler;
+mod region_reconcile_handler;
 mod response_header_handler;
 
 #[async_trait::async_trait]
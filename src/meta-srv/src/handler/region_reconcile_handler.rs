@@ -0,0 +1,701 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use api::v1::meta::{
+    BatchDeleteRequest, BatchPutRequest, CompareAndPutRequest, HeartbeatRequest, KeyValue, Peer,
+    RangeRequest, Role, TableRouteValue,
+};
+use catalog::helper::{
+    build_region_inconsistency_prefix, build_table_global_prefix, RegionInconsistencyKey,
+    RegionInconsistencyKind, RegionInconsistencyValue, TableGlobalKey, TableGlobalValue,
+};
+use common_telemetry::{info, warn};
+use common_time::util as time_util;
+use dashmap::DashMap;
+use metrics::{gauge, increment_counter};
+use snafu::ResultExt;
+use table::engine::region_id;
+
+use crate::error::{self, Result};
+use crate::handler::node_stat::{RegionStat, Stat};
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::keys::TableRouteKey;
+use crate::metasrv::Context;
+use crate::metrics::{METRIC_META_REGION_INCONSISTENCIES, METRIC_META_ROUTE_REPAIRED};
+use crate::service::store::ext::KvStoreExt;
+use crate::util;
+
+const DEFAULT_RECONCILE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Compares the regions a datanode reports in its heartbeats with the regions the table
+/// routes assign to it, and records the differences under the `__ri` keys.
+///
+/// A reported region assigned to the reporting datanode but routed to another one, e.g. after
+/// it's migrated, is routed to the reporting datanode. The assignment only changes after the
+/// region is fenced on its former datanode, so the region is never served by both. A reported
+/// region assigned to another datanode, or to none, is only recorded, as its owner may still
+/// serve it. The datanode itself reopens the missing regions and closes the unowned ones.
+///
+/// Only the tables in the schemas the datanode serves regions of are compared, the tables of
+/// the other schemas assigned to the datanode are opened by the datanode's reconcile loop.
+pub struct RegionReconcileHandler {
+    interval: Duration,
+    last_reconciled: DashMap<u64, Instant>,
+}
+
+impl Default for RegionReconcileHandler {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECONCILE_INTERVAL)
+    }
+}
+
+impl RegionReconcileHandler {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_reconciled: DashMap::new(),
+        }
+    }
+
+    fn should_reconcile(&self, node_id: u64) -> bool {
+        let now = Instant::now();
+        if let Some(last) = self.last_reconciled.get(&node_id) {
+            if now.duration_since(*last) < self.interval {
+                return false;
+            }
+        }
+        let _ = self.last_reconciled.insert(node_id, now);
+        true
+    }
+
+    async fn reconcile(&self, stat: &Stat, ctx: &Context) -> Result<()> {
+        let now = time_util::current_time_millis();
+        let mut reported: HashMap<u32, Vec<&RegionStat>> = HashMap::new();
+        for region in &stat.region_stats {
+            reported
+                .entry((region.id >> 32) as u32)
+                .or_default()
+                .push(region);
+        }
+        let schemas = stat
+            .region_stats
+            .iter()
+            .map(|region| (region.catalog.clone(), region.schema.clone()))
+            .collect::<HashSet<_>>();
+
+        let mut inconsistencies = HashMap::new();
+        for (tgk, tgv_bytes, tgv) in table_global_values(ctx, &schemas).await? {
+            let table_id = tgv.table_id();
+            let assigned = tgv
+                .regions_id_map
+                .get(&stat.id)
+                .cloned()
+                .unwrap_or_default();
+            let served = reported.remove(&table_id).unwrap_or_default();
+
+            // Regions of tables created lately may just not be opened yet.
+            let created_on = tgv.table_info.meta.created_on.timestamp_millis();
+            if now - created_on >= self.interval.as_millis() as i64 {
+                for number in &assigned {
+                    if served.iter().all(|region| region.id as u32 != *number) {
+                        let key = RegionInconsistencyKey {
+                            node_id: stat.id,
+                            region_id: region_id(table_id, *number),
+                        };
+                        let value = inconsistency_value(
+                            &tgk,
+                            *number,
+                            RegionInconsistencyKind::Missing,
+                            Some(stat.id),
+                            now,
+                        );
+                        let _ = inconsistencies.insert(key, value);
+                    }
+                }
+            }
+
+            let mut route_leaders = None;
+            let mut rerouted = vec![];
+            for region in served {
+                let number = region.id as u32;
+                if assigned.contains(&number) {
+                    if route_leaders.is_none() {
+                        route_leaders = Some(region_leaders(ctx, table_id, &tgk).await?);
+                    }
+                    let routed_elsewhere = route_leaders
+                        .as_ref()
+                        .and_then(|leaders| leaders.get(&number))
+                        .map_or(false, |leader| *leader != stat.id);
+                    if routed_elsewhere {
+                        rerouted.push(number);
+                    }
+                    continue;
+                }
+
+                let owner = tgv
+                    .regions_id_map
+                    .iter()
+                    .find(|(_, numbers)| numbers.contains(&number))
+                    .map(|(node_id, _)| *node_id);
+                let key = RegionInconsistencyKey {
+                    node_id: stat.id,
+                    region_id: region.id,
+                };
+                let value =
+                    inconsistency_value(&tgk, number, RegionInconsistencyKind::Unowned, owner, now);
+                let _ = inconsistencies.insert(key, value);
+            }
+
+            if !rerouted.is_empty() {
+                let _ = repair_route(ctx, &tgk, tgv_bytes, &tgv, &rerouted, stat).await?;
+            }
+        }
+
+        // Regions of the tables without any route, probably dropped ones.
+        for region in reported.into_values().flatten() {
+            let key = RegionInconsistencyKey {
+                node_id: stat.id,
+                region_id: region.id,
+            };
+            let value = RegionInconsistencyValue {
+                catalog_name: region.catalog.clone(),
+                schema_name: region.schema.clone(),
+                table_name: region.table.clone(),
+                region_number: region.id as u32,
+                kind: RegionInconsistencyKind::Unowned,
+                assigned_node_id: None,
+                detected_time_millis: now,
+            };
+            let _ = inconsistencies.insert(key, value);
+        }
+
+        gauge!(
+            METRIC_META_REGION_INCONSISTENCIES,
+            inconsistencies.len() as f64,
+            "node_id" => stat.id.to_string()
+        );
+        persist_inconsistencies(ctx, stat.id, inconsistencies).await
+    }
+}
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for RegionReconcileHandler {
+    fn is_acceptable(&self, role: Role) -> bool {
+        role == Role::Datanode
+    }
+
+    async fn handle(
+        &self,
+        _req: &HeartbeatRequest,
+        ctx: &mut Context,
+        acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        let Some(stat) = &acc.stat else { return Ok(()) };
+
+        if ctx.is_infancy || !self.should_reconcile(stat.id) {
+            return Ok(());
+        }
+
+        self.reconcile(stat, ctx).await
+    }
+}
+
+fn inconsistency_value(
+    tgk: &TableGlobalKey,
+    region_number: u32,
+    kind: RegionInconsistencyKind,
+    assigned_node_id: Option<u64>,
+    now: i64,
+) -> RegionInconsistencyValue {
+    RegionInconsistencyValue {
+        catalog_name: tgk.catalog_name.clone(),
+        schema_name: tgk.schema_name.clone(),
+        table_name: tgk.table_name.clone(),
+        region_number,
+        kind,
+        assigned_node_id,
+        detected_time_millis: now,
+    }
+}
+
+/// Returns the tables of the `schemas`, with the encoded table global values.
+async fn table_global_values(
+    ctx: &Context,
+    schemas: &HashSet<(String, String)>,
+) -> Result<Vec<(TableGlobalKey, Vec<u8>, TableGlobalValue)>> {
+    let mut tables = vec![];
+    for (catalog_name, schema_name) in schemas {
+        let key = build_table_global_prefix(catalog_name, schema_name).into_bytes();
+        let range_end = util::get_prefix_end_key(&key);
+        let req = RangeRequest {
+            key,
+            range_end,
+            ..Default::default()
+        };
+        let res = ctx.kv_store.range(req).await?;
+
+        for kv in res.kvs {
+            let key = String::from_utf8_lossy(&kv.key);
+            let Ok(tgk) = TableGlobalKey::parse(&key) else {
+                warn!("Invalid table global key: {}", key);
+                continue;
+            };
+            let tgv =
+                TableGlobalValue::from_bytes(&kv.value).context(error::InvalidCatalogValueSnafu)?;
+            tables.push((tgk, kv.value, tgv));
+        }
+    }
+    Ok(tables)
+}
+
+/// Returns the leader datanodes of the regions in the table route, keyed by the region numbers.
+async fn region_leaders(
+    ctx: &Context,
+    table_id: u32,
+    tgk: &TableGlobalKey,
+) -> Result<HashMap<u32, u64>> {
+    let trk = TableRouteKey::with_table_global_key(table_id as u64, tgk);
+    let Some(kv) = ctx.kv_store.get(trk.key().into_bytes()).await? else {
+        return Ok(HashMap::new());
+    };
+    let trv: TableRouteValue = kv
+        .value
+        .as_slice()
+        .try_into()
+        .context(error::DecodeTableRouteSnafu)?;
+
+    let mut leaders = HashMap::new();
+    let Some(table_route) = &trv.table_route else { return Ok(leaders) };
+    for region_route in &table_route.region_routes {
+        let Some(region) = &region_route.region else { continue };
+        if let Some(peer) = trv.peers.get(region_route.leader_peer_index as usize) {
+            let _ = leaders.insert(region.id as u32, peer.id);
+        }
+    }
+    Ok(leaders)
+}
+
+/// Routes the `region_numbers` of the table to the datanode of `stat`, which the table
+/// global value assigns them to.
+///
+/// The table global value is compared-and-put unchanged before the table route, so the route
+/// is not moved if the regions are reassigned concurrently, e.g. by a migration, and the route
+/// is compared-and-put against the value it's read from, so a concurrent change is never
+/// overwritten. Returns false if either value has changed, the repair is then retried in the
+/// next round.
+async fn repair_route(
+    ctx: &Context,
+    tgk: &TableGlobalKey,
+    tgv_bytes: Vec<u8>,
+    tgv: &TableGlobalValue,
+    region_numbers: &[u32],
+    stat: &Stat,
+) -> Result<bool> {
+    let trk = TableRouteKey::with_table_global_key(tgv.table_id() as u64, tgk);
+    let Some(trkv) = ctx.kv_store.get(trk.key().into_bytes()).await? else {
+        warn!("Table route {} not found, skip repairing it", trk.key());
+        return Ok(false);
+    };
+    let mut trv: TableRouteValue = trkv
+        .value
+        .as_slice()
+        .try_into()
+        .context(error::DecodeTableRouteSnafu)?;
+    let peer_index = match trv.peers.iter().position(|peer| peer.id == stat.id) {
+        Some(index) => index,
+        None => {
+            trv.peers.push(Peer {
+                id: stat.id,
+                addr: stat.addr.clone(),
+            });
+            trv.peers.len() - 1
+        }
+    };
+    if let Some(table_route) = &mut trv.table_route {
+        for region_route in &mut table_route.region_routes {
+            let Some(region) = &region_route.region else { continue };
+            if region_numbers.contains(&(region.id as u32)) {
+                region_route.leader_peer_index = peer_index as u64;
+            }
+        }
+    }
+
+    let tgv_put = compare_and_put(
+        ctx,
+        tgk.to_string().into_bytes(),
+        tgv_bytes.clone(),
+        tgv_bytes,
+    )
+    .await?;
+    if !tgv_put {
+        warn!(
+            "Table global value of {} changed, skip repairing its route",
+            tgk
+        );
+        return Ok(false);
+    }
+    if !compare_and_put(ctx, trkv.key, trkv.value, trv.into()).await? {
+        warn!("Table route {} changed, skip repairing it", trk.key());
+        return Ok(false);
+    }
+
+    increment_counter!(METRIC_META_ROUTE_REPAIRED);
+    info!(
+        "Repaired the route of table {}, regions {:?} are routed to datanode {}",
+        tgk, region_numbers, stat.id
+    );
+    Ok(true)
+}
+
+async fn compare_and_put(
+    ctx: &Context,
+    key: Vec<u8>,
+    expect: Vec<u8>,
+    value: Vec<u8>,
+) -> Result<bool> {
+    let req = CompareAndPutRequest {
+        key,
+        expect,
+        value,
+        ..Default::default()
+    };
+    Ok(ctx.kv_store.compare_and_put(req).await?.success)
+}
+
+/// Replaces the inconsistencies recorded for the datanode `node_id`, keeping the detected
+/// time of the ones that were already recorded.
+async fn persist_inconsistencies(
+    ctx: &Context,
+    node_id: u64,
+    mut inconsistencies: HashMap<RegionInconsistencyKey, RegionInconsistencyValue>,
+) -> Result<()> {
+    let key = build_region_inconsistency_prefix(Some(node_id)).into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+    let res = ctx.kv_store.range(req).await?;
+
+    let mut resolved = vec![];
+    for kv in res.kvs {
+        let Ok(key) = RegionInconsistencyKey::parse(String::from_utf8_lossy(&kv.key)) else { continue };
+        match inconsistencies.get_mut(&key) {
+            Some(value) => {
+                if let Ok(recorded) = RegionInconsistencyValue::from_bytes(&kv.value) {
+                    if recorded.kind == value.kind {
+                        value.detected_time_millis = recorded.detected_time_millis;
+                    }
+                }
+            }
+            None => resolved.push(kv.key),
+        }
+    }
+
+    if !resolved.is_empty() {
+        let req = BatchDeleteRequest {
+            keys: resolved,
+            ..Default::default()
+        };
+        let _ = ctx.kv_store.batch_delete(req).await?;
+    }
+
+    if !inconsistencies.is_empty() {
+        let mut kvs = Vec::with_capacity(inconsistencies.len());
+        for (key, value) in inconsistencies {
+            kvs.push(KeyValue {
+                key: key.to_string().into_bytes(),
+                value: value.as_bytes().context(error::InvalidCatalogValueSnafu)?,
+            });
+        }
+        let req = BatchPutRequest {
+            kvs,
+            ..Default::default()
+        };
+        let _ = ctx.kv_store.batch_put(req).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::{Region, RegionRoute, TableRoute};
+
+    use super::*;
+    use crate::handler::HeartbeatMailbox;
+    use crate::sequence::Sequence;
+    use crate::service::router::get_table_route_value;
+    use crate::service::store::memory::MemStore;
+
+    const TABLE_GLOBAL_VALUE: &str = r#"{"node_id":1,"regions_id_map":{"1":[0,1],"2":[2]},"table_info":{"ident":{"table_id":1024,"version":1},"name":"monitor","desc":null,"catalog_name":"greptime","schema_name":"public","meta":{"schema":{"column_schemas":[],"timestamp_index":null,"version":0},"primary_key_indices":[],"value_indices":[],"engine":"mito","next_column_id":0,"region_numbers":[0,1,2],"engine_options":{},"options":{},"created_on":"1970-01-01T00:00:00Z"},"table_type":"Base"}}"#;
+
+    fn region_stat(number: u32) -> RegionStat {
+        RegionStat {
+            id: region_id(1024, number),
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "monitor".to_string(),
+            rcus: 0,
+            wcus: 0,
+            approximate_bytes: 0,
+            approximate_rows: 0,
+        }
+    }
+
+    async fn reconcile(handler: &RegionReconcileHandler, ctx: &mut Context, numbers: &[u32]) {
+        let mut acc = HeartbeatAccumulator {
+            stat: Some(Stat {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+                region_stats: numbers.iter().map(|n| region_stat(*n)).collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        handler
+            .handle(&HeartbeatRequest::default(), ctx, &mut acc)
+            .await
+            .unwrap();
+    }
+
+    async fn inconsistencies(
+        ctx: &Context,
+    ) -> Vec<(RegionInconsistencyKey, RegionInconsistencyValue)> {
+        let key = build_region_inconsistency_prefix(Some(1)).into_bytes();
+        let range_end = util::get_prefix_end_key(&key);
+        let req = RangeRequest {
+            key,
+            range_end,
+            ..Default::default()
+        };
+        let res = ctx.kv_store.range(req).await.unwrap();
+        let mut inconsistencies = res
+            .kvs
+            .into_iter()
+            .map(|kv| {
+                (
+                    RegionInconsistencyKey::parse(String::from_utf8_lossy(&kv.key)).unwrap(),
+                    RegionInconsistencyValue::from_bytes(&kv.value).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        inconsistencies.sort_by_key(|(key, _)| key.region_id);
+        inconsistencies
+    }
+
+    /// Creates the context with table `monitor` of 3 regions, in which regions 0 and 1 are
+    /// routed to datanode 1, and region 2 to datanode 2.
+    async fn create_context(table_global_value: &str) -> (Context, TableGlobalKey) {
+        let in_memory = Arc::new(MemStore::new());
+        let kv_store = Arc::new(MemStore::new());
+        let seq = Sequence::new("test_seq", 0, 10, kv_store.clone());
+        let mailbox = HeartbeatMailbox::create(Arc::new(Default::default()), seq);
+        let ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory,
+            kv_store: kv_store.clone(),
+            mailbox,
+            election: None,
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+            is_infancy: false,
+        };
+
+        let tgk = TableGlobalKey {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "monitor".to_string(),
+        };
+        let region_routes = (0..3)
+            .map(|i| RegionRoute {
+                region: Some(Region {
+                    id: i,
+                    ..Default::default()
+                }),
+                leader_peer_index: i / 2,
+                follower_peer_indexes: vec![],
+            })
+            .collect();
+        let trv = TableRouteValue {
+            peers: vec![
+                Peer {
+                    id: 1,
+                    addr: "127.0.0.1:3001".to_string(),
+                },
+                Peer {
+                    id: 2,
+                    addr: "127.0.0.1:3002".to_string(),
+                },
+            ],
+            table_route: Some(TableRoute {
+                table: None,
+                region_routes,
+            }),
+        };
+        let trk = TableRouteKey::with_table_global_key(1024, &tgk);
+        let req = BatchPutRequest {
+            kvs: vec![
+                KeyValue {
+                    key: tgk.to_string().into_bytes(),
+                    value: table_global_value.as_bytes().to_vec(),
+                },
+                KeyValue {
+                    key: trk.key().into_bytes(),
+                    value: trv.into(),
+                },
+            ],
+            ..Default::default()
+        };
+        let _ = kv_store.batch_put(req).await.unwrap();
+        (ctx, tgk)
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_regions() {
+        let (mut ctx, tgk) = create_context(TABLE_GLOBAL_VALUE).await;
+        let trk = TableRouteKey::with_table_global_key(1024, &tgk);
+
+        // Datanode 1 serves region 2 of datanode 2, which has no lease, but not its region 1.
+        let handler = RegionReconcileHandler::new(Duration::ZERO);
+        reconcile(&handler, &mut ctx, &[0, 2]).await;
+
+        let inconsistencies = inconsistencies(&ctx).await;
+        assert_eq!(2, inconsistencies.len());
+        let (key, value) = &inconsistencies[0];
+        assert_eq!(region_id(1024, 1), key.region_id);
+        assert_eq!(RegionInconsistencyKind::Missing, value.kind);
+        assert_eq!(Some(1), value.assigned_node_id);
+        let (key, value) = &inconsistencies[1];
+        assert_eq!(region_id(1024, 2), key.region_id);
+        assert_eq!(RegionInconsistencyKind::Unowned, value.kind);
+        assert_eq!(Some(2), value.assigned_node_id);
+
+        // The unowned region is not reassigned, as datanode 2 may still serve it.
+        let kv = ctx
+            .kv_store
+            .get(tgk.to_string().into_bytes())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(TABLE_GLOBAL_VALUE.as_bytes(), kv.value.as_slice());
+        let trv = get_table_route_value(&ctx.kv_store, &trk).await.unwrap();
+        let region_routes = trv.table_route.unwrap().region_routes;
+        assert_eq!(2, trv.peers[region_routes[2].leader_peer_index as usize].id);
+
+        // The datanode opened the missing region and closed the unowned one, all
+        // inconsistencies are resolved.
+        reconcile(&handler, &mut ctx, &[0, 1]).await;
+        assert!(inconsistencies(&ctx).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_migrated_region() {
+        // Region 2 is migrated to datanode 1, but still routed to datanode 2.
+        let tgv = TABLE_GLOBAL_VALUE.replace(r#"{"1":[0,1],"2":[2]}"#, r#"{"1":[0,1,2]}"#);
+        let (mut ctx, tgk) = create_context(&tgv).await;
+        let trk = TableRouteKey::with_table_global_key(1024, &tgk);
+        let handler = RegionReconcileHandler::new(Duration::ZERO);
+
+        // The route is not moved until datanode 1 serves the region.
+        reconcile(&handler, &mut ctx, &[0, 1]).await;
+        let trv = get_table_route_value(&ctx.kv_store, &trk).await.unwrap();
+        let region_routes = trv.table_route.unwrap().region_routes;
+        assert_eq!(2, trv.peers[region_routes[2].leader_peer_index as usize].id);
+
+        reconcile(&handler, &mut ctx, &[0, 1, 2]).await;
+        let trv = get_table_route_value(&ctx.kv_store, &trk).await.unwrap();
+        let region_routes = trv.table_route.unwrap().region_routes;
+        assert_eq!(1, trv.peers[region_routes[2].leader_peer_index as usize].id);
+        assert!(inconsistencies(&ctx).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_repair_changed_route() {
+        let (ctx, tgk) = create_context(TABLE_GLOBAL_VALUE).await;
+        let trk = TableRouteKey::with_table_global_key(1024, &tgk);
+        let stat = Stat {
+            id: 1,
+            addr: "127.0.0.1:3001".to_string(),
+            ..Default::default()
+        };
+
+        // The table global value is changed after it's read, e.g. by a migration.
+        let stale = ctx
+            .kv_store
+            .get(tgk.to_string().into_bytes())
+            .await
+            .unwrap()
+            .unwrap()
+            .value;
+        let changed =
+            TABLE_GLOBAL_VALUE.replace(r#"{"1":[0,1],"2":[2]}"#, r#"{"1":[0],"2":[1,2]}"#);
+        let req = BatchPutRequest {
+            kvs: vec![KeyValue {
+                key: tgk.to_string().into_bytes(),
+                value: changed.as_bytes().to_vec(),
+            }],
+            ..Default::default()
+        };
+        let _ = ctx.kv_store.batch_put(req).await.unwrap();
+
+        let tgv = TableGlobalValue::from_bytes(&stale).unwrap();
+        assert!(!repair_route(&ctx, &tgk, stale, &tgv, &[2], &stat)
+            .await
+            .unwrap());
+
+        // Neither value is overwritten.
+        let kv = ctx
+            .kv_store
+            .get(tgk.to_string().into_bytes())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(changed.as_bytes(), kv.value.as_slice());
+        let trv = get_table_route_value(&ctx.kv_store, &trk).await.unwrap();
+        let region_routes = trv.table_route.unwrap().region_routes;
+        assert_eq!(2, trv.peers[region_routes[2].leader_peer_index as usize].id);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_served_schemas_only() {
+        let (mut ctx, _) = create_context(TABLE_GLOBAL_VALUE).await;
+        let handler = RegionReconcileHandler::new(Duration::ZERO);
+
+        // Datanode 1 serves no region of schema `public`, so its tables are left to the
+        // datanode's own reconcile loop.
+        let mut acc = HeartbeatAccumulator {
+            stat: Some(Stat {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        handler
+            .handle(&HeartbeatRequest::default(), &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        assert!(inconsistencies(&ctx).await.is_empty());
+    }
+}
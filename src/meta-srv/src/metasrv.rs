@@ -17,15 +17,21 @@ pub mod builder;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use api::v1::meta::Peer;
+use api::v1::meta::{BatchDeleteRequest, Peer, PutRequest, RangeRequest};
+use catalog::cluster::{disk_low_warnings, lease_millis, DEFAULT_REPORT_INTERVAL, VERSION};
+use catalog::helper::{build_cluster_node_prefix, ClusterNodeKey, ClusterNodeValue, NodeRole};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_procedure::ProcedureManagerRef;
 use common_telemetry::logging::LoggingOptions;
 use common_telemetry::{error, info, warn};
+use common_time::util as time_util;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use servers::health_checker::HealthChecker;
 use servers::http::HttpOptions;
 use snafu::ResultExt;
 use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
 
 use crate::cluster::MetaPeerClient;
 use crate::election::{Election, LeaderChangeMessage};
@@ -37,6 +43,7 @@ use crate::selector::{Selector, SelectorType};
 use crate::sequence::SequenceRef;
 use crate::service::mailbox::MailboxRef;
 use crate::service::store::kv::{KvStoreRef, ResettableKvStoreRef};
+use crate::util;
 
 pub const TABLE_ID_SEQ: &str = "table_id";
 
@@ -119,6 +126,8 @@ pub struct MetaSrv {
     procedure_manager: ProcedureManagerRef,
     metadata_service: MetadataServiceRef,
     mailbox: MailboxRef,
+    /// Task reporting the info of this metasrv, aborted on shutdown.
+    node_info_reporter: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 impl MetaSrv {
@@ -133,6 +142,7 @@ impl MetaSrv {
         }
 
         self.create_default_schema_if_not_exist().await?;
+        self.start_node_info_reporter();
 
         if let Some(election) = self.election() {
             let procedure_manager = self.procedure_manager.clone();
@@ -194,8 +204,58 @@ impl MetaSrv {
             .await
     }
 
+    /// Reports the info of this metasrv periodically, like what the frontends and datanodes
+    /// do, so that it could be listed in `information_schema.cluster_info`. The info of nodes
+    /// whose lease has expired is removed meanwhile.
+    fn start_node_info_reporter(&self) {
+        let kv_store = self.kv_store.clone();
+        let started = self.started.clone();
+        let key = ClusterNodeKey {
+            role: NodeRole::Metasrv,
+            addr: self.options.server_addr.clone(),
+        }
+        .to_string();
+        let now = time_util::current_time_millis();
+        let mut value = ClusterNodeValue {
+            node_id: None,
+            version: VERSION.to_string(),
+            start_time_millis: now,
+            last_activity_millis: now,
+            disk_low: false,
+            lease_millis: lease_millis(DEFAULT_REPORT_INTERVAL),
+        };
+        let handle = common_runtime::spawn_bg(async move {
+            while started.load(Ordering::Relaxed) {
+                value.last_activity_millis = time_util::current_time_millis();
+                match value.as_bytes() {
+                    Ok(bytes) => {
+                        let req = PutRequest {
+                            key: key.clone().into_bytes(),
+                            value: bytes,
+                            ..Default::default()
+                        };
+                        if let Err(e) = kv_store.put(req).await {
+                            warn!("Failed to report node info, key: {}, error: {}", key, e);
+                        }
+                    }
+                    Err(e) => error!("Failed to serialize node info, error: {}", e),
+                }
+                if let Err(e) = remove_expired_cluster_nodes(&kv_store).await {
+                    warn!("Failed to remove expired node info, error: {}", e);
+                }
+                tokio::time::sleep(DEFAULT_REPORT_INTERVAL).await;
+            }
+        });
+        if let Some(old) = self.node_info_reporter.lock().replace(handle) {
+            old.abort();
+        }
+    }
+
     pub fn shutdown(&self) {
         self.started.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.node_info_reporter.lock().take() {
+            handle.abort();
+        }
     }
 
     #[inline]
@@ -272,3 +332,200 @@ impl MetaSrv {
         }
     }
 }
+
+/// Reports the nodes in the cluster running out of disk space.
+#[async_trait::async_trait]
+impl HealthChecker for MetaSrv {
+    async fn warnings(&self) -> Vec<String> {
+        let now = time_util::current_time_millis();
+        match cluster_nodes(&self.kv_store).await {
+            Ok(nodes) => {
+                let nodes = nodes
+                    .into_iter()
+                    .filter_map(|(_, key, value)| Some((key, value?)))
+                    .filter(|(_, value)| !value.is_expired(now))
+                    .collect::<Vec<_>>();
+                disk_low_warnings(&nodes)
+            }
+            Err(e) => vec![format!("Failed to list the nodes in the cluster: {e}")],
+        }
+    }
+}
+
+/// Returns the info of the nodes in the cluster with their raw keys, the info that can't be
+/// decoded is `None`.
+async fn cluster_nodes(
+    kv_store: &KvStoreRef,
+) -> Result<Vec<(Vec<u8>, ClusterNodeKey, Option<ClusterNodeValue>)>> {
+    let key = build_cluster_node_prefix().into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+    let res = kv_store.range(req).await?;
+
+    let mut nodes = Vec::with_capacity(res.kvs.len());
+    for kv in res.kvs {
+        let Ok(key) = ClusterNodeKey::parse(String::from_utf8_lossy(&kv.key)) else { continue };
+        let value = match ClusterNodeValue::from_bytes(&kv.value) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Failed to decode node info, key: {}, error: {}", key, e);
+                None
+            }
+        };
+        nodes.push((kv.key, key, value));
+    }
+    Ok(nodes)
+}
+
+/// Removes the info of the nodes whose lease has expired, they have stopped reporting. The
+/// info that can't be decoded is removed too, or it would never expire.
+async fn remove_expired_cluster_nodes(kv_store: &KvStoreRef) -> Result<()> {
+    let now = time_util::current_time_millis();
+    let mut keys = Vec::new();
+    for (raw_key, key, value) in cluster_nodes(kv_store).await? {
+        match value {
+            Some(value) if !value.is_expired(now) => {}
+            Some(_) => {
+                info!("Remove expired node info, key: {}", key);
+                keys.push(raw_key);
+            }
+            None => {
+                info!("Remove undecodable node info, key: {}", key);
+                keys.push(raw_key);
+            }
+        }
+    }
+    if !keys.is_empty() {
+        let _ = kv_store
+            .batch_delete(BatchDeleteRequest {
+                keys,
+                ..Default::default()
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metasrv::builder::MetaSrvBuilder;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_remove_expired_cluster_nodes() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::default());
+        let now = time_util::current_time_millis();
+        let put = |addr: &str, last_activity_millis, lease_millis| {
+            let kv_store = kv_store.clone();
+            let key = ClusterNodeKey {
+                role: NodeRole::Frontend,
+                addr: addr.to_string(),
+            };
+            let value = ClusterNodeValue {
+                node_id: None,
+                version: VERSION.to_string(),
+                start_time_millis: 0,
+                last_activity_millis,
+                disk_low: false,
+                lease_millis,
+            };
+            async move {
+                let _ = kv_store
+                    .put(PutRequest {
+                        key: key.to_string().into_bytes(),
+                        value: value.as_bytes().unwrap(),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap();
+            }
+        };
+        put("alive", now, 15000).await;
+        put("expired", now - 20000, 15000).await;
+        // Reported by an older version without the lease.
+        put("no_lease", now - 20000, 0).await;
+        let _ = kv_store
+            .put(PutRequest {
+                key: ClusterNodeKey {
+                    role: NodeRole::Frontend,
+                    addr: "undecodable".to_string(),
+                }
+                .to_string()
+                .into_bytes(),
+                value: b"invalid".to_vec(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        remove_expired_cluster_nodes(&kv_store).await.unwrap();
+
+        let key = build_cluster_node_prefix().into_bytes();
+        let range_end = util::get_prefix_end_key(&key);
+        let res = kv_store
+            .range(RangeRequest {
+                key,
+                range_end,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let mut addrs = res
+            .kvs
+            .iter()
+            .map(|kv| {
+                ClusterNodeKey::parse(String::from_utf8_lossy(&kv.key))
+                    .unwrap()
+                    .addr
+            })
+            .collect::<Vec<_>>();
+        addrs.sort();
+        assert_eq!(vec!["alive", "no_lease"], addrs);
+    }
+
+    #[tokio::test]
+    async fn test_health_warnings() {
+        let meta_srv = MetaSrvBuilder::new().build().await;
+        assert!(meta_srv.warnings().await.is_empty());
+
+        let now = time_util::current_time_millis();
+        for (addr, disk_low, last_activity_millis) in [
+            ("127.0.0.1:3001", true, now),
+            ("127.0.0.1:3002", false, now),
+            // Expired nodes are not reported.
+            ("127.0.0.1:3003", true, now - 60000),
+        ] {
+            let key = ClusterNodeKey {
+                role: NodeRole::Datanode,
+                addr: addr.to_string(),
+            };
+            let value = ClusterNodeValue {
+                node_id: Some(1),
+                version: VERSION.to_string(),
+                start_time_millis: 0,
+                last_activity_millis,
+                disk_low,
+                lease_millis: 15000,
+            };
+            let _ = meta_srv
+                .kv_store()
+                .put(PutRequest {
+                    key: key.to_string().into_bytes(),
+                    value: value.as_bytes().unwrap(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            vec!["Disk space of datanode 127.0.0.1:3001 is below the low watermark".to_string()],
+            meta_srv.warnings().await
+        );
+    }
+}
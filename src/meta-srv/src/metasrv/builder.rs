@@ -16,13 +16,14 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use common_procedure::local::{LocalManager, ManagerConfig};
+use parking_lot::Mutex;
 
 use crate::cluster::MetaPeerClient;
 use crate::handler::mailbox_handler::MailboxHandler;
 use crate::handler::{
     CheckLeaderHandler, CollectStatsHandler, HeartbeatHandlerGroup, HeartbeatMailbox,
     KeepLeaseHandler, OnLeaderStartHandler, PersistStatsHandler, RegionFailureHandler,
-    ResponseHeaderHandler,
+    RegionReconcileHandler, ResponseHeaderHandler,
 };
 use crate::lock::DistLockRef;
 use crate::metadata_service::{DefaultMetadataService, MetadataServiceRef};
@@ -147,6 +148,8 @@ impl MetaSrvBuilder {
                 group.add_handler(CollectStatsHandler).await;
                 group.add_handler(MailboxHandler).await;
                 group.add_handler(region_failure_handler).await;
+                // Must be in front of `PersistStatsHandler`, which takes the stat away.
+                group.add_handler(RegionReconcileHandler::default()).await;
                 group.add_handler(PersistStatsHandler::default()).await;
                 group
             }
@@ -178,6 +181,7 @@ impl MetaSrvBuilder {
             procedure_manager,
             metadata_service,
             mailbox,
+            node_info_reporter: Arc::new(Mutex::new(None)),
         }
     }
 }
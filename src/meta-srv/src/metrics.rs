@@ -14,3 +14,5 @@
 
 pub(crate) const METRIC_META_CREATE_CATALOG: &str = "meta.create_catalog";
 pub(crate) const METRIC_META_CREATE_SCHEMA: &str = "meta.create_schema";
+pub(crate) const METRIC_META_REGION_INCONSISTENCIES: &str = "meta.region_inconsistencies";
+pub(crate) const METRIC_META_ROUTE_REPAIRED: &str = "meta.route_repaired";
@@ -346,7 +346,7 @@ async fn fetch_tables(
     Ok(tables)
 }
 
-async fn get_table_route_value(
+pub(crate) async fn get_table_route_value(
     kv_store: &KvStoreRef,
     key: &TableRouteKey<'_>,
 ) -> Result<TableRouteValue> {
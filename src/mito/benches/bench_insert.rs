@@ -0,0 +1,91 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks the cost of the table layer on small inserts at high request rates.
+//!
+//! Run with `cargo bench -p mito --features test --bench bench_insert`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+use mito::table::test_util::{self, TestEngineComponents, TABLE_NAME};
+use table::requests::InsertRequest;
+use tokio::runtime::Runtime;
+
+/// Number of concurrent inserts in a batch.
+const CONCURRENCY: usize = 64;
+
+fn new_one_row_request(i: i64) -> InsertRequest {
+    let columns_values: HashMap<String, VectorRef> = HashMap::from([
+        (
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1"])) as VectorRef,
+        ),
+        (
+            "cpu".to_string(),
+            Arc::new(Float64Vector::from_vec(vec![1.0])) as VectorRef,
+        ),
+        (
+            "memory".to_string(),
+            Arc::new(Float64Vector::from_vec(vec![1.0])) as VectorRef,
+        ),
+        (
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![i])) as VectorRef,
+        ),
+    ]);
+    test_util::new_insert_request(TABLE_NAME.to_string(), columns_values)
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let TestEngineComponents {
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = runtime.block_on(test_util::setup_test_engine_and_table());
+
+    let mut group = c.benchmark_group("mito_insert");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("one_row", |b| {
+        let mut ts = 0;
+        b.iter(|| {
+            ts += 1;
+            runtime
+                .block_on(table.insert(new_one_row_request(ts)))
+                .unwrap()
+        })
+    });
+
+    group.throughput(Throughput::Elements(CONCURRENCY as u64));
+    group.bench_function("concurrent_one_row", |b| {
+        let mut ts = 0;
+        b.iter(|| {
+            let inserts = (0..CONCURRENCY).map(|_| {
+                ts += 1;
+                let table = table.clone();
+                let request = new_one_row_request(ts);
+                runtime.spawn(async move { table.insert(request).await.unwrap() })
+            });
+            let inserts = inserts.collect::<Vec<_>>();
+            runtime.block_on(futures::future::join_all(inserts))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert);
+criterion_main!(benches);
@@ -47,8 +47,8 @@ use crate::config::EngineConfig;
 use crate::engine::procedure::{AlterMitoTable, CreateMitoTable, DropMitoTable, TableCreator};
 use crate::error::{
     BuildColumnDescriptorSnafu, BuildColumnFamilyDescriptorSnafu, BuildRowKeyDescriptorSnafu,
-    InvalidPrimaryKeySnafu, MissingTimestampIndexSnafu, RegionNotFoundSnafu, Result,
-    TableExistsSnafu,
+    InvalidColumnEncodingSnafu, InvalidPrimaryKeySnafu, MissingTimestampIndexSnafu,
+    RegionNotFoundSnafu, Result, TableExistsSnafu,
 };
 use crate::manifest::TableManifest;
 use crate::metrics;
@@ -378,6 +378,26 @@ fn validate_create_table_request(request: &CreateTableRequest) -> Result<()> {
         }
     );
 
+    for (column_name, encoding) in &request.table_options.column_encodings {
+        let column = request
+            .schema
+            .column_schemas
+            .iter()
+            .find(|column| column.name == *column_name)
+            .with_context(|| InvalidColumnEncodingSnafu {
+                msg: format!("column {column_name} not found"),
+            })?;
+        ensure!(
+            encoding.supports(&column.data_type),
+            InvalidColumnEncodingSnafu {
+                msg: format!(
+                    "encoding {encoding} doesn't support column {column_name} of type {:?}",
+                    column.data_type
+                ),
+            }
+        );
+    }
+
     Ok(())
 }
 
@@ -429,6 +449,11 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                     .map(|s| s.0 as usize),
                 ttl: table_info.meta.options.ttl,
                 compaction_time_window: table_info.meta.options.compaction_time_window,
+                flush_rows: table_info.meta.options.flush_rows,
+                flush_interval: table_info.meta.options.flush_interval,
+                append_mode: table_info.meta.options.append_mode,
+                compact_strings: table_info.meta.options.compact_strings,
+                column_encodings: table_info.meta.options.column_encodings.clone(),
             };
 
             debug!(
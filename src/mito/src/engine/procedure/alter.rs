@@ -249,6 +249,10 @@ impl<S: StorageEngine> AlterMitoTable<S> {
             .await
             .context(UpdateTableManifestSnafu { table_name })?;
 
+        if let AlterKind::SetOptions { .. } = &self.data.request.alter_kind {
+            self.table.set_regions_ttl(new_info.meta.options.ttl).await;
+        }
+
         // Update in memory metadata of the table.
         self.table.set_table_info(new_info.clone());
 
@@ -223,17 +223,32 @@ impl<S: StorageEngine> TableCreator<S> {
         let write_buffer_size = table_options.write_buffer_size.map(|size| size.0 as usize);
         let ttl = table_options.ttl;
         let compaction_time_window = table_options.compaction_time_window;
+        let flush_rows = table_options.flush_rows;
+        let flush_interval = table_options.flush_interval;
+        let append_mode = table_options.append_mode;
+        let compact_strings = table_options.compact_strings;
+        let column_encodings = &table_options.column_encodings;
         let open_opts = OpenOptions {
             parent_dir: table_dir.to_string(),
             write_buffer_size,
             ttl,
             compaction_time_window,
+            flush_rows,
+            flush_interval,
+            append_mode,
+            compact_strings,
+            column_encodings: column_encodings.clone(),
         };
         let create_opts = CreateOptions {
             parent_dir: table_dir.to_string(),
             write_buffer_size,
             ttl,
             compaction_time_window,
+            flush_rows,
+            flush_interval,
+            append_mode,
+            compact_strings,
+            column_encodings: column_encodings.clone(),
         };
 
         let primary_key_indices = &self.data.request.primary_key_indices;
@@ -14,6 +14,8 @@
 
 //! Tests for mito table engine.
 
+use std::time::Duration;
+
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::physical_plan::SessionContext;
 use common_recordbatch::util;
@@ -30,12 +32,13 @@ use storage::config::EngineConfig as StorageEngineConfig;
 use storage::region::RegionImpl;
 use storage::EngineImpl;
 use store_api::manifest::Manifest;
-use store_api::storage::ReadContext;
+use store_api::storage::{ColumnEncoding, ColumnEncodings, ReadContext};
 use table::engine::region_id;
 use table::metadata::TableType;
 use table::requests::{
     AddColumnRequest, AlterKind, DeleteRequest, FlushTableRequest, TableOptions,
 };
+use table::table::TableSnapshot;
 use table::Table;
 
 use super::*;
@@ -226,6 +229,22 @@ fn test_validate_create_table_request() {
 
     request.primary_key_indices = vec![0];
     assert!(validate_create_table_request(&request).is_ok());
+
+    request.table_options.column_encodings =
+        ColumnEncodings::from([("ts".to_string(), ColumnEncoding::Delta)]);
+    assert!(validate_create_table_request(&request).is_ok());
+
+    request.table_options.column_encodings =
+        ColumnEncodings::from([("name".to_string(), ColumnEncoding::Delta)]);
+    let err = validate_create_table_request(&request).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("encoding delta doesn't support column name"));
+
+    request.table_options.column_encodings =
+        ColumnEncodings::from([("cpu".to_string(), ColumnEncoding::ByteStreamSplit)]);
+    let err = validate_create_table_request(&request).unwrap_err();
+    assert!(err.to_string().contains("column cpu not found"));
 }
 
 #[tokio::test]
@@ -851,3 +870,342 @@ async fn test_flush_table_with_region_id() {
 
     assert!(has_parquet_file(&region_dir));
 }
+
+#[tokio::test]
+async fn test_collect_table_statistics() {
+    let TestEngineComponents {
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    assert!(table.statistics().is_none());
+    let plan = table.scan(None, &[], None).await.unwrap();
+    assert_eq!(None, plan.statistics().num_rows);
+
+    setup_table(table.clone()).await;
+
+    let stats = table.collect_statistics().await.unwrap();
+    assert_eq!(4, stats.num_rows);
+    assert!(stats.total_byte_size > 0);
+    // Distinct count is only collected for the tag column `host`.
+    assert_eq!(Some(4), stats.columns["host"].distinct_count);
+    assert_eq!(None, stats.columns["cpu"].distinct_count);
+    assert_eq!(0, stats.columns["cpu"].null_count);
+    assert_eq!(Some(stats.clone()), table.statistics());
+
+    // The statistics are attached to the scan plan.
+    let plan = table.scan(Some(&vec![0]), &[], None).await.unwrap();
+    let plan_stats = plan.statistics();
+    assert_eq!(Some(4), plan_stats.num_rows);
+    let column_stats = plan_stats.column_statistics.unwrap();
+    assert_eq!(1, column_stats.len());
+    assert_eq!(Some(4), column_stats[0].distinct_count);
+}
+
+#[tokio::test]
+async fn test_scan_table_snapshot() {
+    let TestEngineComponents {
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    setup_table(table.clone()).await;
+    let snapshot = table.snapshot().await.unwrap();
+    assert_eq!(1, snapshot.sequences.len());
+    assert_eq!(1, snapshot.versions.len());
+
+    // Overwrites host1 and flushes the new rows.
+    let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+    let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host5"]));
+    let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![10.0, 5.0]));
+    let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![10.0, 5.0]));
+    let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 5]));
+    columns_values.insert("host".to_string(), hosts);
+    columns_values.insert("cpu".to_string(), cpus);
+    columns_values.insert("memory".to_string(), memories);
+    columns_values.insert("ts".to_string(), tss);
+    let insert_req = new_insert_request("demo".to_string(), columns_values);
+    assert_eq!(2, table.insert(insert_req).await.unwrap());
+    table.flush(None, Some(true)).await.unwrap();
+
+    let session_ctx = SessionContext::new();
+    let stream = table
+        .scan_snapshot(None, &[], None, &snapshot)
+        .await
+        .unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect_batches(stream).await.unwrap();
+    assert_eq!(
+        batches.pretty_print().unwrap(),
+        "\
++-------+-----+--------+-------------------------+
+| host  | cpu | memory | ts                      |
++-------+-----+--------+-------------------------+
+| host1 | 1.0 | 1.0    | 1970-01-01T00:00:00.001 |
+| host2 | 2.0 | 2.0    | 1970-01-01T00:00:00.002 |
+| host3 | 3.0 | 3.0    | 1970-01-01T00:00:00.002 |
+| host4 | 4.0 | 4.0    | 1970-01-01T00:00:00.001 |
++-------+-----+--------+-------------------------+"
+    );
+
+    // The latest data are visible to normal scans.
+    let stream = table.scan(None, &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect_batches(stream).await.unwrap();
+    assert_eq!(5, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+
+    // Regions unknown to the snapshot are not readable.
+    let snapshot = TableSnapshot::default();
+    assert!(table
+        .scan_snapshot(None, &[], None, &snapshot)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_scan_table_snapshot_after_alter() {
+    let TestEngineComponents {
+        table_engine,
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    setup_table(table.clone()).await;
+    let snapshot = table.snapshot().await.unwrap();
+
+    let new_tag = ColumnSchema::new("my_tag", ConcreteDataType::string_datatype(), true);
+    let new_field = ColumnSchema::new("my_field", ConcreteDataType::string_datatype(), true);
+    let req = new_add_columns_req(&new_tag, &new_field);
+    let table = table_engine
+        .alter_table(&EngineContext::default(), req)
+        .await
+        .unwrap();
+
+    // Regions are altered after the snapshot is taken.
+    let err = table
+        .scan_snapshot(None, &[], None, &snapshot)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, table::error::Error::SnapshotVersionMismatch { .. }),
+        "unexpected error: {err:?}"
+    );
+
+    let snapshot = table.snapshot().await.unwrap();
+    table
+        .scan_snapshot(None, &[], None, &snapshot)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_scan_table_after_alter_refreshes_schema_cache() {
+    let TestEngineComponents {
+        table_engine,
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    setup_table(table.clone()).await;
+    let new_tag = ColumnSchema::new("my_tag", ConcreteDataType::string_datatype(), true);
+    let new_field = ColumnSchema::new("my_field", ConcreteDataType::string_datatype(), true);
+    let req = new_add_columns_req(&new_tag, &new_field);
+    let table = table_engine
+        .alter_table(&EngineContext::default(), req)
+        .await
+        .unwrap();
+
+    let mito_table = table
+        .as_any()
+        .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+        .unwrap();
+    assert_eq!(
+        table.table_info().ident.version,
+        mito_table.schema_cache_version()
+    );
+
+    // Projects the added columns, which the schema cache must know about.
+    let schema = table.schema();
+    let projection = vec![
+        schema.column_index_by_name("host").unwrap(),
+        schema.column_index_by_name("my_tag").unwrap(),
+        schema.column_index_by_name("my_field").unwrap(),
+    ];
+    let session_ctx = SessionContext::new();
+    let stream = table.scan(Some(&projection), &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect(stream).await.unwrap();
+    assert!(!batches.is_empty());
+    assert_eq!(
+        &["host", "my_tag", "my_field"][..],
+        batches[0]
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column_schema| column_schema.name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    // Projecting a column out of the table schema is an error instead of a panic.
+    let projection = vec![schema.num_columns()];
+    assert!(table.scan(Some(&projection), &[], None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_close_idle_table() {
+    let TestEngineComponents {
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    setup_table(table.clone()).await;
+    let mito_table = table
+        .as_any()
+        .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+        .unwrap();
+
+    // The table is just written.
+    assert!(!table
+        .close_if_idle(Duration::from_secs(3600))
+        .await
+        .unwrap());
+    assert!(table.close_if_idle(Duration::ZERO).await.unwrap());
+    assert!(mito_table.is_idle_closed());
+    // Already closed.
+    assert!(!table.close_if_idle(Duration::ZERO).await.unwrap());
+
+    // Scanning reopens the table, rows are flushed before closing.
+    let session_ctx = SessionContext::new();
+    let stream = table.scan(None, &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect(stream).await.unwrap();
+    assert!(!mito_table.is_idle_closed());
+    assert_eq!(
+        4,
+        batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+    );
+}
+
+#[tokio::test]
+async fn test_append_mode_table() {
+    let table_name = "test_append_mode";
+    let column_schemas = vec![
+        ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_datatype(common_time::timestamp::TimeUnit::Millisecond),
+            true,
+        )
+        .with_time_index(true),
+    ];
+    let schema = RawSchema::new(column_schemas);
+
+    let (_dir, object_store) = test_util::new_test_object_store("test_append_mode_table").await;
+    let compaction_scheduler = Arc::new(NoopCompactionScheduler::default());
+    let table_engine = MitoEngine::new(
+        EngineConfig::default(),
+        EngineImpl::new(
+            StorageEngineConfig::default(),
+            Arc::new(NoopLogStore::default()),
+            object_store.clone(),
+            compaction_scheduler,
+        ),
+        object_store,
+    );
+
+    let table = table_engine
+        .create_table(
+            &EngineContext::default(),
+            CreateTableRequest {
+                id: 1,
+                catalog_name: "greptime".to_string(),
+                schema_name: "public".to_string(),
+                table_name: table_name.to_string(),
+                desc: None,
+                schema,
+                create_if_not_exists: true,
+                primary_key_indices: Vec::default(),
+                table_options: TableOptions {
+                    append_mode: true,
+                    ..Default::default()
+                },
+                region_numbers: vec![0],
+                engine: MITO_ENGINE.to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(table.table_info().meta.options.append_mode);
+
+    // Insert the same rows twice, none of them should be deduplicated.
+    for _ in 0..2 {
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(2);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host1"]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 1]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("ts".to_string(), tss);
+
+        let insert_req = new_insert_request(table_name.to_string(), columns_values);
+        assert_eq!(2, table.insert(insert_req).await.unwrap());
+    }
+
+    let session_ctx = SessionContext::new();
+    let stream = table.scan(None, &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect(stream).await.unwrap();
+    let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(4, num_rows);
+
+    let mut key_column_values = HashMap::with_capacity(1);
+    key_column_values.insert(
+        "ts".to_string(),
+        Arc::new(TimestampMillisecondVector::from_vec(vec![1])) as VectorRef,
+    );
+    let err = table
+        .delete(DeleteRequest { key_column_values })
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, table::error::Error::Unsupported { .. }),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_fence_region() {
+    let TestEngineComponents {
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    setup_table(table.clone()).await;
+    // Unknown regions are rejected.
+    assert!(table.fence_region(1).await.is_err());
+    assert!(table.compact(Some(1), Some(true)).await.is_err());
+
+    table.fence_region(0).await.unwrap();
+    let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+    let hosts: VectorRef = Arc::new(StringVector::from(vec!["host5"]));
+    let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![5.0]));
+    let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![5.0]));
+    let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![5]));
+    columns_values.insert("host".to_string(), hosts);
+    columns_values.insert("cpu".to_string(), cpus);
+    columns_values.insert("memory".to_string(), memories);
+    columns_values.insert("ts".to_string(), tss);
+    let insert_req = new_insert_request("demo".to_string(), columns_values);
+    assert!(table.insert(insert_req).await.is_err());
+
+    // The table is still readable.
+    let session_ctx = SessionContext::new();
+    let stream = table.scan(None, &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect_batches(stream).await.unwrap();
+    assert_eq!(4, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+}
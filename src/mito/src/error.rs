@@ -54,6 +54,9 @@ pub enum Error {
     #[snafu(display("Invalid primary key: {}", msg))]
     InvalidPrimaryKey { msg: String, location: Location },
 
+    #[snafu(display("Invalid column encoding: {}", msg))]
+    InvalidColumnEncoding { msg: String, location: Location },
+
     #[snafu(display("Missing timestamp index for table: {}", table_name))]
     MissingTimestampIndex {
         table_name: String,
@@ -216,6 +219,7 @@ impl ErrorExt for Error {
             | TableExists { .. }
             | ProjectedColumnNotFound { .. }
             | InvalidPrimaryKey { .. }
+            | InvalidColumnEncoding { .. }
             | MissingTimestampIndex { .. }
             | TableNotFound { .. }
             | InvalidRawSchema { .. }
@@ -12,35 +12,42 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod schema_cache;
 #[cfg(any(test, feature = "test"))]
 pub mod test_util;
 
 use std::any::Any;
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
 use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
-use common_recordbatch::{RecordBatch, RecordBatchStream};
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
 use common_telemetry::logging;
+use common_time::Timestamp;
 use datatypes::schema::Schema;
 use futures::task::{Context, Poll};
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use object_store::ObjectStore;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AddColumn, AlterOperation, AlterRequest, ChunkReader, FlushContext, ReadContext, Region,
-    RegionMeta, RegionNumber, ScanRequest, SchemaRef, Snapshot, WriteContext, WriteRequest,
+    AddColumn, AlterOperation, AlterRequest, AttachContext, AttachReport, ChunkReader,
+    CompactContext, DropRangeContext, DropRangeReport, FlushContext, OpType, PreparedAttach,
+    PurgeContext, PurgeReport, ReadContext, Region, RegionHealthReport, RegionMeta, RegionNumber,
+    ScanRequest, SchemaRef, Snapshot, WriteContext, WriteRequest, WriteThrottle,
 };
 use table::error as table_error;
 use table::error::{
-    InvalidTableSnafu, RegionSchemaMismatchSnafu, Result as TableResult, TableOperationSnafu,
+    CloneSourceMismatchSnafu, InvalidTableSnafu, RegionSchemaMismatchSnafu, Result as TableResult,
+    SnapshotVersionMismatchSnafu, TableOperationSnafu,
 };
 use table::metadata::{
     FilterPushDownType, RawTableInfo, TableInfo, TableInfoRef, TableMeta, TableType, TableVersion,
@@ -48,9 +55,10 @@ use table::metadata::{
 use table::requests::{
     AddColumnRequest, AlterKind, AlterTableRequest, DeleteRequest, InsertRequest,
 };
-use table::table::scan::SimpleTableScan;
-use table::table::{AlterContext, RegionStat, Table};
-use tokio::sync::Mutex;
+use table::stats::{self, TableStatistics};
+use table::table::scan::{ScanCost, SimpleTableScan};
+use table::table::{AlterContext, RegionStat, Table, TableRef, TableSnapshot};
+use tokio::sync::{Mutex, RwLock};
 
 use crate::error;
 use crate::error::{
@@ -59,6 +67,14 @@ use crate::error::{
 };
 use crate::manifest::action::*;
 use crate::manifest::TableManifest;
+use crate::table::schema_cache::{column_indices, SchemaCache};
+
+/// Only one of every such many writes logs its rows, as they are too noisy.
+const ROW_LOG_SAMPLE_EVERY: u64 = 100;
+
+/// The last access time of a table closed for idleness. Closing the table and accessing it
+/// both change the same atomic, so an access is never lost by a concurrent close.
+const IDLE_CLOSED: i64 = i64::MIN;
 
 #[inline]
 fn table_manifest_dir(table_dir: &str) -> String {
@@ -68,10 +84,18 @@ fn table_manifest_dir(table_dir: &str) -> String {
 /// [Table] implementation.
 pub struct MitoTable<R: Region> {
     manifest: TableManifest,
-    // guarded by `self.alter_lock`
-    table_info: ArcSwap<TableInfo>,
+    // The table info with the metadata derived from it, guarded by `self.alter_lock`
+    schema_cache: ArcSwap<SchemaCache>,
     regions: HashMap<RegionNumber, R>,
     alter_lock: Mutex<()>,
+    /// Writes hold the read lock while writing regions, taking a snapshot holds the
+    /// write lock to pin sequences of all regions at the same point.
+    write_gate: RwLock<()>,
+    /// Statistics collected last time.
+    statistics: ArcSwapOption<TableStatistics>,
+    /// Time in millis of the last read or write, or [IDLE_CLOSED] if the regions are closed
+    /// for idleness until the next read or write.
+    last_access_millis: AtomicI64,
 }
 
 #[async_trait]
@@ -89,15 +113,12 @@ impl<R: Region> Table for MitoTable<R> {
             return Ok(0);
         }
 
+        self.touch();
         let region = self
             .regions
             .get(&request.region_number)
             .with_context(|| RegionNotFoundSnafu {
-                table: common_catalog::format_full_table_name(
-                    &request.catalog_name,
-                    &request.schema_name,
-                    &request.table_name,
-                ),
+                table: self.schema_cache.load().full_table_name(),
                 region: request.region_number,
             })
             .map_err(BoxedError::new)
@@ -108,10 +129,13 @@ impl<R: Region> Table for MitoTable<R> {
         // columns_values is not empty, it's safe to unwrap
         let rows_num = columns_values.values().next().unwrap().len();
 
-        logging::trace!(
-            "Insert into table {} region {} with data: {:?}",
-            self.table_info().name,
-            region.id(),
+        logging::log_sampled!(
+            every: ROW_LOG_SAMPLE_EVERY,
+            logging::Level::TRACE,
+            table = %self.schema_cache.load().table_name(),
+            region = %region.id(),
+            rows = rows_num,
+            "Insert with data: {:?}",
             columns_values
         );
 
@@ -120,6 +144,7 @@ impl<R: Region> Table for MitoTable<R> {
             .map_err(BoxedError::new)
             .context(table_error::TableOperationSnafu)?;
 
+        let _gate = self.write_gate.read().await;
         let _resp = region
             .write(&WriteContext::default(), write_request)
             .await
@@ -134,7 +159,7 @@ impl<R: Region> Table for MitoTable<R> {
     }
 
     fn table_info(&self) -> TableInfoRef {
-        self.table_info.load_full()
+        self.schema_cache.load().table_info().clone()
     }
 
     async fn scan(
@@ -143,74 +168,55 @@ impl<R: Region> Table for MitoTable<R> {
         filters: &[Expr],
         _limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
-        let read_ctx = ReadContext::default();
-        let mut readers = Vec::with_capacity(self.regions.len());
-        let mut first_schema: Option<Arc<Schema>> = None;
+        self.scan_regions(projection, filters, None, None).await
+    }
 
-        let table_info = self.table_info.load();
-        // TODO(hl): Currently the API between frontend and datanode is under refactoring in
-        // https://github.com/GreptimeTeam/greptimedb/issues/597 . Once it's finished, query plan
-        // can carry filtered region info to avoid scanning all regions on datanode.
-        for region in self.regions.values() {
+    async fn scan_sample(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        _limit: Option<usize>,
+        percent: f64,
+    ) -> TableResult<PhysicalPlanRef> {
+        self.scan_regions(projection, filters, Some(percent), None)
+            .await
+    }
+
+    async fn snapshot(&self) -> TableResult<TableSnapshot> {
+        let read_ctx = ReadContext::default();
+        let mut sequences = HashMap::with_capacity(self.regions.len());
+        let mut versions = HashMap::with_capacity(self.regions.len());
+        // Waits for in-flight writes and blocks new writes, so no write is only visible
+        // to some of the regions.
+        let _gate = self.write_gate.write().await;
+        for (region_number, region) in &self.regions {
             let snapshot = region
                 .snapshot(&read_ctx)
                 .map_err(BoxedError::new)
                 .context(table_error::TableOperationSnafu)?;
-            let projection = self
-                .transform_projection(region, projection.cloned())
-                .map_err(BoxedError::new)
-                .context(table_error::TableOperationSnafu)?;
-            let filters = filters.into();
-            let scan_request = ScanRequest {
-                projection,
-                filters,
-                ..Default::default()
-            };
-            let reader = snapshot
-                .scan(&read_ctx, scan_request)
-                .await
-                .map_err(BoxedError::new)
-                .context(table_error::TableOperationSnafu)?
-                .reader;
-
-            let schema = reader.user_schema().clone();
-            if let Some(first_schema) = &first_schema {
-                // TODO(hl): we assume all regions' schemas are the same, but undergoing table altering
-                // may make these schemas inconsistent.
-                ensure!(
-                    first_schema.version() == schema.version(),
-                    RegionSchemaMismatchSnafu {
-                        table: common_catalog::format_full_table_name(
-                            &table_info.catalog_name,
-                            &table_info.schema_name,
-                            &table_info.name
-                        )
-                    }
-                );
-            } else {
-                first_schema = Some(schema);
-            }
-            readers.push(reader);
+            sequences.insert(*region_number, snapshot.sequence());
+            versions.insert(*region_number, snapshot.schema().version());
         }
 
-        // TODO(hl): we assume table contains at least one region, but with region migration this
-        // assumption may become invalid.
-        let stream_schema = first_schema.context(InvalidTableSnafu {
-            table_id: table_info.ident.table_id,
-        })?;
+        Ok(TableSnapshot {
+            sequences,
+            versions,
+        })
+    }
 
-        let schema = stream_schema.clone();
-        let stream = Box::pin(async_stream::try_stream! {
-            for mut reader in readers {
-                while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
-                    let chunk = reader.project_chunk(chunk);
-                    yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
-                }
-            }
-        });
+    async fn scan_snapshot(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        _limit: Option<usize>,
+        snapshot: &TableSnapshot,
+    ) -> TableResult<PhysicalPlanRef> {
+        self.scan_regions(projection, filters, None, Some(snapshot))
+            .await
+    }
 
-        let stream = Box::pin(ChunkStream { schema, stream });
-        Ok(Arc::new(SimpleTableScan::new(stream)))
+    async fn tail(&self, projection: Option<&Vec<usize>>) -> TableResult<PhysicalPlanRef> {
+        self.tail_regions(projection)
     }
 
     fn supports_filters_pushdown(&self, filters: &[&Expr]) -> TableResult<Vec<FilterPushDownType>> {
@@ -250,6 +256,10 @@ impl<R: Region> Table for MitoTable<R> {
             .map_err(BoxedError::new)
             .context(table_error::TableOperationSnafu)?;
 
+        if let AlterKind::SetOptions { .. } = &req.alter_kind {
+            self.set_regions_ttl(new_info.meta.options.ttl).await;
+        }
+
         // Update in memory metadata of the table.
         self.set_table_info(new_info);
 
@@ -260,7 +270,17 @@ impl<R: Region> Table for MitoTable<R> {
         if request.key_column_values.is_empty() {
             return Ok(0);
         }
+        let schema_cache = self.schema_cache.load();
+        if schema_cache.append_mode() {
+            return table_error::UnsupportedSnafu {
+                operation: "DELETE on append-only table",
+            }
+            .fail();
+        }
+        self.touch();
         let mut rows_deleted = 0;
+        // Deletes from all regions are visible to snapshots atomically.
+        let _gate = self.write_gate.read().await;
         // TODO(hl): Should be tracked by procedure.
         // TODO(hl): Parse delete request into region->keys instead of delete in each region
         for region in self.regions.values() {
@@ -269,9 +289,13 @@ impl<R: Region> Table for MitoTable<R> {
             // Safety: key_column_values isn't empty.
             let rows_num = key_column_values.values().next().unwrap().len();
 
-            logging::trace!(
-                "Delete from table {} where key_columns are: {:?}",
-                self.table_info().name,
+            logging::log_sampled!(
+                every: ROW_LOG_SAMPLE_EVERY,
+                logging::Level::TRACE,
+                table = %schema_cache.table_name(),
+                region = %region.id(),
+                rows = rows_num,
+                "Delete where key_columns are: {:?}",
                 key_column_values
             );
 
@@ -294,15 +318,15 @@ impl<R: Region> Table for MitoTable<R> {
         region_number: Option<RegionNumber>,
         wait: Option<bool>,
     ) -> TableResult<()> {
-        let flush_ctx = wait.map(|wait| FlushContext { wait }).unwrap_or_default();
+        let flush_ctx = wait
+            .map(|wait| FlushContext { wait, fence: false })
+            .unwrap_or_default();
         if let Some(region_number) = region_number {
-            if let Some(region) = self.regions.get(&region_number) {
-                region
-                    .flush(&flush_ctx)
-                    .await
-                    .map_err(BoxedError::new)
-                    .context(table_error::TableOperationSnafu)?;
-            }
+            self.find_region(region_number)?
+                .flush(&flush_ctx)
+                .await
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
         } else {
             futures::future::try_join_all(
                 self.regions.values().map(|region| region.flush(&flush_ctx)),
@@ -315,6 +339,228 @@ impl<R: Region> Table for MitoTable<R> {
         Ok(())
     }
 
+    async fn compact(
+        &self,
+        region_number: Option<RegionNumber>,
+        wait: Option<bool>,
+    ) -> TableResult<()> {
+        let mut compact_ctx = CompactContext::default();
+        if let Some(wait) = wait {
+            compact_ctx.wait = wait;
+        }
+        if let Some(region_number) = region_number {
+            self.find_region(region_number)?
+                .compact(&compact_ctx)
+                .await
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+        } else {
+            futures::future::try_join_all(
+                self.regions
+                    .values()
+                    .map(|region| region.compact(&compact_ctx)),
+            )
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        }
+
+        Ok(())
+    }
+
+    async fn fence_region(&self, region_number: RegionNumber) -> TableResult<()> {
+        let flush_ctx = FlushContext {
+            wait: true,
+            fence: true,
+        };
+        self.find_region(region_number)?
+            .flush(&flush_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        logging::info!(
+            "Fenced region {} of table {}",
+            region_number,
+            self.schema_cache.load().full_table_name()
+        );
+        Ok(())
+    }
+
+    async fn unfence_region(&self, region_number: RegionNumber) -> TableResult<()> {
+        self.find_region(region_number)?
+            .unfence()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        logging::info!(
+            "Unfenced region {} of table {}",
+            region_number,
+            self.schema_cache.load().full_table_name()
+        );
+        Ok(())
+    }
+
+    async fn purge_expired(
+        &self,
+        region_number: Option<RegionNumber>,
+        dry_run: bool,
+    ) -> TableResult<Vec<(RegionNumber, PurgeReport)>> {
+        let purge_ctx = PurgeContext { dry_run };
+        let regions = self.select_regions(region_number)?;
+        futures::future::try_join_all(regions.into_iter().map(|(number, region)| {
+            let purge_ctx = &purge_ctx;
+            async move {
+                region
+                    .purge_expired(purge_ctx)
+                    .await
+                    .map(|report| (*number, report))
+            }
+        }))
+        .await
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)
+    }
+
+    async fn drop_range(
+        &self,
+        region_number: Option<RegionNumber>,
+        start: Timestamp,
+        end: Timestamp,
+        dry_run: bool,
+    ) -> TableResult<Vec<(RegionNumber, DropRangeReport)>> {
+        let drop_ctx = DropRangeContext {
+            start,
+            end,
+            dry_run,
+        };
+        let regions = self.select_regions(region_number)?;
+        futures::future::try_join_all(regions.into_iter().map(|(number, region)| {
+            let drop_ctx = &drop_ctx;
+            async move {
+                region
+                    .drop_range(drop_ctx)
+                    .await
+                    .map(|report| (*number, report))
+            }
+        }))
+        .await
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)
+    }
+
+    async fn attach_ssts(
+        &self,
+        region_number: Option<RegionNumber>,
+        staging_dir: &str,
+    ) -> TableResult<Vec<(RegionNumber, AttachReport)>> {
+        let staging_dir = staging_dir.trim_end_matches('/');
+        let regions = self
+            .regions
+            .iter()
+            .filter(|(number, _)| region_number.map_or(true, |n| n == **number))
+            .collect::<Vec<_>>();
+
+        // Prepares all regions before committing any of them, so an invalid staged file
+        // of any region leaves all regions unchanged.
+        let results = futures::future::join_all(regions.iter().map(|(number, region)| {
+            let attach_ctx = AttachContext {
+                staging_dir: format!("{staging_dir}/{number}/"),
+            };
+            async move { region.prepare_attach(&attach_ctx).await }
+        }))
+        .await;
+        let mut prepared = Vec::with_capacity(results.len());
+        let mut error = None;
+        for ((number, region), result) in regions.into_iter().zip(results) {
+            match result {
+                Ok(files) => prepared.push((*number, region, files)),
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+        if let Some(e) = error {
+            for (_, region, files) in prepared {
+                abort_attach(region, files).await;
+            }
+            return Err(e)
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu);
+        }
+
+        let mut reports = Vec::with_capacity(prepared.len());
+        for (number, region, files) in prepared {
+            if error.is_some() {
+                abort_attach(region, files).await;
+                continue;
+            }
+            match region.commit_attach(files.clone()).await {
+                Ok(report) => reports.push((number, report)),
+                Err(e) => {
+                    abort_attach(region, files).await;
+                    error = Some(e);
+                }
+            }
+        }
+        match error {
+            Some(e) => Err(e)
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu),
+            None => Ok(reports),
+        }
+    }
+
+    async fn scrub(
+        &self,
+        region_number: Option<RegionNumber>,
+    ) -> TableResult<Vec<(RegionNumber, RegionHealthReport)>> {
+        let regions = self.select_regions(region_number)?;
+        futures::future::try_join_all(regions.into_iter().map(|(number, region)| async move {
+            region.scrub().await.map(|report| (*number, report))
+        }))
+        .await
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)
+    }
+
+    async fn clone_data_from(&self, source: TableRef) -> TableResult<()> {
+        let table_info = self.table_info();
+        let table_name = &table_info.name;
+        let source_info = source.table_info();
+        let source = source
+            .as_any()
+            .downcast_ref::<MitoTable<R>>()
+            .with_context(|| CloneSourceMismatchSnafu {
+                table: table_name,
+                source_table: &source_info.name,
+            })?;
+        ensure!(
+            self.regions.len() == source.regions.len()
+                && self
+                    .regions
+                    .keys()
+                    .all(|number| source.regions.contains_key(number)),
+            CloneSourceMismatchSnafu {
+                table: table_name,
+                source_table: &source_info.name,
+            }
+        );
+
+        futures::future::try_join_all(
+            self.regions
+                .iter()
+                .map(|(number, region)| region.clone_data_from(&source.regions[number])),
+        )
+        .await
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)?;
+
+        logging::info!(
+            "Cloned data of table {} into table {}",
+            source_info.name,
+            table_name
+        );
+        Ok(())
+    }
+
     async fn close(&self) -> TableResult<()> {
         futures::future::try_join_all(self.regions.values().map(|region| region.close()))
             .await
@@ -324,6 +570,42 @@ impl<R: Region> Table for MitoTable<R> {
         Ok(())
     }
 
+    async fn close_if_idle(&self, idle_timeout: Duration) -> TableResult<bool> {
+        let last_access = self.last_access_millis.load(Ordering::Acquire);
+        if last_access == IDLE_CLOSED {
+            return Ok(false);
+        }
+        let idle_millis = common_time::util::current_time_millis() - last_access;
+        if idle_millis < idle_timeout.as_millis() as i64 {
+            return Ok(false);
+        }
+
+        futures::future::try_join_all(self.regions.values().map(|region| region.hibernate()))
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        // Requests arriving while the regions are hibernating keep the table open.
+        if self
+            .last_access_millis
+            .compare_exchange(
+                last_access,
+                IDLE_CLOSED,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_err()
+        {
+            return Ok(false);
+        }
+        logging::info!(
+            "Closed table {} after being idle for {}ms",
+            self.schema_cache.load().full_table_name(),
+            idle_millis
+        );
+        Ok(true)
+    }
+
     fn region_stats(&self) -> TableResult<Vec<RegionStat>> {
         Ok(self
             .regions
@@ -334,6 +616,39 @@ impl<R: Region> Table for MitoTable<R> {
             })
             .collect())
     }
+
+    fn write_throttle(&self) -> WriteThrottle {
+        // The most pressured region decides, one region can't take more writes than it
+        // is able to flush no matter how idle the others are.
+        self.regions
+            .values()
+            .map(|region| region.write_throttle())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn statistics(&self) -> Option<TableStatistics> {
+        self.statistics
+            .load_full()
+            .map(|stats| TableStatistics::clone(&stats))
+    }
+
+    fn restore_statistics(&self, statistics: TableStatistics) {
+        self.statistics.store(Some(Arc::new(statistics)));
+    }
+
+    async fn collect_statistics(&self) -> TableResult<TableStatistics> {
+        // Nothing is written to a table closed for idleness, so the statistics collected
+        // last time are still valid, and scanning would reopen the table.
+        if self.is_idle_closed() {
+            if let Some(statistics) = self.statistics.load_full() {
+                return Ok((*statistics).clone());
+            }
+        }
+        let statistics = stats::scan_statistics(self).await?;
+        self.statistics.store(Some(Arc::new(statistics.clone())));
+        Ok(statistics)
+    }
 }
 
 struct ChunkStream {
@@ -355,79 +670,341 @@ impl Stream for ChunkStream {
     }
 }
 
+/// Max number of partitions to scan a table, defaults to the available parallelism.
+fn max_parallelism() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Removes the files prepared to attach to the `region`, the error is only logged as
+/// the files are invisible to the region anyway.
+async fn abort_attach<R: Region>(region: &R, prepared: PreparedAttach) {
+    if let Err(e) = region.abort_attach(prepared).await {
+        logging::warn!(
+            "Failed to remove files prepared to attach to region {}, err: {}",
+            region.name(),
+            e
+        );
+    }
+}
+
 #[inline]
 fn column_qualified_name(table_name: &str, region_name: &str, column_name: &str) -> String {
     format!("{table_name}.{region_name}.{column_name}")
 }
 
 impl<R: Region> MitoTable<R> {
+    fn find_region(&self, region_number: RegionNumber) -> TableResult<&R> {
+        self.regions
+            .get(&region_number)
+            .with_context(|| RegionNotFoundSnafu {
+                table: self.schema_cache.load().full_table_name(),
+                region: region_number,
+            })
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
+    /// Returns the region `region_number`, or all regions if it's absent.
+    fn select_regions(
+        &self,
+        region_number: Option<RegionNumber>,
+    ) -> TableResult<Vec<(&RegionNumber, &R)>> {
+        let Some(region_number) = region_number else {
+            return Ok(self.regions.iter().collect());
+        };
+        let region = self
+            .regions
+            .get_key_value(&region_number)
+            .with_context(|| RegionNotFoundSnafu {
+                table: self.schema_cache.load().full_table_name(),
+                region: region_number,
+            })
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        Ok(vec![region])
+    }
+
+    /// Scans all regions, only reads about `sample_percent` percent of each region if
+    /// it's present.
+    async fn scan_regions(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        sample_percent: Option<f64>,
+        table_snapshot: Option<&TableSnapshot>,
+    ) -> TableResult<PhysicalPlanRef> {
+        self.touch();
+        let read_ctx = ReadContext::default();
+        let mut readers = Vec::with_capacity(self.regions.len());
+        let mut first_schema: Option<Arc<Schema>> = None;
+        let mut scan_cost = ScanCost::default();
+
+        let schema_cache = self.schema_cache.load();
+        // TODO(hl): Currently the API between frontend and datanode is under refactoring in
+        // https://github.com/GreptimeTeam/greptimedb/issues/597 . Once it's finished, query plan
+        // can carry filtered region info to avoid scanning all regions on datanode.
+        for (region_number, region) in &self.regions {
+            // Reads at the sequence of the table snapshot if provided, regions created after
+            // the table snapshot is taken are not readable at the snapshot.
+            let sequence = table_snapshot
+                .map(|table_snapshot| {
+                    table_snapshot
+                        .sequences
+                        .get(region_number)
+                        .copied()
+                        .with_context(|| RegionNotFoundSnafu {
+                            table: schema_cache.full_table_name(),
+                            region: *region_number,
+                        })
+                })
+                .transpose()
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            let snapshot = region
+                .snapshot(&read_ctx)
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            if let Some(snapshot_version) =
+                table_snapshot.and_then(|table_snapshot| table_snapshot.versions.get(region_number))
+            {
+                let current_version = snapshot.schema().version();
+                ensure!(
+                    *snapshot_version == current_version,
+                    SnapshotVersionMismatchSnafu {
+                        table: schema_cache.full_table_name(),
+                        region: *region_number,
+                        snapshot_version: *snapshot_version,
+                        current_version,
+                    }
+                );
+            }
+            let projection = self
+                .transform_projection(&schema_cache, *region_number, region, projection)
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            let filters = filters.into();
+            let scan_request = ScanRequest {
+                sequence,
+                projection,
+                filters,
+                sample_percent,
+            };
+            let response = snapshot
+                .scan(&read_ctx, scan_request)
+                .await
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            scan_cost.merge(&ScanCost {
+                num_files: response.num_files,
+                num_bytes: response.estimated_bytes,
+            });
+            let reader = response.reader;
+
+            let schema = reader.user_schema().clone();
+            if let Some(first_schema) = &first_schema {
+                // TODO(hl): we assume all regions' schemas are the same, but undergoing table altering
+                // may make these schemas inconsistent.
+                ensure!(
+                    first_schema.version() == schema.version(),
+                    RegionSchemaMismatchSnafu {
+                        table: schema_cache.full_table_name(),
+                    }
+                );
+            } else {
+                first_schema = Some(schema);
+            }
+            readers.push(reader);
+        }
+
+        // TODO(hl): we assume table contains at least one region, but with region migration this
+        // assumption may become invalid.
+        let stream_schema = first_schema.with_context(|| InvalidTableSnafu {
+            table_id: schema_cache.table_info().ident.table_id,
+        })?;
+
+        // Each region is read by only one partition, so regions are the upper bound of
+        // the parallelism.
+        let num_partitions = scan_cost.target_partitions(readers.len().min(max_parallelism()));
+        let mut partitioned_readers = (0..num_partitions).map(|_| Vec::new()).collect::<Vec<_>>();
+        for (i, reader) in readers.into_iter().enumerate() {
+            partitioned_readers[i % num_partitions].push(reader);
+        }
+        let streams: Vec<SendableRecordBatchStream> = partitioned_readers
+            .into_iter()
+            .map(|readers| {
+                let schema = stream_schema.clone();
+                let stream = Box::pin(async_stream::try_stream! {
+                    for mut reader in readers {
+                        while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
+                            let chunk = reader.project_chunk(chunk);
+                            yield RecordBatch::new(schema.clone(), chunk.columns)?
+                        }
+                    }
+                });
+                Box::pin(ChunkStream {
+                    schema: stream_schema.clone(),
+                    stream,
+                }) as SendableRecordBatchStream
+            })
+            .collect();
+
+        let mut scan =
+            SimpleTableScan::new_partitioned(stream_schema, streams).with_scan_cost(scan_cost);
+        // Statistics of the whole table don't apply to a sample.
+        if let (Some(statistics), None) = (self.statistics.load_full(), sample_percent) {
+            let table_schema = &schema_cache.table_info().meta.schema;
+            scan = scan.with_statistics(statistics.to_df_statistics(table_schema, projection));
+        }
+        Ok(Arc::new(scan))
+    }
+
+    /// Subscribes to all regions and streams the rows put into them from now on.
+    fn tail_regions(&self, projection: Option<&Vec<usize>>) -> TableResult<PhysicalPlanRef> {
+        self.touch();
+        let table_info = self.table_info();
+        let table_schema = &table_info.meta.schema;
+        let stream_schema = match projection {
+            Some(projection) => Arc::new(Schema::new(
+                projection
+                    .iter()
+                    .map(|idx| table_schema.column_schemas()[*idx].clone())
+                    .collect(),
+            )),
+            None => table_schema.clone(),
+        };
+
+        let mut subscriptions = Vec::with_capacity(self.regions.len());
+        for region in self.regions.values() {
+            let changes = region
+                .subscribe()
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            subscriptions.push(changes.map(|change| change.map_err(BoxedError::new)));
+        }
+
+        let table_name = table_info.name.clone();
+        let schema = stream_schema.clone();
+        let stream = Box::pin(async_stream::try_stream! {
+            let mut changes = futures::stream::select_all(subscriptions);
+            while let Some(change) = changes.next().await {
+                let change = change.context(ExternalSnafu)?;
+                // Deleted rows are not emitted, the tail only appends rows.
+                if change.op_type != OpType::Put {
+                    continue;
+                }
+
+                let mut columns = Vec::with_capacity(schema.num_columns());
+                for column_schema in schema.column_schemas() {
+                    let idx = change
+                        .schema
+                        .column_index_by_name(&column_schema.name)
+                        .with_context(|| ProjectedColumnNotFoundSnafu {
+                            column_qualified_name: format!("{table_name}.{}", column_schema.name),
+                        })
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?;
+                    columns.push(change.columns[idx].clone());
+                }
+                yield RecordBatch::new(schema.clone(), columns)?
+            }
+        });
+        let stream = Box::pin(ChunkStream {
+            schema: stream_schema.clone(),
+            stream,
+        });
+
+        Ok(Arc::new(SimpleTableScan::new(stream)))
+    }
+
     pub(crate) fn new(
         table_info: TableInfo,
         regions: HashMap<RegionNumber, R>,
         manifest: TableManifest,
     ) -> Self {
+        let schema_cache = SchemaCache::new(Arc::new(table_info), &regions);
         Self {
-            table_info: ArcSwap::new(Arc::new(table_info)),
+            schema_cache: ArcSwap::new(Arc::new(schema_cache)),
             regions,
             manifest,
             alter_lock: Mutex::new(()),
+            write_gate: RwLock::new(()),
+            statistics: ArcSwapOption::empty(),
+            last_access_millis: AtomicI64::new(common_time::util::current_time_millis()),
+        }
+    }
+
+    /// Records a read or write of the table, which reopens the table if it's closed for
+    /// idleness. Hibernated regions load their data again on demand.
+    fn touch(&self) {
+        let last_access = self
+            .last_access_millis
+            .swap(common_time::util::current_time_millis(), Ordering::AcqRel);
+        if last_access == IDLE_CLOSED {
+            logging::info!(
+                "Reopened idle table {}",
+                self.schema_cache.load().full_table_name()
+            );
         }
     }
 
+    /// Whether the table is closed for idleness, see [Table::close_if_idle].
+    #[inline]
+    pub fn is_idle_closed(&self) -> bool {
+        self.last_access_millis.load(Ordering::Acquire) == IDLE_CLOSED
+    }
+
     /// Transform projection which is based on table schema
     /// into projection based on region schema.
     fn transform_projection(
         &self,
+        schema_cache: &SchemaCache,
+        region_number: RegionNumber,
         region: &R,
-        projection: Option<Vec<usize>>,
+        projection: Option<&Vec<usize>>,
     ) -> Result<Option<Vec<usize>>> {
-        let table_info = self.table_info();
-        let table_schema = &table_info.meta.schema;
-
         let region_meta = region.in_memory_metadata();
-        let region_schema = region_meta.schema();
+        let column_names = schema_cache.column_names();
+        // Only maps the columns again if the region has been altered since the cache was built.
+        let computed;
+        let region_indices = match schema_cache.region_indices(region_number, region_meta.version())
+        {
+            Some(indices) => indices,
+            None => {
+                computed = column_indices(column_names, region_meta.schema());
+                &computed[..]
+            }
+        };
+
+        let region_index = |idx: usize| {
+            region_indices.get(idx).copied().flatten().with_context(|| {
+                ProjectedColumnNotFoundSnafu {
+                    column_qualified_name: column_qualified_name(
+                        schema_cache.table_name(),
+                        region.name(),
+                        &column_names
+                            .get(idx)
+                            .cloned()
+                            .unwrap_or_else(|| format!("#{idx}")),
+                    ),
+                }
+            })
+        };
 
-        if projection.is_none() {
+        let projection = match projection {
+            Some(p) => p
+                .iter()
+                .map(|idx| region_index(*idx))
+                .collect::<Result<_>>()?,
             // In fact, datafusion always calls scan with not-none projection
             // generated by table schema right now, but to prevent future compatibility
             // issue, we process this case here.
-            let projection: Result<Vec<_>> = table_schema
-                .column_schemas()
-                .iter()
-                .map(|column_schema| &column_schema.name)
-                .map(|name| {
-                    region_schema.column_index_by_name(name).with_context(|| {
-                        ProjectedColumnNotFoundSnafu {
-                            column_qualified_name: column_qualified_name(
-                                &table_info.name,
-                                region.name(),
-                                name,
-                            ),
-                        }
-                    })
-                })
-                .collect();
-            return Some(projection).transpose();
-        }
-
-        projection
-            .map(|p| {
-                p.iter()
-                    .map(|idx| table_schema.column_name_by_index(*idx))
-                    .map(|name| {
-                        region_schema.column_index_by_name(name).with_context(|| {
-                            ProjectedColumnNotFoundSnafu {
-                                column_qualified_name: column_qualified_name(
-                                    &table_info.name,
-                                    region.name(),
-                                    name,
-                                ),
-                            }
-                        })
-                    })
-                    .collect()
-            })
-            .transpose()
+            None => (0..column_names.len())
+                .map(region_index)
+                .collect::<Result<_>>()?,
+        };
+        Ok(Some(projection))
     }
 
     pub async fn create(
@@ -510,8 +1087,22 @@ impl<R: Region> MitoTable<R> {
         &self.regions
     }
 
+    /// Applies the ttl of the table to its regions.
+    pub(crate) async fn set_regions_ttl(&self, ttl: Option<Duration>) {
+        for region in self.regions.values() {
+            region.set_ttl(ttl).await;
+        }
+    }
+
     pub fn set_table_info(&self, table_info: TableInfo) {
-        self.table_info.swap(Arc::new(table_info));
+        let schema_cache = SchemaCache::new(Arc::new(table_info), &self.regions);
+        self.schema_cache.store(Arc::new(schema_cache));
+    }
+
+    /// Returns the version of the table info the schema cache is built from.
+    #[inline]
+    pub fn schema_cache_version(&self) -> TableVersion {
+        self.schema_cache.load().version()
     }
 
     #[inline]
@@ -545,9 +1136,9 @@ impl<R: Region> MitoTable<R> {
             };
             // Alter the region.
             logging::debug!(
-                "start altering region {} of table {}, with request {:?}",
-                region.name(),
-                table_name,
+                table = %table_name,
+                region = %region.name(),
+                "start altering region with request {:?}",
                 alter_req,
             );
             region
@@ -572,7 +1163,9 @@ impl<R: Region> MitoTable<R> {
             AlterKind::RenameTable { new_table_name } => {
                 new_info.name = new_table_name.clone();
             }
-            AlterKind::AddColumns { .. } | AlterKind::DropColumns { .. } => {
+            AlterKind::AddColumns { .. }
+            | AlterKind::DropColumns { .. }
+            | AlterKind::SetOptions { .. } => {
                 let table_meta = &current_info.meta;
                 let new_meta = table_meta
                     .builder_with_alter_kind(table_name, alter_kind)?
@@ -608,6 +1201,8 @@ pub(crate) fn create_alter_operation(
         })),
         // No need to build alter operation when reaming tables.
         AlterKind::RenameTable { .. } => Ok(None),
+        // The options are applied to the regions by the table.
+        AlterKind::SetOptions { .. } => Ok(None),
     }
 }
 
@@ -0,0 +1,160 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use store_api::storage::{Region, RegionMeta, RegionNumber, SchemaRef};
+use table::metadata::{TableInfoRef, TableVersion};
+
+/// The [TableInfo](table::metadata::TableInfo) of a table along with the metadata derived
+/// from it, shared by the write and read paths so they don't derive it for each request.
+///
+/// A cache is built from one version of the table info and is replaced along with the
+/// table info, so readers never see a table info with the metadata of another version.
+#[derive(Debug)]
+pub(crate) struct SchemaCache {
+    /// The table info the cache is built from.
+    table_info: TableInfoRef,
+    /// Full name of the table, used by errors.
+    full_table_name: String,
+    append_mode: bool,
+    /// Names of the columns in the table schema, in order.
+    column_names: Vec<String>,
+    /// Indices of the table columns in the schema of each region.
+    region_indices: HashMap<RegionNumber, RegionIndices>,
+}
+
+#[derive(Debug)]
+struct RegionIndices {
+    /// Version of the region metadata the indices are built from.
+    metadata_version: u32,
+    indices: Vec<Option<usize>>,
+}
+
+impl SchemaCache {
+    pub(crate) fn new<R: Region>(
+        table_info: TableInfoRef,
+        regions: &HashMap<RegionNumber, R>,
+    ) -> SchemaCache {
+        let column_names: Vec<_> = table_info
+            .meta
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column_schema| column_schema.name.clone())
+            .collect();
+        let region_indices = regions
+            .iter()
+            .map(|(region_number, region)| {
+                let metadata = region.in_memory_metadata();
+                let indices = RegionIndices {
+                    metadata_version: metadata.version(),
+                    indices: column_indices(&column_names, metadata.schema()),
+                };
+                (*region_number, indices)
+            })
+            .collect();
+
+        SchemaCache {
+            full_table_name: common_catalog::format_full_table_name(
+                &table_info.catalog_name,
+                &table_info.schema_name,
+                &table_info.name,
+            ),
+            append_mode: table_info.meta.options.append_mode,
+            table_info,
+            column_names,
+            region_indices,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn table_info(&self) -> &TableInfoRef {
+        &self.table_info
+    }
+
+    #[inline]
+    pub(crate) fn version(&self) -> TableVersion {
+        self.table_info.ident.version
+    }
+
+    #[inline]
+    pub(crate) fn table_name(&self) -> &str {
+        &self.table_info.name
+    }
+
+    #[inline]
+    pub(crate) fn full_table_name(&self) -> &str {
+        &self.full_table_name
+    }
+
+    #[inline]
+    pub(crate) fn append_mode(&self) -> bool {
+        self.append_mode
+    }
+
+    #[inline]
+    pub(crate) fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+
+    /// Returns the index of each table column in the schema of the region, `None` if
+    /// the region metadata has changed since the cache was built.
+    pub(crate) fn region_indices(
+        &self,
+        region_number: RegionNumber,
+        metadata_version: u32,
+    ) -> Option<&[Option<usize>]> {
+        self.region_indices
+            .get(&region_number)
+            .filter(|indices| indices.metadata_version == metadata_version)
+            .map(|indices| &indices.indices[..])
+    }
+}
+
+/// Returns the index of each column in the `region_schema`, `None` if the region schema
+/// doesn't have the column.
+pub(crate) fn column_indices(
+    column_names: &[String],
+    region_schema: &SchemaRef,
+) -> Vec<Option<usize>> {
+    column_names
+        .iter()
+        .map(|name| region_schema.column_index_by_name(name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_column_indices() {
+        let region_schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("ts", ConcreteDataType::int64_datatype(), false),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+        ]));
+        let column_names = vec!["host".to_string(), "cpu".to_string(), "ts".to_string()];
+
+        assert_eq!(
+            vec![Some(1), None, Some(0)],
+            column_indices(&column_names, &region_schema)
+        );
+    }
+}
@@ -16,6 +16,7 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
@@ -23,12 +24,15 @@ use common_error::mock::MockError;
 use common_telemetry::logging;
 use datatypes::prelude::{DataType, Value, VectorRef};
 use datatypes::schema::{ColumnSchema, Schema};
+use futures::stream::{self, BoxStream};
 use storage::metadata::{RegionMetaImpl, RegionMetadata};
 use storage::write_batch::WriteBatch;
 use store_api::storage::{
-    AlterRequest, Chunk, ChunkReader, CreateOptions, EngineContext, FlushContext, GetRequest,
-    GetResponse, OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, ScanRequest,
-    ScanResponse, SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
+    AlterRequest, AttachContext, AttachReport, ChangeBatch, Chunk, ChunkReader, CompactContext,
+    CreateOptions, DropRangeContext, DropRangeReport, EngineContext, FlushContext, GetRequest,
+    GetResponse, OpenOptions, PreparedAttach, PurgeContext, PurgeReport, ReadContext, Region,
+    RegionDescriptor, RegionHealthReport, RegionId, ScanRequest, ScanResponse, SchemaRef,
+    SequenceNumber, Snapshot, StorageEngine, WriteContext, WriteResponse, WriteThrottle,
 };
 
 pub type Result<T> = std::result::Result<T, MockError>;
@@ -120,12 +124,20 @@ impl Snapshot for MockSnapshot {
             memtable,
             read: false,
         };
-        Ok(ScanResponse { reader })
+        Ok(ScanResponse {
+            reader,
+            num_files: 0,
+            estimated_bytes: 0,
+        })
     }
 
     async fn get(&self, _ctx: &ReadContext, _request: GetRequest) -> Result<GetResponse> {
         Ok(GetResponse {})
     }
+
+    fn sequence(&self) -> SequenceNumber {
+        0
+    }
 }
 
 // Clones a MockRegion is not cheap as we need to clone the string name, but for test
@@ -193,10 +205,16 @@ impl Region for MockRegion {
         Ok(())
     }
 
+    async fn set_ttl(&self, _ttl: Option<Duration>) {}
+
     async fn close(&self) -> Result<()> {
         Ok(())
     }
 
+    async fn hibernate(&self) -> Result<()> {
+        Ok(())
+    }
+
     fn disk_usage_bytes(&self) -> u64 {
         0
     }
@@ -204,6 +222,50 @@ impl Region for MockRegion {
     async fn flush(&self, _ctx: &FlushContext) -> Result<()> {
         unimplemented!()
     }
+
+    async fn unfence(&self) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn compact(&self, _ctx: &CompactContext) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn purge_expired(&self, _ctx: &PurgeContext) -> Result<PurgeReport> {
+        Ok(PurgeReport::default())
+    }
+
+    async fn drop_range(&self, _ctx: &DropRangeContext) -> Result<DropRangeReport> {
+        Ok(DropRangeReport::default())
+    }
+
+    async fn clone_data_from(&self, _source: &Self) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn prepare_attach(&self, _ctx: &AttachContext) -> Result<PreparedAttach> {
+        Ok(PreparedAttach::default())
+    }
+
+    async fn commit_attach(&self, _prepared: PreparedAttach) -> Result<AttachReport> {
+        Ok(AttachReport::default())
+    }
+
+    async fn abort_attach(&self, _prepared: PreparedAttach) -> Result<()> {
+        Ok(())
+    }
+
+    async fn scrub(&self) -> Result<RegionHealthReport> {
+        Ok(RegionHealthReport::default())
+    }
+
+    fn subscribe(&self) -> Result<BoxStream<'static, Result<ChangeBatch>>> {
+        Ok(Box::pin(stream::empty()))
+    }
+
+    fn write_throttle(&self) -> WriteThrottle {
+        WriteThrottle::Admit
+    }
 }
 
 impl MockRegionInner {
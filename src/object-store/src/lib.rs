@@ -21,5 +21,7 @@ pub use opendal::{
 
 pub mod cache_policy;
 mod metrics;
+pub mod read_only;
+pub mod retry;
 pub mod test_util;
 pub mod util;
@@ -18,3 +18,6 @@ pub const OBJECT_STORE_LRU_CACHE_HIT: &str = "object_store.lru_cache.hit";
 pub const OBJECT_STORE_LRU_CACHE_MISS: &str = "object_store.lru_cache.miss";
 pub const OBJECT_STORE_LRU_CACHE_ERROR: &str = "object_store.lru_cache.error";
 pub const OBJECT_STORE_LRU_CACHE_ERROR_KIND: &str = "error";
+pub const OBJECT_STORE_RETRY: &str = "object_store.retry";
+pub const OBJECT_STORE_RETRY_EXHAUSTED: &str = "object_store.retry.exhausted";
+pub const OBJECT_STORE_OPERATION: &str = "operation";
@@ -0,0 +1,148 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A layer that rejects all requests modifying the object store.
+
+use async_trait::async_trait;
+use opendal::ops::{
+    OpBatch, OpCopy, OpCreate, OpDelete, OpList, OpPresign, OpRead, OpRename, OpScan, OpWrite,
+    PresignOperation,
+};
+use opendal::raw::{
+    Accessor, Layer, LayeredAccessor, RpBatch, RpCopy, RpCreate, RpDelete, RpList, RpPresign,
+    RpRead, RpRename, RpScan, RpWrite,
+};
+use opendal::{Error, ErrorKind, Result};
+
+/// Layer to open an object store read-only, creates, writes, copies, renames and deletes,
+/// including batch deletes and presigned writes, fail with [ErrorKind::PermissionDenied].
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlyLayer;
+
+impl<A: Accessor> Layer<A> for ReadOnlyLayer {
+    type LayeredAccessor = ReadOnlyAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        ReadOnlyAccessor { inner }
+    }
+}
+
+#[derive(Debug)]
+pub struct ReadOnlyAccessor<A> {
+    inner: A,
+}
+
+fn read_only_error(operation: &'static str, path: &str) -> Error {
+    Error::new(
+        ErrorKind::PermissionDenied,
+        "object store is opened read-only",
+    )
+    .with_context("operation", operation)
+    .with_context("path", path)
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for ReadOnlyAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create(&self, path: &str, _args: OpCreate) -> Result<RpCreate> {
+        Err(read_only_error("create", path))
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, _args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        Err(read_only_error("write", path))
+    }
+
+    async fn copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
+        Err(read_only_error("copy", from).with_context("to", to))
+    }
+
+    async fn rename(&self, from: &str, to: &str, _args: OpRename) -> Result<RpRename> {
+        Err(read_only_error("rename", from).with_context("to", to))
+    }
+
+    async fn delete(&self, path: &str, _args: OpDelete) -> Result<RpDelete> {
+        Err(read_only_error("delete", path))
+    }
+
+    async fn batch(&self, _args: OpBatch) -> Result<RpBatch> {
+        // Batch operations only contain deletes.
+        Err(read_only_error("batch", "/"))
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        if let PresignOperation::Write(_) = args.operation() {
+            return Err(read_only_error("presign_write", path));
+        }
+        self.inner.presign(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.inner.scan(path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, _args: OpCreate) -> Result<RpCreate> {
+        Err(read_only_error("blocking_create", path))
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(
+        &self,
+        path: &str,
+        _args: OpWrite,
+    ) -> Result<(RpWrite, Self::BlockingWriter)> {
+        Err(read_only_error("blocking_write", path))
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
+        Err(read_only_error("blocking_copy", from).with_context("to", to))
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, _args: OpRename) -> Result<RpRename> {
+        Err(read_only_error("blocking_rename", from).with_context("to", to))
+    }
+
+    fn blocking_delete(&self, path: &str, _args: OpDelete) -> Result<RpDelete> {
+        Err(read_only_error("blocking_delete", path))
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
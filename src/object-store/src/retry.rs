@@ -0,0 +1,223 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A layer that retries idempotent object store requests with jittered exponential backoff.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use metrics::increment_counter;
+use opendal::ops::{OpDelete, OpList, OpRead, OpScan, OpStat, OpWrite};
+use opendal::raw::{
+    Accessor, Layer, LayeredAccessor, RpDelete, RpList, RpRead, RpScan, RpStat, RpWrite,
+};
+use opendal::Result;
+use rand::Rng;
+
+use crate::metrics::{OBJECT_STORE_OPERATION, OBJECT_STORE_RETRY, OBJECT_STORE_RETRY_EXHAUSTED};
+
+/// Policy to retry failed requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Max retry times of a request, 0 to disable retry.
+    pub max_retries: usize,
+    /// Delay before the first retry.
+    pub min_delay: Duration,
+    /// Max delay between two retries.
+    pub max_delay: Duration,
+    /// Multiplier of the delay after each retry.
+    pub factor: f64,
+    /// Whether to randomize the delay to avoid retrying in lockstep.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            min_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            factor: 2.0,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delay before the `attempt`-th (starting from 0) retry.
+    ///
+    /// The delay grows exponentially and is capped by `max_delay`. With jitter
+    /// enabled, the delay is randomized in `[delay / 2, delay]`.
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let delay = (self.min_delay.as_secs_f64() * self.factor.powi(attempt as i32))
+            .min(self.max_delay.as_secs_f64());
+        let delay = if self.jitter && delay > 0.0 {
+            rand::thread_rng().gen_range(delay / 2.0..=delay)
+        } else {
+            delay
+        };
+        Duration::from_secs_f64(delay)
+    }
+}
+
+/// Layer to retry idempotent requests (read, stat, delete, list and scan) on temporary
+/// errors. Writes are never retried as a failed write may have been partially applied.
+#[derive(Debug, Clone, Default)]
+pub struct RetryPolicyLayer {
+    policy: Arc<RetryPolicy>,
+}
+
+impl RetryPolicyLayer {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy: Arc::new(policy),
+        }
+    }
+}
+
+impl<A: Accessor> Layer<A> for RetryPolicyLayer {
+    type LayeredAccessor = RetryPolicyAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        RetryPolicyAccessor {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RetryPolicyAccessor<A> {
+    inner: A,
+    policy: Arc<RetryPolicy>,
+}
+
+impl<A> RetryPolicyAccessor<A> {
+    async fn retry<T, F, Fut>(&self, operation: &'static str, f: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(e) if e.is_temporary() && attempt < self.policy.max_retries => {
+                    increment_counter!(OBJECT_STORE_RETRY, OBJECT_STORE_OPERATION => operation);
+                    tokio::time::sleep(self.policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    if e.is_temporary() && self.policy.max_retries > 0 {
+                        increment_counter!(OBJECT_STORE_RETRY_EXHAUSTED, OBJECT_STORE_OPERATION => operation);
+                    }
+                    return Err(e);
+                }
+                Ok(v) => return Ok(v),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for RetryPolicyAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.retry("read", || self.inner.read(path, args.clone()))
+            .await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner.write(path, args).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.retry("stat", || self.inner.stat(path, args.clone()))
+            .await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.retry("delete", || self.inner.delete(path, args.clone()))
+            .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.retry("list", || self.inner.list(path, args.clone()))
+            .await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.retry("scan", || self.inner.scan(path, args.clone()))
+            .await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            min_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+            factor: 2.0,
+            jitter: false,
+        };
+        assert_eq!(Duration::from_millis(100), policy.backoff(0));
+        assert_eq!(Duration::from_millis(200), policy.backoff(1));
+        assert_eq!(Duration::from_millis(400), policy.backoff(2));
+        assert_eq!(Duration::from_millis(500), policy.backoff(3));
+        assert_eq!(Duration::from_millis(500), policy.backoff(100));
+
+        let policy = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        for attempt in 0..10 {
+            let delay = policy.backoff(attempt);
+            assert!(delay >= Duration::from_millis(50), "{delay:?}");
+            assert!(delay <= Duration::from_millis(500), "{delay:?}");
+        }
+    }
+}
@@ -12,6 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use opendal::ops::{OpDelete, OpList, OpRead, OpScan, OpStat, OpWrite};
+use opendal::raw::{
+    Accessor, Layer, LayeredAccessor, RpDelete, RpList, RpRead, RpScan, RpStat, RpWrite,
+};
+use opendal::{Error, ErrorKind};
+
 use crate::{ObjectStore, Result};
 
 pub struct TempFolder {
@@ -32,3 +41,179 @@ impl TempFolder {
         self.store.remove_all(&self.path).await
     }
 }
+
+/// Operations that failures can be injected into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectedOperation {
+    Read,
+    Write,
+    Stat,
+    Delete,
+    /// Both `list` and `scan`.
+    List,
+}
+
+#[derive(Debug)]
+struct FailureRule {
+    operation: InjectedOperation,
+    /// Fails paths containing this pattern.
+    pattern: String,
+    /// Remaining times to fail.
+    remaining: usize,
+    temporary: bool,
+}
+
+/// Injects failures into object store operations, so code paths on top of the
+/// object store can be tested against partial failures.
+///
+/// ```ignore
+/// let injector = FailureInjector::default();
+/// let store = store.layer(injector.layer());
+/// // Fails the next 2 reads of manifest files with a temporary error.
+/// injector.fail(InjectedOperation::Read, "manifest/", 2, true);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FailureInjector {
+    rules: Arc<Mutex<Vec<FailureRule>>>,
+}
+
+impl FailureInjector {
+    /// Fails the next `times` `operation`s on paths containing `pattern`. The
+    /// injected error is temporary (retryable) if `temporary` is true.
+    pub fn fail(&self, operation: InjectedOperation, pattern: &str, times: usize, temporary: bool) {
+        self.rules.lock().unwrap().push(FailureRule {
+            operation,
+            pattern: pattern.to_string(),
+            remaining: times,
+            temporary,
+        });
+    }
+
+    /// Removes all pending failures.
+    pub fn clear(&self) {
+        self.rules.lock().unwrap().clear();
+    }
+
+    /// Returns the number of pending failures.
+    pub fn pending(&self) -> usize {
+        self.rules
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|rule| rule.remaining)
+            .sum()
+    }
+
+    /// Returns a layer to inject failures into the object store.
+    pub fn layer(&self) -> FailureInjectionLayer {
+        FailureInjectionLayer {
+            injector: self.clone(),
+        }
+    }
+
+    fn check(&self, operation: InjectedOperation, path: &str) -> Result<()> {
+        let mut rules = self.rules.lock().unwrap();
+        let Some(index) = rules
+            .iter()
+            .position(|rule| rule.operation == operation && path.contains(&rule.pattern)) else { return Ok(()); };
+
+        let rule = &mut rules[index];
+        rule.remaining -= 1;
+        let temporary = rule.temporary;
+        if rule.remaining == 0 {
+            rules.remove(index);
+        }
+
+        let err = Error::new(ErrorKind::Unexpected, "injected failure")
+            .with_context("operation", format!("{operation:?}"))
+            .with_context("path", path);
+        Err(if temporary { err.set_temporary() } else { err })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FailureInjectionLayer {
+    injector: FailureInjector,
+}
+
+impl<A: Accessor> Layer<A> for FailureInjectionLayer {
+    type LayeredAccessor = FailureInjectionAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        FailureInjectionAccessor {
+            inner,
+            injector: self.injector.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FailureInjectionAccessor<A> {
+    inner: A,
+    injector: FailureInjector,
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for FailureInjectionAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.injector.check(InjectedOperation::Read, path)?;
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.injector.check(InjectedOperation::Write, path)?;
+        self.inner.write(path, args).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.injector.check(InjectedOperation::Stat, path)?;
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.injector.check(InjectedOperation::Delete, path)?;
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.injector.check(InjectedOperation::List, path)?;
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.injector.check(InjectedOperation::List, path)?;
+        self.inner.scan(path, args).await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.injector.check(InjectedOperation::Read, path)?;
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.injector.check(InjectedOperation::Write, path)?;
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.injector.check(InjectedOperation::List, path)?;
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.injector.check(InjectedOperation::List, path)?;
+        self.inner.blocking_scan(path, args)
+    }
+}
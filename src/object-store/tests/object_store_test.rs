@@ -14,13 +14,16 @@
 
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use common_telemetry::{logging, metric};
 use common_test_util::temp_dir::create_temp_dir;
 use object_store::cache_policy::LruCacheLayer;
+use object_store::read_only::ReadOnlyLayer;
+use object_store::retry::{RetryPolicy, RetryPolicyLayer};
 use object_store::services::{Fs, S3};
-use object_store::test_util::TempFolder;
+use object_store::test_util::{FailureInjector, InjectedOperation, TempFolder};
 use object_store::{util, ObjectStore, ObjectStoreBuilder};
 use opendal::raw::Accessor;
 use opendal::services::Oss;
@@ -101,6 +104,100 @@ async fn test_fs_backend() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_retry_with_injected_failures() -> Result<()> {
+    let data_dir = create_temp_dir("test_retry_with_injected_failures");
+    let mut builder = Fs::default();
+    builder.root(&data_dir.path().to_string_lossy());
+
+    let injector = FailureInjector::default();
+    let store = ObjectStore::new(builder)
+        .unwrap()
+        .finish()
+        .layer(injector.layer())
+        .layer(RetryPolicyLayer::new(RetryPolicy {
+            max_retries: 3,
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            ..Default::default()
+        }));
+
+    let file_name = "test_file";
+    store.write(file_name, "Hello, World!").await?;
+
+    // Temporary failures are retried.
+    injector.fail(InjectedOperation::Read, file_name, 2, true);
+    let bs = store.read(file_name).await?;
+    assert_eq!("Hello, World!", String::from_utf8(bs)?);
+    assert_eq!(0, injector.pending());
+
+    // Gives up after max retries.
+    injector.fail(InjectedOperation::Stat, file_name, 5, true);
+    assert!(store.stat(file_name).await.is_err());
+    assert_eq!(1, injector.pending());
+    injector.clear();
+
+    // Persistent failures are not retried.
+    injector.fail(InjectedOperation::Delete, file_name, 2, false);
+    assert!(store.delete(file_name).await.is_err());
+    assert_eq!(1, injector.pending());
+    injector.clear();
+
+    // Writes are not retried.
+    injector.fail(InjectedOperation::Write, file_name, 1, true);
+    assert!(store.write(file_name, "Hello").await.is_err());
+    assert_eq!(0, injector.pending());
+    let bs = store.read(file_name).await?;
+    assert_eq!("Hello, World!", String::from_utf8(bs)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_only_layer() -> Result<()> {
+    let data_dir = create_temp_dir("test_read_only_layer");
+    let mut builder = Fs::default();
+    builder.root(&data_dir.path().to_string_lossy());
+    let store = ObjectStore::new(builder).unwrap().finish();
+
+    let file_name = "test_file";
+    store.write(file_name, "Hello, World!").await?;
+
+    let read_only = store.clone().layer(ReadOnlyLayer);
+    let bs = read_only.read(file_name).await?;
+    assert_eq!("Hello, World!", String::from_utf8(bs)?);
+    assert_eq!(13, read_only.stat(file_name).await?.content_length());
+    let entries = util::collect(read_only.list("/").await?).await?;
+    assert_eq!(1, entries.len());
+
+    let err = read_only.write(file_name, "Hello").await.unwrap_err();
+    assert_eq!(opendal::ErrorKind::PermissionDenied, err.kind());
+    let err = read_only.delete(file_name).await.unwrap_err();
+    assert_eq!(opendal::ErrorKind::PermissionDenied, err.kind());
+    let err = read_only.create_dir("test_dir/").await.unwrap_err();
+    assert_eq!(opendal::ErrorKind::PermissionDenied, err.kind());
+    let err = read_only.copy(file_name, "copied_file").await.unwrap_err();
+    assert_eq!(opendal::ErrorKind::PermissionDenied, err.kind());
+    let err = read_only
+        .rename(file_name, "renamed_file")
+        .await
+        .unwrap_err();
+    assert_eq!(opendal::ErrorKind::PermissionDenied, err.kind());
+    let err = read_only
+        .remove(vec![file_name.to_string()])
+        .await
+        .unwrap_err();
+    assert_eq!(opendal::ErrorKind::PermissionDenied, err.kind());
+
+    // The object is left untouched.
+    let bs = store.read(file_name).await?;
+    assert_eq!("Hello, World!", String::from_utf8(bs)?);
+    let entries = util::collect(store.list("/").await?).await?;
+    assert_eq!(1, entries.len());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_s3_backend() -> Result<()> {
     logging::init_default_ut_logging();
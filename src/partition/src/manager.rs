@@ -18,10 +18,11 @@ use std::sync::Arc;
 use common_query::prelude::Expr;
 use datafusion_expr::{BinaryExpr, Expr as DfExpr, Operator};
 use datatypes::prelude::Value;
-use datatypes::schema::Schema;
+use datatypes::schema::{ColumnSchema, Schema};
 use meta_client::rpc::{Peer, TableName, TableRoute};
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::{RegionId, RegionNumber};
+use table::predicate::TimeRangePredicateBuilder;
 use table::requests::InsertRequest;
 
 use crate::columns::RangeColumnsPartitionRule;
@@ -221,6 +222,44 @@ impl PartitionRuleManager {
         Ok(regions)
     }
 
+    /// Prune `regions` by the time range of the filters on the time index column `ts_column`.
+    ///
+    /// The pruning only takes effect when the table is range partitioned by its time index, in
+    /// which case the regions whose partitions are out of the queried time range are dropped.
+    /// Otherwise `regions` are returned untouched.
+    ///
+    /// If none of the regions is in range, the first one is still kept: a distributed scan needs
+    /// at least one region, and it yields nothing after the filters are applied anyway.
+    pub fn prune_regions_by_time_range(
+        &self,
+        partition_rule: &PartitionRuleRef,
+        ts_column: &ColumnSchema,
+        filters: &[Expr],
+        regions: Vec<RegionNumber>,
+    ) -> Vec<RegionNumber> {
+        let Some(rule) = partition_rule.as_any().downcast_ref::<RangePartitionRule>() else {
+            return regions;
+        };
+        if *rule.column_name() != ts_column.name {
+            return regions;
+        }
+
+        let time_range = TimeRangePredicateBuilder::new(ts_column, filters).build();
+        let Some(in_range) = rule.find_regions_by_time_range(&time_range) else {
+            return regions;
+        };
+        let pruned = regions
+            .iter()
+            .filter(|x| in_range.contains(x))
+            .cloned()
+            .collect::<Vec<_>>();
+        if pruned.is_empty() {
+            regions.into_iter().take(1).collect()
+        } else {
+            pruned
+        }
+    }
+
     /// Split [InsertRequest] into [InsertRequestSplit] according to the partition rule
     /// of given table.
     pub async fn split_insert_request(
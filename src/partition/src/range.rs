@@ -14,6 +14,7 @@
 
 use std::any::Any;
 
+use common_time::range::TimestampRange;
 use datafusion_expr::Operator;
 use datatypes::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -94,6 +95,45 @@ impl RangePartitionRule {
     pub fn bounds(&self) -> &Vec<Value> {
         &self.bounds
     }
+
+    /// Finds the regions whose value range overlaps the time range. The `i`th region holds the
+    /// timestamps in `[bounds[i - 1], bounds[i])`, both ends being unbounded at the edges.
+    ///
+    /// Returns `None` if the bounds are not timestamps, i.e. the rule does not partition by time.
+    pub fn find_regions_by_time_range(&self, range: &TimestampRange) -> Option<Vec<RegionNumber>> {
+        let bounds = self
+            .bounds
+            .iter()
+            .map(|bound| match bound {
+                Value::Timestamp(ts) => Some(*ts),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if range.is_empty() {
+            return Some(vec![]);
+        }
+
+        let regions = self
+            .regions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                let lower = i.checked_sub(1).map(|j| bounds[j]);
+                let upper = bounds.get(*i);
+                let after_lower = match (lower, range.end()) {
+                    (Some(lower), Some(end)) => lower < *end,
+                    _ => true,
+                };
+                let before_upper = match (upper, range.start()) {
+                    (Some(upper), Some(start)) => start < upper,
+                    _ => true,
+                };
+                after_lower && before_upper
+            })
+            .map(|(_, region)| *region)
+            .collect();
+        Some(regions)
+    }
 }
 
 impl PartitionRule for RangePartitionRule {
@@ -249,4 +289,52 @@ mod test {
 
         test("b", Operator::Lt, "1", vec![1, 2, 3, 4]);
     }
+
+    #[test]
+    fn test_find_regions_by_time_range() {
+        use common_time::Timestamp;
+
+        // PARTITION BY RANGE (ts) (
+        //   PARTITION p1 VALUES LESS THAN (1000),
+        //   PARTITION p2 VALUES LESS THAN (2000),
+        //   PARTITION p3 VALUES LESS THAN (3000),
+        //   PARTITION p4 VALUES LESS THAN (MAXVALUE)
+        // )
+        let rule = RangePartitionRule::new(
+            "ts",
+            vec![
+                Value::Timestamp(Timestamp::new_millisecond(1000)),
+                Value::Timestamp(Timestamp::new_millisecond(2000)),
+                Value::Timestamp(Timestamp::new_millisecond(3000)),
+            ],
+            vec![1, 2, 3, 4],
+        );
+
+        let test = |range: TimestampRange, expected: Vec<RegionNumber>| {
+            assert_eq!(Some(expected), rule.find_regions_by_time_range(&range));
+        };
+        let ts = Timestamp::new_millisecond;
+
+        test(TimestampRange::min_to_max(), vec![1, 2, 3, 4]);
+        test(TimestampRange::from_start(ts(2500)), vec![3, 4]);
+        test(TimestampRange::from_start(ts(3000)), vec![4]);
+        test(TimestampRange::until_end(ts(1000), false), vec![1]);
+        test(TimestampRange::until_end(ts(1000), true), vec![1, 2]);
+        test(
+            TimestampRange::new_inclusive(Some(ts(1500)), Some(ts(2500))),
+            vec![2, 3],
+        );
+        test(TimestampRange::single(ts(2999)), vec![3]);
+        // Bounds and ranges in different time units are still compared properly.
+        test(
+            TimestampRange::from_start(Timestamp::new_second(2)),
+            vec![3, 4],
+        );
+        test(TimestampRange::empty(), vec![]);
+
+        let rule = RangePartitionRule::new("a", vec![Value::from(10)], vec![1, 2]);
+        assert!(rule
+            .find_regions_by_time_range(&TimestampRange::min_to_max())
+            .is_none());
+    }
 }
@@ -77,4 +77,8 @@ impl TableRoutes {
     pub async fn invalidate_table_route(&self, table_name: &TableName) {
         self.cache.invalidate(table_name).await
     }
+
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all()
+    }
 }
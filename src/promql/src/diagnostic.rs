@@ -0,0 +1,207 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Caret-style diagnostics that point at the part of a PromQL query causing a
+//! planner error.
+//!
+//! The parser doesn't keep the positions of AST nodes, so the offending text reported
+//! by [Error::offending_text] is searched in the tokens of the query instead. The query is
+//! tokenized like [crate::parser] does, whose rewrites keep the offsets of the query.
+
+use std::error::Error as StdError;
+
+use common_error::ext::BoxedError;
+
+use crate::error::Error;
+use crate::parser::{is_identifier_char, is_identifier_start, string_end};
+
+/// Byte range in a PromQL query, `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds the first identifier or string literal in `query` that is exactly `text`, e.g.
+/// `foo` in `rate(foo[5m])` and `some_metric{__field__="foo"}`, but not in `foo_total`,
+/// `some_metric{host="foo-1"}` or comments. The range of a string literal excludes the
+/// quotes.
+pub fn locate(query: &str, text: &str) -> Option<PositionRange> {
+    if text.is_empty() {
+        return None;
+    }
+    let bytes = query.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' | b'`' => {
+                let end = string_end(bytes, i)?;
+                if &query[i + 1..end - 1] == text {
+                    return Some(PositionRange {
+                        start: i + 1,
+                        end: end - 1,
+                    });
+                }
+                i = end;
+            }
+            b'#' => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |pos| i + pos)
+            }
+            // numbers and durations like `5m`
+            b if b.is_ascii_digit() => {
+                while i < bytes.len() && is_identifier_char(bytes[i]) {
+                    i += 1;
+                }
+            }
+            b if is_identifier_start(b) => {
+                let start = i;
+                while i < bytes.len() && is_identifier_char(bytes[i]) {
+                    i += 1;
+                }
+                if &query[start..i] == text {
+                    return Some(PositionRange { start, end: i });
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Renders `message` followed by the line of `query` that contains `range`, with
+/// carets under the range:
+///
+/// ```text
+/// Cannot find time index column in table foo
+///   rate(foo[5m])
+///        ^^^
+/// ```
+pub fn render(query: &str, range: PositionRange, message: &str) -> String {
+    let line_start = query[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = query[range.start..]
+        .find('\n')
+        .map_or(query.len(), |i| range.start + i);
+    // the range may cross lines, only underline the part in the first line
+    let end = range.end.min(line_end);
+
+    let padding = query[line_start..range.start].chars().count();
+    let width = query[range.start..end].chars().count().max(1);
+    format!(
+        "{message}\n  {}\n  {}{}",
+        &query[line_start..line_end],
+        " ".repeat(padding),
+        "^".repeat(width)
+    )
+}
+
+/// Looks for a PromQL planner error in the source chain of `err` and renders its
+/// diagnostic against `query`. Returns `None` if there is no planner error or the
+/// offending text can't be found in the query.
+pub fn diagnose(query: &str, err: &(dyn StdError + 'static)) -> Option<String> {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        let planner_error = e.downcast_ref::<Error>().or_else(|| {
+            e.downcast_ref::<BoxedError>()
+                .and_then(|boxed| boxed.as_any().downcast_ref::<Error>())
+        });
+        if let Some(planner_error) = planner_error {
+            let range = locate(query, planner_error.offending_text()?)?;
+            return Some(render(query, range, &planner_error.to_string()));
+        }
+        current = e.source();
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use common_error::mock::MockError;
+    use common_error::status_code::StatusCode;
+    use snafu::ResultExt;
+
+    use super::*;
+    use crate::error::{ColumnNotFoundSnafu, TimeIndexNotFoundSnafu};
+
+    #[test]
+    fn test_locate() {
+        assert_eq!(
+            Some(PositionRange { start: 5, end: 8 }),
+            locate("rate(foo[5m])", "foo")
+        );
+        assert_eq!(
+            Some(PositionRange { start: 12, end: 15 }),
+            locate("foo_total + foo", "foo")
+        );
+        assert_eq!(None, locate("foo_total", "foo"));
+        assert_eq!(None, locate("foo", ""));
+        assert_eq!(
+            Some(PositionRange { start: 23, end: 26 }),
+            locate(r#"some_metric{__field__="bar"}"#, "bar")
+        );
+
+        // Skips the comments, the durations and the strings only containing the text.
+        assert_eq!(
+            Some(PositionRange { start: 35, end: 36 }),
+            locate("# m\nrate(x{host=\"m-1\"}[5m]) + rate(m[5m])", "m")
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let query = "sum(rate(foo[5m]))";
+        assert_eq!(
+            "error\n  sum(rate(foo[5m]))\n           ^^^",
+            render(query, locate(query, "foo").unwrap(), "error")
+        );
+
+        let query = "bar\n+ foo";
+        assert_eq!(
+            "error\n  + foo\n    ^^^",
+            render(query, locate(query, "foo").unwrap(), "error")
+        );
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let query = "rate(foo[5m]) + bar";
+        let err: crate::error::Result<()> = TimeIndexNotFoundSnafu { table: "bar" }.fail();
+        let err = err.unwrap_err();
+        assert_eq!(
+            "Cannot find time index column in table bar\n  rate(foo[5m]) + bar\n                  ^^^",
+            diagnose(query, &err).unwrap()
+        );
+
+        // planner error wrapped by other errors
+        #[derive(Debug, snafu::Snafu)]
+        #[snafu(display("outer"))]
+        struct Outer {
+            source: BoxedError,
+        }
+        let err: std::result::Result<(), _> = ColumnNotFoundSnafu { col: "foo" }
+            .fail()
+            .map_err(BoxedError::new)
+            .context(OuterSnafu);
+        let diagnostic = diagnose(query, &err.unwrap_err()).unwrap();
+        assert!(diagnostic.ends_with("\n  rate(foo[5m]) + bar\n       ^^^"));
+
+        // not a planner error, or the text is not in query
+        let err = MockError::new(StatusCode::Internal);
+        assert!(diagnose(query, &err).is_none());
+        let err: crate::error::Result<()> = ColumnNotFoundSnafu { col: "baz" }.fail();
+        assert!(diagnose(query, &err.unwrap_err()).is_none());
+    }
+}
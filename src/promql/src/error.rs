@@ -131,6 +131,25 @@ impl ErrorExt for Error {
     }
 }
 
+impl Error {
+    /// Returns the part of PromQL query (metric or column name) this error is
+    /// about, if the planner knows which selector causes it.
+    pub fn offending_text(&self) -> Option<&str> {
+        use Error::*;
+        match self {
+            TimeIndexNotFound { table, .. }
+            | ValueNotFound { table, .. }
+            | TableNotFound { table, .. }
+                if !table.is_empty() && table != "unknown" =>
+            {
+                Some(table)
+            }
+            ColumnNotFound { col, .. } => Some(col),
+            _ => None,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 impl From<Error> for DataFusionError {
@@ -35,13 +35,17 @@ use datatypes::arrow::compute;
 use datatypes::arrow::error::Result as ArrowResult;
 use futures::{Stream, StreamExt};
 
-use crate::extension_plan::Millisecond;
+use crate::extension_plan::{is_stale_marker, Millisecond};
 
 /// Manipulate the input record batch to make it suitable for Instant Operator.
 ///
 /// This plan will try to align the input time series, for every timestamp between
 /// `start` and `end` with step `interval`. Find in the `lookback` range if data
 /// is missing at the given timestamp.
+///
+/// The series is stale at the given timestamp if the newest sample in the `lookback`
+/// range is NaN. Under strict staleness, only the Prometheus staleness markers make
+/// the series stale, while other NaN values are kept as is.
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct InstantManipulate {
     start: Millisecond,
@@ -51,6 +55,7 @@ pub struct InstantManipulate {
     time_index_column: String,
     /// A optional column for validating staleness
     field_column: Option<String>,
+    strict_staleness: bool,
     input: LogicalPlan,
 }
 
@@ -89,6 +94,7 @@ impl UserDefinedLogicalNodeCore for InstantManipulate {
             interval: self.interval,
             time_index_column: self.time_index_column.clone(),
             field_column: self.field_column.clone(),
+            strict_staleness: self.strict_staleness,
             input: inputs[0].clone(),
         }
     }
@@ -111,10 +117,17 @@ impl InstantManipulate {
             interval,
             time_index_column,
             field_column,
+            strict_staleness: false,
             input,
         }
     }
 
+    /// Only treats the Prometheus staleness markers as the end of series.
+    pub fn with_strict_staleness(mut self, strict_staleness: bool) -> Self {
+        self.strict_staleness = strict_staleness;
+        self
+    }
+
     pub fn to_execution_plan(&self, exec_input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
         Arc::new(InstantManipulateExec {
             start: self.start,
@@ -123,6 +136,7 @@ impl InstantManipulate {
             interval: self.interval,
             time_index_column: self.time_index_column.clone(),
             field_column: self.field_column.clone(),
+            strict_staleness: self.strict_staleness,
             input: exec_input,
             metric: ExecutionPlanMetricsSet::new(),
         })
@@ -137,6 +151,7 @@ pub struct InstantManipulateExec {
     interval: Millisecond,
     time_index_column: String,
     field_column: Option<String>,
+    strict_staleness: bool,
 
     input: Arc<dyn ExecutionPlan>,
     metric: ExecutionPlanMetricsSet,
@@ -179,6 +194,7 @@ impl ExecutionPlan for InstantManipulateExec {
             interval: self.interval,
             time_index_column: self.time_index_column.clone(),
             field_column: self.field_column.clone(),
+            strict_staleness: self.strict_staleness,
             input: children[0].clone(),
             metric: self.metric.clone(),
         }))
@@ -209,6 +225,7 @@ impl ExecutionPlan for InstantManipulateExec {
             interval: self.interval,
             time_index,
             field_index,
+            strict_staleness: self.strict_staleness,
             schema,
             input,
             metric: baseline_metric,
@@ -259,6 +276,7 @@ pub struct InstantManipulateStream {
     // Column index of TIME INDEX column's position in schema
     time_index: usize,
     field_index: Option<usize>,
+    strict_staleness: bool,
 
     schema: SchemaRef,
     input: SendableRecordBatchStream,
@@ -314,8 +332,7 @@ impl InstantManipulateStream {
                 let curr = ts_column.value(cursor);
                 match curr.cmp(&expected_ts) {
                     Ordering::Equal => {
-                        if let Some(field_column) = &field_column && field_column.value(cursor).is_nan() {
-                            // ignore the NaN value
+                        if self.is_stale(field_column, cursor) {
                             take_indices.push(None);
                         } else {
                             take_indices.push(Some(cursor as u64));
@@ -333,18 +350,17 @@ impl InstantManipulateStream {
             // then, search backward to lookback
             loop {
                 let curr = ts_column.value(cursor);
-                if let Some(field_column) = &field_column && field_column.value(cursor).is_nan() {
-                    // if the newest value is NaN, it means the value is stale, so we should not use it
-                    take_indices.push(None);
-                    break;
-                }
                 if curr + self.lookback_delta < expected_ts {
                     // not found in lookback, leave this field blank.
                     take_indices.push(None);
                     break;
                 } else if curr < expected_ts && curr + self.lookback_delta >= expected_ts {
-                    // find the expected value, push and break
-                    take_indices.push(Some(cursor as u64));
+                    // find the newest value in lookback, which can't be used if it's stale
+                    if self.is_stale(field_column, cursor) {
+                        take_indices.push(None);
+                    } else {
+                        take_indices.push(Some(cursor as u64));
+                    }
                     break;
                 } else if cursor == 0 {
                     // reach the first value and not found in lookback, leave this field blank
@@ -359,6 +375,19 @@ impl InstantManipulateStream {
         self.take_record_batch_optional(input, take_indices, aligned_ts)
     }
 
+    /// Whether the sample at `index` marks the series as stale.
+    fn is_stale(&self, field_column: Option<&Float64Array>, index: usize) -> bool {
+        let Some(field_column) = field_column else {
+            return false;
+        };
+        let value = field_column.value(index);
+        if self.strict_staleness {
+            is_stale_marker(value)
+        } else {
+            value.is_nan()
+        }
+    }
+
     /// Helper function to apply "take" on record batch.
     fn take_record_batch_optional(
         &self,
@@ -404,6 +433,7 @@ mod test {
     use datatypes::arrow_array::StringArray;
 
     use super::*;
+    use crate::extension_plan::STALE_NAN_BITS;
 
     const TIME_INDEX_COLUMN: &str = "timestamp";
 
@@ -444,6 +474,7 @@ mod test {
             interval,
             time_index_column: TIME_INDEX_COLUMN.to_string(),
             field_column: None,
+            strict_staleness: false,
             input: memory_exec,
             metric: ExecutionPlanMetricsSet::new(),
         });
@@ -692,4 +723,85 @@ mod test {
         );
         do_normalize_test(190_000, 300_000, 30_000, 10_000, expected).await;
     }
+
+    /// A series with a NaN sample at 30s, and ended by a staleness marker at 75s.
+    fn prepare_stale_test_data() -> MemoryExec {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(TIME_INDEX_COLUMN, TimestampMillisecondType::DATA_TYPE, true),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let timestamp_column = Arc::new(TimestampMillisecondArray::from_slice([
+            0, 30_000, 60_000, 75_000,
+        ])) as _;
+        let field_column = Arc::new(Float64Array::from_slice([
+            1.0,
+            f64::NAN,
+            1.0,
+            f64::from_bits(STALE_NAN_BITS),
+        ])) as _;
+        let data =
+            RecordBatch::try_new(schema.clone(), vec![timestamp_column, field_column]).unwrap();
+
+        MemoryExec::try_new(&[vec![data]], schema, None).unwrap()
+    }
+
+    async fn do_staleness_test(strict_staleness: bool, expected: String) {
+        let memory_exec = Arc::new(prepare_stale_test_data());
+        let manipulate_exec = Arc::new(InstantManipulateExec {
+            start: 0,
+            end: 120_000,
+            lookback_delta: 30_000,
+            interval: 10_000,
+            time_index_column: TIME_INDEX_COLUMN.to_string(),
+            field_column: Some("value".to_string()),
+            strict_staleness,
+            input: memory_exec,
+            metric: ExecutionPlanMetricsSet::new(),
+        });
+        let session_context = SessionContext::default();
+        let result =
+            datafusion::physical_plan::collect(manipulate_exec, session_context.task_ctx())
+                .await
+                .unwrap();
+        let result_literal = datatypes::arrow::util::pretty::pretty_format_batches(&result)
+            .unwrap()
+            .to_string();
+
+        assert_eq!(result_literal, expected);
+    }
+
+    #[tokio::test]
+    async fn nan_as_staleness() {
+        let expected = String::from(
+            "+---------------------+-------+\
+            \n| timestamp           | value |\
+            \n+---------------------+-------+\
+            \n| 1970-01-01T00:00:00 | 1.0   |\
+            \n| 1970-01-01T00:00:10 | 1.0   |\
+            \n| 1970-01-01T00:00:20 | 1.0   |\
+            \n| 1970-01-01T00:01:00 | 1.0   |\
+            \n| 1970-01-01T00:01:10 | 1.0   |\
+            \n+---------------------+-------+",
+        );
+        do_staleness_test(false, expected).await;
+    }
+
+    #[tokio::test]
+    async fn strict_staleness() {
+        let expected = String::from(
+            "+---------------------+-------+\
+            \n| timestamp           | value |\
+            \n+---------------------+-------+\
+            \n| 1970-01-01T00:00:00 | 1.0   |\
+            \n| 1970-01-01T00:00:10 | 1.0   |\
+            \n| 1970-01-01T00:00:20 | 1.0   |\
+            \n| 1970-01-01T00:00:30 | NaN   |\
+            \n| 1970-01-01T00:00:40 | NaN   |\
+            \n| 1970-01-01T00:00:50 | NaN   |\
+            \n| 1970-01-01T00:01:00 | 1.0   |\
+            \n| 1970-01-01T00:01:10 | 1.0   |\
+            \n+---------------------+-------+",
+        );
+        do_staleness_test(true, expected).await;
+    }
 }
@@ -34,7 +34,7 @@ use datatypes::arrow::error::Result as ArrowResult;
 use datatypes::arrow::record_batch::RecordBatch;
 use futures::{Stream, StreamExt};
 
-use crate::extension_plan::Millisecond;
+use crate::extension_plan::{is_stale_marker, Millisecond};
 
 /// Normalize the input record batch. Notice that for simplicity, this method assumes
 /// the input batch only contains sample points from one time series.
@@ -42,12 +42,14 @@ use crate::extension_plan::Millisecond;
 /// Roughly speaking, this method does these things:
 /// - bias sample's timestamp by offset
 /// - sort the record batch based on timestamp column
-/// - remove NaN values (optional)
+/// - remove NaN values (optional), or only the Prometheus staleness markers under strict
+///   staleness
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct SeriesNormalize {
     offset: Millisecond,
     time_index_column_name: String,
     need_filter_out_nan: bool,
+    strict_staleness: bool,
 
     input: LogicalPlan,
 }
@@ -84,6 +86,7 @@ impl UserDefinedLogicalNodeCore for SeriesNormalize {
             offset: self.offset,
             time_index_column_name: self.time_index_column_name.clone(),
             need_filter_out_nan: self.need_filter_out_nan,
+            strict_staleness: self.strict_staleness,
             input: inputs[0].clone(),
         }
     }
@@ -100,15 +103,23 @@ impl SeriesNormalize {
             offset,
             time_index_column_name: time_index_column_name.as_ref().to_string(),
             need_filter_out_nan,
+            strict_staleness: false,
             input,
         }
     }
 
+    /// Only filters out the Prometheus staleness markers instead of all the NaN values.
+    pub fn with_strict_staleness(mut self, strict_staleness: bool) -> Self {
+        self.strict_staleness = strict_staleness;
+        self
+    }
+
     pub fn to_execution_plan(&self, exec_input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
         Arc::new(SeriesNormalizeExec {
             offset: self.offset,
             time_index_column_name: self.time_index_column_name.clone(),
             need_filter_out_nan: self.need_filter_out_nan,
+            strict_staleness: self.strict_staleness,
             input: exec_input,
             metric: ExecutionPlanMetricsSet::new(),
         })
@@ -120,6 +131,7 @@ pub struct SeriesNormalizeExec {
     offset: Millisecond,
     time_index_column_name: String,
     need_filter_out_nan: bool,
+    strict_staleness: bool,
 
     input: Arc<dyn ExecutionPlan>,
     metric: ExecutionPlanMetricsSet,
@@ -155,6 +167,7 @@ impl ExecutionPlan for SeriesNormalizeExec {
             offset: self.offset,
             time_index_column_name: self.time_index_column_name.clone(),
             need_filter_out_nan: self.need_filter_out_nan,
+            strict_staleness: self.strict_staleness,
             input: children[0].clone(),
             metric: self.metric.clone(),
         }))
@@ -177,6 +190,7 @@ impl ExecutionPlan for SeriesNormalizeExec {
             offset: self.offset,
             time_index,
             need_filter_out_nan: self.need_filter_out_nan,
+            strict_staleness: self.strict_staleness,
             schema,
             input,
             metric: baseline_metric,
@@ -209,6 +223,7 @@ pub struct SeriesNormalizeStream {
     // Column index of TIME INDEX column's position in schema
     time_index: usize,
     need_filter_out_nan: bool,
+    strict_staleness: bool,
 
     schema: SchemaRef,
     input: SendableRecordBatchStream,
@@ -247,13 +262,18 @@ impl SeriesNormalizeStream {
             return Ok(ordered_batch);
         }
 
-        // TODO(ruihang): consider the "special NaN"
-        // filter out NaN
+        // filter out NaN, or only the staleness markers under strict staleness
         let mut filter = vec![true; input.num_rows()];
         for column in ordered_batch.columns() {
             if let Some(float_column) = column.as_any().downcast_ref::<Float64Array>() {
                 for (i, flag) in filter.iter_mut().enumerate() {
-                    if float_column.value(i).is_nan() {
+                    let value = float_column.value(i);
+                    let filtered = if self.strict_staleness {
+                        is_stale_marker(value)
+                    } else {
+                        value.is_nan()
+                    };
+                    if filtered {
                         *flag = false;
                     }
                 }
@@ -300,6 +320,7 @@ mod test {
     use datatypes::arrow_array::StringArray;
 
     use super::*;
+    use crate::extension_plan::STALE_NAN_BITS;
 
     const TIME_INDEX_COLUMN: &str = "timestamp";
 
@@ -331,6 +352,7 @@ mod test {
             offset: 0,
             time_index_column_name: TIME_INDEX_COLUMN.to_string(),
             need_filter_out_nan: true,
+            strict_staleness: false,
             input: memory_exec,
             metric: ExecutionPlanMetricsSet::new(),
         });
@@ -364,6 +386,7 @@ mod test {
             offset: 1_000, // offset 1s
             time_index_column_name: TIME_INDEX_COLUMN.to_string(),
             need_filter_out_nan: true,
+            strict_staleness: false,
             input: memory_exec,
             metric: ExecutionPlanMetricsSet::new(),
         });
@@ -389,4 +412,63 @@ mod test {
 
         assert_eq!(result_literal, expected);
     }
+    async fn do_filter_staleness_test(strict_staleness: bool, expected: String) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(TIME_INDEX_COLUMN, TimestampMillisecondType::DATA_TYPE, true),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let timestamp_column =
+            Arc::new(TimestampMillisecondArray::from_slice([0, 30_000, 60_000])) as _;
+        let field_column = Arc::new(Float64Array::from_slice([
+            1.0,
+            f64::NAN,
+            f64::from_bits(STALE_NAN_BITS),
+        ])) as _;
+        let data =
+            RecordBatch::try_new(schema.clone(), vec![timestamp_column, field_column]).unwrap();
+        let memory_exec = Arc::new(MemoryExec::try_new(&[vec![data]], schema, None).unwrap());
+
+        let normalize_exec = Arc::new(SeriesNormalizeExec {
+            offset: 0,
+            time_index_column_name: TIME_INDEX_COLUMN.to_string(),
+            need_filter_out_nan: true,
+            strict_staleness,
+            input: memory_exec,
+            metric: ExecutionPlanMetricsSet::new(),
+        });
+        let session_context = SessionContext::default();
+        let result = datafusion::physical_plan::collect(normalize_exec, session_context.task_ctx())
+            .await
+            .unwrap();
+        let result_literal = datatypes::arrow::util::pretty::pretty_format_batches(&result)
+            .unwrap()
+            .to_string();
+
+        assert_eq!(result_literal, expected);
+    }
+
+    #[tokio::test]
+    async fn test_filter_out_nan() {
+        let expected = String::from(
+            "+---------------------+-------+\
+            \n| timestamp           | value |\
+            \n+---------------------+-------+\
+            \n| 1970-01-01T00:00:00 | 1.0   |\
+            \n+---------------------+-------+",
+        );
+        do_filter_staleness_test(false, expected).await;
+    }
+
+    #[tokio::test]
+    async fn test_filter_out_stale_marker() {
+        let expected = String::from(
+            "+---------------------+-------+\
+            \n| timestamp           | value |\
+            \n+---------------------+-------+\
+            \n| 1970-01-01T00:00:00 | 1.0   |\
+            \n| 1970-01-01T00:00:30 | NaN   |\
+            \n+---------------------+-------+",
+        );
+        do_filter_staleness_test(true, expected).await;
+    }
 }
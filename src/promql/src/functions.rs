@@ -13,9 +13,11 @@
 // limitations under the License.
 
 mod aggr_over_time;
+mod anomaly;
 mod changes;
 mod deriv;
 mod extrapolate_rate;
+mod forecast;
 mod holt_winters;
 mod idelta;
 mod predict_linear;
@@ -28,12 +30,14 @@ pub use aggr_over_time::{
     AbsentOverTime, AvgOverTime, CountOverTime, LastOverTime, MaxOverTime, MinOverTime,
     PresentOverTime, StddevOverTime, StdvarOverTime, SumOverTime,
 };
+pub use anomaly::{MadOverTime, ZscoreOverTime};
 pub use changes::Changes;
 use datafusion::arrow::array::{ArrayRef, Float64Array, TimestampMillisecondArray};
 use datafusion::error::DataFusionError;
 use datafusion::physical_plan::ColumnarValue;
 pub use deriv::Deriv;
 pub use extrapolate_rate::{Delta, Increase, Rate};
+pub use forecast::{DoubleExponentialForecast, SeasonalForecast};
 pub use holt_winters::HoltWinters;
 pub use idelta::IDelta;
 pub use predict_linear::PredictLinear;
@@ -0,0 +1,103 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions to detect anomalies of the points in a time window.
+
+use std::sync::Arc;
+
+use common_function::scalars::anomaly::{median_absolute_deviation, zscore};
+use common_function_macro::range_fn;
+use datafusion::arrow::array::{Float64Array, TimestampMillisecondArray};
+use datafusion::arrow::datatypes::TimeUnit;
+use datafusion::common::DataFusionError;
+use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::physical_plan::ColumnarValue;
+use datatypes::arrow::array::Array;
+use datatypes::arrow::datatypes::DataType;
+
+use crate::functions::extract_array;
+use crate::range_array::RangeArray;
+
+/// The z-score of the most recent point in the specified interval, i.e. how many population
+/// standard deviations it is away from the mean of all points in the interval.
+#[range_fn(
+    name = "ZscoreOverTime",
+    ret = "Float64Array",
+    display_name = "prom_zscore_over_time"
+)]
+pub fn zscore_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    // The points in a range are ordered by the timestamp.
+    let values = values.values();
+    zscore(values, *values.last()?)
+}
+
+/// The median absolute deviation of all points in the specified interval, which is a robust
+/// measure of the variability that is not skewed by the outliers.
+#[range_fn(
+    name = "MadOverTime",
+    ret = "Float64Array",
+    display_name = "prom_mad_over_time"
+)]
+pub fn mad_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    median_absolute_deviation(values.values())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::functions::test_util::simple_range_udf_runner;
+
+    fn build_test_range_arrays() -> (RangeArray, RangeArray) {
+        let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+            [1000i64, 2000, 3000, 4000, 5000, 6000]
+                .into_iter()
+                .map(Some),
+        ));
+        let ranges = [
+            (0, 5),
+            (0, 2),
+            (0, 1), // only 1 element
+            (2, 0), // empty range
+            (4, 2), // same values
+        ];
+        let values_array = Arc::new(Float64Array::from_iter([1.0, 2.0, 3.0, 4.0, 10.0, 10.0]));
+
+        let ts_range_array = RangeArray::from_ranges(ts_array, ranges).unwrap();
+        let value_range_array = RangeArray::from_ranges(values_array, ranges).unwrap();
+
+        (ts_range_array, value_range_array)
+    }
+
+    #[test]
+    fn calculate_zscore_over_time() {
+        let (ts_array, value_array) = build_test_range_arrays();
+        simple_range_udf_runner(
+            ZscoreOverTime::scalar_udf(),
+            ts_array,
+            value_array,
+            vec![Some(6.0 / 10f64.sqrt()), Some(1.0), None, None, None],
+        );
+    }
+
+    #[test]
+    fn calculate_mad_over_time() {
+        let (ts_array, value_array) = build_test_range_arrays();
+        simple_range_udf_runner(
+            MadOverTime::scalar_udf(),
+            ts_array,
+            value_array,
+            vec![Some(1.0), Some(0.5), Some(0.0), None, Some(0.0)],
+        );
+    }
+}
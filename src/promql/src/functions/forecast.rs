@@ -0,0 +1,207 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Forecasting range functions beyond `holt_winters`. Unlike `holt_winters`, which returns
+//! the smoothed value at the last point, these functions extrapolate to a horizon given in
+//! seconds.
+
+use std::sync::Arc;
+
+use common_function::scalars::forecast::{double_exponential_forecast, seasonal_forecast};
+use datafusion::arrow::array::{Float64Array, TimestampMillisecondArray};
+use datafusion::arrow::datatypes::TimeUnit;
+use datafusion::common::DataFusionError;
+use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::physical_plan::ColumnarValue;
+use datatypes::arrow::array::Array;
+use datatypes::arrow::datatypes::DataType;
+
+use crate::error;
+use crate::functions::extract_array;
+use crate::range_array::RangeArray;
+
+/// `double_exponential_forecast(v range-vector, sf scalar, tf scalar, horizon scalar)`
+/// forecasts the value `horizon` seconds after the last sample by Holt's linear method.
+pub struct DoubleExponentialForecast {
+    sf: f64,
+    tf: f64,
+    horizon: f64,
+}
+
+impl DoubleExponentialForecast {
+    pub const fn name() -> &'static str {
+        "prom_double_exponential_forecast"
+    }
+
+    pub fn scalar_udf(sf: f64, tf: f64, horizon: f64) -> ScalarUDF {
+        let this = Self { sf, tf, horizon };
+        forecast_udf(Self::name(), move |times, values| {
+            double_exponential_forecast(times, values, this.sf, this.tf, this.horizon)
+        })
+    }
+}
+
+/// `seasonal_forecast(v range-vector, season scalar, horizon scalar)` forecasts the value
+/// `horizon` seconds after the last sample, by decomposing the samples into a linear trend
+/// and a seasonal component with a period of `season` seconds.
+pub struct SeasonalForecast {
+    season: f64,
+    horizon: f64,
+}
+
+impl SeasonalForecast {
+    pub const fn name() -> &'static str {
+        "prom_seasonal_forecast"
+    }
+
+    pub fn scalar_udf(season: f64, horizon: f64) -> ScalarUDF {
+        let this = Self { season, horizon };
+        forecast_udf(Self::name(), move |times, values| {
+            seasonal_forecast(times, values, this.season, this.horizon)
+        })
+    }
+}
+
+fn forecast_udf<F>(name: &'static str, forecast: F) -> ScalarUDF
+where
+    F: Fn(&[i64], &[f64]) -> Option<f64> + Send + Sync + 'static,
+{
+    // time index column and value column
+    let input_type = vec![
+        RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None)),
+        RangeArray::convert_data_type(DataType::Float64),
+    ];
+    ScalarUDF {
+        name: name.to_string(),
+        signature: Signature::new(TypeSignature::Exact(input_type), Volatility::Immutable),
+        return_type: Arc::new(|_| Ok(Arc::new(DataType::Float64))),
+        fun: Arc::new(move |input| calc(name, input, &forecast)),
+    }
+}
+
+fn calc<F>(
+    name: &str,
+    input: &[ColumnarValue],
+    forecast: &F,
+) -> Result<ColumnarValue, DataFusionError>
+where
+    F: Fn(&[i64], &[f64]) -> Option<f64>,
+{
+    assert_eq!(input.len(), 2);
+    let ts_array = extract_array(&input[0])?;
+    let value_array = extract_array(&input[1])?;
+
+    let ts_range: RangeArray = RangeArray::try_new(ts_array.to_data().into())?;
+    let value_range: RangeArray = RangeArray::try_new(value_array.to_data().into())?;
+    error::ensure(
+        ts_range.len() == value_range.len(),
+        DataFusionError::Execution(format!(
+            "{}: input arrays should have the same length, found {} and {}",
+            name,
+            ts_range.len(),
+            value_range.len()
+        )),
+    )?;
+    error::ensure(
+        ts_range.value_type() == DataType::Timestamp(TimeUnit::Millisecond, None),
+        DataFusionError::Execution(format!(
+            "{}: expect TimestampMillisecond as time index array's type, found {}",
+            name,
+            ts_range.value_type()
+        )),
+    )?;
+    error::ensure(
+        value_range.value_type() == DataType::Float64,
+        DataFusionError::Execution(format!(
+            "{}: expect Float64 as value array's type, found {}",
+            name,
+            value_range.value_type()
+        )),
+    )?;
+
+    let mut result_array = Vec::with_capacity(ts_range.len());
+    for index in 0..ts_range.len() {
+        let timestamps = ts_range.get(index).unwrap();
+        let timestamps = timestamps
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap()
+            .values();
+        let values = value_range.get(index).unwrap();
+        let values = values
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap()
+            .values();
+        error::ensure(
+            timestamps.len() == values.len(),
+            DataFusionError::Execution(format!(
+                "{}: input arrays should have the same length, found {} and {}",
+                name,
+                timestamps.len(),
+                values.len()
+            )),
+        )?;
+        result_array.push(forecast(timestamps, values));
+    }
+
+    Ok(ColumnarValue::Array(Arc::new(Float64Array::from_iter(
+        result_array,
+    ))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::test_util::simple_range_udf_runner;
+
+    #[test]
+    fn test_double_exponential_forecast() {
+        let ranges = [(0, 5), (0, 1)];
+        let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+            [0i64, 10000, 20000, 30000, 40000].into_iter().map(Some),
+        ));
+        let values_array = Arc::new(Float64Array::from_iter([0.0, 2.0, 4.0, 6.0, 8.0]));
+        let ts_range_array = RangeArray::from_ranges(ts_array, ranges).unwrap();
+        let value_range_array = RangeArray::from_ranges(values_array, ranges).unwrap();
+        simple_range_udf_runner(
+            DoubleExponentialForecast::scalar_udf(0.5, 0.5, 30.0),
+            ts_range_array,
+            value_range_array,
+            vec![Some(14.0), None],
+        );
+    }
+
+    #[test]
+    fn test_seasonal_forecast() {
+        // A season of 4 minutes without trend.
+        for (horizon, expected) in [(60.0, 5.0), (120.0, 15.0)] {
+            let ranges = [(0, 8)];
+            let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+                (0..8).map(|i| Some(i * 60_000)),
+            ));
+            let values_array = Arc::new(Float64Array::from_iter([
+                5.0, 15.0, 15.0, 5.0, 5.0, 15.0, 15.0, 5.0,
+            ]));
+            let ts_range_array = RangeArray::from_ranges(ts_array, ranges).unwrap();
+            let value_range_array = RangeArray::from_ranges(values_array, ranges).unwrap();
+            simple_range_udf_runner(
+                SeasonalForecast::scalar_udf(240.0, horizon),
+                ts_range_array,
+                value_range_array,
+                vec![Some(expected)],
+            );
+        }
+    }
+}
@@ -15,8 +15,10 @@
 #![feature(option_get_or_insert_default)]
 #![feature(let_chains)]
 
+pub mod diagnostic;
 pub mod error;
 pub mod extension_plan;
 pub mod functions;
+pub mod parser;
 pub mod planner;
 pub mod range_array;
@@ -0,0 +1,502 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parses PromQL with the extensions not supported by [promql_parser] yet.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use promql_parser::parser::{
+    self, AggregateExpr, BinaryExpr, Call, Expr, NumberLiteral, ParenExpr, StringLiteral,
+    SubqueryExpr, UnaryExpr,
+};
+
+/// The function accepting a comma separated list of quantiles as a string literal, like
+/// `quantile_over_time("0.5,0.9,0.99", some_metric[5m])`, to compute several quantiles from
+/// one range selection.
+const QUANTILE_OVER_TIME: &str = "quantile_over_time";
+
+/// The number taking the place of a quantile list, so the query is accepted by the parser.
+/// It's padded by spaces to the length of the list.
+const QUANTILE_LIST_PLACEHOLDER: &str = "0";
+
+/// The range functions unknown to the parser, and the number of their arguments after the
+/// range vector, which must be number literals.
+const EXTENSION_FUNCTIONS: [(&str, usize); 4] = [
+    ("zscore_over_time", 0),
+    ("mad_over_time", 0),
+    ("double_exponential_forecast", 3),
+    ("seasonal_forecast", 2),
+];
+
+/// The function taking the place of an extension function, which only takes the range vector.
+/// It's not longer than the names of the [EXTENSION_FUNCTIONS], so it can be padded by spaces
+/// to the length of the replaced name.
+const EXTENSION_FUNCTION_PLACEHOLDER: &str = "min_over_time";
+
+/// An extension function call replaced by the placeholder.
+#[derive(Debug, PartialEq)]
+struct ExtensionCall {
+    name: &'static str,
+    args: Vec<f64>,
+}
+
+/// Parses the PromQL `query` like [parser::parse], except that:
+/// - `quantile_over_time` also accepts a string literal of quantiles as its first argument.
+/// - the [EXTENSION_FUNCTIONS] are accepted.
+///
+/// The parser only accepts a number as the quantile and only knows the functions of
+/// Prometheus, so the string literals and the extension function calls are replaced by
+/// placeholders before parsing, and put back into the parsed expression afterwards. The
+/// placeholders and the removed arguments are padded by whitespaces, so the rewritten query
+/// keeps the byte offsets and lines of the `query`, and the positions reported by the parser
+/// still point at the `query`.
+pub fn parse(query: &str) -> Result<Expr, String> {
+    let (query, quantile_lists) = replace_quantile_lists(query);
+    let (query, extension_calls) = replace_extension_calls(&query)?;
+    let mut expr = parser::parse(&query)?;
+    if !extension_calls.is_empty() {
+        let mut ordinal = 0;
+        visit_calls(&mut expr, &mut |Call { func, args }| {
+            if func.name != EXTENSION_FUNCTION_PLACEHOLDER {
+                return;
+            }
+            if let Some(call) = extension_calls.get(&ordinal) {
+                func.name = call.name;
+                args.args.extend(
+                    call.args
+                        .iter()
+                        .map(|val| Box::new(Expr::NumberLiteral(NumberLiteral { val: *val }))),
+                );
+            }
+            ordinal += 1;
+        });
+    }
+    if !quantile_lists.is_empty() {
+        restore_quantile_lists(&mut expr, &quantile_lists);
+    }
+    Ok(expr)
+}
+
+/// Replaces the quantile lists in the `query` by the placeholder. Returns the rewritten query
+/// and the lists keyed by the ordinal of their `quantile_over_time` calls in the query.
+fn replace_quantile_lists(query: &str) -> (String, HashMap<usize, String>) {
+    let bytes = query.as_bytes();
+    let mut rewritten = String::with_capacity(query.len());
+    let mut quantile_lists = HashMap::new();
+    let mut ordinal = 0;
+    // The end of the query copied to `rewritten`.
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' | b'`' => i = string_end(bytes, i).unwrap_or(bytes.len()),
+            b'#' => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |pos| i + pos)
+            }
+            b if is_identifier_start(b) => {
+                let start = i;
+                while i < bytes.len() && is_identifier_char(bytes[i]) {
+                    i += 1;
+                }
+                let open = skip_whitespaces(bytes, i);
+                if &query[start..i] != QUANTILE_OVER_TIME || bytes.get(open) != Some(&b'(') {
+                    continue;
+                }
+
+                let arg_start = skip_whitespaces(bytes, open + 1);
+                if matches!(bytes.get(arg_start), Some(b'"' | b'\'' | b'`'))
+                    && let Some(arg_end) = string_end(bytes, arg_start)
+                {
+                    rewritten.push_str(&query[copied..arg_start]);
+                    rewritten.push_str(QUANTILE_LIST_PLACEHOLDER);
+                    rewritten.push_str(&blank(
+                        &query[arg_start + QUANTILE_LIST_PLACEHOLDER.len()..arg_end],
+                    ));
+                    copied = arg_end;
+                    let _ = quantile_lists
+                        .insert(ordinal, query[arg_start + 1..arg_end - 1].to_string());
+                    i = arg_end;
+                }
+                ordinal += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    rewritten.push_str(&query[copied..]);
+    (rewritten, quantile_lists)
+}
+
+/// Puts the quantile lists back into the `quantile_over_time` calls.
+fn restore_quantile_lists(expr: &mut Expr, quantile_lists: &HashMap<usize, String>) {
+    let mut ordinal = 0;
+    visit_calls(expr, &mut |Call { func, args }| {
+        if func.name != QUANTILE_OVER_TIME {
+            return;
+        }
+        if let Some(quantiles) = quantile_lists.get(&ordinal)
+            && let Some(arg) = args.args.first_mut()
+            && matches!(**arg, Expr::NumberLiteral(NumberLiteral { .. }))
+        {
+            *arg = Box::new(Expr::StringLiteral(StringLiteral {
+                val: quantiles.clone(),
+            }));
+        }
+        ordinal += 1;
+    });
+}
+
+/// Replaces the [EXTENSION_FUNCTIONS] calls in the `query` by the placeholder function with
+/// only the range vector. Returns the rewritten query and the replaced calls keyed by the
+/// ordinal of their placeholder calls in the query.
+fn replace_extension_calls(query: &str) -> Result<(String, HashMap<usize, ExtensionCall>), String> {
+    let mut extension_calls = HashMap::new();
+    let rewritten = rewrite_extension_calls(query, &mut extension_calls, &mut 0)?;
+    Ok((rewritten, extension_calls))
+}
+
+fn rewrite_extension_calls(
+    query: &str,
+    extension_calls: &mut HashMap<usize, ExtensionCall>,
+    ordinal: &mut usize,
+) -> Result<String, String> {
+    let bytes = query.as_bytes();
+    let mut rewritten = String::with_capacity(query.len());
+    // The end of the query copied to `rewritten`.
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' | b'`' => i = string_end(bytes, i).unwrap_or(bytes.len()),
+            b'#' => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |pos| i + pos)
+            }
+            b if is_identifier_start(b) => {
+                let start = i;
+                while i < bytes.len() && is_identifier_char(bytes[i]) {
+                    i += 1;
+                }
+                let open = skip_whitespaces(bytes, i);
+                if bytes.get(open) != Some(&b'(') {
+                    continue;
+                }
+                let name = &query[start..i];
+                if name == EXTENSION_FUNCTION_PLACEHOLDER {
+                    *ordinal += 1;
+                    continue;
+                }
+                let extension = EXTENSION_FUNCTIONS.iter().find(|(f, _)| *f == name);
+                let Some(&(name, num_args)) = extension else { continue };
+                // Leaves the unclosed call to the parser.
+                let Some((args, end)) = split_args(bytes, open) else {
+                    continue;
+                };
+                if args.len() != num_args + 1 {
+                    return Err(format!(
+                        "expected {} argument(s) in call to \"{}\", got {}",
+                        num_args + 1,
+                        name,
+                        args.len()
+                    ));
+                }
+                let number_args = args[1..]
+                    .iter()
+                    .map(|arg| {
+                        let arg = query[arg.clone()].trim();
+                        arg.parse::<f64>().map_err(|_| {
+                            format!(
+                                "expected a number literal in call to \"{name}\", got \"{arg}\""
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let _ = extension_calls.insert(
+                    *ordinal,
+                    ExtensionCall {
+                        name,
+                        args: number_args,
+                    },
+                );
+                *ordinal += 1;
+
+                rewritten.push_str(&query[copied..start]);
+                rewritten.push_str(EXTENSION_FUNCTION_PLACEHOLDER);
+                rewritten.push_str(&blank(
+                    &query[start + EXTENSION_FUNCTION_PLACEHOLDER.len()..start + name.len()],
+                ));
+                rewritten.push_str(&query[start + name.len()..=open]);
+                // The range vector may have extension function calls too, like a subquery.
+                rewritten.push_str(&rewrite_extension_calls(
+                    &query[args[0].clone()],
+                    extension_calls,
+                    ordinal,
+                )?);
+                rewritten.push_str(&blank(&query[args[0].end..end - 1]));
+                rewritten.push(')');
+                copied = end;
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    rewritten.push_str(&query[copied..]);
+    Ok(rewritten)
+}
+
+/// Splits the arguments of the call whose opening parenthesis is at `open`. Returns the ranges
+/// of the arguments and the position after the closing parenthesis, or `None` if the call is
+/// not closed.
+fn split_args(bytes: &[u8], open: usize) -> Option<(Vec<Range<usize>>, usize)> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut arg_start = open + 1;
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' | b'`' => {
+                i = string_end(bytes, i)?;
+                continue;
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' if depth == 0 => {
+                args.push(arg_start..i);
+                return Some((args, i + 1));
+            }
+            b')' | b']' | b'}' => depth -= 1,
+            b',' if depth == 0 => {
+                args.push(arg_start..i);
+                arg_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Visits the calls in the `expr` in the same order as they appear in the query, a call is
+/// visited before its arguments.
+fn visit_calls<F>(expr: &mut Expr, f: &mut F)
+where
+    F: FnMut(&mut Call),
+{
+    match expr {
+        Expr::Aggregate(AggregateExpr { expr, param, .. }) => {
+            if let Some(param) = param {
+                visit_calls(param, f);
+            }
+            visit_calls(expr, f);
+        }
+        Expr::Unary(UnaryExpr { expr })
+        | Expr::Paren(ParenExpr { expr })
+        | Expr::Subquery(SubqueryExpr { expr, .. }) => visit_calls(expr, f),
+        Expr::Binary(BinaryExpr { lhs, rhs, .. }) => {
+            visit_calls(lhs, f);
+            visit_calls(rhs, f);
+        }
+        Expr::Call(call) => {
+            f(call);
+            for arg in &mut call.args.args {
+                visit_calls(arg, f);
+            }
+        }
+        Expr::NumberLiteral(_)
+        | Expr::StringLiteral(_)
+        | Expr::VectorSelector(_)
+        | Expr::MatrixSelector(_)
+        | Expr::Extension(_) => {}
+    }
+}
+
+/// Replaces the `text` by spaces of the same byte length, except the line breaks.
+fn blank(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\n' => "\n".to_string(),
+            c => " ".repeat(c.len_utf8()),
+        })
+        .collect()
+}
+
+/// Returns the position after the closing quote of the string starting at `start`, or `None`
+/// if the string is not closed. Only the raw strings quoted by backticks have no escapes.
+pub(crate) fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if quote != b'`' => i += 2,
+            b if b == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn skip_whitespaces(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+pub(crate) fn is_identifier_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b':'
+}
+
+pub(crate) fn is_identifier_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b':'
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn quantile_arg(expr: &Expr) -> &Expr {
+        let Expr::Call(Call { args, .. }) = expr else {
+            unreachable!()
+        };
+        &args.args[0]
+    }
+
+    #[test]
+    fn test_parse_quantile_list() {
+        let expr = parse(r#"quantile_over_time ( "0.5, 0.9" , some_metric[5m])"#).unwrap();
+        assert!(matches!(
+            quantile_arg(&expr),
+            Expr::StringLiteral(StringLiteral { val }) if val == "0.5, 0.9"
+        ));
+
+        // A single quantile is still a number.
+        let expr = parse("quantile_over_time(0.5, some_metric[5m])").unwrap();
+        assert!(matches!(
+            quantile_arg(&expr),
+            Expr::NumberLiteral(NumberLiteral { val }) if *val == 0.5
+        ));
+
+        // Only the calls with lists are changed, the names in strings are ignored.
+        let query = r#"topk(1, quantile_over_time(0.1, a{b="quantile_over_time('0.2')"}[5m]))
+            / on() quantile_over_time(`0.3,0.4`, c[1m])"#;
+        let Expr::Binary(BinaryExpr { lhs, rhs, .. }) = parse(query).unwrap() else {
+            unreachable!()
+        };
+        let Expr::Aggregate(AggregateExpr { expr, .. }) = *lhs else {
+            unreachable!()
+        };
+        assert!(matches!(
+            quantile_arg(&expr),
+            Expr::NumberLiteral(NumberLiteral { val }) if *val == 0.1
+        ));
+        assert!(matches!(
+            quantile_arg(&rhs),
+            Expr::StringLiteral(StringLiteral { val }) if val == "0.3,0.4"
+        ));
+
+        // The other functions still reject strings.
+        assert!(parse(r#"holt_winters("0.5", some_metric[5m], 0.1)"#).is_err());
+        assert!(parse(r#"quantile_over_time("0.5", some_metric[5m]"#).is_err());
+    }
+
+    #[test]
+    fn test_replace_quantile_lists() {
+        let (query, lists) = replace_quantile_lists(r#"quantile_over_time("0.5,0.9", m[5m])"#);
+        assert_eq!("quantile_over_time(0        , m[5m])", query);
+        assert_eq!(HashMap::from([(0, "0.5,0.9".to_string())]), lists);
+
+        // unclosed string
+        let (query, lists) = replace_quantile_lists(r#"quantile_over_time("0.5, m[5m])"#);
+        assert_eq!(r#"quantile_over_time("0.5, m[5m])"#, query);
+        assert!(lists.is_empty());
+    }
+
+    #[test]
+    fn test_parse_extension_functions() {
+        let expr = parse("seasonal_forecast ( some_metric[1h], 600, 1e2 )").unwrap();
+        let Expr::Call(Call { func, args }) = expr else {
+            unreachable!()
+        };
+        assert_eq!("seasonal_forecast", func.name);
+        assert_eq!(3, args.args.len());
+        assert!(matches!(*args.args[0], Expr::MatrixSelector(_)));
+        assert!(matches!(
+            *args.args[2],
+            Expr::NumberLiteral(NumberLiteral { val }) if val == 100.0
+        ));
+
+        // The placeholder function is kept, and the extension functions can be nested.
+        let query = r#"min_over_time(a[5m]) + zscore_over_time(
+            mad_over_time(b{c="zscore_over_time(d[5m])"}[5m])[1h:1m])"#;
+        let Expr::Binary(BinaryExpr { lhs, rhs, .. }) = parse(query).unwrap() else {
+            unreachable!()
+        };
+        let Expr::Call(Call { func, .. }) = *lhs else {
+            unreachable!()
+        };
+        assert_eq!("min_over_time", func.name);
+        let Expr::Call(Call { func, args }) = *rhs else {
+            unreachable!()
+        };
+        assert_eq!("zscore_over_time", func.name);
+        let Expr::Subquery(SubqueryExpr { expr, .. }) = &*args.args[0] else {
+            unreachable!()
+        };
+        let Expr::Call(Call { func, .. }) = &**expr else {
+            unreachable!()
+        };
+        assert_eq!("mad_over_time", func.name);
+
+        // Works with the quantile lists.
+        let expr = parse(r#"zscore_over_time(quantile_over_time("0.5,0.9", m[5m])[1h:1m])"#);
+        assert!(expr.is_ok());
+
+        // The extra arguments must be number literals.
+        assert!(parse("double_exponential_forecast(m[5m], 0.5, 0.5)").is_err());
+        assert!(parse("seasonal_forecast(m[5m], 600, time())").is_err());
+        assert!(parse("zscore_over_time(m)").is_err());
+    }
+
+    #[test]
+    fn test_replace_extension_calls() {
+        let original = "min_over_time(a[5m]) / seasonal_forecast (b[1h], 600,\n 60)";
+        let (query, calls) = replace_extension_calls(original).unwrap();
+        assert_eq!(
+            "min_over_time(a[5m]) / min_over_time     (b[1h]      \n   )",
+            query
+        );
+        // The offsets are kept.
+        assert_eq!(original.len(), query.len());
+        assert_eq!(original.find("b[1h]"), query.find("b[1h]"));
+        assert_eq!(
+            HashMap::from([(
+                1,
+                ExtensionCall {
+                    name: "seasonal_forecast",
+                    args: vec![600.0, 60.0],
+                }
+            )]),
+            calls
+        );
+
+        // unclosed call
+        let (query, calls) = replace_extension_calls("mad_over_time(a[5m]").unwrap();
+        assert_eq!("mad_over_time(a[5m]", query);
+        assert!(calls.is_empty());
+    }
+}
@@ -34,9 +34,9 @@ use datafusion::sql::TableReference;
 use datatypes::arrow::datatypes::DataType as ArrowDataType;
 use promql_parser::label::{MatchOp, Matcher, Matchers, METRIC_NAME};
 use promql_parser::parser::{
-    token, AggregateExpr, BinaryExpr as PromBinaryExpr, Call, EvalStmt, Expr as PromExpr, Function,
-    LabelModifier, MatrixSelector, NumberLiteral, Offset, ParenExpr, StringLiteral, SubqueryExpr,
-    TokenType, UnaryExpr, VectorSelector,
+    token, AggregateExpr, AtModifier, BinaryExpr as PromBinaryExpr, Call, EvalStmt,
+    Expr as PromExpr, Function, LabelModifier, MatrixSelector, NumberLiteral, Offset, ParenExpr,
+    StringLiteral, SubqueryExpr, TokenType, UnaryExpr, VectorSelector,
 };
 use snafu::{ensure, OptionExt, ResultExt};
 use table::table::adapter::DfTableProviderAdapter;
@@ -51,9 +51,10 @@ use crate::extension_plan::{
     EmptyMetric, InstantManipulate, Millisecond, RangeManipulate, SeriesDivide, SeriesNormalize,
 };
 use crate::functions::{
-    AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, HoltWinters, IDelta,
-    Increase, LastOverTime, MaxOverTime, MinOverTime, PredictLinear, PresentOverTime,
-    QuantileOverTime, Rate, Resets, StddevOverTime, StdvarOverTime, SumOverTime,
+    AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, DoubleExponentialForecast,
+    HoltWinters, IDelta, Increase, LastOverTime, MadOverTime, MaxOverTime, MinOverTime,
+    PredictLinear, PresentOverTime, QuantileOverTime, Rate, Resets, SeasonalForecast,
+    StddevOverTime, StdvarOverTime, SumOverTime, ZscoreOverTime,
 };
 
 const LEFT_PLAN_JOIN_ALIAS: &str = "lhs";
@@ -74,6 +75,7 @@ struct PromPlannerContext {
     end: Millisecond,
     interval: Millisecond,
     lookback_delta: Millisecond,
+    strict_staleness: bool,
 
     // planner states
     table_name: Option<String>,
@@ -97,6 +99,14 @@ impl PromPlannerContext {
     }
 }
 
+/// Options to plan PromQL statements.
+#[derive(Default, Debug, Clone)]
+pub struct PromPlannerOptions {
+    /// Handles staleness like Prometheus, only the staleness markers end a series while
+    /// other NaN values are kept as samples.
+    pub strict_staleness: bool,
+}
+
 pub struct PromPlanner {
     table_provider: DfTableSourceProvider,
     ctx: PromPlannerContext,
@@ -107,13 +117,72 @@ impl PromPlanner {
         table_provider: DfTableSourceProvider,
         stmt: EvalStmt,
     ) -> Result<LogicalPlan> {
+        Self::stmt_to_plan_with_options(table_provider, stmt, PromPlannerOptions::default()).await
+    }
+
+    /// Plans the statement with `options`.
+    pub async fn stmt_to_plan_with_options(
+        table_provider: DfTableSourceProvider,
+        stmt: EvalStmt,
+        options: PromPlannerOptions,
+    ) -> Result<LogicalPlan> {
+        let mut ctx = PromPlannerContext::from_eval_stmt(&stmt);
+        ctx.strict_staleness = options.strict_staleness;
         let mut planner = Self {
             table_provider,
-            ctx: PromPlannerContext::from_eval_stmt(&stmt),
+            ctx,
         };
+
+        // resolve all tables referenced by the expression at once
+        let mut table_names = HashSet::new();
+        Self::collect_table_names(&stmt.expr, &mut table_names);
+        let table_names = table_names.iter().map(String::as_str).collect::<Vec<_>>();
+        planner
+            .table_provider
+            .prefetch_tables(&table_names)
+            .await
+            .context(CatalogSnafu)?;
+
         planner.prom_expr_to_plan(stmt.expr).await
     }
 
+    /// Collect the metric names (table names) of all selectors in the expression.
+    fn collect_table_names(prom_expr: &PromExpr, table_names: &mut HashSet<String>) {
+        match prom_expr {
+            PromExpr::Aggregate(AggregateExpr { expr, param, .. }) => {
+                Self::collect_table_names(expr, table_names);
+                if let Some(param) = param {
+                    Self::collect_table_names(param, table_names);
+                }
+            }
+            PromExpr::Unary(UnaryExpr { expr })
+            | PromExpr::Paren(ParenExpr { expr })
+            | PromExpr::Subquery(SubqueryExpr { expr, .. }) => {
+                Self::collect_table_names(expr, table_names)
+            }
+            PromExpr::Binary(PromBinaryExpr { lhs, rhs, .. }) => {
+                Self::collect_table_names(lhs, table_names);
+                Self::collect_table_names(rhs, table_names);
+            }
+            PromExpr::VectorSelector(VectorSelector { matchers, .. })
+            | PromExpr::MatrixSelector(MatrixSelector {
+                vector_selector: VectorSelector { matchers, .. },
+                ..
+            }) => {
+                table_names.extend(matchers.matchers.iter().filter_map(|matcher| {
+                    (matcher.name == METRIC_NAME && matches!(matcher.op, MatchOp::Equal))
+                        .then(|| matcher.value.clone())
+                }));
+            }
+            PromExpr::Call(Call { args, .. }) => {
+                for arg in &args.args {
+                    Self::collect_table_names(arg, table_names);
+                }
+            }
+            PromExpr::NumberLiteral(_) | PromExpr::StringLiteral(_) | PromExpr::Extension(_) => {}
+        }
+    }
+
     #[async_recursion]
     pub async fn prom_expr_to_plan(&mut self, prom_expr: PromExpr) -> Result<LogicalPlan> {
         let res = match &prom_expr {
@@ -181,11 +250,27 @@ impl PromPlanner {
                     Self::try_build_literal_expr(lhs),
                     Self::try_build_literal_expr(rhs),
                 ) {
-                    // TODO(ruihang): handle literal-only expressions
-                    (Some(_lhs), Some(_rhs)) => UnsupportedExprSnafu {
-                        name: "Literal-only expression",
+                    // both are literals, evaluate them on an empty metric
+                    (Some(lhs), Some(rhs)) => {
+                        ensure!(
+                            !is_comparison_op || should_return_bool,
+                            UnsupportedExprSnafu {
+                                name: "Literal comparison without bool modifier",
+                            }
+                        );
+                        let mut binary_expr = DfExpr::BinaryExpr(BinaryExpr {
+                            left: Box::new(lhs),
+                            op: Self::prom_token_to_binary_op(*op)?,
+                            right: Box::new(rhs),
+                        });
+                        if is_comparison_op {
+                            binary_expr = DfExpr::Cast(Cast {
+                                expr: Box::new(binary_expr),
+                                data_type: ArrowDataType::Float64,
+                            });
+                        }
+                        self.create_literal_plan(binary_expr)?
                     }
-                    .fail()?,
                     // lhs is a literal, rhs is a column
                     (Some(expr), None) => {
                         let input = self.prom_expr_to_plan(*rhs.clone()).await?;
@@ -295,10 +380,16 @@ impl PromPlanner {
                 name: _,
                 offset,
                 matchers,
-                at: _,
+                at,
             }) => {
                 let matchers = self.preprocess_label_matchers(matchers)?;
                 self.setup_context().await?;
+                let at = self.at_modifier_to_millis(at);
+                let (start, end) = (self.ctx.start, self.ctx.end);
+                if let Some(at) = at {
+                    self.ctx.start = at;
+                    self.ctx.end = at;
+                }
                 let normalize = self
                     .selector_to_series_normalize_plan(offset, matchers, false)
                     .await?;
@@ -313,20 +404,37 @@ impl PromPlanner {
                         .expect("time index should be set in `setup_context`"),
                     self.ctx.field_columns.get(0).cloned(),
                     normalize,
-                );
-                LogicalPlan::Extension(Extension {
+                )
+                .with_strict_staleness(self.ctx.strict_staleness);
+                (self.ctx.start, self.ctx.end) = (start, end);
+                let plan = LogicalPlan::Extension(Extension {
                     node: Arc::new(manipulate),
-                })
+                });
+                if at.is_some() {
+                    self.align_to_evaluation_grid(plan)?
+                } else {
+                    plan
+                }
             }
             PromExpr::MatrixSelector(MatrixSelector {
                 vector_selector,
                 range,
             }) => {
                 let VectorSelector {
-                    offset, matchers, ..
+                    offset,
+                    matchers,
+                    at,
+                    ..
                 } = vector_selector;
                 let matchers = self.preprocess_label_matchers(matchers)?;
                 self.setup_context().await?;
+                // the caller is responsible for aligning the result of a range selector
+                // with `@` modifier, see the `Call` branch.
+                let (start, end) = (self.ctx.start, self.ctx.end);
+                if let Some(at) = self.at_modifier_to_millis(at) {
+                    self.ctx.start = at;
+                    self.ctx.end = at;
+                }
 
                 ensure!(!range.is_zero(), ZeroRangeSelectorSnafu);
                 let range_ms = range.as_millis() as _;
@@ -349,6 +457,7 @@ impl PromPlanner {
                     normalize,
                 )
                 .context(DataFusionPlanningSnafu)?;
+                (self.ctx.start, self.ctx.end) = (start, end);
 
                 LogicalPlan::Extension(Extension {
                     node: Arc::new(manipulate),
@@ -376,22 +485,33 @@ impl PromPlanner {
                 }
 
                 let args = self.create_function_args(&args.args)?;
-                let input = self
-                    .prom_expr_to_plan(args.input.with_context(|| ExpectExprSnafu {
-                        expr: prom_expr.clone(),
-                    })?)
-                    .await?;
+                let input_expr = args.input.with_context(|| ExpectExprSnafu {
+                    expr: prom_expr.clone(),
+                })?;
+                let is_range_at_modifier = matches!(
+                    &input_expr,
+                    PromExpr::MatrixSelector(MatrixSelector {
+                        vector_selector: VectorSelector { at: Some(_), .. },
+                        ..
+                    })
+                );
+                let input = self.prom_expr_to_plan(input_expr).await?;
                 let mut func_exprs = self.create_function_expr(func, args.literals)?;
                 func_exprs.insert(0, self.create_time_index_column_expr()?);
                 func_exprs.extend_from_slice(&self.create_tag_column_exprs()?);
 
-                LogicalPlanBuilder::from(input)
+                let plan = LogicalPlanBuilder::from(input)
                     .project(func_exprs)
                     .context(DataFusionPlanningSnafu)?
                     .filter(self.create_empty_values_filter_expr()?)
                     .context(DataFusionPlanningSnafu)?
                     .build()
-                    .context(DataFusionPlanningSnafu)?
+                    .context(DataFusionPlanningSnafu)?;
+                if is_range_at_modifier {
+                    self.align_to_evaluation_grid(plan)?
+                } else {
+                    plan
+                }
             }
             PromExpr::Extension(_) => UnsupportedExprSnafu {
                 name: "Prom Extension",
@@ -469,7 +589,7 @@ impl PromPlanner {
                             result_set.insert(matcher.value.clone());
                         } else {
                             return Err(ColumnNotFoundSnafu {
-                                col: self.ctx.table_name.clone().unwrap(),
+                                col: matcher.value.clone(),
                             }
                             .build());
                         }
@@ -551,7 +671,8 @@ impl PromPlanner {
                 .with_context(|| TimeIndexNotFoundSnafu { table: table_name })?,
             is_range_selector,
             divide_plan,
-        );
+        )
+        .with_strict_staleness(self.ctx.strict_staleness);
         let logical_plan = LogicalPlan::Extension(Extension {
             node: Arc::new(series_normalize),
         });
@@ -765,6 +886,17 @@ impl PromPlanner {
     ) -> Result<Vec<DfExpr>> {
         // TODO(ruihang): check function args list
 
+        // `quantile_over_time("0.5,0.9,0.99", ...)` computes several quantiles from the
+        // same range selection.
+        if func.name == "quantile_over_time" {
+            if let Some(DfExpr::Literal(ScalarValue::Utf8(Some(quantiles)))) =
+                other_input_exprs.get(0)
+            {
+                let quantiles = Self::parse_quantile_list(quantiles)?;
+                return self.create_multi_quantile_exprs(&quantiles);
+            }
+        }
+
         // TODO(ruihang): set this according to in-param list
         let field_column_pos = 0;
         let scalar_func = match func.name {
@@ -792,6 +924,8 @@ impl PromPlanner {
             "present_over_time" => ScalarFunc::Udf(PresentOverTime::scalar_udf()),
             "stddev_over_time" => ScalarFunc::Udf(StddevOverTime::scalar_udf()),
             "stdvar_over_time" => ScalarFunc::Udf(StdvarOverTime::scalar_udf()),
+            "zscore_over_time" => ScalarFunc::Udf(ZscoreOverTime::scalar_udf()),
+            "mad_over_time" => ScalarFunc::Udf(MadOverTime::scalar_udf()),
             "quantile_over_time" => {
                 let quantile_expr = match other_input_exprs.get(0) {
                     Some(DfExpr::Literal(ScalarValue::Float64(Some(quantile)))) => *quantile,
@@ -832,6 +966,17 @@ impl PromPlanner {
                 };
                 ScalarFunc::Udf(HoltWinters::scalar_udf(sf_exp, tf_exp))
             }
+            "double_exponential_forecast" => {
+                let sf = Self::float_literal_arg(&other_input_exprs, 0, "smoothing factor")?;
+                let tf = Self::float_literal_arg(&other_input_exprs, 1, "trend factor")?;
+                let horizon = Self::float_literal_arg(&other_input_exprs, 2, "horizon")?;
+                ScalarFunc::Udf(DoubleExponentialForecast::scalar_udf(sf, tf, horizon))
+            }
+            "seasonal_forecast" => {
+                let season = Self::float_literal_arg(&other_input_exprs, 0, "season")?;
+                let horizon = Self::float_literal_arg(&other_input_exprs, 1, "horizon")?;
+                ScalarFunc::Udf(SeasonalForecast::scalar_udf(season, horizon))
+            }
             _ => ScalarFunc::DataFusionBuiltin(
                 BuiltinScalarFunction::from_str(func.name).map_err(|_| {
                     UnsupportedExprSnafu {
@@ -895,9 +1040,46 @@ impl PromPlanner {
             }
         }
 
-        // update value columns' name, and alias them to remove qualifiers
+        self.alias_field_exprs(exprs)
+    }
+
+    /// Creates a `quantile_over_time` expression of each quantile for each value column,
+    /// the range of each value column is only built once for all quantiles.
+    ///
+    /// # Side Effects
+    ///
+    /// This method will update [PromPlannerContext]'s value fields.
+    fn create_multi_quantile_exprs(&mut self, quantiles: &[f64]) -> Result<Vec<DfExpr>> {
+        let ts_range_expr = DfExpr::Column(Column::from_name(
+            RangeManipulate::build_timestamp_range_name(
+                self.ctx
+                    .time_index_column
+                    .as_ref()
+                    .context(ExpectRangeSelectorSnafu)?,
+            ),
+        ));
+        let mut exprs = Vec::with_capacity(self.ctx.field_columns.len() * quantiles.len());
+        for value in &self.ctx.field_columns {
+            for quantile in quantiles {
+                exprs.push(DfExpr::ScalarUDF {
+                    fun: Arc::new(QuantileOverTime::scalar_udf(*quantile)),
+                    args: vec![
+                        ts_range_expr.clone(),
+                        DfExpr::Column(Column::from_name(value)),
+                        DfExpr::Literal(ScalarValue::Float64(Some(*quantile))),
+                    ],
+                });
+            }
+        }
+
+        self.alias_field_exprs(exprs)
+    }
+
+    /// Aliases the expressions of value columns to their display names to remove
+    /// qualifiers, and updates the value columns of [PromPlannerContext].
+    fn alias_field_exprs(&mut self, exprs: Vec<DfExpr>) -> Result<Vec<DfExpr>> {
         let mut new_field_columns = Vec::with_capacity(exprs.len());
-        exprs = exprs
+        let exprs = exprs
             .into_iter()
             .map(|expr| {
                 let display_name = expr.display_name()?;
@@ -911,6 +1093,34 @@ impl PromPlanner {
         Ok(exprs)
     }
 
+    /// Reads the `index`-th extra argument of a function as a f64 literal.
+    fn float_literal_arg(other_input_exprs: &[DfExpr], index: usize, desc: &str) -> Result<f64> {
+        match other_input_exprs.get(index) {
+            Some(DfExpr::Literal(ScalarValue::Float64(Some(value)))) => Ok(*value),
+            other => UnexpectedPlanExprSnafu {
+                desc: format!("expect f64 literal as {desc}, but found {other:?}"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Parses a comma separated list of quantiles like `"0.5,0.9,0.99"`.
+    fn parse_quantile_list(quantiles: &str) -> Result<Vec<f64>> {
+        let parsed = quantiles
+            .split(',')
+            .map(|q| q.trim().parse::<f64>())
+            .collect::<std::result::Result<Vec<_>, _>>();
+        match parsed {
+            Ok(parsed) if !parsed.is_empty() => Ok(parsed),
+            _ => UnexpectedPlanExprSnafu {
+                desc: format!(
+                    "expect comma separated f64 list as quantiles, but found {quantiles:?}"
+                ),
+            }
+            .fail(),
+        }
+    }
+
     fn create_time_index_column_expr(&self) -> Result<DfExpr> {
         Ok(DfExpr::Column(Column::from_name(
             self.ctx
@@ -1029,15 +1239,34 @@ impl PromPlanner {
             PromExpr::Paren(ParenExpr { expr }) => Self::try_build_literal_expr(expr),
             // TODO(ruihang): support Unary operator
             PromExpr::Unary(UnaryExpr { expr, .. }) => Self::try_build_literal_expr(expr),
-            PromExpr::Binary(PromBinaryExpr { lhs, rhs, op, .. }) => {
+            PromExpr::Binary(PromBinaryExpr {
+                lhs,
+                rhs,
+                op,
+                modifier,
+            }) => {
                 let lhs = Self::try_build_literal_expr(lhs)?;
                 let rhs = Self::try_build_literal_expr(rhs)?;
+                let is_comparison_op = Self::is_token_a_comparison_op(*op);
                 let op = Self::prom_token_to_binary_op(*op).ok()?;
-                Some(DfExpr::BinaryExpr(BinaryExpr {
+                let binary_expr = DfExpr::BinaryExpr(BinaryExpr {
                     left: Box::new(lhs),
                     op,
                     right: Box::new(rhs),
-                }))
+                });
+                // comparison between literals is only valid with `bool` modifier, which
+                // returns 0/1 instead of a boolean
+                if is_comparison_op {
+                    if !modifier.as_ref().map_or(false, |m| m.return_bool) {
+                        return None;
+                    }
+                    Some(DfExpr::Cast(Cast {
+                        expr: Box::new(binary_expr),
+                        data_type: ArrowDataType::Float64,
+                    }))
+                } else {
+                    Some(binary_expr)
+                }
             }
         }
     }
@@ -1075,6 +1304,97 @@ impl PromPlanner {
         )
     }
 
+    /// Builds a plan that evaluates the literal `expr` on every step of the evaluation grid,
+    /// like the `time()` function.
+    ///
+    /// # Side Effects
+    ///
+    /// This method will update [PromPlannerContext]'s time index, value and tag fields.
+    fn create_literal_plan(&mut self, expr: DfExpr) -> Result<LogicalPlan> {
+        self.ctx.time_index_column = Some(SPECIAL_TIME_FUNCTION.to_string());
+        self.ctx.field_columns = vec![DEFAULT_FIELD_COLUMN.to_string()];
+        self.ctx.tag_columns = vec![];
+        self.ctx.table_name = Some(String::new());
+
+        let empty_metric = LogicalPlan::Extension(Extension {
+            node: Arc::new(
+                EmptyMetric::new(
+                    self.ctx.start,
+                    self.ctx.end,
+                    self.ctx.interval,
+                    SPECIAL_TIME_FUNCTION.to_string(),
+                    DEFAULT_FIELD_COLUMN.to_string(),
+                )
+                .context(DataFusionPlanningSnafu)?,
+            ),
+        });
+        LogicalPlanBuilder::from(empty_metric)
+            .project(vec![
+                self.create_time_index_column_expr()?,
+                expr.alias(DEFAULT_FIELD_COLUMN),
+            ])
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Resolves the evaluation timestamp of `@` modifier in millisecond.
+    fn at_modifier_to_millis(&self, at: &Option<AtModifier>) -> Option<Millisecond> {
+        match at {
+            Some(AtModifier::Start) => Some(self.ctx.start),
+            Some(AtModifier::End) => Some(self.ctx.end),
+            Some(AtModifier::At(time)) => Some(match time.duration_since(UNIX_EPOCH) {
+                Ok(duration) => duration.as_millis() as Millisecond,
+                Err(e) => -(e.duration().as_millis() as Millisecond),
+            }),
+            None => None,
+        }
+    }
+
+    /// Repeats the samples of `input`, which is evaluated at a single timestamp (e.g. with
+    /// `@` modifier), on every step of the evaluation grid. Otherwise the timestamps won't
+    /// match the other operand and the inner join of binary operation will drop them.
+    fn align_to_evaluation_grid(&self, input: LogicalPlan) -> Result<LogicalPlan> {
+        let time_index_column = self
+            .ctx
+            .time_index_column
+            .clone()
+            .with_context(|| TimeIndexNotFoundSnafu { table: "unknown" })?;
+        let grid = LogicalPlan::Extension(Extension {
+            node: Arc::new(
+                EmptyMetric::new(
+                    self.ctx.start,
+                    self.ctx.end,
+                    self.ctx.interval,
+                    time_index_column.clone(),
+                    DEFAULT_FIELD_COLUMN.to_string(),
+                )
+                .context(DataFusionPlanningSnafu)?,
+            ),
+        });
+        let grid = LogicalPlanBuilder::from(grid)
+            .project(vec![DfExpr::Column(Column::from_name(time_index_column))])
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        // drop the original time index and take the one of grid
+        let mut exprs = self.create_tag_column_exprs()?;
+        exprs.extend(
+            self.ctx
+                .field_columns
+                .iter()
+                .map(|col| DfExpr::Column(Column::from_name(col))),
+        );
+        LogicalPlanBuilder::from(input)
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .cross_join(grid)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
     /// Build a inner join on time index column and tag columns to concat two logical plans.
     /// The left plan will be alised as [`LEFT_PLAN_JOIN_ALIAS`].
     fn join_on_non_field_columns(
@@ -1723,12 +2043,37 @@ mod test {
     }
 
     #[tokio::test]
-    #[ignore = "pure literal arithmetic is not supported yet"]
     async fn binary_op_literal_literal() {
-        let query = r#"1 + 1"#;
-        let expected = String::from("");
+        let plan = binary_op_plan("1 + 1").await;
+        assert!(plan.contains("Float64(1) + Float64(1) AS value"), "{plan}");
+        assert!(
+            plan.contains("EmptyMetric: range=[0..100000000], interval=[5000]"),
+            "{plan}"
+        );
+    }
 
-        indie_query_plan_compare(query, expected).await;
+    #[tokio::test]
+    async fn literal_comparison_with_bool() {
+        let cases = [
+            (
+                "1 >= bool 2",
+                "CAST(Float64(1) >= Float64(2) AS Float64) AS value",
+            ),
+            (
+                "1 == bool 1",
+                "CAST(Float64(1) = Float64(1) AS Float64) AS value",
+            ),
+            (
+                "(1 < bool 2) + 1",
+                "CAST(Float64(1) < Float64(2) AS Float64) + Float64(1) AS value",
+            ),
+        ];
+        for (query, expected) in cases {
+            let plan = binary_op_plan(query).await;
+            assert!(plan.contains(expected), "query: {query}, plan: {plan}");
+            assert!(plan.contains("EmptyMetric"), "query: {query}, plan: {plan}");
+            assert!(!plan.contains("Filter"), "query: {query}, plan: {plan}");
+        }
     }
 
     #[tokio::test]
@@ -1950,4 +2295,204 @@ mod test {
             assert!(plan.is_err(), "case: {:?}", case);
         }
     }
+
+    async fn binary_op_plan(query: &str) -> String {
+        let prom_expr = parser::parse(query).unwrap();
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+        let table_provider = build_test_table_provider("some_metric".to_string(), 1, 1).await;
+        PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+            .await
+            .unwrap()
+            .display_indent()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn binary_op_with_offset() {
+        let cases = [
+            ("some_metric offset 1m + some_metric", ["60000", "0"]),
+            ("some_metric - some_metric offset -30s", ["0", "-30000"]),
+            (
+                "rate(some_metric[5m] offset 1m) / rate(some_metric[5m])",
+                ["60000", "0"],
+            ),
+        ];
+        for (query, offsets) in cases {
+            let plan = binary_op_plan(query).await;
+            for offset in offsets {
+                assert!(
+                    plan.contains(&format!("PromSeriesNormalize: offset=[{offset}]")),
+                    "query: {query}, plan: {plan}"
+                );
+            }
+            // both sides are evaluated on the same grid
+            assert!(!plan.contains("CrossJoin"), "query: {query}, plan: {plan}");
+            for line in plan
+                .lines()
+                .filter(|line| line.contains("PromInstantManipulate"))
+            {
+                assert!(
+                    line.contains("range=[0..100000000]"),
+                    "query: {query}, plan: {plan}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn binary_op_with_at_modifier() {
+        let cases = [
+            ("some_metric @ 50 + some_metric", 1),
+            ("some_metric @ 50 offset 1m + some_metric", 1),
+            ("some_metric @ start() - some_metric @ end()", 2),
+            ("rate(some_metric[5m] @ 50) / rate(some_metric[5m])", 1),
+        ];
+        for (query, aligned) in cases {
+            let plan = binary_op_plan(query).await;
+            // operands with `@` modifier are evaluated once and repeated on the grid
+            assert_eq!(
+                aligned,
+                plan.matches("CrossJoin").count(),
+                "query: {query}, plan: {plan}"
+            );
+            assert_eq!(
+                aligned,
+                plan.matches("EmptyMetric: range=[0..100000000], interval=[5000]")
+                    .count(),
+                "query: {query}, plan: {plan}"
+            );
+        }
+
+        let plan = binary_op_plan("some_metric @ 50 + some_metric").await;
+        assert!(plan.contains("range=[50000..50000]"), "plan: {plan}");
+        let plan = binary_op_plan("some_metric @ end() + some_metric").await;
+        assert!(
+            plan.contains("range=[100000000..100000000]"),
+            "plan: {plan}"
+        );
+    }
+
+    #[test]
+    fn parse_quantile_list() {
+        assert_eq!(
+            vec![0.5, 0.9, 0.99],
+            PromPlanner::parse_quantile_list("0.5, 0.9,0.99").unwrap()
+        );
+        assert!(PromPlanner::parse_quantile_list("").is_err());
+        assert!(PromPlanner::parse_quantile_list("0.5,,0.9").is_err());
+        assert!(PromPlanner::parse_quantile_list("0.5,high").is_err());
+    }
+
+    #[tokio::test]
+    async fn multi_quantile_over_time() {
+        let prom_expr =
+            crate::parser::parse(r#"quantile_over_time("0.5,0.9,0.99", some_metric[5m])"#).unwrap();
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let table_provider = build_test_table_provider("some_metric".to_string(), 1, 1).await;
+        let plan = PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+            .await
+            .unwrap();
+
+        let fields = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .filter(|name| name.starts_with("prom_quantile_over_time"))
+            .collect::<Vec<_>>();
+        assert_eq!(3, fields.len(), "fields: {fields:?}");
+        for quantile in ["0.5", "0.9", "0.99"] {
+            assert!(
+                fields.iter().any(|name| name.contains(quantile)),
+                "quantile {quantile} not found in {fields:?}"
+            );
+        }
+        // all quantiles are computed from one range manipulation
+        assert_eq!(
+            1,
+            plan.display_indent()
+                .to_string()
+                .matches("PromRangeManipulate")
+                .count()
+        );
+    }
+
+    #[tokio::test]
+    async fn extension_range_functions() {
+        let cases = [
+            ("zscore_over_time(some_metric[5m])", "prom_zscore_over_time"),
+            ("mad_over_time(some_metric[5m])", "prom_mad_over_time"),
+            (
+                "double_exponential_forecast(some_metric[5m], 0.5, 0.5, 60)",
+                "prom_double_exponential_forecast",
+            ),
+            (
+                "seasonal_forecast(some_metric[1h], 600, 60)",
+                "prom_seasonal_forecast",
+            ),
+        ];
+
+        for (query, fn_name) in cases {
+            let eval_stmt = EvalStmt {
+                expr: crate::parser::parse(query).unwrap(),
+                start: UNIX_EPOCH,
+                end: UNIX_EPOCH
+                    .checked_add(Duration::from_secs(100_000))
+                    .unwrap(),
+                interval: Duration::from_secs(5),
+                lookback_delta: Duration::from_secs(1),
+            };
+
+            let table_provider = build_test_table_provider("some_metric".to_string(), 1, 1).await;
+            let plan = PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+                .await
+                .unwrap()
+                .display_indent()
+                .to_string();
+            assert!(plan.contains(fn_name), "query: {query}, plan: {plan}");
+        }
+    }
+
+    #[test]
+    fn collect_table_names() {
+        let cases = [
+            ("some_metric", vec!["some_metric"]),
+            (
+                "sum by (tag_0) (rate(some_metric[5m])) / ignoring(tag_1) other_metric",
+                vec!["other_metric", "some_metric"],
+            ),
+            (
+                r#"{__name__="some_metric"} + some_metric offset 1m"#,
+                vec!["some_metric"],
+            ),
+            (r#"{__name__=~"some_.*"}"#, vec![]),
+            ("time()", vec![]),
+        ];
+
+        for (query, expected) in cases {
+            let prom_expr = parser::parse(query).unwrap();
+            let mut table_names = HashSet::new();
+            PromPlanner::collect_table_names(&prom_expr, &mut table_names);
+            let mut table_names = table_names.into_iter().collect::<Vec<_>>();
+            table_names.sort();
+            assert_eq!(expected, table_names, "query: {query}");
+        }
+    }
 }
@@ -0,0 +1,220 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Evaluation of computed columns on insertion.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::compute;
+use datafusion::arrow::datatypes::{Field, Schema as ArrowSchema};
+use datafusion::arrow::record_batch::RecordBatch as DfRecordBatch;
+use datafusion::error::Result as DfResult;
+use datafusion::prelude::SessionContext;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Helper, VectorRef};
+use once_cell::sync::Lazy;
+use snafu::ResultExt;
+
+use crate::error::{EvalComputedColumnsSnafu, Result, VectorComputationSnafu};
+
+/// Prefix of the names of the tables the inserted rows are registered as while evaluating
+/// the expressions.
+const INSERTED_TABLE: &str = "inserted";
+
+/// Context the expressions are evaluated in. It's shared by all insertions as creating a
+/// context is expensive, each insertion registers its rows as a table of a unique name.
+static EVAL_CONTEXT: Lazy<SessionContext> = Lazy::new(SessionContext::new);
+
+static NEXT_INSERTED_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The inserted rows registered in [EVAL_CONTEXT], deregistered on drop.
+struct InsertedTable(String);
+
+impl InsertedTable {
+    fn register(batch: DfRecordBatch) -> DfResult<Self> {
+        let id = NEXT_INSERTED_ID.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{INSERTED_TABLE}_{id}");
+        let _ = EVAL_CONTEXT.register_batch(&name, batch)?;
+        Ok(Self(name))
+    }
+}
+
+impl Drop for InsertedTable {
+    fn drop(&mut self) {
+        let _ = EVAL_CONTEXT.deregister_table(self.0.as_str());
+    }
+}
+
+/// Evaluates the computed columns of `schema` over the inserted `columns_values`, and puts
+/// the results back into `columns_values`. Values given for computed columns are replaced.
+pub async fn fill_computed_columns(
+    schema: &Schema,
+    columns_values: &mut HashMap<String, VectorRef>,
+) -> Result<()> {
+    let computed = schema
+        .column_schemas()
+        .iter()
+        .filter(|column_schema| column_schema.computed_expr().is_some())
+        .collect::<Vec<_>>();
+    if computed.is_empty() {
+        return Ok(());
+    }
+
+    for column_schema in &computed {
+        let _ = columns_values.remove(&column_schema.name);
+    }
+    let num_rows = columns_values.values().next().map(|v| v.len()).unwrap_or(0);
+    if num_rows == 0 {
+        return Ok(());
+    }
+
+    let arrays = evaluate(&computed, columns_values)
+        .await
+        .context(EvalComputedColumnsSnafu)?;
+    for (column_schema, array) in computed.into_iter().zip(arrays) {
+        let vector = Helper::try_into_vector(array).context(VectorComputationSnafu)?;
+        let _ = columns_values.insert(column_schema.name.clone(), vector);
+    }
+    Ok(())
+}
+
+async fn evaluate(
+    computed: &[&ColumnSchema],
+    columns_values: &HashMap<String, VectorRef>,
+) -> DfResult<Vec<ArrayRef>> {
+    let (fields, arrays): (Vec<_>, Vec<_>) = columns_values
+        .iter()
+        .map(|(name, vector)| {
+            (
+                Field::new(name, vector.data_type().as_arrow_type(), true),
+                vector.to_arrow_array(),
+            )
+        })
+        .unzip();
+    let batch = DfRecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), arrays)?;
+
+    let inserted = InsertedTable::register(batch)?;
+
+    // Aliases keep the output names unique even if two columns share the same expression.
+    let projection = computed
+        .iter()
+        .enumerate()
+        .map(|(i, column_schema)| {
+            format!("{} AS computed_{i}", column_schema.computed_expr().unwrap())
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let batches = EVAL_CONTEXT
+        .sql(&format!("SELECT {projection} FROM {}", inserted.0))
+        .await?
+        .collect()
+        .await?;
+
+    computed
+        .iter()
+        .enumerate()
+        .map(|(i, column_schema)| {
+            let columns = batches
+                .iter()
+                .map(|batch| batch.column(i).as_ref())
+                .collect::<Vec<&dyn Array>>();
+            let array = compute::concat(&columns)?;
+            Ok(compute::cast(
+                &array,
+                &column_schema.data_type.as_arrow_type(),
+            )?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::vectors::{Int64Vector, StringVector};
+
+    use super::*;
+
+    fn test_schema() -> Schema {
+        Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("idc", ConcreteDataType::string_datatype(), true)
+                .with_computed_expr("substr(host, 1, 3)"),
+            ColumnSchema::new("host_len", ConcreteDataType::int64_datatype(), true)
+                .with_computed_expr("length(host)"),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_fill_computed_columns() {
+        let schema = test_schema();
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::new();
+        let _ = columns_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["abcd", "xyz12"])),
+        );
+        // Given values of computed columns are overwritten.
+        let _ = columns_values.insert(
+            "idc".to_string(),
+            Arc::new(StringVector::from(vec!["a", "b"])),
+        );
+
+        fill_computed_columns(&schema, &mut columns_values)
+            .await
+            .unwrap();
+
+        let expected: VectorRef = Arc::new(StringVector::from(vec!["abc", "xyz"]));
+        assert_eq!(expected, columns_values["idc"]);
+        let expected: VectorRef = Arc::new(Int64Vector::from_slice([4, 5]));
+        assert_eq!(expected, columns_values["host_len"]);
+    }
+
+    #[tokio::test]
+    async fn test_inserted_table_deregistered() {
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "host",
+            datafusion::arrow::datatypes::DataType::Utf8,
+            true,
+        )]));
+        let inserted = InsertedTable::register(DfRecordBatch::new_empty(schema)).unwrap();
+        let name = inserted.0.clone();
+        assert!(EVAL_CONTEXT.table(name.as_str()).await.is_ok());
+        drop(inserted);
+        assert!(EVAL_CONTEXT.table(name.as_str()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fill_computed_columns_invalid_expr() {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("idc", ConcreteDataType::string_datatype(), true)
+                .with_computed_expr("unknown_column"),
+        ]);
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::new();
+        let _ = columns_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["abcd"])),
+        );
+
+        let err = fill_computed_columns(&schema, &mut columns_values)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::Error::EvalComputedColumns { .. }
+        ));
+    }
+}
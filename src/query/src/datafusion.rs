@@ -18,7 +18,10 @@ mod error;
 mod planner;
 
 use std::collections::HashMap;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
 
 use async_trait::async_trait;
 use common_error::prelude::BoxedError;
@@ -29,20 +32,30 @@ use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlan, PhysicalP
 use common_query::prelude::ScalarUdf;
 use common_query::Output;
 use common_recordbatch::adapter::RecordBatchStreamAdapter;
-use common_recordbatch::{EmptyRecordBatchStream, SendableRecordBatchStream};
+use common_recordbatch::{
+    EmptyRecordBatchStream, ExecutionStats, RecordBatch, RecordBatchStream,
+    SendableRecordBatchStream,
+};
 use common_telemetry::timer;
+use datafusion::datasource::source_as_provider;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion_common::ResolvedTableReference;
 use datafusion_expr::{DmlStatement, LogicalPlan as DfLogicalPlan, WriteOp};
 use datatypes::prelude::VectorRef;
-use datatypes::schema::Schema;
+use datatypes::schema::{
+    Metadata, Schema, SchemaRef, PRECISION_KEY, SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_KEY,
+    SEMANTIC_TYPE_TAG, SEMANTIC_TYPE_TIMESTAMP, UNIT_KEY,
+};
+use futures::Stream;
 use futures_util::StreamExt;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::requests::{DeleteRequest, InsertRequest};
+use table::table::adapter::DfTableProviderAdapter;
 use table::TableRef;
 
+pub(crate) use crate::datafusion::planner::parser_options;
 pub use crate::datafusion::planner::DfContextProviderAdapter;
 use crate::error::{
     CatalogNotFoundSnafu, CatalogSnafu, CreateRecordBatchSnafu, DataFusionSnafu,
@@ -56,6 +69,7 @@ use crate::physical_planner::PhysicalPlanner;
 use crate::plan::LogicalPlan;
 use crate::planner::{DfLogicalPlanner, LogicalPlanner};
 use crate::query_engine::{QueryEngineContext, QueryEngineState};
+use crate::stats::StatsRecordBatchStream;
 use crate::{metrics, QueryEngine};
 
 pub struct DatafusionQueryEngine {
@@ -67,14 +81,26 @@ impl DatafusionQueryEngine {
         Self { state }
     }
 
-    async fn exec_query_plan(&self, plan: LogicalPlan) -> Result<Output> {
-        let mut ctx = QueryEngineContext::new(self.state.session_state());
+    async fn exec_query_plan(
+        &self,
+        plan: LogicalPlan,
+        query_ctx: &QueryContextRef,
+    ) -> Result<Output> {
+        let start = Instant::now();
+        let state = self.state.session_state_with_hints(&query_ctx.hints());
+        let mut ctx = QueryEngineContext::new(state);
 
         // `create_physical_plan` will optimize logical plan internally
         let physical_plan = self.create_physical_plan(&mut ctx, &plan).await?;
         let physical_plan = self.optimize_physical_plan(&mut ctx, physical_plan)?;
 
-        Ok(Output::Stream(self.execute_stream(&ctx, &physical_plan)?))
+        let stream = self.execute_stream(&ctx, &physical_plan)?;
+        Ok(Output::Stream(Box::pin(StatsRecordBatchStream::new(
+            stream,
+            physical_plan,
+            ctx.memory_pool().clone(),
+            start,
+        ))))
     }
 
     async fn exec_dml_statement(
@@ -95,7 +121,7 @@ impl DatafusionQueryEngine {
         let table = self.find_table(&table_name).await?;
 
         let output = self
-            .exec_query_plan(LogicalPlan::DfPlan((*dml.input).clone()))
+            .exec_query_plan(LogicalPlan::DfPlan((*dml.input).clone()), &query_ctx)
             .await?;
         let mut stream = match output {
             Output::RecordBatches(batches) => batches.as_stream(),
@@ -205,6 +231,168 @@ impl DatafusionQueryEngine {
     }
 }
 
+/// Finds the semantic types and units of the output columns, which are only known for the
+/// columns referring to table columns directly.
+fn table_column_metadata(plan: &LogicalPlan) -> Vec<Metadata> {
+    let LogicalPlan::DfPlan(df_plan) = plan;
+    let mut tables = HashMap::new();
+    collect_scanned_tables(df_plan, &mut tables);
+
+    df_plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| {
+            field
+                .qualifier()
+                .and_then(|qualifier| tables.get(&qualifier.to_string())?.as_ref())
+                .map(|table| column_metadata(table, field.name()))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Collects the tables scanned by the plan, keyed by the qualifiers of their columns, which
+/// are the aliases of the tables if they are aliased. The qualifiers of other relations, like
+/// aliased subqueries, are not collected even if they are named after tables, and the
+/// qualifiers shared by different tables are mapped to `None`.
+fn collect_scanned_tables(plan: &DfLogicalPlan, tables: &mut HashMap<String, Option<TableRef>>) {
+    let (qualifier, scan) = match plan {
+        DfLogicalPlan::SubqueryAlias(alias) => match alias.input.as_ref() {
+            DfLogicalPlan::TableScan(scan) => (alias.alias.to_string(), scan),
+            _ => return collect_inputs_scanned_tables(plan, tables),
+        },
+        DfLogicalPlan::TableScan(scan) => (scan.table_name.to_string(), scan),
+        _ => return collect_inputs_scanned_tables(plan, tables),
+    };
+    let table = source_as_provider(&scan.source).ok().and_then(|provider| {
+        provider
+            .as_any()
+            .downcast_ref::<DfTableProviderAdapter>()
+            .map(|adapter| adapter.table())
+    });
+    let _ = tables
+        .entry(qualifier)
+        .and_modify(|existing: &mut Option<TableRef>| {
+            let same = match (existing.as_ref(), table.as_ref()) {
+                (Some(existing), Some(table)) => Arc::ptr_eq(existing, table),
+                _ => false,
+            };
+            if !same {
+                *existing = None;
+            }
+        })
+        .or_insert(table);
+}
+
+fn collect_inputs_scanned_tables(
+    plan: &DfLogicalPlan,
+    tables: &mut HashMap<String, Option<TableRef>>,
+) {
+    for input in plan.inputs() {
+        collect_scanned_tables(input, tables);
+    }
+}
+
+fn column_metadata(table: &TableRef, column_name: &str) -> Metadata {
+    let table_info = table.table_info();
+    let schema = &table_info.meta.schema;
+    let mut metadata = Metadata::new();
+    let Some(index) = schema.column_index_by_name(column_name) else {
+        return metadata;
+    };
+    let semantic_type = if schema.timestamp_index() == Some(index) {
+        SEMANTIC_TYPE_TIMESTAMP
+    } else if table_info.meta.primary_key_indices.contains(&index) {
+        SEMANTIC_TYPE_TAG
+    } else {
+        SEMANTIC_TYPE_FIELD
+    };
+    let _ = metadata.insert(SEMANTIC_TYPE_KEY.to_string(), semantic_type.to_string());
+    if let Some(unit) = schema.column_schemas()[index].unit() {
+        let _ = metadata.insert(UNIT_KEY.to_string(), unit.to_string());
+    }
+    metadata
+}
+
+/// Adds the semantic types and units of the table columns, and the precisions of timestamps
+/// to the metadata of output columns, so clients could render the results without querying
+/// the table schemas. The metadata is kept in the schemas sent by the gRPC (Arrow Flight)
+/// server, and rendered by the HTTP server.
+fn with_column_metadata(output: Output, table_column_metadata: Vec<Metadata>) -> Output {
+    let Output::Stream(stream) = output else {
+        return output;
+    };
+
+    let schema = stream.schema();
+    let column_metadata: Vec<_> = schema
+        .column_schemas()
+        .iter()
+        .zip(
+            table_column_metadata
+                .into_iter()
+                .chain(std::iter::repeat_with(Metadata::new)),
+        )
+        .map(|(column_schema, mut metadata)| {
+            if let Some(precision) = column_schema.timestamp_precision() {
+                let _ = metadata.insert(PRECISION_KEY.to_string(), precision.to_string());
+            }
+            metadata
+        })
+        .collect();
+    if column_metadata.iter().all(Metadata::is_empty) {
+        return Output::Stream(stream);
+    }
+
+    let column_schemas = schema
+        .column_schemas()
+        .iter()
+        .zip(column_metadata)
+        .map(|(column_schema, column_metadata)| {
+            let mut metadata = column_schema.metadata().clone();
+            metadata.extend(column_metadata);
+            column_schema.clone().with_metadata(metadata)
+        })
+        .collect();
+    match Schema::try_new(column_schemas) {
+        Ok(schema) => Output::Stream(Box::pin(SchemaReplacedStream {
+            schema: Arc::new(schema),
+            stream,
+        })),
+        Err(_) => Output::Stream(stream),
+    }
+}
+
+/// A stream yielding the record batches of the inner stream with a schema differs only
+/// in metadata.
+struct SchemaReplacedStream {
+    schema: SchemaRef,
+    stream: SendableRecordBatchStream,
+}
+
+impl RecordBatchStream for SchemaReplacedStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn stats(&self) -> Option<ExecutionStats> {
+        self.stream.stats()
+    }
+}
+
+impl Stream for SchemaReplacedStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let schema = self.schema.clone();
+        self.stream.poll_next_unpin(cx).map(|batch| {
+            batch.map(|batch| {
+                batch.and_then(|batch| RecordBatch::new(schema, batch.columns().iter().cloned()))
+            })
+        })
+    }
+}
+
 #[async_trait]
 impl QueryEngine for DatafusionQueryEngine {
     fn planner(&self) -> Arc<dyn LogicalPlanner> {
@@ -227,7 +415,11 @@ impl QueryEngine for DatafusionQueryEngine {
             LogicalPlan::DfPlan(DfLogicalPlan::Dml(dml)) => {
                 self.exec_dml_statement(dml, query_ctx).await
             }
-            _ => self.exec_query_plan(plan).await,
+            _ => {
+                let table_column_metadata = table_column_metadata(&plan);
+                let output = self.exec_query_plan(plan, &query_ctx).await?;
+                Ok(with_column_metadata(output, table_column_metadata))
+            }
         }
     }
 
@@ -345,7 +537,7 @@ impl QueryExecutor for DatafusionQueryEngine {
         match plan.output_partitioning().partition_count() {
             0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
             1 => Ok(plan
-                .execute(0, ctx.state().task_ctx())
+                .execute(0, ctx.task_ctx())
                 .context(error::ExecutePhysicalPlanSnafu)
                 .map_err(BoxedError::new)
                 .context(QueryExecutionSnafu))?,
@@ -356,7 +548,7 @@ impl QueryExecutor for DatafusionQueryEngine {
                 // CoalescePartitionsExec must produce a single partition
                 assert_eq!(1, plan.output_partitioning().partition_count());
                 let df_stream = plan
-                    .execute(0, ctx.state().task_ctx())
+                    .execute(0, ctx.task_ctx())
                     .context(error::DatafusionSnafu {
                         msg: "Failed to execute DataFusion merge exec",
                     })
@@ -381,13 +573,20 @@ mod tests {
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use common_query::Output;
     use common_recordbatch::util;
+    use datafusion::datasource::source_as_provider;
+    use datafusion_expr::{Expr, LogicalPlan as DfLogicalPlan};
     use datatypes::prelude::ConcreteDataType;
-    use datatypes::schema::ColumnSchema;
+    use datatypes::schema::{
+        ColumnSchema, PRECISION_KEY, SEMANTIC_TYPE_KEY, SEMANTIC_TYPE_TAG, UNIT_KEY,
+    };
     use datatypes::vectors::{UInt64Vector, VectorRef};
-    use session::context::QueryContext;
+    use session::context::{QueryContext, QueryHints, SessionFunction};
+    use table::table::adapter::DfTableProviderAdapter;
     use table::table::numbers::NumbersTable;
 
+    use crate::error::Error;
     use crate::parser::QueryLanguageParser;
+    use crate::plan::LogicalPlan;
     use crate::query_engine::{QueryEngineFactory, QueryEngineRef};
 
     async fn create_test_engine() -> QueryEngineRef {
@@ -470,6 +669,195 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_execute_with_hints() {
+        let engine = create_test_engine().await;
+        let sql = "select sum(number) from numbers where number < 10";
+
+        let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+        let query_ctx = QueryContext::arc();
+        let plan = engine
+            .planner()
+            .plan(stmt, query_ctx.clone())
+            .await
+            .unwrap();
+
+        query_ctx.set_hints(QueryHints {
+            no_index: true,
+            parallelism: Some(2),
+            read_replica: false,
+        });
+        let Output::Stream(stream) = engine.execute(plan, query_ctx).await.unwrap() else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(
+            *batches[0].column(0),
+            Arc::new(UInt64Vector::from_slice([45])) as VectorRef
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_session_function() {
+        let engine = create_test_engine().await;
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_function(SessionFunction {
+            name: "double".to_string(),
+            args: vec!["x".to_string()],
+            body: "x * 2".to_string(),
+        });
+
+        let sql = "select sum(DOUBLE(number)) from numbers where double(number) < 20";
+        let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+        let plan = engine
+            .planner()
+            .plan(stmt, query_ctx.clone())
+            .await
+            .unwrap();
+        let Output::Stream(stream) = engine.execute(plan, query_ctx).await.unwrap() else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!("90", batches[0].column(0).get(0).to_string());
+
+        // The functions of a session are invisible to the others.
+        let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+        assert!(engine
+            .planner()
+            .plan(stmt, QueryContext::arc())
+            .await
+            .is_err());
+    }
+
+    /// Collects the sample percents of the table scans in the plan and its `IN` subqueries.
+    fn scan_samples(plan: &DfLogicalPlan, samples: &mut Vec<Option<f64>>) {
+        if let DfLogicalPlan::TableScan(scan) = plan {
+            let provider = source_as_provider(&scan.source).unwrap();
+            let adapter = provider
+                .as_any()
+                .downcast_ref::<DfTableProviderAdapter>()
+                .unwrap();
+            samples.push(adapter.sample_percent());
+        }
+        for expr in plan.expressions() {
+            if let Expr::InSubquery { subquery, .. } = expr {
+                scan_samples(&subquery.subquery, samples);
+            }
+        }
+        for input in plan.inputs() {
+            scan_samples(input, samples);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_table_sample() {
+        let engine = create_test_engine().await;
+        let plan_samples = |sql: &str| {
+            let engine = engine.clone();
+            let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+            async move {
+                let LogicalPlan::DfPlan(plan) =
+                    engine.planner().plan(stmt, QueryContext::arc()).await?;
+                let mut samples = Vec::new();
+                scan_samples(&plan, &mut samples);
+                Ok::<_, Error>(samples)
+            }
+        };
+
+        // Only the sampled side of a self join is sampled.
+        let sql = "SELECT a.number FROM numbers a TABLESAMPLE SYSTEM (10) \
+                   JOIN numbers b ON a.number = b.number";
+        let mut samples = plan_samples(sql).await.unwrap();
+        samples.sort_by_key(Option::is_some);
+        assert_eq!(vec![None, Some(10.0)], samples);
+
+        // The sampled table can be in a subquery.
+        let sql = "SELECT number FROM numbers WHERE number IN \
+                   (SELECT n.number FROM numbers AS n TABLESAMPLE SYSTEM (10))";
+        let mut samples = plan_samples(sql).await.unwrap();
+        samples.sort_by_key(Option::is_some);
+        assert_eq!(vec![None, Some(10.0)], samples);
+
+        // The sampled relation must be told from the other relations of the table.
+        let sql = "SELECT number FROM numbers TABLESAMPLE SYSTEM (10) WHERE number IN \
+                   (SELECT number FROM numbers)";
+        assert!(matches!(
+            plan_samples(sql).await,
+            Err(Error::AmbiguousTableSample { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_column_metadata() {
+        let engine = create_test_engine().await;
+        let sql = "select number, number + 1, cast(cast(number as bigint) as timestamp) from numbers limit 10";
+
+        let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+        let plan = engine
+            .planner()
+            .plan(stmt, QueryContext::arc())
+            .await
+            .unwrap();
+
+        let output = engine.execute(plan, QueryContext::arc()).await.unwrap();
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        let column_schemas = batches[0].schema.column_schemas();
+        // The only column of numbers table is the primary key.
+        assert_eq!(
+            Some(&SEMANTIC_TYPE_TAG.to_string()),
+            column_schemas[0].metadata().get(SEMANTIC_TYPE_KEY)
+        );
+        assert!(column_schemas[1]
+            .metadata()
+            .get(SEMANTIC_TYPE_KEY)
+            .is_none());
+        // Timestamps always have their precisions.
+        assert_eq!(
+            Some(&"ns".to_string()),
+            column_schemas[2].metadata().get(PRECISION_KEY)
+        );
+        assert!(column_schemas[2].metadata().get(UNIT_KEY).is_none());
+        assert_eq!(10, batches[0].num_rows());
+    }
+
+    #[tokio::test]
+    async fn test_semantic_types_of_aliases() {
+        let engine = create_test_engine().await;
+        let types_of = |sql: &str| {
+            let engine = engine.clone();
+            let sql = sql.to_string();
+            async move {
+                let stmt = QueryLanguageParser::parse_sql(&sql).unwrap();
+                let plan = engine
+                    .planner()
+                    .plan(stmt, QueryContext::arc())
+                    .await
+                    .unwrap();
+                table_column_metadata(&plan)
+                    .into_iter()
+                    .map(|metadata| metadata.get(SEMANTIC_TYPE_KEY).cloned())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Aliased tables.
+        assert_eq!(
+            vec![Some(SEMANTIC_TYPE_TAG.to_string())],
+            types_of("select n.number from numbers as n limit 1").await
+        );
+        // Subqueries aliased as tables are not tables.
+        assert_eq!(
+            vec![None],
+            types_of(
+                "select numbers.number from (select number + 1 as number from numbers) as numbers"
+            )
+            .await
+        );
+    }
+
     #[tokio::test]
     async fn test_describe() {
         let engine = create_test_engine().await;
@@ -18,8 +18,10 @@ use std::sync::Arc;
 
 use arrow_schema::DataType;
 use catalog::table_source::DfTableSourceProvider;
+use common_catalog::format_full_table_name;
 use common_query::logical_plan::create_aggregate_function;
 use datafusion::catalog::TableReference;
+use datafusion::datasource::{provider_as_source, DefaultTableSource, ViewTable as DfViewTable};
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::SessionState;
 use datafusion::physical_plan::udaf::AggregateUDF;
@@ -30,10 +32,20 @@ use datafusion_common::{DataFusionError, OwnedTableReference};
 use datafusion_expr::TableSource;
 use datafusion_physical_expr::var_provider::{is_system_variables, VarType};
 use datafusion_sql::parser::Statement as DfStatement;
-use session::context::QueryContextRef;
-use snafu::ResultExt;
+use datafusion_sql::planner::{ParserOptions, SqlToRel};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{ensure, ResultExt};
+use sql::statements::statement::Statement;
+use table::metadata::TableInfoRef;
+use table::table::adapter::DfTableProviderAdapter;
+use table::table::view::ViewTable;
 
-use crate::error::{CatalogSnafu, DataFusionSnafu, Result};
+use crate::error::{
+    CatalogSnafu, DataFusionSnafu, InvalidViewSnafu, PlanSqlSnafu, Result, SqlSnafu,
+};
+use crate::parser::{QueryLanguageParser, QueryStatement};
 use crate::query_engine::QueryEngineState;
 
 pub struct DfContextProviderAdapter {
@@ -49,6 +61,18 @@ impl DfContextProviderAdapter {
         session_state: SessionState,
         df_stmt: &DfStatement,
         query_ctx: QueryContextRef,
+    ) -> Result<Self> {
+        Self::try_new_with_views(engine_state, session_state, df_stmt, query_ctx, vec![]).await
+    }
+
+    /// `expanding_views` are the views (full names) whose definitions contain `df_stmt`,
+    /// used to detect views referring to themselves.
+    async fn try_new_with_views(
+        engine_state: Arc<QueryEngineState>,
+        session_state: SessionState,
+        df_stmt: &DfStatement,
+        query_ctx: QueryContextRef,
+        expanding_views: Vec<String>,
     ) -> Result<Self> {
         let table_names = session_state
             .resolve_table_references(df_stmt)
@@ -60,7 +84,14 @@ impl DfContextProviderAdapter {
             query_ctx.as_ref(),
         );
 
-        let tables = resolve_tables(table_names, &mut table_provider).await?;
+        let tables = resolve_tables(
+            table_names,
+            &mut table_provider,
+            &engine_state,
+            &session_state,
+            &expanding_views,
+        )
+        .await?;
 
         Ok(Self {
             engine_state,
@@ -74,6 +105,9 @@ impl DfContextProviderAdapter {
 async fn resolve_tables(
     table_names: Vec<OwnedTableReference>,
     table_provider: &mut DfTableSourceProvider,
+    engine_state: &Arc<QueryEngineState>,
+    session_state: &SessionState,
+    expanding_views: &[String],
 ) -> Result<HashMap<String, Arc<dyn TableSource>>> {
     let mut tables = HashMap::with_capacity(table_names.len());
 
@@ -88,12 +122,110 @@ async fn resolve_tables(
                 .await
                 .context(CatalogSnafu)?;
 
+            let table = match view_of(&table) {
+                Some((view_info, definition)) => {
+                    plan_view(
+                        engine_state.clone(),
+                        session_state.clone(),
+                        view_info,
+                        definition,
+                        expanding_views.to_vec(),
+                    )
+                    .await?
+                }
+                None => table,
+            };
+
             v.insert(table);
         }
     }
     Ok(tables)
 }
 
+/// Returns the info and definition of the view behind the table source, if it's a view.
+fn view_of(table: &Arc<dyn TableSource>) -> Option<(TableInfoRef, String)> {
+    let table = table
+        .as_any()
+        .downcast_ref::<DefaultTableSource>()?
+        .table_provider
+        .as_any()
+        .downcast_ref::<DfTableProviderAdapter>()?
+        .table();
+    let view = table.as_any().downcast_ref::<ViewTable>()?;
+    Some((view.table_info(), view.definition().to_string()))
+}
+
+/// Plans the definition of a view. The returned table source carries the plan, which
+/// replaces the scan of the view when the query is analyzed.
+///
+/// Tables in the definition are resolved under the catalog and schema of the view.
+fn plan_view(
+    engine_state: Arc<QueryEngineState>,
+    session_state: SessionState,
+    view_info: TableInfoRef,
+    definition: String,
+    mut expanding_views: Vec<String>,
+) -> BoxFuture<'static, Result<Arc<dyn TableSource>>> {
+    async move {
+        let view_name = format_full_table_name(
+            &view_info.catalog_name,
+            &view_info.schema_name,
+            &view_info.name,
+        );
+        ensure!(
+            !expanding_views.contains(&view_name),
+            InvalidViewSnafu {
+                view: &view_name,
+                reason: "the view refers to itself",
+            }
+        );
+
+        let stmt = match QueryLanguageParser::parse_sql(&definition)? {
+            QueryStatement::Sql(stmt @ Statement::Query(_)) => stmt,
+            _ => {
+                return InvalidViewSnafu {
+                    view: view_name,
+                    reason: "the definition is not a query",
+                }
+                .fail()
+            }
+        };
+        let df_stmt = (&stmt).try_into().context(SqlSnafu)?;
+
+        let query_ctx = Arc::new(QueryContext::with(
+            &view_info.catalog_name,
+            &view_info.schema_name,
+        ));
+        expanding_views.push(view_name);
+        let context_provider = DfContextProviderAdapter::try_new_with_views(
+            engine_state,
+            session_state.clone(),
+            &df_stmt,
+            query_ctx,
+            expanding_views,
+        )
+        .await?;
+
+        let sql_to_rel =
+            SqlToRel::new_with_options(&context_provider, parser_options(&session_state));
+        let plan = sql_to_rel
+            .statement_to_plan(df_stmt)
+            .context(PlanSqlSnafu { sql: &definition })?;
+
+        let view = DfViewTable::try_new(plan, Some(definition)).context(DataFusionSnafu)?;
+        Ok(provider_as_source(Arc::new(view)))
+    }
+    .boxed()
+}
+
+pub(crate) fn parser_options(session_state: &SessionState) -> ParserOptions {
+    let config_options = session_state.config().options();
+    ParserOptions {
+        enable_ident_normalization: config_options.sql_parser.enable_ident_normalization,
+        parse_float_as_decimal: config_options.sql_parser.parse_float_as_decimal,
+    }
+}
+
 impl ContextProvider for DfContextProviderAdapter {
     fn get_table_provider(&self, name: TableReference) -> DfResult<Arc<dyn TableSource>> {
         let table_ref = self.table_provider.resolve_table_ref(name)?;
@@ -136,6 +136,12 @@ pub enum Error {
     #[snafu(display("Missing required field: {}", name))]
     MissingRequiredField { name: String, location: Location },
 
+    #[snafu(display("Columns of the remote table {} must be declared", table_name))]
+    MissingRemoteTableColumns {
+        table_name: String,
+        location: Location,
+    },
+
     #[snafu(display("Failed to regex, source: {}", source))]
     BuildRegex {
         location: Location,
@@ -174,6 +180,25 @@ pub enum Error {
         #[snafu(backtrace)]
         source: datatypes::error::Error,
     },
+
+    #[snafu(display("Failed to evaluate computed columns, source: {}", source))]
+    EvalComputedColumns {
+        source: DataFusionError,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid view {}: {}", view, reason))]
+    InvalidView {
+        view: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "TABLESAMPLE of table {} is ambiguous, sample the table under a unique alias",
+        table
+    ))]
+    AmbiguousTableSample { table: String, location: Location },
 }
 
 impl ErrorExt for Error {
@@ -189,9 +214,13 @@ impl ErrorExt for Error {
             | ParseTimestamp { .. }
             | ParseFloat { .. }
             | MissingRequiredField { .. }
+            | MissingRemoteTableColumns { .. }
             | BuildRegex { .. }
             | UnsupportedFileFormat { .. }
-            | ConvertSchema { .. } => StatusCode::InvalidArguments,
+            | ConvertSchema { .. }
+            | InvalidView { .. }
+            | AmbiguousTableSample { .. }
+            | EvalComputedColumns { .. } => StatusCode::InvalidArguments,
 
             BuildBackend { .. } | ListObjects { .. } => StatusCode::StorageUnavailable,
 
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod computed;
 pub mod datafusion;
 pub mod error;
 pub mod executor;
@@ -25,6 +26,7 @@ pub mod plan;
 pub mod planner;
 pub mod query_engine;
 pub mod sql;
+mod stats;
 #[cfg(test)]
 mod tests;
 
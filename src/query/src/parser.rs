@@ -70,7 +70,7 @@ impl QueryLanguageParser {
     pub fn parse_promql(query: &PromQuery) -> Result<QueryStatement> {
         let _timer = timer!(METRIC_PARSE_PROMQL_ELAPSED);
 
-        let expr = promql_parser::parser::parse(&query.query)
+        let expr = promql::parser::parse(&query.query)
             .map_err(|msg| BoxedError::new(PlainError::new(msg, StatusCode::InvalidArguments)))
             .context(QueryParseSnafu {
                 query: &query.query,
@@ -174,7 +174,7 @@ mod test {
             sort_by: [], \
             having: None, \
             qualify: None \
-            }), order_by: [], limit: None, offset: None, fetch: None, locks: [] }, param_types: [] }))");
+            }), order_by: [], limit: None, offset: None, fetch: None, locks: [] }, param_types: [], table_samples: [], tail: false }))");
 
         assert_eq!(format!("{stmt:?}"), expected);
     }
@@ -12,10 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod adaptive_parallelism;
+mod aggregate_pushdown;
+mod top_k_pushdown;
+
 use std::sync::Arc;
 
 use common_query::physical_plan::PhysicalPlan;
 
+pub use self::adaptive_parallelism::AdaptiveParallelismRule;
+pub use self::aggregate_pushdown::AggregatePushDownRule;
+pub use self::top_k_pushdown::TopKPushDownRule;
 use crate::error::Result;
 use crate::query_engine::QueryEngineContext;
 
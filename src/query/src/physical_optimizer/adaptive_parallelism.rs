@@ -0,0 +1,160 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::physical_plan::DfPhysicalPlanAdapter;
+use datafusion::config::ConfigOptions;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::{ExecutionPlan, Partitioning};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::Result as DfResult;
+use table::table::scan::{ScanCost, SimpleTableScan};
+
+/// Adjusts the round-robin repartitions over table scans according to the estimated cost
+/// of the scans.
+///
+/// DataFusion always repartitions the scanned data to `target_partitions` partitions, which
+/// is wasteful for scans reading only a few bytes. This rule lowers the partitions of such
+/// repartitions, or removes them if the scans are already parallel enough.
+pub struct AdaptiveParallelismRule;
+
+impl PhysicalOptimizerRule for AdaptiveParallelismRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            let Some(repartition) = plan.as_any().downcast_ref::<RepartitionExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            let Partitioning::RoundRobinBatch(partitions) = repartition.partitioning() else {
+                return Ok(Transformed::No(plan));
+            };
+            let input = repartition.input().clone();
+            let Some(cost) = scan_cost(&input) else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let target_partitions = cost.target_partitions(*partitions);
+            if target_partitions == *partitions {
+                Ok(Transformed::No(plan))
+            } else if target_partitions <= input.output_partitioning().partition_count() {
+                Ok(Transformed::Yes(input))
+            } else {
+                Ok(Transformed::Yes(Arc::new(RepartitionExec::try_new(
+                    input,
+                    Partitioning::RoundRobinBatch(target_partitions),
+                )?)))
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "AdaptiveParallelismRule"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Sums up the costs of all table scans in the `plan`. Returns `None` if the cost of any
+/// scan is unknown.
+fn scan_cost(plan: &Arc<dyn ExecutionPlan>) -> Option<ScanCost> {
+    let children = plan.children();
+    if children.is_empty() {
+        let adapter = plan.as_any().downcast_ref::<DfPhysicalPlanAdapter>()?;
+        let scan = adapter.0.as_any().downcast_ref::<SimpleTableScan>()?;
+        return scan.scan_cost();
+    }
+
+    let mut cost = ScanCost::default();
+    for child in &children {
+        cost.merge(&scan_cost(child)?);
+    }
+    Some(cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use common_recordbatch::RecordBatches;
+    use datafusion::physical_plan::empty::EmptyExec;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use table::table::scan::BYTES_PER_PARTITION;
+
+    use super::*;
+
+    fn new_scan(cost: ScanCost) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let stream = RecordBatches::try_new(schema.clone(), vec![])
+            .unwrap()
+            .as_stream();
+        let scan = SimpleTableScan::new_partitioned(schema, vec![stream]).with_scan_cost(cost);
+        Arc::new(DfPhysicalPlanAdapter(Arc::new(scan)))
+    }
+
+    fn round_robin(input: Arc<dyn ExecutionPlan>, partitions: usize) -> Arc<dyn ExecutionPlan> {
+        Arc::new(
+            RepartitionExec::try_new(input, Partitioning::RoundRobinBatch(partitions)).unwrap(),
+        )
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        AdaptiveParallelismRule
+            .optimize(plan, &ConfigOptions::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_remove_repartition_of_small_scan() {
+        let plan = optimize(round_robin(new_scan(ScanCost::default()), 8));
+        assert!(plan.as_any().is::<DfPhysicalPlanAdapter>());
+    }
+
+    #[test]
+    fn test_reduce_repartition_of_medium_scan() {
+        let cost = ScanCost {
+            num_files: 4,
+            num_bytes: BYTES_PER_PARTITION * 3,
+        };
+        let plan = optimize(round_robin(new_scan(cost), 8));
+        assert_eq!(3, plan.output_partitioning().partition_count());
+        assert!(plan.as_any().is::<RepartitionExec>());
+    }
+
+    #[test]
+    fn test_keep_repartition_of_large_scan() {
+        let cost = ScanCost {
+            num_files: 100,
+            num_bytes: BYTES_PER_PARTITION * 100,
+        };
+        let plan = optimize(round_robin(new_scan(cost), 8));
+        assert_eq!(8, plan.output_partitioning().partition_count());
+    }
+
+    #[test]
+    fn test_keep_repartition_of_unknown_scan() {
+        let schema = Arc::new(datatypes::arrow::datatypes::Schema::empty());
+        let plan = optimize(round_robin(Arc::new(EmptyExec::new(false, schema)), 8));
+        assert_eq!(8, plan.output_partitioning().partition_count());
+    }
+}
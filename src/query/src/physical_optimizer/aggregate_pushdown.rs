@@ -0,0 +1,362 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::logical_plan::DfExpr;
+use common_query::physical_plan::{DfPhysicalPlanAdapter, PartialAggregate};
+use datafusion::config::ConfigOptions;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::expressions::{
+    Avg, CastExpr, Column, Count, Literal, Max, Min, Sum,
+};
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::{AggregateExpr, ExecutionPlan, Partitioning, PhysicalExpr};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Column as DfColumn, Result as DfResult};
+use datafusion_expr::{cast, count, max, min, sum};
+use datatypes::schema::Schema;
+
+/// Pushes the partial aggregations down to the table scans.
+///
+/// The `SUM`, `COUNT`, `MIN`, `MAX` and `AVG` on columns are decomposed into the aggregations
+/// computing their partial states. Table scans (like the distributed ones) that support
+/// [with_partial_aggregate] then only output the partial states, which are merged by the
+/// final aggregations as usual.
+///
+/// [with_partial_aggregate]: common_query::physical_plan::PhysicalPlan::with_partial_aggregate
+pub struct AggregatePushDownRule;
+
+impl PhysicalOptimizerRule for AggregatePushDownRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(aggregate) = plan.as_any().downcast_ref::<AggregateExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            match push_down(aggregate)? {
+                Some(plan) => Ok(Transformed::Yes(plan)),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "AggregatePushDownRule"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Replaces the partial `aggregate` with the table scan computing the partial states.
+/// Returns `None` if the aggregate can't be pushed down.
+fn push_down(aggregate: &AggregateExec) -> DfResult<Option<Arc<dyn ExecutionPlan>>> {
+    if *aggregate.mode() != AggregateMode::Partial
+        || !aggregate.group_expr().is_single()
+        || aggregate.filter_expr().iter().any(Option::is_some)
+    {
+        return Ok(None);
+    }
+    let Some((scan, columns)) = find_scan(aggregate.input()) else {
+        return Ok(None);
+    };
+    let Some(partial_aggregate) = to_partial_aggregate(aggregate, &columns) else {
+        return Ok(None);
+    };
+    let Some(plan) = scan.0.with_partial_aggregate(&partial_aggregate) else {
+        return Ok(None);
+    };
+
+    // Keep the partitioning of the partial aggregate, which the final aggregate relies on.
+    let plan: Arc<dyn ExecutionPlan> = Arc::new(DfPhysicalPlanAdapter(plan));
+    let partitions = aggregate.output_partitioning().partition_count();
+    let plan: Arc<dyn ExecutionPlan> = if plan.output_partitioning().partition_count() == partitions
+    {
+        plan
+    } else if partitions == 1 {
+        Arc::new(CoalescePartitionsExec::new(plan))
+    } else {
+        Arc::new(RepartitionExec::try_new(
+            plan,
+            Partitioning::RoundRobinBatch(partitions),
+        )?)
+    };
+    Ok(Some(plan))
+}
+
+/// Finds the table scan under the plans not changing the rows. Returns the scan and the
+/// names of the scanned columns each column of `plan` refers to.
+fn find_scan(plan: &Arc<dyn ExecutionPlan>) -> Option<(&DfPhysicalPlanAdapter, Vec<String>)> {
+    let any = plan.as_any();
+    if let Some(adapter) = any.downcast_ref::<DfPhysicalPlanAdapter>() {
+        let columns = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+        return Some((adapter, columns));
+    }
+
+    if let Some(projection) = any.downcast_ref::<ProjectionExec>() {
+        let (scan, input_columns) = find_scan(projection.input())?;
+        let columns = projection
+            .expr()
+            .iter()
+            .map(|(expr, _)| {
+                let column = expr.as_any().downcast_ref::<Column>()?;
+                input_columns.get(column.index()).cloned()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some((scan, columns))
+    } else if let Some(coalesce) = any.downcast_ref::<CoalesceBatchesExec>() {
+        find_scan(coalesce.input())
+    } else if let Some(repartition) = any.downcast_ref::<RepartitionExec>() {
+        find_scan(repartition.input())
+    } else {
+        None
+    }
+}
+
+fn to_partial_aggregate(aggregate: &AggregateExec, columns: &[String]) -> Option<PartialAggregate> {
+    let group_exprs = aggregate
+        .group_expr()
+        .expr()
+        .iter()
+        .map(|(expr, name)| Some(to_logical_expr(expr, columns)?.alias(name)))
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut aggr_exprs = Vec::new();
+    for aggr_expr in aggregate.aggr_expr() {
+        let state_fields = aggr_expr.state_fields().ok()?;
+        let state_exprs = to_state_exprs(aggr_expr, columns)?;
+        if state_fields.len() != state_exprs.len() {
+            return None;
+        }
+        aggr_exprs.extend(
+            state_exprs
+                .into_iter()
+                .zip(state_fields.iter())
+                .map(|(expr, field)| expr.alias(field.name())),
+        );
+    }
+
+    let schema = Schema::try_from(aggregate.schema()).ok()?;
+    Some(PartialAggregate {
+        group_exprs,
+        aggr_exprs,
+        schema: Arc::new(schema),
+    })
+}
+
+/// Decomposes the aggregate expression into the aggregations computing its partial states,
+/// in the order of its state fields.
+fn to_state_exprs(aggr_expr: &Arc<dyn AggregateExpr>, columns: &[String]) -> Option<Vec<DfExpr>> {
+    let args = aggr_expr.expressions();
+    let [arg] = args.as_slice() else {
+        return None;
+    };
+    let arg = to_logical_expr(arg, columns)?;
+
+    let any = aggr_expr.as_any();
+    if any.is::<Count>() {
+        Some(vec![count(arg)])
+    } else if any.is::<Sum>() {
+        Some(vec![sum(arg)])
+    } else if any.is::<Min>() {
+        Some(vec![min(arg)])
+    } else if any.is::<Max>() {
+        Some(vec![max(arg)])
+    } else if any.is::<Avg>() {
+        Some(vec![count(arg.clone()), sum(arg)])
+    } else {
+        None
+    }
+}
+
+/// Converts the physical expression to the logical one on the scanned `columns`. Only columns,
+/// literals and casts are supported.
+fn to_logical_expr(expr: &Arc<dyn PhysicalExpr>, columns: &[String]) -> Option<DfExpr> {
+    let any = expr.as_any();
+    if let Some(column) = any.downcast_ref::<Column>() {
+        let name = columns.get(column.index())?;
+        Some(DfExpr::Column(DfColumn::from_name(name)))
+    } else if let Some(literal) = any.downcast_ref::<Literal>() {
+        Some(DfExpr::Literal(literal.value().clone()))
+    } else if let Some(cast_expr) = any.downcast_ref::<CastExpr>() {
+        let expr = to_logical_expr(cast_expr.expr(), columns)?;
+        Some(cast(expr, cast_expr.cast_type().clone()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::sync::Mutex;
+
+    use common_query::error::Result as QueryResult;
+    use common_query::physical_plan::{PhysicalPlan, PhysicalPlanRef, TaskContext};
+    use common_recordbatch::SendableRecordBatchStream;
+    use datafusion::arrow::datatypes::DataType;
+    use datafusion::physical_plan::aggregates::PhysicalGroupBy;
+    use datafusion::physical_plan::expressions::{cast as physical_cast, col as physical_col};
+    use datafusion::physical_plan::filter::FilterExec;
+    use datafusion_expr::col;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, SchemaRef};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockScan {
+        schema: SchemaRef,
+        aggregate: Arc<Mutex<Option<PartialAggregate>>>,
+    }
+
+    impl PhysicalPlan for MockScan {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn output_partitioning(&self) -> Partitioning {
+            Partitioning::UnknownPartitioning(2)
+        }
+
+        fn children(&self) -> Vec<PhysicalPlanRef> {
+            vec![]
+        }
+
+        fn with_new_children(
+            &self,
+            _children: Vec<PhysicalPlanRef>,
+        ) -> QueryResult<PhysicalPlanRef> {
+            unimplemented!()
+        }
+
+        fn execute(
+            &self,
+            _partition: usize,
+            _context: Arc<TaskContext>,
+        ) -> QueryResult<SendableRecordBatchStream> {
+            unimplemented!()
+        }
+
+        fn with_partial_aggregate(&self, aggregate: &PartialAggregate) -> Option<PhysicalPlanRef> {
+            *self.aggregate.lock().unwrap() = Some(aggregate.clone());
+            Some(Arc::new(MockScan {
+                schema: aggregate.schema.clone(),
+                aggregate: self.aggregate.clone(),
+            }))
+        }
+    }
+
+    fn new_scan() -> (Arc<dyn ExecutionPlan>, Arc<Mutex<Option<PartialAggregate>>>) {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("a", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new("b", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("c", ConcreteDataType::boolean_datatype(), true),
+        ]));
+        let aggregate = Arc::new(Mutex::new(None));
+        let scan = MockScan {
+            schema,
+            aggregate: aggregate.clone(),
+        };
+        (Arc::new(DfPhysicalPlanAdapter(Arc::new(scan))), aggregate)
+    }
+
+    /// Partial aggregate of `SELECT b, COUNT(a), AVG(a) FROM t GROUP BY b`.
+    fn partial_aggregate(input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let schema = input.schema();
+        let a = physical_col("a", &schema).unwrap();
+        let group_by = PhysicalGroupBy::new_single(vec![(
+            physical_col("b", &schema).unwrap(),
+            "b".to_string(),
+        )]);
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Count::new(a.clone(), "COUNT(a)", DataType::Int64)),
+            Arc::new(Avg::new(
+                physical_cast(a, &schema, DataType::Float64).unwrap(),
+                "AVG(a)",
+                DataType::Float64,
+            )),
+        ];
+        Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::Partial,
+                group_by,
+                aggr_expr,
+                vec![None, None],
+                input,
+                schema,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        AggregatePushDownRule
+            .optimize(plan, &ConfigOptions::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_push_down_partial_aggregate() {
+        let (scan, aggregate) = new_scan();
+        let partial = partial_aggregate(scan);
+        let plan = Arc::new(CoalescePartitionsExec::new(partial.clone()));
+        let plan = optimize(plan);
+
+        let input = plan.children()[0].clone();
+        assert!(input.as_any().is::<DfPhysicalPlanAdapter>());
+        assert_eq!(partial.schema(), input.schema());
+
+        let aggregate = aggregate.lock().unwrap().clone().unwrap();
+        assert_eq!(vec![col("b").alias("b")], aggregate.group_exprs);
+        let a = cast(col("a"), DataType::Float64);
+        assert_eq!(
+            vec![
+                count(col("a")).alias("COUNT(a)[count]"),
+                count(a.clone()).alias("AVG(a)[count]"),
+                sum(a).alias("AVG(a)[sum]"),
+            ],
+            aggregate.aggr_exprs
+        );
+    }
+
+    #[test]
+    fn test_not_push_down_partial_aggregate() {
+        // Rows filtered after scan.
+        let (scan, aggregate) = new_scan();
+        let predicate = physical_col("c", &scan.schema()).unwrap();
+        let filter = Arc::new(FilterExec::try_new(predicate, scan).unwrap());
+        let plan = optimize(partial_aggregate(filter));
+        assert!(plan.as_any().is::<AggregateExec>());
+        assert!(aggregate.lock().unwrap().is_none());
+    }
+}
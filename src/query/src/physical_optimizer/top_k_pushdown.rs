@@ -0,0 +1,273 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::logical_plan::DfExpr;
+use common_query::physical_plan::DfPhysicalPlanAdapter;
+use datafusion::config::ConfigOptions;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::expressions::{Column, PhysicalSortExpr};
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Column as DfColumn, Result as DfResult};
+
+/// Pushes the top-k of `ORDER BY ... LIMIT k` queries down to the table scans.
+///
+/// The sort with fetch is kept where it is, to merge the partial top-k rows from the scans.
+/// Table scans (like the distributed ones) that support [with_top_k] then only need to
+/// fetch the top-k rows of each partition, instead of the whole table.
+///
+/// [with_top_k]: common_query::physical_plan::PhysicalPlan::with_top_k
+pub struct TopKPushDownRule;
+
+impl PhysicalOptimizerRule for TopKPushDownRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            let Some(fetch) = sort.fetch() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            match push_down(sort.input(), sort.expr(), fetch)? {
+                Some(input) => Ok(Transformed::Yes(plan.with_new_children(vec![input])?)),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "TopKPushDownRule"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Pushes the top-k down through the plans not changing the rows, until reaching a table
+/// scan. Returns `None` if the top-k can't be pushed down to the scan.
+fn push_down(
+    plan: &Arc<dyn ExecutionPlan>,
+    sort_exprs: &[PhysicalSortExpr],
+    fetch: usize,
+) -> DfResult<Option<Arc<dyn ExecutionPlan>>> {
+    let any = plan.as_any();
+    if let Some(adapter) = any.downcast_ref::<DfPhysicalPlanAdapter>() {
+        let sort_exprs = sort_exprs
+            .iter()
+            .map(to_logical_sort_expr)
+            .collect::<Option<Vec<_>>>();
+        return Ok(sort_exprs
+            .and_then(|sort_exprs| adapter.0.with_top_k(&sort_exprs, fetch))
+            .map(|plan| Arc::new(DfPhysicalPlanAdapter(plan)) as _));
+    }
+
+    let sort_exprs = if let Some(projection) = any.downcast_ref::<ProjectionExec>() {
+        let sort_exprs = sort_exprs
+            .iter()
+            .map(|sort_expr| project_sort_expr(projection, sort_expr))
+            .collect::<Option<Vec<_>>>();
+        match sort_exprs {
+            Some(sort_exprs) => sort_exprs,
+            None => return Ok(None),
+        }
+    } else if any.is::<CoalescePartitionsExec>()
+        || any.is::<CoalesceBatchesExec>()
+        || any.is::<RepartitionExec>()
+    {
+        sort_exprs.to_vec()
+    } else {
+        return Ok(None);
+    };
+
+    let input = plan.children()[0].clone();
+    match push_down(&input, &sort_exprs, fetch)? {
+        Some(input) => Ok(Some(plan.clone().with_new_children(vec![input])?)),
+        None => Ok(None),
+    }
+}
+
+/// Maps the sort expression on the output of `projection` to its input. Only the projected
+/// columns are supported.
+fn project_sort_expr(
+    projection: &ProjectionExec,
+    sort_expr: &PhysicalSortExpr,
+) -> Option<PhysicalSortExpr> {
+    let column = sort_expr.expr.as_any().downcast_ref::<Column>()?;
+    let (expr, _) = projection.expr().get(column.index())?;
+    let _ = expr.as_any().downcast_ref::<Column>()?;
+    Some(PhysicalSortExpr {
+        expr: expr.clone(),
+        options: sort_expr.options,
+    })
+}
+
+fn to_logical_sort_expr(sort_expr: &PhysicalSortExpr) -> Option<DfExpr> {
+    let column = sort_expr.expr.as_any().downcast_ref::<Column>()?;
+    Some(
+        DfExpr::Column(DfColumn::from_name(column.name()))
+            .sort(!sort_expr.options.descending, sort_expr.options.nulls_first),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+
+    use common_query::error::Result as QueryResult;
+    use common_query::physical_plan::{Partitioning, PhysicalPlan, PhysicalPlanRef, TaskContext};
+    use common_recordbatch::SendableRecordBatchStream;
+    use datafusion::arrow::compute::SortOptions;
+    use datafusion::physical_plan::expressions::col;
+    use datafusion::physical_plan::filter::FilterExec;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockScan {
+        schema: SchemaRef,
+        top_k: Option<(Vec<DfExpr>, usize)>,
+    }
+
+    impl PhysicalPlan for MockScan {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn output_partitioning(&self) -> Partitioning {
+            Partitioning::UnknownPartitioning(2)
+        }
+
+        fn children(&self) -> Vec<PhysicalPlanRef> {
+            vec![]
+        }
+
+        fn with_new_children(
+            &self,
+            _children: Vec<PhysicalPlanRef>,
+        ) -> QueryResult<PhysicalPlanRef> {
+            unimplemented!()
+        }
+
+        fn execute(
+            &self,
+            _partition: usize,
+            _context: Arc<TaskContext>,
+        ) -> QueryResult<SendableRecordBatchStream> {
+            unimplemented!()
+        }
+
+        fn with_top_k(&self, sort_exprs: &[DfExpr], fetch: usize) -> Option<PhysicalPlanRef> {
+            Some(Arc::new(MockScan {
+                schema: self.schema.clone(),
+                top_k: Some((sort_exprs.to_vec(), fetch)),
+            }))
+        }
+    }
+
+    fn new_scan() -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("a", ConcreteDataType::int32_datatype(), false),
+            ColumnSchema::new("b", ConcreteDataType::boolean_datatype(), false),
+        ]));
+        Arc::new(DfPhysicalPlanAdapter(Arc::new(MockScan {
+            schema,
+            top_k: None,
+        })))
+    }
+
+    fn sort(input: Arc<dyn ExecutionPlan>, fetch: Option<usize>) -> Arc<dyn ExecutionPlan> {
+        let sort_expr = PhysicalSortExpr {
+            expr: col("x", &input.schema()).unwrap(),
+            options: SortOptions {
+                descending: true,
+                nulls_first: false,
+            },
+        };
+        Arc::new(SortExec::new(vec![sort_expr], input).with_fetch(fetch))
+    }
+
+    fn project(input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let expr = col("a", &input.schema()).unwrap();
+        Arc::new(ProjectionExec::try_new(vec![(expr, "x".to_string())], input).unwrap())
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        TopKPushDownRule
+            .optimize(plan, &ConfigOptions::default())
+            .unwrap()
+    }
+
+    fn scan_top_k(plan: &Arc<dyn ExecutionPlan>) -> Option<(Vec<DfExpr>, usize)> {
+        let children = plan.children();
+        if children.is_empty() {
+            let adapter = plan
+                .as_any()
+                .downcast_ref::<DfPhysicalPlanAdapter>()
+                .unwrap();
+            let scan = adapter.0.as_any().downcast_ref::<MockScan>().unwrap();
+            return scan.top_k.clone();
+        }
+        scan_top_k(&children[0])
+    }
+
+    #[test]
+    fn test_push_down_top_k() {
+        let plan = sort(
+            Arc::new(CoalescePartitionsExec::new(project(new_scan()))),
+            Some(10),
+        );
+        let plan = optimize(plan);
+        assert!(plan.as_any().is::<SortExec>());
+
+        let (sort_exprs, fetch) = scan_top_k(&plan).unwrap();
+        assert_eq!(10, fetch);
+        assert_eq!(
+            vec![DfExpr::Column(DfColumn::from_name("a")).sort(false, false)],
+            sort_exprs
+        );
+    }
+
+    #[test]
+    fn test_not_push_down_top_k() {
+        // No limit.
+        let plan = optimize(sort(project(new_scan()), None));
+        assert!(scan_top_k(&plan).is_none());
+
+        // Rows filtered after scan.
+        let scan = new_scan();
+        let predicate = col("b", &scan.schema()).unwrap();
+        let filter = Arc::new(FilterExec::try_new(predicate, scan).unwrap());
+        let plan = optimize(sort(project(filter), Some(10)));
+        assert!(scan_top_k(&plan).is_none());
+    }
+}
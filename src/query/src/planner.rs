@@ -12,20 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cell::Cell;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use catalog::table_source::DfTableSourceProvider;
 use common_error::prelude::BoxedError;
+use datafusion::datasource::{provider_as_source, source_as_provider};
 use datafusion::execution::context::SessionState;
-use datafusion_sql::planner::{ParserOptions, SqlToRel};
-use promql::planner::PromPlanner;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{DataFusionError, Result as DfResult, TableReference};
+use datafusion_expr::utils::from_plan;
+use datafusion_expr::{Expr, LogicalPlan as DfLogicalPlan, Subquery, TableScan};
+use datafusion_sql::planner::SqlToRel;
+use promql::planner::{PromPlanner, PromPlannerOptions};
 use promql_parser::parser::EvalStmt;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
+use sql::function::{expand_functions, FunctionTemplate};
+use sql::statements::query::TableSample;
 use sql::statements::statement::Statement;
+use table::table::adapter::DfTableProviderAdapter;
 
-use crate::error::{PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu};
+use crate::datafusion::parser_options;
+use crate::error::{
+    AmbiguousTableSampleSnafu, DataFusionSnafu, PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu,
+    TableNotFoundSnafu, UnsupportedExprSnafu,
+};
 use crate::parser::QueryStatement;
 use crate::plan::LogicalPlan;
 use crate::query_engine::QueryEngineState;
@@ -51,23 +64,31 @@ impl DfLogicalPlanner {
     }
 
     async fn plan_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
-        let df_stmt = (&stmt).try_into().context(SqlSnafu)?;
+        let (table_samples, tail) = match &stmt {
+            Statement::Query(query) => (query.table_samples.clone(), query.tail),
+            _ => (vec![], false),
+        };
+        let mut df_stmt = (&stmt).try_into().context(SqlSnafu)?;
+        if query_ctx.has_functions() {
+            expand_functions(&mut df_stmt, |name| {
+                query_ctx.function(name).map(|function| FunctionTemplate {
+                    args: function.args.clone(),
+                    body: function.body.clone(),
+                })
+            })
+            .context(SqlSnafu)?;
+        }
 
         let context_provider = DfContextProviderAdapter::try_new(
             self.engine_state.clone(),
             self.session_state.clone(),
             &df_stmt,
-            query_ctx,
+            query_ctx.clone(),
         )
         .await?;
 
-        let config_options = self.session_state.config().options();
-        let parser_options = ParserOptions {
-            enable_ident_normalization: config_options.sql_parser.enable_ident_normalization,
-            parse_float_as_decimal: config_options.sql_parser.parse_float_as_decimal,
-        };
-
-        let sql_to_rel = SqlToRel::new_with_options(&context_provider, parser_options);
+        let sql_to_rel =
+            SqlToRel::new_with_options(&context_provider, parser_options(&self.session_state));
 
         let result = sql_to_rel.statement_to_plan(df_stmt).with_context(|_| {
             let sql = if let Statement::Query(query) = stmt {
@@ -77,6 +98,18 @@ impl DfLogicalPlanner {
             };
             PlanSqlSnafu { sql }
         })?;
+        let result = apply_table_samples(result, &table_samples, &query_ctx)?;
+        let result = if tail {
+            ensure!(
+                !query_ctx.collects_output(),
+                UnsupportedExprSnafu {
+                    name: "tail over a protocol collecting the whole result",
+                }
+            );
+            apply_tail(result)?
+        } else {
+            result
+        };
         Ok(LogicalPlan::DfPlan(result))
     }
 
@@ -86,7 +119,10 @@ impl DfLogicalPlanner {
             self.engine_state.disallow_cross_schema_query(),
             query_ctx.as_ref(),
         );
-        PromPlanner::stmt_to_plan(table_provider, stmt)
+        let options = PromPlannerOptions {
+            strict_staleness: self.engine_state.promql_strict_staleness(),
+        };
+        PromPlanner::stmt_to_plan_with_options(table_provider, stmt, options)
             .await
             .map(LogicalPlan::DfPlan)
             .map_err(BoxedError::new)
@@ -94,6 +130,196 @@ impl DfLogicalPlanner {
     }
 }
 
+/// A relation sampled by `TABLESAMPLE`, which is the table read under the alias, or the
+/// table read without any alias.
+struct SampledRelation {
+    table: String,
+    alias: Option<String>,
+    percent: f64,
+    /// Number of the scans of the relation in the plan.
+    scans: Cell<usize>,
+}
+
+/// Replaces the sources of the table scans sampled by `TABLESAMPLE` with
+/// adapters that only scan a part of the table. Only the scans of the sampled
+/// relations are replaced, including the ones in the subqueries, and each sampled
+/// relation must be scanned exactly once, e.g. a table read more than once must be
+/// sampled under a unique alias.
+fn apply_table_samples(
+    plan: DfLogicalPlan,
+    table_samples: &[TableSample],
+    query_ctx: &QueryContextRef,
+) -> Result<DfLogicalPlan> {
+    if table_samples.is_empty() {
+        return Ok(plan);
+    }
+
+    let catalog = query_ctx.current_catalog();
+    let schema = query_ctx.current_schema();
+    let relations = table_samples
+        .iter()
+        .map(|sample| {
+            let table = sample.table.to_string();
+            let table = TableReference::from(table.as_str())
+                .resolve(&catalog, &schema)
+                .to_string();
+            // Parses the alias as a table name, so it's normalized like the planner does.
+            let alias = sample.alias.as_ref().map(|alias| {
+                TableReference::from(alias.to_string().as_str())
+                    .table()
+                    .to_string()
+            });
+            SampledRelation {
+                table,
+                alias,
+                percent: sample.percent,
+                scans: Cell::new(0),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let sampler = |scan: &TableScan, alias: Option<&str>| -> DfResult<Option<DfLogicalPlan>> {
+        let name = scan
+            .table_name
+            .clone()
+            .resolve(&catalog, &schema)
+            .to_string();
+        let Some(relation) = relations
+            .iter()
+            .find(|relation| relation.table == name && relation.alias.as_deref() == alias)
+        else {
+            return Ok(None);
+        };
+        let provider = source_as_provider(&scan.source)?;
+        let Some(adapter) = provider.as_any().downcast_ref::<DfTableProviderAdapter>() else {
+            return Ok(None);
+        };
+        relation.scans.set(relation.scans.get() + 1);
+        let source = provider_as_source(Arc::new(DfTableProviderAdapter::with_sample(
+            adapter.table(),
+            relation.percent,
+        )));
+        Ok(Some(DfLogicalPlan::TableScan(TableScan {
+            source,
+            ..scan.clone()
+        })))
+    };
+    let sampled = sample_scans(&plan, &sampler).context(DataFusionSnafu)?;
+
+    for relation in &relations {
+        let table = &relation.table;
+        ensure!(relation.scans.get() > 0, TableNotFoundSnafu { table });
+        ensure!(
+            relation.scans.get() == 1,
+            AmbiguousTableSampleSnafu { table }
+        );
+    }
+    Ok(sampled.unwrap_or(plan))
+}
+
+/// Returns the sampled scan of the table scan read under the alias, if it's sampled.
+type ScanSampler<'a> = dyn Fn(&TableScan, Option<&str>) -> DfResult<Option<DfLogicalPlan>> + 'a;
+
+/// Replaces the table scans in the plan, including the ones in the subqueries, by the
+/// sampler. Returns `None` if nothing is replaced.
+fn sample_scans(plan: &DfLogicalPlan, sampler: &ScanSampler) -> DfResult<Option<DfLogicalPlan>> {
+    match plan {
+        DfLogicalPlan::TableScan(scan) => return sampler(scan, None),
+        DfLogicalPlan::SubqueryAlias(alias) => {
+            if let DfLogicalPlan::TableScan(scan) = alias.input.as_ref() {
+                let Some(input) = sampler(scan, Some(&alias.alias.to_string()))? else {
+                    return Ok(None);
+                };
+                return from_plan(plan, &plan.expressions(), &[input]).map(Some);
+            }
+        }
+        _ => {}
+    }
+
+    let inputs = plan.inputs();
+    let exprs = plan.expressions();
+    let new_inputs = inputs
+        .iter()
+        .map(|input| sample_scans(input, sampler))
+        .collect::<DfResult<Vec<_>>>()?;
+    let new_exprs = exprs
+        .iter()
+        .map(|expr| sample_subqueries(expr, sampler))
+        .collect::<DfResult<Vec<_>>>()?;
+    if new_inputs.iter().all(Option::is_none) && new_exprs.iter().all(Option::is_none) {
+        return Ok(None);
+    }
+
+    let inputs = new_inputs
+        .into_iter()
+        .zip(inputs)
+        .map(|(new, old)| new.unwrap_or_else(|| old.clone()))
+        .collect::<Vec<_>>();
+    let exprs = new_exprs
+        .into_iter()
+        .zip(exprs)
+        .map(|(new, old)| new.unwrap_or(old))
+        .collect::<Vec<_>>();
+    from_plan(plan, &exprs, &inputs).map(Some)
+}
+
+/// Replaces the table scans in the subqueries of the expression by [sample_scans].
+fn sample_subqueries(expr: &Expr, sampler: &ScanSampler) -> DfResult<Option<Expr>> {
+    let sampled = Cell::new(false);
+    let expr = expr.clone().transform_up(&|expr| {
+        let subquery = match &expr {
+            Expr::ScalarSubquery(subquery)
+            | Expr::Exists { subquery, .. }
+            | Expr::InSubquery { subquery, .. } => subquery,
+            _ => return Ok(Transformed::No(expr)),
+        };
+        let Some(plan) = sample_scans(&subquery.subquery, sampler)? else {
+            return Ok(Transformed::No(expr));
+        };
+        let subquery = Subquery {
+            subquery: Arc::new(plan),
+            ..subquery.clone()
+        };
+        sampled.set(true);
+
+        let expr = match expr {
+            Expr::ScalarSubquery(_) => Expr::ScalarSubquery(subquery),
+            Expr::Exists { negated, .. } => Expr::Exists { subquery, negated },
+            Expr::InSubquery { expr, negated, .. } => Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            },
+            _ => unreachable!(),
+        };
+        Ok(Transformed::Yes(expr))
+    })?;
+    Ok(sampled.get().then_some(expr))
+}
+
+/// Replaces the sources of all table scans with adapters that tail the tables. Only the tables
+/// implementing [Table::tail](table::Table::tail) (mito tables on the datanode or in standalone
+/// mode) can be tailed, the others fail the query when it's executed.
+fn apply_tail(plan: DfLogicalPlan) -> Result<DfLogicalPlan> {
+    plan.transform_up(&|plan| {
+        let DfLogicalPlan::TableScan(scan) = plan else { return Ok(Transformed::No(plan)) };
+        let provider = source_as_provider(&scan.source)?;
+        let Some(adapter) = provider.as_any().downcast_ref::<DfTableProviderAdapter>() else {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Tailing table {}",
+                scan.table_name
+            )));
+        };
+        let source =
+            provider_as_source(Arc::new(DfTableProviderAdapter::with_tail(adapter.table())));
+        Ok(Transformed::Yes(DfLogicalPlan::TableScan(TableScan {
+            source,
+            ..scan
+        })))
+    })
+    .context(DataFusionSnafu)
+}
+
 #[async_trait]
 impl LogicalPlanner for DfLogicalPlanner {
     async fn plan(&self, stmt: QueryStatement, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
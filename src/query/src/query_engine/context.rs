@@ -12,20 +12,52 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use datafusion::execution::context::SessionState;
+use std::sync::Arc;
+
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::execution::runtime_env::RuntimeEnv;
+
+use crate::stats::QueryMemoryPool;
 
 #[derive(Debug)]
 pub struct QueryEngineContext {
     state: SessionState,
+    memory_pool: Arc<QueryMemoryPool>,
 }
 
 impl QueryEngineContext {
     pub fn new(state: SessionState) -> Self {
-        Self { state }
+        let memory_pool = Arc::new(QueryMemoryPool::new(
+            state.runtime_env().memory_pool.clone(),
+        ));
+        Self { state, memory_pool }
     }
 
     #[inline]
     pub fn state(&self) -> &SessionState {
         &self.state
     }
+
+    /// Creates the context to execute the query in, whose memory reservations are
+    /// tracked by the query's own pool.
+    pub fn task_ctx(&self) -> Arc<TaskContext> {
+        let runtime = self.state.runtime_env();
+        let runtime = Arc::new(RuntimeEnv {
+            memory_pool: self.memory_pool.clone(),
+            disk_manager: runtime.disk_manager.clone(),
+            object_store_registry: runtime.object_store_registry.clone(),
+        });
+        Arc::new(TaskContext::new(
+            None,
+            self.state.session_id().to_string(),
+            self.state.config().clone(),
+            self.state.scalar_functions().clone(),
+            self.state.aggregate_functions().clone(),
+            runtime,
+        ))
+    }
+
+    pub(crate) fn memory_pool(&self) -> &Arc<QueryMemoryPool> {
+        &self.memory_pool
+    }
 }
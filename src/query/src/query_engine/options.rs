@@ -20,6 +20,11 @@ use crate::error::{QueryAccessDeniedSnafu, Result};
 #[derive(Default, Clone)]
 pub struct QueryOptions {
     pub disallow_cross_schema_query: bool,
+    /// Rejects the `ADMIN` statements, which operate on tables or regions directly.
+    pub disallow_admin_statement: bool,
+    /// Handles staleness in PromQL queries like Prometheus: only the staleness markers end
+    /// a series, while other NaN values are kept as samples.
+    pub promql_strict_staleness: bool,
 }
 
 // TODO(shuiyisong): remove one method after #559 is done
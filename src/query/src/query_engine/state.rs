@@ -22,17 +22,22 @@ use common_base::Plugins;
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_query::physical_plan::SessionContext;
 use common_query::prelude::ScalarUdf;
+use common_telemetry::memory::{MemoryUsage, Subsystem};
 use datafusion::catalog::catalog::MemoryCatalogList;
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::{QueryPlanner, SessionConfig, SessionState};
+use datafusion::execution::memory_pool::{MemoryPool, MemoryReservation, UnboundedMemoryPool};
 use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
 use datafusion::physical_plan::{ExecutionPlan, PhysicalPlanner};
 use datafusion_expr::LogicalPlan as DfLogicalPlan;
 use datafusion_optimizer::analyzer::Analyzer;
 use promql::extension_plan::PromExtensionPlanner;
+use session::context::QueryHints;
+use table::table::scan::ScanHints;
 
 use crate::optimizer::TypeConversionRule;
+use crate::physical_optimizer::{AdaptiveParallelismRule, AggregatePushDownRule, TopKPushDownRule};
 use crate::query_engine::options::QueryOptions;
 
 /// Query engine global state
@@ -56,7 +61,12 @@ impl fmt::Debug for QueryEngineState {
 
 impl QueryEngineState {
     pub fn new(catalog_list: CatalogManagerRef, plugins: Arc<Plugins>) -> Self {
-        let runtime_env = Arc::new(RuntimeEnv::default());
+        // Only replaces the memory pool of the default runtime, building a runtime from a
+        // config would make the constructor fallible.
+        let runtime_env = Arc::new(RuntimeEnv {
+            memory_pool: Arc::new(ReportingMemoryPool::default()),
+            ..RuntimeEnv::default()
+        });
         let session_config = SessionConfig::new().with_create_default_catalog_and_schema(false);
         // Apply the type conversion rule first.
         let mut analyzer = Analyzer::new();
@@ -69,6 +79,13 @@ impl QueryEngineState {
         )
         .with_analyzer_rules(analyzer.rules)
         .with_query_planner(Arc::new(DfQueryPlanner::new()));
+        // Adjust the parallelism after DataFusion's repartition rules, and push down the
+        // top-k and aggregates after the sorts and partitionings are settled.
+        let mut physical_optimizers = session_state.physical_optimizers().to_vec();
+        physical_optimizers.push(Arc::new(AdaptiveParallelismRule));
+        physical_optimizers.push(Arc::new(TopKPushDownRule));
+        physical_optimizers.push(Arc::new(AggregatePushDownRule));
+        let session_state = session_state.with_physical_optimizer_rules(physical_optimizers);
 
         let df_context = SessionContext::with_state(session_state);
 
@@ -115,9 +132,34 @@ impl QueryEngineState {
             .unwrap_or(false)
     }
 
+    pub(crate) fn promql_strict_staleness(&self) -> bool {
+        self.plugins
+            .get::<QueryOptions>()
+            .map(|x| x.promql_strict_staleness)
+            .unwrap_or(false)
+    }
+
     pub(crate) fn session_state(&self) -> SessionState {
         self.df_context.state()
     }
+
+    /// Returns the session state to execute a query with the `hints`.
+    pub(crate) fn session_state_with_hints(&self, hints: &QueryHints) -> SessionState {
+        let mut state = self.session_state();
+        if hints.is_empty() {
+            return state;
+        }
+
+        let config = state.config_mut();
+        if let Some(parallelism) = hints.parallelism {
+            config.options_mut().execution.target_partitions = parallelism;
+        }
+        let scan_hints = ScanHints {
+            no_index: hints.no_index,
+        };
+        *config = std::mem::take(config).with_extension(Arc::new(scan_hints));
+        state
+    }
 }
 
 struct DfQueryPlanner {
@@ -137,6 +179,45 @@ impl QueryPlanner for DfQueryPlanner {
     }
 }
 
+/// [UnboundedMemoryPool] that reports the memory reserved by operators like sorts and
+/// joins to [Subsystem::QueryExecution].
+#[derive(Debug)]
+struct ReportingMemoryPool {
+    inner: UnboundedMemoryPool,
+    usage: MemoryUsage,
+}
+
+impl Default for ReportingMemoryPool {
+    fn default() -> Self {
+        Self {
+            inner: UnboundedMemoryPool::default(),
+            usage: MemoryUsage::new(Subsystem::QueryExecution),
+        }
+    }
+}
+
+impl MemoryPool for ReportingMemoryPool {
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional);
+        self.usage.add(additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink);
+        self.usage.sub(shrink);
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> DfResult<()> {
+        self.inner.try_grow(reservation, additional)?;
+        self.usage.add(additional);
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.inner.reserved()
+    }
+}
+
 impl DfQueryPlanner {
     fn new() -> Self {
         Self {
@@ -18,7 +18,6 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use catalog::CatalogManagerRef;
-use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use common_datasource::file_format::{infer_schemas, FileFormat, Format};
 use common_datasource::lister::{Lister, Source};
 use common_datasource::object_store::build_backend;
@@ -32,16 +31,19 @@ use object_store::ObjectStore;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use session::context::QueryContextRef;
+pub use show::create_table_stmt;
 use snafu::{ensure, OptionExt, ResultExt};
-use sql::ast::ColumnDef;
+use sql::ast::{ColumnDef, Ident};
 use sql::statements::column_def_to_schema;
 use sql::statements::create::Partitions;
 use sql::statements::show::{ShowDatabases, ShowKind, ShowTables};
 use table::requests::{IMMUTABLE_TABLE_LOCATION_KEY, IMMUTABLE_TABLE_PATTERN_KEY};
+use table::table::view::ViewTable;
 use table::TableRef;
 
 use crate::error::{self, Result};
 
+const CATALOGS_COLUMN: &str = "Catalogs";
 const SCHEMAS_COLUMN: &str = "Schemas";
 const TABLES_COLUMN: &str = "Tables";
 const COLUMN_NAME_COLUMN: &str = "Field";
@@ -94,9 +96,29 @@ static SHOW_CREATE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
     ]))
 });
 
+pub async fn show_catalogs(catalog_manager: CatalogManagerRef) -> Result<Output> {
+    let mut catalogs = catalog_manager
+        .catalog_names()
+        .await
+        .context(error::CatalogSnafu)?;
+    catalogs.sort();
+
+    let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+        CATALOGS_COLUMN,
+        ConcreteDataType::string_datatype(),
+        false,
+    )]));
+    let records =
+        RecordBatches::try_from_columns(schema, vec![Arc::new(StringVector::from(catalogs)) as _])
+            .context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+/// Shows the schemas of the current catalog.
 pub async fn show_databases(
     stmt: ShowDatabases,
     catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
 ) -> Result<Output> {
     // TODO(LFC): supports WHERE
     ensure!(
@@ -106,12 +128,13 @@ pub async fn show_databases(
         }
     );
 
+    let catalog_name = query_ctx.current_catalog();
     let catalog = catalog_manager
-        .catalog(DEFAULT_CATALOG_NAME)
+        .catalog(&catalog_name)
         .await
         .context(error::CatalogSnafu)?
         .context(error::CatalogNotFoundSnafu {
-            catalog: DEFAULT_CATALOG_NAME,
+            catalog: &catalog_name,
         })?;
     let mut databases = catalog.schema_names().await.context(error::CatalogSnafu)?;
     // TODO(dennis): Specify the order of the results in catalog manager API
@@ -180,9 +203,17 @@ pub async fn show_tables(
 pub fn show_create_table(table: TableRef, partitions: Option<Partitions>) -> Result<Output> {
     let table_info = table.table_info();
     let table_name = &table_info.name;
-    let mut stmt = show::create_table_stmt(&table_info)?;
-    stmt.partitions = partitions;
-    let sql = format!("{}", stmt);
+    let sql = if let Some(view) = table.as_any().downcast_ref::<ViewTable>() {
+        format!(
+            "CREATE VIEW IF NOT EXISTS {} AS {}",
+            quote_ident(table_name),
+            view.definition()
+        )
+    } else {
+        let mut stmt = show::create_table_stmt(&table_info)?;
+        stmt.partitions = partitions;
+        format!("{}", stmt)
+    };
     let columns = vec![
         Arc::new(StringVector::from(vec![table_name.clone()])) as _,
         Arc::new(StringVector::from(vec![sql])) as _,
@@ -193,6 +224,20 @@ pub fn show_create_table(table: TableRef, partitions: Option<Partitions>) -> Res
     Ok(Output::RecordBatches(records))
 }
 
+/// Quotes the identifier `name` unless it's a plain lowercase identifier, so it's parsed back
+/// to the same name.
+fn quote_ident(name: &str) -> Ident {
+    let is_plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if is_plain {
+        Ident::new(name)
+    } else {
+        Ident::with_quote('"', name)
+    }
+}
+
 pub fn describe_table(table: TableRef) -> Result<Output> {
     let table_info = table.table_info();
     let columns_schemas = table_info.meta.schema.column_schemas();
@@ -284,6 +329,20 @@ pub async fn prepare_immutable_file_table_files_and_schema(
     Ok((files, schema))
 }
 
+/// Builds the schema of a remote table from the declared `columns`. The schema of
+/// a remote table can't be inferred so columns are required.
+pub fn prepare_remote_table_schema(table_name: &str, columns: &[ColumnDef]) -> Result<RawSchema> {
+    ensure!(
+        !columns.is_empty(),
+        error::MissingRemoteTableColumnsSnafu { table_name }
+    );
+    let columns_schemas: Vec<_> = columns
+        .iter()
+        .map(|column| column_def_to_schema(column, false).context(error::ParseSqlSnafu))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RawSchema::new(columns_schemas))
+}
+
 // lists files in the frontend to reduce unnecessary scan requests repeated in each datanode.
 async fn prepare_immutable_file_table(
     options: &HashMap<String, String>,
@@ -364,13 +423,15 @@ mod test {
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema, SchemaRef};
     use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt32Vector, VectorRef};
     use snafu::ResultExt;
+    use table::requests::CreateViewRequest;
+    use table::table::view::ViewTable;
     use table::test_util::MemTable;
     use table::TableRef;
 
     use crate::error;
     use crate::error::Result;
     use crate::sql::{
-        describe_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
+        describe_table, show_create_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
         SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_TIME_INDEX,
     };
 
@@ -438,4 +499,39 @@ mod test {
         let record_batch = RecordBatch::new(table_schema, data).unwrap();
         Arc::new(MemTable::new(table_name, record_batch))
     }
+
+    #[test]
+    fn test_show_create_view() {
+        let show_create_view = |view_name: &str| {
+            let request = CreateViewRequest {
+                id: 1024,
+                catalog_name: "greptime".to_string(),
+                schema_name: "public".to_string(),
+                view_name: view_name.to_string(),
+                schema: Arc::new(Schema::new(vec![ColumnSchema::new(
+                    "host",
+                    ConcreteDataType::string_datatype(),
+                    true,
+                )])),
+                definition: "SELECT host FROM monitor".to_string(),
+                create_if_not_exists: false,
+            };
+            let view: TableRef = Arc::new(ViewTable::new(request));
+            let Output::RecordBatches(batches) = show_create_table(view, None).unwrap() else {
+                unreachable!()
+            };
+            let batches = batches.take();
+            let sql = batches[0].column(1).get_ref(0);
+            sql.as_string().unwrap().unwrap().to_string()
+        };
+
+        assert_eq!(
+            "CREATE VIEW IF NOT EXISTS host_view AS SELECT host FROM monitor",
+            show_create_view("host_view")
+        );
+        assert_eq!(
+            "CREATE VIEW IF NOT EXISTS \"Host View\" AS SELECT host FROM monitor",
+            show_create_view("Host View")
+        );
+    }
 }
@@ -0,0 +1,192 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Collects the [ExecutionStats] of queries.
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use common_query::physical_plan::{PhysicalPlan, PhysicalPlanRef, BYTES_SCANNED};
+use common_recordbatch::error::Result as RecordBatchResult;
+use common_recordbatch::{
+    ExecutionStats, RecordBatch, RecordBatchStream, SendableRecordBatchStream,
+};
+use datafusion::error::Result as DfResult;
+use datafusion::execution::memory_pool::{MemoryPool, MemoryReservation};
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use futures_util::StreamExt;
+
+/// [MemoryPool] of a single query, tracks the peak memory reserved by the query and passes
+/// the reservations on to the pool shared by all queries.
+#[derive(Debug)]
+pub(crate) struct QueryMemoryPool {
+    inner: Arc<dyn MemoryPool>,
+    reserved: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl QueryMemoryPool {
+    pub(crate) fn new(inner: Arc<dyn MemoryPool>) -> Self {
+        Self {
+            inner,
+            reserved: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Peak memory reserved by the query, in bytes.
+    pub(crate) fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    fn add(&self, additional: usize) {
+        let reserved = self.reserved.fetch_add(additional, Ordering::Relaxed) + additional;
+        let _ = self.peak.fetch_max(reserved, Ordering::Relaxed);
+    }
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional);
+        self.add(additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink);
+        let _ = self.reserved.fetch_sub(shrink, Ordering::Relaxed);
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> DfResult<()> {
+        self.inner.try_grow(reservation, additional)?;
+        self.add(additional);
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.reserved.load(Ordering::Relaxed)
+    }
+}
+
+/// Yields the record batches of a query, and summarizes the [ExecutionStats] of the query
+/// once they are all yielded.
+pub(crate) struct StatsRecordBatchStream {
+    stream: SendableRecordBatchStream,
+    plan: PhysicalPlanRef,
+    memory_pool: Arc<QueryMemoryPool>,
+    start: Instant,
+    stats: Option<ExecutionStats>,
+}
+
+impl StatsRecordBatchStream {
+    pub(crate) fn new(
+        stream: SendableRecordBatchStream,
+        plan: PhysicalPlanRef,
+        memory_pool: Arc<QueryMemoryPool>,
+        start: Instant,
+    ) -> Self {
+        Self {
+            stream,
+            plan,
+            memory_pool,
+            start,
+            stats: None,
+        }
+    }
+
+    fn collect_stats(&self) -> ExecutionStats {
+        let mut stats = ExecutionStats {
+            elapsed_ms: self.start.elapsed().as_millis() as u64,
+            peak_memory_bytes: self.memory_pool.peak() as u64,
+            ..Default::default()
+        };
+        collect_scan_stats(self.plan.as_ref(), &mut stats);
+        stats
+    }
+}
+
+/// Sums up the rows and bytes read by the leaves of the `plan`, which are the table scans.
+fn collect_scan_stats(plan: &dyn PhysicalPlan, stats: &mut ExecutionStats) {
+    let children = plan.children();
+    if children.is_empty() {
+        if let Some(metrics) = plan.metrics() {
+            stats.rows_scanned += metrics.output_rows().unwrap_or(0) as u64;
+            stats.bytes_read += metrics
+                .sum_by_name(BYTES_SCANNED)
+                .map(|bytes| bytes.as_usize())
+                .unwrap_or(0) as u64;
+        }
+        return;
+    }
+    for child in children {
+        collect_scan_stats(child.as_ref(), stats);
+    }
+}
+
+impl RecordBatchStream for StatsRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn stats(&self) -> Option<ExecutionStats> {
+        self.stats
+    }
+}
+
+impl Stream for StatsRecordBatchStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.stream.poll_next_unpin(cx);
+        if let Poll::Ready(None) = poll {
+            if self.stats.is_none() {
+                self.stats = Some(self.collect_stats());
+            }
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::execution::memory_pool::{MemoryConsumer, UnboundedMemoryPool};
+
+    use super::*;
+
+    #[test]
+    fn test_query_memory_pool_peak() {
+        let shared: Arc<dyn MemoryPool> = Arc::new(UnboundedMemoryPool::default());
+        let pool = Arc::new(QueryMemoryPool::new(shared.clone()));
+        let query_pool: Arc<dyn MemoryPool> = pool.clone();
+
+        let mut reservation = MemoryConsumer::new("test").register(&query_pool);
+        reservation.grow(100);
+        reservation.try_grow(50).unwrap();
+        reservation.shrink(120);
+        reservation.grow(10);
+
+        assert_eq!(40, pool.reserved());
+        assert_eq!(40, shared.reserved());
+        drop(reservation);
+        assert_eq!(0, pool.reserved());
+        assert_eq!(150, pool.peak());
+    }
+}
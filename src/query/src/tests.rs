@@ -21,6 +21,7 @@ use crate::QueryEngineRef;
 
 mod argmax_test;
 mod argmin_test;
+mod mad_over_time_test;
 mod mean_test;
 mod my_sum_udaf_example;
 mod percentile_test;
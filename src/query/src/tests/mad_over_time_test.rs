@@ -0,0 +1,76 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datatypes::for_all_primitive_types;
+use datatypes::prelude::*;
+use datatypes::types::WrapperType;
+use datatypes::value::OrderedFloat;
+use num_traits::AsPrimitive;
+
+use crate::error::Result;
+use crate::tests::{exec_selection, function};
+use crate::QueryEngine;
+
+#[tokio::test]
+async fn test_mad_over_time_aggregator() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+    let engine = function::create_query_engine();
+
+    macro_rules! test_mad_over_time {
+        ([], $( { $T:ty } ),*) => {
+            $(
+                let column_name = format!("{}_number", std::any::type_name::<$T>());
+                test_mad_over_time_success::<$T>(&column_name, "numbers", engine.clone()).await?;
+            )*
+        }
+    }
+    for_all_primitive_types! { test_mad_over_time }
+    Ok(())
+}
+
+async fn test_mad_over_time_success<T>(
+    column_name: &str,
+    table_name: &str,
+    engine: Arc<dyn QueryEngine>,
+) -> Result<()>
+where
+    T: WrapperType + AsPrimitive<f64>,
+{
+    let sql = format!("select MAD_OVER_TIME({column_name}) as mad from {table_name}");
+    let result = exec_selection(engine.clone(), &sql).await;
+    let value = function::get_value_from_batches("mad", result);
+
+    let numbers =
+        function::get_numbers_from_table::<T>(column_name, table_name, engine.clone()).await;
+    let numbers = numbers.iter().map(|&n| n.as_()).collect::<Vec<f64>>();
+    let median = |mut values: Vec<f64>| {
+        values.sort_by(f64::total_cmp);
+        let mid = values.len() / 2;
+        if values.len() % 2 == 0 {
+            (values[mid - 1] + values[mid]) / 2.0
+        } else {
+            values[mid]
+        }
+    };
+    let numbers_median = median(numbers.clone());
+    let expected = median(numbers.iter().map(|n| (n - numbers_median).abs()).collect());
+    let Value::Float64(OrderedFloat(value)) = value else { unreachable!() };
+    assert!(
+        (value - expected).abs() < 1e-3,
+        "expected {expected}, actual {value}"
+    );
+    Ok(())
+}
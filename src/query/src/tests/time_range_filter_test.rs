@@ -133,7 +133,12 @@ impl TimeRangeTester {
         let _ = exec_selection(self.engine.clone(), sql).await;
         let filters = self.table.get_filters().await;
 
-        let range = TimeRangePredicateBuilder::new("ts", &filters).build();
+        let ts_col = ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        );
+        let range = TimeRangePredicateBuilder::new(&ts_col, &filters).build();
         assert_eq!(expect, range);
     }
 }
@@ -0,0 +1,169 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client of MySQL.
+
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use common_recordbatch::SendableRecordBatchStream;
+use datatypes::schema::SchemaRef;
+use mysql_async::prelude::Queryable;
+use mysql_async::{Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts, Row, Value};
+use snafu::{OptionExt, ResultExt};
+use table::requests::REMOTE_TABLE_POOL_SIZE_KEY;
+
+use crate::client::{batch_stream, BatchBuilder, Datum, RemoteClient};
+use crate::error::{self, Result};
+
+/// Client of MySQL, connections are managed by the pool of the driver.
+pub(crate) struct MySqlClient {
+    pool: Pool,
+    pool_size: usize,
+}
+
+impl fmt::Debug for MySqlClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't print the pool as its options contain the password.
+        f.debug_struct("MySqlClient")
+            .field("pool_size", &self.pool_size)
+            .finish()
+    }
+}
+
+impl MySqlClient {
+    pub(crate) fn try_new(url: &str, pool_size: usize) -> Result<MySqlClient> {
+        let opts = Opts::from_url(url).context(error::ParseMySqlUrlSnafu)?;
+        let constraints =
+            PoolConstraints::new(0, pool_size).with_context(|| error::InvalidTableOptionSnafu {
+                key: REMOTE_TABLE_POOL_SIZE_KEY,
+                value: pool_size.to_string(),
+            })?;
+        let opts = OptsBuilder::from_opts(opts)
+            .pool_opts(PoolOpts::default().with_constraints(constraints));
+
+        Ok(MySqlClient {
+            pool: Pool::new(opts),
+            pool_size,
+        })
+    }
+}
+
+#[async_trait]
+impl RemoteClient for MySqlClient {
+    async fn query(&self, sql: &str, schema: &SchemaRef) -> Result<SendableRecordBatchStream> {
+        let mut conn = self
+            .pool
+            .get_conn()
+            .await
+            .context(error::QueryMySqlSnafu { sql })?;
+        let sql = sql.to_string();
+        let rows = async_stream::try_stream! {
+            // Uses the binary protocol so values are typed. The connection is held until all
+            // rows are read, and returned to the pool once dropped.
+            let mut result = conn
+                .exec_iter(sql.as_str(), ())
+                .await
+                .context(error::QueryMySqlSnafu { sql: &sql })?;
+            while let Some(row) = result
+                .next()
+                .await
+                .context(error::QueryMySqlSnafu { sql: &sql })?
+            {
+                yield row;
+            }
+        };
+        Ok(batch_stream(schema.clone(), rows, push_row))
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.pool
+            .clone()
+            .disconnect()
+            .await
+            .context(error::DisconnectMySqlSnafu)
+    }
+}
+
+fn push_row(row: &Row, builder: &mut BatchBuilder) -> Result<()> {
+    let schema = builder.schema().clone();
+    for (idx, column_schema) in schema.column_schemas().iter().enumerate() {
+        let datum = match row.as_ref(idx) {
+            Some(value) => {
+                value_to_datum(value).with_context(|| error::UnsupportedRemoteTypeSnafu {
+                    column: &column_schema.name,
+                    remote_type: format!("{value:?}"),
+                })?
+            }
+            None => Datum::Null,
+        };
+        builder.push(idx, &datum)?;
+    }
+    Ok(())
+}
+
+/// Converts the MySQL `value` to [Datum], returns `None` if the value is not supported.
+fn value_to_datum(value: &Value) -> Option<Datum> {
+    let datum = match value {
+        Value::NULL => Datum::Null,
+        Value::Bytes(v) => Datum::Bytes(v.clone()),
+        Value::Int(v) => Datum::Int(*v),
+        Value::UInt(v) => Datum::UInt(*v),
+        Value::Float(v) => Datum::Float((*v).into()),
+        Value::Double(v) => Datum::Float(*v),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            // MySQL allows zero dates like `0000-00-00`, which are treated as null.
+            NaiveDate::from_ymd_opt((*year).into(), (*month).into(), (*day).into())
+                .and_then(|date| {
+                    date.and_hms_micro_opt(
+                        (*hour).into(),
+                        (*minute).into(),
+                        (*second).into(),
+                        *micros,
+                    )
+                })
+                .map(|datetime| Datum::Timestamp(datetime.timestamp_micros()))
+                .unwrap_or(Datum::Null)
+        }
+        Value::Time(..) => return None,
+    };
+    Some(datum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_to_datum() {
+        assert_eq!(Some(Datum::Null), value_to_datum(&Value::NULL));
+        assert_eq!(Some(Datum::Int(-3)), value_to_datum(&Value::Int(-3)));
+        assert_eq!(Some(Datum::UInt(3)), value_to_datum(&Value::UInt(3)));
+        assert_eq!(Some(Datum::Float(1.5)), value_to_datum(&Value::Float(1.5)));
+        assert_eq!(
+            Some(Datum::Bytes(b"abc".to_vec())),
+            value_to_datum(&Value::Bytes(b"abc".to_vec()))
+        );
+        assert_eq!(
+            Some(Datum::Timestamp(86_400_000_001)),
+            value_to_datum(&Value::Date(1970, 1, 2, 0, 0, 0, 1))
+        );
+        assert_eq!(
+            Some(Datum::Null),
+            value_to_datum(&Value::Date(0, 0, 0, 0, 0, 0, 0))
+        );
+        assert_eq!(None, value_to_datum(&Value::Time(false, 0, 1, 0, 0, 0)));
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client of PostgreSQL.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use common_recordbatch::SendableRecordBatchStream;
+use common_telemetry::warn;
+use datatypes::schema::SchemaRef;
+use futures::StreamExt;
+use snafu::{OptionExt, ResultExt};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_postgres::types::Type;
+use tokio_postgres::{Client, Config, NoTls, Row};
+
+use crate::client::{batch_stream, BatchBuilder, Datum, RemoteClient};
+use crate::error::{self, Result};
+
+/// Client of PostgreSQL with a pool of connections.
+pub(crate) struct PostgresClient {
+    config: Config,
+    /// Idle connections.
+    idle: Arc<Mutex<Vec<Client>>>,
+    /// Limits the number of connections in use.
+    semaphore: Arc<Semaphore>,
+    pool_size: usize,
+}
+
+/// A connection in use, which is put back to the idle connections once dropped, unless
+/// the client is closed.
+struct PooledClient {
+    client: Option<Client>,
+    idle: Arc<Mutex<Vec<Client>>>,
+    semaphore: Arc<Semaphore>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            if !self.semaphore.is_closed() {
+                self.idle.lock().unwrap().push(client);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for PostgresClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't print the config as it contains the password.
+        f.debug_struct("PostgresClient")
+            .field("pool_size", &self.pool_size)
+            .finish()
+    }
+}
+
+impl PostgresClient {
+    pub(crate) fn try_new(url: &str, pool_size: usize) -> Result<PostgresClient> {
+        let config = url.parse().context(error::ParsePostgresUrlSnafu)?;
+        Ok(PostgresClient {
+            config,
+            idle: Arc::new(Mutex::new(Vec::new())),
+            semaphore: Arc::new(Semaphore::new(pool_size)),
+            pool_size,
+        })
+    }
+
+    /// Takes an idle connection from the pool or creates a new connection.
+    async fn get_client(&self) -> Result<Client> {
+        loop {
+            let client = self.idle.lock().unwrap().pop();
+            match client {
+                Some(client) if !client.is_closed() => return Ok(client),
+                Some(_) => continue,
+                None => break,
+            }
+        }
+
+        let (client, connection) = self
+            .config
+            .connect(NoTls)
+            .await
+            .context(error::ConnectPostgresSnafu)?;
+        // The connection performs the actual communication with the database.
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!(
+                    "Connection to the remote PostgreSQL is closed, error: {}",
+                    e
+                );
+            }
+        });
+        Ok(client)
+    }
+}
+
+#[async_trait]
+impl RemoteClient for PostgresClient {
+    async fn query(&self, sql: &str, schema: &SchemaRef) -> Result<SendableRecordBatchStream> {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .ok()
+            .context(error::ClientClosedSnafu)?;
+        let pooled = PooledClient {
+            client: Some(self.get_client().await?),
+            idle: self.idle.clone(),
+            semaphore: self.semaphore.clone(),
+            _permit: permit,
+        };
+        // Unwrap safety: the client is only taken on drop.
+        let rows = pooled
+            .client
+            .as_ref()
+            .unwrap()
+            .query_raw(sql, std::iter::empty::<&str>())
+            .await
+            .context(error::QueryPostgresSnafu { sql })?;
+
+        let sql = sql.to_string();
+        let rows = async_stream::try_stream! {
+            // Holds the connection until all rows are read.
+            let _pooled = pooled;
+            let mut rows = Box::pin(rows);
+            while let Some(row) = rows.next().await {
+                yield row.context(error::QueryPostgresSnafu { sql: &sql })?;
+            }
+        };
+        Ok(batch_stream(schema.clone(), rows, push_row))
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.semaphore.close();
+        // Dropping the clients closes the connections.
+        self.idle.lock().unwrap().clear();
+        Ok(())
+    }
+}
+
+fn push_row(row: &Row, builder: &mut BatchBuilder) -> Result<()> {
+    for idx in 0..builder.schema().num_columns() {
+        builder.push(idx, &read_datum(row, idx)?)?;
+    }
+    Ok(())
+}
+
+/// Reads the value of the column at `idx` from the `row`.
+fn read_datum(row: &Row, idx: usize) -> Result<Datum> {
+    let column = &row.columns()[idx];
+    let ty = column.type_();
+    let context = || error::DecodePostgresRowSnafu {
+        column: column.name(),
+    };
+
+    let datum = if *ty == Type::BOOL {
+        row.try_get::<_, Option<bool>>(idx)
+            .with_context(context)?
+            .map(Datum::Bool)
+    } else if *ty == Type::CHAR {
+        row.try_get::<_, Option<i8>>(idx)
+            .with_context(context)?
+            .map(|v| Datum::Int(v.into()))
+    } else if *ty == Type::INT2 {
+        row.try_get::<_, Option<i16>>(idx)
+            .with_context(context)?
+            .map(|v| Datum::Int(v.into()))
+    } else if *ty == Type::INT4 {
+        row.try_get::<_, Option<i32>>(idx)
+            .with_context(context)?
+            .map(|v| Datum::Int(v.into()))
+    } else if *ty == Type::INT8 {
+        row.try_get::<_, Option<i64>>(idx)
+            .with_context(context)?
+            .map(Datum::Int)
+    } else if *ty == Type::OID {
+        row.try_get::<_, Option<u32>>(idx)
+            .with_context(context)?
+            .map(|v| Datum::UInt(v.into()))
+    } else if *ty == Type::FLOAT4 {
+        row.try_get::<_, Option<f32>>(idx)
+            .with_context(context)?
+            .map(|v| Datum::Float(v.into()))
+    } else if *ty == Type::FLOAT8 {
+        row.try_get::<_, Option<f64>>(idx)
+            .with_context(context)?
+            .map(Datum::Float)
+    } else if *ty == Type::TEXT || *ty == Type::VARCHAR || *ty == Type::BPCHAR || *ty == Type::NAME
+    {
+        row.try_get::<_, Option<String>>(idx)
+            .with_context(context)?
+            .map(Datum::Text)
+    } else if *ty == Type::BYTEA {
+        row.try_get::<_, Option<Vec<u8>>>(idx)
+            .with_context(context)?
+            .map(Datum::Bytes)
+    } else if *ty == Type::TIMESTAMP {
+        row.try_get::<_, Option<NaiveDateTime>>(idx)
+            .with_context(context)?
+            .map(|v| Datum::Timestamp(v.timestamp_micros()))
+    } else if *ty == Type::TIMESTAMPTZ {
+        row.try_get::<_, Option<chrono::DateTime<Utc>>>(idx)
+            .with_context(context)?
+            .map(|v| Datum::Timestamp(v.timestamp_micros()))
+    } else if *ty == Type::DATE {
+        row.try_get::<_, Option<NaiveDate>>(idx)
+            .with_context(context)?
+            .map(|v| {
+                Datum::Timestamp(NaiveDateTime::new(v, NaiveTime::default()).timestamp_micros())
+            })
+    } else {
+        return error::UnsupportedRemoteTypeSnafu {
+            column: column.name(),
+            remote_type: ty.name(),
+        }
+        .fail();
+    };
+
+    Ok(datum.unwrap_or(Datum::Null))
+}
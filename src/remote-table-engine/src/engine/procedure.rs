@@ -0,0 +1,37 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Procedures for immutable file table engine.
+
+mod create;
+mod drop;
+
+use common_procedure::ProcedureManager;
+
+pub(crate) use crate::engine::procedure::create::CreateRemoteTable;
+pub(crate) use crate::engine::procedure::drop::DropRemoteTable;
+use crate::engine::RemoteTableEngine;
+
+/// Register all procedure loaders to the procedure manager.
+///
+/// # Panics
+/// Panics on error.
+pub(crate) fn register_procedure_loaders(
+    engine: RemoteTableEngine,
+    procedure_manager: &dyn ProcedureManager,
+) {
+    // The procedure names are expected to be unique, so we just panic on error.
+    CreateRemoteTable::register_loader(engine.clone(), procedure_manager);
+    DropRemoteTable::register_loader(engine, procedure_manager);
+}
@@ -0,0 +1,270 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use common_error::prelude::*;
+use datatypes::prelude::ConcreteDataType;
+use snafu::Location;
+use table::metadata::{TableInfoBuilderError, TableMetaBuilderError};
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum Error {
+    #[snafu(display("Failed to access table manifest, source: {}", source))]
+    Manifest {
+        #[snafu(backtrace)]
+        source: file_table_engine::error::Error,
+    },
+
+    #[snafu(display("Failed to drop table, table: {}, source: {}", table_name, source))]
+    DropTable {
+        source: BoxedError,
+        table_name: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to build table meta for table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    BuildTableMeta {
+        source: TableMetaBuilderError,
+        table_name: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to build table info for table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    BuildTableInfo {
+        source: TableInfoBuilderError,
+        table_name: String,
+        location: Location,
+    },
+
+    #[snafu(display("Table already exists: {}", table_name))]
+    TableExists {
+        location: Location,
+        table_name: String,
+    },
+
+    #[snafu(display(
+        "Failed to convert metadata from deserialized data, source: {}",
+        source
+    ))]
+    ConvertRaw {
+        #[snafu(backtrace)]
+        source: table::metadata::ConvertError,
+    },
+
+    #[snafu(display("Invalid schema, source: {}", source))]
+    InvalidRawSchema {
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Missing required field: {}", name))]
+    MissingRequiredField { name: String, location: Location },
+
+    #[snafu(display("Invalid table option {}: {}", key, value))]
+    InvalidTableOption {
+        key: String,
+        value: String,
+        location: Location,
+    },
+
+    #[snafu(display("Unsupported remote database url: {}", url))]
+    UnsupportedUrl { url: String, location: Location },
+
+    #[snafu(display("Invalid remote database url, source: {}", source))]
+    ParsePostgresUrl {
+        source: tokio_postgres::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid remote database url, source: {}", source))]
+    ParseMySqlUrl {
+        source: mysql_async::UrlError,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid GreptimeDB url {}, reason: {}", url, reason))]
+    InvalidGreptimeUrl {
+        url: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to connect to the remote database, source: {}", source))]
+    ConnectPostgres {
+        source: tokio_postgres::Error,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to query the remote database, sql: {}, source: {}",
+        sql,
+        source
+    ))]
+    QueryPostgres {
+        sql: String,
+        source: tokio_postgres::Error,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to query the remote database, sql: {}, source: {}",
+        sql,
+        source
+    ))]
+    QueryMySql {
+        sql: String,
+        source: mysql_async::Error,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to query the remote database, sql: {}, source: {}",
+        sql,
+        source
+    ))]
+    QueryGreptime {
+        sql: String,
+        #[snafu(backtrace)]
+        source: client::Error,
+    },
+
+    #[snafu(display("Expect record batches from the remote database, sql: {}", sql))]
+    UnexpectedOutput { sql: String, location: Location },
+
+    #[snafu(display(
+        "Unexpected number of columns from the remote database, expect: {}, actual: {}",
+        expect,
+        actual
+    ))]
+    UnexpectedColumns {
+        expect: usize,
+        actual: usize,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to cast remote column {}, source: {}", column, source))]
+    CastColumn {
+        column: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Failed to disconnect from the remote database, source: {}", source))]
+    DisconnectMySql {
+        source: mysql_async::Error,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to decode column {} from the remote row, source: {}",
+        column,
+        source
+    ))]
+    DecodePostgresRow {
+        column: String,
+        source: tokio_postgres::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Unsupported type {} of remote column {}", remote_type, column))]
+    UnsupportedRemoteType {
+        column: String,
+        remote_type: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to convert remote value {} of column {} to {:?}",
+        value,
+        column,
+        data_type
+    ))]
+    ConvertValue {
+        column: String,
+        value: String,
+        data_type: ConcreteDataType,
+        location: Location,
+    },
+
+    #[snafu(display("Client of the remote database has been closed"))]
+    ClientClosed { location: Location },
+
+    #[snafu(display("Failed to create record batch, source: {}", source))]
+    CreateRecordBatch {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl ErrorExt for Error {
+    fn status_code(&self) -> StatusCode {
+        use Error::*;
+
+        match self {
+            TableExists { .. } => StatusCode::TableAlreadyExists,
+
+            BuildTableMeta { .. }
+            | BuildTableInfo { .. }
+            | InvalidRawSchema { .. }
+            | MissingRequiredField { .. }
+            | InvalidTableOption { .. }
+            | UnsupportedUrl { .. }
+            | ParsePostgresUrl { .. }
+            | ParseMySqlUrl { .. }
+            | InvalidGreptimeUrl { .. }
+            | CastColumn { .. }
+            | UnsupportedRemoteType { .. }
+            | ConvertValue { .. } => StatusCode::InvalidArguments,
+
+            Manifest { source } => source.status_code(),
+            CreateRecordBatch { source } => source.status_code(),
+
+            ConnectPostgres { .. }
+            | QueryPostgres { .. }
+            | QueryMySql { .. }
+            | DisconnectMySql { .. }
+            | DecodePostgresRow { .. }
+            | UnexpectedOutput { .. }
+            | UnexpectedColumns { .. } => StatusCode::Internal,
+
+            QueryGreptime { source, .. } => source.status_code(),
+
+            ClientClosed { .. } => StatusCode::TableNotFound,
+
+            ConvertRaw { .. } | DropTable { .. } => StatusCode::Unexpected,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl From<Error> for common_procedure::Error {
+    fn from(e: Error) -> common_procedure::Error {
+        common_procedure::Error::from_error_ext(e)
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Translates simple filters to SQL predicates of the remote database.
+
+use datafusion_common::ScalarValue;
+use datafusion_expr::{BinaryExpr, Expr as DfExpr, Operator};
+
+use crate::client::Dialect;
+
+/// Converts the `expr` to a predicate of the remote database, returns `None` if
+/// the expr can't be pushed down.
+///
+/// Only comparisons between columns and literals, `IS [NOT] NULL` and their
+/// combinations via `AND`, `OR` and `NOT` are supported.
+pub(crate) fn filter_to_sql(dialect: Dialect, expr: &DfExpr) -> Option<String> {
+    match expr {
+        DfExpr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            let op_str = match op {
+                Operator::And | Operator::Or => {
+                    let left = filter_to_sql(dialect, left)?;
+                    let right = filter_to_sql(dialect, right)?;
+                    return Some(format!("({left} {op} {right})"));
+                }
+                Operator::Eq => "=",
+                Operator::NotEq => "<>",
+                Operator::Lt => "<",
+                Operator::LtEq => "<=",
+                Operator::Gt => ">",
+                Operator::GtEq => ">=",
+                _ => return None,
+            };
+            let left = operand_to_sql(dialect, left)?;
+            let right = operand_to_sql(dialect, right)?;
+            Some(format!("{left} {op_str} {right}"))
+        }
+        DfExpr::Not(expr) => Some(format!("(NOT {})", filter_to_sql(dialect, expr)?)),
+        DfExpr::IsNull(expr) => Some(format!("{} IS NULL", operand_to_sql(dialect, expr)?)),
+        DfExpr::IsNotNull(expr) => Some(format!("{} IS NOT NULL", operand_to_sql(dialect, expr)?)),
+        _ => None,
+    }
+}
+
+fn operand_to_sql(dialect: Dialect, expr: &DfExpr) -> Option<String> {
+    match expr {
+        DfExpr::Column(column) => Some(dialect.quote_identifier(&column.name)),
+        DfExpr::Literal(value) => literal_to_sql(value),
+        _ => None,
+    }
+}
+
+fn literal_to_sql(value: &ScalarValue) -> Option<String> {
+    let sql = match value {
+        ScalarValue::Boolean(Some(v)) => if *v { "TRUE" } else { "FALSE" }.to_string(),
+        ScalarValue::Int8(Some(v)) => v.to_string(),
+        ScalarValue::Int16(Some(v)) => v.to_string(),
+        ScalarValue::Int32(Some(v)) => v.to_string(),
+        ScalarValue::Int64(Some(v)) => v.to_string(),
+        ScalarValue::UInt8(Some(v)) => v.to_string(),
+        ScalarValue::UInt16(Some(v)) => v.to_string(),
+        ScalarValue::UInt32(Some(v)) => v.to_string(),
+        ScalarValue::UInt64(Some(v)) => v.to_string(),
+        ScalarValue::Float32(Some(v)) if v.is_finite() => v.to_string(),
+        ScalarValue::Float64(Some(v)) if v.is_finite() => v.to_string(),
+        // Backslashes are escape characters in MySQL by default, we don't push down
+        // strings containing them to avoid differences between databases.
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v))
+            if !v.contains(['\\', '\0']) =>
+        {
+            format!("'{}'", v.replace('\'', "''"))
+        }
+        _ => return None,
+    };
+    Some(sql)
+}
+
+/// Builds the query to select `columns` from the remote table.
+pub(crate) fn build_select(
+    dialect: Dialect,
+    table_name: &str,
+    columns: &[&str],
+    predicates: &[String],
+    limit: Option<usize>,
+) -> String {
+    let columns = columns
+        .iter()
+        .map(|column| dialect.quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!(
+        "SELECT {} FROM {}",
+        columns,
+        dialect.quote_table_name(table_name)
+    );
+    if !predicates.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&predicates.join(" AND "));
+    }
+    if let Some(limit) = limit {
+        sql.push_str(&format!(" LIMIT {limit}"));
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_expr::{col, lit};
+
+    use super::*;
+
+    #[test]
+    fn test_filter_to_sql() {
+        let expr = col("host").eq(lit("a'b")).and(col("id").gt_eq(lit(10i64)));
+        assert_eq!(
+            r#"("host" = 'a''b' AND "id" >= 10)"#,
+            filter_to_sql(Dialect::Postgres, &expr).unwrap()
+        );
+
+        let expr = col("enabled")
+            .is_null()
+            .or(datafusion_expr::not(col("enabled").eq(lit(true))));
+        assert_eq!(
+            "(`enabled` IS NULL OR (NOT `enabled` = TRUE))",
+            filter_to_sql(Dialect::MySql, &expr).unwrap()
+        );
+
+        assert_eq!(
+            r#""v" <> 1.5"#,
+            filter_to_sql(Dialect::Postgres, &col("v").not_eq(lit(1.5f64))).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_filter_not_pushed_down() {
+        // Functions are not supported.
+        let expr = datafusion_expr::abs(col("v")).gt(lit(1i64));
+        assert!(filter_to_sql(Dialect::Postgres, &expr).is_none());
+        // Part of the conjunction is not supported.
+        let expr = col("id")
+            .eq(lit(1i64))
+            .and(col("v").eq(col("w") + lit(1i64)));
+        assert!(filter_to_sql(Dialect::Postgres, &expr).is_none());
+        // Strings with backslashes.
+        let expr = col("host").eq(lit("a\\"));
+        assert!(filter_to_sql(Dialect::MySql, &expr).is_none());
+        // Null literals.
+        let expr = col("host").eq(lit(ScalarValue::Utf8(None)));
+        assert!(filter_to_sql(Dialect::MySql, &expr).is_none());
+    }
+
+    #[test]
+    fn test_build_select() {
+        assert_eq!(
+            r#"SELECT "id", "name" FROM "public"."dim""#,
+            build_select(Dialect::Postgres, "public.dim", &["id", "name"], &[], None)
+        );
+        assert_eq!(
+            "SELECT `id` FROM `dim` WHERE `id` > 1 AND `id` < 10 LIMIT 5",
+            build_select(
+                Dialect::MySql,
+                "dim",
+                &["id"],
+                &["`id` > 1".to_string(), "`id` < 10".to_string()],
+                Some(5)
+            )
+        );
+    }
+}
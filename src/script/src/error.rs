@@ -50,6 +50,7 @@ pub enum Error {
         source: table::error::Error,
     },
 
+    #[cfg(feature = "python")]
     #[snafu(display("Failed to compile python script, name: {}, source: {}", name, source))]
     CompilePython {
         name: String,
@@ -57,6 +58,7 @@ pub enum Error {
         source: crate::python::error::Error,
     },
 
+    #[cfg(feature = "python")]
     #[snafu(display("Failed to execute python script {}, source: {}", name, source))]
     ExecutePython {
         name: String,
@@ -82,6 +84,37 @@ pub enum Error {
 
     #[snafu(display("Failed to cast type, msg: {}", msg))]
     CastType { msg: String, location: Location },
+
+    #[snafu(display("Script engine {} is not enabled in this build", engine))]
+    EngineNotEnabled { engine: String, location: Location },
+
+    #[cfg(feature = "wasm")]
+    #[snafu(display("Failed to compile WASM function {}, reason: {}", name, reason))]
+    CompileWasm {
+        name: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[cfg(feature = "wasm")]
+    #[snafu(display("Failed to execute WASM function {}, reason: {}", name, reason))]
+    ExecuteWasm {
+        name: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[cfg(feature = "wasm")]
+    #[snafu(display(
+        "Failed to decode WASM module of function {}, source: {}",
+        name,
+        source
+    ))]
+    DecodeWasm {
+        name: String,
+        source: base64::DecodeError,
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -94,10 +127,18 @@ impl ErrorExt for Error {
             ScriptsTableNotFound { .. } => StatusCode::TableNotFound,
             RegisterScriptsTable { source } | FindScriptsTable { source } => source.status_code(),
             InsertScript { source, .. } => source.status_code(),
+            #[cfg(feature = "python")]
             CompilePython { source, .. } | ExecutePython { source, .. } => source.status_code(),
             FindScript { source, .. } => source.status_code(),
             CollectRecords { source } => source.status_code(),
             ScriptNotFound { .. } => StatusCode::InvalidArguments,
+            EngineNotEnabled { .. } => StatusCode::Unsupported,
+            #[cfg(feature = "wasm")]
+            CompileWasm { .. } => StatusCode::InvalidArguments,
+            #[cfg(feature = "wasm")]
+            ExecuteWasm { .. } => StatusCode::EngineExecuteQuery,
+            #[cfg(feature = "wasm")]
+            DecodeWasm { .. } => StatusCode::Unexpected,
         }
     }
 
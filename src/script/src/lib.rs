@@ -17,8 +17,13 @@
 
 pub mod engine;
 pub mod error;
-#[cfg(feature = "python")]
+#[cfg(any(feature = "python", feature = "wasm"))]
 pub mod manager;
 #[cfg(feature = "python")]
 pub mod python;
+#[cfg(any(feature = "python", feature = "wasm"))]
 pub mod table;
+#[cfg(any(feature = "python", feature = "wasm"))]
+mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -14,22 +14,39 @@
 
 //! Scripts manager
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
+#[cfg(feature = "python")]
+use std::sync::RwLock;
 
 use catalog::CatalogManagerRef;
 use common_query::Output;
 use common_telemetry::logging;
 use query::QueryEngineRef;
+#[cfg(feature = "python")]
 use snafu::{OptionExt, ResultExt};
 
+#[cfg(feature = "python")]
 use crate::engine::{CompileContext, EvalContext, Script, ScriptEngine};
-use crate::error::{CompilePythonSnafu, ExecutePythonSnafu, Result, ScriptNotFoundSnafu};
+#[cfg(not(all(feature = "python", feature = "wasm")))]
+use crate::error::EngineNotEnabledSnafu;
+use crate::error::Result;
+#[cfg(feature = "python")]
+use crate::error::{CompilePythonSnafu, ExecutePythonSnafu, ScriptNotFoundSnafu};
+#[cfg(feature = "python")]
 use crate::python::{PyEngine, PyScript};
 use crate::table::ScriptsTable;
+#[cfg(feature = "wasm")]
+use crate::wasm::{encode_module, WasmFunction, WASM_ENGINE};
 
+/// Manages the scripts and WASM functions saved in scripts table, the methods of an
+/// engine not enabled in this build return [EngineNotEnabled](crate::error::Error::EngineNotEnabled).
 pub struct ScriptManager {
+    #[cfg(feature = "python")]
     compiled: RwLock<HashMap<String, Arc<PyScript>>>,
+    #[cfg(feature = "python")]
     py_engine: PyEngine,
+    #[cfg(feature = "wasm")]
+    query_engine: QueryEngineRef,
     table: ScriptsTable,
 }
 
@@ -39,13 +56,18 @@ impl ScriptManager {
         query_engine: QueryEngineRef,
     ) -> Result<Self> {
         Ok(Self {
+            #[cfg(feature = "python")]
             compiled: RwLock::new(HashMap::default()),
+            #[cfg(feature = "python")]
             py_engine: PyEngine::new(query_engine.clone()),
+            #[cfg(feature = "wasm")]
+            query_engine: query_engine.clone(),
             table: ScriptsTable::new(catalog_manager, query_engine).await?,
         })
     }
 
     /// compile script, and register them to the query engine and UDF registry
+    #[cfg(feature = "python")]
     async fn compile(&self, name: &str, script: &str) -> Result<Arc<PyScript>> {
         let script = Arc::new(Self::compile_without_cache(&self.py_engine, name, script).await?);
 
@@ -63,6 +85,7 @@ impl ScriptManager {
     }
 
     /// compile script to PyScript, but not register them to the query engine and UDF registry nor caching in `compiled`
+    #[cfg(feature = "python")]
     async fn compile_without_cache(
         py_engine: &PyEngine,
         name: &str,
@@ -74,6 +97,7 @@ impl ScriptManager {
             .context(CompilePythonSnafu { name })
     }
 
+    #[cfg(feature = "python")]
     pub async fn insert_and_compile(
         &self,
         schema: &str,
@@ -81,10 +105,51 @@ impl ScriptManager {
         script: &str,
     ) -> Result<Arc<PyScript>> {
         let compiled_script = self.compile(name, script).await?;
-        self.table.insert(schema, name, script).await?;
+        self.table
+            .insert(schema, name, script, self.py_engine.name())
+            .await?;
         Ok(compiled_script)
     }
 
+    #[cfg(not(feature = "python"))]
+    pub async fn insert_and_compile(
+        &self,
+        _schema: &str,
+        _name: &str,
+        _script: &str,
+    ) -> Result<()> {
+        EngineNotEnabledSnafu { engine: "python" }.fail()
+    }
+
+    /// Compile the WASM module, register the function it exports to the query engine and
+    /// save the module in scripts table, so it could be registered again on restart.
+    #[cfg(feature = "wasm")]
+    pub async fn insert_and_register_wasm(
+        &self,
+        schema: &str,
+        name: &str,
+        module: &[u8],
+    ) -> Result<()> {
+        let func = WasmFunction::try_new(name, module)?;
+        self.query_engine.register_function(Arc::new(func));
+        logging::info!("WASM function register as UDF: {}", name);
+
+        self.table
+            .insert(schema, name, &encode_module(module), WASM_ENGINE)
+            .await
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    pub async fn insert_and_register_wasm(
+        &self,
+        _schema: &str,
+        _name: &str,
+        _module: &[u8],
+    ) -> Result<()> {
+        EngineNotEnabledSnafu { engine: "wasm" }.fail()
+    }
+
+    #[cfg(feature = "python")]
     pub async fn execute(
         &self,
         schema: &str,
@@ -109,6 +174,17 @@ impl ScriptManager {
             .context(ExecutePythonSnafu { name })
     }
 
+    #[cfg(not(feature = "python"))]
+    pub async fn execute(
+        &self,
+        _schema: &str,
+        _name: &str,
+        _params: HashMap<String, String>,
+    ) -> Result<Output> {
+        EngineNotEnabledSnafu { engine: "python" }.fail()
+    }
+
+    #[cfg(feature = "python")]
     async fn try_find_script_and_compile(
         &self,
         schema: &str,
@@ -120,7 +196,7 @@ impl ScriptManager {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "python", feature = "wasm"))]
 mod tests {
     use catalog::CatalogManager;
     use mito::config::EngineConfig as TableEngineConfig;
@@ -188,6 +264,7 @@ mod tests {
 def test(n):
     return n + 1;
 "#,
+                "python",
             )
             .await
             .unwrap();
@@ -205,5 +282,26 @@ def test(n):
             let cached = mgr.compiled.read().unwrap();
             assert!(cached.get(name).is_some());
         }
+
+        let module = r#"
+(module
+  (func (export "add_one") (param i64) (result i64)
+    local.get 0
+    i64.const 1
+    i64.add))
+"#;
+        mgr.insert_and_register_wasm(schema, "add_one", module.as_bytes())
+            .await
+            .unwrap();
+        let script = mgr
+            .table
+            .find_script_by_name(schema, "add_one")
+            .await
+            .unwrap();
+        assert_eq!(encode_module(module.as_bytes()), script);
+        assert!(mgr
+            .insert_and_register_wasm(schema, "add_two", module.as_bytes())
+            .await
+            .is_err());
     }
 }
@@ -13,11 +13,11 @@
 // limitations under the License.
 
 use common_runtime::JoinHandle;
-use futures::Future;
 use rustpython_vm::builtins::PyBaseExceptionRef;
 use rustpython_vm::VirtualMachine;
 
 use crate::python::error;
+pub use crate::utils::block_on_async;
 
 pub fn format_py_error(excep: PyBaseExceptionRef, vm: &VirtualMachine) -> error::Error {
     let mut msg = String::new();
@@ -38,20 +38,3 @@ where
 {
     common_runtime::spawn_blocking_bg(f)
 }
-
-/// Please only use this method because you are calling from (optionally first as async) to sync then to a async
-/// a terrible hack to call async from sync by:
-///
-/// TODO(discord9): find a better way
-/// 1. using a cached runtime
-/// 2. block on that runtime
-pub fn block_on_async<T, F>(f: F) -> std::thread::Result<T>
-where
-    F: Future<Output = T> + Send + 'static,
-    T: Send + 'static,
-{
-    let rt = common_runtime::bg_runtime();
-    // spawn a thread to block on the runtime, also should prevent `start a runtime inside of runtime` error
-    // it's ok to block here, assume calling from async to sync is using a `spawn_blocking_*` call
-    std::thread::spawn(move || rt.block_on(f)).join()
-}
@@ -43,8 +43,11 @@ use crate::error::{
     FindScriptsTableSnafu, InsertScriptSnafu, RegisterScriptsTableSnafu, Result,
     ScriptNotFoundSnafu, ScriptsTableNotFoundSnafu,
 };
-use crate::python::utils::block_on_async;
+#[cfg(feature = "python")]
 use crate::python::PyScript;
+use crate::utils::block_on_async;
+#[cfg(feature = "wasm")]
+use crate::wasm::{decode_module, WasmFunction, WASM_ENGINE};
 
 pub const SCRIPTS_TABLE_NAME: &str = "scripts";
 
@@ -93,7 +96,7 @@ impl ScriptsTable {
             .map_err(BoxedError::new)
             .context(CompileScriptInternalSnafu)?;
 
-        let mut script_list: Vec<(String, String)> = Vec::new();
+        let mut script_list: Vec<(String, String, String)> = Vec::new();
         for record in records {
             let names = Self::get_str_col_by_name(&record, "name")
                 .map_err(BoxedError::new)
@@ -101,38 +104,81 @@ impl ScriptsTable {
             let scripts = Self::get_str_col_by_name(&record, "script")
                 .map_err(BoxedError::new)
                 .context(CompileScriptInternalSnafu)?;
+            let engines = Self::get_str_col_by_name(&record, "engine")
+                .map_err(BoxedError::new)
+                .context(CompileScriptInternalSnafu)?;
 
-            let part_of_scripts_list =
-                names
-                    .iter_data()
-                    .zip(scripts.iter_data())
-                    .filter_map(|i| match i {
-                        (Some(a), Some(b)) => Some((a.to_string(), b.to_string())),
-                        _ => None,
-                    });
+            let part_of_scripts_list = names
+                .iter_data()
+                .zip(scripts.iter_data())
+                .zip(engines.iter_data())
+                .filter_map(|i| match i {
+                    ((Some(a), Some(b)), Some(c)) => {
+                        Some((a.to_string(), b.to_string(), c.to_string()))
+                    }
+                    _ => None,
+                });
             script_list.extend(part_of_scripts_list);
         }
 
-        for (name, script) in script_list {
-            match PyScript::from_script(&script, query_engine.clone()) {
-                Ok(script) => {
-                    script.register_udf().await;
-                    logging::debug!(
-                        "Script in `scripts` system table re-register as UDF: {}",
-                        name
-                    );
-                }
-                Err(err) => {
-                    logging::warn!(
-                        r#"Failed to compile script "{}"" in `scripts` table: {}"#,
-                        name,
-                        err
-                    );
-                }
+        for (name, script, engine) in script_list {
+            #[cfg(feature = "wasm")]
+            if engine == WASM_ENGINE {
+                Self::register_wasm_udf(&name, &script, &query_engine);
+                continue;
             }
+
+            #[cfg(feature = "python")]
+            Self::register_python_udf(&name, &script, &query_engine).await;
+            #[cfg(not(feature = "python"))]
+            logging::warn!(
+                r#"Script "{}" in `scripts` table requires engine {}, which is not enabled"#,
+                name,
+                engine
+            );
         }
         Ok(())
     }
+
+    #[cfg(feature = "wasm")]
+    fn register_wasm_udf(name: &str, script: &str, query_engine: &QueryEngineRef) {
+        match decode_module(name, script).and_then(|module| WasmFunction::try_new(name, &module)) {
+            Ok(func) => {
+                query_engine.register_function(Arc::new(func));
+                logging::debug!(
+                    "WASM function in `scripts` system table re-register as UDF: {}",
+                    name
+                );
+            }
+            Err(err) => {
+                logging::warn!(
+                    r#"Failed to compile WASM function "{}" in `scripts` table: {}"#,
+                    name,
+                    err
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "python")]
+    async fn register_python_udf(name: &str, script: &str, query_engine: &QueryEngineRef) {
+        match PyScript::from_script(script, query_engine.clone()) {
+            Ok(script) => {
+                script.register_udf().await;
+                logging::debug!(
+                    "Script in `scripts` system table re-register as UDF: {}",
+                    name
+                );
+            }
+            Err(err) => {
+                logging::warn!(
+                    r#"Failed to compile script "{}"" in `scripts` table: {}"#,
+                    name,
+                    err
+                );
+            }
+        }
+    }
     pub async fn new(
         catalog_manager: CatalogManagerRef,
         query_engine: QueryEngineRef,
@@ -182,7 +228,7 @@ impl ScriptsTable {
         })
     }
 
-    pub async fn insert(&self, schema: &str, name: &str, script: &str) -> Result<()> {
+    pub async fn insert(&self, schema: &str, name: &str, script: &str, engine: &str) -> Result<()> {
         let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(8);
         columns_values.insert(
             "schema".to_string(),
@@ -196,10 +242,9 @@ impl ScriptsTable {
             "script".to_string(),
             Arc::new(StringVector::from(vec![script])) as _,
         );
-        // TODO(dennis): we only supports python right now.
         columns_values.insert(
             "engine".to_string(),
-            Arc::new(StringVector::from(vec!["python"])) as _,
+            Arc::new(StringVector::from(vec![engine])) as _,
         );
         // Timestamp in key part is intentionally left to 0
         columns_values.insert(
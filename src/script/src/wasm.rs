@@ -0,0 +1,346 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scalar functions compiled to WebAssembly
+//!
+//! A WASM function is a module exporting a function of the same name, whose params and
+//! result are numbers (`i32`, `i64`, `f32` or `f64`). Modules are not allowed to import
+//! anything from the host, and every batch is evaluated in a fresh instance with bounded
+//! fuel and memory, so a misbehaving function can't affect the query engine.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_function::scalars::function::{Function, FunctionContext};
+use common_query::error::WasmUdfSnafu;
+use common_query::prelude::{Signature, Volatility};
+use datatypes::data_type::DataType;
+use datatypes::prelude::{ConcreteDataType, Value, VectorRef};
+use snafu::{ensure, OptionExt, ResultExt};
+use wasmtime::{
+    Config, Engine, ExternType, Instance, Module, Store, StoreLimits, StoreLimitsBuilder, Val,
+    ValType,
+};
+
+use crate::error::{CompileWasmSnafu, DecodeWasmSnafu, ExecuteWasmSnafu, Result};
+
+/// Engine name of WASM functions in the scripts table.
+pub const WASM_ENGINE: &str = "wasm";
+
+/// Fuel for evaluating one row, roughly the number of WASM instructions executed.
+const FUEL_PER_ROW: u64 = 100_000;
+/// Max linear memory of an instance.
+const MAX_MEMORY_BYTES: usize = 16 * 1024 * 1024;
+
+/// A scalar function compiled from a WASM module.
+pub struct WasmFunction {
+    name: String,
+    engine: Engine,
+    module: Module,
+    param_types: Vec<ConcreteDataType>,
+    return_type: ConcreteDataType,
+}
+
+struct StoreState {
+    limits: StoreLimits,
+}
+
+impl WasmFunction {
+    /// Compiles the module, which could be either in binary or text format, and checks
+    /// the signature of the exported function `name`.
+    pub fn try_new(name: &str, module: &[u8]) -> Result<Self> {
+        let mut config = Config::new();
+        let _ = config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| {
+            CompileWasmSnafu {
+                name,
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+        let module = Module::new(&engine, module).map_err(|e| {
+            CompileWasmSnafu {
+                name,
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+        ensure!(
+            module.imports().len() == 0,
+            CompileWasmSnafu {
+                name,
+                reason: "importing from host is not allowed",
+            }
+        );
+
+        let Some(ExternType::Func(func_type)) = module.get_export(name) else {
+            return CompileWasmSnafu {
+                name,
+                reason: format!("function {name} is not exported"),
+            }
+            .fail();
+        };
+        let param_types = func_type
+            .params()
+            .map(|ty| to_concrete_type(name, &ty))
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(
+            !param_types.is_empty(),
+            CompileWasmSnafu {
+                name,
+                reason: "function without params is not supported",
+            }
+        );
+        let results = func_type.results().collect::<Vec<_>>();
+        ensure!(
+            results.len() == 1,
+            CompileWasmSnafu {
+                name,
+                reason: format!("expect exactly one result, but found {}", results.len()),
+            }
+        );
+        let return_type = to_concrete_type(name, &results[0])?;
+
+        Ok(Self {
+            name: name.to_string(),
+            engine,
+            module,
+            param_types,
+            return_type,
+        })
+    }
+
+    /// Evaluates the function on a batch of columns, rows with null arguments yield null.
+    fn eval_batch(&self, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == self.param_types.len(),
+            ExecuteWasmSnafu {
+                name: &self.name,
+                reason: format!(
+                    "expect {} arguments, but found {}",
+                    self.param_types.len(),
+                    columns.len()
+                ),
+            }
+        );
+        let rows = columns[0].len();
+
+        let mut store = Store::new(
+            &self.engine,
+            StoreState {
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(MAX_MEMORY_BYTES)
+                    .instances(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        let execute_error = |e: wasmtime::Error| {
+            ExecuteWasmSnafu {
+                name: &self.name,
+                reason: e.to_string(),
+            }
+            .build()
+        };
+        store
+            .add_fuel(FUEL_PER_ROW.saturating_mul(rows.max(1) as u64))
+            .map_err(execute_error)?;
+        let instance = Instance::new(&mut store, &self.module, &[]).map_err(execute_error)?;
+        let func = instance
+            .get_func(&mut store, &self.name)
+            .with_context(|| ExecuteWasmSnafu {
+                name: &self.name,
+                reason: format!("function {} is not exported", self.name),
+            })?;
+
+        let mut builder = self.return_type.create_mutable_vector(rows);
+        let mut params = Vec::with_capacity(columns.len());
+        let mut results = [Val::I32(0)];
+        for row in 0..rows {
+            params.clear();
+            params.extend(
+                columns
+                    .iter()
+                    .map_while(|column| to_wasm_val(column.get(row))),
+            );
+            if params.len() < columns.len() {
+                builder.push_null();
+                continue;
+            }
+            func.call(&mut store, &params, &mut results)
+                .map_err(execute_error)?;
+            builder.push_value_ref(from_wasm_val(&results[0]).as_value_ref());
+        }
+        Ok(builder.to_vector())
+    }
+}
+
+/// Encodes the module into text stored in the `script` column of the scripts table.
+pub fn encode_module(module: &[u8]) -> String {
+    base64::encode(module)
+}
+
+/// Decodes the module of function `name` from the `script` column of the scripts table.
+pub fn decode_module(name: &str, script: &str) -> Result<Vec<u8>> {
+    base64::decode(script).context(DecodeWasmSnafu { name })
+}
+
+fn to_concrete_type(name: &str, ty: &ValType) -> Result<ConcreteDataType> {
+    match ty {
+        ValType::I32 => Ok(ConcreteDataType::int32_datatype()),
+        ValType::I64 => Ok(ConcreteDataType::int64_datatype()),
+        ValType::F32 => Ok(ConcreteDataType::float32_datatype()),
+        ValType::F64 => Ok(ConcreteDataType::float64_datatype()),
+        _ => CompileWasmSnafu {
+            name,
+            reason: format!("unsupported type {ty}"),
+        }
+        .fail(),
+    }
+}
+
+fn to_wasm_val(value: Value) -> Option<Val> {
+    match value {
+        Value::Int32(v) => Some(Val::I32(v)),
+        Value::Int64(v) => Some(Val::I64(v)),
+        Value::Float32(v) => Some(Val::F32(v.0.to_bits())),
+        Value::Float64(v) => Some(Val::F64(v.0.to_bits())),
+        _ => None,
+    }
+}
+
+fn from_wasm_val(val: &Val) -> Value {
+    match val {
+        Val::I32(v) => Value::Int32(*v),
+        Val::I64(v) => Value::Int64(*v),
+        Val::F32(v) => Value::Float32(f32::from_bits(*v).into()),
+        Val::F64(v) => Value::Float64(f64::from_bits(*v).into()),
+        _ => Value::Null,
+    }
+}
+
+impl Function for WasmFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn return_type(
+        &self,
+        _input_types: &[ConcreteDataType],
+    ) -> common_query::error::Result<ConcreteDataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(self.param_types.clone(), Volatility::Immutable)
+    }
+
+    fn eval(
+        &self,
+        _func_ctx: FunctionContext,
+        columns: &[VectorRef],
+    ) -> common_query::error::Result<VectorRef> {
+        self.eval_batch(columns)
+            .map_err(|e| WasmUdfSnafu { msg: e.to_string() }.build())
+    }
+}
+
+impl fmt::Display for WasmFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WASM({})", self.name)
+    }
+}
+
+pub type WasmFunctionRef = Arc<WasmFunction>;
+
+#[cfg(test)]
+mod tests {
+    use common_query::prelude::TypeSignature;
+    use datatypes::vectors::{Float64Vector, Int64Vector};
+
+    use super::*;
+
+    const ADD_ONE: &str = r#"
+(module
+  (func (export "add_one") (param i64) (result i64)
+    local.get 0
+    i64.const 1
+    i64.add))
+"#;
+
+    #[test]
+    fn test_wasm_function() {
+        let func = WasmFunction::try_new("add_one", ADD_ONE.as_bytes()).unwrap();
+        assert_eq!("add_one", func.name());
+        assert_eq!(
+            ConcreteDataType::int64_datatype(),
+            func.return_type(&[]).unwrap()
+        );
+        assert!(matches!(func.signature(),
+                         Signature {
+                             type_signature: TypeSignature::Exact(types),
+                             volatility: Volatility::Immutable
+                         } if types == vec![ConcreteDataType::int64_datatype()]
+        ));
+
+        let args: Vec<VectorRef> = vec![Arc::new(Int64Vector::from(vec![Some(1), None, Some(-3)]))];
+        let vector = func.eval(FunctionContext::default(), &args).unwrap();
+        let expect: VectorRef = Arc::new(Int64Vector::from(vec![Some(2), None, Some(-2)]));
+        assert_eq!(expect, vector);
+    }
+
+    #[test]
+    fn test_encode_decode_module() {
+        let script = encode_module(ADD_ONE.as_bytes());
+        let module = decode_module("add_one", &script).unwrap();
+        assert_eq!(ADD_ONE.as_bytes(), module);
+        assert!(decode_module("add_one", "not base64!").is_err());
+    }
+
+    #[test]
+    fn test_invalid_wasm_function() {
+        // not exported
+        assert!(WasmFunction::try_new("add_two", ADD_ONE.as_bytes()).is_err());
+        // not a module
+        assert!(WasmFunction::try_new("add_one", b"add_one").is_err());
+
+        let imports = r#"
+(module
+  (import "env" "log" (func $log (param i32)))
+  (func (export "f") (param i32) (result i32)
+    local.get 0))
+"#;
+        assert!(WasmFunction::try_new("f", imports.as_bytes()).is_err());
+
+        let no_result = r#"
+(module
+  (func (export "f") (param f64)))
+"#;
+        assert!(WasmFunction::try_new("f", no_result.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_wasm_function_out_of_fuel() {
+        let spin = r#"
+(module
+  (func (export "spin") (param f64) (result f64)
+    (loop $forever
+      br $forever)
+    local.get 0))
+"#;
+        let func = WasmFunction::try_new("spin", spin.as_bytes()).unwrap();
+        let args: Vec<VectorRef> = vec![Arc::new(Float64Vector::from_slice([1.0]))];
+        assert!(func.eval(FunctionContext::default(), &args).is_err());
+    }
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use common_error::ext::BoxedError;
@@ -50,6 +52,37 @@ pub trait UserProvider: Send + Sync {
         self.authorize(catalog, schema, &user_info).await?;
         Ok(user_info)
     }
+
+    /// [`row_filter`] returns the filter expression that should be conjoined to every scan
+    /// on the given table when it is read by the user, in SQL syntax. `None` means the user
+    /// can see all the rows of the table.
+    fn row_filter(
+        &self,
+        _user_info: &UserInfo,
+        _catalog: &str,
+        _schema: &str,
+        _table: &str,
+    ) -> Option<String> {
+        None
+    }
+
+    /// [`column_masks`] returns the masking rules of the columns of the given table when it
+    /// is read by the user. The columns not in the result are read as is.
+    fn column_masks(
+        &self,
+        _user_info: &UserInfo,
+        _catalog: &str,
+        _schema: &str,
+        _table: &str,
+    ) -> HashMap<String, MaskingRule> {
+        HashMap::new()
+    }
+
+    /// [`allow_admin`] checks whether the user can run the `ADMIN` statements, which operate
+    /// on tables or regions directly. All the users can run them by default.
+    fn allow_admin(&self, _user_info: &UserInfo) -> bool {
+        true
+    }
 }
 
 pub type UserProviderRef = Arc<dyn UserProvider>;
@@ -62,6 +95,34 @@ pub enum Identity<'a> {
     UserId(Username<'a>, Option<HostOrIp<'a>>),
 }
 
+/// How the values of a column are masked when the column is read by a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskingRule {
+    /// Reads the values as is, to exempt privileged users from the rules for all users.
+    Unmasked,
+    /// Replaces the values with a fixed string, or NULL if the column is not a string column.
+    Redact,
+    /// Replaces the values with the hex encoded MD5 hash of their string representations.
+    Hash,
+}
+
+impl FromStr for MaskingRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Ok(MaskingRule::Unmasked),
+            "redact" => Ok(MaskingRule::Redact),
+            "hash" => Ok(MaskingRule::Hash),
+            _ => InvalidConfigSnafu {
+                value: s.to_string(),
+                msg: "Masking rule must be one of `none`, `redact` or `hash`",
+            }
+            .fail(),
+        }
+    }
+}
+
 pub type HashedPassword<'a> = &'a [u8];
 pub type Salt<'a> = &'a [u8];
 
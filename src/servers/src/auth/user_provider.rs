@@ -12,13 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io;
 use std::io::BufRead;
 use std::path::Path;
 
 use async_trait::async_trait;
+use common_catalog::format_full_table_name;
 use digest;
 use digest::Digest;
 use secrecy::ExposeSecret;
@@ -27,13 +28,43 @@ use sha1::Sha1;
 use snafu::{ensure, OptionExt, ResultExt};
 
 use crate::auth::{
-    Error, HashedPassword, Identity, IllegalParamSnafu, InvalidConfigSnafu, IoSnafu, Password,
-    Result, Salt, UnsupportedPasswordTypeSnafu, UserNotFoundSnafu, UserPasswordMismatchSnafu,
-    UserProvider,
+    Error, HashedPassword, Identity, IllegalParamSnafu, InvalidConfigSnafu, IoSnafu, MaskingRule,
+    Password, Result, Salt, UnsupportedPasswordTypeSnafu, UserNotFoundSnafu,
+    UserPasswordMismatchSnafu, UserProvider,
 };
 
 pub const STATIC_USER_PROVIDER: &str = "static_user_provider";
 
+/// The username in masking rules standing for all the users.
+const ALL_USERS: &str = "*";
+
+/// The key of the line listing the users allowed to run `ADMIN` statements.
+const ADMINS_KEY: &str = "@admins";
+
+/// The prefix of the keys of the row filters, so they are never taken as usernames.
+const ROW_FILTER_PREFIX: &str = "@row_filter:";
+
+/// The prefix of the keys of the masking rules, so they are never taken as usernames.
+const MASK_PREFIX: &str = "@mask:";
+
+/// Splits the key `<user>@<name>` of a rule, where the name has `parts` dot separated parts.
+/// The name follows the last `@`, so the username may contain `@`.
+fn split_rule_key(key: &str, parts: usize) -> Result<(String, String)> {
+    let invalid = || InvalidConfigSnafu {
+        value: key.to_string(),
+        msg: format!("Rule key must be in format `<user>@<name>` with {parts} parts in the name"),
+    };
+    let (user, name) = key.rsplit_once('@').with_context(invalid)?;
+    let name_parts = name.split('.').collect::<Vec<_>>();
+    ensure!(
+        !user.is_empty()
+            && name_parts.len() == parts
+            && name_parts.iter().all(|part| !part.is_empty()),
+        invalid()
+    );
+    Ok((user.to_string(), name.to_string()))
+}
+
 impl TryFrom<&str> for StaticUserProvider {
     type Error = Error;
 
@@ -52,24 +83,50 @@ impl TryFrom<&str> for StaticUserProvider {
                 });
 
                 let file = File::open(path).context(IoSnafu)?;
-                let credential = io::BufReader::new(file)
-                    .lines()
-                    .filter_map(|line| line.ok())
-                    .filter_map(|line| {
-                        if let Some((k, v)) = line.split_once('=') {
-                            Some((k.to_string(), v.as_bytes().to_vec()))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<HashMap<String, Vec<u8>>>();
+                let mut credential = HashMap::new();
+                let mut row_filters = HashMap::new();
+                let mut column_masks = HashMap::new();
+                let mut admins = None;
+                for line in io::BufReader::new(file).lines().filter_map(|line| line.ok()) {
+                    let Some((k, v)) = line.split_once('=') else {
+                        continue;
+                    };
+                    // A line like `@row_filter:user@catalog.schema.table=<filter>` is a row
+                    // filter, and a line like `@mask:user@catalog.schema.table.column=<rule>`
+                    // is a masking rule, in which the user `*` stands for all the users. A line
+                    // like `@admins=user[,user]` lists the only users allowed to run `ADMIN`
+                    // statements.
+                    if k.trim() == ADMINS_KEY {
+                        admins
+                            .get_or_insert_with(HashSet::new)
+                            .extend(v.split(',').map(|user| user.trim().to_string()));
+                    } else if let Some(key) = k.trim().strip_prefix(ROW_FILTER_PREFIX) {
+                        let (user, table) = split_rule_key(key, 3)?;
+                        let _ = row_filters.insert((user, table), v.trim().to_string());
+                    } else if let Some(key) = k.trim().strip_prefix(MASK_PREFIX) {
+                        let (user, name) = split_rule_key(key, 4)?;
+                        // the name has 4 parts, so it always has a `.`
+                        let (table, column) = name.rsplit_once('.').unwrap();
+                        let _ = column_masks
+                            .entry((user, table.to_string()))
+                            .or_insert_with(HashMap::new)
+                            .insert(column.to_string(), v.parse::<MaskingRule>()?);
+                    } else {
+                        let _ = credential.insert(k.to_string(), v.as_bytes().to_vec());
+                    }
+                }
 
                 ensure!(!credential.is_empty(), InvalidConfigSnafu {
                     value: content.to_string(),
                     msg: "StaticUserProviderOption file must contains at least one valid credential",
                 });
 
-                Ok(StaticUserProvider { users: credential, })
+                Ok(StaticUserProvider {
+                    users: credential,
+                    row_filters,
+                    column_masks,
+                    admins,
+                })
             }
             "cmd" => content
                 .split(',')
@@ -81,7 +138,12 @@ impl TryFrom<&str> for StaticUserProvider {
                     Ok((k.to_string(), v.as_bytes().to_vec()))
                 })
                 .collect::<Result<HashMap<String, Vec<u8>>>>()
-                .map(|users| StaticUserProvider { users }),
+                .map(|users| StaticUserProvider {
+                    users,
+                    row_filters: HashMap::new(),
+                    column_masks: HashMap::new(),
+                    admins: None,
+                }),
             _ => InvalidConfigSnafu {
                 value: mode.to_string(),
                 msg: "StaticUserProviderOption must be in format `file:<path>` or `cmd:<values>`",
@@ -93,6 +155,12 @@ impl TryFrom<&str> for StaticUserProvider {
 
 pub struct StaticUserProvider {
     users: HashMap<String, Vec<u8>>,
+    /// Row filters keyed by username and full table name.
+    row_filters: HashMap<(String, String), String>,
+    /// Masking rules of the columns keyed by username and full table name.
+    column_masks: HashMap<(String, String), HashMap<String, MaskingRule>>,
+    /// Users allowed to run `ADMIN` statements, all the users are allowed if absent.
+    admins: Option<HashSet<String>>,
 }
 
 #[async_trait]
@@ -158,6 +226,52 @@ impl UserProvider for StaticUserProvider {
         // default allow all
         Ok(())
     }
+
+    fn row_filter(
+        &self,
+        user_info: &UserInfo,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+    ) -> Option<String> {
+        if self.row_filters.is_empty() {
+            return None;
+        }
+        let key = (
+            user_info.username().to_string(),
+            format_full_table_name(catalog, schema, table),
+        );
+        self.row_filters.get(&key).cloned()
+    }
+
+    fn column_masks(
+        &self,
+        user_info: &UserInfo,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+    ) -> HashMap<String, MaskingRule> {
+        if self.column_masks.is_empty() {
+            return HashMap::new();
+        }
+        let table = format_full_table_name(catalog, schema, table);
+        let rules_of = |user: &str| self.column_masks.get(&(user.to_string(), table.clone()));
+
+        // The rules of the user override the ones for all the users.
+        let mut masks = rules_of(ALL_USERS).cloned().unwrap_or_default();
+        if let Some(rules) = rules_of(user_info.username()) {
+            masks.extend(rules.iter().map(|(column, rule)| (column.clone(), *rule)));
+        }
+        masks.retain(|_, rule| *rule != MaskingRule::Unmasked);
+        masks
+    }
+
+    fn allow_admin(&self, user_info: &UserInfo) -> bool {
+        self.admins
+            .as_ref()
+            .map(|admins| admins.contains(user_info.username()))
+            .unwrap_or(true)
+    }
 }
 
 pub fn auth_mysql(
@@ -211,7 +325,7 @@ pub mod test {
     use session::context::UserInfo;
 
     use crate::auth::user_provider::{double_sha1, sha1_one, sha1_two, StaticUserProvider};
-    use crate::auth::{Identity, Password, UserProvider};
+    use crate::auth::{Identity, MaskingRule, Password, UserProvider};
 
     #[test]
     fn test_sha() {
@@ -287,4 +401,141 @@ admin=654321",
         test_authenticate(&provider, "root", "123456").await;
         test_authenticate(&provider, "admin", "654321").await;
     }
+
+    #[tokio::test]
+    async fn test_file_provider_row_filter() {
+        let dir = create_temp_dir("test_file_provider_row_filter");
+        let file_path = format!("{}/test_file_provider", dir.path().to_str().unwrap());
+        std::fs::write(
+            &file_path,
+            "root=123456
+alice=654321
+bob@corp=123456
+@row_filter:alice@greptime.public.metrics=tenant = 'alice'
+@row_filter:bob@corp@greptime.public.metrics=tenant = 'bob'",
+        )
+        .unwrap();
+
+        let param = format!("file:{file_path}");
+        let provider = StaticUserProvider::try_from(param.as_str()).unwrap();
+        test_authenticate(&provider, "alice", "654321").await;
+        // a username with `@` is still a credential
+        test_authenticate(&provider, "bob@corp", "123456").await;
+        // the row filter line is not a credential
+        assert!(provider
+            .authenticate(
+                Identity::UserId("@row_filter:alice@greptime.public.metrics", None),
+                Password::PlainText("tenant = 'alice'".to_string().into()),
+            )
+            .await
+            .is_err());
+        assert_eq!(
+            Some("tenant = 'bob'".to_string()),
+            provider.row_filter(&UserInfo::new("bob@corp"), "greptime", "public", "metrics")
+        );
+
+        let alice = UserInfo::new("alice");
+        assert_eq!(
+            Some("tenant = 'alice'".to_string()),
+            provider.row_filter(&alice, "greptime", "public", "metrics")
+        );
+        assert_eq!(
+            None,
+            provider.row_filter(&alice, "greptime", "public", "other")
+        );
+        assert_eq!(
+            None,
+            provider.row_filter(&UserInfo::new("root"), "greptime", "public", "metrics")
+        );
+
+        std::fs::write(
+            &file_path,
+            "root=123456\n@row_filter:alice@greptime.metrics=tenant = 'alice'",
+        )
+        .unwrap();
+        assert!(StaticUserProvider::try_from(param.as_str()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_column_masks() {
+        let dir = create_temp_dir("test_file_provider_column_masks");
+        let file_path = format!("{}/test_file_provider", dir.path().to_str().unwrap());
+        std::fs::write(
+            &file_path,
+            "root=123456
+alice=654321
+@mask:*@greptime.public.users.email=hash
+@mask:*@greptime.public.users.phone=redact
+@mask:root@greptime.public.users.email=none
+@mask:root@greptime.public.users.phone=none
+@mask:alice@greptime.public.users.phone=hash",
+        )
+        .unwrap();
+
+        let param = format!("file:{file_path}");
+        let provider = StaticUserProvider::try_from(param.as_str()).unwrap();
+        test_authenticate(&provider, "alice", "654321").await;
+        // the masking rule line is not a credential
+        assert!(provider
+            .authenticate(
+                Identity::UserId("@mask:alice@greptime.public.users.phone", None),
+                Password::PlainText("hash".to_string().into()),
+            )
+            .await
+            .is_err());
+
+        let masks = provider.column_masks(&UserInfo::new("bob"), "greptime", "public", "users");
+        assert_eq!(2, masks.len());
+        assert_eq!(MaskingRule::Hash, masks["email"]);
+        assert_eq!(MaskingRule::Redact, masks["phone"]);
+
+        let masks = provider.column_masks(&UserInfo::new("alice"), "greptime", "public", "users");
+        assert_eq!(MaskingRule::Hash, masks["email"]);
+        assert_eq!(MaskingRule::Hash, masks["phone"]);
+
+        let root = UserInfo::new("root");
+        assert!(provider
+            .column_masks(&root, "greptime", "public", "users")
+            .is_empty());
+        assert!(provider
+            .column_masks(&root, "greptime", "public", "others")
+            .is_empty());
+        // masking rules are not row filters
+        assert_eq!(
+            None,
+            provider.row_filter(&UserInfo::new("bob"), "greptime", "public", "users")
+        );
+
+        std::fs::write(
+            &file_path,
+            "root=123456\n@mask:*@greptime.public.users.email=hide",
+        )
+        .unwrap();
+        assert!(StaticUserProvider::try_from(param.as_str()).is_err());
+
+        // a username like the key of a masking rule is still a credential
+        std::fs::write(&file_path, "alice@greptime.public.users.email=654321").unwrap();
+        let provider = StaticUserProvider::try_from(param.as_str()).unwrap();
+        test_authenticate(&provider, "alice@greptime.public.users.email", "654321").await;
+        assert!(provider
+            .column_masks(&UserInfo::new("alice"), "greptime", "public", "users")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_admins() {
+        let dir = create_temp_dir("test_file_provider_admins");
+        let file_path = format!("{}/test_file_provider", dir.path().to_str().unwrap());
+        std::fs::write(&file_path, "root=123456\nalice=654321").unwrap();
+        let param = format!("file:{file_path}");
+        let provider = StaticUserProvider::try_from(param.as_str()).unwrap();
+        // all the users are admins if not listed
+        assert!(provider.allow_admin(&UserInfo::new("alice")));
+
+        std::fs::write(&file_path, "root=123456\nalice=654321\n@admins=root").unwrap();
+        let provider = StaticUserProvider::try_from(param.as_str()).unwrap();
+        test_authenticate(&provider, "alice", "654321").await;
+        assert!(provider.allow_admin(&UserInfo::new("root")));
+        assert!(!provider.allow_admin(&UserInfo::new("alice")));
+    }
 }
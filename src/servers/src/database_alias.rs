@@ -0,0 +1,120 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aliases of database names, so clients of the ingestion protocols could keep writing to
+//! the database names they are configured with, e.g. InfluxDB `db=telegraf`, while the
+//! data lands in another catalog and schema.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+/// Catalog and schema a database alias refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseAlias {
+    pub catalog: String,
+    pub schema: String,
+}
+
+/// Database aliases honored by the InfluxDB, OpenTSDB and Prometheus handlers, keyed by
+/// the database names used by clients.
+#[derive(Debug, Default)]
+pub struct DatabaseAliases {
+    aliases: RwLock<HashMap<String, DatabaseAlias>>,
+}
+
+pub type DatabaseAliasesRef = Arc<DatabaseAliases>;
+
+impl DatabaseAliases {
+    /// Replaces all aliases with `aliases`.
+    pub fn replace(&self, aliases: HashMap<String, DatabaseAlias>) {
+        *self.aliases.write() = aliases;
+    }
+
+    pub fn get(&self, db: &str) -> Option<DatabaseAlias> {
+        self.aliases.read().get(db).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.aliases.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.read().is_empty()
+    }
+
+    /// Resolves catalog and schema of a request like [crate::resolve_catalog_and_schema],
+    /// except that a database name having an alias resolves to the catalog and schema of
+    /// the alias. Aliases are ignored if the catalog is given explicitly.
+    pub fn resolve_catalog_and_schema(&self, catalog: Option<&str>, db: &str) -> (String, String) {
+        if catalog.map_or(true, |catalog| catalog.is_empty()) {
+            if let Some(alias) = self.get(db) {
+                return (alias.catalog, alias.schema);
+            }
+        }
+        let (catalog, schema) = crate::resolve_catalog_and_schema(catalog, db);
+        (catalog.to_string(), schema.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_catalog::consts::DEFAULT_CATALOG_NAME;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_catalog_and_schema() {
+        let aliases = DatabaseAliases::default();
+        assert!(aliases.is_empty());
+        assert_eq!(
+            (DEFAULT_CATALOG_NAME.to_string(), "telegraf".to_string()),
+            aliases.resolve_catalog_and_schema(None, "telegraf")
+        );
+
+        aliases.replace(HashMap::from([(
+            "telegraf".to_string(),
+            DatabaseAlias {
+                catalog: "metrics".to_string(),
+                schema: "hosts".to_string(),
+            },
+        )]));
+        assert_eq!(1, aliases.len());
+        assert_eq!(
+            ("metrics".to_string(), "hosts".to_string()),
+            aliases.resolve_catalog_and_schema(None, "telegraf")
+        );
+        assert_eq!(
+            ("metrics".to_string(), "hosts".to_string()),
+            aliases.resolve_catalog_and_schema(Some(""), "telegraf")
+        );
+        // The catalog given explicitly takes precedence.
+        assert_eq!(
+            ("header_catalog".to_string(), "telegraf".to_string()),
+            aliases.resolve_catalog_and_schema(Some("header_catalog"), "telegraf")
+        );
+        // Names without aliases are resolved as usual.
+        assert_eq!(
+            ("catalog".to_string(), "schema".to_string()),
+            aliases.resolve_catalog_and_schema(None, "catalog-schema")
+        );
+
+        aliases.replace(HashMap::new());
+        assert_eq!(
+            (DEFAULT_CATALOG_NAME.to_string(), "telegraf".to_string()),
+            aliases.resolve_catalog_and_schema(None, "telegraf")
+        );
+    }
+}
@@ -190,6 +190,12 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid admin request, source: {}", source))]
+    InvalidAdminRequest {
+        source: serde_json::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("Failed to start frontend service, source: {}", source))]
     StartFrontend {
         #[snafu(backtrace)]
@@ -257,12 +263,22 @@ pub enum Error {
         source: common_mem_prof::error::Error,
     },
 
+    #[cfg(feature = "mem-prof")]
+    #[snafu(display("Failed to read memory statistics, source: {}", source))]
+    ReadMemoryStats {
+        #[snafu(backtrace)]
+        source: common_mem_prof::error::Error,
+    },
+
     #[snafu(display("Invalid prepare statement: {}", err_msg))]
     InvalidPrepareStatement { err_msg: String },
 
     #[snafu(display("Invalid flush argument: {}", err_msg))]
     InvalidFlushArgument { err_msg: String },
 
+    #[snafu(display("Failed to update log level: {}", err_msg))]
+    UpdateLogLevel { err_msg: String },
+
     #[snafu(display("Failed to build gRPC reflection service, source: {}", source))]
     GrpcReflectionService {
         source: tonic_reflection::server::Error,
@@ -281,6 +297,54 @@ pub enum Error {
         #[snafu(backtrace)]
         source: query::error::Error,
     },
+
+    #[snafu(display("Invalid events: {}", reason))]
+    InvalidEvents { reason: String, location: Location },
+
+    #[snafu(display("Failed to parse events JSON, source: {}", source))]
+    ParseEventsJson {
+        source: serde_json::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to write events, source: {}", source))]
+    WriteEvents {
+        #[snafu(backtrace)]
+        source: common_grpc::error::Error,
+    },
+
+    #[snafu(display("Invalid Grafana query, reason: {}", reason))]
+    InvalidGrafanaQuery { reason: String, location: Location },
+
+    #[snafu(display("Invalid schema file, reason: {}", reason))]
+    InvalidSchemaFile { reason: String, location: Location },
+
+    #[snafu(display("Failed to parse schema file, source: {}", source))]
+    ParseSchemaFile {
+        #[snafu(backtrace)]
+        source: sql::error::Error,
+    },
+
+    #[snafu(display(
+        "Cannot migrate column {} of table {} from {} to {}",
+        column_name,
+        table_name,
+        current_type,
+        desired_type
+    ))]
+    IncompatibleSchemaMigration {
+        table_name: String,
+        column_name: String,
+        current_type: String,
+        desired_type: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to filter rows of live query, source: {}", source))]
+    FilterLiveQueryRows {
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -293,6 +357,7 @@ impl ErrorExt for Error {
             | InternalIo { .. }
             | TokioIo { .. }
             | CollectRecordbatch { .. }
+            | FilterLiveQueryRows { .. }
             | StartHttp { .. }
             | StartGrpc { .. }
             | AlreadyStarted { .. }
@@ -322,12 +387,19 @@ impl ErrorExt for Error {
             | DecompressPromRemoteRequest { .. }
             | InvalidPromRemoteRequest { .. }
             | InvalidFlightTicket { .. }
+            | InvalidAdminRequest { .. }
             | InvalidPrepareStatement { .. }
-            | TimePrecision { .. } => StatusCode::InvalidArguments,
-
-            InfluxdbLinesWrite { source, .. } | ConvertFlightMessage { source } => {
-                source.status_code()
-            }
+            | TimePrecision { .. }
+            | InvalidEvents { .. }
+            | ParseEventsJson { .. }
+            | InvalidGrafanaQuery { .. }
+            | InvalidSchemaFile { .. }
+            | ParseSchemaFile { .. }
+            | IncompatibleSchemaMigration { .. } => StatusCode::InvalidArguments,
+
+            InfluxdbLinesWrite { source, .. }
+            | ConvertFlightMessage { source }
+            | WriteEvents { source } => source.status_code(),
 
             Hyper { .. } => StatusCode::Unknown,
             TlsRequired { .. } => StatusCode::Unknown,
@@ -345,7 +417,9 @@ impl ErrorExt for Error {
             DatabaseNotFound { .. } => StatusCode::DatabaseNotFound,
             #[cfg(feature = "mem-prof")]
             DumpProfileData { source, .. } => source.status_code(),
-            InvalidFlushArgument { .. } => StatusCode::InvalidArguments,
+            #[cfg(feature = "mem-prof")]
+            ReadMemoryStats { source, .. } => source.status_code(),
+            InvalidFlushArgument { .. } | UpdateLogLevel { .. } => StatusCode::InvalidArguments,
 
             ParsePromQL { source, .. } => source.status_code(),
         }
@@ -358,20 +432,55 @@ impl ErrorExt for Error {
 
 impl From<Error> for tonic::Status {
     fn from(err: Error) -> Self {
-        let mut headers = HeaderMap::<HeaderValue>::with_capacity(2);
+        status_from_error(&err)
+    }
+}
+
+/// Converts the error to [tonic::Status], carrying its [StatusCode] and root cause in the
+/// metadata, so that clients can tell what the error is, e.g. whether it's retryable.
+pub fn status_from_error<E: ErrorExt + ErrorCompat + 'static>(err: &E) -> tonic::Status {
+    let mut headers = HeaderMap::<HeaderValue>::with_capacity(2);
+
+    // If either of the status_code or error msg cannot convert to valid HTTP header value
+    // (which is a very rare case), just ignore. Client will use Tonic status code and message.
+    if let Ok(code) = HeaderValue::from_bytes(err.status_code().to_string().as_bytes()) {
+        headers.insert(INNER_ERROR_CODE, code);
+    }
+    let root_error = err.iter_chain().last().unwrap();
+    if let Ok(err_msg) = HeaderValue::from_bytes(root_error.to_string().as_bytes()) {
+        headers.insert(INNER_ERROR_MSG, err_msg);
+    }
 
-        // If either of the status_code or error msg cannot convert to valid HTTP header value
-        // (which is a very rare case), just ignore. Client will use Tonic status code and message.
-        if let Ok(code) = HeaderValue::from_bytes(err.status_code().to_string().as_bytes()) {
-            headers.insert(INNER_ERROR_CODE, code);
+    let metadata = MetadataMap::from_headers(headers);
+    tonic::Status::with_metadata(tonic_code(err.status_code()), err.to_string(), metadata)
+}
+
+/// Returns the gRPC code matching the [StatusCode], for the clients only looking at the code
+/// of [tonic::Status].
+fn tonic_code(status_code: StatusCode) -> Code {
+    match status_code {
+        StatusCode::Success => Code::Ok,
+        StatusCode::Unknown => Code::Unknown,
+        StatusCode::Unsupported => Code::Unimplemented,
+        StatusCode::Unexpected | StatusCode::Internal | StatusCode::EngineExecuteQuery => {
+            Code::Internal
         }
-        let root_error = err.iter_chain().last().unwrap();
-        if let Ok(err_msg) = HeaderValue::from_bytes(root_error.to_string().as_bytes()) {
-            headers.insert(INNER_ERROR_MSG, err_msg);
+        StatusCode::InvalidArguments | StatusCode::InvalidSyntax | StatusCode::PlanQuery => {
+            Code::InvalidArgument
         }
-
-        let metadata = MetadataMap::from_headers(headers);
-        tonic::Status::with_metadata(Code::Internal, err.to_string(), metadata)
+        StatusCode::TableAlreadyExists | StatusCode::TableColumnExists => Code::AlreadyExists,
+        StatusCode::TableNotFound
+        | StatusCode::TableColumnNotFound
+        | StatusCode::DatabaseNotFound => Code::NotFound,
+        StatusCode::StorageUnavailable => Code::Unavailable,
+        StatusCode::StorageCorrupted => Code::DataLoss,
+        StatusCode::RuntimeResourcesExhausted => Code::ResourceExhausted,
+        StatusCode::UserNotFound
+        | StatusCode::UnsupportedPasswordType
+        | StatusCode::UserPasswordMismatch
+        | StatusCode::AuthHeaderNotFound
+        | StatusCode::InvalidAuthHeader => Code::Unauthenticated,
+        StatusCode::AccessDenied => Code::PermissionDenied,
     }
 }
 
@@ -398,12 +507,64 @@ impl IntoResponse for Error {
             | Error::DecompressPromRemoteRequest { .. }
             | Error::InvalidPromRemoteRequest { .. }
             | Error::InvalidQuery { .. }
-            | Error::TimePrecision { .. } => (HttpStatusCode::BAD_REQUEST, self.to_string()),
+            | Error::TimePrecision { .. }
+            | Error::InvalidEvents { .. }
+            | Error::ParseEventsJson { .. }
+            | Error::WriteEvents { .. }
+            | Error::InvalidGrafanaQuery { .. }
+            | Error::InvalidSchemaFile { .. }
+            | Error::ParseSchemaFile { .. }
+            | Error::IncompatibleSchemaMigration { .. }
+            | Error::UpdateLogLevel { .. } => (HttpStatusCode::BAD_REQUEST, self.to_string()),
             _ => (HttpStatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
         let body = Json(json!({
+            "code": self.status_code() as u32,
             "error": error_message,
         }));
         (status, body).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_with_code() {
+        let err = InvalidQuerySnafu { reason: "bad" }.build();
+        assert!(!err.is_retryable());
+
+        let status = tonic::Status::from(err);
+        assert_eq!(Code::InvalidArgument, status.code());
+        assert_eq!(
+            Code::NotFound,
+            tonic::Status::from(
+                DatabaseNotFoundSnafu {
+                    catalog: "greptime",
+                    schema: "db",
+                }
+                .build()
+            )
+            .code()
+        );
+        assert_eq!(
+            "InvalidArguments",
+            status
+                .metadata()
+                .get(INNER_ERROR_CODE)
+                .unwrap()
+                .to_str()
+                .unwrap()
+        );
+        assert_eq!(
+            "Invalid query: bad",
+            status
+                .metadata()
+                .get(INNER_ERROR_MSG)
+                .unwrap()
+                .to_str()
+                .unwrap()
+        );
+    }
+}
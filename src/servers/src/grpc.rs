@@ -46,6 +46,7 @@ use crate::grpc::flight::FlightHandler;
 use crate::grpc::handler::GreptimeRequestHandler;
 use crate::prom::PromHandlerRef;
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
+use crate::query_handler::AdminHandlerRef;
 use crate::server::Server;
 
 type TonicResult<T> = std::result::Result<T, Status>;
@@ -55,6 +56,10 @@ pub struct GrpcServer {
     request_handler: Arc<GreptimeRequestHandler>,
     /// Handler for Prometheus-compatible PromQL queries. Only present for frontend server.
     promql_handler: Option<PromHandlerRef>,
+    /// Threshold (in bytes) of compressing the record batches in flight data.
+    flight_compression_threshold: Option<usize>,
+    /// Handler for the admin Flight actions. Only present for datanode server.
+    admin_handler: Option<AdminHandlerRef>,
 }
 
 impl GrpcServer {
@@ -73,11 +78,29 @@ impl GrpcServer {
             shutdown_tx: Mutex::new(None),
             request_handler,
             promql_handler,
+            flight_compression_threshold: None,
+            admin_handler: None,
         }
     }
 
+    /// Compresses the record batches no smaller than `threshold` bytes in the flight data
+    /// with zstd, or doesn't compress if `threshold` is `None`.
+    pub fn with_flight_compression(mut self, threshold: Option<usize>) -> Self {
+        self.flight_compression_threshold = threshold;
+        self
+    }
+
+    pub fn with_admin_handler(mut self, handler: AdminHandlerRef) -> Self {
+        self.admin_handler = Some(handler);
+        self
+    }
+
     pub fn create_flight_service(&self) -> FlightServiceServer<impl FlightService> {
-        FlightServiceServer::new(FlightHandler::new(self.request_handler.clone()))
+        FlightServiceServer::new(
+            FlightHandler::new(self.request_handler.clone())
+                .with_compression_threshold(self.flight_compression_threshold)
+                .with_admin_handler(self.admin_handler.clone()),
+        )
     }
 
     pub fn create_database_service(&self) -> GreptimeDatabaseServer<impl GreptimeDatabase> {
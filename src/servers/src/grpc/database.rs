@@ -22,7 +22,7 @@ use common_query::Output;
 use futures::StreamExt;
 use tonic::{Request, Response, Status, Streaming};
 
-use crate::grpc::handler::GreptimeRequestHandler;
+use crate::grpc::handler::{catalog_from_metadata, trace_id_from_metadata, GreptimeRequestHandler};
 use crate::grpc::TonicResult;
 
 pub(crate) struct DatabaseService {
@@ -41,8 +41,13 @@ impl GreptimeDatabase for DatabaseService {
         &self,
         request: Request<GreptimeRequest>,
     ) -> TonicResult<Response<GreptimeResponse>> {
+        let catalog = catalog_from_metadata(request.metadata());
+        let trace_id = trace_id_from_metadata(request.metadata());
         let request = request.into_inner();
-        let output = self.handler.handle_request(request).await?;
+        let output = self
+            .handler
+            .handle_request(request, catalog.as_deref(), trace_id)
+            .await?;
         let response = match output {
             Output::AffectedRows(rows) => GreptimeResponse {
                 header: None,
@@ -61,10 +66,15 @@ impl GreptimeDatabase for DatabaseService {
     ) -> Result<Response<GreptimeResponse>, Status> {
         let mut affected_rows = 0;
 
+        let catalog = catalog_from_metadata(request.metadata());
+        let trace_id = trace_id_from_metadata(request.metadata());
         let mut stream = request.into_inner();
         while let Some(request) = stream.next().await {
             let request = request?;
-            let output = self.handler.handle_request(request).await?;
+            let output = self
+                .handler
+                .handle_request(request, catalog.as_deref(), trace_id)
+                .await?;
             match output {
                 Output::AffectedRows(rows) => affected_rows += rows,
                 Output::Stream(_) | Output::RecordBatches(_) => {
@@ -24,27 +24,53 @@ use arrow_flight::{
     HandshakeRequest, HandshakeResponse, PutResult, SchemaResult, Ticket,
 };
 use async_trait::async_trait;
-use common_grpc::flight::{FlightEncoder, FlightMessage};
+use common_grpc::flight::{FlightEncoder, FlightMessage, ADMIN_ACTION};
 use common_query::Output;
-use futures::Stream;
+use futures::{Stream, TryStreamExt};
 use prost::Message;
 use snafu::ResultExt;
+use table::requests::AdminRequest;
 use tonic::{Request, Response, Status, Streaming};
 
 use crate::error;
 use crate::grpc::flight::stream::FlightRecordBatchStream;
-use crate::grpc::handler::GreptimeRequestHandler;
+use crate::grpc::handler::{catalog_from_metadata, trace_id_from_metadata, GreptimeRequestHandler};
 use crate::grpc::TonicResult;
+use crate::query_handler::AdminHandlerRef;
 
 type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + Sync + 'static>>;
 
 pub struct FlightHandler {
     handler: Arc<GreptimeRequestHandler>,
+    /// Record batches no smaller than this threshold (in bytes) are compressed if present.
+    compression_threshold: Option<usize>,
+    admin_handler: Option<AdminHandlerRef>,
 }
 
 impl FlightHandler {
     pub fn new(handler: Arc<GreptimeRequestHandler>) -> Self {
-        Self { handler }
+        Self {
+            handler,
+            compression_threshold: None,
+            admin_handler: None,
+        }
+    }
+
+    pub fn with_compression_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.compression_threshold = threshold;
+        self
+    }
+
+    pub fn with_admin_handler(mut self, handler: Option<AdminHandlerRef>) -> Self {
+        self.admin_handler = handler;
+        self
+    }
+
+    fn new_encoder(&self) -> FlightEncoder {
+        match self.compression_threshold {
+            Some(threshold) => FlightEncoder::with_compression(threshold),
+            None => FlightEncoder::default(),
+        }
     }
 }
 
@@ -85,13 +111,18 @@ impl FlightService for FlightHandler {
     type DoGetStream = TonicStream<FlightData>;
 
     async fn do_get(&self, request: Request<Ticket>) -> TonicResult<Response<Self::DoGetStream>> {
+        let catalog = catalog_from_metadata(request.metadata());
+        let trace_id = trace_id_from_metadata(request.metadata());
         let ticket = request.into_inner().ticket;
         let request =
             GreptimeRequest::decode(ticket.as_ref()).context(error::InvalidFlightTicketSnafu)?;
 
-        let output = self.handler.handle_request(request).await?;
+        let output = self
+            .handler
+            .handle_request(request, catalog.as_deref(), trace_id)
+            .await?;
 
-        let stream = to_flight_data_stream(output);
+        let stream = to_flight_data_stream(output, self.new_encoder());
         Ok(Response::new(stream))
     }
 
@@ -115,8 +146,30 @@ impl FlightService for FlightHandler {
 
     type DoActionStream = TonicStream<arrow_flight::Result>;
 
-    async fn do_action(&self, _: Request<Action>) -> TonicResult<Response<Self::DoActionStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<Self::DoActionStream>> {
+        let action = request.into_inner();
+        let Some(handler) = &self.admin_handler else {
+            return Err(Status::unimplemented("Admin actions are not supported"));
+        };
+        if action.r#type != ADMIN_ACTION {
+            return Err(Status::unimplemented(format!(
+                "Unknown action type: {}",
+                action.r#type
+            )));
+        }
+        let request: AdminRequest =
+            serde_json::from_slice(&action.body).context(error::InvalidAdminRequestSnafu)?;
+
+        let output = handler.do_admin(request).await?;
+
+        let stream =
+            to_flight_data_stream(output, self.new_encoder()).map_ok(|data| arrow_flight::Result {
+                body: data.encode_to_vec().into(),
+            });
+        Ok(Response::new(Box::pin(stream) as _))
     }
 
     type ListActionsStream = TonicStream<ActionType>;
@@ -129,20 +182,18 @@ impl FlightService for FlightHandler {
     }
 }
 
-fn to_flight_data_stream(output: Output) -> TonicStream<FlightData> {
+fn to_flight_data_stream(output: Output, mut encoder: FlightEncoder) -> TonicStream<FlightData> {
     match output {
         Output::Stream(stream) => {
-            let stream = FlightRecordBatchStream::new(stream);
+            let stream = FlightRecordBatchStream::new(stream, encoder);
             Box::pin(stream) as _
         }
         Output::RecordBatches(x) => {
-            let stream = FlightRecordBatchStream::new(x.as_stream());
+            let stream = FlightRecordBatchStream::new(x.as_stream(), encoder);
             Box::pin(stream) as _
         }
         Output::AffectedRows(rows) => {
-            let stream = tokio_stream::once(Ok(
-                FlightEncoder::default().encode(FlightMessage::AffectedRows(rows))
-            ));
+            let stream = tokio_stream::once(Ok(encoder.encode(FlightMessage::AffectedRows(rows))));
             Box::pin(stream) as _
         }
     }
@@ -17,12 +17,14 @@ use std::sync::Arc;
 use api::v1::auth_header::AuthScheme;
 use api::v1::{Basic, GreptimeRequest, RequestHeader};
 use common_error::prelude::ErrorExt;
+use common_grpc::GREPTIME_TRACE_ID_HEADER;
 use common_query::Output;
 use common_runtime::Runtime;
 use common_telemetry::{logging, timer};
 use metrics::increment_counter;
 use session::context::{QueryContext, QueryContextRef};
 use snafu::OptionExt;
+use tonic::metadata::MetadataMap;
 use tonic::Status;
 
 use crate::auth::{Identity, Password, UserProviderRef};
@@ -30,6 +32,7 @@ use crate::error::Error::{Auth, UnsupportedAuthScheme};
 use crate::error::{InvalidQuerySnafu, NotFoundAuthHeaderSnafu};
 use crate::grpc::TonicResult;
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
+use crate::GREPTIME_CATALOG_HEADER;
 
 pub struct GreptimeRequestHandler {
     handler: ServerGrpcQueryHandlerRef,
@@ -50,13 +53,20 @@ impl GreptimeRequestHandler {
         }
     }
 
-    pub(crate) async fn handle_request(&self, request: GreptimeRequest) -> TonicResult<Output> {
+    /// Handles the `request` as the request of `trace_id`, or of a new trace id if it's not
+    /// given by the caller.
+    pub(crate) async fn handle_request(
+        &self,
+        request: GreptimeRequest,
+        catalog: Option<&str>,
+        trace_id: Option<u64>,
+    ) -> TonicResult<Output> {
         let query = request.request.context(InvalidQuerySnafu {
             reason: "Expecting non-empty GreptimeRequest.",
         })?;
 
         let header = request.header.as_ref();
-        let query_ctx = create_query_context(header);
+        let query_ctx = create_query_context(header, catalog);
 
         self.auth(header, &query_ctx).await?;
 
@@ -65,6 +75,7 @@ impl GreptimeRequestHandler {
             &[(crate::metrics::METRIC_DB_LABEL, &query_ctx.get_db_string())]
         );
         let handler = self.handler.clone();
+        let trace_id = trace_id.unwrap_or_else(logging::gen_trace_id);
 
         // Executes requests in another runtime to
         // 1. prevent the execution from being cancelled unexpected by Tonic runtime;
@@ -73,21 +84,28 @@ impl GreptimeRequestHandler {
         //   - Obtaining a `JoinHandle` to get the panic message (if there's any).
         //     From its docs, `JoinHandle` is cancel safe. The task keeps running even it's handle been dropped.
         // 2. avoid the handler blocks the gRPC runtime incidentally.
-        let handle = self.runtime.spawn(async move {
-            handler.do_query(query, query_ctx).await.map_err(|e| {
-                if e.status_code().should_log_error() {
-                    logging::error!(e; "Failed to handle request");
-                } else {
-                    // Currently, we still print a debug log.
-                    logging::debug!("Failed to handle request, err: {}", e);
-                }
-                e
-            })
-        });
+        let handle = self
+            .runtime
+            .spawn(logging::with_trace_id(trace_id, async move {
+                let db = query_ctx.get_db_string();
+                handler.do_query(query, query_ctx).await.map_err(|e| {
+                    if e.status_code().should_log_error() {
+                        logging::error!(e; db = %db, "Failed to handle request");
+                    } else {
+                        // Currently, we still print a debug log.
+                        logging::debug!(db = %db, "Failed to handle request, err: {}", e);
+                    }
+                    e
+                })
+            }));
 
         let output = handle.await.map_err(|e| {
             // logs the runtime join error.
-            logging::error!("Failed to join handle, err: {}", e);
+            logging::error!(
+                trace_id = %logging::format_trace_id(trace_id),
+                "Failed to join handle, err: {}",
+                e
+            );
 
             if e.is_cancelled() {
                 Status::cancelled(e.to_string())
@@ -116,7 +134,7 @@ impl GreptimeRequestHandler {
             })
             .context(NotFoundAuthHeaderSnafu)?;
 
-        match auth_scheme {
+        let user_info = match auth_scheme {
             AuthScheme::Basic(Basic { username, password }) => user_provider
                 .auth(
                     Identity::UserId(&username, None),
@@ -140,18 +158,40 @@ impl GreptimeRequestHandler {
             );
             Status::unauthenticated(e.to_string())
         })?;
+        query_ctx.set_current_user(user_info);
         Ok(())
     }
 }
 
-pub(crate) fn create_query_context(header: Option<&RequestHeader>) -> QueryContextRef {
+/// Get the catalog selected by [GREPTIME_CATALOG_HEADER] in request metadata, an
+/// empty or non-ASCII value is ignored.
+pub(crate) fn catalog_from_metadata(metadata: &MetadataMap) -> Option<String> {
+    metadata
+        .get(GREPTIME_CATALOG_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|catalog| !catalog.is_empty())
+        .map(|catalog| catalog.to_string())
+}
+
+/// Get the trace id given by [GREPTIME_TRACE_ID_HEADER] in request metadata, a malformed
+/// value is ignored.
+pub(crate) fn trace_id_from_metadata(metadata: &MetadataMap) -> Option<u64> {
+    metadata
+        .get(GREPTIME_TRACE_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(logging::parse_trace_id)
+}
+
+pub(crate) fn create_query_context(
+    header: Option<&RequestHeader>,
+    catalog: Option<&str>,
+) -> QueryContextRef {
     let ctx = QueryContext::arc();
     if let Some(header) = header {
         // We provide dbname field in newer versions of protos/sdks
         // parse dbname from header in priority
         if !header.dbname.is_empty() {
-            let (catalog, schema) =
-                crate::parse_catalog_and_schema_from_client_database_name(&header.dbname);
+            let (catalog, schema) = crate::resolve_catalog_and_schema(catalog, &header.dbname);
             ctx.set_current_catalog(catalog);
             ctx.set_current_schema(schema);
         } else {
@@ -163,5 +203,75 @@ pub(crate) fn create_query_context(header: Option<&RequestHeader>) -> QueryConte
             }
         }
     };
+    // The catalog selected by metadata overrides the one in request header.
+    if let Some(catalog) = catalog.filter(|catalog| !catalog.is_empty()) {
+        ctx.set_current_catalog(catalog);
+    }
     ctx
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_query_context() {
+        let header = RequestHeader {
+            catalog: "catalog".to_string(),
+            schema: "schema".to_string(),
+            ..Default::default()
+        };
+        let ctx = create_query_context(Some(&header), None);
+        assert_eq!("catalog", ctx.current_catalog());
+        assert_eq!("schema", ctx.current_schema());
+
+        let ctx = create_query_context(Some(&header), Some("header_catalog"));
+        assert_eq!("header_catalog", ctx.current_catalog());
+        assert_eq!("schema", ctx.current_schema());
+
+        let header = RequestHeader {
+            dbname: "catalog-schema".to_string(),
+            ..Default::default()
+        };
+        let ctx = create_query_context(Some(&header), None);
+        assert_eq!("catalog", ctx.current_catalog());
+        assert_eq!("schema", ctx.current_schema());
+
+        let ctx = create_query_context(Some(&header), Some("header_catalog"));
+        assert_eq!("header_catalog", ctx.current_catalog());
+        assert_eq!("catalog-schema", ctx.current_schema());
+
+        let ctx = create_query_context(None, Some("header_catalog"));
+        assert_eq!("header_catalog", ctx.current_catalog());
+    }
+
+    #[test]
+    fn test_catalog_from_metadata() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(None, catalog_from_metadata(&metadata));
+
+        let _ = metadata.insert(GREPTIME_CATALOG_HEADER, "".parse().unwrap());
+        assert_eq!(None, catalog_from_metadata(&metadata));
+
+        let _ = metadata.insert(GREPTIME_CATALOG_HEADER, "catalog".parse().unwrap());
+        assert_eq!(
+            Some("catalog".to_string()),
+            catalog_from_metadata(&metadata)
+        );
+    }
+
+    #[test]
+    fn test_trace_id_from_metadata() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(None, trace_id_from_metadata(&metadata));
+
+        let _ = metadata.insert(GREPTIME_TRACE_ID_HEADER, "xyz".parse().unwrap());
+        assert_eq!(None, trace_id_from_metadata(&metadata));
+
+        let _ = metadata.insert(
+            GREPTIME_TRACE_ID_HEADER,
+            "00000000000004d2".parse().unwrap(),
+        );
+        assert_eq!(Some(1234), trace_id_from_metadata(&metadata));
+    }
+}
@@ -27,9 +27,11 @@ use snafu::OptionExt;
 use tonic::{Request, Response};
 
 use crate::error::InvalidQuerySnafu;
-use crate::grpc::handler::create_query_context;
+use crate::grpc::handler::{catalog_from_metadata, create_query_context};
 use crate::grpc::TonicResult;
-use crate::prom::{retrieve_metric_name_and_result_type, PromHandlerRef, PromJsonResponse};
+use crate::prom::{
+    retrieve_metric_name_and_result_type, PromHandlerRef, PromJsonResponse, PromResponseOptions,
+};
 
 pub struct PrometheusGatewayService {
     handler: PromHandlerRef,
@@ -39,6 +41,7 @@ pub struct PrometheusGatewayService {
 impl PrometheusGateway for PrometheusGatewayService {
     async fn handle(&self, req: Request<PromqlRequest>) -> TonicResult<Response<PromqlResponse>> {
         let mut is_range_query = false;
+        let catalog = catalog_from_metadata(req.metadata());
         let inner = req.into_inner();
         let prom_query = match inner.promql.context(InvalidQuerySnafu {
             reason: "Expecting non-empty PromqlRequest.",
@@ -67,7 +70,7 @@ impl PrometheusGateway for PrometheusGatewayService {
             }
         };
 
-        let query_context = create_query_context(inner.header.as_ref());
+        let query_context = create_query_context(inner.header.as_ref(), catalog.as_deref());
         let _timer = timer!(
             crate::metrics::METRIC_SERVER_GRPC_PROM_REQUEST_TIMER,
             &[(
@@ -82,9 +85,15 @@ impl PrometheusGateway for PrometheusGatewayService {
         if is_range_query {
             result_type = Some(ValueType::Matrix)
         };
-        let json_response = PromJsonResponse::from_query_result(result, metric_name, result_type)
-            .await
-            .0;
+        let json_response = PromJsonResponse::from_query_result(
+            result,
+            &prom_query.query,
+            metric_name,
+            result_type,
+            PromResponseOptions::default(),
+        )
+        .await
+        .0;
         let json_bytes = serde_json::to_string(&json_response).unwrap().into_bytes();
 
         let response = Response::new(PromqlResponse {
@@ -0,0 +1,28 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+/// Checks the health of a server, served by the `/health` HTTP API.
+#[async_trait]
+pub trait HealthChecker: Send + Sync {
+    /// Returns the problems of the server which don't stop it from serving requests, but
+    /// need the attention of the operators, e.g. the disk is running out of space. Empty if
+    /// the server is healthy.
+    async fn warnings(&self) -> Vec<String>;
+}
+
+pub type HealthCheckerRef = Arc<dyn HealthChecker>;
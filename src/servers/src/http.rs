@@ -13,8 +13,13 @@
 // limitations under the License.
 
 pub mod authorize;
+pub mod events;
+pub mod grafana;
 pub mod handler;
 pub mod influxdb;
+pub mod live;
+pub mod log_level;
+pub mod migrate;
 pub mod opentsdb;
 pub mod prometheus;
 pub mod script;
@@ -25,6 +30,7 @@ mod dashboard;
 #[cfg(feature = "mem-prof")]
 pub mod mem_prof;
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -34,14 +40,18 @@ use aide::openapi::{Info, OpenApi, Server as OpenAPIServer};
 use async_trait::async_trait;
 use axum::body::BoxBody;
 use axum::error_handling::HandleErrorLayer;
+use axum::http::HeaderMap;
 use axum::response::{Html, Json};
 use axum::{routing, BoxError, Extension, Router};
+use common_catalog::build_db_string;
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
 use common_query::Output;
 use common_recordbatch::{util, RecordBatch};
 use common_telemetry::logging::info;
 use datatypes::data_type::DataType;
+use datatypes::schema::SEMANTIC_TYPE_KEY;
 use futures::FutureExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -56,9 +66,12 @@ use tower_http::auth::AsyncRequireAuthorizationLayer;
 use tower_http::trace::TraceLayer;
 
 use self::authorize::HttpAuth;
+use self::events::{EventMapping, EventsState};
 use self::influxdb::{influxdb_health, influxdb_ping, influxdb_write};
 use crate::auth::UserProviderRef;
+use crate::database_alias::DatabaseAliasesRef;
 use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
+use crate::health_checker::HealthCheckerRef;
 use crate::http::admin::flush;
 use crate::metrics_handler::MetricsHandler;
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
@@ -68,29 +81,50 @@ use crate::query_handler::{
     ScriptHandlerRef,
 };
 use crate::server::Server;
+use crate::GREPTIME_CATALOG_HEADER;
+
+/// Get the catalog selected by [GREPTIME_CATALOG_HEADER], an empty or non-ASCII
+/// header value is ignored.
+pub(crate) fn catalog_from_headers(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(GREPTIME_CATALOG_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|catalog| !catalog.is_empty())
+}
 
 /// create query context from database name information, catalog and schema are
-/// resolved from the name
+/// resolved from the name, or the catalog header if it's provided. The JSON responses
+/// collect the whole output, so the context rejects the queries never finishing.
 pub(crate) async fn query_context_from_db(
     query_handler: ServerSqlQueryHandlerRef,
+    catalog: Option<&str>,
     db: Option<String>,
 ) -> std::result::Result<Arc<QueryContext>, JsonResponse> {
-    if let Some(db) = &db {
-        let (catalog, schema) = super::parse_catalog_and_schema_from_client_database_name(db);
-
-        match query_handler.is_valid_schema(catalog, schema).await {
-            Ok(true) => Ok(Arc::new(QueryContext::with(catalog, schema))),
-            Ok(false) => Err(JsonResponse::with_error(
-                format!("Database not found: {db}"),
-                StatusCode::DatabaseNotFound,
-            )),
-            Err(e) => Err(JsonResponse::with_error(
-                format!("Error checking database: {db}, {e}"),
-                StatusCode::Internal,
-            )),
+    let (catalog, schema) = match (catalog, &db) {
+        (_, Some(db)) => super::resolve_catalog_and_schema(catalog, db),
+        (Some(catalog), None) => (catalog, DEFAULT_SCHEMA_NAME),
+        (None, None) => {
+            let query_ctx = QueryContext::arc();
+            query_ctx.set_collects_output(true);
+            return Ok(query_ctx);
+        }
+    };
+    let db = build_db_string(catalog, schema);
+
+    match query_handler.is_valid_schema(catalog, schema).await {
+        Ok(true) => {
+            let query_ctx = Arc::new(QueryContext::with(catalog, schema));
+            query_ctx.set_collects_output(true);
+            Ok(query_ctx)
         }
-    } else {
-        Ok(QueryContext::arc())
+        Ok(false) => Err(JsonResponse::with_error(
+            format!("Database not found: {db}"),
+            StatusCode::DatabaseNotFound,
+        )),
+        Err(e) => Err(JsonResponse::with_error(
+            format!("Error checking database: {db}, {e}"),
+            StatusCode::Internal,
+        )),
     }
 }
 
@@ -100,6 +134,14 @@ pub const HTTP_API_PREFIX: &str = "/v1/";
 // TODO(fys): This is a temporary workaround, it will be improved later
 pub static PUBLIC_APIS: [&str; 2] = ["/v1/influxdb/ping", "/v1/influxdb/health"];
 
+/// APIs honoring the database aliases, including the query APIs of [crate::prom::PromServer].
+pub static DATABASE_ALIAS_APIS: [&str; 4] = [
+    "/v1/influxdb",
+    "/v1/opentsdb",
+    "/v1/prometheus",
+    "/api/v1/query",
+];
+
 #[derive(Default)]
 pub struct HttpServer {
     sql_handler: Option<ServerSqlQueryHandlerRef>,
@@ -112,6 +154,8 @@ pub struct HttpServer {
     shutdown_tx: Mutex<Option<Sender<()>>>,
     user_provider: Option<UserProviderRef>,
     metrics_handler: Option<MetricsHandler>,
+    health_checker: Option<HealthCheckerRef>,
+    database_aliases: DatabaseAliasesRef,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -124,6 +168,9 @@ pub struct HttpOptions {
 
     #[serde(skip)]
     pub disable_dashboard: bool,
+
+    /// Mappings of the events posted to `/v1/events/{table}`, keyed by table name.
+    pub event_mappings: HashMap<String, EventMapping>,
 }
 
 impl Default for HttpOptions {
@@ -132,6 +179,7 @@ impl Default for HttpOptions {
             addr: "127.0.0.1:4000".to_string(),
             timeout: Duration::from_secs(30),
             disable_dashboard: false,
+            event_mappings: HashMap::new(),
         }
     }
 }
@@ -140,11 +188,39 @@ impl Default for HttpOptions {
 pub struct ColumnSchema {
     name: String,
     data_type: String,
+    /// Semantic type of the column, one of `timestamp`, `tag` and `field`, only present
+    /// for columns referring to table columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    semantic_type: Option<String>,
+    /// Unit of the column values declared by the table column, e.g. `bytes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unit: Option<String>,
+    /// Precision of timestamp columns, one of `s`, `ms`, `us` and `ns`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    precision: Option<String>,
 }
 
 impl ColumnSchema {
     pub fn new(name: String, data_type: String) -> ColumnSchema {
-        ColumnSchema { name, data_type }
+        ColumnSchema {
+            name,
+            data_type,
+            semantic_type: None,
+            unit: None,
+            precision: None,
+        }
+    }
+}
+
+impl From<&datatypes::schema::ColumnSchema> for ColumnSchema {
+    fn from(column_schema: &datatypes::schema::ColumnSchema) -> Self {
+        ColumnSchema {
+            name: column_schema.name.clone(),
+            data_type: column_schema.data_type.name().to_owned(),
+            semantic_type: column_schema.metadata().get(SEMANTIC_TYPE_KEY).cloned(),
+            unit: column_schema.unit().map(str::to_string),
+            precision: column_schema.timestamp_precision().map(str::to_string),
+        }
     }
 }
 
@@ -208,10 +284,7 @@ impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
                     .schema
                     .column_schemas()
                     .iter()
-                    .map(|cs| ColumnSchema {
-                        name: cs.name.clone(),
-                        data_type: cs.data_type.name().to_owned(),
-                    })
+                    .map(ColumnSchema::from)
                     .collect(),
             };
 
@@ -253,6 +326,28 @@ pub struct JsonResponse {
     output: Option<Vec<JsonOutput>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     execution_time_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    execution_stats: Option<ExecutionStats>,
+}
+
+/// Cost of executing the queries of a request.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
+pub struct ExecutionStats {
+    pub elapsed_ms: u64,
+    pub rows_scanned: u64,
+    pub bytes_read: u64,
+    pub peak_memory_bytes: u64,
+}
+
+impl From<common_recordbatch::ExecutionStats> for ExecutionStats {
+    fn from(stats: common_recordbatch::ExecutionStats) -> Self {
+        ExecutionStats {
+            elapsed_ms: stats.elapsed_ms,
+            rows_scanned: stats.rows_scanned,
+            bytes_read: stats.bytes_read,
+            peak_memory_bytes: stats.peak_memory_bytes,
+        }
+    }
 }
 
 impl JsonResponse {
@@ -262,6 +357,7 @@ impl JsonResponse {
             code: error_code as u32,
             output: None,
             execution_time_ms: None,
+            execution_stats: None,
         }
     }
 
@@ -271,6 +367,7 @@ impl JsonResponse {
             code: StatusCode::Success as u32,
             output,
             execution_time_ms: None,
+            execution_stats: None,
         }
     }
 
@@ -279,11 +376,17 @@ impl JsonResponse {
         self
     }
 
+    fn with_execution_stats(mut self, stats: Option<common_recordbatch::ExecutionStats>) -> Self {
+        self.execution_stats = stats.map(ExecutionStats::from);
+        self
+    }
+
     /// Create a json response from query result
     async fn from_output(outputs: Vec<Result<Output>>) -> Self {
         // TODO(sunng87): this api response structure cannot represent error
         // well. It hides successful execution results from error response
         let mut results = Vec::with_capacity(outputs.len());
+        let mut execution_stats: Option<common_recordbatch::ExecutionStats> = None;
         for out in outputs {
             match out {
                 Ok(Output::AffectedRows(rows)) => {
@@ -291,10 +394,15 @@ impl JsonResponse {
                 }
                 Ok(Output::Stream(stream)) => {
                     // TODO(sunng87): streaming response
-                    match util::collect(stream).await {
-                        Ok(rows) => match HttpRecordsOutput::try_from(rows) {
+                    match util::collect_with_stats(stream).await {
+                        Ok((rows, stats)) => match HttpRecordsOutput::try_from(rows) {
                             Ok(rows) => {
                                 results.push(JsonOutput::Records(rows));
+                                if let Some(stats) = stats {
+                                    execution_stats
+                                        .get_or_insert_with(Default::default)
+                                        .merge(&stats);
+                                }
                             }
                             Err(err) => {
                                 return Self::with_error(err, StatusCode::Internal);
@@ -325,7 +433,7 @@ impl JsonResponse {
                 }
             }
         }
-        Self::with_output(Some(results))
+        Self::with_output(Some(results)).with_execution_stats(execution_stats)
     }
 
     pub fn code(&self) -> u32 {
@@ -347,6 +455,10 @@ impl JsonResponse {
     pub fn execution_time_ms(&self) -> Option<u128> {
         self.execution_time_ms
     }
+
+    pub fn execution_stats(&self) -> Option<&ExecutionStats> {
+        self.execution_stats.as_ref()
+    }
 }
 
 async fn serve_api(Extension(api): Extension<OpenApi>) -> impl IntoApiResponse {
@@ -381,6 +493,8 @@ impl HttpServerBuilder {
                 user_provider: None,
                 script_handler: None,
                 metrics_handler: None,
+                health_checker: None,
+                database_aliases: Default::default(),
                 shutdown_tx: Mutex::new(None),
             },
         }
@@ -425,6 +539,17 @@ impl HttpServerBuilder {
         self.inner.metrics_handler.get_or_insert(handler);
         self
     }
+
+    pub fn with_health_checker(&mut self, checker: HealthCheckerRef) -> &mut Self {
+        self.inner.health_checker.get_or_insert(checker);
+        self
+    }
+
+    pub fn with_database_aliases(&mut self, database_aliases: DatabaseAliasesRef) -> &mut Self {
+        self.inner.database_aliases = database_aliases;
+        self
+    }
+
     pub fn build(&mut self) -> HttpServer {
         std::mem::take(self).inner
     }
@@ -449,6 +574,11 @@ impl HttpServer {
         let mut router = Router::new();
 
         if let Some(sql_handler) = self.sql_handler.clone() {
+            router = router.nest(
+                &format!("/{HTTP_API_VERSION}/grafana"),
+                self.route_grafana(sql_handler.clone()),
+            );
+
             let sql_router = self
                 .route_sql(ApiState {
                     sql_handler,
@@ -464,6 +594,13 @@ impl HttpServer {
                 &format!("/{HTTP_API_VERSION}/admin"),
                 self.route_admin(grpc_handler.clone()),
             );
+            router = router.nest(
+                &format!("/{HTTP_API_VERSION}/events"),
+                self.route_events(EventsState {
+                    grpc_handler,
+                    mappings: Arc::new(self.options.event_mappings.clone()),
+                }),
+            );
         }
 
         if let Some(opentsdb_handler) = self.opentsdb_handler.clone() {
@@ -492,18 +629,23 @@ impl HttpServer {
         {
             router = router.nest(
                 &format!("/{HTTP_API_VERSION}/prof"),
-                Router::new().route("/mem", routing::get(crate::http::mem_prof::mem_prof)),
+                Router::new()
+                    .route("/mem", routing::get(crate::http::mem_prof::mem_prof))
+                    .route("/mem/stats", routing::get(crate::http::mem_prof::mem_stats)),
             );
         }
 
+        // change log level at runtime
+        router = router.route(
+            &format!("/{HTTP_API_VERSION}/log_level"),
+            routing::get(log_level::log_level).put(log_level::set_log_level),
+        );
+
         if let Some(metrics_handler) = self.metrics_handler {
             router = router.nest("", self.route_metrics(metrics_handler));
         }
 
-        router = router.route(
-            "/health",
-            routing::get(handler::health).post(handler::health),
-        );
+        router = router.nest("", self.route_health(self.health_checker.clone()));
 
         #[cfg(feature = "dashboard")]
         {
@@ -522,11 +664,21 @@ impl HttpServer {
                     .layer(TimeoutLayer::new(self.options.timeout))
                     // custom layer
                     .layer(AsyncRequireAuthorizationLayer::new(
-                        HttpAuth::<BoxBody>::new(self.user_provider.clone()),
+                        HttpAuth::<BoxBody>::new(self.user_provider.clone())
+                            .with_database_aliases(self.database_aliases.clone()),
                     )),
             )
     }
 
+    fn route_health<S>(&self, health_checker: Option<HealthCheckerRef>) -> Router<S> {
+        Router::new()
+            .route(
+                "/health",
+                routing::get(handler::health).post(handler::health),
+            )
+            .with_state(health_checker)
+    }
+
     fn route_metrics<S>(&self, metrics_handler: MetricsHandler) -> Router<S> {
         Router::new()
             .route("/metrics", routing::get(handler::metrics))
@@ -545,18 +697,33 @@ impl HttpServer {
                 apirouting::get_with(handler::promql, handler::sql_docs)
                     .post_with(handler::promql, handler::sql_docs),
             )
+            .api_route(
+                "/cardinality",
+                apirouting::get_with(handler::cardinality, handler::sql_docs),
+            )
             .api_route("/scripts", apirouting::post(script::scripts))
             .api_route("/run-script", apirouting::post(script::run_script))
+            .route("/live", routing::get(live::live))
+            .route("/migrate", routing::post(migrate::migrate))
             .route("/private/api.json", apirouting::get(serve_api))
             .route("/private/docs", apirouting::get(serve_docs))
             .with_state(api_state)
     }
 
+    fn route_grafana<S>(&self, sql_handler: ServerSqlQueryHandlerRef) -> Router<S> {
+        Router::new()
+            .route("/", routing::get(grafana::health))
+            .route("/search", routing::post(grafana::search))
+            .route("/query", routing::post(grafana::query))
+            .with_state(sql_handler)
+    }
+
     fn route_prom<S>(&self, prom_handler: PrometheusProtocolHandlerRef) -> Router<S> {
         Router::new()
             .route("/write", routing::post(prometheus::remote_write))
             .route("/read", routing::post(prometheus::remote_read))
             .with_state(prom_handler)
+            .layer(Extension(self.database_aliases.clone()))
     }
 
     fn route_influxdb<S>(&self, influxdb_handler: InfluxdbLineProtocolHandlerRef) -> Router<S> {
@@ -565,12 +732,14 @@ impl HttpServer {
             .route("/ping", routing::get(influxdb_ping))
             .route("/health", routing::get(influxdb_health))
             .with_state(influxdb_handler)
+            .layer(Extension(self.database_aliases.clone()))
     }
 
     fn route_opentsdb<S>(&self, opentsdb_handler: OpentsdbProtocolHandlerRef) -> Router<S> {
         Router::new()
             .route("/api/put", routing::post(opentsdb::put))
             .with_state(opentsdb_handler)
+            .layer(Extension(self.database_aliases.clone()))
     }
 
     fn route_admin<S>(&self, grpc_handler: ServerGrpcQueryHandlerRef) -> Router<S> {
@@ -578,6 +747,12 @@ impl HttpServer {
             .route("/flush", routing::post(flush))
             .with_state(grpc_handler)
     }
+
+    fn route_events<S>(&self, events_state: EventsState) -> Router<S> {
+        Router::new()
+            .route("/:table", routing::post(events::events))
+            .with_state(events_state)
+    }
 }
 
 pub const HTTP_SERVER: &str = "HTTP_SERVER";
@@ -646,7 +821,7 @@ mod test {
     use axum_test_helper::TestClient;
     use common_recordbatch::RecordBatches;
     use datatypes::prelude::*;
-    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::schema::{ColumnSchema, Schema, UNIT_KEY};
     use datatypes::vectors::{StringVector, UInt32Vector};
     use query::parser::PromQuery;
     use session::context::QueryContextRef;
@@ -782,4 +957,44 @@ mod test {
             panic!("invalid output type");
         }
     }
+
+    #[test]
+    fn test_column_schema_semantic_type() {
+        let column_schema = ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_metadata(HashMap::from([(
+            SEMANTIC_TYPE_KEY.to_string(),
+            "timestamp".to_string(),
+        )]));
+        let column_schema = super::ColumnSchema::from(&column_schema);
+        assert_eq!(column_schema.semantic_type.as_deref(), Some("timestamp"));
+        assert_eq!(column_schema.precision.as_deref(), Some("ms"));
+        assert_eq!(
+            r#"{"name":"ts","data_type":"TimestampMillisecond","semantic_type":"timestamp","precision":"ms"}"#,
+            serde_json::to_string(&column_schema).unwrap()
+        );
+
+        let column_schema = ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), true)
+            .with_metadata(HashMap::from([
+                (SEMANTIC_TYPE_KEY.to_string(), "field".to_string()),
+                (UNIT_KEY.to_string(), "bytes".to_string()),
+            ]));
+        assert_eq!(
+            r#"{"name":"memory","data_type":"Float64","semantic_type":"field","unit":"bytes"}"#,
+            serde_json::to_string(&super::ColumnSchema::from(&column_schema)).unwrap()
+        );
+
+        let column_schema = super::ColumnSchema::from(&ColumnSchema::new(
+            "number",
+            ConcreteDataType::uint32_datatype(),
+            true,
+        ));
+        assert_eq!(
+            r#"{"name":"number","data_type":"UInt32"}"#,
+            serde_json::to_string(&column_schema).unwrap()
+        );
+    }
 }
@@ -16,6 +16,7 @@ use std::marker::PhantomData;
 
 use axum::http::{self, Request, StatusCode};
 use axum::response::Response;
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_error::prelude::ErrorExt;
 use common_telemetry::warn;
 use futures::future::BoxFuture;
@@ -26,18 +27,22 @@ use session::context::UserInfo;
 use snafu::{ensure, OptionExt, ResultExt};
 use tower_http::auth::AsyncAuthorizeRequest;
 
-use super::PUBLIC_APIS;
+use super::{DATABASE_ALIAS_APIS, PUBLIC_APIS};
 use crate::auth::Error::IllegalParam;
-use crate::auth::{Identity, IllegalParamSnafu, UserProviderRef};
+use crate::auth::{Identity, UserProviderRef};
+use crate::database_alias::DatabaseAliasesRef;
 use crate::error::Error::Auth;
 use crate::error::{
     self, InvalidAuthorizationHeaderSnafu, InvisibleASCIISnafu, NotFoundInfluxAuthSnafu, Result,
     UnsupportedAuthSchemeSnafu,
 };
-use crate::http::HTTP_API_PREFIX;
+use crate::http::{catalog_from_headers, HTTP_API_PREFIX};
 
 pub struct HttpAuth<RespBody> {
     user_provider: Option<UserProviderRef>,
+    /// Aliases of the databases requested by [DATABASE_ALIAS_APIS], so users are
+    /// authorized against the databases their requests are served by.
+    database_aliases: Option<DatabaseAliasesRef>,
     _ty: PhantomData<RespBody>,
 }
 
@@ -45,15 +50,22 @@ impl<RespBody> HttpAuth<RespBody> {
     pub fn new(user_provider: Option<UserProviderRef>) -> Self {
         Self {
             user_provider,
+            database_aliases: None,
             _ty: PhantomData,
         }
     }
+
+    pub fn with_database_aliases(mut self, database_aliases: DatabaseAliasesRef) -> Self {
+        self.database_aliases = Some(database_aliases);
+        self
+    }
 }
 
 impl<RespBody> Clone for HttpAuth<RespBody> {
     fn clone(&self) -> Self {
         Self {
             user_provider: self.user_provider.clone(),
+            database_aliases: self.database_aliases.clone(),
             _ty: PhantomData,
         }
     }
@@ -70,6 +82,7 @@ where
 
     fn authorize(&mut self, mut request: Request<B>) -> Self::Future {
         let user_provider = self.user_provider.clone();
+        let database_aliases = self.database_aliases.clone();
         Box::pin(async move {
             let need_auth = need_auth(&request);
 
@@ -95,7 +108,7 @@ where
                 }
             };
 
-            let (catalog, schema) = match extract_catalog_and_schema(&request) {
+            let (catalog, schema) = match extract_catalog_and_schema(&request, database_aliases) {
                 Ok((catalog, schema)) => (catalog, schema),
                 Err(e) => {
                     warn!("extract catalog and schema failed: {}", e);
@@ -114,8 +127,8 @@ where
                 .auth(
                     Identity::UserId(username.as_str(), None),
                     crate::auth::Password::PlainText(password),
-                    catalog,
-                    schema,
+                    &catalog,
+                    &schema,
                 )
                 .await
             {
@@ -141,16 +154,24 @@ where
 
 fn extract_catalog_and_schema<B: Send + Sync + 'static>(
     request: &Request<B>,
-) -> crate::auth::Result<(&str, &str)> {
-    // try get database name
+    database_aliases: Option<DatabaseAliasesRef>,
+) -> crate::auth::Result<(String, String)> {
+    // The handlers use the default schema if db is not provided, in the catalog selected by
+    // the header or the default one.
     let query = request.uri().query().unwrap_or_default();
-    let input_database = extract_db_from_query(query).context(IllegalParamSnafu {
-        msg: "db not provided or corrupted",
-    })?;
+    let input_database = extract_db_from_query(query).unwrap_or(DEFAULT_SCHEMA_NAME);
+    let catalog = catalog_from_headers(request.headers());
 
-    Ok(crate::parse_catalog_and_schema_from_client_database_name(
-        input_database,
-    ))
+    let path = request.uri().path();
+    match database_aliases {
+        Some(database_aliases) if DATABASE_ALIAS_APIS.iter().any(|api| path.starts_with(api)) => {
+            Ok(database_aliases.resolve_catalog_and_schema(catalog, input_database))
+        }
+        _ => {
+            let (catalog, schema) = crate::resolve_catalog_and_schema(catalog, input_database);
+            Ok((catalog.to_string(), schema.to_string()))
+        }
+    }
 }
 
 fn get_influxdb_credentials<B: Send + Sync + 'static>(
@@ -307,10 +328,14 @@ fn extract_influxdb_user_from_query(query: &str) -> (Option<&str>, Option<&str>)
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
+    use std::collections::HashMap;
+    use std::sync::Arc;
 
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use secrecy::ExposeSecret;
 
     use super::*;
+    use crate::database_alias::{DatabaseAlias, DatabaseAliases};
 
     #[test]
     fn test_need_auth() {
@@ -413,6 +438,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_catalog_and_schema() {
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql?db=catalog-schema")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            ("catalog".to_string(), "schema".to_string()),
+            extract_catalog_and_schema(&req, None).unwrap()
+        );
+
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql?db=catalog-schema")
+            .header(crate::GREPTIME_CATALOG_HEADER, "header_catalog")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            ("header_catalog".to_string(), "catalog-schema".to_string()),
+            extract_catalog_and_schema(&req, None).unwrap()
+        );
+
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql")
+            .header(crate::GREPTIME_CATALOG_HEADER, "header_catalog")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            (
+                "header_catalog".to_string(),
+                DEFAULT_SCHEMA_NAME.to_string()
+            ),
+            extract_catalog_and_schema(&req, None).unwrap()
+        );
+
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql?db=")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            (
+                DEFAULT_CATALOG_NAME.to_string(),
+                DEFAULT_SCHEMA_NAME.to_string()
+            ),
+            extract_catalog_and_schema(&req, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_extract_catalog_and_schema_with_aliases() {
+        let aliases = Arc::new(DatabaseAliases::default());
+        aliases.replace(HashMap::from([(
+            "telegraf".to_string(),
+            DatabaseAlias {
+                catalog: "metrics".to_string(),
+                schema: "hosts".to_string(),
+            },
+        )]));
+
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/influxdb/write?db=telegraf")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            ("metrics".to_string(), "hosts".to_string()),
+            extract_catalog_and_schema(&req, Some(aliases.clone())).unwrap()
+        );
+
+        // Aliases are only honored by the ingestion protocols.
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql?db=telegraf")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            (DEFAULT_CATALOG_NAME.to_string(), "telegraf".to_string()),
+            extract_catalog_and_schema(&req, Some(aliases)).unwrap()
+        );
+    }
+
     #[test]
     fn test_extract_user() {
         assert_matches!(extract_influxdb_user_from_query(""), (None, None));
@@ -0,0 +1,506 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generic JSON events ingestion, exposed as `POST /v1/events/{table}`.
+//!
+//! Each event in the request body is mapped to a row of the target table. How an
+//! event is mapped is configured per table in [EventMapping]; tables without a
+//! mapping take every top level key of the event as a column.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use api::v1::greptime_request::Request;
+use api::v1::InsertRequest;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
+use common_grpc::writer::{LinesWriter, Precision};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use session::context::QueryContext;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{InvalidEventsSnafu, ParseEventsJsonSnafu, Result, WriteEventsSnafu};
+use crate::http::catalog_from_headers;
+use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
+use crate::resolve_catalog_and_schema;
+
+pub const DEFAULT_EVENTS_TIMESTAMP_COLUMN: &str = "ts";
+
+/// Describes how events posted to one table are turned into rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventMapping {
+    pub timestamp: TimestampMapping,
+    /// Columns extracted from the event. If empty, all top level keys of the event
+    /// are written: strings become tags, numbers and booleans become fields.
+    pub columns: Vec<ColumnMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimestampMapping {
+    /// JSON pointer (RFC 6901) to the event time. Events without it are stamped
+    /// with the time they are received.
+    pub pointer: String,
+    pub column: String,
+    pub format: TimestampFormat,
+}
+
+impl Default for TimestampMapping {
+    fn default() -> Self {
+        Self {
+            pointer: format!("/{DEFAULT_EVENTS_TIMESTAMP_COLUMN}"),
+            column: DEFAULT_EVENTS_TIMESTAMP_COLUMN.to_string(),
+            format: TimestampFormat::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    Second,
+    #[default]
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+    /// A string like "2023-04-01T08:00:00Z".
+    Rfc3339,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    /// JSON pointer (RFC 6901) to the value, e.g. "/host/name".
+    pub pointer: String,
+    pub column: String,
+    #[serde(default)]
+    pub semantic_type: EventSemanticType,
+    /// Type the value is coerced to. Inferred from the JSON value if absent.
+    #[serde(default)]
+    pub data_type: Option<EventDataType>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSemanticType {
+    Tag,
+    #[default]
+    Field,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventDataType {
+    String,
+    Int64,
+    Uint64,
+    Float64,
+    Boolean,
+}
+
+#[derive(Clone)]
+pub struct EventsState {
+    pub grpc_handler: ServerGrpcQueryHandlerRef,
+    pub mappings: Arc<HashMap<String, EventMapping>>,
+}
+
+#[axum_macros::debug_handler]
+pub async fn events(
+    State(state): State<EventsState>,
+    Path(table): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<impl IntoResponse> {
+    let db = params
+        .get("db")
+        .cloned()
+        .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string());
+    let (catalog, schema) = resolve_catalog_and_schema(catalog_from_headers(&headers), &db);
+    let ctx = Arc::new(QueryContext::with(catalog, schema));
+
+    let payload: Value = serde_json::from_str(&body).context(ParseEventsJsonSnafu)?;
+    let default_mapping = EventMapping::default();
+    let mapping = state.mappings.get(&table).unwrap_or(&default_mapping);
+    let request = events_to_insert_request(&table, payload, mapping)?;
+
+    let _ = state
+        .grpc_handler
+        .do_query(Request::Insert(request), ctx)
+        .await?;
+    Ok((StatusCode::NO_CONTENT, ()))
+}
+
+/// Converts the posted payload, either a single event object or an array of them,
+/// into one insert request of `table`.
+pub(crate) fn events_to_insert_request(
+    table: &str,
+    payload: Value,
+    mapping: &EventMapping,
+) -> Result<InsertRequest> {
+    let events = match payload {
+        Value::Array(events) => events,
+        event @ Value::Object(_) => vec![event],
+        _ => {
+            return InvalidEventsSnafu {
+                reason: "expect a JSON object or an array of JSON objects",
+            }
+            .fail()
+        }
+    };
+    ensure!(
+        !events.is_empty(),
+        InvalidEventsSnafu {
+            reason: "no events to write",
+        }
+    );
+
+    let mut writer = LinesWriter::with_lines(events.len());
+    let now = common_time::util::current_time_millis();
+    for event in &events {
+        ensure!(
+            event.is_object(),
+            InvalidEventsSnafu {
+                reason: format!("event is not a JSON object: {event}"),
+            }
+        );
+
+        let ts = match event.pointer(&mapping.timestamp.pointer) {
+            Some(value) if !value.is_null() => extract_timestamp(value, mapping.timestamp.format)?,
+            _ => now,
+        };
+        writer
+            .write_ts(&mapping.timestamp.column, (ts, Precision::Millisecond))
+            .context(WriteEventsSnafu)?;
+
+        if mapping.columns.is_empty() {
+            write_top_level_keys(&mut writer, event, &mapping.timestamp)?;
+        } else {
+            for column in &mapping.columns {
+                if let Some(value) = event.pointer(&column.pointer) {
+                    write_column(&mut writer, column, value)?;
+                }
+            }
+        }
+        writer.commit();
+    }
+
+    let (columns, row_count) = writer.finish();
+    Ok(InsertRequest {
+        table_name: table.to_string(),
+        region_number: 0,
+        columns,
+        row_count,
+    })
+}
+
+fn write_top_level_keys(
+    writer: &mut LinesWriter,
+    event: &Value,
+    timestamp: &TimestampMapping,
+) -> Result<()> {
+    // Safety: callers have checked the event is an object.
+    let object = event.as_object().unwrap();
+    for (key, value) in object {
+        if timestamp.pointer.strip_prefix('/') == Some(key.as_str()) {
+            continue;
+        }
+        let semantic_type = if value.is_string() {
+            EventSemanticType::Tag
+        } else {
+            EventSemanticType::Field
+        };
+        let column = ColumnMapping {
+            pointer: String::new(),
+            column: key.clone(),
+            semantic_type,
+            data_type: None,
+        };
+        write_column(writer, &column, value)?;
+    }
+    Ok(())
+}
+
+fn write_column(writer: &mut LinesWriter, column: &ColumnMapping, value: &Value) -> Result<()> {
+    if value.is_null() {
+        return Ok(());
+    }
+    let name = column.column.as_str();
+
+    if column.semantic_type == EventSemanticType::Tag {
+        let tag = match value {
+            Value::String(s) => s.clone(),
+            Value::Number(_) | Value::Bool(_) => value.to_string(),
+            _ => return invalid_value(name, value, "tag"),
+        };
+        return writer.write_tag(name, &tag).context(WriteEventsSnafu);
+    }
+
+    let data_type = match column.data_type {
+        Some(data_type) => data_type,
+        None => infer_data_type(value).with_context(|| InvalidEventsSnafu {
+            reason: format!("unsupported value of column {name}: {value}"),
+        })?,
+    };
+    match data_type {
+        EventDataType::String => {
+            let s = match value {
+                Value::String(s) => s.clone(),
+                _ => value.to_string(),
+            };
+            writer.write_string(name, &s)
+        }
+        EventDataType::Int64 => {
+            let v = match value {
+                Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+                Value::String(s) => s.trim().parse().ok(),
+                Value::Bool(b) => Some(*b as i64),
+                _ => None,
+            };
+            let Some(v) = v else { return invalid_value(name, value, "int64") };
+            writer.write_i64(name, v)
+        }
+        EventDataType::Uint64 => {
+            let v = match value {
+                Value::Number(n) => n.as_u64(),
+                Value::String(s) => s.trim().parse().ok(),
+                Value::Bool(b) => Some(*b as u64),
+                _ => None,
+            };
+            let Some(v) = v else { return invalid_value(name, value, "uint64") };
+            writer.write_u64(name, v)
+        }
+        EventDataType::Float64 => {
+            let v = match value {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            };
+            let Some(v) = v else { return invalid_value(name, value, "float64") };
+            writer.write_f64(name, v)
+        }
+        EventDataType::Boolean => {
+            let v = match value {
+                Value::Bool(b) => Some(*b),
+                Value::String(s) => s.trim().parse().ok(),
+                Value::Number(n) => n.as_i64().map(|i| i != 0),
+                _ => None,
+            };
+            let Some(v) = v else { return invalid_value(name, value, "boolean") };
+            writer.write_bool(name, v)
+        }
+    }
+    .context(WriteEventsSnafu)
+}
+
+fn infer_data_type(value: &Value) -> Option<EventDataType> {
+    match value {
+        Value::String(_) => Some(EventDataType::String),
+        Value::Bool(_) => Some(EventDataType::Boolean),
+        Value::Number(n) if n.is_i64() => Some(EventDataType::Int64),
+        Value::Number(n) if n.is_u64() => Some(EventDataType::Uint64),
+        Value::Number(_) => Some(EventDataType::Float64),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+fn invalid_value<T>(column: &str, value: &Value, expected: &str) -> Result<T> {
+    InvalidEventsSnafu {
+        reason: format!("cannot convert value {value} of column {column} to {expected}"),
+    }
+    .fail()
+}
+
+/// Extracts the event time in milliseconds.
+fn extract_timestamp(value: &Value, format: TimestampFormat) -> Result<i64> {
+    if format == TimestampFormat::Rfc3339 {
+        let ts = value
+            .as_str()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .with_context(|| InvalidEventsSnafu {
+                reason: format!("invalid RFC 3339 timestamp: {value}"),
+            })?;
+        return Ok(ts.timestamp_millis());
+    }
+
+    let ts = match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+    .with_context(|| InvalidEventsSnafu {
+        reason: format!("invalid timestamp: {value}"),
+    })?;
+    let millis = match format {
+        TimestampFormat::Second => ts * 1_000.0,
+        TimestampFormat::Millisecond => ts,
+        TimestampFormat::Microsecond => ts / 1_000.0,
+        TimestampFormat::Nanosecond => ts / 1_000_000.0,
+        TimestampFormat::Rfc3339 => unreachable!(),
+    };
+    Ok(millis as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::column::{SemanticType, Values};
+    use api::v1::{Column, ColumnDataType};
+    use serde_json::json;
+
+    use super::*;
+
+    fn find_column<'a>(request: &'a InsertRequest, name: &str) -> &'a Column {
+        request
+            .columns
+            .iter()
+            .find(|c| c.column_name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_events_without_mapping() {
+        let payload = json!([
+            {"ts": 1000, "host": "h1", "cpu": 0.5, "up": true},
+            {"ts": 2000, "host": "h2", "cpu": 0.7, "count": 3},
+        ]);
+        let request =
+            events_to_insert_request("monitor", payload, &EventMapping::default()).unwrap();
+        assert_eq!("monitor", request.table_name);
+        assert_eq!(2, request.row_count);
+        assert_eq!(5, request.columns.len());
+
+        let ts = find_column(&request, "ts");
+        assert_eq!(SemanticType::Timestamp as i32, ts.semantic_type);
+        assert_eq!(
+            vec![1000, 2000],
+            ts.values.as_ref().unwrap().ts_millisecond_values
+        );
+
+        let host = find_column(&request, "host");
+        assert_eq!(SemanticType::Tag as i32, host.semantic_type);
+        assert_eq!(
+            vec!["h1".to_string(), "h2".to_string()],
+            host.values.as_ref().unwrap().string_values
+        );
+
+        let cpu = find_column(&request, "cpu");
+        assert_eq!(ColumnDataType::Float64 as i32, cpu.datatype);
+        assert_eq!(vec![0.5, 0.7], cpu.values.as_ref().unwrap().f64_values);
+
+        let up = find_column(&request, "up");
+        assert_eq!(vec![true], up.values.as_ref().unwrap().bool_values);
+        assert_eq!(vec![2], up.null_mask);
+
+        let count = find_column(&request, "count");
+        assert_eq!(vec![3], count.values.as_ref().unwrap().i64_values);
+        assert_eq!(vec![1], count.null_mask);
+    }
+
+    #[test]
+    fn test_events_with_mapping() {
+        let mapping = EventMapping {
+            timestamp: TimestampMapping {
+                pointer: "/event/time".to_string(),
+                column: "greptime_timestamp".to_string(),
+                format: TimestampFormat::Rfc3339,
+            },
+            columns: vec![
+                ColumnMapping {
+                    pointer: "/source/service".to_string(),
+                    column: "service".to_string(),
+                    semantic_type: EventSemanticType::Tag,
+                    data_type: None,
+                },
+                ColumnMapping {
+                    pointer: "/event/latency".to_string(),
+                    column: "latency".to_string(),
+                    semantic_type: EventSemanticType::Field,
+                    data_type: Some(EventDataType::Float64),
+                },
+                ColumnMapping {
+                    pointer: "/event/status".to_string(),
+                    column: "status".to_string(),
+                    semantic_type: EventSemanticType::Field,
+                    data_type: Some(EventDataType::Int64),
+                },
+            ],
+        };
+        let payload = json!({
+            "source": {"service": "billing", "ignored": 1},
+            "event": {"time": "1970-01-01T00:00:01.5Z", "latency": "12.5", "status": "200"},
+        });
+        let request = events_to_insert_request("requests", payload, &mapping).unwrap();
+        assert_eq!(1, request.row_count);
+        assert_eq!(4, request.columns.len());
+
+        let ts = find_column(&request, "greptime_timestamp");
+        assert_eq!(
+            vec![1500],
+            ts.values.as_ref().unwrap().ts_millisecond_values
+        );
+        let service = find_column(&request, "service");
+        assert_eq!(
+            Values {
+                string_values: vec!["billing".to_string()],
+                ..Default::default()
+            },
+            service.values.clone().unwrap()
+        );
+        let latency = find_column(&request, "latency");
+        assert_eq!(vec![12.5], latency.values.as_ref().unwrap().f64_values);
+        let status = find_column(&request, "status");
+        assert_eq!(vec![200], status.values.as_ref().unwrap().i64_values);
+    }
+
+    #[test]
+    fn test_extract_timestamp() {
+        assert_eq!(
+            1500,
+            extract_timestamp(&json!(1.5), TimestampFormat::Second).unwrap()
+        );
+        assert_eq!(
+            1000,
+            extract_timestamp(&json!("1000"), TimestampFormat::Millisecond).unwrap()
+        );
+        assert_eq!(
+            2,
+            extract_timestamp(&json!(2_000_000), TimestampFormat::Nanosecond).unwrap()
+        );
+        assert!(extract_timestamp(&json!("foo"), TimestampFormat::Second).is_err());
+        assert!(extract_timestamp(&json!(1), TimestampFormat::Rfc3339).is_err());
+    }
+
+    #[test]
+    fn test_invalid_events() {
+        let mapping = EventMapping::default();
+        assert!(events_to_insert_request("t", json!(1), &mapping).is_err());
+        assert!(events_to_insert_request("t", json!([]), &mapping).is_err());
+        assert!(events_to_insert_request("t", json!([1, 2]), &mapping).is_err());
+        assert!(events_to_insert_request("t", json!([{"ts": "now"}]), &mapping).is_err());
+
+        let mapping = EventMapping {
+            columns: vec![ColumnMapping {
+                pointer: "/v".to_string(),
+                column: "v".to_string(),
+                semantic_type: EventSemanticType::Field,
+                data_type: Some(EventDataType::Int64),
+            }],
+            ..Default::default()
+        };
+        assert!(events_to_insert_request("t", json!({"v": "abc"}), &mapping).is_err());
+    }
+}
@@ -0,0 +1,663 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Endpoints of the Grafana [SimpleJSON] datasource contract, exposed under `/v1/grafana`.
+//!
+//! - `GET /` tests the connection of the datasource.
+//! - `POST /search` lists the tables whose names contain the `target` of request.
+//! - `POST /query` evaluates each target as SQL, or PromQL if the `data.language` of the
+//!   target is `promql`, and returns the result as time series or table.
+//!
+//! In SQL targets, `$__timeFrom` and `$__timeTo` are replaced by the time range of the
+//! request as timestamp literals, except in quoted strings and identifiers.
+//!
+//! [SimpleJSON]: https://github.com/grafana/simple-json-datasource
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode as HttpStatusCode};
+use axum::{Extension, Json};
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch};
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use datatypes::data_type::ConcreteDataType;
+use datatypes::value::Value;
+use query::parser::PromQuery;
+use serde::{Deserialize, Serialize};
+use session::context::{QueryContext, QueryContextRef, UserInfo};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{
+    CollectRecordbatchSnafu, DatabaseNotFoundSnafu, InvalidGrafanaQuerySnafu, Result,
+};
+use crate::http::catalog_from_headers;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+use crate::resolve_catalog_and_schema;
+
+const TIME_FROM_MACRO: &str = "$__timeFrom";
+const TIME_TO_MACRO: &str = "$__timeTo";
+const DEFAULT_PROMQL_STEP: &str = "15s";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GrafanaParams {
+    pub db: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchRequest {
+    #[serde(default)]
+    pub target: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    pub range: Option<TimeRange>,
+    pub interval_ms: Option<u64>,
+    #[serde(default)]
+    pub targets: Vec<Target>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Target {
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub ref_id: String,
+    #[serde(default, rename = "type")]
+    pub format: TargetFormat,
+    #[serde(default)]
+    pub hide: bool,
+    #[serde(default)]
+    pub data: TargetData,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetFormat {
+    #[default]
+    Timeserie,
+    Table,
+}
+
+/// Additional data of a target, set by the "Additional JSON Data" of the datasource.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TargetData {
+    #[serde(default)]
+    pub language: QueryLanguage,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryLanguage {
+    #[default]
+    Sql,
+    Promql,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum QueryResult {
+    TimeSeries {
+        target: String,
+        /// Pairs of value and timestamp in milliseconds.
+        datapoints: Vec<(Option<f64>, i64)>,
+    },
+    Table {
+        #[serde(rename = "type")]
+        kind: String,
+        columns: Vec<TableColumn>,
+        rows: Vec<Vec<serde_json::Value>>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableColumn {
+    pub text: String,
+    #[serde(rename = "type")]
+    pub column_type: String,
+}
+
+/// Handler to test the connection of the datasource.
+#[axum_macros::debug_handler]
+pub async fn health() -> HttpStatusCode {
+    HttpStatusCode::OK
+}
+
+/// Handler to list the tables for the metric picker.
+#[axum_macros::debug_handler]
+pub async fn search(
+    State(handler): State<ServerSqlQueryHandlerRef>,
+    Query(params): Query<GrafanaParams>,
+    Extension(user_info): Extension<UserInfo>,
+    headers: HeaderMap,
+    Json(request): Json<SearchRequest>,
+) -> Result<Json<Vec<String>>> {
+    let ctx = query_context(&handler, &headers, params.db).await?;
+    ctx.set_current_user(user_info);
+
+    let mut tables = Vec::new();
+    for batch in execute_sql(&handler, "SHOW TABLES", ctx).await? {
+        for row in batch.rows() {
+            if let Some(Value::String(table)) = row.first() {
+                let table = table.as_utf8();
+                if table.contains(&request.target) {
+                    tables.push(table.to_string());
+                }
+            }
+        }
+    }
+    Ok(Json(tables))
+}
+
+/// Handler to evaluate the targets of a panel.
+#[axum_macros::debug_handler]
+pub async fn query(
+    State(handler): State<ServerSqlQueryHandlerRef>,
+    Query(params): Query<GrafanaParams>,
+    Extension(user_info): Extension<UserInfo>,
+    headers: HeaderMap,
+    Json(request): Json<QueryRequest>,
+) -> Result<Json<Vec<QueryResult>>> {
+    let ctx = query_context(&handler, &headers, params.db).await?;
+    ctx.set_current_user(user_info);
+
+    let mut results = Vec::with_capacity(request.targets.len());
+    for target in request.targets.iter().filter(|target| !target.hide) {
+        let batches = match target.data.language {
+            QueryLanguage::Sql => {
+                let sql = expand_time_macros(&target.target, request.range.as_ref())?;
+                execute_sql(&handler, &sql, ctx.clone()).await?
+            }
+            QueryLanguage::Promql => {
+                let range = request.range.as_ref().context(InvalidGrafanaQuerySnafu {
+                    reason: "time range is required by PromQL targets",
+                })?;
+                let query = PromQuery {
+                    query: target.target.clone(),
+                    start: range.from.clone(),
+                    end: range.to.clone(),
+                    step: request
+                        .interval_ms
+                        .map(|interval| format!("{}ms", interval.max(1)))
+                        .unwrap_or_else(|| DEFAULT_PROMQL_STEP.to_string()),
+                };
+                collect_outputs(handler.do_promql_query(&query, ctx.clone()).await).await?
+            }
+        };
+
+        match target.format {
+            TargetFormat::Timeserie => results.extend(to_time_series(&target.target, &batches)?),
+            TargetFormat::Table => results.push(to_table(&batches)),
+        }
+    }
+    Ok(Json(results))
+}
+
+async fn query_context(
+    handler: &ServerSqlQueryHandlerRef,
+    headers: &HeaderMap,
+    db: Option<String>,
+) -> Result<QueryContextRef> {
+    let Some(db) = db else {
+        let ctx = QueryContext::arc();
+        if let Some(catalog) = catalog_from_headers(headers) {
+            ctx.set_current_catalog(catalog);
+        }
+        return Ok(ctx);
+    };
+    let (catalog, schema) = resolve_catalog_and_schema(catalog_from_headers(headers), &db);
+    ensure!(
+        handler.is_valid_schema(catalog, schema).await?,
+        DatabaseNotFoundSnafu { catalog, schema }
+    );
+    Ok(Arc::new(QueryContext::with(catalog, schema)))
+}
+
+/// Replaces the time macros in SQL by the time range, which are validated as timestamps
+/// so they can't break out of the literals.
+fn expand_time_macros(sql: &str, range: Option<&TimeRange>) -> Result<String> {
+    let macros = find_time_macros(sql);
+    if macros.is_empty() {
+        return Ok(sql.to_string());
+    }
+    let range = range.context(InvalidGrafanaQuerySnafu {
+        reason: "time range is required by time macros",
+    })?;
+    let literal = |time: &str| {
+        time.parse::<Timestamp>()
+            .ok()
+            .map(|ts| format!("'{}'", ts.to_iso8601_string()))
+            .with_context(|| InvalidGrafanaQuerySnafu {
+                reason: format!("invalid time {time}"),
+            })
+    };
+    let (from, to) = (literal(&range.from)?, literal(&range.to)?);
+
+    let mut expanded = String::with_capacity(sql.len());
+    let mut last = 0;
+    for (offset, time_macro) in macros {
+        expanded.push_str(&sql[last..offset]);
+        expanded.push_str(if time_macro == TIME_FROM_MACRO {
+            &from
+        } else {
+            &to
+        });
+        last = offset + time_macro.len();
+    }
+    expanded.push_str(&sql[last..]);
+    Ok(expanded)
+}
+
+/// Finds the time macros in SQL outside the quoted strings and identifiers, returns their
+/// byte offsets and the macros.
+fn find_time_macros(sql: &str) -> Vec<(usize, &'static str)> {
+    let bytes = sql.as_bytes();
+    let mut macros = Vec::new();
+    let mut quote = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match quote {
+            // A doubled quote escapes the quote, which is handled as closing the quoted region
+            // and opening another right after it.
+            Some(q) if bytes[i] == q => quote = None,
+            Some(_) => {}
+            None if matches!(bytes[i], b'\'' | b'"' | b'`') => quote = Some(bytes[i]),
+            None => {
+                if let Some(time_macro) = [TIME_FROM_MACRO, TIME_TO_MACRO]
+                    .into_iter()
+                    .find(|time_macro| bytes[i..].starts_with(time_macro.as_bytes()))
+                {
+                    macros.push((i, time_macro));
+                    i += time_macro.len();
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    macros
+}
+
+async fn execute_sql(
+    handler: &ServerSqlQueryHandlerRef,
+    sql: &str,
+    ctx: QueryContextRef,
+) -> Result<Vec<RecordBatch>> {
+    collect_outputs(handler.do_query(sql, ctx).await).await
+}
+
+/// Collects the record batches of the last output, the outputs before are from statements
+/// like `SET`.
+async fn collect_outputs(outputs: Vec<Result<Output>>) -> Result<Vec<RecordBatch>> {
+    let mut batches = Vec::new();
+    for output in outputs {
+        batches = match output? {
+            Output::AffectedRows(_) => Vec::new(),
+            Output::RecordBatches(output) => output.take(),
+            Output::Stream(stream) => util::collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+        };
+    }
+    Ok(batches)
+}
+
+enum ColumnKind {
+    Time,
+    Label,
+    Field,
+    Other,
+}
+
+fn column_kind(data_type: &ConcreteDataType) -> ColumnKind {
+    match data_type {
+        ConcreteDataType::Timestamp(_) => ColumnKind::Time,
+        ConcreteDataType::String(_) => ColumnKind::Label,
+        ConcreteDataType::Date(_) | ConcreteDataType::DateTime(_) => ColumnKind::Other,
+        t if t.is_float() || t.is_signed() || t.is_unsigned() || t.is_boolean() => {
+            ColumnKind::Field
+        }
+        _ => ColumnKind::Other,
+    }
+}
+
+/// Converts the rows to time series, one for each field column and each combination of
+/// the label columns. The first timestamp column is used as the time of data points.
+fn to_time_series(target: &str, batches: &[RecordBatch]) -> Result<Vec<QueryResult>> {
+    let Some(first) = batches.first() else {
+        return Ok(Vec::new());
+    };
+    let columns = first.schema.column_schemas();
+    let mut time_index = None;
+    let mut labels = Vec::new();
+    let mut fields = Vec::new();
+    for (i, column) in columns.iter().enumerate() {
+        match column_kind(&column.data_type) {
+            ColumnKind::Time if time_index.is_none() => time_index = Some(i),
+            ColumnKind::Label => labels.push(i),
+            ColumnKind::Field => fields.push(i),
+            _ => {}
+        }
+    }
+    let time_index = time_index.context(InvalidGrafanaQuerySnafu {
+        reason: format!("no timestamp column in the result of target {target}"),
+    })?;
+
+    // Series in the order of their first appearance.
+    let mut series: Vec<(String, Vec<(Option<f64>, i64)>)> = Vec::new();
+    let mut series_index = HashMap::new();
+    for batch in batches {
+        for row in batch.rows() {
+            let Some(ts) = timestamp_millis(&row[time_index]) else {
+                continue;
+            };
+            let label_values = labels
+                .iter()
+                .map(|i| {
+                    let value = match &row[*i] {
+                        Value::String(s) => s.as_utf8().to_string(),
+                        _ => String::new(),
+                    };
+                    format!("{}=\"{}\"", columns[*i].name, value)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            for field in &fields {
+                let name = &columns[*field].name;
+                let series_name = if label_values.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{name}{{{label_values}}}")
+                };
+                let index = *series_index.entry(series_name.clone()).or_insert_with(|| {
+                    series.push((series_name, Vec::new()));
+                    series.len() - 1
+                });
+                series[index].1.push((value_to_f64(&row[*field]), ts));
+            }
+        }
+    }
+
+    Ok(series
+        .into_iter()
+        .map(|(target, datapoints)| QueryResult::TimeSeries { target, datapoints })
+        .collect())
+}
+
+fn to_table(batches: &[RecordBatch]) -> QueryResult {
+    let columns = batches
+        .first()
+        .map(|batch| {
+            batch
+                .schema
+                .column_schemas()
+                .iter()
+                .map(|column| TableColumn {
+                    text: column.name.clone(),
+                    column_type: match column_kind(&column.data_type) {
+                        ColumnKind::Time => "time",
+                        ColumnKind::Field => "number",
+                        ColumnKind::Label | ColumnKind::Other => "string",
+                    }
+                    .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let rows = batches
+        .iter()
+        .flat_map(|batch| batch.rows())
+        .map(|row| row.into_iter().map(value_to_json).collect())
+        .collect();
+
+    QueryResult::Table {
+        kind: "table".to_string(),
+        columns,
+        rows,
+    }
+}
+
+fn timestamp_millis(value: &Value) -> Option<i64> {
+    match value {
+        Value::Timestamp(ts) => ts.convert_to(TimeUnit::Millisecond).map(|ts| ts.value()),
+        _ => None,
+    }
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Boolean(v) => Some(if *v { 1.0 } else { 0.0 }),
+        Value::Int8(v) => Some(*v as f64),
+        Value::Int16(v) => Some(*v as f64),
+        Value::Int32(v) => Some(*v as f64),
+        Value::Int64(v) => Some(*v as f64),
+        Value::UInt8(v) => Some(*v as f64),
+        Value::UInt16(v) => Some(*v as f64),
+        Value::UInt32(v) => Some(*v as f64),
+        Value::UInt64(v) => Some(*v as f64),
+        Value::Float32(v) => Some(v.0 as f64),
+        Value::Float64(v) => Some(v.0),
+        _ => None,
+    }
+}
+
+fn value_to_json(value: Value) -> serde_json::Value {
+    if let Some(ts) = timestamp_millis(&value) {
+        return ts.into();
+    }
+    serde_json::Value::try_from(value).unwrap_or(serde_json::Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{
+        BooleanVector, Float64Vector, StringVector, TimestampMillisecondVector,
+        TimestampSecondVector,
+    };
+
+    use super::*;
+
+    fn metrics_batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ]));
+        RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(StringVector::from(vec!["host1", "host2", "host1"])) as _,
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1000, 1000, 2000])) as _,
+                Arc::new(Float64Vector::from(vec![Some(1.0), Some(2.0), None])) as _,
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_time_series() {
+        let series = to_time_series("cpu", &[metrics_batch()]).unwrap();
+        assert_eq!(
+            vec![
+                QueryResult::TimeSeries {
+                    target: "cpu{host=\"host1\"}".to_string(),
+                    datapoints: vec![(Some(1.0), 1000), (None, 2000)],
+                },
+                QueryResult::TimeSeries {
+                    target: "cpu{host=\"host2\"}".to_string(),
+                    datapoints: vec![(Some(2.0), 1000)],
+                },
+            ],
+            series
+        );
+
+        let json = serde_json::to_string(&series[1]).unwrap();
+        assert_eq!(
+            r#"{"target":"cpu{host=\"host2\"}","datapoints":[[2.0,1000]]}"#,
+            json
+        );
+
+        assert!(to_time_series("cpu", &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_to_time_series_without_timestamp() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "cpu",
+            ConcreteDataType::float64_datatype(),
+            true,
+        )]));
+        let batch = RecordBatch::new(
+            schema,
+            vec![Arc::new(Float64Vector::from_slice([1.0])) as _],
+        )
+        .unwrap();
+        assert!(to_time_series("cpu", &[batch]).is_err());
+    }
+
+    #[test]
+    fn test_to_time_series_with_bool_field() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("up", ConcreteDataType::boolean_datatype(), true),
+        ]));
+        let batch = RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1000, 2000])) as _,
+                Arc::new(BooleanVector::from(vec![true, false])) as _,
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            vec![QueryResult::TimeSeries {
+                target: "up".to_string(),
+                datapoints: vec![(Some(1.0), 1000), (Some(0.0), 2000)],
+            }],
+            to_time_series("up", &[batch]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_table() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_second_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema,
+            vec![Arc::new(TimestampSecondVector::from_vec(vec![1])) as _],
+        )
+        .unwrap();
+        let table = to_table(&[metrics_batch(), batch]);
+        let json = serde_json::to_value(&table).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "type": "table",
+                "columns": [
+                    {"text": "host", "type": "string"},
+                    {"text": "ts", "type": "time"},
+                    {"text": "cpu", "type": "number"},
+                ],
+                "rows": [
+                    ["host1", 1000, 1.0],
+                    ["host2", 1000, 2.0],
+                    ["host1", 2000, null],
+                    [1000],
+                ],
+            }),
+            json
+        );
+    }
+
+    #[test]
+    fn test_expand_time_macros() {
+        let range = TimeRange {
+            from: "2023-01-01T00:00:00Z".to_string(),
+            to: "2023-01-01T01:00:00Z".to_string(),
+        };
+        let sql = "SELECT * FROM cpu WHERE ts >= $__timeFrom AND ts < $__timeTo";
+        let expanded = expand_time_macros(sql, Some(&range)).unwrap();
+        assert!(!expanded.contains("$__time"), "{expanded}");
+        assert!(expanded.contains("ts >= '2023-01-01"), "{expanded}");
+
+        assert_eq!("SELECT 1", expand_time_macros("SELECT 1", None).unwrap());
+        assert!(expand_time_macros(sql, None).is_err());
+
+        // macros in quoted strings and identifiers are kept
+        let quoted =
+            r#"SELECT '$__timeFrom', "$__timeTo", 'it''s $__timeTo' FROM cpu WHERE ts < $__timeTo"#;
+        let to = range.to.parse::<Timestamp>().unwrap().to_iso8601_string();
+        assert_eq!(
+            format!(
+                r#"SELECT '$__timeFrom', "$__timeTo", 'it''s $__timeTo' FROM cpu WHERE ts < '{to}'"#
+            ),
+            expand_time_macros(quoted, Some(&range)).unwrap()
+        );
+        let quoted = "SELECT '$__timeFrom' FROM cpu";
+        assert_eq!(quoted, expand_time_macros(quoted, None).unwrap());
+
+        let range = TimeRange {
+            from: "' OR 1=1 --".to_string(),
+            to: "2023-01-01T01:00:00Z".to_string(),
+        };
+        assert!(expand_time_macros(sql, Some(&range)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_query_request() {
+        let request: QueryRequest = serde_json::from_str(
+            r#"{
+                "range": {"from": "2023-01-01T00:00:00Z", "to": "2023-01-01T01:00:00Z"},
+                "intervalMs": 15000,
+                "maxDataPoints": 100,
+                "targets": [
+                    {"target": "SELECT 1", "refId": "A", "type": "table"},
+                    {"target": "up", "refId": "B", "data": {"language": "promql"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(Some(15000), request.interval_ms);
+        assert_eq!(2, request.targets.len());
+        assert_eq!(TargetFormat::Table, request.targets[0].format);
+        assert_eq!(QueryLanguage::Sql, request.targets[0].data.language);
+        assert_eq!(TargetFormat::Timeserie, request.targets[1].format);
+        assert_eq!(QueryLanguage::Promql, request.targets[1].data.language);
+    }
+}
@@ -17,6 +17,7 @@ use std::time::Instant;
 
 use aide::transform::TransformOperation;
 use axum::extract::{Json, Query, State};
+use axum::http::HeaderMap;
 use axum::{Extension, Form};
 use common_error::status_code::StatusCode;
 use common_telemetry::timer;
@@ -25,7 +26,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use session::context::UserInfo;
 
-use crate::http::{ApiState, JsonResponse};
+use crate::health_checker::HealthCheckerRef;
+use crate::http::{catalog_from_headers, ApiState, JsonResponse};
 use crate::metrics_handler::MetricsHandler;
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -39,8 +41,8 @@ pub struct SqlQuery {
 pub async fn sql(
     State(state): State<ApiState>,
     Query(query_params): Query<SqlQuery>,
-    // TODO(fys): pass _user_info into query context
-    _user_info: Extension<UserInfo>,
+    Extension(user_info): Extension<UserInfo>,
+    headers: HeaderMap,
     Form(form_params): Form<SqlQuery>,
 ) -> Json<JsonResponse> {
     let sql_handler = &state.sql_handler;
@@ -54,8 +56,15 @@ pub async fn sql(
     );
 
     let resp = if let Some(sql) = &sql {
-        match crate::http::query_context_from_db(sql_handler.clone(), db).await {
+        match crate::http::query_context_from_db(
+            sql_handler.clone(),
+            catalog_from_headers(&headers),
+            db,
+        )
+        .await
+        {
             Ok(query_ctx) => {
+                query_ctx.set_current_user(user_info);
                 JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await
             }
             Err(resp) => resp,
@@ -95,8 +104,8 @@ impl From<PromqlQuery> for PromQuery {
 pub async fn promql(
     State(state): State<ApiState>,
     Query(params): Query<PromqlQuery>,
-    // TODO(fys): pass _user_info into query context
-    _user_info: Extension<UserInfo>,
+    Extension(user_info): Extension<UserInfo>,
+    headers: HeaderMap,
 ) -> Json<JsonResponse> {
     let sql_handler = &state.sql_handler;
     let exec_start = Instant::now();
@@ -107,15 +116,68 @@ pub async fn promql(
     );
 
     let prom_query = params.into();
-    let resp = match super::query_context_from_db(sql_handler.clone(), db).await {
-        Ok(query_ctx) => {
-            JsonResponse::from_output(sql_handler.do_promql_query(&prom_query, query_ctx).await)
-                .await
+    let resp =
+        match super::query_context_from_db(sql_handler.clone(), catalog_from_headers(&headers), db)
+            .await
+        {
+            Ok(query_ctx) => {
+                query_ctx.set_current_user(user_info);
+                JsonResponse::from_output(sql_handler.do_promql_query(&prom_query, query_ctx).await)
+                    .await
+            }
+            Err(resp) => resp,
+        };
+
+    Json(resp.with_execution_time(exec_start.elapsed().as_millis()))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CardinalityQuery {
+    pub table: Option<String>,
+    pub db: Option<String>,
+}
+
+/// Handler to estimate the series cardinality of a table, the same as
+/// `SHOW CARDINALITY FOR TABLE <table>`.
+#[axum_macros::debug_handler]
+pub async fn cardinality(
+    State(state): State<ApiState>,
+    Query(params): Query<CardinalityQuery>,
+    Extension(user_info): Extension<UserInfo>,
+    headers: HeaderMap,
+) -> Json<JsonResponse> {
+    let sql_handler = &state.sql_handler;
+    let start = Instant::now();
+
+    let resp = if let Some(table) = &params.table {
+        // Quotes each part of the table name, so it can't be interpreted as anything else.
+        let table = table
+            .split('.')
+            .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(".");
+        let sql = format!("SHOW CARDINALITY FOR TABLE {table}");
+        match super::query_context_from_db(
+            sql_handler.clone(),
+            catalog_from_headers(&headers),
+            params.db,
+        )
+        .await
+        {
+            Ok(query_ctx) => {
+                query_ctx.set_current_user(user_info);
+                JsonResponse::from_output(sql_handler.do_query(&sql, query_ctx).await).await
+            }
+            Err(resp) => resp,
         }
-        Err(resp) => resp,
+    } else {
+        JsonResponse::with_error(
+            "table parameter is required.".to_string(),
+            StatusCode::InvalidArguments,
+        )
     };
 
-    Json(resp.with_execution_time(exec_start.elapsed().as_millis()))
+    Json(resp.with_execution_time(start.elapsed().as_millis()))
 }
 
 pub(crate) fn sql_docs(op: TransformOperation) -> TransformOperation {
@@ -134,13 +196,26 @@ pub async fn metrics(
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct HealthQuery {}
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
-pub struct HealthResponse {}
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct HealthResponse {
+    /// Problems reported by the [HealthChecker](crate::health_checker::HealthChecker) of the
+    /// server, omitted if there is none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
 
 /// Handler to export healthy check
 ///
-/// Currently simply return status "200 OK" (default) with an empty json payload "{}"
+/// Always return status "200 OK" (default) as long as the server is serving, with the warnings
+/// of the server in the json payload, or an empty json payload "{}" if there is none.
 #[axum_macros::debug_handler]
-pub async fn health(Query(_params): Query<HealthQuery>) -> Json<HealthResponse> {
-    Json(HealthResponse {})
+pub async fn health(
+    State(health_checker): State<Option<HealthCheckerRef>>,
+    Query(_params): Query<HealthQuery>,
+) -> Json<HealthResponse> {
+    let warnings = match health_checker {
+        Some(checker) => checker.warnings().await,
+        None => vec![],
+    };
+    Json(HealthResponse { warnings })
 }
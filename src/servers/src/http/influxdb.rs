@@ -16,16 +16,18 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::extract::{Query, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
+use axum::Extension;
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_grpc::writer::Precision;
 use common_telemetry::timer;
 use session::context::QueryContext;
 
+use crate::database_alias::DatabaseAliasesRef;
 use crate::error::{Result, TimePrecisionSnafu};
+use crate::http::catalog_from_headers;
 use crate::influxdb::InfluxdbRequest;
-use crate::parse_catalog_and_schema_from_client_database_name;
 use crate::query_handler::InfluxdbLineProtocolHandlerRef;
 
 // https://docs.influxdata.com/influxdb/v1.8/tools/api/#ping-http-endpoint
@@ -43,7 +45,9 @@ pub async fn influxdb_health() -> Result<impl IntoResponse> {
 #[axum_macros::debug_handler]
 pub async fn influxdb_write(
     State(handler): State<InfluxdbLineProtocolHandlerRef>,
+    Extension(database_aliases): Extension<DatabaseAliasesRef>,
     Query(mut params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
     lines: String,
 ) -> Result<impl IntoResponse> {
     let db = params
@@ -53,8 +57,9 @@ pub async fn influxdb_write(
         crate::metrics::METRIC_HTTP_INFLUXDB_WRITE_ELAPSED,
         &[(crate::metrics::METRIC_DB_LABEL, &db)]
     );
-    let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(&db);
-    let ctx = Arc::new(QueryContext::with(catalog, schema));
+    let (catalog, schema) =
+        database_aliases.resolve_catalog_and_schema(catalog_from_headers(&headers), &db);
+    let ctx = Arc::new(QueryContext::with(&catalog, &schema));
 
     let precision = params
         .get("precision")
@@ -0,0 +1,361 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Live queries over WebSocket, exposed as `GET /v1/live`.
+//!
+//! The query is executed on an interval and each result is pushed to the client
+//! as a JSON [JsonResponse] text message. With the `tail` parameter, only rows whose
+//! timestamp column is newer than the last pushed row are sent, and empty results
+//! are not pushed at all.
+
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use common_error::status_code::StatusCode;
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch, RecordBatches};
+use common_telemetry::logging::debug;
+use common_time::Timestamp;
+use datatypes::value::Value;
+use datatypes::vectors::BooleanVector;
+use serde::{Deserialize, Serialize};
+use session::context::{QueryContextRef, UserInfo};
+use snafu::{OptionExt, ResultExt};
+use tokio::time::MissedTickBehavior;
+
+use crate::error::{CollectRecordbatchSnafu, FilterLiveQueryRowsSnafu, InvalidQuerySnafu, Result};
+use crate::http::{catalog_from_headers, ApiState, JsonResponse};
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+/// Default interval between two executions of a live query.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+/// Live queries can't be executed more frequently than this.
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LiveQuery {
+    pub db: Option<String>,
+    pub sql: Option<String>,
+    /// Interval between executions, like `5s`, defaults to 1s.
+    pub interval: Option<String>,
+    /// Timestamp column to tail, only rows newer than the last pushed row are sent.
+    pub tail: Option<String>,
+}
+
+/// Handler to upgrade the connection to a WebSocket that pushes results of the query.
+#[axum_macros::debug_handler]
+pub async fn live(
+    State(state): State<ApiState>,
+    Query(params): Query<LiveQuery>,
+    Extension(user_info): Extension<UserInfo>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let Some(sql) = params.sql else {
+        return error_response("sql parameter is required.".to_string());
+    };
+    let interval = match params.interval.as_deref().map(humantime::parse_duration) {
+        Some(Ok(interval)) if interval >= MIN_INTERVAL => interval,
+        Some(Ok(_)) => {
+            return error_response(format!(
+                "interval must not be less than {}.",
+                humantime::format_duration(MIN_INTERVAL)
+            ));
+        }
+        Some(Err(e)) => return error_response(format!("Invalid interval: {e}")),
+        None => DEFAULT_INTERVAL,
+    };
+
+    let sql_handler = state.sql_handler;
+    let query_ctx = match crate::http::query_context_from_db(
+        sql_handler.clone(),
+        catalog_from_headers(&headers),
+        params.db,
+    )
+    .await
+    {
+        Ok(query_ctx) => query_ctx,
+        Err(resp) => return Json(resp).into_response(),
+    };
+    query_ctx.set_current_user(user_info);
+
+    let live_query = LiveQueryTask {
+        sql_handler,
+        query_ctx,
+        sql,
+        tail: params.tail.map(|column| TailState { column, last: None }),
+    };
+    ws.on_upgrade(move |socket| live_query.run(socket, interval))
+}
+
+fn error_response(error: String) -> Response {
+    Json(JsonResponse::with_error(
+        error,
+        StatusCode::InvalidArguments,
+    ))
+    .into_response()
+}
+
+struct TailState {
+    column: String,
+    /// Timestamp of the newest row pushed to the client.
+    last: Option<Timestamp>,
+}
+
+impl TailState {
+    /// Returns the sql that only selects rows not older than the last pushed row.
+    ///
+    /// Timestamps in sql are compared in milliseconds so rows in the same millisecond
+    /// of the last pushed row are also selected, they are filtered by [TailState::retain_newer].
+    fn tail_sql(&self, sql: &str) -> String {
+        let Some(last) = self.last.and_then(|last| last.to_chrono_datetime()) else {
+            return sql.to_string();
+        };
+        format!(
+            "SELECT * FROM ({}) AS live WHERE \"{}\" >= '{}'",
+            sql.trim().trim_end_matches(';'),
+            self.column.replace('"', "\"\""),
+            last.format("%Y-%m-%d %H:%M:%S%.fZ")
+        )
+    }
+
+    /// Removes rows not newer than the last pushed row from the `batches` and
+    /// advances the last timestamp.
+    fn retain_newer(&mut self, batches: Vec<RecordBatch>) -> Result<Vec<RecordBatch>> {
+        let last = self.last;
+        let mut newest = last;
+        let mut retained = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let ts_vector =
+                batch
+                    .column_by_name(&self.column)
+                    .with_context(|| InvalidQuerySnafu {
+                        reason: format!("tail column {} not found in query result", self.column),
+                    })?;
+
+            let mut filter = Vec::with_capacity(ts_vector.len());
+            for i in 0..ts_vector.len() {
+                let Value::Timestamp(ts) = ts_vector.get(i) else {
+                    filter.push(false);
+                    continue;
+                };
+                filter.push(last.map(|last| ts > last).unwrap_or(true));
+                if newest.map(|newest| ts > newest).unwrap_or(true) {
+                    newest = Some(ts);
+                }
+            }
+            if !filter.contains(&true) {
+                continue;
+            }
+
+            let filter = BooleanVector::from(filter);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| column.filter(&filter))
+                .collect::<datatypes::error::Result<Vec<_>>>()
+                .context(FilterLiveQueryRowsSnafu)?;
+            retained.push(
+                RecordBatch::new(batch.schema.clone(), columns).context(CollectRecordbatchSnafu)?,
+            );
+        }
+        self.last = newest;
+
+        Ok(retained)
+    }
+}
+
+struct LiveQueryTask {
+    sql_handler: ServerSqlQueryHandlerRef,
+    query_ctx: QueryContextRef,
+    sql: String,
+    tail: Option<TailState>,
+}
+
+impl LiveQueryTask {
+    async fn run(mut self, mut socket: WebSocket, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let Some(resp) = self.execute().await else { continue; };
+                    let text = match serde_json::to_string(&resp) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            debug!("Failed to serialize live query response: {}", e);
+                            break;
+                        }
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                msg = socket.recv() => match msg {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // Messages from the client are ignored.
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+        debug!("Live query finished: {}", self.sql);
+    }
+
+    /// Executes the query once, returns `None` if there is nothing to push.
+    async fn execute(&mut self) -> Option<JsonResponse> {
+        let start = Instant::now();
+        let Some(tail) = &mut self.tail else {
+            let outputs = self.sql_handler.do_query(&self.sql, self.query_ctx.clone()).await;
+            return Some(
+                JsonResponse::from_output(outputs)
+                    .await
+                    .with_execution_time(start.elapsed().as_millis()),
+            );
+        };
+
+        let first = tail.last.is_none();
+        let sql = tail.tail_sql(&self.sql);
+        let mut outputs = Vec::new();
+        let mut num_rows = 0;
+        for output in self
+            .sql_handler
+            .do_query(&sql, self.query_ctx.clone())
+            .await
+        {
+            let output = match output {
+                Ok(Output::Stream(stream)) => util::collect(stream)
+                    .await
+                    .context(CollectRecordbatchSnafu)
+                    .and_then(|batches| tail_output(tail, batches, &mut num_rows)),
+                Ok(Output::RecordBatches(batches)) => {
+                    tail_output(tail, batches.take(), &mut num_rows)
+                }
+                other => other,
+            };
+            outputs.push(output);
+        }
+
+        // Only errors and new rows are pushed after the first execution.
+        let has_error = outputs.iter().any(|output| output.is_err());
+        if !first && !has_error && num_rows == 0 {
+            return None;
+        }
+        Some(
+            JsonResponse::from_output(outputs)
+                .await
+                .with_execution_time(start.elapsed().as_millis()),
+        )
+    }
+}
+
+fn tail_output(
+    tail: &mut TailState,
+    batches: Vec<RecordBatch>,
+    num_rows: &mut usize,
+) -> Result<Output> {
+    let Some(schema) = batches.first().map(|batch| batch.schema.clone()) else {
+        return Ok(Output::RecordBatches(RecordBatches::empty()));
+    };
+    let batches = tail.retain_newer(batches)?;
+    *num_rows += batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+    let batches = RecordBatches::try_new(schema, batches).context(CollectRecordbatchSnafu)?;
+    Ok(Output::RecordBatches(batches))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_time::timestamp::TimeUnit;
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int64Vector, TimestampMillisecondVector};
+
+    use super::*;
+
+    fn new_batch(ts: Vec<i64>, values: Vec<i64>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("v", ConcreteDataType::int64_datatype(), true),
+        ]));
+        RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(TimestampMillisecondVector::from_vec(ts)) as _,
+                Arc::new(Int64Vector::from_vec(values)) as _,
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tail_sql() {
+        let mut tail = TailState {
+            column: "ts".to_string(),
+            last: None,
+        };
+        assert_eq!("SELECT * FROM cpu", tail.tail_sql("SELECT * FROM cpu"));
+
+        tail.last = Some(Timestamp::new(1_000_001, TimeUnit::Microsecond));
+        assert_eq!(
+            r#"SELECT * FROM (SELECT * FROM cpu) AS live WHERE "ts" >= '1970-01-01 00:00:01.000001Z'"#,
+            tail.tail_sql("SELECT * FROM cpu;")
+        );
+    }
+
+    #[test]
+    fn test_retain_newer() {
+        let mut tail = TailState {
+            column: "ts".to_string(),
+            last: None,
+        };
+        let batches = tail
+            .retain_newer(vec![new_batch(vec![1, 3, 2], vec![10, 30, 20])])
+            .unwrap();
+        assert_eq!(3, batches[0].num_rows());
+        assert_eq!(Some(Timestamp::new_millisecond(3)), tail.last);
+
+        let batches = tail
+            .retain_newer(vec![
+                new_batch(vec![2, 3], vec![20, 30]),
+                new_batch(vec![3, 5, 4], vec![30, 50, 40]),
+            ])
+            .unwrap();
+        assert_eq!(1, batches.len());
+        assert_eq!(
+            vec![Value::Int64(50), Value::Int64(40)],
+            (0..2)
+                .map(|i| batches[0].column(1).get(i))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(Timestamp::new_millisecond(5)), tail.last);
+
+        let mut tail = TailState {
+            column: "unknown".to_string(),
+            last: None,
+        };
+        assert!(tail
+            .retain_newer(vec![new_batch(vec![1], vec![1])])
+            .is_err());
+    }
+}
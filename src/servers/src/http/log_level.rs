@@ -0,0 +1,35 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::http::StatusCode;
+use common_telemetry::logging;
+
+use crate::error::{Result, UpdateLogLevelSnafu};
+
+/// Returns the log level in use, like `info,mito=debug`.
+#[axum_macros::debug_handler]
+pub async fn log_level() -> (StatusCode, String) {
+    (StatusCode::OK, logging::log_level())
+}
+
+/// Sets the log level from the request body, in the same format as the `level` of
+/// logging options, e.g. `info,mito=debug,storage::flush=trace`.
+#[axum_macros::debug_handler]
+pub async fn set_log_level(body: String) -> Result<(StatusCode, String)> {
+    let directives = body.trim();
+    logging::set_log_level(directives)
+        .map_err(|err_msg| UpdateLogLevelSnafu { err_msg }.build())?;
+    logging::info!("Log level is changed to {directives}");
+    Ok((StatusCode::OK, directives.to_string()))
+}
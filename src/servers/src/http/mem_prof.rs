@@ -12,19 +12,115 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use axum::http::StatusCode;
+use std::collections::HashMap;
+
+use axum::extract::Query;
+use axum::http::{header, StatusCode};
 use axum::response::IntoResponse;
+use axum::Json;
+use common_telemetry::logging;
+use common_telemetry::memory::subsystem_usages;
+use serde_json::json;
 use snafu::ResultExt;
 
-use crate::error::DumpProfileDataSnafu;
+use crate::error::{DumpProfileDataSnafu, ReadMemoryStatsSnafu};
 
+/// Dumps the heap profile. The dump can be tagged by the `tag` param, e.g. the
+/// subsystem under investigation, which prefixes the file name of the dump after the
+/// characters other than ASCII alphanumerics, `-`, `_` and `.` are replaced. The memory
+/// usage of subsystems at the time of dump is returned in header
+/// `x-greptime-memory-usage`.
 #[cfg(feature = "mem-prof")]
 #[axum_macros::debug_handler]
-pub async fn mem_prof() -> crate::error::Result<impl IntoResponse> {
+pub async fn mem_prof(
+    Query(params): Query<HashMap<String, String>>,
+) -> crate::error::Result<impl IntoResponse> {
+    let usages = subsystem_usages()
+        .into_iter()
+        .map(|(subsystem, bytes)| format!("{}={bytes}", subsystem.name()))
+        .collect::<Vec<_>>()
+        .join(",");
+    let file_name = dump_file_name(params.get("tag").map(String::as_str));
+    logging::info!("Dumping heap profile {file_name}, memory usage: {usages}");
+
+    let profile = common_mem_prof::dump_profile()
+        .await
+        .context(DumpProfileDataSnafu)?;
     Ok((
         StatusCode::OK,
-        common_mem_prof::dump_profile()
-            .await
-            .context(DumpProfileDataSnafu)?,
+        [
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+            (
+                header::HeaderName::from_static("x-greptime-memory-usage"),
+                usages,
+            ),
+        ],
+        profile,
     ))
 }
+
+/// Returns the file name of the dump tagged by `tag`, which is safe to quote in the
+/// `Content-Disposition` header.
+#[cfg(feature = "mem-prof")]
+fn dump_file_name(tag: Option<&str>) -> String {
+    match tag.filter(|tag| !tag.is_empty()) {
+        Some(tag) => {
+            let tag = tag
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>();
+            format!("{tag}-greptimedb.hprof")
+        }
+        None => "greptimedb.hprof".to_string(),
+    }
+}
+
+/// Returns the allocator statistics and the memory usage of subsystems in bytes.
+#[cfg(feature = "mem-prof")]
+#[axum_macros::debug_handler]
+pub async fn mem_stats() -> crate::error::Result<impl IntoResponse> {
+    let stats = common_mem_prof::allocator_stats().context(ReadMemoryStatsSnafu)?;
+    let subsystems = subsystem_usages()
+        .into_iter()
+        .map(|(subsystem, bytes)| (subsystem.name(), bytes))
+        .collect::<HashMap<_, _>>();
+    Ok(Json(json!({
+        "allocator": {
+            "allocated": stats.allocated,
+            "active": stats.active,
+            "resident": stats.resident,
+            "mapped": stats.mapped,
+            "retained": stats.retained,
+            "metadata": stats.metadata,
+        },
+        "subsystems": subsystems,
+    })))
+}
+
+#[cfg(all(test, feature = "mem-prof"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_file_name() {
+        assert_eq!("greptimedb.hprof", dump_file_name(None));
+        assert_eq!("greptimedb.hprof", dump_file_name(Some("")));
+        assert_eq!(
+            "memtable-greptimedb.hprof",
+            dump_file_name(Some("memtable"))
+        );
+        assert_eq!(
+            "a___b_c-greptimedb.hprof",
+            dump_file_name(Some("a\"\r\nb;c"))
+        );
+    }
+}
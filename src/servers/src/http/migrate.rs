@@ -0,0 +1,400 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative schema migration, exposed as `POST /v1/migrate`.
+//!
+//! The request body is a schema file made of `CREATE TABLE` statements describing the
+//! desired tables of a database. The handler diffs it against the current catalog and
+//! executes the `CREATE TABLE` and `ALTER TABLE` statements needed to converge, or only
+//! returns them in dry run mode. Migrations tagged with a `version` are recorded in
+//! [MIGRATIONS_TABLE] and applied at most once. A migration failing halfway is recorded
+//! as `partial` with the statements executed before the failure, and is retried by the
+//! next request of the same version.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::{Extension, Json};
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch};
+use datatypes::data_type::DataType;
+use datatypes::value::Value;
+use serde::{Deserialize, Serialize};
+use session::context::{QueryContext, QueryContextRef, UserInfo};
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::create::CreateTable;
+use sql::statements::sql_data_type_to_concrete_data_type;
+use sql::statements::statement::Statement;
+
+use crate::error::{
+    CollectRecordbatchSnafu, DatabaseNotFoundSnafu, IncompatibleSchemaMigrationSnafu,
+    InvalidSchemaFileSnafu, ParseSchemaFileSnafu, Result,
+};
+use crate::http::{catalog_from_headers, ApiState};
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+use crate::resolve_catalog_and_schema;
+
+/// Table recording the versions of applied migrations.
+pub const MIGRATIONS_TABLE: &str = "greptime_schema_migrations";
+
+const STATUS_APPLIED: &str = "applied";
+const STATUS_PARTIAL: &str = "partial";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MigrateQuery {
+    pub db: Option<String>,
+    /// Only computes the statements of the migration, without executing them.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Drops columns absent from the schema file. Extra columns are kept otherwise.
+    #[serde(default)]
+    pub allow_drop: bool,
+    /// Version of the schema file, a version already applied is skipped.
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MigrateResponse {
+    /// Statements converging the database to the schema file.
+    pub statements: Vec<String>,
+    /// Whether the statements were executed.
+    pub applied: bool,
+    /// Whether the version of the migration was applied before.
+    pub already_applied: bool,
+}
+
+/// Handler to migrate the tables of a database to the posted schema file.
+#[axum_macros::debug_handler]
+pub async fn migrate(
+    State(state): State<ApiState>,
+    Query(params): Query<MigrateQuery>,
+    Extension(user_info): Extension<UserInfo>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<MigrateResponse>> {
+    let db = params
+        .db
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string());
+    let (catalog, schema) = resolve_catalog_and_schema(catalog_from_headers(&headers), &db);
+    let handler = state.sql_handler;
+    ensure!(
+        handler.is_valid_schema(catalog, schema).await?,
+        DatabaseNotFoundSnafu { catalog, schema }
+    );
+    let ctx = Arc::new(QueryContext::with(catalog, schema));
+    ctx.set_current_user(user_info);
+
+    let desired = parse_schema_file(&body)?;
+
+    if let Some(version) = &params.version {
+        if !params.dry_run {
+            ensure_migrations_table(&handler, ctx.clone()).await?;
+        }
+        if is_version_applied(&handler, ctx.clone(), schema, version).await? {
+            return Ok(Json(MigrateResponse {
+                already_applied: true,
+                ..Default::default()
+            }));
+        }
+    }
+
+    let current = current_columns(&handler, ctx.clone(), schema).await?;
+    let statements = diff(&desired, &current, params.allow_drop)?;
+    if params.dry_run {
+        return Ok(Json(MigrateResponse {
+            statements,
+            ..Default::default()
+        }));
+    }
+
+    for (i, statement) in statements.iter().enumerate() {
+        if let Err(e) = execute(&handler, statement, ctx.clone()).await {
+            if let Some(version) = &params.version {
+                // Keeps the original error if recording the partial migration fails too.
+                let _ = record_version(
+                    &handler,
+                    ctx.clone(),
+                    version,
+                    &statements[..i],
+                    STATUS_PARTIAL,
+                )
+                .await;
+            }
+            return Err(e);
+        }
+    }
+    if let Some(version) = &params.version {
+        record_version(&handler, ctx, version, &statements, STATUS_APPLIED).await?;
+    }
+
+    Ok(Json(MigrateResponse {
+        statements,
+        applied: true,
+        already_applied: false,
+    }))
+}
+
+fn parse_schema_file(body: &str) -> Result<Vec<CreateTable>> {
+    let statements = ParserContext::create_with_dialect(body, &GenericDialect {})
+        .context(ParseSchemaFileSnafu)?;
+    statements
+        .into_iter()
+        .map(|statement| match statement {
+            Statement::CreateTable(create) => Ok(create),
+            other => InvalidSchemaFileSnafu {
+                reason: format!("expect only CREATE TABLE statements, found: {other:?}"),
+            }
+            .fail(),
+        })
+        .collect()
+}
+
+/// Column name to data type name of each table in the current database.
+type CurrentColumns = HashMap<String, Vec<(String, String)>>;
+
+async fn current_columns(
+    handler: &ServerSqlQueryHandlerRef,
+    ctx: QueryContextRef,
+    schema: &str,
+) -> Result<CurrentColumns> {
+    let sql = format!(
+        "SELECT table_name, column_name, data_type FROM information_schema.columns \
+         WHERE table_schema = '{}'",
+        escape_string(schema)
+    );
+    let mut tables = CurrentColumns::new();
+    for batch in execute(handler, &sql, ctx).await? {
+        for row in batch.rows() {
+            let row = string_values(row);
+            let [table, column, data_type] = row.as_slice() else { continue };
+            tables
+                .entry(table.clone())
+                .or_default()
+                .push((column.clone(), data_type.clone()));
+        }
+    }
+    Ok(tables)
+}
+
+async fn ensure_migrations_table(
+    handler: &ServerSqlQueryHandlerRef,
+    ctx: QueryContextRef,
+) -> Result<()> {
+    let sql = format!(
+        "CREATE TABLE IF NOT EXISTS {MIGRATIONS_TABLE} (\
+         version STRING, \
+         statements STRING, \
+         status STRING, \
+         applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP(), \
+         TIME INDEX (applied_at), \
+         PRIMARY KEY (version))"
+    );
+    let _ = execute(handler, &sql, ctx).await?;
+    Ok(())
+}
+
+async fn is_version_applied(
+    handler: &ServerSqlQueryHandlerRef,
+    ctx: QueryContextRef,
+    schema: &str,
+    version: &str,
+) -> Result<bool> {
+    // The migrations table doesn't exist before the first versioned migration.
+    let sql = format!(
+        "SELECT table_name FROM information_schema.tables \
+         WHERE table_schema = '{}' AND table_name = '{MIGRATIONS_TABLE}'",
+        escape_string(schema)
+    );
+    if !has_rows(&execute(handler, &sql, ctx.clone()).await?) {
+        return Ok(false);
+    }
+
+    let sql = format!(
+        "SELECT version FROM {MIGRATIONS_TABLE} WHERE version = '{}' AND status = '{STATUS_APPLIED}'",
+        escape_string(version)
+    );
+    Ok(has_rows(&execute(handler, &sql, ctx).await?))
+}
+
+async fn record_version(
+    handler: &ServerSqlQueryHandlerRef,
+    ctx: QueryContextRef,
+    version: &str,
+    statements: &[String],
+    status: &str,
+) -> Result<()> {
+    let sql = format!(
+        "INSERT INTO {MIGRATIONS_TABLE}(version, statements, status) VALUES ('{}', '{}', '{status}')",
+        escape_string(version),
+        escape_string(&statements.join(";\n")),
+    );
+    let _ = execute(handler, &sql, ctx).await?;
+    Ok(())
+}
+
+fn has_rows(batches: &[RecordBatch]) -> bool {
+    batches.iter().any(|batch| batch.num_rows() > 0)
+}
+
+async fn execute(
+    handler: &ServerSqlQueryHandlerRef,
+    sql: &str,
+    ctx: QueryContextRef,
+) -> Result<Vec<RecordBatch>> {
+    let mut batches = Vec::new();
+    for output in handler.do_query(sql, ctx.clone()).await {
+        match output? {
+            Output::AffectedRows(_) => {}
+            Output::RecordBatches(output) => batches.extend(output.take()),
+            Output::Stream(stream) => batches.extend(
+                util::collect(stream)
+                    .await
+                    .context(CollectRecordbatchSnafu)?,
+            ),
+        }
+    }
+    Ok(batches)
+}
+
+fn string_values(row: Vec<Value>) -> Vec<String> {
+    row.into_iter()
+        .filter_map(|value| match value {
+            Value::String(s) => Some(s.as_utf8().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn escape_string(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
+/// Computes the statements converging the `current` tables to the `desired` ones.
+///
+/// Missing tables are created and missing columns are added. Changing the type of an
+/// existing column is not supported, and extra columns are dropped only if `allow_drop`.
+pub(crate) fn diff(
+    desired: &[CreateTable],
+    current: &CurrentColumns,
+    allow_drop: bool,
+) -> Result<Vec<String>> {
+    let mut statements = Vec::new();
+    for create in desired {
+        let table_name = &create
+            .name
+            .0
+            .last()
+            .context(InvalidSchemaFileSnafu {
+                reason: "table name is empty",
+            })?
+            .value;
+        let Some(columns) = current.get(table_name) else {
+            statements.push(create.to_string());
+            continue;
+        };
+
+        for column in &create.columns {
+            let name = &column.name.value;
+            let desired_type = sql_data_type_to_concrete_data_type(&column.data_type)
+                .context(ParseSchemaFileSnafu)?;
+            match columns.iter().find(|(current, _)| current == name) {
+                Some((_, current_type)) => ensure!(
+                    desired_type.name() == current_type,
+                    IncompatibleSchemaMigrationSnafu {
+                        table_name,
+                        column_name: name,
+                        current_type,
+                        desired_type: desired_type.name(),
+                    }
+                ),
+                None => statements.push(format!("ALTER TABLE {} ADD COLUMN {column}", create.name)),
+            }
+        }
+
+        if allow_drop {
+            for (current, _) in columns {
+                if !create.columns.iter().any(|c| &c.name.value == current) {
+                    statements.push(format!(
+                        "ALTER TABLE {} DROP COLUMN \"{current}\"",
+                        create.name
+                    ));
+                }
+            }
+        }
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(columns: &[(&str, &str)]) -> Vec<(String, String)> {
+        columns
+            .iter()
+            .map(|(name, data_type)| (name.to_string(), data_type.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let desired = parse_schema_file(
+            r"
+CREATE TABLE monitor (host STRING, ts TIMESTAMP, cpu DOUBLE, memory DOUBLE, TIME INDEX (ts), PRIMARY KEY (host));
+CREATE TABLE events (ts TIMESTAMP TIME INDEX, message STRING);",
+        )
+        .unwrap();
+        let mut current = CurrentColumns::new();
+        let _ = current.insert(
+            "monitor".to_string(),
+            columns(&[
+                ("host", "String"),
+                ("ts", "TimestampMillisecond"),
+                ("cpu", "Float64"),
+                ("disk", "Float64"),
+            ]),
+        );
+
+        let statements = diff(&desired, &current, false).unwrap();
+        assert_eq!(2, statements.len());
+        assert_eq!(
+            "ALTER TABLE monitor ADD COLUMN memory DOUBLE",
+            statements[0]
+        );
+        assert!(statements[1].starts_with("CREATE TABLE events"));
+
+        let statements = diff(&desired, &current, true).unwrap();
+        assert_eq!(3, statements.len());
+        assert_eq!("ALTER TABLE monitor DROP COLUMN \"disk\"", statements[1]);
+
+        let _ = current.insert(
+            "events".to_string(),
+            columns(&[("ts", "TimestampMillisecond"), ("message", "Int64")]),
+        );
+        let err = diff(&desired, &current, false).unwrap_err();
+        assert!(err.to_string().contains("message"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_schema_file() {
+        assert!(parse_schema_file("CREATE TABLE t (ts TIMESTAMP TIME INDEX)").is_ok());
+        assert!(parse_schema_file("SELECT 1").is_err());
+    }
+}
@@ -16,17 +16,18 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::extract::{Query, RawBody, State};
-use axum::http::StatusCode as HttpStatusCode;
-use axum::Json;
+use axum::http::{HeaderMap, StatusCode as HttpStatusCode};
+use axum::{Extension, Json};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use hyper::Body;
 use serde::{Deserialize, Serialize};
 use session::context::QueryContext;
 use snafu::ResultExt;
 
+use crate::database_alias::DatabaseAliasesRef;
 use crate::error::{self, Error, Result};
+use crate::http::catalog_from_headers;
 use crate::opentsdb::codec::DataPoint;
-use crate::parse_catalog_and_schema_from_client_database_name;
 use crate::query_handler::OpentsdbProtocolHandlerRef;
 
 #[derive(Serialize, Deserialize)]
@@ -79,7 +80,9 @@ pub enum OpentsdbPutResponse {
 #[axum_macros::debug_handler]
 pub async fn put(
     State(opentsdb_handler): State<OpentsdbProtocolHandlerRef>,
+    Extension(database_aliases): Extension<DatabaseAliasesRef>,
     Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
     RawBody(body): RawBody,
 ) -> Result<(HttpStatusCode, Json<OpentsdbPutResponse>)> {
     let summary = params.contains_key("summary");
@@ -90,8 +93,9 @@ pub async fn put(
         .map(|v| v.as_str())
         .unwrap_or(DEFAULT_SCHEMA_NAME);
 
-    let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(db);
-    let ctx = Arc::new(QueryContext::with(catalog, schema));
+    let (catalog, schema) =
+        database_aliases.resolve_catalog_and_schema(catalog_from_headers(&headers), db);
+    let ctx = Arc::new(QueryContext::with(&catalog, &schema));
 
     let data_points = parse_data_points(body).await?;
 
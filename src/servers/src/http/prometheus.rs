@@ -16,19 +16,21 @@ use std::sync::Arc;
 
 use api::prometheus::remote::{ReadRequest, WriteRequest};
 use axum::extract::{Query, RawBody, State};
-use axum::http::{header, StatusCode};
+use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::IntoResponse;
+use axum::Extension;
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_telemetry::timer;
 use hyper::Body;
 use prost::Message;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use session::context::QueryContext;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::prelude::*;
 
+use crate::database_alias::DatabaseAliasesRef;
 use crate::error::{self, Result};
-use crate::parse_catalog_and_schema_from_client_database_name;
+use crate::http::catalog_from_headers;
 use crate::prometheus::snappy_decompress;
 use crate::query_handler::{PrometheusProtocolHandlerRef, PrometheusResponse};
 
@@ -48,7 +50,9 @@ impl Default for DatabaseQuery {
 #[axum_macros::debug_handler]
 pub async fn remote_write(
     State(handler): State<PrometheusProtocolHandlerRef>,
+    Extension(database_aliases): Extension<DatabaseAliasesRef>,
     Query(params): Query<DatabaseQuery>,
+    headers: HeaderMap,
     RawBody(body): RawBody,
 ) -> Result<(StatusCode, ())> {
     let request = decode_remote_write_request(body).await?;
@@ -60,12 +64,7 @@ pub async fn remote_write(
             params.db.as_deref().unwrap_or("")
         )]
     );
-    let ctx = if let Some(db) = params.db {
-        let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(&db);
-        Arc::new(QueryContext::with(catalog, schema))
-    } else {
-        QueryContext::arc()
-    };
+    let ctx = query_context(&database_aliases, &headers, params.db.as_deref());
 
     // TODO(shuiyisong): add more error log
     handler.write(request, ctx).await?;
@@ -88,7 +87,9 @@ impl IntoResponse for PrometheusResponse {
 #[axum_macros::debug_handler]
 pub async fn remote_read(
     State(handler): State<PrometheusProtocolHandlerRef>,
+    Extension(database_aliases): Extension<DatabaseAliasesRef>,
     Query(params): Query<DatabaseQuery>,
+    headers: HeaderMap,
     RawBody(body): RawBody,
 ) -> Result<PrometheusResponse> {
     let request = decode_remote_read_request(body).await?;
@@ -100,17 +101,28 @@ pub async fn remote_read(
             params.db.as_deref().unwrap_or("")
         )]
     );
-    let ctx = if let Some(db) = params.db {
-        let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(&db);
-        Arc::new(QueryContext::with(catalog, schema))
-    } else {
-        QueryContext::arc()
-    };
+    let ctx = query_context(&database_aliases, &headers, params.db.as_deref());
 
     // TODO(shuiyisong): add more error log
     handler.read(request, ctx).await
 }
 
+fn query_context(
+    database_aliases: &DatabaseAliasesRef,
+    headers: &HeaderMap,
+    db: Option<&str>,
+) -> QueryContextRef {
+    let catalog = catalog_from_headers(headers);
+    match (catalog, db) {
+        (_, Some(db)) => {
+            let (catalog, schema) = database_aliases.resolve_catalog_and_schema(catalog, db);
+            Arc::new(QueryContext::with(&catalog, &schema))
+        }
+        (Some(catalog), None) => Arc::new(QueryContext::with(catalog, DEFAULT_SCHEMA_NAME)),
+        (None, None) => QueryContext::arc(),
+    }
+}
+
 async fn decode_remote_write_request(body: Body) -> Result<WriteRequest> {
     let body = hyper::body::to_bytes(body)
         .await
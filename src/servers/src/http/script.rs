@@ -65,12 +65,23 @@ pub async fn scripts(
 
         let bytes = unwrap_or_json_err!(hyper::body::to_bytes(body).await);
 
-        let script = unwrap_or_json_err!(String::from_utf8(bytes.to_vec()));
+        let result = match params.engine.as_deref() {
+            None | Some("python") => {
+                let script = unwrap_or_json_err!(String::from_utf8(bytes.to_vec()));
+                script_handler
+                    .insert_script(schema.unwrap(), name.unwrap(), &script)
+                    .await
+            }
+            // The body is the compiled WASM module.
+            Some("wasm") => {
+                script_handler
+                    .insert_wasm_function(schema.unwrap(), name.unwrap(), &bytes)
+                    .await
+            }
+            Some(engine) => json_err!(format!("unknown engine {engine}")),
+        };
 
-        let body = match script_handler
-            .insert_script(schema.unwrap(), name.unwrap(), &script)
-            .await
-        {
+        let body = match result {
             Ok(()) => JsonResponse::with_output(None),
             Err(e) => json_err!(format!("Insert script error: {e}"), e.status_code()),
         };
@@ -85,6 +96,8 @@ pub async fn scripts(
 pub struct ScriptQuery {
     pub db: Option<String>,
     pub name: Option<String>,
+    /// Engine of the uploaded script, `python` by default or `wasm`.
+    pub engine: Option<String>,
     #[serde(flatten)]
     pub params: HashMap<String, String>,
 }
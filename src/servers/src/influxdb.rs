@@ -152,6 +152,47 @@ monitor2,host=host4 cpu=66.3,memory=1029 1663840496400340003";
         }
     }
 
+    #[test]
+    fn test_convert_influxdb_lines_with_bool_and_uint() {
+        let lines = r"
+monitor3,host=host1 up=t,requests=10u 1663840496100023100
+monitor3,host=host2 up=FALSE,requests=18446744073709551615u 1663840496400340001";
+
+        let influxdb_req = &InfluxdbRequest {
+            precision: None,
+            lines: lines.to_string(),
+        };
+
+        let requests: Vec<GrpcInsertRequest> = influxdb_req.try_into().unwrap();
+        assert_eq!(1, requests.len());
+        let columns = &requests[0].columns;
+        assert_eq!(4, columns.len());
+
+        verify_column(
+            &columns[1],
+            "up",
+            ColumnDataType::Boolean,
+            SemanticType::Field,
+            Vec::new(),
+            Values {
+                bool_values: vec![true, false],
+                ..Default::default()
+            },
+        );
+
+        verify_column(
+            &columns[2],
+            "requests",
+            ColumnDataType::Uint64,
+            SemanticType::Field,
+            Vec::new(),
+            Values {
+                u64_values: vec![10, u64::MAX],
+                ..Default::default()
+            },
+        );
+    }
+
     fn assert_monitor_1(columns: &[Column]) {
         assert_eq!(4, columns.len());
         verify_column(
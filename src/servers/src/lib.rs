@@ -19,8 +19,10 @@ use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use serde::{Deserialize, Serialize};
 
 pub mod auth;
+pub mod database_alias;
 pub mod error;
 pub mod grpc;
+pub mod health_checker;
 pub mod http;
 pub mod influxdb;
 pub mod interceptor;
@@ -71,6 +73,25 @@ pub(crate) fn parse_catalog_and_schema_from_client_database_name(db: &str) -> (&
     }
 }
 
+/// Header of HTTP and gRPC requests to select the catalog explicitly.
+pub const GREPTIME_CATALOG_HEADER: &str = "x-greptime-catalog";
+
+/// Resolve catalog and schema of a request with an optional catalog from
+/// [GREPTIME_CATALOG_HEADER].
+///
+/// When the catalog is given by header, the whole database name is used as
+/// schema name. Otherwise we fall back to
+/// [parse_catalog_and_schema_from_client_database_name].
+pub(crate) fn resolve_catalog_and_schema<'a>(
+    catalog: Option<&'a str>,
+    db: &'a str,
+) -> (&'a str, &'a str) {
+    match catalog {
+        Some(catalog) if !catalog.is_empty() => (catalog, db),
+        _ => parse_catalog_and_schema_from_client_database_name(db),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +113,27 @@ mod tests {
             parse_catalog_and_schema_from_client_database_name("catalog-schema1-schema2")
         );
     }
+
+    #[test]
+    fn test_resolve_catalog_and_schema() {
+        assert_eq!(
+            ("catalog", "schema"),
+            resolve_catalog_and_schema(None, "catalog-schema")
+        );
+
+        assert_eq!(
+            ("catalog", "schema"),
+            resolve_catalog_and_schema(Some(""), "catalog-schema")
+        );
+
+        assert_eq!(
+            ("header_catalog", "catalog-schema"),
+            resolve_catalog_and_schema(Some("header_catalog"), "catalog-schema")
+        );
+
+        assert_eq!(
+            ("header_catalog", "schema"),
+            resolve_catalog_and_schema(Some("header_catalog"), "schema")
+        );
+    }
 }
@@ -14,5 +14,7 @@
 
 mod federated;
 pub mod handler;
+mod load_data;
+mod local_infile;
 pub mod server;
 pub mod writer;
@@ -14,7 +14,7 @@
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -41,6 +41,7 @@ use tokio::io::AsyncWrite;
 
 use crate::auth::{Identity, Password, UserProviderRef};
 use crate::error::{self, InvalidPrepareStatementSnafu, Result};
+use crate::mysql::local_infile::{LocalInfile, SharedWriter};
 use crate::mysql::writer;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 
@@ -53,6 +54,7 @@ pub struct MysqlInstanceShim {
     // TODO(SSebo): use something like moka to achieve TTL or LRU
     prepared_stmts: Arc<RwLock<HashMap<u32, String>>>,
     prepared_stmts_counter: AtomicU32,
+    authenticated: Arc<AtomicBool>,
 }
 
 impl MysqlInstanceShim {
@@ -81,9 +83,24 @@ impl MysqlInstanceShim {
             user_provider,
             prepared_stmts: Default::default(),
             prepared_stmts_counter: AtomicU32::new(1),
+            authenticated: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Creates the [LocalInfile] relaying the packets of the connection, which serves the
+    /// `LOAD DATA LOCAL INFILE` queries after the client is authenticated.
+    pub(crate) fn local_infile<W: AsyncWrite + Unpin>(
+        &self,
+        writer: SharedWriter<W>,
+    ) -> LocalInfile<W> {
+        LocalInfile::new(
+            self.query_handler.clone(),
+            self.session.clone(),
+            self.authenticated.clone(),
+            writer,
+        )
+    }
+
     async fn do_query(&self, query: &str) -> Vec<Result<Output>> {
         trace!("Start executing query: '{}'", query);
         let start = Instant::now();
@@ -94,6 +111,15 @@ impl MysqlInstanceShim {
         let output =
             if let Some(output) = crate::mysql::federated::check(query, self.session.context()) {
                 vec![Ok(output)]
+            } else if let Some(copy) = crate::mysql::load_data::rewrite_load_data(query) {
+                match copy {
+                    Ok(copy) => {
+                        self.query_handler
+                            .do_query(&copy, self.session.context())
+                            .await
+                    }
+                    Err(e) => vec![Err(e)],
+                }
             } else {
                 self.query_handler
                     .do_query(query, self.session.context())
@@ -165,6 +191,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         let user_info = user_info.unwrap_or_default();
 
         self.session.set_user_info(user_info);
+        self.authenticated.store(true, Ordering::Release);
 
         true
     }
@@ -176,8 +203,11 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
     ) -> Result<()> {
         let (query, param_num) = replace_placeholder(query);
         if let Err(e) = validate_query(&query).await {
-            w.error(ErrorKind::ER_UNKNOWN_ERROR, e.to_string().as_bytes())
-                .await?;
+            w.error(
+                writer::error_kind(&e, ErrorKind::ER_UNKNOWN_ERROR),
+                e.to_string().as_bytes(),
+            )
+            .await?;
             return Ok(());
         };
 
@@ -0,0 +1,371 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support of MySQL's `LOAD DATA INFILE` statement, which is rewritten to a
+//! `COPY ... FROM` of csv format so that it shares the same file reading and
+//! schema mapping with `COPY`.
+//!
+//! The file of `LOAD DATA LOCAL INFILE` is sent by the client, see
+//! [local_infile](crate::mysql::local_infile).
+
+use common_datasource::file_format::{FORMAT_DELIMTERL, FORMAT_HAS_HEADER, FORMAT_TYPE};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use snafu::{ensure, OptionExt};
+
+use crate::error::{InvalidQuerySnafu, NotSupportedSnafu, Result};
+
+static LOAD_DATA_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)^\s*LOAD\s+DATA\s+(?:(LOW_PRIORITY|CONCURRENT)\s+)?(LOCAL\s+)?INFILE\s+(.*)$")
+        .unwrap()
+});
+
+static TOKEN_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"'(?:[^'\\]|\\.|'')*'|\([^)]*\)|;|[^\s;'(]+").unwrap());
+
+/// A `LOAD DATA` statement, whose file is loaded by a `COPY ... FROM` statement.
+#[derive(Debug)]
+pub(crate) struct LoadData {
+    /// Whether the file is sent by the client.
+    pub(crate) local: bool,
+    pub(crate) file_name: String,
+    table: String,
+    delimiter: u8,
+    has_header: bool,
+}
+
+impl LoadData {
+    /// Returns the `COPY ... FROM` statement loading the file at `path` on server.
+    pub(crate) fn to_copy(&self, path: &str) -> String {
+        format!(
+            "COPY {} FROM '{}' WITH ({FORMAT_TYPE}='csv', {FORMAT_DELIMTERL}='{}', {FORMAT_HAS_HEADER}='{}')",
+            self.table,
+            path.replace('\'', "''"),
+            self.delimiter,
+            self.has_header,
+        )
+    }
+}
+
+/// Parses the `LOAD DATA` statement. Returns `None` if the query is not a `LOAD DATA`
+/// statement.
+///
+/// Supported clauses are `FIELDS TERMINATED BY`, `FIELDS [OPTIONALLY] ENCLOSED BY '"'`,
+/// `LINES TERMINATED BY '\n'` and `IGNORE 1 LINES`, which skips the csv header.
+pub(crate) fn parse_load_data(query: &str) -> Option<Result<LoadData>> {
+    let captures = LOAD_DATA_PATTERN.captures(query)?;
+    let local = captures.get(2).is_some();
+    // Safety: the last group always matches.
+    let rest = captures.get(3).unwrap().as_str();
+    Some(parse_clauses(rest, local))
+}
+
+/// Rewrites `LOAD DATA INFILE` to a `COPY ... FROM` statement. Returns `None` if
+/// the query is not a `LOAD DATA` statement.
+///
+/// `LOAD DATA LOCAL INFILE` only reaches here if the connection is secured by TLS, whose
+/// packets can't be intercepted to read the file from the client, so it's rejected.
+pub(crate) fn rewrite_load_data(query: &str) -> Option<Result<String>> {
+    let load_data = match parse_load_data(query)? {
+        Ok(load_data) => load_data,
+        Err(e) => return Some(Err(e)),
+    };
+    if load_data.local {
+        return Some(
+            NotSupportedSnafu {
+                feat: "LOAD DATA LOCAL INFILE over TLS, please use LOAD DATA INFILE or COPY FROM",
+            }
+            .fail(),
+        );
+    }
+    Some(Ok(load_data.to_copy(&load_data.file_name)))
+}
+
+fn parse_clauses(rest: &str, local: bool) -> Result<LoadData> {
+    let tokens = TOKEN_PATTERN
+        .find_iter(rest)
+        .map(|m| m.as_str())
+        .collect::<Vec<_>>();
+    let mut parser = Parser { tokens, pos: 0 };
+
+    let file_name = parser.parse_literal("file name")?;
+    let _ = parser.parse_keyword("REPLACE") || parser.parse_keyword("IGNORE");
+    parser.expect_keyword("INTO")?;
+    parser.expect_keyword("TABLE")?;
+    let table = parser.next().context(InvalidQuerySnafu {
+        reason: "LOAD DATA requires a table name",
+    })?;
+
+    let mut delimiter = b'\t';
+    let mut has_header = false;
+    while let Some(token) = parser.next() {
+        match token.to_ascii_uppercase().as_str() {
+            "CHARACTER" => {
+                parser.expect_keyword("SET")?;
+                let charset = parser.next().unwrap_or_default().to_ascii_lowercase();
+                ensure!(
+                    matches!(charset.as_str(), "utf8" | "utf8mb4" | "binary" | "ascii"),
+                    NotSupportedSnafu {
+                        feat: format!("LOAD DATA with character set {charset}"),
+                    }
+                );
+            }
+            "FIELDS" | "COLUMNS" => loop {
+                if parser.parse_keyword("TERMINATED") {
+                    parser.expect_keyword("BY")?;
+                    delimiter = single_byte("FIELDS TERMINATED BY", &parser.parse_literal("")?)?;
+                } else if parser.parse_keyword("OPTIONALLY") || parser.parse_keyword("ENCLOSED") {
+                    let _ = parser.parse_keyword("ENCLOSED");
+                    parser.expect_keyword("BY")?;
+                    let enclosed = parser.parse_literal("")?;
+                    ensure!(
+                        enclosed.is_empty() || enclosed == "\"",
+                        NotSupportedSnafu {
+                            feat: format!("LOAD DATA with FIELDS ENCLOSED BY '{enclosed}'"),
+                        }
+                    );
+                } else if parser.parse_keyword("ESCAPED") {
+                    parser.expect_keyword("BY")?;
+                    let _ = parser.parse_literal("")?;
+                } else {
+                    break;
+                }
+            },
+            "LINES" => loop {
+                if parser.parse_keyword("STARTING") {
+                    parser.expect_keyword("BY")?;
+                    let starting = parser.parse_literal("")?;
+                    ensure!(
+                        starting.is_empty(),
+                        NotSupportedSnafu {
+                            feat: "LOAD DATA with LINES STARTING BY",
+                        }
+                    );
+                } else if parser.parse_keyword("TERMINATED") {
+                    parser.expect_keyword("BY")?;
+                    let terminated = parser.parse_literal("")?;
+                    ensure!(
+                        terminated == "\n" || terminated == "\r\n",
+                        NotSupportedSnafu {
+                            feat: "LOAD DATA with LINES TERMINATED BY other than newline",
+                        }
+                    );
+                } else {
+                    break;
+                }
+            },
+            "IGNORE" => {
+                let lines = parser.next().unwrap_or_default();
+                ensure!(
+                    parser.parse_keyword("LINES") || parser.parse_keyword("ROWS"),
+                    InvalidQuerySnafu {
+                        reason: "expect LINES or ROWS after IGNORE number",
+                    }
+                );
+                match lines {
+                    "0" => has_header = false,
+                    "1" => has_header = true,
+                    _ => {
+                        return NotSupportedSnafu {
+                            feat: format!("LOAD DATA ignoring {lines} lines"),
+                        }
+                        .fail()
+                    }
+                }
+            }
+            ";" => ensure!(
+                parser.peek().is_none(),
+                InvalidQuerySnafu {
+                    reason: "multiple statements are not allowed after LOAD DATA",
+                }
+            ),
+            other => {
+                return NotSupportedSnafu {
+                    feat: format!("LOAD DATA clause {other}"),
+                }
+                .fail()
+            }
+        }
+    }
+
+    Ok(LoadData {
+        local,
+        file_name,
+        table: table.to_string(),
+        delimiter,
+        has_header,
+    })
+}
+
+fn single_byte(clause: &str, s: &str) -> Result<u8> {
+    match s.as_bytes() {
+        [b] => Ok(*b),
+        _ => NotSupportedSnafu {
+            feat: format!("LOAD DATA with multi-byte {clause} '{s}'"),
+        }
+        .fail(),
+    }
+}
+
+/// Unquotes a MySQL string literal and resolves its escape sequences.
+fn unquote(literal: &str) -> String {
+    let inner = &literal[1..literal.len() - 1];
+    let mut s = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('t') => s.push('\t'),
+                Some('n') => s.push('\n'),
+                Some('r') => s.push('\r'),
+                Some('0') => s.push('\0'),
+                Some(c) => s.push(c),
+                None => s.push('\\'),
+            },
+            '\'' => {
+                // a doubled quote
+                let _ = chars.next();
+                s.push('\'');
+            }
+            c => s.push(c),
+        }
+    }
+    s
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(token) if token.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        ensure!(
+            self.parse_keyword(keyword),
+            InvalidQuerySnafu {
+                reason: format!(
+                    "expect {keyword} in LOAD DATA, found: {}",
+                    self.peek().unwrap_or("EOF")
+                ),
+            }
+        );
+        Ok(())
+    }
+
+    fn parse_literal(&mut self, desc: &str) -> Result<String> {
+        match self.next() {
+            Some(token) if token.len() >= 2 && token.starts_with('\'') => Ok(unquote(token)),
+            token => InvalidQuerySnafu {
+                reason: format!(
+                    "expect a string literal {desc} in LOAD DATA, found: {}",
+                    token.unwrap_or("EOF")
+                ),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(query: &str) -> String {
+        rewrite_load_data(query).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_rewrite_load_data() {
+        assert_eq!(
+            "COPY monitor FROM '/tmp/monitor.tsv' WITH (FORMAT='csv', DELIMTERL='9', FORMAT_HAS_HEADER='false')",
+            rewrite("LOAD DATA INFILE '/tmp/monitor.tsv' INTO TABLE monitor")
+        );
+        assert_eq!(
+            "COPY public.monitor FROM '/tmp/it''s.csv' WITH (FORMAT='csv', DELIMTERL='44', FORMAT_HAS_HEADER='true')",
+            rewrite(
+                "load data infile '/tmp/it''s.csv' ignore into table public.monitor \
+                 character set utf8mb4 \
+                 fields terminated by ',' optionally enclosed by '\"' escaped by '\\\\' \
+                 lines terminated by '\\n' \
+                 ignore 1 lines;"
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_load_data_local() {
+        let load_data = parse_load_data(
+            "LOAD DATA LOCAL INFILE 'monitor.csv' INTO TABLE monitor FIELDS TERMINATED BY ','",
+        )
+        .unwrap()
+        .unwrap();
+        assert!(load_data.local);
+        assert_eq!("monitor.csv", load_data.file_name);
+        assert_eq!(
+            "COPY monitor FROM '/tmp/greptimedb-load-data' WITH (FORMAT='csv', DELIMTERL='44', FORMAT_HAS_HEADER='false')",
+            load_data.to_copy("/tmp/greptimedb-load-data")
+        );
+    }
+
+    #[test]
+    fn test_rewrite_load_data_errors() {
+        assert!(rewrite_load_data("SELECT 1").is_none());
+        assert!(
+            rewrite_load_data("LOAD DATA LOCAL INFILE 'a.csv' INTO TABLE t")
+                .unwrap()
+                .is_err()
+        );
+
+        for query in [
+            "LOAD DATA INFILE a.csv INTO TABLE t",
+            "LOAD DATA INFILE 'a.csv' TABLE t",
+            "LOAD DATA INFILE 'a.csv' INTO TABLE",
+            "LOAD DATA INFILE 'a.csv' INTO TABLE t FIELDS TERMINATED BY '||'",
+            "LOAD DATA INFILE 'a.csv' INTO TABLE t FIELDS ENCLOSED BY '#'",
+            "LOAD DATA INFILE 'a.csv' INTO TABLE t LINES TERMINATED BY ';'",
+            "LOAD DATA INFILE 'a.csv' INTO TABLE t IGNORE 2 LINES",
+            "LOAD DATA INFILE 'a.csv' INTO TABLE t (a, b)",
+            "LOAD DATA INFILE 'a.csv' INTO TABLE t CHARACTER SET latin1",
+        ] {
+            assert!(rewrite_load_data(query).unwrap().is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_unquote() {
+        assert_eq!("\t", unquote("'\\t'"));
+        assert_eq!("it's", unquote("'it''s'"));
+        assert_eq!("a\\b", unquote("'a\\\\b'"));
+    }
+}
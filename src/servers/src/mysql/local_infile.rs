@@ -0,0 +1,485 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support of MySQL's `LOAD DATA LOCAL INFILE` statement, whose file is sent by the client in
+//! the local-infile sub-protocol:
+//!
+//! 1. the client sends the query in a `COM_QUERY` packet;
+//! 2. the server replies a `0xFB` packet with the file name;
+//! 3. the client sends the file content in packets, ending with an empty packet;
+//! 4. the server replies an OK or ERR packet.
+//!
+//! `opensrv_mysql` doesn't expose the connection reader to
+//! [AsyncMysqlShim](opensrv_mysql::AsyncMysqlShim), so the packets from the client are
+//! relayed by [LocalInfile], which serves the `LOAD DATA LOCAL INFILE` queries itself and
+//! passes the other packets to `opensrv_mysql` as they are. The file is received into a
+//! temporary file, then loaded by `COPY ... FROM` like `LOAD DATA INFILE`.
+//!
+//! The packets of the connections secured by TLS can't be inspected, their
+//! `LOAD DATA LOCAL INFILE` queries are rejected.
+
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use common_query::Output;
+use common_telemetry::{debug, warn};
+use opensrv_mysql::ErrorKind;
+use parking_lot::Mutex;
+use session::Session;
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream};
+
+use crate::error::{self, Result};
+use crate::mysql::load_data::{self, LoadData};
+use crate::mysql::writer;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+/// Size of the buffer relaying the packets to `opensrv_mysql`.
+pub(crate) const RELAY_BUFFER_SIZE: usize = 64 * 1024;
+
+const COM_QUERY: u8 = 0x03;
+const LOCAL_INFILE_REQUEST: u8 = 0xFB;
+const OK_HEADER: u8 = 0x00;
+const ERR_HEADER: u8 = 0xFF;
+
+const CLIENT_LOCAL_FILES: u32 = 0x0080;
+const CLIENT_PROTOCOL_41: u32 = 0x0200;
+const CLIENT_SSL: u32 = 0x0800;
+const SERVER_STATUS_AUTOCOMMIT: u16 = 0x0002;
+const SQL_STATE_GENERAL_ERROR: &[u8; 5] = b"HY000";
+
+/// Max payload size of a packet, larger payloads are split into multiple packets.
+const MAX_PAYLOAD_SIZE: usize = 0xFF_FFFF;
+
+/// Write half of a connection shared by `opensrv_mysql` and [LocalInfile]. They never write
+/// at the same time, since the client waits for the response of a command before sending
+/// the next one.
+pub(crate) struct SharedWriter<W>(Arc<Mutex<W>>);
+
+impl<W> SharedWriter<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self(Arc::new(Mutex::new(writer)))
+    }
+}
+
+impl<W> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for SharedWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0.lock()).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0.lock()).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0.lock()).poll_shutdown(cx)
+    }
+}
+
+/// Relays the packets from the client to `opensrv_mysql`, except the
+/// `LOAD DATA LOCAL INFILE` queries, which are served by itself.
+pub(crate) struct LocalInfile<W> {
+    query_handler: ServerSqlQueryHandlerRef,
+    session: Arc<Session>,
+    /// Whether the client is authenticated, the queries are relayed until then.
+    authenticated: Arc<AtomicBool>,
+    writer: SharedWriter<W>,
+}
+
+impl<W: AsyncWrite + Unpin> LocalInfile<W> {
+    pub(crate) fn new(
+        query_handler: ServerSqlQueryHandlerRef,
+        session: Arc<Session>,
+        authenticated: Arc<AtomicBool>,
+        writer: SharedWriter<W>,
+    ) -> Self {
+        Self {
+            query_handler,
+            session,
+            authenticated,
+            writer,
+        }
+    }
+
+    /// Relays the packets read from the client by `reader` to `relay`, until the client
+    /// closes the connection or `opensrv_mysql` stops reading.
+    pub(crate) async fn run<R: AsyncRead + Unpin>(
+        mut self,
+        mut reader: R,
+        mut relay: DuplexStream,
+    ) -> Result<()> {
+        // Capabilities of the client, from the first packet it sends.
+        let mut capabilities = None;
+        while let Some((seq, payload)) = read_packet(&mut reader).await? {
+            let client_capabilities = *capabilities.get_or_insert_with(|| {
+                payload
+                    .get(..4)
+                    .map(|flags| u32::from_le_bytes([flags[0], flags[1], flags[2], flags[3]]))
+                    .unwrap_or_default()
+            });
+            if client_capabilities & CLIENT_SSL != 0 {
+                // The rest of the connection is encrypted.
+                if write_packet(&mut relay, seq, &payload).await.is_ok() {
+                    let _ = tokio::io::copy(&mut reader, &mut relay).await;
+                }
+                return Ok(());
+            }
+
+            if let Some(load_data) = self.parse_local_load_data(seq, &payload) {
+                self.load_local_file(&mut reader, client_capabilities, load_data)
+                    .await?;
+                continue;
+            }
+            if write_packet(&mut relay, seq, &payload).await.is_err() {
+                // `opensrv_mysql` has closed the connection.
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses the `LOAD DATA LOCAL INFILE` query of the packet if it's a command of an
+    /// authenticated client. The invalid queries are left to `opensrv_mysql` to report.
+    fn parse_local_load_data(&self, seq: u8, payload: &[u8]) -> Option<LoadData> {
+        if seq != 0
+            || payload.len() >= MAX_PAYLOAD_SIZE
+            || payload.first() != Some(&COM_QUERY)
+            || !self.authenticated.load(Ordering::Acquire)
+        {
+            return None;
+        }
+        let query = std::str::from_utf8(&payload[1..]).ok()?;
+        match load_data::parse_load_data(query)? {
+            Ok(load_data) if load_data.local => Some(load_data),
+            _ => None,
+        }
+    }
+
+    /// Serves the `LOAD DATA LOCAL INFILE` query, the client has sent its command packet
+    /// numbered 0.
+    async fn load_local_file<R: AsyncRead + Unpin>(
+        &mut self,
+        reader: &mut R,
+        capabilities: u32,
+        load_data: LoadData,
+    ) -> Result<()> {
+        if capabilities & CLIENT_LOCAL_FILES == 0 {
+            let packet = err_packet(
+                ErrorKind::ER_NOT_ALLOWED_COMMAND,
+                "LOAD DATA LOCAL INFILE is not enabled by the client",
+                capabilities,
+            );
+            return write_packet(&mut self.writer, 1, &packet).await;
+        }
+
+        let mut request = vec![LOCAL_INFILE_REQUEST];
+        request.extend_from_slice(load_data.file_name.as_bytes());
+        write_packet(&mut self.writer, 1, &request).await?;
+
+        let path = std::env::temp_dir().join(format!(
+            "greptimedb-load-data-{:016x}",
+            rand::random::<u64>()
+        ));
+        let (seq, received) = receive_file(reader, &path).await?;
+        let output = match received {
+            // There is nothing to load if the client sends an empty file, or fails to read it.
+            Ok(0) => Ok(Output::AffectedRows(0)),
+            Ok(size) => {
+                debug!(
+                    "Received {} bytes of local file {} into {}",
+                    size,
+                    load_data.file_name,
+                    path.display()
+                );
+                let copy = load_data.to_copy(&path.to_string_lossy());
+                self.query_handler
+                    .do_query(&copy, self.session.context())
+                    .await
+                    .into_iter()
+                    .next()
+                    .unwrap_or(Ok(Output::AffectedRows(0)))
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            warn!("Failed to remove local file {}: {}", path.display(), e);
+        }
+
+        let packet = match output {
+            Ok(Output::AffectedRows(rows)) => ok_packet(rows as u64, capabilities),
+            Ok(_) => ok_packet(0, capabilities),
+            Err(e) => err_packet(
+                writer::error_kind(&e, ErrorKind::ER_UNKNOWN_ERROR),
+                &e.to_string(),
+                capabilities,
+            ),
+        };
+        write_packet(&mut self.writer, seq.wrapping_add(1), &packet).await
+    }
+}
+
+/// Receives the file sent by the client into `path`. Returns the sequence of the last packet
+/// and the size of the file, or the error writing the file, in which case the rest of the file
+/// is discarded.
+async fn receive_file<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    path: &Path,
+) -> Result<(u8, Result<u64>)> {
+    let mut file = File::create(path).await.map_err(error::Error::from);
+    let mut size = 0;
+    // Whether the packet continues the previous one of the max size.
+    let mut continued = false;
+    loop {
+        let (seq, payload) = read_packet(reader).await?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed while receiving local file",
+            )
+        })?;
+        if payload.is_empty() && !continued {
+            let received = match file {
+                Ok(mut file) => file.flush().await.map(|_| size).map_err(Into::into),
+                Err(e) => Err(e),
+            };
+            return Ok((seq, received));
+        }
+        continued = payload.len() == MAX_PAYLOAD_SIZE;
+        size += payload.len() as u64;
+        if let Ok(f) = &mut file {
+            if let Err(e) = f.write_all(&payload).await {
+                file = Err(e.into());
+            }
+        }
+    }
+}
+
+/// Reads a packet, returns `None` if the connection is closed.
+async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<(u8, Vec<u8>)>> {
+    let mut header = [0u8; 4];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let size = u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize;
+    let mut payload = vec![0; size];
+    let _ = reader.read_exact(&mut payload).await?;
+    Ok(Some((header[3], payload)))
+}
+
+/// Writes a packet, whose payload must be smaller than [MAX_PAYLOAD_SIZE] unless it's
+/// continued by the next packet.
+async fn write_packet<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    seq: u8,
+    payload: &[u8],
+) -> Result<()> {
+    debug_assert!(payload.len() <= MAX_PAYLOAD_SIZE);
+    let size = (payload.len() as u32).to_le_bytes();
+    let mut packet = Vec::with_capacity(4 + payload.len());
+    packet.extend_from_slice(&size[..3]);
+    packet.push(seq);
+    packet.extend_from_slice(payload);
+    writer.write_all(&packet).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+fn ok_packet(affected_rows: u64, capabilities: u32) -> Vec<u8> {
+    let mut packet = vec![OK_HEADER];
+    put_length_encoded_int(&mut packet, affected_rows);
+    // Last insert id.
+    put_length_encoded_int(&mut packet, 0);
+    if capabilities & CLIENT_PROTOCOL_41 != 0 {
+        packet.extend_from_slice(&SERVER_STATUS_AUTOCOMMIT.to_le_bytes());
+        // Number of warnings.
+        packet.extend_from_slice(&0u16.to_le_bytes());
+    }
+    packet
+}
+
+fn err_packet(kind: ErrorKind, message: &str, capabilities: u32) -> Vec<u8> {
+    let mut packet = vec![ERR_HEADER];
+    packet.extend_from_slice(&(kind as u16).to_le_bytes());
+    if capabilities & CLIENT_PROTOCOL_41 != 0 {
+        packet.push(b'#');
+        packet.extend_from_slice(SQL_STATE_GENERAL_ERROR);
+    }
+    packet.extend_from_slice(message.as_bytes());
+    packet
+}
+
+fn put_length_encoded_int(buf: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=250 => buf.push(n as u8),
+        251..=0xFFFF => {
+            buf.push(0xFC);
+            buf.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        0x1_0000..=0xFF_FFFF => {
+            buf.push(0xFD);
+            buf.extend_from_slice(&(n as u32).to_le_bytes()[..3]);
+        }
+        _ => {
+            buf.push(0xFE);
+            buf.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use datatypes::schema::Schema;
+    use query::parser::PromQuery;
+    use session::context::{Channel, QueryContextRef};
+    use sql::statements::statement::Statement;
+
+    use super::*;
+    use crate::query_handler::sql::SqlQueryHandler;
+
+    /// Loads the files by counting their lines.
+    struct LineCounter;
+
+    #[async_trait]
+    impl SqlQueryHandler for LineCounter {
+        type Error = error::Error;
+
+        async fn do_query(&self, query: &str, _: QueryContextRef) -> Vec<Result<Output>> {
+            assert!(query.starts_with("COPY monitor FROM '"), "{query}");
+            let path = query.split('\'').nth(1).unwrap();
+            let content = tokio::fs::read_to_string(path).await.unwrap();
+            vec![Ok(Output::AffectedRows(content.lines().count()))]
+        }
+
+        async fn do_promql_query(&self, _: &PromQuery, _: QueryContextRef) -> Vec<Result<Output>> {
+            unimplemented!()
+        }
+
+        async fn do_describe(&self, _: Statement, _: QueryContextRef) -> Result<Option<Schema>> {
+            unimplemented!()
+        }
+
+        async fn is_valid_schema(&self, _: &str, _: &str) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    /// Starts relaying, returns the writer and reader of the client, the reader of
+    /// `opensrv_mysql` and whether the client is authenticated.
+    fn start_relay() -> (DuplexStream, DuplexStream, DuplexStream, Arc<AtomicBool>) {
+        let (client_writer, reader) = tokio::io::duplex(1024);
+        let (writer, client_reader) = tokio::io::duplex(1024);
+        let (opensrv_reader, relay) = tokio::io::duplex(1024);
+        let authenticated = Arc::new(AtomicBool::new(false));
+        let local_infile = LocalInfile::new(
+            Arc::new(LineCounter),
+            Arc::new(Session::new(
+                "127.0.0.1:4000".parse().unwrap(),
+                Channel::Mysql,
+            )),
+            authenticated.clone(),
+            SharedWriter::new(writer),
+        );
+        let _handle = tokio::spawn(local_infile.run(reader, relay));
+        (client_writer, client_reader, opensrv_reader, authenticated)
+    }
+
+    #[tokio::test]
+    async fn test_load_local_file() {
+        let (mut client_writer, mut client_reader, mut opensrv_reader, authenticated) =
+            start_relay();
+        let capabilities = (CLIENT_PROTOCOL_41 | CLIENT_LOCAL_FILES).to_le_bytes();
+        write_packet(&mut client_writer, 1, &capabilities)
+            .await
+            .unwrap();
+        assert_eq!(
+            Some((1, capabilities.to_vec())),
+            read_packet(&mut opensrv_reader).await.unwrap()
+        );
+        authenticated.store(true, Ordering::Release);
+
+        let query = b"\x03LOAD DATA LOCAL INFILE 'monitor.csv' INTO TABLE monitor \
+            FIELDS TERMINATED BY ','";
+        write_packet(&mut client_writer, 0, query).await.unwrap();
+        assert_eq!(
+            Some((1, b"\xFBmonitor.csv".to_vec())),
+            read_packet(&mut client_reader).await.unwrap()
+        );
+        write_packet(&mut client_writer, 2, b"host,cpu\nhost1,1.0\n")
+            .await
+            .unwrap();
+        write_packet(&mut client_writer, 3, b"host2,2.0\n")
+            .await
+            .unwrap();
+        write_packet(&mut client_writer, 4, b"").await.unwrap();
+        assert_eq!(
+            Some((5, ok_packet(3, CLIENT_PROTOCOL_41))),
+            read_packet(&mut client_reader).await.unwrap()
+        );
+
+        // Other queries are relayed.
+        write_packet(&mut client_writer, 0, b"\x03SELECT 1")
+            .await
+            .unwrap();
+        assert_eq!(
+            Some((0, b"\x03SELECT 1".to_vec())),
+            read_packet(&mut opensrv_reader).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_local_file_not_allowed() {
+        let (mut client_writer, mut client_reader, mut opensrv_reader, authenticated) =
+            start_relay();
+        let capabilities = CLIENT_PROTOCOL_41.to_le_bytes();
+        write_packet(&mut client_writer, 1, &capabilities)
+            .await
+            .unwrap();
+        let _ = read_packet(&mut opensrv_reader).await.unwrap();
+
+        // Relayed before the client is authenticated.
+        let query = b"\x03LOAD DATA LOCAL INFILE 'monitor.csv' INTO TABLE monitor";
+        write_packet(&mut client_writer, 0, query).await.unwrap();
+        assert_eq!(
+            Some((0, query.to_vec())),
+            read_packet(&mut opensrv_reader).await.unwrap()
+        );
+
+        // Rejected if the client doesn't enable local infile.
+        authenticated.store(true, Ordering::Release);
+        write_packet(&mut client_writer, 0, query).await.unwrap();
+        let (seq, payload) = read_packet(&mut client_reader).await.unwrap().unwrap();
+        assert_eq!(1, seq);
+        assert_eq!(ERR_HEADER, payload[0]);
+        assert_eq!(
+            ErrorKind::ER_NOT_ALLOWED_COMMAND as u16,
+            u16::from_le_bytes([payload[1], payload[2]])
+        );
+    }
+}
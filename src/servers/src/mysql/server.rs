@@ -25,13 +25,15 @@ use opensrv_mysql::{
     plain_run_with_options, secure_run_with_options, AsyncMysqlIntermediary, IntermediaryOptions,
 };
 use tokio;
-use tokio::io::BufWriter;
+use tokio::io::{BufWriter, DuplexStream};
+use tokio::net::tcp::OwnedWriteHalf;
 use tokio::net::TcpStream;
 use tokio_rustls::rustls::ServerConfig;
 
 use crate::auth::UserProviderRef;
 use crate::error::{Error, Result};
 use crate::mysql::handler::MysqlInstanceShim;
+use crate::mysql::local_infile::{SharedWriter, RELAY_BUFFER_SIZE};
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::server::{AbortableStream, BaseTcpServer, Server};
 
@@ -174,18 +176,41 @@ impl MysqlServer {
         spawn_ref: Arc<MysqlSpawnRef>,
         spawn_config: Arc<MysqlSpawnConfig>,
     ) -> Result<()> {
-        let mut shim = MysqlInstanceShim::create(
+        let shim = MysqlInstanceShim::create(
             spawn_ref.query_handler(),
             spawn_ref.user_provider(),
             stream.peer_addr()?,
         );
-        let (mut r, w) = stream.into_split();
+        let (reader, w) = stream.into_split();
+        let w = SharedWriter::new(w);
+
+        // The packets from the client are relayed to `opensrv_mysql`, except the ones of
+        // `LOAD DATA LOCAL INFILE` queries, see `local_infile`.
+        let (mut r, relay) = tokio::io::duplex(RELAY_BUFFER_SIZE);
+        let local_infile = shim.local_infile(w.clone());
+        let relay_handle = tokio::spawn(async move {
+            if let Err(e) = local_infile.run(reader, relay).await {
+                warn!("Failed to relay MySQL packets: {}", e);
+            }
+        });
+
+        let result = Self::run_shim(shim, &mut r, w, &spawn_config).await;
+        relay_handle.abort();
+        result
+    }
+
+    async fn run_shim(
+        mut shim: MysqlInstanceShim,
+        r: &mut DuplexStream,
+        w: SharedWriter<OwnedWriteHalf>,
+        spawn_config: &MysqlSpawnConfig,
+    ) -> Result<()> {
         let mut w = BufWriter::with_capacity(DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE, w);
 
-        let ops = spawn_config.as_ref().into();
+        let ops = spawn_config.into();
 
         let (client_tls, init_params) =
-            AsyncMysqlIntermediary::init_before_ssl(&mut shim, &mut r, &mut w, &spawn_config.tls())
+            AsyncMysqlIntermediary::init_before_ssl(&mut shim, r, &mut w, &spawn_config.tls())
                 .await?;
 
         if spawn_config.force_tls && !client_tls {
@@ -14,11 +14,13 @@
 
 use std::ops::Deref;
 
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
-use common_recordbatch::{util, RecordBatch};
+use common_recordbatch::{ExecutionStats, RecordBatch, SendableRecordBatchStream};
 use common_telemetry::error;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{ColumnSchema, SchemaRef};
+use futures::TryStreamExt;
 use opensrv_mysql::{
     Column, ColumnFlags, ColumnType, ErrorKind, OkResponse, QueryResultWriter, RowWriter,
 };
@@ -50,6 +52,7 @@ pub async fn write_output<'a, W: AsyncWrite + Send + Sync + Unpin>(
 struct QueryResult {
     recordbatches: Vec<RecordBatch>,
     schema: SchemaRef,
+    stats: Option<ExecutionStats>,
 }
 
 pub struct MysqlResultWriter<'a, W: AsyncWrite + Unpin> {
@@ -72,20 +75,13 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
         match output {
             Ok(output) => match output {
                 Output::Stream(stream) => {
-                    let schema = stream.schema().clone();
-                    let recordbatches = util::collect(stream)
-                        .await
-                        .context(error::CollectRecordbatchSnafu)?;
-                    let query_result = QueryResult {
-                        recordbatches,
-                        schema,
-                    };
-                    Self::write_query_result(query, query_result, self.writer).await?;
+                    Self::write_stream(query, stream, self.writer).await?;
                 }
                 Output::RecordBatches(recordbatches) => {
                     let query_result = QueryResult {
                         schema: recordbatches.schema(),
                         recordbatches: recordbatches.take(),
+                        stats: None,
                     };
                     Self::write_query_result(query, query_result, self.writer).await?;
                 }
@@ -131,13 +127,55 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                 for recordbatch in &query_result.recordbatches {
                     Self::write_recordbatch(&mut row_writer, recordbatch).await?;
                 }
-                row_writer.finish().await?;
+                match &query_result.stats {
+                    Some(stats) => row_writer.finish_with_info(&stats_info(stats)).await?,
+                    None => row_writer.finish().await?,
+                }
                 Ok(())
             }
             Err(error) => Self::write_query_error(query, error, writer).await,
         }
     }
 
+    /// Writes the rows of each record batch as soon as the stream yields it, so the queries
+    /// never finishing, like tails, keep sending rows to the client.
+    async fn write_stream(
+        query: &str,
+        mut stream: SendableRecordBatchStream,
+        writer: QueryResultWriter<'a, W>,
+    ) -> Result<()> {
+        // Fails the whole result set if the stream fails before yielding any rows.
+        let first = match stream
+            .try_next()
+            .await
+            .context(error::CollectRecordbatchSnafu)
+        {
+            Ok(first) => first,
+            Err(error) => return Self::write_query_error(query, error, writer).await,
+        };
+        let column_def = match create_mysql_column_def(&stream.schema()) {
+            Ok(column_def) => column_def,
+            Err(error) => return Self::write_query_error(query, error, writer).await,
+        };
+
+        let mut row_writer = writer.start(&column_def).await?;
+        if let Some(recordbatch) = first {
+            Self::write_recordbatch(&mut row_writer, &recordbatch).await?;
+        }
+        while let Some(recordbatch) = stream
+            .try_next()
+            .await
+            .context(error::CollectRecordbatchSnafu)?
+        {
+            Self::write_recordbatch(&mut row_writer, &recordbatch).await?;
+        }
+        match stream.stats() {
+            Some(stats) => row_writer.finish_with_info(&stats_info(&stats)).await?,
+            None => row_writer.finish().await?,
+        }
+        Ok(())
+    }
+
     async fn write_recordbatch(
         row_writer: &mut RowWriter<'_, W>,
         recordbatch: &RecordBatch,
@@ -184,12 +222,41 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     ) -> Result<()> {
         error!(error; "Failed to execute query '{}'", query);
 
-        let kind = ErrorKind::ER_INTERNAL_ERROR;
+        let kind = error_kind(&error, ErrorKind::ER_INTERNAL_ERROR);
         w.error(kind, error.to_string().as_bytes()).await?;
         Ok(())
     }
 }
 
+/// Returns the MySQL error kind matching the [StatusCode] of the error, so that clients could
+/// tell what the error is by its code. `default` is returned if there is no such kind.
+pub(crate) fn error_kind(err: &dyn ErrorExt, default: ErrorKind) -> ErrorKind {
+    match err.status_code() {
+        StatusCode::TableNotFound => ErrorKind::ER_NO_SUCH_TABLE,
+        StatusCode::TableAlreadyExists => ErrorKind::ER_TABLE_EXISTS_ERROR,
+        StatusCode::TableColumnNotFound => ErrorKind::ER_BAD_FIELD_ERROR,
+        StatusCode::TableColumnExists => ErrorKind::ER_DUP_FIELDNAME,
+        StatusCode::DatabaseNotFound => ErrorKind::ER_BAD_DB_ERROR,
+        StatusCode::InvalidSyntax => ErrorKind::ER_PARSE_ERROR,
+        StatusCode::Unsupported => ErrorKind::ER_NOT_SUPPORTED_YET,
+        StatusCode::AccessDenied => ErrorKind::ER_DBACCESS_DENIED_ERROR,
+        StatusCode::UserNotFound
+        | StatusCode::UnsupportedPasswordType
+        | StatusCode::UserPasswordMismatch
+        | StatusCode::AuthHeaderNotFound
+        | StatusCode::InvalidAuthHeader => ErrorKind::ER_ACCESS_DENIED_ERROR,
+        _ => default,
+    }
+}
+
+/// Renders the [ExecutionStats] as the info of the `OK` packet ending the result set.
+fn stats_info(stats: &ExecutionStats) -> String {
+    format!(
+        "elapsed: {} ms, rows scanned: {}, bytes read: {}, peak memory: {} bytes",
+        stats.elapsed_ms, stats.rows_scanned, stats.bytes_read, stats.peak_memory_bytes
+    )
+}
+
 fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
     let column_type = match column_schema.data_type {
         ConcreteDataType::Null(_) => Ok(ColumnType::MYSQL_TYPE_NULL),
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod auth_handler;
+mod copy;
 mod handler;
 mod server;
 
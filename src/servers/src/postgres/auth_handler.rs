@@ -24,7 +24,7 @@ use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
 use pgwire::messages::response::ErrorResponse;
 use pgwire::messages::startup::Authentication;
 use pgwire::messages::{PgWireBackendMessage, PgWireFrontendMessage};
-use session::context::QueryContextRef;
+use session::context::{QueryContextRef, UserInfo};
 
 use super::PostgresServerHandler;
 use crate::auth::{Identity, Password, UserProviderRef};
@@ -122,6 +122,9 @@ where
     if let Some(current_schema) = client.metadata().get(super::METADATA_SCHEMA) {
         query_context.set_current_schema(current_schema);
     }
+    if let Some(user) = client.metadata().get(super::METADATA_USER) {
+        query_context.set_current_user(UserInfo::new(user));
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,584 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support of `COPY ... TO STDOUT`, which streams the result of a table or a query
+//! back to the client in the COPY sub-protocol instead of writing it to a file.
+//! `COPY ... TO 'file'` is not handled here and goes through the normal query path.
+
+use std::fmt::Write;
+
+use datatypes::value::Value;
+use snafu::{ensure, OptionExt};
+
+use crate::error::{InvalidQuerySnafu, NotSupportedSnafu, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyFormat {
+    Text,
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CopyOptions {
+    pub format: CopyFormat,
+    pub header: bool,
+    pub delimiter: char,
+    pub null: String,
+    pub quote: char,
+}
+
+impl CopyOptions {
+    fn with_format(format: CopyFormat) -> Self {
+        match format {
+            CopyFormat::Text => Self {
+                format,
+                header: false,
+                delimiter: '\t',
+                null: "\\N".to_string(),
+                quote: '"',
+            },
+            CopyFormat::Csv => Self {
+                format,
+                header: false,
+                delimiter: ',',
+                null: String::new(),
+                quote: '"',
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CopyToStdout {
+    /// The query whose result is copied out.
+    pub query: String,
+    pub options: CopyOptions,
+}
+
+/// Parses `COPY { table [(column, ...)] | (query) } TO STDOUT [[WITH] options]`.
+///
+/// Both the parenthesized option list and the legacy option syntax used by older
+/// clients (`CSV HEADER DELIMITER ','`) are accepted. Returns `None` if the
+/// statement is not a `COPY ... TO STDOUT`.
+pub(crate) fn parse_copy_to_stdout(sql: &str) -> Result<Option<CopyToStdout>> {
+    let tokens = match tokenize(sql) {
+        Some(tokens) => tokens,
+        None => return Ok(None),
+    };
+    let mut parser = TokenParser { tokens, pos: 0 };
+    if !parser.parse_keyword("COPY") {
+        return Ok(None);
+    }
+
+    let query = match parser.next() {
+        Some(Token::Group(query)) => query,
+        Some(Token::Word(table)) => {
+            let columns = match parser.peek() {
+                Some(Token::Group(columns)) => {
+                    let columns = columns.clone();
+                    parser.pos += 1;
+                    columns
+                }
+                _ => "*".to_string(),
+            };
+            format!("SELECT {columns} FROM {table}")
+        }
+        _ => return Ok(None),
+    };
+    if !parser.parse_keyword("TO") || !parser.parse_keyword("STDOUT") {
+        return Ok(None);
+    }
+
+    let _ = parser.parse_keyword("WITH");
+    let options = match parser.peek() {
+        Some(Token::Group(options)) => {
+            let options = options.clone();
+            parser.pos += 1;
+            parse_options(&options)?
+        }
+        _ => parser.parse_legacy_options()?,
+    };
+    let _ = parser.next_if(|t| *t == Token::Semicolon);
+    ensure!(
+        parser.peek().is_none(),
+        InvalidQuerySnafu {
+            reason: format!("unexpected trailing tokens in COPY statement: {sql}"),
+        }
+    );
+
+    Ok(Some(CopyToStdout { query, options }))
+}
+
+fn parse_options(options: &str) -> Result<CopyOptions> {
+    let mut settings = Vec::new();
+    for option in split_top_level(options, ',') {
+        let tokens = tokenize(option).context(InvalidQuerySnafu {
+            reason: format!("invalid COPY option: {option}"),
+        })?;
+        let mut parser = TokenParser { tokens, pos: 0 };
+        let name = match parser.next() {
+            Some(Token::Word(name)) => name.to_ascii_uppercase(),
+            _ => {
+                return InvalidQuerySnafu {
+                    reason: format!("invalid COPY option: {option}"),
+                }
+                .fail()
+            }
+        };
+        let value = match parser.next() {
+            Some(Token::Word(v)) | Some(Token::Literal(v)) => Some(v),
+            None => None,
+            _ => {
+                return InvalidQuerySnafu {
+                    reason: format!("invalid value of COPY option {name}"),
+                }
+                .fail()
+            }
+        };
+        settings.push((name, value));
+    }
+
+    let format = match settings.iter().find(|(name, _)| name == "FORMAT") {
+        Some((_, value)) => parse_format(value.as_deref().unwrap_or_default())?,
+        None => CopyFormat::Text,
+    };
+    let mut copy_options = CopyOptions::with_format(format);
+    for (name, value) in settings {
+        match name.as_str() {
+            "FORMAT" => {}
+            "HEADER" => {
+                copy_options.header = match value.as_deref().map(str::to_ascii_lowercase) {
+                    None => true,
+                    Some(v) if matches!(v.as_str(), "true" | "on" | "1") => true,
+                    Some(v) if matches!(v.as_str(), "false" | "off" | "0") => false,
+                    Some(v) => {
+                        return InvalidQuerySnafu {
+                            reason: format!("HEADER requires a boolean value, got: {v}"),
+                        }
+                        .fail()
+                    }
+                }
+            }
+            "DELIMITER" => copy_options.delimiter = single_char(&name, value)?,
+            "QUOTE" => copy_options.quote = single_char(&name, value)?,
+            "NULL" => {
+                copy_options.null = value.context(InvalidQuerySnafu {
+                    reason: "NULL requires a string value",
+                })?
+            }
+            _ => {
+                return NotSupportedSnafu {
+                    feat: format!("COPY option {name}"),
+                }
+                .fail()
+            }
+        }
+    }
+    Ok(copy_options)
+}
+
+fn parse_format(format: &str) -> Result<CopyFormat> {
+    match format.to_ascii_lowercase().as_str() {
+        "text" => Ok(CopyFormat::Text),
+        "csv" => Ok(CopyFormat::Csv),
+        other => NotSupportedSnafu {
+            feat: format!("COPY format {other}"),
+        }
+        .fail(),
+    }
+}
+
+fn single_char(name: &str, value: Option<String>) -> Result<char> {
+    let value = value.unwrap_or_default();
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => InvalidQuerySnafu {
+            reason: format!("{name} must be a single one-byte character"),
+        }
+        .fail(),
+    }
+}
+
+/// Encodes the header line of the copied columns.
+pub(crate) fn encode_header(names: &[&str], options: &CopyOptions) -> String {
+    let mut line = String::new();
+    for (i, name) in names.iter().enumerate() {
+        if i > 0 {
+            line.push(options.delimiter);
+        }
+        encode_str(name, options, &mut line);
+    }
+    line.push('\n');
+    line
+}
+
+/// Encodes one row, including the trailing newline.
+pub(crate) fn encode_row<'a>(
+    values: impl Iterator<Item = &'a Value>,
+    options: &CopyOptions,
+) -> String {
+    let mut line = String::new();
+    for (i, value) in values.enumerate() {
+        if i > 0 {
+            line.push(options.delimiter);
+        }
+        match value {
+            Value::Null => line.push_str(&options.null),
+            Value::String(s) => encode_str(s.as_utf8(), options, &mut line),
+            Value::Binary(b) => {
+                let mut hex = String::with_capacity(2 + b.len() * 2);
+                hex.push_str("\\x");
+                for byte in b.iter() {
+                    // Safety: writing to a String never fails.
+                    write!(hex, "{byte:02x}").unwrap();
+                }
+                encode_str(&hex, options, &mut line);
+            }
+            value => encode_str(&value.to_string(), options, &mut line),
+        }
+    }
+    line.push('\n');
+    line
+}
+
+fn encode_str(s: &str, options: &CopyOptions, out: &mut String) {
+    match options.format {
+        CopyFormat::Text => {
+            for c in s.chars() {
+                match c {
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    c if c == options.delimiter => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                    c => out.push(c),
+                }
+            }
+        }
+        CopyFormat::Csv => {
+            // A non-null value equal to the null string must be quoted, otherwise it
+            // would be read back as null.
+            let need_quote = s == options.null
+                || s.contains(|c| {
+                    c == options.delimiter || c == options.quote || c == '\n' || c == '\r'
+                });
+            if need_quote {
+                out.push(options.quote);
+                for c in s.chars() {
+                    if c == options.quote {
+                        out.push(options.quote);
+                    }
+                    out.push(c);
+                }
+                out.push(options.quote);
+            } else {
+                out.push_str(s);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// An identifier, a keyword or a (possibly qualified) object name, kept as written.
+    Word(String),
+    /// A single quoted string literal, unescaped.
+    Literal(String),
+    /// Text between a pair of balanced parentheses.
+    Group(String),
+    Semicolon,
+}
+
+/// Splits the statement into [Token]s. Returns `None` on anything unexpected, in
+/// which case the statement is left to the SQL parser.
+fn tokenize(sql: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars = sql.char_indices().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            ';' => {
+                tokens.push(Token::Semicolon);
+                i += 1;
+            }
+            '\'' => {
+                let mut literal = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some((_, '\'')) if matches!(chars.get(i + 1), Some((_, '\''))) => {
+                            literal.push('\'');
+                            i += 2;
+                        }
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => {
+                            literal.push(*c);
+                            i += 1;
+                        }
+                        None => return None,
+                    }
+                }
+                i += 1;
+                tokens.push(Token::Literal(literal));
+            }
+            '(' => {
+                let mut depth = 0;
+                let mut in_quote = None;
+                let mut end = None;
+                while let Some((pos, c)) = chars.get(i) {
+                    match (in_quote, c) {
+                        (Some(q), c) if q == *c => in_quote = None,
+                        (Some(_), _) => {}
+                        (None, '\'' | '"') => in_quote = Some(*c),
+                        (None, '(') => depth += 1,
+                        (None, ')') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                end = Some(*pos);
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                let end = end?;
+                i += 1;
+                tokens.push(Token::Group(sql[start + 1..end].trim().to_string()));
+            }
+            _ => {
+                let mut in_quote = false;
+                while let Some((_, c)) = chars.get(i) {
+                    if *c == '"' {
+                        in_quote = !in_quote;
+                    } else if !in_quote && (c.is_whitespace() || matches!(c, '(' | ';' | '\'')) {
+                        break;
+                    }
+                    i += 1;
+                }
+                if in_quote {
+                    return None;
+                }
+                let end = chars.get(i).map(|(pos, _)| *pos).unwrap_or(sql.len());
+                tokens.push(Token::Word(sql[start..end].to_string()));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+/// Splits `s` by `sep`, ignoring the separators inside quotes.
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quote = None;
+    let mut start = 0;
+    for (pos, c) in s.char_indices() {
+        match (in_quote, c) {
+            (Some(q), c) if q == c => in_quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => in_quote = Some(c),
+            (None, c) if c == sep => {
+                parts.push(s[start..pos].trim());
+                start = pos + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].trim());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+struct TokenParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl TokenParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn next_if(&mut self, f: impl Fn(&Token) -> bool) -> Option<Token> {
+        match self.peek() {
+            Some(token) if f(token) => self.next(),
+            _ => None,
+        }
+    }
+
+    fn parse_keyword(&mut self, keyword: &str) -> bool {
+        self.next_if(|t| matches!(t, Token::Word(w) if w.eq_ignore_ascii_case(keyword)))
+            .is_some()
+    }
+
+    fn parse_literal(&mut self, option: &str) -> Result<String> {
+        let _ = self.parse_keyword("AS");
+        match self.next() {
+            Some(Token::Literal(v)) => Ok(v),
+            _ => InvalidQuerySnafu {
+                reason: format!("{option} requires a string value"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Parses options in the syntax before PostgreSQL 9.0, e.g. `CSV HEADER`.
+    fn parse_legacy_options(&mut self) -> Result<CopyOptions> {
+        let mut format = CopyFormat::Text;
+        let mut header = false;
+        let mut delimiter = None;
+        let mut null = None;
+        let mut quote = None;
+        while let Some(Token::Word(word)) = self.peek() {
+            let word = word.to_ascii_uppercase();
+            self.pos += 1;
+            match word.as_str() {
+                "CSV" => format = CopyFormat::Csv,
+                "HEADER" => header = true,
+                "DELIMITER" => delimiter = Some(self.parse_literal(&word)?),
+                "NULL" => null = Some(self.parse_literal(&word)?),
+                "QUOTE" => quote = Some(self.parse_literal(&word)?),
+                "BINARY" => {
+                    return NotSupportedSnafu {
+                        feat: "COPY format binary",
+                    }
+                    .fail()
+                }
+                _ => {
+                    return NotSupportedSnafu {
+                        feat: format!("COPY option {word}"),
+                    }
+                    .fail()
+                }
+            }
+        }
+
+        let mut options = CopyOptions::with_format(format);
+        options.header = header;
+        if delimiter.is_some() {
+            options.delimiter = single_char("DELIMITER", delimiter)?;
+        }
+        if quote.is_some() {
+            options.quote = single_char("QUOTE", quote)?;
+        }
+        if let Some(null) = null {
+            options.null = null;
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+
+    use super::*;
+
+    fn parse(sql: &str) -> CopyToStdout {
+        parse_copy_to_stdout(sql).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_copy_to_stdout() {
+        let copy = parse("COPY monitor TO STDOUT");
+        assert_eq!("SELECT * FROM monitor", copy.query);
+        assert_eq!(CopyOptions::with_format(CopyFormat::Text), copy.options);
+
+        let copy = parse("copy public.monitor (host, cpu) to stdout with (format csv, header);");
+        assert_eq!("SELECT host, cpu FROM public.monitor", copy.query);
+        assert_eq!(CopyFormat::Csv, copy.options.format);
+        assert!(copy.options.header);
+        assert_eq!(',', copy.options.delimiter);
+
+        let copy = parse(
+            "COPY (SELECT * FROM \"My Table\" WHERE host = 'a(b') TO STDOUT (FORMAT 'csv', DELIMITER '|', NULL 'NULL', HEADER false)",
+        );
+        assert_eq!("SELECT * FROM \"My Table\" WHERE host = 'a(b'", copy.query);
+        assert_eq!(
+            CopyOptions {
+                format: CopyFormat::Csv,
+                header: false,
+                delimiter: '|',
+                null: "NULL".to_string(),
+                quote: '"',
+            },
+            copy.options
+        );
+
+        // the syntax psql's \copy sends
+        let copy = parse("COPY monitor TO STDOUT CSV HEADER DELIMITER AS ';'");
+        assert_eq!(CopyFormat::Csv, copy.options.format);
+        assert!(copy.options.header);
+        assert_eq!(';', copy.options.delimiter);
+    }
+
+    #[test]
+    fn test_parse_not_copy_to_stdout() {
+        for sql in [
+            "SELECT * FROM monitor",
+            "COPY monitor TO 'monitor.parquet'",
+            "COPY monitor FROM STDIN",
+            "",
+        ] {
+            assert!(parse_copy_to_stdout(sql).unwrap().is_none(), "{sql}");
+        }
+
+        for sql in [
+            "COPY monitor TO STDOUT (FORMAT binary)",
+            "COPY monitor TO STDOUT BINARY",
+            "COPY monitor TO STDOUT (FORCE_QUOTE *)",
+            "COPY monitor TO STDOUT (DELIMITER '||')",
+            "COPY monitor TO STDOUT (HEADER maybe)",
+            "COPY monitor TO STDOUT CSV foo",
+        ] {
+            assert!(parse_copy_to_stdout(sql).is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_encode_row() {
+        let values = vec![
+            Value::String("host, \"1\"".into()),
+            Value::Null,
+            Value::Float64(0.5.into()),
+            Value::String("".into()),
+            Value::Binary([1u8, 255].as_slice().into()),
+            Value::String("a\tb\\c\n".into()),
+        ];
+
+        let text = CopyOptions::with_format(CopyFormat::Text);
+        assert_eq!(
+            "host, \"1\"\t\\N\t0.5\t\t\\\\x01ff\ta\\tb\\\\c\\n\n",
+            encode_row(values.iter(), &text)
+        );
+
+        let csv = CopyOptions::with_format(CopyFormat::Csv);
+        assert_eq!(
+            "\"host, \"\"1\"\"\",,0.5,\"\",\\x01ff,\"a\tb\\c\n\"\n",
+            encode_row(values.iter(), &csv)
+        );
+        assert_eq!("host,\"a,b\"\n", encode_header(&["host", "a,b"], &csv));
+    }
+}
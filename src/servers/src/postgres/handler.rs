@@ -12,36 +12,101 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::Debug;
 use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
 use common_query::Output;
 use common_recordbatch::error::Result as RecordBatchResult;
 use common_recordbatch::RecordBatch;
 use common_telemetry::timer;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{Schema, SchemaRef};
-use futures::{future, stream, Stream, StreamExt};
+use futures::{future, stream, Sink, SinkExt, Stream, StreamExt};
 use metrics::increment_counter;
 use pgwire::api::portal::{Format, Portal};
-use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler, StatementOrPortal};
+use pgwire::api::query::{
+    send_execution_response, send_query_response, ExtendedQueryHandler, SimpleQueryHandler,
+    StatementOrPortal,
+};
 use pgwire::api::results::{
     DataRowEncoder, DescribeResponse, FieldInfo, QueryResponse, Response, Tag,
 };
 use pgwire::api::stmt::QueryParser;
 use pgwire::api::store::MemPortalStore;
-use pgwire::api::{ClientInfo, Type};
+use pgwire::api::{ClientInfo, PgWireConnectionState, Type};
 use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
+use pgwire::messages::copy::{CopyData, CopyDone, CopyOutResponse};
+use pgwire::messages::response::{EmptyQueryResponse, ReadyForQuery, READY_STATUS_IDLE};
+use pgwire::messages::simplequery::Query;
+use pgwire::messages::PgWireBackendMessage;
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
 use sql::statements::statement::Statement;
 
+use super::copy::{self, CopyToStdout};
 use super::PostgresServerHandler;
 use crate::error::{self, Error, Result};
 
 #[async_trait]
 impl SimpleQueryHandler for PostgresServerHandler {
+    // Overrides the default implementation to serve `COPY ... TO STDOUT`, which
+    // doesn't fit in a `Response`. Other queries are still handled by `do_query`.
+    async fn on_query<C>(&self, client: &mut C, query: &Query) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        client.set_state(PgWireConnectionState::QueryInProgress);
+
+        match copy::parse_copy_to_stdout(query.query()) {
+            Ok(Some(copy)) => self.copy_to_stdout(client, copy).await?,
+            Ok(None) => {
+                let responses = self.do_query(client, query.query()).await?;
+                for response in responses {
+                    match response {
+                        Response::EmptyQuery => {
+                            client
+                                .feed(PgWireBackendMessage::EmptyQueryResponse(
+                                    EmptyQueryResponse::new(),
+                                ))
+                                .await?;
+                        }
+                        Response::Query(results) => {
+                            send_query_response(client, results, true).await?;
+                        }
+                        Response::Execution(tag) => {
+                            send_execution_response(client, tag).await?;
+                        }
+                        Response::Error(e) => {
+                            client
+                                .feed(PgWireBackendMessage::ErrorResponse((*e).into()))
+                                .await?;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                client
+                    .feed(PgWireBackendMessage::ErrorResponse(error_info(&e).into()))
+                    .await?;
+            }
+        }
+
+        client
+            .feed(PgWireBackendMessage::ReadyForQuery(ReadyForQuery::new(
+                READY_STATUS_IDLE,
+            )))
+            .await?;
+        client.flush().await?;
+        client.set_state(PgWireConnectionState::ReadyForQuery);
+        Ok(())
+    }
+
     async fn do_query<'a, C>(&self, _client: &C, query: &'a str) -> PgWireResult<Vec<Response<'a>>>
     where
         C: ClientInfo + Unpin + Send + Sync,
@@ -75,6 +140,138 @@ impl SimpleQueryHandler for PostgresServerHandler {
     }
 }
 
+impl PostgresServerHandler {
+    /// Streams the result of the copied query to client with `CopyData` messages,
+    /// one for each row.
+    async fn copy_to_stdout<C>(&self, client: &mut C, copy: CopyToStdout) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let output = self
+            .query_handler
+            .do_query(&copy.query, self.query_ctx.clone())
+            .await
+            .into_iter()
+            .next();
+        let mut stream = match output {
+            Some(Ok(Output::Stream(stream))) => stream,
+            Some(Ok(Output::RecordBatches(recordbatches))) => recordbatches.as_stream(),
+            // An empty query like `COPY () TO STDOUT` has no output.
+            Some(Ok(Output::AffectedRows(_))) | None => {
+                let e = error::InvalidQuerySnafu {
+                    reason: format!("COPY source is not a query: {}", copy.query),
+                }
+                .build();
+                client
+                    .feed(PgWireBackendMessage::ErrorResponse(error_info(&e).into()))
+                    .await?;
+                return Ok(());
+            }
+            Some(Err(e)) => {
+                client
+                    .feed(PgWireBackendMessage::ErrorResponse(error_info(&e).into()))
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        let schema = stream.schema();
+        let columns = schema.num_columns();
+        client
+            .feed(PgWireBackendMessage::CopyOutResponse(CopyOutResponse::new(
+                0,
+                columns as i16,
+                vec![0; columns],
+            )))
+            .await?;
+        if copy.options.header {
+            let names = schema
+                .column_schemas()
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>();
+            let header = copy::encode_header(&names, &copy.options);
+            client
+                .feed(PgWireBackendMessage::CopyData(CopyData::new(header.into())))
+                .await?;
+        }
+
+        let mut rows = 0;
+        while let Some(batch) = stream.next().await {
+            let batch = match batch {
+                Ok(batch) => batch,
+                Err(e) => {
+                    // Aborts the copy, client discards the received data.
+                    client
+                        .feed(PgWireBackendMessage::ErrorResponse(error_info(&e).into()))
+                        .await?;
+                    return Ok(());
+                }
+            };
+            for row in batch.rows() {
+                let line = copy::encode_row(row.iter(), &copy.options);
+                client
+                    .feed(PgWireBackendMessage::CopyData(CopyData::new(line.into())))
+                    .await?;
+                rows += 1;
+            }
+            client.flush().await?;
+        }
+
+        client
+            .feed(PgWireBackendMessage::CopyDone(CopyDone::new()))
+            .await?;
+        send_execution_response(client, Tag::new_for_execution("COPY", Some(rows))).await
+    }
+}
+
+fn error_info(e: &dyn ErrorExt) -> ErrorInfo {
+    ErrorInfo::new(
+        "ERROR".to_string(),
+        sqlstate(e.status_code()).to_string(),
+        e.to_string(),
+    )
+}
+
+/// Returns the SQLSTATE matching the [StatusCode], so that clients could tell what the error
+/// is by its code.
+fn sqlstate(status_code: StatusCode) -> &'static str {
+    match status_code {
+        // invalid_parameter_value
+        StatusCode::InvalidArguments => "22023",
+        // syntax_error
+        StatusCode::InvalidSyntax => "42601",
+        // undefined_table
+        StatusCode::TableNotFound => "42P01",
+        // duplicate_table
+        StatusCode::TableAlreadyExists => "42P07",
+        // undefined_column
+        StatusCode::TableColumnNotFound => "42703",
+        // duplicate_column
+        StatusCode::TableColumnExists => "42701",
+        // invalid_schema_name
+        StatusCode::DatabaseNotFound => "3F000",
+        // feature_not_supported
+        StatusCode::Unsupported => "0A000",
+        // insufficient_resources
+        StatusCode::RuntimeResourcesExhausted => "53000",
+        // data_corrupted
+        StatusCode::StorageCorrupted => "XX001",
+        // invalid_authorization_specification
+        StatusCode::UserNotFound
+        | StatusCode::UnsupportedPasswordType
+        | StatusCode::UserPasswordMismatch
+        | StatusCode::AuthHeaderNotFound
+        | StatusCode::InvalidAuthHeader => "28000",
+        // insufficient_privilege
+        StatusCode::AccessDenied => "42501",
+        // internal_error
+        _ => "XX000",
+    }
+}
+
 fn output_to_query_response<'a>(
     output: Result<Output>,
     field_format: &Format,
@@ -92,11 +289,7 @@ fn output_to_query_response<'a>(
             let schema = recordbatches.schema();
             recordbatches_to_query_response(recordbatches.as_stream(), schema, field_format)
         }
-        Err(e) => Ok(Response::Error(Box::new(ErrorInfo::new(
-            "ERROR".to_string(),
-            "XX000".to_string(),
-            e.to_string(),
-        )))),
+        Err(e) => Ok(Response::Error(Box::new(error_info(&e)))),
     }
 }
 
@@ -429,6 +622,14 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_sqlstate() {
+        assert_eq!("42P01", sqlstate(StatusCode::TableNotFound));
+        assert_eq!("42601", sqlstate(StatusCode::InvalidSyntax));
+        assert_eq!("28000", sqlstate(StatusCode::UserPasswordMismatch));
+        assert_eq!("XX000", sqlstate(StatusCode::Internal));
+    }
+
     #[test]
     fn test_schema_convert() {
         let column_schemas = vec![
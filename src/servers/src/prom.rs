@@ -20,7 +20,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use axum::body::BoxBody;
 use axum::extract::{Query, State};
-use axum::{routing, Form, Json, Router};
+use axum::http::HeaderMap;
+use axum::{routing, Extension, Form, Json, Router};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
@@ -30,7 +31,8 @@ use common_telemetry::info;
 use common_time::util::current_time_rfc3339;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::scalars::ScalarVector;
-use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+use datatypes::value::Value;
+use datatypes::vectors::{StringVector, TimestampMillisecondVector};
 use futures::FutureExt;
 use promql_parser::label::METRIC_NAME;
 use promql_parser::parser::{
@@ -40,7 +42,7 @@ use promql_parser::parser::{
 use query::parser::PromQuery;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{QueryContext, QueryContextRef, UserInfo};
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{oneshot, Mutex};
@@ -50,11 +52,13 @@ use tower_http::compression::CompressionLayer;
 use tower_http::trace::TraceLayer;
 
 use crate::auth::UserProviderRef;
+use crate::database_alias::DatabaseAliasesRef;
 use crate::error::{
     AlreadyStartedSnafu, CollectRecordbatchSnafu, InternalSnafu, NotSupportedSnafu, Result,
     StartHttpSnafu,
 };
 use crate::http::authorize::HttpAuth;
+use crate::http::catalog_from_headers;
 use crate::server::Server;
 
 pub const PROM_API_VERSION: &str = "v1";
@@ -71,6 +75,23 @@ pub struct PromServer {
     query_handler: PromHandlerRef,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     user_provider: Option<UserProviderRef>,
+    database_aliases: DatabaseAliasesRef,
+    response_options: PromResponseOptions,
+}
+
+/// Options of building the responses of the Prometheus HTTP API.
+#[derive(Debug, Clone, Copy)]
+pub struct PromResponseOptions {
+    /// Sorts the series by their label sets and the samples of each series by timestamps,
+    /// so the responses are stable across queries. Otherwise the series are returned in
+    /// the order the query yields, which may vary with the regions responding first.
+    pub sort_series: bool,
+}
+
+impl Default for PromResponseOptions {
+    fn default() -> Self {
+        Self { sort_series: true }
+    }
 }
 
 impl PromServer {
@@ -79,6 +100,8 @@ impl PromServer {
             query_handler,
             shutdown_tx: Mutex::new(None),
             user_provider: None,
+            database_aliases: Default::default(),
+            response_options: PromResponseOptions::default(),
         })
     }
 
@@ -87,6 +110,14 @@ impl PromServer {
         self.user_provider = Some(user_provider);
     }
 
+    pub fn set_database_aliases(&mut self, database_aliases: DatabaseAliasesRef) {
+        self.database_aliases = database_aliases;
+    }
+
+    pub fn set_response_options(&mut self, response_options: PromResponseOptions) {
+        self.response_options = response_options;
+    }
+
     pub fn make_app(&self) -> Router {
         // TODO(ruihang): implement format_query, series, labels, values, query_examplars and targets methods
 
@@ -102,9 +133,12 @@ impl PromServer {
                 ServiceBuilder::new()
                     .layer(TraceLayer::new_for_http())
                     .layer(CompressionLayer::new())
+                    .layer(Extension(self.database_aliases.clone()))
+                    .layer(Extension(self.response_options))
                     // custom layer
                     .layer(AsyncRequireAuthorizationLayer::new(
-                        HttpAuth::<BoxBody>::new(self.user_provider.clone()),
+                        HttpAuth::<BoxBody>::new(self.user_provider.clone())
+                            .with_database_aliases(self.database_aliases.clone()),
                     )),
             )
     }
@@ -160,7 +194,8 @@ impl Server for PromServer {
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct PromSeries {
-    pub metric: HashMap<String, String>,
+    /// Labels of the series, serialized in the order of label names.
+    pub metric: BTreeMap<String, String>,
     /// For [ValueType::Matrix] result type
     pub values: Vec<(f64, String)>,
     /// For [ValueType::Vector] result type
@@ -213,11 +248,13 @@ impl PromJsonResponse {
         })
     }
 
-    /// Convert from `Result<Output>`
+    /// Convert from `Result<Output>` of PromQL `query`
     pub async fn from_query_result(
         result: Result<Output>,
+        query: &str,
         metric_name: String,
         result_type: Option<ValueType>,
+        options: PromResponseOptions,
     ) -> Json<Self> {
         let response: Result<Json<Self>> = try {
             let json = match result? {
@@ -225,6 +262,7 @@ impl PromJsonResponse {
                     batches,
                     metric_name,
                     result_type,
+                    options,
                 )?),
                 Output::Stream(stream) => {
                     let record_batches = RecordBatches::try_collect(stream)
@@ -234,6 +272,7 @@ impl PromJsonResponse {
                         record_batches,
                         metric_name,
                         result_type,
+                        options,
                     )?)
                 }
                 Output::AffectedRows(_) => Self::error(
@@ -259,7 +298,10 @@ impl PromJsonResponse {
                         ..Default::default()
                     })
                 } else {
-                    Self::error(err.status_code().to_string(), err.to_string())
+                    // point at the offending selector in query if possible
+                    let reason = promql::diagnostic::diagnose(query, &err)
+                        .unwrap_or_else(|| err.to_string());
+                    Self::error(err.status_code().to_string(), reason)
                 }
             }
         }
@@ -270,6 +312,7 @@ impl PromJsonResponse {
         batches: RecordBatches,
         metric_name: String,
         result_type: Option<ValueType>,
+        options: PromResponseOptions,
     ) -> Result<PromData> {
         // infer semantic type of each column from schema.
         // TODO(ruihang): wish there is a better way to do this.
@@ -284,7 +327,7 @@ impl PromJsonResponse {
                         timestamp_column_index = Some(i);
                     }
                 }
-                ConcreteDataType::Float64(_) => {
+                ref data_type if is_sample_type(data_type) => {
                     if first_field_column_index.is_none() {
                         first_field_column_index = Some(i);
                     }
@@ -304,7 +347,9 @@ impl PromJsonResponse {
         })?;
 
         let metric_name = (METRIC_NAME.to_string(), metric_name);
-        let mut buffer = BTreeMap::<Vec<(String, String)>, Vec<(f64, String)>>::new();
+        // Series in the order of their first samples, and their positions keyed by tags.
+        let mut buffer = Vec::<(Vec<(String, String)>, Vec<(f64, String)>)>::new();
+        let mut series_indices = HashMap::<Vec<(String, String)>, usize>::new();
 
         for batch in batches.iter() {
             // prepare things...
@@ -327,11 +372,7 @@ impl PromJsonResponse {
                 .as_any()
                 .downcast_ref::<TimestampMillisecondVector>()
                 .unwrap();
-            let field_column = batch
-                .column(first_field_column_index)
-                .as_any()
-                .downcast_ref::<Float64Vector>()
-                .unwrap();
+            let field_column = batch.column(first_field_column_index);
 
             // assemble rows
             for row_index in 0..batch.num_rows() {
@@ -349,15 +390,27 @@ impl PromJsonResponse {
                 let timestamp_millis: i64 = timestamp_column.get_data(row_index).unwrap().into();
                 let timestamp = timestamp_millis as f64 / 1000.0;
 
-                // retrieve value
-                let value =
-                    Into::<f64>::into(field_column.get_data(row_index).unwrap()).to_string();
+                // retrieve value, skip the row if it's null
+                let Some(value) = value_to_f64(&field_column.get(row_index)) else {
+                    continue;
+                };
+                let value = value.to_string();
+
+                let index = *series_indices.entry(tags).or_insert_with_key(|tags| {
+                    buffer.push((tags.clone(), Vec::new()));
+                    buffer.len() - 1
+                });
+                buffer[index].1.push((timestamp, value));
+            }
+        }
 
-                buffer.entry(tags).or_default().push((timestamp, value));
+        if options.sort_series {
+            for (_, values) in &mut buffer {
+                values.sort_by(|a, b| a.0.total_cmp(&b.0));
             }
         }
 
-        let result = buffer
+        let mut result = buffer
             .into_iter()
             .map(|(tags, mut values)| {
                 let metric = tags.into_iter().collect();
@@ -379,6 +432,9 @@ impl PromJsonResponse {
                 }
             })
             .collect::<Result<Vec<_>>>()?;
+        if options.sort_series {
+            result.sort_by(|a, b| a.metric.cmp(&b.metric));
+        }
 
         let result_type_string = result_type.map(|t| t.to_string()).unwrap_or_default();
         let data = PromData {
@@ -402,6 +458,10 @@ pub struct InstantQuery {
 pub async fn instant_query(
     State(handler): State<PromHandlerRef>,
     Query(params): Query<InstantQuery>,
+    Extension(user_info): Extension<UserInfo>,
+    Extension(database_aliases): Extension<DatabaseAliasesRef>,
+    Extension(response_options): Extension<PromResponseOptions>,
+    headers: HeaderMap,
     Form(form_params): Form<InstantQuery>,
 ) -> Json<PromJsonResponse> {
     // Extract time from query string, or use current server time if not specified.
@@ -417,14 +477,23 @@ pub async fn instant_query(
     };
 
     let db = &params.db.unwrap_or(DEFAULT_SCHEMA_NAME.to_string());
-    let (catalog, schema) = super::parse_catalog_and_schema_from_client_database_name(db);
+    let (catalog, schema) =
+        database_aliases.resolve_catalog_and_schema(catalog_from_headers(&headers), db);
 
-    let query_ctx = QueryContext::with(catalog, schema);
+    let query_ctx = QueryContext::with(&catalog, &schema);
+    query_ctx.set_current_user(user_info);
 
     let result = handler.do_query(&prom_query, Arc::new(query_ctx)).await;
     let (metric_name, result_type) =
         retrieve_metric_name_and_result_type(&prom_query.query).unwrap_or_default();
-    PromJsonResponse::from_query_result(result, metric_name, result_type).await
+    PromJsonResponse::from_query_result(
+        result,
+        &prom_query.query,
+        metric_name,
+        result_type,
+        response_options,
+    )
+    .await
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -441,6 +510,10 @@ pub struct RangeQuery {
 pub async fn range_query(
     State(handler): State<PromHandlerRef>,
     Query(params): Query<RangeQuery>,
+    Extension(user_info): Extension<UserInfo>,
+    Extension(database_aliases): Extension<DatabaseAliasesRef>,
+    Extension(response_options): Extension<PromResponseOptions>,
+    headers: HeaderMap,
     Form(form_params): Form<RangeQuery>,
 ) -> Json<PromJsonResponse> {
     let prom_query = PromQuery {
@@ -451,20 +524,29 @@ pub async fn range_query(
     };
 
     let db = &params.db.unwrap_or(DEFAULT_SCHEMA_NAME.to_string());
-    let (catalog, schema) = super::parse_catalog_and_schema_from_client_database_name(db);
+    let (catalog, schema) =
+        database_aliases.resolve_catalog_and_schema(catalog_from_headers(&headers), db);
 
-    let query_ctx = QueryContext::with(catalog, schema);
+    let query_ctx = QueryContext::with(&catalog, &schema);
+    query_ctx.set_current_user(user_info);
 
     let result = handler.do_query(&prom_query, Arc::new(query_ctx)).await;
     let (metric_name, _) =
         retrieve_metric_name_and_result_type(&prom_query.query).unwrap_or_default();
-    PromJsonResponse::from_query_result(result, metric_name, Some(ValueType::Matrix)).await
+    PromJsonResponse::from_query_result(
+        result,
+        &prom_query.query,
+        metric_name,
+        Some(ValueType::Matrix),
+        response_options,
+    )
+    .await
 }
 
 pub(crate) fn retrieve_metric_name_and_result_type(
     promql: &str,
 ) -> Option<(String, Option<ValueType>)> {
-    let promql_expr = promql_parser::parser::parse(promql).ok()?;
+    let promql_expr = promql::parser::parse(promql).ok()?;
     let metric_name = promql_expr_to_metric_name(&promql_expr)?;
     let result_type = Some(promql_expr.value_type());
 
@@ -497,3 +579,187 @@ fn promql_expr_to_metric_name(expr: &PromqlExpr) -> Option<String> {
         }
     }
 }
+
+/// Whether a column of `data_type` can be returned as the sample values of series.
+fn is_sample_type(data_type: &ConcreteDataType) -> bool {
+    data_type.is_float()
+        || data_type.is_unsigned()
+        || data_type.is_boolean()
+        || matches!(
+            data_type,
+            ConcreteDataType::Int8(_)
+                | ConcreteDataType::Int16(_)
+                | ConcreteDataType::Int32(_)
+                | ConcreteDataType::Int64(_)
+        )
+}
+
+/// Converts a sample value to f64, booleans are converted to 1 or 0.
+fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Boolean(v) => Some(if *v { 1.0 } else { 0.0 }),
+        Value::UInt8(v) => Some(*v as f64),
+        Value::UInt16(v) => Some(*v as f64),
+        Value::UInt32(v) => Some(*v as f64),
+        Value::UInt64(v) => Some(*v as f64),
+        Value::Int8(v) => Some(*v as f64),
+        Value::Int16(v) => Some(*v as f64),
+        Value::Int32(v) => Some(*v as f64),
+        Value::Int64(v) => Some(*v as f64),
+        Value::Float32(v) => Some(v.0 as f64),
+        Value::Float64(v) => Some(v.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::VectorRef;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{BooleanVector, Float64Vector, UInt64Vector};
+
+    use super::*;
+
+    fn new_batches(rows: &[(i64, f64, &str)]) -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("val", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(TimestampMillisecondVector::from_vec(
+                rows.iter().map(|row| row.0).collect(),
+            )),
+            Arc::new(Float64Vector::from_vec(
+                rows.iter().map(|row| row.1).collect(),
+            )),
+            Arc::new(StringVector::from(
+                rows.iter().map(|row| row.2).collect::<Vec<_>>(),
+            )),
+        ];
+        RecordBatches::try_from_columns(schema, columns).unwrap()
+    }
+
+    fn series(data: &PromData) -> Vec<(String, Vec<f64>)> {
+        data.result
+            .iter()
+            .map(|series| {
+                (
+                    series.metric["host"].clone(),
+                    series.values.iter().map(|(ts, _)| *ts).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_record_batches_to_data_ordering() {
+        // Samples of different regions are interleaved.
+        let rows = [
+            (2000, 1.0, "c"),
+            (1000, 2.0, "a"),
+            (1000, 3.0, "c"),
+            (2000, 4.0, "a"),
+            (1000, 5.0, "b"),
+        ];
+
+        let data = PromJsonResponse::record_batches_to_data(
+            new_batches(&rows),
+            "cpu".to_string(),
+            Some(ValueType::Matrix),
+            PromResponseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                ("a".to_string(), vec![1.0, 2.0]),
+                ("b".to_string(), vec![1.0]),
+                ("c".to_string(), vec![1.0, 2.0]),
+            ],
+            series(&data)
+        );
+        assert_eq!(
+            BTreeMap::from([
+                (METRIC_NAME.to_string(), "cpu".to_string()),
+                ("host".to_string(), "a".to_string()),
+            ]),
+            data.result[0].metric
+        );
+
+        // Without sorting, series and samples are in the order of the query output.
+        let data = PromJsonResponse::record_batches_to_data(
+            new_batches(&rows),
+            "cpu".to_string(),
+            Some(ValueType::Matrix),
+            PromResponseOptions { sort_series: false },
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                ("c".to_string(), vec![2.0, 1.0]),
+                ("a".to_string(), vec![1.0, 2.0]),
+                ("b".to_string(), vec![1.0]),
+            ],
+            series(&data)
+        );
+
+        // The vector result takes the latest sample of each series.
+        let data = PromJsonResponse::record_batches_to_data(
+            new_batches(&rows),
+            "cpu".to_string(),
+            Some(ValueType::Vector),
+            PromResponseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(Some((2.0, "1".to_string())), data.result[2].value);
+    }
+
+    #[test]
+    fn test_record_batches_to_data_non_float_values() {
+        let new_batches = |data_type: ConcreteDataType, values: VectorRef| {
+            let schema = Arc::new(Schema::new(vec![
+                ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                ColumnSchema::new("val", data_type, true),
+            ]));
+            let columns: Vec<VectorRef> = vec![
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1000, 2000, 3000])),
+                values,
+            ];
+            RecordBatches::try_from_columns(schema, columns).unwrap()
+        };
+        let values = |batches| {
+            let data = PromJsonResponse::record_batches_to_data(
+                batches,
+                "up".to_string(),
+                Some(ValueType::Matrix),
+                PromResponseOptions::default(),
+            )
+            .unwrap();
+            data.result[0]
+                .values
+                .iter()
+                .map(|(_, value)| value.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let batches = new_batches(
+            ConcreteDataType::boolean_datatype(),
+            Arc::new(BooleanVector::from(vec![Some(true), None, Some(false)])),
+        );
+        assert_eq!(vec!["1", "0"], values(batches));
+
+        let batches = new_batches(
+            ConcreteDataType::uint64_datatype(),
+            Arc::new(UInt64Vector::from_vec(vec![1, 10, 100])),
+        );
+        assert_eq!(vec!["1", "10", "100"], values(batches));
+    }
+}
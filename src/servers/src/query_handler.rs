@@ -32,6 +32,7 @@ use api::prometheus::remote::{ReadRequest, WriteRequest};
 use async_trait::async_trait;
 use common_query::Output;
 use session::context::QueryContextRef;
+use table::requests::AdminRequest;
 
 use crate::error::Result;
 use crate::influxdb::InfluxdbRequest;
@@ -42,10 +43,13 @@ pub type OpentsdbProtocolHandlerRef = Arc<dyn OpentsdbProtocolHandler + Send + S
 pub type InfluxdbLineProtocolHandlerRef = Arc<dyn InfluxdbLineProtocolHandler + Send + Sync>;
 pub type PrometheusProtocolHandlerRef = Arc<dyn PrometheusProtocolHandler + Send + Sync>;
 pub type ScriptHandlerRef = Arc<dyn ScriptHandler + Send + Sync>;
+pub type AdminHandlerRef = Arc<dyn AdminHandler + Send + Sync>;
 
 #[async_trait]
 pub trait ScriptHandler {
     async fn insert_script(&self, schema: &str, name: &str, script: &str) -> Result<()>;
+    /// Registers the scalar function exported by the WASM module as an UDF.
+    async fn insert_wasm_function(&self, schema: &str, name: &str, module: &[u8]) -> Result<()>;
     async fn execute_script(
         &self,
         schema: &str,
@@ -54,6 +58,13 @@ pub trait ScriptHandler {
     ) -> Result<Output>;
 }
 
+/// Handles the typed admin requests (compaction, fencing...) that the frontend sends to the
+/// datanodes as Flight actions.
+#[async_trait]
+pub trait AdminHandler {
+    async fn do_admin(&self, request: AdminRequest) -> Result<Output>;
+}
+
 #[async_trait]
 pub trait InfluxdbLineProtocolHandler {
     /// A successful request will not return a response.
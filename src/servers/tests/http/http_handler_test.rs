@@ -13,20 +13,36 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
+use async_trait::async_trait;
 use axum::body::Body;
 use axum::extract::{Json, Query, RawBody, State};
+use axum::http::HeaderMap;
 use axum::Form;
+use common_query::Output;
+use common_recordbatch::{RecordBatch, RecordBatches};
 use common_telemetry::metric;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::StringVector;
 use metrics::counter;
-use servers::http::{handler as http_handler, script as script_handler, ApiState, JsonOutput};
+use query::parser::PromQuery;
+use servers::error::NotSupportedSnafu;
+use servers::health_checker::HealthChecker;
+use servers::http::{
+    handler as http_handler, migrate, script as script_handler, ApiState, JsonOutput,
+};
 use servers::metrics_handler::MetricsHandler;
-use session::context::UserInfo;
+use servers::query_handler::sql::SqlQueryHandler;
+use servers::GREPTIME_CATALOG_HEADER;
+use session::context::{QueryContextRef, UserInfo};
+use sql::statements::statement::Statement;
 use table::test_util::MemTable;
 
 use crate::{
-    create_testing_script_handler, create_testing_sql_query_handler, ScriptHandlerRef,
-    ServerSqlQueryHandlerRef,
+    create_testing_instance, create_testing_script_handler, create_testing_sql_query_handler,
+    ScriptHandlerRef, ServerSqlQueryHandlerRef,
 };
 
 #[tokio::test]
@@ -39,6 +55,7 @@ async fn test_sql_not_provided() {
         }),
         Query(http_handler::SqlQuery::default()),
         axum::Extension(UserInfo::default()),
+        HeaderMap::new(),
         Form(http_handler::SqlQuery::default()),
     )
     .await;
@@ -64,6 +81,7 @@ async fn test_sql_output_rows() {
         }),
         query,
         axum::Extension(UserInfo::default()),
+        HeaderMap::new(),
         Form(http_handler::SqlQuery::default()),
     )
     .await;
@@ -110,6 +128,7 @@ async fn test_sql_form() {
         }),
         Query(http_handler::SqlQuery::default()),
         axum::Extension(UserInfo::default()),
+        HeaderMap::new(),
         form,
     )
     .await;
@@ -142,6 +161,43 @@ async fn test_sql_form() {
     }
 }
 
+#[tokio::test]
+async fn test_sql_catalog_header() {
+    let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
+    let state = ApiState {
+        sql_handler,
+        script_handler: None,
+    };
+
+    let mut headers = HeaderMap::new();
+    let _ = headers.insert(GREPTIME_CATALOG_HEADER, "greptime".parse().unwrap());
+    let Json(json) = http_handler::sql(
+        State(state.clone()),
+        create_query(),
+        axum::Extension(UserInfo::default()),
+        headers,
+        Form(http_handler::SqlQuery::default()),
+    )
+    .await;
+    assert!(json.success(), "{json:?}");
+
+    let mut headers = HeaderMap::new();
+    let _ = headers.insert(GREPTIME_CATALOG_HEADER, "other".parse().unwrap());
+    let Json(json) = http_handler::sql(
+        State(state),
+        create_query(),
+        axum::Extension(UserInfo::default()),
+        headers,
+        Form(http_handler::SqlQuery::default()),
+    )
+    .await;
+    assert!(!json.success());
+    assert_eq!(
+        Some(&"Database not found: other-public".to_string()),
+        json.error()
+    );
+}
+
 #[tokio::test]
 async fn test_metrics() {
     metric::init_default_metrics_recorder();
@@ -321,6 +377,57 @@ def test(n, **params)  -> vector[i64]:
     }
 }
 
+#[tokio::test]
+async fn test_wasm_function() {
+    common_telemetry::init_default_ut_logging();
+
+    let module = r#"
+(module
+  (func (export "add_one") (param i64) (result i64)
+    local.get 0
+    i64.const 1
+    i64.add))
+"#;
+    let instance = Arc::new(create_testing_instance(MemTable::default_numbers_table()));
+    let state = ApiState {
+        sql_handler: instance.clone(),
+        script_handler: Some(instance),
+    };
+
+    let Json(json) = script_handler::scripts(
+        State(state.clone()),
+        Query(script_handler::ScriptQuery {
+            db: Some("test".to_string()),
+            name: Some("add_one".to_string()),
+            engine: Some("wasm".to_string()),
+            ..Default::default()
+        }),
+        RawBody(Body::from(module)),
+    )
+    .await;
+    assert!(json.success(), "{json:?}");
+
+    let Json(json) = http_handler::sql(
+        State(state),
+        Query(http_handler::SqlQuery {
+            sql: Some("select add_one(uint32s) as n from numbers limit 3".to_string()),
+            ..Default::default()
+        }),
+        axum::Extension(UserInfo::default()),
+        HeaderMap::new(),
+        Form(http_handler::SqlQuery::default()),
+    )
+    .await;
+    assert!(json.success(), "{json:?}");
+    match &json.output().unwrap()[0] {
+        JsonOutput::Records(records) => {
+            let rows = serde_json::to_string(records.rows()).unwrap();
+            assert_eq!("[[1],[2],[3]]", rows);
+        }
+        _ => unreachable!(),
+    }
+}
+
 fn create_script_query() -> Query<script_handler::ScriptQuery> {
     Query(script_handler::ScriptQuery {
         db: Some("test".to_string()),
@@ -351,17 +458,129 @@ fn create_form() -> Form<http_handler::SqlQuery> {
     })
 }
 
-/// Currently the payload of response should be simply an empty json "{}";
+/// The payload of response should be simply an empty json "{}" without warnings.
 #[tokio::test]
 async fn test_health() {
-    let expected_json = http_handler::HealthResponse {};
+    let expected_json = http_handler::HealthResponse::default();
     let expected_json_str = "{}".to_string();
 
     let query = http_handler::HealthQuery {};
-    let Json(json) = http_handler::health(Query(query)).await;
+    let Json(json) = http_handler::health(State(None), Query(query)).await;
     assert_eq!(json, expected_json);
     assert_eq!(
         serde_json::ser::to_string(&json).unwrap(),
         expected_json_str
     );
 }
+
+struct DiskLowChecker;
+
+#[async_trait]
+impl HealthChecker for DiskLowChecker {
+    async fn warnings(&self) -> Vec<String> {
+        vec!["disk is low".to_string()]
+    }
+}
+
+#[tokio::test]
+async fn test_health_with_warnings() {
+    let query = http_handler::HealthQuery {};
+    let Json(json) =
+        http_handler::health(State(Some(Arc::new(DiskLowChecker))), Query(query)).await;
+    assert_eq!(json.warnings, vec!["disk is low".to_string()]);
+    assert_eq!(
+        serde_json::ser::to_string(&json).unwrap(),
+        r#"{"warnings":["disk is low"]}"#
+    );
+}
+
+/// Records the queries of the migrate handler with the user issuing them, and fails
+/// the statements creating `fail_table`.
+#[derive(Default)]
+struct MigrationRecorder {
+    queries: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait]
+impl SqlQueryHandler for MigrationRecorder {
+    type Error = servers::error::Error;
+
+    async fn do_query(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> Vec<servers::error::Result<Output>> {
+        self.queries.lock().unwrap().push((
+            query.to_string(),
+            query_ctx.current_user().username().to_string(),
+        ));
+        if query.contains("fail_table") {
+            return vec![NotSupportedSnafu { feat: "fail_table" }.fail()];
+        }
+        if query.contains("information_schema.tables") {
+            let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+                "table_name",
+                ConcreteDataType::string_datatype(),
+                false,
+            )]));
+            let batch = RecordBatch::new(
+                schema.clone(),
+                vec![Arc::new(StringVector::from(vec![migrate::MIGRATIONS_TABLE])) as _],
+            )
+            .unwrap();
+            let batches = RecordBatches::try_new(schema, vec![batch]).unwrap();
+            return vec![Ok(Output::RecordBatches(batches))];
+        }
+        vec![Ok(Output::AffectedRows(0))]
+    }
+
+    async fn do_promql_query(
+        &self,
+        _: &PromQuery,
+        _: QueryContextRef,
+    ) -> Vec<servers::error::Result<Output>> {
+        unimplemented!()
+    }
+
+    async fn do_describe(
+        &self,
+        _: Statement,
+        _: QueryContextRef,
+    ) -> servers::error::Result<Option<Schema>> {
+        unimplemented!()
+    }
+
+    async fn is_valid_schema(&self, _: &str, _: &str) -> servers::error::Result<bool> {
+        Ok(true)
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_records_partial_migration() {
+    let recorder = Arc::new(MigrationRecorder::default());
+    let result = migrate::migrate(
+        State(ApiState {
+            sql_handler: recorder.clone(),
+            script_handler: None,
+        }),
+        Query(migrate::MigrateQuery {
+            version: Some("v1".to_string()),
+            ..Default::default()
+        }),
+        axum::Extension(UserInfo::new("migrator")),
+        HeaderMap::new(),
+        "CREATE TABLE ok_table (ts TIMESTAMP TIME INDEX); \
+         CREATE TABLE fail_table (ts TIMESTAMP TIME INDEX);"
+            .to_string(),
+    )
+    .await;
+    assert!(result.is_err());
+
+    let queries = recorder.queries.lock().unwrap();
+    assert!(queries.iter().all(|(_, user)| user == "migrator"));
+    let (record, _) = queries.last().unwrap();
+    assert!(record.starts_with(&format!("INSERT INTO {}", migrate::MIGRATIONS_TABLE)));
+    assert!(record.contains("ok_table"), "{record}");
+    assert!(!record.contains("fail_table"), "{record}");
+    assert!(record.ends_with("'partial')"), "{record}");
+}
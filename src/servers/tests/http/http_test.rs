@@ -40,3 +40,43 @@ async fn test_api_and_doc() {
     let result = client.get("/v1/private/docs").send().await;
     assert_eq!(result.status(), 200);
 }
+
+#[tokio::test]
+async fn test_grafana_query() {
+    let app = make_test_app();
+    let client = TestClient::new(app);
+    let result = client.get("/v1/grafana").send().await;
+    assert_eq!(result.status(), 200);
+
+    let result = client
+        .post("/v1/grafana/query")
+        .header("Content-Type", "application/json")
+        .body(
+            r#"{
+                "targets": [
+                    {"target": "select sum(uint32s) from numbers", "refId": "A", "type": "table"}
+                ]
+            }"#,
+        )
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(
+        serde_json::json!([{
+            "type": "table",
+            "columns": [{"text": "SUM(numbers.uint32s)", "type": "number"}],
+            "rows": [[4950]],
+        }]),
+        body
+    );
+
+    // The result of a time series target must have a timestamp column.
+    let result = client
+        .post("/v1/grafana/query")
+        .header("Content-Type", "application/json")
+        .body(r#"{"targets": [{"target": "select sum(uint32s) from numbers"}]}"#)
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+}
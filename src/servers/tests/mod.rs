@@ -26,6 +26,7 @@ use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::{QueryEngineFactory, QueryEngineRef};
 use script::engine::{CompileContext, EvalContext, Script, ScriptEngine};
 use script::python::{PyEngine, PyScript};
+use script::wasm::WasmFunction;
 use servers::error::{Error, NotSupportedSnafu, Result};
 use servers::query_handler::grpc::{GrpcQueryHandler, ServerGrpcQueryHandlerRef};
 use servers::query_handler::sql::{ServerSqlQueryHandlerRef, SqlQueryHandler};
@@ -127,6 +128,12 @@ impl ScriptHandler for DummyInstance {
         Ok(())
     }
 
+    async fn insert_wasm_function(&self, _schema: &str, name: &str, module: &[u8]) -> Result<()> {
+        let func = WasmFunction::try_new(name, module).unwrap();
+        self.query_engine.register_function(Arc::new(func));
+        Ok(())
+    }
+
     async fn execute_script(
         &self,
         schema: &str,
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
@@ -28,6 +30,16 @@ pub type ConnInfoRef = Arc<ConnInfo>;
 pub struct QueryContext {
     current_catalog: ArcSwap<String>,
     current_schema: ArcSwap<String>,
+    current_user: ArcSwap<UserInfo>,
+    /// Variables set by `SET`, which are referenced as parameters of TQL.
+    variables: ArcSwap<HashMap<String, String>>,
+    /// Hints of the statement being executed.
+    hints: ArcSwap<QueryHints>,
+    /// Functions created by `CREATE FUNCTION`, keyed by their lowercase names.
+    functions: ArcSwap<HashMap<String, Arc<SessionFunction>>>,
+    /// Whether the protocol collects the whole output before replying, which rules out the
+    /// queries never finishing, like tails.
+    collects_output: AtomicBool,
 }
 
 impl Default for QueryContext {
@@ -56,6 +68,11 @@ impl QueryContext {
         Self {
             current_catalog: ArcSwap::new(Arc::new(DEFAULT_CATALOG_NAME.to_string())),
             current_schema: ArcSwap::new(Arc::new(DEFAULT_SCHEMA_NAME.to_string())),
+            current_user: ArcSwap::new(Arc::new(UserInfo::default())),
+            variables: ArcSwap::default(),
+            hints: ArcSwap::default(),
+            functions: ArcSwap::default(),
+            collects_output: AtomicBool::new(false),
         }
     }
 
@@ -63,6 +80,11 @@ impl QueryContext {
         Self {
             current_catalog: ArcSwap::new(Arc::new(catalog.to_string())),
             current_schema: ArcSwap::new(Arc::new(schema.to_string())),
+            current_user: ArcSwap::new(Arc::new(UserInfo::default())),
+            variables: ArcSwap::default(),
+            hints: ArcSwap::default(),
+            functions: ArcSwap::default(),
+            collects_output: AtomicBool::new(false),
         }
     }
 
@@ -74,6 +96,15 @@ impl QueryContext {
         self.current_catalog.load().as_ref().clone()
     }
 
+    /// The user who issues the query, used by the frontend to enforce per user policies.
+    pub fn current_user(&self) -> Arc<UserInfo> {
+        self.current_user.load().clone()
+    }
+
+    pub fn set_current_user(&self, user: UserInfo) {
+        self.current_user.store(Arc::new(user));
+    }
+
     pub fn set_current_schema(&self, schema: &str) {
         let last = self.current_schema.swap(Arc::new(schema.to_string()));
         if schema != last.as_str() {
@@ -94,6 +125,68 @@ impl QueryContext {
         }
     }
 
+    /// Gets the value of a variable set in this session.
+    pub fn variable(&self, name: &str) -> Option<String> {
+        self.variables.load().get(name).cloned()
+    }
+
+    pub fn set_variable(&self, name: &str, value: String) {
+        let _ = self.variables.rcu(|variables| {
+            let mut variables = HashMap::clone(variables);
+            let _ = variables.insert(name.to_string(), value.clone());
+            variables
+        });
+    }
+
+    /// Gets the hints of the statement being executed.
+    pub fn hints(&self) -> Arc<QueryHints> {
+        self.hints.load().clone()
+    }
+
+    /// Sets the hints of the statement to execute. The hints are kept until replaced, so
+    /// they must be set for every statement, even if it has no hints.
+    pub fn set_hints(&self, hints: QueryHints) {
+        self.hints.store(Arc::new(hints));
+    }
+
+    pub fn collects_output(&self) -> bool {
+        self.collects_output.load(Ordering::Relaxed)
+    }
+
+    pub fn set_collects_output(&self, collects_output: bool) {
+        self.collects_output
+            .store(collects_output, Ordering::Relaxed);
+    }
+
+    /// Gets the function created in this session by its lowercase name.
+    pub fn function(&self, name: &str) -> Option<Arc<SessionFunction>> {
+        self.functions.load().get(name).cloned()
+    }
+
+    pub fn has_functions(&self) -> bool {
+        !self.functions.load().is_empty()
+    }
+
+    /// Adds the `function` to this session, replacing the one of the same name.
+    pub fn set_function(&self, function: SessionFunction) {
+        let function = Arc::new(function);
+        let _ = self.functions.rcu(|functions| {
+            let mut functions = HashMap::clone(functions);
+            let _ = functions.insert(function.name.clone(), function.clone());
+            functions
+        });
+    }
+
+    /// Removes the function of `name` from this session, returns whether it existed.
+    pub fn remove_function(&self, name: &str) -> bool {
+        let last = self.functions.rcu(|functions| {
+            let mut functions = HashMap::clone(functions);
+            let _ = functions.remove(name);
+            functions
+        });
+        last.contains_key(name)
+    }
+
     pub fn get_db_string(&self) -> String {
         let catalog = self.current_catalog();
         let schema = self.current_schema();
@@ -101,6 +194,35 @@ impl QueryContext {
     }
 }
 
+/// Optimizer and executor hints of a statement, e.g. `/*+ no_index, parallel(8) */`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryHints {
+    /// Scans tables without pruning the data by the filters.
+    pub no_index: bool,
+    /// Overrides the parallelism of the query execution.
+    pub parallelism: Option<usize>,
+    /// Reads the data from the replicas of the regions if possible.
+    pub read_replica: bool,
+}
+
+impl QueryHints {
+    pub fn is_empty(&self) -> bool {
+        *self == QueryHints::default()
+    }
+}
+
+/// A function created by `CREATE FUNCTION`, which only lives in the session. Calls of the
+/// function are expanded to its body when planned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionFunction {
+    /// Name of the function in lowercase.
+    pub name: String,
+    /// Names of the arguments in lowercase.
+    pub args: Vec<String>,
+    /// SQL expression of the function, referring the arguments by names.
+    pub body: String,
+}
+
 pub const DEFAULT_USERNAME: &str = "greptime";
 
 #[derive(Clone, Debug)]
@@ -190,4 +312,61 @@ mod test {
 
         assert_eq!("test", context.get_db_string());
     }
+
+    #[test]
+    fn test_context_variables() {
+        let context = QueryContext::new();
+        assert_eq!(None, context.variable("host"));
+
+        context.set_variable("host", "host1".to_string());
+        context.set_variable("threshold", "0.5".to_string());
+        assert_eq!(Some("host1".to_string()), context.variable("host"));
+        assert_eq!(Some("0.5".to_string()), context.variable("threshold"));
+
+        context.set_variable("host", "host2".to_string());
+        assert_eq!(Some("host2".to_string()), context.variable("host"));
+    }
+
+    #[test]
+    fn test_context_hints() {
+        let context = QueryContext::new();
+        assert!(context.hints().is_empty());
+
+        let hints = QueryHints {
+            no_index: true,
+            parallelism: Some(8),
+            read_replica: false,
+        };
+        context.set_hints(hints.clone());
+        assert_eq!(hints, *context.hints());
+
+        context.set_hints(QueryHints::default());
+        assert!(context.hints().is_empty());
+    }
+
+    #[test]
+    fn test_context_functions() {
+        let context = QueryContext::new();
+        assert!(!context.has_functions());
+        assert!(!context.remove_function("double"));
+
+        let function = SessionFunction {
+            name: "double".to_string(),
+            args: vec!["x".to_string()],
+            body: "x * 2".to_string(),
+        };
+        context.set_function(function.clone());
+        assert!(context.has_functions());
+        assert_eq!(function, *context.function("double").unwrap());
+
+        context.set_function(SessionFunction {
+            body: "x + x".to_string(),
+            ..function
+        });
+        assert_eq!("x + x", context.function("double").unwrap().body);
+
+        assert!(context.remove_function("double"));
+        assert!(context.function("double").is_none());
+        assert!(!context.has_functions());
+    }
 }
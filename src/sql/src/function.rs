@@ -0,0 +1,208 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Expands the calls of the functions defined by `CREATE FUNCTION`, which are expression
+//! templates rather than real functions, so the planner never sees them.
+
+use std::ops::ControlFlow;
+
+use datafusion_sql::parser::Statement as DfStatement;
+use snafu::ensure;
+use sqlparser::ast::{visit_expressions_mut, Expr, FunctionArg, FunctionArgExpr};
+use sqlparser::dialect::GenericDialect;
+
+use crate::error::{InvalidSqlSnafu, Result};
+use crate::parser::ParserContext;
+
+/// Max depth of the functions calling other functions, which stops the recursive ones.
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+/// Definition of a function created by `CREATE FUNCTION`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionTemplate {
+    /// Names of the arguments in lowercase.
+    pub args: Vec<String>,
+    /// The expression the calls are expanded to.
+    pub body: String,
+}
+
+/// Expands the calls of the functions in `stmt`. `lookup` finds the function by its lowercase
+/// name, the calls of the functions not found are kept as they are.
+pub fn expand_functions<F>(stmt: &mut DfStatement, lookup: F) -> Result<()>
+where
+    F: Fn(&str) -> Option<FunctionTemplate>,
+{
+    let DfStatement::Statement(stmt) = stmt else {
+        return Ok(());
+    };
+    break_to_result(visit_expressions_mut(stmt.as_mut(), |expr| {
+        result_to_flow(expand_call(expr, &lookup, 0))
+    }))
+}
+
+/// Replaces `expr` with the body of the function if it calls one. The expressions are visited
+/// bottom up, so the arguments of the call are expanded already.
+fn expand_call<F>(expr: &mut Expr, lookup: &F, depth: usize) -> Result<()>
+where
+    F: Fn(&str) -> Option<FunctionTemplate>,
+{
+    let Expr::Function(function) = expr else {
+        return Ok(());
+    };
+    // The functions don't belong to any schema, so they are never qualified.
+    let [name] = function.name.0.as_slice() else {
+        return Ok(());
+    };
+    let name = name.value.to_lowercase();
+    let Some(template) = lookup(&name) else {
+        return Ok(());
+    };
+
+    ensure!(
+        depth < MAX_EXPANSION_DEPTH,
+        InvalidSqlSnafu {
+            msg: format!("function {name} is nested too deeply, it may call itself"),
+        }
+    );
+    ensure!(
+        function.over.is_none() && !function.distinct,
+        InvalidSqlSnafu {
+            msg: format!("function {name} is not an aggregate or window function"),
+        }
+    );
+    ensure!(
+        function.args.len() == template.args.len(),
+        InvalidSqlSnafu {
+            msg: format!(
+                "function {name} expects {} arguments, found {}",
+                template.args.len(),
+                function.args.len()
+            ),
+        }
+    );
+    let args = function
+        .args
+        .iter()
+        .map(|arg| match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) => Ok(arg.clone()),
+            _ => InvalidSqlSnafu {
+                msg: format!("invalid argument of function {name}: {arg}"),
+            }
+            .fail(),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut body = ParserContext::parse_expr(&template.body, &GenericDialect {})?;
+    // Replaced arguments are not visited again, as they are visited after their children.
+    let _ = visit_expressions_mut(&mut body, |expr| {
+        if let Expr::Identifier(ident) = expr {
+            let position = template
+                .args
+                .iter()
+                .position(|arg| *arg == ident.value.to_lowercase());
+            if let Some(position) = position {
+                *expr = Expr::Nested(Box::new(args[position].clone()));
+            }
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    // The body may call other functions.
+    break_to_result(visit_expressions_mut(&mut body, |expr| {
+        result_to_flow(expand_call(expr, lookup, depth + 1))
+    }))?;
+
+    *expr = Expr::Nested(Box::new(body));
+    Ok(())
+}
+
+fn result_to_flow(result: Result<()>) -> ControlFlow<crate::error::Error> {
+    match result {
+        Ok(()) => ControlFlow::Continue(()),
+        Err(e) => ControlFlow::Break(e),
+    }
+}
+
+fn break_to_result(flow: ControlFlow<crate::error::Error>) -> Result<()> {
+    match flow {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn expand(sql: &str, functions: &[(&str, &[&str], &str)]) -> Result<String> {
+        let functions = functions
+            .iter()
+            .map(|(name, args, body)| {
+                let template = FunctionTemplate {
+                    args: args.iter().map(|arg| arg.to_string()).collect(),
+                    body: body.to_string(),
+                };
+                (name.to_string(), template)
+            })
+            .collect::<HashMap<_, _>>();
+        let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .pop()
+            .unwrap();
+        let mut stmt = DfStatement::try_from(&stmt).unwrap();
+        expand_functions(&mut stmt, |name| functions.get(name).cloned())?;
+        let DfStatement::Statement(stmt) = stmt else {
+            unreachable!()
+        };
+        Ok(stmt.to_string())
+    }
+
+    #[test]
+    fn test_expand_functions() {
+        let celsius: (&str, &[&str], &str) = ("celsius", &["f"], "(f - 32) * 5 / 9");
+        assert_eq!(
+            "SELECT (((temp + 1) - 32) * 5 / 9) FROM t WHERE (((temp) - 32) * 5 / 9) > 30",
+            expand(
+                "SELECT CELSIUS(temp + 1) FROM t WHERE celsius(temp) > 30",
+                &[celsius]
+            )
+            .unwrap()
+        );
+
+        // Nested calls and calls in the bodies.
+        let fahrenheit: (&str, &[&str], &str) = ("fahrenheit", &["c"], "celsius(c) * 0 + c");
+        assert_eq!(
+            "SELECT (((((x)) - 32) * 5 / 9) * 0 + (x)) FROM t",
+            expand("SELECT fahrenheit(x) FROM t", &[celsius, fahrenheit]).unwrap()
+        );
+
+        // Other functions and qualified names are kept.
+        assert_eq!(
+            "SELECT abs(x), s.celsius(x) FROM t",
+            expand("SELECT abs(x), s.celsius(x) FROM t", &[celsius]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_invalid_calls() {
+        let celsius: (&str, &[&str], &str) = ("celsius", &["f"], "(f - 32) * 5 / 9");
+        assert!(expand("SELECT celsius(a, b) FROM t", &[celsius]).is_err());
+        assert!(expand("SELECT celsius(*) FROM t", &[celsius]).is_err());
+        assert!(expand("SELECT celsius(a) OVER () FROM t", &[celsius]).is_err());
+
+        let recursive: (&str, &[&str], &str) = ("r", &["x"], "r(x) + 1");
+        assert!(expand("SELECT r(a) FROM t", &[recursive]).is_err());
+    }
+}
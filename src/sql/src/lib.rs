@@ -17,6 +17,7 @@
 pub mod ast;
 pub mod dialect;
 pub mod error;
+pub mod function;
 pub mod parser;
 pub mod parsers;
 pub mod statements;
@@ -13,18 +13,24 @@
 // limitations under the License.
 
 use snafu::{ensure, ResultExt};
+use sqlparser::ast::Statement as SpStatement;
 use sqlparser::dialect::Dialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError};
-use sqlparser::tokenizer::{Token, TokenWithLocation};
+use sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
 
 use crate::ast::{Expr, ObjectName};
-use crate::error::{self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu};
-use crate::parsers::tql_parser;
+use crate::error::{
+    self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu, TokenizerSnafu,
+};
+use crate::parsers::{admin_parser, tablesample_parser, tql_parser};
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
+use crate::statements::drop::{DropFunction, DropTable, DropView};
 use crate::statements::explain::Explain;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowTables};
+use crate::statements::set_variables::SetVariables;
+use crate::statements::show::{
+    ShowCardinality, ShowCatalogs, ShowCreateTable, ShowDatabases, ShowKind, ShowTables,
+};
 use crate::statements::statement::Statement;
 
 /// GrepTime SQL parser context, a simple wrapper for Datafusion SQL parser.
@@ -38,9 +44,24 @@ impl<'a> ParserContext<'a> {
     pub fn create_with_dialect(sql: &'a str, dialect: &dyn Dialect) -> Result<Vec<Statement>> {
         let mut stmts: Vec<Statement> = Vec::new();
 
-        let parser = Parser::new(dialect)
-            .try_with_sql(sql)
-            .context(SyntaxSnafu { sql })?;
+        let (parser, mut table_samples) = if sql
+            .to_ascii_uppercase()
+            .contains(tablesample_parser::TABLESAMPLE)
+        {
+            let tokens = Tokenizer::new(dialect, sql)
+                .tokenize_with_location()
+                .context(TokenizerSnafu { sql })?;
+            let (tokens, samples) = tablesample_parser::extract_table_samples(tokens)?;
+            (
+                Parser::new(dialect).with_tokens_with_locations(tokens),
+                samples,
+            )
+        } else {
+            let parser = Parser::new(dialect)
+                .try_with_sql(sql)
+                .context(SyntaxSnafu { sql })?;
+            (parser, vec![])
+        };
         let mut parser_ctx = ParserContext { sql, parser };
 
         let mut expecting_statement_delimiter = false;
@@ -57,7 +78,20 @@ impl<'a> ParserContext<'a> {
                 return parser_ctx.unsupported(parser_ctx.peek_token_as_string());
             }
 
-            let statement = parser_ctx.parse_statement()?;
+            let mut statement = parser_ctx.parse_statement()?;
+            let (samples, rest): (Vec<_>, Vec<_>) = table_samples
+                .into_iter()
+                .partition(|(stmt_index, _)| *stmt_index == stmts.len());
+            table_samples = rest;
+            if !samples.is_empty() {
+                let Statement::Query(query) = &mut statement else {
+                    return error::InvalidSqlSnafu {
+                        msg: "TABLESAMPLE is only supported in queries",
+                    }
+                    .fail();
+                };
+                query.table_samples = samples.into_iter().map(|(_, s)| s).collect();
+            }
             stmts.push(statement);
             expecting_statement_delimiter = true;
         }
@@ -76,6 +110,25 @@ impl<'a> ParserContext<'a> {
             .context(SyntaxSnafu { sql })
     }
 
+    /// Parses `sql` as a single expression.
+    pub fn parse_expr(sql: &'a str, dialect: &dyn Dialect) -> Result<Expr> {
+        let mut parser = Parser::new(dialect)
+            .try_with_sql(sql)
+            .context(SyntaxSnafu { sql })?;
+
+        let expr = parser.parse_expr().context(SyntaxSnafu { sql })?;
+        ensure!(
+            parser.peek_token() == Token::EOF,
+            error::InvalidSqlSnafu {
+                msg: format!(
+                    "unexpected token after expression {sql}: {}",
+                    parser.peek_token()
+                ),
+            }
+        );
+        Ok(expr)
+    }
+
     /// Parses parser context to a set of statements.
     pub fn parse_statement(&mut self) -> Result<Statement> {
         match self.parser.peek_token().token {
@@ -125,6 +178,8 @@ impl<'a> ParserContext<'a> {
                         Ok(Statement::Use(database_name.value))
                     }
 
+                    Keyword::SET => self.parse_set_variables(),
+
                     Keyword::COPY => self.parse_copy(),
 
                     Keyword::NoKeyword
@@ -133,6 +188,12 @@ impl<'a> ParserContext<'a> {
                         self.parse_tql()
                     }
 
+                    _ if w.value.to_uppercase() == admin_parser::ADMIN
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_admin()
+                    }
+
                     // todo(hl) support more statements.
                     _ => self.unsupported(self.peek_token_as_string()),
                 }
@@ -142,6 +203,20 @@ impl<'a> ParserContext<'a> {
         }
     }
 
+    /// Parses `SET variable = value` by the underlying SQL parser.
+    fn parse_set_variables(&mut self) -> Result<Statement> {
+        let statement = self
+            .parser
+            .parse_statement()
+            .context(SyntaxSnafu { sql: self.sql })?;
+        match statement {
+            SpStatement::SetVariable {
+                variable, value, ..
+            } => Ok(Statement::SetVariables(SetVariables { variable, value })),
+            statement => self.unsupported(statement.to_string()),
+        }
+    }
+
     /// Raises an "unsupported statement" error.
     pub fn unsupported<T>(&self, keyword: String) -> Result<T> {
         error::UnsupportedSnafu {
@@ -154,7 +229,12 @@ impl<'a> ParserContext<'a> {
     /// Parses SHOW statements
     /// todo(hl) support `show settings`/`show create`/`show users` etc.
     fn parse_show(&mut self) -> Result<Statement> {
-        if self.consume_token("DATABASES") || self.consume_token("SCHEMAS") {
+        if self.consume_token("CATALOGS") {
+            match self.parser.peek_token().token {
+                Token::EOF | Token::SemiColon => Ok(Statement::ShowCatalogs(ShowCatalogs)),
+                _ => self.unsupported(self.peek_token_as_string()),
+            }
+        } else if self.consume_token("DATABASES") || self.consume_token("SCHEMAS") {
             self.parse_show_databases()
         } else if self.matches_keyword(Keyword::TABLES) {
             self.parser.next_token();
@@ -165,6 +245,8 @@ impl<'a> ParserContext<'a> {
             } else {
                 self.unsupported(self.peek_token_as_string())
             }
+        } else if self.consume_token("CARDINALITY") {
+            self.parse_show_cardinality()
         } else {
             self.unsupported(self.peek_token_as_string())
         }
@@ -189,6 +271,28 @@ impl<'a> ParserContext<'a> {
         Ok(Statement::ShowCreateTable(ShowCreateTable { table_name }))
     }
 
+    /// Parse SHOW CARDINALITY FOR TABLE statement
+    fn parse_show_cardinality(&mut self) -> Result<Statement> {
+        if !(self.consume_token("FOR") && self.consume_token("TABLE")) {
+            return self.expected("FOR TABLE", self.parser.peek_token());
+        }
+        let table_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_name.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_name.to_string(),
+            }
+        );
+        Ok(Statement::ShowCardinality(ShowCardinality { table_name }))
+    }
+
     fn parse_show_tables(&mut self) -> Result<Statement> {
         let database = match self.parser.peek_token().token {
             Token::EOF | Token::SemiColon => {
@@ -300,6 +404,12 @@ impl<'a> ParserContext<'a> {
 
     fn parse_drop(&mut self) -> Result<Statement> {
         self.parser.next_token();
+        if self.matches_keyword(Keyword::VIEW) {
+            return self.parse_drop_view();
+        }
+        if self.matches_keyword(Keyword::FUNCTION) {
+            return self.parse_drop_function();
+        }
         if !self.matches_keyword(Keyword::TABLE) {
             return self.unsupported(self.peek_token_as_string());
         }
@@ -323,6 +433,43 @@ impl<'a> ParserContext<'a> {
         Ok(Statement::DropTable(DropTable::new(table_ident)))
     }
 
+    fn parse_drop_view(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+
+        let view_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a view name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !view_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: view_ident.to_string()
+            }
+        );
+
+        Ok(Statement::DropView(DropView::new(view_ident)))
+    }
+
+    fn parse_drop_function(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self
+            .parser
+            .parse_identifier()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a function name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok(Statement::DropFunction(DropFunction { name, if_exists }))
+    }
+
     // Report unexpected token
     pub(crate) fn expected<T>(&self, expected: &str, found: TokenWithLocation) -> Result<T> {
         Err(ParserError::ParserError(format!(
@@ -620,6 +767,52 @@ mod tests {
         )
     }
 
+    #[test]
+    pub fn test_drop_view() {
+        let sql = "DROP VIEW my_schema.foo";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropView(DropView::new(ObjectName(vec![
+                Ident::new("my_schema"),
+                Ident::new("foo")
+            ])))
+        );
+    }
+
+    #[test]
+    pub fn test_drop_function() {
+        let sql = "DROP FUNCTION IF EXISTS double_it";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropFunction(DropFunction {
+                name: Ident::new("double_it"),
+                if_exists: true,
+            })
+        );
+
+        let sql = "DROP FUNCTION double_it";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_matches!(
+            stmts.pop().unwrap(),
+            Statement::DropFunction(DropFunction {
+                if_exists: false,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_parse_expr() {
+        let expr = ParserContext::parse_expr("a * 2 + b", &GenericDialect {}).unwrap();
+        assert_eq!("a * 2 + b", expr.to_string());
+
+        assert!(ParserContext::parse_expr("a * 2 b", &GenericDialect {}).is_err());
+        assert!(ParserContext::parse_expr("", &GenericDialect {}).is_err());
+    }
+
     fn test_timestamp_precision(sql: &str, expected_type: ConcreteDataType) {
         match ParserContext::create_with_dialect(sql, &GenericDialect {})
             .unwrap()
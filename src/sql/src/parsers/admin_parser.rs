@@ -0,0 +1,634 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use snafu::{ensure, ResultExt};
+use sqlparser::ast::ObjectName;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, InvalidTableNameSnafu, Result};
+use crate::parser::ParserContext;
+use crate::statements::admin::{
+    Admin, AdminAttach, AdminBulk, AdminCompact, AdminDropRange, AdminFlush, AdminMigrate,
+    AdminPurge, AdminReplay, AdminScrub, BulkOperation,
+};
+use crate::statements::statement::Statement;
+use crate::util::to_lowercase_options_map;
+
+pub const ADMIN: &str = "ADMIN";
+const FLUSH: &str = "FLUSH";
+const COMPACT: &str = "COMPACT";
+const MIGRATE: &str = "MIGRATE";
+const PURGE: &str = "PURGE";
+const DROP: &str = "DROP";
+const ATTACH: &str = "ATTACH";
+const REPLAY: &str = "REPLAY";
+const SCRUB: &str = "SCRUB";
+const REGION: &str = "REGION";
+const SET: &str = "SET";
+const TABLES: &str = "TABLES";
+
+/// ADMIN extension parser, including:
+/// - ADMIN FLUSH TABLE <table> [REGION <region_number>]
+/// - ADMIN COMPACT TABLE <table> [REGION <region_number>]
+/// - ADMIN MIGRATE REGION <region_number> OF TABLE <table> FROM <from_peer> TO <to_peer>
+/// - ADMIN PURGE TABLE <table> [REGION <region_number>] [DRY RUN]
+/// - ADMIN DROP RANGE TABLE <table> [REGION <region_number>] FROM '<start>' TO '<end>' [DRY RUN]
+/// - ADMIN ATTACH TABLE <table> [REGION <region_number>] FROM '<staging_dir>'
+/// - ADMIN SCRUB TABLE <table> [REGION <region_number>]
+/// - ADMIN REPLAY TABLE <table> [FROM '<start>'] [TO '<end>'] INTO TABLE <target>
+///   [TRANSFORM (<expr> [AS <column>], ...)] [CONNECTION (<options>)]
+/// - ADMIN { FLUSH | COMPACT | PURGE | SET } TABLES [IN <schema>] [LIKE '<pattern>']
+///   [PARALLELISM <parallelism>] [DRY RUN] [WITH (<options>)]
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_admin(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+
+        let admin = if let Some(operation) = self.parse_admin_bulk_operation() {
+            self.parse_admin_bulk(operation)?
+        } else if self.consume_token(FLUSH) {
+            let (table_name, region_number) = self.parse_admin_table_regions()?;
+            Admin::Flush(AdminFlush {
+                table_name,
+                region_number,
+            })
+        } else if self.consume_token(COMPACT) {
+            let (table_name, region_number) = self.parse_admin_table_regions()?;
+            Admin::Compact(AdminCompact {
+                table_name,
+                region_number,
+            })
+        } else if self.consume_token(MIGRATE) {
+            self.parse_admin_migrate()?
+        } else if self.consume_token(PURGE) {
+            let (table_name, region_number) = self.parse_admin_table_regions()?;
+            let dry_run = self.parse_admin_dry_run()?;
+            Admin::Purge(AdminPurge {
+                table_name,
+                region_number,
+                dry_run,
+            })
+        } else if self.consume_token(DROP) {
+            self.expect_admin_token("RANGE")?;
+            let (table_name, region_number) = self.parse_admin_table_regions()?;
+            self.expect_admin_token("FROM")?;
+            let start = self.parse_admin_string("a start time")?;
+            self.expect_admin_token("TO")?;
+            let end = self.parse_admin_string("an end time")?;
+            let dry_run = self.parse_admin_dry_run()?;
+            Admin::DropRange(AdminDropRange {
+                table_name,
+                region_number,
+                start,
+                end,
+                dry_run,
+            })
+        } else if self.consume_token(ATTACH) {
+            let (table_name, region_number) = self.parse_admin_table_regions()?;
+            self.expect_admin_token("FROM")?;
+            let staging_dir = self.parse_admin_string("a staging directory")?;
+            Admin::Attach(AdminAttach {
+                table_name,
+                region_number,
+                staging_dir,
+            })
+        } else if self.consume_token(SCRUB) {
+            let (table_name, region_number) = self.parse_admin_table_regions()?;
+            Admin::Scrub(AdminScrub {
+                table_name,
+                region_number,
+            })
+        } else if self.consume_token(REPLAY) {
+            self.parse_admin_replay()?
+        } else {
+            return self.unsupported(self.peek_token_as_string());
+        };
+        Ok(Statement::Admin(admin))
+    }
+
+    /// Parses `{ FLUSH | COMPACT | PURGE | SET } TABLES`, returns `None` without consuming any
+    /// token if the statement doesn't operate on multiple tables.
+    fn parse_admin_bulk_operation(&mut self) -> Option<BulkOperation> {
+        let operation = self.peek_token_as_string().to_uppercase();
+        let operation = match operation.as_str() {
+            FLUSH => BulkOperation::Flush,
+            COMPACT => BulkOperation::Compact,
+            PURGE => BulkOperation::Purge { dry_run: false },
+            SET => BulkOperation::SetOptions {
+                options: HashMap::new(),
+            },
+            _ => return None,
+        };
+        let next = self.parser.peek_nth_token(1).to_string();
+        if !next.eq_ignore_ascii_case(TABLES) {
+            return None;
+        }
+        self.parser.next_token();
+        self.parser.next_token();
+        Some(operation)
+    }
+
+    fn parse_admin_bulk(&mut self, mut operation: BulkOperation) -> Result<Admin> {
+        let schema = if self.parser.parse_keyword(Keyword::IN) {
+            let schema =
+                self.parser
+                    .parse_identifier()
+                    .with_context(|_| error::UnexpectedSnafu {
+                        sql: self.sql,
+                        expected: "a schema name",
+                        actual: self.peek_token_as_string(),
+                    })?;
+            Some(schema.value)
+        } else {
+            None
+        };
+        let pattern = if self.parser.parse_keyword(Keyword::LIKE) {
+            Some(self.parse_admin_string("a table name pattern")?)
+        } else {
+            None
+        };
+        let parallelism = if self.consume_token("PARALLELISM") {
+            let parallelism = self.parse_admin_number("a parallelism")?;
+            ensure!(
+                parallelism > 0,
+                error::InvalidSqlSnafu {
+                    msg: "PARALLELISM must be positive",
+                }
+            );
+            Some(parallelism as usize)
+        } else {
+            None
+        };
+        match &mut operation {
+            BulkOperation::Purge { dry_run } => *dry_run = self.parse_admin_dry_run()?,
+            BulkOperation::SetOptions { options } => {
+                let with = self
+                    .parser
+                    .parse_options(Keyword::WITH)
+                    .context(error::SyntaxSnafu { sql: self.sql })?;
+                ensure!(
+                    !with.is_empty(),
+                    error::InvalidSqlSnafu {
+                        msg: "SET TABLES requires the options to set in WITH (<options>)",
+                    }
+                );
+                *options = to_lowercase_options_map(&with);
+            }
+            BulkOperation::Flush | BulkOperation::Compact => {}
+        }
+
+        Ok(Admin::Bulk(AdminBulk {
+            operation,
+            schema,
+            pattern,
+            parallelism,
+        }))
+    }
+
+    /// Parses `TABLE <table> [REGION <region_number>]`.
+    fn parse_admin_table_regions(&mut self) -> Result<(ObjectName, Option<u32>)> {
+        let table_name = self.parse_admin_table_name()?;
+        let region_number = if self.consume_token(REGION) {
+            Some(self.parse_admin_number("a region number")? as u32)
+        } else {
+            None
+        };
+        Ok((table_name, region_number))
+    }
+
+    /// Parses optional `DRY RUN`.
+    fn parse_admin_dry_run(&mut self) -> Result<bool> {
+        if self.consume_token("DRY") {
+            self.expect_admin_token("RUN")?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn parse_admin_migrate(&mut self) -> Result<Admin> {
+        self.expect_admin_token(REGION)?;
+        let region_number = self.parse_admin_number("a region number")? as u32;
+        self.expect_admin_token("OF")?;
+        let table_name = self.parse_admin_table_name()?;
+        self.expect_admin_token("FROM")?;
+        let from_peer = self.parse_admin_number("a datanode id")?;
+        self.expect_admin_token("TO")?;
+        let to_peer = self.parse_admin_number("a datanode id")?;
+
+        Ok(Admin::Migrate(AdminMigrate {
+            table_name,
+            region_number,
+            from_peer,
+            to_peer,
+        }))
+    }
+
+    fn parse_admin_replay(&mut self) -> Result<Admin> {
+        let table_name = self.parse_admin_table_name()?;
+        let start = if self.consume_token("FROM") {
+            Some(self.parse_admin_string("a start time")?)
+        } else {
+            None
+        };
+        let end = if self.consume_token("TO") {
+            Some(self.parse_admin_string("an end time")?)
+        } else {
+            None
+        };
+        self.expect_admin_token("INTO")?;
+        let target_table = self.parse_admin_table_name()?;
+
+        let transforms = if self.consume_token("TRANSFORM") {
+            self.parser
+                .expect_token(&Token::LParen)
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            let transforms = self
+                .parser
+                .parse_comma_separated(Parser::parse_select_item)
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            self.parser
+                .expect_token(&Token::RParen)
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            transforms
+        } else {
+            vec![]
+        };
+
+        let connection = self
+            .parser
+            .parse_options(Keyword::CONNECTION)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        Ok(Admin::Replay(AdminReplay {
+            table_name,
+            start,
+            end,
+            target_table,
+            transforms,
+            connection: to_lowercase_options_map(&connection),
+        }))
+    }
+
+    fn parse_admin_table_name(&mut self) -> Result<ObjectName> {
+        self.expect_admin_token("TABLE")?;
+        let table_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_name.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_name.to_string()
+            }
+        );
+        Ok(table_name)
+    }
+
+    fn parse_admin_number(&mut self, expected: &str) -> Result<u64> {
+        self.parser
+            .parse_literal_uint()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected,
+                actual: self.peek_token_as_string(),
+            })
+    }
+
+    fn parse_admin_string(&mut self, expected: &str) -> Result<String> {
+        self.parser
+            .parse_literal_string()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected,
+                actual: self.peek_token_as_string(),
+            })
+    }
+
+    fn expect_admin_token(&mut self, expected: &str) -> Result<()> {
+        if self.consume_token(expected) {
+            Ok(())
+        } else {
+            self.unsupported(self.peek_token_as_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    fn parse_admin(sql: &str) -> Admin {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        match stmts.remove(0) {
+            Statement::Admin(admin) => admin,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_parse_admin_flush() {
+        let admin = parse_admin("ADMIN FLUSH TABLE my_schema.monitor");
+        assert_eq!(
+            Admin::Flush(AdminFlush {
+                table_name: ObjectName(vec!["my_schema".into(), "monitor".into()]),
+                region_number: None,
+            }),
+            admin
+        );
+
+        let admin = parse_admin("admin flush table monitor region 1;");
+        assert_eq!(
+            Admin::Flush(AdminFlush {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: Some(1),
+            }),
+            admin
+        );
+    }
+
+    #[test]
+    fn test_parse_admin_compact() {
+        let admin = parse_admin("ADMIN COMPACT TABLE monitor REGION 2");
+        assert_eq!(
+            Admin::Compact(AdminCompact {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: Some(2),
+            }),
+            admin
+        );
+    }
+
+    #[test]
+    fn test_parse_admin_migrate() {
+        let admin = parse_admin("ADMIN MIGRATE REGION 1 OF TABLE monitor FROM 1 TO 2");
+        assert_eq!(
+            Admin::Migrate(AdminMigrate {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: 1,
+                from_peer: 1,
+                to_peer: 2,
+            }),
+            admin
+        );
+    }
+
+    #[test]
+    fn test_parse_admin_purge() {
+        let admin = parse_admin("ADMIN PURGE TABLE monitor");
+        assert_eq!(
+            Admin::Purge(AdminPurge {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: None,
+                dry_run: false,
+            }),
+            admin
+        );
+
+        let admin = parse_admin("admin purge table monitor region 1 dry run");
+        assert_eq!(
+            Admin::Purge(AdminPurge {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: Some(1),
+                dry_run: true,
+            }),
+            admin
+        );
+    }
+
+    #[test]
+    fn test_parse_admin_drop_range() {
+        let admin = parse_admin(
+            "ADMIN DROP RANGE TABLE monitor FROM '2023-01-01 00:00:00' TO '2023-01-01 23:59:59'",
+        );
+        assert_eq!(
+            Admin::DropRange(AdminDropRange {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: None,
+                start: "2023-01-01 00:00:00".to_string(),
+                end: "2023-01-01 23:59:59".to_string(),
+                dry_run: false,
+            }),
+            admin
+        );
+
+        let admin = parse_admin("admin drop range table monitor region 1 from '0' to '1' dry run");
+        assert_eq!(
+            Admin::DropRange(AdminDropRange {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: Some(1),
+                start: "0".to_string(),
+                end: "1".to_string(),
+                dry_run: true,
+            }),
+            admin
+        );
+
+        assert!(ParserContext::create_with_dialect(
+            "ADMIN DROP RANGE TABLE monitor FROM '0'",
+            &GenericDialect {}
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_admin_attach() {
+        let admin = parse_admin("ADMIN ATTACH TABLE monitor FROM 'staging/monitor/'");
+        assert_eq!(
+            Admin::Attach(AdminAttach {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: None,
+                staging_dir: "staging/monitor/".to_string(),
+            }),
+            admin
+        );
+
+        let admin = parse_admin("admin attach table monitor region 1 from 'staging'");
+        assert_eq!(
+            Admin::Attach(AdminAttach {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: Some(1),
+                staging_dir: "staging".to_string(),
+            }),
+            admin
+        );
+
+        assert!(ParserContext::create_with_dialect(
+            "ADMIN ATTACH TABLE monitor",
+            &GenericDialect {}
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_admin_scrub() {
+        let admin = parse_admin("ADMIN SCRUB TABLE monitor");
+        assert_eq!(
+            Admin::Scrub(AdminScrub {
+                table_name: ObjectName(vec!["monitor".into()]),
+                region_number: None,
+            }),
+            admin
+        );
+
+        let admin = parse_admin("admin scrub table public.monitor region 2");
+        assert_eq!(
+            Admin::Scrub(AdminScrub {
+                table_name: ObjectName(vec!["public".into(), "monitor".into()]),
+                region_number: Some(2),
+            }),
+            admin
+        );
+    }
+
+    #[test]
+    fn test_parse_admin_replay() {
+        let admin = parse_admin("ADMIN REPLAY TABLE monitor INTO TABLE monitor_v2");
+        assert_eq!(
+            Admin::Replay(AdminReplay {
+                table_name: ObjectName(vec!["monitor".into()]),
+                start: None,
+                end: None,
+                target_table: ObjectName(vec!["monitor_v2".into()]),
+                transforms: vec![],
+                connection: HashMap::new(),
+            }),
+            admin
+        );
+
+        let admin = parse_admin(
+            "ADMIN REPLAY TABLE monitor FROM '2023-01-01 00:00:00' TO '2023-01-02 00:00:00' \
+             INTO TABLE monitor_v2 TRANSFORM (host, CAST(cpu AS DOUBLE) AS cpu, ts) \
+             CONNECTION (ENDPOINT = '127.0.0.1:4001')",
+        );
+        let Admin::Replay(replay) = admin else { unreachable!() };
+        assert_eq!(Some("2023-01-01 00:00:00"), replay.start.as_deref());
+        assert_eq!(Some("2023-01-02 00:00:00"), replay.end.as_deref());
+        assert_eq!(
+            vec!["host", "CAST(cpu AS DOUBLE) AS cpu", "ts"],
+            replay
+                .transforms
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(&"127.0.0.1:4001".to_string()),
+            replay.connection.get("endpoint")
+        );
+    }
+
+    #[test]
+    fn test_parse_admin_bulk() {
+        let admin = parse_admin("ADMIN FLUSH TABLES");
+        assert_eq!(
+            Admin::Bulk(AdminBulk {
+                operation: BulkOperation::Flush,
+                schema: None,
+                pattern: None,
+                parallelism: None,
+            }),
+            admin
+        );
+
+        let admin = parse_admin("admin compact tables in metrics like 'cpu_%' parallelism 8");
+        assert_eq!(
+            Admin::Bulk(AdminBulk {
+                operation: BulkOperation::Compact,
+                schema: Some("metrics".to_string()),
+                pattern: Some("cpu_%".to_string()),
+                parallelism: Some(8),
+            }),
+            admin
+        );
+
+        let admin = parse_admin("ADMIN PURGE TABLES LIKE 'tmp_%' DRY RUN");
+        assert_eq!(
+            Admin::Bulk(AdminBulk {
+                operation: BulkOperation::Purge { dry_run: true },
+                schema: None,
+                pattern: Some("tmp_%".to_string()),
+                parallelism: None,
+            }),
+            admin
+        );
+
+        let admin = parse_admin("ADMIN SET TABLES IN metrics LIKE 'cpu_%' WITH (TTL = '7d')");
+        assert_eq!(
+            Admin::Bulk(AdminBulk {
+                operation: BulkOperation::SetOptions {
+                    options: [("ttl".to_string(), "7d".to_string())].into(),
+                },
+                schema: Some("metrics".to_string()),
+                pattern: Some("cpu_%".to_string()),
+                parallelism: None,
+            }),
+            admin
+        );
+        let result = ParserContext::create_with_dialect("ADMIN SET TABLES", &GenericDialect {});
+        assert_matches!(result, Err(error::Error::InvalidSql { .. }));
+
+        // A table named `tables`.
+        let admin = parse_admin("ADMIN FLUSH TABLE tables");
+        assert_eq!(
+            Admin::Flush(AdminFlush {
+                table_name: ObjectName(vec!["tables".into()]),
+                region_number: None,
+            }),
+            admin
+        );
+        assert_eq!(None, parse_admin("ADMIN FLUSH TABLES").table_name());
+
+        let result = ParserContext::create_with_dialect(
+            "ADMIN FLUSH TABLES PARALLELISM 0",
+            &GenericDialect {},
+        );
+        assert_matches!(result, Err(error::Error::InvalidSql { .. }));
+    }
+
+    #[test]
+    fn test_parse_admin_error() {
+        let sqls = [
+            "ADMIN",
+            "ADMIN VACUUM TABLE monitor",
+            "ADMIN FLUSH monitor",
+            "ADMIN FLUSH TABLE monitor REGION",
+            "ADMIN MIGRATE REGION 1 OF TABLE monitor TO 2",
+            "ADMIN PURGE TABLE monitor DRY",
+            "ADMIN REPLAY TABLE monitor TO TABLE monitor_v2",
+            "ADMIN COMPACT TABLES LIKE",
+            "ADMIN FLUSH TABLES PARALLELISM many",
+        ];
+        for sql in sqls {
+            let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+            assert_matches!(
+                result,
+                Err(error::Error::Unsupported { .. } | error::Error::Unexpected { .. }),
+                "{sql}"
+            );
+        }
+    }
+}
@@ -20,6 +20,7 @@ use crate::error::{self, Result};
 use crate::parser::ParserContext;
 use crate::statements::alter::{AlterTable, AlterTableOperation};
 use crate::statements::statement::Statement;
+use crate::util::to_lowercase_options_map;
 
 impl<'a> ParserContext<'a> {
     pub(crate) fn parse_alter(&mut self) -> Result<Statement> {
@@ -65,10 +66,16 @@ impl<'a> ParserContext<'a> {
             };
             AlterTableOperation::RenameTable { new_table_name }
         } else {
-            return Err(ParserError::ParserError(format!(
-                "expect keyword ADD or DROP or RENAME after ALTER TABLE, found {}",
-                parser.peek_token()
-            )));
+            let options = parser.parse_options(Keyword::SET)?;
+            if options.is_empty() {
+                return Err(ParserError::ParserError(format!(
+                    "expect keyword ADD, DROP, RENAME or SET after ALTER TABLE, found {}",
+                    parser.peek_token()
+                )));
+            }
+            AlterTableOperation::SetOptions {
+                options: to_lowercase_options_map(&options),
+            }
         };
         Ok(AlterTable::new(table_name, alter_operation))
     }
@@ -150,7 +157,7 @@ mod tests {
         let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
         assert!(result
             .to_string()
-            .contains("expect keyword ADD or DROP or RENAME after ALTER TABLE"));
+            .contains("expect keyword ADD, DROP, RENAME or SET after ALTER TABLE"));
 
         let sql = "ALTER TABLE test_table RENAME table_t";
         let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
@@ -174,4 +181,20 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_parse_alter_set_options() {
+        let sql = "ALTER TABLE test_table SET (TTL = '7d')";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Alter(alter_table) = result.remove(0) else { unreachable!() };
+        assert_eq!(
+            &AlterTableOperation::SetOptions {
+                options: [("ttl".to_string(), "7d".to_string())].into(),
+            },
+            alter_table.alter_operation()
+        );
+
+        let sql = "ALTER TABLE test_table SET ()";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
 }
@@ -19,6 +19,7 @@ use once_cell::sync::Lazy;
 use snafu::{ensure, OptionExt, ResultExt};
 use sqlparser::ast::{ColumnOption, ColumnOptionDef, DataType, Value};
 use sqlparser::dialect::keywords::Keyword;
+use sqlparser::dialect::GenericDialect;
 use sqlparser::keywords::ALL_KEYWORDS;
 use sqlparser::parser::IsOptional::Mandatory;
 use sqlparser::parser::{Parser, ParserError};
@@ -31,12 +32,14 @@ use crate::error::{
 };
 use crate::parser::ParserContext;
 use crate::statements::create::{
-    CreateDatabase, CreateExternalTable, CreateTable, PartitionEntry, Partitions, TIME_INDEX,
+    computed_column_option, unit_column_option, CloneTable, CreateDatabase, CreateExternalTable,
+    CreateFunction, CreateTable, CreateView, PartitionEntry, Partitions, TIME_INDEX, UNIT,
 };
 use crate::statements::statement::Statement;
 use crate::statements::{sql_data_type_to_concrete_data_type, sql_value_to_value};
 use crate::util::parse_option_string;
 
+const CLONE: &str = "CLONE";
 const ENGINE: &str = "ENGINE";
 const MAXVALUE: &str = "MAXVALUE";
 
@@ -54,6 +57,12 @@ impl<'a> ParserContext<'a> {
 
                 Keyword::EXTERNAL => self.parse_create_external_table(),
 
+                Keyword::VIEW => self.parse_create_view(),
+
+                Keyword::OR | Keyword::TEMPORARY | Keyword::FUNCTION => {
+                    self.parse_create_function()
+                }
+
                 _ => self.unsupported(w.to_string()),
             },
             unexpected => self.unsupported(unexpected.to_string()),
@@ -102,6 +111,92 @@ impl<'a> ParserContext<'a> {
         }))
     }
 
+    fn parse_create_view(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let view_name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a view name",
+                actual: self.peek_token_as_string(),
+            })?;
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let query = self
+            .parser
+            .parse_query()
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        Ok(Statement::CreateView(CreateView {
+            name: view_name,
+            query: Box::new(query.try_into()?),
+            if_not_exists,
+        }))
+    }
+
+    /// Parses `CREATE [OR REPLACE] [TEMPORARY] FUNCTION name(arg, ...) AS 'expression'`.
+    fn parse_create_function(&mut self) -> Result<Statement> {
+        let or_replace = self.parser.parse_keywords(&[Keyword::OR, Keyword::REPLACE]);
+        // Functions are always scoped to the session, `TEMPORARY` is only accepted for
+        // compatibility.
+        let _ = self.parser.parse_keyword(Keyword::TEMPORARY);
+        self.parser
+            .expect_keyword(Keyword::FUNCTION)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        let name = self
+            .parser
+            .parse_identifier()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a function name",
+                actual: self.peek_token_as_string(),
+            })?;
+        self.parser
+            .expect_token(&Token::LParen)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let args = if self.parser.consume_token(&Token::RParen) {
+            vec![]
+        } else {
+            let args = self
+                .parser
+                .parse_comma_separated(Parser::parse_identifier)
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            self.parser
+                .expect_token(&Token::RParen)
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            args
+        };
+        ensure!(
+            args.iter().map(|arg| arg.value.to_lowercase()).all_unique(),
+            error::InvalidSqlSnafu {
+                msg: format!("duplicate arguments in function {name}"),
+            }
+        );
+
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let body = self
+            .parser
+            .parse_literal_string()
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        // Checks the body now, so the errors are reported on creation instead of every call.
+        let _ = ParserContext::parse_expr(&body, &GenericDialect {})?;
+
+        Ok(Statement::CreateFunction(CreateFunction {
+            name,
+            args,
+            body,
+            or_replace,
+        }))
+    }
+
     fn parse_create_database(&mut self) -> Result<Statement> {
         self.parser.next_token();
 
@@ -139,6 +234,22 @@ impl<'a> ParserContext<'a> {
                 actual: self.peek_token_as_string(),
             })?;
 
+        if self.consume_token(CLONE) {
+            let source = self
+                .parser
+                .parse_object_name()
+                .context(error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name to clone",
+                    actual: self.peek_token_as_string(),
+                })?;
+            return Ok(Statement::CloneTable(CloneTable {
+                name: table_name,
+                source,
+                if_not_exists,
+            }));
+        }
+
         let (columns, constraints) = self.parse_columns()?;
 
         let partitions = self.parse_partitions()?;
@@ -456,6 +567,25 @@ impl<'a> ParserContext<'a> {
             Ok(Some(ColumnOption::Unique { is_primary: true }))
         } else if parser.parse_keyword(Keyword::UNIQUE) {
             Ok(Some(ColumnOption::Unique { is_primary: false }))
+        } else if parser.parse_keyword(Keyword::AS) {
+            // `AS (<expr>)` defines a computed column.
+            parser.expect_token(&Token::LParen)?;
+            let expr = parser.parse_expr()?;
+            parser.expect_token(&Token::RParen)?;
+            Ok(Some(computed_column_option(&expr.to_string())))
+        } else if matches!(
+            &parser.peek_token().token,
+            Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(UNIT)
+        ) {
+            // `UNIT '<unit>'` declares the unit of the column values.
+            let _ = parser.next_token();
+            match parser.next_token() {
+                TokenWithLocation {
+                    token: Token::SingleQuotedString(value),
+                    ..
+                } => Ok(Some(unit_column_option(&value))),
+                unexpected => parser.expected("string", unexpected),
+            }
         } else if parser.parse_keywords(&[Keyword::TIME, Keyword::INDEX]) {
             // Use a DialectSpecific option for time index
             Ok(Some(ColumnOption::DialectSpecific(vec![
@@ -787,6 +917,7 @@ mod tests {
     use sqlparser::dialect::GenericDialect;
 
     use super::*;
+    use crate::statements::create::{column_unit, computed_expr};
 
     #[test]
     fn test_parse_create_external_table() {
@@ -885,6 +1016,108 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_view() {
+        let sql = "CREATE VIEW cpu_avg AS SELECT host, avg(cpu) FROM monitor GROUP BY host";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::CreateView(c) => {
+                assert_eq!("cpu_avg", c.name.to_string());
+                assert_eq!(
+                    "SELECT host, avg(cpu) FROM monitor GROUP BY host",
+                    c.query.inner.to_string()
+                );
+                assert!(!c.if_not_exists);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE VIEW IF NOT EXISTS my_db.cpu_avg AS SELECT * FROM monitor";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::CreateView(c) => {
+                assert_eq!("my_db.cpu_avg", c.name.to_string());
+                assert!(c.if_not_exists);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE VIEW cpu_avg SELECT * FROM monitor";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
+    #[test]
+    fn test_parse_create_function() {
+        let sql = "CREATE FUNCTION celsius(f) AS '(f - 32) * 5 / 9'";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::CreateFunction(c) => {
+                assert_eq!("celsius", c.name.value);
+                assert_eq!(vec![Ident::new("f")], c.args);
+                assert_eq!("(f - 32) * 5 / 9", c.body);
+                assert!(!c.or_replace);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE OR REPLACE TEMPORARY FUNCTION ratio(a, b) AS 'a / b'";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::CreateFunction(c) => {
+                assert_eq!(2, c.args.len());
+                assert!(c.or_replace);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE FUNCTION one() AS '1'";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_matches!(&stmts[0], Statement::CreateFunction(c) if c.args.is_empty());
+
+        for sql in [
+            "CREATE FUNCTION f(a) AS 'a +'",
+            "CREATE FUNCTION f(a, A) AS 'a'",
+            "CREATE FUNCTION f(a) AS a + 1",
+            "CREATE FUNCTION f AS 'a'",
+            "CREATE OR REPLACE VIEW v AS SELECT 1",
+        ] {
+            assert!(
+                ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err(),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_clone_table() {
+        let sql = "CREATE TABLE monitor_dev CLONE monitor";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::CloneTable(c) => {
+                assert_eq!("monitor_dev", c.name.to_string());
+                assert_eq!("monitor", c.source.to_string());
+                assert!(!c.if_not_exists);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "create table if not exists dev.monitor clone public.monitor";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::CloneTable(c) => {
+                assert_eq!("dev.monitor", c.name.to_string());
+                assert_eq!("public.monitor", c.source.to_string());
+                assert!(c.if_not_exists);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE TABLE monitor_dev CLONE";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
     #[test]
     fn test_parse_create_database() {
         let sql = "create database";
@@ -917,6 +1150,13 @@ mod tests {
             }
             _ => unreachable!(),
         }
+
+        let sql = r#"create database "my-db""#;
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::CreateDatabase(c) => assert_eq!(c.database_name(), "my-db"),
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -1329,6 +1569,61 @@ ENGINE=mito";
         }
     }
 
+    #[test]
+    fn test_parse_create_table_with_computed_column() {
+        let sql = r"
+CREATE TABLE monitor (
+  host       STRING,
+  idc        STRING AS (substr(host, 1, 3)),
+  ts         TIMESTAMP TIME INDEX,
+  PRIMARY KEY (host),
+)";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::CreateTable(c) = &result[0] else {
+            unreachable!("should be create table statement");
+        };
+        let idc = &c.columns[1];
+        assert_eq!(1, idc.options.len());
+        assert_eq!(
+            Some("substr(host, 1, 3)"),
+            computed_expr(&idc.options[0].option)
+        );
+        assert_eq!("idc STRING AS (substr(host, 1, 3))", idc.to_string());
+        assert!(c.columns[2]
+            .options
+            .iter()
+            .all(|o| computed_expr(&o.option).is_none()));
+
+        let sql = "CREATE TABLE monitor (host STRING, idc STRING AS substr(host, 1, 3), ts TIMESTAMP TIME INDEX)";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
+    #[test]
+    fn test_parse_create_table_with_column_unit() {
+        let sql = r"
+CREATE TABLE monitor (
+  host       STRING,
+  memory     DOUBLE unit 'bytes' NULL,
+  ts         TIMESTAMP TIME INDEX,
+  PRIMARY KEY (host),
+)";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::CreateTable(c) = &result[0] else {
+            unreachable!("should be create table statement");
+        };
+        let memory = &c.columns[1];
+        assert_eq!(2, memory.options.len());
+        assert_eq!(Some("bytes"), column_unit(&memory.options[0].option));
+        assert_eq!("memory DOUBLE UNIT 'bytes' NULL", memory.to_string());
+        assert!(c.columns[0]
+            .options
+            .iter()
+            .all(|o| column_unit(&o.option).is_none()));
+
+        let sql = "CREATE TABLE monitor (memory DOUBLE UNIT bytes, ts TIMESTAMP TIME INDEX)";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
     #[test]
     fn test_parse_partitions_with_error_syntax() {
         let sql = r"
@@ -0,0 +1,164 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parses the hints in `/*+ ... */` comments, e.g. `/*+ no_index, parallel(8), read_replica */`.
+//!
+//! Hints are comments to sqlparser, so they are looked up in the tokens of the whole
+//! query. This also makes them work for statements whose body isn't SQL, like TQL.
+
+use snafu::{ensure, ResultExt};
+use sqlparser::dialect::Dialect;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace};
+
+use crate::error::{InvalidSqlSnafu, Result, TokenizerSnafu};
+use crate::parser::ParserContext;
+use crate::statements::hint::Hint;
+
+const HINT_PREFIX: &str = "/*+";
+const NO_INDEX: &str = "no_index";
+const PARALLEL: &str = "parallel";
+const READ_REPLICA: &str = "read_replica";
+
+impl<'a> ParserContext<'a> {
+    /// Parses the hints in `sql`. Unknown hints are ignored, as other databases do,
+    /// so that hints written for them don't fail the queries.
+    pub fn parse_hints(sql: &'a str, dialect: &dyn Dialect) -> Result<Vec<Hint>> {
+        if !sql.contains(HINT_PREFIX) {
+            return Ok(vec![]);
+        }
+
+        let tokens = Tokenizer::new(dialect, sql)
+            .tokenize()
+            .context(TokenizerSnafu { sql })?;
+        let mut hints = Vec::new();
+        for token in tokens {
+            if let Token::Whitespace(Whitespace::MultiLineComment(comment)) = token {
+                if let Some(content) = comment.strip_prefix('+') {
+                    parse_hint_list(content, dialect, &mut hints)?;
+                }
+            }
+        }
+        Ok(hints)
+    }
+}
+
+/// Parses hints like `name` or `name(arg, ...)`, separated by commas or spaces.
+fn parse_hint_list(content: &str, dialect: &dyn Dialect, hints: &mut Vec<Hint>) -> Result<()> {
+    let tokens = Tokenizer::new(dialect, content)
+        .tokenize()
+        .context(TokenizerSnafu { sql: content })?;
+    let mut tokens = tokens
+        .into_iter()
+        .filter(|t| !matches!(t, Token::Whitespace(_) | Token::Comma))
+        .peekable();
+
+    while let Some(token) = tokens.next() {
+        let Token::Word(w) = token else {
+            return InvalidSqlSnafu {
+                msg: format!("expect a hint name, found: {token}"),
+            }
+            .fail();
+        };
+
+        let mut args = Vec::new();
+        if tokens.next_if_eq(&Token::LParen).is_some() {
+            loop {
+                match tokens.next() {
+                    Some(Token::RParen) => break,
+                    Some(arg) => args.push(arg.to_string()),
+                    None => {
+                        return InvalidSqlSnafu {
+                            msg: format!("unclosed arguments of hint: {}", w.value),
+                        }
+                        .fail()
+                    }
+                }
+            }
+        }
+
+        if let Some(hint) = to_hint(&w.value.to_lowercase(), &args)? {
+            hints.push(hint);
+        }
+    }
+    Ok(())
+}
+
+fn to_hint(name: &str, args: &[String]) -> Result<Option<Hint>> {
+    let hint = match name {
+        NO_INDEX => Hint::NoIndex,
+        READ_REPLICA => Hint::ReadReplica,
+        PARALLEL => {
+            let parallelism = match args {
+                [arg] => arg.parse::<usize>().ok().filter(|n| *n > 0),
+                _ => None,
+            };
+            let Some(parallelism) = parallelism else {
+                return InvalidSqlSnafu {
+                    msg: format!("expect a positive integer in hint parallel, found: {args:?}"),
+                }
+                .fail();
+            };
+            return Ok(Some(Hint::Parallel(parallelism)));
+        }
+        _ => return Ok(None),
+    };
+
+    ensure!(
+        args.is_empty(),
+        InvalidSqlSnafu {
+            msg: format!("hint {name} takes no arguments, found: {args:?}"),
+        }
+    );
+    Ok(Some(hint))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    fn parse(sql: &str) -> Result<Vec<Hint>> {
+        ParserContext::parse_hints(sql, &GenericDialect {})
+    }
+
+    #[test]
+    fn test_parse_hints() {
+        let hints =
+            parse("SELECT /*+ no_index, PARALLEL(8) read_replica */ * FROM monitor").unwrap();
+        assert_eq!(
+            vec![Hint::NoIndex, Hint::Parallel(8), Hint::ReadReplica],
+            hints
+        );
+
+        let hints = parse("/*+ parallel(2) */ TQL EVAL (0, 10, '5s') up").unwrap();
+        assert_eq!(vec![Hint::Parallel(2)], hints);
+
+        // Plain comments, unknown hints and hints in string literals are ignored.
+        let hints =
+            parse("SELECT /* no_index */ '/*+ no_index */' FROM t /*+ use_hash(t) */").unwrap();
+        assert!(hints.is_empty());
+        assert!(parse("SELECT * FROM monitor").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_hints() {
+        assert!(parse("SELECT /*+ parallel */ * FROM t").is_err());
+        assert!(parse("SELECT /*+ parallel(0) */ * FROM t").is_err());
+        assert!(parse("SELECT /*+ parallel(a) */ * FROM t").is_err());
+        assert!(parse("SELECT /*+ parallel(8 */ * FROM t").is_err());
+        assert!(parse("SELECT /*+ no_index(t) */ * FROM t").is_err());
+        assert!(parse("SELECT /*+ 8 */ * FROM t").is_err());
+    }
+}
@@ -13,21 +13,103 @@
 // limitations under the License.
 
 use snafu::prelude::*;
+use sqlparser::ast::{
+    BinaryOperator, Expr, Query as SpQuery, SetExpr, SqlOption, TableFactor, Value,
+};
+use sqlparser::keywords::Keyword;
 
 use crate::error::{self, Result};
 use crate::parser::ParserContext;
 use crate::statements::query::Query;
 use crate::statements::statement::Statement;
 
+/// Option to tail the tables read by a query, e.g. `SELECT * FROM t WITH (tail = true)`.
+const TAIL: &str = "tail";
+
 impl<'a> ParserContext<'a> {
     /// Parses select and it's variants.
     pub(crate) fn parse_query(&mut self) -> Result<Statement> {
-        let spquery = self
+        let mut spquery = self
             .parser
             .parse_query()
             .context(error::SyntaxSnafu { sql: self.sql })?;
+        // sqlparser takes `WITH (...)` right after a table as table hints.
+        let mut tail = extract_tail_hint(&mut spquery)?;
+
+        let options = self
+            .parser
+            .parse_options(Keyword::WITH)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        for SqlOption { name, value } in options {
+            ensure!(
+                name.value.eq_ignore_ascii_case(TAIL),
+                error::InvalidSqlSnafu {
+                    msg: format!("unknown query option: {name}"),
+                }
+            );
+            tail = parse_tail(value)?;
+        }
 
-        Ok(Statement::Query(Box::new(Query::try_from(spquery)?)))
+        let mut query = Query::try_from(spquery)?;
+        query.tail = tail;
+        Ok(Statement::Query(Box::new(query)))
+    }
+}
+
+/// Removes the `tail = ...` hints of the tables in the top level select and returns
+/// whether the query should tail its tables.
+fn extract_tail_hint(query: &mut SpQuery) -> Result<bool> {
+    let SetExpr::Select(select) = query.body.as_mut() else { return Ok(false) };
+
+    let mut tail = false;
+    let relations = select.from.iter_mut().flat_map(|table| {
+        std::iter::once(&mut table.relation)
+            .chain(table.joins.iter_mut().map(|join| &mut join.relation))
+    });
+    for relation in relations {
+        let TableFactor::Table { with_hints, .. } = relation else { continue };
+        let mut hints = Vec::with_capacity(with_hints.len());
+        for hint in with_hints.drain(..) {
+            match hint {
+                Expr::BinaryOp {
+                    left,
+                    op: BinaryOperator::Eq,
+                    right,
+                } if is_tail(&left) => match *right {
+                    Expr::Value(value) => tail = parse_tail(value)?,
+                    right => {
+                        return error::InvalidSqlSnafu {
+                            msg: format!("invalid value of option {TAIL}: {right}"),
+                        }
+                        .fail()
+                    }
+                },
+                hint => hints.push(hint),
+            }
+        }
+        *with_hints = hints;
+    }
+
+    Ok(tail)
+}
+
+fn is_tail(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case(TAIL))
+}
+
+fn parse_tail(value: Value) -> Result<bool> {
+    match value {
+        Value::Boolean(tail) => Ok(tail),
+        Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => s
+            .parse::<bool>()
+            .ok()
+            .with_context(|| error::InvalidSqlSnafu {
+                msg: format!("invalid value of option {TAIL}: {s}"),
+            }),
+        value => error::InvalidSqlSnafu {
+            msg: format!("invalid value of option {TAIL}: {value}"),
+        }
+        .fail(),
     }
 }
 
@@ -36,6 +118,14 @@ mod tests {
     use sqlparser::dialect::GenericDialect;
 
     use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    fn parse_tail(sql: &str) -> bool {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::Query(query) = stmts.remove(0) else { unreachable!() };
+        query.tail
+    }
 
     #[test]
     pub fn test_parse_query() {
@@ -57,4 +147,33 @@ mod tests {
             .to_string()
             .contains("Expected an expression"));
     }
+
+    #[test]
+    pub fn test_parse_tail_query() {
+        assert!(!parse_tail("SELECT * FROM t"));
+        assert!(parse_tail("SELECT * FROM t WITH (tail = true)"));
+        assert!(parse_tail("SELECT * FROM t WITH (TAIL = 'true')"));
+        assert!(!parse_tail("SELECT * FROM t WITH (tail = false)"));
+        assert!(parse_tail("SELECT a FROM t WHERE a > 1 WITH (tail = true)"));
+
+        // The hint is removed from the table.
+        let stmts = ParserContext::create_with_dialect(
+            "SELECT * FROM t WITH (tail = true)",
+            &GenericDialect {},
+        )
+        .unwrap();
+        let Statement::Query(query) = &stmts[0] else { unreachable!() };
+        assert_eq!("SELECT * FROM t", query.inner.to_string());
+
+        assert!(ParserContext::create_with_dialect(
+            "SELECT * FROM t WITH (tail = 1)",
+            &GenericDialect {}
+        )
+        .is_err());
+        assert!(ParserContext::create_with_dialect(
+            "SELECT * FROM t WHERE a > 1 WITH (follow = true)",
+            &GenericDialect {}
+        )
+        .is_err());
+    }
 }
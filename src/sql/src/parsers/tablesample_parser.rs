@@ -0,0 +1,290 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parses `TABLESAMPLE SYSTEM (n [PERCENT])` after a table in the FROM clause.
+//!
+//! sqlparser doesn't understand the clause, so it's taken out of the token stream
+//! before the statements are parsed, and attached to the parsed query afterwards.
+
+use snafu::{ensure, OptionExt};
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, TokenWithLocation, Word};
+
+use crate::error::{InvalidSqlSnafu, Result};
+use crate::statements::query::TableSample;
+
+pub(crate) const TABLESAMPLE: &str = "TABLESAMPLE";
+
+/// Removes all the sample clauses from `tokens`. Each returned sample comes with
+/// the index of the statement it belongs to.
+pub(crate) fn extract_table_samples(
+    tokens: Vec<TokenWithLocation>,
+) -> Result<(Vec<TokenWithLocation>, Vec<(usize, TableSample)>)> {
+    let mut output: Vec<TokenWithLocation> = Vec::with_capacity(tokens.len());
+    let mut samples = Vec::new();
+    let mut stmt_index = 0;
+    let mut in_statement = false;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i].token {
+            Token::SemiColon => {
+                // Empty statements are skipped by the parser, don't count them.
+                if in_statement {
+                    stmt_index += 1;
+                    in_statement = false;
+                }
+            }
+            Token::Word(w) if is_word(w, TABLESAMPLE) => {
+                let (table, alias) = sampled_table(&output)?;
+                let (percent, next) = parse_sample_method(&tokens, i + 1)?;
+                samples.push((
+                    stmt_index,
+                    TableSample {
+                        table,
+                        alias,
+                        percent,
+                    },
+                ));
+                i = next;
+                continue;
+            }
+            Token::Whitespace(_) => {}
+            _ => in_statement = true,
+        }
+        output.push(tokens[i].clone());
+        i += 1;
+    }
+    Ok((output, samples))
+}
+
+fn is_word(w: &Word, s: &str) -> bool {
+    w.quote_style.is_none() && w.value.eq_ignore_ascii_case(s)
+}
+
+/// Finds the name and the alias of the table sampled, from the tokens before
+/// `TABLESAMPLE`, which are `table [[AS] alias]`.
+fn sampled_table(tokens: &[TokenWithLocation]) -> Result<(ObjectName, Option<Ident>)> {
+    let mut pos = tokens.len();
+    let mut name = object_name_before(tokens, &mut pos)?;
+    let mut alias = None;
+
+    match prev_token(tokens, &mut pos) {
+        Some(Token::Word(w)) if w.keyword == Keyword::AS => {
+            alias = name.0.pop();
+            name = object_name_before(tokens, &mut pos)?;
+        }
+        Some(Token::Word(w)) if !matches!(w.keyword, Keyword::FROM | Keyword::JOIN) => {
+            // The name is an alias, then the word is the last part of the table name.
+            alias = name.0.pop();
+            pos += 1;
+            name = object_name_before(tokens, &mut pos)?;
+        }
+        Some(Token::Word(_)) | Some(Token::Comma) => {}
+        _ => {
+            return InvalidSqlSnafu {
+                msg: "TABLESAMPLE can only be applied to a table",
+            }
+            .fail()
+        }
+    }
+    Ok((name, alias))
+}
+
+/// Reads a possibly qualified name ending before `pos`, and moves `pos` to its start.
+fn object_name_before(tokens: &[TokenWithLocation], pos: &mut usize) -> Result<ObjectName> {
+    let mut idents = Vec::new();
+    loop {
+        match prev_token(tokens, pos) {
+            Some(Token::Word(w)) => idents.push(Ident {
+                value: w.value.clone(),
+                quote_style: w.quote_style,
+            }),
+            _ => {
+                return InvalidSqlSnafu {
+                    msg: "TABLESAMPLE must follow a table name",
+                }
+                .fail()
+            }
+        }
+
+        let before_word = *pos;
+        match prev_token(tokens, pos) {
+            Some(Token::Period) => {}
+            _ => {
+                *pos = before_word;
+                break;
+            }
+        }
+    }
+    idents.reverse();
+    Ok(ObjectName(idents))
+}
+
+/// Moves `pos` backward to the previous non-whitespace token and returns it.
+fn prev_token<'a>(tokens: &'a [TokenWithLocation], pos: &mut usize) -> Option<&'a Token> {
+    while *pos > 0 {
+        *pos -= 1;
+        match &tokens[*pos].token {
+            Token::Whitespace(_) => continue,
+            token => return Some(token),
+        }
+    }
+    None
+}
+
+/// Moves `pos` forward to the next non-whitespace token and returns it.
+fn next_token<'a>(tokens: &'a [TokenWithLocation], pos: &mut usize) -> Option<&'a Token> {
+    while let Some(token) = tokens.get(*pos) {
+        *pos += 1;
+        if !matches!(token.token, Token::Whitespace(_)) {
+            return Some(&token.token);
+        }
+    }
+    None
+}
+
+/// Parses `SYSTEM (n [PERCENT])` from `pos`, returns the percentage and the position
+/// right after the clause.
+fn parse_sample_method(tokens: &[TokenWithLocation], mut pos: usize) -> Result<(f64, usize)> {
+    match next_token(tokens, &mut pos) {
+        Some(Token::Word(w)) if is_word(w, "SYSTEM") => {}
+        Some(Token::Word(w)) if is_word(w, "BERNOULLI") => {
+            return InvalidSqlSnafu {
+                msg: "TABLESAMPLE BERNOULLI is not supported, use TABLESAMPLE SYSTEM",
+            }
+            .fail()
+        }
+        other => {
+            return InvalidSqlSnafu {
+                msg: format!("expect a sample method after TABLESAMPLE, found: {other:?}"),
+            }
+            .fail()
+        }
+    }
+
+    ensure!(
+        next_token(tokens, &mut pos) == Some(&Token::LParen),
+        InvalidSqlSnafu {
+            msg: "expect '(' after TABLESAMPLE SYSTEM",
+        }
+    );
+    let percent = match next_token(tokens, &mut pos) {
+        Some(Token::Number(n, _)) => n.parse::<f64>().ok(),
+        _ => None,
+    }
+    .context(InvalidSqlSnafu {
+        msg: "expect a number as the sample percentage",
+    })?;
+    ensure!(
+        (0.0..=100.0).contains(&percent),
+        InvalidSqlSnafu {
+            msg: format!("sample percentage must be between 0 and 100, got: {percent}"),
+        }
+    );
+
+    let mut token = next_token(tokens, &mut pos);
+    if matches!(token, Some(Token::Word(w)) if is_word(w, "PERCENT")) {
+        token = next_token(tokens, &mut pos);
+    }
+    ensure!(
+        token == Some(&Token::RParen),
+        InvalidSqlSnafu {
+            msg: "expect ')' after the sample percentage",
+        }
+    );
+
+    let after_clause = pos;
+    if matches!(next_token(tokens, &mut pos), Some(Token::Word(w)) if is_word(w, "REPEATABLE")) {
+        return InvalidSqlSnafu {
+            msg: "TABLESAMPLE REPEATABLE is not supported",
+        }
+        .fail();
+    }
+    Ok((percent, after_clause))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    fn parse_samples(sql: &str) -> Vec<(String, Option<String>, f64)> {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Query(query) = stmts.remove(0) else { unreachable!() };
+        query
+            .table_samples
+            .iter()
+            .map(|s| {
+                let alias = s.alias.as_ref().map(|alias| alias.to_string());
+                (s.table.to_string(), alias, s.percent)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_table_sample() {
+        assert_eq!(
+            vec![("monitor".to_string(), None, 10.0)],
+            parse_samples("SELECT * FROM monitor TABLESAMPLE SYSTEM (10 PERCENT)")
+        );
+        assert_eq!(
+            vec![
+                ("public.monitor".to_string(), Some("m".to_string()), 0.5),
+                ("\"Cpu\"".to_string(), Some("c".to_string()), 100.0)
+            ],
+            parse_samples(
+                "select host from public.monitor as m tablesample system(0.5) \
+                 join \"Cpu\" c TABLESAMPLE SYSTEM (100) on m.host = c.host where m.host = 'a'"
+            )
+        );
+
+        let sql = "SELECT * FROM monitor TABLESAMPLE SYSTEM (1 PERCENT) WHERE host = 'a'";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Query(query) = stmts.remove(0) else { unreachable!() };
+        assert_eq!(
+            "SELECT * FROM monitor WHERE host = 'a'",
+            query.inner.to_string()
+        );
+
+        // samples are attached to the statement they belong to
+        let sql = ";SELECT 1; SELECT * FROM t TABLESAMPLE SYSTEM (5)";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let (Statement::Query(first), Statement::Query(second)) = (&stmts[0], &stmts[1]) else { unreachable!() };
+        assert!(first.table_samples.is_empty());
+        assert_eq!(1, second.table_samples.len());
+    }
+
+    #[test]
+    fn test_parse_invalid_table_sample() {
+        for sql in [
+            "SELECT * FROM t TABLESAMPLE BERNOULLI (10)",
+            "SELECT * FROM t TABLESAMPLE SYSTEM 10",
+            "SELECT * FROM t TABLESAMPLE SYSTEM (101)",
+            "SELECT * FROM t TABLESAMPLE SYSTEM (ten)",
+            "SELECT * FROM t TABLESAMPLE SYSTEM (10) REPEATABLE (1)",
+            "SELECT * FROM (SELECT * FROM t) TABLESAMPLE SYSTEM (10)",
+            "SELECT * FROM t TABLESAMPLE",
+            "DELETE FROM t TABLESAMPLE SYSTEM (10)",
+        ] {
+            assert!(
+                ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err(),
+                "{sql}"
+            );
+        }
+    }
+}
@@ -83,9 +83,11 @@ impl<'a> ParserContext<'a> {
         let value = match parser.next_token().token {
             Token::Number(n, _) => n,
             Token::DoubleQuotedString(s) | Token::SingleQuotedString(s) => s,
+            // A parameter like `$start`, which is bound when the TQL is executed.
+            Token::Placeholder(p) => p,
             unexpected => {
                 return Err(ParserError::ParserError(format!(
-                    "Expect number or string, but is {unexpected:?}"
+                    "Expect number, string or parameter, but is {unexpected:?}"
                 )));
             }
         };
@@ -206,6 +208,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_tql_with_parameters() {
+        let sql = "TQL EVAL ($start, $end, '1m') http_requests_total{host=$host} > $threshold";
+
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        let statement = result.remove(0);
+        match statement {
+            Statement::Tql(Tql::Eval(eval)) => {
+                assert_eq!(eval.start, "$start");
+                assert_eq!(eval.end, "$end");
+                assert_eq!(eval.step, "1m");
+                assert_eq!(eval.query, "http_requests_total{host=$host} > $threshold");
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_parse_tql_error() {
         // Invalid duration
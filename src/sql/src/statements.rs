@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod admin;
 pub mod alter;
 pub mod copy;
 pub mod create;
@@ -19,8 +20,10 @@ pub mod delete;
 pub mod describe;
 pub mod drop;
 pub mod explain;
+pub mod hint;
 pub mod insert;
 pub mod query;
+pub mod set_variables;
 pub mod show;
 pub mod statement;
 pub mod tql;
@@ -45,6 +48,7 @@ use crate::error::{
     ConvertValueSnafu, InvalidSqlValueSnafu, ParseSqlValueSnafu, Result,
     SerializeColumnDefaultConstraintSnafu, TimestampOverflowSnafu, UnsupportedDefaultValueSnafu,
 };
+use crate::statements::create::{column_unit, computed_expr};
 
 fn parse_string_to_value(
     column_name: &str,
@@ -288,6 +292,22 @@ pub fn column_def_to_schema(column_def: &ColumnDef, is_time_index: bool) -> Resu
             column: &column_def.name.value,
         })?;
 
+    if let Some(expr) = column_def
+        .options
+        .iter()
+        .find_map(|o| computed_expr(&o.option))
+    {
+        column_schema = column_schema.with_computed_expr(expr);
+    }
+
+    if let Some(unit) = column_def
+        .options
+        .iter()
+        .find_map(|o| column_unit(&o.option))
+    {
+        column_schema = column_schema.with_unit(unit);
+    }
+
     if let Some(ColumnOption::Comment(c)) = column_def.options.iter().find_map(|o| {
         if matches!(o.option, ColumnOption::Comment(_)) {
             Some(&o.option)
@@ -408,6 +428,7 @@ mod tests {
 
     use super::*;
     use crate::ast::TimezoneInfo;
+    use crate::statements::create::{computed_column_option, unit_column_option};
     use crate::statements::ColumnOption;
 
     fn check_type(sql_type: SqlDataType, data_type: ConcreteDataType) {
@@ -773,6 +794,38 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_column_def_to_schema_with_computed_expr() {
+        let column_def = ColumnDef {
+            name: "idc".into(),
+            data_type: SqlDataType::String,
+            collation: None,
+            options: vec![ColumnOptionDef {
+                name: None,
+                option: computed_column_option("substr(host, 1, 3)"),
+            }],
+        };
+
+        let column_schema = column_def_to_schema(&column_def, false).unwrap();
+        assert_eq!(Some("substr(host, 1, 3)"), column_schema.computed_expr());
+    }
+
+    #[test]
+    pub fn test_column_def_to_schema_with_unit() {
+        let column_def = ColumnDef {
+            name: "memory".into(),
+            data_type: SqlDataType::Double,
+            collation: None,
+            options: vec![ColumnOptionDef {
+                name: None,
+                option: unit_column_option("bytes"),
+            }],
+        };
+
+        let column_schema = column_def_to_schema(&column_def, false).unwrap();
+        assert_eq!(Some("bytes"), column_schema.unit());
+    }
+
     #[test]
     pub fn test_parse_placeholder_value() {
         assert!(sql_value_to_value(
@@ -0,0 +1,197 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use sqlparser::ast::{ObjectName, SelectItem};
+
+use crate::statements::alter::{AlterTable, AlterTableOperation};
+use crate::statements::statement::Statement;
+
+/// Administrative statements, which operate on tables or regions directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admin {
+    Flush(AdminFlush),
+    Compact(AdminCompact),
+    Migrate(AdminMigrate),
+    Purge(AdminPurge),
+    DropRange(AdminDropRange),
+    Attach(AdminAttach),
+    Scrub(AdminScrub),
+    Replay(AdminReplay),
+    Bulk(AdminBulk),
+}
+
+/// ADMIN FLUSH TABLE <table> [REGION <region_number>]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminFlush {
+    pub table_name: ObjectName,
+    /// Flush all regions of the table if absent.
+    pub region_number: Option<u32>,
+}
+
+/// ADMIN COMPACT TABLE <table> [REGION <region_number>]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminCompact {
+    pub table_name: ObjectName,
+    /// Compact all regions of the table if absent.
+    pub region_number: Option<u32>,
+}
+
+/// ADMIN MIGRATE REGION <region_number> OF TABLE <table> FROM <from_peer> TO <to_peer>
+///
+/// Datanodes open or close a table as a whole, so the source datanode must serve no other
+/// region of the table, and the target datanode must serve none of its regions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminMigrate {
+    pub table_name: ObjectName,
+    pub region_number: u32,
+    pub from_peer: u64,
+    pub to_peer: u64,
+}
+
+/// ADMIN PURGE TABLE <table> [REGION <region_number>] [DRY RUN]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminPurge {
+    pub table_name: ObjectName,
+    /// Purge all regions of the table if absent.
+    pub region_number: Option<u32>,
+    /// Only reports the expired data without purging it.
+    pub dry_run: bool,
+}
+
+/// ADMIN DROP RANGE TABLE <table> [REGION <region_number>] FROM '<start>' TO '<end>' [DRY RUN]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminDropRange {
+    pub table_name: ObjectName,
+    /// Drop data from all regions of the table if absent.
+    pub region_number: Option<u32>,
+    /// Inclusive start of the time range to drop.
+    pub start: String,
+    /// Inclusive end of the time range to drop.
+    pub end: String,
+    /// Only reports the data to drop without dropping it.
+    pub dry_run: bool,
+}
+
+/// ADMIN ATTACH TABLE <table> [REGION <region_number>] FROM '<staging_dir>'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminAttach {
+    pub table_name: ObjectName,
+    /// Attach staged files to all regions of the table if absent.
+    pub region_number: Option<u32>,
+    /// Directory in the object store holding the staged SST files of each region.
+    pub staging_dir: String,
+}
+
+/// ADMIN SCRUB TABLE <table> [REGION <region_number>]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminScrub {
+    pub table_name: ObjectName,
+    /// Scrub all regions of the table if absent.
+    pub region_number: Option<u32>,
+}
+
+/// ADMIN REPLAY TABLE <table> [FROM '<start>'] [TO '<end>'] INTO TABLE <target>
+/// [TRANSFORM (<expr> [AS <column>], ...)] [CONNECTION (<options>)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminReplay {
+    pub table_name: ObjectName,
+    /// Inclusive start of the time range to replay, replays from the earliest data if absent.
+    pub start: Option<String>,
+    /// Exclusive end of the time range to replay, replays to the latest data if absent.
+    pub end: Option<String>,
+    pub target_table: ObjectName,
+    /// Expressions computing the columns of the target table from the rows of the table,
+    /// all columns are replayed as is if empty.
+    pub transforms: Vec<SelectItem>,
+    /// Options to connect to another cluster to replay into, the target table is in this
+    /// cluster if empty. Keys are in lowercase.
+    pub connection: HashMap<String, String>,
+}
+
+/// ADMIN { FLUSH | COMPACT | PURGE | SET } TABLES [IN <schema>] [LIKE '<pattern>']
+/// [PARALLELISM <parallelism>] [DRY RUN] [WITH (<options>)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminBulk {
+    pub operation: BulkOperation,
+    /// Schema of the tables, the current schema if absent.
+    pub schema: Option<String>,
+    /// `LIKE` pattern of the table names, all tables of the schema if absent.
+    pub pattern: Option<String>,
+    /// Max number of tables operated on at the same time, uses the default if absent.
+    pub parallelism: Option<usize>,
+}
+
+/// Operation applied to each table by [AdminBulk].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkOperation {
+    Flush,
+    Compact,
+    /// Only reports the expired data without purging it if `dry_run` is set.
+    Purge {
+        dry_run: bool,
+    },
+    /// Sets the options of the tables, like `ALTER TABLE <table> SET (<options>)`.
+    SetOptions {
+        options: HashMap<String, String>,
+    },
+}
+
+impl BulkOperation {
+    /// Returns the statement applying the operation to the table.
+    pub fn to_statement(&self, table_name: ObjectName) -> Statement {
+        let admin = match self {
+            BulkOperation::Flush => Admin::Flush(AdminFlush {
+                table_name,
+                region_number: None,
+            }),
+            BulkOperation::Compact => Admin::Compact(AdminCompact {
+                table_name,
+                region_number: None,
+            }),
+            BulkOperation::Purge { dry_run } => Admin::Purge(AdminPurge {
+                table_name,
+                region_number: None,
+                dry_run: *dry_run,
+            }),
+            BulkOperation::SetOptions { options } => {
+                return Statement::Alter(AlterTable::new(
+                    table_name,
+                    AlterTableOperation::SetOptions {
+                        options: options.clone(),
+                    },
+                ))
+            }
+        };
+        Statement::Admin(admin)
+    }
+}
+
+impl Admin {
+    /// The table this statement operates on, `None` if it operates on multiple tables.
+    pub fn table_name(&self) -> Option<&ObjectName> {
+        match self {
+            Admin::Flush(flush) => Some(&flush.table_name),
+            Admin::Compact(compact) => Some(&compact.table_name),
+            Admin::Migrate(migrate) => Some(&migrate.table_name),
+            Admin::Purge(purge) => Some(&purge.table_name),
+            Admin::DropRange(drop_range) => Some(&drop_range.table_name),
+            Admin::Attach(attach) => Some(&attach.table_name),
+            Admin::Scrub(scrub) => Some(&scrub.table_name),
+            Admin::Replay(replay) => Some(&replay.table_name),
+            Admin::Bulk(_) => None,
+        }
+    }
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use sqlparser::ast::{ColumnDef, Ident, ObjectName, TableConstraint};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,4 +49,6 @@ pub enum AlterTableOperation {
     DropColumn { name: Ident },
     /// `RENAME <new_table_name>`
     RenameTable { new_table_name: String },
+    /// `SET (<option> = <value>, ...)`
+    SetOptions { options: HashMap<String, String> },
 }
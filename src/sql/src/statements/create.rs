@@ -15,10 +15,15 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-use common_catalog::consts::IMMUTABLE_FILE_ENGINE;
+use common_catalog::consts::{IMMUTABLE_FILE_ENGINE, REMOTE_TABLE_ENGINE};
 use itertools::Itertools;
+use sqlparser::dialect::keywords::Keyword;
+use sqlparser::tokenizer::{Token, Word};
 
-use crate::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint, Value as SqlValue};
+use crate::ast::{
+    ColumnDef, ColumnOption, Ident, ObjectName, SqlOption, TableConstraint, Value as SqlValue,
+};
+use crate::statements::query::Query;
 
 const LINE_SEP: &str = ",\n";
 const COMMA_SEP: &str = ", ";
@@ -57,6 +62,54 @@ pub fn is_time_index(constraint: &TableConstraint) -> bool {
     }  if name.value == TIME_INDEX)
 }
 
+/// Creates the option of a computed column, a dialect specific option displayed as
+/// `AS (<expr>)`.
+pub fn computed_column_option(expr: &str) -> ColumnOption {
+    ColumnOption::DialectSpecific(vec![
+        Token::make_keyword("AS"),
+        Token::make_word(&format!("({expr})"), None),
+    ])
+}
+
+/// Returns the expression if the option is created by [computed_column_option].
+pub fn computed_expr(option: &ColumnOption) -> Option<&str> {
+    let ColumnOption::DialectSpecific(tokens) = option else {
+        return None;
+    };
+    match &tokens[..] {
+        [Token::Word(Word {
+            keyword: Keyword::AS,
+            ..
+        }), Token::Word(Word { value, .. })] => value.strip_prefix('(')?.strip_suffix(')'),
+        _ => None,
+    }
+}
+
+/// Keyword of the column option declaring the unit of the column values.
+pub const UNIT: &str = "UNIT";
+
+/// Creates the option of the unit of a column, a dialect specific option displayed as
+/// `UNIT '<unit>'`.
+pub fn unit_column_option(unit: &str) -> ColumnOption {
+    ColumnOption::DialectSpecific(vec![
+        Token::make_word(UNIT, None),
+        Token::SingleQuotedString(unit.to_string()),
+    ])
+}
+
+/// Returns the unit if the option is created by [unit_column_option].
+pub fn column_unit(option: &ColumnOption) -> Option<&str> {
+    let ColumnOption::DialectSpecific(tokens) = option else {
+        return None;
+    };
+    match &tokens[..] {
+        [Token::Word(Word { value, .. }), Token::SingleQuotedString(unit)] if value == UNIT => {
+            Some(unit)
+        }
+        _ => None,
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CreateTable {
     /// Create if not exists
@@ -167,11 +220,12 @@ impl Display for CreateTable {
         let partitions = self.format_partitions();
         let engine = &self.engine;
         let options = self.format_options();
-        let maybe_external = if self.engine == IMMUTABLE_FILE_ENGINE {
-            "EXTERNAL "
-        } else {
-            ""
-        };
+        let maybe_external =
+            if self.engine == IMMUTABLE_FILE_ENGINE || self.engine == REMOTE_TABLE_ENGINE {
+                "EXTERNAL "
+            } else {
+                ""
+            };
         write!(
             f,
             r#"CREATE {maybe_external}TABLE {if_not_exists} {name} (
@@ -191,6 +245,18 @@ pub struct CreateDatabase {
     pub if_not_exists: bool,
 }
 
+impl CreateDatabase {
+    /// Returns the name of the database to create, without the quotes.
+    pub fn database_name(&self) -> String {
+        self.name
+            .0
+            .iter()
+            .map(|ident| ident.value.as_str())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CreateExternalTable {
     /// Table name
@@ -205,6 +271,40 @@ pub struct CreateExternalTable {
     pub engine: String,
 }
 
+/// `CREATE TABLE <name> CLONE <source>` statement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CloneTable {
+    /// Name of the table to create.
+    pub name: ObjectName,
+    /// Name of the table to clone.
+    pub source: ObjectName,
+    /// Create if not exists
+    pub if_not_exists: bool,
+}
+
+/// `CREATE VIEW` statement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CreateView {
+    /// View name
+    pub name: ObjectName,
+    /// The query the view is defined by.
+    pub query: Box<Query>,
+    /// Create if not exists
+    pub if_not_exists: bool,
+}
+
+/// `CREATE FUNCTION` statement, which defines a function in the session that is expanded
+/// to its expression when the queries calling it are planned.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CreateFunction {
+    pub name: Ident,
+    pub args: Vec<Ident>,
+    /// The expression the function is expanded to, which refers the arguments by names.
+    pub body: String,
+    /// Replaces the function of the same name.
+    pub or_replace: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use sqlparser::dialect::GenericDialect;
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sqlparser::ast::ObjectName;
+use sqlparser::ast::{Ident, ObjectName};
 
 /// DROP TABLE statement.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,3 +30,28 @@ impl DropTable {
         &self.table_name
     }
 }
+
+/// DROP VIEW statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropView {
+    view_name: ObjectName,
+}
+
+impl DropView {
+    /// Creates a statement for `DROP VIEW`
+    pub fn new(view_name: ObjectName) -> Self {
+        Self { view_name }
+    }
+
+    pub fn view_name(&self) -> &ObjectName {
+        &self.view_name
+    }
+}
+
+/// DROP FUNCTION statement, which drops a function defined in the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropFunction {
+    pub name: Ident,
+    /// Don't fail if the function doesn't exist.
+    pub if_exists: bool,
+}
@@ -0,0 +1,37 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+
+/// A hint embedded in a statement as a `/*+ ... */` comment, e.g.
+/// `SELECT /*+ no_index, parallel(8) */ * FROM monitor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// Scans tables without pruning the data by the filters.
+    NoIndex,
+    /// Executes the query with the given parallelism.
+    Parallel(usize),
+    /// Reads the data from the replicas of the regions if possible.
+    ReadReplica,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hint::NoIndex => write!(f, "no_index"),
+            Hint::Parallel(n) => write!(f, "parallel({n})"),
+            Hint::ReadReplica => write!(f, "read_replica"),
+        }
+    }
+}
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use datatypes::prelude::ConcreteDataType;
-use sqlparser::ast::Query as SpQuery;
+use sqlparser::ast::{Ident, ObjectName, Query as SpQuery};
 
 use crate::error::Error;
 
@@ -22,8 +22,26 @@ use crate::error::Error;
 pub struct Query {
     pub inner: SpQuery,
     pub param_types: Vec<ConcreteDataType>,
+    /// Tables read with `TABLESAMPLE`.
+    pub table_samples: Vec<TableSample>,
+    /// Whether to tail the tables, set by `WITH (tail = true)`.
+    pub tail: bool,
 }
 
+/// `TABLESAMPLE SYSTEM (percent PERCENT)` of a table, which reads about `percent`
+/// percent of the table, sampled in units of storage blocks instead of rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSample {
+    pub table: ObjectName,
+    /// The alias of the sampled table, which tells the sampled relation from other
+    /// relations reading the same table.
+    pub alias: Option<Ident>,
+    pub percent: f64,
+}
+
+// The percent is always a finite number, checked by the parser.
+impl Eq for TableSample {}
+
 /// Automatically converts from sqlparser Query instance to SqlQuery.
 impl TryFrom<SpQuery> for Query {
     type Error = Error;
@@ -32,6 +50,8 @@ impl TryFrom<SpQuery> for Query {
         Ok(Query {
             inner: q,
             param_types: vec![],
+            table_samples: vec![],
+            tail: false,
         })
     }
 }
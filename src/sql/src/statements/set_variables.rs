@@ -0,0 +1,65 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::{Expr, ObjectName};
+
+/// SQL structure for `SET variable = value`, the variables are kept in the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetVariables {
+    pub variable: ObjectName,
+    pub value: Vec<Expr>,
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    #[test]
+    pub fn test_set_variables() {
+        let sql = "SET @host = 'host1'";
+        let stmts: Vec<Statement> =
+            ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::SetVariables(set) => {
+                assert_eq!("@host", set.variable.to_string());
+                assert_eq!(1, set.value.len());
+                assert_eq!("'host1'", set.value[0].to_string());
+            }
+            _ => {
+                unreachable!();
+            }
+        }
+
+        let sql = "SET @threshold = 0.5; SELECT 1";
+        let stmts: Vec<Statement> =
+            ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(2, stmts.len());
+        match &stmts[0] {
+            Statement::SetVariables(set) => {
+                assert_eq!("@threshold", set.variable.to_string());
+                assert_eq!("0.5", set.value[0].to_string());
+            }
+            _ => {
+                unreachable!();
+            }
+        }
+
+        let sql = "SET";
+        ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+    }
+}
@@ -34,6 +34,10 @@ impl fmt::Display for ShowKind {
     }
 }
 
+/// SQL structure for `SHOW CATALOGS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowCatalogs;
+
 /// SQL structure for `SHOW DATABASES`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ShowDatabases {
@@ -60,6 +64,12 @@ pub struct ShowCreateTable {
     pub table_name: ObjectName,
 }
 
+/// SQL structure for `SHOW CARDINALITY FOR TABLE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowCardinality {
+    pub table_name: ObjectName,
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -137,4 +147,37 @@ mod tests {
         let sql = "SHOW CREATE TABLE";
         ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
     }
+
+    #[test]
+    pub fn test_show_catalogs() {
+        let sql = "SHOW CATALOGS";
+        let stmts: Vec<Statement> =
+            ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        assert_matches!(&stmts[0], Statement::ShowCatalogs(ShowCatalogs));
+
+        let sql = "SHOW CATALOGS LIKE test";
+        ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+    }
+
+    #[test]
+    pub fn test_show_cardinality() {
+        let sql = "SHOW CARDINALITY FOR TABLE test_db.test";
+        let stmts: Vec<Statement> =
+            ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::ShowCardinality(show) => {
+                assert_eq!("test_db.test", show.table_name.to_string());
+            }
+            _ => {
+                unreachable!();
+            }
+        }
+
+        let sql = "SHOW CARDINALITY test";
+        ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        let sql = "SHOW CARDINALITY FOR TABLE";
+        ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+    }
 }
@@ -16,16 +16,22 @@ use datafusion_sql::parser::Statement as DfStatement;
 use sqlparser::ast::Statement as SpStatement;
 
 use crate::error::{ConvertToDfStatementSnafu, Error};
+use crate::statements::admin::Admin;
 use crate::statements::alter::AlterTable;
 use crate::statements::copy::CopyTable;
-use crate::statements::create::{CreateDatabase, CreateExternalTable, CreateTable};
+use crate::statements::create::{
+    CloneTable, CreateDatabase, CreateExternalTable, CreateFunction, CreateTable, CreateView,
+};
 use crate::statements::delete::Delete;
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
+use crate::statements::drop::{DropFunction, DropTable, DropView};
 use crate::statements::explain::Explain;
 use crate::statements::insert::Insert;
 use crate::statements::query::Query;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
+use crate::statements::set_variables::SetVariables;
+use crate::statements::show::{
+    ShowCardinality, ShowCatalogs, ShowCreateTable, ShowDatabases, ShowTables,
+};
 use crate::statements::tql::Tql;
 
 /// Tokens parsed by `DFParser` are converted into these values.
@@ -42,26 +48,44 @@ pub enum Statement {
     CreateTable(CreateTable),
     // CREATE EXTERNAL TABLE
     CreateExternalTable(CreateExternalTable),
+    /// CREATE TABLE ... CLONE
+    CloneTable(CloneTable),
     // DROP TABLE
     DropTable(DropTable),
+    /// CREATE VIEW
+    CreateView(CreateView),
+    // DROP VIEW
+    DropView(DropView),
+    /// CREATE FUNCTION
+    CreateFunction(CreateFunction),
+    // DROP FUNCTION
+    DropFunction(DropFunction),
     // CREATE DATABASE
     CreateDatabase(CreateDatabase),
     /// ALTER TABLE
     Alter(AlterTable),
+    // SHOW CATALOGS
+    ShowCatalogs(ShowCatalogs),
     // Databases.
     ShowDatabases(ShowDatabases),
     // SHOW TABLES
     ShowTables(ShowTables),
     // SHOW CREATE TABLE
     ShowCreateTable(ShowCreateTable),
+    // SHOW CARDINALITY FOR TABLE
+    ShowCardinality(ShowCardinality),
     // DESCRIBE TABLE
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
     Explain(Explain),
     Use(String),
+    // SET VARIABLE
+    SetVariables(SetVariables),
     // COPY
     Copy(CopyTable),
     Tql(Tql),
+    // ADMIN FLUSH/COMPACT/MIGRATE
+    Admin(Admin),
 }
 
 /// Comment hints from SQL.
@@ -15,12 +15,17 @@
 use criterion::criterion_main;
 
 mod memtable;
+mod read;
+mod sst;
 mod wal;
 
 criterion_main! {
     memtable::bench_memtable_read::benches,
     memtable::bench_memtable_write::benches,
     memtable::bench_memtable_read_write_ratio::benches,
+    read::bench_read_chain::benches,
+    sst::bench_sst_decode::benches,
+    sst::bench_sst_write::benches,
     wal::bench_wal::benches,
     wal::bench_decode::benches,
     wal::bench_encode::benches,
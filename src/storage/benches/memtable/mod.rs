@@ -63,7 +63,7 @@ fn random_kvs(len: usize, value_size: usize) -> (Vec<KeyTuple>, Vec<ValueTuple>)
     (keys, values)
 }
 
-fn kvs_with_index(
+pub fn kvs_with_index(
     sequence: SequenceNumber,
     op_type: OpType,
     start_index_in_batch: usize,
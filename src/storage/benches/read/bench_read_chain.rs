@@ -0,0 +1,115 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use storage::error::Result;
+use storage::memtable::{BoxedBatchIterator, IterContext, MemtableRef};
+use storage::read::{
+    Batch, BatchReader, BoxedBatchReader, ChainReader, DedupReader, MergeReaderBuilder,
+};
+use storage::schema::{ProjectedSchema, ProjectedSchemaRef};
+use store_api::storage::OpType;
+use tokio::runtime::Runtime;
+
+use crate::memtable::kvs_with_index;
+use crate::memtable::util::new_memtable;
+
+const NUM_SOURCES: usize = 8;
+const ROWS_PER_SOURCE: usize = 10000;
+const WRITE_BATCH_SIZE: usize = 100;
+
+/// Reads a memtable iterator as a [BatchReader], like what we do for SST files.
+struct IterReader(BoxedBatchIterator);
+
+#[async_trait]
+impl BatchReader for IterReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.0.next().transpose()
+    }
+}
+
+/// Creates memtables whose time ranges don't overlap, just like SSTs flushed from an
+/// append-only workload.
+fn new_disjoint_memtables() -> Vec<MemtableRef> {
+    (0..NUM_SOURCES)
+        .map(|i| {
+            let memtable = new_memtable();
+            let start = (i * ROWS_PER_SOURCE) as i64;
+            for batch_start in (0..ROWS_PER_SOURCE).step_by(WRITE_BATCH_SIZE) {
+                let keys: Vec<_> = (batch_start..batch_start + WRITE_BATCH_SIZE)
+                    .map(|offset| (start + offset as i64, 0))
+                    .collect();
+                let values: Vec<_> = keys
+                    .iter()
+                    .map(|key| (Some(key.0 as u64), "value".to_string()))
+                    .collect();
+                let kvs = kvs_with_index(i as u64, OpType::Put, batch_start, &keys, &values);
+                memtable.write(&kvs).unwrap();
+            }
+            memtable
+        })
+        .collect()
+}
+
+fn new_readers(memtables: &[MemtableRef], iter_ctx: &IterContext) -> Vec<BoxedBatchReader> {
+    memtables
+        .iter()
+        .map(|memtable| Box::new(IterReader(memtable.iter(iter_ctx).unwrap())) as BoxedBatchReader)
+        .collect()
+}
+
+async fn read_all(schema: ProjectedSchemaRef, reader: BoxedBatchReader) -> usize {
+    let mut reader = DedupReader::new(schema, reader);
+    let mut num_rows = 0;
+    while let Some(batch) = reader.next_batch().await.unwrap() {
+        num_rows += batch.num_rows();
+    }
+    num_rows
+}
+
+fn bench_read_chain(c: &mut Criterion) {
+    let memtables = new_disjoint_memtables();
+    let schema = Arc::new(ProjectedSchema::no_projection(memtables[0].schema()));
+    let iter_ctx = IterContext {
+        projected_schema: Some(schema.clone()),
+        ..Default::default()
+    };
+    let runtime = Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("read_disjoint_sources");
+    group.throughput(Throughput::Elements((NUM_SOURCES * ROWS_PER_SOURCE) as u64));
+    group.bench_function("merge", |b| {
+        b.iter(|| {
+            let reader = new_readers(&memtables, &iter_ctx).into_iter().fold(
+                MergeReaderBuilder::with_capacity(schema.clone(), NUM_SOURCES),
+                |builder, reader| builder.push_batch_reader(reader),
+            );
+            let num_rows = runtime.block_on(read_all(schema.clone(), Box::new(reader.build())));
+            assert_eq!(NUM_SOURCES * ROWS_PER_SOURCE, num_rows);
+        })
+    });
+    group.bench_function("chain", |b| {
+        b.iter(|| {
+            let reader = ChainReader::new(new_readers(&memtables, &iter_ctx));
+            let num_rows = runtime.block_on(read_all(schema.clone(), Box::new(reader)));
+            assert_eq!(NUM_SOURCES * ROWS_PER_SOURCE, num_rows);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_read_chain);
+criterion_main!(benches);
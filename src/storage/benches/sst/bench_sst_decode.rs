@@ -0,0 +1,157 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks decoding metric SSTs written with column encodings for time series against
+//! the default encodings.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use common_test_util::temp_dir::create_temp_dir;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use datatypes::prelude::ScalarVectorBuilder;
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::type_id::LogicalTypeId;
+use datatypes::vectors::{Float64VectorBuilder, TimestampMillisecondVectorBuilder};
+use object_store::services::Fs;
+use object_store::ObjectStore;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use storage::memtable::{
+    DefaultMemtableBuilder, IterContext, KeyValues, MemtableBuilder, MemtableRef,
+};
+use storage::metadata::RegionMetadata;
+use storage::sst::WriteOptions;
+use storage::{ParquetWriter, Source};
+use store_api::storage::{ColumnEncoding, ColumnEncodings, OpType};
+use tokio::runtime::Runtime;
+
+use crate::memtable::util::regiondesc_util::RegionDescBuilder;
+use crate::memtable::util::TIMESTAMP_NAME;
+
+const NUM_ROWS: usize = 100000;
+const WRITE_BATCH_SIZE: usize = 100;
+/// Interval between samples in milliseconds.
+const SCRAPE_INTERVAL: i64 = 10_000;
+const CPU_NAME: &str = "cpu";
+
+/// Returns the cpu usage sample at `i`, which changes slowly like real metrics.
+fn cpu_usage(i: usize) -> f64 {
+    let usage = 50.0 + (i as f64 / 360.0).sin() * 20.0 + (i % 7) as f64 * 0.5;
+    (usage * 10.0).round() / 10.0
+}
+
+/// Creates a memtable filled with cpu usage samples at a fixed interval.
+fn new_metric_memtable() -> MemtableRef {
+    let desc = RegionDescBuilder::new("bench_metrics")
+        .enable_version_column(false)
+        .push_field_column((CPU_NAME, LogicalTypeId::Float64, true))
+        .build();
+    let metadata: RegionMetadata = desc.try_into().unwrap();
+    let memtable = DefaultMemtableBuilder::default().build(metadata.schema().clone());
+
+    for batch_start in (0..NUM_ROWS).step_by(WRITE_BATCH_SIZE) {
+        let mut timestamps = TimestampMillisecondVectorBuilder::with_capacity(WRITE_BATCH_SIZE);
+        let mut cpus = Float64VectorBuilder::with_capacity(WRITE_BATCH_SIZE);
+        for i in batch_start..batch_start + WRITE_BATCH_SIZE {
+            timestamps.push(Some(TimestampMillisecond::from(i as i64 * SCRAPE_INTERVAL)));
+            cpus.push(Some(cpu_usage(i)));
+        }
+        let kvs = KeyValues {
+            sequence: 0,
+            op_type: OpType::Put,
+            start_index_in_batch: batch_start,
+            keys: vec![Arc::new(timestamps.finish()) as _],
+            values: vec![Arc::new(cpus.finish()) as _],
+        };
+        memtable.write(&kvs).unwrap();
+    }
+    memtable
+}
+
+fn new_object_store(root: &str) -> ObjectStore {
+    let mut builder = Fs::default();
+    builder.root(root);
+    ObjectStore::new(builder).unwrap().finish()
+}
+
+/// Writes the memtable to a SST file and returns the content of the file.
+async fn write_sst(
+    memtable: &MemtableRef,
+    object_store: ObjectStore,
+    file_name: &str,
+    column_encodings: ColumnEncodings,
+) -> Bytes {
+    let iter = memtable.iter(&IterContext::default()).unwrap();
+    let opts = WriteOptions {
+        column_encodings,
+        ..Default::default()
+    };
+    ParquetWriter::new(file_name, Source::Iter(iter), object_store.clone())
+        .write_sst(&opts)
+        .await
+        .unwrap()
+        .unwrap();
+    Bytes::from(object_store.read(file_name).await.unwrap())
+}
+
+/// Decodes all rows of the SST file with `content`.
+fn decode_sst(content: Bytes) -> usize {
+    ParquetRecordBatchReaderBuilder::try_new(content)
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(|batch| batch.unwrap().num_rows())
+        .sum()
+}
+
+#[allow(clippy::print_stdout)]
+fn bench_sst_decode(c: &mut Criterion) {
+    let memtable = new_metric_memtable();
+    let dir = create_temp_dir("bench_sst_decode");
+    let object_store = new_object_store(dir.path().to_str().unwrap());
+    let runtime = Runtime::new().unwrap();
+
+    let time_series_encodings = ColumnEncodings::from([
+        (TIMESTAMP_NAME.to_string(), ColumnEncoding::Delta),
+        (CPU_NAME.to_string(), ColumnEncoding::ByteStreamSplit),
+    ]);
+    let plain = runtime.block_on(write_sst(
+        &memtable,
+        object_store.clone(),
+        "plain.parquet",
+        ColumnEncodings::new(),
+    ));
+    let time_series = runtime.block_on(write_sst(
+        &memtable,
+        object_store,
+        "time_series.parquet",
+        time_series_encodings,
+    ));
+    println!(
+        "write {NUM_ROWS} cpu samples, plain sst size: {}, time series sst size: {}",
+        plain.len(),
+        time_series.len()
+    );
+
+    let mut group = c.benchmark_group("decode_metric_sst");
+    group.throughput(Throughput::Elements(NUM_ROWS as u64));
+    group.bench_function("plain", |b| b.iter(|| decode_sst(plain.clone())));
+    group.bench_function("time_series", |b| {
+        b.iter(|| decode_sst(time_series.clone()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_sst_decode);
+criterion_main!(benches);
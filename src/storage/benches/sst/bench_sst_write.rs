@@ -0,0 +1,124 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_test_util::temp_dir::create_temp_dir;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use object_store::services::Fs;
+use object_store::ObjectStore;
+use storage::memtable::{IterContext, MemtableRef};
+use storage::sst::WriteOptions;
+use storage::{ParquetWriter, Source};
+use store_api::storage::OpType;
+use tokio::runtime::Runtime;
+
+use crate::memtable::kvs_with_index;
+use crate::memtable::util::new_memtable;
+
+const NUM_ROWS: usize = 100000;
+const WRITE_BATCH_SIZE: usize = 100;
+
+const LEVELS: [&str; 4] = ["INFO", "WARN", "ERROR", "DEBUG"];
+const PATHS: [&str; 5] = [
+    "/v1/sql",
+    "/v1/promql",
+    "/v1/influxdb/write",
+    "/v1/prometheus/write",
+    "/health",
+];
+
+/// Generates an access log line, lines share lots of common substrings like real logs.
+fn log_line(i: usize) -> String {
+    format!(
+        "2023-05-10T08:{:02}:{:02}.{:03}Z {} [http] method=POST path={} status={} latency={}ms client=10.0.{}.{}",
+        (i / 60000) % 60,
+        (i / 1000) % 60,
+        i % 1000,
+        LEVELS[i % LEVELS.len()],
+        PATHS[i % PATHS.len()],
+        if i % 17 == 0 { 500 } else { 200 },
+        i % 97,
+        i % 8,
+        i % 251,
+    )
+}
+
+/// Creates a memtable filled with log lines, using an increasing timestamp as the key.
+fn new_log_memtable() -> MemtableRef {
+    let memtable = new_memtable();
+    for batch_start in (0..NUM_ROWS).step_by(WRITE_BATCH_SIZE) {
+        let keys: Vec<_> = (batch_start..batch_start + WRITE_BATCH_SIZE)
+            .map(|i| (i as i64, 0))
+            .collect();
+        let values: Vec<_> = (batch_start..batch_start + WRITE_BATCH_SIZE)
+            .map(|i| (Some(i as u64), log_line(i)))
+            .collect();
+        let kvs = kvs_with_index(0, OpType::Put, batch_start, &keys, &values);
+        memtable.write(&kvs).unwrap();
+    }
+    memtable
+}
+
+fn new_object_store(root: &str) -> ObjectStore {
+    let mut builder = Fs::default();
+    builder.root(root);
+    ObjectStore::new(builder).unwrap().finish()
+}
+
+async fn write_sst(
+    memtable: &MemtableRef,
+    object_store: ObjectStore,
+    compact_strings: bool,
+) -> u64 {
+    let iter = memtable.iter(&IterContext::default()).unwrap();
+    let opts = WriteOptions {
+        compact_strings,
+        ..Default::default()
+    };
+    ParquetWriter::new("bench.parquet", Source::Iter(iter), object_store)
+        .write_sst(&opts)
+        .await
+        .unwrap()
+        .unwrap()
+        .file_size
+}
+
+#[allow(clippy::print_stdout)]
+fn bench_sst_write(c: &mut Criterion) {
+    let memtable = new_log_memtable();
+    let dir = create_temp_dir("bench_sst_write");
+    let object_store = new_object_store(dir.path().to_str().unwrap());
+    let runtime = Runtime::new().unwrap();
+
+    // Compares the size of SSTs, which matters more than the write throughput.
+    for compact_strings in [false, true] {
+        let file_size =
+            runtime.block_on(write_sst(&memtable, object_store.clone(), compact_strings));
+        println!(
+            "write {NUM_ROWS} log lines, compact_strings: {compact_strings}, sst size: {file_size}"
+        );
+    }
+
+    let mut group = c.benchmark_group("write_log_sst");
+    group.throughput(Throughput::Elements(NUM_ROWS as u64));
+    group.bench_function("plain", |b| {
+        b.iter(|| runtime.block_on(write_sst(&memtable, object_store.clone(), false)))
+    });
+    group.bench_function("compact_strings", |b| {
+        b.iter(|| runtime.block_on(write_sst(&memtable, object_store.clone(), true)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_sst_write);
+criterion_main!(benches);
@@ -18,10 +18,12 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use common_runtime::{self, JoinHandle};
+use common_runtime::{self, JoinHandle, Runtime};
+use metrics::{decrement_gauge, increment_gauge};
 use snafu::ResultExt;
 
 use crate::error::{self, Result};
+use crate::metrics::FLUSH_QUEUE_DEPTH;
 
 /// Background job context.
 #[derive(Clone, Debug, Default)]
@@ -98,8 +100,17 @@ pub trait JobPool: Send + Sync + std::fmt::Debug {
 
 pub type JobPoolRef = Arc<dyn JobPool>;
 
-#[derive(Debug)]
-pub struct JobPoolImpl {}
+#[derive(Debug, Default)]
+pub struct JobPoolImpl {
+    /// Runtime dedicated to the jobs, jobs run in the global background runtime if `None`.
+    runtime: Option<Runtime>,
+}
+
+impl JobPoolImpl {
+    pub fn new(runtime: Option<Runtime>) -> JobPoolImpl {
+        JobPoolImpl { runtime }
+    }
+}
 
 #[async_trait]
 impl JobPool for JobPoolImpl {
@@ -108,7 +119,16 @@ impl JobPool for JobPoolImpl {
 
         let ctx = Context::new();
         let job_ctx = ctx.clone();
-        let handle = common_runtime::spawn_bg(async move { job.run(&job_ctx).await });
+        increment_gauge!(FLUSH_QUEUE_DEPTH, 1.0);
+        let job = async move {
+            let result = job.run(&job_ctx).await;
+            decrement_gauge!(FLUSH_QUEUE_DEPTH, 1.0);
+            result
+        };
+        let handle = match &self.runtime {
+            Some(runtime) => runtime.spawn(job),
+            None => common_runtime::spawn_bg(job),
+        };
 
         Ok(JobHandle { ctx, handle })
     }
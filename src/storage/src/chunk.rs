@@ -18,15 +18,19 @@ use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_telemetry::debug;
 use common_time::range::TimestampRange;
+use futures::{StreamExt, TryFutureExt, TryStreamExt};
 use snafu::ResultExt;
 use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber};
 use table::predicate::{Predicate, TimeRangePredicateBuilder};
 
 use crate::error::{self, Error, Result};
 use crate::memtable::{IterContext, MemtableRef};
-use crate::read::{Batch, BoxedBatchReader, DedupReader, MergeReaderBuilder};
+use crate::read::{
+    Batch, BoxedBatchReader, ChainReader, DedupReader, IterReader, MergeReaderBuilder,
+    TombstoneReader, VisibleReader,
+};
 use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
-use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions};
+use crate::sst::{self, AccessLayerRef, FileHandle, LevelMetas, RangeTombstone, ReadOptions};
 
 /// Chunk reader implementation.
 // Now we use async-trait to implement the chunk reader, which is easier to implement than
@@ -84,6 +88,11 @@ pub struct ChunkReaderBuilder {
     iter_ctx: IterContext,
     memtables: Vec<MemtableRef>,
     files_to_read: Vec<FileHandle>,
+    sample_percent: Option<f64>,
+    filter_sst_sequence: bool,
+    tombstones: Vec<RangeTombstone>,
+    append_mode: bool,
+    sorted: bool,
 }
 
 impl ChunkReaderBuilder {
@@ -96,6 +105,11 @@ impl ChunkReaderBuilder {
             iter_ctx: IterContext::default(),
             memtables: Vec::new(),
             files_to_read: Vec::new(),
+            sample_percent: None,
+            filter_sst_sequence: false,
+            tombstones: Vec::new(),
+            append_mode: false,
+            sorted: false,
         }
     }
 
@@ -125,6 +139,44 @@ impl ChunkReaderBuilder {
         self
     }
 
+    /// Only reads about `sample_percent` percent of memtables and SST row groups.
+    pub fn sample_percent(mut self, sample_percent: Option<f64>) -> Self {
+        self.sample_percent = sample_percent;
+        self
+    }
+
+    /// Whether to filter out rows in SSTs that are invisible to the visible sequence.
+    ///
+    /// Memtables always skip invisible rows, but SSTs only need filtering if they may
+    /// contain rows newer than the visible sequence.
+    pub fn filter_sst_sequence(mut self, filter_sst_sequence: bool) -> Self {
+        self.filter_sst_sequence = filter_sst_sequence;
+        self
+    }
+
+    /// Whether the region to read is in append mode. Rows of such regions are never
+    /// deduplicated, so the sources are read one by one instead of being merged.
+    pub fn append_mode(mut self, append_mode: bool) -> Self {
+        self.append_mode = append_mode;
+        self
+    }
+
+    /// Whether the rows must be sorted by row keys, e.g. when they are written to new SSTs
+    /// by compaction. The sources are always merged then.
+    pub fn sorted(mut self, sorted: bool) -> Self {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Range tombstones masking rows of SSTs to read.
+    ///
+    /// Memtables only contain rows written after these tombstones, so we only apply
+    /// them to SSTs.
+    pub fn tombstones(mut self, tombstones: &[RangeTombstone]) -> Self {
+        self.tombstones = tombstones.to_vec();
+        self
+    }
+
     pub fn pick_memtables(mut self, memtables: MemtableRef) -> Self {
         self.memtables.push(memtables);
         self
@@ -158,6 +210,7 @@ impl ChunkReaderBuilder {
             time_range_predicate
         );
 
+        let num_row_key_columns = self.schema.num_row_key_columns();
         let schema = Arc::new(
             ProjectedSchema::new(self.schema, self.projection)
                 .context(error::InvalidProjectionSnafu)?,
@@ -168,9 +221,21 @@ impl ChunkReaderBuilder {
             .batch_size(self.iter_ctx.batch_size);
 
         self.iter_ctx.projected_schema = Some(schema.clone());
+        self.iter_ctx.dedup = !self.append_mode;
+        let chain_memtables = self.append_mode && !self.sorted;
+        let mut has_memtable_source = false;
+        let mut memtable_readers = Vec::new();
         for mem in self.memtables {
+            if mem.num_rows() == 0 || !sst::sampled(self.sample_percent) {
+                continue;
+            }
             let iter = mem.iter(&self.iter_ctx)?;
-            reader_builder = reader_builder.push_batch_iter(iter);
+            if chain_memtables {
+                memtable_readers.push(Box::new(IterReader::new(iter)) as BoxedBatchReader);
+            } else {
+                reader_builder = reader_builder.push_batch_iter(iter);
+            }
+            has_memtable_source = true;
         }
 
         let read_opts = ReadOptions {
@@ -178,21 +243,83 @@ impl ChunkReaderBuilder {
             projected_schema: schema.clone(),
             predicate: Predicate::new(self.filters),
             time_range: time_range_predicate,
+            sample_percent: self.sample_percent,
         };
-        for file in &self.files_to_read {
-            if !Self::file_in_range(file, time_range_predicate) {
-                debug!(
-                    "Skip file {:?}, predicate: {:?}",
-                    file, time_range_predicate
-                );
-                continue;
+        let mut files: Vec<_> = self
+            .files_to_read
+            .iter()
+            .filter(|file| {
+                let in_range = Self::file_in_range(file, time_range_predicate);
+                if !in_range {
+                    debug!(
+                        "Skip file {:?}, predicate: {:?}",
+                        file, time_range_predicate
+                    );
+                }
+                in_range
+            })
+            .collect();
+        // If the timestamp is the only row key column, the rows of files whose time ranges
+        // don't overlap are already sorted after sorting these files, so we could read them
+        // one by one instead of merging them. Otherwise their keys may interleave. Regions in
+        // append mode don't need merging at all as their rows are never deduplicated, unless
+        // the rows must be sorted.
+        let chain_files = !self.sorted
+            && (self.append_mode
+                || (!has_memtable_source
+                    && num_row_key_columns == 1
+                    && Self::sort_disjoint_files(&mut files)));
+        let num_files = files.len();
+        // Opens the next files while waiting for the current one if prefetch is enabled.
+        let sst_layer = &self.sst_layer;
+        let read_opts = &read_opts;
+        let visible_sequence = self
+            .filter_sst_sequence
+            .then_some(self.iter_ctx.visible_sequence);
+        let tombstones = &self.tombstones;
+        let mut readers = futures::stream::iter(files)
+            .map(|file| {
+                let masked_by: Arc<[RangeTombstone]> = tombstones
+                    .iter()
+                    .filter(|tombstone| tombstone.overlaps(file.time_range()))
+                    .copied()
+                    .collect();
+                sst_layer
+                    .read_sst(file.clone(), read_opts)
+                    .map_ok(move |reader| (reader, masked_by))
+            })
+            .buffered(sst_layer.prefetch_depth().max(1))
+            .map_ok(|(reader, masked_by)| {
+                let reader = if masked_by.is_empty() {
+                    reader
+                } else {
+                    Box::new(TombstoneReader::new(schema.clone(), reader, masked_by)) as _
+                };
+                match visible_sequence {
+                    Some(sequence) => {
+                        Box::new(VisibleReader::new(schema.clone(), reader, sequence)) as _
+                    }
+                    None => reader,
+                }
+            });
+        let reader: BoxedBatchReader = if chain_files {
+            debug!(
+                "Chain {} memtables and {} files without merging",
+                memtable_readers.len(),
+                num_files
+            );
+            memtable_readers.extend(readers.try_collect::<Vec<_>>().await?);
+            Box::new(ChainReader::new(memtable_readers))
+        } else {
+            while let Some(reader) = readers.try_next().await? {
+                reader_builder = reader_builder.push_batch_reader(reader);
             }
-            let reader = self.sst_layer.read_sst(file.clone(), &read_opts).await?;
-
-            reader_builder = reader_builder.push_batch_reader(reader);
+            Box::new(reader_builder.build())
+        };
+        if self.append_mode {
+            return Ok(ChunkReaderImpl::new(schema, reader));
         }
-
-        let reader = reader_builder.build();
+        // We still need to dedup rows inside each file and filter deleted rows.
         let reader = DedupReader::new(schema.clone(), reader);
 
         Ok(ChunkReaderImpl::new(schema, Box::new(reader)))
@@ -201,7 +328,7 @@ impl ChunkReaderBuilder {
     /// Build time range predicate from schema and filters.
     pub fn build_time_range_predicate(&self) -> TimestampRange {
         let Some(ts_col) = self.schema.user_schema().timestamp_column() else { return TimestampRange::min_to_max() };
-        TimeRangePredicateBuilder::new(&ts_col.name, &self.filters).build()
+        TimeRangePredicateBuilder::new(ts_col, &self.filters).build()
     }
 
     /// Check if SST file's time range matches predicate.
@@ -215,4 +342,127 @@ impl ChunkReaderBuilder {
         let file_ts_range = TimestampRange::new_inclusive(Some(start), Some(end));
         file_ts_range.intersects(&predicate)
     }
+
+    /// Sorts `files` by their start timestamps, returns true if all files have time ranges
+    /// and these ranges don't overlap with each other.
+    fn sort_disjoint_files(files: &mut [&FileHandle]) -> bool {
+        if files.iter().any(|file| file.time_range().is_none()) {
+            return false;
+        }
+        files.sort_unstable_by_key(|file| (*file.time_range()).map(|(start, _)| start));
+        // end_timestamp of sst file is inclusive.
+        files.windows(2).all(
+            |pair| match (*pair[0].time_range(), *pair[1].time_range()) {
+                (Some((_, prev_end)), Some((next_start, _))) => prev_end < next_start,
+                _ => false,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::logical_plan::Expr;
+    use common_time::Timestamp;
+    use datafusion_common::ScalarValue;
+    use datafusion_expr::expr_fn::{binary_expr, cast, col};
+    use datafusion_expr::{lit, Operator};
+    use datatypes::arrow::datatypes::{DataType, TimeUnit};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+    use table::predicate::TimeRangePredicateBuilder;
+
+    use super::*;
+    use crate::file_purger::noop::new_noop_file_purger;
+    use crate::sst::{FileId, FileMeta, StorageTier};
+    use crate::test_util::access_layer_util::MockAccessLayer;
+
+    fn new_file_handle(time_range: Option<(i64, i64)>) -> FileHandle {
+        FileHandle::new(
+            FileMeta {
+                region_id: 0,
+                file_id: FileId::random(),
+                time_range: time_range.map(|(start, end)| {
+                    (
+                        Timestamp::new_millisecond(start),
+                        Timestamp::new_millisecond(end),
+                    )
+                }),
+                level: 0,
+                file_size: 0,
+                checksums: None,
+                tier: StorageTier::Hot,
+                source_dir: None,
+            },
+            Arc::new(MockAccessLayer {}),
+            new_noop_file_purger(),
+        )
+    }
+
+    fn check_sort_disjoint_files(ranges: &[Option<(i64, i64)>], expect: Option<&[i64]>) {
+        let handles: Vec<_> = ranges.iter().map(|r| new_file_handle(*r)).collect();
+        let mut files: Vec<_> = handles.iter().collect();
+        let disjoint = ChunkReaderBuilder::sort_disjoint_files(&mut files);
+        assert_eq!(expect.is_some(), disjoint);
+        if let Some(expect) = expect {
+            let starts: Vec<_> = files
+                .iter()
+                .map(|file| file.time_range().unwrap().0.value())
+                .collect();
+            assert_eq!(expect, &starts[..]);
+        }
+    }
+
+    #[test]
+    fn test_sort_disjoint_files() {
+        check_sort_disjoint_files(&[], Some(&[]));
+        check_sort_disjoint_files(&[Some((0, 9))], Some(&[0]));
+        check_sort_disjoint_files(
+            &[Some((20, 29)), Some((0, 9)), Some((10, 19))],
+            Some(&[0, 10, 20]),
+        );
+        // End timestamp is inclusive.
+        check_sort_disjoint_files(&[Some((0, 10)), Some((10, 19))], None);
+        check_sort_disjoint_files(&[Some((0, 20)), Some((10, 19))], None);
+        check_sort_disjoint_files(&[Some((0, 9)), None], None);
+    }
+
+    #[test]
+    fn test_file_in_casted_time_range() {
+        let ts_col = ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        );
+        let check = |filter: Expr, time_range: (i64, i64), expect: bool| {
+            let filters = [filter];
+            let predicate = TimeRangePredicateBuilder::new(&ts_col, &filters).build();
+            let file = new_file_handle(Some(time_range));
+            assert_eq!(
+                expect,
+                ChunkReaderBuilder::file_in_range(&file, predicate),
+                "{:?}",
+                filters[0].df_expr()
+            );
+        };
+
+        // CAST(ts AS TIMESTAMP(0)) <= 1s holds for the rows before 2s, the cast truncates
+        // the milliseconds, so the file can't be pruned by `ts <= 1s`.
+        let casted = |unit| cast(col("ts"), DataType::Timestamp(unit, None));
+        let filter = binary_expr(
+            casted(TimeUnit::Second),
+            Operator::LtEq,
+            lit(ScalarValue::TimestampSecond(Some(1), None)),
+        );
+        check(filter.into(), (1500, 1900), true);
+
+        // The cast to a finer unit still bounds the time index.
+        let filter = binary_expr(
+            casted(TimeUnit::Nanosecond),
+            Operator::GtEq,
+            lit(ScalarValue::TimestampNanosecond(Some(1_000_000_000), None)),
+        );
+        check(filter.clone().into(), (0, 999), false);
+        check(filter.into(), (500, 1000), true);
+    }
 }
@@ -16,7 +16,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use common_base::readable_size::ReadableSize;
+use common_runtime::Runtime;
 use common_telemetry::{debug, error, info};
+use metrics::{decrement_gauge, increment_gauge};
 use store_api::logstore::LogStore;
 use store_api::storage::RegionId;
 use tokio::sync::oneshot::Sender;
@@ -26,6 +28,7 @@ use crate::compaction::picker::Picker;
 use crate::compaction::task::CompactionTask;
 use crate::error::Result;
 use crate::manifest::region::RegionManifest;
+use crate::metrics::COMPACTION_QUEUE_DEPTH;
 use crate::region::{RegionWriterRef, SharedDataRef};
 use crate::scheduler::rate_limit::BoxedRateLimitToken;
 use crate::scheduler::{Handler, Request};
@@ -80,11 +83,23 @@ impl<S: LogStore> CompactionRequestImpl<S> {
 
 pub struct CompactionHandler<P> {
     pub picker: P,
+    /// Runtime dedicated to compaction tasks, tasks run in the global background
+    /// runtime if `None`.
+    runtime: Option<Runtime>,
 }
 
 impl<P> CompactionHandler<P> {
     pub fn new(picker: P) -> Self {
-        Self { picker }
+        Self {
+            picker,
+            runtime: None,
+        }
+    }
+
+    /// Runs compaction tasks in the dedicated `runtime`.
+    pub fn with_runtime(mut self, runtime: Runtime) -> Self {
+        self.runtime = Some(runtime);
+        self
     }
 }
 
@@ -110,7 +125,8 @@ where
 
         debug!("Compaction task, region: {:?}, task: {:?}", region_id, task);
         // TODO(hl): we need to keep a track of task handle here to allow task cancellation.
-        common_runtime::spawn_bg(async move {
+        increment_gauge!(COMPACTION_QUEUE_DEPTH, 1.0);
+        let compaction = async move {
             if let Err(e) = task.run().await {
                 // TODO(hl): maybe resubmit compaction task on failure?
                 error!(e; "Failed to compact region: {:?}", region_id);
@@ -125,7 +141,12 @@ where
             token.try_release();
             // notify scheduler to schedule next task when current task finishes.
             finish_notifier.notify_one();
-        });
+            decrement_gauge!(COMPACTION_QUEUE_DEPTH, 1.0);
+        };
+        let _ = match &self.runtime {
+            Some(runtime) => runtime.spawn(compaction),
+            None => common_runtime::spawn_bg(compaction),
+        };
 
         Ok(())
     }
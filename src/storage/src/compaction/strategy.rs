@@ -175,7 +175,7 @@ mod tests {
 
     use super::*;
     use crate::file_purger::noop::new_noop_file_purger;
-    use crate::sst::{FileId, FileMeta};
+    use crate::sst::{FileId, FileMeta, StorageTier};
 
     #[test]
     fn test_time_bucket_span() {
@@ -246,6 +246,9 @@ mod tests {
                 )),
                 level: 0,
                 file_size: 0,
+                checksums: None,
+                tier: StorageTier::Hot,
+                source_dir: None,
             },
             layer,
             file_purger,
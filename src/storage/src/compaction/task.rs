@@ -14,20 +14,22 @@
 
 use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 
 use common_base::readable_size::ReadableSize;
 use common_telemetry::{debug, error};
+use common_time::Timestamp;
 use store_api::logstore::LogStore;
-use store_api::storage::RegionId;
 
 use crate::compaction::writer::build_sst_reader;
 use crate::error::Result;
 use crate::manifest::action::RegionEdit;
 use crate::manifest::region::RegionManifest;
-use crate::region::{RegionWriterRef, SharedDataRef};
+use crate::region::{RegionWriterRef, SharedData, SharedDataRef};
 use crate::schema::RegionSchemaRef;
 use crate::sst::{
-    AccessLayerRef, FileHandle, FileId, FileMeta, Level, Source, SstInfo, WriteOptions,
+    AccessLayerRef, FileHandle, FileId, FileMeta, Level, RangeTombstone, Source, SstInfo,
+    WriteOptions,
 };
 use crate::wal::Wal;
 
@@ -70,17 +72,31 @@ impl<S: LogStore> CompactionTaskImpl<S> {
     async fn merge_ssts(&mut self) -> Result<(HashSet<FileMeta>, HashSet<FileMeta>)> {
         let mut futs = Vec::with_capacity(self.outputs.len());
         let mut compacted_inputs = HashSet::new();
-        let region_id = self.shared_data.id();
+        // Rows masked by tombstones are removed from the outputs.
+        let tombstones: Arc<[RangeTombstone]> = self
+            .shared_data
+            .version_control
+            .current()
+            .tombstones()
+            .into();
         for output in self.outputs.drain(..) {
+            let shared_data = self.shared_data.clone();
             let schema = self.schema.clone();
             let sst_layer = self.sst_layer.clone();
             let sst_write_buffer_size = self.sst_write_buffer_size;
+            let tombstones = tombstones.clone();
             compacted_inputs.extend(output.inputs.iter().map(FileHandle::meta));
 
             // TODO(hl): Maybe spawn to runtime to exploit in-job parallelism.
             futs.push(async move {
                 output
-                    .build(region_id, schema, sst_layer, sst_write_buffer_size)
+                    .build(
+                        &shared_data,
+                        schema,
+                        sst_layer,
+                        sst_write_buffer_size,
+                        &tombstones,
+                    )
                     .await
             });
         }
@@ -116,6 +132,7 @@ impl<S: LogStore> CompactionTaskImpl<S> {
             flushed_sequence: None,
             files_to_add: Vec::from_iter(output.into_iter()),
             files_to_remove: Vec::from_iter(input.into_iter()),
+            tombstones_to_add: Vec::new(),
         };
         debug!(
             "Compacted region: {}, region edit: {:?}",
@@ -173,23 +190,35 @@ pub struct CompactionOutput {
 impl CompactionOutput {
     async fn build(
         &self,
-        region_id: RegionId,
+        shared_data: &SharedData,
         schema: RegionSchemaRef,
         sst_layer: AccessLayerRef,
         sst_write_buffer_size: ReadableSize,
+        tombstones: &[RangeTombstone],
     ) -> Result<Option<FileMeta>> {
+        let region_id = shared_data.id();
         let reader = build_sst_reader(
             schema,
             sst_layer.clone(),
             &self.inputs,
             self.bucket_bound,
             self.bucket_bound + self.bucket,
+            tombstones,
+            shared_data.append_mode(),
         )
         .await?;
 
         let output_file_id = FileId::random();
+        // All rows of the output are before the right bound of the time bucket, so
+        // the tiering policy of the access layer decides where to put it.
+        let tier = sst_layer.storage_tier(Timestamp::new_second(self.bucket_bound + self.bucket));
         let opts = WriteOptions {
             sst_write_buffer_size,
+            tier,
+            // Only export compaction outputs as they don't overlap with each other.
+            export: true,
+            compact_strings: shared_data.compact_strings(),
+            column_encodings: shared_data.column_encodings().clone(),
         };
 
         Ok(sst_layer
@@ -199,6 +228,7 @@ impl CompactionOutput {
                 |SstInfo {
                      time_range,
                      file_size,
+                     checksums,
                      ..
                  }| FileMeta {
                     region_id,
@@ -206,6 +236,9 @@ impl CompactionOutput {
                     time_range,
                     level: self.output_level,
                     file_size,
+                    checksums,
+                    tier,
+                    source_dir: None,
                 },
             ))
     }
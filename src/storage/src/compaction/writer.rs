@@ -19,15 +19,18 @@ use datafusion_expr::{BinaryExpr, Operator};
 use crate::chunk::{ChunkReaderBuilder, ChunkReaderImpl};
 use crate::error;
 use crate::schema::RegionSchemaRef;
-use crate::sst::{AccessLayerRef, FileHandle};
+use crate::sst::{AccessLayerRef, FileHandle, RangeTombstone};
 
-/// Builds an SST reader that only reads rows within given time range.
+/// Builds an SST reader that only reads rows within given time range and not masked
+/// by `tombstones`.
 pub(crate) async fn build_sst_reader(
     schema: RegionSchemaRef,
     sst_layer: AccessLayerRef,
     files: &[FileHandle],
     lower_sec_inclusive: i64,
     upper_sec_exclusive: i64,
+    tombstones: &[RangeTombstone],
+    append_mode: bool,
 ) -> error::Result<ChunkReaderImpl> {
     // TODO(hl): Schemas in different SSTs may differ, thus we should infer
     // timestamp column name from Parquet metadata.
@@ -45,6 +48,10 @@ pub(crate) async fn build_sst_reader(
             upper_sec_exclusive,
             &ts_col_name,
         )])
+        .tombstones(tombstones)
+        .append_mode(append_mode)
+        // Rows written to the new SSTs must be sorted.
+        .sorted(true)
         .build()
         .await
 }
@@ -91,11 +98,12 @@ mod tests {
     use datatypes::prelude::{LogicalTypeId, ScalarVector, ScalarVectorBuilder};
     use datatypes::timestamp::TimestampMillisecond;
     use datatypes::vectors::{
-        TimestampMillisecondVector, TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+        Int64Vector, TimestampMillisecondVector, TimestampMillisecondVectorBuilder, UInt64Vector,
+        UInt64VectorBuilder,
     };
     use object_store::services::Fs;
     use object_store::ObjectStore;
-    use store_api::storage::{ChunkReader, OpType, SequenceNumber};
+    use store_api::storage::{ChunkReader, ColumnEncodings, OpType, SequenceNumber};
 
     use super::*;
     use crate::file_purger::noop::new_noop_file_purger;
@@ -104,7 +112,9 @@ mod tests {
     };
     use crate::metadata::RegionMetadata;
     use crate::sst::parquet::ParquetWriter;
-    use crate::sst::{self, FileId, FileMeta, FsAccessLayer, Source, SstInfo, WriteOptions};
+    use crate::sst::{
+        self, FileId, FileMeta, FsAccessLayer, Source, SstInfo, StorageTier, WriteOptions,
+    };
     use crate::test_util::descriptor_util::RegionDescBuilder;
 
     fn schema_for_test() -> RegionSchemaRef {
@@ -238,6 +248,9 @@ mod tests {
                 time_range,
                 level: 0,
                 file_size,
+                checksums: None,
+                tier: StorageTier::Hot,
+                source_dir: None,
             },
             Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
             new_noop_file_purger(),
@@ -260,6 +273,8 @@ mod tests {
             files,
             lower_sec_inclusive,
             upper_sec_exclusive,
+            &[],
+            false,
         )
         .await
         .unwrap();
@@ -339,7 +354,7 @@ mod tests {
         sst_layer: AccessLayerRef,
     ) -> Vec<i64> {
         let mut timestamps = vec![];
-        let mut reader = build_sst_reader(schema, sst_layer, files, i64::MIN, i64::MAX)
+        let mut reader = build_sst_reader(schema, sst_layer, files, i64::MIN, i64::MAX, &[], false)
             .await
             .unwrap();
         while let Some(chunk) = reader.next_chunk().await.unwrap() {
@@ -403,18 +418,46 @@ mod tests {
         let sst_layer = Arc::new(FsAccessLayer::new("./", object_store.clone()));
         let input_files = vec![file2, file1];
 
-        let reader1 = build_sst_reader(schema.clone(), sst_layer.clone(), &input_files, 0, 3)
-            .await
-            .unwrap();
-        let reader2 = build_sst_reader(schema.clone(), sst_layer.clone(), &input_files, 3, 6)
-            .await
-            .unwrap();
-        let reader3 = build_sst_reader(schema.clone(), sst_layer.clone(), &input_files, 6, 10)
-            .await
-            .unwrap();
+        let reader1 = build_sst_reader(
+            schema.clone(),
+            sst_layer.clone(),
+            &input_files,
+            0,
+            3,
+            &[],
+            false,
+        )
+        .await
+        .unwrap();
+        let reader2 = build_sst_reader(
+            schema.clone(),
+            sst_layer.clone(),
+            &input_files,
+            3,
+            6,
+            &[],
+            false,
+        )
+        .await
+        .unwrap();
+        let reader3 = build_sst_reader(
+            schema.clone(),
+            sst_layer.clone(),
+            &input_files,
+            6,
+            10,
+            &[],
+            false,
+        )
+        .await
+        .unwrap();
 
         let opts = WriteOptions {
             sst_write_buffer_size: ReadableSize::mb(8),
+            tier: StorageTier::Hot,
+            export: false,
+            compact_strings: false,
+            column_encodings: ColumnEncodings::new(),
         };
         let s1 = ParquetWriter::new(
             &output_file_ids[0].as_parquet(),
@@ -478,6 +521,9 @@ mod tests {
                         level: 1,
                         time_range: None,
                         file_size: 0,
+                        checksums: None,
+                        tier: StorageTier::Hot,
+                        source_dir: None,
                     },
                     Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
                     new_noop_file_purger(),
@@ -491,4 +537,128 @@ mod tests {
 
         assert_eq!(timestamps_in_outputs, timestamps_in_inputs);
     }
+
+    /// Writes `(key, timestamp)` rows into an SST of the schema with key column `k`.
+    async fn write_keyed_sst(
+        schema: RegionSchemaRef,
+        object_store: ObjectStore,
+        rows: &[(i64, i64)],
+    ) -> FileHandle {
+        let memtable = DefaultMemtableBuilder::default().build(schema);
+        let keys = Int64Vector::from_values(rows.iter().map(|(k, _)| *k));
+        let ts = TimestampMillisecondVector::from_values(rows.iter().map(|(_, ts)| *ts));
+        let values = UInt64Vector::from_values(rows.iter().map(|(_, ts)| *ts as u64));
+        let kvs = KeyValues {
+            sequence: 0,
+            op_type: OpType::Put,
+            start_index_in_batch: 0,
+            keys: vec![Arc::new(keys) as _, Arc::new(ts) as _],
+            values: vec![Arc::new(values) as _],
+        };
+        memtable.write(&kvs).unwrap();
+
+        let file_id = FileId::random();
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let writer = ParquetWriter::new(&file_id.as_parquet(), Source::Iter(iter), object_store);
+        let SstInfo {
+            time_range,
+            file_size,
+            ..
+        } = writer
+            .write_sst(&sst::WriteOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        FileHandle::new(
+            FileMeta {
+                region_id: 0,
+                file_id,
+                time_range,
+                level: 0,
+                file_size,
+                checksums: None,
+                tier: StorageTier::Hot,
+                source_dir: None,
+            },
+            Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
+            new_noop_file_purger(),
+        )
+    }
+
+    async fn read_keys(mut reader: ChunkReaderImpl) -> Vec<(i64, i64)> {
+        let mut rows = vec![];
+        while let Some(chunk) = reader.next_chunk().await.unwrap() {
+            let keys = chunk.columns[0]
+                .as_any()
+                .downcast_ref::<Int64Vector>()
+                .unwrap();
+            let ts = chunk.columns[1]
+                .as_any()
+                .downcast_ref::<TimestampMillisecondVector>()
+                .unwrap();
+            rows.extend(
+                keys.iter_data()
+                    .zip(ts.iter_data())
+                    .map(|(k, ts)| (k.unwrap(), ts.unwrap().0.value())),
+            );
+        }
+        rows
+    }
+
+    #[tokio::test]
+    async fn test_read_time_disjoint_ssts_with_interleaved_keys() {
+        let dir = create_temp_dir("read_interleaved_keys");
+        let path = dir.path().to_str().unwrap();
+        let mut builder = Fs::default();
+        builder.root(path);
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+
+        let desc = RegionDescBuilder::new("test")
+            .enable_version_column(false)
+            .push_key_column(("k", LogicalTypeId::Int64, false))
+            .push_field_column(("v", LogicalTypeId::UInt64, true))
+            .build();
+        let metadata: RegionMetadata = desc.try_into().unwrap();
+        let schema = metadata.schema().clone();
+
+        // The time ranges of the files don't overlap, but their keys interleave.
+        let files = vec![
+            write_keyed_sst(
+                schema.clone(),
+                object_store.clone(),
+                &[(1, 1000), (2, 2000)],
+            )
+            .await,
+            write_keyed_sst(
+                schema.clone(),
+                object_store.clone(),
+                &[(1, 3000), (2, 4000)],
+            )
+            .await,
+        ];
+        let sst_layer = Arc::new(FsAccessLayer::new("./", object_store));
+        let expect = vec![(1, 1000), (1, 3000), (2, 2000), (2, 4000)];
+
+        for append_mode in [false, true] {
+            let reader = build_sst_reader(
+                schema.clone(),
+                sst_layer.clone(),
+                &files,
+                i64::MIN,
+                i64::MAX,
+                &[],
+                append_mode,
+            )
+            .await
+            .unwrap();
+            assert_eq!(expect, read_keys(reader).await);
+        }
+
+        let reader = ChunkReaderBuilder::new(schema, sst_layer)
+            .pick_ssts(&files)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(expect, read_keys(reader).await);
+    }
 }
@@ -23,9 +23,45 @@ pub struct EngineConfig {
     pub manifest_checkpoint_on_startup: bool,
     pub manifest_checkpoint_margin: Option<u16>,
     pub manifest_gc_duration: Option<Duration>,
+    /// Max number of manifest checkpoints of all regions running concurrently. Setting it
+    /// or `manifest_checkpoint_rate_limit` schedules checkpoints in background, otherwise
+    /// checkpoints are done on manifest updates.
+    pub manifest_checkpoint_concurrency: Option<usize>,
+    /// Max bytes of manifest checkpoints written per second, unlimited if `None`.
+    pub manifest_checkpoint_rate_limit: Option<ReadableSize>,
+    /// Max random delay of a scheduled manifest checkpoint.
+    pub manifest_checkpoint_jitter: Duration,
     pub max_files_in_l0: usize,
     pub max_purge_tasks: usize,
     pub sst_write_buffer_size: ReadableSize,
+    /// Whether to verify checksums of SST files on read.
+    pub verify_sst_checksum: bool,
+    /// Interval to verify checksums of all SST files in background, `None` to disable.
+    pub sst_scrub_interval: Option<Duration>,
+    /// SST files whose data are all older than this duration are moved to the cold
+    /// storage during compaction, `None` to keep all files in the hot storage.
+    pub cold_after: Option<Duration>,
+    /// Whether to export compaction outputs as standard parquet files for external readers.
+    pub export_sst: bool,
+    /// Number of threads dedicated to flush jobs, flushes run in the shared background
+    /// runtime if `None`.
+    pub flush_workers: Option<usize>,
+    /// Max bytes written per second by flush and compaction jobs, unlimited if `None`.
+    pub background_io_rate_limit: Option<ReadableSize>,
+    /// Size of the in-memory cache of pages read from SST files, disabled if `None`.
+    pub block_cache_size: Option<ReadableSize>,
+    /// Max number of SST files whose parsed metadata are cached in memory, disabled
+    /// if `None`.
+    pub sst_meta_cache_capacity: Option<u64>,
+    /// Number of batches of a SST file, and SST files of a scan, read ahead during
+    /// scans, 0 to disable reading ahead.
+    pub scan_prefetch_depth: usize,
+    /// Max number of SST readers prefetching at the same time across all scans, readers
+    /// beyond the limit read without prefetching. Unlimited if `None`.
+    pub scan_max_prefetch_readers: Option<usize>,
+    /// Interval to check whether regions need to be flushed by their row count or flush
+    /// interval, `None` to only check on writes.
+    pub flush_check_interval: Option<Duration>,
 }
 
 impl Default for EngineConfig {
@@ -34,9 +70,23 @@ impl Default for EngineConfig {
             manifest_checkpoint_on_startup: false,
             manifest_checkpoint_margin: Some(10),
             manifest_gc_duration: Some(Duration::from_secs(30)),
+            manifest_checkpoint_concurrency: None,
+            manifest_checkpoint_rate_limit: None,
+            manifest_checkpoint_jitter: Duration::from_secs(5),
             max_files_in_l0: 8,
             max_purge_tasks: 32,
             sst_write_buffer_size: ReadableSize::mb(8),
+            verify_sst_checksum: false,
+            sst_scrub_interval: None,
+            cold_after: None,
+            export_sst: false,
+            flush_workers: None,
+            background_io_rate_limit: None,
+            block_cache_size: None,
+            sst_meta_cache_capacity: None,
+            scan_prefetch_depth: 0,
+            scan_max_prefetch_readers: None,
+            flush_check_interval: None,
         }
     }
 }
@@ -17,13 +17,15 @@ use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 use async_trait::async_trait;
-use common_telemetry::logging::debug;
+use common_runtime::RepeatedTask;
+use common_telemetry::logging::{self, debug};
 use object_store::{util, ObjectStore};
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::manifest::Manifest;
 use store_api::storage::{
-    CreateOptions, EngineContext, OpenOptions, Region, RegionDescriptor, StorageEngine,
+    ColumnEncodings, CreateOptions, EngineContext, OpenOptions, Region, RegionDescriptor,
+    StorageEngine,
 };
 
 use crate::background::JobPoolImpl;
@@ -31,23 +33,37 @@ use crate::compaction::CompactionSchedulerRef;
 use crate::config::EngineConfig;
 use crate::error::{self, Error, Result};
 use crate::file_purger::{FilePurgeHandler, FilePurgerRef};
-use crate::flush::{FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, SizeBasedStrategy};
+use crate::flush::{
+    FlushChecker, FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, SizeBasedStrategy,
+};
+use crate::manifest::checkpoint::{CheckpointScheduler, CheckpointSchedulerRef};
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{DefaultMemtableBuilder, MemtableBuilderRef};
 use crate::metadata::RegionMetadata;
+use crate::read::{PrefetchLimiter, PrefetchLimiterRef};
 use crate::region::{RegionImpl, StoreConfig};
 use crate::scheduler::{LocalScheduler, SchedulerConfig};
-use crate::sst::FsAccessLayer;
+use crate::scrub::SstScrubber;
+use crate::sst::block_cache::{BlockCache, BlockCacheRef};
+use crate::sst::meta_cache::{SstMetaCache, SstMetaCacheRef};
+use crate::sst::rate_limit::{IoRateLimiter, IoRateLimiterRef};
+use crate::sst::{ColdStorage, FsAccessLayer};
 
 /// [StorageEngine] implementation.
 pub struct EngineImpl<S: LogStore> {
     inner: Arc<EngineInner<S>>,
+    /// Task to verify checksums of SST files in background.
+    scrub_task: Option<Arc<RepeatedTask<Error>>>,
+    /// Task to flush regions by their row count or flush interval in background.
+    flush_check_task: Option<Arc<RepeatedTask<Error>>>,
 }
 
 impl<S: LogStore> Clone for EngineImpl<S> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            scrub_task: self.scrub_task.clone(),
+            flush_check_task: self.flush_check_task.clone(),
         }
     }
 }
@@ -95,13 +111,60 @@ impl<S: LogStore> EngineImpl<S> {
         object_store: ObjectStore,
         compaction_scheduler: CompactionSchedulerRef<S>,
     ) -> Self {
+        Self::new_with_cold_store(config, log_store, object_store, None, compaction_scheduler)
+    }
+
+    /// Creates a new engine whose old SST files could be moved to the `cold_store`
+    /// according to [EngineConfig::cold_after].
+    pub fn new_with_cold_store(
+        config: EngineConfig,
+        log_store: Arc<S>,
+        object_store: ObjectStore,
+        cold_store: Option<ObjectStore>,
+        compaction_scheduler: CompactionSchedulerRef<S>,
+    ) -> Self {
+        let scrub_interval = config.sst_scrub_interval;
+        let flush_check_interval = config.flush_check_interval;
+        let inner = Arc::new(EngineInner::new(
+            config,
+            log_store,
+            object_store,
+            cold_store,
+            compaction_scheduler,
+        ));
+
+        let scrub_task = scrub_interval.map(|interval| {
+            let task = Arc::new(RepeatedTask::new(
+                interval,
+                Arc::new(SstScrubber::new(Arc::downgrade(&inner))),
+            ));
+            let task_to_start = task.clone();
+            common_runtime::spawn_bg(async move {
+                if let Err(e) = task_to_start.start(common_runtime::bg_runtime()).await {
+                    logging::error!(e; "Failed to start SST scrubber");
+                }
+            });
+            task
+        });
+
+        let flush_check_task = flush_check_interval.map(|interval| {
+            let task = Arc::new(RepeatedTask::new(
+                interval,
+                Arc::new(FlushChecker::new(Arc::downgrade(&inner))),
+            ));
+            let task_to_start = task.clone();
+            common_runtime::spawn_bg(async move {
+                if let Err(e) = task_to_start.start(common_runtime::bg_runtime()).await {
+                    logging::error!(e; "Failed to start flush checker");
+                }
+            });
+            task
+        });
+
         Self {
-            inner: Arc::new(EngineInner::new(
-                config,
-                log_store,
-                object_store,
-                compaction_scheduler,
-            )),
+            inner,
+            scrub_task,
+            flush_check_task,
         }
     }
 }
@@ -218,8 +281,10 @@ impl<'a, S: LogStore> Drop for SlotGuard<'a, S> {
 
 type RegionMap<S> = HashMap<String, RegionSlot<S>>;
 
-struct EngineInner<S: LogStore> {
+pub(crate) struct EngineInner<S: LogStore> {
     object_store: ObjectStore,
+    /// Object store for SST files in the [StorageTier::Cold](crate::sst::StorageTier::Cold) tier.
+    cold_store: Option<ObjectStore>,
     log_store: Arc<S>,
     regions: RwLock<RegionMap<S>>,
     memtable_builder: MemtableBuilderRef,
@@ -227,6 +292,16 @@ struct EngineInner<S: LogStore> {
     flush_strategy: FlushStrategyRef,
     compaction_scheduler: CompactionSchedulerRef<S>,
     file_purger: FilePurgerRef,
+    /// Limiter of the bytes written by flush and compaction jobs of all regions.
+    io_rate_limiter: Option<IoRateLimiterRef>,
+    /// Cache of pages read from SST files of all regions.
+    block_cache: Option<BlockCacheRef>,
+    /// Cache of parsed metadata of SST files of all regions.
+    meta_cache: Option<SstMetaCacheRef>,
+    /// Limiter of the SST readers prefetching at the same time in all regions.
+    prefetch_limiter: Option<PrefetchLimiterRef>,
+    /// Scheduler of the manifest checkpoints of all regions.
+    checkpoint_scheduler: Option<CheckpointSchedulerRef>,
     config: Arc<EngineConfig>,
 }
 
@@ -235,9 +310,36 @@ impl<S: LogStore> EngineInner<S> {
         config: EngineConfig,
         log_store: Arc<S>,
         object_store: ObjectStore,
+        cold_store: Option<ObjectStore>,
         compaction_scheduler: CompactionSchedulerRef<S>,
     ) -> Self {
-        let job_pool = Arc::new(JobPoolImpl {});
+        let flush_runtime = config
+            .flush_workers
+            .map(|workers| common_runtime::create_runtime("flush-worker", workers));
+        let job_pool = Arc::new(JobPoolImpl::new(flush_runtime));
+        let io_rate_limiter = config
+            .background_io_rate_limit
+            .map(|limit| Arc::new(IoRateLimiter::new(limit.as_bytes())));
+        let block_cache = config
+            .block_cache_size
+            .map(|size| Arc::new(BlockCache::new(size.as_bytes())));
+        let meta_cache = config
+            .sst_meta_cache_capacity
+            .map(|capacity| Arc::new(SstMetaCache::new(capacity)));
+        let prefetch_limiter = config
+            .scan_max_prefetch_readers
+            .map(|max_readers| Arc::new(PrefetchLimiter::new(max_readers)));
+        let checkpoint_scheduler = (config.manifest_checkpoint_concurrency.is_some()
+            || config.manifest_checkpoint_rate_limit.is_some())
+        .then(|| {
+            Arc::new(CheckpointScheduler::new(
+                config.manifest_checkpoint_concurrency,
+                config
+                    .manifest_checkpoint_rate_limit
+                    .map(|limit| limit.as_bytes()),
+                config.manifest_checkpoint_jitter,
+            ))
+        });
         let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
 
         let file_purger = Arc::new(LocalScheduler::new(
@@ -248,6 +350,7 @@ impl<S: LogStore> EngineInner<S> {
         ));
         Self {
             object_store,
+            cold_store,
             log_store,
             regions: RwLock::new(Default::default()),
             memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
@@ -255,6 +358,11 @@ impl<S: LogStore> EngineInner<S> {
             flush_strategy: Arc::new(SizeBasedStrategy::default()),
             compaction_scheduler,
             file_purger,
+            io_rate_limiter,
+            block_cache,
+            meta_cache,
+            prefetch_limiter,
+            checkpoint_scheduler,
             config: Arc::new(config),
         }
     }
@@ -294,11 +402,14 @@ impl<S: LogStore> EngineInner<S> {
         let store_config = self
             .region_store_config(
                 &opts.parent_dir,
-                opts.write_buffer_size,
+                self.flush_strategy(opts.write_buffer_size, opts.flush_rows, opts.flush_interval),
                 name,
                 &self.config,
                 opts.ttl,
                 opts.compaction_time_window,
+                opts.append_mode,
+                opts.compact_strings,
+                opts.column_encodings.clone(),
             )
             .await?;
 
@@ -337,11 +448,14 @@ impl<S: LogStore> EngineInner<S> {
         let store_config = self
             .region_store_config(
                 &opts.parent_dir,
-                opts.write_buffer_size,
+                self.flush_strategy(opts.write_buffer_size, opts.flush_rows, opts.flush_interval),
                 &region_name,
                 &self.config,
                 opts.ttl,
                 opts.compaction_time_window,
+                opts.append_mode,
+                opts.compact_strings,
+                opts.column_encodings.clone(),
             )
             .await?;
 
@@ -363,32 +477,76 @@ impl<S: LogStore> EngineInner<S> {
         slot.get_ready_region()
     }
 
+    /// Returns all regions that are ready for access.
+    pub(crate) fn ready_regions(&self) -> Vec<RegionImpl<S>> {
+        self.regions
+            .read()
+            .unwrap()
+            .values()
+            .filter_map(|slot| slot.get_ready_region())
+            .collect()
+    }
+
+    /// Returns the flush strategy of a region, uses the default strategy of the engine if
+    /// the region doesn't specify any flush options.
+    fn flush_strategy(
+        &self,
+        write_buffer_size: Option<usize>,
+        flush_rows: Option<usize>,
+        flush_interval: Option<Duration>,
+    ) -> FlushStrategyRef {
+        if write_buffer_size.is_none() && flush_rows.is_none() && flush_interval.is_none() {
+            return self.flush_strategy.clone();
+        }
+
+        let strategy = write_buffer_size
+            .map(SizeBasedStrategy::new)
+            .unwrap_or_default()
+            .with_max_rows(flush_rows)
+            .with_flush_interval(flush_interval);
+        Arc::new(strategy)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn region_store_config(
         &self,
         parent_dir: &str,
-        write_buffer_size: Option<usize>,
+        flush_strategy: FlushStrategyRef,
         region_name: &str,
         config: &EngineConfig,
         ttl: Option<Duration>,
         compaction_time_window: Option<i64>,
+        append_mode: bool,
+        compact_strings: bool,
+        column_encodings: ColumnEncodings,
     ) -> Result<StoreConfig<S>> {
         let parent_dir = util::normalize_dir(parent_dir);
 
         let sst_dir = &region_sst_dir(&parent_dir, region_name);
-        let sst_layer = Arc::new(FsAccessLayer::new(sst_dir, self.object_store.clone()));
+        let sst_layer = Arc::new(
+            FsAccessLayer::new(sst_dir, self.object_store.clone())
+                .with_checksum_verification(config.verify_sst_checksum)
+                .with_sst_export(config.export_sst)
+                .with_io_rate_limiter(self.io_rate_limiter.clone())
+                .with_block_cache(self.block_cache.clone())
+                .with_meta_cache(self.meta_cache.clone())
+                .with_prefetch_depth(config.scan_prefetch_depth)
+                .with_prefetch_limiter(self.prefetch_limiter.clone())
+                .with_cold_storage(self.cold_store.clone().map(|object_store| ColdStorage {
+                    object_store,
+                    cold_after: config.cold_after,
+                })),
+        );
         let manifest_dir = region_manifest_dir(&parent_dir, region_name);
         let manifest = RegionManifest::with_checkpointer(
             &manifest_dir,
             self.object_store.clone(),
             config.manifest_checkpoint_margin,
             config.manifest_gc_duration,
-        );
+        )
+        .with_checkpoint_scheduler(self.checkpoint_scheduler.clone());
         manifest.start().await?;
 
-        let flush_strategy = write_buffer_size
-            .map(|size| Arc::new(SizeBasedStrategy::new(size)) as Arc<_>)
-            .unwrap_or_else(|| self.flush_strategy.clone());
-
         Ok(StoreConfig {
             log_store: self.log_store.clone(),
             sst_layer,
@@ -401,6 +559,9 @@ impl<S: LogStore> EngineInner<S> {
             file_purger: self.file_purger.clone(),
             ttl,
             compaction_time_window,
+            append_mode,
+            compact_strings,
+            column_encodings,
         })
     }
 }
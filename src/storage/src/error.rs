@@ -18,6 +18,7 @@ use std::str::Utf8Error;
 
 use common_error::prelude::*;
 use common_runtime::error::Error as RuntimeError;
+use common_time::Timestamp;
 use datatypes::arrow::error::ArrowError;
 use datatypes::prelude::ConcreteDataType;
 use object_store::ErrorKind;
@@ -29,6 +30,7 @@ use store_api::storage::{RegionId, SequenceNumber};
 use tokio::task::JoinError;
 
 use crate::metadata::Error as MetadataError;
+use crate::sst::StorageTier;
 use crate::write_batch;
 
 #[derive(Debug, Snafu)]
@@ -69,6 +71,13 @@ pub enum Error {
         source: object_store::Error,
     },
 
+    #[snafu(display("Fail to read SST file {}, source: {}", path, source))]
+    ReadSst {
+        path: String,
+        location: Location,
+        source: std::io::Error,
+    },
+
     #[snafu(display("Fail to write object into path: {}, source: {}", path, source))]
     WriteObject {
         path: String,
@@ -115,6 +124,40 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid time range to drop, start: {:?}, end: {:?}", start, end))]
+    InvalidDropRange {
+        start: Timestamp,
+        end: Timestamp,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Columns of region {} differ from the source region {} to clone",
+        region,
+        source_region
+    ))]
+    CloneColumnsMismatch {
+        region: String,
+        source_region: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to clone data into non-empty region {}", region))]
+    CloneToNonEmptyRegion { region: String, location: Location },
+
+    #[snafu(display(
+        "Failed to attach SSTs to region {} as it is altered after the SSTs are prepared",
+        region
+    ))]
+    AttachAfterAlter { region: String, location: Location },
+
+    #[snafu(display("Invalid staged SST file {}, reason: {}", path, reason))]
+    InvalidStagedSst {
+        path: String,
+        reason: String,
+        location: Location,
+    },
+
     #[snafu(display(
         "Failed to write WAL, WAL region_id: {}, source: {}",
         region_id,
@@ -188,6 +231,26 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("SST file {} is corrupted at block {}", file, block))]
+    CorruptedSst {
+        file: String,
+        block: usize,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid SST file {}, reason: {}", path, reason))]
+    InvalidSst {
+        path: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Object store of storage tier {:?} is not configured", tier))]
+    StorageTierNotConfigured {
+        tier: StorageTier,
+        location: Location,
+    },
+
     #[snafu(display("Region is under {} state, cannot proceed operation", state))]
     InvalidRegionState {
         state: &'static str,
@@ -247,6 +310,17 @@ pub enum Error {
     #[snafu(display("Try to write the closed region"))]
     ClosedRegion { location: Location },
 
+    #[snafu(display(
+        "Subscriber of region {} lagged behind and skipped {} changes",
+        region,
+        skipped
+    ))]
+    SubscriberLagged {
+        region: String,
+        skipped: u64,
+        location: Location,
+    },
+
     #[snafu(display("Invalid projection, source: {}", source))]
     InvalidProjection {
         #[snafu(backtrace)]
@@ -477,6 +551,11 @@ impl ErrorExt for Error {
 
         match self {
             InvalidScanIndex { .. }
+            | InvalidDropRange { .. }
+            | CloneColumnsMismatch { .. }
+            | CloneToNonEmptyRegion { .. }
+            | InvalidStagedSst { .. }
+            | AttachAfterAlter { .. }
             | BatchMissingColumn { .. }
             | InvalidProjection { .. }
             | BuildBatch { .. }
@@ -516,18 +595,24 @@ impl ErrorExt for Error {
 
             WriteParquet { .. }
             | ReadObject { .. }
+            | ReadSst { .. }
             | WriteObject { .. }
             | ListObjects { .. }
             | DeleteObject { .. }
             | WriteWal { .. }
             | DecodeWalHeader { .. }
             | EncodeWalHeader { .. }
-            | ManifestProtocolForbidRead { .. }
-            | ManifestProtocolForbidWrite { .. }
             | ReadParquet { .. }
             | InvalidRegionState { .. }
             | ReadWal { .. } => StatusCode::StorageUnavailable,
 
+            CorruptedSst { .. } | InvalidSst { .. } => StatusCode::StorageCorrupted,
+
+            // Retrying doesn't help until the server is upgraded or reconfigured.
+            ManifestProtocolForbidRead { .. }
+            | ManifestProtocolForbidWrite { .. }
+            | StorageTierNotConfigured { .. } => StatusCode::Unsupported,
+
             UnknownColumn { .. } => StatusCode::TableColumnNotFound,
 
             InvalidAlterRequest { source, .. } | InvalidRegionDesc { source, .. } => {
@@ -539,9 +624,10 @@ impl ErrorExt for Error {
             ConvertChunk { source, .. } => source.status_code(),
             MarkWalObsolete { source, .. } => source.status_code(),
             DecodeParquetTimeRange { .. } => StatusCode::Unexpected,
-            RateLimited { .. } | StopScheduler { .. } | CompactTaskCancel { .. } => {
-                StatusCode::Internal
-            }
+            RateLimited { .. }
+            | StopScheduler { .. }
+            | CompactTaskCancel { .. }
+            | SubscriberLagged { .. } => StatusCode::Internal,
             DeleteSst { .. } => StatusCode::StorageUnavailable,
 
             StartManifestGcTask { .. }
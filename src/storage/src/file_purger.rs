@@ -21,11 +21,14 @@ use tokio::sync::Notify;
 use crate::error::Result;
 use crate::scheduler::rate_limit::{BoxedRateLimitToken, RateLimitToken};
 use crate::scheduler::{Handler, LocalScheduler, Request};
-use crate::sst::{AccessLayerRef, FileId};
+use crate::sst::{AccessLayerRef, FileId, StorageTier};
 
 pub struct FilePurgeRequest {
     pub region_id: RegionId,
     pub file_id: FileId,
+    pub tier: StorageTier,
+    /// Directory of the file if it's shared from another region.
+    pub source_dir: Option<String>,
     pub sst_layer: AccessLayerRef,
 }
 
@@ -51,11 +54,19 @@ impl Handler for FilePurgeHandler {
         token: BoxedRateLimitToken,
         finish_notifier: Arc<Notify>,
     ) -> Result<()> {
-        req.sst_layer.delete_sst(req.file_id).await.map_err(|e| {
-            error!(e; "Failed to delete SST file, file: {}, region: {}", 
+        req.sst_layer
+            .release_sst(
+                req.region_id,
+                req.file_id,
+                req.tier,
+                req.source_dir.as_deref(),
+            )
+            .await
+            .map_err(|e| {
+                error!(e; "Failed to delete SST file, file: {}, region: {}", 
                 req.file_id.as_parquet(), req.region_id);
-            e
-        })?;
+                e
+            })?;
         debug!(
             "Successfully deleted SST file: {}, region: {}",
             req.file_id.as_parquet(),
@@ -160,6 +171,9 @@ mod tests {
                     time_range: None,
                     level: 0,
                     file_size: sst_info.file_size,
+                    checksums: None,
+                    tier: StorageTier::Hot,
+                    source_dir: None,
                 },
                 layer.clone(),
                 file_purger,
@@ -187,6 +201,8 @@ mod tests {
         let request = FilePurgeRequest {
             region_id: 0,
             file_id: sst_file_id,
+            tier: StorageTier::Hot,
+            source_dir: None,
             sst_layer: layer,
         };
 
@@ -14,22 +14,25 @@
 
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use async_trait::async_trait;
+use common_runtime::TaskFunction;
 use common_telemetry::logging;
 use store_api::logstore::LogStore;
 use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
-use store_api::storage::SequenceNumber;
+use store_api::storage::{Region, SequenceNumber, WriteThrottle};
 
 use crate::background::{Context, Job, JobHandle, JobPoolRef};
 use crate::config::EngineConfig;
-use crate::error::{CancelledSnafu, Result};
+use crate::engine::EngineInner;
+use crate::error::{CancelledSnafu, Error, Result};
 use crate::manifest::action::*;
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{IterContext, MemtableId, MemtableRef};
 use crate::region::{RegionWriterRef, SharedDataRef};
-use crate::sst::{AccessLayerRef, FileId, FileMeta, Source, SstInfo, WriteOptions};
+use crate::sst::{AccessLayerRef, FileId, FileMeta, Source, SstInfo, StorageTier, WriteOptions};
 use crate::wal::Wal;
 
 /// Default write buffer size (32M).
@@ -42,6 +45,19 @@ pub trait FlushStrategy: Send + Sync + std::fmt::Debug {
         bytes_mutable: usize,
         bytes_total: usize,
     ) -> bool;
+
+    /// Returns the admission signal of writes to a region with `bytes_mutable` bytes
+    /// in its mutable memtable and `bytes_total` bytes in all memtables.
+    fn write_throttle(&self, _bytes_mutable: usize, _bytes_total: usize) -> WriteThrottle {
+        WriteThrottle::Admit
+    }
+
+    /// Returns true if the non-empty mutable memtable with `mutable_rows` rows should be
+    /// flushed regardless of its size, given that `since_last_flush` has elapsed since
+    /// the region was flushed last time.
+    fn should_flush_mutable(&self, _mutable_rows: usize, _since_last_flush: Duration) -> bool {
+        false
+    }
 }
 
 pub type FlushStrategyRef = Arc<dyn FlushStrategy>;
@@ -52,6 +68,10 @@ pub struct SizeBasedStrategy {
     max_write_buffer_size: usize,
     /// Mutable memtable memory size limitation
     mutable_limitation: usize,
+    /// Max number of rows in the mutable memtable.
+    max_rows: Option<usize>,
+    /// Max duration between two flushes.
+    flush_interval: Option<Duration>,
 }
 
 impl SizeBasedStrategy {
@@ -59,8 +79,23 @@ impl SizeBasedStrategy {
         Self {
             max_write_buffer_size,
             mutable_limitation: get_mutable_limitation(max_write_buffer_size),
+            max_rows: None,
+            flush_interval: None,
         }
     }
+
+    /// Also flushes the mutable memtable once it holds more than `max_rows` rows.
+    pub fn with_max_rows(mut self, max_rows: Option<usize>) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    /// Also flushes the mutable memtable if the region hasn't been flushed for
+    /// `flush_interval`.
+    pub fn with_flush_interval(mut self, flush_interval: Option<Duration>) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
 }
 
 #[inline]
@@ -76,6 +111,8 @@ impl Default for SizeBasedStrategy {
         Self {
             max_write_buffer_size,
             mutable_limitation: get_mutable_limitation(max_write_buffer_size),
+            max_rows: None,
+            flush_interval: None,
         }
     }
 }
@@ -125,6 +162,31 @@ impl FlushStrategy for SizeBasedStrategy {
 
         should_flush
     }
+
+    fn write_throttle(&self, _bytes_mutable: usize, bytes_total: usize) -> WriteThrottle {
+        // Memtables above the buffer size are waiting for flushes, writes are slowed down
+        // to let the flushes catch up, and rejected once twice the buffer size is used.
+        if bytes_total >= self.max_write_buffer_size * 2 {
+            WriteThrottle::Reject
+        } else if bytes_total >= self.max_write_buffer_size {
+            WriteThrottle::Delay
+        } else {
+            WriteThrottle::Admit
+        }
+    }
+
+    fn should_flush_mutable(&self, mutable_rows: usize, since_last_flush: Duration) -> bool {
+        let exceeds_rows = self
+            .max_rows
+            .map(|max_rows| mutable_rows > max_rows)
+            .unwrap_or(false);
+        let exceeds_interval = self
+            .flush_interval
+            .map(|interval| since_last_flush >= interval)
+            .unwrap_or(false);
+
+        exceeds_rows || exceeds_interval
+    }
 }
 
 #[async_trait]
@@ -189,6 +251,8 @@ impl<S: LogStore> FlushJob<S> {
         let mut futures = Vec::with_capacity(self.memtables.len());
         let iter_ctx = IterContext {
             for_flush: true,
+            // Flushes all rows of the same key in append mode.
+            dedup: !self.shared.append_mode(),
             // TODO(ruihang): dynamic row group size based on content (#412)
             batch_size: WRITE_ROW_GROUP_SIZE,
             ..Default::default()
@@ -204,8 +268,13 @@ impl<S: LogStore> FlushJob<S> {
             // TODO(hl): Check if random file name already exists in meta.
             let iter = m.iter(&iter_ctx)?;
             let sst_layer = self.sst_layer.clone();
+            // Newly flushed data always goes to the hot storage.
             let write_options = WriteOptions {
                 sst_write_buffer_size: self.engine_config.sst_write_buffer_size,
+                tier: StorageTier::Hot,
+                export: false,
+                compact_strings: self.shared.compact_strings(),
+                column_encodings: self.shared.column_encodings().clone(),
             };
             futures.push(async move {
                 Ok(sst_layer
@@ -215,6 +284,7 @@ impl<S: LogStore> FlushJob<S> {
                         |SstInfo {
                              time_range,
                              file_size,
+                             checksums,
                              ..
                          }| FileMeta {
                             region_id,
@@ -222,6 +292,9 @@ impl<S: LogStore> FlushJob<S> {
                             time_range,
                             level: 0,
                             file_size,
+                            checksums,
+                            tier: StorageTier::Hot,
+                            source_dir: None,
                         },
                     ))
             });
@@ -243,6 +316,7 @@ impl<S: LogStore> FlushJob<S> {
             flushed_sequence: Some(self.flush_sequence),
             files_to_add: file_metas.to_vec(),
             files_to_remove: Vec::default(),
+            tombstones_to_add: Vec::default(),
         };
 
         self.writer
@@ -272,6 +346,38 @@ impl<S: LogStore> Job for FlushJob<S> {
     }
 }
 
+/// Periodically checks all regions in the engine and flushes regions required by their
+/// flush strategies, so regions without writes are still flushed in time.
+pub(crate) struct FlushChecker<S: LogStore> {
+    engine: Weak<EngineInner<S>>,
+}
+
+impl<S: LogStore> FlushChecker<S> {
+    pub(crate) fn new(engine: Weak<EngineInner<S>>) -> FlushChecker<S> {
+        FlushChecker { engine }
+    }
+}
+
+#[async_trait]
+impl<S: LogStore> TaskFunction<Error> for FlushChecker<S> {
+    fn name(&self) -> &str {
+        "flush-checker"
+    }
+
+    async fn call(&self) -> Result<()> {
+        // The engine is dropped, nothing to flush.
+        let Some(engine) = self.engine.upgrade() else { return Ok(()); };
+
+        for region in engine.ready_regions() {
+            if let Err(e) = region.flush_if_needed().await {
+                logging::error!(e; "Failed to flush region {}", region.name());
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,4 +388,26 @@ mod tests {
         assert_eq!(8, get_mutable_limitation(10));
         assert_eq!(56, get_mutable_limitation(64));
     }
+
+    #[test]
+    fn test_size_based_write_throttle() {
+        let strategy = SizeBasedStrategy::new(64);
+        assert_eq!(WriteThrottle::Admit, strategy.write_throttle(32, 63));
+        assert_eq!(WriteThrottle::Delay, strategy.write_throttle(16, 64));
+        assert_eq!(WriteThrottle::Delay, strategy.write_throttle(16, 127));
+        assert_eq!(WriteThrottle::Reject, strategy.write_throttle(16, 128));
+    }
+
+    #[test]
+    fn test_size_based_should_flush_mutable() {
+        let strategy = SizeBasedStrategy::new(64);
+        assert!(!strategy.should_flush_mutable(usize::MAX, Duration::MAX));
+
+        let strategy = SizeBasedStrategy::new(64)
+            .with_max_rows(Some(100))
+            .with_flush_interval(Some(Duration::from_secs(60)));
+        assert!(!strategy.should_flush_mutable(100, Duration::from_secs(59)));
+        assert!(strategy.should_flush_mutable(101, Duration::from_secs(59)));
+        assert!(strategy.should_flush_mutable(1, Duration::from_secs(60)));
+    }
 }
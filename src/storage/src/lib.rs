@@ -30,6 +30,7 @@ pub mod read;
 pub mod region;
 pub mod scheduler;
 pub mod schema;
+mod scrub;
 mod snapshot;
 pub mod sst;
 mod sync;
@@ -28,7 +28,7 @@ use crate::error::{
 };
 use crate::manifest::helper;
 use crate::metadata::{ColumnFamilyMetadata, ColumnMetadata, VersionNumber};
-use crate::sst::{FileId, FileMeta};
+use crate::sst::{self, FileId, FileMeta, RangeTombstone};
 
 /// Minimal data that could be used to persist and recover [RegionMetadata](crate::metadata::RegionMetadata).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -79,6 +79,9 @@ pub struct RegionEdit {
     pub flushed_sequence: Option<SequenceNumber>,
     pub files_to_add: Vec<FileMeta>,
     pub files_to_remove: Vec<FileMeta>,
+    /// Range tombstones masking rows of the remaining files.
+    #[serde(default)]
+    pub tombstones_to_add: Vec<RangeTombstone>,
 }
 
 /// The region version checkpoint
@@ -87,6 +90,9 @@ pub struct RegionVersion {
     pub manifest_version: ManifestVersion,
     pub flushed_sequence: Option<SequenceNumber>,
     pub files: HashMap<FileId, FileMeta>,
+    /// Range tombstones that still mask rows of the files.
+    #[serde(default)]
+    pub tombstones: Vec<RangeTombstone>,
 }
 
 /// The region manifest data checkpoint
@@ -132,6 +138,11 @@ impl RegionManifestDataBuilder {
             for file in edit.files_to_remove {
                 version.files.remove(&file.file_id);
             }
+            version.tombstones.extend(edit.tombstones_to_add);
+            sst::retain_tombstones(
+                &mut version.tombstones,
+                version.files.values().map(|f| f.time_range),
+            );
         } else {
             self.version = Some(RegionVersion {
                 manifest_version,
@@ -141,6 +152,7 @@ impl RegionManifestDataBuilder {
                     .into_iter()
                     .map(|f| (f.file_id, f))
                     .collect(),
+                tombstones: Vec::new(),
             });
         }
     }
@@ -294,7 +306,7 @@ mod tests {
     use super::*;
     use crate::manifest::test_utils;
     use crate::metadata::RegionMetadata;
-    use crate::sst::FileId;
+    use crate::sst::{FileId, StorageTier};
     use crate::test_util::descriptor_util::RegionDescBuilder;
 
     #[test]
@@ -359,6 +371,9 @@ mod tests {
             time_range: None,
             level: 0,
             file_size: 1024,
+            checksums: None,
+            tier: StorageTier::Hot,
+            source_dir: None,
         }
     }
 
@@ -384,6 +399,7 @@ mod tests {
                 flushed_sequence: Some(99),
                 files_to_add: files.clone(),
                 files_to_remove: vec![],
+                tombstones_to_add: vec![],
             },
         );
         builder.apply_edit(
@@ -393,6 +409,7 @@ mod tests {
                 flushed_sequence: Some(100),
                 files_to_add: vec![],
                 files_to_remove: vec![files[0].clone()],
+                tombstones_to_add: vec![],
             },
         );
 
@@ -405,6 +422,7 @@ mod tests {
                 manifest_version: 85,
                 flushed_sequence: Some(100),
                 files: files[1..].iter().map(|f| (f.file_id, f.clone())).collect(),
+                tombstones: vec![],
             })
         );
     }
@@ -425,6 +443,7 @@ mod tests {
                         .into_iter()
                         .map(|f| (f.file_id, f))
                         .collect(),
+                    tombstones: vec![],
                 }),
             }),
         };
@@ -13,12 +13,17 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use rand::Rng;
 use store_api::manifest::{Checkpoint, MetaAction};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 use crate::error::{Error, Result};
 use crate::manifest::ManifestImpl;
+use crate::sst::rate_limit::IoRateLimiter;
 
 #[async_trait]
 pub trait Checkpointer: Send + Sync + std::fmt::Debug {
@@ -33,3 +38,111 @@ pub trait Checkpointer: Send + Sync + std::fmt::Debug {
 
     fn as_any(&self) -> &dyn Any;
 }
+
+/// Schedules the checkpoints of all region manifests of an engine, so the checkpoints of
+/// thousands of regions don't stampede the object store.
+///
+/// Manifests with a scheduler checkpoint in background instead of on manifest updates.
+/// Each checkpoint waits for a random delay, to spread out the checkpoints triggered at
+/// the same time such as on startup, and then for a permit limiting the checkpoints
+/// running concurrently. The bytes of checkpoints written are rate limited as well.
+#[derive(Debug)]
+pub struct CheckpointScheduler {
+    permits: Semaphore,
+    io_rate_limiter: Option<IoRateLimiter>,
+    max_jitter: Duration,
+}
+
+pub type CheckpointSchedulerRef = Arc<CheckpointScheduler>;
+
+impl CheckpointScheduler {
+    /// Creates a scheduler running at most `max_concurrency` checkpoints concurrently,
+    /// unlimited if `None`, and writing at most `rate_limit` bytes of checkpoints per
+    /// second, unlimited if `None`.
+    pub fn new(
+        max_concurrency: Option<usize>,
+        rate_limit: Option<u64>,
+        max_jitter: Duration,
+    ) -> Self {
+        let permits = max_concurrency
+            .unwrap_or(Semaphore::MAX_PERMITS)
+            .clamp(1, Semaphore::MAX_PERMITS);
+        Self {
+            permits: Semaphore::new(permits),
+            io_rate_limiter: rate_limit
+                .filter(|limit| *limit > 0)
+                .map(IoRateLimiter::new),
+            max_jitter,
+        }
+    }
+
+    /// Waits for a random delay and then a permit to run a checkpoint, the permit is
+    /// returned once it's dropped.
+    pub(crate) async fn acquire(&self) -> SemaphorePermit<'_> {
+        let jitter = self.jitter();
+        if !jitter.is_zero() {
+            tokio::time::sleep(jitter).await;
+        }
+        // The semaphore is never closed.
+        self.permits.acquire().await.unwrap()
+    }
+
+    /// Waits until `bytes` of a checkpoint are allowed to be written.
+    pub(crate) async fn acquire_bytes(&self, bytes: usize) {
+        if let Some(limiter) = &self.io_rate_limiter {
+            limiter.acquire(bytes).await;
+        }
+    }
+
+    fn jitter(&self) -> Duration {
+        let max_millis = self.max_jitter.as_millis() as u64;
+        if max_millis == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(0..=max_millis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_checkpoint_scheduler_concurrency() {
+        let scheduler = CheckpointScheduler::new(Some(2), None, Duration::ZERO);
+        let first = scheduler.acquire().await;
+        let _second = scheduler.acquire().await;
+
+        // The third checkpoint waits until a running one finishes.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), scheduler.acquire())
+                .await
+                .is_err()
+        );
+        drop(first);
+        let _third = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_scheduler_jitter_and_rate_limit() {
+        let scheduler = CheckpointScheduler::new(None, Some(1000), Duration::from_millis(100));
+        for _ in 0..10 {
+            assert!(scheduler.jitter() <= Duration::from_millis(100));
+        }
+
+        scheduler.acquire_bytes(1000).await;
+        let start = Instant::now();
+        scheduler.acquire_bytes(100).await;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let unlimited = CheckpointScheduler::new(None, None, Duration::ZERO);
+        assert_eq!(Duration::ZERO, unlimited.jitter());
+        let start = Instant::now();
+        unlimited.acquire_bytes(usize::MAX).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
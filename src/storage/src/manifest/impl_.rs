@@ -14,35 +14,49 @@
 
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
-use common_runtime::{RepeatedTask, TaskFunction};
-use common_telemetry::{debug, logging, warn};
+use common_runtime::{JoinHandle, RepeatedTask, TaskFunction};
+use common_telemetry::{debug, error, logging, warn};
 use object_store::ObjectStore;
 use snafu::{ensure, ResultExt};
 use store_api::manifest::action::{self, ProtocolAction, ProtocolVersion};
 use store_api::manifest::*;
+use tokio_util::sync::CancellationToken;
 
 use crate::error::{
     Error, ManifestProtocolForbidWriteSnafu, Result, StartManifestGcTaskSnafu,
     StopManifestGcTaskSnafu,
 };
 use crate::manifest::action::RegionCheckpoint;
-use crate::manifest::checkpoint::Checkpointer;
+use crate::manifest::checkpoint::{CheckpointSchedulerRef, Checkpointer};
 use crate::manifest::storage::{ManifestObjectStore, ObjectStoreLogIterator};
 
 const CHECKPOINT_ACTIONS_MARGIN: u16 = 10;
 const GC_DURATION_SECS: u64 = 30;
 
+/// Checkpoint waiting for or running in the checkpoint scheduler.
+#[derive(Debug)]
+struct CheckpointTask {
+    handle: JoinHandle<()>,
+    /// Cancels the checkpoint if it's still waiting for the scheduler.
+    cancel_token: CancellationToken,
+}
+
 #[derive(Clone, Debug)]
 pub struct ManifestImpl<S: Checkpoint<Error = Error>, M: MetaAction<Error = Error>> {
     inner: Arc<ManifestImplInner<S, M>>,
     checkpointer: Option<Arc<dyn Checkpointer<Checkpoint = S, MetaAction = M>>>,
     last_checkpoint_version: Arc<AtomicU64>,
     checkpoint_actions_margin: u16,
+    /// Schedules the checkpoints in background, checkpoints are done on updates if `None`.
+    checkpoint_scheduler: Option<CheckpointSchedulerRef>,
+    /// The checkpoint scheduled in the `checkpoint_scheduler`, which is cancelled or
+    /// awaited when the manifest stops.
+    checkpoint_task: Arc<Mutex<Option<CheckpointTask>>>,
     gc_task: Option<Arc<RepeatedTask<Error>>>,
 }
 
@@ -73,10 +87,21 @@ impl<S: 'static + Checkpoint<Error = Error>, M: 'static + MetaAction<Error = Err
             checkpoint_actions_margin: checkpoint_actions_margin
                 .unwrap_or(CHECKPOINT_ACTIONS_MARGIN),
             last_checkpoint_version: Arc::new(AtomicU64::new(MIN_VERSION)),
+            checkpoint_scheduler: None,
+            checkpoint_task: Arc::new(Mutex::new(None)),
             gc_task,
         }
     }
 
+    /// Checkpoints in background through the `checkpoint_scheduler` shared by manifests.
+    pub fn with_checkpoint_scheduler(
+        mut self,
+        checkpoint_scheduler: Option<CheckpointSchedulerRef>,
+    ) -> Self {
+        self.checkpoint_scheduler = checkpoint_scheduler;
+        self
+    }
+
     pub fn create(manifest_dir: &str, object_store: ObjectStore) -> Self {
         Self::new(manifest_dir, object_store, None, None, None)
     }
@@ -110,6 +135,9 @@ impl<S: 'static + Checkpoint<Error = Error>, M: 'static + MetaAction<Error = Err
             }
         );
         let bytes = checkpoint.encode()?;
+        if let Some(scheduler) = &self.checkpoint_scheduler {
+            scheduler.acquire_bytes(bytes.len()).await;
+        }
         self.manifest_store()
             .save_checkpoint(checkpoint.last_version, &bytes)
             .await
@@ -119,13 +147,62 @@ impl<S: 'static + Checkpoint<Error = Error>, M: 'static + MetaAction<Error = Err
         if version - self.last_checkpoint_version.load(Ordering::Relaxed)
             >= self.checkpoint_actions_margin as u64
         {
-            let s = self.do_checkpoint().await?;
-            debug!("Manifest checkpoint, checkpoint: {:#?}", s);
+            match &self.checkpoint_scheduler {
+                Some(scheduler) => self.schedule_checkpoint(scheduler.clone()),
+                None => {
+                    let s = self.do_checkpoint().await?;
+                    debug!("Manifest checkpoint, checkpoint: {:#?}", s);
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// Checkpoints in background once the `scheduler` permits. At most one checkpoint of
+    /// the manifest is scheduled at a time, which covers all actions saved before it runs.
+    fn schedule_checkpoint(&self, scheduler: CheckpointSchedulerRef) {
+        let mut task = self.checkpoint_task.lock().unwrap();
+        if task
+            .as_ref()
+            .map_or(false, |task| !task.handle.is_finished())
+        {
+            return;
+        }
+
+        let cancel_token = CancellationToken::new();
+        let cancelled = cancel_token.clone();
+        let manifest = self.clone();
+        let handle = common_runtime::spawn_bg(async move {
+            let _permit = tokio::select! {
+                permit = scheduler.acquire() => permit,
+                _ = cancelled.cancelled() => return,
+            };
+            match manifest.do_checkpoint().await {
+                Ok(s) => debug!("Manifest checkpoint, checkpoint: {:#?}", s),
+                Err(e) => warn!(
+                    "Failed to checkpoint manifest in path: {}, err: {}",
+                    manifest.manifest_store().path(),
+                    e
+                ),
+            }
+        });
+        *task = Some(CheckpointTask {
+            handle,
+            cancel_token,
+        });
+    }
+
+    /// Cancels the scheduled checkpoint if it's still waiting, or waits for it to finish
+    /// if it's running, so no checkpoint is written after the manifest stops.
+    async fn stop_checkpoint_task(&self) {
+        let Some(task) = self.checkpoint_task.lock().unwrap().take() else { return; };
+        task.cancel_token.cancel();
+        if let Err(e) = task.handle.await {
+            error!(e; "Failed to join checkpoint task of manifest in path: {}", self.manifest_store().path());
+        }
+    }
+
     #[inline]
     pub(crate) fn manifest_store(&self) -> &Arc<ManifestObjectStore> {
         self.inner.manifest_store()
@@ -187,6 +264,8 @@ impl<S: 'static + Checkpoint<Error = Error>, M: 'static + MetaAction<Error = Err
     }
 
     async fn stop(&self) -> Result<()> {
+        self.stop_checkpoint_task().await;
+
         if let Some(task) = &self.gc_task {
             task.stop().await.context(StopManifestGcTaskSnafu)?;
         }
@@ -180,12 +180,15 @@ mod tests {
     use std::sync::Arc;
 
     use common_test_util::temp_dir::create_temp_dir;
+    use object_store::retry::{RetryPolicy, RetryPolicyLayer};
     use object_store::services::Fs;
+    use object_store::test_util::{FailureInjector, InjectedOperation};
     use object_store::ObjectStore;
     use store_api::manifest::action::ProtocolAction;
     use store_api::manifest::{Manifest, MetaActionIterator, MAX_VERSION};
 
     use super::*;
+    use crate::manifest::checkpoint::CheckpointScheduler;
     use crate::manifest::test_utils::*;
     use crate::metadata::RegionMetadata;
     use crate::sst::FileId;
@@ -295,6 +298,55 @@ mod tests {
         manifest.stop().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_region_manifest_with_injected_failures() {
+        common_telemetry::init_default_ut_logging();
+        let tmp_dir = create_temp_dir("test_region_manifest_with_injected_failures");
+        let mut builder = Fs::default();
+        builder.root(&tmp_dir.path().to_string_lossy());
+        let injector = FailureInjector::default();
+        let object_store = ObjectStore::new(builder)
+            .unwrap()
+            .finish()
+            .layer(injector.layer())
+            .layer(RetryPolicyLayer::new(RetryPolicy {
+                min_delay: Duration::from_millis(1),
+                ..Default::default()
+            }));
+
+        let manifest = RegionManifest::with_checkpointer("/manifest/", object_store, None, None);
+        manifest.start().await.unwrap();
+
+        let region_meta = Arc::new(build_region_meta());
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Change(
+                RegionChange {
+                    metadata: region_meta.as_ref().into(),
+                    committed_sequence: 99,
+                },
+            )))
+            .await
+            .unwrap();
+
+        // Temporary failures of listing and reading manifest files are retried.
+        injector.fail(InjectedOperation::List, "manifest/", 1, true);
+        injector.fail(InjectedOperation::Read, "manifest/", 2, true);
+        let mut iter = manifest.scan(0, MAX_VERSION).await.unwrap();
+        let (v, _) = iter.next_action().await.unwrap().unwrap();
+        assert_eq!(0, v);
+        assert!(iter.next_action().await.unwrap().is_none());
+        assert_eq!(0, injector.pending());
+
+        // Persistent failures are surfaced.
+        injector.fail(InjectedOperation::List, "manifest/", 1, false);
+        assert!(manifest.scan(0, MAX_VERSION).await.is_err());
+
+        let mut iter = manifest.scan(0, MAX_VERSION).await.unwrap();
+        assert!(iter.next_action().await.unwrap().is_some());
+
+        manifest.stop().await.unwrap();
+    }
+
     async fn assert_scan(manifest: &RegionManifest, start_version: ManifestVersion, expected: u64) {
         let mut iter = manifest.scan(0, MAX_VERSION).await.unwrap();
         let mut actions = 0;
@@ -367,6 +419,7 @@ mod tests {
                 manifest_version: 1,
                 flushed_sequence: Some(3),
                 files,
+                ..
             }),
         }) if files.len() == 2 &&
                          files.contains_key(&file_ids[0]) &&
@@ -423,6 +476,7 @@ mod tests {
                 manifest_version: 1,
                 flushed_sequence: Some(3),
                 files,
+                ..
             }),
         }) if files.len() == 2 &&
                          files.contains_key(&file_ids[0]) &&
@@ -445,6 +499,7 @@ mod tests {
                 manifest_version: 4,
                 flushed_sequence: Some(201),
                 files,
+                ..
             }),
         }) if files.len() == 1 &&
                          files.contains_key(&new_file) &&
@@ -483,4 +538,85 @@ mod tests {
 
         manifest.stop().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_scheduled_checkpoint() {
+        common_telemetry::init_default_ut_logging();
+        let tmp_dir = create_temp_dir("test_scheduled_checkpoint");
+        let mut builder = Fs::default();
+        builder.root(&tmp_dir.path().to_string_lossy());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+
+        let scheduler = Arc::new(CheckpointScheduler::new(Some(1), None, Duration::ZERO));
+        let manifest = RegionManifest::with_checkpointer("/manifest/", object_store, Some(1), None)
+            .with_checkpoint_scheduler(Some(scheduler));
+        manifest.start().await.unwrap();
+
+        let region_meta = Arc::new(build_region_meta());
+        manifest.set_flushed_manifest_version(1);
+        for committed_sequence in [1, 2] {
+            manifest
+                .update(RegionMetaActionList::with_action(RegionMetaAction::Change(
+                    RegionChange {
+                        metadata: region_meta.as_ref().into(),
+                        committed_sequence,
+                    },
+                )))
+                .await
+                .unwrap();
+        }
+
+        // The checkpoint is done in background.
+        let mut checkpoint = None;
+        for _ in 0..100 {
+            checkpoint = manifest.last_checkpoint().await.unwrap();
+            if checkpoint.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let checkpoint = checkpoint.unwrap();
+        assert_eq!(1, checkpoint.last_version);
+        assert_eq!(2, checkpoint.compacted_actions);
+
+        manifest.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stop_cancels_scheduled_checkpoint() {
+        common_telemetry::init_default_ut_logging();
+        let tmp_dir = create_temp_dir("test_stop_cancels_scheduled_checkpoint");
+        let mut builder = Fs::default();
+        builder.root(&tmp_dir.path().to_string_lossy());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+
+        let scheduler = Arc::new(CheckpointScheduler::new(Some(1), None, Duration::ZERO));
+        let manifest = RegionManifest::with_checkpointer("/manifest/", object_store, Some(1), None)
+            .with_checkpoint_scheduler(Some(scheduler.clone()));
+        manifest.start().await.unwrap();
+
+        // Holds the only permit so the checkpoint keeps waiting.
+        let permit = scheduler.acquire().await;
+        let region_meta = Arc::new(build_region_meta());
+        manifest.set_flushed_manifest_version(1);
+        for committed_sequence in [1, 2] {
+            manifest
+                .update(RegionMetaActionList::with_action(RegionMetaAction::Change(
+                    RegionChange {
+                        metadata: region_meta.as_ref().into(),
+                        committed_sequence,
+                    },
+                )))
+                .await
+                .unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), manifest.stop())
+            .await
+            .unwrap()
+            .unwrap();
+        drop(permit);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(manifest.last_checkpoint().await.unwrap().is_none());
+    }
 }
@@ -77,6 +77,10 @@ pub struct IterContext {
     /// Returns all rows, ignores sequence visibility and key duplication.
     pub for_flush: bool,
 
+    /// Whether to only return the latest visible row of the same key. Regions in append mode
+    /// disable it to return all visible rows.
+    pub dedup: bool,
+
     /// Schema the reader expect to read.
     ///
     /// Set to `None` to read all columns.
@@ -90,6 +94,7 @@ impl Default for IterContext {
             // All data in memory is visible by default.
             visible_sequence: SequenceNumber::MAX,
             for_flush: false,
+            dedup: true,
             projected_schema: None,
         }
     }
@@ -16,9 +16,9 @@ use std::cmp::Ordering;
 use std::collections::{btree_map, BTreeMap};
 use std::fmt;
 use std::ops::Bound;
-use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::{Arc, RwLock};
 
+use common_telemetry::memory::{MemoryUsage, Subsystem};
 use datatypes::data_type::DataType;
 use datatypes::prelude::*;
 use datatypes::value::Value;
@@ -42,7 +42,8 @@ pub struct BTreeMemtable {
     id: MemtableId,
     schema: RegionSchemaRef,
     map: Arc<RwLockMap>,
-    estimated_bytes: AtomicUsize,
+    /// Estimated bytes of rows written, reported to [Subsystem::Memtables].
+    estimated_bytes: MemoryUsage,
 }
 
 impl BTreeMemtable {
@@ -51,7 +52,7 @@ impl BTreeMemtable {
             id,
             schema,
             map: Arc::new(RwLock::new(BTreeMap::new())),
-            estimated_bytes: AtomicUsize::new(0),
+            estimated_bytes: MemoryUsage::new(Subsystem::Memtables),
         }
     }
 }
@@ -65,7 +66,7 @@ impl fmt::Debug for BTreeMemtable {
             // Only show StoreSchema
             .field("schema", &self.schema)
             .field("rows", &len)
-            .field("estimated_bytes", &self.estimated_bytes)
+            .field("estimated_bytes", &self.estimated_bytes.bytes())
             .finish()
     }
 }
@@ -80,8 +81,7 @@ impl Memtable for BTreeMemtable {
     }
 
     fn write(&self, kvs: &KeyValues) -> Result<()> {
-        self.estimated_bytes
-            .fetch_add(kvs.estimated_memory_size(), AtomicOrdering::Relaxed);
+        self.estimated_bytes.add(kvs.estimated_memory_size());
 
         let mut map = self.map.write().unwrap();
         let iter_row = IterRow::new(kvs);
@@ -101,7 +101,7 @@ impl Memtable for BTreeMemtable {
     }
 
     fn bytes_allocated(&self) -> usize {
-        self.estimated_bytes.load(AtomicOrdering::Relaxed)
+        self.estimated_bytes.bytes() as usize
     }
 
     fn num_rows(&self) -> usize {
@@ -168,10 +168,14 @@ impl BTreeIterator {
             map.range(..)
         };
 
+        let visible_sequence = self.ctx.visible_sequence;
         let (keys, sequences, op_types, values) = if self.ctx.for_flush {
             collect_iter(iter, self.ctx.batch_size)
+        } else if !self.ctx.dedup {
+            let iter = iter.filter(|(k, _)| k.is_visible(visible_sequence));
+            collect_iter(iter, self.ctx.batch_size)
         } else {
-            let iter = MapIterWrapper::new(iter, self.ctx.visible_sequence);
+            let iter = MapIterWrapper::new(iter, visible_sequence);
             collect_iter(iter, self.ctx.batch_size)
         };
 
@@ -180,7 +184,11 @@ impl BTreeIterator {
         }
         self.last_key = keys.last().map(|k| {
             let mut last_key = (*k).clone();
-            last_key.reset_for_seek();
+            // Skips the remaining rows with the same row key as the last row unless all rows
+            // of the same key are required.
+            if self.ctx.dedup {
+                last_key.reset_for_seek();
+            }
             last_key
         });
 
@@ -409,6 +409,62 @@ fn test_duplicate_key_across_batch() {
     });
 }
 
+#[test]
+fn test_duplicate_key_without_dedup() {
+    let tester = MemtableTester::default();
+    tester.run_testcase(|ctx| {
+        write_kvs(
+            &*ctx.memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 1), (2001, 2)], // keys
+            &[(Some(1), None), (Some(2), None), (None, None)], // values
+        );
+
+        write_kvs(
+            &*ctx.memtable,
+            11, // sequence
+            OpType::Put,
+            &[(1000, 1), (2001, 2)],               // keys
+            &[(Some(11), None), (Some(12), None)], // values
+        );
+
+        write_kvs(
+            &*ctx.memtable,
+            12, // sequence
+            OpType::Put,
+            &[(1000, 1)],        // keys
+            &[(Some(21), None)], // values
+        );
+
+        let batch_sizes = [1, 2, 3, 4, 5, 6];
+        for batch_size in batch_sizes {
+            let iter_ctx = IterContext {
+                batch_size,
+                visible_sequence: 11,
+                dedup: false,
+                ..Default::default()
+            };
+
+            // All visible rows are returned, the latest ones first.
+            let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
+            check_iter_content(
+                &mut *iter,
+                &[(1000, 1), (1000, 1), (1000, 1), (2001, 2), (2001, 2)], // keys
+                &[11, 10, 10, 11, 10],                                    // sequences
+                &[OpType::Put; 5],                                        // op_types
+                &[
+                    (Some(11), None),
+                    (Some(2), None),
+                    (Some(1), None),
+                    (Some(12), None),
+                    (None, None),
+                ], // values
+            );
+        }
+    });
+}
+
 #[test]
 fn test_duplicate_key_in_batch() {
     let tester = MemtableTester::default();
@@ -473,6 +529,7 @@ fn test_sequence_visibility() {
                 batch_size: 1,
                 visible_sequence: 9,
                 for_flush: false,
+                dedup: true,
                 projected_schema: None,
             };
 
@@ -491,6 +548,7 @@ fn test_sequence_visibility() {
                 batch_size: 1,
                 visible_sequence: 10,
                 for_flush: false,
+                dedup: true,
                 projected_schema: None,
             };
 
@@ -509,6 +567,7 @@ fn test_sequence_visibility() {
                 batch_size: 1,
                 visible_sequence: 11,
                 for_flush: false,
+                dedup: true,
                 projected_schema: None,
             };
 
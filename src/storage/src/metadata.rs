@@ -20,6 +20,7 @@ use std::sync::Arc;
 use common_error::prelude::*;
 use datatypes::data_type::ConcreteDataType;
 use datatypes::schema::{ColumnSchema, Metadata, COMMENT_KEY};
+use datatypes::value::Value;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, Location, OptionExt};
 use store_api::storage::consts::{self, ReservedColumnId};
@@ -139,6 +140,17 @@ pub enum Error {
 
     #[snafu(display("Invalid projection, {}", msg))]
     InvalidProjection { msg: String, location: Location },
+
+    #[snafu(display(
+        "Failed to evaluate default value of column {}, source: {}",
+        name,
+        source
+    ))]
+    EvaluateDefault {
+        name: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -270,8 +282,23 @@ impl RegionMetadata {
         // Apply the alter operation to the descriptor.
         req.operation.apply(&mut desc);
 
+        // Keeps backfill values of existing columns, dropped columns are ignored by the builder.
+        let mut backfills: HashMap<_, _> = self
+            .columns
+            .iter_user_columns()
+            .filter_map(|column| column.backfill.clone().map(|value| (column.id(), value)))
+            .collect();
+        if let AlterOperation::AddColumns { columns } = &req.operation {
+            for add_column in columns {
+                if let Some(value) = evaluate_backfill(&add_column.desc)? {
+                    backfills.insert(add_column.desc.id, value);
+                }
+            }
+        }
+
         RegionMetadataBuilder::try_from(desc)?
             .version(self.version + 1) // Bump the metadata version.
+            .backfills(backfills)
             .build()
     }
 
@@ -385,6 +412,13 @@ const METADATA_COLUMN_ID_KEY: &str = "greptime:storage:column_id";
 pub struct ColumnMetadata {
     pub cf_id: ColumnFamilyId,
     pub desc: ColumnDescriptor,
+    /// Value of this column for rows written before the column was added.
+    ///
+    /// The default constraint is evaluated once when the column is added, so
+    /// functions like `now()` don't give old rows a different value on each read.
+    /// `None` means null.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backfill: Option<Value>,
 }
 
 impl ColumnMetadata {
@@ -429,7 +463,11 @@ impl ColumnMetadata {
         .build()
         .context(BuildColumnDescriptorSnafu)?;
 
-        Ok(ColumnMetadata { cf_id, desc })
+        Ok(ColumnMetadata {
+            cf_id,
+            desc,
+            backfill: None,
+        })
     }
 
     fn to_metadata(&self) -> Metadata {
@@ -446,6 +484,18 @@ impl ColumnMetadata {
     }
 }
 
+/// Evaluates the default constraint of a column to add into the value to fill for
+/// existing rows, returns `None` if existing rows should be null.
+fn evaluate_backfill(desc: &ColumnDescriptor) -> Result<Option<Value>> {
+    let Some(constraint) = desc.default_constraint() else { return Ok(None); };
+    let vector = constraint
+        .create_default_vector(&desc.data_type, desc.is_nullable(), 1)
+        .context(EvaluateDefaultSnafu { name: &desc.name })?;
+    let value = vector.get(0);
+
+    Ok((!value.is_null()).then_some(value))
+}
+
 fn try_parse_int<T>(metadata: &Metadata, key: &str, default_value: Option<T>) -> Result<T>
 where
     T: FromStr<Err = ParseIntError>,
@@ -670,6 +720,9 @@ struct ColumnsMetadataBuilder {
     row_key_end: usize,
     timestamp_key_index: Option<usize>,
     enable_version_column: bool,
+
+    /// Backfill values of columns, see [ColumnMetadata::backfill].
+    backfills: HashMap<ColumnId, Value>,
 }
 
 impl ColumnsMetadataBuilder {
@@ -727,7 +780,11 @@ impl ColumnsMetadataBuilder {
 
         let column_name = desc.name.clone();
         let column_id = desc.id;
-        let meta = ColumnMetadata { cf_id, desc };
+        let meta = ColumnMetadata {
+            cf_id,
+            desc,
+            backfill: None,
+        };
 
         let column_index = self.columns.len();
         self.columns.push(meta);
@@ -745,6 +802,9 @@ impl ColumnsMetadataBuilder {
         for internal_desc in internal_column_descs() {
             self.push_new_column(consts::DEFAULT_CF_ID, internal_desc)?;
         }
+        for column in &mut self.columns {
+            column.backfill = self.backfills.remove(&column.id());
+        }
 
         Ok(ColumnsMetadata {
             columns: self.columns,
@@ -841,6 +901,11 @@ impl RegionMetadataBuilder {
         self
     }
 
+    fn backfills(mut self, backfills: HashMap<ColumnId, Value>) -> Self {
+        self.columns_meta_builder.backfills = backfills;
+        self
+    }
+
     fn add_column_family(mut self, cf: ColumnFamilyDescriptor) -> Result<Self> {
         let column_index_start = self.columns_meta_builder.columns.len();
         let column_index_end = column_index_start + cf.columns.len();
@@ -1238,6 +1303,90 @@ mod tests {
         assert_eq!(expect, metadata);
     }
 
+    #[test]
+    fn test_alter_metadata_backfill() {
+        let builder = RegionDescBuilder::new("region-0")
+            .enable_version_column(false)
+            .push_key_column(("k1", LogicalTypeId::Int32, false))
+            .push_field_column(("v1", LogicalTypeId::Float32, true));
+        let last_column_id = builder.last_column_id();
+        let metadata: RegionMetadata = builder.build().try_into().unwrap();
+
+        let req = AlterRequest {
+            operation: AlterOperation::AddColumns {
+                columns: vec![
+                    AddColumn {
+                        desc: ColumnDescriptorBuilder::new(
+                            last_column_id + 1,
+                            "v2",
+                            ConcreteDataType::int32_datatype(),
+                        )
+                        .default_constraint(Some(ColumnDefaultConstraint::Value(Value::Int32(7))))
+                        .build()
+                        .unwrap(),
+                        is_key: false,
+                    },
+                    AddColumn {
+                        desc: ColumnDescriptorBuilder::new(
+                            last_column_id + 2,
+                            "v3",
+                            ConcreteDataType::timestamp_millisecond_datatype(),
+                        )
+                        .default_constraint(Some(ColumnDefaultConstraint::Function(
+                            "now()".to_string(),
+                        )))
+                        .build()
+                        .unwrap(),
+                        is_key: false,
+                    },
+                    AddColumn {
+                        desc: ColumnDescriptorBuilder::new(
+                            last_column_id + 3,
+                            "v4",
+                            ConcreteDataType::int32_datatype(),
+                        )
+                        .build()
+                        .unwrap(),
+                        is_key: false,
+                    },
+                ],
+            },
+            version: 0,
+        };
+        metadata.validate_alter(&req).unwrap();
+        let metadata = metadata.alter(&req).unwrap();
+
+        let backfill = |metadata: &RegionMetadata, name: &str| {
+            metadata
+                .columns
+                .iter_user_columns()
+                .find(|column| column.name() == name)
+                .unwrap()
+                .backfill
+                .clone()
+        };
+        assert_eq!(None, backfill(&metadata, "v1"));
+        assert_eq!(Some(Value::Int32(7)), backfill(&metadata, "v2"));
+        let now = backfill(&metadata, "v3").unwrap();
+        assert!(matches!(now, Value::Timestamp(_)));
+        assert_eq!(None, backfill(&metadata, "v4"));
+
+        // Backfill values are kept by later alterations and persisted in the manifest.
+        let req = AlterRequest {
+            operation: AlterOperation::DropColumns {
+                names: vec!["v1".to_string()],
+            },
+            version: 1,
+        };
+        let metadata = metadata.alter(&req).unwrap();
+        assert_eq!(Some(Value::Int32(7)), backfill(&metadata, "v2"));
+        assert_eq!(Some(now), backfill(&metadata, "v3"));
+
+        let raw = RawRegionMetadata::from(&metadata);
+        let converted = RegionMetadata::try_from(raw).unwrap();
+        assert_eq!(metadata, converted);
+    }
+
     #[test]
     fn test_alter_metadata_drop_columns() {
         let region_name = "region-0";
@@ -1403,12 +1552,17 @@ mod tests {
         let meta = ColumnMetadata {
             cf_id: consts::DEFAULT_CF_ID,
             desc: desc.clone(),
+            backfill: None,
         };
         let column_schema = meta.to_column_schema().unwrap();
         let new_meta = ColumnMetadata::from_column_schema(&column_schema).unwrap();
         assert_eq!(meta, new_meta);
 
-        let meta = ColumnMetadata { cf_id: 567, desc };
+        let meta = ColumnMetadata {
+            cf_id: 567,
+            desc,
+            backfill: None,
+        };
         let column_schema = meta.to_column_schema().unwrap();
         let new_meta = ColumnMetadata::from_column_schema(&column_schema).unwrap();
         assert_eq!(meta, new_meta);
@@ -16,3 +16,19 @@
 
 /// Elapsed time of updating manifest when creating regions.
 pub const CREATE_REGION_UPDATE_MANIFEST: &str = "storage.create_region.update_manifest";
+/// Counter of SST files verified by the scrubber.
+pub const SCRUB_SST_FILES: &str = "storage.sst.scrub.files";
+/// Counter of corrupted SST files found by the scrubber.
+pub const SCRUB_SST_CORRUPTED: &str = "storage.sst.scrub.corrupted";
+/// Gauge of flush jobs submitted and not finished yet.
+pub const FLUSH_QUEUE_DEPTH: &str = "storage.flush.queue_depth";
+/// Gauge of compaction tasks submitted and not finished yet.
+pub const COMPACTION_QUEUE_DEPTH: &str = "storage.compaction.queue_depth";
+/// Counter of SST reads served by the block cache.
+pub const BLOCK_CACHE_HIT: &str = "storage.block_cache.hit";
+/// Counter of SST reads missing the block cache.
+pub const BLOCK_CACHE_MISS: &str = "storage.block_cache.miss";
+/// Counter of SST metadata loaded from the metadata cache.
+pub const SST_META_CACHE_HIT: &str = "storage.sst_meta_cache.hit";
+/// Counter of SST metadata missing the metadata cache.
+pub const SST_META_CACHE_MISS: &str = "storage.sst_meta_cache.miss";
@@ -14,21 +14,32 @@
 
 //! Common structs and utilities for read.
 
+mod chain;
 mod dedup;
 mod merge;
+mod prefetch;
+mod tombstone;
+mod visible;
 
 use std::cmp::Ordering;
 
 use async_trait::async_trait;
+pub use chain::ChainReader;
 use common_base::BitVec;
 use datatypes::data_type::DataType;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::vectors::{BooleanVector, MutableVector, VectorRef};
 pub use dedup::DedupReader;
 pub use merge::{MergeReader, MergeReaderBuilder};
+pub use prefetch::{PrefetchLimiter, PrefetchLimiterRef, PrefetchReader};
 use snafu::{ensure, ResultExt};
+use store_api::storage::SequenceNumber;
+pub use tombstone::TombstoneReader;
+pub use visible::VisibleReader;
 
 use crate::error::{self, Result};
+use crate::memtable::BoxedBatchIterator;
+use crate::sst::RangeTombstone;
 
 /// Storage internal representation of a batch of rows.
 // Now the structure of `Batch` is still unstable, all pub fields may be changed.
@@ -143,6 +154,32 @@ pub trait BatchOp {
     /// - `batch` doesn't have a valid op type column.
     /// - `selected.len()` is less than the number of rows.
     fn unselect_deleted(&self, batch: &Batch, selected: &mut BitVec);
+
+    /// Unselect rows whose sequences are greater than `visible_sequence`.
+    ///
+    /// # Panics
+    /// Panics if
+    /// - `batch` doesn't have a valid sequence column.
+    /// - `selected.len()` is less than the number of rows.
+    fn unselect_invisible(
+        &self,
+        batch: &Batch,
+        selected: &mut BitVec,
+        visible_sequence: SequenceNumber,
+    );
+
+    /// Unselect rows masked by any of the range `tombstones`.
+    ///
+    /// # Panics
+    /// Panics if
+    /// - `batch` doesn't have a valid sequence column.
+    /// - `selected.len()` is less than the number of rows.
+    fn unselect_tombstoned(
+        &self,
+        batch: &Batch,
+        selected: &mut BitVec,
+        tombstones: &[RangeTombstone],
+    );
 }
 
 /// Reusable [Batch] builder.
@@ -258,3 +295,29 @@ pub trait BatchReader: Send {
 
 /// Pointer to [BatchReader].
 pub type BoxedBatchReader = Box<dyn BatchReader>;
+
+#[async_trait]
+impl<T: BatchReader + ?Sized> BatchReader for Box<T> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        (**self).next_batch().await
+    }
+}
+
+/// A [BatchReader] reading batches from a [BatchIterator](crate::memtable::BatchIterator)
+/// of memtables.
+pub struct IterReader {
+    iter: BoxedBatchIterator,
+}
+
+impl IterReader {
+    pub fn new(iter: BoxedBatchIterator) -> IterReader {
+        IterReader { iter }
+    }
+}
+
+#[async_trait]
+impl BatchReader for IterReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.iter.next().transpose()
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::VecDeque;
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::read::{Batch, BatchReader, BoxedBatchReader};
+
+/// A reader that yields all batches of its inner readers one after another.
+///
+/// Unlike [MergeReader](crate::read::MergeReader), it doesn't sort rows across readers,
+/// so it should only be used when the key ranges of the readers don't overlap.
+pub struct ChainReader {
+    readers: VecDeque<BoxedBatchReader>,
+}
+
+impl ChainReader {
+    pub fn new(readers: Vec<BoxedBatchReader>) -> ChainReader {
+        ChainReader {
+            readers: readers.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl BatchReader for ChainReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(reader) = self.readers.front_mut() {
+            if let Some(batch) = reader.next_batch().await? {
+                return Ok(Some(batch));
+            }
+            // The current reader is exhausted, release it and moves to the next one.
+            self.readers.pop_front();
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_util;
+
+    #[tokio::test]
+    async fn test_chain_reader() {
+        let mut reader = ChainReader::new(vec![
+            read_util::build_boxed_reader(&[&[(1, Some(1)), (2, Some(2))], &[(3, None)]]),
+            read_util::build_boxed_reader(&[]),
+            read_util::build_boxed_reader(&[&[(4, Some(4))]]),
+        ]);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        assert_eq!(
+            vec![(1, Some(1)), (2, Some(2)), (3, None), (4, Some(4))],
+            result
+        );
+        assert!(reader.next_batch().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chain_reader_empty() {
+        let mut reader = ChainReader::new(Vec::new());
+        assert!(reader.next_batch().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+
+use crate::error::Result;
+use crate::read::{Batch, BatchReader, BoxedBatchReader};
+
+/// A reader that reads ahead batches of the inner reader in background, so the latency
+/// of fetching the next batches from the object store overlaps with consuming the
+/// current ones.
+pub struct PrefetchReader {
+    receiver: mpsc::Receiver<Result<Batch>>,
+}
+
+impl PrefetchReader {
+    /// Creates a reader prefetching at most `depth` batches of `reader`.
+    ///
+    /// # Panics
+    /// Panics if `depth` is 0.
+    pub fn new(reader: BoxedBatchReader, depth: usize) -> PrefetchReader {
+        Self::with_permit(reader, depth, None)
+    }
+
+    /// Creates a reader holding the `permit` of a [PrefetchLimiter] until it stops
+    /// prefetching.
+    fn with_permit(
+        mut reader: BoxedBatchReader,
+        depth: usize,
+        permit: Option<OwnedSemaphorePermit>,
+    ) -> PrefetchReader {
+        let (sender, receiver) = mpsc::channel(depth);
+        let _ = common_runtime::spawn_read(async move {
+            let _permit = permit;
+            loop {
+                match reader.next_batch().await {
+                    Ok(Some(batch)) => {
+                        // Stops reading if the prefetch reader is dropped.
+                        if sender.send(Ok(batch)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        let _ = sender.send(Err(e)).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        PrefetchReader { receiver }
+    }
+}
+
+#[async_trait]
+impl BatchReader for PrefetchReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.receiver.recv().await.transpose()
+    }
+}
+
+pub type PrefetchLimiterRef = Arc<PrefetchLimiter>;
+
+/// Limits the number of [PrefetchReader]s prefetching at the same time across all scans, so
+/// the memory held by prefetched batches is bounded.
+#[derive(Debug)]
+pub struct PrefetchLimiter {
+    permits: Arc<Semaphore>,
+}
+
+impl PrefetchLimiter {
+    pub fn new(max_readers: usize) -> PrefetchLimiter {
+        PrefetchLimiter {
+            permits: Arc::new(Semaphore::new(max_readers)),
+        }
+    }
+
+    /// Prefetches at most `depth` batches of `reader` if the limit is not reached, otherwise
+    /// returns the `reader` as is. Never waits for other readers, since they may belong to
+    /// the same scan and wait for this one to be consumed.
+    pub fn prefetch(&self, reader: BoxedBatchReader, depth: usize) -> BoxedBatchReader {
+        match self.permits.clone().try_acquire_owned() {
+            Ok(permit) => Box::new(PrefetchReader::with_permit(reader, depth, Some(permit))),
+            Err(_) => reader,
+        }
+    }
+
+    /// Returns the number of readers that could start prefetching.
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_util;
+
+    #[tokio::test]
+    async fn test_prefetch_reader() {
+        let reader = read_util::build_boxed_reader(&[
+            &[(1, Some(1)), (2, Some(2))],
+            &[(3, None)],
+            &[(4, Some(4))],
+        ]);
+        let mut reader = PrefetchReader::new(reader, 1);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        assert_eq!(
+            vec![(1, Some(1)), (2, Some(2)), (3, None), (4, Some(4))],
+            result
+        );
+        // Call next_batch() again is allowed.
+        assert!(reader.next_batch().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_limiter() {
+        let limiter = PrefetchLimiter::new(1);
+        let build_reader = || read_util::build_boxed_reader(&[&[(1, Some(1))], &[(2, Some(2))]]);
+
+        let mut first = limiter.prefetch(build_reader(), 1);
+        assert_eq!(0, limiter.available());
+        // Exceeds the limit, reads without prefetching.
+        let mut second = limiter.prefetch(build_reader(), 1);
+        assert_eq!(0, limiter.available());
+
+        assert_eq!(
+            vec![(1, Some(1)), (2, Some(2))],
+            read_util::collect_kv_batch(&mut second).await
+        );
+        assert_eq!(
+            vec![(1, Some(1)), (2, Some(2))],
+            read_util::collect_kv_batch(&mut first).await
+        );
+        // The permit is released once the first reader finishes prefetching.
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert_eq!(1, limiter.available());
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_base::BitVec;
+use datatypes::prelude::ScalarVector;
+use datatypes::vectors::BooleanVector;
+
+use crate::error::Result;
+use crate::read::{Batch, BatchOp, BatchReader};
+use crate::schema::ProjectedSchemaRef;
+use crate::sst::RangeTombstone;
+
+/// A reader that filters out rows masked by range tombstones.
+pub struct TombstoneReader<R> {
+    /// Projected schema to read.
+    schema: ProjectedSchemaRef,
+    /// The inner reader.
+    reader: R,
+    /// Tombstones to apply.
+    tombstones: Arc<[RangeTombstone]>,
+    /// Reused bitmap buffer.
+    selected: BitVec,
+}
+
+impl<R> TombstoneReader<R> {
+    pub fn new(
+        schema: ProjectedSchemaRef,
+        reader: R,
+        tombstones: Arc<[RangeTombstone]>,
+    ) -> TombstoneReader<R> {
+        TombstoneReader {
+            schema,
+            reader,
+            tombstones,
+            selected: BitVec::default(),
+        }
+    }
+
+    fn filter_batch(&mut self, batch: Batch) -> Result<Batch> {
+        self.selected.clear();
+        self.selected.resize(batch.num_rows(), true);
+        self.schema
+            .unselect_tombstoned(&batch, &mut self.selected, &self.tombstones);
+        if self.selected.all() {
+            return Ok(batch);
+        }
+
+        let filter = BooleanVector::from_iterator(self.selected.iter().by_vals());
+        self.schema.filter(&batch, &filter)
+    }
+}
+
+#[async_trait]
+impl<R: BatchReader> BatchReader for TombstoneReader<R> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(batch) = self.reader.next_batch().await? {
+            let filtered = self.filter_batch(batch)?;
+            // Skip empty batch.
+            if !filtered.is_empty() {
+                return Ok(Some(filtered));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_time::Timestamp;
+    use store_api::storage::OpType;
+
+    use super::*;
+    use crate::test_util::read_util;
+
+    #[tokio::test]
+    async fn test_tombstone_reader() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 10, OpType::Put),
+                (101, 1, 11, OpType::Put),
+                (102, 1, 12, OpType::Put),
+            ],
+            &[(103, 1, 5, OpType::Put), (104, 1, 5, OpType::Put)],
+            &[(105, 1, 5, OpType::Put)],
+        ]);
+        let tombstones = vec![RangeTombstone {
+            start: Timestamp::new_millisecond(101),
+            end: Timestamp::new_millisecond(103),
+            sequence: 11,
+        }];
+        let mut reader = TombstoneReader::new(schema, reader, tombstones.into());
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        // 102 is written after the tombstone.
+        assert_eq!(
+            vec![
+                (100, Some(1)),
+                (102, Some(1)),
+                (104, Some(1)),
+                (105, Some(1))
+            ],
+            result
+        );
+    }
+}
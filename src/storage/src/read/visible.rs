@@ -0,0 +1,108 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use async_trait::async_trait;
+use common_base::BitVec;
+use datatypes::prelude::ScalarVector;
+use datatypes::vectors::BooleanVector;
+use store_api::storage::SequenceNumber;
+
+use crate::error::Result;
+use crate::read::{Batch, BatchOp, BatchReader};
+use crate::schema::ProjectedSchemaRef;
+
+/// A reader that filters out rows whose sequences are greater than the visible sequence.
+///
+/// Memtables already skip invisible rows while iterating, but SST files don't. Files
+/// flushed after a snapshot is taken may contain rows newer than the snapshot, so reads
+/// at an older sequence need to filter these rows out.
+pub struct VisibleReader<R> {
+    /// Projected schema to read.
+    schema: ProjectedSchemaRef,
+    /// The inner reader.
+    reader: R,
+    /// Max visible sequence (inclusive).
+    visible_sequence: SequenceNumber,
+    /// Reused bitmap buffer.
+    selected: BitVec,
+}
+
+impl<R> VisibleReader<R> {
+    pub fn new(
+        schema: ProjectedSchemaRef,
+        reader: R,
+        visible_sequence: SequenceNumber,
+    ) -> VisibleReader<R> {
+        VisibleReader {
+            schema,
+            reader,
+            visible_sequence,
+            selected: BitVec::default(),
+        }
+    }
+
+    fn filter_batch(&mut self, batch: Batch) -> Result<Batch> {
+        self.selected.clear();
+        self.selected.resize(batch.num_rows(), true);
+        self.schema
+            .unselect_invisible(&batch, &mut self.selected, self.visible_sequence);
+        if self.selected.all() {
+            return Ok(batch);
+        }
+
+        let filter = BooleanVector::from_iterator(self.selected.iter().by_vals());
+        self.schema.filter(&batch, &filter)
+    }
+}
+
+#[async_trait]
+impl<R: BatchReader> BatchReader for VisibleReader<R> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(batch) = self.reader.next_batch().await? {
+            let filtered = self.filter_batch(batch)?;
+            // Skip empty batch.
+            if !filtered.is_empty() {
+                return Ok(Some(filtered));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use store_api::storage::OpType;
+
+    use super::*;
+    use crate::test_util::read_util;
+
+    #[tokio::test]
+    async fn test_visible_reader() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 1000, OpType::Put),
+                (101, 1, 999, OpType::Put),
+                (102, 1, 1001, OpType::Put),
+            ],
+            &[(103, 1, 1002, OpType::Put)],
+            &[(104, 1, 998, OpType::Delete)],
+        ]);
+        let mut reader = VisibleReader::new(schema, reader, 1000);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        assert_eq!(vec![(100, Some(1)), (101, Some(1)), (104, Some(1))], result);
+    }
+}
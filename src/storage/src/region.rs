@@ -23,13 +23,18 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use common_telemetry::logging;
+use futures::stream::BoxStream;
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AlterRequest, FlushContext, OpenOptions, ReadContext, Region, RegionId, SequenceNumber,
-    WriteContext, WriteResponse,
+    AlterRequest, AttachContext, AttachReport, ChangeBatch, ColumnEncodings, CompactContext,
+    DropRangeContext, DropRangeReport, FlushContext, OpenOptions, PreparedAttach, PreparedFile,
+    PurgeContext, PurgeReport, ReadContext, Region, RegionHealthReport, RegionId, SequenceNumber,
+    WriteContext, WriteResponse, WriteThrottle,
 };
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::compaction::CompactionSchedulerRef;
 use crate::config::EngineConfig;
@@ -44,13 +49,17 @@ use crate::memtable::MemtableBuilderRef;
 use crate::metadata::{RegionMetaImpl, RegionMetadata, RegionMetadataRef};
 pub use crate::region::writer::{AlterContext, RegionWriter, RegionWriterRef, WriterContext};
 use crate::schema::compat::CompatWrite;
+use crate::scrub;
 use crate::snapshot::SnapshotImpl;
-use crate::sst::AccessLayerRef;
+use crate::sst::{AccessLayerRef, FileHandle, FileId, StorageTier};
 use crate::version::{
     Version, VersionControl, VersionControlRef, VersionEdit, INIT_COMMITTED_SEQUENCE,
 };
 use crate::wal::Wal;
-use crate::write_batch::WriteBatch;
+use crate::write_batch::{Payload, WriteBatch};
+
+/// Max number of change batches buffered for each subscriber of a region.
+const CHANGE_CHANNEL_CAPACITY: usize = 1024;
 
 /// [Region] implementation.
 pub struct RegionImpl<S: LogStore> {
@@ -122,10 +131,18 @@ impl<S: LogStore> Region for RegionImpl<S> {
         self.inner.alter(request).await
     }
 
+    async fn set_ttl(&self, ttl: Option<Duration>) {
+        self.inner.writer.set_ttl(ttl).await
+    }
+
     async fn close(&self) -> Result<()> {
         self.inner.close().await
     }
 
+    async fn hibernate(&self) -> Result<()> {
+        self.inner.hibernate().await
+    }
+
     fn disk_usage_bytes(&self) -> u64 {
         let version = self.inner.version_control().current();
         version
@@ -139,6 +156,90 @@ impl<S: LogStore> Region for RegionImpl<S> {
     async fn flush(&self, ctx: &FlushContext) -> Result<()> {
         self.inner.flush(ctx).await
     }
+
+    async fn unfence(&self) -> Result<()> {
+        self.inner.writer.unfence().await
+    }
+
+    async fn compact(&self, ctx: &CompactContext) -> Result<()> {
+        self.inner.compact(ctx.clone()).await
+    }
+
+    async fn purge_expired(&self, ctx: &PurgeContext) -> Result<PurgeReport> {
+        self.inner.purge_expired(ctx).await
+    }
+
+    async fn drop_range(&self, ctx: &DropRangeContext) -> Result<DropRangeReport> {
+        self.inner.drop_range(ctx).await
+    }
+
+    async fn prepare_attach(&self, ctx: &AttachContext) -> Result<PreparedAttach> {
+        self.inner.prepare_attach(ctx).await
+    }
+
+    async fn commit_attach(&self, prepared: PreparedAttach) -> Result<AttachReport> {
+        self.inner.commit_attach(&prepared).await
+    }
+
+    async fn abort_attach(&self, prepared: PreparedAttach) -> Result<()> {
+        self.inner.abort_attach(&prepared).await;
+        Ok(())
+    }
+
+    async fn scrub(&self) -> Result<RegionHealthReport> {
+        let report =
+            scrub::check_region_health(&self.inner.manifest, &self.inner.sst_layer).await?;
+        logging::info!(
+            "Scrubbed region {}, healthy: {}, report: {:?}",
+            self.inner.shared.name,
+            report.is_healthy(),
+            report
+        );
+        Ok(report)
+    }
+
+    async fn clone_data_from(&self, source: &Self) -> Result<()> {
+        // Flushes the source so all its rows are in SSTs.
+        source
+            .inner
+            .flush(&FlushContext {
+                wait: true,
+                fence: false,
+            })
+            .await?;
+        self.inner.clone_data_from(&source.inner).await
+    }
+
+    fn subscribe(&self) -> Result<BoxStream<'static, Result<ChangeBatch>>> {
+        let region = self.inner.shared.name.clone();
+        let mut receiver = self.inner.shared.changes.subscribe();
+        let stream = async_stream::try_stream! {
+            loop {
+                match receiver.recv().await {
+                    Ok(change) => yield change,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(skipped)) => {
+                        error::SubscriberLaggedSnafu {
+                            region: &region,
+                            skipped,
+                        }
+                        .fail()?;
+                    }
+                }
+            }
+        };
+
+        Ok(Box::pin(stream))
+    }
+
+    fn write_throttle(&self) -> WriteThrottle {
+        let version = self.inner.version_control().current();
+        let memtables = version.memtables();
+        self.inner.flush_strategy.write_throttle(
+            memtables.mutable_bytes_allocated(),
+            memtables.total_bytes_allocated(),
+        )
+    }
 }
 
 /// Storage related config for region.
@@ -157,28 +258,17 @@ pub struct StoreConfig<S: LogStore> {
     pub file_purger: FilePurgerRef,
     pub ttl: Option<Duration>,
     pub compaction_time_window: Option<i64>,
+    /// Whether the region only appends rows, without deduplicating rows with the same key.
+    pub append_mode: bool,
+    /// Whether to encode string columns of SSTs with dictionaries and prefix compression.
+    pub compact_strings: bool,
+    /// Encodings of columns in SSTs overriding the default ones.
+    pub column_encodings: ColumnEncodings,
 }
 
 pub type RecoverdMetadata = (SequenceNumber, (ManifestVersion, RawRegionMetadata));
 pub type RecoveredMetadataMap = BTreeMap<SequenceNumber, (ManifestVersion, RawRegionMetadata)>;
 
-#[derive(Debug)]
-pub struct CompactContext {
-    /// Whether to wait the compaction result.
-    pub wait: bool,
-    /// Max file number in level 0.
-    pub max_files_in_l0: usize,
-}
-
-impl Default for CompactContext {
-    fn default() -> CompactContext {
-        CompactContext {
-            wait: true,
-            max_files_in_l0: 1,
-        }
-    }
-}
-
 impl<S: LogStore> RegionImpl<S> {
     /// Create a new region and also persist the region metadata to manifest.
     ///
@@ -228,11 +318,14 @@ impl<S: LogStore> RegionImpl<S> {
         let wal = Wal::new(id, store_config.log_store);
 
         let inner = Arc::new(RegionInner {
-            shared: Arc::new(SharedData {
+            shared: Arc::new(SharedData::new(
                 id,
                 name,
-                version_control: Arc::new(version_control),
-            }),
+                Arc::new(version_control),
+                store_config.append_mode,
+                store_config.compact_strings,
+                store_config.column_encodings,
+            )),
             writer: Arc::new(RegionWriter::new(
                 store_config.memtable_builder,
                 store_config.engine_config.clone(),
@@ -308,11 +401,14 @@ impl<S: LogStore> RegionImpl<S> {
 
         let wal = Wal::new(metadata.id(), store_config.log_store);
         wal.obsolete(flushed_sequence).await?;
-        let shared = Arc::new(SharedData {
-            id: metadata.id(),
+        let shared = Arc::new(SharedData::new(
+            metadata.id(),
             name,
             version_control,
-        });
+            store_config.append_mode,
+            store_config.compact_strings,
+            store_config.column_encodings,
+        ));
         let compaction_time_window = store_config
             .compaction_time_window
             .or(opts.compaction_time_window);
@@ -362,6 +458,27 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.shared.id()
     }
 
+    /// Returns handles of all SST files in current version.
+    pub(crate) fn sst_files(&self) -> Vec<FileHandle> {
+        let version = self.inner.version_control().current();
+        version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files().cloned())
+            .collect()
+    }
+
+    #[inline]
+    pub(crate) fn sst_layer(&self) -> &AccessLayerRef {
+        &self.inner.sst_layer
+    }
+
+    /// Flushes the region if required by its flush strategy.
+    pub(crate) async fn flush_if_needed(&self) -> Result<()> {
+        self.inner.flush_if_needed().await
+    }
+
     fn create_version_with_checkpoint(
         checkpoint: RegionCheckpoint,
         memtable_builder: &MemtableBuilderRef,
@@ -394,6 +511,7 @@ impl<S: LogStore> RegionImpl<S> {
                 v.flushed_sequence,
                 v.manifest_version,
                 v.files.into_values(),
+                v.tombstones,
             );
         }
 
@@ -491,6 +609,7 @@ impl<S: LogStore> RegionImpl<S> {
             let edit = VersionEdit {
                 files_to_add: e.files_to_add,
                 files_to_remove: e.files_to_remove,
+                tombstones_to_add: e.tombstones_to_add,
                 flushed_sequence: e.flushed_sequence,
                 manifest_version,
                 max_memtable_id: None,
@@ -503,11 +622,6 @@ impl<S: LogStore> RegionImpl<S> {
             version
         }
     }
-
-    /// Compact the region manually.
-    pub async fn compact(&self, ctx: CompactContext) -> Result<()> {
-        self.inner.compact(ctx).await
-    }
 }
 
 // Private methods for tests.
@@ -554,9 +668,52 @@ pub struct SharedData {
     name: String,
     // TODO(yingwen): Maybe no need to use Arc for version control.
     pub version_control: VersionControlRef,
+    /// Sender to publish committed changes to subscribers of the region.
+    changes: broadcast::Sender<ChangeBatch>,
+    /// Whether the region only appends rows, without deduplicating rows with the same key.
+    append_mode: bool,
+    /// Whether to encode string columns of SSTs with dictionaries and prefix compression.
+    compact_strings: bool,
+    /// Encodings of columns in SSTs overriding the default ones.
+    column_encodings: ColumnEncodings,
 }
 
 impl SharedData {
+    fn new(
+        id: RegionId,
+        name: String,
+        version_control: VersionControlRef,
+        append_mode: bool,
+        compact_strings: bool,
+        column_encodings: ColumnEncodings,
+    ) -> SharedData {
+        let (changes, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        SharedData {
+            id,
+            name,
+            version_control,
+            changes,
+            append_mode,
+            compact_strings,
+            column_encodings,
+        }
+    }
+
+    #[inline]
+    pub fn append_mode(&self) -> bool {
+        self.append_mode
+    }
+
+    #[inline]
+    pub fn compact_strings(&self) -> bool {
+        self.compact_strings
+    }
+
+    #[inline]
+    pub fn column_encodings(&self) -> &ColumnEncodings {
+        &self.column_encodings
+    }
+
     #[inline]
     pub fn id(&self) -> RegionId {
         self.id
@@ -566,6 +723,25 @@ impl SharedData {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Publishes mutations in the `payload` committed with `sequence` to subscribers.
+    pub(crate) fn publish_changes(&self, sequence: SequenceNumber, payload: &Payload) {
+        // Avoid cloning the columns if nobody is interested in the changes.
+        if self.changes.receiver_count() == 0 {
+            return;
+        }
+
+        for mutation in &payload.mutations {
+            let change = ChangeBatch {
+                sequence,
+                op_type: mutation.op_type,
+                schema: payload.schema.clone(),
+                columns: mutation.record_batch.columns().to_vec(),
+            };
+            // The send only fails if all receivers are dropped, so we can ignore it.
+            let _ = self.changes.send(change);
+        }
+    }
 }
 
 pub type SharedDataRef = Arc<SharedData>;
@@ -598,6 +774,7 @@ impl<S: LogStore> RegionInner<S> {
         let sequence = self.version_control().committed_sequence();
 
         SnapshotImpl::new(version, sequence, self.sst_layer.clone())
+            .with_append_mode(self.shared.append_mode())
     }
 
     fn compat_write_batch(&self, request: &mut WriteBatch) -> Result<()> {
@@ -648,6 +825,31 @@ impl<S: LogStore> RegionInner<S> {
         self.manifest.stop().await
     }
 
+    async fn hibernate(&self) -> Result<()> {
+        // Flushing frees the memtables, the new mutable memtable is empty.
+        self.flush(&FlushContext {
+            wait: true,
+            fence: false,
+        })
+        .await?;
+
+        let version = self.version_control().current();
+        let files: Vec<_> = version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files().cloned())
+            .collect();
+        self.sst_layer.evict_cached_ssts(&files);
+
+        logging::info!(
+            "Region {} hibernated, evicted cached data of {} SSTs",
+            self.shared.name,
+            files.len()
+        );
+        Ok(())
+    }
+
     async fn flush(&self, ctx: &FlushContext) -> Result<()> {
         let writer_ctx = WriterContext {
             shared: &self.shared,
@@ -662,6 +864,20 @@ impl<S: LogStore> RegionInner<S> {
         self.writer.flush(writer_ctx, ctx).await
     }
 
+    async fn flush_if_needed(&self) -> Result<()> {
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            compaction_scheduler: &self.compaction_scheduler,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+        self.writer.flush_if_needed(writer_ctx).await
+    }
+
     /// Compact the region manually.
     async fn compact(&self, ctx: CompactContext) -> Result<()> {
         let writer_ctx = WriterContext {
@@ -676,4 +892,121 @@ impl<S: LogStore> RegionInner<S> {
         };
         self.writer.compact(writer_ctx, ctx).await
     }
+
+    /// Purge the expired SSTs of the region.
+    async fn purge_expired(&self, ctx: &PurgeContext) -> Result<PurgeReport> {
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            compaction_scheduler: &self.compaction_scheduler,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+        self.writer.purge_expired(writer_ctx, ctx).await
+    }
+
+    /// Drop data of the region within a time range.
+    async fn drop_range(&self, ctx: &DropRangeContext) -> Result<DropRangeReport> {
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            compaction_scheduler: &self.compaction_scheduler,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+        self.writer.drop_range(writer_ctx, ctx).await
+    }
+
+    /// Validates the SSTs staged in the object store and rewrites them into the region
+    /// without holding the writer lock, the files are invisible until committed.
+    async fn prepare_attach(&self, ctx: &AttachContext) -> Result<PreparedAttach> {
+        let version = self.version_control().current();
+        let mut prepared = PreparedAttach {
+            region_version: version.metadata().version(),
+            staged_paths: self.sst_layer.list_staged_ssts(&ctx.staging_dir).await?,
+            files: Vec::new(),
+        };
+        let store_schema = version.metadata().schema().store_schema().clone();
+        for path in &prepared.staged_paths {
+            let file_id = FileId::random();
+            match self
+                .sst_layer
+                .attach_sst(path, file_id, &store_schema)
+                .await
+            {
+                Ok(attached) => prepared.files.push(PreparedFile {
+                    file_id: file_id.to_string(),
+                    file_size: attached.file_size,
+                    time_range: attached.time_range,
+                    max_sequence: attached.max_sequence,
+                }),
+                Err(e) => {
+                    self.abort_attach(&prepared).await;
+                    // The rewritten file may be partially written.
+                    self.delete_attached_sst(file_id).await;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(prepared)
+    }
+
+    /// Removes the SSTs rewritten by [RegionInner::prepare_attach], the staged files are kept.
+    async fn abort_attach(&self, prepared: &PreparedAttach) {
+        for file in &prepared.files {
+            match file.file_id.parse() {
+                Ok(file_id) => self.delete_attached_sst(file_id).await,
+                Err(e) => logging::warn!("Invalid attached SST {}, err: {}", file.file_id, e),
+            }
+        }
+    }
+
+    async fn delete_attached_sst(&self, file_id: FileId) {
+        if let Err(e) = self.sst_layer.delete_sst(file_id, StorageTier::Hot).await {
+            logging::warn!("Failed to delete attached SST {}, err: {}", file_id, e);
+        }
+    }
+
+    /// Adds the SSTs prepared by [RegionInner::prepare_attach] to the region.
+    async fn commit_attach(&self, prepared: &PreparedAttach) -> Result<AttachReport> {
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            compaction_scheduler: &self.compaction_scheduler,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+        self.writer.commit_attach(writer_ctx, prepared).await
+    }
+
+    /// Clone data of the `source` region into the region.
+    async fn clone_data_from(&self, source: &RegionInner<S>) -> Result<()> {
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            compaction_scheduler: &self.compaction_scheduler,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+        // Holding the version keeps the SSTs of the source alive until they are
+        // referenced by the region.
+        let source_version = source.version_control().current();
+        let source_dir = source.sst_layer.sst_file_path("");
+        let source_sequence = source.version_control().committed_sequence();
+        self.writer
+            .clone_data_from(writer_ctx, &source_version, &source_dir, source_sequence)
+            .await
+    }
 }
@@ -118,7 +118,9 @@ impl AlterTester {
     }
 
     async fn flush(&self, wait: Option<bool>) {
-        let ctx = wait.map(|wait| FlushContext { wait }).unwrap_or_default();
+        let ctx = wait
+            .map(|wait| FlushContext { wait, fence: false })
+            .unwrap_or_default();
         self.base().region.flush(&ctx).await.unwrap();
     }
 
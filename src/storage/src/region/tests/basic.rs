@@ -16,8 +16,9 @@
 
 use common_telemetry::info;
 use common_test_util::temp_dir::create_temp_dir;
+use futures::StreamExt;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
-use store_api::storage::{OpenOptions, SequenceNumber, WriteResponse};
+use store_api::storage::{OpType, OpenOptions, Region, SequenceNumber, WriteResponse};
 
 use crate::error::Result;
 use crate::region::tests::{self, FileTesterBase};
@@ -274,3 +275,27 @@ async fn test_put_delete_absent_key() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_subscribe_changes() {
+    let dir = create_temp_dir("subscribe-changes");
+    let store_dir = dir.path().to_str().unwrap();
+    let tester = Tester::new(REGION_NAME, store_dir).await;
+
+    // Changes committed before subscribing are not published.
+    tester.put(&[(1000, Some(100))]).await;
+
+    let mut changes = tester.base().region.subscribe().unwrap();
+    tester.put(&[(1001, Some(101)), (1002, None)]).await;
+    tester.delete(&[1000]).await;
+
+    let change = changes.next().await.unwrap().unwrap();
+    assert_eq!(2, change.sequence);
+    assert_eq!(OpType::Put, change.op_type);
+    assert_eq!(2, change.columns[0].len());
+
+    let change = changes.next().await.unwrap().unwrap();
+    assert_eq!(3, change.sequence);
+    assert_eq!(OpType::Delete, change.op_type);
+    assert_eq!(1, change.columns[0].len());
+}
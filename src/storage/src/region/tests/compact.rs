@@ -17,13 +17,23 @@
 use std::env;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_telemetry::logging;
 use common_test_util::temp_dir::create_temp_dir;
+use common_time::Timestamp;
+use datatypes::arrow::array::{Int64Array, TimestampMillisecondArray};
+use datatypes::arrow::datatypes::{DataType, Field, Schema as ArrowSchema, TimeUnit};
+use datatypes::arrow::record_batch::RecordBatch;
+use datatypes::type_id::LogicalTypeId;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use object_store::services::{Fs, S3};
 use object_store::ObjectStore;
-use store_api::storage::{FlushContext, Region, WriteResponse};
+use parquet::arrow::ArrowWriter;
+use store_api::storage::{
+    AttachContext, AttachReport, CompactContext, DropRangeContext, DropRangeReport, FlushContext,
+    PurgeContext, PurgeReport, Region, WriteResponse,
+};
 use tokio::sync::Notify;
 
 use crate::compaction::{CompactionHandler, SimplePicker};
@@ -31,13 +41,15 @@ use crate::config::EngineConfig;
 use crate::error::Result;
 use crate::file_purger::{FilePurgeHandler, FilePurgeRequest};
 use crate::region::tests::{self, FileTesterBase};
-use crate::region::{CompactContext, FlushStrategyRef, RegionImpl};
+use crate::region::{FlushStrategyRef, RegionImpl};
 use crate::scheduler::rate_limit::BoxedRateLimitToken;
 use crate::scheduler::{Handler, LocalScheduler, SchedulerConfig};
-use crate::test_util::config_util;
+use crate::test_util::descriptor_util::RegionDescBuilder;
 use crate::test_util::flush_switch::FlushSwitch;
+use crate::test_util::{self, config_util};
 
 const REGION_NAME: &str = "region-compact-0";
+const CLONE_REGION_NAME: &str = "region-compact-clone-1";
 
 fn new_object_store(store_dir: &str, s3_bucket: Option<String>) -> ObjectStore {
     if let Some(bucket) = s3_bucket {
@@ -75,6 +87,7 @@ async fn create_region_for_compaction<
     purge_handler: H,
     flush_strategy: FlushStrategyRef,
     s3_bucket: Option<String>,
+    ttl: Option<Duration>,
 ) -> (RegionImpl<RaftEngineLogStore>, ObjectStore) {
     let metadata = tests::new_metadata(REGION_NAME, enable_version_column);
 
@@ -88,6 +101,7 @@ async fn create_region_for_compaction<
     .await;
     store_config.engine_config = Arc::new(engine_config);
     store_config.flush_strategy = flush_strategy;
+    store_config.ttl = ttl;
 
     let picker = SimplePicker::default();
     let handler = CompactionHandler::new(picker);
@@ -107,6 +121,30 @@ async fn create_region_for_compaction<
     )
 }
 
+/// Create a new region with the same columns as the region for compaction test.
+async fn create_region_to_clone_into(
+    store_dir: &str,
+    object_store: ObjectStore,
+) -> RegionImpl<RaftEngineLogStore> {
+    let desc = RegionDescBuilder::new(CLONE_REGION_NAME)
+        .id(1)
+        .enable_version_column(false)
+        .push_field_column(("v0", LogicalTypeId::Int64, true))
+        .build();
+
+    let mut store_config =
+        config_util::new_store_config_with_object_store(CLONE_REGION_NAME, store_dir, object_store)
+            .await;
+    store_config.file_purger = Arc::new(LocalScheduler::new(
+        SchedulerConfig::default(),
+        FilePurgeHandler,
+    ));
+
+    RegionImpl::create(desc.try_into().unwrap(), store_config)
+        .await
+        .unwrap()
+}
+
 #[derive(Debug, Default, Clone)]
 struct MockFilePurgeHandler {
     num_deleted: Arc<AtomicUsize>,
@@ -159,6 +197,7 @@ impl CompactionTester {
         engine_config: EngineConfig,
         flush_strategy: FlushStrategyRef,
         s3_bucket: Option<String>,
+        ttl: Option<Duration>,
     ) -> CompactionTester {
         let purge_handler = MockFilePurgeHandler::default();
         let (region, object_store) = create_region_for_compaction(
@@ -168,6 +207,7 @@ impl CompactionTester {
             purge_handler.clone(),
             flush_strategy,
             s3_bucket,
+            ttl,
         )
         .await;
 
@@ -193,7 +233,9 @@ impl CompactionTester {
     }
 
     async fn flush(&self, wait: Option<bool>) {
-        let ctx = wait.map(|wait| FlushContext { wait }).unwrap_or_default();
+        let ctx = wait
+            .map(|wait| FlushContext { wait, fence: false })
+            .unwrap_or_default();
         self.base().region.flush(&ctx).await.unwrap();
     }
 
@@ -201,11 +243,31 @@ impl CompactionTester {
         // Trigger compaction and wait until it is done.
         self.base()
             .region
-            .compact(CompactContext::default())
+            .compact(&CompactContext::default())
             .await
             .unwrap();
     }
 
+    async fn purge_expired(&self, dry_run: bool) -> PurgeReport {
+        self.base()
+            .region
+            .purge_expired(&PurgeContext { dry_run })
+            .await
+            .unwrap()
+    }
+
+    async fn drop_range(&self, start: i64, end: i64, dry_run: bool) -> DropRangeReport {
+        self.base()
+            .region
+            .drop_range(&DropRangeContext {
+                start: Timestamp::new_millisecond(start),
+                end: Timestamp::new_millisecond(end),
+                dry_run,
+            })
+            .await
+            .unwrap()
+    }
+
     /// Close region and clean up files.
     async fn clean_up(mut self) {
         self.base = None;
@@ -228,6 +290,7 @@ async fn compact_during_read(s3_bucket: Option<String>) {
         // Disable auto-flush.
         Arc::new(FlushSwitch::default()),
         s3_bucket,
+        None,
     )
     .await;
 
@@ -281,3 +344,300 @@ async fn test_compact_during_read_on_s3() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_purge_expired() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("purge_expired");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let tester = CompactionTester::new(
+        store_dir,
+        EngineConfig {
+            max_files_in_l0: 100,
+            ..Default::default()
+        },
+        // Disable auto-flush.
+        Arc::new(FlushSwitch::default()),
+        None,
+        Some(Duration::from_secs(3600)),
+    )
+    .await;
+
+    // Timestamps near the epoch are already expired.
+    let data: Vec<_> = (0..100).map(|v| (v, Some(v))).collect();
+    tester.put(&data).await;
+    tester.flush(None).await;
+
+    let report = tester.purge_expired(true).await;
+    assert_eq!(1, report.num_files);
+    assert!(report.file_size > 0);
+    assert_eq!(
+        Some((
+            Timestamp::new_millisecond(0),
+            Timestamp::new_millisecond(99)
+        )),
+        report.time_range
+    );
+    // Dry run keeps the files.
+    assert_eq!(data, tester.base().full_scan().await);
+
+    assert_eq!(report, tester.purge_expired(false).await);
+    assert!(tester.base().full_scan().await.is_empty());
+    assert_eq!(PurgeReport::default(), tester.purge_expired(true).await);
+
+    tester.clean_up().await;
+}
+
+#[tokio::test]
+async fn test_drop_range() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("drop_range");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let tester = CompactionTester::new(
+        store_dir,
+        EngineConfig {
+            max_files_in_l0: 100,
+            ..Default::default()
+        },
+        // Disable auto-flush.
+        Arc::new(FlushSwitch::default()),
+        None,
+        None,
+    )
+    .await;
+
+    let data: Vec<_> = (0..250).map(|v| (v, Some(v))).collect();
+    // SST1 [0, 99], SST2 [100, 199] and rows [200, 249] in the memtable.
+    tester.put(&data[..100]).await;
+    tester.flush(None).await;
+    tester.put(&data[100..200]).await;
+    tester.flush(None).await;
+    tester.put(&data[200..]).await;
+
+    // Dry run only reports SSTs.
+    let report = tester.drop_range(50, 199, true).await;
+    assert_eq!(1, report.removed_files);
+    assert!(report.removed_file_size > 0);
+    assert_eq!(1, report.masked_files);
+    assert_eq!(data, tester.base().full_scan().await);
+
+    // Rows in the memtable are flushed to SST3 [200, 249] and masked.
+    let report = tester.drop_range(50, 220, false).await;
+    assert_eq!(1, report.removed_files);
+    assert_eq!(2, report.masked_files);
+    let expect: Vec<_> = data[..50].iter().chain(&data[221..]).cloned().collect();
+    assert_eq!(expect, tester.base().full_scan().await);
+
+    // Rows written after dropping are visible.
+    tester.put(&[(60, Some(600))]).await;
+    let mut expect_after_put = expect.clone();
+    expect_after_put.insert(50, (60, Some(600)));
+    assert_eq!(expect_after_put, tester.base().full_scan().await);
+
+    // Compaction removes masked rows physically and keeps the result.
+    tester.flush(None).await;
+    tester.compact().await;
+    assert_eq!(expect_after_put, tester.base().full_scan().await);
+
+    tester.clean_up().await;
+}
+
+#[tokio::test]
+async fn test_clone_data() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("clone_data");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let tester = CompactionTester::new(
+        store_dir,
+        EngineConfig {
+            max_files_in_l0: 100,
+            ..Default::default()
+        },
+        // Disable auto-flush.
+        Arc::new(FlushSwitch::default()),
+        None,
+        None,
+    )
+    .await;
+
+    let data: Vec<_> = (0..150).map(|v| (v, Some(v))).collect();
+    // SST1 [0, 99] and rows [100, 149] in the memtable.
+    tester.put(&data[..100]).await;
+    tester.flush(None).await;
+    tester.put(&data[100..]).await;
+
+    // The cloned region uses its own WAL.
+    let clone_dir = create_temp_dir("clone_data_wal");
+    let region = create_region_to_clone_into(
+        clone_dir.path().to_str().unwrap(),
+        tester.object_store.clone(),
+    )
+    .await;
+    region.clone_data_from(&tester.base().region).await.unwrap();
+    let clone = FileTesterBase::with_region(region);
+    assert_eq!(data, clone.full_scan().await);
+    // Rows in the memtable of the source are flushed to SST2, and both SSTs are shared.
+    let files = clone.region.sst_files();
+    assert_eq!(2, files.len());
+    assert!(files.iter().all(|f| f.meta().source_dir.is_some()));
+
+    // Cloning into a region with data is rejected.
+    assert!(clone
+        .region
+        .clone_data_from(&tester.base().region)
+        .await
+        .is_err());
+
+    // Writes to one region are invisible to the other.
+    clone.put(&[(0, Some(1000))]).await;
+    tester.put(&[(1, Some(1001))]).await;
+    let mut clone_expect = data.clone();
+    clone_expect[0] = (0, Some(1000));
+    let mut source_expect = data.clone();
+    source_expect[1] = (1, Some(1001));
+    assert_eq!(clone_expect, clone.full_scan().await);
+    assert_eq!(source_expect, tester.base().full_scan().await);
+
+    // Shared SSTs removed from the source are still readable by the clone.
+    let report = tester.drop_range(0, 149, false).await;
+    assert_eq!(3, report.removed_files);
+    assert!(tester.base().full_scan().await.is_empty());
+    assert_eq!(clone_expect, clone.full_scan().await);
+
+    drop(clone);
+    tester.clean_up().await;
+}
+
+/// Writes `rows` to a parquet file with the user columns of the region to clone into.
+fn new_external_parquet(rows: &[(i64, Option<i64>)]) -> Vec<u8> {
+    let schema = Arc::new(ArrowSchema::new(vec![
+        Field::new(
+            test_util::TIMESTAMP_NAME,
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            false,
+        ),
+        Field::new("v0", DataType::Int64, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(TimestampMillisecondArray::from_iter_values(
+                rows.iter().map(|(ts, _)| *ts),
+            )),
+            Arc::new(Int64Array::from_iter(rows.iter().map(|(_, v)| *v))),
+        ],
+    )
+    .unwrap();
+
+    let mut buf = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buf, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    buf
+}
+
+#[tokio::test]
+async fn test_attach_ssts() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("attach_ssts");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let tester = CompactionTester::new(
+        store_dir,
+        EngineConfig {
+            max_files_in_l0: 100,
+            ..Default::default()
+        },
+        // Disable auto-flush.
+        Arc::new(FlushSwitch::default()),
+        None,
+        None,
+    )
+    .await;
+
+    let data: Vec<_> = (0..100).map(|v| (v, Some(v))).collect();
+    tester.put(&data).await;
+    tester.flush(None).await;
+
+    // Stages the SSTs of the tester as if they are written elsewhere.
+    let staging_dir = "staging/";
+    let object_store = &tester.object_store;
+    for file in tester.base().region.sst_files() {
+        let content = object_store.read(&file.file_path()).await.unwrap();
+        object_store
+            .write(&format!("{staging_dir}{}", file.file_name()), content)
+            .await
+            .unwrap();
+    }
+
+    let attach_dir = create_temp_dir("attach_ssts_wal");
+    let region =
+        create_region_to_clone_into(attach_dir.path().to_str().unwrap(), object_store.clone())
+            .await;
+    let region = FileTesterBase::with_region(region);
+    region.put(&[(200, Some(200))]).await;
+
+    let ctx = AttachContext {
+        staging_dir: staging_dir.to_string(),
+    };
+    let report = region.region.attach_ssts(&ctx).await.unwrap();
+    assert_eq!(1, report.num_files);
+    assert!(report.file_size > 0);
+    assert_eq!(
+        Some((
+            Timestamp::new_millisecond(0),
+            Timestamp::new_millisecond(99)
+        )),
+        report.time_range
+    );
+    let mut expect = data.clone();
+    expect.push((200, Some(200)));
+    assert_eq!(expect, region.full_scan().await);
+    // Staged files are removed once attached.
+    assert_eq!(
+        AttachReport::default(),
+        region.region.attach_ssts(&ctx).await.unwrap()
+    );
+
+    // Files that are not parquet are rejected.
+    object_store
+        .write(&format!("{staging_dir}invalid.parquet"), vec![1, 2, 3])
+        .await
+        .unwrap();
+    assert!(region.region.attach_ssts(&ctx).await.is_err());
+    assert_eq!(expect, region.full_scan().await);
+    object_store
+        .delete(&format!("{staging_dir}invalid.parquet"))
+        .await
+        .unwrap();
+
+    // Parquet files written by other tools only need the user columns, but their rows
+    // must be sorted.
+    let unsorted = new_external_parquet(&[(301, None), (300, Some(300))]);
+    object_store
+        .write(&format!("{staging_dir}external.parquet"), unsorted)
+        .await
+        .unwrap();
+    assert!(region.region.attach_ssts(&ctx).await.is_err());
+    assert_eq!(expect, region.full_scan().await);
+
+    let sorted = new_external_parquet(&[(300, Some(300)), (301, None)]);
+    object_store
+        .write(&format!("{staging_dir}external.parquet"), sorted)
+        .await
+        .unwrap();
+    let report = region.region.attach_ssts(&ctx).await.unwrap();
+    assert_eq!(1, report.num_files);
+    expect.extend([(300, Some(300)), (301, None)]);
+    assert_eq!(expect, region.full_scan().await);
+
+    drop(region);
+    tester.clean_up().await;
+}
@@ -18,14 +18,17 @@ use std::sync::Arc;
 
 use common_test_util::temp_dir::create_temp_dir;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
-use store_api::storage::{FlushContext, OpenOptions, Region, WriteResponse};
+use store_api::storage::{
+    FlushContext, HealthIssueKind, OpenOptions, ReadContext, Region, ScanRequest, Snapshot,
+    WriteResponse,
+};
 
-use crate::engine;
-use crate::flush::FlushStrategyRef;
+use crate::flush::{FlushStrategyRef, SizeBasedStrategy};
 use crate::region::tests::{self, FileTesterBase};
 use crate::region::RegionImpl;
 use crate::test_util::config_util;
 use crate::test_util::flush_switch::{has_parquet_file, FlushSwitch};
+use crate::{engine, scrub};
 
 const REGION_NAME: &str = "region-flush-0";
 
@@ -92,9 +95,22 @@ impl FlushTester {
     }
 
     async fn flush(&self, wait: Option<bool>) {
-        let ctx = wait.map(|wait| FlushContext { wait }).unwrap_or_default();
+        let ctx = wait
+            .map(|wait| FlushContext { wait, fence: false })
+            .unwrap_or_default();
         self.base().region.flush(&ctx).await.unwrap();
     }
+
+    /// Returns number of files and estimated bytes to read in a full scan.
+    async fn scan_cost(&self) -> (usize, u64) {
+        let read_ctx = ReadContext::default();
+        let snapshot = self.base().region.snapshot(&read_ctx).unwrap();
+        let resp = snapshot
+            .scan(&read_ctx, ScanRequest::default())
+            .await
+            .unwrap();
+        (resp.num_files, resp.estimated_bytes)
+    }
 }
 
 #[tokio::test]
@@ -251,3 +267,237 @@ async fn test_merge_read_after_flush() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_scan_cost_after_flush() {
+    let dir = create_temp_dir("scan-cost-flush");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    let (num_files, estimated_bytes) = tester.scan_cost().await;
+    assert_eq!(0, num_files);
+
+    tester.put(&[(1000, Some(100))]).await;
+    let (num_files, memtable_bytes) = tester.scan_cost().await;
+    assert_eq!(0, num_files);
+    assert!(memtable_bytes > estimated_bytes);
+
+    tester.flush(None).await;
+    tester.put(&[(2000, Some(200))]).await;
+    tester.flush(None).await;
+
+    let (num_files, estimated_bytes) = tester.scan_cost().await;
+    assert_eq!(2, num_files);
+    assert!(estimated_bytes > 0);
+}
+
+#[tokio::test]
+async fn test_scrub_after_flush() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("scrub-flush");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    tester.flush(None).await;
+
+    let region = &tester.base().region;
+    let report = scrub::scrub_region(region).await;
+    assert_eq!(1, report.files);
+    assert!(report.corrupted.is_empty());
+
+    // Corrupt the flushed file.
+    let file = region.sst_files().pop().unwrap();
+    let path = format!(
+        "{}/{}",
+        store_dir,
+        region.sst_layer().sst_file_path(&file.file_name())
+    );
+    let mut content = std::fs::read(&path).unwrap();
+    content[0] = !content[0];
+    std::fs::write(&path, content).unwrap();
+
+    let report = scrub::scrub_region(region).await;
+    assert_eq!(1, report.files);
+    assert_eq!(vec![file.file_id()], report.corrupted);
+}
+
+#[tokio::test]
+async fn test_scrub_region_health() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("scrub-health");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.flush(None).await;
+    tester.put(&[(3000, Some(300))]).await;
+    tester.flush(None).await;
+
+    let region = &tester.base().region;
+    let report = region.scrub().await.unwrap();
+    assert!(report.is_healthy(), "{report:?}");
+    assert_eq!(2, report.num_files);
+
+    // Remove one flushed file.
+    let file = region.sst_files().pop().unwrap();
+    let path = format!(
+        "{}/{}",
+        store_dir,
+        region.sst_layer().sst_file_path(&file.file_name())
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    let report = region.scrub().await.unwrap();
+    assert_eq!(2, report.num_files);
+    assert_eq!(1, report.issues.len());
+    let issue = &report.issues[0];
+    assert_eq!(HealthIssueKind::MissingFile, issue.kind);
+    assert_eq!(Some(file.file_name()), issue.file);
+}
+
+#[tokio::test]
+async fn test_flush_by_rows() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("flush-by-rows");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_strategy = Arc::new(SizeBasedStrategy::default().with_max_rows(Some(2)));
+    let tester = FlushTester::new(store_dir, flush_strategy).await;
+    let region = &tester.base().region;
+    let mutable_rows = || {
+        region
+            .inner
+            .version_control()
+            .current()
+            .memtables()
+            .mutable_memtable()
+            .num_rows()
+    };
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    region.flush_if_needed().await.unwrap();
+    // Rows in the memtable don't exceed the limit.
+    assert_eq!(2, mutable_rows());
+
+    tester.put(&[(3000, Some(300))]).await;
+    region.flush_if_needed().await.unwrap();
+    // The mutable memtable is frozen and flushed.
+    assert_eq!(0, mutable_rows());
+
+    tester.flush(Some(true)).await;
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    assert!(has_parquet_file(&sst_dir));
+}
+
+#[tokio::test]
+async fn test_hibernate_region() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("hibernate-region");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch).await;
+    let region = &tester.base().region;
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    region.hibernate().await.unwrap();
+
+    // Rows are flushed out of the memtables.
+    let version = region.inner.version_control().current();
+    assert_eq!(0, version.memtables().mutable_memtable().num_rows());
+    assert!(version.memtables().immutable_memtables().is_empty());
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    assert!(has_parquet_file(&sst_dir));
+
+    // The region is still readable and writable.
+    tester.put(&[(3000, Some(300))]).await;
+    let expect = vec![(1000, Some(100)), (2000, Some(200)), (3000, Some(300))];
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_fence_region() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("fence-region");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch).await;
+    let region = &tester.base().region;
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    region
+        .flush(&FlushContext {
+            wait: true,
+            fence: true,
+        })
+        .await
+        .unwrap();
+
+    // All rows are flushed, and the region rejects writes afterwards.
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    assert!(has_parquet_file(&sst_dir));
+    assert!(tester.base().try_put(&[(3000, Some(300))]).await.is_err());
+    let expect = vec![(1000, Some(100)), (2000, Some(200))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // The region accepts writes again after it's unfenced.
+    region.unfence().await.unwrap();
+    tester.put(&[(3000, Some(300))]).await;
+    let expect = vec![(1000, Some(100)), (2000, Some(200)), (3000, Some(300))];
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_append_mode_keeps_duplicate_rows() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("append-mode-flush");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.append_mode = true;
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let base = FileTesterBase::with_region(region);
+
+    // Rows with the same key are all kept in memtables.
+    base.put(&[(1000, Some(1)), (1000, Some(2))]).await;
+    base.put(&[(1000, Some(3)), (2000, Some(4))]).await;
+    let mut output = base.full_scan().await;
+    output.sort_unstable();
+    let expect = vec![
+        (1000, Some(1)),
+        (1000, Some(2)),
+        (1000, Some(3)),
+        (2000, Some(4)),
+    ];
+    assert_eq!(expect, output);
+
+    // And in SSTs after flush.
+    base.region
+        .flush(&FlushContext {
+            wait: true,
+            fence: false,
+        })
+        .await
+        .unwrap();
+    base.put(&[(1000, Some(5))]).await;
+    let mut output = base.full_scan().await;
+    output.sort_unstable();
+    let expect = vec![
+        (1000, Some(1)),
+        (1000, Some(2)),
+        (1000, Some(3)),
+        (1000, Some(5)),
+        (2000, Some(4)),
+    ];
+    assert_eq!(expect, output);
+}
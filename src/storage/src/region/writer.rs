@@ -13,17 +13,21 @@
 // limitations under the License.
 
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use common_base::readable_size::ReadableSize;
 use common_error::prelude::BoxedError;
 use common_telemetry::tracing::log::{debug, info};
 use common_telemetry::{error, logging};
+use common_time::Timestamp;
 use futures::TryStreamExt;
 use snafu::{ensure, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{Manifest, ManifestVersion, MetaAction};
-use store_api::storage::{AlterRequest, FlushContext, SequenceNumber, WriteContext, WriteResponse};
+use store_api::storage::{
+    AlterRequest, AttachReport, CompactContext, DropRangeContext, DropRangeReport, FlushContext,
+    PreparedAttach, PurgeContext, PurgeReport, SequenceNumber, WriteContext, WriteResponse,
+};
 use tokio::sync::{oneshot, Mutex};
 
 use crate::background::JobHandle;
@@ -37,11 +41,9 @@ use crate::manifest::action::{
 use crate::memtable::{Inserter, MemtableBuilderRef, MemtableId, MemtableRef};
 use crate::metadata::RegionMetadataRef;
 use crate::proto::wal::WalHeader;
-use crate::region::{
-    CompactContext, RecoverdMetadata, RecoveredMetadataMap, RegionManifest, SharedDataRef,
-};
+use crate::region::{RecoverdMetadata, RecoveredMetadataMap, RegionManifest, SharedDataRef};
 use crate::schema::compat::CompatWrite;
-use crate::sst::AccessLayerRef;
+use crate::sst::{AccessLayerRef, FileHandle, FileId, FileMeta, RangeTombstone, StorageTier};
 use crate::version::{VersionControl, VersionControlRef, VersionEdit, VersionRef};
 use crate::wal::Wal;
 use crate::write_batch::WriteBatch;
@@ -81,6 +83,10 @@ impl RegionWriter {
         }
     }
 
+    pub async fn set_ttl(&self, ttl: Option<Duration>) {
+        self.inner.lock().await.ttl = ttl;
+    }
+
     /// Write to region in the write lock.
     pub async fn write<S: LogStore>(
         &self,
@@ -134,6 +140,7 @@ impl RegionWriter {
 
         let files_to_add = edit.files_to_add.clone();
         let files_to_remove = edit.files_to_remove.clone();
+        let tombstones_to_add = edit.tombstones_to_add.clone();
         let flushed_sequence = edit.flushed_sequence;
 
         // Persist the meta action.
@@ -149,6 +156,7 @@ impl RegionWriter {
         let version_edit = VersionEdit {
             files_to_add,
             files_to_remove,
+            tombstones_to_add,
             flushed_sequence,
             manifest_version,
             max_memtable_id,
@@ -259,7 +267,8 @@ impl RegionWriter {
         {
             let mut inner = self.inner.lock().await;
 
-            if inner.is_closed() {
+            // A fenced region is not closed yet.
+            if inner.closed {
                 return Ok(());
             }
 
@@ -285,6 +294,10 @@ impl RegionWriter {
         ensure!(!inner.is_closed(), error::ClosedRegionSnafu);
 
         inner.manual_flush(writer_ctx).await?;
+        if ctx.fence {
+            // The lock is still held, so no write is accepted after the frozen memtables.
+            inner.fenced = true;
+        }
 
         if ctx.wait {
             if let Some(handle) = inner.flush_handle.take() {
@@ -295,6 +308,40 @@ impl RegionWriter {
         Ok(())
     }
 
+    /// Accepts the writes again after the region is fenced by [RegionWriter::flush].
+    pub async fn unfence(&self) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+
+        ensure!(!inner.closed, error::ClosedRegionSnafu);
+
+        inner.fenced = false;
+
+        Ok(())
+    }
+
+    /// Flushes the region if its flush strategy requires, e.g. the region hasn't been
+    /// flushed for a long time.
+    pub async fn flush_if_needed<S: LogStore>(
+        &self,
+        writer_ctx: WriterContext<'_, S>,
+    ) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+
+        if inner.is_closed() {
+            return Ok(());
+        }
+
+        if inner.should_flush(
+            writer_ctx.shared,
+            writer_ctx.version_control(),
+            writer_ctx.flush_strategy,
+        ) {
+            inner.trigger_flush(&writer_ctx).await?;
+        }
+
+        Ok(())
+    }
+
     /// Compact manually.
     pub async fn compact<S: LogStore>(
         &self,
@@ -310,6 +357,351 @@ impl RegionWriter {
             .await
     }
 
+    /// Purges SSTs expired by the TTL of the region. Only reports the expired SSTs
+    /// if `ctx.dry_run` is set.
+    pub async fn purge_expired<S: LogStore>(
+        &self,
+        writer_ctx: WriterContext<'_, S>,
+        ctx: &PurgeContext,
+    ) -> Result<PurgeReport> {
+        let inner = self.inner.lock().await;
+
+        ensure!(!inner.is_closed(), error::ClosedRegionSnafu);
+        let Some(ttl) = inner.ttl else { return Ok(PurgeReport::default()) };
+
+        let expire_time = Timestamp::current_millis()
+            .sub(ttl)
+            .context(error::TtlCalculationSnafu)?;
+        let version = writer_ctx.shared.version_control.current();
+        // Files under compaction are skipped, the compaction task removes them
+        // if they are expired.
+        let expired_ssts = version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.get_expired_files(&expire_time))
+            .filter(|file| !file.compacting())
+            .collect::<Vec<_>>();
+
+        let mut report = PurgeReport::default();
+        for file in &expired_ssts {
+            report.add_file(file.file_size(), *file.time_range());
+        }
+        if ctx.dry_run || expired_ssts.is_empty() {
+            return Ok(report);
+        }
+
+        // Prevents the compaction from picking these files.
+        expired_ssts.iter().for_each(|f| f.mark_compacting(true));
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: None,
+            files_to_add: Vec::new(),
+            files_to_remove: expired_ssts.iter().map(FileHandle::meta).collect(),
+            tombstones_to_add: Vec::new(),
+        };
+        let result = self
+            .write_edit_and_apply(
+                writer_ctx.wal,
+                writer_ctx.shared,
+                writer_ctx.manifest,
+                edit,
+                None,
+            )
+            .await;
+        expired_ssts.iter().for_each(|f| f.mark_compacting(false));
+        result?;
+
+        info!(
+            "Purged expired SSTs of region {}, report: {:?}",
+            writer_ctx.shared.name(),
+            report
+        );
+        Ok(report)
+    }
+
+    /// Drops all data within the time range of `ctx`. SSTs wholly within the range are
+    /// removed and rows of other overlapping SSTs are masked by a range tombstone. Only
+    /// reports the SSTs to drop if `ctx.dry_run` is set.
+    pub async fn drop_range<S: LogStore>(
+        &self,
+        writer_ctx: WriterContext<'_, S>,
+        ctx: &DropRangeContext,
+    ) -> Result<DropRangeReport> {
+        let mut inner = self.inner.lock().await;
+
+        ensure!(!inner.is_closed(), error::ClosedRegionSnafu);
+        ensure!(
+            ctx.start <= ctx.end,
+            error::InvalidDropRangeSnafu {
+                start: ctx.start,
+                end: ctx.end,
+            }
+        );
+
+        if !ctx.dry_run {
+            // Flushes all memtables so rows to drop are all in SSTs. The write lock
+            // ensures no rows are written before we add the tombstone.
+            inner.trigger_flush(&writer_ctx).await?;
+            if let Some(handle) = inner.flush_handle.take() {
+                handle.join().await?;
+            }
+        }
+
+        let version = writer_ctx.shared.version_control.current();
+        let range = RangeTombstone {
+            start: ctx.start,
+            end: ctx.end,
+            sequence: writer_ctx.shared.version_control.committed_sequence(),
+        };
+        let mut report = DropRangeReport::default();
+        let mut files_to_remove = Vec::new();
+        for file in version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files())
+        {
+            if !range.overlaps(file.time_range()) {
+                continue;
+            }
+            let contained = match *file.time_range() {
+                Some((start, end)) => range.start <= start && end <= range.end,
+                None => false,
+            };
+            // Files under compaction will be replaced by the compaction task, so
+            // we mask them instead.
+            if contained && !file.compacting() {
+                report.removed_files += 1;
+                report.removed_file_size += file.file_size();
+                files_to_remove.push(file.clone());
+            } else {
+                report.masked_files += 1;
+            }
+        }
+        if ctx.dry_run || (files_to_remove.is_empty() && report.masked_files == 0) {
+            return Ok(report);
+        }
+
+        // Prevents the compaction from picking these files.
+        files_to_remove.iter().for_each(|f| f.mark_compacting(true));
+        let tombstones_to_add = if report.masked_files > 0 {
+            vec![range]
+        } else {
+            Vec::new()
+        };
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: None,
+            files_to_add: Vec::new(),
+            files_to_remove: files_to_remove.iter().map(FileHandle::meta).collect(),
+            tombstones_to_add,
+        };
+        let result = self
+            .write_edit_and_apply(
+                writer_ctx.wal,
+                writer_ctx.shared,
+                writer_ctx.manifest,
+                edit,
+                None,
+            )
+            .await;
+        files_to_remove
+            .iter()
+            .for_each(|f| f.mark_compacting(false));
+        result?;
+
+        info!(
+            "Dropped range [{:?}, {:?}] of region {}, report: {:?}",
+            ctx.start,
+            ctx.end,
+            writer_ctx.shared.name(),
+            report
+        );
+        Ok(report)
+    }
+
+    /// Adds all SSTs of the `source` version to the region by referencing them from
+    /// `source_dir`, the SST directory of the source region. The region must be empty
+    /// and the memtables of the source must have been flushed.
+    pub async fn clone_data_from<S: LogStore>(
+        &self,
+        writer_ctx: WriterContext<'_, S>,
+        source: &VersionRef,
+        source_dir: &str,
+        source_sequence: SequenceNumber,
+    ) -> Result<()> {
+        let inner = self.inner.lock().await;
+
+        ensure!(!inner.is_closed(), error::ClosedRegionSnafu);
+
+        let version_control = &writer_ctx.shared.version_control;
+        let version = version_control.current();
+        let region = writer_ctx.shared.name();
+        ensure!(
+            version.metadata().columns() == source.metadata().columns(),
+            error::CloneColumnsMismatchSnafu {
+                region,
+                source_region: source.metadata().name(),
+            }
+        );
+        let is_empty = version.ssts().levels().iter().all(|l| l.file_num() == 0)
+            && version.memtables().num_memtables() == 1
+            && version.memtables().mutable_memtable().num_rows() == 0;
+        ensure!(is_empty, error::CloneToNonEmptyRegionSnafu { region });
+
+        let region_id = writer_ctx.shared.id();
+        let source_id = source.metadata().id();
+        let mut files_to_add = Vec::new();
+        for file in source
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files())
+        {
+            let meta = file.meta();
+            // Files already shared by the source still live in their original directory.
+            let dir = meta
+                .source_dir
+                .clone()
+                .unwrap_or_else(|| source_dir.to_string());
+            writer_ctx
+                .sst_layer
+                .add_sst_refs(meta.file_id, Some(&dir), &[source_id, region_id])
+                .await?;
+            files_to_add.push(FileMeta {
+                region_id,
+                source_dir: Some(dir),
+                ..meta
+            });
+        }
+
+        // Sequences of cloned rows must be visible to the region, and rows written to
+        // the region later must override them.
+        let sequence = version_control.committed_sequence().max(source_sequence);
+        version_control.set_committed_sequence(sequence);
+        let num_files = files_to_add.len();
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: Some(sequence),
+            files_to_add,
+            files_to_remove: Vec::new(),
+            tombstones_to_add: source.tombstones().to_vec(),
+        };
+        self.write_edit_and_apply(
+            writer_ctx.wal,
+            writer_ctx.shared,
+            writer_ctx.manifest,
+            edit,
+            None,
+        )
+        .await?;
+
+        info!(
+            "Cloned {} SSTs from region {} to region {}",
+            num_files,
+            source.metadata().name(),
+            region
+        );
+        Ok(())
+    }
+
+    /// Adds the prepared SSTs to the region by a single manifest edit, so either all of
+    /// them are visible or none.
+    /// The files are validated and copied before, so the lock is only held to flush the
+    /// memtables and write the edit.
+    ///
+    /// Rows of the attached files keep their sequences, rows with the same key already
+    /// in the region override them if they have larger sequences.
+    pub async fn commit_attach<S: LogStore>(
+        &self,
+        writer_ctx: WriterContext<'_, S>,
+        prepared: &PreparedAttach,
+    ) -> Result<AttachReport> {
+        let mut report = AttachReport::default();
+        if prepared.files.is_empty() {
+            return Ok(report);
+        }
+
+        let mut inner = self.inner.lock().await;
+
+        ensure!(!inner.is_closed(), error::ClosedRegionSnafu);
+
+        let version_control = &writer_ctx.shared.version_control;
+        let version = version_control.current();
+        ensure!(
+            version.metadata().version() == prepared.region_version,
+            error::AttachAfterAlterSnafu {
+                region: writer_ctx.shared.name(),
+            }
+        );
+
+        // Flushes all memtables so the flushed sequence could cover the attached rows.
+        // The write lock ensures no rows are written before we attach the files.
+        inner.trigger_flush(&writer_ctx).await?;
+        if let Some(handle) = inner.flush_handle.take() {
+            handle.join().await?;
+        }
+
+        let region_id = writer_ctx.shared.id();
+        let mut files_to_add = Vec::with_capacity(prepared.files.len());
+        let mut max_sequence = 0;
+        for file in &prepared.files {
+            let file_id = file.file_id.parse::<FileId>().map_err(|e| {
+                error::InvalidStagedSstSnafu {
+                    path: &file.file_id,
+                    reason: e.to_string(),
+                }
+                .build()
+            })?;
+            report.add_file(file.file_size, file.time_range);
+            max_sequence = max_sequence.max(file.max_sequence);
+            files_to_add.push(FileMeta {
+                region_id,
+                file_id,
+                time_range: file.time_range,
+                level: 0,
+                file_size: file.file_size,
+                checksums: None,
+                tier: StorageTier::Hot,
+                source_dir: None,
+            });
+        }
+
+        // Sequences of attached rows must be visible to the region.
+        let sequence = version_control.committed_sequence().max(max_sequence);
+        version_control.set_committed_sequence(sequence);
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: Some(sequence),
+            files_to_add,
+            files_to_remove: Vec::new(),
+            tombstones_to_add: Vec::new(),
+        };
+        self.write_edit_and_apply(
+            writer_ctx.wal,
+            writer_ctx.shared,
+            writer_ctx.manifest,
+            edit,
+            None,
+        )
+        .await?;
+
+        for path in &prepared.staged_paths {
+            if let Err(e) = writer_ctx.sst_layer.delete_staged_sst(path).await {
+                logging::warn!("Failed to delete staged SST {}, err: {}", path, e);
+            }
+        }
+
+        info!(
+            "Attached {} SSTs to region {}, report: {:?}",
+            prepared.files.len(),
+            writer_ctx.shared.name(),
+            report
+        );
+        Ok(report)
+    }
+
     /// Cancel flush task if any
     async fn cancel_flush(&self) -> Result<()> {
         let mut inner = self.inner.lock().await;
@@ -365,9 +757,15 @@ struct WriterInner {
     ///
     /// It should protected by upper mutex
     closed: bool,
+    /// `WriterInner` also rejects any writing while the fenced flag is set, but the flag
+    /// can be cleared to accept writes again.
+    fenced: bool,
     engine_config: Arc<EngineConfig>,
     ttl: Option<Duration>,
     compaction_time_window: Option<i64>,
+    /// Time of the last flush, or the time the writer is created if the region isn't
+    /// flushed yet.
+    last_flush_time: Instant,
 }
 
 impl WriterInner {
@@ -382,8 +780,10 @@ impl WriterInner {
             flush_handle: None,
             engine_config,
             closed: false,
+            fenced: false,
             ttl,
             compaction_time_window,
+            last_flush_time: Instant::now(),
         }
     }
 
@@ -428,6 +828,9 @@ impl WriterInner {
         // Update committed_sequence to make current batch visible. The `&mut self` of WriterInner
         // guarantees the writer is exclusive.
         version_control.set_committed_sequence(next_sequence);
+        writer_ctx
+            .shared
+            .publish_changes(next_sequence, request.payload());
 
         Ok(WriteResponse {})
     }
@@ -615,7 +1018,26 @@ impl WriterInner {
         let memtables = current.memtables();
         let mutable_bytes_allocated = memtables.mutable_bytes_allocated();
         let total_bytes_allocated = memtables.total_bytes_allocated();
-        flush_strategy.should_flush(shared, mutable_bytes_allocated, total_bytes_allocated)
+        if flush_strategy.should_flush(shared, mutable_bytes_allocated, total_bytes_allocated) {
+            return true;
+        }
+
+        let mutable_rows = memtables.mutable_memtable().num_rows();
+        if mutable_rows == 0 {
+            return false;
+        }
+        let since_last_flush = self.last_flush_time.elapsed();
+        let should_flush = flush_strategy.should_flush_mutable(mutable_rows, since_last_flush);
+        if should_flush {
+            logging::info!(
+                "Region should flush, region: {}, mutable_rows: {}, since_last_flush: {:?}",
+                shared.name(),
+                mutable_rows,
+                since_last_flush
+            );
+        }
+
+        should_flush
     }
 
     async fn trigger_flush<S: LogStore>(&mut self, ctx: &WriterContext<'_, S>) -> Result<()> {
@@ -674,6 +1096,7 @@ impl WriterInner {
             .schedule_flush(Box::new(flush_req))
             .await?;
         self.flush_handle = Some(flush_handle);
+        self.last_flush_time = Instant::now();
 
         Ok(())
     }
@@ -818,7 +1241,7 @@ impl WriterInner {
 
     #[inline]
     fn is_closed(&self) -> bool {
-        self.closed
+        self.closed || self.fenced
     }
 
     #[inline]
@@ -15,7 +15,7 @@
 //! Utilities for resolving schema compatibility problems.
 
 use datatypes::arrow::record_batch::RecordBatch;
-use datatypes::schema::SchemaRef;
+use datatypes::schema::{ColumnDefaultConstraint, SchemaRef};
 use datatypes::vectors::{Helper, VectorRef};
 use snafu::{ensure, OptionExt, ResultExt};
 
@@ -275,14 +275,27 @@ impl ReadAdapter {
     }
 
     fn source_columns_to_batch(&self, source: Vec<VectorRef>, num_rows: usize) -> Result<Batch> {
-        let column_schemas = self.dest_schema.schema_to_read().schema().column_schemas();
+        let schema_to_read = self.dest_schema.schema_to_read();
+        let column_schemas = schema_to_read.schema().column_schemas();
         let columns = self
             .indices_in_result
             .iter()
             .zip(column_schemas)
-            .map(|(index_opt, column_schema)| {
+            .zip(schema_to_read.columns())
+            .map(|((index_opt, column_schema), column)| {
                 if let Some(idx) = index_opt {
                     Ok(source[*idx].clone())
+                } else if let Some(backfill) = &column.backfill {
+                    // Uses the value recorded while adding the column.
+                    ColumnDefaultConstraint::Value(backfill.clone())
+                        .create_default_vector(
+                            &column_schema.data_type,
+                            column_schema.is_nullable(),
+                            num_rows,
+                        )
+                        .context(error::CreateDefaultToReadSnafu {
+                            column: &column_schema.name,
+                        })
                 } else {
                     let vector = column_schema
                         .create_default_vector(num_rows)
@@ -307,7 +320,7 @@ mod tests {
 
     use datatypes::data_type::ConcreteDataType;
     use datatypes::schema::Schema;
-    use store_api::storage::ColumnDescriptorBuilder;
+    use store_api::storage::{AddColumn, AlterOperation, AlterRequest, ColumnDescriptorBuilder};
 
     use super::*;
     use crate::error::Error;
@@ -490,6 +503,54 @@ mod tests {
         check_batch_with_null_padding(&batch, &new_batch, &[3]);
     }
 
+    #[test]
+    fn test_compat_backfill_new_column() {
+        // (k0, timestamp, v0) with version 0.
+        let metadata: RegionMetadata =
+            descriptor_util::desc_with_field_columns(tests::REGION_NAME, 1)
+                .try_into()
+                .unwrap();
+        let source_schema = metadata.schema().store_schema().clone();
+        let req = AlterRequest {
+            operation: AlterOperation::AddColumns {
+                columns: vec![AddColumn {
+                    desc: ColumnDescriptorBuilder::new(
+                        100,
+                        "v1",
+                        ConcreteDataType::timestamp_millisecond_datatype(),
+                    )
+                    .default_constraint(Some(ColumnDefaultConstraint::Function(
+                        "now()".to_string(),
+                    )))
+                    .build()
+                    .unwrap(),
+                    is_key: false,
+                }],
+            },
+            version: 0,
+        };
+        // (k0, timestamp, v0, v1) with version 1.
+        let metadata = metadata.alter(&req).unwrap();
+        let backfill = metadata.schema().store_schema().columns()[3]
+            .backfill
+            .clone()
+            .unwrap();
+
+        let projected_schema = Arc::new(ProjectedSchema::no_projection(metadata.schema().clone()));
+        let adapter = ReadAdapter::new(source_schema, projected_schema).unwrap();
+
+        // Old rows always read the value evaluated while adding the column.
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let batch = tests::new_batch_with_num_values(1);
+        let expect = ColumnDefaultConstraint::Value(backfill)
+            .create_default_vector(&ConcreteDataType::timestamp_millisecond_datatype(), true, 3)
+            .unwrap();
+        let new_batch = call_batch_from_parts(&adapter, &batch, 1);
+        assert_eq!(expect, *new_batch.column(3));
+        let new_batch = call_arrow_chunk_to_batch(&adapter, &batch);
+        assert_eq!(expect, *new_batch.column(3));
+    }
+
     #[test]
     fn test_compat_different_column() {
         // (k0, timestamp, v0, v1) with version 0.
@@ -537,7 +598,11 @@ mod tests {
     #[test]
     fn test_is_source_column_compatible() {
         let desc = new_column_desc_builder().build().unwrap();
-        let source = ColumnMetadata { cf_id: 1, desc };
+        let source = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
 
         // Same column is always compatible, also tests read nullable column
         // as a nullable column.
@@ -548,7 +613,11 @@ mod tests {
             .id(source.desc.id + 1)
             .build()
             .unwrap();
-        let dest = ColumnMetadata { cf_id: 1, desc };
+        let dest = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
         assert!(!is_source_column_compatible(&source, &dest).unwrap());
     }
 
@@ -556,13 +625,21 @@ mod tests {
     fn test_nullable_column_read_by_not_null() {
         let desc = new_column_desc_builder().build().unwrap();
         assert!(desc.is_nullable());
-        let source = ColumnMetadata { cf_id: 1, desc };
+        let source = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
 
         let desc = new_column_desc_builder()
             .is_nullable(false)
             .build()
             .unwrap();
-        let dest = ColumnMetadata { cf_id: 1, desc };
+        let dest = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
 
         let err = is_source_column_compatible(&source, &dest).unwrap_err();
         assert!(
@@ -577,30 +654,50 @@ mod tests {
             .is_nullable(false)
             .build()
             .unwrap();
-        let source = ColumnMetadata { cf_id: 1, desc };
+        let source = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
 
         let desc = new_column_desc_builder()
             .is_nullable(false)
             .build()
             .unwrap();
-        let not_null_dest = ColumnMetadata { cf_id: 1, desc };
+        let not_null_dest = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
         assert!(is_source_column_compatible(&source, &not_null_dest).unwrap());
 
         let desc = new_column_desc_builder().build().unwrap();
-        let null_dest = ColumnMetadata { cf_id: 1, desc };
+        let null_dest = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
         assert!(is_source_column_compatible(&source, &null_dest).unwrap());
     }
 
     #[test]
     fn test_read_column_with_different_name() {
         let desc = new_column_desc_builder().build().unwrap();
-        let source = ColumnMetadata { cf_id: 1, desc };
+        let source = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
 
         let desc = new_column_desc_builder()
             .name(format!("{}_other", source.desc.name))
             .build()
             .unwrap();
-        let dest = ColumnMetadata { cf_id: 1, desc };
+        let dest = ColumnMetadata {
+            cf_id: 1,
+            desc,
+            backfill: None,
+        };
 
         let err = is_source_column_compatible(&source, &dest).unwrap_err();
         assert!(
@@ -18,15 +18,16 @@ use std::sync::Arc;
 
 use common_base::BitVec;
 use common_error::prelude::*;
-use datatypes::prelude::ScalarVector;
+use datatypes::prelude::{ScalarVector, Value};
 use datatypes::schema::{SchemaBuilder, SchemaRef};
-use datatypes::vectors::{BooleanVector, UInt8Vector};
-use store_api::storage::{Chunk, ColumnId, OpType};
+use datatypes::vectors::{BooleanVector, UInt64Vector, UInt8Vector};
+use store_api::storage::{Chunk, ColumnId, OpType, SequenceNumber};
 
 use crate::error;
 use crate::metadata::{self, Result};
 use crate::read::{Batch, BatchOp};
 use crate::schema::{RegionSchema, RegionSchemaRef, StoreSchema, StoreSchemaRef};
+use crate::sst::RangeTombstone;
 
 /// Metadata about projection.
 #[derive(Debug, Default)]
@@ -356,6 +357,63 @@ impl BatchOp for ProjectedSchema {
             }
         }
     }
+
+    fn unselect_invisible(
+        &self,
+        batch: &Batch,
+        selected: &mut BitVec,
+        visible_sequence: SequenceNumber,
+    ) {
+        let sequences = batch.column(self.schema_to_read.sequence_index());
+        // Safety: Same as `unselect_deleted`, the read procedure should guarantee the
+        // batch has the same schema as `self.schema_to_read`.
+        let sequences = sequences
+            .as_any()
+            .downcast_ref::<UInt64Vector>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Expect sequence (UInt64) column at index {}, given {:?}",
+                    self.schema_to_read.sequence_index(),
+                    sequences.data_type()
+                );
+            });
+
+        for (i, sequence) in sequences.iter_data().enumerate() {
+            if sequence.map(|s| s > visible_sequence).unwrap_or(false) {
+                selected.set(i, false);
+            }
+        }
+    }
+
+    fn unselect_tombstoned(
+        &self,
+        batch: &Batch,
+        selected: &mut BitVec,
+        tombstones: &[RangeTombstone],
+    ) {
+        let Some(ts_index) = self.schema_to_read.schema().timestamp_index() else { return };
+        let timestamps = batch.column(ts_index);
+        let sequences = batch.column(self.schema_to_read.sequence_index());
+        // Safety: Same as `unselect_deleted`, the read procedure should guarantee the
+        // batch has the same schema as `self.schema_to_read`.
+        let sequences = sequences
+            .as_any()
+            .downcast_ref::<UInt64Vector>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Expect sequence (UInt64) column at index {}, given {:?}",
+                    self.schema_to_read.sequence_index(),
+                    sequences.data_type()
+                );
+            });
+
+        for (i, sequence) in sequences.iter_data().enumerate() {
+            let (Some(sequence), Value::Timestamp(ts)) = (sequence, timestamps.get(i)) else { continue };
+            if tombstones.iter().any(|t| t.masks(&ts, sequence)) {
+                selected.set(i, false);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
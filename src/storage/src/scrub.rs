@@ -0,0 +1,272 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Background task that verifies checksums of SST files, and the on demand scrub
+//! checking the manifest and SST files of a region.
+
+use std::collections::HashMap;
+use std::sync::Weak;
+
+use async_trait::async_trait;
+use common_runtime::TaskFunction;
+use common_telemetry::logging;
+use metrics::increment_counter;
+use store_api::logstore::LogStore;
+use store_api::manifest::{self, Manifest, MetaActionIterator};
+use store_api::storage::{
+    HealthIssue, HealthIssueKind, Region, RegionHealthReport, SequenceNumber,
+};
+
+use crate::engine::EngineInner;
+use crate::error::{Error, Result};
+use crate::manifest::action::RegionMetaAction;
+use crate::manifest::region::RegionManifest;
+use crate::metrics::{SCRUB_SST_CORRUPTED, SCRUB_SST_FILES};
+use crate::region::RegionImpl;
+use crate::sst::{AccessLayerRef, FileId, FileMeta};
+
+/// Result of scrubbing SST files.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ScrubReport {
+    /// Number of files verified.
+    pub files: usize,
+    /// Ids of corrupted files.
+    pub corrupted: Vec<FileId>,
+}
+
+/// Verifies all SST files with checksums in the `region`.
+pub(crate) async fn scrub_region<S: LogStore>(region: &RegionImpl<S>) -> ScrubReport {
+    let mut report = ScrubReport::default();
+    // Holding the file handles prevents files from being purged during verification.
+    for file in region.sst_files() {
+        let meta = file.meta();
+        if meta.checksums.is_none() {
+            continue;
+        }
+
+        increment_counter!(SCRUB_SST_FILES);
+        report.files += 1;
+        match region.sst_layer().verify_sst(&meta).await {
+            Ok(()) => (),
+            Err(e @ Error::CorruptedSst { .. }) => {
+                increment_counter!(SCRUB_SST_CORRUPTED);
+                logging::error!(e; "Found corrupted SST file in region {}", region.name());
+                report.corrupted.push(meta.file_id);
+            }
+            Err(e) => {
+                logging::warn!(
+                    "Failed to scrub SST file {} in region {}, err: {}",
+                    meta.file_id,
+                    region.name(),
+                    e
+                );
+            }
+        }
+    }
+
+    report
+}
+
+/// Walks the region `manifest` from its last checkpoint and checks that the manifest
+/// versions are continuous and the flushed sequence never goes backwards, then checks
+/// every SST file referenced by the manifest with `sst_layer`.
+pub(crate) async fn check_region_health(
+    manifest: &RegionManifest,
+    sst_layer: &AccessLayerRef,
+) -> Result<RegionHealthReport> {
+    let mut report = RegionHealthReport::default();
+    let mut files = HashMap::new();
+    let mut flushed_sequence = None;
+
+    let start = match manifest.last_checkpoint().await? {
+        Some(checkpoint) => {
+            if let Some(version) = checkpoint.checkpoint.and_then(|c| c.version) {
+                files = version.files;
+                flushed_sequence = version.flushed_sequence;
+            }
+            checkpoint.last_version + 1
+        }
+        None => manifest::MIN_VERSION,
+    };
+    report.start_version = start;
+    report.end_version = start;
+
+    let mut iter = manifest.scan(start, manifest::MAX_VERSION).await?;
+    let mut expected_version = start;
+    while let Some((version, action_list)) = iter.next_action().await? {
+        if version != expected_version {
+            report.issues.push(HealthIssue {
+                kind: HealthIssueKind::ManifestGap,
+                file: None,
+                detail: format!(
+                    "manifest versions {}..{} are missing",
+                    expected_version, version
+                ),
+            });
+        }
+        expected_version = version + 1;
+        report.end_version = version;
+
+        for action in action_list.actions {
+            let RegionMetaAction::Edit(edit) = action else { continue; };
+            check_flushed_sequence(
+                &mut report,
+                version,
+                &mut flushed_sequence,
+                edit.flushed_sequence,
+            );
+            for file in edit.files_to_add {
+                files.insert(file.file_id, file);
+            }
+            for file in edit.files_to_remove {
+                files.remove(&file.file_id);
+            }
+        }
+    }
+
+    let mut files = files.into_values().collect::<Vec<_>>();
+    files.sort_unstable_by_key(|file| (file.level, file.file_id));
+    for file in &files {
+        report.num_files += 1;
+        report.file_size += file.file_size;
+        check_sst(&mut report, sst_layer, file).await?;
+    }
+
+    Ok(report)
+}
+
+fn check_flushed_sequence(
+    report: &mut RegionHealthReport,
+    version: manifest::ManifestVersion,
+    flushed_sequence: &mut Option<SequenceNumber>,
+    edit_sequence: Option<SequenceNumber>,
+) {
+    let Some(sequence) = edit_sequence else { return; };
+    match *flushed_sequence {
+        Some(previous) if sequence < previous => {
+            report.issues.push(HealthIssue {
+                kind: HealthIssueKind::SequenceRegressed,
+                file: None,
+                detail: format!(
+                    "flushed sequence {sequence} at manifest version {version} is less than {previous}"
+                ),
+            });
+        }
+        _ => *flushed_sequence = Some(sequence),
+    }
+}
+
+async fn check_sst(
+    report: &mut RegionHealthReport,
+    sst_layer: &AccessLayerRef,
+    file: &FileMeta,
+) -> Result<()> {
+    let mut add_issue = |kind, detail| {
+        report.issues.push(HealthIssue {
+            kind,
+            file: Some(file.file_id.as_parquet()),
+            detail,
+        })
+    };
+
+    if let Some((start, end)) = &file.time_range {
+        if start > end {
+            add_issue(
+                HealthIssueKind::TimeRangeInverted,
+                format!(
+                    "recorded time range starts at {} after it ends at {}",
+                    start.to_iso8601_string(),
+                    end.to_iso8601_string()
+                ),
+            );
+        }
+    }
+
+    let inspected = match sst_layer.inspect_sst(file).await {
+        Ok(Some(inspected)) => inspected,
+        Ok(None) => {
+            add_issue(
+                HealthIssueKind::MissingFile,
+                "file doesn't exist".to_string(),
+            );
+            return Ok(());
+        }
+        Err(e @ (Error::CorruptedSst { .. } | Error::InvalidSst { .. })) => {
+            add_issue(HealthIssueKind::CorruptedFile, e.to_string());
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Files written by old versions don't record their sizes.
+    if file.file_size != 0 && file.file_size != inspected.file_size {
+        add_issue(
+            HealthIssueKind::CorruptedFile,
+            format!(
+                "file has {} bytes but {} bytes are recorded",
+                inspected.file_size, file.file_size
+            ),
+        );
+    }
+    if let (Some((start, end)), Some((min, max))) = (&file.time_range, &inspected.time_range) {
+        if min < start || max > end {
+            add_issue(
+                HealthIssueKind::TimeRangeMismatch,
+                format!(
+                    "rows in [{}, {}] are out of the recorded time range [{}, {}]",
+                    min.to_iso8601_string(),
+                    max.to_iso8601_string(),
+                    start.to_iso8601_string(),
+                    end.to_iso8601_string()
+                ),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Periodically scrubs SST files of all regions in the engine.
+pub(crate) struct SstScrubber<S: LogStore> {
+    engine: Weak<EngineInner<S>>,
+}
+
+impl<S: LogStore> SstScrubber<S> {
+    pub(crate) fn new(engine: Weak<EngineInner<S>>) -> SstScrubber<S> {
+        SstScrubber { engine }
+    }
+}
+
+#[async_trait]
+impl<S: LogStore> TaskFunction<Error> for SstScrubber<S> {
+    fn name(&self) -> &str {
+        "sst-scrubber"
+    }
+
+    async fn call(&self) -> Result<()> {
+        // The engine is dropped, nothing to scrub.
+        let Some(engine) = self.engine.upgrade() else { return Ok(()); };
+
+        for region in engine.ready_regions() {
+            let report = scrub_region(&region).await;
+            logging::debug!(
+                "Scrubbed SST files of region {}, report: {:?}",
+                region.name(),
+                report
+            );
+        }
+
+        Ok(())
+    }
+}
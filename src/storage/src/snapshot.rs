@@ -31,6 +31,7 @@ pub struct SnapshotImpl {
     /// Max sequence number (inclusive) visible to user.
     visible_sequence: SequenceNumber,
     sst_layer: AccessLayerRef,
+    append_mode: bool,
 }
 
 #[async_trait]
@@ -60,20 +61,42 @@ impl Snapshot for SnapshotImpl {
                 .filters(request.filters)
                 .batch_size(ctx.batch_size)
                 .visible_sequence(visible_sequence)
+                .sample_percent(request.sample_percent)
+                // SSTs never contain rows newer than the snapshot, unless the request
+                // reads at an older sequence.
+                .filter_sst_sequence(visible_sequence < self.visible_sequence)
+                .tombstones(self.version.tombstones())
+                .append_mode(self.append_mode)
                 .pick_memtables(mutables.clone());
 
+        let mut estimated_bytes = mutables.bytes_allocated() as u64;
         for memtable in immutables {
+            estimated_bytes += memtable.bytes_allocated() as u64;
             builder = builder.pick_memtables(memtable.clone());
         }
 
-        let reader = builder.pick_all_ssts(self.version.ssts())?.build().await?;
+        let ssts = self.version.ssts();
+        let mut num_files = 0;
+        for file in ssts.levels().iter().flat_map(|level| level.files()) {
+            num_files += 1;
+            estimated_bytes += file.file_size();
+        }
+        let reader = builder.pick_all_ssts(ssts)?.build().await?;
 
-        Ok(ScanResponse { reader })
+        Ok(ScanResponse {
+            reader,
+            num_files,
+            estimated_bytes,
+        })
     }
 
     async fn get(&self, _ctx: &ReadContext, _request: GetRequest) -> Result<GetResponse> {
         unimplemented!()
     }
+
+    fn sequence(&self) -> SequenceNumber {
+        self.visible_sequence
+    }
 }
 
 impl SnapshotImpl {
@@ -86,9 +109,16 @@ impl SnapshotImpl {
             version,
             visible_sequence,
             sst_layer,
+            append_mode: false,
         }
     }
 
+    /// Sets whether the region of the snapshot is in append mode.
+    pub fn with_append_mode(mut self, append_mode: bool) -> SnapshotImpl {
+        self.append_mode = append_mode;
+        self
+    }
+
     #[inline]
     fn sequence_to_read(&self, request_sequence: Option<SequenceNumber>) -> SequenceNumber {
         request_sequence
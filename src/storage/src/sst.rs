@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod block_cache;
+pub mod checksum;
+pub mod export;
+pub mod meta_cache;
 pub(crate) mod parquet;
+pub mod rate_limit;
 mod stream_writer;
 
 use std::collections::HashMap;
@@ -20,6 +25,7 @@ use std::fmt;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_base::readable_size::ReadableSize;
@@ -28,11 +34,11 @@ use common_telemetry::{debug, error};
 use common_time::range::TimestampRange;
 use common_time::Timestamp;
 use datatypes::schema::SchemaRef;
-use futures_util::StreamExt;
-use object_store::{util, ObjectStore};
+use futures_util::{AsyncReadExt, StreamExt, TryStreamExt};
+use object_store::{util, ErrorKind, ObjectStore};
 use serde::{Deserialize, Deserializer, Serialize};
-use snafu::{ResultExt, Snafu};
-use store_api::storage::{ChunkReader, RegionId};
+use snafu::{OptionExt, ResultExt, Snafu};
+use store_api::storage::{ChunkReader, ColumnEncodings, RegionId, SequenceNumber};
 use table::predicate::Predicate;
 use uuid::Uuid;
 
@@ -41,14 +47,23 @@ use crate::error;
 use crate::error::{DeleteSstSnafu, Result};
 use crate::file_purger::{FilePurgeRequest, FilePurgerRef};
 use crate::memtable::BoxedBatchIterator;
-use crate::read::{Batch, BoxedBatchReader};
+use crate::read::{Batch, BoxedBatchReader, PrefetchLimiterRef, PrefetchReader};
 use crate::scheduler::Scheduler;
-use crate::schema::ProjectedSchemaRef;
-use crate::sst::parquet::{ParquetReader, ParquetWriter};
+use crate::schema::{ProjectedSchemaRef, StoreSchema};
+use crate::sst::block_cache::BlockCacheRef;
+use crate::sst::checksum::{BlockHasher, FileChecksums};
+use crate::sst::meta_cache::SstMetaCacheRef;
+use crate::sst::parquet::{self as parquet_sst, ParquetReader, ParquetWriter};
+use crate::sst::rate_limit::IoRateLimiterRef;
 
 /// Maximum level of SSTs.
 pub const MAX_LEVEL: u8 = 2;
 
+/// Directory under the SST directory holding references to shared SST files.
+const REFS_DIR: &str = "refs/";
+/// Size of the buffer to read SST files while inspecting them.
+const INSPECT_BUFFER_SIZE: usize = 64 * 1024;
+
 pub type Level = u8;
 
 pub use crate::sst::stream_writer::BufferedWriter;
@@ -230,9 +245,11 @@ impl FileHandle {
 
     #[inline]
     pub fn file_path(&self) -> String {
-        self.inner
-            .sst_layer
-            .sst_file_path(&self.inner.meta.file_id.as_parquet())
+        let file_name = self.inner.meta.file_id.as_parquet();
+        match &self.inner.meta.source_dir {
+            Some(dir) => format!("{}{}", util::normalize_dir(dir), file_name),
+            None => self.inner.sst_layer.sst_file_path(&file_name),
+        }
     }
 
     #[inline]
@@ -267,6 +284,17 @@ impl FileHandle {
         self.inner.deleted.store(true, Ordering::Relaxed);
     }
 
+    /// Returns true if the content of the file is verified against its checksums.
+    #[inline]
+    pub fn verified(&self) -> bool {
+        self.inner.verified.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn mark_verified(&self) {
+        self.inner.verified.store(true, Ordering::Relaxed);
+    }
+
     #[inline]
     pub fn meta(&self) -> FileMeta {
         self.inner.meta.clone()
@@ -285,6 +313,7 @@ struct FileHandleInner {
     meta: FileMeta,
     compacting: AtomicBool,
     deleted: AtomicBool,
+    verified: AtomicBool,
     sst_layer: AccessLayerRef,
     file_purger: FilePurgerRef,
 }
@@ -295,6 +324,7 @@ impl fmt::Debug for FileHandleInner {
             .field("meta", &self.meta)
             .field("compacting", &self.compacting)
             .field("deleted", &self.deleted)
+            .field("verified", &self.verified)
             .finish()
     }
 }
@@ -306,6 +336,8 @@ impl Drop for FileHandleInner {
                 sst_layer: self.sst_layer.clone(),
                 file_id: self.meta.file_id,
                 region_id: self.meta.region_id,
+                tier: self.meta.tier,
+                source_dir: self.meta.source_dir.clone(),
             };
             match self.file_purger.schedule(request) {
                 Ok(res) => {
@@ -335,6 +367,7 @@ impl FileHandleInner {
             meta,
             compacting: AtomicBool::new(false),
             deleted: AtomicBool::new(false),
+            verified: AtomicBool::new(false),
             sst_layer,
             file_purger,
         }
@@ -397,6 +430,68 @@ pub struct FileMeta {
     pub level: Level,
     /// Size of the file.
     pub file_size: u64,
+    /// Block checksums of the file, `None` if the file was written without checksums.
+    pub checksums: Option<FileChecksums>,
+    /// Storage tier that the file is located in.
+    pub tier: StorageTier,
+    /// Directory of the file if it's shared from another region, e.g. by cloning a table.
+    /// `None` if the file is in the directory of its region.
+    pub source_dir: Option<String>,
+}
+
+/// Storage tier of SST files. Each tier is backed by a different object store.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StorageTier {
+    /// The default object store, for recent data.
+    #[default]
+    Hot,
+    /// The object store for old data, usually cheaper and slower.
+    Cold,
+}
+
+/// Marks rows within a time range written before a sequence as deleted.
+///
+/// Dropping a time range removes SSTs wholly within the range and masks rows of the
+/// partially overlapping SSTs by a range tombstone, so we don't need to rewrite these
+/// files. The masked rows are removed physically once their files are compacted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RangeTombstone {
+    /// Inclusive start of the time range.
+    pub start: Timestamp,
+    /// Inclusive end of the time range.
+    pub end: Timestamp,
+    /// Rows whose sequences are less than or equal to this sequence are deleted.
+    pub sequence: SequenceNumber,
+}
+
+impl RangeTombstone {
+    /// Returns true if the tombstone masks the row with given timestamp and sequence.
+    #[inline]
+    pub fn masks(&self, ts: &Timestamp, sequence: SequenceNumber) -> bool {
+        sequence <= self.sequence && self.start <= *ts && *ts <= self.end
+    }
+
+    /// Returns true if the inclusive `time_range` of a file overlaps with the tombstone.
+    /// Files without time range are considered overlapping.
+    pub fn overlaps(&self, time_range: &Option<(Timestamp, Timestamp)>) -> bool {
+        match time_range {
+            Some((start, end)) => *start <= self.end && self.start <= *end,
+            None => true,
+        }
+    }
+}
+
+/// Retains tombstones that still overlap with any of the time ranges of files, tombstones
+/// that overlap no file mask nothing and could be removed.
+pub(crate) fn retain_tombstones(
+    tombstones: &mut Vec<RangeTombstone>,
+    file_time_ranges: impl Iterator<Item = Option<(Timestamp, Timestamp)>> + Clone,
+) {
+    tombstones.retain(|tombstone| {
+        file_time_ranges
+            .clone()
+            .any(|time_range| tombstone.overlaps(&time_range))
+    });
 }
 
 fn deserialize_from_string<'de, D>(deserializer: D) -> std::result::Result<FileId, D::Error>
@@ -412,12 +507,24 @@ where
 pub struct WriteOptions {
     // TODO(yingwen): [flush] row group size.
     pub sst_write_buffer_size: ReadableSize,
+    /// Storage tier to write the SST to.
+    pub tier: StorageTier,
+    /// Whether the SST could be exported for external readers.
+    pub export: bool,
+    /// Whether to encode string columns with dictionaries and prefix compression.
+    pub compact_strings: bool,
+    /// Encodings of columns overriding the default ones, by column names.
+    pub column_encodings: ColumnEncodings,
 }
 
 impl Default for WriteOptions {
     fn default() -> Self {
         Self {
             sst_write_buffer_size: ReadableSize::mb(8),
+            tier: StorageTier::Hot,
+            export: false,
+            compact_strings: false,
+            column_encodings: ColumnEncodings::new(),
         }
     }
 }
@@ -431,6 +538,17 @@ pub struct ReadOptions {
 
     pub predicate: Predicate,
     pub time_range: TimestampRange,
+    /// Percentage of row groups to read, `None` to read all of them.
+    pub sample_percent: Option<f64>,
+}
+
+/// Randomly decides whether a unit of data should be read under the
+/// given sample percentage.
+pub(crate) fn sampled(sample_percent: Option<f64>) -> bool {
+    match sample_percent {
+        Some(percent) => rand::random::<f64>() * 100.0 < percent,
+        None => true,
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -438,6 +556,24 @@ pub struct SstInfo {
     pub time_range: Option<(Timestamp, Timestamp)>,
     pub file_size: u64,
     pub num_rows: usize,
+    pub checksums: Option<FileChecksums>,
+}
+
+/// A staged SST file rewritten into the directory of a region.
+#[derive(Debug, PartialEq)]
+pub struct AttachedSst {
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    pub file_size: u64,
+    /// Max sequence of rows in the file.
+    pub max_sequence: SequenceNumber,
+}
+
+/// A SST file read back from the object store.
+#[derive(Debug, PartialEq)]
+pub struct InspectedSst {
+    /// Time range of rows in the file from its statistics.
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    pub file_size: u64,
 }
 
 /// SST access layer.
@@ -462,8 +598,76 @@ pub trait AccessLayer: Send + Sync + std::fmt::Debug {
         opts: &ReadOptions,
     ) -> Result<BoxedBatchReader>;
 
-    /// Deletes a SST file with given name.
-    async fn delete_sst(&self, file_id: FileId) -> Result<()>;
+    /// Deletes a SST file with given name from the object store of `tier`.
+    async fn delete_sst(&self, file_id: FileId, tier: StorageTier) -> Result<()>;
+
+    /// Verifies the content of the SST file against the checksums in `file_meta`.
+    /// Files without checksums are always valid.
+    async fn verify_sst(&self, file_meta: &FileMeta) -> Result<()>;
+
+    /// Reads the SST file of `file_meta`, verifies it against its checksums and parses
+    /// it, returns `None` if the file doesn't exist.
+    async fn inspect_sst(&self, file_meta: &FileMeta) -> Result<Option<InspectedSst>>;
+
+    /// Lists paths of the SST files staged under `staging_dir` of the object store.
+    async fn list_staged_ssts(&self, staging_dir: &str) -> Result<Vec<String>>;
+
+    /// Validates the parquet file staged at `path` against the region `schema` and
+    /// rewrites it into the directory of this layer as the SST file with `file_id`.
+    /// The staged file could be written by the storage engine or by other tools.
+    async fn attach_sst(
+        &self,
+        path: &str,
+        file_id: FileId,
+        schema: &StoreSchema,
+    ) -> Result<AttachedSst>;
+
+    /// Deletes the SST file staged at `path`.
+    async fn delete_staged_sst(&self, path: &str) -> Result<()>;
+
+    /// Adds references of `region_ids` to a SST file in `source_dir`, or in the directory
+    /// of this layer if `source_dir` is `None`. A referenced file is only deleted after
+    /// all regions referencing it release it.
+    async fn add_sst_refs(
+        &self,
+        _file_id: FileId,
+        _source_dir: Option<&str>,
+        _region_ids: &[RegionId],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Releases the reference of `region_id` to a SST file and deletes the file if no
+    /// region references it anymore. Files never referenced are deleted directly.
+    async fn release_sst(
+        &self,
+        _region_id: RegionId,
+        file_id: FileId,
+        tier: StorageTier,
+        source_dir: Option<&str>,
+    ) -> Result<()> {
+        if source_dir.is_some() {
+            // We don't own files shared from other regions.
+            return Ok(());
+        }
+        self.delete_sst(file_id, tier).await
+    }
+
+    /// Returns the storage tier to put a SST file whose timestamps are all
+    /// before `max_timestamp`.
+    fn storage_tier(&self, _max_timestamp: Timestamp) -> StorageTier {
+        StorageTier::Hot
+    }
+
+    /// Returns how many batches of a SST file, and how many SST files of a scan, are
+    /// read ahead. Reading ahead is disabled if it's 0.
+    fn prefetch_depth(&self) -> usize {
+        0
+    }
+
+    /// Evicts the cached pages and metadata of the SST files in `files`, they are loaded
+    /// again by the next read.
+    fn evict_cached_ssts(&self, _files: &[FileHandle]) {}
 }
 
 pub type AccessLayerRef = Arc<dyn AccessLayer>;
@@ -507,16 +711,52 @@ impl Source {
     }
 }
 
+/// Cold storage of SST files.
+#[derive(Debug, Clone)]
+pub struct ColdStorage {
+    /// Object store for the [StorageTier::Cold] tier, files are stored under the
+    /// same path as in the hot object store.
+    pub object_store: ObjectStore,
+    /// Files whose timestamps are all older than this duration are moved to cold
+    /// storage on compaction, `None` to never move files.
+    pub cold_after: Option<Duration>,
+}
+
 /// Sst access layer.
 pub struct FsAccessLayer {
     sst_dir: String,
     object_store: ObjectStore,
+    /// Whether to verify checksums of SST files on read.
+    verify_checksum: bool,
+    cold_storage: Option<ColdStorage>,
+    /// Whether to export SST files that allow exporting.
+    export: bool,
+    /// Limiter of the bytes written to SST files.
+    io_rate_limiter: Option<IoRateLimiterRef>,
+    /// Cache of pages read from SST files.
+    block_cache: Option<BlockCacheRef>,
+    /// Cache of parsed metadata of SST files.
+    meta_cache: Option<SstMetaCacheRef>,
+    prefetch_depth: usize,
+    /// Limiter of the readers prefetching at the same time, shared by all regions.
+    prefetch_limiter: Option<PrefetchLimiterRef>,
 }
 
 impl fmt::Debug for FsAccessLayer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("FsAccessLayer")
             .field("sst_dir", &self.sst_dir)
+            .field("verify_checksum", &self.verify_checksum)
+            .field("export", &self.export)
+            .field("io_rate_limiter", &self.io_rate_limiter)
+            .field("block_cache", &self.block_cache.is_some())
+            .field("meta_cache", &self.meta_cache.is_some())
+            .field("prefetch_depth", &self.prefetch_depth)
+            .field("prefetch_limiter", &self.prefetch_limiter)
+            .field(
+                "cold_after",
+                &self.cold_storage.as_ref().map(|c| c.cold_after),
+            )
             .finish()
     }
 }
@@ -526,6 +766,124 @@ impl FsAccessLayer {
         FsAccessLayer {
             sst_dir: util::normalize_dir(sst_dir),
             object_store,
+            verify_checksum: false,
+            cold_storage: None,
+            export: false,
+            io_rate_limiter: None,
+            block_cache: None,
+            meta_cache: None,
+            prefetch_depth: 0,
+            prefetch_limiter: None,
+        }
+    }
+
+    /// Sets the cache of pages read from SST files.
+    pub fn with_block_cache(mut self, block_cache: Option<BlockCacheRef>) -> FsAccessLayer {
+        self.block_cache = block_cache;
+        self
+    }
+
+    /// Sets the cache of parsed metadata of SST files.
+    pub fn with_meta_cache(mut self, meta_cache: Option<SstMetaCacheRef>) -> FsAccessLayer {
+        self.meta_cache = meta_cache;
+        self
+    }
+
+    /// Sets how many batches and files to read ahead during scans.
+    pub fn with_prefetch_depth(mut self, prefetch_depth: usize) -> FsAccessLayer {
+        self.prefetch_depth = prefetch_depth;
+        self
+    }
+
+    /// Sets the limiter of the readers prefetching at the same time, unlimited if `None`.
+    pub fn with_prefetch_limiter(
+        mut self,
+        prefetch_limiter: Option<PrefetchLimiterRef>,
+    ) -> FsAccessLayer {
+        self.prefetch_limiter = prefetch_limiter;
+        self
+    }
+
+    /// Sets the limiter of the bytes written to SST files.
+    pub fn with_io_rate_limiter(
+        mut self,
+        io_rate_limiter: Option<IoRateLimiterRef>,
+    ) -> FsAccessLayer {
+        self.io_rate_limiter = io_rate_limiter;
+        self
+    }
+
+    /// Sets whether to write a copy of SST files in standard parquet format under
+    /// the [EXPORT_DIR](export::EXPORT_DIR), for files written with
+    /// [WriteOptions::export].
+    pub fn with_sst_export(mut self, export: bool) -> FsAccessLayer {
+        self.export = export;
+        self
+    }
+
+    /// Returns the directory of SST files in `source_dir`, or in the directory of this
+    /// layer if `source_dir` is `None`.
+    fn file_dir(&self, source_dir: Option<&str>) -> String {
+        source_dir
+            .map(util::normalize_dir)
+            .unwrap_or_else(|| self.sst_dir.clone())
+    }
+
+    /// Returns the path of the SST file of `file_meta`.
+    fn file_meta_path(&self, file_meta: &FileMeta) -> String {
+        format!(
+            "{}{}",
+            self.file_dir(file_meta.source_dir.as_deref()),
+            file_meta.file_id.as_parquet()
+        )
+    }
+
+    /// Returns the directory holding references to the SST file.
+    fn refs_dir(file_dir: &str, file_id: FileId) -> String {
+        format!("{}{}{}/", file_dir, REFS_DIR, file_id)
+    }
+
+    /// Lists regions referencing the SST file in `file_dir`.
+    async fn list_sst_refs(&self, file_dir: &str, file_id: FileId) -> Result<Vec<String>> {
+        let refs_dir = Self::refs_dir(file_dir, file_id);
+        let streamer = match self.object_store.list(&refs_dir).await {
+            Ok(streamer) => streamer,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context(error::ListObjectsSnafu { path: refs_dir }),
+        };
+        streamer
+            .map_ok(|entry| entry.name().to_string())
+            .try_collect()
+            .await
+            .context(error::ListObjectsSnafu { path: refs_dir })
+    }
+
+    /// Returns the path of the exported copy of the SST file.
+    fn export_file_path(&self, file_name: &str) -> String {
+        format!("{}{}{}", self.sst_dir, export::EXPORT_DIR, file_name)
+    }
+
+    /// Sets whether to verify checksums of SST files on read.
+    pub fn with_checksum_verification(mut self, verify_checksum: bool) -> FsAccessLayer {
+        self.verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Sets the cold storage for old SST files.
+    pub fn with_cold_storage(mut self, cold_storage: Option<ColdStorage>) -> FsAccessLayer {
+        self.cold_storage = cold_storage;
+        self
+    }
+
+    /// Returns the object store of the storage `tier`.
+    fn object_store(&self, tier: StorageTier) -> Result<&ObjectStore> {
+        match tier {
+            StorageTier::Hot => Ok(&self.object_store),
+            StorageTier::Cold => self
+                .cold_storage
+                .as_ref()
+                .map(|c| &c.object_store)
+                .context(error::StorageTierNotConfiguredSnafu { tier }),
         }
     }
 }
@@ -546,7 +904,14 @@ impl AccessLayer for FsAccessLayer {
         // Now we only supports parquet format. We may allow caller to specific SST format in
         // WriteOptions in the future.
         let file_path = self.sst_file_path(&file_id.as_parquet());
-        let writer = ParquetWriter::new(&file_path, source, self.object_store.clone());
+        let object_store = self.object_store(opts.tier)?.clone();
+        let mut writer = ParquetWriter::new(&file_path, source, object_store);
+        if self.export && opts.export {
+            writer = writer.with_export_path(self.export_file_path(&file_id.as_parquet()));
+        }
+        if let Some(io_rate_limiter) = &self.io_rate_limiter {
+            writer = writer.with_io_rate_limiter(io_rate_limiter.clone());
+        }
         writer.write_sst(opts).await
     }
 
@@ -556,25 +921,220 @@ impl AccessLayer for FsAccessLayer {
         file_handle: FileHandle,
         opts: &ReadOptions,
     ) -> Result<BoxedBatchReader> {
+        let object_store = self.object_store(file_handle.meta().tier)?.clone();
         let reader = ParquetReader::new(
             file_handle,
-            self.object_store.clone(),
+            object_store,
             opts.projected_schema.clone(),
             opts.predicate.clone(),
             opts.time_range,
-        );
-
-        let stream = reader.chunk_stream().await?;
-        Ok(Box::new(stream))
+            opts.sample_percent,
+        )
+        .with_checksum_verification(self.verify_checksum)
+        .with_block_cache(self.block_cache.clone())
+        .with_meta_cache(self.meta_cache.clone());
+
+        let stream = Box::new(reader.chunk_stream().await?);
+        if self.prefetch_depth == 0 {
+            return Ok(stream);
+        }
+        match &self.prefetch_limiter {
+            Some(limiter) => Ok(limiter.prefetch(stream, self.prefetch_depth)),
+            None => Ok(Box::new(PrefetchReader::new(stream, self.prefetch_depth))),
+        }
     }
 
     /// Deletes a SST file with given file id.
-    async fn delete_sst(&self, file_id: FileId) -> Result<()> {
+    async fn delete_sst(&self, file_id: FileId, tier: StorageTier) -> Result<()> {
+        let object_store = self.object_store(tier)?;
         let path = self.sst_file_path(&file_id.as_parquet());
-        self.object_store
+        object_store.delete(&path).await.context(DeleteSstSnafu)?;
+        if self.export {
+            // The exported copy is stored alongside the SST file, deleting a file
+            // that doesn't exist is a no-op.
+            let export_path = self.export_file_path(&file_id.as_parquet());
+            object_store
+                .delete(&export_path)
+                .await
+                .context(DeleteSstSnafu)?;
+        }
+        Ok(())
+    }
+
+    async fn verify_sst(&self, file_meta: &FileMeta) -> Result<()> {
+        let Some(checksums) = &file_meta.checksums else { return Ok(()); };
+        let path = self.file_meta_path(file_meta);
+        let content = self
+            .object_store(file_meta.tier)?
+            .read(&path)
+            .await
+            .context(error::ReadObjectSnafu { path: &path })?;
+        checksums.verify(&path, &content)
+    }
+
+    async fn inspect_sst(&self, file_meta: &FileMeta) -> Result<Option<InspectedSst>> {
+        let path = self.file_meta_path(file_meta);
+        let object_store = self.object_store(file_meta.tier)?;
+        let file_size = match object_store.stat(&path).await {
+            Ok(metadata) => metadata.content_length(),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context(error::ReadObjectSnafu { path }),
+        };
+        if let Some(checksums) = &file_meta.checksums {
+            // Streams the file instead of reading it at once, as large files may be inspected.
+            let mut reader = object_store
+                .reader(&path)
+                .await
+                .context(error::ReadObjectSnafu { path: &path })?;
+            let mut hasher = BlockHasher::new(checksums.block_size);
+            let mut buf = vec![0; INSPECT_BUFFER_SIZE];
+            loop {
+                let n = reader
+                    .read(&mut buf)
+                    .await
+                    .context(error::ReadSstSnafu { path: &path })?;
+                if n == 0 {
+                    break;
+                }
+                hasher.update(&buf[..n]);
+            }
+            checksums.verify_computed(&path, &hasher.finish())?;
+        }
+
+        // Only the footer of the file is read for the statistics.
+        let time_range = parquet_sst::read_sst_time_range(&path, &object_store).await?;
+        Ok(Some(InspectedSst {
+            time_range,
+            file_size,
+        }))
+    }
+
+    async fn list_staged_ssts(&self, staging_dir: &str) -> Result<Vec<String>> {
+        let staging_dir = util::normalize_dir(staging_dir);
+        let streamer = match self.object_store.list(&staging_dir).await {
+            Ok(streamer) => streamer,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context(error::ListObjectsSnafu { path: staging_dir }),
+        };
+        let mut paths = streamer
+            .try_filter_map(|entry| {
+                let path = entry
+                    .name()
+                    .ends_with(".parquet")
+                    .then(|| format!("{}{}", staging_dir, entry.name()));
+                async move { Ok(path) }
+            })
+            .try_collect::<Vec<_>>()
+            .await
+            .context(error::ListObjectsSnafu { path: &staging_dir })?;
+        paths.sort_unstable();
+        Ok(paths)
+    }
+
+    async fn attach_sst(
+        &self,
+        path: &str,
+        file_id: FileId,
+        schema: &StoreSchema,
+    ) -> Result<AttachedSst> {
+        let file_path = self.sst_file_path(&file_id.as_parquet());
+        let info = parquet_sst::rewrite_staged_sst(
+            path,
+            &self.object_store,
+            &file_path,
+            schema,
+            &WriteOptions::default(),
+        )
+        .await?;
+        Ok(AttachedSst {
+            time_range: info.time_range,
+            file_size: info.file_size,
+            max_sequence: info.max_sequence,
+        })
+    }
+
+    async fn delete_staged_sst(&self, path: &str) -> Result<()> {
+        self.object_store.delete(path).await.context(DeleteSstSnafu)
+    }
+
+    async fn add_sst_refs(
+        &self,
+        file_id: FileId,
+        source_dir: Option<&str>,
+        region_ids: &[RegionId],
+    ) -> Result<()> {
+        let refs_dir = Self::refs_dir(&self.file_dir(source_dir), file_id);
+        for region_id in region_ids {
+            let path = format!("{}{}", refs_dir, region_id);
+            self.object_store
+                .write(&path, Vec::<u8>::new())
+                .await
+                .context(error::WriteObjectSnafu { path: &path })?;
+        }
+        Ok(())
+    }
+
+    async fn release_sst(
+        &self,
+        region_id: RegionId,
+        file_id: FileId,
+        tier: StorageTier,
+        source_dir: Option<&str>,
+    ) -> Result<()> {
+        let file_dir = self.file_dir(source_dir);
+        let refs = self.list_sst_refs(&file_dir, file_id).await?;
+        if refs.is_empty() {
+            // The file is never shared.
+            return if source_dir.is_none() {
+                self.delete_sst(file_id, tier).await
+            } else {
+                Ok(())
+            };
+        }
+
+        let region_ref = region_id.to_string();
+        if refs.contains(&region_ref) {
+            let path = format!("{}{}", Self::refs_dir(&file_dir, file_id), region_ref);
+            self.object_store
+                .delete(&path)
+                .await
+                .context(DeleteSstSnafu)?;
+        }
+        if refs.iter().any(|r| *r != region_ref) {
+            // Other regions still reference the file.
+            return Ok(());
+        }
+
+        let path = format!("{}{}", file_dir, file_id.as_parquet());
+        self.object_store(tier)?
             .delete(&path)
             .await
-            .context(DeleteSstSnafu)
+            .context(DeleteSstSnafu)?;
+        debug!("Deleted shared SST file: {}", path);
+        Ok(())
+    }
+
+    fn prefetch_depth(&self) -> usize {
+        self.prefetch_depth
+    }
+
+    fn evict_cached_ssts(&self, files: &[FileHandle]) {
+        if let Some(cache) = &self.block_cache {
+            cache.evict_files(files.iter().map(|file| file.file_path()).collect());
+        }
+        if let Some(cache) = &self.meta_cache {
+            for file in files {
+                cache.evict(&file.file_id());
+            }
+        }
+    }
+
+    fn storage_tier(&self, max_timestamp: Timestamp) -> StorageTier {
+        let Some(cold_after) = self.cold_storage.as_ref().and_then(|c| c.cold_after) else { return StorageTier::Hot; };
+        match Timestamp::current_millis().sub(cold_after) {
+            Ok(cold_before) if max_timestamp <= cold_before => StorageTier::Cold,
+            _ => StorageTier::Hot,
+        }
     }
 }
 
@@ -582,9 +1142,14 @@ impl AccessLayer for FsAccessLayer {
 mod tests {
     use std::collections::HashSet;
 
+    use common_test_util::temp_dir::create_temp_dir;
+    use object_store::services::Fs;
+
     use super::*;
+    use crate::error::Error;
     use crate::file_purger::noop::NoopFilePurgeHandler;
     use crate::scheduler::{LocalScheduler, SchedulerConfig};
+    use crate::sst::checksum::DEFAULT_CHECKSUM_BLOCK_SIZE;
 
     #[test]
     fn test_file_id() {
@@ -664,9 +1229,121 @@ mod tests {
             time_range: None,
             level,
             file_size: 0,
+            checksums: None,
+            tier: StorageTier::Hot,
+            source_dir: None,
         }
     }
 
+    fn new_fs_object_store(path: &str) -> ObjectStore {
+        let mut builder = Fs::default();
+        builder.root(path);
+        ObjectStore::new(builder).unwrap().finish()
+    }
+
+    #[tokio::test]
+    async fn test_fs_access_layer_storage_tier() {
+        let hot_dir = create_temp_dir("hot");
+        let cold_dir = create_temp_dir("cold");
+        let hot_store = new_fs_object_store(hot_dir.path().to_str().unwrap());
+        let cold_store = new_fs_object_store(cold_dir.path().to_str().unwrap());
+
+        let layer = FsAccessLayer::new("region/", hot_store.clone());
+        // Without a cold storage, all files are hot.
+        assert_eq!(
+            StorageTier::Hot,
+            layer.storage_tier(Timestamp::new_second(0))
+        );
+        let err = layer
+            .delete_sst(FileId::random(), StorageTier::Cold)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::StorageTierNotConfigured { .. }),
+            "{err:?}"
+        );
+
+        let layer = layer.with_cold_storage(Some(ColdStorage {
+            object_store: cold_store.clone(),
+            cold_after: Some(Duration::from_secs(3600)),
+        }));
+        assert_eq!(
+            StorageTier::Cold,
+            layer.storage_tier(Timestamp::new_second(0))
+        );
+        assert_eq!(
+            StorageTier::Hot,
+            layer.storage_tier(Timestamp::current_millis())
+        );
+
+        // Put a file into the cold store, it should only be accessible in the cold tier.
+        let file_id = FileId::random();
+        let content = b"cold sst".to_vec();
+        let path = layer.sst_file_path(&file_id.as_parquet());
+        cold_store.write(&path, content.clone()).await.unwrap();
+        let mut file_meta = create_file_meta(file_id, 1);
+        file_meta.checksums = Some(FileChecksums::compute(
+            &content,
+            DEFAULT_CHECKSUM_BLOCK_SIZE,
+        ));
+        file_meta.tier = StorageTier::Cold;
+        layer.verify_sst(&file_meta).await.unwrap();
+        file_meta.tier = StorageTier::Hot;
+        assert!(layer.verify_sst(&file_meta).await.is_err());
+
+        layer.delete_sst(file_id, StorageTier::Cold).await.unwrap();
+        assert!(!cold_store.is_exist(&path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_fs_access_layer_shared_sst() {
+        let dir = create_temp_dir("shared");
+        let store = new_fs_object_store(dir.path().to_str().unwrap());
+        let source = FsAccessLayer::new("source/", store.clone());
+        let target = Arc::new(FsAccessLayer::new("target/", store.clone()));
+
+        let file_id = FileId::random();
+        let path = source.sst_file_path(&file_id.as_parquet());
+        store.write(&path, b"shared sst".to_vec()).await.unwrap();
+        let mut file_meta = create_file_meta(file_id, 0);
+        file_meta.source_dir = Some("source/".to_string());
+        let handle = FileHandle::new(
+            file_meta,
+            target.clone(),
+            Arc::new(LocalScheduler::new(
+                SchedulerConfig::default(),
+                NoopFilePurgeHandler,
+            )),
+        );
+        assert_eq!(path, handle.file_path());
+
+        target
+            .add_sst_refs(file_id, Some("source/"), &[0, 1])
+            .await
+            .unwrap();
+        // The source region releases the file, which is still referenced by the target.
+        source
+            .release_sst(0, file_id, StorageTier::Hot, None)
+            .await
+            .unwrap();
+        assert!(store.is_exist(&path).await.unwrap());
+
+        // The last reference is released.
+        target
+            .release_sst(1, file_id, StorageTier::Hot, Some("source/"))
+            .await
+            .unwrap();
+        assert!(!store.is_exist(&path).await.unwrap());
+
+        // Files never shared are deleted directly.
+        store.write(&path, b"sst".to_vec()).await.unwrap();
+        source
+            .release_sst(0, file_id, StorageTier::Hot, None)
+            .await
+            .unwrap();
+        assert!(!store.is_exist(&path).await.unwrap());
+    }
+
     #[test]
     fn test_level_metas_add_and_remove() {
         let layer = Arc::new(crate::test_util::access_layer_util::MockAccessLayer {});
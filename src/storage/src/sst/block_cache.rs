@@ -0,0 +1,203 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-memory cache of byte ranges read from SST files.
+//!
+//! SST files are immutable, so pages read from a file can be reused by later scans over
+//! the same time ranges without going to the object store again.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use common_telemetry::memory::{MemoryUsage, Subsystem};
+use common_telemetry::warn;
+use futures_util::future::BoxFuture;
+use metrics::increment_counter;
+use moka::sync::Cache;
+use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::file::metadata::ParquetMetaData;
+
+use crate::metrics::{BLOCK_CACHE_HIT, BLOCK_CACHE_MISS};
+
+/// Key of a cached byte range of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PageKey {
+    file_path: String,
+    range: Range<usize>,
+}
+
+/// LRU cache of byte ranges of SST files, bounded by the total size of cached bytes.
+#[derive(Debug)]
+pub struct BlockCache {
+    pages: Cache<PageKey, Bytes>,
+    /// Bytes of cached pages, reported to [Subsystem::Caches].
+    usage: Arc<MemoryUsage>,
+}
+
+fn page_weight(key: &PageKey, value: &Bytes) -> usize {
+    key.file_path.len() + value.len()
+}
+
+pub type BlockCacheRef = Arc<BlockCache>;
+
+impl BlockCache {
+    /// Creates a cache holding at most `capacity` bytes.
+    pub fn new(capacity: u64) -> BlockCache {
+        let usage = Arc::new(MemoryUsage::new(Subsystem::Caches));
+        let evicted_usage = usage.clone();
+        let pages = Cache::builder()
+            .max_capacity(capacity)
+            .weigher(|key: &PageKey, value: &Bytes| {
+                page_weight(key, value).try_into().unwrap_or(u32::MAX)
+            })
+            .eviction_listener(move |key, value, _cause| {
+                evicted_usage.sub(page_weight(&key, &value));
+            })
+            .support_invalidation_closures()
+            .build();
+        BlockCache { pages, usage }
+    }
+
+    fn get(&self, key: &PageKey) -> Option<Bytes> {
+        let value = self.pages.get(key);
+        if value.is_some() {
+            increment_counter!(BLOCK_CACHE_HIT);
+        } else {
+            increment_counter!(BLOCK_CACHE_MISS);
+        }
+        value
+    }
+
+    fn insert(&self, key: PageKey, value: Bytes) {
+        self.usage.add(page_weight(&key, &value));
+        self.pages.insert(key, value);
+    }
+
+    /// Evicts the cached pages of the files in `file_paths` in background.
+    pub fn evict_files(&self, file_paths: HashSet<String>) {
+        if file_paths.is_empty() {
+            return;
+        }
+        if let Err(e) = self
+            .pages
+            .invalidate_entries_if(move |key, _| file_paths.contains(&key.file_path))
+        {
+            warn!("Failed to evict pages from block cache, error: {}", e);
+        }
+    }
+}
+
+/// [AsyncFileReader] that serves byte ranges from the [BlockCache] if possible.
+pub struct CachedFileReader {
+    file_path: String,
+    inner: Box<dyn AsyncFileReader>,
+    cache: BlockCacheRef,
+}
+
+impl CachedFileReader {
+    pub fn new(
+        file_path: String,
+        inner: Box<dyn AsyncFileReader>,
+        cache: BlockCacheRef,
+    ) -> CachedFileReader {
+        CachedFileReader {
+            file_path,
+            inner,
+            cache,
+        }
+    }
+}
+
+impl AsyncFileReader for CachedFileReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, parquet::errors::Result<Bytes>> {
+        Box::pin(async move {
+            let key = PageKey {
+                file_path: self.file_path.clone(),
+                range: range.clone(),
+            };
+            if let Some(bytes) = self.cache.get(&key) {
+                return Ok(bytes);
+            }
+
+            let bytes = self.inner.get_bytes(range).await?;
+            self.cache.insert(key, bytes.clone());
+            Ok(bytes)
+        })
+    }
+
+    fn get_metadata(&mut self) -> BoxFuture<'_, parquet::errors::Result<Arc<ParquetMetaData>>> {
+        self.inner.get_metadata()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cached_file_reader() {
+        let cache = Arc::new(BlockCache::new(1024));
+        let content = b"0123456789".to_vec();
+        let mut reader = CachedFileReader::new(
+            "a.parquet".to_string(),
+            Box::new(Cursor::new(content)),
+            cache.clone(),
+        );
+
+        assert_eq!(&b"2345"[..], reader.get_bytes(2..6).await.unwrap());
+        let key = PageKey {
+            file_path: "a.parquet".to_string(),
+            range: 2..6,
+        };
+        assert_eq!(Some(Bytes::from_static(b"2345")), cache.get(&key));
+
+        // Served from the cache even though the underlying file changes.
+        let mut reader = CachedFileReader::new(
+            "a.parquet".to_string(),
+            Box::new(Cursor::new(b"abcdefghij".to_vec())),
+            cache.clone(),
+        );
+        assert_eq!(&b"2345"[..], reader.get_bytes(2..6).await.unwrap());
+        assert_eq!(&b"abcd"[..], reader.get_bytes(0..4).await.unwrap());
+
+        // Both pages with the file path are reported as memory of caches.
+        assert_eq!(2 * ("a.parquet".len() + 4) as i64, cache.usage.bytes());
+    }
+
+    #[test]
+    fn test_evict_files() {
+        let cache = BlockCache::new(1024);
+        for file_path in ["a.parquet", "b.parquet"] {
+            cache.insert(
+                PageKey {
+                    file_path: file_path.to_string(),
+                    range: 0..4,
+                },
+                Bytes::from_static(b"0123"),
+            );
+        }
+
+        cache.evict_files(HashSet::from(["a.parquet".to_string()]));
+        let key = |file_path: &str| PageKey {
+            file_path: file_path.to_string(),
+            range: 0..4,
+        };
+        assert!(cache.get(&key("a.parquet")).is_none());
+        assert!(cache.get(&key("b.parquet")).is_some());
+    }
+}
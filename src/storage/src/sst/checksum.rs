@@ -0,0 +1,250 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Block checksums of SST files.
+//!
+//! Files are split into fixed-size blocks while they are written and a CRC32
+//! checksum is computed for each block. The checksums are persisted in the
+//! [FileMeta](crate::sst::FileMeta) so the content can be verified on read.
+
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tokio::io::AsyncWrite;
+
+use crate::error::{CorruptedSstSnafu, Result};
+
+/// Default size of a checksum block (1 MiB).
+pub const DEFAULT_CHECKSUM_BLOCK_SIZE: u64 = 1024 * 1024;
+
+/// Per-block checksums of a SST file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FileChecksums {
+    /// Size of each block, the last block may be smaller.
+    pub block_size: u64,
+    /// CRC32 checksum of each block.
+    pub checksums: Vec<u32>,
+}
+
+impl FileChecksums {
+    /// Computes checksums of `content`.
+    pub fn compute(content: &[u8], block_size: u64) -> FileChecksums {
+        let mut hasher = BlockHasher::new(block_size);
+        hasher.update(content);
+        hasher.finish()
+    }
+
+    /// Verifies `content` of the `file` against the checksums, returns
+    /// [CorruptedSst](crate::error::Error::CorruptedSst) with the index of the
+    /// first mismatched block.
+    pub fn verify(&self, file: &str, content: &[u8]) -> Result<()> {
+        self.verify_computed(file, &FileChecksums::compute(content, self.block_size))
+    }
+
+    /// Verifies the checksums `actual` computed from the content of the `file` with the same
+    /// block size, like [FileChecksums::verify].
+    pub(crate) fn verify_computed(&self, file: &str, actual: &FileChecksums) -> Result<()> {
+        if let Some(block) = self
+            .checksums
+            .iter()
+            .zip(actual.checksums.iter())
+            .position(|(expect, actual)| expect != actual)
+        {
+            return CorruptedSstSnafu { file, block }.fail();
+        }
+
+        // The file is truncated or has extra bytes appended.
+        ensure!(
+            self.checksums.len() == actual.checksums.len(),
+            CorruptedSstSnafu {
+                file,
+                block: self.checksums.len().min(actual.checksums.len()),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// Computes block checksums incrementally.
+#[derive(Debug)]
+pub(crate) struct BlockHasher {
+    block_size: u64,
+    hasher: crc32fast::Hasher,
+    /// Bytes already fed into current block.
+    filled: u64,
+    checksums: Vec<u32>,
+}
+
+impl BlockHasher {
+    pub(crate) fn new(block_size: u64) -> BlockHasher {
+        assert!(block_size > 0, "Checksum block size must be positive");
+        BlockHasher {
+            block_size,
+            hasher: crc32fast::Hasher::new(),
+            filled: 0,
+            checksums: Vec::new(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let remaining = (self.block_size - self.filled) as usize;
+            let len = remaining.min(data.len());
+            self.hasher.update(&data[..len]);
+            self.filled += len as u64;
+            data = &data[len..];
+
+            if self.filled == self.block_size {
+                let hasher = std::mem::replace(&mut self.hasher, crc32fast::Hasher::new());
+                self.checksums.push(hasher.finalize());
+                self.filled = 0;
+            }
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> FileChecksums {
+        if self.filled > 0 {
+            self.checksums.push(self.hasher.finalize());
+        }
+        FileChecksums {
+            block_size: self.block_size,
+            checksums: self.checksums,
+        }
+    }
+}
+
+pub(crate) type BlockHasherRef = Arc<Mutex<BlockHasher>>;
+
+/// Writer that computes block checksums of all bytes written to the inner writer.
+pub(crate) struct ChecksumWriter<W> {
+    inner: W,
+    hasher: BlockHasherRef,
+}
+
+impl<W> ChecksumWriter<W> {
+    pub(crate) fn new(inner: W, hasher: BlockHasherRef) -> ChecksumWriter<W> {
+        ChecksumWriter { inner, hasher }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for ChecksumWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = &res {
+            this.hasher.lock().unwrap().update(&buf[..*written]);
+        }
+        res
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::prelude::ErrorExt;
+    use common_error::status_code::StatusCode;
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+    use crate::error::Error;
+
+    fn corrupted_block(res: Result<()>) -> usize {
+        let err = res.unwrap_err();
+        assert_eq!(StatusCode::StorageCorrupted, err.status_code());
+        match err {
+            Error::CorruptedSst { block, .. } => block,
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_compute_checksums() {
+        let checksums = FileChecksums::compute(b"", 4);
+        assert!(checksums.checksums.is_empty());
+
+        let checksums = FileChecksums::compute(b"abcdefghij", 4);
+        assert_eq!(4, checksums.block_size);
+        assert_eq!(
+            vec![
+                crc32fast::hash(b"abcd"),
+                crc32fast::hash(b"efgh"),
+                crc32fast::hash(b"ij")
+            ],
+            checksums.checksums
+        );
+
+        // Feeding data in pieces gets the same result.
+        let mut hasher = BlockHasher::new(4);
+        for piece in [&b"a"[..], b"bcdef", b"", b"ghij"] {
+            hasher.update(piece);
+        }
+        assert_eq!(checksums, hasher.finish());
+    }
+
+    #[test]
+    fn test_verify_checksums() {
+        let content = b"abcdefghij".to_vec();
+        let checksums = FileChecksums::compute(&content, 4);
+        checksums.verify("test.parquet", &content).unwrap();
+
+        let mut corrupted = content.clone();
+        corrupted[5] = b'x';
+        assert_eq!(
+            1,
+            corrupted_block(checksums.verify("test.parquet", &corrupted))
+        );
+
+        assert_eq!(
+            2,
+            corrupted_block(checksums.verify("test.parquet", &content[..8]))
+        );
+
+        let mut appended = content;
+        appended.extend_from_slice(b"klmn");
+        assert_eq!(
+            2,
+            corrupted_block(checksums.verify("test.parquet", &appended))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checksum_writer() {
+        let hasher = Arc::new(Mutex::new(BlockHasher::new(4)));
+        let mut writer = ChecksumWriter::new(Vec::new(), hasher.clone());
+        writer.write_all(b"abcde").await.unwrap();
+        writer.write_all(b"fghij").await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        let content = writer.inner.clone();
+        drop(writer);
+        let hasher = Arc::try_unwrap(hasher).unwrap().into_inner().unwrap();
+        assert_eq!(FileChecksums::compute(&content, 4), hasher.finish());
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Exports SST files as standard parquet files that external readers, such as Spark
+//! and Trino, could read directly from the object store.
+//!
+//! Exported files only contain user columns. Rows deleted by the delete operation
+//! are removed and the internal columns are dropped. The key value metadata of the
+//! file maps columns to their semantic types:
+//! - `greptime:export:timestamp`: name of the time index column.
+//! - `greptime:export:tags`: JSON array of tag (row key) column names.
+//! - `greptime:export:fields`: JSON array of field column names.
+
+use std::sync::Arc;
+
+use arrow::compute;
+use arrow::datatypes::{Field, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use arrow_array::{RecordBatch, UInt8Array};
+use common_telemetry::warn;
+use datatypes::schema::SchemaRef;
+use object_store::ObjectStore;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::OpType;
+
+use crate::error::{self, Result};
+use crate::read::Batch;
+use crate::schema::StoreSchema;
+use crate::sst::stream_writer::BufferedWriter;
+
+/// Directory under the SST directory to put exported files.
+pub const EXPORT_DIR: &str = "export/";
+/// Metadata key of the time index column name.
+pub const TIMESTAMP_KEY: &str = "greptime:export:timestamp";
+/// Metadata key of tag column names.
+pub const TAGS_KEY: &str = "greptime:export:tags";
+/// Metadata key of field column names.
+pub const FIELDS_KEY: &str = "greptime:export:fields";
+
+/// Writer to write rows of a SST to an exported parquet file.
+pub(crate) struct ExportWriter {
+    path: String,
+    writer: BufferedWriter,
+    arrow_schema: ArrowSchemaRef,
+    /// Indices of user columns in the SST schema.
+    projection: Vec<usize>,
+    /// Index of the op type column in the SST schema.
+    op_type_index: usize,
+}
+
+impl ExportWriter {
+    /// Creates a writer for SST with given `schema`, returns `None` if the schema
+    /// is not a schema of the storage engine.
+    pub(crate) async fn try_new(
+        path: String,
+        object_store: ObjectStore,
+        schema: &SchemaRef,
+        buffer_threshold: usize,
+    ) -> Result<Option<ExportWriter>> {
+        let store_schema = match StoreSchema::try_from(schema.arrow_schema().clone()) {
+            Ok(store_schema) => store_schema,
+            Err(e) => {
+                warn!(
+                    "Unable to export SST {} with a non storage schema: {}",
+                    path, e
+                );
+                return Ok(None);
+            }
+        };
+
+        let projection: Vec<_> = (0..store_schema.user_column_end()).collect();
+        let arrow_schema = export_arrow_schema(&store_schema);
+        let writer_props = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .set_key_value_metadata(Some(export_metadata(&store_schema)?))
+            .build();
+        let writer = BufferedWriter::try_new_with_arrow_schema(
+            path.clone(),
+            object_store,
+            arrow_schema.clone(),
+            Some(writer_props),
+            buffer_threshold,
+        )
+        .await?;
+
+        Ok(Some(ExportWriter {
+            path,
+            writer,
+            arrow_schema,
+            projection,
+            op_type_index: store_schema.op_type_index(),
+        }))
+    }
+
+    /// Writes rows in the SST `batch` that are not deleted.
+    pub(crate) async fn write(&mut self, batch: &Batch) -> Result<()> {
+        let columns = self
+            .projection
+            .iter()
+            .map(|idx| batch.column(*idx).to_arrow_array())
+            .collect();
+        let record_batch = RecordBatch::try_new(self.arrow_schema.clone(), columns)
+            .context(error::NewRecordBatchSnafu)?;
+
+        let op_types = batch.column(self.op_type_index).to_arrow_array();
+        let op_types = op_types
+            .as_any()
+            .downcast_ref::<UInt8Array>()
+            .with_context(|| error::BatchCorruptedSnafu {
+                message: format!("Unexpected op type column {:?}", op_types.data_type()),
+            })?;
+        let not_deleted = compute::neq_scalar(op_types, OpType::Delete.as_u8())
+            .context(error::NewRecordBatchSnafu)?;
+        let record_batch = compute::filter_record_batch(&record_batch, &not_deleted)
+            .context(error::NewRecordBatchSnafu)?;
+        if record_batch.num_rows() == 0 {
+            return Ok(());
+        }
+
+        self.writer.write_record_batch(&record_batch).await
+    }
+
+    /// Finishes the exported file.
+    pub(crate) async fn close(self) -> Result<()> {
+        self.writer.close().await.map(|_| ())
+    }
+
+    /// Aborts the exported file.
+    pub(crate) async fn abort(self) {
+        if !self.writer.abort().await {
+            warn!("Partial exported file {} has been uploaded", self.path);
+        }
+    }
+}
+
+/// Returns the arrow schema of user columns without metadata of GreptimeDB.
+fn export_arrow_schema(store_schema: &StoreSchema) -> ArrowSchemaRef {
+    let fields = store_schema.arrow_schema().fields()[..store_schema.user_column_end()]
+        .iter()
+        .map(|field| Field::new(field.name(), field.data_type().clone(), field.is_nullable()))
+        .collect::<Vec<_>>();
+    Arc::new(ArrowSchema::new(fields))
+}
+
+/// Returns metadata that maps column names to their semantic types.
+fn export_metadata(store_schema: &StoreSchema) -> Result<Vec<KeyValue>> {
+    let timestamp_index = store_schema.schema().timestamp_index();
+    let tags = store_schema
+        .row_key_indices()
+        .filter(|idx| Some(*idx) != timestamp_index)
+        .map(|idx| store_schema.column_name(idx))
+        .collect::<Vec<_>>();
+    let fields = store_schema
+        .value_indices()
+        .map(|idx| store_schema.column_name(idx))
+        .collect::<Vec<_>>();
+
+    let mut metadata = vec![
+        KeyValue::new(
+            TAGS_KEY.to_string(),
+            serde_json::to_string(&tags).context(error::EncodeJsonSnafu)?,
+        ),
+        KeyValue::new(
+            FIELDS_KEY.to_string(),
+            serde_json::to_string(&fields).context(error::EncodeJsonSnafu)?,
+        ),
+    ];
+    if let Some(idx) = timestamp_index {
+        metadata.push(KeyValue::new(
+            TIMESTAMP_KEY.to_string(),
+            store_schema.column_name(idx).to_string(),
+        ));
+    }
+
+    Ok(metadata)
+}
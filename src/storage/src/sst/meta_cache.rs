@@ -0,0 +1,114 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-memory cache of parsed SST metadata.
+//!
+//! The parquet footer of an SST file holds the schema and the min/max statistics used to
+//! prune row groups. Caching the parsed footer avoids fetching and decoding it from the
+//! object store each time the file is scanned.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use metrics::increment_counter;
+use moka::sync::Cache;
+use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::file::metadata::ParquetMetaData;
+
+use crate::metrics::{SST_META_CACHE_HIT, SST_META_CACHE_MISS};
+use crate::sst::FileId;
+
+/// LRU cache of parsed parquet metadata keyed by the id of SST files.
+#[derive(Debug)]
+pub struct SstMetaCache {
+    metas: Cache<FileId, Arc<ParquetMetaData>>,
+}
+
+pub type SstMetaCacheRef = Arc<SstMetaCache>;
+
+impl SstMetaCache {
+    /// Creates a cache holding metadata of at most `capacity` files.
+    pub fn new(capacity: u64) -> SstMetaCache {
+        SstMetaCache {
+            metas: Cache::new(capacity),
+        }
+    }
+
+    pub(crate) fn get(&self, file_id: &FileId) -> Option<Arc<ParquetMetaData>> {
+        let value = self.metas.get(file_id);
+        if value.is_some() {
+            increment_counter!(SST_META_CACHE_HIT);
+        } else {
+            increment_counter!(SST_META_CACHE_MISS);
+        }
+        value
+    }
+
+    fn insert(&self, file_id: FileId, metadata: Arc<ParquetMetaData>) {
+        self.metas.insert(file_id, metadata);
+    }
+
+    /// Evicts the metadata of the file with `file_id`.
+    pub fn evict(&self, file_id: &FileId) {
+        self.metas.invalidate(file_id);
+    }
+}
+
+/// [AsyncFileReader] that loads the metadata of the file from the [SstMetaCache] if possible.
+pub struct CachedMetaReader {
+    file_id: FileId,
+    inner: Box<dyn AsyncFileReader>,
+    cache: SstMetaCacheRef,
+}
+
+impl CachedMetaReader {
+    pub fn new(
+        file_id: FileId,
+        inner: Box<dyn AsyncFileReader>,
+        cache: SstMetaCacheRef,
+    ) -> CachedMetaReader {
+        CachedMetaReader {
+            file_id,
+            inner,
+            cache,
+        }
+    }
+}
+
+impl AsyncFileReader for CachedMetaReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, parquet::errors::Result<Bytes>> {
+        self.inner.get_bytes(range)
+    }
+
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, parquet::errors::Result<Vec<Bytes>>> {
+        self.inner.get_byte_ranges(ranges)
+    }
+
+    fn get_metadata(&mut self) -> BoxFuture<'_, parquet::errors::Result<Arc<ParquetMetaData>>> {
+        Box::pin(async move {
+            if let Some(metadata) = self.cache.get(&self.file_id) {
+                return Ok(metadata);
+            }
+
+            let metadata = self.inner.get_metadata().await?;
+            self.cache.insert(self.file_id, metadata.clone());
+            Ok(metadata)
+        })
+    }
+}
@@ -15,14 +15,17 @@
 //! Parquet sst format.
 
 use std::collections::HashMap;
+use std::io::Cursor;
 use std::pin::Pin;
 use std::sync::Arc;
 
 use arrow::datatypes::DataType;
+use arrow::row::{OwnedRow, RowConverter, SortField};
 use arrow_array::types::Int64Type;
 use arrow_array::{
-    Array, PrimitiveArray, TimestampMicrosecondArray, TimestampMillisecondArray,
-    TimestampNanosecondArray, TimestampSecondArray,
+    new_null_array, Array, ArrayRef, PrimitiveArray, TimestampMicrosecondArray,
+    TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray, UInt64Array,
+    UInt8Array,
 };
 use async_compat::CompatExt;
 use async_stream::try_stream;
@@ -38,13 +41,16 @@ use datatypes::prelude::ConcreteDataType;
 use futures_util::{Stream, StreamExt, TryStreamExt};
 use object_store::ObjectStore;
 use parquet::arrow::arrow_reader::{ArrowPredicate, RowFilter};
+use parquet::arrow::async_reader::AsyncFileReader;
 use parquet::arrow::{ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{Compression, Encoding, ZstdLevel};
-use parquet::file::metadata::KeyValue;
-use parquet::file::properties::WriterProperties;
+use parquet::file::metadata::{KeyValue, ParquetMetaData};
+use parquet::file::properties::{WriterProperties, WriterPropertiesBuilder};
+use parquet::file::statistics::Statistics;
 use parquet::format::FileMetaData;
-use parquet::schema::types::SchemaDescriptor;
-use snafu::{OptionExt, ResultExt};
+use parquet::schema::types::{ColumnPath, SchemaDescriptor};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::{ColumnEncoding, ColumnEncodings, OpType, SequenceNumber};
 use table::predicate::Predicate;
 use tokio::io::BufReader;
 
@@ -53,6 +59,10 @@ use crate::read::{Batch, BatchReader};
 use crate::schema::compat::ReadAdapter;
 use crate::schema::{ProjectedSchemaRef, StoreSchema};
 use crate::sst;
+use crate::sst::block_cache::{BlockCacheRef, CachedFileReader};
+use crate::sst::export::ExportWriter;
+use crate::sst::meta_cache::{CachedMetaReader, SstMetaCacheRef};
+use crate::sst::rate_limit::IoRateLimiterRef;
 use crate::sst::stream_writer::BufferedWriter;
 use crate::sst::{FileHandle, Source, SstInfo};
 
@@ -62,6 +72,9 @@ pub struct ParquetWriter<'a> {
     source: Source,
     object_store: ObjectStore,
     max_row_group_size: usize,
+    /// Path to write an exported copy of the SST.
+    export_path: Option<String>,
+    io_rate_limiter: Option<IoRateLimiterRef>,
 }
 
 impl<'a> ParquetWriter<'a> {
@@ -71,9 +84,23 @@ impl<'a> ParquetWriter<'a> {
             source,
             object_store,
             max_row_group_size: 4096, // TODO(hl): make this configurable
+            export_path: None,
+            io_rate_limiter: None,
         }
     }
 
+    /// Limits the bytes written per second by `io_rate_limiter`.
+    pub fn with_io_rate_limiter(mut self, io_rate_limiter: IoRateLimiterRef) -> Self {
+        self.io_rate_limiter = Some(io_rate_limiter);
+        self
+    }
+
+    /// Also writes rows to an exported parquet file in `export_path`, see [sst::export].
+    pub fn with_export_path(mut self, export_path: String) -> Self {
+        self.export_path = Some(export_path);
+        self
+    }
+
     pub async fn write_sst(self, opts: &sst::WriteOptions) -> Result<Option<SstInfo>> {
         self.write_rows(None, opts).await
     }
@@ -87,16 +114,8 @@ impl<'a> ParquetWriter<'a> {
         opts: &sst::WriteOptions,
     ) -> Result<Option<SstInfo>> {
         let schema = self.source.schema();
-        let writer_props = WriterProperties::builder()
-            .set_compression(Compression::ZSTD(ZstdLevel::default()))
-            .set_encoding(Encoding::PLAIN)
-            .set_max_row_group_size(self.max_row_group_size)
-            .set_key_value_metadata(extra_meta.map(|map| {
-                map.iter()
-                    .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
-                    .collect::<Vec<_>>()
-            }))
-            .build();
+        let writer_props =
+            build_writer_properties(&schema, self.max_row_group_size, extra_meta, opts);
 
         let mut buffered_writer = BufferedWriter::try_new(
             self.file_path.to_string(),
@@ -106,14 +125,36 @@ impl<'a> ParquetWriter<'a> {
             opts.sst_write_buffer_size.as_bytes() as usize,
         )
         .await?;
+        let mut export_writer = match self.export_path.take() {
+            Some(export_path) => {
+                ExportWriter::try_new(
+                    export_path,
+                    self.object_store.clone(),
+                    &schema,
+                    opts.sst_write_buffer_size.as_bytes() as usize,
+                )
+                .await?
+            }
+            None => None,
+        };
         let mut rows_written = 0;
 
         while let Some(batch) = self.source.next_batch().await? {
             buffered_writer.write(&batch).await?;
+            if let Some(export_writer) = &mut export_writer {
+                export_writer.write(&batch).await?;
+            }
             rows_written += batch.num_rows();
+            if let Some(io_rate_limiter) = &self.io_rate_limiter {
+                let bytes = batch.columns().iter().map(|c| c.memory_size()).sum();
+                io_rate_limiter.acquire(bytes).await;
+            }
         }
 
         if rows_written == 0 {
+            if let Some(export_writer) = export_writer {
+                export_writer.abort().await;
+            }
             // if the source does not contain any batch, we skip writing an empty parquet file.
             if !buffered_writer.abort().await {
                 warn!(
@@ -124,7 +165,10 @@ impl<'a> ParquetWriter<'a> {
             return Ok(None);
         }
 
-        let (file_meta, file_size) = buffered_writer.close().await?;
+        let (file_meta, file_size, checksums) = buffered_writer.close().await?;
+        if let Some(export_writer) = export_writer {
+            export_writer.close().await?;
+        }
         let time_range = decode_timestamp_range(&file_meta, &schema).ok().flatten();
 
         // object_store.write will make sure all bytes are written or an error is raised.
@@ -132,10 +176,93 @@ impl<'a> ParquetWriter<'a> {
             time_range,
             file_size,
             num_rows: rows_written,
+            checksums: Some(checksums),
         }))
     }
 }
 
+/// Max size of the dictionary page of a column chunk if the strings are compacted, larger
+/// than the default 1MiB so dictionaries of long strings like log messages hold more
+/// distinct values before falling back.
+const COMPACT_STRINGS_DICTIONARY_PAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Builds the properties of the parquet writer of an SST with `schema`.
+fn build_writer_properties(
+    schema: &datatypes::schema::SchemaRef,
+    max_row_group_size: usize,
+    extra_meta: Option<HashMap<String, String>>,
+    opts: &sst::WriteOptions,
+) -> WriterProperties {
+    let mut builder = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .set_encoding(Encoding::PLAIN)
+        .set_max_row_group_size(max_row_group_size)
+        .set_key_value_metadata(extra_meta.map(|map| {
+            map.iter()
+                .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
+                .collect::<Vec<_>>()
+        }));
+    if opts.compact_strings {
+        builder = compact_string_columns(builder, schema);
+    }
+    if !opts.column_encodings.is_empty() {
+        builder = encode_columns(builder, schema, &opts.column_encodings);
+    }
+    builder.build()
+}
+
+/// Encodes string and binary columns with a larger dictionary per row group, falling back
+/// to front coding (`DELTA_BYTE_ARRAY`) instead of `PLAIN` once the dictionary is full.
+/// Rows in an SST are sorted, so adjacent values of key columns usually share long
+/// prefixes.
+fn compact_string_columns(
+    mut builder: WriterPropertiesBuilder,
+    schema: &datatypes::schema::SchemaRef,
+) -> WriterPropertiesBuilder {
+    // The limit can't be set per column.
+    builder = builder.set_dictionary_pagesize_limit(COMPACT_STRINGS_DICTIONARY_PAGE_SIZE);
+    for field in schema.arrow_schema().fields() {
+        if matches!(
+            field.data_type(),
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Binary | DataType::LargeBinary
+        ) {
+            let path = ColumnPath::from(field.name().as_str());
+            builder = builder
+                .set_column_dictionary_enabled(path.clone(), true)
+                .set_column_encoding(path, Encoding::DELTA_BYTE_ARRAY);
+        }
+    }
+    builder
+}
+
+/// Overrides the encodings of columns by `column_encodings`, skipping columns missing in
+/// the `schema` or whose types don't support their encodings, which may happen after
+/// altering the table. Dictionaries of these columns are disabled as the encodings only
+/// apply once dictionaries grow too large otherwise.
+///
+/// Readers don't need to know the encodings as each parquet page records its encoding.
+fn encode_columns(
+    mut builder: WriterPropertiesBuilder,
+    schema: &datatypes::schema::SchemaRef,
+    column_encodings: &ColumnEncodings,
+) -> WriterPropertiesBuilder {
+    for column in schema.column_schemas() {
+        let Some(encoding) = column_encodings.get(&column.name) else { continue; };
+        if !encoding.supports(&column.data_type) {
+            continue;
+        }
+        let encoding = match encoding {
+            ColumnEncoding::Delta => Encoding::DELTA_BINARY_PACKED,
+            ColumnEncoding::ByteStreamSplit => Encoding::BYTE_STREAM_SPLIT,
+        };
+        let path = ColumnPath::from(column.name.as_str());
+        builder = builder
+            .set_column_dictionary_enabled(path.clone(), false)
+            .set_column_encoding(path, encoding);
+    }
+    builder
+}
+
 fn decode_timestamp_range(
     file_meta: &FileMetaData,
     schema: &datatypes::schema::SchemaRef,
@@ -214,6 +341,230 @@ fn decode_timestamp_range_inner(
     )))
 }
 
+/// A SST file staged to be attached to a region, rewritten into the region.
+#[derive(Debug)]
+pub(crate) struct StagedSstInfo {
+    pub(crate) time_range: Option<(Timestamp, Timestamp)>,
+    pub(crate) file_size: u64,
+    /// Max sequence of rows in the file.
+    pub(crate) max_sequence: SequenceNumber,
+}
+
+/// Rewrites the parquet file staged at `path` into the SST file at `file_path` with the
+/// region `schema`, the file is read and written batch by batch.
+///
+/// The staged file could be written by the storage engine or by other tools. Columns
+/// are matched by names, missing nullable columns are filled with nulls, and rows of a
+/// file without the sequence and op type columns are puts at sequence 0, so rows with
+/// the same key in the region override them. Rows must be sorted by the row key.
+pub(crate) async fn rewrite_staged_sst(
+    path: &str,
+    object_store: &ObjectStore,
+    file_path: &str,
+    schema: &StoreSchema,
+    opts: &sst::WriteOptions,
+) -> Result<StagedSstInfo> {
+    let invalid = |reason: String| error::InvalidStagedSstSnafu { path, reason }.build();
+
+    let reader = object_store
+        .reader(path)
+        .await
+        .context(ReadObjectSnafu { path })?
+        .compat();
+    let builder = ParquetRecordBatchStreamBuilder::new(BufReader::new(reader))
+        .await
+        .map_err(|e| invalid(format!("not a parquet file, {e}")))?;
+    let indices = staged_column_indices(builder.schema(), schema).map_err(invalid)?;
+    let mut stream = builder.build().context(ReadParquetSnafu { file: path })?;
+
+    let arrow_schema = schema.arrow_schema();
+    let row_key_end = schema.row_key_end();
+    let mut converter = RowConverter::new(
+        arrow_schema.fields()[..row_key_end]
+            .iter()
+            .map(|field| SortField::new(field.data_type().clone()))
+            .collect(),
+    )
+    .context(error::EncodeArrowSnafu)?;
+    let writer_props = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .set_encoding(Encoding::PLAIN)
+        .build();
+    let mut writer = BufferedWriter::try_new(
+        file_path.to_string(),
+        object_store.clone(),
+        schema.schema(),
+        Some(writer_props),
+        opts.sst_write_buffer_size.as_bytes() as usize,
+    )
+    .await?;
+
+    let mut last_key: Option<OwnedRow> = None;
+    let mut num_rows = 0;
+    let mut max_sequence = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch.context(ReadParquetSnafu { file: path })?;
+        let columns =
+            indices
+                .iter()
+                .zip(arrow_schema.fields())
+                .enumerate()
+                .map(|(i, (index, field))| match index {
+                    Some(index) => batch.column(*index).clone(),
+                    None if i == schema.sequence_index() => {
+                        Arc::new(UInt64Array::from(vec![0; batch.num_rows()])) as ArrayRef
+                    }
+                    None if i == schema.op_type_index() => Arc::new(UInt8Array::from(vec![
+                        OpType::Put.as_u8();
+                        batch.num_rows()
+                    ])) as ArrayRef,
+                    None => new_null_array(field.data_type(), batch.num_rows()),
+                })
+                .collect::<Vec<_>>();
+
+        let keys = converter
+            .convert_columns(&columns[..row_key_end])
+            .context(error::EncodeArrowSnafu)?;
+        for i in 0..keys.num_rows() {
+            let sorted = match (i, &last_key) {
+                (0, Some(last_key)) => last_key.row() <= keys.row(0),
+                (0, None) => true,
+                _ => keys.row(i - 1) <= keys.row(i),
+            };
+            ensure!(
+                sorted,
+                error::InvalidStagedSstSnafu {
+                    path,
+                    reason: "rows are not sorted by the row key",
+                }
+            );
+        }
+        if keys.num_rows() > 0 {
+            last_key = Some(keys.row(keys.num_rows() - 1).owned());
+        }
+
+        let sequences = columns[schema.sequence_index()]
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .with_context(|| error::InvalidStagedSstSnafu {
+                path,
+                reason: "sequence column is not uint64",
+            })?;
+        max_sequence = max_sequence.max(arrow::compute::max(sequences).unwrap_or_default());
+        num_rows += batch.num_rows();
+
+        let batch = RecordBatch::try_new(arrow_schema.clone(), columns)
+            .context(error::NewRecordBatchSnafu)?;
+        writer.write_record_batch(&batch).await?;
+    }
+
+    if num_rows == 0 {
+        if !writer.abort().await {
+            warn!(
+                "Partial file {} has been uploaded to remote storage",
+                file_path
+            );
+        }
+        return error::InvalidStagedSstSnafu {
+            path,
+            reason: "no rows in the file",
+        }
+        .fail();
+    }
+    let (file_meta, file_size, _) = writer.close().await?;
+    let time_range = decode_timestamp_range(&file_meta, schema.schema())
+        .ok()
+        .flatten();
+
+    Ok(StagedSstInfo {
+        time_range,
+        file_size,
+        max_sequence,
+    })
+}
+
+/// Returns the index in the `staged` schema of each column of the region `schema`,
+/// `None` if the column is filled on rewrite, or the reason the schema is invalid.
+fn staged_column_indices(
+    staged: &arrow::datatypes::Schema,
+    schema: &StoreSchema,
+) -> std::result::Result<Vec<Option<usize>>, String> {
+    let arrow_schema = schema.arrow_schema();
+    if let Some(field) = staged
+        .fields()
+        .iter()
+        .find(|field| arrow_schema.index_of(field.name()).is_err())
+    {
+        return Err(format!("unknown column {}", field.name()));
+    }
+
+    arrow_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match staged.index_of(field.name()) {
+            Ok(index) if staged.field(index).data_type() == field.data_type() => Ok(Some(index)),
+            Ok(index) => Err(format!(
+                "column {} has type {:?}, expect {:?}",
+                field.name(),
+                staged.field(index).data_type(),
+                field.data_type()
+            )),
+            Err(_) if i >= schema.user_column_end() || field.is_nullable() => Ok(None),
+            Err(_) => Err(format!("missing column {}", field.name())),
+        })
+        .collect()
+}
+
+/// Parses the metadata of the SST file at `path`, returns the time range of its rows from
+/// the statistics of the file, `None` if the file has no timestamp statistics.
+pub(crate) async fn read_sst_time_range(
+    path: &str,
+    object_store: &ObjectStore,
+) -> Result<Option<(Timestamp, Timestamp)>> {
+    let invalid = |reason: String| error::InvalidSstSnafu { path, reason }.build();
+
+    let reader = object_store
+        .reader(path)
+        .await
+        .context(ReadObjectSnafu { path })?
+        .compat();
+    let builder = ParquetRecordBatchStreamBuilder::new(BufReader::new(reader))
+        .await
+        .map_err(|e| invalid(format!("not a parquet file, {e}")))?;
+    let schema = StoreSchema::try_from(builder.schema().clone())
+        .map_err(|e| invalid(format!("not written by the storage engine, {e}")))?;
+
+    let (Some(ts_index), Some(ts_column)) = (
+        schema.schema().timestamp_index(),
+        schema.schema().timestamp_column(),
+    ) else { return Ok(None); };
+    let unit = match &ts_column.data_type {
+        ConcreteDataType::Timestamp(type_) => type_.unit(),
+        _ => TimeUnit::Millisecond,
+    };
+    Ok(column_min_max(builder.metadata(), ts_index)
+        .map(|(start, end)| (Timestamp::new(start, unit), Timestamp::new(end, unit))))
+}
+
+/// Returns the min and max values of the int64 column at `index` from the statistics of
+/// all row groups, `None` if any row group has no statistics.
+fn column_min_max(metadata: &ParquetMetaData, index: usize) -> Option<(i64, i64)> {
+    let mut min_max: Option<(i64, i64)> = None;
+    for row_group in metadata.row_groups() {
+        let Some(Statistics::Int64(stats)) = row_group.column(index).statistics() else { return None; };
+        if !stats.has_min_max_set() {
+            return None;
+        }
+        let (min, max) = (*stats.min(), *stats.max());
+        min_max = Some(match min_max {
+            Some((start, end)) => (start.min(min), end.max(max)),
+            None => (min, max),
+        });
+    }
+    min_max
+}
+
 pub struct ParquetReader {
     // Holds the file handle to avoid the file purge purge it.
     file_handle: FileHandle,
@@ -221,6 +572,12 @@ pub struct ParquetReader {
     projected_schema: ProjectedSchemaRef,
     predicate: Predicate,
     time_range: TimestampRange,
+    /// Percentage of row groups to read, `None` to read all.
+    sample_percent: Option<f64>,
+    /// Whether to verify checksums of the file before reading it.
+    verify_checksum: bool,
+    block_cache: Option<BlockCacheRef>,
+    meta_cache: Option<SstMetaCacheRef>,
 }
 
 impl ParquetReader {
@@ -230,6 +587,7 @@ impl ParquetReader {
         projected_schema: ProjectedSchemaRef,
         predicate: Predicate,
         time_range: TimestampRange,
+        sample_percent: Option<f64>,
     ) -> ParquetReader {
         ParquetReader {
             file_handle,
@@ -237,20 +595,78 @@ impl ParquetReader {
             projected_schema,
             predicate,
             time_range,
+            sample_percent,
+            verify_checksum: false,
+            block_cache: None,
+            meta_cache: None,
         }
     }
 
-    pub async fn chunk_stream(&self) -> Result<ChunkStream> {
-        let file_path = self.file_handle.file_path();
+    /// Sets the cache to read pages of the file from.
+    pub fn with_block_cache(mut self, block_cache: Option<BlockCacheRef>) -> ParquetReader {
+        self.block_cache = block_cache;
+        self
+    }
+
+    /// Sets the cache to load the metadata of the file from.
+    pub fn with_meta_cache(mut self, meta_cache: Option<SstMetaCacheRef>) -> ParquetReader {
+        self.meta_cache = meta_cache;
+        self
+    }
+
+    /// Sets whether to verify checksums of the file before reading it.
+    pub fn with_checksum_verification(mut self, verify_checksum: bool) -> ParquetReader {
+        self.verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Opens the file, verifying its checksums if required.
+    async fn open_file(&self, file_path: &str) -> Result<Box<dyn AsyncFileReader>> {
         let operator = self.object_store.clone();
 
+        // Files are immutable, so each file is only verified on its first read.
+        if self.verify_checksum && !self.file_handle.verified() {
+            if let Some(checksums) = &self.file_handle.meta().checksums {
+                // Checksums are computed over the whole file, so we need to read
+                // all content in memory to verify it.
+                let content = operator
+                    .read(file_path)
+                    .await
+                    .context(ReadObjectSnafu { path: file_path })?;
+                checksums.verify(file_path, &content)?;
+                self.file_handle.mark_verified();
+                return Ok(Box::new(Cursor::new(content)));
+            }
+        }
+
         let reader = operator
-            .reader(&file_path)
+            .reader(file_path)
             .await
-            .context(ReadObjectSnafu { path: &file_path })?
+            .context(ReadObjectSnafu { path: file_path })?
             .compat();
-        let buf_reader = BufReader::new(reader);
-        let builder = ParquetRecordBatchStreamBuilder::new(buf_reader)
+        let reader = Box::new(BufReader::new(reader));
+        match &self.block_cache {
+            Some(cache) => Ok(Box::new(CachedFileReader::new(
+                file_path.to_string(),
+                reader,
+                cache.clone(),
+            ))),
+            None => Ok(reader),
+        }
+    }
+
+    pub async fn chunk_stream(&self) -> Result<ChunkStream> {
+        let file_path = self.file_handle.file_path();
+
+        let mut reader = self.open_file(&file_path).await?;
+        if let Some(cache) = &self.meta_cache {
+            reader = Box::new(CachedMetaReader::new(
+                self.file_handle.file_id(),
+                reader,
+                cache.clone(),
+            ));
+        }
+        let builder = ParquetRecordBatchStreamBuilder::new(reader)
             .await
             .context(ReadParquetSnafu { file: &file_path })?;
         let arrow_schema = builder.schema().clone();
@@ -271,6 +687,7 @@ impl ParquetReader {
             .into_iter()
             .enumerate()
             .filter_map(|(idx, valid)| if valid { Some(idx) } else { None })
+            .filter(|_| sst::sampled(self.sample_percent))
             .collect::<Vec<_>>();
 
         let parquet_schema_desc = builder.metadata().file_metadata().schema_descr_ptr();
@@ -547,7 +964,9 @@ mod tests {
         tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
     };
     use crate::schema::ProjectedSchema;
-    use crate::sst::{FileId, FileMeta};
+    use crate::sst::block_cache::BlockCache;
+    use crate::sst::meta_cache::SstMetaCache;
+    use crate::sst::{FileId, FileMeta, StorageTier};
 
     fn create_object_store(root: &str) -> ObjectStore {
         let mut builder = Fs::default();
@@ -704,11 +1123,12 @@ mod tests {
 
         let projected_schema = Arc::new(ProjectedSchema::new(schema, Some(vec![1])).unwrap());
         let reader = ParquetReader::new(
-            sst_file_handle,
-            operator,
-            projected_schema,
+            sst_file_handle.clone(),
+            operator.clone(),
+            projected_schema.clone(),
             Predicate::empty(),
             TimestampRange::min_to_max(),
+            None,
         );
 
         let mut rows_fetched = 0;
@@ -717,6 +1137,18 @@ mod tests {
             rows_fetched += res.num_rows();
         }
         assert_eq!(rows_total, rows_fetched);
+
+        // Sampling 0 percent skips all row groups.
+        let reader = ParquetReader::new(
+            sst_file_handle,
+            operator,
+            projected_schema,
+            Predicate::empty(),
+            TimestampRange::min_to_max(),
+            Some(0.0),
+        );
+        let mut stream = reader.chunk_stream().await.unwrap();
+        assert!(stream.next_batch().await.unwrap().is_none());
     }
 
     fn new_file_handle(file_id: FileId) -> FileHandle {
@@ -732,6 +1164,9 @@ mod tests {
                 )),
                 level: 0,
                 file_size: 0,
+                checksums: None,
+                tier: StorageTier::Hot,
+                source_dir: None,
             },
             layer,
             file_purger,
@@ -802,6 +1237,7 @@ mod tests {
             projected_schema,
             Predicate::empty(),
             TimestampRange::min_to_max(),
+            None,
         );
 
         let mut stream = reader.chunk_stream().await.unwrap();
@@ -817,6 +1253,152 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_parquet_reader_verify_checksum() {
+        common_telemetry::init_default_ut_logging();
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema.clone());
+
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2002, 1)], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(7), Some(1234)),
+            ], // values
+        );
+
+        let dir = create_temp_dir("read_parquet_checksum");
+        let object_store = create_object_store(dir.path().to_str().unwrap());
+        let file_id = FileId::random();
+        let sst_file_name = file_id.as_parquet();
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let writer = ParquetWriter::new(&sst_file_name, Source::Iter(iter), object_store.clone());
+
+        let SstInfo {
+            time_range,
+            file_size,
+            checksums,
+            ..
+        } = writer
+            .write_sst(&sst::WriteOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        let checksums = checksums.unwrap();
+        assert_eq!(1, checksums.checksums.len());
+
+        let new_file_handle = || {
+            FileHandle::new(
+                FileMeta {
+                    region_id: 0,
+                    file_id,
+                    time_range,
+                    level: 0,
+                    file_size,
+                    checksums: Some(checksums.clone()),
+                    tier: StorageTier::Hot,
+                    source_dir: None,
+                },
+                Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
+                new_noop_file_purger(),
+            )
+        };
+        let projected_schema = Arc::new(ProjectedSchema::new(schema, Some(vec![1])).unwrap());
+        let new_reader = |file_handle: &FileHandle| {
+            ParquetReader::new(
+                file_handle.clone(),
+                object_store.clone(),
+                projected_schema.clone(),
+                Predicate::empty(),
+                TimestampRange::min_to_max(),
+                None,
+            )
+            .with_checksum_verification(true)
+        };
+
+        let file_handle = new_file_handle();
+        assert!(!file_handle.verified());
+        let mut stream = new_reader(&file_handle).chunk_stream().await.unwrap();
+        let batch = stream.next_batch().await.unwrap().unwrap();
+        assert_eq!(3, batch.num_rows());
+        // The file is only verified on the first read.
+        assert!(file_handle.verified());
+        let mut stream = new_reader(&file_handle).chunk_stream().await.unwrap();
+        let batch = stream.next_batch().await.unwrap().unwrap();
+        assert_eq!(3, batch.num_rows());
+
+        // Flips a byte in the middle of the file.
+        let mut content = object_store.read(&sst_file_name).await.unwrap();
+        let pos = content.len() / 2;
+        content[pos] = !content[pos];
+        object_store.write(&sst_file_name, content).await.unwrap();
+
+        // The handle of the reopened region verifies the file again.
+        match new_reader(&new_file_handle()).chunk_stream().await {
+            Err(error::Error::CorruptedSst { file, block, .. }) => {
+                assert_eq!(sst_file_name, file);
+                assert_eq!(0, block);
+            }
+            Err(e) => panic!("Unexpected error: {e:?}"),
+            Ok(_) => panic!("Corrupted file should not be read"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parquet_reader_with_caches() {
+        common_telemetry::init_default_ut_logging();
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema.clone());
+
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2002, 1)], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(7), Some(1234)),
+            ], // values
+        );
+
+        let dir = create_temp_dir("read_parquet_caches");
+        let object_store = create_object_store(dir.path().to_str().unwrap());
+        let file_handle = new_file_handle(FileId::random());
+        let sst_file_name = file_handle.file_name();
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let writer = ParquetWriter::new(&sst_file_name, Source::Iter(iter), object_store.clone());
+        writer
+            .write_sst(&sst::WriteOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let block_cache = Arc::new(BlockCache::new(1024 * 1024));
+        let meta_cache = Arc::new(SstMetaCache::new(16));
+        let projected_schema = Arc::new(ProjectedSchema::new(schema, Some(vec![1])).unwrap());
+        for _ in 0..2 {
+            let reader = ParquetReader::new(
+                file_handle.clone(),
+                object_store.clone(),
+                projected_schema.clone(),
+                Predicate::empty(),
+                TimestampRange::min_to_max(),
+                None,
+            )
+            .with_block_cache(Some(block_cache.clone()))
+            .with_meta_cache(Some(meta_cache.clone()));
+            let mut stream = reader.chunk_stream().await.unwrap();
+            let batch = stream.next_batch().await.unwrap().unwrap();
+            assert_eq!(3, batch.num_rows());
+        }
+        assert!(meta_cache.get(&file_handle.file_id()).is_some());
+    }
+
     async fn check_range_read(
         file_handle: FileHandle,
         object_store: ObjectStore,
@@ -824,8 +1406,14 @@ mod tests {
         range: TimestampRange,
         expect: Vec<i64>,
     ) {
-        let reader =
-            ParquetReader::new(file_handle, object_store, schema, Predicate::empty(), range);
+        let reader = ParquetReader::new(
+            file_handle,
+            object_store,
+            schema,
+            Predicate::empty(),
+            range,
+            None,
+        );
         let mut stream = reader.chunk_stream().await.unwrap();
         let result = stream.next_batch().await;
 
@@ -993,6 +1581,247 @@ mod tests {
         assert!(sst_info_opt.is_none());
     }
 
+    #[tokio::test]
+    async fn test_parquet_writer_export() {
+        common_telemetry::init_default_ut_logging();
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema);
+
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2002, 1)], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(7), Some(1234)),
+            ], // values
+        );
+        memtable_tests::write_kvs(
+            &*memtable,
+            11, // sequence
+            OpType::Delete,
+            &[(3000, 1)],    // keys
+            &[(None, None)], // values
+        );
+
+        let dir = create_temp_dir("write_parquet_export");
+        let path = dir.path().to_str().unwrap();
+
+        let object_store = create_object_store(path);
+        let sst_file_name = "test-export.parquet";
+        let export_file_name = "export/test-export.parquet";
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let writer = ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store.clone())
+            .with_export_path(export_file_name.to_string());
+
+        let sst_info = writer
+            .write_sst(&sst::WriteOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(4, sst_info.num_rows);
+
+        let reader = BufReader::new(
+            object_store
+                .reader(export_file_name)
+                .await
+                .unwrap()
+                .compat(),
+        );
+        let builder = ParquetRecordBatchStreamBuilder::new(reader).await.unwrap();
+        // Internal columns are removed.
+        let column_names = builder
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["timestamp", "__version", "v0", "v1"], column_names);
+        assert!(builder.schema().metadata().is_empty());
+
+        let metadata: HashMap<_, _> = builder
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .map(|kv| (kv.key.as_str(), kv.value.clone().unwrap()))
+            .collect();
+        assert_eq!("timestamp", metadata[sst::export::TIMESTAMP_KEY]);
+        assert_eq!(r#"["__version"]"#, metadata[sst::export::TAGS_KEY]);
+        assert_eq!(r#"["v0","v1"]"#, metadata[sst::export::FIELDS_KEY]);
+
+        // The deleted row is removed.
+        let chunks = builder
+            .build()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let num_rows: usize = chunks.iter().map(|chunk| chunk.num_rows()).sum();
+        assert_eq!(3, num_rows);
+        assert_eq!(
+            &(Arc::new(UInt64Array::from(vec![1, 2, 7])) as Arc<dyn Array>),
+            chunks[0].column(2)
+        );
+    }
+
+    #[test]
+    fn test_compact_string_columns() {
+        let schema = Arc::new(datatypes::schema::Schema::new(vec![
+            datatypes::schema::ColumnSchema::new(
+                "host",
+                ConcreteDataType::string_datatype(),
+                false,
+            ),
+            datatypes::schema::ColumnSchema::new("v0", ConcreteDataType::uint64_datatype(), true),
+        ]));
+        let props = compact_string_columns(WriterProperties::builder(), &schema).build();
+
+        let host = ColumnPath::from("host");
+        assert!(props.dictionary_enabled(&host));
+        assert_eq!(Some(Encoding::DELTA_BYTE_ARRAY), props.encoding(&host));
+        assert_eq!(None, props.encoding(&ColumnPath::from("v0")));
+    }
+
+    #[test]
+    fn test_build_writer_properties_compact_strings() {
+        let schema = Arc::new(datatypes::schema::Schema::new(vec![
+            datatypes::schema::ColumnSchema::new(
+                "host",
+                ConcreteDataType::string_datatype(),
+                false,
+            ),
+            datatypes::schema::ColumnSchema::new("v0", ConcreteDataType::uint64_datatype(), true),
+        ]));
+        let host = ColumnPath::from("host");
+        let v0 = ColumnPath::from("v0");
+
+        let mut opts = sst::WriteOptions::default();
+        let props = build_writer_properties(&schema, 1024, None, &opts);
+        assert_eq!(Some(Encoding::PLAIN), props.encoding(&host));
+        assert_ne!(
+            COMPACT_STRINGS_DICTIONARY_PAGE_SIZE,
+            props.dictionary_pagesize_limit()
+        );
+
+        opts.compact_strings = true;
+        let props = build_writer_properties(&schema, 1024, None, &opts);
+        assert!(props.dictionary_enabled(&host));
+        assert_eq!(Some(Encoding::DELTA_BYTE_ARRAY), props.encoding(&host));
+        assert_eq!(
+            COMPACT_STRINGS_DICTIONARY_PAGE_SIZE,
+            props.dictionary_pagesize_limit()
+        );
+        // Other columns are left untouched.
+        assert_eq!(Some(Encoding::PLAIN), props.encoding(&v0));
+        assert_eq!(1024, props.max_row_group_size());
+    }
+
+    #[test]
+    fn test_encode_columns() {
+        let schema = Arc::new(datatypes::schema::Schema::new(vec![
+            datatypes::schema::ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            datatypes::schema::ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            datatypes::schema::ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+        ]));
+        let column_encodings = ColumnEncodings::from([
+            ("ts".to_string(), ColumnEncoding::Delta),
+            ("cpu".to_string(), ColumnEncoding::ByteStreamSplit),
+            // Unsupported type.
+            ("host".to_string(), ColumnEncoding::Delta),
+            // Missing column.
+            ("mem".to_string(), ColumnEncoding::ByteStreamSplit),
+        ]);
+        let props = encode_columns(WriterProperties::builder(), &schema, &column_encodings).build();
+
+        let ts = ColumnPath::from("ts");
+        assert!(!props.dictionary_enabled(&ts));
+        assert_eq!(Some(Encoding::DELTA_BINARY_PACKED), props.encoding(&ts));
+        let cpu = ColumnPath::from("cpu");
+        assert!(!props.dictionary_enabled(&cpu));
+        assert_eq!(Some(Encoding::BYTE_STREAM_SPLIT), props.encoding(&cpu));
+        let host = ColumnPath::from("host");
+        assert!(props.dictionary_enabled(&host));
+        assert_eq!(None, props.encoding(&host));
+    }
+
+    #[tokio::test]
+    async fn test_parquet_writer_column_encodings() {
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema);
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (2000, 1), (3000, 1), (4000, 1)], // keys
+            &[
+                (Some(1), Some(10)),
+                (Some(2), Some(20)),
+                (Some(4), Some(30)),
+                (Some(8), Some(40)),
+            ], // values
+        );
+
+        let dir = create_temp_dir("write_parquet_column_encodings");
+        let object_store = create_object_store(dir.path().to_str().unwrap());
+        let sst_file_name = "test-encodings.parquet";
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let opts = sst::WriteOptions {
+            column_encodings: ColumnEncodings::from([
+                ("timestamp".to_string(), ColumnEncoding::Delta),
+                ("v0".to_string(), ColumnEncoding::Delta),
+            ]),
+            ..Default::default()
+        };
+        ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store.clone())
+            .write_sst(&opts)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let reader = BufReader::new(object_store.reader(sst_file_name).await.unwrap().compat());
+        let builder = ParquetRecordBatchStreamBuilder::new(reader).await.unwrap();
+        let row_group = &builder.metadata().row_groups()[0];
+        // chunk schema: timestamp, __version, v0, v1, __sequence, __op_type
+        for index in [0, 2] {
+            assert!(row_group
+                .column(index)
+                .encodings()
+                .contains(&Encoding::DELTA_BINARY_PACKED));
+        }
+        assert!(!row_group
+            .column(3)
+            .encodings()
+            .contains(&Encoding::DELTA_BINARY_PACKED));
+
+        let chunk = builder.build().unwrap().next().await.unwrap().unwrap();
+        assert_eq!(
+            &TimestampMillisecondVector::from_slice([
+                1000.into(),
+                2000.into(),
+                3000.into(),
+                4000.into()
+            ])
+            .to_arrow_array(),
+            chunk.column(0)
+        );
+        assert_eq!(
+            &(Arc::new(UInt64Array::from(vec![1, 2, 4, 8])) as ArrayRef),
+            chunk.column(2)
+        );
+        assert_eq!(
+            &(Arc::new(UInt64Array::from(vec![10, 20, 30, 40])) as ArrayRef),
+            chunk.column(3)
+        );
+    }
+
     #[test]
     fn test_time_unit_lossy() {
         // converting a range with unit second to millisecond will not cause rounding error
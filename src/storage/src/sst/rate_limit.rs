@@ -0,0 +1,105 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rate limiter of the bytes written by flush and compaction jobs.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Token bucket limiting the bytes written to SST files per second.
+///
+/// The bucket holds at most one second of bytes, a writer may overdraw it and then
+/// waits until the debt is paid back.
+#[derive(Debug)]
+pub struct IoRateLimiter {
+    bytes_per_second: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    available: f64,
+    last_refill: Instant,
+}
+
+pub type IoRateLimiterRef = Arc<IoRateLimiter>;
+
+impl IoRateLimiter {
+    /// Creates a limiter allowing `bytes_per_second` bytes to be written per second.
+    ///
+    /// # Panics
+    /// Panics if `bytes_per_second` is 0.
+    pub fn new(bytes_per_second: u64) -> IoRateLimiter {
+        assert!(bytes_per_second > 0, "IO rate limit must be positive");
+
+        IoRateLimiter {
+            bytes_per_second: bytes_per_second as f64,
+            state: Mutex::new(BucketState {
+                available: bytes_per_second as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes `bytes` from the bucket, waits if the bucket is overdrawn.
+    pub async fn acquire(&self, bytes: usize) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes `bytes` from the bucket and returns how long the writer should wait.
+    fn reserve(&self, bytes: usize) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.available =
+            (state.available + elapsed * self.bytes_per_second).min(self.bytes_per_second);
+        state.last_refill = now;
+
+        state.available -= bytes as f64;
+        if state.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.available / self.bytes_per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve() {
+        let limiter = IoRateLimiter::new(1000);
+        assert_eq!(Duration::ZERO, limiter.reserve(1000));
+
+        // The bucket is empty now, 500 bytes take about half a second.
+        let wait = limiter.reserve(500);
+        assert!(wait > Duration::from_millis(400), "{wait:?}");
+        assert!(wait <= Duration::from_millis(500), "{wait:?}");
+    }
+
+    #[tokio::test]
+    async fn test_acquire() {
+        let limiter = IoRateLimiter::new(1000);
+        limiter.acquire(1000).await;
+
+        let start = Instant::now();
+        limiter.acquire(100).await;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
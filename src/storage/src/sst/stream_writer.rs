@@ -12,30 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::{Arc, Mutex};
+
 use arrow_array::RecordBatch;
-use common_datasource::buffered_writer::{
-    BufferedWriter as DatasourceBufferedWriter, DefaultBufferedWriter,
-};
+use common_datasource::buffered_writer::BufferedWriter as DatasourceBufferedWriter;
 use common_datasource::share_buffer::SharedBuffer;
 use datatypes::schema::SchemaRef;
-use object_store::ObjectStore;
+use object_store::{ObjectStore, Writer};
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
 use parquet::format::FileMetaData;
 use snafu::ResultExt;
-use tokio_util::compat::FuturesAsyncWriteCompatExt;
+use tokio_util::compat::{Compat, FuturesAsyncWriteCompatExt};
 
 use crate::error;
 use crate::error::{NewRecordBatchSnafu, WriteObjectSnafu, WriteParquetSnafu};
 use crate::read::Batch;
+use crate::sst::checksum::{
+    BlockHasher, BlockHasherRef, ChecksumWriter, FileChecksums, DEFAULT_CHECKSUM_BLOCK_SIZE,
+};
+
 /// Parquet writer that buffers row groups in memory and writes buffered data to an underlying
 /// storage by chunks to reduce memory consumption.
 pub struct BufferedWriter {
     inner: InnerBufferedWriter,
     arrow_schema: arrow::datatypes::SchemaRef,
+    /// Computes block checksums of bytes written to the object store.
+    hasher: BlockHasherRef,
 }
 
-type InnerBufferedWriter = DefaultBufferedWriter<ArrowWriter<SharedBuffer>>;
+type InnerBufferedWriter =
+    DatasourceBufferedWriter<ChecksumWriter<Compat<Writer>>, ArrowWriter<SharedBuffer>>;
 
 impl BufferedWriter {
     pub async fn try_new(
@@ -45,7 +52,24 @@ impl BufferedWriter {
         props: Option<WriterProperties>,
         buffer_threshold: usize,
     ) -> error::Result<Self> {
-        let arrow_schema = schema.arrow_schema();
+        Self::try_new_with_arrow_schema(
+            path,
+            store,
+            schema.arrow_schema().clone(),
+            props,
+            buffer_threshold,
+        )
+        .await
+    }
+
+    /// Creates a writer that writes record batches with given arrow schema.
+    pub async fn try_new_with_arrow_schema(
+        path: String,
+        store: ObjectStore,
+        arrow_schema: arrow::datatypes::SchemaRef,
+        props: Option<WriterProperties>,
+        buffer_threshold: usize,
+    ) -> error::Result<Self> {
         let buffer = SharedBuffer::with_capacity(buffer_threshold);
         let writer = store
             .writer(&path)
@@ -55,7 +79,8 @@ impl BufferedWriter {
         let arrow_writer = ArrowWriter::try_new(buffer.clone(), arrow_schema.clone(), props)
             .context(WriteParquetSnafu)?;
 
-        let writer = writer.compat_write();
+        let hasher = Arc::new(Mutex::new(BlockHasher::new(DEFAULT_CHECKSUM_BLOCK_SIZE)));
+        let writer = ChecksumWriter::new(writer.compat_write(), hasher.clone());
 
         Ok(Self {
             inner: DatasourceBufferedWriter::new(
@@ -64,7 +89,8 @@ impl BufferedWriter {
                 arrow_writer,
                 writer,
             ),
-            arrow_schema: arrow_schema.clone(),
+            arrow_schema,
+            hasher,
         })
     }
 
@@ -80,8 +106,14 @@ impl BufferedWriter {
         )
         .context(NewRecordBatchSnafu)?;
 
+        self.write_record_batch(&arrow_batch).await
+    }
+
+    /// Write an arrow record batch to stream writer, the batch must have the same
+    /// schema as the writer.
+    pub async fn write_record_batch(&mut self, arrow_batch: &RecordBatch) -> error::Result<()> {
         self.inner
-            .write(&arrow_batch)
+            .write(arrow_batch)
             .await
             .context(error::WriteBufferSnafu)?;
         self.inner
@@ -100,10 +132,17 @@ impl BufferedWriter {
     }
 
     /// Close parquet writer and ensure all buffered data are written into underlying storage.
-    pub async fn close(self) -> error::Result<(FileMetaData, u64)> {
-        self.inner
+    /// Returns the parquet metadata, file size and block checksums of the file.
+    pub async fn close(self) -> error::Result<(FileMetaData, u64, FileChecksums)> {
+        let (metadata, file_size) = self
+            .inner
             .close_with_arrow_writer()
             .await
-            .context(error::WriteBufferSnafu)
+            .context(error::WriteBufferSnafu)?;
+        let hasher = std::mem::replace(
+            &mut *self.hasher.lock().unwrap(),
+            BlockHasher::new(DEFAULT_CHECKSUM_BLOCK_SIZE),
+        );
+        Ok((metadata, file_size, hasher.finish()))
     }
 }
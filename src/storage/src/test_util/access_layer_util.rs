@@ -13,7 +13,11 @@
 // limitations under the License.
 
 use crate::read::BoxedBatchReader;
-use crate::sst::{AccessLayer, FileHandle, FileId, ReadOptions, Source, SstInfo, WriteOptions};
+use crate::schema::StoreSchema;
+use crate::sst::{
+    AccessLayer, AttachedSst, FileHandle, FileId, FileMeta, InspectedSst, ReadOptions, Source,
+    SstInfo, StorageTier, WriteOptions,
+};
 
 #[derive(Debug)]
 pub struct MockAccessLayer;
@@ -41,7 +45,38 @@ impl AccessLayer for MockAccessLayer {
         unimplemented!()
     }
 
-    async fn delete_sst(&self, _file_id: FileId) -> crate::error::Result<()> {
+    async fn delete_sst(&self, _file_id: FileId, _tier: StorageTier) -> crate::error::Result<()> {
+        Ok(())
+    }
+
+    async fn verify_sst(&self, _file_meta: &FileMeta) -> crate::error::Result<()> {
+        Ok(())
+    }
+
+    async fn inspect_sst(
+        &self,
+        file_meta: &FileMeta,
+    ) -> crate::error::Result<Option<InspectedSst>> {
+        Ok(Some(InspectedSst {
+            time_range: file_meta.time_range,
+            file_size: file_meta.file_size,
+        }))
+    }
+
+    async fn list_staged_ssts(&self, _staging_dir: &str) -> crate::error::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn attach_sst(
+        &self,
+        _path: &str,
+        _file_id: FileId,
+        _schema: &StoreSchema,
+    ) -> crate::error::Result<AttachedSst> {
+        unimplemented!()
+    }
+
+    async fn delete_staged_sst(&self, _path: &str) -> crate::error::Result<()> {
         Ok(())
     }
 }
@@ -19,6 +19,7 @@ use log_store::LogConfig;
 use object_store::services::Fs;
 use object_store::ObjectStore;
 use store_api::manifest::Manifest;
+use store_api::storage::ColumnEncodings;
 
 use crate::background::JobPoolImpl;
 use crate::compaction::noop::NoopCompactionScheduler;
@@ -60,7 +61,7 @@ pub async fn new_store_config_with_object_store(
     let sst_layer = Arc::new(FsAccessLayer::new(&sst_dir, object_store.clone()));
     let manifest = RegionManifest::with_checkpointer(&manifest_dir, object_store, None, None);
     manifest.start().await.unwrap();
-    let job_pool = Arc::new(JobPoolImpl {});
+    let job_pool = Arc::new(JobPoolImpl::default());
     let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
     let log_config = LogConfig {
         log_file_dir: log_store_dir(store_dir),
@@ -84,5 +85,8 @@ pub async fn new_store_config_with_object_store(
         file_purger,
         ttl: None,
         compaction_time_window: None,
+        append_mode: false,
+        compact_strings: false,
+        column_encodings: ColumnEncodings::new(),
     }
 }
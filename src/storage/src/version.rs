@@ -32,7 +32,7 @@ use crate::file_purger::FilePurgerRef;
 use crate::memtable::{MemtableId, MemtableRef, MemtableVersion};
 use crate::metadata::RegionMetadataRef;
 use crate::schema::RegionSchemaRef;
-use crate::sst::{AccessLayerRef, FileMeta, LevelMetas};
+use crate::sst::{self, AccessLayerRef, FileMeta, LevelMetas, RangeTombstone};
 use crate::sync::CowCell;
 pub const INIT_COMMITTED_SEQUENCE: u64 = 0;
 
@@ -131,6 +131,7 @@ impl VersionControl {
 pub struct VersionEdit {
     pub files_to_add: Vec<FileMeta>,
     pub files_to_remove: Vec<FileMeta>,
+    pub tombstones_to_add: Vec<RangeTombstone>,
     pub flushed_sequence: Option<SequenceNumber>,
     pub manifest_version: ManifestVersion,
     pub max_memtable_id: Option<MemtableId>,
@@ -155,6 +156,8 @@ pub struct Version {
     memtables: MemtableVersionRef,
     /// SSTs of the region.
     ssts: LevelMetasRef,
+    /// Range tombstones masking rows of SSTs.
+    tombstones: Arc<Vec<RangeTombstone>>,
     /// Inclusive max sequence of flushed data.
     flushed_sequence: SequenceNumber,
     /// Current version of manifest.
@@ -187,6 +190,7 @@ impl Version {
             metadata,
             memtables: Arc::new(MemtableVersion::new(mutable_memtable)),
             ssts: Arc::new(LevelMetas::new(sst_layer, file_purger)),
+            tombstones: Arc::new(Vec::new()),
             flushed_sequence: 0,
             manifest_version,
         }
@@ -222,6 +226,11 @@ impl Version {
         &self.ssts
     }
 
+    #[inline]
+    pub fn tombstones(&self) -> &[RangeTombstone] {
+        &self.tombstones
+    }
+
     #[inline]
     pub fn flushed_sequence(&self) -> SequenceNumber {
         self.flushed_sequence
@@ -232,10 +241,12 @@ impl Version {
         flushed_sequence: Option<SequenceNumber>,
         manifest_version: ManifestVersion,
         files: impl Iterator<Item = FileMeta>,
+        tombstones: Vec<RangeTombstone>,
     ) {
         self.flushed_sequence = flushed_sequence.unwrap_or(self.flushed_sequence);
         self.manifest_version = manifest_version;
         let ssts = self.ssts.merge(files, std::iter::empty());
+        self.tombstones = Arc::new(tombstones);
         info!(
             "After applying checkpoint, region: {}, id: {}, flushed_sequence: {}, manifest_version: {}",
             self.metadata.name(),
@@ -275,6 +286,27 @@ impl Version {
             merged_ssts
         );
         self.ssts = Arc::new(merged_ssts);
+        self.apply_tombstones(edit.tombstones_to_add);
+    }
+
+    /// Adds `tombstones` and removes tombstones no longer overlapping any SST.
+    fn apply_tombstones(&mut self, tombstones: Vec<RangeTombstone>) {
+        if tombstones.is_empty() && self.tombstones.is_empty() {
+            return;
+        }
+
+        let mut merged = Vec::with_capacity(self.tombstones.len() + tombstones.len());
+        merged.extend_from_slice(&self.tombstones);
+        merged.extend(tombstones);
+        let time_ranges: Vec<_> = self
+            .ssts
+            .levels()
+            .iter()
+            .flat_map(|level| level.files())
+            .map(|file| *file.time_range())
+            .collect();
+        sst::retain_tombstones(&mut merged, time_ranges.into_iter());
+        self.tombstones = Arc::new(merged);
     }
 
     /// Updates metadata of the version.
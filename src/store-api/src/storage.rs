@@ -32,9 +32,15 @@ pub use datatypes::schema::{
 
 pub use self::chunk::{Chunk, ChunkReader};
 pub use self::descriptors::*;
-pub use self::engine::{CreateOptions, EngineContext, OpenOptions, StorageEngine};
+pub use self::engine::{
+    ColumnEncoding, ColumnEncodings, CreateOptions, EngineContext, OpenOptions, StorageEngine,
+};
 pub use self::metadata::RegionMeta;
-pub use self::region::{FlushContext, Region, WriteContext};
+pub use self::region::{
+    AttachContext, AttachReport, ChangeBatch, CompactContext, DropRangeContext, DropRangeReport,
+    FlushContext, HealthIssue, HealthIssueKind, PreparedAttach, PreparedFile, PurgeContext,
+    PurgeReport, Region, RegionHealthReport, WriteContext, WriteThrottle,
+};
 pub use self::requests::{
     AddColumn, AlterOperation, AlterRequest, GetRequest, ScanRequest, WriteRequest,
 };
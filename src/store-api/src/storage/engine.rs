@@ -18,10 +18,15 @@
 //! a [`StorageEngine`] instance manages a bunch of storage unit called [`Region`], which holds
 //! chunks of rows, support operations like PUT/DELETE/SCAN.
 
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use datatypes::prelude::ConcreteDataType;
+use serde::{Deserialize, Serialize};
 
 use crate::storage::descriptors::RegionDescriptor;
 use crate::storage::region::Region;
@@ -89,6 +94,16 @@ pub struct CreateOptions {
     /// Region SST files TTL
     pub ttl: Option<Duration>,
     pub compaction_time_window: Option<i64>,
+    /// Max number of rows in the memtable before flushing it
+    pub flush_rows: Option<usize>,
+    /// Max duration between two flushes of the region
+    pub flush_interval: Option<Duration>,
+    /// Whether the region only appends rows, without deduplicating rows with the same key
+    pub append_mode: bool,
+    /// Whether to encode string columns with dictionaries and prefix compression
+    pub compact_strings: bool,
+    /// Encodings of columns overriding the default ones, by column names
+    pub column_encodings: ColumnEncodings,
 }
 
 /// Options to open a region.
@@ -101,4 +116,87 @@ pub struct OpenOptions {
     /// Region SST files TTL
     pub ttl: Option<Duration>,
     pub compaction_time_window: Option<i64>,
+    /// Max number of rows in the memtable before flushing it
+    pub flush_rows: Option<usize>,
+    /// Max duration between two flushes of the region
+    pub flush_interval: Option<Duration>,
+    /// Whether the region only appends rows, without deduplicating rows with the same key
+    pub append_mode: bool,
+    /// Whether to encode string columns with dictionaries and prefix compression
+    pub compact_strings: bool,
+    /// Encodings of columns overriding the default ones, by column names
+    pub column_encodings: ColumnEncodings,
+}
+
+/// Encoding of a column in SSTs, overriding the default encoding of its data type. These
+/// encodings suit time series, whose adjacent values are usually close to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnEncoding {
+    /// Encodes the deltas of adjacent values, minus the min delta of each block, with bit
+    /// packing. Values at a fixed interval, like timestamps, take nearly no space as their
+    /// deltas of deltas are zeros. Only for integer and timestamp columns.
+    Delta,
+    /// Splits the bytes of values into a stream for each byte position, so the exponents
+    /// and high bits of the mantissas of close floats are compressed together. Only for
+    /// float columns.
+    ByteStreamSplit,
+}
+
+/// Encodings of columns by column names.
+pub type ColumnEncodings = HashMap<String, ColumnEncoding>;
+
+impl ColumnEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColumnEncoding::Delta => "delta",
+            ColumnEncoding::ByteStreamSplit => "byte_stream_split",
+        }
+    }
+
+    /// Returns whether columns of `data_type` can use the encoding.
+    pub fn supports(&self, data_type: &ConcreteDataType) -> bool {
+        match self {
+            ColumnEncoding::Delta => data_type.is_signed() || data_type.is_unsigned(),
+            ColumnEncoding::ByteStreamSplit => data_type.is_float(),
+        }
+    }
+}
+
+impl fmt::Display for ColumnEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ColumnEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "delta" => Ok(ColumnEncoding::Delta),
+            "byte_stream_split" => Ok(ColumnEncoding::ByteStreamSplit),
+            _ => Err(format!("unknown column encoding {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_encoding() {
+        for encoding in [ColumnEncoding::Delta, ColumnEncoding::ByteStreamSplit] {
+            assert_eq!(encoding, encoding.as_str().parse().unwrap());
+        }
+        assert_eq!(ColumnEncoding::Delta, "DELTA".parse().unwrap());
+        assert!("gorilla".parse::<ColumnEncoding>().is_err());
+
+        assert!(ColumnEncoding::Delta.supports(&ConcreteDataType::timestamp_millisecond_datatype()));
+        assert!(ColumnEncoding::Delta.supports(&ConcreteDataType::uint32_datatype()));
+        assert!(!ColumnEncoding::Delta.supports(&ConcreteDataType::float64_datatype()));
+        assert!(ColumnEncoding::ByteStreamSplit.supports(&ConcreteDataType::float32_datatype()));
+        assert!(!ColumnEncoding::ByteStreamSplit.supports(&ConcreteDataType::string_datatype()));
+    }
 }
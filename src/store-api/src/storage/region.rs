@@ -32,14 +32,22 @@
 //! a row key. Note that the implementation may allow multiple rows have same row
 //! key (like ClickHouse), which is useful in analytic scenario.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use common_time::Timestamp;
+use datatypes::schema::SchemaRef;
+use datatypes::vectors::VectorRef;
+use futures::stream::BoxStream;
 
+use crate::manifest::ManifestVersion;
 use crate::storage::engine::OpenOptions;
 use crate::storage::metadata::RegionMeta;
 use crate::storage::requests::{AlterRequest, WriteRequest};
 use crate::storage::responses::WriteResponse;
 use crate::storage::snapshot::{ReadContext, Snapshot};
+use crate::storage::types::{OpType, SequenceNumber};
 use crate::storage::RegionId;
 
 /// Chunks of rows in storage engine.
@@ -75,10 +83,103 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
 
     async fn close(&self) -> Result<(), Self::Error>;
 
+    /// Releases the memory held by the region while it's idle: flushes the memtables and
+    /// evicts the cached data of its SSTs. The region stays open and the next request
+    /// loads what it needs again.
+    async fn hibernate(&self) -> Result<(), Self::Error>;
+
     fn disk_usage_bytes(&self) -> u64;
 
     /// Flush memtable of the region to disk.
     async fn flush(&self, ctx: &FlushContext) -> Result<(), Self::Error>;
+
+    /// Accepts the writes again after the region is fenced by [Region::flush], e.g. when
+    /// the migration of the region fails.
+    async fn unfence(&self) -> Result<(), Self::Error>;
+
+    /// Compact the SST files of the region manually.
+    async fn compact(&self, ctx: &CompactContext) -> Result<(), Self::Error>;
+
+    /// Purge the SST files expired by the TTL of the region, returns what is (or
+    /// would be, in dry run) purged.
+    async fn purge_expired(&self, ctx: &PurgeContext) -> Result<PurgeReport, Self::Error>;
+
+    /// Drops all data of the region within a time range, returns what is (or would
+    /// be, in dry run) dropped.
+    async fn drop_range(&self, ctx: &DropRangeContext) -> Result<DropRangeReport, Self::Error>;
+
+    /// Adds all data of the `source` region to this region by referencing the SST
+    /// files of `source` instead of copying them. Both regions must have the same
+    /// columns, and later writes to either region don't affect the other one.
+    async fn clone_data_from(&self, source: &Self) -> Result<(), Self::Error>;
+
+    /// Attaches the SST files staged under the staging directory of `ctx` to the region
+    /// without writing their rows through the write path. Either all staged files are
+    /// attached or none of them, returns what is attached.
+    async fn attach_ssts(&self, ctx: &AttachContext) -> Result<AttachReport, Self::Error> {
+        let prepared = self.prepare_attach(ctx).await?;
+        match self.commit_attach(prepared.clone()).await {
+            Ok(report) => Ok(report),
+            Err(e) => {
+                // The error of the commit is more useful than the error of the cleanup.
+                let _ = self.abort_attach(prepared).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Validates the SST files staged under the staging directory of `ctx` and copies
+    /// them into the region, the copies are invisible until [Region::commit_attach].
+    async fn prepare_attach(&self, ctx: &AttachContext) -> Result<PreparedAttach, Self::Error>;
+
+    /// Adds the files copied by [Region::prepare_attach] to the region by a single
+    /// manifest edit, and removes the staged files.
+    async fn commit_attach(&self, prepared: PreparedAttach) -> Result<AttachReport, Self::Error>;
+
+    /// Removes the files copied by [Region::prepare_attach], the staged files are kept.
+    async fn abort_attach(&self, prepared: PreparedAttach) -> Result<(), Self::Error>;
+
+    /// Walks the manifest of the region and checks every SST file it references, returns
+    /// the problems found without fixing any of them.
+    async fn scrub(&self) -> Result<RegionHealthReport, Self::Error>;
+
+    /// Subscribes to the changes committed to the region after this call.
+    ///
+    /// The stream ends when the region is dropped and yields an error if the
+    /// subscriber falls too far behind the writer.
+    fn subscribe(
+        &self,
+    ) -> Result<BoxStream<'static, Result<ChangeBatch, Self::Error>>, Self::Error>;
+
+    /// Returns whether writes to the region should be admitted, delayed or rejected
+    /// under the current memtable pressure of the region.
+    fn write_throttle(&self) -> WriteThrottle;
+
+    /// Sets the TTL of the region, which applies to the following compactions and purges.
+    async fn set_ttl(&self, ttl: Option<Duration>);
+}
+
+/// Admission signal of writes to a region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WriteThrottle {
+    /// Writes are admitted as usual.
+    #[default]
+    Admit,
+    /// Flushes fall behind the writes, writers should slow down.
+    Delay,
+    /// Memtables are full, writes should be rejected until flushes catch up.
+    Reject,
+}
+
+/// A batch of rows committed to the region by a single mutation.
+#[derive(Debug, Clone)]
+pub struct ChangeBatch {
+    /// Sequence of the write request this change belongs to.
+    pub sequence: SequenceNumber,
+    pub op_type: OpType,
+    /// Schema of the `columns`, without internal columns.
+    pub schema: SchemaRef,
+    pub columns: Vec<VectorRef>,
 }
 
 /// Context for write operations.
@@ -97,10 +198,209 @@ pub struct FlushContext {
     /// If true, the flush will wait until the flush is done.
     /// Default: true
     pub wait: bool,
+    /// If true, the region rejects all writes once the memtables to flush are frozen, so
+    /// nothing is written to the region after the flush, e.g. before migrating it.
+    /// Default: false
+    pub fence: bool,
 }
 
 impl Default for FlushContext {
     fn default() -> FlushContext {
-        FlushContext { wait: true }
+        FlushContext {
+            wait: true,
+            fence: false,
+        }
+    }
+}
+
+/// Context for manual compaction.
+#[derive(Debug, Clone)]
+pub struct CompactContext {
+    /// Whether to wait the compaction result.
+    pub wait: bool,
+    /// Max file number in level 0.
+    pub max_files_in_l0: usize,
+}
+
+impl Default for CompactContext {
+    fn default() -> CompactContext {
+        CompactContext {
+            wait: true,
+            max_files_in_l0: 1,
+        }
+    }
+}
+
+/// Context for purging expired data.
+#[derive(Debug, Clone, Default)]
+pub struct PurgeContext {
+    /// Only reports the files to purge without removing them.
+    pub dry_run: bool,
+}
+
+/// Summary of the SST files purged by the retention policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PurgeReport {
+    /// Number of purged files.
+    pub num_files: usize,
+    /// Total size of purged files in bytes.
+    pub file_size: u64,
+    /// Inclusive time range covered by the purged files, `None` if nothing is purged.
+    pub time_range: Option<(Timestamp, Timestamp)>,
+}
+
+/// Context for dropping data within a time range.
+#[derive(Debug, Clone)]
+pub struct DropRangeContext {
+    /// Inclusive start of the time range to drop.
+    pub start: Timestamp,
+    /// Inclusive end of the time range to drop.
+    pub end: Timestamp,
+    /// Only reports the files to drop without dropping them.
+    pub dry_run: bool,
+}
+
+/// Summary of the SST files affected by dropping a time range.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DropRangeReport {
+    /// Number of files wholly within the time range, which are removed.
+    pub removed_files: usize,
+    /// Total size of removed files in bytes.
+    pub removed_file_size: u64,
+    /// Number of files partially overlapping the time range, whose rows within the
+    /// range are masked by a range tombstone.
+    pub masked_files: usize,
+}
+
+/// Context for attaching staged SST files.
+#[derive(Debug, Clone)]
+pub struct AttachContext {
+    /// Directory in the object store of the region holding the staged SST files.
+    pub staging_dir: String,
+}
+
+/// SST files copied into a region by [Region::prepare_attach] but not attached yet.
+#[derive(Debug, Clone, Default)]
+pub struct PreparedAttach {
+    /// Version of the region metadata the files are validated against.
+    pub region_version: u32,
+    /// Paths of the staged files.
+    pub staged_paths: Vec<String>,
+    /// Files copied into the region.
+    pub files: Vec<PreparedFile>,
+}
+
+/// A staged SST file copied into a region.
+#[derive(Debug, Clone)]
+pub struct PreparedFile {
+    /// Id of the copy in the region.
+    pub file_id: String,
+    /// Size of the copy in bytes.
+    pub file_size: u64,
+    /// Inclusive time range of rows in the file.
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    /// Max sequence of rows in the file.
+    pub max_sequence: SequenceNumber,
+}
+
+/// Summary of the SST files attached to a region.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttachReport {
+    /// Number of attached files.
+    pub num_files: usize,
+    /// Total size of attached files in bytes.
+    pub file_size: u64,
+    /// Inclusive time range covered by the attached files, `None` if nothing is attached.
+    pub time_range: Option<(Timestamp, Timestamp)>,
+}
+
+/// Health of a region found by scrubbing its manifest and SST files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionHealthReport {
+    /// Manifest version the scrub starts from, which is the version after the last
+    /// checkpoint if any.
+    pub start_version: ManifestVersion,
+    /// Last manifest version walked by the scrub.
+    pub end_version: ManifestVersion,
+    /// Number of SST files referenced by the manifest.
+    pub num_files: usize,
+    /// Total size of referenced files in bytes, as recorded in the manifest.
+    pub file_size: u64,
+    /// Problems found, the region is healthy if empty.
+    pub issues: Vec<HealthIssue>,
+}
+
+impl RegionHealthReport {
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A problem of the region found by scrubbing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    /// Name of the SST file with the problem, `None` for problems of the manifest.
+    pub file: Option<String>,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthIssueKind {
+    /// Some manifest versions are missing.
+    ManifestGap,
+    /// The flushed sequence of an edit is less than the one of a previous edit.
+    SequenceRegressed,
+    /// A referenced SST file doesn't exist.
+    MissingFile,
+    /// A referenced SST file doesn't match its checksums or can't be parsed.
+    CorruptedFile,
+    /// The recorded time range of a SST file starts after it ends.
+    TimeRangeInverted,
+    /// The rows of a SST file are out of its recorded time range.
+    TimeRangeMismatch,
+}
+
+impl HealthIssueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthIssueKind::ManifestGap => "manifest_gap",
+            HealthIssueKind::SequenceRegressed => "sequence_regressed",
+            HealthIssueKind::MissingFile => "missing_file",
+            HealthIssueKind::CorruptedFile => "corrupted_file",
+            HealthIssueKind::TimeRangeInverted => "time_range_inverted",
+            HealthIssueKind::TimeRangeMismatch => "time_range_mismatch",
+        }
+    }
+}
+
+impl PurgeReport {
+    /// Adds a purged file of `file_size` bytes within `time_range` to the report.
+    pub fn add_file(&mut self, file_size: u64, time_range: Option<(Timestamp, Timestamp)>) {
+        self.num_files += 1;
+        self.file_size += file_size;
+        extend_time_range(&mut self.time_range, time_range);
+    }
+}
+
+impl AttachReport {
+    /// Adds an attached file of `file_size` bytes within `time_range` to the report.
+    pub fn add_file(&mut self, file_size: u64, time_range: Option<(Timestamp, Timestamp)>) {
+        self.num_files += 1;
+        self.file_size += file_size;
+        extend_time_range(&mut self.time_range, time_range);
+    }
+}
+
+/// Extends the inclusive time range `range` to cover `time_range`.
+fn extend_time_range(
+    range: &mut Option<(Timestamp, Timestamp)>,
+    time_range: Option<(Timestamp, Timestamp)>,
+) {
+    if let Some((start, end)) = time_range {
+        *range = Some(match *range {
+            Some((min, max)) => (min.min(start), max.max(end)),
+            None => (start, end),
+        });
     }
 }
@@ -49,6 +49,10 @@ pub struct ScanRequest {
     pub projection: Option<Vec<usize>>,
     /// Filters pushed down
     pub filters: Vec<Expr>,
+    /// Percentage of data to read, `None` to read all data.
+    ///
+    /// Data is sampled in units of memtables and SST row groups.
+    pub sample_percent: Option<f64>,
 }
 
 #[derive(Debug)]
@@ -19,6 +19,10 @@ pub struct WriteResponse {}
 pub struct ScanResponse<R> {
     /// Reader to read result chunks.
     pub reader: R,
+    /// Number of SST files to read.
+    pub num_files: usize,
+    /// Estimated bytes to read, including both SST files and memtables.
+    pub estimated_bytes: u64,
 }
 
 #[derive(Debug)]
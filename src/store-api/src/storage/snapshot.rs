@@ -20,8 +20,18 @@ use crate::storage::chunk::ChunkReader;
 use crate::storage::consts;
 use crate::storage::requests::{GetRequest, ScanRequest};
 use crate::storage::responses::{GetResponse, ScanResponse};
+use crate::storage::types::SequenceNumber;
 
 /// A consistent read-only view of region.
+///
+/// A snapshot pins the memtables and SST files of the region when it is created, so
+/// flushes and compactions finishing later never change what it reads, and only rows
+/// whose sequences are less than or equal to [Snapshot::sequence] are visible.
+///
+/// Scans could read at an older sequence by setting [ScanRequest::sequence], e.g. to
+/// read different regions, or the same region repeatedly, at a sequence chosen before.
+/// Note that compactions may discard old versions of rows overwritten later, so such
+/// scans could see the newer versions.
 #[async_trait]
 pub trait Snapshot: Send + Sync {
     type Error: ErrorExt + Send + Sync;
@@ -37,6 +47,9 @@ pub trait Snapshot: Send + Sync {
 
     async fn get(&self, ctx: &ReadContext, request: GetRequest)
         -> Result<GetResponse, Self::Error>;
+
+    /// Returns the max sequence number (inclusive) visible to this snapshot.
+    fn sequence(&self) -> SequenceNumber;
 }
 
 /// Context for read.
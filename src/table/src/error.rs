@@ -19,6 +19,7 @@ use common_recordbatch::error::Error as RecordBatchError;
 use datafusion::error::DataFusionError;
 use datatypes::arrow::error::ArrowError;
 use snafu::Location;
+use store_api::storage::RegionNumber;
 
 use crate::metadata::TableId;
 
@@ -112,6 +113,21 @@ pub enum Error {
     #[snafu(display("Regions schemas mismatch in table: {}", table))]
     RegionSchemaMismatch { table: String, location: Location },
 
+    #[snafu(display(
+        "Region {} of table {} is altered after the snapshot is taken, snapshot version: {}, current version: {}",
+        region,
+        table,
+        snapshot_version,
+        current_version
+    ))]
+    SnapshotVersionMismatch {
+        table: String,
+        region: RegionNumber,
+        snapshot_version: u32,
+        current_version: u32,
+        location: Location,
+    },
+
     #[snafu(display("Failed to operate table, source: {}", source))]
     TableOperation { source: BoxedError },
 
@@ -136,6 +152,30 @@ pub enum Error {
         table_name: String,
         location: Location,
     },
+
+    #[snafu(display("Missing definition of view: {}", view_name))]
+    MissingViewDefinition {
+        view_name: String,
+        location: Location,
+    },
+
+    #[snafu(display("Table {} can't clone data from table {}", table, source_table))]
+    CloneSourceMismatch {
+        table: String,
+        source_table: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Failed to collect statistics of table {}, source: {}",
+        table_name,
+        source
+    ))]
+    CollectStatistics {
+        table_name: String,
+        source: BoxedError,
+        location: Location,
+    },
 }
 
 impl ErrorExt for Error {
@@ -152,16 +192,19 @@ impl ErrorExt for Error {
             Error::ColumnExists { .. } => StatusCode::TableColumnExists,
             Error::SchemaBuild { source, .. } => source.status_code(),
             Error::TableOperation { source } => source.status_code(),
+            Error::CollectStatistics { source, .. } => source.status_code(),
             Error::ColumnNotExists { .. } => StatusCode::TableColumnNotFound,
             Error::RegionSchemaMismatch { .. } => StatusCode::StorageUnavailable,
+            Error::SnapshotVersionMismatch { .. } => StatusCode::InvalidArguments,
             Error::Unsupported { .. } => StatusCode::Unsupported,
             Error::ParseTableOption { .. }
             | Error::EngineNotFound { .. }
-            | Error::EngineExist { .. } => StatusCode::InvalidArguments,
+            | Error::EngineExist { .. }
+            | Error::CloneSourceMismatch { .. } => StatusCode::InvalidArguments,
 
-            Error::InvalidTable { .. } | Error::MissingTimeIndexColumn { .. } => {
-                StatusCode::Internal
-            }
+            Error::InvalidTable { .. }
+            | Error::MissingTimeIndexColumn { .. }
+            | Error::MissingViewDefinition { .. } => StatusCode::Internal,
         }
     }
 
@@ -18,6 +18,8 @@ pub mod error;
 pub mod metadata;
 pub mod predicate;
 pub mod requests;
+pub mod sketch;
+pub mod stats;
 pub mod table;
 pub mod test_util;
 
@@ -26,7 +26,7 @@ use snafu::{ensure, ResultExt};
 use store_api::storage::{ColumnDescriptor, ColumnDescriptorBuilder, ColumnId};
 
 use crate::error::{self, Result};
-use crate::requests::{AddColumnRequest, AlterKind, TableOptions};
+use crate::requests::{AddColumnRequest, AlterKind, TableOptions, TTL_KEY};
 
 pub type TableId = u32;
 pub type TableVersion = u64;
@@ -89,6 +89,33 @@ pub struct TableIdent {
     pub version: TableVersion,
 }
 
+/// Kind of a change to the columns of a table.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnChangeKind {
+    Added,
+    Dropped,
+}
+
+impl ColumnChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColumnChangeKind::Added => "ADDED",
+            ColumnChangeKind::Dropped => "DROPPED",
+        }
+    }
+}
+
+/// A column added to or dropped from a table, recorded in the table metadata to keep the
+/// history of schema changes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ColumnChange {
+    pub column_name: String,
+    pub kind: ColumnChangeKind,
+    /// Version of the table schema after this change.
+    pub schema_version: u32,
+    pub changed_on: DateTime<Utc>,
+}
+
 /// The table metadata
 /// Note: if you add new fields to this struct, please ensure 'new_meta_builder' function works.
 /// TODO(dennis): find a better way to ensure 'new_meta_builder' works when adding new fields.
@@ -114,6 +141,9 @@ pub struct TableMeta {
     pub options: TableOptions,
     #[builder(default = "Utc::now()")]
     pub created_on: DateTime<Utc>,
+    /// Columns added or dropped after the table is created, in the order of changes.
+    #[builder(default)]
+    pub column_history: Vec<ColumnChange>,
 }
 
 impl TableMetaBuilder {
@@ -178,7 +208,17 @@ impl TableMeta {
                     .schema(self.schema.clone())
                     .primary_key_indices(self.primary_key_indices.clone())
                     .engine(self.engine.clone())
-                    .next_column_id(self.next_column_id);
+                    .next_column_id(self.next_column_id)
+                    .column_history(self.column_history.clone());
+                Ok(meta_builder)
+            }
+            AlterKind::SetOptions { options } => {
+                let mut meta_builder = self.new_meta_builder();
+                meta_builder
+                    .schema(self.schema.clone())
+                    .primary_key_indices(self.primary_key_indices.clone())
+                    .value_indices(self.value_indices.clone())
+                    .options(self.options_with(options)?);
                 Ok(meta_builder)
             }
         }
@@ -211,6 +251,23 @@ impl TableMeta {
         Ok(desc)
     }
 
+    /// Returns the options of the table after setting `options`. Other options take effect
+    /// only when the regions are created, so only the TTL can be set.
+    fn options_with(&self, options: &HashMap<String, String>) -> Result<TableOptions> {
+        if let Some(key) = options.keys().find(|key| key.as_str() != TTL_KEY) {
+            return error::UnsupportedSnafu {
+                operation: format!("setting table option {key}"),
+            }
+            .fail();
+        }
+        let parsed = TableOptions::try_from(options)?;
+        let mut new_options = self.options.clone();
+        if parsed.ttl.is_some() {
+            new_options.ttl = parsed.ttl;
+        }
+        Ok(new_options)
+    }
+
     fn new_meta_builder(&self) -> TableMetaBuilder {
         let mut builder = TableMetaBuilder::default();
         builder
@@ -219,11 +276,29 @@ impl TableMeta {
             .options(self.options.clone())
             .created_on(self.created_on)
             .region_numbers(self.region_numbers.clone())
-            .next_column_id(self.next_column_id);
+            .next_column_id(self.next_column_id)
+            .column_history(self.column_history.clone());
 
         builder
     }
 
+    fn column_history_with(
+        &self,
+        column_names: impl Iterator<Item = String>,
+        kind: ColumnChangeKind,
+        schema_version: u32,
+    ) -> Vec<ColumnChange> {
+        let changed_on = Utc::now();
+        let mut history = self.column_history.clone();
+        history.extend(column_names.map(|column_name| ColumnChange {
+            column_name,
+            kind,
+            schema_version,
+            changed_on,
+        }));
+        history
+    }
+
     fn add_columns(
         &self,
         table_name: &str,
@@ -272,10 +347,17 @@ impl TableMeta {
             msg: format!("Table {table_name} cannot add new columns {column_names:?}"),
         })?;
 
+        let column_history = self.column_history_with(
+            column_names.into_iter(),
+            ColumnChangeKind::Added,
+            new_schema.version(),
+        );
+
         // value_indices would be generated automatically.
         meta_builder
             .schema(Arc::new(new_schema))
-            .primary_key_indices(primary_key_indices);
+            .primary_key_indices(primary_key_indices)
+            .column_history(column_history);
 
         Ok(meta_builder)
     }
@@ -352,9 +434,22 @@ impl TableMeta {
             .map(|name| new_schema.column_index_by_name(name).unwrap())
             .collect();
 
+        // Keep the order of dropped columns in table schema.
+        let dropped_columns = table_schema
+            .column_schemas()
+            .iter()
+            .filter(|column_schema| column_names.contains(&column_schema.name))
+            .map(|column_schema| column_schema.name.clone());
+        let column_history = self.column_history_with(
+            dropped_columns,
+            ColumnChangeKind::Dropped,
+            new_schema.version(),
+        );
+
         meta_builder
             .schema(Arc::new(new_schema))
-            .primary_key_indices(primary_key_indices);
+            .primary_key_indices(primary_key_indices)
+            .column_history(column_history);
 
         Ok(meta_builder)
     }
@@ -432,6 +527,8 @@ pub struct RawTableMeta {
     pub engine_options: HashMap<String, String>,
     pub options: TableOptions,
     pub created_on: DateTime<Utc>,
+    #[serde(default)]
+    pub column_history: Vec<ColumnChange>,
 }
 
 impl From<TableMeta> for RawTableMeta {
@@ -446,6 +543,7 @@ impl From<TableMeta> for RawTableMeta {
             engine_options: meta.engine_options,
             options: meta.options,
             created_on: meta.created_on,
+            column_history: meta.column_history,
         }
     }
 }
@@ -464,6 +562,7 @@ impl TryFrom<RawTableMeta> for TableMeta {
             engine_options: raw.engine_options,
             options: raw.options,
             created_on: raw.created_on,
+            column_history: raw.column_history,
         })
     }
 }
@@ -582,6 +681,47 @@ mod tests {
         builder.build().unwrap()
     }
 
+    #[test]
+    fn test_set_options() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .options(TableOptions {
+                flush_rows: Some(100),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        let alter_kind = AlterKind::SetOptions {
+            options: HashMap::from([(TTL_KEY.to_string(), "7d".to_string())]),
+        };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            Some(std::time::Duration::from_secs(7 * 24 * 3600)),
+            new_meta.options.ttl
+        );
+        assert_eq!(Some(100), new_meta.options.flush_rows);
+        assert_eq!(meta.schema, new_meta.schema);
+        assert_eq!(meta.value_indices, new_meta.value_indices);
+
+        let alter_kind = AlterKind::SetOptions {
+            options: HashMap::from([("flush_rows".to_string(), "10".to_string())]),
+        };
+        let err = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .err()
+            .unwrap();
+        assert_eq!(StatusCode::Unsupported, err.status_code());
+    }
+
     #[test]
     fn test_add_columns() {
         let schema = Arc::new(new_test_schema());
@@ -646,6 +786,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_column_history() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .build()
+            .unwrap();
+        assert!(meta.column_history.is_empty());
+
+        let meta = add_columns_to_meta(&meta);
+        let alter_kind = AlterKind::DropColumns {
+            names: vec![String::from("my_field"), String::from("col2")],
+        };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let history = new_meta
+            .column_history
+            .iter()
+            .map(|c| (c.column_name.as_str(), c.kind, c.schema_version))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("my_tag", ColumnChangeKind::Added, 124),
+                ("my_field", ColumnChangeKind::Added, 124),
+                ("col2", ColumnChangeKind::Dropped, 125),
+                ("my_field", ColumnChangeKind::Dropped, 125),
+            ],
+            history
+        );
+
+        // History is kept after renaming.
+        let alter_kind = AlterKind::RenameTable {
+            new_table_name: "my_new_table".to_string(),
+        };
+        let renamed_meta = new_meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(new_meta.column_history, renamed_meta.column_history);
+
+        let raw = RawTableMeta::from(renamed_meta.clone());
+        assert_eq!(renamed_meta, TableMeta::try_from(raw).unwrap());
+    }
+
     #[test]
     fn test_remove_multiple_columns_before_timestamp() {
         let column_schemas = vec![
@@ -15,14 +15,17 @@
 use common_query::logical_plan::{DfExpr, Expr};
 use common_telemetry::{error, warn};
 use common_time::range::TimestampRange;
+use common_time::timestamp::TimeUnit;
 use common_time::Timestamp;
 use datafusion::parquet::file::metadata::RowGroupMetaData;
 use datafusion::physical_optimizer::pruning::PruningPredicate;
 use datafusion_common::ToDFSchema;
-use datafusion_expr::{Between, BinaryExpr, Operator};
+use datafusion_expr::{Between, BinaryExpr, Cast, Operator};
 use datafusion_physical_expr::create_physical_expr;
 use datafusion_physical_expr::execution_props::ExecutionProps;
-use datatypes::schema::SchemaRef;
+use datatypes::arrow::datatypes::{DataType, TimeUnit as ArrowTimeUnit};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, SchemaRef};
 use datatypes::value::scalar_value_to_timestamp;
 
 use crate::predicate::stats::RowGroupPruningStatistics;
@@ -95,13 +98,19 @@ impl Predicate {
 // since it requires query engine to convert sql to filters.
 pub struct TimeRangePredicateBuilder<'a> {
     ts_col_name: &'a str,
+    ts_col_unit: Option<TimeUnit>,
     filters: &'a [Expr],
 }
 
 impl<'a> TimeRangePredicateBuilder<'a> {
-    pub fn new(ts_col_name: &'a str, filters: &'a [Expr]) -> Self {
+    pub fn new(ts_col: &'a ColumnSchema, filters: &'a [Expr]) -> Self {
+        let ts_col_unit = match &ts_col.data_type {
+            ConcreteDataType::Timestamp(ts_type) => Some(ts_type.unit()),
+            _ => None,
+        };
         Self {
-            ts_col_name,
+            ts_col_name: &ts_col.name,
+            ts_col_unit,
             filters,
         }
     }
@@ -199,7 +208,10 @@ impl<'a> TimeRangePredicateBuilder<'a> {
     }
 
     fn get_timestamp_filter(&self, left: &DfExpr, right: &DfExpr) -> Option<Timestamp> {
-        let (col, lit) = match (left, right) {
+        let (col, lit) = match (
+            self.unwrap_timestamp_cast(left),
+            self.unwrap_timestamp_cast(right),
+        ) {
             (DfExpr::Column(column), DfExpr::Literal(scalar)) => (column, scalar),
             (DfExpr::Literal(scalar), DfExpr::Column(column)) => (column, scalar),
             _ => {
@@ -270,6 +282,34 @@ impl<'a> TimeRangePredicateBuilder<'a> {
         }
         Some(init_range)
     }
+
+    /// Strips the cast of the time index to a timestamp type of the same or a finer unit (e.g.
+    /// `CAST(ts AS TIMESTAMP(9))` of a millisecond time index), which keeps every timestamp
+    /// at the same instant, so the comparison still bounds the time index. The cast to a
+    /// coarser unit truncates the timestamps and is left in place.
+    fn unwrap_timestamp_cast<'b>(&self, expr: &'b DfExpr) -> &'b DfExpr {
+        match expr {
+            DfExpr::Cast(Cast {
+                expr: inner,
+                data_type: DataType::Timestamp(unit, _),
+            }) if self.ts_col_unit.map_or(false, |ts_unit| {
+                time_unit(unit).factor() <= ts_unit.factor()
+            }) =>
+            {
+                inner.as_ref()
+            }
+            _ => expr,
+        }
+    }
+}
+
+fn time_unit(unit: &ArrowTimeUnit) -> TimeUnit {
+    match unit {
+        ArrowTimeUnit::Second => TimeUnit::Second,
+        ArrowTimeUnit::Millisecond => TimeUnit::Millisecond,
+        ArrowTimeUnit::Microsecond => TimeUnit::Microsecond,
+        ArrowTimeUnit::Nanosecond => TimeUnit::Nanosecond,
+    }
 }
 
 #[cfg(test)]
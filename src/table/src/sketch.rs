@@ -0,0 +1,141 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Probabilistic sketches summarizing large amounts of values in bounded memory.
+
+use std::hash::{Hash, Hasher};
+
+use datatypes::value::ValueRef;
+
+/// Precision of the sketches, each sketch takes `2^PRECISION` bytes.
+const PRECISION: u32 = 12;
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch counting the distinct hashes approximately, with a standard error
+/// of about 1.6%.
+#[derive(Clone)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    /// Sum of `2^-register` of all registers, maintained incrementally so the estimation
+    /// is cheap enough to do for each row.
+    harmonic_sum: f64,
+    num_zeros: usize,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+            harmonic_sum: NUM_REGISTERS as f64,
+            num_zeros: NUM_REGISTERS,
+        }
+    }
+
+    fn index_and_rank(hash: u64) -> (usize, u8) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // Sets the lowest bit so the rank is at most `64 - PRECISION + 1`.
+        let rest = (hash << PRECISION) | (1 << (PRECISION - 1));
+        (index, rest.leading_zeros() as u8 + 1)
+    }
+
+    /// Whether inserting the hash would change the sketch.
+    pub fn is_new(&self, hash: u64) -> bool {
+        let (index, rank) = Self::index_and_rank(hash);
+        rank > self.registers[index]
+    }
+
+    pub fn insert(&mut self, hash: u64) {
+        let (index, rank) = Self::index_and_rank(hash);
+        let register = &mut self.registers[index];
+        if rank <= *register {
+            return;
+        }
+        if *register == 0 {
+            self.num_zeros -= 1;
+        }
+        self.harmonic_sum += 2f64.powi(-(rank as i32)) - 2f64.powi(-(*register as i32));
+        *register = rank;
+    }
+
+    pub fn estimate(&self) -> f64 {
+        let m = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let estimate = alpha * m * m / self.harmonic_sum;
+        if estimate <= 2.5 * m && self.num_zeros > 0 {
+            // Linear counting is more accurate for small cardinalities.
+            m * (m / self.num_zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+}
+
+/// Feeds the value into the hasher, values of different types are hashed differently.
+pub fn hash_value(value: ValueRef, hasher: &mut impl Hasher) {
+    std::mem::discriminant(&value).hash(hasher);
+    match value {
+        ValueRef::Null => {}
+        ValueRef::Boolean(v) => v.hash(hasher),
+        ValueRef::UInt8(v) => v.hash(hasher),
+        ValueRef::UInt16(v) => v.hash(hasher),
+        ValueRef::UInt32(v) => v.hash(hasher),
+        ValueRef::UInt64(v) => v.hash(hasher),
+        ValueRef::Int8(v) => v.hash(hasher),
+        ValueRef::Int16(v) => v.hash(hasher),
+        ValueRef::Int32(v) => v.hash(hasher),
+        ValueRef::Int64(v) => v.hash(hasher),
+        ValueRef::Float32(v) => v.hash(hasher),
+        ValueRef::Float64(v) => v.hash(hasher),
+        ValueRef::String(v) => v.hash(hasher),
+        ValueRef::Binary(v) => v.hash(hasher),
+        ValueRef::Date(v) => v.val().hash(hasher),
+        ValueRef::DateTime(v) => v.val().hash(hasher),
+        ValueRef::Timestamp(v) => v.hash(hasher),
+        // Lists can't be primary keys.
+        ValueRef::List(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    #[test]
+    fn test_hyper_log_log_estimate() {
+        let mut sketch = HyperLogLog::new();
+        assert_eq!(0.0, sketch.estimate());
+
+        for n in [100, 10_000, 1_000_000] {
+            let mut sketch = HyperLogLog::new();
+            for i in 0..n {
+                let mut hasher = DefaultHasher::new();
+                i.hash(&mut hasher);
+                sketch.insert(hasher.finish());
+            }
+            let error = (sketch.estimate() - n as f64).abs() / n as f64;
+            assert!(error < 0.05, "n: {n}, estimate: {}", sketch.estimate());
+        }
+
+        sketch.insert(1);
+        assert!(!sketch.is_new(1));
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics of tables, used by the query optimizer to estimate the cost of plans.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::Hasher;
+
+use common_error::prelude::BoxedError;
+use common_query::physical_plan::SessionContext;
+use common_recordbatch::RecordBatch;
+use common_time::util::current_time_millis;
+use datafusion::physical_plan::{
+    ColumnStatistics as DfColumnStatistics, Statistics as DfStatistics,
+};
+use datatypes::schema::Schema;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::error::{CollectStatisticsSnafu, Result};
+use crate::sketch::{hash_value, HyperLogLog};
+use crate::Table;
+
+/// Statistics of a column.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnStatistics {
+    /// Number of null values in the column.
+    pub null_count: usize,
+    /// Approximate number of distinct values in the column. Only collected for tag columns.
+    pub distinct_count: Option<usize>,
+}
+
+impl ColumnStatistics {
+    /// Fraction of null values in the column, `num_rows` is the row count of the table.
+    pub fn null_fraction(&self, num_rows: usize) -> f64 {
+        if num_rows == 0 {
+            0.0
+        } else {
+            self.null_count as f64 / num_rows as f64
+        }
+    }
+}
+
+/// Statistics of a table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableStatistics {
+    /// Number of rows in the table.
+    pub num_rows: usize,
+    /// Total memory size of the table data in bytes.
+    pub total_byte_size: usize,
+    /// Statistics of the columns, keyed by column name.
+    pub columns: HashMap<String, ColumnStatistics>,
+    /// Time these statistics were collected at, in milliseconds.
+    pub collected_at_millis: i64,
+}
+
+impl TableStatistics {
+    /// Converts to DataFusion [Statistics](DfStatistics) of the table scan with `projection`
+    /// over the table `schema`.
+    ///
+    /// The statistics are never exact, as the table may have changed since they were collected.
+    pub fn to_df_statistics(
+        &self,
+        schema: &Schema,
+        projection: Option<&Vec<usize>>,
+    ) -> DfStatistics {
+        let indices = match projection {
+            Some(projection) => projection.clone(),
+            None => (0..schema.num_columns()).collect(),
+        };
+        let column_statistics = indices
+            .into_iter()
+            .map(|index| {
+                schema
+                    .column_schemas()
+                    .get(index)
+                    .and_then(|column_schema| self.columns.get(&column_schema.name))
+                    .map(|stats| DfColumnStatistics {
+                        null_count: Some(stats.null_count),
+                        distinct_count: stats.distinct_count,
+                        ..Default::default()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        DfStatistics {
+            num_rows: Some(self.num_rows),
+            total_byte_size: Some(self.total_byte_size),
+            column_statistics: Some(column_statistics),
+            is_exact: false,
+        }
+    }
+}
+
+/// Builds [TableStatistics] from the record batches of a table.
+pub struct StatisticsBuilder {
+    column_names: Vec<String>,
+    null_counts: Vec<usize>,
+    /// Sketches of the distinct values of the columns, `None` if the column is not tracked.
+    distinct_values: Vec<Option<HyperLogLog>>,
+    num_rows: usize,
+    total_byte_size: usize,
+}
+
+impl StatisticsBuilder {
+    /// Creates a builder for table with `schema`, which collects distinct counts of the
+    /// columns in `tag_indices`.
+    pub fn new(schema: &Schema, tag_indices: &[usize]) -> Self {
+        let column_names = schema
+            .column_schemas()
+            .iter()
+            .map(|column_schema| column_schema.name.clone())
+            .collect::<Vec<_>>();
+        let distinct_values = (0..column_names.len())
+            .map(|index| tag_indices.contains(&index).then(HyperLogLog::new))
+            .collect();
+
+        Self {
+            null_counts: vec![0; column_names.len()],
+            column_names,
+            distinct_values,
+            num_rows: 0,
+            total_byte_size: 0,
+        }
+    }
+
+    /// Updates the statistics with a batch of the table. Columns absent in the batch are
+    /// skipped.
+    pub fn update(&mut self, batch: &RecordBatch) {
+        self.num_rows += batch.num_rows();
+
+        for (index, name) in self.column_names.iter().enumerate() {
+            let Some(vector) = batch.column_by_name(name) else { continue };
+            self.total_byte_size += vector.memory_size();
+            self.null_counts[index] += vector.null_count();
+
+            if let Some(sketch) = &mut self.distinct_values[index] {
+                for row in 0..vector.len() {
+                    if !vector.is_null(row) {
+                        let mut hasher = DefaultHasher::new();
+                        hash_value(vector.get_ref(row), &mut hasher);
+                        sketch.insert(hasher.finish());
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> TableStatistics {
+        let columns = self
+            .column_names
+            .into_iter()
+            .zip(self.null_counts)
+            .zip(self.distinct_values)
+            .map(|((name, null_count), distinct_values)| {
+                let stats = ColumnStatistics {
+                    null_count,
+                    distinct_count: distinct_values
+                        .map(|sketch| sketch.estimate().round() as usize),
+                };
+                (name, stats)
+            })
+            .collect();
+
+        TableStatistics {
+            num_rows: self.num_rows,
+            total_byte_size: self.total_byte_size,
+            columns,
+            collected_at_millis: current_time_millis(),
+        }
+    }
+}
+
+/// Scans all data in the `table` to collect its statistics. Distinct counts are collected
+/// for the primary key (tag) columns.
+pub async fn scan_statistics(table: &dyn Table) -> Result<TableStatistics> {
+    let table_info = table.table_info();
+    let mut builder = StatisticsBuilder::new(
+        &table_info.meta.schema,
+        &table_info.meta.primary_key_indices,
+    );
+
+    let plan = table.scan(None, &[], None).await?;
+    let ctx = SessionContext::new();
+    for partition in 0..plan.output_partitioning().partition_count() {
+        let mut stream = plan
+            .execute(partition, ctx.task_ctx())
+            .map_err(BoxedError::new)
+            .context(CollectStatisticsSnafu {
+                table_name: &table_info.name,
+            })?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch
+                .map_err(BoxedError::new)
+                .context(CollectStatisticsSnafu {
+                    table_name: &table_info.name,
+                })?;
+            builder.update(&batch);
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+
+    use super::*;
+
+    fn new_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ]))
+    }
+
+    fn new_batch(
+        schema: Arc<Schema>,
+        hosts: Vec<Option<&str>>,
+        cpus: Vec<Option<f64>>,
+    ) -> RecordBatch {
+        let ts = (0..hosts.len() as i64).collect::<Vec<_>>();
+        RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(StringVector::from(hosts)) as _,
+                Arc::new(TimestampMillisecondVector::from_vec(ts)) as _,
+                Arc::new(Float64Vector::from(cpus)) as _,
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_statistics() {
+        let schema = new_schema();
+        let mut builder = StatisticsBuilder::new(&schema, &[0]);
+        builder.update(&new_batch(
+            schema.clone(),
+            vec![Some("host1"), Some("host2"), None],
+            vec![Some(1.0), None, None],
+        ));
+        builder.update(&new_batch(
+            schema.clone(),
+            vec![Some("host1"), Some("host3")],
+            vec![Some(2.0), Some(3.0)],
+        ));
+        let stats = builder.finish();
+
+        assert_eq!(5, stats.num_rows);
+        assert!(stats.total_byte_size > 0);
+        let host = &stats.columns["host"];
+        assert_eq!(1, host.null_count);
+        assert_eq!(Some(3), host.distinct_count);
+        assert!((host.null_fraction(stats.num_rows) - 0.2).abs() < f64::EPSILON);
+        let cpu = &stats.columns["cpu"];
+        assert_eq!(2, cpu.null_count);
+        assert_eq!(None, cpu.distinct_count);
+        assert_eq!(0, stats.columns["ts"].null_count);
+    }
+
+    #[test]
+    fn test_distinct_count_of_many_values() {
+        let schema = new_schema();
+        let mut builder = StatisticsBuilder::new(&schema, &[0]);
+        let hosts = (0..200_000).map(|i| format!("host{i}")).collect::<Vec<_>>();
+        for chunk in hosts.chunks(10_000) {
+            builder.update(&new_batch(
+                schema.clone(),
+                chunk.iter().map(|host| Some(host.as_str())).collect(),
+                vec![None; chunk.len()],
+            ));
+        }
+        let stats = builder.finish();
+
+        let distinct_count = stats.columns["host"].distinct_count.unwrap();
+        let error = (distinct_count as f64 - 200_000.0).abs() / 200_000.0;
+        assert!(error < 0.05, "distinct count: {distinct_count}");
+    }
+
+    #[test]
+    fn test_to_df_statistics() {
+        let schema = new_schema();
+        let mut builder = StatisticsBuilder::new(&schema, &[0]);
+        builder.update(&new_batch(
+            schema.clone(),
+            vec![Some("host1"), Some("host2")],
+            vec![Some(1.0), None],
+        ));
+        let stats = builder.finish();
+
+        let df_stats = stats.to_df_statistics(&schema, None);
+        assert_eq!(Some(2), df_stats.num_rows);
+        assert!(!df_stats.is_exact);
+        let columns = df_stats.column_statistics.unwrap();
+        assert_eq!(3, columns.len());
+        assert_eq!(Some(2), columns[0].distinct_count);
+        assert_eq!(Some(1), columns[2].null_count);
+
+        let df_stats = stats.to_df_statistics(&schema, Some(&vec![2]));
+        let columns = df_stats.column_statistics.unwrap();
+        assert_eq!(1, columns.len());
+        assert_eq!(Some(1), columns[0].null_count);
+        assert_eq!(None, columns[0].distinct_count);
+    }
+
+    #[test]
+    fn test_null_fraction_of_empty_table() {
+        let stats = ColumnStatistics::default();
+        assert_eq!(0.0, stats.null_fraction(0));
+    }
+}
@@ -15,19 +15,28 @@
 pub mod adapter;
 pub mod numbers;
 pub mod scan;
+pub mod view;
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
+use common_time::Timestamp;
 use datatypes::schema::SchemaRef;
-use store_api::storage::RegionNumber;
+use store_api::storage::{
+    AttachReport, DropRangeReport, PurgeReport, RegionHealthReport, RegionNumber, SequenceNumber,
+    WriteThrottle,
+};
 
 use crate::error::{Result, UnsupportedSnafu};
 use crate::metadata::{FilterPushDownType, TableId, TableInfoRef, TableType};
 use crate::requests::{AlterTableRequest, DeleteRequest, InsertRequest};
+use crate::stats::TableStatistics;
+use crate::table::scan::SampleExec;
 
 pub type AlterContext = anymap::Map<dyn Any + Send + Sync>;
 
@@ -71,6 +80,63 @@ pub trait Table: Send + Sync {
         limit: Option<usize>,
     ) -> Result<PhysicalPlanRef>;
 
+    /// Scans about `percent` percent of the table. Data is sampled in units of the
+    /// storage, e.g. SST row groups, rather than rows, so the sample is cheap to read
+    /// but not uniform in rows.
+    ///
+    /// By default, the whole table is scanned and about `percent` percent of the scanned
+    /// batches are kept. Tables able to skip reading the unsampled data should override it.
+    async fn scan_sample(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        percent: f64,
+    ) -> Result<PhysicalPlanRef> {
+        // The limit applies to the sampled rows, so it can't be pushed down to the scan.
+        let _ = limit;
+        let plan = self.scan(projection, filters, None).await?;
+        Ok(Arc::new(SampleExec::new(plan, percent)))
+    }
+
+    /// Returns the sequence numbers of all regions of the table, which could be passed to
+    /// [scan_snapshot](Table::scan_snapshot) to read the table as of now later.
+    ///
+    /// The sequences of all regions are pinned at the same point, a write to the table is
+    /// either visible in all regions of the snapshot or in none of them.
+    async fn snapshot(&self) -> Result<TableSnapshot> {
+        UnsupportedSnafu {
+            operation: "SNAPSHOT",
+        }
+        .fail()?
+    }
+
+    /// Scans the table at the sequence numbers in `snapshot`, rows written after the
+    /// snapshot is taken are invisible, no matter they are flushed or compacted.
+    ///
+    /// Multiple scans at the same snapshot always see the same data, unless the rows
+    /// visible to the snapshot are overwritten and then compacted.
+    async fn scan_snapshot(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        snapshot: &TableSnapshot,
+    ) -> Result<PhysicalPlanRef> {
+        let _ = (projection, filters, limit, snapshot);
+        UnsupportedSnafu {
+            operation: "SNAPSHOT",
+        }
+        .fail()?
+    }
+
+    /// Tails the table, the returned plan never finishes and keeps emitting rows inserted
+    /// into the table after the call, rather than rows already in the table.
+    async fn tail(&self, projection: Option<&Vec<usize>>) -> Result<PhysicalPlanRef> {
+        let _ = projection;
+        UnsupportedSnafu { operation: "TAIL" }.fail()?
+    }
+
     /// Tests whether the table provider can make use of any or all filter expressions
     /// to optimise data retrieval.
     fn supports_filters_pushdown(&self, filters: &[&Expr]) -> Result<Vec<FilterPushDownType>> {
@@ -105,11 +171,125 @@ pub trait Table: Send + Sync {
         UnsupportedSnafu { operation: "FLUSH" }.fail()?
     }
 
+    /// Compact table manually.
+    ///
+    /// Options:
+    /// - region_number: specify region to compact.
+    /// - wait: Whether to wait until compaction is done.
+    async fn compact(&self, region_number: Option<RegionNumber>, wait: Option<bool>) -> Result<()> {
+        let _ = (region_number, wait);
+        UnsupportedSnafu {
+            operation: "COMPACT",
+        }
+        .fail()?
+    }
+
+    /// Flush the region and reject all writes to it afterwards, so nothing written to the
+    /// region is left behind when it's moved to another node.
+    async fn fence_region(&self, region_number: RegionNumber) -> Result<()> {
+        let _ = region_number;
+        UnsupportedSnafu { operation: "FENCE" }.fail()?
+    }
+
+    /// Accept the writes to the region again after it's fenced by [Table::fence_region].
+    async fn unfence_region(&self, region_number: RegionNumber) -> Result<()> {
+        let _ = region_number;
+        UnsupportedSnafu {
+            operation: "UNFENCE",
+        }
+        .fail()?
+    }
+
+    /// Purge data expired by the TTL of the table, returns what is purged in
+    /// each region.
+    ///
+    /// Options:
+    /// - region_number: specify region to purge.
+    /// - dry_run: Only reports what would be purged.
+    async fn purge_expired(
+        &self,
+        region_number: Option<RegionNumber>,
+        dry_run: bool,
+    ) -> Result<Vec<(RegionNumber, PurgeReport)>> {
+        let _ = (region_number, dry_run);
+        UnsupportedSnafu { operation: "PURGE" }.fail()?
+    }
+
+    /// Drop all data of the table within the inclusive time range `[start, end]`,
+    /// returns what is dropped in each region.
+    ///
+    /// Options:
+    /// - region_number: specify region to drop data from.
+    /// - dry_run: Only reports what would be dropped.
+    async fn drop_range(
+        &self,
+        region_number: Option<RegionNumber>,
+        start: Timestamp,
+        end: Timestamp,
+        dry_run: bool,
+    ) -> Result<Vec<(RegionNumber, DropRangeReport)>> {
+        let _ = (region_number, start, end, dry_run);
+        UnsupportedSnafu {
+            operation: "DROP RANGE",
+        }
+        .fail()?
+    }
+
+    /// Attach the SST files staged in the object store to the table, returns what is
+    /// attached to each region. Files of each region are staged under
+    /// `<staging_dir>/<region_number>/`. All files are validated and copied before any
+    /// region attaches them, so an invalid file leaves the table unchanged.
+    ///
+    /// Options:
+    /// - region_number: specify region to attach files to.
+    async fn attach_ssts(
+        &self,
+        region_number: Option<RegionNumber>,
+        staging_dir: &str,
+    ) -> Result<Vec<(RegionNumber, AttachReport)>> {
+        let _ = (region_number, staging_dir);
+        UnsupportedSnafu {
+            operation: "ATTACH",
+        }
+        .fail()?
+    }
+
+    /// Scrub the manifest and data files of the table without fixing anything, returns
+    /// the health of each region.
+    ///
+    /// Options:
+    /// - region_number: specify region to scrub.
+    async fn scrub(
+        &self,
+        region_number: Option<RegionNumber>,
+    ) -> Result<Vec<(RegionNumber, RegionHealthReport)>> {
+        let _ = region_number;
+        UnsupportedSnafu { operation: "SCRUB" }.fail()?
+    }
+
+    /// Clone all data of the `source` table into this table by sharing its data files.
+    /// Both tables must be created by the same engine with the same schema and regions.
+    async fn clone_data_from(&self, source: TableRef) -> Result<()> {
+        let _ = source;
+        UnsupportedSnafu { operation: "CLONE" }.fail()?
+    }
+
     /// Close the table.
     async fn close(&self) -> Result<()> {
         Ok(())
     }
 
+    /// Closes the table if it hasn't been read or written for `idle_timeout`, which flushes
+    /// its regions and releases their memtables and caches. The next read or write reopens
+    /// the table lazily. Returns whether the table is closed by this call.
+    async fn close_if_idle(&self, idle_timeout: Duration) -> Result<bool> {
+        let _ = idle_timeout;
+        UnsupportedSnafu {
+            operation: "CLOSE_IF_IDLE",
+        }
+        .fail()?
+    }
+
     /// Get region stats in this table.
     fn region_stats(&self) -> Result<Vec<RegionStat>> {
         UnsupportedSnafu {
@@ -117,6 +297,30 @@ pub trait Table: Send + Sync {
         }
         .fail()?
     }
+
+    /// Returns whether inserts to the table should be admitted, delayed or rejected
+    /// under the current write pressure of the table.
+    fn write_throttle(&self) -> WriteThrottle {
+        WriteThrottle::Admit
+    }
+
+    /// Get the statistics of the table collected last time, if any.
+    fn statistics(&self) -> Option<TableStatistics> {
+        None
+    }
+
+    /// Restore the statistics collected before, e.g. the persisted ones after the table is
+    /// opened. Tables not supporting statistics ignore them.
+    fn restore_statistics(&self, _statistics: TableStatistics) {}
+
+    /// Collect the statistics of the table, which are returned by
+    /// [statistics](Table::statistics) afterwards.
+    async fn collect_statistics(&self) -> Result<TableStatistics> {
+        UnsupportedSnafu {
+            operation: "COLLECT_STATISTICS",
+        }
+        .fail()?
+    }
 }
 
 pub type TableRef = Arc<dyn Table>;
@@ -128,6 +332,15 @@ pub trait TableIdProvider {
 
 pub type TableIdProviderRef = Arc<dyn TableIdProvider + Send + Sync>;
 
+/// Sequence numbers of regions in a table, a consistent point to read the table at.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableSnapshot {
+    pub sequences: HashMap<RegionNumber, SequenceNumber>,
+    /// Metadata versions of regions, reading a region altered after the snapshot is
+    /// taken fails so all regions are read with the same schema.
+    pub versions: HashMap<RegionNumber, u32>,
+}
+
 #[derive(Default, Debug)]
 pub struct RegionStat {
     pub region_id: u64,
@@ -31,21 +31,65 @@ use snafu::prelude::*;
 
 use crate::error::{self, Result};
 use crate::metadata::TableInfoRef;
+use crate::table::scan::ScanHints;
 use crate::table::{FilterPushDownType, Table, TableRef, TableType};
 
 /// Greptime Table ->  datafusion TableProvider
 pub struct DfTableProviderAdapter {
     table: TableRef,
+    sample_percent: Option<f64>,
+    tail: bool,
 }
 
 impl DfTableProviderAdapter {
     pub fn new(table: TableRef) -> Self {
-        Self { table }
+        Self {
+            table,
+            sample_percent: None,
+            tail: false,
+        }
+    }
+
+    /// Keeps the `filters` the table evaluates exactly, which are required for the correct
+    /// results, and drops the ones only used to prune the data.
+    fn exact_filters(&self, filters: Vec<Expr>) -> Result<Vec<Expr>> {
+        let pushdowns = self
+            .table
+            .supports_filters_pushdown(&filters.iter().collect::<Vec<_>>())?;
+        Ok(filters
+            .into_iter()
+            .zip(pushdowns)
+            .filter(|(_, pushdown)| *pushdown == FilterPushDownType::Exact)
+            .map(|(filter, _)| filter)
+            .collect())
+    }
+
+    /// Creates an adapter that only scans a sample of about `percent` percent of the table.
+    pub fn with_sample(table: TableRef, percent: f64) -> Self {
+        Self {
+            table,
+            sample_percent: Some(percent),
+            tail: false,
+        }
+    }
+
+    /// Creates an adapter that tails the table instead of scanning it.
+    pub fn with_tail(table: TableRef) -> Self {
+        Self {
+            table,
+            sample_percent: None,
+            tail: true,
+        }
     }
 
     pub fn table(&self) -> TableRef {
         self.table.clone()
     }
+
+    /// Returns the percent of the table to sample, if only a sample of the table is scanned.
+    pub fn sample_percent(&self) -> Option<f64> {
+        self.sample_percent
+    }
 }
 
 #[async_trait::async_trait]
@@ -68,13 +112,29 @@ impl TableProvider for DfTableProviderAdapter {
 
     async fn scan(
         &self,
-        _ctx: &SessionState,
+        ctx: &SessionState,
         projection: Option<&Vec<usize>>,
         filters: &[DfExpr],
         limit: Option<usize>,
     ) -> DfResult<Arc<dyn DfPhysicalPlan>> {
-        let filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
-        let inner = self.table.scan(projection, &filters, limit).await?;
+        let mut filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
+        let no_index = ctx
+            .config()
+            .get_extension::<ScanHints>()
+            .map(|hints| hints.no_index)
+            .unwrap_or(false);
+        if no_index {
+            filters = self.exact_filters(filters)?;
+        }
+        let inner = match self.sample_percent {
+            _ if self.tail => self.table.tail(projection).await?,
+            Some(percent) => {
+                self.table
+                    .scan_sample(projection, &filters, limit, percent)
+                    .await?
+            }
+            None => self.table.scan(projection, &filters, limit).await?,
+        };
         Ok(Arc::new(DfPhysicalPlanAdapter(inner)))
     }
 
@@ -82,6 +142,14 @@ impl TableProvider for DfTableProviderAdapter {
         &self,
         filters: &[&DfExpr],
     ) -> DfResult<Vec<DfTableProviderFilterPushDown>> {
+        // Filters are always evaluated over the tailed rows by the plan.
+        if self.tail {
+            return Ok(vec![
+                DfTableProviderFilterPushDown::Unsupported;
+                filters.len()
+            ]);
+        }
+
         let filters = filters
             .iter()
             .map(|&x| x.clone().into())
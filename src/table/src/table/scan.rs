@@ -14,27 +14,85 @@
 
 use std::any::Any;
 use std::fmt::{Debug, Formatter};
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 
 use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
-use common_query::physical_plan::{Partitioning, PhysicalPlan, PhysicalPlanRef};
-use common_recordbatch::SendableRecordBatchStream;
+use common_query::physical_plan::{
+    Partitioning, PhysicalPlan, PhysicalPlanRef, Statistics, BYTES_SCANNED,
+};
+use common_recordbatch::error::Result as RecordBatchResult;
+use common_recordbatch::{
+    ExecutionStats, RecordBatch, RecordBatchStream, SendableRecordBatchStream,
+};
+use datafusion::arrow::array::Array;
 use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::metrics::{
+    Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
 use datafusion_physical_expr::PhysicalSortExpr;
 use datatypes::schema::SchemaRef;
+use futures::{Stream, StreamExt};
 use snafu::OptionExt;
 
+/// Bytes a partition is expected to scan at least, scanning fewer bytes doesn't deserve
+/// a dedicated partition.
+pub const BYTES_PER_PARTITION: u64 = 64 * 1024 * 1024;
+/// Files a partition is expected to scan at least.
+pub const FILES_PER_PARTITION: usize = 8;
+
+/// Estimated cost of a table scan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanCost {
+    /// Number of files to read.
+    pub num_files: usize,
+    /// Estimated bytes to read.
+    pub num_bytes: u64,
+}
+
+impl ScanCost {
+    pub fn merge(&mut self, other: &ScanCost) {
+        self.num_files += other.num_files;
+        self.num_bytes += other.num_bytes;
+    }
+
+    /// Number of partitions worth scanning with this cost, no more than `max_partitions`.
+    ///
+    /// Small scans use a single partition to avoid the overhead of parallelism, while large
+    /// scans are spread over more partitions as the files or bytes to read grow.
+    pub fn target_partitions(&self, max_partitions: usize) -> usize {
+        let by_files = (self.num_files + FILES_PER_PARTITION - 1) / FILES_PER_PARTITION;
+        let by_bytes = ((self.num_bytes + BYTES_PER_PARTITION - 1) / BYTES_PER_PARTITION) as usize;
+        by_files.max(by_bytes).clamp(1, max_partitions.max(1))
+    }
+}
+
+/// Hints of the query for the table scans, passed to the table providers as an extension
+/// of the DataFusion session config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanHints {
+    /// Scans without pruning the data by the filters, which are still evaluated over the
+    /// scanned rows unless the table handles them exactly.
+    pub no_index: bool,
+}
+
 pub struct SimpleTableScan {
-    stream: Mutex<Option<SendableRecordBatchStream>>,
+    /// Streams of each partition.
+    streams: Vec<Mutex<Option<SendableRecordBatchStream>>>,
     schema: SchemaRef,
     output_ordering: Option<Vec<PhysicalSortExpr>>,
+    statistics: Statistics,
+    scan_cost: Option<ScanCost>,
+    metrics: ExecutionPlanMetricsSet,
 }
 
 impl Debug for SimpleTableScan {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SimpleTableScan")
             .field("stream", &"<SendableRecordBatchStream>")
+            .field("partitions", &self.streams.len())
             .field("schema", &self.schema)
             .finish()
     }
@@ -42,12 +100,22 @@ impl Debug for SimpleTableScan {
 
 impl SimpleTableScan {
     pub fn new(stream: SendableRecordBatchStream) -> Self {
-        let schema = stream.schema();
+        Self::new_partitioned(stream.schema(), vec![stream])
+    }
 
+    /// Creates a scan with one partition for each stream, all the streams must have the
+    /// same `schema`.
+    pub fn new_partitioned(schema: SchemaRef, streams: Vec<SendableRecordBatchStream>) -> Self {
         Self {
-            stream: Mutex::new(Some(stream)),
+            streams: streams
+                .into_iter()
+                .map(|stream| Mutex::new(Some(stream)))
+                .collect(),
             schema,
             output_ordering: None,
+            statistics: Statistics::default(),
+            scan_cost: None,
+            metrics: ExecutionPlanMetricsSet::new(),
         }
     }
 
@@ -55,6 +123,21 @@ impl SimpleTableScan {
         self.output_ordering = Some(output_ordering);
         self
     }
+
+    pub fn with_statistics(mut self, statistics: Statistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    pub fn with_scan_cost(mut self, scan_cost: ScanCost) -> Self {
+        self.scan_cost = Some(scan_cost);
+        self
+    }
+
+    /// Estimated cost of this scan, if known.
+    pub fn scan_cost(&self) -> Option<ScanCost> {
+        self.scan_cost
+    }
 }
 
 impl PhysicalPlan for SimpleTableScan {
@@ -67,7 +150,7 @@ impl PhysicalPlan for SimpleTableScan {
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(1)
+        Partitioning::UnknownPartitioning(self.streams.len())
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
@@ -84,11 +167,177 @@ impl PhysicalPlan for SimpleTableScan {
 
     fn execute(
         &self,
-        _partition: usize,
+        partition: usize,
         _context: Arc<TaskContext>,
     ) -> QueryResult<SendableRecordBatchStream> {
-        let mut stream = self.stream.lock().unwrap();
-        stream.take().context(query_error::ExecuteRepeatedlySnafu)
+        let stream = self
+            .streams
+            .get(partition)
+            .context(query_error::PartitionOutOfRangeSnafu {
+                partition,
+                num_partitions: self.streams.len(),
+            })?
+            .lock()
+            .unwrap()
+            .take()
+            .context(query_error::ExecuteRepeatedlySnafu)?;
+        Ok(Box::pin(ScanMetricsStream {
+            stream,
+            output_rows: MetricBuilder::new(&self.metrics).output_rows(partition),
+            bytes_scanned: MetricBuilder::new(&self.metrics).counter(BYTES_SCANNED, partition),
+        }))
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics.clone()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// Counts the rows and bytes yielded by the stream of a scan partition.
+struct ScanMetricsStream {
+    stream: SendableRecordBatchStream,
+    output_rows: Count,
+    bytes_scanned: Count,
+}
+
+impl RecordBatchStream for ScanMetricsStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn stats(&self) -> Option<ExecutionStats> {
+        self.stream.stats()
+    }
+}
+
+impl Stream for ScanMetricsStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.stream.poll_next_unpin(cx);
+        if let Poll::Ready(Some(Ok(batch))) = &poll {
+            let bytes = batch
+                .df_record_batch()
+                .columns()
+                .iter()
+                .map(|array| array.get_array_memory_size())
+                .sum();
+            self.output_rows.add(batch.num_rows());
+            self.bytes_scanned.add(bytes);
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// Keeps about `percent` percent of the record batches of the input plan, for the tables
+/// whose storage can't skip reading the unsampled data. Batches are kept evenly spaced, so
+/// the sample of the same data is stable.
+#[derive(Debug)]
+pub struct SampleExec {
+    input: PhysicalPlanRef,
+    percent: f64,
+}
+
+impl SampleExec {
+    pub fn new(input: PhysicalPlanRef, percent: f64) -> Self {
+        Self { input, percent }
+    }
+}
+
+impl PhysicalPlan for SampleExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn children(&self) -> Vec<PhysicalPlanRef> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        &self,
+        mut children: Vec<PhysicalPlanRef>,
+    ) -> QueryResult<PhysicalPlanRef> {
+        Ok(Arc::new(SampleExec::new(children.remove(0), self.percent)))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> QueryResult<SendableRecordBatchStream> {
+        Ok(Box::pin(SampleStream {
+            stream: self.input.execute(partition, context)?,
+            percent: self.percent,
+            polled: 0,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.input.metrics()
+    }
+}
+
+/// Yields the batch `i` of the stream only if `(i + 1) * percent / 100` reaches the next
+/// integer, which keeps about `percent` percent of the batches.
+struct SampleStream {
+    stream: SendableRecordBatchStream,
+    percent: f64,
+    polled: usize,
+}
+
+impl SampleStream {
+    fn sampled(&self, index: usize) -> bool {
+        let kept = |n: usize| (n as f64 * self.percent / 100.0).floor();
+        kept(index + 1) > kept(index)
+    }
+}
+
+impl RecordBatchStream for SampleStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn stats(&self) -> Option<ExecutionStats> {
+        self.stream.stats()
+    }
+}
+
+impl Stream for SampleStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(batch))) => {
+                    let index = self.polled;
+                    self.polled += 1;
+                    if self.sampled(index) {
+                        return Poll::Ready(Some(Ok(batch)));
+                    }
+                }
+                poll => return poll,
+            }
+        }
     }
 }
 
@@ -98,6 +347,7 @@ mod test {
     use datafusion::prelude::SessionContext;
     use datatypes::data_type::ConcreteDataType;
     use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::value::Value;
     use datatypes::vectors::Int32Vector;
 
     use super::*;
@@ -135,6 +385,10 @@ mod test {
         assert_eq!(recordbatches[0], batch1);
         assert_eq!(recordbatches[1], batch2);
 
+        let metrics = scan.metrics().unwrap();
+        assert_eq!(Some(5), metrics.output_rows());
+        assert!(metrics.sum_by_name(BYTES_SCANNED).unwrap().as_usize() > 0);
+
         let result = scan.execute(0, ctx.task_ctx());
         assert!(result.is_err());
         match result {
@@ -144,4 +398,108 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_sample_exec() {
+        let ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batches = (0..10)
+            .map(|i| {
+                RecordBatch::new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Vector::from_slice([i])) as _],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let stream = RecordBatches::try_new(schema.clone(), batches)
+            .unwrap()
+            .as_stream();
+        let scan = SampleExec::new(Arc::new(SimpleTableScan::new(stream)), 30.0);
+
+        assert_eq!(scan.schema(), schema);
+        let stream = scan.execute(0, ctx.task_ctx()).unwrap();
+        let recordbatches = util::collect(stream).await.unwrap();
+        let values = recordbatches
+            .iter()
+            .map(|batch| batch.column(0).get(0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![Value::Int32(3), Value::Int32(6), Value::Int32(9)],
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partitioned_table_scan() {
+        let ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+
+        let batches = [vec![1, 2], vec![3, 4, 5]].map(|values| {
+            RecordBatch::new(
+                schema.clone(),
+                vec![Arc::new(Int32Vector::from_vec(values)) as _],
+            )
+            .unwrap()
+        });
+        let streams = batches
+            .iter()
+            .map(|batch| {
+                RecordBatches::try_new(schema.clone(), vec![batch.clone()])
+                    .unwrap()
+                    .as_stream()
+            })
+            .collect();
+
+        let scan = SimpleTableScan::new_partitioned(schema, streams);
+        assert_eq!(2, scan.output_partitioning().partition_count());
+
+        for (partition, batch) in batches.iter().enumerate() {
+            let stream = scan.execute(partition, ctx.task_ctx()).unwrap();
+            let recordbatches = util::collect(stream).await.unwrap();
+            assert_eq!(vec![batch.clone()], recordbatches);
+        }
+        assert!(scan.execute(2, ctx.task_ctx()).is_err());
+    }
+
+    #[test]
+    fn test_scan_target_partitions() {
+        let cost = ScanCost::default();
+        assert_eq!(1, cost.target_partitions(8));
+
+        let cost = ScanCost {
+            num_files: FILES_PER_PARTITION + 1,
+            num_bytes: 1024,
+        };
+        assert_eq!(2, cost.target_partitions(8));
+        assert_eq!(1, cost.target_partitions(1));
+
+        let mut cost = ScanCost {
+            num_files: 1,
+            num_bytes: BYTES_PER_PARTITION * 4,
+        };
+        assert_eq!(4, cost.target_partitions(8));
+        cost.merge(&ScanCost {
+            num_files: 1,
+            num_bytes: BYTES_PER_PARTITION * 8,
+        });
+        assert_eq!(
+            ScanCost {
+                num_files: 2,
+                num_bytes: BYTES_PER_PARTITION * 12
+            },
+            cost
+        );
+        assert_eq!(8, cost.target_partitions(8));
+        // Always use at least one partition.
+        assert_eq!(1, ScanCost::default().target_partitions(0));
+    }
 }
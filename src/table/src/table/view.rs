@@ -0,0 +1,191 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use common_catalog::consts::VIEW_ENGINE;
+use common_query::physical_plan::PhysicalPlanRef;
+use datatypes::schema::SchemaRef;
+use snafu::OptionExt;
+
+use crate::error::{MissingViewDefinitionSnafu, Result, UnsupportedSnafu};
+use crate::metadata::{TableIdent, TableInfo, TableInfoRef, TableMeta, TableType};
+use crate::requests::{CreateViewRequest, TableOptions, VIEW_DEFINITION_KEY};
+use crate::table::{Expr, Table};
+
+/// A view, the non-materialized table defined by a query.
+///
+/// A view holds no data and can't be scanned. The query engine replaces the view with
+/// the plan of its definition when planning the queries referencing it.
+#[derive(Debug)]
+pub struct ViewTable {
+    table_info: TableInfoRef,
+    definition: String,
+}
+
+impl ViewTable {
+    /// Creates a view from the [CreateViewRequest].
+    pub fn new(request: CreateViewRequest) -> Self {
+        let num_columns = request.schema.num_columns();
+        let mut options = TableOptions::default();
+        options
+            .extra_options
+            .insert(VIEW_DEFINITION_KEY.to_string(), request.definition.clone());
+
+        let meta = TableMeta {
+            schema: request.schema,
+            primary_key_indices: vec![],
+            value_indices: (0..num_columns).collect(),
+            engine: VIEW_ENGINE.to_string(),
+            region_numbers: vec![],
+            next_column_id: num_columns as u32,
+            engine_options: HashMap::new(),
+            options,
+            created_on: Utc::now(),
+            column_history: vec![],
+        };
+        let table_info = TableInfo {
+            ident: TableIdent::new(request.id),
+            name: request.view_name,
+            desc: None,
+            catalog_name: request.catalog_name,
+            schema_name: request.schema_name,
+            meta,
+            table_type: TableType::View,
+        };
+
+        Self {
+            table_info: Arc::new(table_info),
+            definition: request.definition,
+        }
+    }
+
+    /// Restores a view from its table info, which must contain the view definition in
+    /// its options.
+    pub fn try_from_table_info(table_info: TableInfoRef) -> Result<Self> {
+        let definition = table_info
+            .meta
+            .options
+            .extra_options
+            .get(VIEW_DEFINITION_KEY)
+            .cloned()
+            .with_context(|| MissingViewDefinitionSnafu {
+                view_name: &table_info.name,
+            })?;
+        Ok(Self {
+            table_info,
+            definition,
+        })
+    }
+
+    /// The query text this view is defined by.
+    pub fn definition(&self) -> &str {
+        &self.definition
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for ViewTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.table_info.meta.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.table_info.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<PhysicalPlanRef> {
+        UnsupportedSnafu {
+            operation: "SCAN VIEW",
+        }
+        .fail()?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+
+    use super::*;
+    use crate::error::Error;
+    use crate::requests::InsertRequest;
+
+    fn new_view() -> ViewTable {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "host",
+            ConcreteDataType::string_datatype(),
+            true,
+        )]));
+        ViewTable::new(CreateViewRequest {
+            id: 1024,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            view_name: "hosts".to_string(),
+            schema,
+            definition: "SELECT host FROM monitor".to_string(),
+            create_if_not_exists: false,
+        })
+    }
+
+    #[test]
+    fn test_view_table_info() {
+        let view = new_view();
+        let table_info = view.table_info();
+        assert_eq!(TableType::View, table_info.table_type);
+        assert_eq!(VIEW_ENGINE, table_info.meta.engine);
+        assert_eq!(1024, table_info.ident.table_id);
+        assert_eq!("SELECT host FROM monitor", view.definition());
+
+        let restored = ViewTable::try_from_table_info(table_info.clone()).unwrap();
+        assert_eq!(view.definition(), restored.definition());
+        assert_eq!(table_info, restored.table_info());
+
+        let mut table_info = TableInfo::clone(&table_info);
+        table_info.meta.options.extra_options.clear();
+        let err = ViewTable::try_from_table_info(Arc::new(table_info)).unwrap_err();
+        assert!(matches!(err, Error::MissingViewDefinition { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_view_is_read_only() {
+        let view = new_view();
+        assert!(view.scan(None, &[], None).await.is_err());
+
+        let request = InsertRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "hosts".to_string(),
+            columns_values: HashMap::new(),
+            region_number: 0,
+        };
+        assert!(view.insert(request).await.is_err());
+    }
+}
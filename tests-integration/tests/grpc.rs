@@ -22,6 +22,7 @@ use api::v1::{
 use client::{Client, Database, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_catalog::consts::{MIN_USER_TABLE_ID, MITO_ENGINE};
 use common_query::Output;
+use datatypes::schema::{PRECISION_KEY, SEMANTIC_TYPE_KEY, UNIT_KEY};
 use servers::prom::{PromData, PromJsonResponse, PromSeries};
 use servers::server::Server;
 use tests_integration::test_util::{setup_grpc_server, StorageType};
@@ -62,6 +63,7 @@ macro_rules! grpc_tests {
                 test_dbname,
                 test_health_check,
                 test_prom_gateway_query,
+                test_query_column_metadata,
             );
         )*
     };
@@ -313,6 +315,50 @@ fn testing_create_expr() -> CreateTableExpr {
     }
 }
 
+pub async fn test_query_column_metadata(store_type: StorageType) {
+    let (addr, mut guard, fe_grpc_server) =
+        setup_grpc_server(store_type, "query_column_metadata").await;
+
+    let grpc_client = Client::with_urls(vec![addr]);
+    let db = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, grpc_client);
+    for sql in [
+        "CREATE TABLE monitor (host STRING, memory DOUBLE UNIT 'bytes', ts TIMESTAMP(3) TIME INDEX, PRIMARY KEY (host))",
+        "INSERT INTO monitor VALUES ('host1', 1024, 1000)",
+    ] {
+        let _ = db.sql(sql).await.unwrap();
+    }
+
+    let Output::RecordBatches(recordbatches) = db
+        .sql("SELECT host, memory, memory * 2, ts FROM monitor")
+        .await
+        .unwrap() else {
+        unreachable!()
+    };
+    // The metadata is kept in the schema sent by the Flight server.
+    let schema = recordbatches.schema();
+    let metadata = schema
+        .column_schemas()
+        .iter()
+        .map(|column_schema| {
+            let metadata = column_schema.metadata();
+            [SEMANTIC_TYPE_KEY, UNIT_KEY, PRECISION_KEY].map(|key| metadata.get(key).cloned())
+        })
+        .collect::<Vec<_>>();
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(
+        vec![
+            [some("tag"), None, None],
+            [some("field"), some("bytes"), None],
+            [None, None, None],
+            [some("timestamp"), None, some("ms")],
+        ],
+        metadata
+    );
+
+    let _ = fe_grpc_server.shutdown().await;
+    guard.remove_all().await;
+}
+
 pub async fn test_health_check(store_type: StorageType) {
     let (addr, mut guard, fe_grpc_server) =
         setup_grpc_server(store_type, "auto_create_table").await;
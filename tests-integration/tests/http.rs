@@ -53,6 +53,7 @@ macro_rules! http_tests {
                 $service,
 
                 test_sql_api,
+                test_cardinality_api,
                 test_prometheus_promql_api,
                 test_prom_http_api,
                 test_metrics_api,
@@ -92,7 +93,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         output[0],
         serde_json::from_value::<JsonOutput>(json!({
-            "records" :{"schema":{"column_schemas":[{"name":"number","data_type":"UInt32"}]},"rows":[[0],[1],[2],[3],[4],[5],[6],[7],[8],[9]]}
+            "records" :{"schema":{"column_schemas":[{"name":"number","data_type":"UInt32","semantic_type":"tag"}]},"rows":[[0],[1],[2],[3],[4],[5],[6],[7],[8],[9]]}
         })).unwrap()
     );
 
@@ -113,13 +114,16 @@ pub async fn test_sql_api(store_type: StorageType) {
     let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
     assert!(body.success());
     assert!(body.execution_time_ms().is_some());
+    let stats = body.execution_stats().unwrap();
+    assert_eq!(stats.rows_scanned, 1);
+    assert!(stats.bytes_read > 0);
     let output = body.output().unwrap();
     assert_eq!(output.len(), 1);
 
     assert_eq!(
         output[0],
         serde_json::from_value::<JsonOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"host","data_type":"String"},{"name":"cpu","data_type":"Float64"},{"name":"memory","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[["host",66.6,1024.0,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"host","data_type":"String","semantic_type":"tag"},{"name":"cpu","data_type":"Float64","semantic_type":"field"},{"name":"memory","data_type":"Float64","semantic_type":"field"},{"name":"ts","data_type":"TimestampMillisecond","semantic_type":"timestamp","precision":"ms"}]},"rows":[["host",66.6,1024.0,0]]}
         })).unwrap()
     );
 
@@ -139,7 +143,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         output[0],
         serde_json::from_value::<JsonOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","semantic_type":"field"},{"name":"ts","data_type":"TimestampMillisecond","semantic_type":"timestamp","precision":"ms"}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -158,7 +162,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         output[0],
         serde_json::from_value::<JsonOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"c","data_type":"Float64"},{"name":"time","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"c","data_type":"Float64"},{"name":"time","data_type":"TimestampMillisecond","precision":"ms"}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -177,7 +181,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         outputs[0],
         serde_json::from_value::<JsonOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","semantic_type":"field"},{"name":"ts","data_type":"TimestampMillisecond","semantic_type":"timestamp","precision":"ms"}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
     assert_eq!(
@@ -215,7 +219,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         outputs[0],
         serde_json::from_value::<JsonOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","semantic_type":"field"},{"name":"ts","data_type":"TimestampMillisecond","semantic_type":"timestamp","precision":"ms"}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -243,7 +247,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         outputs[0],
         serde_json::from_value::<JsonOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","semantic_type":"field"},{"name":"ts","data_type":"TimestampMillisecond","semantic_type":"timestamp","precision":"ms"}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -268,6 +272,45 @@ pub async fn test_sql_api(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_cardinality_api(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) = setup_test_http_app_with_frontend(store_type, "cardinality_api").await;
+    let client = TestClient::new(app);
+
+    let res = client.get("/v1/cardinality").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
+    assert_eq!(body.code(), ErrorCode::InvalidArguments as u32);
+    assert_eq!(body.error().unwrap(), "table parameter is required.");
+
+    let res = client
+        .get(
+            "/v1/sql?sql=insert into demo values('host1', 66.6, 1024, 0), ('host2', 66.6, 1024, 0)",
+        )
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client.get("/v1/cardinality?table=demo").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
+    assert!(body.success(), "{body:?}");
+    let output = body.output().unwrap();
+    assert_eq!(output.len(), 1);
+    assert_eq!(
+        output[0],
+        serde_json::from_value::<JsonOutput>(json!({
+            "records":{"schema":{"column_schemas":[{"name":"Column","data_type":"String"},{"name":"Distinct","data_type":"UInt64"},{"name":"Top Values","data_type":"String"}]},"rows":[["*",2,""],["host",2,"host1 (1), host2 (1)"]]}
+        })).unwrap()
+    );
+
+    let res = client.get("/v1/cardinality?table=not_exist").send().await;
+    let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
+    assert_eq!(body.code(), ErrorCode::TableNotFound as u32);
+
+    guard.remove_all().await;
+}
+
 pub async fn test_prometheus_promql_api(store_type: StorageType) {
     common_telemetry::init_default_ut_logging();
     let (app, mut guard) = setup_test_http_app_with_frontend(store_type, "sql_api").await;
@@ -442,11 +485,11 @@ pub async fn test_health_api(store_type: StorageType) {
     let body_text = res_post.text().await;
     assert_eq!(body_text, res_get.text().await);
 
-    // currently health api simply returns an empty json `{}`, which can be deserialized to an empty `HealthResponse`
+    // health api returns an empty json `{}` without warnings, which can be deserialized to an empty `HealthResponse`
     assert_eq!(body_text, "{}");
 
     let body = serde_json::from_str::<HealthResponse>(&body_text).unwrap();
-    assert_eq!(body, HealthResponse {});
+    assert_eq!(body, HealthResponse::default());
 }
 
 #[cfg(feature = "dashboard")]
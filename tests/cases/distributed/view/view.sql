@@ -0,0 +1,7 @@
+CREATE TABLE monitor (host STRING, ts TIMESTAMP TIME INDEX, cpu DOUBLE, PRIMARY KEY(host));
+
+CREATE VIEW host1_monitor AS SELECT ts, cpu FROM monitor WHERE host = 'host1';
+
+DROP VIEW host1_monitor;
+
+DROP TABLE monitor;
@@ -26,5 +26,15 @@ where table_catalog = 'greptime'
   and table_schema != 'public'
 order by table_schema, table_name;
 
+alter table foo add column val double;
+
+alter table foo drop column val;
+
+select table_schema, table_name, column_name, change_type, schema_version
+from information_schema.column_history
+where table_catalog = 'greptime'
+  and table_schema = 'my_db'
+order by schema_version;
+
 use
 public;
@@ -0,0 +1,21 @@
+CREATE TABLE monitor (host STRING, ts TIMESTAMP TIME INDEX, cpu DOUBLE, PRIMARY KEY(host));
+
+INSERT INTO monitor VALUES ('host1', 1000, 1.0), ('host2', 2000, 2.0), ('host1', 3000, 3.0);
+
+CREATE VIEW host1_monitor AS SELECT ts, cpu FROM monitor WHERE host = 'host1';
+
+CREATE VIEW host1_monitor AS SELECT ts FROM monitor;
+
+CREATE VIEW IF NOT EXISTS host1_monitor AS SELECT ts FROM monitor;
+
+SELECT * FROM host1_monitor ORDER BY ts;
+
+SELECT max(cpu) FROM host1_monitor;
+
+DROP VIEW monitor;
+
+DROP VIEW host1_monitor;
+
+SELECT * FROM host1_monitor;
+
+DROP TABLE monitor;